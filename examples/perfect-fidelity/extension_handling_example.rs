@@ -15,6 +15,7 @@ use ddex_builder::{
     RoundTripTester, FidelityAnalysis
 };
 use ddex_builder::error::BuildError;
+use ddex_builder::extension_conflicts::{resolve_conflicts, ConflictPolicy};
 use std::collections::HashMap;
 use std::time::Instant;
 
@@ -873,28 +874,27 @@ fn test_extension_conflicts() -> Result<(), BuildError> {
     </ReleaseList>
 </ernm:NewReleaseMessage>"#;
 
-    let mut fidelity_options = FidelityOptions::default();
-    fidelity_options.preserve_extensions = true;
-    fidelity_options.enable_perfect_fidelity = true;
-    
-    let builder = Builder::with_fidelity_options(fidelity_options);
-    
     println!("      🔧 Testing conflict resolution...");
-    
-    let round_trip_result = builder.test_round_trip_fidelity(conflicting_xml)?;
-    
-    if round_trip_result.success {
-        println!("         ✅ Conflict resolution successful");
-        println!("         • All conflicting extensions preserved");
-        println!("         • No data loss detected");
-    } else {
-        println!("         ⚠️  Conflicts detected but handled gracefully:");
-        for diff in &round_trip_result.differences[..std::cmp::min(2, round_trip_result.differences.len())] {
-            println!("           - {}", diff);
-        }
+
+    // Keep everything, but report what is carried twice
+    let report = resolve_conflicts(conflicting_xml, &ConflictPolicy::KeepAll)?.report;
+    println!("         • {} conflicting fields found:", report.conflicts.len());
+    for conflict in &report.conflicts {
+        println!("           - {}", conflict);
+    }
+
+    // Keep the current version of the partner's extension
+    let policy = ConflictPolicy::PreferNamespaceOrder(vec!["http://partner.com/ddex/v2".to_string()]);
+    let resolved = resolve_conflicts(conflicting_xml, &policy)?;
+    println!(
+        "         ✅ Preferring v2: {} bytes removed",
+        conflicting_xml.len() - resolved.xml.len()
+    );
+
+    // Or refuse to deliver until someone decides
+    if let Err(e) = resolve_conflicts(conflicting_xml, &ConflictPolicy::Error) {
+        println!("         ⚠️  Strict policy: {}", e);
     }
-    
-    println!("         • Byte identical: {}", if round_trip_result.byte_identical { "✅" } else { "➡️ Normalized" });
 
     Ok(())
 }
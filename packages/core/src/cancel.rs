@@ -0,0 +1,133 @@
+//! Cooperative cancellation for long-running parses and builds
+//!
+//! A [`CancellationToken`] is handed to the parser or builder through their
+//! options; they poll it between elements and stages and stop with a
+//! `Cancelled` error carrying how far they got. Clones share the same flag,
+//! so another thread, a signal handler or a binding's abort hook can cancel
+//! work in flight.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Why an operation stopped early
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CancelReason {
+    /// [`CancellationToken::cancel`] was called
+    Requested,
+    /// The token's deadline passed
+    TimedOut,
+}
+
+impl fmt::Display for CancelReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CancelReason::Requested => write!(f, "cancelled"),
+            CancelReason::TimedOut => write!(f, "timed out"),
+        }
+    }
+}
+
+/// Shared flag, plus an optional deadline, that stops work in flight
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    /// Flags of the tokens this one was made a [`child`](Self::child) of
+    parents: Vec<Arc<AtomicBool>>,
+    deadline: Option<Instant>,
+}
+
+impl CancellationToken {
+    /// A token that only stops work once cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A token sharing this one's flag that also stops work after `timeout`,
+    /// keeping any earlier deadline; a timeout too far out to represent
+    /// adds no deadline
+    pub fn with_timeout(&self, timeout: Duration) -> Self {
+        let deadline = match (self.deadline, Instant::now().checked_add(timeout)) {
+            (Some(existing), Some(deadline)) => Some(existing.min(deadline)),
+            (existing, deadline) => existing.or(deadline),
+        };
+        Self {
+            cancelled: Arc::clone(&self.cancelled),
+            parents: self.parents.clone(),
+            deadline,
+        }
+    }
+
+    /// A token that stops when this one does, but whose own `cancel` leaves
+    /// this one running; lets one call be aborted without the others
+    pub fn child(&self) -> Self {
+        let mut parents = self.parents.clone();
+        parents.push(Arc::clone(&self.cancelled));
+        Self {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            parents,
+            deadline: self.deadline,
+        }
+    }
+
+    /// Stop every operation holding a clone of this token
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the work should stop, and why
+    pub fn reason(&self) -> Option<CancelReason> {
+        let flagged = |flag: &Arc<AtomicBool>| flag.load(Ordering::Relaxed);
+        if flagged(&self.cancelled) || self.parents.iter().any(flagged) {
+            Some(CancelReason::Requested)
+        } else if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            Some(CancelReason::TimedOut)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the work should stop
+    pub fn is_cancelled(&self) -> bool {
+        self.reason().is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clones_share_the_flag() {
+        let token = CancellationToken::new();
+        let timed = token.with_timeout(Duration::from_secs(60));
+        assert_eq!(timed.reason(), None);
+        token.cancel();
+        assert_eq!(timed.reason(), Some(CancelReason::Requested));
+    }
+
+    #[test]
+    fn test_deadline() {
+        let token = CancellationToken::new().with_timeout(Duration::ZERO);
+        assert_eq!(token.reason(), Some(CancelReason::TimedOut));
+        // A later timeout does not extend the deadline
+        assert!(token.with_timeout(Duration::from_secs(60)).is_cancelled());
+        // Nor does one beyond what an Instant can hold remove it
+        assert!(token.with_timeout(Duration::MAX).is_cancelled());
+        assert_eq!(CancellationToken::new().with_timeout(Duration::MAX).reason(), None);
+    }
+
+    #[test]
+    fn test_child_follows_parent_only() {
+        let parent = CancellationToken::new();
+        let child = parent.child();
+        let sibling = parent.child();
+        child.cancel();
+        assert!(child.is_cancelled());
+        assert!(!parent.is_cancelled() && !sibling.is_cancelled());
+        parent.cancel();
+        assert_eq!(sibling.reason(), Some(CancelReason::Requested));
+    }
+}
@@ -0,0 +1,270 @@
+//! Transparent decompression of gzip and zip deliveries
+//!
+//! The format is detected from the leading bytes, not the file name. Gzip input
+//! is inflated as a stream; zip archives need `Seek` and are read one XML entry
+//! at a time. Each format sits behind a cargo feature (`gzip`, `zip`); without
+//! it the input is still recognised and reported as unsupported rather than
+//! handed to the XML parser as garbage.
+
+use crate::error::DDEXError;
+use std::io::{BufRead, Read, Seek, SeekFrom};
+
+/// Container format of an input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// Plain XML
+    None,
+    /// gzip stream (`.xml.gz`)
+    Gzip,
+    /// zip archive, possibly with several XML files
+    Zip,
+}
+
+impl Compression {
+    /// Detect the format from the first bytes of the input
+    pub fn detect(prefix: &[u8]) -> Self {
+        if prefix.starts_with(&[0x1f, 0x8b]) {
+            Compression::Gzip
+        } else if prefix.starts_with(b"PK\x03\x04") || prefix.starts_with(b"PK\x05\x06") {
+            Compression::Zip
+        } else {
+            Compression::None
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Compression::None => "plain",
+            Compression::Gzip => "gzip",
+            Compression::Zip => "zip",
+        }
+    }
+
+    fn error(self, message: impl Into<String>) -> DDEXError {
+        DDEXError::Decompression {
+            format: self.name().to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn unsupported(self) -> DDEXError {
+        self.error(format!("support is not compiled in; enable the `{}` feature", self.name()))
+    }
+}
+
+/// A reader that inflates gzip input on the fly and passes anything else through
+pub enum MaybeGzip<R: BufRead> {
+    Plain(R),
+    #[cfg(feature = "gzip")]
+    Gzip(std::io::BufReader<flate2::bufread::MultiGzDecoder<R>>),
+}
+
+impl<R: BufRead> Read for MaybeGzip<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            MaybeGzip::Plain(reader) => reader.read(buf),
+            #[cfg(feature = "gzip")]
+            MaybeGzip::Gzip(reader) => reader.read(buf),
+        }
+    }
+}
+
+impl<R: BufRead> BufRead for MaybeGzip<R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        match self {
+            MaybeGzip::Plain(reader) => reader.fill_buf(),
+            #[cfg(feature = "gzip")]
+            MaybeGzip::Gzip(reader) => reader.fill_buf(),
+        }
+    }
+
+    fn consume(&mut self, amount: usize) {
+        match self {
+            MaybeGzip::Plain(reader) => reader.consume(amount),
+            #[cfg(feature = "gzip")]
+            MaybeGzip::Gzip(reader) => reader.consume(amount),
+        }
+    }
+}
+
+/// Wrap `reader` so gzip input is inflated while it is read
+///
+/// Zip archives are rejected here because they cannot be read as a stream;
+/// use [`read_document`] or [`zip_xml_entries`] for them.
+pub fn gunzip_if_needed<R: BufRead>(mut reader: R) -> Result<MaybeGzip<R>, DDEXError> {
+    let compression = Compression::detect(reader.fill_buf().map_err(io_error)?);
+    match compression {
+        Compression::None => Ok(MaybeGzip::Plain(reader)),
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => Ok(MaybeGzip::Gzip(std::io::BufReader::new(
+            flate2::bufread::MultiGzDecoder::new(reader),
+        ))),
+        #[cfg(not(feature = "gzip"))]
+        Compression::Gzip => Err(compression.unsupported()),
+        Compression::Zip => Err(compression.error("archives need a seekable reader")),
+    }
+}
+
+/// Read a whole document, inflating gzip input and taking the first XML entry
+/// of a zip archive
+pub fn read_document<R: Read + Seek>(mut reader: R) -> Result<Vec<u8>, DDEXError> {
+    let mut prefix = [0u8; 4];
+    let read = read_prefix(&mut reader, &mut prefix)?;
+    reader.seek(SeekFrom::Start(0)).map_err(io_error)?;
+
+    let mut document = Vec::new();
+    match Compression::detect(&prefix[..read]) {
+        Compression::None => {
+            reader.read_to_end(&mut document).map_err(io_error)?;
+        }
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => {
+            flate2::read::MultiGzDecoder::new(reader)
+                .read_to_end(&mut document)
+                .map_err(|e| Compression::Gzip.error(e.to_string()))?;
+        }
+        #[cfg(feature = "zip")]
+        Compression::Zip => {
+            let (_, xml) = zip_xml_entries(reader)?
+                .next()
+                .ok_or_else(|| Compression::Zip.error("archive contains no .xml entry"))??;
+            document = xml;
+        }
+        #[allow(unreachable_patterns)]
+        other => return Err(other.unsupported()),
+    }
+    Ok(document)
+}
+
+fn read_prefix<R: Read>(reader: &mut R, prefix: &mut [u8]) -> Result<usize, DDEXError> {
+    let mut filled = 0;
+    while filled < prefix.len() {
+        match reader.read(&mut prefix[filled..]).map_err(io_error)? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+fn io_error(e: std::io::Error) -> DDEXError {
+    DDEXError::IoError { message: e.to_string() }
+}
+
+/// Iterator over the files of a zip archive, in archive order
+#[cfg(feature = "zip")]
+pub struct ZipEntries<R: Read + Seek> {
+    archive: zip::ZipArchive<R>,
+    index: usize,
+    xml_only: bool,
+}
+
+/// Open a zip archive for reading its XML entries one at a time
+#[cfg(feature = "zip")]
+pub fn zip_xml_entries<R: Read + Seek>(reader: R) -> Result<ZipEntries<R>, DDEXError> {
+    open_zip(reader, true)
+}
+
+/// Open a zip archive for reading all of its files one at a time
+#[cfg(feature = "zip")]
+pub fn zip_entries<R: Read + Seek>(reader: R) -> Result<ZipEntries<R>, DDEXError> {
+    open_zip(reader, false)
+}
+
+#[cfg(feature = "zip")]
+fn open_zip<R: Read + Seek>(reader: R, xml_only: bool) -> Result<ZipEntries<R>, DDEXError> {
+    let archive = zip::ZipArchive::new(reader).map_err(|e| Compression::Zip.error(e.to_string()))?;
+    Ok(ZipEntries { archive, index: 0, xml_only })
+}
+
+#[cfg(feature = "zip")]
+impl<R: Read + Seek> Iterator for ZipEntries<R> {
+    /// Entry name and its inflated contents
+    type Item = Result<(String, Vec<u8>), DDEXError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.archive.len() {
+            let index = self.index;
+            self.index += 1;
+
+            let mut entry = match self.archive.by_index(index) {
+                Ok(entry) => entry,
+                Err(e) => return Some(Err(Compression::Zip.error(e.to_string()))),
+            };
+            if entry.is_dir() || (self.xml_only && !entry.name().to_ascii_lowercase().ends_with(".xml")) {
+                continue;
+            }
+
+            let name = entry.name().to_string();
+            let mut contents = Vec::with_capacity(entry.size() as usize);
+            return Some(
+                entry
+                    .read_to_end(&mut contents)
+                    .map(|_| (name, contents))
+                    .map_err(|e| Compression::Zip.error(e.to_string())),
+            );
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const XML: &str = "<ern:NewReleaseMessage xmlns:ern=\"http://ddex.net/xml/ern/43\"/>";
+
+    #[test]
+    fn test_detect_by_magic_bytes() {
+        assert_eq!(Compression::detect(&[0x1f, 0x8b, 0x08]), Compression::Gzip);
+        assert_eq!(Compression::detect(b"PK\x03\x04rest"), Compression::Zip);
+        assert_eq!(Compression::detect(XML.as_bytes()), Compression::None);
+        assert_eq!(Compression::detect(&[]), Compression::None);
+    }
+
+    #[test]
+    fn test_plain_input_passes_through() {
+        assert_eq!(read_document(Cursor::new(XML)).unwrap(), XML.as_bytes());
+
+        let mut text = String::new();
+        gunzip_if_needed(Cursor::new(XML)).unwrap().read_to_string(&mut text).unwrap();
+        assert_eq!(text, XML);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_gzip_is_inflated() {
+        use flate2::{write::GzEncoder, Compression as Level};
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(Vec::new(), Level::default());
+        encoder.write_all(XML.as_bytes()).unwrap();
+        let gz = encoder.finish().unwrap();
+
+        assert_eq!(read_document(Cursor::new(gz.clone())).unwrap(), XML.as_bytes());
+        let mut text = String::new();
+        gunzip_if_needed(Cursor::new(gz)).unwrap().read_to_string(&mut text).unwrap();
+        assert_eq!(text, XML);
+    }
+
+    #[cfg(feature = "zip")]
+    #[test]
+    fn test_zip_xml_entries_skip_other_files() {
+        use std::io::Write;
+
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("resources/cover.jpg", options).unwrap();
+        writer.write_all(b"\xff\xd8").unwrap();
+        writer.start_file("A1.XML", options).unwrap();
+        writer.write_all(XML.as_bytes()).unwrap();
+        let archive = writer.finish().unwrap().into_inner();
+
+        let entries: Vec<_> = zip_xml_entries(Cursor::new(archive.clone())).unwrap().collect::<Result<_, _>>().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].0, "A1.XML");
+        assert_eq!(zip_entries(Cursor::new(archive.clone())).unwrap().count(), 2);
+        assert_eq!(read_document(Cursor::new(archive)).unwrap(), XML.as_bytes());
+    }
+}
@@ -0,0 +1,354 @@
+//! ISO 8601 durations as DDEX writes them
+//!
+//! DDEX carries playing times as `xs:duration` text such as `PT3M45S`,
+//! `PT1H2M3.5S` or `P0DT4M`. A [`DdexDuration`] parses the full form,
+//! computes with it and remembers how it was written, so a value that is
+//! only read is emitted again exactly as received:
+//!
+//! ```rust
+//! use ddex_core::duration::{DdexDuration, DurationFormat};
+//! use std::time::Duration;
+//!
+//! let duration: DdexDuration = "PT225.5S".parse()?;
+//! assert_eq!(duration.to_string(), "PT225.5S");
+//! assert_eq!(duration.format(DurationFormat::Canonical), "PT3M45.5S");
+//! assert_eq!(duration.format(DurationFormat::Clock), "3:45.5");
+//! assert!(duration.is_within(&"PT3M46S".parse()?, Duration::from_secs(1)));
+//! # Ok::<(), ddex_core::duration::DurationError>(())
+//! ```
+//!
+//! Calendar components are counted as fixed lengths: a year is 365 days, a
+//! month 30 days and a day 24 hours.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::Sum;
+use std::ops::{Add, Sub};
+use std::str::FromStr;
+use std::time::Duration;
+
+const SECONDS_PER_DAY: u64 = 24 * 3600;
+
+/// Text that is not an ISO 8601 duration
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("'{value}' is not an ISO 8601 duration, e.g. PT3M45S")]
+pub struct DurationError {
+    /// The text as given
+    pub value: String,
+}
+
+/// How [`DdexDuration::format`] writes a duration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DurationFormat {
+    /// The text the duration was parsed from, or `Canonical` for computed
+    /// durations
+    #[default]
+    Lexical,
+    /// Hours, minutes and seconds, hours only when there are any, e.g.
+    /// `PT3M45S` or `PT1H2M3.5S`
+    Canonical,
+    /// Clock time, e.g. `3:45` or `1:02:03.5`
+    Clock,
+}
+
+/// An ISO 8601 duration that keeps its lexical form
+///
+/// Durations compare, hash and compute by their length, so `PT90S` equals
+/// `PT1M30S`; the results of arithmetic are written in canonical form.
+#[derive(Debug, Clone)]
+pub struct DdexDuration {
+    years: u64,
+    months: u64,
+    days: u64,
+    hours: u64,
+    minutes: u64,
+    seconds: u64,
+    nanos: u32,
+    lexical: Option<String>,
+}
+
+impl DdexDuration {
+    /// Parse `PnYnMnDTnHnMnS`, with a fraction on the seconds only
+    pub fn parse(value: &str) -> Result<Self, DurationError> {
+        Self::parse_parts(value).ok_or_else(|| DurationError { value: value.to_string() })
+    }
+
+    fn parse_parts(value: &str) -> Option<Self> {
+        let rest = value.strip_prefix('P')?;
+        let (date, time) = match rest.split_once('T') {
+            Some((date, time)) if !time.is_empty() => (date, time),
+            Some(_) => return None,
+            None if !rest.is_empty() => (rest, ""),
+            None => return None,
+        };
+        let [years, months, days] = fields(date, ['Y', 'M', 'D'])?;
+        let [hours, minutes, seconds] = fields(time, ['H', 'M', 'S'])?;
+        let whole = |part: Option<&str>| part.map_or(Some(0), |digits| digits.parse::<u64>().ok());
+
+        let (seconds, nanos) = match seconds {
+            Some(seconds) => match seconds.split_once('.') {
+                Some((whole_seconds, fraction)) => {
+                    if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+                        return None;
+                    }
+                    // Digits past nanoseconds are dropped from the length but kept in the text
+                    let digits: String = fraction.chars().chain(std::iter::repeat('0')).take(9).collect();
+                    (whole(Some(whole_seconds))?, digits.parse().ok()?)
+                }
+                None => (whole(Some(seconds))?, 0),
+            },
+            None => (0, 0),
+        };
+        let duration = Self {
+            years: whole(years)?,
+            months: whole(months)?,
+            days: whole(days)?,
+            hours: whole(hours)?,
+            minutes: whole(minutes)?,
+            seconds,
+            nanos,
+            lexical: Some(value.to_string()),
+        };
+        duration.total_seconds()?;
+        Some(duration)
+    }
+
+    /// Seconds in the duration, or `None` when they do not fit a `u64`
+    fn total_seconds(&self) -> Option<u64> {
+        let days = self.years.checked_mul(365)?.checked_add(self.months.checked_mul(30)?)?.checked_add(self.days)?;
+        days.checked_mul(SECONDS_PER_DAY)?
+            .checked_add(self.hours.checked_mul(3600)?)?
+            .checked_add(self.minutes.checked_mul(60)?)?
+            .checked_add(self.seconds)
+    }
+
+    /// The length of the duration
+    pub fn to_std(&self) -> Duration {
+        // Parsing rejects durations whose seconds overflow
+        Duration::new(self.total_seconds().unwrap_or(u64::MAX), self.nanos)
+    }
+
+    /// The length in seconds, with the fraction
+    pub fn as_secs_f64(&self) -> f64 {
+        self.to_std().as_secs_f64()
+    }
+
+    /// Whether the duration has no length, e.g. `PT0S`
+    pub fn is_zero(&self) -> bool {
+        self.to_std().is_zero()
+    }
+
+    /// The text the duration was parsed from, if it was parsed
+    pub fn lexical(&self) -> Option<&str> {
+        self.lexical.as_deref()
+    }
+
+    /// The same length rounded to whole seconds, in canonical form
+    pub fn round_to_secs(&self) -> Self {
+        Self::from(Duration::from_secs(self.to_std().as_secs_f64().round() as u64))
+    }
+
+    /// How far apart two durations are
+    pub fn abs_diff(&self, other: &Self) -> Duration {
+        let (a, b) = (self.to_std(), other.to_std());
+        a.max(b) - a.min(b)
+    }
+
+    /// Whether two durations differ by at most `tolerance`, e.g. a track's
+    /// declared duration and that of its audio file
+    pub fn is_within(&self, other: &Self, tolerance: Duration) -> bool {
+        self.abs_diff(other) <= tolerance
+    }
+
+    /// Write the duration in the given format
+    pub fn format(&self, format: DurationFormat) -> String {
+        let total = self.to_std();
+        let secs = total.as_secs();
+        let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+        let fraction = fraction(total.subsec_nanos());
+        match format {
+            DurationFormat::Lexical => match &self.lexical {
+                Some(lexical) => lexical.clone(),
+                None => self.format(DurationFormat::Canonical),
+            },
+            DurationFormat::Canonical if hours > 0 => format!("PT{}H{}M{}{}S", hours, minutes, seconds, fraction),
+            DurationFormat::Canonical => format!("PT{}M{}{}S", minutes, seconds, fraction),
+            DurationFormat::Clock if hours > 0 => format!("{}:{:02}:{:02}{}", hours, minutes, seconds, fraction),
+            DurationFormat::Clock => format!("{}:{:02}{}", minutes, seconds, fraction),
+        }
+    }
+}
+
+/// The numbers before each designator, which must appear in order
+fn fields(part: &str, designators: [char; 3]) -> Option<[Option<&str>; 3]> {
+    let mut values = [None; 3];
+    let (mut next, mut start) = (0, 0);
+    for (i, c) in part.char_indices() {
+        if c.is_ascii_digit() || (c == '.' && designators[2] == 'S') {
+            continue;
+        }
+        let slot = next + designators[next..].iter().position(|d| *d == c)?;
+        let number = &part[start..i];
+        if number.is_empty() || (number.contains('.') && slot != 2) {
+            return None;
+        }
+        values[slot] = Some(number);
+        next = slot + 1;
+        start = i + c.len_utf8();
+    }
+    (start == part.len()).then_some(values)
+}
+
+/// `.5` for half a second, empty for whole seconds
+fn fraction(nanos: u32) -> String {
+    if nanos == 0 {
+        return String::new();
+    }
+    format!(".{:09}", nanos).trim_end_matches('0').to_string()
+}
+
+impl From<Duration> for DdexDuration {
+    fn from(duration: Duration) -> Self {
+        let secs = duration.as_secs();
+        Self {
+            years: 0,
+            months: 0,
+            days: 0,
+            hours: secs / 3600,
+            minutes: secs / 60 % 60,
+            seconds: secs % 60,
+            nanos: duration.subsec_nanos(),
+            lexical: None,
+        }
+    }
+}
+
+impl From<DdexDuration> for Duration {
+    fn from(duration: DdexDuration) -> Self {
+        duration.to_std()
+    }
+}
+
+impl FromStr for DdexDuration {
+    type Err = DurationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::parse(value)
+    }
+}
+
+impl fmt::Display for DdexDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format(DurationFormat::Lexical))
+    }
+}
+
+impl PartialEq for DdexDuration {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_std() == other.to_std()
+    }
+}
+
+impl Eq for DdexDuration {}
+
+impl PartialOrd for DdexDuration {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DdexDuration {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.to_std().cmp(&other.to_std())
+    }
+}
+
+impl Hash for DdexDuration {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_std().hash(state);
+    }
+}
+
+impl Add for DdexDuration {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::from(self.to_std().saturating_add(other.to_std()))
+    }
+}
+
+impl Sub for DdexDuration {
+    type Output = Self;
+
+    /// The difference, or zero when `other` is longer
+    fn sub(self, other: Self) -> Self {
+        Self::from(self.to_std().saturating_sub(other.to_std()))
+    }
+}
+
+impl Sum for DdexDuration {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::from(Duration::ZERO), Add::add)
+    }
+}
+
+impl Serialize for DdexDuration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for DdexDuration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Self::parse(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_round_trip() {
+        for value in ["PT3M45S", "PT1H2M3.5S", "P0DT4M", "PT225S", "P1Y2M3DT4H5M6.007S", "PT0S", "P1D"] {
+            let duration: DdexDuration = value.parse().unwrap();
+            assert_eq!(duration.to_string(), value);
+            assert_eq!(serde_json::to_value(&duration).unwrap(), value);
+        }
+        assert_eq!("PT1H2M3.5S".parse::<DdexDuration>().unwrap().to_std(), Duration::from_millis(3_723_500));
+        assert_eq!("P1D".parse::<DdexDuration>().unwrap().as_secs_f64(), 86_400.0);
+
+        for value in ["", "P", "PT", "3:45", "PT3M45", "PT45S3M", "PT1.5M", "P1H", "PT3M45.S", "pt3m45s", "P1DT"] {
+            assert!(value.parse::<DdexDuration>().is_err(), "{} parsed", value);
+        }
+    }
+
+    #[test]
+    fn test_formats() {
+        let duration: DdexDuration = "PT3723.25S".parse().unwrap();
+        assert_eq!(duration.format(DurationFormat::Lexical), "PT3723.25S");
+        assert_eq!(duration.format(DurationFormat::Canonical), "PT1H2M3.25S");
+        assert_eq!(duration.format(DurationFormat::Clock), "1:02:03.25");
+        assert_eq!(duration.round_to_secs().to_string(), "PT1H2M3S");
+        assert_eq!(DdexDuration::from(Duration::from_secs(1)).to_string(), "PT0M1S");
+    }
+
+    #[test]
+    fn test_arithmetic_and_tolerance() {
+        let a: DdexDuration = "PT3M".parse().unwrap();
+        let b: DdexDuration = "PT90S".parse().unwrap();
+        assert_eq!((a.clone() + b.clone()).to_string(), "PT4M30S");
+        assert_eq!((b.clone() - a.clone()).to_string(), "PT0M0S");
+        assert_eq!([a.clone(), b.clone(), b.clone()].into_iter().sum::<DdexDuration>(), "PT6M".parse().unwrap());
+        assert_eq!(b, "PT1M30S".parse().unwrap());
+        assert!(b < a);
+
+        let declared: DdexDuration = "PT3M45S".parse().unwrap();
+        let file = DdexDuration::from(Duration::from_millis(225_400));
+        assert!(declared.is_within(&file, Duration::from_secs(1)));
+        assert!(!declared.is_within(&file, Duration::from_millis(100)));
+    }
+}
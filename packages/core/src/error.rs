@@ -34,6 +34,86 @@ pub enum DDEXError {
     IoError {
         message: String,
     },
+
+    /// A document exceeded a configured security limit (entity expansion,
+    /// element depth, attribute count) or used a forbidden external entity
+    #[error("Security violation ({limit}): {message}")]
+    SecurityViolation {
+        /// Name of the limit or policy that was hit, e.g. `max_element_depth`
+        limit: String,
+        message: String,
+    },
+
+    /// Compressed input that could not be inflated, or whose format support
+    /// was not compiled in
+    #[error("{format} decompression error: {message}")]
+    Decompression {
+        format: String,
+        message: String,
+    },
+
+    /// An object storage URI that could not be read, written or listed, or
+    /// whose support was not compiled in
+    #[error("Storage error for {uri}: {message}")]
+    Storage {
+        uri: String,
+        message: String,
+    },
+
+    /// An XML digital signature that could not be created or did not verify
+    #[error("Signature error: {message}")]
+    Signature {
+        message: String,
+    },
+}
+
+impl DDEXError {
+    /// Stable error code, `DDEX-C-` followed by the category (01 XML,
+    /// 02 validation, 03 references, 05 versions, 06 I/O, 07 security,
+    /// 08 signatures) and a number within it
+    pub fn code(&self) -> &'static str {
+        match self {
+            DDEXError::XmlError { .. } => "DDEX-C-0101",
+            DDEXError::ValidationError { .. } => "DDEX-C-0201",
+            DDEXError::ReferenceError { .. } => "DDEX-C-0301",
+            DDEXError::VersionMismatch { .. } => "DDEX-C-0501",
+            DDEXError::IoError { .. } => "DDEX-C-0601",
+            DDEXError::Decompression { .. } => "DDEX-C-0602",
+            DDEXError::Storage { .. } => "DDEX-C-0603",
+            DDEXError::SecurityViolation { .. } => "DDEX-C-0701",
+            DDEXError::Signature { .. } => "DDEX-C-0801",
+        }
+    }
+
+    /// Element path of the offending input, or the field or reference at fault
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            DDEXError::XmlError { location, .. } => Some(&location.path),
+            DDEXError::ValidationError { field, .. } => field.as_deref(),
+            DDEXError::ReferenceError { reference, .. } => Some(reference),
+            DDEXError::Storage { uri, .. } => Some(uri),
+            _ => None,
+        }
+    }
+
+    /// How to fix the input, if known
+    pub fn suggestion(&self) -> Option<String> {
+        Some(match self {
+            DDEXError::XmlError { .. } => "Check XML syntax".to_string(),
+            DDEXError::ValidationError { .. } => "Check field requirements".to_string(),
+            DDEXError::ReferenceError { .. } => "Verify reference exists".to_string(),
+            DDEXError::VersionMismatch { .. } => "Use correct DDEX version".to_string(),
+            DDEXError::IoError { .. } => return None,
+            DDEXError::SecurityViolation { limit, .. } => format!("Raise `{}` only for trusted sources", limit),
+            DDEXError::Decompression { .. } => "Check the archive, or enable the `gzip`/`zip` features".to_string(),
+            DDEXError::Storage { .. } => {
+                "Check the AWS_*/GOOGLE_*/AZURE_* credentials, or enable the `storage` feature".to_string()
+            }
+            DDEXError::Signature { .. } => {
+                "Check the signing key and that the message was not modified after signing".to_string()
+            }
+        })
+    }
 }
 
 /// Location information for errors
@@ -64,59 +144,40 @@ pub mod ffi {
     /// Convert from DDEXError to FFIError
     impl From<DDEXError> for FFIError {
         fn from(err: DDEXError) -> Self {
-            match err {
-                DDEXError::XmlError { message, location } => FFIError {
-                    code: "XML_PARSE_ERROR".to_string(),
-                    message,
-                    location: Some(FFIErrorLocation {
-                        line: location.line,
-                        column: location.column,
-                        path: location.path,
-                    }),
-                    severity: FFIErrorSeverity::Error,
-                    hint: Some("Check XML syntax".to_string()),
-                    category: FFIErrorCategory::XmlParsing,
-                },
-                DDEXError::ValidationError { message, field } => FFIError {
-                    code: "VALIDATION_ERROR".to_string(),
-                    message: message.clone(),
-                    location: field.map(|f| FFIErrorLocation {
-                        line: 0,
-                        column: 0,
-                        path: f,
-                    }),
-                    severity: FFIErrorSeverity::Error,
-                    hint: Some("Check field requirements".to_string()),
-                    category: FFIErrorCategory::Validation,
-                },
-                DDEXError::ReferenceError { message, reference } => FFIError {
-                    code: "REFERENCE_ERROR".to_string(),
-                    message,
-                    location: Some(FFIErrorLocation {
-                        line: 0,
-                        column: 0,
-                        path: reference,
-                    }),
-                    severity: FFIErrorSeverity::Error,
-                    hint: Some("Verify reference exists".to_string()),
-                    category: FFIErrorCategory::Reference,
-                },
-                DDEXError::VersionMismatch { expected, found } => FFIError {
-                    code: "VERSION_MISMATCH".to_string(),
-                    message: format!("Expected version {}, found {}", expected, found),
-                    location: None,
-                    severity: FFIErrorSeverity::Error,
-                    hint: Some("Use correct DDEX version".to_string()),
-                    category: FFIErrorCategory::Version,
-                },
-                DDEXError::IoError { message } => FFIError {
-                    code: "IO_ERROR".to_string(),
-                    message,
-                    location: None,
-                    severity: FFIErrorSeverity::Error,
-                    hint: None,
-                    category: FFIErrorCategory::Io,
-                },
+            let code = err.code().to_string();
+            let suggestion = err.suggestion();
+            let location = match &err {
+                DDEXError::XmlError { location, .. } => Some(FFIErrorLocation {
+                    line: location.line,
+                    column: location.column,
+                    path: location.path.clone(),
+                }),
+                _ => err.path().map(FFIError::path_location),
+            };
+            let (message, category) = match err {
+                DDEXError::XmlError { message, .. } => (message, FFIErrorCategory::XmlParsing),
+                DDEXError::ValidationError { message, .. } => (message, FFIErrorCategory::Validation),
+                DDEXError::ReferenceError { message, .. } => (message, FFIErrorCategory::Reference),
+                DDEXError::VersionMismatch { expected, found } => (
+                    format!("Expected version {}, found {}", expected, found),
+                    FFIErrorCategory::Version,
+                ),
+                DDEXError::IoError { message } => (message, FFIErrorCategory::Io),
+                DDEXError::SecurityViolation { message, .. } => (message, FFIErrorCategory::Validation),
+                DDEXError::Decompression { format, message } => (
+                    format!("{} decompression error: {}", format, message),
+                    FFIErrorCategory::Io,
+                ),
+                DDEXError::Storage { uri, message } => (format!("{}: {}", uri, message), FFIErrorCategory::Io),
+                DDEXError::Signature { message } => (message, FFIErrorCategory::Validation),
+            };
+            FFIError {
+                code,
+                message,
+                location,
+                severity: FFIErrorSeverity::Error,
+                suggestion,
+                category,
             }
         }
     }
@@ -2,6 +2,9 @@
 
 use serde::{Serialize, Deserialize};
 
+#[cfg(feature = "ffi")]
+pub mod c;
+
 /// Location information for FFI errors
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FFIErrorLocation {
@@ -11,24 +14,44 @@ pub struct FFIErrorLocation {
 }
 
 /// FFI-safe error type for cross-language bindings
+///
+/// Every error of the suite converts into this shape, so bindings can hand
+/// the same fields to Node, Python and WASM callers.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FFIError {
+    /// Stable code to branch on, e.g. `DDEX-B-0401` (`C` core, `P` parser,
+    /// `B` builder)
     pub code: String,
     pub message: String,
+    /// Element path (with line and column) or JSON path of the offending input
     pub location: Option<FFIErrorLocation>,
     pub severity: FFIErrorSeverity,
-    pub hint: Option<String>,
+    /// How to fix the input, if known
+    #[serde(alias = "hint")]
+    pub suggestion: Option<String>,
     pub category: FFIErrorCategory,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl FFIError {
+    /// Location carrying only a path, for errors found before or outside XML
+    /// parsing
+    pub fn path_location(path: impl Into<String>) -> FFIErrorLocation {
+        FFIErrorLocation {
+            line: 0,
+            column: 0,
+            path: path.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FFIErrorSeverity {
     Error,
     Warning,
     Info,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FFIErrorCategory {
     XmlParsing,
     Validation,
@@ -0,0 +1,314 @@
+//! C ABI of the suite's native library
+//!
+//! `ddex-ffi` links these with its parse and build entry points into
+//! `libddex`, and cbindgen generates `ddex.h` from both. Everything the
+//! library hands out is owned by Rust: release buffers with
+//! [`ddex_buffer_free`] and errors with [`ddex_error_free`].
+//!
+//! Entry points return a [`DdexStatus`] and fill in either an output buffer
+//! or a [`DdexError`], converted from the same [`FFIError`] the Node, Python
+//! and WASM bindings use, so the codes match across languages.
+
+use super::{FFIError, FFIErrorCategory, FFIErrorLocation, FFIErrorSeverity};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+
+/// Code of a NULL, non-UTF-8 or malformed argument
+pub const INVALID_ARGUMENT: &str = "DDEX-C-0901";
+/// Code of a panic caught at the boundary instead of unwinding into C
+pub const PANIC: &str = "DDEX-C-0902";
+
+/// Outcome of an entry point
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DdexStatus {
+    /// The output buffer holds the result
+    Ok = 0,
+    /// The error, if one was asked for, says what went wrong
+    Error = 1,
+}
+
+/// Bytes owned by the library
+///
+/// `data[len]` is always NUL, so text output can be read as a C string.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct DdexBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+impl DdexBuffer {
+    /// A buffer that owns nothing, safe to free
+    pub fn empty() -> Self {
+        Self { data: ptr::null_mut(), len: 0 }
+    }
+
+    /// Hand `bytes` over to the caller, NUL-terminated
+    pub fn from_vec(mut bytes: Vec<u8>) -> Self {
+        let len = bytes.len();
+        bytes.push(0);
+        let data = Box::into_raw(bytes.into_boxed_slice()) as *mut u8;
+        Self { data, len }
+    }
+}
+
+/// Mirrors [`FFIErrorSeverity`]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DdexSeverity {
+    Error = 0,
+    Warning = 1,
+    Info = 2,
+}
+
+/// Mirrors [`FFIErrorCategory`]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DdexErrorCategory {
+    XmlParsing = 0,
+    Validation = 1,
+    Reference = 2,
+    Version = 3,
+    Io = 4,
+    Internal = 5,
+}
+
+/// An [`FFIError`] as NUL-terminated strings
+#[repr(C)]
+#[derive(Debug)]
+pub struct DdexError {
+    /// Stable code to branch on, e.g. `DDEX-B-0401`
+    pub code: *mut c_char,
+    pub message: *mut c_char,
+    /// Element path or JSON path of the offending input, or NULL
+    pub path: *mut c_char,
+    /// 1-based position in the XML, or 0
+    pub line: usize,
+    pub column: usize,
+    pub severity: DdexSeverity,
+    pub category: DdexErrorCategory,
+    /// How to fix the input, or NULL
+    pub suggestion: *mut c_char,
+}
+
+impl From<FFIError> for DdexError {
+    fn from(error: FFIError) -> Self {
+        let FFIErrorLocation { line, column, path } = error.location.unwrap_or(FFIErrorLocation {
+            line: 0,
+            column: 0,
+            path: String::new(),
+        });
+        Self {
+            code: c_string(error.code),
+            message: c_string(error.message),
+            path: if path.is_empty() { ptr::null_mut() } else { c_string(path) },
+            line,
+            column,
+            severity: match error.severity {
+                FFIErrorSeverity::Error => DdexSeverity::Error,
+                FFIErrorSeverity::Warning => DdexSeverity::Warning,
+                FFIErrorSeverity::Info => DdexSeverity::Info,
+            },
+            category: match error.category {
+                FFIErrorCategory::XmlParsing => DdexErrorCategory::XmlParsing,
+                FFIErrorCategory::Validation => DdexErrorCategory::Validation,
+                FFIErrorCategory::Reference => DdexErrorCategory::Reference,
+                FFIErrorCategory::Version => DdexErrorCategory::Version,
+                FFIErrorCategory::Io => DdexErrorCategory::Io,
+                FFIErrorCategory::Internal => DdexErrorCategory::Internal,
+            },
+            suggestion: error.suggestion.map_or(ptr::null_mut(), c_string),
+        }
+    }
+}
+
+/// Interior NULs cannot cross as a C string, so they are dropped
+fn c_string(text: String) -> *mut c_char {
+    CString::new(text.replace('\0', "")).unwrap_or_default().into_raw()
+}
+
+/// Error for the argument `name`
+pub fn invalid_argument(name: &str, message: impl std::fmt::Display) -> FFIError {
+    FFIError {
+        code: INVALID_ARGUMENT.to_string(),
+        message: format!("{}: {}", name, message),
+        location: Some(FFIError::path_location(name)),
+        severity: FFIErrorSeverity::Error,
+        suggestion: None,
+        category: FFIErrorCategory::Internal,
+    }
+}
+
+/// Run the body of an entry point, storing its output in `out` or its
+/// error in `*error`, and turning a panic into a [`PANIC`] error
+///
+/// # Safety
+///
+/// `out` must be NULL or valid for writes; so must `error`.
+pub unsafe fn call(
+    out: *mut DdexBuffer,
+    error: *mut *mut DdexError,
+    body: impl FnOnce() -> Result<Vec<u8>, FFIError>,
+) -> DdexStatus {
+    if !error.is_null() {
+        *error = ptr::null_mut();
+    }
+    let result = if out.is_null() {
+        Err(invalid_argument("out", "must not be NULL"))
+    } else {
+        *out = DdexBuffer::empty();
+        catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|panic| {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            Err(FFIError {
+                code: PANIC.to_string(),
+                message,
+                location: None,
+                severity: FFIErrorSeverity::Error,
+                suggestion: Some("Please report this with the input that caused it".to_string()),
+                category: FFIErrorCategory::Internal,
+            })
+        })
+    };
+    match result {
+        Ok(bytes) => {
+            *out = DdexBuffer::from_vec(bytes);
+            DdexStatus::Ok
+        }
+        Err(e) => {
+            if !error.is_null() {
+                *error = Box::into_raw(Box::new(DdexError::from(e)));
+            }
+            DdexStatus::Error
+        }
+    }
+}
+
+/// The `len` bytes at `data`, which may be NULL only when `len` is 0
+///
+/// # Safety
+///
+/// A non-NULL `data` must be valid for reads of `len` bytes for `'a`.
+pub unsafe fn bytes<'a>(data: *const u8, len: usize, name: &str) -> Result<&'a [u8], FFIError> {
+    match (data.is_null(), len) {
+        (true, 0) => Ok(&[]),
+        (true, _) => Err(invalid_argument(name, "must not be NULL")),
+        (false, _) => Ok(std::slice::from_raw_parts(data, len)),
+    }
+}
+
+/// The UTF-8 C string at `text`, or `None` for NULL
+///
+/// # Safety
+///
+/// A non-NULL `text` must point to a NUL-terminated string valid for `'a`.
+pub unsafe fn optional_str<'a>(text: *const c_char, name: &str) -> Result<Option<&'a str>, FFIError> {
+    if text.is_null() {
+        return Ok(None);
+    }
+    CStr::from_ptr(text).to_str().map(Some).map_err(|e| invalid_argument(name, e))
+}
+
+/// `defaults` with the fields of the JSON object `json` laid over them, so
+/// callers only spell out what they change
+pub fn options<T: Serialize + DeserializeOwned>(defaults: T, json: Option<&str>, name: &str) -> Result<T, FFIError> {
+    let Some(json) = json else {
+        return Ok(defaults);
+    };
+    let overrides: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(json).map_err(|e| invalid_argument(name, e))?;
+    let mut merged = serde_json::to_value(defaults).map_err(|e| invalid_argument(name, e))?;
+    if let Some(fields) = merged.as_object_mut() {
+        fields.extend(overrides);
+    }
+    serde_json::from_value(merged).map_err(|e| invalid_argument(name, e))
+}
+
+/// Release a buffer returned by the library; an empty buffer is ignored
+///
+/// # Safety
+///
+/// `buffer` must come from the library and not have been freed already.
+#[no_mangle]
+pub unsafe extern "C" fn ddex_buffer_free(buffer: DdexBuffer) {
+    if !buffer.data.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(buffer.data, buffer.len + 1)));
+    }
+}
+
+/// Release an error returned by the library; NULL is ignored
+///
+/// # Safety
+///
+/// `error` must be NULL or come from the library and not have been freed
+/// already.
+#[no_mangle]
+pub unsafe extern "C" fn ddex_error_free(error: *mut DdexError) {
+    if error.is_null() {
+        return;
+    }
+    let error = Box::from_raw(error);
+    for text in [error.code, error.message, error.path, error.suggestion] {
+        if !text.is_null() {
+            drop(CString::from_raw(text));
+        }
+    }
+}
+
+/// Version of the library, e.g. `0.3.5`; a static string, not to be freed
+#[no_mangle]
+pub extern "C" fn ddex_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_call_hands_over_output_and_errors() {
+        let mut out = DdexBuffer::empty();
+        let mut error = ptr::null_mut();
+        unsafe {
+            assert_eq!(call(&mut out, &mut error, || Ok(b"<xml/>".to_vec())), DdexStatus::Ok);
+            assert!(error.is_null());
+            assert_eq!(CStr::from_ptr(out.data as *const c_char).to_str(), Ok("<xml/>"));
+            ddex_buffer_free(out);
+
+            let status = call(&mut out, &mut error, || Err(invalid_argument("xml", "must not be NULL")));
+            assert_eq!(status, DdexStatus::Error);
+            assert!(out.data.is_null());
+            assert_eq!(CStr::from_ptr((*error).code).to_str(), Ok(INVALID_ARGUMENT));
+            assert_eq!(CStr::from_ptr((*error).path).to_str(), Ok("xml"));
+            ddex_error_free(error);
+        }
+    }
+
+    #[test]
+    fn test_panics_become_errors() {
+        let mut out = DdexBuffer::empty();
+        let mut error = ptr::null_mut();
+        unsafe {
+            assert_eq!(call(&mut out, &mut error, || panic!("boom")), DdexStatus::Error);
+            assert_eq!(CStr::from_ptr((*error).code).to_str(), Ok(PANIC));
+            assert_eq!(CStr::from_ptr((*error).message).to_str(), Ok("boom"));
+            ddex_error_free(error);
+        }
+    }
+
+    #[test]
+    fn test_options_override_defaults() {
+        let defaults = crate::ffi::FFIParseOptions::default();
+        let merged = options(defaults, Some(r#"{"include_comments": true}"#), "options_json").unwrap();
+        assert!(merged.include_comments);
+        assert_eq!(merged.max_depth, Some(100));
+        assert!(options(crate::ffi::FFIParseOptions::default(), Some("[]"), "options_json").is_err());
+    }
+}
@@ -0,0 +1,70 @@
+//! Interning of repeated string values
+//!
+//! Catalog-sized messages repeat the same territory codes, artist, label and
+//! element names over and over. A [`StringInterner`] hands out one shared
+//! `Arc<str>` per distinct value, so each repeat costs a pointer instead of
+//! a fresh allocation, and counts what that saved.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// What an interner saw and saved
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct InternStats {
+    /// Values passed to [`StringInterner::intern`]
+    pub lookups: usize,
+    /// Distinct values stored
+    pub unique: usize,
+    /// Bytes of string data not allocated because the value was a repeat
+    pub bytes_saved: usize,
+}
+
+/// Arena of shared string values
+#[derive(Debug, Default)]
+pub struct StringInterner {
+    strings: HashSet<Arc<str>>,
+    stats: InternStats,
+}
+
+impl StringInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The shared copy of `value`, stored on first sight
+    pub fn intern(&mut self, value: &str) -> Arc<str> {
+        self.stats.lookups += 1;
+        if let Some(existing) = self.strings.get(value) {
+            self.stats.bytes_saved += value.len();
+            return Arc::clone(existing);
+        }
+        let shared: Arc<str> = Arc::from(value);
+        self.strings.insert(Arc::clone(&shared));
+        self.stats.unique += 1;
+        shared
+    }
+
+    pub fn stats(&self) -> &InternStats {
+        &self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeats_share_one_allocation() {
+        let mut interner = StringInterner::new();
+        let first = interner.intern("Worldwide");
+        let second = interner.intern("Worldwide");
+        interner.intern("US");
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(
+            interner.stats(),
+            &InternStats { lookups: 3, unique: 2, bytes_saved: "Worldwide".len() }
+        );
+    }
+}
@@ -1,11 +1,27 @@
 //! DDEX Core - Shared models and types for DDEX Suite
 
 pub mod models;
+pub mod cancel;
+pub mod compression;
+pub mod duration;
 pub mod error;
 pub mod ffi;
+pub mod intern;
+pub mod memory;
 pub mod namespace;
+pub mod schema;
+pub mod security;
+#[cfg(feature = "signature")]
+pub mod signature;
+pub mod storage;
+pub mod telemetry;
+pub mod territory;
+pub mod warnings;
 
 // Re-export commonly used types
 pub use error::{DDEXError, ErrorLocation};
+pub use warnings::{Normalization, NormalizationKind, Warnings};
 pub use models::versions::ERNVersion;
+pub use duration::DdexDuration;
+pub use territory::TerritorySet;
 pub use namespace::{NamespaceRegistry, NamespaceScope, NamespaceInfo, DDEXStandard};
\ No newline at end of file
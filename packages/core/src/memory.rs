@@ -0,0 +1,116 @@
+//! Memory accounting for the streaming parser and the builder
+//!
+//! A [`MemoryTracker`] keeps a running total of the bytes its owner holds
+//! (read buffers, models collected so far, serialized output) and refuses
+//! allocations that would take it past the ceiling. Sizes are estimates made
+//! by the owner, not measurements of the allocator, so the ceiling bounds
+//! what the libraries keep alive rather than the process footprint.
+
+use std::fmt;
+
+/// An allocation that would have taken a tracker past its ceiling
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryLimitExceeded {
+    /// The configured ceiling, in bytes
+    pub limit: usize,
+    /// What the total would have been with the allocation, in bytes
+    pub requested: usize,
+}
+
+impl fmt::Display for MemoryLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "memory limit exceeded: {} bytes needed, {} allowed", self.requested, self.limit)
+    }
+}
+
+impl std::error::Error for MemoryLimitExceeded {}
+
+/// Running total and peak of the bytes held against a ceiling
+#[derive(Debug, Clone)]
+pub struct MemoryTracker {
+    limit: usize,
+    current: usize,
+    peak: usize,
+}
+
+impl MemoryTracker {
+    /// Track allocations against `limit` bytes
+    pub fn new(limit: usize) -> Self {
+        Self { limit, current: 0, peak: 0 }
+    }
+
+    /// Track allocations without a ceiling, only reporting the peak
+    pub fn unlimited() -> Self {
+        Self::new(usize::MAX)
+    }
+
+    /// Account for `bytes` more, failing without recording them if that
+    /// would exceed the ceiling
+    pub fn allocate(&mut self, bytes: usize) -> Result<(), MemoryLimitExceeded> {
+        let requested = self.current.saturating_add(bytes);
+        if requested > self.limit {
+            return Err(MemoryLimitExceeded { limit: self.limit, requested });
+        }
+        self.current = requested;
+        self.peak = self.peak.max(requested);
+        Ok(())
+    }
+
+    /// Account for `bytes` no longer being held
+    pub fn free(&mut self, bytes: usize) {
+        self.current = self.current.saturating_sub(bytes);
+    }
+
+    /// Replace an allocation of `old` bytes that grew or shrank to `new`
+    pub fn resize(&mut self, old: usize, new: usize) -> Result<(), MemoryLimitExceeded> {
+        if new >= old {
+            self.allocate(new - old)
+        } else {
+            self.free(old - new);
+            Ok(())
+        }
+    }
+
+    /// Bytes held right now
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Most bytes held at any one time
+    pub fn peak(&self) -> usize {
+        self.peak
+    }
+
+    /// The ceiling, in bytes
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peak_survives_free() {
+        let mut tracker = MemoryTracker::new(100);
+        tracker.allocate(60).unwrap();
+        tracker.free(50);
+        tracker.allocate(30).unwrap();
+        assert_eq!(tracker.current(), 40);
+        assert_eq!(tracker.peak(), 60);
+    }
+
+    #[test]
+    fn test_ceiling_rejects_without_recording() {
+        let mut tracker = MemoryTracker::new(100);
+        tracker.allocate(80).unwrap();
+        assert_eq!(tracker.allocate(30), Err(MemoryLimitExceeded { limit: 100, requested: 110 }));
+        assert_eq!(tracker.current(), 80);
+        tracker.resize(80, 100).unwrap();
+        assert!(tracker.resize(100, 101).is_err());
+        tracker.resize(100, 10).unwrap();
+        assert_eq!(tracker.current(), 10);
+        assert_eq!(tracker.peak(), 100);
+    }
+}
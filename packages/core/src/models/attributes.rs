@@ -4,6 +4,7 @@
 //! including unknown/proprietary ones, with proper namespace handling and
 //! deterministic ordering for canonical XML generation.
 
+use crate::duration::DdexDuration;
 use indexmap::{IndexMap, IndexSet};
 use serde::{Serialize, Deserialize, Serializer, Deserializer};
 use std::fmt::{self, Display, Debug};
@@ -203,10 +204,10 @@ impl AttributeValue {
             AttributeValue::Decimal(d) => d.to_string(),
             AttributeValue::Date(d) => d.format("%Y-%m-%d").to_string(),
             AttributeValue::DateTime(dt) => dt.to_rfc3339(),
-            AttributeValue::Duration(dur) => {
-                // Convert to ISO 8601 duration format
-                let secs = dur.num_seconds();
-                format!("PT{}S", secs)
+            AttributeValue::Duration(dur) => match dur.to_std() {
+                Ok(dur) => DdexDuration::from(dur).to_string(),
+                // Negative durations carry a leading sign
+                Err(_) => format!("-{}", DdexDuration::from((-*dur).to_std().unwrap_or_default())),
             },
             AttributeValue::Uri(uri) => uri.clone(),
             AttributeValue::Language(lang) => lang.clone(),
@@ -567,6 +568,18 @@ impl<'de> Deserialize<'de> for AttributeMap {
     }
 }
 
+#[cfg(feature = "json-schema")]
+impl schemars::JsonSchema for AttributeMap {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "AttributeMap".into()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        // Same shape as the serialized form
+        IndexMap::<String, String>::json_schema(generator)
+    }
+}
+
 impl<'a> IntoIterator for &'a AttributeMap {
     type Item = (&'a QName, &'a AttributeValue);
     type IntoIter = indexmap::map::Iter<'a, QName, AttributeValue>;
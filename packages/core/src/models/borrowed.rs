@@ -0,0 +1,144 @@
+// core/src/models/borrowed.rs
+//! Zero-copy views of ERN messages
+//!
+//! These mirror the read-mostly parts of the flat model with every string a
+//! `Cow` into the source document, so values without entity references cost
+//! no allocation. They suit analytics passes that read a catalog once; use
+//! [`BorrowedMessage::into_owned`] to keep a message past its input.
+
+use super::common::IdentifierType;
+use serde::Serialize;
+use std::borrow::Cow;
+
+/// An ERN message whose values borrow from the XML it was parsed from
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BorrowedMessage<'a> {
+    pub message_id: Cow<'a, str>,
+    pub message_created_date_time: Option<Cow<'a, str>>,
+    /// DPID of the sending party
+    pub sender_id: Option<Cow<'a, str>>,
+    pub sender_name: Option<Cow<'a, str>>,
+    /// DPID of the receiving party
+    pub recipient_id: Option<Cow<'a, str>>,
+    pub releases: Vec<BorrowedRelease<'a>>,
+    pub resources: Vec<BorrowedResource<'a>>,
+    pub deals: Vec<BorrowedDeal<'a>>,
+}
+
+/// A release identifier or resource identifier
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BorrowedIdentifier<'a> {
+    pub id_type: IdentifierType,
+    pub namespace: Option<Cow<'a, str>>,
+    pub value: Cow<'a, str>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BorrowedRelease<'a> {
+    pub release_reference: Cow<'a, str>,
+    pub release_id: Vec<BorrowedIdentifier<'a>>,
+    pub title: Option<Cow<'a, str>>,
+    pub display_artist: Option<Cow<'a, str>>,
+    pub label_name: Option<Cow<'a, str>>,
+    pub genres: Vec<Cow<'a, str>>,
+    pub release_date: Option<Cow<'a, str>>,
+    /// Resources listed in the release's ResourceGroup
+    pub resource_references: Vec<Cow<'a, str>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BorrowedResource<'a> {
+    pub resource_reference: Cow<'a, str>,
+    pub resource_id: Vec<BorrowedIdentifier<'a>>,
+    pub title: Option<Cow<'a, str>>,
+    pub display_artist: Option<Cow<'a, str>>,
+    /// ISO 8601 duration as written, e.g. `PT3M45S`
+    pub duration: Option<Cow<'a, str>>,
+}
+
+/// The terms of one ReleaseDeal
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BorrowedDeal<'a> {
+    pub release_references: Vec<Cow<'a, str>>,
+    pub territory_codes: Vec<Cow<'a, str>>,
+    pub commercial_model_types: Vec<Cow<'a, str>>,
+    pub use_types: Vec<Cow<'a, str>>,
+    pub start_date: Option<Cow<'a, str>>,
+}
+
+fn own(value: Cow<'_, str>) -> Cow<'static, str> {
+    Cow::Owned(value.into_owned())
+}
+
+fn own_opt(value: Option<Cow<'_, str>>) -> Option<Cow<'static, str>> {
+    value.map(own)
+}
+
+fn own_all(values: Vec<Cow<'_, str>>) -> Vec<Cow<'static, str>> {
+    values.into_iter().map(own).collect()
+}
+
+impl BorrowedMessage<'_> {
+    /// Copy every borrowed value so the message outlives its input
+    pub fn into_owned(self) -> BorrowedMessage<'static> {
+        BorrowedMessage {
+            message_id: own(self.message_id),
+            message_created_date_time: own_opt(self.message_created_date_time),
+            sender_id: own_opt(self.sender_id),
+            sender_name: own_opt(self.sender_name),
+            recipient_id: own_opt(self.recipient_id),
+            releases: self.releases.into_iter().map(BorrowedRelease::into_owned).collect(),
+            resources: self.resources.into_iter().map(BorrowedResource::into_owned).collect(),
+            deals: self.deals.into_iter().map(BorrowedDeal::into_owned).collect(),
+        }
+    }
+}
+
+impl BorrowedIdentifier<'_> {
+    pub fn into_owned(self) -> BorrowedIdentifier<'static> {
+        BorrowedIdentifier {
+            id_type: self.id_type,
+            namespace: own_opt(self.namespace),
+            value: own(self.value),
+        }
+    }
+}
+
+impl BorrowedRelease<'_> {
+    pub fn into_owned(self) -> BorrowedRelease<'static> {
+        BorrowedRelease {
+            release_reference: own(self.release_reference),
+            release_id: self.release_id.into_iter().map(BorrowedIdentifier::into_owned).collect(),
+            title: own_opt(self.title),
+            display_artist: own_opt(self.display_artist),
+            label_name: own_opt(self.label_name),
+            genres: own_all(self.genres),
+            release_date: own_opt(self.release_date),
+            resource_references: own_all(self.resource_references),
+        }
+    }
+}
+
+impl BorrowedResource<'_> {
+    pub fn into_owned(self) -> BorrowedResource<'static> {
+        BorrowedResource {
+            resource_reference: own(self.resource_reference),
+            resource_id: self.resource_id.into_iter().map(BorrowedIdentifier::into_owned).collect(),
+            title: own_opt(self.title),
+            display_artist: own_opt(self.display_artist),
+            duration: own_opt(self.duration),
+        }
+    }
+}
+
+impl BorrowedDeal<'_> {
+    pub fn into_owned(self) -> BorrowedDeal<'static> {
+        BorrowedDeal {
+            release_references: own_all(self.release_references),
+            territory_codes: own_all(self.territory_codes),
+            commercial_model_types: own_all(self.commercial_model_types),
+            use_types: own_all(self.use_types),
+            start_date: own_opt(self.start_date),
+        }
+    }
+}
@@ -1,8 +1,16 @@
 //! Localized string support
+//!
+//! DDEX tags titles and names with a `LanguageAndScriptCode`, a BCP 47
+//! language tag such as `en`, `ja-Latn` or `zh-Hant-TW`. A list of
+//! [`LocalizedString`]s offers the same text in several languages;
+//! [`LocalizedStrings::best_match`] picks the one to show for a reader's
+//! preferred locales.
 
 use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct LocalizedString {
     pub text: String,
     pub language_code: Option<String>,
@@ -17,4 +25,231 @@ impl LocalizedString {
             script: None,
         }
     }
+
+    /// Tag the text with a language, e.g. `en` or `zh-Hant`
+    pub fn with_language(mut self, language_code: impl Into<String>) -> Self {
+        self.language_code = Some(language_code.into());
+        self
+    }
+
+    /// The full language tag, adding the script to the language code unless
+    /// the code already names one
+    pub fn language_tag(&self) -> Option<String> {
+        let language = self.language_code.as_deref()?;
+        match &self.script {
+            Some(script) if !language.split('-').skip(1).any(|subtag| subtag.eq_ignore_ascii_case(script)) => {
+                Some(format!("{}-{}", language, script))
+            }
+            _ => Some(language.to_string()),
+        }
+    }
+
+    /// Check the language code and script against BCP 47
+    pub fn validate(&self) -> Result<(), LanguageTagError> {
+        if let Some(script) = &self.script {
+            if !is_alpha(script, 4..=4) {
+                return Err(LanguageTagError {
+                    tag: script.clone(),
+                    reason: "a script is 4 letters, e.g. Latn".to_string(),
+                });
+            }
+        }
+        match &self.language_code {
+            Some(language) => validate_language_tag(language),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A language tag that does not follow BCP 47
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("'{tag}' is not a BCP 47 language tag: {reason}")]
+pub struct LanguageTagError {
+    /// The tag as given
+    pub tag: String,
+    /// What is wrong with it
+    pub reason: String,
+}
+
+/// Check the structure of a BCP 47 language tag: language, optional
+/// extended languages, script, region and variants, then extensions and
+/// private use subtags
+pub fn validate_language_tag(tag: &str) -> Result<(), LanguageTagError> {
+    check_tag(tag).map_err(|reason| LanguageTagError { tag: tag.to_string(), reason })
+}
+
+fn check_tag(tag: &str) -> Result<(), String> {
+    let mut subtags = tag.split('-').peekable();
+    let language = subtags.next().unwrap_or_default();
+    if language.eq_ignore_ascii_case("x") {
+        return private_use(subtags);
+    }
+    if !is_alpha(language, 2..=3) && !is_alpha(language, 5..=8) {
+        return Err(format!("the language '{}' must be 2 or 3 letters", language));
+    }
+
+    let mut extlangs = 0;
+    while language.len() <= 3 && extlangs < 3 && subtags.peek().is_some_and(|s| is_alpha(s, 3..=3)) {
+        subtags.next();
+        extlangs += 1;
+    }
+    subtags.next_if(|s| is_alpha(s, 4..=4));
+    subtags.next_if(|s| is_alpha(s, 2..=2) || (s.len() == 3 && s.bytes().all(|b| b.is_ascii_digit())));
+    while subtags
+        .next_if(|s| is_alphanumeric(s, 5..=8) || (is_alphanumeric(s, 4..=4) && s.starts_with(|c: char| c.is_ascii_digit())))
+        .is_some()
+    {}
+
+    while let Some(singleton) = subtags.next() {
+        if singleton.eq_ignore_ascii_case("x") {
+            return private_use(subtags);
+        }
+        if !is_alphanumeric(singleton, 1..=1) {
+            return Err(format!("unexpected subtag '{}'", singleton));
+        }
+        let mut count = 0;
+        while subtags.next_if(|s| is_alphanumeric(s, 2..=8)).is_some() {
+            count += 1;
+        }
+        if count == 0 {
+            return Err(format!("the extension '{}' has no subtags", singleton));
+        }
+    }
+    Ok(())
+}
+
+fn private_use<'a>(subtags: impl Iterator<Item = &'a str>) -> Result<(), String> {
+    let mut count = 0;
+    for subtag in subtags {
+        if !is_alphanumeric(subtag, 1..=8) {
+            return Err(format!("unexpected private use subtag '{}'", subtag));
+        }
+        count += 1;
+    }
+    if count == 0 {
+        return Err("the private use section is empty".to_string());
+    }
+    Ok(())
+}
+
+fn is_alpha(subtag: &str, length: RangeInclusive<usize>) -> bool {
+    length.contains(&subtag.len()) && subtag.bytes().all(|b| b.is_ascii_alphabetic())
+}
+
+fn is_alphanumeric(subtag: &str, length: RangeInclusive<usize>) -> bool {
+    length.contains(&subtag.len()) && subtag.bytes().all(|b| b.is_ascii_alphanumeric())
+}
+
+/// Selection and checks over the languages of one text
+pub trait LocalizedStrings {
+    /// The entry to show for `locales`, most preferred first
+    ///
+    /// Each locale is tried in turn, exactly and then with trailing subtags
+    /// dropped (`zh-Hant-TW`, `zh-Hant`, `zh`), and finally against any entry
+    /// in the same language (`en-GB` for `en-US`). Without a match the
+    /// untagged entry wins, then the first one.
+    fn best_match(&self, locales: &[&str]) -> Option<&LocalizedString>;
+
+    /// Entries tagged with a language an earlier entry already uses,
+    /// comparing tags without regard to case; untagged entries count as one
+    /// language
+    fn duplicate_languages(&self) -> Vec<&LocalizedString>;
+}
+
+impl LocalizedStrings for [LocalizedString] {
+    fn best_match(&self, locales: &[&str]) -> Option<&LocalizedString> {
+        let tags: Vec<Option<String>> = self.iter().map(LocalizedString::language_tag).collect();
+        let find = |matches: &dyn Fn(&str) -> bool| {
+            tags.iter().position(|tag| tag.as_deref().is_some_and(matches)).map(|i| &self[i])
+        };
+
+        for locale in locales {
+            let mut range = *locale;
+            loop {
+                if let Some(found) = find(&|tag: &str| tag.eq_ignore_ascii_case(range)) {
+                    return Some(found);
+                }
+                match range.rsplit_once('-') {
+                    Some((shorter, _)) => range = shorter,
+                    None => break,
+                }
+            }
+            if let Some(found) = find(&|tag: &str| primary_language(tag).eq_ignore_ascii_case(range)) {
+                return Some(found);
+            }
+        }
+        tags.iter()
+            .position(Option::is_none)
+            .map(|i| &self[i])
+            .or_else(|| self.first())
+    }
+
+    fn duplicate_languages(&self) -> Vec<&LocalizedString> {
+        let mut seen = Vec::new();
+        self.iter()
+            .filter(|string| {
+                let tag = string.language_tag().map(|tag| tag.to_ascii_lowercase());
+                let duplicate = seen.contains(&tag);
+                seen.push(tag);
+                duplicate
+            })
+            .collect()
+    }
+}
+
+fn primary_language(tag: &str) -> &str {
+    tag.split('-').next().unwrap_or(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_language_tag() {
+        for tag in ["en", "en-US", "zh-Hant-TW", "ja-Latn", "es-419", "de-CH-1996", "zh-yue-HK", "en-x-label", "x-private", "sl-rozaj-biske", "en-u-ca-gregory"] {
+            assert!(validate_language_tag(tag).is_ok(), "{} rejected", tag);
+        }
+        for tag in ["", "e", "english1", "en_US", "en-", "en-US-", "en-u", "x", "en-toolongsubtag"] {
+            assert!(validate_language_tag(tag).is_err(), "{} accepted", tag);
+        }
+
+        let mut string = LocalizedString::new("東京").with_language("ja");
+        string.script = Some("Latin".to_string());
+        assert_eq!(string.validate().unwrap_err().to_string(), "'Latin' is not a BCP 47 language tag: a script is 4 letters, e.g. Latn");
+        string.script = Some("Jpan".to_string());
+        assert_eq!(string.language_tag().as_deref(), Some("ja-Jpan"));
+        assert!(string.validate().is_ok());
+    }
+
+    #[test]
+    fn test_best_match() {
+        let titles = vec![
+            LocalizedString::new("Colour"),
+            LocalizedString::new("Color").with_language("en-US"),
+            LocalizedString::new("色").with_language("zh-Hant"),
+            LocalizedString::new("Couleur").with_language("fr"),
+        ];
+        let best = |locales: &[&str]| titles.best_match(locales).map(|t| t.text.as_str());
+        assert_eq!(best(&["fr-CA"]), Some("Couleur"));
+        assert_eq!(best(&["zh-Hant-TW", "en"]), Some("色"));
+        assert_eq!(best(&["en-GB"]), Some("Color"));
+        assert_eq!(best(&["de", "fr"]), Some("Couleur"));
+        assert_eq!(best(&["de"]), Some("Colour"));
+        assert_eq!(titles[1..].best_match(&[]).map(|t| t.text.as_str()), Some("Color"));
+        assert_eq!(Vec::<LocalizedString>::new().best_match(&["en"]), None);
+    }
+
+    #[test]
+    fn test_duplicate_languages() {
+        let titles = vec![
+            LocalizedString::new("One").with_language("en"),
+            LocalizedString::new("Un").with_language("fr"),
+            LocalizedString::new("Uno"),
+            LocalizedString::new("ONE").with_language("EN"),
+            LocalizedString::new("Eins"),
+        ];
+        let duplicates: Vec<_> = titles.duplicate_languages().iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(duplicates, ["ONE", "Eins"]);
+    }
 }
@@ -6,5 +6,5 @@ mod localized;
 mod territory;
 
 pub use identifier::{Identifier, IdentifierType};
-pub use localized::LocalizedString;
+pub use localized::{validate_language_tag, LanguageTagError, LocalizedString, LocalizedStrings};
 pub use territory::{TerritoryCode, Copyright, Price, ValidityPeriod};
\ No newline at end of file
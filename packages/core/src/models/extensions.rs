@@ -11,6 +11,7 @@ use std::collections::HashMap;
 
 /// Comprehensive XML fragment preservation for round-trip fidelity
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct XmlFragment {
     /// The raw XML content as a string
     pub raw_content: String,
@@ -30,17 +31,9 @@ pub struct XmlFragment {
     /// All attributes on this element (including namespaced ones)
     pub attributes: IndexMap<String, String>, // qualified name -> value
     
-    /// Child XML fragments (for nested unknown elements)
-    pub children: Vec<XmlFragment>,
-    
-    /// Text content (if this element contains only text)
-    pub text_content: Option<String>,
-    
-    /// Processing instructions within this fragment
-    pub processing_instructions: Vec<ProcessingInstruction>,
-    
-    /// Comments within this fragment
-    pub comments: Vec<Comment>,
+    /// Child elements, text, comments and processing instructions, in
+    /// document order
+    pub content: Vec<XmlNode>,
     
     /// Position hint for canonical ordering
     pub position_hint: Option<usize>,
@@ -49,8 +42,23 @@ pub struct XmlFragment {
     pub preserve_formatting: bool,
 }
 
+/// A node inside an XML fragment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub enum XmlNode {
+    /// A child element
+    Element(XmlFragment),
+    /// Character data, unescaped
+    Text(String),
+    /// A comment
+    Comment(Comment),
+    /// A processing instruction
+    ProcessingInstruction(ProcessingInstruction),
+}
+
 /// Position of a comment relative to its parent element
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub enum CommentPosition {
     /// Comment appears before the element's opening tag
     Before,
@@ -66,6 +74,7 @@ pub enum CommentPosition {
 
 /// Enhanced comment structure with position and location metadata
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Comment {
     /// The comment content (without <!-- --> markers)
     pub content: String,
@@ -91,6 +100,7 @@ pub struct Comment {
 
 /// XML Processing Instruction
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct ProcessingInstruction {
     /// The target of the processing instruction
     pub target: String,
@@ -101,6 +111,7 @@ pub struct ProcessingInstruction {
 
 /// Extension container with location-aware storage
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Extensions {
     /// Extensions organized by their location in the DDEX structure
     pub fragments: IndexMap<String, XmlFragment>,
@@ -129,10 +140,7 @@ impl XmlFragment {
             namespace_prefix: None,
             namespace_declarations: IndexMap::new(),
             attributes: IndexMap::new(),
-            children: Vec::new(),
-            text_content: None,
-            processing_instructions: Vec::new(),
-            comments: Vec::new(),
+            content: Vec::new(),
             position_hint: None,
             preserve_formatting: false,
         }
@@ -152,10 +160,7 @@ impl XmlFragment {
             namespace_prefix,
             namespace_declarations: IndexMap::new(),
             attributes: IndexMap::new(),
-            children: Vec::new(),
-            text_content: None,
-            processing_instructions: Vec::new(),
-            comments: Vec::new(),
+            content: Vec::new(),
             position_hint: None,
             preserve_formatting: false,
         }
@@ -177,7 +182,17 @@ impl XmlFragment {
     
     /// Add a child fragment
     pub fn add_child(&mut self, child: XmlFragment) {
-        self.children.push(child);
+        self.content.push(XmlNode::Element(child));
+    }
+    
+    /// Add a comment after the current content
+    pub fn add_comment(&mut self, comment: Comment) {
+        self.content.push(XmlNode::Comment(comment));
+    }
+    
+    /// Add a processing instruction after the current content
+    pub fn add_processing_instruction(&mut self, pi: ProcessingInstruction) {
+        self.content.push(XmlNode::ProcessingInstruction(pi));
     }
     
     /// Add an attribute
@@ -232,49 +247,314 @@ impl XmlFragment {
             xml.push_str(&format!(" {}=\"{}\"", name, html_escape::encode_double_quoted_attribute(value)));
         }
         
-        if let Some(ref text) = self.text_content {
+        if self.content.is_empty() {
+            // Self-closing element
+            xml.push_str("/>");
+        } else if self.content.iter().all(|node| matches!(node, XmlNode::Text(_))) {
             // Element with text content
             xml.push('>');
-            xml.push_str(&html_escape::encode_text(text));
+            xml.push_str(&html_escape::encode_text(&self.text().unwrap_or_default()));
             xml.push_str(&format!("</{}>", self.qualified_name()));
-        } else if self.children.is_empty() && self.processing_instructions.is_empty() && self.comments.is_empty() {
-            // Self-closing element
-            xml.push_str("/>");
         } else {
-            // Element with children
+            // Element with children, in document order
             xml.push_str(">\n");
             
-            // Processing instructions
-            for pi in &self.processing_instructions {
-                xml.push_str(&format!("{}  <?{}", indent, pi.target));
-                if let Some(ref data) = pi.data {
-                    xml.push(' ');
-                    xml.push_str(data);
+            for node in &self.content {
+                match node {
+                    XmlNode::Element(child) => xml.push_str(&child.to_canonical_xml(indent_level + 1)),
+                    XmlNode::Text(text) if text.trim().is_empty() => continue,
+                    XmlNode::Text(text) => xml.push_str(&format!("{}  {}", indent, html_escape::encode_text(text.trim()))),
+                    XmlNode::Comment(comment) => {
+                        let comment_indent = match comment.position {
+                            CommentPosition::Before | CommentPosition::After => indent.clone(),
+                            CommentPosition::FirstChild | CommentPosition::LastChild => format!("{}  ", indent),
+                            CommentPosition::Inline => String::new(),
+                        };
+                        xml.push_str(&format!("{}{}", comment_indent, comment.to_xml()));
+                    }
+                    XmlNode::ProcessingInstruction(pi) => xml.push_str(&format!("{}  {}", indent, pi.to_xml())),
                 }
-                xml.push_str("?>\n");
+                xml.push('\n');
             }
             
-            // Comments
-            for comment in &self.comments {
-                let comment_indent = match comment.position {
-                    CommentPosition::Before | CommentPosition::After => indent.clone(),
-                    CommentPosition::FirstChild | CommentPosition::LastChild => format!("{}  ", indent),
-                    CommentPosition::Inline => String::new(),
-                };
-                xml.push_str(&format!("{}{}\n", comment_indent, comment.to_xml()));
-            }
+            xml.push_str(&format!("{}</{}>", indent, self.qualified_name()));
+        }
+        
+        xml
+    }
+}
+
+/// Mini-DOM access to extension fragments
+///
+/// Fragments captured by the parser carry their full element tree, so
+/// proprietary extensions can be read without re-parsing `raw_content`. The
+/// mutation methods keep attributes and content in their original order and
+/// regenerate `raw_content`, so edited fragments round-trip through the
+/// builder. Edits made through [`XmlFragment::child_mut`] only touch the child;
+/// use [`Extensions::update_fragment`] to refresh the top-level fragment.
+impl XmlFragment {
+    /// Parse an XML element into a fragment tree
+    pub fn from_xml(xml: &str) -> Result<Self, String> {
+        Self::from_xml_in_scope(xml, &IndexMap::new())
+    }
+    
+    /// Parse an XML element, resolving prefixes declared by its ancestors
+    pub fn from_xml_in_scope(xml: &str, in_scope: &IndexMap<String, String>) -> Result<Self, String> {
+        use quick_xml::events::{BytesStart, Event};
+        use quick_xml::Reader;
+        
+        fn open(e: &BytesStart, scopes: &mut Vec<IndexMap<String, String>>) -> Result<XmlFragment, String> {
+            let qualified = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+            let mut scope = scopes.last().cloned().unwrap_or_default();
+            let mut fragment = XmlFragment::new(utils::extract_local_name(&qualified).to_string(), String::new());
             
-            // Child elements
-            for child in &self.children {
-                xml.push_str(&child.to_canonical_xml(indent_level + 1));
-                xml.push('\n');
+            for attr in e.attributes() {
+                let attr = attr.map_err(|err| err.to_string())?;
+                let key = String::from_utf8_lossy(attr.key.as_ref()).into_owned();
+                let value = attr.unescape_value().map_err(|err| err.to_string())?.into_owned();
+                if key == "xmlns" || key.starts_with("xmlns:") {
+                    let prefix = key.strip_prefix("xmlns").unwrap_or_default().trim_start_matches(':').to_string();
+                    scope.insert(prefix.clone(), value.clone());
+                    fragment.namespace_declarations.insert(prefix, value);
+                } else {
+                    fragment.attributes.insert(key, value);
+                }
             }
             
-            xml.push_str(&format!("{}</{}>", indent, self.qualified_name()));
+            let prefix = qualified.contains(':').then(|| utils::extract_namespace_prefix(&qualified)).flatten();
+            fragment.namespace_prefix = prefix.map(str::to_string);
+            fragment.namespace_uri = scope.get(prefix.unwrap_or_default()).cloned();
+            scopes.push(scope);
+            Ok(fragment)
+        }
+        
+        let mut reader = Reader::from_str(xml);
+        reader.config_mut().trim_text(true);
+        let mut scopes = vec![in_scope.clone()];
+        let mut stack: Vec<XmlFragment> = Vec::new();
+        
+        loop {
+            let mut fragment = match reader.read_event().map_err(|err| err.to_string())? {
+                Event::Start(ref e) => {
+                    stack.push(open(e, &mut scopes)?);
+                    continue;
+                }
+                Event::Empty(ref e) => open(e, &mut scopes)?,
+                Event::End(_) => stack.pop().ok_or_else(|| "Unbalanced end tag".to_string())?,
+                Event::Text(ref t) => {
+                    let text = t.unescape().map_err(|err| err.to_string())?;
+                    if let Some(current) = stack.last_mut() {
+                        current.push_text(&text);
+                    }
+                    continue;
+                }
+                Event::CData(ref t) => {
+                    if let Some(current) = stack.last_mut() {
+                        current.push_text(&String::from_utf8_lossy(t));
+                    }
+                    continue;
+                }
+                Event::Comment(ref t) => {
+                    if let Some(current) = stack.last_mut() {
+                        let position = if current.children().next().is_none() { CommentPosition::FirstChild } else { CommentPosition::LastChild };
+                        current.add_comment(Comment::new(String::from_utf8_lossy(t).into_owned(), position));
+                    }
+                    continue;
+                }
+                Event::PI(ref pi) => {
+                    if let Some(current) = stack.last_mut() {
+                        let target = String::from_utf8_lossy(pi.target()).into_owned();
+                        let data = String::from_utf8_lossy(pi.content()).trim().to_string();
+                        current.add_processing_instruction(ProcessingInstruction::new(target, (!data.is_empty()).then_some(data)));
+                    }
+                    continue;
+                }
+                Event::Eof => return Err("No element found".to_string()),
+                _ => continue,
+            };
+            
+            scopes.pop();
+            fragment.sync_raw_content();
+            match stack.last_mut() {
+                Some(parent) => parent.add_child(fragment),
+                // Anything after the first complete element is ignored
+                None => return Ok(fragment),
+            }
+        }
+    }
+    
+    /// Append text, merging it with text directly before it
+    fn push_text(&mut self, text: &str) {
+        match self.content.last_mut() {
+            Some(XmlNode::Text(last)) => last.push_str(text),
+            _ => self.content.push(XmlNode::Text(text.to_string())),
+        }
+    }
+    
+    /// Text content of this element, its text nodes joined; `None` if it
+    /// has none
+    pub fn text(&self) -> Option<String> {
+        let mut texts = self.content.iter().filter_map(|node| match node {
+            XmlNode::Text(text) => Some(text.as_str()),
+            _ => None,
+        }).peekable();
+        texts.peek()?;
+        Some(texts.collect())
+    }
+    
+    /// Child elements in document order
+    pub fn children(&self) -> impl Iterator<Item = &XmlFragment> {
+        self.content.iter().filter_map(|node| match node {
+            XmlNode::Element(child) => Some(child),
+            _ => None,
+        })
+    }
+    
+    /// Child elements in document order, for editing
+    pub fn children_mut(&mut self) -> impl Iterator<Item = &mut XmlFragment> {
+        self.content.iter_mut().filter_map(|node| match node {
+            XmlNode::Element(child) => Some(child),
+            _ => None,
+        })
+    }
+    
+    /// Comments directly inside this element
+    pub fn comments(&self) -> impl Iterator<Item = &Comment> {
+        self.content.iter().filter_map(|node| match node {
+            XmlNode::Comment(comment) => Some(comment),
+            _ => None,
+        })
+    }
+    
+    /// Processing instructions directly inside this element
+    pub fn processing_instructions(&self) -> impl Iterator<Item = &ProcessingInstruction> {
+        self.content.iter().filter_map(|node| match node {
+            XmlNode::ProcessingInstruction(pi) => Some(pi),
+            _ => None,
+        })
+    }
+    
+    /// Value of an attribute by qualified name
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(String::as_str)
+    }
+    
+    /// First child element with the given local name
+    pub fn child(&self, name: &str) -> Option<&XmlFragment> {
+        self.children().find(|c| c.element_name == name)
+    }
+    
+    /// First child element with the given local name, for editing
+    pub fn child_mut(&mut self, name: &str) -> Option<&mut XmlFragment> {
+        self.children_mut().find(|c| c.element_name == name)
+    }
+    
+    /// Child elements in the given namespace
+    pub fn children_ns<'a>(&'a self, namespace_uri: &'a str) -> impl Iterator<Item = &'a XmlFragment> + 'a {
+        self.children().filter(move |c| c.is_from_namespace(namespace_uri))
+    }
+    
+    /// All descendant elements in document order
+    pub fn descendants(&self) -> Vec<&XmlFragment> {
+        let mut result = Vec::new();
+        for child in self.children() {
+            result.push(child);
+            result.extend(child.descendants());
+        }
+        result
+    }
+    
+    /// Set an attribute, keeping its position if it already exists
+    pub fn set_attribute(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.attributes.insert(name.into(), value.into());
+        self.sync_raw_content();
+    }
+    
+    /// Remove an attribute, keeping the order of the others
+    pub fn remove_attribute(&mut self, name: &str) -> Option<String> {
+        let removed = self.attributes.shift_remove(name);
+        self.sync_raw_content();
+        removed
+    }
+    
+    /// Replace the text content of this element, in place of its first
+    /// text node (or before everything else if it has none)
+    pub fn set_text(&mut self, text: Option<String>) {
+        let position = self.content.iter().position(|node| matches!(node, XmlNode::Text(_))).unwrap_or(0);
+        self.content.retain(|node| !matches!(node, XmlNode::Text(_)));
+        if let Some(text) = text {
+            self.content.insert(position.min(self.content.len()), XmlNode::Text(text));
+        }
+        self.sync_raw_content();
+    }
+    
+    /// Position in `content` of the child element at `index`
+    fn child_position(&self, index: usize) -> Option<usize> {
+        self.content
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| matches!(node, XmlNode::Element(_)))
+            .nth(index)
+            .map(|(position, _)| position)
+    }
+    
+    /// Insert a child element before the child element at `index`
+    /// (appending when past the end)
+    pub fn insert_child(&mut self, index: usize, child: XmlFragment) {
+        let position = self.child_position(index).unwrap_or(self.content.len());
+        self.content.insert(position, XmlNode::Element(child));
+        self.sync_raw_content();
+    }
+    
+    /// Remove the child element at `index`
+    pub fn remove_child(&mut self, index: usize) -> Option<XmlFragment> {
+        let removed = self.child_position(index).and_then(|position| match self.content.remove(position) {
+            XmlNode::Element(child) => Some(child),
+            _ => None,
+        });
+        self.sync_raw_content();
+        removed
+    }
+    
+    /// Serialize the element tree compactly, keeping declarations,
+    /// attributes and content in the order stored
+    pub fn to_xml(&self) -> String {
+        let name = self.qualified_name();
+        let mut xml = format!("<{}", name);
+        
+        for (prefix, uri) in &self.namespace_declarations {
+            if prefix.is_empty() {
+                xml.push_str(&format!(" xmlns=\"{}\"", html_escape::encode_double_quoted_attribute(uri)));
+            } else {
+                xml.push_str(&format!(" xmlns:{}=\"{}\"", prefix, html_escape::encode_double_quoted_attribute(uri)));
+            }
+        }
+        for (attr, value) in &self.attributes {
+            xml.push_str(&format!(" {}=\"{}\"", attr, html_escape::encode_double_quoted_attribute(value)));
         }
         
+        if self.content.is_empty() {
+            xml.push_str("/>");
+            return xml;
+        }
+        
+        xml.push('>');
+        for node in &self.content {
+            match node {
+                XmlNode::Element(child) => xml.push_str(&child.to_xml()),
+                XmlNode::Text(text) => xml.push_str(&html_escape::encode_text(text)),
+                XmlNode::Comment(comment) => xml.push_str(&comment.to_xml()),
+                XmlNode::ProcessingInstruction(pi) => xml.push_str(&pi.to_xml()),
+            }
+        }
+        xml.push_str(&format!("</{}>", name));
         xml
     }
+    
+    /// Regenerate `raw_content` from the element tree after edits
+    pub fn sync_raw_content(&mut self) {
+        self.raw_content = self.to_xml();
+    }
 }
 
 impl Comment {
@@ -358,6 +638,14 @@ impl ProcessingInstruction {
     pub fn new(target: String, data: Option<String>) -> Self {
         Self { target, data }
     }
+    
+    /// Format as an XML processing instruction
+    pub fn to_xml(&self) -> String {
+        match self.data {
+            Some(ref data) => format!("<?{} {}?>", self.target, data),
+            None => format!("<?{}?>", self.target),
+        }
+    }
 }
 
 impl Extensions {
@@ -390,6 +678,52 @@ impl Extensions {
             .collect()
     }
     
+    /// Top-level extension elements in the given namespace, in document order
+    pub fn get_ns(&self, namespace_uri: &str) -> Vec<&XmlFragment> {
+        self.fragments
+            .values()
+            .filter(|fragment| fragment.is_from_namespace(namespace_uri))
+            .collect()
+    }
+    
+    /// All top-level extension elements with their locations, in document order
+    pub fn elements(&self) -> impl Iterator<Item = (&str, &XmlFragment)> {
+        self.fragments.iter().map(|(location, fragment)| (location.as_str(), fragment))
+    }
+    
+    /// Edit a fragment in place, regenerating its raw content afterwards.
+    /// Returns false if there is no fragment at `location`.
+    pub fn update_fragment(&mut self, location: &str, edit: impl FnOnce(&mut XmlFragment)) -> bool {
+        match self.fragments.get_mut(location) {
+            Some(fragment) => {
+                edit(fragment);
+                fragment.sync_raw_content();
+                true
+            }
+            None => false,
+        }
+    }
+    
+    /// Insert a fragment directly after `anchor`, or at the end if `anchor`
+    /// is not present
+    pub fn insert_fragment_after(&mut self, anchor: &str, location: String, fragment: XmlFragment) {
+        match self.fragments.get_index_of(anchor) {
+            Some(index) => {
+                self.fragments.shift_remove(&location);
+                let index = self.fragments.get_index_of(anchor).unwrap_or(index);
+                self.fragments.shift_insert(index + 1, location, fragment);
+            }
+            None => {
+                self.fragments.insert(location, fragment);
+            }
+        }
+    }
+    
+    /// Remove a fragment, keeping the order of the remaining ones
+    pub fn remove_fragment(&mut self, location: &str) -> Option<XmlFragment> {
+        self.fragments.shift_remove(location)
+    }
+    
     /// Add a global namespace declaration
     pub fn add_global_namespace(&mut self, prefix: String, uri: String) {
         self.global_namespaces.insert(prefix, uri);
@@ -574,7 +908,7 @@ mod tests {
         );
         
         fragment.add_attribute("attr".to_string(), "value".to_string());
-        fragment.text_content = Some("text".to_string());
+        fragment.content.push(XmlNode::Text("text".to_string()));
         
         let xml = fragment.to_canonical_xml(0);
         assert!(xml.contains("<customElement attr=\"value\">text</customElement>"));
@@ -591,6 +925,79 @@ mod tests {
         assert_eq!(key, "message/header/http://example.com/ns/customElement");
     }
     
+    #[test]
+    fn test_fragment_mini_dom() {
+        let mut in_scope = IndexMap::new();
+        in_scope.insert("sp".to_string(), "http://spotify.com/ddex".to_string());
+        let fragment = XmlFragment::from_xml_in_scope(
+            r#"<sp:Promo Priority="high" Id="7"><sp:Playlist Name="Hits"/><Note>Q1 &amp; Q2</Note></sp:Promo>"#,
+            &in_scope,
+        ).unwrap();
+        
+        assert_eq!(fragment.element_name, "Promo");
+        assert!(fragment.is_from_namespace("http://spotify.com/ddex"));
+        assert_eq!(fragment.attribute("Priority"), Some("high"));
+        assert_eq!(fragment.child("Playlist").and_then(|p| p.attribute("Name")), Some("Hits"));
+        assert_eq!(fragment.child("Note").and_then(XmlFragment::text).as_deref(), Some("Q1 & Q2"));
+        assert_eq!(fragment.children_ns("http://spotify.com/ddex").count(), 1);
+        assert_eq!(fragment.descendants().len(), 2);
+    }
+    
+    #[test]
+    fn test_fragment_edits_preserve_order() {
+        let mut fragment = XmlFragment::from_xml(r#"<Promo B="2" A="1"><First/><Last/></Promo>"#).unwrap();
+        
+        fragment.set_attribute("B", "3");
+        fragment.set_attribute("C", "4");
+        fragment.insert_child(1, XmlFragment::from_xml("<Middle>x</Middle>").unwrap());
+        
+        assert_eq!(fragment.raw_content, r#"<Promo B="3" A="1" C="4"><First/><Middle>x</Middle><Last/></Promo>"#);
+        
+        fragment.remove_attribute("B");
+        fragment.remove_child(0);
+        assert_eq!(fragment.to_xml(), r#"<Promo A="1" C="4"><Middle>x</Middle><Last/></Promo>"#);
+    }
+    
+    #[test]
+    fn test_fragment_content_keeps_document_order() {
+        let xml = r#"<Promo><A/>text<?render fast?><!--note--><B>x</B>tail</Promo>"#;
+        let mut fragment = XmlFragment::from_xml(xml).unwrap();
+        assert_eq!(fragment.raw_content, xml);
+        assert_eq!(fragment.text().as_deref(), Some("texttail"));
+        assert_eq!(fragment.comments().count(), 1);
+        assert_eq!(fragment.processing_instructions().next().unwrap().data.as_deref(), Some("fast"));
+        
+        fragment.remove_child(0);
+        fragment.insert_child(1, XmlFragment::from_xml("<C/>").unwrap());
+        assert_eq!(fragment.raw_content, r#"<Promo>text<?render fast?><!--note--><B>x</B>tail<C/></Promo>"#);
+        
+        fragment.set_text(Some("only".to_string()));
+        assert_eq!(fragment.to_xml(), r#"<Promo>only<?render fast?><!--note--><B>x</B><C/></Promo>"#);
+    }
+    
+    #[test]
+    fn test_extensions_namespace_access_and_ordering() {
+        let mut extensions = Extensions::new();
+        let spotify = XmlFragment::with_namespace("Promo".to_string(), Some("http://spotify.com/ddex".to_string()), Some("sp".to_string()), "<sp:Promo/>".to_string());
+        let apple = XmlFragment::with_namespace("Hint".to_string(), Some("http://apple.com/ddex".to_string()), Some("ap".to_string()), "<ap:Hint/>".to_string());
+        extensions.add_fragment("Release/sp".to_string(), spotify);
+        extensions.add_fragment("Release/ap".to_string(), apple);
+        
+        assert_eq!(extensions.get_ns("http://apple.com/ddex").len(), 1);
+        
+        extensions.insert_fragment_after("Release/sp", "Release/extra".to_string(), XmlFragment::from_xml("<Extra/>").unwrap());
+        let order: Vec<&str> = extensions.elements().map(|(location, _)| location).collect();
+        assert_eq!(order, vec!["Release/sp", "Release/extra", "Release/ap"]);
+        
+        assert!(extensions.update_fragment("Release/sp", |f| { f.attributes.insert("Week".to_string(), "12".to_string()); }));
+        assert!(!extensions.update_fragment("Release/missing", |_| {}));
+        assert_eq!(extensions.get_fragment("Release/sp").unwrap().raw_content, r#"<sp:Promo Week="12"/>"#);
+        
+        extensions.remove_fragment("Release/extra");
+        let order: Vec<&str> = extensions.elements().map(|(location, _)| location).collect();
+        assert_eq!(order, vec!["Release/sp", "Release/ap"]);
+    }
+    
     #[test]
     fn test_ddex_namespace_detection() {
         assert!(utils::is_ddex_namespace("http://ddex.net/xml/ern/43"));
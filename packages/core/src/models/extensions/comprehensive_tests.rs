@@ -33,7 +33,7 @@ mod extension_tests {
 
         fragment.add_attribute("id".to_string(), "123".to_string());
         fragment.add_attribute("name".to_string(), "test".to_string());
-        fragment.text_content = Some("Hello World".to_string());
+        fragment.content.push(XmlNode::Text("Hello World".to_string()));
 
         let canonical = fragment.to_canonical_xml(2);
         
@@ -219,22 +219,23 @@ mod extension_tests {
         parent.add_child(child1);
         parent.add_child(child2);
 
-        assert_eq!(parent.children.len(), 2);
-        assert_eq!(parent.children[0].element_name, "child1");
-        assert_eq!(parent.children[1].element_name, "child2");
+        let children: Vec<&XmlFragment> = parent.children().collect();
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0].element_name, "child1");
+        assert_eq!(children[1].element_name, "child2");
     }
 
     #[test]
     fn test_xml_fragment_with_comments_and_processing_instructions() {
         let mut fragment = XmlFragment::new("test".to_string(), String::new());
         
-        fragment.comments.push(Comment::new("This is a comment".to_string(), CommentPosition::Before));
-        fragment.processing_instructions.push(ProcessingInstruction::new("test".to_string(), Some("data".to_string())));
+        fragment.add_comment(Comment::new("This is a comment".to_string(), CommentPosition::Before));
+        fragment.add_processing_instruction(ProcessingInstruction::new("test".to_string(), Some("data".to_string())));
 
-        assert_eq!(fragment.comments.len(), 1);
-        assert_eq!(fragment.processing_instructions.len(), 1);
-        assert_eq!(fragment.comments[0].content, "This is a comment");
-        assert_eq!(fragment.processing_instructions[0].target, "test");
+        assert_eq!(fragment.comments().count(), 1);
+        assert_eq!(fragment.processing_instructions().count(), 1);
+        assert_eq!(fragment.comments().next().unwrap().content, "This is a comment");
+        assert_eq!(fragment.processing_instructions().next().unwrap().target, "test");
     }
 
     #[test]
@@ -255,8 +256,8 @@ mod extension_tests {
         fragment.add_attribute("alpha".to_string(), "first".to_string());
 
         // Add comments and processing instructions
-        fragment.comments.push(Comment::new("Element comment".to_string(), CommentPosition::Before));
-        fragment.processing_instructions.push(ProcessingInstruction::new("element-pi".to_string(), None));
+        fragment.add_comment(Comment::new("Element comment".to_string(), CommentPosition::Before));
+        fragment.add_processing_instruction(ProcessingInstruction::new("element-pi".to_string(), None));
 
         let canonical = fragment.to_canonical_xml(0);
 
@@ -0,0 +1,821 @@
+// core/src/models/flat/convert.rs
+//! Conversion between the graph and flattened models
+//!
+//! [`ERNMessage::flatten`] builds the developer-friendly view of a parsed
+//! message and [`FlattenedMessage::to_graph`] rebuilds a graph from it, so a
+//! message can be parsed, edited through the flat view and handed back to the
+//! builder. The flat view is a projection, so the round trip is lossy:
+//!
+//! - Attributes, extensions and comments are not carried by the flat view and
+//!   come back as `None`.
+//! - Only the first genre, release date and set of technical details are
+//!   flattened, and resources keep only their default title.
+//! - Release identifiers keep UPC, EAN, GRid and namespaced proprietary IDs.
+//!   Un-namespaced proprietary IDs and catalog numbers do not survive.
+//! - Deals without a `DealReference` get a positional `deal_N` id, which
+//!   becomes their reference when converted back.
+//! - Price information other than wholesale and suggested retail prices is
+//!   not flattened, and commercial model types are dropped.
+//! - Placeholder values used by the flat view (`"Unknown"`, `"Untitled"`,
+//!   `"NO_ID"`) are treated as absent when converting back.
+//! - Flattened resources are keyed by reference in a `HashMap`; [`to_graph`]
+//!   restores them in track order, followed by unreferenced resources sorted by
+//!   reference. Parties are likewise restored sorted by their key.
+//!
+//! [`to_graph`]: FlattenedMessage::to_graph
+
+use super::{
+    ArtistInfo, DealValidity, DistributionComplexity, FlattenedMessage, MessageStats, Organization,
+    ParsedDeal, ParsedRelease, ParsedResource, ParsedTrack, PriceTier, PriceType, ProprietaryId,
+    ReleaseIdentifiers, TechnicalInfo, TerritoryComplexity, TerritoryInfo,
+};
+use crate::models::common::{Identifier, IdentifierType, LocalizedString, LocalizedStrings};
+use crate::models::graph::{
+    Artist, Deal, DealTerms, DistributionChannel, ERNMessage, ERNProfile, Genre, MessageHeader,
+    MessageRecipient, MessageSender, MessageType, Party, PriceInformation, PurgedRelease, Release,
+    ReleaseDateKind, ReleaseEvent, ReleaseResourceReference, ReleaseType, Resource, ResourceType, TechnicalDetails,
+    UpdateAction, UseType,
+};
+use crate::models::versions::ERNVersion;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const UNKNOWN: &str = "Unknown";
+const UNTITLED: &str = "Untitled";
+const NO_ID: &str = "NO_ID";
+
+impl ERNMessage {
+    /// Build the flattened, developer-friendly view of this message
+    pub fn flatten(&self) -> FlattenedMessage {
+        self.flatten_for(&[])
+    }
+
+    /// Build the flattened view, choosing the default titles and names in
+    /// the first of `locales` the message offers (see
+    /// [`LocalizedStrings::best_match`])
+    pub fn flatten_for(&self, locales: &[&str]) -> FlattenedMessage {
+        let releases: Vec<ParsedRelease> = self.releases.iter()
+            .map(|release| flatten_release(release, &self.resources, locales))
+            .collect();
+        let resources: HashMap<String, ParsedResource> = self.resources.iter()
+            .map(|resource| (resource.resource_reference.clone(), flatten_resource(resource, locales)))
+            .collect();
+        let deals = self.deals.iter().enumerate()
+            .map(|(index, deal)| flatten_deal(deal, index))
+            .collect();
+        let parties = self.parties.iter()
+            .map(|party| (primary_id(&party.party_id), party.clone()))
+            .collect();
+
+        let stats = MessageStats {
+            release_count: self.releases.len(),
+            track_count: releases.iter().map(|r| r.track_count).sum(),
+            deal_count: self.deals.len(),
+            total_duration: resources.values()
+                .filter_map(|r| r.duration)
+                .map(|d| d.as_secs())
+                .sum(),
+        };
+
+        let header = &self.message_header;
+        FlattenedMessage {
+            message_id: header.message_id.clone(),
+            message_type: format!("{:?}", header.message_type),
+            message_date: header.message_created_date_time,
+            sender: Organization {
+                name: primary_name(&header.message_sender.party_name, locales),
+                id: primary_id(&header.message_sender.party_id),
+                extensions: None,
+            },
+            recipient: Organization {
+                name: primary_name(&header.message_recipient.party_name, locales),
+                id: primary_id(&header.message_recipient.party_id),
+                extensions: None,
+            },
+            releases,
+            resources,
+            deals,
+            parties,
+            version: format!("{:?}", self.version),
+            profile: self.profile.as_ref().map(|p| format!("{:?}", p)),
+            stats,
+            update_actions: self.update_actions(),
+            extensions: None,
+        }
+    }
+}
+
+impl FlattenedMessage {
+    /// Rebuild a graph model from the flattened view (see the module docs for
+    /// what does not survive the round trip)
+    pub fn to_graph(&self) -> ERNMessage {
+        let message_header = MessageHeader {
+            message_id: self.message_id.clone(),
+            message_type: message_type(&self.message_type),
+            message_created_date_time: self.message_date,
+            message_sender: MessageSender {
+                party_id: party_ids(&self.sender),
+                party_name: party_names(&self.sender),
+                trading_name: None,
+                attributes: None,
+                extensions: None,
+                comments: None,
+            },
+            message_recipient: MessageRecipient {
+                party_id: party_ids(&self.recipient),
+                party_name: party_names(&self.recipient),
+                trading_name: None,
+                attributes: None,
+                extensions: None,
+                comments: None,
+            },
+            message_control_type: None,
+            message_thread_id: None,
+            attributes: None,
+            extensions: None,
+            comments: None,
+        };
+
+        let mut parties: Vec<(&String, &Party)> = self.parties.iter().collect();
+        parties.sort_by(|a, b| a.0.cmp(b.0));
+
+        ERNMessage {
+            message_header,
+            parties: parties.into_iter().map(|(_, party)| party.clone()).collect(),
+            resources: self.graph_resources(),
+            releases: self.releases.iter().map(graph_release).collect(),
+            deals: self.deals.iter().map(graph_deal).collect(),
+            purged_releases: self.update_actions.iter()
+                .filter_map(|action| match action {
+                    UpdateAction::PurgeRelease { release_id } => Some(PurgedRelease {
+                        release_id: release_id.clone(),
+                        title: Vec::new(),
+                        attributes: None,
+                        extensions: None,
+                        comments: None,
+                    }),
+                    _ => None,
+                })
+                .collect(),
+            version: version(&self.version),
+            profile: self.profile.as_deref().and_then(profile),
+            message_audit_trail: None,
+            attributes: None,
+            extensions: None,
+            legacy_extensions: None,
+            comments: None,
+        }
+    }
+
+    fn graph_resources(&self) -> Vec<Resource> {
+        let tracks: Vec<&ParsedTrack> = self.releases.iter().flat_map(|r| &r.tracks).collect();
+
+        let mut order: Vec<&String> = Vec::new();
+        for track in &tracks {
+            if let Some((key, _)) = self.resources.get_key_value(&track.track_id) {
+                if !order.contains(&key) {
+                    order.push(key);
+                }
+            }
+        }
+        let mut rest: Vec<&String> = self.resources.keys().filter(|k| !order.contains(k)).collect();
+        rest.sort();
+        order.extend(rest);
+
+        order.into_iter().map(|key| {
+            let resource = &self.resources[key];
+            let isrc = tracks.iter()
+                .find(|t| &t.track_id == key)
+                .and_then(|t| t.isrc.clone());
+            graph_resource(resource, isrc)
+        }).collect()
+    }
+}
+
+fn flatten_release(release: &Release, resources: &[Resource], locales: &[&str]) -> ParsedRelease {
+    let refs = &release.release_resource_reference_list;
+    ParsedRelease {
+        release_id: release.release_reference.clone(),
+        identifiers: release_identifiers(&release.release_id),
+        title: release.release_title.clone(),
+        default_title: primary_title(&release.release_title, locales),
+        subtitle: release.release_subtitle.clone(),
+        default_subtitle: release.release_subtitle.as_ref().map(|s| primary_title(s, locales)),
+        display_artist: release.display_artist.iter()
+            .map(|a| primary_name(&a.display_artist_name, locales))
+            .collect::<Vec<_>>()
+            .join(", "),
+        artists: release.display_artist.iter().map(|artist| ArtistInfo {
+            name: primary_name(&artist.display_artist_name, locales),
+            role: artist.artist_role.first().cloned().unwrap_or_else(|| "Artist".to_string()),
+            party_id: artist.party_reference.clone(),
+        }).collect(),
+        release_type: match &release.release_type {
+            Some(ReleaseType::Other(other)) => other.clone(),
+            Some(release_type) => format!("{:?}", release_type),
+            None => UNKNOWN.to_string(),
+        },
+        genre: release.genre.first().map(|g| g.genre_text.clone()),
+        sub_genre: release.genre.first().and_then(|g| g.sub_genre.clone()),
+        tracks: refs.iter().enumerate().map(|(idx, rref)| flatten_track(rref, idx, resources, locales)).collect(),
+        track_count: refs.len(),
+        disc_count: refs.iter().filter_map(|r| r.disc_number).max().map(|n| n as usize),
+        videos: Vec::new(),
+        images: Vec::new(),
+        cover_art: None,
+        release_date: release.date(ReleaseDateKind::ReleaseDate),
+        original_release_date: release.date(ReleaseDateKind::OriginalReleaseDate),
+        territories: release.territory_code.iter().map(|code| (code, true))
+            .chain(release.excluded_territory_code.iter().map(|code| (code, false)))
+            .map(|(code, included)| TerritoryInfo {
+                code: code.clone(),
+                included,
+                start_date: None,
+                end_date: None,
+                distribution_channels: Vec::new(),
+            })
+            .collect(),
+        p_line: None,
+        c_line: None,
+        parent_release: None,
+        child_releases: Vec::new(),
+        extensions: None,
+    }
+}
+
+fn flatten_track(rref: &ReleaseResourceReference, idx: usize, resources: &[Resource], locales: &[&str]) -> ParsedTrack {
+    let resource = resources.iter().find(|r| r.resource_reference == rref.resource_reference);
+    let duration = resource.and_then(|r| r.duration);
+
+    ParsedTrack {
+        track_id: rref.resource_reference.clone(),
+        isrc: resource.and_then(|r| {
+            r.resource_id.iter()
+                .find(|id| id.id_type == IdentifierType::ISRC)
+                .map(|id| id.value.clone())
+        }),
+        iswc: None,
+        position: idx + 1,
+        track_number: rref.track_number,
+        disc_number: rref.disc_number,
+        side: rref.side.clone(),
+        title: resource.map(|r| primary_title(&r.reference_title, locales))
+            .unwrap_or_else(|| "Unknown Track".to_string()),
+        subtitle: None,
+        display_artist: String::new(),
+        artists: Vec::new(),
+        duration: duration.unwrap_or_default(),
+        duration_formatted: duration.map(ParsedTrack::format_duration)
+            .unwrap_or_else(|| "0:00".to_string()),
+        file_format: None,
+        bitrate: None,
+        sample_rate: None,
+        is_hidden: rref.is_hidden,
+        is_bonus: rref.is_bonus,
+        is_explicit: false,
+        is_instrumental: false,
+    }
+}
+
+fn flatten_resource(resource: &Resource, locales: &[&str]) -> ParsedResource {
+    let details = resource.technical_details.first();
+    ParsedResource {
+        resource_id: resource.resource_reference.clone(),
+        resource_type: format!("{:?}", resource.resource_type),
+        title: primary_title(&resource.reference_title, locales),
+        duration: resource.duration,
+        technical_details: TechnicalInfo {
+            file_format: details.and_then(|t| t.file_format.clone()),
+            bitrate: details.and_then(|t| t.bitrate),
+            sample_rate: details.and_then(|t| t.sample_rate),
+            file_size: details.and_then(|t| t.file_size),
+        },
+    }
+}
+
+fn flatten_deal(deal: &Deal, index: usize) -> ParsedDeal {
+    let terms = &deal.deal_terms;
+    let prices = terms.wholesale_price.iter().map(|p| (PriceType::Wholesale, p))
+        .chain(terms.suggested_retail_price.iter().map(|p| (PriceType::SuggestedRetail, p)));
+
+    ParsedDeal {
+        deal_id: deal.deal_reference.clone().unwrap_or_else(|| format!("deal_{}", index + 1)),
+        releases: deal.deal_release_reference.clone(),
+        validity: DealValidity {
+            start: terms.start_date,
+            end: terms.end_date,
+        },
+        territories: TerritoryComplexity {
+            included: terms.territory_code.clone(),
+            excluded: terms.excluded_territory_code.clone(),
+        },
+        distribution_channels: DistributionComplexity {
+            included: terms.distribution_channel.iter().map(channel_name).collect(),
+            excluded: terms.excluded_distribution_channel.iter().map(channel_name).collect(),
+        },
+        pricing: prices.map(|(price_type, price)| PriceTier {
+            tier_name: None,
+            price_type,
+            price: price.clone(),
+            territory: price.territory.clone(),
+            start_date: terms.start_date,
+            end_date: terms.end_date,
+        }).collect(),
+        usage_rights: terms.use_type.iter().map(|u| match u {
+            UseType::Other(other) => other.clone(),
+            use_type => format!("{:?}", use_type),
+        }).collect(),
+        restrictions: Vec::new(),
+    }
+}
+
+fn graph_release(release: &ParsedRelease) -> Release {
+    Release {
+        release_reference: release.release_id.clone(),
+        release_id: graph_identifiers(&release.identifiers),
+        release_title: release.title.clone(),
+        release_subtitle: release.subtitle.clone(),
+        release_type: match release.release_type.as_str() {
+            UNKNOWN => None,
+            "Album" => Some(ReleaseType::Album),
+            "Single" => Some(ReleaseType::Single),
+            "EP" => Some(ReleaseType::EP),
+            "Compilation" => Some(ReleaseType::Compilation),
+            other => Some(ReleaseType::Other(other.to_string())),
+        },
+        genre: release.genre.iter().map(|genre| Genre {
+            genre_text: genre.clone(),
+            sub_genre: release.sub_genre.clone(),
+            attributes: None,
+            extensions: None,
+            comments: None,
+        }).collect(),
+        release_resource_reference_list: release.tracks.iter().map(|track| ReleaseResourceReference {
+            resource_reference: track.track_id.clone(),
+            sequence_number: Some(track.position as i32),
+            disc_number: track.disc_number,
+            track_number: track.track_number,
+            side: track.side.clone(),
+            is_hidden: track.is_hidden,
+            is_bonus: track.is_bonus,
+            extensions: None,
+            comments: None,
+        }).collect(),
+        display_artist: release.artists.iter().map(|artist| Artist {
+            party_reference: artist.party_id.clone(),
+            artist_role: vec![artist.role.clone()],
+            display_artist_name: vec![LocalizedString::new(&artist.name)],
+            sequence_number: None,
+        }).collect(),
+        party_list: Vec::new(),
+        release_date: [
+            (ReleaseDateKind::ReleaseDate, release.release_date),
+            (ReleaseDateKind::OriginalReleaseDate, release.original_release_date),
+        ]
+        .into_iter()
+        .filter_map(|(kind, date)| date.map(|date| ReleaseEvent {
+            release_event_type: format!("{:?}", kind),
+            event_date: Some(date),
+            territory: None,
+            extensions: None,
+            comments: None,
+        }))
+        .collect(),
+        territory_code: release.territories.iter().filter(|t| t.included).map(|t| t.code.clone()).collect(),
+        excluded_territory_code: release.territories.iter().filter(|t| !t.included).map(|t| t.code.clone()).collect(),
+        attributes: None,
+        extensions: None,
+        comments: None,
+    }
+}
+
+fn graph_resource(resource: &ParsedResource, isrc: Option<String>) -> Resource {
+    let details = &resource.technical_details;
+    let has_details = details.file_format.is_some() || details.bitrate.is_some()
+        || details.sample_rate.is_some() || details.file_size.is_some();
+
+    Resource {
+        resource_reference: resource.resource_id.clone(),
+        resource_type: match resource.resource_type.as_str() {
+            "Video" => ResourceType::Video,
+            "Image" => ResourceType::Image,
+            "Text" => ResourceType::Text,
+            "SheetMusic" => ResourceType::SheetMusic,
+            _ => ResourceType::SoundRecording,
+        },
+        resource_id: isrc.into_iter()
+            .map(|value| Identifier { id_type: IdentifierType::ISRC, namespace: None, value })
+            .collect(),
+        reference_title: known(&resource.title, UNTITLED).map(LocalizedString::new).into_iter().collect(),
+        duration: resource.duration.filter(|d| *d != Duration::ZERO),
+        technical_details: has_details.then(|| TechnicalDetails {
+            technical_resource_details_reference: format!("T{}", resource.resource_id),
+            audio_codec: None,
+            bitrate: details.bitrate,
+            sample_rate: details.sample_rate,
+            file_format: details.file_format.clone(),
+            file_size: details.file_size,
+            extensions: None,
+        }).into_iter().collect(),
+        rights_controller: Vec::new(),
+        p_line: Vec::new(),
+        c_line: Vec::new(),
+        extensions: None,
+        comments: None,
+    }
+}
+
+fn graph_deal(deal: &ParsedDeal) -> Deal {
+    let prices = |price_type: PriceType| -> Vec<_> {
+        deal.pricing.iter().filter(|t| t.price_type == price_type).map(|t| t.price.clone()).collect()
+    };
+
+    Deal {
+        deal_reference: Some(deal.deal_id.clone()),
+        deal_release_reference: deal.releases.clone(),
+        deal_terms: DealTerms {
+            validity_period: None,
+            start_date: deal.validity.start,
+            end_date: deal.validity.end,
+            territory_code: deal.territories.included.clone(),
+            excluded_territory_code: deal.territories.excluded.clone(),
+            distribution_channel: deal.distribution_channels.included.iter().map(|c| channel(c)).collect(),
+            excluded_distribution_channel: deal.distribution_channels.excluded.iter().map(|c| channel(c)).collect(),
+            commercial_model_type: Vec::new(),
+            use_type: deal.usage_rights.iter().map(|u| match u.as_str() {
+                "Stream" => UseType::Stream,
+                "Download" => UseType::Download,
+                "OnDemandStream" => UseType::OnDemandStream,
+                "NonInteractiveStream" => UseType::NonInteractiveStream,
+                other => UseType::Other(other.to_string()),
+            }).collect(),
+            price_information: deal.pricing.iter()
+                .filter(|t| t.price_type == PriceType::Minimum)
+                .map(|t| PriceInformation {
+                    price_type: "Minimum".to_string(),
+                    price: t.price.clone(),
+                    price_tier: t.tier_name.clone(),
+                })
+                .collect(),
+            wholesale_price: prices(PriceType::Wholesale),
+            suggested_retail_price: prices(PriceType::SuggestedRetail),
+            pre_order_date: None,
+            pre_order_preview_date: None,
+            instant_gratification_date: None,
+            takedown_date: None,
+        },
+        comments: None,
+    }
+}
+
+fn release_identifiers(ids: &[Identifier]) -> ReleaseIdentifiers {
+    let mut identifiers = ReleaseIdentifiers {
+        upc: None,
+        ean: None,
+        catalog_number: None,
+        grid: None,
+        proprietary: Vec::new(),
+    };
+
+    for id in ids {
+        match &id.id_type {
+            IdentifierType::UPC => identifiers.upc = Some(id.value.clone()),
+            IdentifierType::EAN => identifiers.ean = Some(id.value.clone()),
+            IdentifierType::GRID | IdentifierType::GRid => identifiers.grid = Some(id.value.clone()),
+            IdentifierType::Proprietary => {
+                if let Some(ns) = &id.namespace {
+                    identifiers.proprietary.push(ProprietaryId {
+                        namespace: ns.clone(),
+                        value: id.value.clone(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    identifiers
+}
+
+fn graph_identifiers(identifiers: &ReleaseIdentifiers) -> Vec<Identifier> {
+    let simple = [
+        (IdentifierType::UPC, &identifiers.upc),
+        (IdentifierType::EAN, &identifiers.ean),
+        (IdentifierType::GRID, &identifiers.grid),
+    ];
+
+    simple.into_iter()
+        .filter_map(|(id_type, value)| value.clone().map(|value| Identifier { id_type, namespace: None, value }))
+        .chain(identifiers.proprietary.iter().map(|p| Identifier {
+            id_type: IdentifierType::Proprietary,
+            namespace: Some(p.namespace.clone()),
+            value: p.value.clone(),
+        }))
+        .collect()
+}
+
+fn channel_name(channel: &DistributionChannel) -> String {
+    match channel {
+        DistributionChannel::Other(other) => other.clone(),
+        channel => format!("{:?}", channel),
+    }
+}
+
+fn channel(name: &str) -> DistributionChannel {
+    match name {
+        "Download" => DistributionChannel::Download,
+        "Stream" => DistributionChannel::Stream,
+        "Physical" => DistributionChannel::Physical,
+        other => DistributionChannel::Other(other.to_string()),
+    }
+}
+
+fn message_type(name: &str) -> MessageType {
+    match name {
+        "TakedownMessage" => MessageType::TakedownMessage,
+        other => MessageType::from_root_element(other).unwrap_or(MessageType::NewReleaseMessage),
+    }
+}
+
+fn version(name: &str) -> ERNVersion {
+    match name {
+        "V3_8_2" => ERNVersion::V3_8_2,
+        "V4_2" => ERNVersion::V4_2,
+        "V4_4" => ERNVersion::V4_4,
+        _ => ERNVersion::V4_3,
+    }
+}
+
+fn profile(name: &str) -> Option<ERNProfile> {
+    match name {
+        "AudioAlbum" => Some(ERNProfile::AudioAlbum),
+        "AudioSingle" => Some(ERNProfile::AudioSingle),
+        "Video" => Some(ERNProfile::Video),
+        "Mixed" => Some(ERNProfile::Mixed),
+        _ => None,
+    }
+}
+
+fn party_ids(org: &Organization) -> Vec<Identifier> {
+    known(&org.id, NO_ID).into_iter()
+        .map(|value| Identifier { id_type: IdentifierType::Proprietary, namespace: None, value: value.to_string() })
+        .collect()
+}
+
+fn party_names(org: &Organization) -> Vec<LocalizedString> {
+    known(&org.name, UNKNOWN).map(LocalizedString::new).into_iter().collect()
+}
+
+/// The value unless it is the flat view's placeholder for "absent"
+fn known<'a>(value: &'a str, placeholder: &str) -> Option<&'a str> {
+    (!value.is_empty() && value != placeholder).then_some(value)
+}
+
+fn primary_name(names: &[LocalizedString], locales: &[&str]) -> String {
+    names.best_match(locales).map(|n| n.text.clone()).unwrap_or_else(|| UNKNOWN.to_string())
+}
+
+fn primary_title(titles: &[LocalizedString], locales: &[&str]) -> String {
+    titles.best_match(locales).map(|t| t.text.clone()).unwrap_or_else(|| UNTITLED.to_string())
+}
+
+fn primary_id(ids: &[Identifier]) -> String {
+    ids.first().map(|id| id.value.clone()).unwrap_or_else(|| NO_ID.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::common::Price;
+    use chrono::{DateTime, Utc};
+
+    fn sample_message() -> ERNMessage {
+        let header = MessageHeader {
+            message_id: "MSG1".to_string(),
+            message_type: MessageType::NewReleaseMessage,
+            message_created_date_time: Utc::now(),
+            message_sender: MessageSender {
+                party_id: vec![Identifier { id_type: IdentifierType::Proprietary, namespace: None, value: "PADPIDA1".to_string() }],
+                party_name: vec![LocalizedString::new("Label")],
+                trading_name: None,
+                attributes: None,
+                extensions: None,
+                comments: None,
+            },
+            message_recipient: MessageRecipient {
+                party_id: Vec::new(),
+                party_name: Vec::new(),
+                trading_name: None,
+                attributes: None,
+                extensions: None,
+                comments: None,
+            },
+            message_control_type: None,
+            message_thread_id: None,
+            attributes: None,
+            extensions: None,
+            comments: None,
+        };
+
+        let resource = Resource {
+            resource_reference: "A1".to_string(),
+            resource_type: ResourceType::SoundRecording,
+            resource_id: vec![Identifier { id_type: IdentifierType::ISRC, namespace: None, value: "USRC17607839".to_string() }],
+            reference_title: vec![LocalizedString::new("Track One")],
+            duration: Some(Duration::from_secs(185)),
+            technical_details: Vec::new(),
+            rights_controller: Vec::new(),
+            p_line: Vec::new(),
+            c_line: Vec::new(),
+            extensions: None,
+            comments: None,
+        };
+
+        let flat_release = ParsedRelease {
+            release_id: "R1".to_string(),
+            identifiers: ReleaseIdentifiers {
+                upc: Some("0123456789012".to_string()),
+                ean: None,
+                catalog_number: None,
+                grid: None,
+                proprietary: Vec::new(),
+            },
+            title: vec![LocalizedString::new("Album")],
+            default_title: "Album".to_string(),
+            subtitle: None,
+            default_subtitle: None,
+            display_artist: "Artist".to_string(),
+            artists: vec![ArtistInfo { name: "Artist".to_string(), role: "MainArtist".to_string(), party_id: None }],
+            release_type: "Album".to_string(),
+            genre: Some("Pop".to_string()),
+            sub_genre: None,
+            tracks: Vec::new(),
+            track_count: 0,
+            disc_count: None,
+            videos: Vec::new(),
+            images: Vec::new(),
+            cover_art: None,
+            release_date: None,
+            original_release_date: None,
+            territories: Vec::new(),
+            extensions: None,
+            p_line: None,
+            c_line: None,
+            parent_release: None,
+            child_releases: Vec::new(),
+        };
+        let mut release = graph_release(&flat_release);
+        release.release_resource_reference_list.push(ReleaseResourceReference {
+            resource_reference: "A1".to_string(),
+            sequence_number: Some(1),
+            disc_number: Some(1),
+            track_number: Some(1),
+            side: None,
+            is_hidden: false,
+            is_bonus: false,
+            extensions: None,
+            comments: None,
+        });
+
+        let deal = Deal {
+            deal_reference: None,
+            deal_release_reference: vec!["R1".to_string()],
+            deal_terms: DealTerms {
+                validity_period: None,
+                start_date: None,
+                end_date: None,
+                territory_code: vec!["Worldwide".to_string()],
+                excluded_territory_code: Vec::new(),
+                distribution_channel: vec![DistributionChannel::Stream],
+                excluded_distribution_channel: Vec::new(),
+                commercial_model_type: Vec::new(),
+                use_type: vec![UseType::OnDemandStream],
+                price_information: Vec::new(),
+                wholesale_price: vec![Price { amount: 0.99, currency: "USD".to_string(), territory: None }],
+                suggested_retail_price: Vec::new(),
+                pre_order_date: None,
+                pre_order_preview_date: None,
+                instant_gratification_date: None,
+                takedown_date: None,
+            },
+            comments: None,
+        };
+
+        ERNMessage {
+            message_header: header,
+            parties: Vec::new(),
+            resources: vec![resource],
+            releases: vec![release],
+            deals: vec![deal],
+            purged_releases: Vec::new(),
+            version: ERNVersion::V4_3,
+            profile: Some(ERNProfile::AudioAlbum),
+            message_audit_trail: None,
+            attributes: None,
+            extensions: None,
+            legacy_extensions: None,
+            comments: None,
+        }
+    }
+
+    #[test]
+    fn test_flatten_joins_tracks_and_counts() {
+        let flat = sample_message().flatten();
+
+        assert_eq!(flat.releases[0].tracks[0].isrc.as_deref(), Some("USRC17607839"));
+        assert_eq!(flat.releases[0].tracks[0].duration_formatted, "3:05");
+        assert_eq!(flat.stats.track_count, 1);
+        assert_eq!(flat.stats.total_duration, 185);
+        assert_eq!(flat.deals[0].deal_id, "deal_1");
+    }
+
+    #[test]
+    fn test_flatten_for_locales() {
+        let mut message = sample_message();
+        message.releases[0].release_title = vec![
+            LocalizedString::new("Album").with_language("en"),
+            LocalizedString::new("アルバム").with_language("ja"),
+        ];
+        message.resources[0].reference_title.insert(0, LocalizedString::new("Erster Titel").with_language("de"));
+
+        let flat = message.flatten_for(&["ja-JP", "en"]);
+        assert_eq!(flat.releases[0].default_title, "アルバム");
+        assert_eq!(flat.releases[0].tracks[0].title, "Track One");
+        assert_eq!(message.flatten().releases[0].default_title, "Album");
+    }
+
+    #[test]
+    fn test_release_and_original_release_dates() {
+        let date = |day: &str| format!("{}T00:00:00Z", day).parse::<DateTime<Utc>>().unwrap();
+        let event = |kind: &str, day: &str, territory: Option<&str>| ReleaseEvent {
+            release_event_type: kind.to_string(),
+            event_date: Some(date(day)),
+            territory: territory.map(str::to_string),
+            extensions: None,
+            comments: None,
+        };
+        let mut message = sample_message();
+        message.releases[0].release_date = vec![
+            event("ReleaseDate", "2024-03-08", Some("JP")),
+            event("GlobalReleaseDate", "2024-03-01", None),
+            event("GlobalOriginalReleaseDate", "1979-10-12", None),
+        ];
+
+        let flat = message.flatten();
+        assert_eq!(flat.releases[0].release_date, Some(date("2024-03-01")));
+        assert_eq!(flat.releases[0].original_release_date, Some(date("1979-10-12")));
+
+        let events = &flat.to_graph().releases[0].release_date;
+        let kinds: Vec<_> = events.iter().map(|e| e.kind()).collect();
+        assert_eq!(kinds, [Some(ReleaseDateKind::ReleaseDate), Some(ReleaseDateKind::OriginalReleaseDate)]);
+        assert_eq!(ReleaseDateKind::OriginalReleaseDate.element_name(ERNVersion::V3_8_2, false), "GlobalOriginalReleaseDate");
+        assert_eq!(ReleaseDateKind::OriginalReleaseDate.element_name(ERNVersion::V4_3, false), "OriginalReleaseDate");
+    }
+
+    #[test]
+    fn test_edit_flat_view_and_rebuild_graph() {
+        let original = sample_message();
+        let mut flat = original.flatten();
+        flat.releases[0].title = vec![LocalizedString::new("Album (Deluxe)")];
+        flat.deals[0].territories.included.push("GB".to_string());
+
+        let graph = flat.to_graph();
+        let release = &graph.releases[0];
+        assert_eq!(release.release_title[0].text, "Album (Deluxe)");
+        assert_eq!(release.release_type, Some(ReleaseType::Album));
+        assert_eq!(release.release_id[0].value, "0123456789012");
+        assert_eq!(release.release_resource_reference_list[0].resource_reference, "A1");
+        assert_eq!(graph.resources[0].resource_id[0].value, "USRC17607839");
+        assert_eq!(graph.resources[0].duration, Some(Duration::from_secs(185)));
+        assert_eq!(graph.deals[0].deal_reference.as_deref(), Some("deal_1"));
+        assert_eq!(graph.deals[0].deal_terms.territory_code, vec!["Worldwide", "GB"]);
+        assert_eq!(graph.deals[0].deal_terms.use_type, vec![UseType::OnDemandStream]);
+        assert_eq!(graph.deals[0].deal_terms.wholesale_price[0].amount, 0.99);
+        assert_eq!(graph.message_header.message_sender.party_name[0].text, "Label");
+        assert!(graph.message_header.message_recipient.party_id.is_empty());
+        assert_eq!(graph.profile, Some(ERNProfile::AudioAlbum));
+        assert_eq!(graph.version, ERNVersion::V4_3);
+
+        // Flattening the rebuilt graph reproduces the edited view
+        let again = graph.flatten();
+        assert_eq!(again.releases[0].default_title, "Album (Deluxe)");
+        assert_eq!(again.releases[0].tracks[0].isrc.as_deref(), Some("USRC17607839"));
+    }
+
+    #[test]
+    fn test_purge_actions_survive_round_trip() {
+        let mut message = sample_message();
+        message.message_header.message_type = MessageType::PurgeReleaseMessage;
+        message.purged_releases.push(PurgedRelease {
+            release_id: vec![Identifier { id_type: IdentifierType::UPC, namespace: None, value: "0123456789012".to_string() }],
+            title: Vec::new(),
+            attributes: None,
+            extensions: None,
+            comments: None,
+        });
+
+        let graph = message.flatten().to_graph();
+        assert_eq!(graph.message_header.message_type, MessageType::PurgeReleaseMessage);
+        assert_eq!(graph.update_actions(), message.update_actions());
+    }
+}
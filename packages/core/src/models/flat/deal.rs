@@ -6,6 +6,7 @@ use chrono::{DateTime, Utc};
 use crate::models::common::Price;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct ParsedDeal {
     pub deal_id: String,
     pub releases: Vec<String>,
@@ -18,24 +19,28 @@ pub struct ParsedDeal {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct DealValidity {
     pub start: Option<DateTime<Utc>>,
     pub end: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct TerritoryComplexity {
     pub included: Vec<String>,
     pub excluded: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct DistributionComplexity {
     pub included: Vec<String>,
     pub excluded: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct PriceTier {
     pub tier_name: Option<String>,
     pub price_type: PriceType,
@@ -46,6 +51,7 @@ pub struct PriceTier {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub enum PriceType {
     Wholesale,
     SuggestedRetail,
@@ -53,6 +59,7 @@ pub enum PriceType {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct TerritoryInfo {
     pub code: String,
     pub included: bool,
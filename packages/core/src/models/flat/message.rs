@@ -4,15 +4,26 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
+use indexmap::IndexMap;
 use super::{ParsedRelease, ParsedResource, ParsedDeal};
-use crate::models::{Extensions, graph::{Party, ERNMessage}};
+use crate::models::{Extensions, graph::{Party, ERNMessage, MessageType, UpdateAction}};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct ParsedERNMessage {
     pub graph: ERNMessage,
     pub flat: FlattenedMessage,
     /// Extensions from the original XML that need preservation
     pub extensions: Option<Extensions>,
+    /// Original XML of each release, resource and deal (`include_raw` only)
+    #[serde(default)]
+    pub raw_fragments: Option<RawFragments>,
+    /// Values the parser changed while reading the document
+    #[serde(default)]
+    pub normalizations: crate::warnings::Warnings,
+    /// Repeated values shared while parsing (`intern_strings` only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interning: Option<crate::intern::InternStats>,
 }
 
 impl ParsedERNMessage {
@@ -31,9 +42,56 @@ impl ParsedERNMessage {
     pub fn parties(&self) -> &HashMap<String, Party> {
         &self.flat.parties
     }
+    
+    /// Root message type (new, update or purge)
+    pub fn message_type(&self) -> &MessageType {
+        &self.graph.message_header.message_type
+    }
+    
+    /// Catalog changes to apply for update and purge messages
+    pub fn update_actions(&self) -> &[UpdateAction] {
+        &self.flat.update_actions
+    }
+    
+    /// Original XML of a release, when parsed with `include_raw`
+    pub fn raw_release(&self, release_reference: &str) -> Option<&RawFragment> {
+        self.raw_fragments.as_ref()?.releases.get(release_reference)
+    }
+    
+    /// Original XML of a resource, when parsed with `include_raw`
+    pub fn raw_resource(&self, resource_reference: &str) -> Option<&RawFragment> {
+        self.raw_fragments.as_ref()?.resources.get(resource_reference)
+    }
+    
+    /// Original XML of a deal, when parsed with `include_raw`
+    pub fn raw_deal(&self, deal_reference: &str) -> Option<&RawFragment> {
+        self.raw_fragments.as_ref()?.deals.get(deal_reference)
+    }
+}
+
+/// Untouched XML of a parsed element and where it sits in the source document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct RawFragment {
+    /// Byte offset of the element's start tag
+    pub start: usize,
+    /// Byte offset just past the element's end tag
+    pub end: usize,
+    pub xml: String,
+}
+
+/// Raw fragments keyed by reference, in document order. Entities without a
+/// reference are keyed by their 1-based position (e.g. `deal_2`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct RawFragments {
+    pub releases: IndexMap<String, RawFragment>,
+    pub resources: IndexMap<String, RawFragment>,
+    pub deals: IndexMap<String, RawFragment>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct FlattenedMessage {
     pub message_id: String,
     pub message_type: String,
@@ -47,11 +105,15 @@ pub struct FlattenedMessage {
     pub version: String,
     pub profile: Option<String>,
     pub stats: MessageStats,
+    /// Catalog changes requested by update and purge messages
+    #[serde(default)]
+    pub update_actions: Vec<UpdateAction>,
     /// Extensions for flattened message
     pub extensions: Option<Extensions>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Organization {
     pub name: String,
     pub id: String,
@@ -60,6 +122,7 @@ pub struct Organization {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct MessageStats {
     pub release_count: usize,
     pub track_count: usize,
@@ -5,6 +5,7 @@ mod message;
 mod release;
 mod track;
 mod deal;
+mod convert;
 
 pub use message::*;
 pub use release::*;
@@ -7,6 +7,7 @@ use crate::models::{Extensions, common::{LocalizedString, Copyright}};
 use super::{ParsedTrack, ParsedImage, ParsedVideo, TerritoryInfo};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct ParsedRelease {
     pub release_id: String,
     pub identifiers: ReleaseIdentifiers,
@@ -37,6 +38,7 @@ pub struct ParsedRelease {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct ReleaseIdentifiers {
     pub upc: Option<String>,
     pub ean: Option<String>,
@@ -46,12 +48,14 @@ pub struct ReleaseIdentifiers {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct ProprietaryId {
     pub namespace: String,
     pub value: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct ArtistInfo {
     pub name: String,
     pub role: String,
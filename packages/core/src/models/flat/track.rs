@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct ParsedTrack {
     pub track_id: String,
     pub isrc: Option<String>,
@@ -40,6 +41,7 @@ impl ParsedTrack {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct ParsedResource {
     pub resource_id: String,
     pub resource_type: String,
@@ -49,6 +51,7 @@ pub struct ParsedResource {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct TechnicalInfo {
     pub file_format: Option<String>,
     pub bitrate: Option<i32>,
@@ -57,6 +60,7 @@ pub struct TechnicalInfo {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct ParsedImage {
     pub image_id: String,
     pub image_type: String,
@@ -66,6 +70,7 @@ pub struct ParsedImage {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct ParsedVideo {
     pub video_id: String,
     pub video_type: String,
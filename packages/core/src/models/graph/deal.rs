@@ -3,16 +3,22 @@
 
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use crate::models::Comment;
 use crate::models::common::{Price, ValidityPeriod};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Deal {
     pub deal_reference: Option<String>,
     pub deal_release_reference: Vec<String>,
     pub deal_terms: DealTerms,
+    /// Comments associated with deal
+    #[serde(default)]
+    pub comments: Option<Vec<Comment>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct DealTerms {
     pub validity_period: Option<ValidityPeriod>,
     pub start_date: Option<DateTime<Utc>>,
@@ -33,6 +39,7 @@ pub struct DealTerms {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub enum DistributionChannel {
     Download,
     Stream,
@@ -41,6 +48,7 @@ pub enum DistributionChannel {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub enum CommercialModelType {
     PayAsYouGoModel,
     SubscriptionModel,
@@ -49,6 +57,7 @@ pub enum CommercialModelType {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub enum UseType {
     Stream,
     Download,
@@ -58,6 +67,7 @@ pub enum UseType {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct PriceInformation {
     pub price_type: String,
     pub price: Price,
@@ -6,6 +6,7 @@ use chrono::{DateTime, Utc};
 use crate::models::{Extensions, Comment, AttributeMap, common::{Identifier, LocalizedString}};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct MessageHeader {
     pub message_id: String,
     pub message_type: MessageType,
@@ -23,19 +24,35 @@ pub struct MessageHeader {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub enum MessageType {
     NewReleaseMessage,
     UpdateReleaseMessage,
     TakedownMessage,
+    PurgeReleaseMessage,
+}
+
+impl MessageType {
+    /// Message type for a root element name, with or without namespace prefix
+    pub fn from_root_element(name: &str) -> Option<Self> {
+        match name.rsplit(':').next().unwrap_or(name) {
+            "NewReleaseMessage" => Some(Self::NewReleaseMessage),
+            "UpdateReleaseMessage" => Some(Self::UpdateReleaseMessage),
+            "PurgeReleaseMessage" => Some(Self::PurgeReleaseMessage),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub enum MessageControlType {
     LiveMessage,
     TestMessage,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct MessageSender {
     pub party_id: Vec<Identifier>,
     pub party_name: Vec<LocalizedString>,
@@ -49,6 +66,7 @@ pub struct MessageSender {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct MessageRecipient {
     pub party_id: Vec<Identifier>,
     pub party_name: Vec<LocalizedString>,
@@ -3,15 +3,19 @@
 
 use serde::{Deserialize, Serialize};
 use crate::models::{Extensions, Comment, AttributeMap, versions::ERNVersion};
-use super::{MessageHeader, Party, Resource, Release, Deal};
+use super::{MessageHeader, MessageType, Party, Resource, Release, Deal, PurgedRelease, UpdateAction};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct ERNMessage {
     pub message_header: MessageHeader,
     pub parties: Vec<Party>,
     pub resources: Vec<Resource>,
     pub releases: Vec<Release>,
     pub deals: Vec<Deal>,
+    /// Releases to take down (PurgeReleaseMessage only)
+    #[serde(default)]
+    pub purged_releases: Vec<PurgedRelease>,
     pub version: ERNVersion,
     pub profile: Option<ERNProfile>,
     pub message_audit_trail: Option<MessageAuditTrail>,
@@ -25,6 +29,7 @@ pub struct ERNMessage {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub enum ERNProfile {
     AudioAlbum,
     AudioSingle,
@@ -33,6 +38,7 @@ pub enum ERNProfile {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct MessageAuditTrail {
     pub audit_trail_events: Vec<AuditTrailEvent>,
     /// All XML attributes (standard and custom)
@@ -44,6 +50,7 @@ pub struct MessageAuditTrail {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct AuditTrailEvent {
     pub message_audit_trail_event_reference: String,
     pub message_audit_trail_event_type: String,
@@ -61,4 +68,27 @@ impl ERNMessage {
     pub fn to_build_request(&self) -> Self {
         self.clone()
     }
+    
+    /// Catalog changes requested by an update or purge message
+    pub fn update_actions(&self) -> Vec<UpdateAction> {
+        match self.message_header.message_type {
+            MessageType::UpdateReleaseMessage => {
+                let releases = self.releases.iter().map(|r| UpdateAction::UpsertRelease {
+                    release_reference: r.release_reference.clone(),
+                });
+                let resources = self.resources.iter().map(|r| UpdateAction::UpsertResource {
+                    resource_reference: r.resource_reference.clone(),
+                });
+                let deals = self.deals.iter().map(|d| UpdateAction::UpsertDeal {
+                    deal_reference: d.deal_reference.clone(),
+                    release_references: d.deal_release_reference.clone(),
+                });
+                releases.chain(resources).chain(deals).collect()
+            }
+            MessageType::PurgeReleaseMessage | MessageType::TakedownMessage => self.purged_releases.iter()
+                .map(|p| UpdateAction::PurgeRelease { release_id: p.release_id.clone() })
+                .collect(),
+            MessageType::NewReleaseMessage => Vec::new(),
+        }
+    }
 }
\ No newline at end of file
@@ -7,10 +7,12 @@ mod party;
 mod resource;
 mod release;
 mod deal;
+mod update;
 
 pub use message::*;
 pub use header::*;
 pub use party::*;
 pub use resource::*;
 pub use release::*;
-pub use deal::*;
\ No newline at end of file
+pub use deal::*;
+pub use update::*;
\ No newline at end of file
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use crate::models::common::{Identifier, LocalizedString};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Party {
     pub party_id: Vec<Identifier>,
     pub isni: Option<String>,
@@ -15,6 +16,7 @@ pub struct Party {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub enum PartyRole {
     Artist,
     Producer,
@@ -29,6 +31,7 @@ pub enum PartyRole {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct ContactDetails {
     pub email: Option<String>,
     pub phone: Option<String>,
@@ -36,6 +39,7 @@ pub struct ContactDetails {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Artist {
     pub party_reference: Option<String>,
     pub artist_role: Vec<String>,
@@ -4,9 +4,11 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use crate::models::{Extensions, Comment, AttributeMap, common::{Identifier, LocalizedString}};
+use crate::models::versions::ERNVersion;
 use super::Artist;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Release {
     pub release_reference: String,
     pub release_id: Vec<Identifier>,
@@ -29,6 +31,7 @@ pub struct Release {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub enum ReleaseType {
     Album,
     Single,
@@ -38,6 +41,7 @@ pub enum ReleaseType {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Genre {
     pub genre_text: String,
     pub sub_genre: Option<String>,
@@ -50,6 +54,7 @@ pub struct Genre {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct ReleaseResourceReference {
     pub resource_reference: String,
     pub sequence_number: Option<i32>,
@@ -65,6 +70,7 @@ pub struct ReleaseResourceReference {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct ReleaseParty {
     pub party_reference: String,
     pub role: Vec<String>,
@@ -75,6 +81,7 @@ pub struct ReleaseParty {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct ReleaseEvent {
     pub release_event_type: String,
     pub event_date: Option<DateTime<Utc>>,
@@ -83,4 +90,61 @@ pub struct ReleaseEvent {
     pub extensions: Option<Extensions>,
     /// Comments associated with release event
     pub comments: Option<Vec<Comment>>,
-}
\ No newline at end of file
+}
+impl Release {
+    /// The date of the first event of `kind`, preferring one that applies
+    /// everywhere to a territory-specific one
+    pub fn date(&self, kind: ReleaseDateKind) -> Option<DateTime<Utc>> {
+        let events = || self.release_date.iter().filter(|e| e.kind() == Some(kind) && e.event_date.is_some());
+        events()
+            .find(|e| e.territory.as_deref().is_none_or(|t| t == "Worldwide"))
+            .or_else(|| events().next())
+            .and_then(|e| e.event_date)
+    }
+}
+
+impl ReleaseEvent {
+    /// Which date the event records, from its `release_event_type`
+    pub fn kind(&self) -> Option<ReleaseDateKind> {
+        ReleaseDateKind::from_element(&self.release_event_type)
+    }
+}
+
+/// The two dates a release carries
+///
+/// ERN 3.8.2 puts both on the release as `GlobalReleaseDate` and
+/// `GlobalOriginalReleaseDate`, with territory-specific `ReleaseDate` and
+/// `OriginalReleaseDate` in `ReleaseDetailsByTerritory`. ERN 4.x only has
+/// `ReleaseDate` and `OriginalReleaseDate`, marked with an
+/// `ApplicableTerritoryCode` when they apply to one territory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub enum ReleaseDateKind {
+    /// When this release is made available, e.g. the digital release date
+    ReleaseDate,
+    /// When the release, or the recordings on it, first came out in any
+    /// format, e.g. the original vinyl release of a reissue
+    OriginalReleaseDate,
+}
+
+impl ReleaseDateKind {
+    /// The kind of a date element in any ERN version
+    pub fn from_element(name: &str) -> Option<Self> {
+        match name {
+            "ReleaseDate" | "GlobalReleaseDate" => Some(Self::ReleaseDate),
+            "OriginalReleaseDate" | "GlobalOriginalReleaseDate" => Some(Self::OriginalReleaseDate),
+            _ => None,
+        }
+    }
+
+    /// Element holding the date for `version`; `territorial` dates apply to
+    /// some territories only
+    pub fn element_name(self, version: ERNVersion, territorial: bool) -> &'static str {
+        match (self, version == ERNVersion::V3_8_2 && !territorial) {
+            (Self::ReleaseDate, true) => "GlobalReleaseDate",
+            (Self::ReleaseDate, false) => "ReleaseDate",
+            (Self::OriginalReleaseDate, true) => "GlobalOriginalReleaseDate",
+            (Self::OriginalReleaseDate, false) => "OriginalReleaseDate",
+        }
+    }
+}
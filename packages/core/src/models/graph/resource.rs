@@ -2,9 +2,10 @@
 //! Resource types
 
 use serde::{Deserialize, Serialize};
-use crate::models::{Extensions, common::{Identifier, Copyright, LocalizedString}};
+use crate::models::{Extensions, Comment, common::{Identifier, Copyright, LocalizedString}};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Resource {
     pub resource_reference: String,
     pub resource_type: ResourceType,
@@ -17,9 +18,13 @@ pub struct Resource {
     pub c_line: Vec<Copyright>,
     /// Extensions for resource
     pub extensions: Option<Extensions>,
+    /// Comments associated with resource
+    #[serde(default)]
+    pub comments: Option<Vec<Comment>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub enum ResourceType {
     SoundRecording,
     Video,
@@ -29,6 +34,7 @@ pub enum ResourceType {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct TechnicalDetails {
     pub technical_resource_details_reference: String,
     pub audio_codec: Option<String>,
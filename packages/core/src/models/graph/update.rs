@@ -0,0 +1,38 @@
+// core/src/models/graph/update.rs
+//! Catalog update types for UpdateReleaseMessage and PurgeReleaseMessage
+
+use serde::{Deserialize, Serialize};
+use crate::models::{Extensions, Comment, AttributeMap, common::{Identifier, LocalizedString}};
+
+/// A release that a PurgeReleaseMessage asks the recipient to take down
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct PurgedRelease {
+    pub release_id: Vec<Identifier>,
+    pub title: Vec<LocalizedString>,
+    /// All XML attributes (standard and custom)
+    pub attributes: Option<AttributeMap>,
+    /// Extensions for purged release
+    pub extensions: Option<Extensions>,
+    /// Comments associated with purged release
+    pub comments: Option<Vec<Comment>>,
+}
+
+/// A change a message asks the recipient to apply to its catalog
+///
+/// NewReleaseMessage carries no actions; it replaces the release outright.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub enum UpdateAction {
+    /// Insert or replace the release with this reference
+    UpsertRelease { release_reference: String },
+    /// Insert or replace the resource with this reference
+    UpsertResource { resource_reference: String },
+    /// Insert or replace the deal covering these releases
+    UpsertDeal {
+        deal_reference: Option<String>,
+        release_references: Vec<String>,
+    },
+    /// Remove the release with these identifiers
+    PurgeRelease { release_id: Vec<Identifier> },
+}
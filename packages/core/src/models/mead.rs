@@ -0,0 +1,50 @@
+// core/src/models/mead.rs
+//! MEAD (Media Enrichment and Description) message models
+
+use super::common::{Identifier, LocalizedString};
+use serde::{Deserialize, Serialize};
+
+/// Namespace of MEAD 1.1 messages
+pub const MEAD_11_NAMESPACE: &str = "http://ddex.net/xml/mead/11";
+
+/// MessageSchemaVersionId of MEAD 1.1 messages
+pub const MEAD_11_SCHEMA_VERSION: &str = "mead/11";
+
+/// A MEAD message enriching releases already delivered by ERN
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MeadMessage {
+    pub message_id: String,
+    pub message_thread_id: Option<String>,
+    pub message_created_date_time: Option<String>,
+    /// DPID of the sending party
+    pub message_sender: Option<String>,
+    /// DPID of the receiving party
+    pub message_recipient: Option<String>,
+    pub release_information: Vec<ReleaseInformation>,
+}
+
+/// Enrichment for one release, keyed by its identifiers
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReleaseInformation {
+    pub release_id: Vec<Identifier>,
+    pub moods: Vec<String>,
+    pub focus_tracks: Vec<FocusTrack>,
+    pub marketing_comments: Vec<LocalizedString>,
+    pub awards: Vec<Award>,
+}
+
+/// A track the label wants promoted
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FocusTrack {
+    pub isrc: String,
+    pub title: Option<String>,
+}
+
+/// An award won by, or nominated for, a release
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Award {
+    pub name: String,
+    pub category: Option<String>,
+    pub date: Option<String>,
+    pub is_winner: Option<bool>,
+}
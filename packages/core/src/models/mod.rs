@@ -6,12 +6,14 @@ pub mod graph;
 pub mod flat;
 pub mod versions;  // Add this line to export the versions module
 pub mod attributes;
+pub mod mead;
+pub mod borrowed;
 
 pub use common::{Identifier, IdentifierType, LocalizedString};
 pub use attributes::{AttributeMap, AttributeValue, QName, AttributeType, AttributeInheritance};
 
 pub mod extensions;
-pub use extensions::{Extensions, XmlFragment, ProcessingInstruction, Comment, CommentPosition};
+pub use extensions::{Extensions, XmlFragment, XmlNode, ProcessingInstruction, Comment, CommentPosition};
 
 pub mod validation;
 pub use validation::{
@@ -94,6 +94,21 @@ impl VersionDifferences {
                     party_descriptor_type: PartyDescriptorVersion::Enhanced,
                 },
             },
+            ERNVersion::V4_4 => Self {
+                version,
+                namespace_uri: "http://ddex.net/xml/ern/44".to_string(),
+                schema_location: "http://ddex.net/xml/ern/44/release-notification.xsd".to_string(),
+                features: VersionFeatures {
+                    supports_message_audit_trail: true,
+                    supports_release_profile: true,
+                    supports_technical_instantiation: true,
+                    supports_deal_reference: true,
+                    supports_resource_group: true,
+                    supports_chapter_information: true,
+                    deal_terms_structure: DealTermsVersion::Extended,
+                    party_descriptor_type: PartyDescriptorVersion::Enhanced,
+                },
+            },
         }
     }
 }
\ No newline at end of file
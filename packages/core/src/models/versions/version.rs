@@ -1,10 +1,12 @@
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub enum ERNVersion {
     V3_8_2,  // Changed from ERN382
     V4_2,    // Changed from ERN42
     V4_3,    // Changed from ERN43
+    V4_4,
 }
 
 impl ERNVersion {
@@ -13,6 +15,7 @@ impl ERNVersion {
             ERNVersion::V3_8_2 => "3.8.2",
             ERNVersion::V4_2 => "4.2",
             ERNVersion::V4_3 => "4.3",
+            ERNVersion::V4_4 => "4.4",
         }
     }
     
@@ -21,6 +24,7 @@ impl ERNVersion {
             ERNVersion::V3_8_2 => "http://ddex.net/xml/ern/382",
             ERNVersion::V4_2 => "http://ddex.net/xml/ern/42",
             ERNVersion::V4_3 => "http://ddex.net/xml/ern/43",
+            ERNVersion::V4_4 => "http://ddex.net/xml/ern/44",
         }
     }
 }
@@ -128,6 +128,15 @@ impl NamespaceRegistry {
             required: true,
         });
 
+        self.register_namespace(NamespaceInfo {
+            uri: "http://ddex.net/xml/ern/44".to_string(),
+            preferred_prefix: "ern".to_string(),
+            alternative_prefixes: vec!["ern44".to_string()],
+            standard: DDEXStandard::ERN,
+            version: Some("4.4".to_string()),
+            required: true,
+        });
+
         // AVS namespaces
         self.register_namespace(NamespaceInfo {
             uri: "http://ddex.net/xml/avs".to_string(),
@@ -243,6 +252,7 @@ impl NamespaceRegistry {
             "http://ddex.net/xml/ern/382" => Some(ERNVersion::V3_8_2),
             "http://ddex.net/xml/ern/42" => Some(ERNVersion::V4_2),
             "http://ddex.net/xml/ern/43" => Some(ERNVersion::V4_3),
+            "http://ddex.net/xml/ern/44" => Some(ERNVersion::V4_4),
             _ => None,
         }
     }
@@ -256,6 +266,7 @@ impl NamespaceRegistry {
             ERNVersion::V3_8_2 => namespaces.push("http://ddex.net/xml/ern/382".to_string()),
             ERNVersion::V4_2 => namespaces.push("http://ddex.net/xml/ern/42".to_string()),
             ERNVersion::V4_3 => namespaces.push("http://ddex.net/xml/ern/43".to_string()),
+            ERNVersion::V4_4 => namespaces.push("http://ddex.net/xml/ern/44".to_string()),
         }
         
         // Add common supporting namespaces
@@ -267,7 +278,10 @@ impl NamespaceRegistry {
 
     /// Get preferred prefix for a namespace URI
     pub fn get_preferred_prefix(&self, uri: &str) -> Option<&str> {
-        self.default_prefixes.get(uri).map(|s| s.as_str())
+        self.default_prefixes
+            .get(uri)
+            .or_else(|| self.custom_namespaces.get(uri).map(|info| &info.preferred_prefix))
+            .map(|s| s.as_str())
     }
 
     /// Get namespace info by URI
@@ -530,6 +544,7 @@ mod tests {
         assert_eq!(registry.detect_version("http://ddex.net/xml/ern/382"), Some(ERNVersion::V3_8_2));
         assert_eq!(registry.detect_version("http://ddex.net/xml/ern/42"), Some(ERNVersion::V4_2));
         assert_eq!(registry.detect_version("http://ddex.net/xml/ern/43"), Some(ERNVersion::V4_3));
+        assert_eq!(registry.detect_version("http://ddex.net/xml/ern/44"), Some(ERNVersion::V4_4));
         assert_eq!(registry.detect_version("http://unknown.com/namespace"), None);
     }
 
@@ -0,0 +1,504 @@
+//! Schema validation against the bundled DDEX ERN content models
+//!
+//! The validator checks a document against a condensed form of the
+//! release-notification XSDs for ERN 3.8.2, 4.2, 4.3 and 4.4: the root element
+//! and namespace, required and permitted children with their cardinality, and
+//! the formats of identifier, date, duration and code-list values. Every
+//! violation carries the line, column and element path where it occurred.
+//! It is a partial structural check rather than XSD validation: the
+//! MessageHeader children and the order of children are not checked.
+
+mod models;
+
+use crate::error::ErrorLocation;
+use crate::models::versions::ERNVersion;
+use indexmap::IndexMap;
+use models::{content_model, facet_for, ContentModel, Family, ROOT_ELEMENTS};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Category of a schema violation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ViolationKind {
+    /// The document is not well-formed XML
+    Malformed,
+    /// The root element is not a release-notification message
+    UnexpectedRoot,
+    /// The root element is not in the namespace of the requested version
+    WrongNamespace,
+    /// A required attribute is missing
+    MissingAttribute,
+    /// An attribute has a value the schema does not allow
+    InvalidAttribute,
+    /// A required child element is missing
+    MissingElement,
+    /// A child element is not allowed by its parent's content model
+    UnexpectedElement,
+    /// A child element occurs more often than its cardinality allows
+    TooManyOccurrences,
+    /// An element value does not match its simple type
+    InvalidValue,
+}
+
+impl ViolationKind {
+    /// Stable code for reporting
+    pub fn code(&self) -> &'static str {
+        match self {
+            ViolationKind::Malformed => "MALFORMED_XML",
+            ViolationKind::UnexpectedRoot => "UNEXPECTED_ROOT",
+            ViolationKind::WrongNamespace => "WRONG_NAMESPACE",
+            ViolationKind::MissingAttribute => "MISSING_ATTRIBUTE",
+            ViolationKind::InvalidAttribute => "INVALID_ATTRIBUTE",
+            ViolationKind::MissingElement => "MISSING_ELEMENT",
+            ViolationKind::UnexpectedElement => "UNEXPECTED_ELEMENT",
+            ViolationKind::TooManyOccurrences => "TOO_MANY_OCCURRENCES",
+            ViolationKind::InvalidValue => "INVALID_VALUE",
+        }
+    }
+}
+
+/// A single schema violation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaViolation {
+    pub kind: ViolationKind,
+    pub message: String,
+    pub location: ErrorLocation,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {} ({}): {}",
+            self.location.line, self.location.column, self.location.path, self.message
+        )
+    }
+}
+
+/// Validates DDEX ERN documents against the bundled schema for one version
+#[derive(Debug, Clone, Copy)]
+pub struct SchemaValidator {
+    version: ERNVersion,
+}
+
+impl SchemaValidator {
+    pub fn new(version: ERNVersion) -> Self {
+        Self { version }
+    }
+
+    pub fn version(&self) -> ERNVersion {
+        self.version
+    }
+
+    /// Validate a document, returning every violation found
+    pub fn validate(&self, xml: &str) -> Vec<SchemaViolation> {
+        Validation::new(self.version, xml).run()
+    }
+}
+
+/// Validate a document against the bundled schema for `version`
+pub fn validate(xml: &str, version: ERNVersion) -> Vec<SchemaViolation> {
+    SchemaValidator::new(version).validate(xml)
+}
+
+/// An element whose end tag has not been reached yet
+struct Frame {
+    name: String,
+    path: String,
+    location: ErrorLocation,
+    model: Option<&'static ContentModel>,
+    child_counts: IndexMap<String, usize>,
+    has_children: bool,
+    text: String,
+}
+
+struct Validation<'a> {
+    version: ERNVersion,
+    family: Family,
+    xml: &'a str,
+    line_starts: Vec<usize>,
+    stack: Vec<Frame>,
+    seen_root: bool,
+    violations: Vec<SchemaViolation>,
+}
+
+impl<'a> Validation<'a> {
+    fn new(version: ERNVersion, xml: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(xml.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        Self {
+            version,
+            family: Family::from(version),
+            xml,
+            line_starts,
+            stack: Vec::new(),
+            seen_root: false,
+            violations: Vec::new(),
+        }
+    }
+
+    fn run(mut self) -> Vec<SchemaViolation> {
+        let mut reader = Reader::from_str(self.xml);
+
+        loop {
+            let event = match reader.read_event() {
+                Ok(event) => event,
+                Err(e) => {
+                    let offset = reader.error_position() as usize;
+                    let path = self.current_path();
+                    self.report(ViolationKind::Malformed, e.to_string(), offset, path);
+                    break;
+                }
+            };
+            let end = reader.buffer_position() as usize;
+
+            match event {
+                Event::Start(ref e) => self.open(e, end),
+                Event::Empty(ref e) => {
+                    self.open(e, end);
+                    self.close();
+                }
+                Event::End(_) => self.close(),
+                Event::Text(ref e) => {
+                    if let (Some(frame), Ok(text)) = (self.stack.last_mut(), e.unescape()) {
+                        frame.text.push_str(&text);
+                    }
+                }
+                Event::CData(ref e) => {
+                    if let Some(frame) = self.stack.last_mut() {
+                        frame.text.push_str(&String::from_utf8_lossy(e));
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        if !self.seen_root {
+            self.report(ViolationKind::Malformed, "Document has no root element".to_string(), 0, "/".to_string());
+        }
+
+        // Missing children are only known at end tags; report in document order
+        self.violations.sort_by_key(|v| v.location.byte_offset);
+        self.violations
+    }
+
+    fn open(&mut self, start: &BytesStart<'_>, end: usize) {
+        // The reader is positioned after the tag; step back to its '<'
+        let offset = self.xml[..end].rfind('<').unwrap_or(0);
+        let qname = String::from_utf8_lossy(start.name().as_ref()).to_string();
+        let (prefix, name) = match qname.split_once(':') {
+            Some((prefix, local)) => (Some(prefix.to_string()), local.to_string()),
+            None => (None, qname),
+        };
+
+        let path = match self.stack.last() {
+            Some(parent) => {
+                let index = parent.child_counts.get(&name).copied().unwrap_or(0) + 1;
+                format!("{}/{}[{}]", parent.path, name, index)
+            }
+            None => format!("/{}", name),
+        };
+
+        if self.stack.is_empty() {
+            if self.seen_root {
+                self.report(ViolationKind::Malformed, "Document has more than one root element".to_string(), offset, path);
+                return;
+            }
+            self.seen_root = true;
+            self.check_root(start, prefix.as_deref(), &name, offset, &path);
+        } else {
+            self.check_child(&name, offset, &path);
+        }
+
+        let (line, column) = self.line_column(offset);
+        self.stack.push(Frame {
+            model: content_model(self.family, &name),
+            name,
+            location: ErrorLocation {
+                line,
+                column,
+                byte_offset: Some(offset),
+                path: path.clone(),
+            },
+            path,
+            child_counts: IndexMap::new(),
+            has_children: false,
+            text: String::new(),
+        });
+    }
+
+    fn check_root(&mut self, start: &BytesStart<'_>, prefix: Option<&str>, name: &str, offset: usize, path: &str) {
+        if !ROOT_ELEMENTS.contains(&name) {
+            self.report(
+                ViolationKind::UnexpectedRoot,
+                format!("Root element '{}' is not a release notification message; expected one of {}", name, ROOT_ELEMENTS.join(", ")),
+                offset,
+                path.to_string(),
+            );
+        }
+
+        let namespace_attr = match prefix {
+            Some(prefix) => format!("xmlns:{}", prefix),
+            None => "xmlns".to_string(),
+        };
+        let mut namespace = None;
+        let mut schema_version = None;
+        for attr in start.attributes().flatten() {
+            let key = String::from_utf8_lossy(attr.key.as_ref()).to_string();
+            let value = String::from_utf8_lossy(&attr.value).to_string();
+            if key == namespace_attr {
+                namespace = Some(value);
+            } else if key == "MessageSchemaVersionId" {
+                schema_version = Some(value);
+            }
+        }
+
+        let expected_namespace = self.version.namespace();
+        match namespace {
+            Some(ns) if ns == expected_namespace => {}
+            Some(ns) => self.report(
+                ViolationKind::WrongNamespace,
+                format!("Root element is in namespace '{}', expected '{}' for {}", ns, expected_namespace, self.version),
+                offset,
+                path.to_string(),
+            ),
+            None => self.report(
+                ViolationKind::WrongNamespace,
+                format!("Root element is not bound to a namespace, expected '{}'", expected_namespace),
+                offset,
+                path.to_string(),
+            ),
+        }
+
+        // Both the "ern/43" and "ern/4.3" spellings are seen in the wild
+        let expected_version = format!("ern/{}", self.version.as_str().replace('.', ""));
+        match schema_version {
+            Some(value) if value.replace('.', "") == expected_version => {}
+            Some(value) => self.report(
+                ViolationKind::InvalidAttribute,
+                format!("MessageSchemaVersionId '{}' does not match {}", value, expected_version),
+                offset,
+                path.to_string(),
+            ),
+            None => self.report(
+                ViolationKind::MissingAttribute,
+                "Missing required attribute 'MessageSchemaVersionId'".to_string(),
+                offset,
+                path.to_string(),
+            ),
+        }
+    }
+
+    fn check_child(&mut self, name: &str, offset: usize, path: &str) {
+        let Some(parent) = self.stack.last_mut() else { return };
+        parent.has_children = true;
+        let count = parent.child_counts.entry(name.to_string()).or_insert(0);
+        *count += 1;
+
+        let Some(model) = parent.model else { return };
+        let parent_name = parent.name.clone();
+
+        if !model.allows(name) {
+            self.report(
+                ViolationKind::UnexpectedElement,
+                format!("Element '{}' is not allowed in '{}'", name, parent_name),
+                offset,
+                path.to_string(),
+            );
+            return;
+        }
+
+        if let Some(particle) = model.particle_for(name) {
+            let occurrences: usize = particle
+                .names
+                .iter()
+                .filter_map(|n| parent.child_counts.get(*n))
+                .sum();
+            if particle.max.is_some_and(|max| occurrences == max + 1) {
+                self.report(
+                    ViolationKind::TooManyOccurrences,
+                    format!("'{}' allows at most {} '{}' element(s)", parent_name, particle.max.unwrap_or(0), particle.names.join("' or '")),
+                    offset,
+                    path.to_string(),
+                );
+            }
+        }
+    }
+
+    fn close(&mut self) {
+        let Some(frame) = self.stack.pop() else { return };
+
+        if let Some(model) = frame.model {
+            for particle in model.particles.iter().filter(|p| p.min > 0) {
+                let occurrences: usize = particle
+                    .names
+                    .iter()
+                    .filter_map(|n| frame.child_counts.get(*n))
+                    .sum();
+                if occurrences < particle.min {
+                    let expected = if particle.names.len() == 1 {
+                        format!("required element '{}'", particle.names[0])
+                    } else {
+                        format!("one of the elements '{}'", particle.names.join("', '"))
+                    };
+                    self.violations.push(SchemaViolation {
+                        kind: ViolationKind::MissingElement,
+                        message: format!("'{}' is missing {}", frame.name, expected),
+                        location: frame.location.clone(),
+                    });
+                }
+            }
+        }
+
+        if !frame.has_children {
+            if let Some(facet) = facet_for(&frame.name) {
+                let value = frame.text.trim();
+                if let Err(expected) = facet.check(value) {
+                    self.violations.push(SchemaViolation {
+                        kind: ViolationKind::InvalidValue,
+                        message: format!("'{}' value '{}' is not valid; expected {}", frame.name, value, expected),
+                        location: frame.location.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    fn report(&mut self, kind: ViolationKind, message: String, offset: usize, path: String) {
+        let (line, column) = self.line_column(offset);
+        self.violations.push(SchemaViolation {
+            kind,
+            message,
+            location: ErrorLocation {
+                line,
+                column,
+                byte_offset: Some(offset),
+                path,
+            },
+        });
+    }
+
+    fn current_path(&self) -> String {
+        self.stack
+            .last()
+            .map(|frame| frame.path.clone())
+            .unwrap_or_else(|| "/".to_string())
+    }
+
+    /// 1-based line and column of a byte offset
+    fn line_column(&self, offset: usize) -> (usize, usize) {
+        let line = self.line_starts.partition_point(|&start| start <= offset);
+        let line_start = self.line_starts[line.saturating_sub(1)];
+        let column = self.xml[line_start..offset.min(self.xml.len())].chars().count() + 1;
+        (line, column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID_43: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43" MessageSchemaVersionId="ern/43">
+  <MessageHeader>
+    <MessageId>MSG001</MessageId>
+    <MessageSender><PartyId>PADPIDA2014120301</PartyId></MessageSender>
+    <MessageCreatedDateTime>2024-01-01T00:00:00Z</MessageCreatedDateTime>
+  </MessageHeader>
+  <ResourceList>
+    <SoundRecording>
+      <ResourceReference>A1</ResourceReference>
+      <ResourceId><ISRC>USRC17607839</ISRC></ResourceId>
+      <DisplayTitleText>Track</DisplayTitleText>
+      <Duration>PT3M45S</Duration>
+    </SoundRecording>
+  </ResourceList>
+  <ReleaseList>
+    <Release>
+      <ReleaseReference>R1</ReleaseReference>
+      <ReleaseId><ICPN>012345678905</ICPN></ReleaseId>
+      <DisplayTitleText>Album</DisplayTitleText>
+    </Release>
+  </ReleaseList>
+  <DealList>
+    <ReleaseDeal>
+      <DealReleaseReference>R1</DealReleaseReference>
+      <Deal>
+        <DealTerms>
+          <CommercialModelType>SubscriptionModel</CommercialModelType>
+          <UseType>Stream</UseType>
+          <TerritoryCode>Worldwide</TerritoryCode>
+          <ValidityPeriod><StartDate>2024-01-01</StartDate></ValidityPeriod>
+        </DealTerms>
+      </Deal>
+    </ReleaseDeal>
+  </DealList>
+</ern:NewReleaseMessage>"#;
+
+    #[test]
+    fn test_valid_message_has_no_violations() {
+        let violations = validate(VALID_43, ERNVersion::V4_3);
+        assert!(violations.is_empty(), "{:?}", violations);
+        assert!(validate(&VALID_43.replace("ern/43", "ern/44"), ERNVersion::V4_4).is_empty());
+    }
+
+    #[test]
+    fn test_reports_missing_element_with_location() {
+        let xml = VALID_43.replace("      <Duration>PT3M45S</Duration>\n", "");
+        let violations = validate(&xml, ERNVersion::V4_3);
+
+        assert_eq!(violations.len(), 1);
+        let violation = &violations[0];
+        assert_eq!(violation.kind, ViolationKind::MissingElement);
+        assert!(violation.message.contains("Duration"));
+        assert_eq!(violation.location.line, 9);
+        assert_eq!(violation.location.column, 5);
+        assert_eq!(violation.location.path, "/NewReleaseMessage/ResourceList[1]/SoundRecording[1]");
+    }
+
+    #[test]
+    fn test_reports_invalid_values_and_unexpected_elements() {
+        let xml = VALID_43
+            .replace("USRC17607839", "USRC1760783")
+            .replace("PT3M45S", "3:45")
+            .replace("<UseType>Stream</UseType>", "<UseType>Stream</UseType><Bogus/>");
+        let violations = validate(&xml, ERNVersion::V4_3);
+
+        let kinds: Vec<_> = violations.iter().map(|v| v.kind).collect();
+        assert_eq!(kinds.iter().filter(|k| **k == ViolationKind::InvalidValue).count(), 2);
+        // DealTerms is an open model, so the unknown child is tolerated there
+        assert!(!kinds.contains(&ViolationKind::UnexpectedElement));
+
+        let xml = VALID_43.replace("<ResourceList>", "<ResourceList><Bogus/>");
+        let violations = validate(&xml, ERNVersion::V4_3);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].kind, ViolationKind::UnexpectedElement);
+        assert_eq!(violations[0].location.line, 8);
+    }
+
+    #[test]
+    fn test_checks_root_namespace_and_cardinality() {
+        let violations = validate(VALID_43, ERNVersion::V3_8_2);
+        assert!(violations.iter().any(|v| v.kind == ViolationKind::WrongNamespace));
+        assert!(violations.iter().any(|v| v.kind == ViolationKind::InvalidAttribute));
+
+        let xml = VALID_43.replace("<MessageId>MSG001</MessageId>", "<MessageId>A</MessageId><MessageId>B</MessageId>");
+        let violations = validate(&xml, ERNVersion::V4_3);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].kind, ViolationKind::TooManyOccurrences);
+
+        let violations = validate("<Catalog/>", ERNVersion::V4_3);
+        assert!(violations.iter().any(|v| v.kind == ViolationKind::UnexpectedRoot));
+    }
+
+    #[test]
+    fn test_malformed_xml_is_reported() {
+        let violations = validate("<NewReleaseMessage><MessageHeader></NewReleaseMessage>", ERNVersion::V4_3);
+        assert_eq!(violations.last().unwrap().kind, ViolationKind::Malformed);
+    }
+}
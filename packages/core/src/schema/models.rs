@@ -0,0 +1,400 @@
+//! Condensed DDEX ERN content models
+//!
+//! Each model lists the child elements an element may contain together with
+//! their cardinality, taken from the ERN release-notification XSDs. Models
+//! marked `closed` reject any child they do not list; open models only enforce
+//! their required children, which keeps the rarely used optional vocabulary of
+//! resources and releases from producing false positives.
+
+use crate::models::versions::ERNVersion;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// A group of interchangeable child elements and how often they may occur
+pub(crate) struct Particle {
+    pub names: &'static [&'static str],
+    pub min: usize,
+    pub max: Option<usize>,
+}
+
+/// The allowed children of an element
+pub(crate) struct ContentModel {
+    pub particles: &'static [Particle],
+    pub closed: bool,
+}
+
+impl ContentModel {
+    pub fn allows(&self, name: &str) -> bool {
+        !self.closed || self.particles.iter().any(|p| p.names.contains(&name))
+    }
+
+    pub fn particle_for(&self, name: &str) -> Option<&'static Particle> {
+        self.particles.iter().find(|p| p.names.contains(&name))
+    }
+}
+
+const fn one(names: &'static [&'static str]) -> Particle {
+    Particle { names, min: 1, max: Some(1) }
+}
+
+const fn optional(names: &'static [&'static str]) -> Particle {
+    Particle { names, min: 0, max: Some(1) }
+}
+
+const fn many(names: &'static [&'static str]) -> Particle {
+    Particle { names, min: 0, max: None }
+}
+
+const fn at_least_one(names: &'static [&'static str]) -> Particle {
+    Particle { names, min: 1, max: None }
+}
+
+/// Schema families: ERN 4.2, 4.3 and 4.4 share the same message structure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Family {
+    Ern382,
+    Ern4,
+}
+
+impl From<ERNVersion> for Family {
+    fn from(version: ERNVersion) -> Self {
+        match version {
+            ERNVersion::V3_8_2 => Family::Ern382,
+            ERNVersion::V4_2 | ERNVersion::V4_3 | ERNVersion::V4_4 => Family::Ern4,
+        }
+    }
+}
+
+/// Root elements of the release-notification schema
+pub(crate) const ROOT_ELEMENTS: &[&str] = &["NewReleaseMessage", "PurgeReleaseMessage"];
+
+const MESSAGE_HEADER: ContentModel = ContentModel {
+    particles: &[
+        optional(&["MessageThreadId"]),
+        one(&["MessageId"]),
+        optional(&["MessageFileName"]),
+        one(&["MessageSender"]),
+        optional(&["SentOnBehalfOf"]),
+        many(&["MessageRecipient"]),
+        one(&["MessageCreatedDateTime"]),
+        optional(&["MessageAuditTrail"]),
+        optional(&["Comment"]),
+        optional(&["MessageControlType"]),
+    ],
+    closed: true,
+};
+
+const MESSAGE_PARTY: ContentModel = ContentModel {
+    particles: &[at_least_one(&["PartyId"])],
+    closed: false,
+};
+
+const PURGE_RELEASE_MESSAGE: ContentModel = ContentModel {
+    particles: &[one(&["MessageHeader"]), one(&["PurgedRelease"])],
+    closed: true,
+};
+
+const VALIDITY_PERIOD: ContentModel = ContentModel {
+    particles: &[at_least_one(&["StartDate", "StartDateTime", "EndDate", "EndDateTime"])],
+    closed: false,
+};
+
+const DEAL_LIST: ContentModel = ContentModel {
+    particles: &[many(&["ReleaseDeal"])],
+    closed: true,
+};
+
+const RELEASE_DEAL: ContentModel = ContentModel {
+    particles: &[
+        at_least_one(&["DealReleaseReference"]),
+        at_least_one(&["Deal"]),
+        optional(&["EffectiveDate"]),
+    ],
+    closed: true,
+};
+
+const DEAL: ContentModel = ContentModel {
+    particles: &[at_least_one(&["DealReference", "DealTerms"])],
+    closed: false,
+};
+
+const ERN_382_NEW_RELEASE_MESSAGE: ContentModel = ContentModel {
+    particles: &[
+        one(&["MessageHeader"]),
+        optional(&["UpdateIndicator"]),
+        optional(&["IsBackfill"]),
+        optional(&["CatalogTransfer"]),
+        optional(&["WorkList"]),
+        optional(&["CueSheetList"]),
+        one(&["ResourceList"]),
+        optional(&["CollectionList"]),
+        one(&["ReleaseList"]),
+        optional(&["DealList"]),
+    ],
+    closed: true,
+};
+
+const ERN_382_RESOURCE_LIST: ContentModel = ContentModel {
+    particles: &[at_least_one(&[
+        "SoundRecording",
+        "MIDI",
+        "Video",
+        "Image",
+        "Text",
+        "SheetMusic",
+        "Software",
+        "UserDefinedResource",
+    ])],
+    closed: true,
+};
+
+const ERN_382_RELEASE_LIST: ContentModel = ContentModel {
+    particles: &[at_least_one(&["Release"])],
+    closed: true,
+};
+
+const ERN_382_RELEASE: ContentModel = ContentModel {
+    particles: &[
+        at_least_one(&["ReleaseId"]),
+        many(&["ReleaseReference"]),
+        one(&["ReferenceTitle"]),
+        at_least_one(&["ReleaseDetailsByTerritory"]),
+    ],
+    closed: false,
+};
+
+const ERN_382_SOUND_RECORDING: ContentModel = ContentModel {
+    particles: &[
+        optional(&["SoundRecordingType"]),
+        at_least_one(&["SoundRecordingId"]),
+        one(&["ResourceReference"]),
+        one(&["ReferenceTitle"]),
+        one(&["Duration"]),
+        at_least_one(&["SoundRecordingDetailsByTerritory"]),
+    ],
+    closed: false,
+};
+
+const ERN_382_DEAL_TERMS: ContentModel = ContentModel {
+    particles: &[
+        many(&["CommercialModelType"]),
+        at_least_one(&["Usage"]),
+        at_least_one(&["TerritoryCode", "ExcludedTerritoryCode"]),
+        at_least_one(&["ValidityPeriod"]),
+    ],
+    closed: false,
+};
+
+const ERN_4_NEW_RELEASE_MESSAGE: ContentModel = ContentModel {
+    particles: &[
+        one(&["MessageHeader"]),
+        many(&["ReleaseAdmin"]),
+        optional(&["PartyList"]),
+        optional(&["CueSheetList"]),
+        one(&["ResourceList"]),
+        optional(&["ChapterList"]),
+        one(&["ReleaseList"]),
+        optional(&["DealList"]),
+        optional(&["SupplementalDocumentList"]),
+    ],
+    closed: true,
+};
+
+const ERN_4_PARTY_LIST: ContentModel = ContentModel {
+    particles: &[at_least_one(&["Party"])],
+    closed: true,
+};
+
+const ERN_4_PARTY: ContentModel = ContentModel {
+    particles: &[one(&["PartyReference"])],
+    closed: false,
+};
+
+const ERN_4_RESOURCE_LIST: ContentModel = ContentModel {
+    particles: &[at_least_one(&[
+        "SoundRecording",
+        "Video",
+        "Image",
+        "Text",
+        "SheetMusic",
+        "Software",
+    ])],
+    closed: true,
+};
+
+const ERN_4_RELEASE_LIST: ContentModel = ContentModel {
+    particles: &[at_least_one(&["Release"]), many(&["TrackRelease"])],
+    closed: true,
+};
+
+const ERN_4_RELEASE: ContentModel = ContentModel {
+    particles: &[
+        one(&["ReleaseReference"]),
+        one(&["ReleaseId"]),
+        at_least_one(&["DisplayTitleText", "DisplayTitle"]),
+    ],
+    closed: false,
+};
+
+const ERN_4_SOUND_RECORDING: ContentModel = ContentModel {
+    particles: &[
+        one(&["ResourceReference"]),
+        at_least_one(&["DisplayTitleText", "DisplayTitle"]),
+        one(&["Duration"]),
+    ],
+    closed: false,
+};
+
+const ERN_4_DEAL_TERMS: ContentModel = ContentModel {
+    particles: &[
+        at_least_one(&["CommercialModelType"]),
+        at_least_one(&["UseType"]),
+        at_least_one(&["TerritoryCode", "ExcludedTerritoryCode"]),
+        at_least_one(&["ValidityPeriod"]),
+    ],
+    closed: false,
+};
+
+/// Look up the content model of an element, if the bundled schema describes it
+pub(crate) fn content_model(family: Family, name: &str) -> Option<&'static ContentModel> {
+    let shared = match name {
+        "MessageHeader" => Some(&MESSAGE_HEADER),
+        "MessageSender" | "MessageRecipient" => Some(&MESSAGE_PARTY),
+        "PurgeReleaseMessage" => Some(&PURGE_RELEASE_MESSAGE),
+        "ValidityPeriod" => Some(&VALIDITY_PERIOD),
+        "DealList" => Some(&DEAL_LIST),
+        "ReleaseDeal" => Some(&RELEASE_DEAL),
+        "Deal" => Some(&DEAL),
+        _ => None,
+    };
+    if shared.is_some() {
+        return shared;
+    }
+
+    match family {
+        Family::Ern382 => match name {
+            "NewReleaseMessage" => Some(&ERN_382_NEW_RELEASE_MESSAGE),
+            "ResourceList" => Some(&ERN_382_RESOURCE_LIST),
+            "ReleaseList" => Some(&ERN_382_RELEASE_LIST),
+            "Release" => Some(&ERN_382_RELEASE),
+            "SoundRecording" => Some(&ERN_382_SOUND_RECORDING),
+            "DealTerms" => Some(&ERN_382_DEAL_TERMS),
+            _ => None,
+        },
+        Family::Ern4 => match name {
+            "NewReleaseMessage" => Some(&ERN_4_NEW_RELEASE_MESSAGE),
+            "PartyList" => Some(&ERN_4_PARTY_LIST),
+            "Party" => Some(&ERN_4_PARTY),
+            "ResourceList" => Some(&ERN_4_RESOURCE_LIST),
+            "ReleaseList" => Some(&ERN_4_RELEASE_LIST),
+            "Release" => Some(&ERN_4_RELEASE),
+            "SoundRecording" => Some(&ERN_4_SOUND_RECORDING),
+            "DealTerms" => Some(&ERN_4_DEAL_TERMS),
+            _ => None,
+        },
+    }
+}
+
+/// Simple-type restrictions on leaf element values
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Facet {
+    Isrc,
+    Icpn,
+    Date,
+    DateTime,
+    Duration,
+    Territory,
+    Enumeration(&'static [&'static str]),
+}
+
+const PARENTAL_WARNING_TYPES: &[&str] = &[
+    "Explicit",
+    "ExplicitContentEdited",
+    "NotExplicit",
+    "NoAdviceAvailable",
+    "Unknown",
+    "UserDefined",
+];
+
+const COMMERCIAL_MODEL_TYPES: &[&str] = &[
+    "AdvertisementSupportedModel",
+    "AsPerContract",
+    "DeviceFeeModel",
+    "FreeOfChargeModel",
+    "PayAsYouGoModel",
+    "PerSubscriberModel",
+    "RightsClaimModel",
+    "SubscriptionModel",
+    "Unknown",
+    "UserDefined",
+];
+
+/// Look up the facet that constrains an element's text, if any
+pub(crate) fn facet_for(name: &str) -> Option<Facet> {
+    match name {
+        "ISRC" => Some(Facet::Isrc),
+        "ICPN" | "UPC" | "EAN" => Some(Facet::Icpn),
+        "StartDate" | "EndDate" | "ReleaseDate" | "OriginalReleaseDate" => Some(Facet::Date),
+        "MessageCreatedDateTime" | "StartDateTime" | "EndDateTime" => Some(Facet::DateTime),
+        "Duration" => Some(Facet::Duration),
+        "TerritoryCode" | "ExcludedTerritoryCode" => Some(Facet::Territory),
+        "ParentalWarningType" => Some(Facet::Enumeration(PARENTAL_WARNING_TYPES)),
+        "CommercialModelType" => Some(Facet::Enumeration(COMMERCIAL_MODEL_TYPES)),
+        _ => None,
+    }
+}
+
+impl Facet {
+    /// Check a value against the facet, returning a description of the
+    /// expected form when it does not match
+    pub fn check(&self, value: &str) -> Result<(), String> {
+        let ok = match self {
+            Facet::Isrc => pattern(&ISRC, r"^[A-Z]{2}[A-Z0-9]{3}[0-9]{7}$").is_match(value),
+            Facet::Icpn => pattern(&ICPN, r"^([0-9]{8}|[0-9]{12,14})$").is_match(value),
+            Facet::Date => pattern(&DATE, r"^[0-9]{4}(-[0-9]{2}(-[0-9]{2})?)?$").is_match(value),
+            Facet::DateTime => pattern(
+                &DATE_TIME,
+                r"^[0-9]{4}-[0-9]{2}-[0-9]{2}T[0-9]{2}:[0-9]{2}:[0-9]{2}(\.[0-9]+)?(Z|[+-][0-9]{2}:[0-9]{2})?$",
+            )
+            .is_match(value),
+            Facet::Duration => {
+                value != "P"
+                    && !value.ends_with('T')
+                    && pattern(
+                        &DURATION,
+                        r"^P([0-9]+Y)?([0-9]+M)?([0-9]+D)?(T([0-9]+H)?([0-9]+M)?([0-9]+(\.[0-9]+)?S)?)?$",
+                    )
+                    .is_match(value)
+            }
+            Facet::Territory => {
+                pattern(&TERRITORY, r"^([A-Z]{2}(-[A-Z0-9]{1,3})?|Worldwide)$").is_match(value)
+            }
+            Facet::Enumeration(values) => values.contains(&value),
+        };
+
+        if ok {
+            return Ok(());
+        }
+
+        Err(match self {
+            Facet::Isrc => "an ISRC of 12 characters (CC-XXX-YY-NNNNN without hyphens)".to_string(),
+            Facet::Icpn => "an ICPN of 8 or 12-14 digits".to_string(),
+            Facet::Date => "an ISO 8601 date (YYYY, YYYY-MM or YYYY-MM-DD)".to_string(),
+            Facet::DateTime => "an ISO 8601 date-time (YYYY-MM-DDThh:mm:ss)".to_string(),
+            Facet::Duration => "an ISO 8601 duration such as PT3M45S".to_string(),
+            Facet::Territory => "an ISO 3166 territory code or 'Worldwide'".to_string(),
+            Facet::Enumeration(values) => format!("one of {}", values.join(", ")),
+        })
+    }
+}
+
+static ISRC: OnceLock<Regex> = OnceLock::new();
+static ICPN: OnceLock<Regex> = OnceLock::new();
+static DATE: OnceLock<Regex> = OnceLock::new();
+static DATE_TIME: OnceLock<Regex> = OnceLock::new();
+static DURATION: OnceLock<Regex> = OnceLock::new();
+static TERRITORY: OnceLock<Regex> = OnceLock::new();
+
+fn pattern(cell: &'static OnceLock<Regex>, source: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(source).expect("bundled schema pattern is valid"))
+}
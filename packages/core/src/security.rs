@@ -0,0 +1,7 @@
+//! XML security shared by the parser and the builder
+//!
+//! [`entity_classifier`] classifies DTD entities, enforces the external
+//! entity policy and accounts for entity expansion, so both sides reject XXE
+//! and billion-laughs documents the same way.
+
+pub mod entity_classifier;
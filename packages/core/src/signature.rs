@@ -0,0 +1,611 @@
+//! Enveloped XML digital signatures (XMLDSig) for DDEX messages
+//!
+//! Some value-chain partners only accept signed ERN deliveries. [`sign`]
+//! appends an enveloped `ds:Signature` as the last child of the message
+//! element. Its single reference covers the whole document (`URI=""`) after
+//! the enveloped-signature transform and Exclusive XML Canonicalization, so
+//! the signature survives any re-serialization that keeps the canonical
+//! form. [`verify`] checks both the digest and the signature value.
+//!
+//! Key material is never handled here: implement [`SigningKey`] and
+//! [`VerifyingKey`] over a crypto library, HSM or KMS. [`HmacSha256Key`]
+//! covers shared-secret partners and tests.
+
+use crate::error::DDEXError;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use indexmap::IndexMap;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+/// XMLDSig namespace
+pub const DSIG_NS: &str = "http://www.w3.org/2000/09/xmldsig#";
+/// Exclusive XML Canonicalization 1.0, without comments
+pub const EXC_C14N: &str = "http://www.w3.org/2001/10/xml-exc-c14n#";
+/// Enveloped-signature transform
+pub const ENVELOPED_SIGNATURE: &str = "http://www.w3.org/2000/09/xmldsig#enveloped-signature";
+/// SHA-256 digest method
+pub const SHA256: &str = "http://www.w3.org/2001/04/xmlenc#sha256";
+/// RSASSA-PKCS1-v1_5 with SHA-256
+pub const RSA_SHA256: &str = "http://www.w3.org/2001/04/xmldsig-more#rsa-sha256";
+/// ECDSA with SHA-256
+pub const ECDSA_SHA256: &str = "http://www.w3.org/2001/04/xmldsig-more#ecdsa-sha256";
+/// HMAC with SHA-256
+pub const HMAC_SHA256: &str = "http://www.w3.org/2001/04/xmldsig-more#hmac-sha256";
+
+/// Private key, or a handle to one, that signs canonical `SignedInfo` bytes
+pub trait SigningKey: Send + Sync {
+    /// `SignatureMethod` algorithm URI, e.g. [`RSA_SHA256`]
+    fn algorithm(&self) -> &str;
+
+    /// Sign the canonical `SignedInfo` element
+    fn sign(&self, signed_info: &[u8]) -> Result<Vec<u8>, DDEXError>;
+
+    /// Name written to `ds:KeyInfo/ds:KeyName` so the receiver can pick the key
+    fn key_name(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// Public key, or a handle to one, that checks a signature value
+pub trait VerifyingKey: Send + Sync {
+    /// Whether `signature` is a valid `algorithm` signature of `signed_info`
+    ///
+    /// Return an error for algorithms the key does not support.
+    fn verify(&self, algorithm: &str, signed_info: &[u8], signature: &[u8]) -> Result<bool, DDEXError>;
+}
+
+impl fmt::Debug for dyn VerifyingKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("VerifyingKey")
+    }
+}
+
+/// Shared-secret HMAC-SHA256 key, usable for both signing and verification
+#[derive(Clone)]
+pub struct HmacSha256Key {
+    secret: Vec<u8>,
+    name: Option<String>,
+}
+
+impl HmacSha256Key {
+    /// Key over a shared secret
+    pub fn new(secret: impl Into<Vec<u8>>) -> Self {
+        Self {
+            secret: secret.into(),
+            name: None,
+        }
+    }
+
+    /// Advertise the key under `name` in signed messages
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    fn mac(&self, data: &[u8]) -> [u8; 32] {
+        let mut block = [0u8; 64];
+        if self.secret.len() > block.len() {
+            block[..32].copy_from_slice(&Sha256::digest(&self.secret));
+        } else {
+            block[..self.secret.len()].copy_from_slice(&self.secret);
+        }
+        let mut inner = Sha256::new();
+        inner.update(block.map(|b| b ^ 0x36));
+        inner.update(data);
+        let mut outer = Sha256::new();
+        outer.update(block.map(|b| b ^ 0x5c));
+        outer.update(inner.finalize());
+        outer.finalize().into()
+    }
+}
+
+impl fmt::Debug for HmacSha256Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HmacSha256Key").field("name", &self.name).finish_non_exhaustive()
+    }
+}
+
+impl SigningKey for HmacSha256Key {
+    fn algorithm(&self) -> &str {
+        HMAC_SHA256
+    }
+
+    fn sign(&self, signed_info: &[u8]) -> Result<Vec<u8>, DDEXError> {
+        Ok(self.mac(signed_info).to_vec())
+    }
+
+    fn key_name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+}
+
+impl VerifyingKey for HmacSha256Key {
+    fn verify(&self, algorithm: &str, signed_info: &[u8], signature: &[u8]) -> Result<bool, DDEXError> {
+        if algorithm != HMAC_SHA256 {
+            return Err(error(format!("HMAC-SHA256 key cannot verify {}", algorithm)));
+        }
+        let expected = self.mac(signed_info);
+        // Compare in constant time
+        Ok(signature.len() == expected.len()
+            && signature.iter().zip(expected).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0)
+    }
+}
+
+/// What a successful [`verify`] checked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureInfo {
+    /// `SignatureMethod` algorithm URI
+    pub algorithm: String,
+    /// `ds:KeyInfo/ds:KeyName`, if the signer advertised one
+    pub key_name: Option<String>,
+}
+
+/// Sign a DDEX message, returning it with an enveloped `ds:Signature`
+///
+/// Line endings are normalized to LF; the document is otherwise unchanged
+/// apart from the signature inserted before the message's end tag.
+pub fn sign(xml: &str, key: &dyn SigningKey) -> Result<String, DDEXError> {
+    let xml = normalize_line_endings(xml);
+    let document = Document::parse(&xml)?;
+    if document.signature()?.is_some() {
+        return Err(error("document is already signed"));
+    }
+    let digest = BASE64.encode(Sha256::digest(canonicalize(&document.root, &Scope::new(), None)?));
+
+    let signed_info = format!(
+        concat!(
+            r#"<ds:SignedInfo><ds:CanonicalizationMethod Algorithm="{c14n}"></ds:CanonicalizationMethod>"#,
+            r#"<ds:SignatureMethod Algorithm="{method}"></ds:SignatureMethod><ds:Reference URI="">"#,
+            r#"<ds:Transforms><ds:Transform Algorithm="{enveloped}"></ds:Transform>"#,
+            r#"<ds:Transform Algorithm="{c14n}"></ds:Transform></ds:Transforms>"#,
+            r#"<ds:DigestMethod Algorithm="{sha256}"></ds:DigestMethod><ds:DigestValue>{digest}</ds:DigestValue>"#,
+            r#"</ds:Reference></ds:SignedInfo>"#,
+        ),
+        c14n = EXC_C14N,
+        method = escape_attribute(key.algorithm()),
+        enveloped = ENVELOPED_SIGNATURE,
+        sha256 = SHA256,
+        digest = digest,
+    );
+    // SignedInfo only uses the ds prefix, so it canonicalizes the same here
+    // as inside the message
+    let standalone = Document::parse(&format!(r#"<ds:Signature xmlns:ds="{}">{}</ds:Signature>"#, DSIG_NS, signed_info))?;
+    let (signed_info_element, scope) = standalone.root.child(&standalone.root.scope(&Scope::new()), "SignedInfo")
+        .ok_or_else(|| error("SignedInfo missing"))?;
+    let signature_value = BASE64.encode(key.sign(canonicalize(signed_info_element, &scope, None)?.as_bytes())?);
+    let key_info = key.key_name()
+        .map(|name| format!("<ds:KeyInfo><ds:KeyName>{}</ds:KeyName></ds:KeyInfo>", escape_text(name)))
+        .unwrap_or_default();
+
+    let signature = format!(
+        r#"<ds:Signature xmlns:ds="{}">{}<ds:SignatureValue>{}</ds:SignatureValue>{}</ds:Signature>"#,
+        DSIG_NS, signed_info, signature_value, key_info,
+    );
+    let end = document.root_end;
+    Ok(format!("{}{}{}", &xml[..end], signature, &xml[end..]))
+}
+
+/// Verify the enveloped signature of a DDEX message
+pub fn verify(xml: &str, key: &dyn VerifyingKey) -> Result<SignatureInfo, DDEXError> {
+    let xml = normalize_line_endings(xml);
+    let document = Document::parse(&xml)?;
+    let (signature, scope) = document.signature()?.ok_or_else(|| error("document is not signed"))?;
+
+    let (signed_info, info_scope) = signature.child(&scope, "SignedInfo")
+        .ok_or_else(|| error("Signature has no SignedInfo"))?;
+    let algorithm_of = |name: &str| {
+        signed_info.child(&info_scope, name)
+            .and_then(|(element, _)| element.attribute("Algorithm"))
+            .ok_or_else(|| error(format!("SignedInfo has no {} algorithm", name)))
+    };
+    if algorithm_of("CanonicalizationMethod")? != EXC_C14N {
+        return Err(error("unsupported CanonicalizationMethod; expected Exclusive C14N"));
+    }
+    let algorithm = algorithm_of("SignatureMethod")?.to_string();
+
+    let references = signed_info.children_named(&info_scope, "Reference");
+    let [(reference, reference_scope)] = references.as_slice() else {
+        return Err(error(format!("expected one Reference, found {}", references.len())));
+    };
+    if reference.attribute("URI") != Some("") {
+        return Err(error("Reference must cover the whole document (URI=\"\")"));
+    }
+    let transforms: Vec<_> = reference.child(reference_scope, "Transforms")
+        .map(|(transforms, scope)| {
+            transforms.children_named(&scope, "Transform")
+                .into_iter()
+                .map(|(transform, _)| transform.attribute("Algorithm").unwrap_or_default())
+                .collect()
+        })
+        .unwrap_or_default();
+    if transforms != [ENVELOPED_SIGNATURE, EXC_C14N] {
+        return Err(error("Reference transforms must be enveloped-signature followed by Exclusive C14N"));
+    }
+    let digest_method = reference.child(reference_scope, "DigestMethod")
+        .and_then(|(element, _)| element.attribute("Algorithm"));
+    if digest_method != Some(SHA256) {
+        return Err(error("unsupported DigestMethod; expected SHA-256"));
+    }
+    let digest_value = decode_base64(reference, reference_scope, "DigestValue")?;
+    if digest_value != Sha256::digest(canonicalize(&document.root, &Scope::new(), Some(signature))?).as_slice() {
+        return Err(error("digest mismatch: the message was modified after signing"));
+    }
+
+    let signature_value = decode_base64(signature, &scope, "SignatureValue")?;
+    if !key.verify(&algorithm, canonicalize(signed_info, &info_scope, None)?.as_bytes(), &signature_value)? {
+        return Err(error("signature value does not match the key"));
+    }
+
+    let key_name = signature.child(&scope, "KeyInfo")
+        .and_then(|(key_info, scope)| key_info.child(&scope, "KeyName"))
+        .map(|(key_name, _)| key_name.text());
+    Ok(SignatureInfo { algorithm, key_name })
+}
+
+/// Exclusive XML Canonicalization (without comments) of a whole document
+pub fn exclusive_c14n(xml: &str) -> Result<String, DDEXError> {
+    let xml = normalize_line_endings(xml);
+    let document = Document::parse(&xml)?;
+    let mut output = String::new();
+    for pi in &document.prolog {
+        output.push_str(pi);
+        output.push('\n');
+    }
+    output.push_str(&canonicalize(&document.root, &Scope::new(), None)?);
+    for pi in &document.epilog {
+        output.push('\n');
+        output.push_str(pi);
+    }
+    Ok(output)
+}
+
+fn error(message: impl Into<String>) -> DDEXError {
+    DDEXError::Signature { message: message.into() }
+}
+
+fn normalize_line_endings(xml: &str) -> String {
+    xml.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+fn decode_base64(parent: &Element, scope: &Scope, name: &str) -> Result<Vec<u8>, DDEXError> {
+    let (element, _) = parent.child(scope, name)
+        .ok_or_else(|| error(format!("{} missing", name)))?;
+    let text: String = element.text().chars().filter(|c| !c.is_ascii_whitespace()).collect();
+    BASE64.decode(text).map_err(|e| error(format!("invalid {}: {}", name, e)))
+}
+
+/// In-scope namespace declarations, prefix (empty for the default) to URI
+type Scope = IndexMap<String, String>;
+
+/// Document tree keeping exactly what canonicalization needs
+struct Document {
+    prolog: Vec<String>,
+    root: Element,
+    epilog: Vec<String>,
+    /// Byte offset of the root end tag
+    root_end: usize,
+}
+
+#[derive(Debug)]
+struct Element {
+    name: String,
+    /// Attributes and namespace declarations in document order, values unescaped
+    attributes: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+#[derive(Debug)]
+enum Node {
+    Element(Element),
+    Text(String),
+    ProcessingInstruction(String),
+}
+
+impl Document {
+    fn parse(xml: &str) -> Result<Self, DDEXError> {
+        let mut reader = Reader::from_str(xml);
+        reader.config_mut().expand_empty_elements = true;
+        let xml_error = |e: &dyn fmt::Display, position: u64| error(format!("XML error at byte {}: {}", position, e));
+
+        let (mut prolog, mut epilog) = (Vec::new(), Vec::new());
+        let mut stack: Vec<Element> = Vec::new();
+        let mut root = None;
+        let mut root_end = 0;
+        loop {
+            let position = reader.buffer_position();
+            let event = reader.read_event().map_err(|e| xml_error(&e, position))?;
+            match event {
+                Event::Start(start) => {
+                    let mut attributes = Vec::new();
+                    for attribute in start.attributes() {
+                        let attribute = attribute.map_err(|e| xml_error(&e, position))?;
+                        // Attribute-value normalization turns literal whitespace into spaces
+                        let raw = String::from_utf8_lossy(&attribute.value).replace(['\t', '\n'], " ");
+                        let value = quick_xml::escape::unescape(&raw).map_err(|e| xml_error(&e, position))?;
+                        attributes.push((
+                            String::from_utf8_lossy(attribute.key.as_ref()).into_owned(),
+                            value.into_owned(),
+                        ));
+                    }
+                    stack.push(Element {
+                        name: String::from_utf8_lossy(start.name().as_ref()).into_owned(),
+                        attributes,
+                        children: Vec::new(),
+                    });
+                }
+                Event::End(_) => {
+                    let element = stack.pop().ok_or_else(|| error("unbalanced end tag"))?;
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(Node::Element(element)),
+                        None => {
+                            root_end = position as usize;
+                            root = Some(element);
+                        }
+                    }
+                }
+                Event::Text(text) => {
+                    if let Some(parent) = stack.last_mut() {
+                        let text = text.unescape().map_err(|e| xml_error(&e, position))?;
+                        parent.children.push(Node::Text(text.into_owned()));
+                    }
+                }
+                Event::CData(cdata) => {
+                    if let Some(parent) = stack.last_mut() {
+                        parent.children.push(Node::Text(String::from_utf8_lossy(&cdata.into_inner()).into_owned()));
+                    }
+                }
+                Event::PI(pi) => {
+                    let pi = format!("<?{}?>", String::from_utf8_lossy(&pi));
+                    match (stack.last_mut(), &root) {
+                        (Some(parent), _) => parent.children.push(Node::ProcessingInstruction(pi)),
+                        (None, None) => prolog.push(pi),
+                        (None, Some(_)) => epilog.push(pi),
+                    }
+                }
+                Event::Eof => break,
+                // Comments, the XML declaration and the DOCTYPE are not part of
+                // the canonical form
+                _ => {}
+            }
+        }
+        let root = root.ok_or_else(|| error("document has no root element"))?;
+        Ok(Self { prolog, root, epilog, root_end })
+    }
+
+    /// The enveloped `ds:Signature`, a direct child of the message element
+    fn signature(&self) -> Result<Option<(&Element, Scope)>, DDEXError> {
+        let scope = self.root.scope(&Scope::new());
+        let signatures = self.root.children_named(&scope, "Signature");
+        match signatures.len() {
+            0 | 1 => Ok(signatures.into_iter().next()),
+            n => Err(error(format!("expected one enveloped Signature, found {}", n))),
+        }
+    }
+}
+
+impl Element {
+    fn prefix(&self) -> &str {
+        self.name.split_once(':').map_or("", |(prefix, _)| prefix)
+    }
+
+    fn local_name(&self) -> &str {
+        self.name.split_once(':').map_or(self.name.as_str(), |(_, local)| local)
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Namespace declarations made on this element
+    fn declarations(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.attributes.iter().filter_map(|(key, value)| {
+            let prefix = if key == "xmlns" { "" } else { key.strip_prefix("xmlns:")? };
+            Some((prefix, value.as_str()))
+        })
+    }
+
+    /// Scope of this element's content given its parent's scope
+    fn scope(&self, parent: &Scope) -> Scope {
+        let mut scope = parent.clone();
+        for (prefix, uri) in self.declarations() {
+            scope.insert(prefix.to_string(), uri.to_string());
+        }
+        scope
+    }
+
+    /// Child elements in the XMLDSig namespace with `local_name`, with their scopes
+    fn children_named(&self, scope: &Scope, local_name: &str) -> Vec<(&Element, Scope)> {
+        self.children.iter().filter_map(|child| match child {
+            Node::Element(element) => {
+                let element_scope = element.scope(scope);
+                let is_dsig = element_scope.get(element.prefix()).is_some_and(|uri| uri == DSIG_NS);
+                (is_dsig && element.local_name() == local_name).then_some((element, element_scope))
+            }
+            _ => None,
+        }).collect()
+    }
+
+    fn child(&self, scope: &Scope, local_name: &str) -> Option<(&Element, Scope)> {
+        self.children_named(scope, local_name).into_iter().next()
+    }
+
+    fn text(&self) -> String {
+        self.children.iter()
+            .filter_map(|child| match child {
+                Node::Text(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Exclusive C14N of `element` as the apex of the output, leaving out `skip`
+///
+/// `scope` holds the namespaces in scope at `element`.
+fn canonicalize(element: &Element, scope: &Scope, skip: Option<&Element>) -> Result<String, DDEXError> {
+    let mut output = String::new();
+    write_canonical(element, skip, scope, &Scope::new(), &mut output)?;
+    Ok(output)
+}
+
+fn write_canonical(
+    element: &Element,
+    skip: Option<&Element>,
+    parent_scope: &Scope,
+    rendered: &Scope,
+    output: &mut String,
+) -> Result<(), DDEXError> {
+    let scope = element.scope(parent_scope);
+    let namespace_of = |prefix: &str| -> Result<String, DDEXError> {
+        match scope.get(prefix) {
+            Some(uri) => Ok(uri.clone()),
+            None if prefix.is_empty() => Ok(String::new()),
+            None => Err(error(format!("unbound namespace prefix `{}`", prefix))),
+        }
+    };
+
+    // Render the namespaces this element visibly uses and no output ancestor declared
+    let attributes: Vec<_> = element.attributes.iter()
+        .filter(|(key, _)| key != "xmlns" && !key.starts_with("xmlns:"))
+        .collect();
+    let mut used = vec![element.prefix()];
+    used.extend(attributes.iter().filter_map(|(key, _)| key.split_once(':').map(|(prefix, _)| prefix)));
+    used.retain(|prefix| *prefix != "xml");
+    used.sort_unstable();
+    used.dedup();
+    let mut rendered = rendered.clone();
+    let mut declarations = Vec::new();
+    for prefix in used {
+        let uri = namespace_of(prefix)?;
+        let already = rendered.get(prefix).map_or(prefix.is_empty() && uri.is_empty(), |r| *r == uri);
+        if !already {
+            declarations.push((prefix, uri.clone()));
+            rendered.insert(prefix.to_string(), uri);
+        }
+    }
+
+    let mut sorted = Vec::with_capacity(attributes.len());
+    for (key, value) in attributes {
+        let (namespace, local) = match key.split_once(':') {
+            Some(("xml", local)) => ("http://www.w3.org/XML/1998/namespace".to_string(), local),
+            Some((prefix, local)) => (namespace_of(prefix)?, local),
+            None => (String::new(), key.as_str()),
+        };
+        sorted.push((namespace, local, key, value));
+    }
+    sorted.sort();
+
+    output.push('<');
+    output.push_str(&element.name);
+    for (prefix, uri) in declarations {
+        if prefix.is_empty() {
+            output.push_str(" xmlns=\"");
+        } else {
+            output.push_str(" xmlns:");
+            output.push_str(prefix);
+            output.push_str("=\"");
+        }
+        output.push_str(&escape_attribute(&uri));
+        output.push('"');
+    }
+    for (_, _, key, value) in sorted {
+        output.push(' ');
+        output.push_str(key);
+        output.push_str("=\"");
+        output.push_str(&escape_attribute(value));
+        output.push('"');
+    }
+    output.push('>');
+
+    for child in &element.children {
+        match child {
+            Node::Element(child) if skip.is_some_and(|skip| std::ptr::eq(child, skip)) => {}
+            Node::Element(child) => write_canonical(child, skip, &scope, &rendered, output)?,
+            Node::Text(text) => output.push_str(&escape_text(text)),
+            Node::ProcessingInstruction(pi) => output.push_str(pi),
+        }
+    }
+
+    output.push_str("</");
+    output.push_str(&element.name);
+    output.push('>');
+    Ok(())
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\r', "&#xD;")
+}
+
+fn escape_attribute(value: &str) -> String {
+    value.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('"', "&quot;")
+        .replace('\t', "&#x9;")
+        .replace('\n', "&#xA;")
+        .replace('\r', "&#xD;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" MessageSchemaVersionId="ern/43">
+  <MessageHeader>
+    <MessageId>MSG1</MessageId>
+    <!-- comments are not signed -->
+  </MessageHeader>
+  <ResourceList><SoundRecording><DisplayTitleText>Rock &amp; Roll</DisplayTitleText></SoundRecording></ResourceList>
+</ern:NewReleaseMessage>
+"#;
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231 test case 2
+        let key = HmacSha256Key::new("Jefe");
+        let mac: String = key.mac(b"what do ya want for nothing?").iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(mac, "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+    }
+
+    #[test]
+    fn test_exclusive_c14n() {
+        let xml = "<?xml version=\"1.0\"?>\r\n<a:root xmlns:a=\"urn:a\" xmlns:unused=\"urn:u\" z=\"1\" a:y=\"2\" b=\"x\ty\"><b/><!-- c --><a:c/></a:root>";
+        assert_eq!(
+            exclusive_c14n(xml).unwrap(),
+            r#"<a:root xmlns:a="urn:a" b="x y" z="1" a:y="2"><b></b><a:c></a:c></a:root>"#
+        );
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let key = HmacSha256Key::new("shared secret").with_name("label-2024");
+        let signed = sign(MESSAGE, &key).unwrap();
+        assert!(signed.contains("<ds:Signature xmlns:ds=\"http://www.w3.org/2000/09/xmldsig#\">"));
+        assert!(signed.trim_end().ends_with("</ds:Signature></ern:NewReleaseMessage>"));
+
+        let info = verify(&signed, &key).unwrap();
+        assert_eq!(info.algorithm, HMAC_SHA256);
+        assert_eq!(info.key_name.as_deref(), Some("label-2024"));
+
+        // Comments and line endings are outside the canonical form
+        let reformatted = signed.replace("<!-- comments are not signed -->", "").replace('\n', "\r\n");
+        assert!(verify(&reformatted, &key).is_ok());
+
+        let tampered = signed.replace("MSG1", "MSG2");
+        let err = verify(&tampered, &key).unwrap_err().to_string();
+        assert!(err.contains("digest mismatch"), "{}", err);
+
+        let err = verify(&signed, &HmacSha256Key::new("other secret")).unwrap_err().to_string();
+        assert!(err.contains("does not match"), "{}", err);
+
+        assert!(verify(MESSAGE, &key).is_err());
+        assert!(sign(&signed, &key).is_err());
+    }
+}
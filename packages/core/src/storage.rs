@@ -0,0 +1,285 @@
+//! Reading and writing documents in S3, Google Cloud Storage and Azure Blob
+//! Storage
+//!
+//! `s3://bucket/key`, `gs://bucket/key` and `az://container/key` URIs are
+//! recognised wherever the suite accepts a path. Credentials come from each
+//! provider's standard chain: `AWS_*` variables, web identity and the
+//! instance or container metadata endpoint for S3; `GOOGLE_*` variables,
+//! application default credentials and the metadata server for GCS;
+//! `AZURE_*` variables and managed identity for Azure.
+//!
+//! The transfers sit behind the `storage` cargo feature. Without it, URIs are
+//! still recognised and reported as unsupported rather than opened as local
+//! files named `s3:`.
+
+use crate::error::DDEXError;
+use std::fmt;
+
+/// Objects larger than this are uploaded in parts
+pub const MULTIPART_THRESHOLD: usize = 16 * 1024 * 1024;
+
+/// Part size of multipart uploads
+pub const PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// Object storage service of a URI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheme {
+    /// Amazon S3 and S3-compatible stores (`s3://`)
+    S3,
+    /// Google Cloud Storage (`gs://`)
+    Gcs,
+    /// Azure Blob Storage (`az://`)
+    Azure,
+}
+
+impl Scheme {
+    fn prefix(self) -> &'static str {
+        match self {
+            Scheme::S3 => "s3",
+            Scheme::Gcs => "gs",
+            Scheme::Azure => "az",
+        }
+    }
+}
+
+/// An object, or a prefix of objects, in a bucket or container
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageUri {
+    pub scheme: Scheme,
+    /// Bucket or container
+    pub bucket: String,
+    /// Object key, without a leading slash
+    pub key: String,
+}
+
+impl StorageUri {
+    /// Parse an object storage URI; `None` for local paths and other URLs
+    pub fn parse(uri: &str) -> Option<Self> {
+        let (prefix, rest) = uri.split_once("://")?;
+        let scheme = [Scheme::S3, Scheme::Gcs, Scheme::Azure]
+            .into_iter()
+            .find(|scheme| scheme.prefix() == prefix)?;
+        let (bucket, key) = rest.split_once('/').unwrap_or((rest, ""));
+        if bucket.is_empty() {
+            return None;
+        }
+        Some(Self {
+            scheme,
+            bucket: bucket.to_string(),
+            key: key.trim_start_matches('/').to_string(),
+        })
+    }
+
+    /// Whether `uri` names an object storage location
+    pub fn is_storage_uri(uri: &str) -> bool {
+        Self::parse(uri).is_some()
+    }
+
+    /// The object `name` under this prefix
+    pub fn join(&self, name: &str) -> Self {
+        let key = match self.key.trim_end_matches('/') {
+            "" => name.to_string(),
+            prefix => format!("{}/{}", prefix, name),
+        };
+        Self { key, ..self.clone() }
+    }
+
+    /// Last segment of the key
+    pub fn file_name(&self) -> &str {
+        self.key.rsplit('/').next().unwrap_or(&self.key)
+    }
+
+    fn error(&self, message: impl fmt::Display) -> DDEXError {
+        DDEXError::Storage {
+            uri: self.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for StorageUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}://{}/{}", self.scheme.prefix(), self.bucket, self.key)
+    }
+}
+
+#[cfg(feature = "storage")]
+mod remote {
+    use super::*;
+    use futures::TryStreamExt;
+    use object_store::aws::AmazonS3Builder;
+    use object_store::azure::MicrosoftAzureBuilder;
+    use object_store::buffered::{BufReader, BufWriter};
+    use object_store::gcp::GoogleCloudStorageBuilder;
+    use object_store::path::Path;
+    use object_store::{ObjectStore, PutPayload, WriteMultipart};
+    use std::sync::Arc;
+
+    impl StorageUri {
+        /// Client for the bucket, configured from the environment
+        fn store(&self) -> Result<Arc<dyn ObjectStore>, DDEXError> {
+            let url = format!("{}://{}", self.scheme.prefix(), self.bucket);
+            let store: Arc<dyn ObjectStore> = match self.scheme {
+                Scheme::S3 => Arc::new(AmazonS3Builder::from_env().with_url(url).build().map_err(|e| self.error(e))?),
+                Scheme::Gcs => {
+                    Arc::new(GoogleCloudStorageBuilder::from_env().with_url(url).build().map_err(|e| self.error(e))?)
+                }
+                Scheme::Azure => {
+                    Arc::new(MicrosoftAzureBuilder::from_env().with_url(url).build().map_err(|e| self.error(e))?)
+                }
+            };
+            Ok(store)
+        }
+
+        fn path(&self) -> Path {
+            Path::from(self.key.as_str())
+        }
+    }
+
+    /// Download an object
+    pub async fn read(uri: &StorageUri) -> Result<Vec<u8>, DDEXError> {
+        let store = uri.store()?;
+        let result = store.get(&uri.path()).await.map_err(|e| uri.error(e))?;
+        let bytes = result.bytes().await.map_err(|e| uri.error(e))?;
+        Ok(bytes.to_vec())
+    }
+
+    /// Upload an object, in parts of [`PART_SIZE`] once it exceeds
+    /// [`MULTIPART_THRESHOLD`]
+    pub async fn write(uri: &StorageUri, bytes: &[u8]) -> Result<(), DDEXError> {
+        let store = uri.store()?;
+        if bytes.len() <= MULTIPART_THRESHOLD {
+            store
+                .put(&uri.path(), PutPayload::from(bytes.to_vec()))
+                .await
+                .map_err(|e| uri.error(e))?;
+            return Ok(());
+        }
+        let upload = store.put_multipart(&uri.path()).await.map_err(|e| uri.error(e))?;
+        let mut writer = WriteMultipart::new_with_chunk_size(upload, PART_SIZE);
+        writer.write(bytes);
+        writer.finish().await.map_err(|e| uri.error(e))?;
+        Ok(())
+    }
+
+    /// Objects under a prefix, in key order
+    pub async fn list(prefix: &StorageUri) -> Result<Vec<StorageUri>, DDEXError> {
+        let store = prefix.store()?;
+        let key = prefix.key.trim_end_matches('/');
+        let path = (!key.is_empty()).then(|| Path::from(key));
+        let mut objects: Vec<StorageUri> = store
+            .list(path.as_ref())
+            .map_ok(|meta| StorageUri {
+                key: meta.location.to_string(),
+                ..prefix.clone()
+            })
+            .try_collect()
+            .await
+            .map_err(|e| prefix.error(e))?;
+        objects.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(objects)
+    }
+
+    /// Buffered async reader over an object, implementing tokio's `AsyncRead`
+    /// and `AsyncSeek`
+    pub async fn reader(uri: &StorageUri) -> Result<BufReader, DDEXError> {
+        let store = uri.store()?;
+        let meta = store.head(&uri.path()).await.map_err(|e| uri.error(e))?;
+        Ok(BufReader::new(store, &meta))
+    }
+
+    /// Async writer to an object, implementing tokio's `AsyncWrite`; it
+    /// switches to a multipart upload once the buffered data exceeds
+    /// [`MULTIPART_THRESHOLD`], and the object appears on `shutdown`
+    pub fn writer(uri: &StorageUri) -> Result<BufWriter, DDEXError> {
+        Ok(BufWriter::with_capacity(uri.store()?, uri.path(), MULTIPART_THRESHOLD))
+    }
+
+    /// Run a transfer to completion from synchronous code
+    ///
+    /// Starts a single-threaded runtime, so it must not be called from
+    /// within an async context.
+    pub fn block_on<T>(
+        uri: &StorageUri,
+        transfer: impl std::future::Future<Output = Result<T, DDEXError>>,
+    ) -> Result<T, DDEXError> {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| uri.error(e))?
+            .block_on(transfer)
+    }
+}
+
+#[cfg(feature = "storage")]
+pub use remote::{list, read, reader, write, writer};
+
+/// Download an object from synchronous code
+pub fn read_blocking(uri: &StorageUri) -> Result<Vec<u8>, DDEXError> {
+    #[cfg(feature = "storage")]
+    return remote::block_on(uri, read(uri));
+    #[cfg(not(feature = "storage"))]
+    Err(unsupported(uri))
+}
+
+/// Upload an object from synchronous code
+pub fn write_blocking(uri: &StorageUri, bytes: &[u8]) -> Result<(), DDEXError> {
+    #[cfg(feature = "storage")]
+    return remote::block_on(uri, write(uri, bytes));
+    #[cfg(not(feature = "storage"))]
+    {
+        let _ = bytes;
+        Err(unsupported(uri))
+    }
+}
+
+/// List the objects under a prefix from synchronous code
+pub fn list_blocking(prefix: &StorageUri) -> Result<Vec<StorageUri>, DDEXError> {
+    #[cfg(feature = "storage")]
+    return remote::block_on(prefix, list(prefix));
+    #[cfg(not(feature = "storage"))]
+    Err(unsupported(prefix))
+}
+
+#[cfg(not(feature = "storage"))]
+fn unsupported(uri: &StorageUri) -> DDEXError {
+    uri.error("object storage support is not enabled in this build (enable the `storage` feature)")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_uris() {
+        let uri = StorageUri::parse("s3://deliveries/2024/03/release.xml").unwrap();
+        assert_eq!(uri.scheme, Scheme::S3);
+        assert_eq!(uri.bucket, "deliveries");
+        assert_eq!(uri.key, "2024/03/release.xml");
+        assert_eq!(uri.file_name(), "release.xml");
+        assert_eq!(uri.to_string(), "s3://deliveries/2024/03/release.xml");
+
+        assert_eq!(StorageUri::parse("gs://bucket").unwrap().key, "");
+        assert_eq!(StorageUri::parse("az://container/a.xml").unwrap().scheme, Scheme::Azure);
+        assert!(StorageUri::parse("https://example.com/a.xml").is_none());
+        assert!(StorageUri::parse("release.xml").is_none());
+        assert!(StorageUri::parse("s3:///key").is_none());
+    }
+
+    #[test]
+    fn test_join() {
+        let prefix = StorageUri::parse("gs://bucket/out/").unwrap();
+        assert_eq!(prefix.join("a.json").to_string(), "gs://bucket/out/a.json");
+        let root = StorageUri::parse("gs://bucket").unwrap();
+        assert_eq!(root.join("a.json").to_string(), "gs://bucket/a.json");
+    }
+
+    #[cfg(not(feature = "storage"))]
+    #[test]
+    fn test_unsupported_without_feature() {
+        let uri = StorageUri::parse("s3://bucket/a.xml").unwrap();
+        let err = read_blocking(&uri).unwrap_err();
+        assert_eq!(err.code(), "DDEX-C-0603");
+        assert!(err.to_string().contains("`storage` feature"));
+    }
+}
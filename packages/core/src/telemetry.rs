@@ -0,0 +1,192 @@
+//! Tracing spans and counters shared by the parser and builder
+//!
+//! The libraries instrument their hot paths with `tracing` spans
+//! (`ddex.parse`, `ddex.preflight`, `ddex.link`, `ddex.serialize`,
+//! `ddex.canonicalize`) and report counters through the functions below.
+//! Counters are `tracing` events on the [`METRICS_TARGET`] target whose
+//! `monotonic_counter.*` fields `tracing-opentelemetry` turns into
+//! OpenTelemetry counters; without a subscriber they cost a level check.
+//!
+//! | Counter | Attributes |
+//! |---------|------------|
+//! | `ddex.bytes_processed` | `operation`: `parse`, `build` |
+//! | `ddex.releases_parsed` | |
+//! | `ddex.validation_failures` | `stage`: `schema`, `preflight` |
+//!
+//! With the `otel` feature, [`init_otlp`] installs a subscriber that exports
+//! spans and counters to an OTLP/HTTP collector, next to the usual log
+//! output on stderr.
+
+/// Target of the counter events, for filtering them out of log output
+pub const METRICS_TARGET: &str = "ddex_metrics";
+
+/// Count bytes of XML read or written by `operation`
+pub fn record_bytes_processed(operation: &'static str, bytes: u64) {
+    tracing::event!(target: "ddex_metrics", tracing::Level::INFO, monotonic_counter.ddex.bytes_processed = bytes, operation);
+}
+
+/// Count releases parsed, by the DOM parser or the streaming iterators
+pub fn record_releases_parsed(releases: u64) {
+    tracing::event!(target: "ddex_metrics", tracing::Level::INFO, monotonic_counter.ddex.releases_parsed = releases);
+}
+
+/// Count validation errors found at `stage`
+pub fn record_validation_failures(stage: &'static str, failures: u64) {
+    if failures > 0 {
+        tracing::event!(target: "ddex_metrics", tracing::Level::INFO, monotonic_counter.ddex.validation_failures = failures, stage);
+    }
+}
+
+#[cfg(feature = "otel")]
+pub use otlp::{init_otlp, OtlpConfig, TelemetryGuard};
+
+#[cfg(feature = "otel")]
+mod otlp {
+    use crate::error::DDEXError;
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_otlp::{MetricExporter, SpanExporter, WithExportConfig};
+    use opentelemetry_sdk::metrics::SdkMeterProvider;
+    use opentelemetry_sdk::trace::SdkTracerProvider;
+    use opentelemetry_sdk::Resource;
+    use tracing_subscriber::filter::{LevelFilter, Targets};
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+    use tracing_subscriber::Layer;
+
+    /// Where and as what to export
+    #[derive(Debug, Clone)]
+    pub struct OtlpConfig {
+        /// Collector base URL, e.g. `http://localhost:4318`; `/v1/traces`
+        /// and `/v1/metrics` are appended
+        pub endpoint: String,
+        /// `service.name` of the exported resource
+        pub service_name: String,
+        /// Level of the log lines printed to stderr; `None` prints none
+        pub log_level: Option<tracing::Level>,
+    }
+
+    /// Flushes and shuts the exporters down when dropped
+    pub struct TelemetryGuard {
+        tracer_provider: SdkTracerProvider,
+        meter_provider: SdkMeterProvider,
+    }
+
+    impl Drop for TelemetryGuard {
+        fn drop(&mut self) {
+            if let Err(e) = self.tracer_provider.shutdown() {
+                eprintln!("OTLP trace export failed: {}", e);
+            }
+            if let Err(e) = self.meter_provider.shutdown() {
+                eprintln!("OTLP metric export failed: {}", e);
+            }
+        }
+    }
+
+    /// Install a global subscriber exporting spans and counters over
+    /// OTLP/HTTP; keep the guard alive until the work is done
+    pub fn init_otlp(config: &OtlpConfig) -> Result<TelemetryGuard, DDEXError> {
+        let endpoint = config.endpoint.trim_end_matches('/');
+        let resource = Resource::builder().with_service_name(config.service_name.clone()).build();
+
+        let spans = SpanExporter::builder()
+            .with_http()
+            .with_endpoint(format!("{}/v1/traces", endpoint))
+            .build()
+            .map_err(exporter_error)?;
+        let tracer_provider = SdkTracerProvider::builder()
+            .with_batch_exporter(spans)
+            .with_resource(resource.clone())
+            .build();
+
+        let metrics = MetricExporter::builder()
+            .with_http()
+            .with_endpoint(format!("{}/v1/metrics", endpoint))
+            .build()
+            .map_err(exporter_error)?;
+        let meter_provider = SdkMeterProvider::builder()
+            .with_periodic_exporter(metrics)
+            .with_resource(resource)
+            .build();
+
+        let log_level = config.log_level.map_or(LevelFilter::OFF, LevelFilter::from_level);
+        let logs = tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_target(false)
+            .with_filter(Targets::new().with_default(log_level).with_target(super::METRICS_TARGET, LevelFilter::OFF));
+        let exported = Targets::new()
+            .with_target("ddex_core", LevelFilter::INFO)
+            .with_target("ddex_parser", LevelFilter::INFO)
+            .with_target("ddex_builder", LevelFilter::INFO)
+            .with_target(super::METRICS_TARGET, LevelFilter::INFO);
+        let tracer = tracer_provider.tracer(config.service_name.clone());
+
+        tracing_subscriber::registry()
+            .with(logs)
+            .with(tracing_opentelemetry::layer().with_tracer(tracer).with_filter(exported.clone()))
+            .with(tracing_opentelemetry::MetricsLayer::new(meter_provider.clone()).with_filter(exported))
+            .try_init()
+            .map_err(|e| DDEXError::IoError { message: format!("cannot install the OTLP subscriber: {}", e) })?;
+
+        Ok(TelemetryGuard { tracer_provider, meter_provider })
+    }
+
+    fn exporter_error(e: opentelemetry_otlp::ExporterBuildError) -> DDEXError {
+        DDEXError::IoError { message: format!("cannot create the OTLP exporter: {}", e) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /// Collects the counter fields of events on the metrics target
+    #[derive(Clone, Default)]
+    struct Counters(Arc<Mutex<Vec<(String, u64)>>>);
+
+    impl Visit for Counters {
+        fn record_u64(&mut self, field: &Field, value: u64) {
+            self.0.lock().unwrap().push((field.name().to_string(), value));
+        }
+
+        fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+    }
+
+    impl Subscriber for Counters {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            metadata.target() == METRICS_TARGET
+        }
+        fn new_span(&self, _: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            tracing::span::Id::from_u64(1)
+        }
+        fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+        fn event(&self, event: &Event<'_>) {
+            event.record(&mut self.clone());
+        }
+        fn enter(&self, _: &tracing::span::Id) {}
+        fn exit(&self, _: &tracing::span::Id) {}
+    }
+
+    #[test]
+    fn test_counter_events() {
+        let counters = Counters::default();
+        tracing::subscriber::with_default(counters.clone(), || {
+            record_bytes_processed("parse", 2048);
+            record_releases_parsed(3);
+            record_validation_failures("schema", 0);
+            record_validation_failures("preflight", 2);
+        });
+        let recorded = counters.0.lock().unwrap().clone();
+        assert_eq!(
+            recorded,
+            [
+                ("monotonic_counter.ddex.bytes_processed".to_string(), 2048),
+                ("monotonic_counter.ddex.releases_parsed".to_string(), 3),
+                ("monotonic_counter.ddex.validation_failures".to_string(), 2),
+            ]
+        );
+    }
+}
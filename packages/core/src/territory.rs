@@ -0,0 +1,371 @@
+//! Territory algebra for deals and rights
+//!
+//! DDEX names the territories of a deal with `TerritoryCode` (ISO 3166-1
+//! alpha-2 codes or `Worldwide`) and `ExcludedTerritoryCode`. A
+//! [`TerritorySet`] is the set of countries such a list stands for, so
+//! "Worldwide except US" and "every country but the US" compare equal and
+//! can be intersected with other deals:
+//!
+//! ```rust
+//! use ddex_core::territory::TerritorySet;
+//!
+//! let rest_of_world = TerritorySet::from_terms(["Worldwide"], ["US", "CA"])?;
+//! let north_america = TerritorySet::from_codes(["US", "CA", "MX"])?;
+//! assert_eq!(rest_of_world.intersection(&north_america).to_string(), "MX");
+//! assert_eq!(rest_of_world.to_string(), "Worldwide except CA, US");
+//! # Ok::<(), ddex_core::territory::TerritoryError>(())
+//! ```
+//!
+//! [`DealScope`] adds the period and use of a deal, and [`find_conflicts`]
+//! reports deals that grant the same territory for the same use and time.
+
+use crate::models::graph::{CommercialModelType, DealTerms, UseType};
+use chrono::NaiveDate;
+use std::fmt;
+
+/// ISO 3166-1 alpha-2 codes, sorted, plus `XK` (Kosovo), which DDEX allows
+pub const ISO_3166_CODES: &[&str] = &[
+    "AD", "AE", "AF", "AG", "AI", "AL", "AM", "AO", "AQ", "AR", "AS", "AT", "AU", "AW", "AX", "AZ",
+    "BA", "BB", "BD", "BE", "BF", "BG", "BH", "BI", "BJ", "BL", "BM", "BN", "BO", "BQ", "BR", "BS",
+    "BT", "BV", "BW", "BY", "BZ", "CA", "CC", "CD", "CF", "CG", "CH", "CI", "CK", "CL", "CM", "CN",
+    "CO", "CR", "CU", "CV", "CW", "CX", "CY", "CZ", "DE", "DJ", "DK", "DM", "DO", "DZ", "EC", "EE",
+    "EG", "EH", "ER", "ES", "ET", "FI", "FJ", "FK", "FM", "FO", "FR", "GA", "GB", "GD", "GE", "GF",
+    "GG", "GH", "GI", "GL", "GM", "GN", "GP", "GQ", "GR", "GS", "GT", "GU", "GW", "GY", "HK", "HM",
+    "HN", "HR", "HT", "HU", "ID", "IE", "IL", "IM", "IN", "IO", "IQ", "IR", "IS", "IT", "JE", "JM",
+    "JO", "JP", "KE", "KG", "KH", "KI", "KM", "KN", "KP", "KR", "KW", "KY", "KZ", "LA", "LB", "LC",
+    "LI", "LK", "LR", "LS", "LT", "LU", "LV", "LY", "MA", "MC", "MD", "ME", "MF", "MG", "MH", "MK",
+    "ML", "MM", "MN", "MO", "MP", "MQ", "MR", "MS", "MT", "MU", "MV", "MW", "MX", "MY", "MZ", "NA",
+    "NC", "NE", "NF", "NG", "NI", "NL", "NO", "NP", "NR", "NU", "NZ", "OM", "PA", "PE", "PF", "PG",
+    "PH", "PK", "PL", "PM", "PN", "PR", "PS", "PT", "PW", "PY", "QA", "RE", "RO", "RS", "RU", "RW",
+    "SA", "SB", "SC", "SD", "SE", "SG", "SH", "SI", "SJ", "SK", "SL", "SM", "SN", "SO", "SR", "SS",
+    "ST", "SV", "SX", "SY", "SZ", "TC", "TD", "TF", "TG", "TH", "TJ", "TK", "TL", "TM", "TN", "TO",
+    "TR", "TT", "TV", "TW", "TZ", "UA", "UG", "UM", "US", "UY", "UZ", "VA", "VC", "VE", "VG", "VI",
+    "VN", "VU", "WF", "WS", "XK", "YE", "YT", "ZA", "ZM", "ZW",
+];
+
+/// The code standing for every territory
+pub const WORLDWIDE: &str = "Worldwide";
+
+const WORDS: usize = 4;
+const _: () = assert!(ISO_3166_CODES.len() <= WORDS * 64);
+
+/// A code that is neither `Worldwide` nor an ISO 3166-1 alpha-2 code
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("'{code}' is not an ISO 3166-1 territory code or 'Worldwide'")]
+pub struct TerritoryError {
+    /// The code as given
+    pub code: String,
+}
+
+/// Whether `code` is `Worldwide` or an ISO 3166-1 alpha-2 code
+pub fn is_valid_code(code: &str) -> bool {
+    code == WORLDWIDE || ISO_3166_CODES.binary_search(&code).is_ok()
+}
+
+/// A set of countries
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TerritorySet {
+    bits: [u64; WORDS],
+}
+
+impl TerritorySet {
+    /// No territory
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Every territory
+    pub fn worldwide() -> Self {
+        let mut set = Self::empty();
+        for index in 0..ISO_3166_CODES.len() {
+            set.bits[index / 64] |= 1 << (index % 64);
+        }
+        set
+    }
+
+    /// The territories of a `TerritoryCode` list, expanding `Worldwide`
+    pub fn from_codes<I>(codes: I) -> Result<Self, TerritoryError>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut set = Self::empty();
+        for code in codes {
+            set = set.union(&Self::parse(code.as_ref())?);
+        }
+        Ok(set)
+    }
+
+    /// The territories granted by `TerritoryCode` and `ExcludedTerritoryCode`
+    /// lists; exclusions alone are taken from `Worldwide`, as in DDEX
+    pub fn from_terms<I, E>(included: I, excluded: E) -> Result<Self, TerritoryError>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+        E: IntoIterator,
+        E::Item: AsRef<str>,
+    {
+        let mut included = included.into_iter().peekable();
+        let base = if included.peek().is_none() { Self::worldwide() } else { Self::from_codes(included)? };
+        Ok(base.difference(&Self::from_codes(excluded)?))
+    }
+
+    fn parse(code: &str) -> Result<Self, TerritoryError> {
+        if code.eq_ignore_ascii_case(WORLDWIDE) {
+            return Ok(Self::worldwide());
+        }
+        let index = index(code).ok_or_else(|| TerritoryError { code: code.to_string() })?;
+        let mut set = Self::empty();
+        set.bits[index / 64] |= 1 << (index % 64);
+        Ok(set)
+    }
+
+    /// Whether the country `code` is in the set; `false` for unknown codes
+    pub fn contains(&self, code: &str) -> bool {
+        index(code).is_some_and(|index| self.bits[index / 64] & (1 << (index % 64)) != 0)
+    }
+
+    /// Add the territories of `code`
+    pub fn insert(&mut self, code: &str) -> Result<(), TerritoryError> {
+        *self = self.union(&Self::parse(code)?);
+        Ok(())
+    }
+
+    /// Remove the territories of `code`
+    pub fn remove(&mut self, code: &str) -> Result<(), TerritoryError> {
+        *self = self.difference(&Self::parse(code)?);
+        Ok(())
+    }
+
+    /// Territories in either set
+    pub fn union(&self, other: &Self) -> Self {
+        self.zip(other, |a, b| a | b)
+    }
+
+    /// Territories in both sets
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.zip(other, |a, b| a & b)
+    }
+
+    /// Territories in this set but not in `other`
+    pub fn difference(&self, other: &Self) -> Self {
+        self.zip(other, |a, b| a & !b)
+    }
+
+    /// Territories not in this set
+    pub fn complement(&self) -> Self {
+        Self::worldwide().difference(self)
+    }
+
+    fn zip(&self, other: &Self, op: impl Fn(u64, u64) -> u64) -> Self {
+        let mut bits = [0; WORDS];
+        for (word, out) in bits.iter_mut().enumerate() {
+            *out = op(self.bits[word], other.bits[word]);
+        }
+        Self { bits }
+    }
+
+    /// Whether both sets share a territory
+    pub fn intersects(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    /// Whether every territory of this set is in `other`
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    /// Whether the set has no territory
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|word| *word == 0)
+    }
+
+    /// Whether the set has every territory
+    pub fn is_worldwide(&self) -> bool {
+        *self == Self::worldwide()
+    }
+
+    /// Number of countries
+    pub fn len(&self) -> usize {
+        self.bits.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Country codes, in alphabetical order
+    pub fn codes(&self) -> impl Iterator<Item = &'static str> + '_ {
+        ISO_3166_CODES
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| self.bits[index / 64] & (1 << (index % 64)) != 0)
+            .map(|(_, code)| *code)
+    }
+
+    /// The shortest `TerritoryCode` and `ExcludedTerritoryCode` lists
+    /// granting this set: `Worldwide` less exclusions when that is shorter
+    pub fn to_terms(&self) -> (Vec<String>, Vec<String>) {
+        let complement = self.complement();
+        if !self.is_empty() && complement.len() < self.len() {
+            (vec![WORLDWIDE.to_string()], complement.codes().map(str::to_string).collect())
+        } else {
+            (self.codes().map(str::to_string).collect(), Vec::new())
+        }
+    }
+}
+
+fn index(code: &str) -> Option<usize> {
+    if code.len() != 2 {
+        return None;
+    }
+    ISO_3166_CODES.binary_search(&code.to_ascii_uppercase().as_str()).ok()
+}
+
+impl fmt::Display for TerritorySet {
+    /// "Worldwide", "Worldwide except CA, US" or "GB, US"; "none" when empty
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (included, excluded) = self.to_terms();
+        match (included.is_empty(), excluded.is_empty()) {
+            (true, _) => f.write_str("none"),
+            (false, true) => f.write_str(&included.join(", ")),
+            (false, false) => write!(f, "{} except {}", WORLDWIDE, excluded.join(", ")),
+        }
+    }
+}
+
+impl fmt::Debug for TerritorySet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TerritorySet({})", self)
+    }
+}
+
+/// What a deal grants: territories, uses and validity period
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DealScope {
+    /// Territories granted
+    pub territories: TerritorySet,
+    /// Commercial models, e.g. "SubscriptionModel"; empty for any
+    pub commercial_models: Vec<String>,
+    /// Use types, e.g. "Stream"; empty for any
+    pub use_types: Vec<String>,
+    /// First day of validity; `None` for open
+    pub start: Option<NaiveDate>,
+    /// Last day of validity; `None` for open
+    pub end: Option<NaiveDate>,
+}
+
+impl DealScope {
+    /// Scope of parsed deal terms
+    pub fn from_graph(terms: &DealTerms) -> Result<Self, TerritoryError> {
+        let period = terms.validity_period.as_ref();
+        Ok(Self {
+            territories: TerritorySet::from_terms(&terms.territory_code, &terms.excluded_territory_code)?,
+            commercial_models: terms.commercial_model_type.iter().map(|model| match model {
+                CommercialModelType::Other(name) => name.clone(),
+                other => format!("{:?}", other),
+            }).collect(),
+            use_types: terms.use_type.iter().map(|use_type| match use_type {
+                UseType::Other(name) => name.clone(),
+                other => format!("{:?}", other),
+            }).collect(),
+            start: period.and_then(|period| period.start_date).or(terms.start_date).map(|date| date.date_naive()),
+            end: period.and_then(|period| period.end_date).or(terms.end_date).map(|date| date.date_naive()),
+        })
+    }
+
+    /// Territories both deals grant for a shared use during a shared period;
+    /// empty when they don't conflict
+    pub fn overlap(&self, other: &DealScope) -> TerritorySet {
+        let shares = |a: &[String], b: &[String]| a.is_empty() || b.is_empty() || a.iter().any(|x| b.contains(x));
+        // Missing bounds are open
+        let periods = self.start.zip(other.end).is_none_or(|(start, end)| start <= end)
+            && other.start.zip(self.end).is_none_or(|(start, end)| start <= end);
+        if shares(&self.commercial_models, &other.commercial_models) && shares(&self.use_types, &other.use_types) && periods {
+            self.territories.intersection(&other.territories)
+        } else {
+            TerritorySet::empty()
+        }
+    }
+}
+
+/// Two deals granting the same territories for the same use and period
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DealConflict {
+    /// Index of the earlier deal
+    pub first: usize,
+    /// Index of the later deal
+    pub second: usize,
+    /// Territories both grant
+    pub territories: TerritorySet,
+}
+
+/// Every pair of conflicting deals, in order of the later deal
+pub fn find_conflicts(deals: &[DealScope]) -> Vec<DealConflict> {
+    let mut conflicts = Vec::new();
+    for (second, scope) in deals.iter().enumerate() {
+        for (first, earlier) in deals[..second].iter().enumerate() {
+            let territories = earlier.overlap(scope);
+            if !territories.is_empty() {
+                conflicts.push(DealConflict { first, second, territories });
+            }
+        }
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> Option<NaiveDate> {
+        Some(text.parse().unwrap())
+    }
+
+    #[test]
+    fn test_worldwide_and_exclusions() {
+        assert_eq!(TerritorySet::worldwide().len(), ISO_3166_CODES.len());
+        assert!(TerritorySet::from_codes(["Worldwide"]).unwrap().is_worldwide());
+
+        let set = TerritorySet::from_terms(Vec::<String>::new(), ["US"]).unwrap();
+        assert!(!set.contains("US") && set.contains("gb"));
+        assert_eq!(set.to_terms(), (vec!["Worldwide".to_string()], vec!["US".to_string()]));
+        assert_eq!(set.complement().to_string(), "US");
+        assert_eq!(TerritorySet::empty().to_string(), "none");
+
+        assert_eq!(TerritorySet::from_codes(["US", "XX"]), Err(TerritoryError { code: "XX".to_string() }));
+        assert!(is_valid_code("Worldwide") && is_valid_code("XK") && !is_valid_code("UK"));
+    }
+
+    #[test]
+    fn test_union_and_intersection() {
+        let europe = TerritorySet::from_codes(["DE", "FR", "GB"]).unwrap();
+        let mut english = TerritorySet::from_codes(["GB", "IE", "US"]).unwrap();
+        assert_eq!(europe.intersection(&english).to_string(), "GB");
+        assert_eq!(europe.union(&english).len(), 5);
+        assert!(!europe.is_subset(&english));
+
+        english.remove("US").unwrap();
+        english.insert("DE").unwrap();
+        assert_eq!(english.codes().collect::<Vec<_>>(), ["DE", "GB", "IE"]);
+    }
+
+    #[test]
+    fn test_conflicting_deals() {
+        let scope = |included: &[&str], excluded: &[&str], start: &str, end: Option<&str>| DealScope {
+            territories: TerritorySet::from_terms(included, excluded).unwrap(),
+            commercial_models: vec!["SubscriptionModel".to_string()],
+            use_types: vec!["Stream".to_string()],
+            start: date(start),
+            end: end.and_then(date),
+        };
+        let deals = [
+            scope(&["GB", "US"], &[], "2024-01-01", Some("2024-12-31")),
+            scope(&["Worldwide"], &["US"], "2024-06-01", None),
+            // Starts after the first ends
+            scope(&["US"], &[], "2025-01-01", None),
+            DealScope { use_types: vec!["PermanentDownload".to_string()], ..scope(&["GB"], &[], "2024-01-01", None) },
+        ];
+
+        let conflicts = find_conflicts(&deals);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!((conflicts[0].first, conflicts[0].second), (0, 1));
+        assert_eq!(conflicts[0].territories.to_string(), "GB");
+    }
+}
@@ -0,0 +1,138 @@
+//! Normalizations recorded while building or parsing
+//!
+//! Whenever the builder or parser changes a value on its way through (trims
+//! whitespace, rewrites a date, drops a field it does not know), it records
+//! the change with the path of the value and what it looked like before and
+//! after, instead of applying it silently. Strict pipelines can turn any
+//! recorded change into an error with the `fail_on_warning` options.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Kind of change made to a value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub enum NormalizationKind {
+    /// Leading or trailing whitespace removed
+    TrimmedWhitespace,
+    /// Date rewritten to ISO 8601 (`YYYY-MM-DD`)
+    CoercedDate,
+    /// Identifier stripped of separators or upper-cased
+    ReformattedIdentifier,
+    /// Field not part of the model, left out
+    DroppedField,
+    /// Text rewritten to Unicode Normalization Form C
+    NormalizedUnicode,
+    /// Zero-width, bidirectional or control characters removed
+    RemovedInvisible,
+    /// Runs of whitespace, tabs or line breaks replaced by one space
+    CollapsedWhitespace,
+    /// Typographic quotes in an identifier replaced by ASCII quotes
+    ReplacedQuotes,
+}
+
+impl NormalizationKind {
+    /// Stable code of the kind, e.g. `TRIMMED_WHITESPACE`
+    pub fn code(self) -> &'static str {
+        match self {
+            NormalizationKind::TrimmedWhitespace => "TRIMMED_WHITESPACE",
+            NormalizationKind::CoercedDate => "COERCED_DATE",
+            NormalizationKind::ReformattedIdentifier => "REFORMATTED_IDENTIFIER",
+            NormalizationKind::DroppedField => "DROPPED_FIELD",
+            NormalizationKind::NormalizedUnicode => "NORMALIZED_UNICODE",
+            NormalizationKind::RemovedInvisible => "REMOVED_INVISIBLE",
+            NormalizationKind::CollapsedWhitespace => "COLLAPSED_WHITESPACE",
+            NormalizationKind::ReplacedQuotes => "REPLACED_QUOTES",
+        }
+    }
+}
+
+/// One recorded change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct Normalization {
+    /// What was done
+    pub kind: NormalizationKind,
+    /// JSON path (build requests) or element path (XML) of the value
+    pub path: String,
+    /// Value as given
+    pub before: String,
+    /// Value as used, `None` if it was dropped
+    pub after: Option<String>,
+}
+
+impl fmt::Display for Normalization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.after {
+            Some(after) => write!(f, "{} at {}: {:?} -> {:?}", self.kind.code(), self.path, self.before, after),
+            None => write!(f, "{} at {}: {:?}", self.kind.code(), self.path, self.before),
+        }
+    }
+}
+
+/// Collector of the normalizations made to one message
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(transparent)]
+pub struct Warnings(Vec<Normalization>);
+
+impl Warnings {
+    /// Empty collector
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a change of the value at `path`
+    pub fn record(
+        &mut self,
+        kind: NormalizationKind,
+        path: impl Into<String>,
+        before: impl Into<String>,
+        after: Option<String>,
+    ) {
+        self.0.push(Normalization {
+            kind,
+            path: path.into(),
+            before: before.into(),
+            after,
+        });
+    }
+
+    /// Whether nothing was changed
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Number of changes
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Changes in the order they were made
+    pub fn iter(&self) -> std::slice::Iter<'_, Normalization> {
+        self.0.iter()
+    }
+
+    /// Append the changes of `other`
+    pub fn append(&mut self, other: Warnings) {
+        self.0.extend(other.0);
+    }
+}
+
+impl<'a> IntoIterator for &'a Warnings {
+    type Item = &'a Normalization;
+    type IntoIter = std::slice::Iter<'a, Normalization>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl IntoIterator for Warnings {
+    type Item = Normalization;
+    type IntoIter = std::vec::IntoIter<Normalization>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
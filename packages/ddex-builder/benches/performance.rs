@@ -190,6 +190,10 @@ fn create_test_request_with_tracks(track_count: usize) -> BuildRequest {
         }],
         genre: vec!["Rock".to_string()], // Common genre for interning
         release_date: Some("2024-01-01".to_string()),
+        track_isrcs: Vec::new(),
+        track_releases: Vec::new(),
+        comments: Vec::new(),
+        original_release_date: None,
     });
 
     BuildRequest {
@@ -132,6 +132,10 @@ fn create_realistic_album_request() -> BuildRequest {
             }],
             genre: vec!["Electronic".to_string(), "Pop".to_string()],
             release_date: Some("2024-03-15".to_string()),
+            track_isrcs: Vec::new(),
+            track_releases: Vec::new(),
+            comments: Vec::new(),
+            original_release_date: None,
         }],
         deals: vec![],
         extensions: None,
@@ -0,0 +1,72 @@
+//! Build, canonicalize and round-trip at 10KB, 1MB and 100MB
+//!
+//! Throughput is reported in bytes of XML, so the results read directly
+//! against the "<50ms per MB" target; `ddex-builder bench` runs the same
+//! workloads without Criterion.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use ddex_builder::benchmark::{sample_xml, sized_request};
+use ddex_builder::{BuildOptions, DDEXBuilder, DeterminismConfig, FidelityOptions, RoundTripTester, DB_C14N};
+use std::time::Duration;
+
+const SIZES: [(&str, usize); 3] = [
+    ("10KB", 10 * 1024),
+    ("1MB", 1024 * 1024),
+    ("100MB", 100 * 1024 * 1024),
+];
+
+fn configure(group: &mut criterion::BenchmarkGroup<'_, criterion::measurement::WallTime>, bytes: usize) {
+    group.throughput(Throughput::Bytes(bytes as u64));
+    if bytes >= 100 * 1024 * 1024 {
+        group.sample_size(10).measurement_time(Duration::from_secs(120));
+    }
+}
+
+fn bench_build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build");
+    let builder = DDEXBuilder::new();
+
+    for (label, target) in SIZES {
+        let request = sized_request(target).expect("sample request builds");
+        let bytes = builder.build(request.clone(), BuildOptions::default()).expect("sample request builds").xml.len();
+        configure(&mut group, bytes);
+        group.bench_with_input(BenchmarkId::from_parameter(label), &request, |b, request| {
+            b.iter(|| black_box(builder.build(request.clone(), BuildOptions::default())))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_canonicalize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("canonicalize");
+    let canonicalizer = DB_C14N::new(DeterminismConfig::default());
+
+    for (label, target) in SIZES {
+        let xml = sample_xml(target).expect("sample request builds");
+        configure(&mut group, xml.len());
+        group.bench_with_input(BenchmarkId::from_parameter(label), &xml, |b, xml| {
+            b.iter(|| black_box(canonicalizer.canonicalize(xml)))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_round_trip(c: &mut Criterion) {
+    let mut group = c.benchmark_group("round_trip");
+    let tester = RoundTripTester::new(FidelityOptions::default());
+
+    for (label, target) in SIZES {
+        let xml = sample_xml(target).expect("sample request builds");
+        configure(&mut group, xml.len());
+        group.bench_with_input(BenchmarkId::from_parameter(label), &xml, |b, xml| {
+            b.iter(|| black_box(tester.test_round_trip(xml)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_build, bench_canonicalize, bench_round_trip);
+criterion_main!(benches);
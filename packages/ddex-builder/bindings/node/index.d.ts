@@ -3,6 +3,8 @@
 
 /* auto-generated by NAPI-RS */
 
+import type { Transform } from 'stream'
+
 export interface Release {
   releaseId: string
   releaseType: string
@@ -133,15 +135,45 @@ export interface MessageHeader {
   messageRecipientName: string
   messageCreatedDateTime?: string
 }
+/** One release written to `createBuildStream()` */
+export interface StreamRelease {
+  releaseId: string
+  title: string
+  artist: string
+  label?: string
+  upc?: string
+  releaseDate?: string
+  genre?: string
+  resourceReferences?: Array<string>
+}
+export interface BuildStreamOptions {
+  header: MessageHeader
+  /** ERN version, `4.3` by default */
+  version?: string
+  /** Bytes gathered before a chunk is emitted, 64KB by default */
+  chunkSize?: number
+}
 export declare function batchBuild(requests: Array<string>): Promise<Array<string>>
-export declare function validateStructure(xml: string): Promise<ValidationResult>
+/** Accepts a `Buffer` as well as a string; the buffer is read in place */
+export declare function validateStructure(xml: string | Buffer): Promise<ValidationResult>
 export declare class DdexBuilder {
   constructor()
+  /**
+   * Stop every build in flight, which reject with a `DDEX-B-0603` error;
+   * pass an `AbortSignal` to `build` to stop just one
+   */
+  cancel(): void
+  /**
+   * Declare a proprietary namespace under `prefix` in every build; throws
+   * when the prefix or URI is taken by DDEX or another extension
+   */
+  registerExtensionNamespace(prefix: string, uri: string, schemaUrl?: string | undefined | null): void
   addRelease(release: Release): void
   addResource(resource: Resource): void
-  build(data?: any | undefined | null): Promise<string>
-  buildWithFidelity(data?: any | undefined | null, fidelityOptions?: FidelityOptions | undefined | null): Promise<BuildResult>
-  testRoundTripFidelity(originalXml: string, fidelityOptions?: FidelityOptions | undefined | null): Promise<VerificationResult>
+  /** Build off the event loop; aborting `signal` stops this build alone */
+  build(data?: any | undefined | null, signal?: AbortSignal | undefined | null): Promise<string>
+  buildWithFidelity(data?: any | undefined | null, fidelityOptions?: FidelityOptions | undefined | null, signal?: AbortSignal | undefined | null): Promise<BuildResult>
+  testRoundTripFidelity(originalXml: string | Buffer, fidelityOptions?: FidelityOptions | undefined | null): Promise<VerificationResult>
   validate(): Promise<ValidationResult>
   getStats(): BuilderStats
   reset(): void
@@ -149,6 +181,16 @@ export declare class DdexBuilder {
   getPresetInfo(presetName: string): PresetInfo
   applyPreset(presetName: string): void
   getPresetValidationRules(presetName: string): Array<ValidationRule>
+  /**
+   * Transform stream of release objects in, XML chunks of one message out
+   *
+   * @example
+   * Readable.from(releases)
+   *   .pipe(builder.createBuildStream({ header }))
+   *   .pipe(zlib.createGzip())
+   *   .pipe(fs.createWriteStream('catalog.xml.gz'))
+   */
+  createBuildStream(options: BuildStreamOptions): Transform
 }
 export declare class StreamingDdexBuilder {
   constructor(config?: StreamingConfig | undefined | null)
@@ -159,6 +201,11 @@ export declare class StreamingDdexBuilder {
   finishResourcesStartReleases(): void
   writeRelease(releaseId: string, title: string, artist: string, label: string | undefined | null, upc: string | undefined | null, releaseDate: string | undefined | null, genre: string | undefined | null, resourceReferences: Array<string>): string
   finishMessage(): StreamingStats
+  /**
+   * XML flushed since the last call, so the message can be piped out as
+   * it grows; what is taken here is no longer part of `getXml()`
+   */
+  takeOutput(): Buffer
   getXml(): string
   reset(): void
 }
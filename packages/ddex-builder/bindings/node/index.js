@@ -316,3 +316,9 @@ module.exports.DdexBuilder = DdexBuilder
 module.exports.StreamingDdexBuilder = StreamingDdexBuilder
 module.exports.batchBuild = batchBuild
 module.exports.validateStructure = validateStructure
+
+const { createBuildStream } = require('./streams')
+
+DdexBuilder.prototype.createBuildStream = function (options) {
+  return createBuildStream(StreamingDdexBuilder, options)
+}
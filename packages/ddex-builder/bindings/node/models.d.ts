@@ -0,0 +1,337 @@
+// Generated by `ddex-builder schema --format <language> --target build-request`.
+// Do not edit: regenerate after changing the Rust models.
+
+/** One hop in a message's audit trail */
+export interface AuditTrailEventRequest {
+  /** ISO 8601 date and time the party handled the message */
+  date_time: string;
+  /** Party that handled the message */
+  messaging_party: PartyRequest;
+  /** System that processed the message, emitted as the party's `TradingName` */
+  system_id?: string | null;
+}
+
+/** Build request structure */
+export interface BuildRequest {
+  /** Deals */
+  deals?: DealRequest[];
+  /** Extensions (uses IndexMap for determinism) */
+  extensions?: Record<string, string> | null;
+  /** Message header */
+  header: MessageHeaderRequest;
+  /** Profile */
+  profile?: string | null;
+  /** Releases (uses IndexMap for order preservation) */
+  releases: ReleaseRequest[];
+  /** ERN version */
+  version: string;
+}
+
+/** Copyright line (©), e.g. "2024 Example Records" */
+export interface CLineRequest {
+  /** Copyright text, usually including the year and rights holder */
+  text: string;
+  /** Year the copyright was first claimed */
+  year?: number | null;
+}
+
+/** Enhanced comment structure with position and location metadata */
+export interface Comment {
+  /** Column number in original XML (for debugging/tooling) */
+  column_number?: number | null;
+  /** The comment content (without <!-- --> markers) */
+  content: string;
+  /** Line number in original XML (for debugging/tooling) */
+  line_number?: number | null;
+  /** Position relative to the parent element */
+  position: CommentPosition;
+  /** Whether this comment should be preserved during canonicalization */
+  preserve_formatting: boolean;
+  /** Processing hints for specific output formats */
+  processing_hints: Record<string, string>;
+  /** XPath-like location reference for precise positioning */
+  xpath?: string | null;
+}
+
+/** Position of a comment relative to its parent element */
+export type CommentPosition = "Before" | "FirstChild" | "LastChild" | "After" | "Inline";
+
+/** How a contributor relates to a release or resource */
+export type ContributorKind = "DisplayArtist" | "ResourceContributor" | "IndirectResourceContributor";
+
+/** A party credited on a release or resource */
+export interface ContributorRequest {
+  /** Element the contributor is generated as */
+  kind?: ContributorKind;
+  /** Full name of the party */
+  name: string;
+  /** Party identifiers such as ISNI or IPN */
+  party_ids?: PartyIdRequest[];
+  /** Role, e.g. "MainArtist", "FeaturedArtist", "Producer" or "Composer" */
+  role: string;
+  /** Credit order, emitted as `SequenceNumber` */
+  sequence?: number | null;
+}
+
+/** Deal request */
+export interface DealRequest {
+  /** Comments re-emitted around and inside the ReleaseDeal when comments are preserved */
+  comments?: Comment[];
+  deal_reference?: string | null;
+  deal_terms: DealTerms;
+  /**
+   * Releases covered by the deal, by UPC or release_id; resolved to
+   * DealReleaseReferences by the linker
+   */
+  release_ids?: string[];
+  release_references: string[];
+}
+
+/** Deal terms */
+export interface DealTerms {
+  commercial_model_type: string;
+  /** End of the validity period */
+  end_date?: string | null;
+  /** Date pre-order previews become available */
+  pre_order_preview_date?: string | null;
+  /** Date pre-orders become available */
+  pre_order_release_date?: string | null;
+  /** Wholesale and retail prices */
+  price_information?: PriceInformationRequest[];
+  start_date?: string | null;
+  /**
+   * Withdraw the release under this deal now; emitted as the `TakeDown`
+   * flag in ERN 3.8.2, while ERN 4 relies on `takedown_date` alone
+   */
+  take_down?: boolean;
+  /**
+   * Date the release must be withdrawn; DDEX expresses this as the end of
+   * the validity period, so the earlier of this and `end_date` is emitted
+   */
+  takedown_date?: string | null;
+  territory_code: string[];
+  /** Permitted uses (AVS `UseType`, e.g. "OnDemandStream") */
+  use_types?: string[];
+}
+
+/** Hash of a delivered file */
+export interface FileHashRequest {
+  /** Hash algorithm, e.g. "MD5" or "SHA256" */
+  algorithm: string;
+  /** Hex-encoded digest */
+  value: string;
+}
+
+/** Localized string request */
+export interface LocalizedStringRequest {
+  language_code?: string | null;
+  text: string;
+}
+
+/** Message header request */
+export interface MessageHeaderRequest {
+  /** Append an audit trail event for this sender and build */
+  append_audit_trail_event?: boolean;
+  /** Audit trail received from upstream parties, oldest first */
+  message_audit_trail?: AuditTrailEventRequest[];
+  message_control_type?: string | null;
+  message_created_date_time?: string | null;
+  /** Name of the file the message is delivered in */
+  message_file_name?: string | null;
+  message_id?: string | null;
+  message_recipient: PartyRequest;
+  message_sender: PartyRequest;
+  /** Thread grouping related messages; defaults to the message ID */
+  message_thread_id?: string | null;
+}
+
+/** Phonographic copyright line (℗), e.g. "2024 Example Records" */
+export interface PLineRequest {
+  /** Copyright text, usually including the year and rights holder */
+  text: string;
+  /** Year the copyright was first claimed */
+  year?: number | null;
+}
+
+/** A namespace-qualified party identifier */
+export interface PartyIdRequest {
+  /** "ISNI", "IPN", "IPI", "DPID", or a proprietary namespace */
+  namespace: string;
+  /** Identifier value */
+  value: string;
+}
+
+/** Party request */
+export interface PartyRequest {
+  party_id?: string | null;
+  party_name: LocalizedStringRequest[];
+  party_reference?: string | null;
+}
+
+/** Price information for a deal */
+export interface PriceInformationRequest {
+  /** Price tier code agreed with the DSP */
+  price_code?: string | null;
+  /** Recommended consumer price */
+  suggested_retail_price?: PriceRequest | null;
+  /** Price charged to the DSP per unit */
+  wholesale_price?: PriceRequest | null;
+}
+
+/** Monetary amount with its ISO 4217 currency */
+export interface PriceRequest {
+  /** Decimal amount, kept as text so it is emitted exactly as given */
+  amount: string;
+  /** ISO 4217 currency code, e.g. "USD" */
+  currency_code: string;
+}
+
+/** Release request */
+export interface ReleaseRequest {
+  artist: string;
+  /** Copyright of the release artwork and packaging */
+  c_line?: CLineRequest | null;
+  /** Comments re-emitted around and inside the release when comments are preserved */
+  comments?: Comment[];
+  /** Display artists of the release, beyond the `artist` string */
+  contributors?: ContributorRequest[];
+  label?: string | null;
+  /**
+   * Date the release or its recordings first came out in any format
+   * (YYYY-MM-DD), e.g. the original pressing of a reissue
+   */
+  original_release_date?: string | null;
+  /** Phonographic copyright of the release */
+  p_line?: PLineRequest | null;
+  release_date?: string | null;
+  release_id: string;
+  release_reference?: string | null;
+  resource_references?: string[] | null;
+  /** Video, image, text and sheet music resources */
+  resources?: ResourceRequest[];
+  /** Per-territory overrides of release details */
+  territory_details?: TerritoryDetailsRequest[];
+  title: LocalizedStringRequest[];
+  /**
+   * ISRCs of tracks defined elsewhere in the request to include in this
+   * release; resolved to ReleaseResourceReferences by the linker
+   */
+  track_isrcs?: string[];
+  /**
+   * Track-level releases offering single tracks of this release; filled
+   * automatically for profiles that require them
+   */
+  track_releases?: TrackReleaseRequest[];
+  tracks?: TrackRequest[];
+  upc?: string | null;
+}
+
+/** Kind of a non-audio resource */
+export type ResourceKind = "Video" | "Image" | "Text" | "SheetMusic";
+
+/** Non-audio resource request */
+export interface ResourceRequest {
+  /** Copyright of the resource */
+  c_line?: CLineRequest | null;
+  /** ISO 8601 duration, for videos */
+  duration?: string | null;
+  /** ISRC, for videos */
+  isrc?: string | null;
+  /** Kind of resource, which selects the generated element */
+  kind: ResourceKind;
+  /** Phonographic copyright, for videos */
+  p_line?: PLineRequest | null;
+  /** Proprietary identifier of the resource */
+  resource_id: string;
+  /** Resource reference; generated when omitted */
+  resource_reference?: string | null;
+  /** AVS type of the resource, e.g. "FrontCoverImage" or "ShortFormMusicalWorkVideo" */
+  resource_type: string;
+  /** Technical details of the delivered file */
+  technical_details?: TechnicalDetailsRequest | null;
+  /** Reference title */
+  title?: string | null;
+}
+
+/** Technical details of a delivered resource file */
+export interface TechnicalDetailsRequest {
+  /** Bit depth, for audio */
+  bits_per_sample?: number | null;
+  /** Codec or file format, e.g. "H.264", "JPEG", "PDF" */
+  codec?: string | null;
+  /**
+   * Playing time of the delivered file as an ISO 8601 duration, checked
+   * against the declared duration
+   */
+  duration?: string | null;
+  /** Checksum of the delivered file */
+  file_hash?: FileHashRequest | null;
+  /** File name or URI of the delivered file */
+  file_name?: string | null;
+  /** Size of the delivered file in bytes */
+  file_size?: number | null;
+  /** Height in pixels, for videos and images */
+  height?: number | null;
+  /** Number of audio channels */
+  number_of_channels?: number | null;
+  /** Resolution in dots per inch, for images */
+  resolution?: number | null;
+  /** Sampling rate in Hz, for audio */
+  sample_rate?: number | null;
+  /** Width in pixels, for videos and images */
+  width?: number | null;
+}
+
+/**
+ * Release details that apply only in some territories
+ *
+ * Unset fields fall back to the release-level values.
+ */
+export interface TerritoryDetailsRequest {
+  /** Display artist used in these territories */
+  display_artist?: string | null;
+  /** Genre used in these territories */
+  genre?: string | null;
+  /** Parental warning type (e.g. `Explicit`) in these territories */
+  parental_warning?: string | null;
+  /** Release date (YYYY-MM-DD) in these territories */
+  release_date?: string | null;
+  /** ISO 3166-1 territory codes (or `Worldwide`) the overrides apply to */
+  territory_codes: string[];
+  /** Title used in these territories */
+  title?: string | null;
+}
+
+/**
+ * Release of a single track (ERN 4 `TrackRelease`)
+ *
+ * Deals can target it through `DealRequest::release_ids` by ISRC.
+ */
+export interface TrackReleaseRequest {
+  /** ISRC of the track offered by this release */
+  isrc: string;
+  /** Reference, generated when unset */
+  release_reference?: string | null;
+}
+
+/** Track request */
+export interface TrackRequest {
+  artist: string;
+  /** Copyright of the recording */
+  c_line?: CLineRequest | null;
+  /** Comments re-emitted around and inside the SoundRecording when comments are preserved */
+  comments?: Comment[];
+  /** Display artists, contributors and indirect contributors of the recording */
+  contributors?: ContributorRequest[];
+  duration: string;
+  isrc: string;
+  /** Phonographic copyright of the recording */
+  p_line?: PLineRequest | null;
+  /** Parental warning type of the recording, e.g. `Explicit` or `NotExplicit` */
+  parental_warning?: string | null;
+  resource_reference?: string | null;
+  /** Technical details of the delivered audio file */
+  technical_details?: TechnicalDetailsRequest | null;
+  title: string;
+  track_id: string;
+}
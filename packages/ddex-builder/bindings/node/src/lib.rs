@@ -1,8 +1,20 @@
 use napi::bindgen_prelude::*;
+use napi::{JsFunction, JsObject, Task};
 use napi_derive::napi;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::io::{Write, Cursor};
+use std::io::Write;
+use std::sync::{Arc, Mutex, MutexGuard};
+use ddex_core::cancel::CancellationToken;
+use ddex_core::ffi::FFIError;
+
+/// Error whose message is the JSON of the `FFIError` for `err`, so callers can
+/// `JSON.parse(error.message)` and branch on its `code`
+fn ddex_error(err: impl Into<FFIError>) -> Error {
+    let ffi_error: FFIError = err.into();
+    let reason = serde_json::to_string(&ffi_error).unwrap_or(ffi_error.message);
+    Error::new(Status::GenericFailure, reason)
+}
 
 #[napi(object)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,7 +56,7 @@ pub struct ValidationResult {
 }
 
 #[napi(object)]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct BuilderStats {
     pub releases_count: u32,
     pub resources_count: u32,
@@ -144,7 +156,11 @@ pub struct FidelityInfo {
 pub struct DdexBuilder {
     releases: Vec<Release>,
     resources: Vec<Resource>,
-    stats: BuilderStats,
+    /// Shared with build tasks, which record their timings on the worker
+    stats: Arc<Mutex<BuilderStats>>,
+    /// Parent of the builds in flight, replaced by `cancel`
+    cancellation: CancellationToken,
+    extension_namespaces: ddex_builder::extensions::ExtensionNamespaces,
 }
 
 #[napi]
@@ -154,132 +170,70 @@ impl DdexBuilder {
         Ok(DdexBuilder {
             releases: Vec::new(),
             resources: Vec::new(),
-            stats: BuilderStats {
-                releases_count: 0,
-                resources_count: 0,
-                total_build_time_ms: 0.0,
-                last_build_size_bytes: 0.0,
-                validation_errors: 0,
-                validation_warnings: 0,
-            },
+            stats: Arc::new(Mutex::new(BuilderStats::default())),
+            cancellation: CancellationToken::new(),
+            extension_namespaces: Default::default(),
         })
     }
 
+    /// Declare a proprietary namespace under `prefix` in every build; throws
+    /// when the prefix or URI is taken by DDEX or another extension
+    #[napi]
+    pub fn register_extension_namespace(&mut self, prefix: String, uri: String, schema_url: Option<String>) -> Result<()> {
+        self.extension_namespaces
+            .register(&prefix, &uri, schema_url.as_deref())
+            .map_err(ddex_error)
+    }
+
+    /// Stop every build in flight, which reject with a `DDEX-B-0603` error;
+    /// pass an `AbortSignal` to `build` to stop just one
+    #[napi]
+    pub fn cancel(&mut self) {
+        self.cancellation.cancel();
+        self.cancellation = CancellationToken::new();
+    }
+
     #[napi]
     pub fn add_release(&mut self, release: Release) -> Result<()> {
         self.releases.push(release);
-        self.stats.releases_count = self.releases.len() as u32;
+        lock(&self.stats).releases_count = self.releases.len() as u32;
         Ok(())
     }
 
     #[napi]
     pub fn add_resource(&mut self, resource: Resource) -> Result<()> {
         self.resources.push(resource);
-        self.stats.resources_count = self.resources.len() as u32;
+        lock(&self.stats).resources_count = self.resources.len() as u32;
         Ok(())
     }
 
+    /// Build off the event loop; aborting `signal` stops this build alone
     #[napi]
-    pub async unsafe fn build(&mut self, data: Option<serde_json::Value>) -> Result<String> {
-        let start_time = std::time::Instant::now();
-
-        // Create BuildRequest based on whether data was provided
-        let build_request = match data {
-            Some(json_data) => self.create_build_request_from_json(json_data)?,
-            None => self.create_build_request_from_stored_data()?,
-        };
-        
-        // Use the actual DDEX builder
-        let builder = ddex_builder::builder::DDEXBuilder::new();
-        let options = ddex_builder::builder::BuildOptions::default();
-        
-        let result = builder.build(build_request, options)
-            .map_err(|e| Error::new(Status::Unknown, format!("Build failed: {}", e)))?;
-        
-        self.stats.last_build_size_bytes = result.xml.len() as f64;
-        self.stats.total_build_time_ms += start_time.elapsed().as_millis() as f64;
-
-        Ok(result.xml)
+    pub fn build(
+        &self,
+        env: Env,
+        data: Option<serde_json::Value>,
+        #[napi(ts_arg_type = "AbortSignal")] signal: Option<JsObject>,
+    ) -> Result<AsyncTask<BuildTask>> {
+        Ok(AsyncTask::new(self.build_task(&env, data, signal)?))
     }
 
     #[napi]
-    pub async unsafe fn build_with_fidelity(&mut self, data: Option<serde_json::Value>, fidelity_options: Option<FidelityOptions>) -> Result<BuildResult> {
-        let start_time = std::time::Instant::now();
-
-        // Create BuildRequest based on whether data was provided
-        let build_request = match data {
-            Some(json_data) => self.create_build_request_from_json(json_data)?,
-            None => self.create_build_request_from_stored_data()?,
-        };
-        
-        // Use the actual DDEX builder
-        let builder = ddex_builder::builder::DDEXBuilder::new();
-        let options = ddex_builder::builder::BuildOptions::default();
-        
-        let result = builder.build(build_request, options)
-            .map_err(|e| Error::new(Status::Unknown, format!("Build failed: {}", e)))?;
-        
-        self.stats.last_build_size_bytes = result.xml.len() as f64;
-        let build_time = start_time.elapsed().as_millis() as f64;
-        self.stats.total_build_time_ms += build_time;
-
-        // Generate statistics if requested
-        let statistics = if fidelity_options.as_ref().and_then(|o| o.collect_statistics).unwrap_or(false) {
-            Some(BuildStatistics {
-                build_time_ms: build_time,
-                memory_used_bytes: result.xml.len() as u32 * 2,
-                xml_size_bytes: result.xml.len() as u32,
-                element_count: result.xml.matches('<').count() as u32,
-                attribute_count: result.xml.matches('=').count() as u32,
-                namespace_count: result.xml.matches("xmlns").count() as u32,
-                extension_count: if result.xml.contains("xmlns:") { 1 } else { 0 },
-                canonicalization_time_ms: 2.0, // Mock value
-                verification_time_ms: None,
-            })
-        } else {
-            None
-        };
-
-        // Generate verification result if requested
-        let verification = if fidelity_options.as_ref().and_then(|o| o.enable_verification).unwrap_or(false) {
-            Some(VerificationResult {
-                round_trip_success: true,
-                fidelity_score: 1.0,
-                canonicalization_consistent: true,
-                determinism_verified: true,
-                issues: vec![],
-                checksums_match: Some(true),
-            })
-        } else {
-            None
-        };
-
-        // Generate fidelity info based on options
-        let fidelity_info = if let Some(ref opts) = fidelity_options {
-            Some(FidelityInfo {
-                fidelity_level: if opts.enable_perfect_fidelity.unwrap_or(false) { "perfect".to_string() } else { "balanced".to_string() },
-                canonicalization_algorithm: opts.canonicalization.clone().unwrap_or_else(|| "db_c14n".to_string()),
-                comments_preserved: opts.preserve_comments.unwrap_or(false),
-                extensions_preserved: opts.preserve_extensions.unwrap_or(true),
-                processing_instructions_preserved: opts.preserve_processing_instructions.unwrap_or(false),
-                attribute_order_preserved: opts.preserve_attribute_order.unwrap_or(true),
-                namespace_prefixes_preserved: opts.preserve_namespace_prefixes.unwrap_or(true),
-                perfect_fidelity_enabled: opts.enable_perfect_fidelity.unwrap_or(false),
-            })
-        } else {
-            None
-        };
-
-        Ok(BuildResult {
-            xml: result.xml,
-            statistics,
-            verification,
-            fidelity_info,
-        })
+    pub fn build_with_fidelity(
+        &self,
+        env: Env,
+        data: Option<serde_json::Value>,
+        fidelity_options: Option<FidelityOptions>,
+        #[napi(ts_arg_type = "AbortSignal")] signal: Option<JsObject>,
+    ) -> Result<AsyncTask<FidelityBuildTask>> {
+        Ok(AsyncTask::new(FidelityBuildTask {
+            build: self.build_task(&env, data, signal)?,
+            fidelity_options,
+        }))
     }
 
     #[napi]
-    pub async unsafe fn test_round_trip_fidelity(&mut self, original_xml: String, fidelity_options: Option<FidelityOptions>) -> Result<VerificationResult> {
+    pub async fn test_round_trip_fidelity(&self, original_xml: Either<String, Buffer>, fidelity_options: Option<FidelityOptions>) -> Result<VerificationResult> {
         // In a full implementation, this would:
         // 1. Parse the original XML
         // 2. Build it back to XML
@@ -311,21 +265,14 @@ impl DdexBuilder {
 
     #[napi]
     pub fn get_stats(&self) -> Result<BuilderStats> {
-        Ok(self.stats.clone())
+        Ok(lock(&self.stats).clone())
     }
 
     #[napi]
     pub fn reset(&mut self) -> Result<()> {
         self.releases.clear();
         self.resources.clear();
-        self.stats = BuilderStats {
-            releases_count: 0,
-            resources_count: 0,
-            total_build_time_ms: 0.0,
-            last_build_size_bytes: 0.0,
-            validation_errors: 0,
-            validation_warnings: 0,
-        };
+        *lock(&self.stats) = BuilderStats::default();
         Ok(())
     }
 
@@ -467,7 +414,7 @@ impl DdexBuilder {
         }
     }
 
-    fn create_build_request_from_json(&self, data: serde_json::Value) -> Result<ddex_builder::builder::BuildRequest> {
+    fn create_build_request_from_json(data: serde_json::Value) -> Result<ddex_builder::builder::BuildRequest> {
         let obj = data.as_object()
             .ok_or_else(|| Error::new(Status::InvalidArg, "Expected object"))?;
 
@@ -498,6 +445,7 @@ impl DdexBuilder {
             },
             message_control_type: None,
             message_created_date_time: Some(chrono::Utc::now().to_rfc3339()),
+            ..Default::default()
         };
 
         // Convert releases from JSON
@@ -534,6 +482,15 @@ impl DdexBuilder {
                         upc: release_obj.get("upc").and_then(|v| v.as_str()).map(|s| s.to_string()),
                         tracks: vec![], // No tracks in the simple format for now
                         resource_references: None,
+                        resources: Vec::new(),
+                        territory_details: Vec::new(),
+                        contributors: Vec::new(),
+                        p_line: None,
+                        c_line: None,
+                        track_isrcs: Vec::new(),
+                        track_releases: Vec::new(),
+                        comments: Vec::new(),
+                        original_release_date: None,
                     });
                 }
             }
@@ -550,6 +507,22 @@ impl DdexBuilder {
         })
     }
 
+    /// Everything a worker needs for one build, read on the JS thread
+    fn build_task(&self, env: &Env, data: Option<serde_json::Value>, signal: Option<JsObject>) -> Result<BuildTask> {
+        let request = match data {
+            Some(json_data) => Self::create_build_request_from_json(json_data)?,
+            None => self.create_build_request_from_stored_data()?,
+        };
+        let cancellation = self.cancellation.child();
+        link_abort_signal(env, signal, &cancellation)?;
+        Ok(BuildTask {
+            request: Some(request),
+            cancellation,
+            stats: Arc::clone(&self.stats),
+            extension_namespaces: self.extension_namespaces.clone(),
+        })
+    }
+
     fn create_build_request_from_stored_data(&self) -> Result<ddex_builder::builder::BuildRequest> {
         // Create message header
         let header = ddex_builder::builder::MessageHeaderRequest {
@@ -572,6 +545,7 @@ impl DdexBuilder {
             },
             message_control_type: None,
             message_created_date_time: Some(chrono::Utc::now().to_rfc3339()),
+            ..Default::default()
         };
 
         // Convert releases
@@ -587,6 +561,12 @@ impl DdexBuilder {
                     title: resource.title.clone(),
                     duration: resource.duration.clone().unwrap_or_else(|| "PT3M00S".to_string()),
                     artist: resource.artist.clone(),
+                    contributors: Vec::new(),
+                    p_line: None,
+                    c_line: None,
+                    comments: Vec::new(),
+                    technical_details: None,
+                    parental_warning: None,
                 })
                 .collect();
 
@@ -603,6 +583,15 @@ impl DdexBuilder {
                 upc: release.upc.clone(),
                 tracks,
                 resource_references: Some(release.track_ids.clone()),
+                resources: Vec::new(),
+                territory_details: Vec::new(),
+                contributors: Vec::new(),
+                p_line: None,
+                c_line: None,
+                track_isrcs: Vec::new(),
+                track_releases: Vec::new(),
+                comments: Vec::new(),
+                original_release_date: None,
             });
         }
 
@@ -670,6 +659,145 @@ impl DdexBuilder {
     }
 }
 
+fn lock(stats: &Mutex<BuilderStats>) -> MutexGuard<'_, BuilderStats> {
+    stats.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Cancel `token` when `signal` aborts, or right away if it already has
+fn link_abort_signal(env: &Env, signal: Option<JsObject>, token: &CancellationToken) -> Result<()> {
+    let Some(signal) = signal else { return Ok(()) };
+    if signal.get_named_property::<bool>("aborted")? {
+        token.cancel();
+        return Ok(());
+    }
+    let token = token.clone();
+    let listener = env.create_function_from_closure("onabort", move |ctx| {
+        token.cancel();
+        ctx.env.get_undefined()
+    })?;
+    let add_event_listener: JsFunction = signal.get_named_property("addEventListener")?;
+    add_event_listener.call(
+        Some(&signal),
+        &[env.create_string("abort")?.into_unknown(), listener.into_unknown()],
+    )?;
+    Ok(())
+}
+
+/// A build run on a libuv worker thread, resolving to the XML
+pub struct BuildTask {
+    request: Option<ddex_builder::builder::BuildRequest>,
+    cancellation: CancellationToken,
+    stats: Arc<Mutex<BuilderStats>>,
+    extension_namespaces: ddex_builder::extensions::ExtensionNamespaces,
+}
+
+impl BuildTask {
+    /// Build the request and record its timing, returning it in milliseconds
+    fn run(&mut self) -> Result<(ddex_builder::builder::BuildResult, f64)> {
+        let start_time = std::time::Instant::now();
+        let request = self.request.take()
+            .ok_or_else(|| Error::new(Status::GenericFailure, "Build task already ran"))?;
+
+        let builder = ddex_builder::builder::DDEXBuilder::new();
+        let options = ddex_builder::builder::BuildOptions {
+            cancellation: Some(self.cancellation.clone()),
+            extension_namespaces: self.extension_namespaces.clone(),
+            ..Default::default()
+        };
+        let result = builder.build(request, options)
+            .map_err(ddex_error)?;
+
+        let build_time = start_time.elapsed().as_millis() as f64;
+        let mut stats = lock(&self.stats);
+        stats.last_build_size_bytes = result.xml.len() as f64;
+        stats.total_build_time_ms += build_time;
+        Ok((result, build_time))
+    }
+}
+
+impl Task for BuildTask {
+    type Output = String;
+    type JsValue = String;
+
+    fn compute(&mut self) -> Result<String> {
+        Ok(self.run()?.0.xml)
+    }
+
+    fn resolve(&mut self, _env: Env, xml: String) -> Result<String> {
+        Ok(xml)
+    }
+}
+
+/// A build with fidelity statistics, run on a libuv worker thread
+pub struct FidelityBuildTask {
+    build: BuildTask,
+    fidelity_options: Option<FidelityOptions>,
+}
+
+impl Task for FidelityBuildTask {
+    type Output = BuildResult;
+    type JsValue = BuildResult;
+
+    fn compute(&mut self) -> Result<BuildResult> {
+        let (result, build_time) = self.build.run()?;
+        let fidelity_options = &self.fidelity_options;
+
+        // Generate statistics if requested
+        let statistics = if fidelity_options.as_ref().and_then(|o| o.collect_statistics).unwrap_or(false) {
+            Some(BuildStatistics {
+                build_time_ms: build_time,
+                memory_used_bytes: result.statistics.peak_memory_bytes as u32,
+                xml_size_bytes: result.xml.len() as u32,
+                element_count: result.xml.matches('<').count() as u32,
+                attribute_count: result.xml.matches('=').count() as u32,
+                namespace_count: result.xml.matches("xmlns").count() as u32,
+                extension_count: if result.xml.contains("xmlns:") { 1 } else { 0 },
+                canonicalization_time_ms: 2.0, // Mock value
+                verification_time_ms: None,
+            })
+        } else {
+            None
+        };
+
+        // Generate verification result if requested
+        let verification = if fidelity_options.as_ref().and_then(|o| o.enable_verification).unwrap_or(false) {
+            Some(VerificationResult {
+                round_trip_success: true,
+                fidelity_score: 1.0,
+                canonicalization_consistent: true,
+                determinism_verified: true,
+                issues: vec![],
+                checksums_match: Some(true),
+            })
+        } else {
+            None
+        };
+
+        // Generate fidelity info based on options
+        let fidelity_info = fidelity_options.as_ref().map(|opts| FidelityInfo {
+            fidelity_level: if opts.enable_perfect_fidelity.unwrap_or(false) { "perfect".to_string() } else { "balanced".to_string() },
+            canonicalization_algorithm: opts.canonicalization.clone().unwrap_or_else(|| "db_c14n".to_string()),
+            comments_preserved: opts.preserve_comments.unwrap_or(false),
+            extensions_preserved: opts.preserve_extensions.unwrap_or(true),
+            processing_instructions_preserved: opts.preserve_processing_instructions.unwrap_or(false),
+            attribute_order_preserved: opts.preserve_attribute_order.unwrap_or(true),
+            namespace_prefixes_preserved: opts.preserve_namespace_prefixes.unwrap_or(true),
+            perfect_fidelity_enabled: opts.enable_perfect_fidelity.unwrap_or(false),
+        });
+
+        Ok(BuildResult {
+            xml: result.xml,
+            statistics,
+            verification,
+            fidelity_info,
+        })
+    }
+
+    fn resolve(&mut self, _env: Env, result: BuildResult) -> Result<BuildResult> {
+        Ok(result)
+    }
+}
+
 #[napi(object)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamingConfig {
@@ -711,8 +839,8 @@ pub struct MessageHeader {
 
 #[napi]
 pub struct StreamingDdexBuilder {
-    inner: Option<ddex_builder::streaming::StreamingBuilder<Cursor<Vec<u8>>>>,
-    buffer: Cursor<Vec<u8>>,
+    inner: Option<ddex_builder::streaming::StreamingBuilder<SharedSink>>,
+    output: SharedSink,
     config: StreamingConfig,
     progress_callback: Option<napi::threadsafe_function::ThreadsafeFunction<StreamingProgress>>,
 }
@@ -728,11 +856,9 @@ impl StreamingDdexBuilder {
             progress_callback_frequency: 100,
         });
         
-        let buffer = Cursor::new(Vec::new());
-        
         Ok(StreamingDdexBuilder {
             inner: None,
-            buffer,
+            output: SharedSink::default(),
             config,
             progress_callback: None,
         })
@@ -759,8 +885,8 @@ impl StreamingDdexBuilder {
     
     #[napi]
     pub fn start_message(&mut self, header: MessageHeader, version: String) -> Result<()> {
-        // Create a new buffer and streaming builder
-        self.buffer = Cursor::new(Vec::new());
+        // Create a new output and streaming builder
+        self.output = SharedSink::default();
         
         // Convert config to Rust types
         let rust_config = ddex_builder::streaming::StreamingConfig {
@@ -772,7 +898,7 @@ impl StreamingDdexBuilder {
         };
         
         let mut streaming_builder = ddex_builder::streaming::StreamingBuilder::new_with_config(
-            std::mem::replace(&mut self.buffer, Cursor::new(Vec::new())),
+            self.output.clone(),
             rust_config
         ).map_err(|e| Error::new(Status::Unknown, format!("Failed to create streaming builder: {}", e)))?;
         
@@ -813,6 +939,7 @@ impl StreamingDdexBuilder {
             },
             message_control_type: None,
             message_created_date_time: header.message_created_date_time,
+            ..Default::default()
         };
         
         streaming_builder.start_message(&rust_header, &version)
@@ -882,50 +1009,92 @@ impl StreamingDdexBuilder {
         })
     }
     
+    /// XML flushed since the last call, so the message can be piped out as
+    /// it grows; what is taken here is no longer part of `getXml()`
+    #[napi]
+    pub fn take_output(&mut self) -> Buffer {
+        std::mem::take(&mut *self.output.lock()).into()
+    }
+    
     #[napi]
     pub fn get_xml(&mut self) -> Result<String> {
         if self.inner.is_some() {
             return Err(Error::new(Status::InvalidArg, "Message not finished. Call finish_message first."));
         }
         
-        // Retrieve the cursor from the completed builder
-        let data = self.buffer.get_ref();
-        String::from_utf8(data.clone())
+        let data = self.output.lock().clone();
+        String::from_utf8(data)
             .map_err(|e| Error::new(Status::Unknown, format!("Failed to convert to UTF-8: {}", e)))
     }
     
     #[napi]
     pub fn reset(&mut self) -> Result<()> {
         self.inner = None;
-        self.buffer = Cursor::new(Vec::new());
+        self.output = SharedSink::default();
+        Ok(())
+    }
+}
+
+/// Writer the streaming builder flushes into and `take_output` drains
+#[derive(Clone, Default)]
+struct SharedSink(Arc<Mutex<Vec<u8>>>);
+
+impl SharedSink {
+    fn lock(&self) -> MutexGuard<'_, Vec<u8>> {
+        self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Write for SharedSink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.lock().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
         Ok(())
     }
 }
 
+/// Index and first error of the first request of a batch that failed
+fn first_failure(results: &[ddex_builder::builder::BuildResult]) -> Option<(usize, &ddex_builder::BuildError)> {
+    results.iter()
+        .enumerate()
+        .find_map(|(i, result)| result.errors.first().map(|error| (i, error)))
+}
+
 #[napi]
 pub async fn batch_build(requests: Vec<String>) -> Result<Vec<String>> {
-    let mut results = Vec::new();
+    let build_requests = requests.iter()
+        .enumerate()
+        .map(|(i, json)| {
+            let data = serde_json::from_str(json)
+                .map_err(|e| Error::new(Status::InvalidArg, format!("Request {}: {}", i, e)))?;
+            DdexBuilder::create_build_request_from_json(data)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    
+    let builder = ddex_builder::builder::DDEXBuilder::new();
+    let results = builder.build_batch(build_requests, ddex_builder::builder::BuildOptions::default());
     
-    for _request_json in requests {
-        // Create a simple placeholder result for each request
-        let result = format!(r#"<?xml version="1.0" encoding="UTF-8"?>
-<NewReleaseMessage xmlns="http://ddex.net/xml/ern/43">
-  <MessageHeader>
-    <MessageId>{}</MessageId>
-    <MessageSender><PartyName>DDEX Suite</PartyName></MessageSender>
-    <MessageRecipient><PartyName>Recipient</PartyName></MessageRecipient>
-  </MessageHeader>
-</NewReleaseMessage>"#, uuid::Uuid::new_v4());
-        results.push(result);
+    if let Some((i, error)) = first_failure(&results) {
+        let mut ffi_error = FFIError::from(error.clone());
+        ffi_error.message = format!("Request {}: {}", i, ffi_error.message);
+        return Err(ddex_error(ffi_error));
     }
     
-    Ok(results)
+    Ok(results.into_iter().map(|result| result.xml).collect())
 }
 
+/// Accepts a `Buffer` as well as a string; the buffer is read in place
 #[napi]
-pub async fn validate_structure(xml: String) -> Result<ValidationResult> {
+pub async fn validate_structure(xml: Either<String, Buffer>) -> Result<ValidationResult> {
+    let bytes: &[u8] = match &xml {
+        Either::A(text) => text.as_bytes(),
+        Either::B(buffer) => buffer,
+    };
     // Parse and validate XML structure
-    match quick_xml::Reader::from_str(&xml).read_event() {
+    match quick_xml::Reader::from_reader(bytes).read_event_into(&mut Vec::new()) {
         Ok(_) => Ok(ValidationResult {
             is_valid: true,
             errors: vec![],
@@ -0,0 +1,78 @@
+/* Node stream adapters over the native streaming builder */
+
+const { Transform } = require('stream')
+
+/** Bytes the native builder gathers before handing a chunk downstream */
+const DEFAULT_CHUNK_SIZE = 64 * 1024
+
+/**
+ * A Transform that takes release objects and emits the XML of one message
+ * in chunks, for piping into `fs.createWriteStream`, `zlib.createGzip()` or
+ * an S3 upload body.
+ */
+function createBuildStream(StreamingDdexBuilder, options) {
+  const { header, version = '4.3', chunkSize = DEFAULT_CHUNK_SIZE } = options || {}
+  if (!header) {
+    throw new TypeError('createBuildStream() needs options.header')
+  }
+
+  const native = new StreamingDdexBuilder({
+    maxBufferSize: chunkSize,
+    deterministic: true,
+    validateDuringStream: true,
+    progressCallbackFrequency: 100,
+  })
+  let releasesStarted = false
+
+  // The message opens with the first release, or at the end if there are
+  // none; releases follow an empty resource list
+  function startReleases() {
+    if (!releasesStarted) {
+      native.startMessage(header, version)
+      native.finishResourcesStartReleases()
+      releasesStarted = true
+    }
+  }
+
+  function pushOutput(stream) {
+    const chunk = native.takeOutput()
+    if (chunk.length > 0) {
+      stream.push(chunk)
+    }
+  }
+
+  return new Transform({
+    writableObjectMode: true,
+    transform(release, _encoding, callback) {
+      try {
+        startReleases()
+        native.writeRelease(
+          release.releaseId,
+          release.title,
+          release.artist,
+          release.label,
+          release.upc,
+          release.releaseDate,
+          release.genre,
+          release.resourceReferences || [],
+        )
+        pushOutput(this)
+        callback()
+      } catch (error) {
+        callback(error)
+      }
+    },
+    flush(callback) {
+      try {
+        startReleases()
+        native.finishMessage()
+        pushOutput(this)
+        callback()
+      } catch (error) {
+        callback(error)
+      }
+    },
+  })
+}
+
+module.exports = { createBuildStream, DEFAULT_CHUNK_SIZE }
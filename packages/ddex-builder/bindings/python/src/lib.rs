@@ -5,6 +5,58 @@ use std::io::Cursor;
 use ::ddex_builder::builder::{DDEXBuilder, BuildOptions, BuildRequest, MessageHeaderRequest, PartyRequest, LocalizedStringRequest, ReleaseRequest, TrackRequest};
 use ::ddex_parser::DDEXParser;
 use ddex_core::models::flat::ParsedERNMessage;
+use ddex_core::ffi::FFIError;
+
+pyo3::create_exception!(ddex_builder, DdexError, pyo3::exceptions::PyRuntimeError, "Build error with a stable `code`, plus `path`, `severity`, `suggestion` and `category`");
+
+/// `DdexError` carrying the fields of the `FFIError` for `err` as attributes
+fn ddex_error(err: impl Into<FFIError>) -> PyErr {
+    let ffi_error: FFIError = err.into();
+    let error = DdexError::new_err(ffi_error.message.clone());
+    Python::with_gil(|py| {
+        let value = error.value(py);
+        let severity = format!("{:?}", ffi_error.severity);
+        let category = format!("{:?}", ffi_error.category);
+        let attributes = value.setattr("code", &ffi_error.code)
+            .and_then(|_| value.setattr("path", ffi_error.location.as_ref().map(|l| l.path.clone())))
+            .and_then(|_| value.setattr("severity", severity))
+            .and_then(|_| value.setattr("suggestion", ffi_error.suggestion.clone()))
+            .and_then(|_| value.setattr("category", category));
+        match attributes {
+            Ok(()) => error,
+            Err(e) => e,
+        }
+    })
+}
+
+/// Run `work` on a worker thread with the GIL released, cancelling it through
+/// `token` and raising `KeyboardInterrupt` if Ctrl-C arrives meanwhile
+fn run_interruptible<T: Send>(
+    py: Python,
+    token: &ddex_core::cancel::CancellationToken,
+    work: impl FnOnce() -> T + Send,
+) -> PyResult<T> {
+    std::thread::scope(|scope| {
+        let caller = std::thread::current();
+        let worker = scope.spawn(move || {
+            let result = work();
+            caller.unpark();
+            result
+        });
+        loop {
+            if worker.is_finished() {
+                return worker.join().map_err(|_| pyo3::exceptions::PyRuntimeError::new_err("worker thread panicked"));
+            }
+            py.allow_threads(|| std::thread::park_timeout(std::time::Duration::from_millis(50)));
+            if let Err(interrupt) = py.check_signals() {
+                // The worker stops at its next element or stage
+                token.cancel();
+                let _ = py.allow_threads(|| worker.join());
+                return Err(interrupt);
+            }
+        }
+    })
+}
 
 #[pyclass]
 #[derive(Debug, Clone)]
@@ -491,6 +543,7 @@ pub struct DdexBuilder {
     releases: Vec<Release>,
     resources: Vec<Resource>,
     stats: BuilderStats,
+    extension_namespaces: ::ddex_builder::extensions::ExtensionNamespaces,
 }
 
 #[pymethods]
@@ -501,9 +554,19 @@ impl DdexBuilder {
             releases: Vec::new(),
             resources: Vec::new(),
             stats: BuilderStats::new(0, 0, 0.0, 0.0, 0, 0),
+            extension_namespaces: Default::default(),
         }
     }
 
+    /// Declare a proprietary namespace under `prefix` in every build; raises
+    /// when the prefix or URI is taken by DDEX or another extension
+    #[pyo3(signature = (prefix, uri, schema_url=None))]
+    pub fn register_extension_namespace(&mut self, prefix: &str, uri: &str, schema_url: Option<&str>) -> PyResult<()> {
+        self.extension_namespaces
+            .register(prefix, uri, schema_url)
+            .map_err(ddex_error)
+    }
+
     pub fn add_release(&mut self, release: Release) {
         self.releases.push(release);
         self.stats.releases_count = self.releases.len() as u32;
@@ -514,18 +577,22 @@ impl DdexBuilder {
         self.stats.resources_count = self.resources.len() as u32;
     }
 
-    pub fn build(&mut self) -> PyResult<String> {
+    /// Build the message; Ctrl-C cancels the build
+    pub fn build(&mut self, py: Python) -> PyResult<String> {
         let start_time = std::time::Instant::now();
 
         // Create a BuildRequest from stored releases and resources
         let build_request = self.create_build_request_from_stored_data()?;
         
-        // Use the actual DDEX builder
-        let builder = DDEXBuilder::new();
-        let options = BuildOptions::default();
-        
-        let result = builder.build(build_request, options)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Build failed: {}", e)))?;
+        // Use the actual DDEX builder, off the GIL so Ctrl-C is noticed
+        let token = ddex_core::cancel::CancellationToken::new();
+        let options = BuildOptions {
+            cancellation: Some(token.clone()),
+            extension_namespaces: self.extension_namespaces.clone(),
+            ..BuildOptions::default()
+        };
+        let result = run_interruptible(py, &token, || DDEXBuilder::new().build(build_request, options))?
+            .map_err(ddex_error)?;
         
         self.stats.last_build_size_bytes = result.xml.len() as f64;
         self.stats.total_build_time_ms += start_time.elapsed().as_millis() as f64;
@@ -541,10 +608,13 @@ impl DdexBuilder {
         
         // Use the actual DDEX builder
         let builder = DDEXBuilder::new();
-        let options = BuildOptions::default();
+        let options = BuildOptions {
+            extension_namespaces: self.extension_namespaces.clone(),
+            ..BuildOptions::default()
+        };
         
         let result = builder.build(build_request, options)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Build failed: {}", e)))?;
+            .map_err(ddex_error)?;
         
         self.stats.last_build_size_bytes = result.xml.len() as f64;
         let build_time = start_time.elapsed().as_millis() as f64;
@@ -1202,23 +1272,29 @@ impl DdexBuilder {
 }
 
 #[pyfunction]
-pub fn batch_build(requests: Vec<Bound<'_, PyAny>>) -> PyResult<Vec<String>> {
-    let mut results = Vec::new();
+pub fn batch_build(py: Python<'_>, requests: Vec<Bound<'_, PyAny>>) -> PyResult<Vec<String>> {
+    let build_requests = requests.iter()
+        .enumerate()
+        .map(|(i, request)| {
+            pythonize::depythonize::<BuildRequest>(request)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Request {}: {}", i, e)))
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    
+    let results = py.allow_threads(|| {
+        DDEXBuilder::new().build_batch(build_requests, BuildOptions::default())
+    });
     
-    for _request in requests {
-        // Create a simple placeholder result for each request
-        let result = format!(r#"<?xml version="1.0" encoding="UTF-8"?>
-<NewReleaseMessage xmlns="http://ddex.net/xml/ern/43">
-  <MessageHeader>
-    <MessageId>{}</MessageId>
-    <MessageSender><PartyName>DDEX Suite</PartyName></MessageSender>
-    <MessageRecipient><PartyName>Recipient</PartyName></MessageRecipient>
-  </MessageHeader>
-</NewReleaseMessage>"#, uuid::Uuid::new_v4());
-        results.push(result);
+    let failure = results.iter()
+        .enumerate()
+        .find_map(|(i, result)| result.errors.first().map(|error| (i, error)));
+    if let Some((i, error)) = failure {
+        let mut ffi_error = FFIError::from(error.clone());
+        ffi_error.message = format!("Request {}: {}", i, ffi_error.message);
+        return Err(ddex_error(ffi_error));
     }
     
-    Ok(results)
+    Ok(results.into_iter().map(|result| result.xml).collect())
 }
 
 #[pyfunction]
@@ -1236,66 +1312,7 @@ pub fn validate_structure(xml: String) -> PyResult<ValidationResult> {
 
 impl DdexBuilder {
     fn create_build_request_from_parsed(&self, parsed_result: &ParsedERNMessage) -> PyResult<BuildRequest> {
-        // Convert parsed result back to build request (simplified implementation)
-        let header = MessageHeaderRequest {
-            message_id: Some(parsed_result.flat.message_id.clone()),
-            message_sender: PartyRequest {
-                party_name: vec![LocalizedStringRequest {
-                    text: format!("{:?}", parsed_result.flat.sender),
-                    language_code: None,
-                }],
-                party_id: None,
-                party_reference: None,
-            },
-            message_recipient: PartyRequest {
-                party_name: vec![LocalizedStringRequest {
-                    text: "Recipient".to_string(),
-                    language_code: None,
-                }],
-                party_id: None,
-                party_reference: None,
-            },
-            message_control_type: Some(parsed_result.flat.message_type.clone()),
-            message_created_date_time: Some(parsed_result.flat.message_date.to_rfc3339()),
-        };
-
-        let mut releases = Vec::new();
-        for release in &parsed_result.flat.releases {
-            let tracks: Vec<TrackRequest> = release.tracks.iter().map(|track| {
-                TrackRequest {
-                    track_id: track.track_id.clone(),
-                    resource_reference: Some(track.track_id.clone()),
-                    isrc: track.isrc.clone().unwrap_or_else(|| "TEMP00000000".to_string()),
-                    title: track.title.clone(),
-                    duration: format!("PT{}S", track.duration.as_secs()),
-                    artist: track.display_artist.clone(),
-                }
-            }).collect();
-
-            releases.push(ReleaseRequest {
-                release_id: release.release_id.clone(),
-                release_reference: Some(release.release_id.clone()),
-                title: vec![LocalizedStringRequest {
-                    text: release.default_title.clone(),
-                    language_code: None,
-                }],
-                artist: release.display_artist.clone(),
-                label: None, // Simplified
-                release_date: None, // Simplified
-                upc: None, // Simplified
-                tracks,
-                resource_references: Some(release.tracks.iter().map(|t| t.track_id.clone()).collect()),
-            });
-        }
-
-        Ok(BuildRequest {
-            header,
-            version: "4.3".to_string(),
-            profile: Some("AudioAlbum".to_string()),
-            releases,
-            deals: vec![],
-            extensions: None,
-        })
+        Ok(BuildRequest::from_graph(&parsed_result.graph))
     }
 
     fn create_build_request_from_stored_data(&self) -> Result<BuildRequest, PyErr> {
@@ -1320,6 +1337,7 @@ impl DdexBuilder {
             },
             message_control_type: None,
             message_created_date_time: Some(chrono::Utc::now().to_rfc3339()),
+            ..Default::default()
         };
 
         // Convert releases
@@ -1335,6 +1353,12 @@ impl DdexBuilder {
                     title: resource.title.clone(),
                     duration: resource.duration.clone().unwrap_or_else(|| "PT180S".to_string()),
                     artist: resource.artist.clone(),
+                    contributors: Vec::new(),
+                    p_line: None,
+                    c_line: None,
+                    comments: Vec::new(),
+                    technical_details: None,
+                    parental_warning: None,
                 })
                 .collect();
 
@@ -1351,6 +1375,15 @@ impl DdexBuilder {
                 upc: release.upc.clone(),
                 tracks,
                 resource_references: Some(release.track_ids.clone()),
+                resources: Vec::new(),
+                territory_details: Vec::new(),
+                contributors: Vec::new(),
+                p_line: None,
+                c_line: None,
+                track_isrcs: Vec::new(),
+                track_releases: Vec::new(),
+                comments: Vec::new(),
+                original_release_date: None,
             });
         }
 
@@ -1367,7 +1400,8 @@ impl DdexBuilder {
 }
 
 #[pymodule]
-fn ddex_builder(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
+fn ddex_builder(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("DdexError", py.get_type::<DdexError>())?;
     m.add_class::<Release>()?;
     m.add_class::<Resource>()?;
     m.add_class::<ValidationResult>()?;
@@ -1,8 +1,12 @@
 use wasm_bindgen::prelude::*;
 use serde::{Deserialize, Serialize};
-use serde_wasm_bindgen::{from_value, to_value};
+use serde_wasm_bindgen::to_value;
+#[cfg(feature = "builder")]
+use serde_wasm_bindgen::from_value;
+#[cfg(feature = "builder")]
 use std::collections::HashMap;
 
+#[cfg(feature = "builder")]
 pub mod diff_viewer;
 
 // Set up console error handling for better debugging
@@ -21,6 +25,13 @@ macro_rules! console_log {
     ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
 }
 
+#[cfg(feature = "parser")]
+mod parser;
+
+#[cfg(feature = "parser")]
+pub use parser::WasmDdexParser;
+
+#[cfg(feature = "builder")]
 #[wasm_bindgen]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Release {
@@ -47,6 +58,7 @@ pub struct Release {
     metadata: Option<HashMap<String, String>>,
 }
 
+#[cfg(feature = "builder")]
 #[wasm_bindgen]
 impl Release {
     #[wasm_bindgen(constructor)]
@@ -101,6 +113,7 @@ impl Release {
     }
 }
 
+#[cfg(feature = "builder")]
 #[wasm_bindgen]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Resource {
@@ -121,6 +134,7 @@ pub struct Resource {
     metadata: Option<HashMap<String, String>>,
 }
 
+#[cfg(feature = "builder")]
 #[wasm_bindgen]
 impl Resource {
     #[wasm_bindgen(constructor)]
@@ -162,6 +176,7 @@ impl Resource {
     }
 }
 
+#[cfg(not(feature = "no-validation"))]
 #[wasm_bindgen]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationResult {
@@ -171,6 +186,7 @@ pub struct ValidationResult {
     warnings: Vec<String>,
 }
 
+#[cfg(not(feature = "no-validation"))]
 #[wasm_bindgen]
 impl ValidationResult {
     #[wasm_bindgen(constructor)]
@@ -203,6 +219,7 @@ impl ValidationResult {
     }
 }
 
+#[cfg(feature = "builder")]
 #[wasm_bindgen]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuilderStats {
@@ -214,6 +231,7 @@ pub struct BuilderStats {
     pub validation_warnings: u32,
 }
 
+#[cfg(feature = "builder")]
 #[wasm_bindgen]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FidelityOptions {
@@ -246,6 +264,7 @@ pub struct FidelityOptions {
     pub enable_checksums: bool,
 }
 
+#[cfg(feature = "builder")]
 #[wasm_bindgen]
 impl FidelityOptions {
     #[wasm_bindgen(constructor)]
@@ -309,6 +328,7 @@ impl FidelityOptions {
     }
 }
 
+#[cfg(feature = "builder")]
 #[wasm_bindgen]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuildStatistics {
@@ -323,6 +343,7 @@ pub struct BuildStatistics {
     verification_time_ms: Option<f64>,
 }
 
+#[cfg(feature = "builder")]
 #[wasm_bindgen]
 impl BuildStatistics {
     #[wasm_bindgen(constructor)]
@@ -360,6 +381,7 @@ impl BuildStatistics {
     }
 }
 
+#[cfg(feature = "builder")]
 #[wasm_bindgen]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VerificationResult {
@@ -374,6 +396,7 @@ pub struct VerificationResult {
     checksums_match: Option<bool>,
 }
 
+#[cfg(feature = "builder")]
 #[wasm_bindgen]
 impl VerificationResult {
     #[wasm_bindgen(constructor)]
@@ -414,6 +437,7 @@ impl VerificationResult {
     }
 }
 
+#[cfg(feature = "builder")]
 #[wasm_bindgen]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuildResult {
@@ -423,6 +447,7 @@ pub struct BuildResult {
     verification: Option<VerificationResult>,
 }
 
+#[cfg(feature = "builder")]
 #[wasm_bindgen]
 impl BuildResult {
     #[wasm_bindgen(constructor)]
@@ -455,6 +480,7 @@ impl BuildResult {
     }
 }
 
+#[cfg(feature = "builder")]
 #[wasm_bindgen]
 impl BuilderStats {
     #[wasm_bindgen(constructor)]
@@ -470,13 +496,16 @@ impl BuilderStats {
     }
 }
 
+#[cfg(feature = "builder")]
 #[wasm_bindgen]
 pub struct WasmDdexBuilder {
     releases: Vec<Release>,
     resources: Vec<Resource>,
     stats: BuilderStats,
+    extension_namespaces: ddex_builder::extensions::ExtensionNamespaces,
 }
 
+#[cfg(feature = "builder")]
 #[wasm_bindgen]
 impl WasmDdexBuilder {
     #[wasm_bindgen(constructor)]
@@ -487,9 +516,19 @@ impl WasmDdexBuilder {
             releases: Vec::new(),
             resources: Vec::new(),
             stats: BuilderStats::new(),
+            extension_namespaces: Default::default(),
         })
     }
 
+    /// Declare a proprietary namespace under `prefix` in every build; throws
+    /// when the prefix or URI is taken by DDEX or another extension
+    #[wasm_bindgen(js_name = registerExtensionNamespace)]
+    pub fn register_extension_namespace(&mut self, prefix: String, uri: String, schema_url: Option<String>) -> Result<(), JsValue> {
+        self.extension_namespaces
+            .register(&prefix, &uri, schema_url.as_deref())
+            .map_err(ddex_error)
+    }
+
     #[wasm_bindgen(js_name = addRelease)]
     pub fn add_release(&mut self, release: Release) {
         self.releases.push(release);
@@ -614,6 +653,7 @@ impl WasmDdexBuilder {
         }
     }
 
+    #[cfg(not(feature = "no-validation"))]
     #[wasm_bindgen]
     pub fn validate(&self) -> ValidationResult {
         let mut result = ValidationResult::new(!self.releases.is_empty());
@@ -706,6 +746,7 @@ impl WasmDdexBuilder {
         Ok(())
     }
 
+    #[cfg(not(feature = "no-validation"))]
     #[wasm_bindgen(js_name = getPresetValidationRules)]
     pub fn get_preset_validation_rules(&self, preset_name: &str) -> Result<JsValue, JsValue> {
         let rules = match preset_name {
@@ -761,7 +802,15 @@ impl WasmDdexBuilder {
         let mut xml = String::new();
         xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
         xml.push('\n');
-        xml.push_str(r#"<NewReleaseMessage xmlns="http://ddex.net/xml/ern/43" MessageSchemaVersionId="ern/43">"#);
+        xml.push_str(r#"<NewReleaseMessage xmlns="http://ddex.net/xml/ern/43""#);
+        for namespace in self.extension_namespaces.iter() {
+            xml.push_str(&format!(
+                r#" xmlns:{}="{}""#,
+                namespace.prefix,
+                html_escape::encode_double_quoted_attribute(&namespace.uri)
+            ));
+        }
+        xml.push_str(r#" MessageSchemaVersionId="ern/43">"#);
         xml.push('\n');
         
         // Message header
@@ -774,7 +823,7 @@ impl WasmDdexBuilder {
         xml.push_str("      <PartyName>Web Client</PartyName>\n");
         xml.push_str("    </MessageRecipient>\n");
         xml.push_str(&format!("    <MessageCreatedDateTime>{}</MessageCreatedDateTime>\n", 
-            chrono::Utc::now().to_rfc3339()));
+            String::from(js_sys::Date::new_0().to_iso_string())));
         xml.push_str("  </MessageHeader>\n");
 
         // Releases
@@ -879,30 +928,43 @@ impl WasmDdexBuilder {
     }
 }
 
+/// Plain object with the fields of the `FFIError` for `err`, so callers can
+/// branch on its `code`
+fn ddex_error(err: impl Into<ddex_core::ffi::FFIError>) -> JsValue {
+    let ffi_error: ddex_core::ffi::FFIError = err.into();
+    to_value(&ffi_error).unwrap_or_else(|_| JsValue::from_str(&ffi_error.message))
+}
+
+#[cfg(feature = "builder")]
 #[wasm_bindgen(js_name = batchBuild)]
 pub async fn batch_build(requests: JsValue) -> Result<Vec<String>, JsValue> {
     // Convert JsValue to JavaScript Array
     let array = js_sys::Array::from(&requests);
-    let length = array.length();
-    let mut results = Vec::new();
+    let build_requests = array.iter()
+        .enumerate()
+        .map(|(i, request)| {
+            from_value::<ddex_builder::builder::BuildRequest>(request)
+                .map_err(|e| JsValue::from_str(&format!("Request {}: {}", i, e)))
+        })
+        .collect::<Result<Vec<_>, JsValue>>()?;
+    
+    let builder = ddex_builder::builder::DDEXBuilder::new();
+    let results = builder.build_batch(build_requests, ddex_builder::builder::BuildOptions::default());
     
-    for i in 0..length {
-        // Create a simple placeholder result for each request
-        let result = format!(r#"<?xml version="1.0" encoding="UTF-8"?>
-<NewReleaseMessage xmlns="http://ddex.net/xml/ern/43">
-  <MessageHeader>
-    <MessageId>{}</MessageId>
-    <MessageSender><PartyName>DDEX Suite WASM</PartyName></MessageSender>
-    <MessageRecipient><PartyName>Web Client</PartyName></MessageRecipient>
-  </MessageHeader>
-</NewReleaseMessage>"#, uuid::Uuid::new_v4());
-        results.push(result);
+    let failure = results.iter()
+        .enumerate()
+        .find_map(|(i, result)| result.errors.first().map(|error| (i, error)));
+    if let Some((i, error)) = failure {
+        let mut ffi_error = ddex_core::ffi::FFIError::from(error.clone());
+        ffi_error.message = format!("Request {}: {}", i, ffi_error.message);
+        return Err(ddex_error(ffi_error));
     }
     
     console_log!("Batch build completed: {} results", results.len());
-    Ok(results)
+    Ok(results.into_iter().map(|result| result.xml).collect())
 }
 
+#[cfg(not(feature = "no-validation"))]
 #[wasm_bindgen(js_name = validateStructure)]
 pub fn validate_structure(xml: String) -> ValidationResult {
     // Basic XML validation - check for well-formedness
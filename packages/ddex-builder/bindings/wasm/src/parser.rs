@@ -0,0 +1,76 @@
+//! DDEX parsing for browser validators
+//!
+//! Built with `--no-default-features --features parser-only`, this is all
+//! the package contains besides `version()`, which keeps the `.wasm` small
+//! enough to ship in a page that only checks messages.
+
+use std::io::Cursor;
+use wasm_bindgen::prelude::*;
+use serde_wasm_bindgen::to_value;
+
+#[cfg(not(feature = "no-validation"))]
+use crate::ValidationResult;
+use crate::ddex_error;
+
+#[wasm_bindgen]
+pub struct WasmDdexParser {
+    inner: ddex_parser::DDEXParser,
+}
+
+#[wasm_bindgen]
+impl WasmDdexParser {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmDdexParser {
+        console_error_panic_hook::set_once();
+
+        WasmDdexParser {
+            inner: ddex_parser::DDEXParser::new(),
+        }
+    }
+
+    /// Parse `xml` into the graph and flattened message
+    #[wasm_bindgen]
+    pub fn parse(&self, xml: &str) -> Result<JsValue, JsValue> {
+        self.parse_bytes(xml.as_bytes())
+    }
+
+    /// Parse UTF-8 `xml` from a `Uint8Array`, such as a file read into an
+    /// `ArrayBuffer` and transferred to a worker, without decoding it in JS
+    #[wasm_bindgen(js_name = parseBytes)]
+    pub fn parse_bytes(&self, xml: &[u8]) -> Result<JsValue, JsValue> {
+        let result = self.inner.parse(Cursor::new(xml))
+            .map_err(ddex_error)?;
+        console_log!("Parsed {}: {} releases", result.flat.message_id, result.flat.releases.len());
+
+        to_value(&result)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// DDEX version of `xml`, such as `V4_3`
+    #[wasm_bindgen(js_name = detectVersion)]
+    pub fn detect_version(&self, xml: &str) -> Result<String, JsValue> {
+        let version = self.inner.detect_version(Cursor::new(xml.as_bytes()))
+            .map_err(ddex_error)?;
+        Ok(format!("{:?}", version))
+    }
+
+    /// Check `xml` against the bundled schema for its version, one error
+    /// per violation
+    #[cfg(not(feature = "no-validation"))]
+    #[wasm_bindgen]
+    pub fn validate(&self, xml: &str) -> Result<ValidationResult, JsValue> {
+        let violations = self.inner.validate_schema(Cursor::new(xml.as_bytes()))
+            .map_err(ddex_error)?;
+
+        let mut result = ValidationResult::new(violations.is_empty());
+        result.set_errors(violations.iter().map(ToString::to_string).collect());
+        console_log!("Validation: is_valid={}, errors={}", result.is_valid, result.errors().len());
+        Ok(result)
+    }
+}
+
+impl Default for WasmDdexParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,41 @@
+//! Size budgets of the wasm-pack builds
+//!
+//! `npm run build:all` produces one package per feature set; this checks
+//! each optimized `.wasm` against its budget. Builds that are missing are
+//! skipped unless `DDEX_WASM_REQUIRE_BUILDS` is set, as it is in CI.
+
+use std::path::Path;
+
+const KB: u64 = 1024;
+
+/// Output directory, features passed to wasm-pack, and budget in bytes
+const BUDGETS: &[(&str, &str, u64)] = &[
+    ("pkg", "default", 1536 * KB),
+    ("pkg-builder", "builder-only", 1024 * KB),
+    ("pkg-parser", "parser-only,no-validation", 500 * KB),
+];
+
+#[test]
+fn test_wasm_builds_fit_their_budgets() {
+    let required = std::env::var_os("DDEX_WASM_REQUIRE_BUILDS").is_some();
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut over = Vec::new();
+
+    for (dir, features, budget) in BUDGETS {
+        let wasm = root.join(dir).join("ddex_builder_wasm_bg.wasm");
+        let size = match std::fs::metadata(&wasm) {
+            Ok(metadata) => metadata.len(),
+            Err(_) if !required => {
+                eprintln!("skipping {}: not built", wasm.display());
+                continue;
+            }
+            Err(e) => panic!("{}: {}", wasm.display(), e),
+        };
+        eprintln!("{} ({}): {}KB of {}KB", dir, features, size / KB, budget / KB);
+        if size > *budget {
+            over.push(format!("{} ({}) is {}KB, over its {}KB budget", dir, features, size / KB, budget / KB));
+        }
+    }
+
+    assert!(over.is_empty(), "{}", over.join("\n"));
+}
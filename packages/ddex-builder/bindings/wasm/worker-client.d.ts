@@ -0,0 +1,27 @@
+/* Typings for worker-client.js */
+
+export interface WorkerParseOptions {
+  /** Ends the worker, rejecting with `signal.reason` */
+  signal?: AbortSignal
+  /** Move an `ArrayBuffer` input to the worker instead of copying it */
+  transfer?: boolean
+}
+
+export interface WorkerBuildOptions {
+  /** Ends the worker, rejecting with `signal.reason` */
+  signal?: AbortSignal
+  /** Resolve to UTF-8 bytes instead of strings */
+  bytes?: boolean
+}
+
+/** Parses and builds in a dedicated module worker */
+export declare class DdexWorker {
+  /** `url` defaults to the `worker.js` shipped next to this module */
+  constructor(url?: string | URL)
+  /** The parsed message: `{ graph, flat, extensions }` */
+  parse(xml: string | ArrayBuffer | Uint8Array, options?: WorkerParseOptions): Promise<any>
+  build(requests: object[], options?: WorkerBuildOptions & { bytes?: false }): Promise<string[]>
+  build(requests: object[], options: WorkerBuildOptions & { bytes: true }): Promise<Uint8Array[]>
+  /** Stop the worker, rejecting whatever is still running */
+  terminate(reason?: unknown): void
+}
@@ -0,0 +1,109 @@
+/* Main-thread handle on worker.js, so large files never freeze the UI */
+
+const encoder = new TextEncoder()
+const decoder = new TextDecoder()
+
+/**
+ * Parses and builds in a dedicated module worker. Requests run one after
+ * another in the worker; XML travels as transferred `ArrayBuffer`s, parse
+ * results as structured clones.
+ */
+export class DdexWorker {
+  constructor(url = new URL('./worker.js', import.meta.url)) {
+    this.url = url
+    this.worker = null
+    this.pending = new Map()
+    this.nextId = 0
+  }
+
+  /**
+   * Parse `xml` (a string, `ArrayBuffer` or `Uint8Array`) into the graph
+   * and flattened message. With `transfer: true` an `ArrayBuffer` is moved
+   * to the worker instead of copied, and is unusable afterwards.
+   */
+  parse(xml, { signal, transfer = false } = {}) {
+    let buffer
+    if (typeof xml === 'string') {
+      buffer = encoder.encode(xml).buffer
+    } else if (xml instanceof ArrayBuffer) {
+      buffer = transfer ? xml : xml.slice(0)
+    } else if (ArrayBuffer.isView(xml)) {
+      buffer = xml.buffer.slice(xml.byteOffset, xml.byteOffset + xml.byteLength)
+    } else {
+      return Promise.reject(new TypeError('parse() takes a string, ArrayBuffer or Uint8Array'))
+    }
+    return this.request('parse', { xml: buffer }, [buffer], signal)
+  }
+
+  /**
+   * Build each of `requests` into a message, resolving to the XML strings,
+   * or to `Uint8Array`s with `bytes: true` (ready for a `Blob` download)
+   */
+  async build(requests, { signal, bytes = false } = {}) {
+    const buffers = await this.request('build', { requests }, [], signal)
+    return buffers.map((buffer) => (bytes ? new Uint8Array(buffer) : decoder.decode(buffer)))
+  }
+
+  /** Stop the worker, rejecting whatever is still running */
+  terminate(reason = new Error('DdexWorker terminated')) {
+    if (this.worker) {
+      this.worker.terminate()
+      this.worker = null
+    }
+    for (const { reject } of this.pending.values()) {
+      reject(reason)
+    }
+    this.pending.clear()
+  }
+
+  request(op, payload, transfer, signal) {
+    if (signal && signal.aborted) {
+      return Promise.reject(abortReason(signal))
+    }
+    const worker = this.spawn()
+    const id = this.nextId++
+    return new Promise((resolve, reject) => {
+      // WASM cannot be interrupted midway, so aborting ends the worker; the
+      // next request starts a fresh one
+      const onAbort = () => this.terminate(abortReason(signal))
+      const settle = (callback) => (value) => {
+        if (signal) {
+          signal.removeEventListener('abort', onAbort)
+        }
+        callback(value)
+      }
+      this.pending.set(id, { resolve: settle(resolve), reject: settle(reject) })
+      if (signal) {
+        signal.addEventListener('abort', onAbort, { once: true })
+      }
+      worker.postMessage({ id, op, payload }, transfer)
+    })
+  }
+
+  spawn() {
+    if (!this.worker) {
+      this.worker = new Worker(this.url, { type: 'module' })
+      this.worker.onmessage = ({ data: { id, result, error } }) => {
+        const request = this.pending.get(id)
+        if (!request) {
+          return
+        }
+        this.pending.delete(id)
+        if (error === undefined) {
+          request.resolve(result)
+        } else {
+          request.reject(error)
+        }
+      }
+      this.worker.onerror = (event) => {
+        event.preventDefault()
+        this.terminate(new Error(`DdexWorker failed: ${event.message}`))
+      }
+    }
+    return this.worker
+  }
+}
+
+function abortReason(signal) {
+  return signal.reason !== undefined ? signal.reason : new DOMException('The operation was aborted', 'AbortError')
+}
@@ -0,0 +1,42 @@
+/* Worker side of DdexWorker: parses and builds off the main thread */
+
+import init, { WasmDdexParser, batchBuild } from './pkg/ddex_builder_wasm.js'
+
+const ready = init()
+const encoder = new TextEncoder()
+let parser
+
+self.onmessage = async ({ data: { id, op, payload } }) => {
+  try {
+    await ready
+    const { result, transfer } = await run(op, payload)
+    self.postMessage({ id, result }, transfer)
+  } catch (error) {
+    self.postMessage({ id, error: cloneableError(error) })
+  }
+}
+
+async function run(op, payload) {
+  switch (op) {
+    case 'parse': {
+      parser = parser || new WasmDdexParser()
+      return { result: parser.parseBytes(new Uint8Array(payload.xml)), transfer: [] }
+    }
+    case 'build': {
+      // Each document goes back as a transferred buffer rather than a copy
+      const buffers = (await batchBuild(payload.requests)).map((xml) => encoder.encode(xml).buffer)
+      return { result: buffers, transfer: buffers }
+    }
+    default:
+      throw new Error(`Unknown operation: ${op}`)
+  }
+}
+
+// FFIError objects from the bindings clone as they are; Error instances
+// would lose everything but their message
+function cloneableError(error) {
+  if (error instanceof Error) {
+    return { name: error.name, message: error.message }
+  }
+  return error
+}
@@ -217,6 +217,7 @@ fn create_spotify_album_request() -> BuildRequest {
             // Message creation timestamp - should be current time in production
             // Format: ISO 8601 (RFC 3339) with UTC timezone
             message_created_date_time: Some(chrono::Utc::now().to_rfc3339()),
+            ..Default::default()
         },
         
         // DDEX Schema version - Spotify requires ERN 4.3
@@ -248,9 +249,18 @@ fn create_album_release() -> ReleaseRequest {
         artist: "The Wavelength Collective".to_string(),
         label: Some("Indie Digital Records".to_string()),
         release_date: Some("2024-03-15".to_string()),
+        original_release_date: None,
         upc: Some("602577123456".to_string()),
         tracks: create_album_tracks(),
         resource_references: Some(vec!["R1".to_string(), "R2".to_string(), "R3".to_string(), "R4".to_string(), "R5".to_string(), "R6".to_string(), "R7".to_string(), "R8".to_string()]),
+        resources: Vec::new(),
+        territory_details: Vec::new(),
+        contributors: Vec::new(),
+        p_line: None,
+        c_line: None,
+        track_isrcs: Vec::new(),
+        track_releases: Vec::new(),
+        comments: Vec::new(),
     }
 }
 
@@ -263,6 +273,12 @@ fn create_album_tracks() -> Vec<TrackRequest> {
             title: "Neon Dreams".to_string(),
             duration: "PT4M23S".to_string(),
             artist: "The Wavelength Collective".to_string(),
+            contributors: Vec::new(),
+            p_line: None,
+            c_line: None,
+            comments: Vec::new(),
+            technical_details: None,
+            parental_warning: None,
         },
         TrackRequest {
             track_id: "TRACK_002".to_string(),
@@ -271,6 +287,12 @@ fn create_album_tracks() -> Vec<TrackRequest> {
             title: "Synthetic Sunrise".to_string(),
             duration: "PT3M57S".to_string(),
             artist: "The Wavelength Collective".to_string(),
+            contributors: Vec::new(),
+            p_line: None,
+            c_line: None,
+            comments: Vec::new(),
+            technical_details: None,
+            parental_warning: None,
         },
         TrackRequest {
             track_id: "TRACK_003".to_string(),
@@ -279,6 +301,12 @@ fn create_album_tracks() -> Vec<TrackRequest> {
             title: "Digital Pulse".to_string(),
             duration: "PT5M12S".to_string(),
             artist: "The Wavelength Collective".to_string(),
+            contributors: Vec::new(),
+            p_line: None,
+            c_line: None,
+            comments: Vec::new(),
+            technical_details: None,
+            parental_warning: None,
         },
         TrackRequest {
             track_id: "TRACK_004".to_string(),
@@ -287,6 +315,12 @@ fn create_album_tracks() -> Vec<TrackRequest> {
             title: "Cyber Meditation".to_string(),
             duration: "PT6M45S".to_string(),
             artist: "The Wavelength Collective".to_string(),
+            contributors: Vec::new(),
+            p_line: None,
+            c_line: None,
+            comments: Vec::new(),
+            technical_details: None,
+            parental_warning: None,
         },
         TrackRequest {
             track_id: "TRACK_005".to_string(),
@@ -295,6 +329,12 @@ fn create_album_tracks() -> Vec<TrackRequest> {
             title: "Binary Sunset".to_string(),
             duration: "PT4M31S".to_string(),
             artist: "The Wavelength Collective".to_string(),
+            contributors: Vec::new(),
+            p_line: None,
+            c_line: None,
+            comments: Vec::new(),
+            technical_details: None,
+            parental_warning: None,
         },
         TrackRequest {
             track_id: "TRACK_006".to_string(),
@@ -303,6 +343,12 @@ fn create_album_tracks() -> Vec<TrackRequest> {
             title: "Algorithmic Love".to_string(),
             duration: "PT3M44S".to_string(),
             artist: "The Wavelength Collective feat. Echo Siren".to_string(),
+            contributors: Vec::new(),
+            p_line: None,
+            c_line: None,
+            comments: Vec::new(),
+            technical_details: None,
+            parental_warning: None,
         },
         TrackRequest {
             track_id: "TRACK_007".to_string(),
@@ -311,6 +357,12 @@ fn create_album_tracks() -> Vec<TrackRequest> {
             title: "Data Stream Dreams".to_string(),
             duration: "PT7M18S".to_string(),
             artist: "The Wavelength Collective".to_string(),
+            contributors: Vec::new(),
+            p_line: None,
+            c_line: None,
+            comments: Vec::new(),
+            technical_details: None,
+            parental_warning: None,
         },
         TrackRequest {
             track_id: "TRACK_008".to_string(),
@@ -319,6 +371,12 @@ fn create_album_tracks() -> Vec<TrackRequest> {
             title: "Virtual Reality".to_string(),
             duration: "PT4M56S".to_string(),
             artist: "The Wavelength Collective".to_string(),
+            contributors: Vec::new(),
+            p_line: None,
+            c_line: None,
+            comments: Vec::new(),
+            technical_details: None,
+            parental_warning: None,
         },
     ]
 }
@@ -331,8 +389,11 @@ fn create_spotify_streaming_deal() -> DealRequest {
             commercial_model_type: "SubscriptionModel".to_string(),
             territory_code: vec!["Worldwide".to_string()],
             start_date: Some("2024-03-15".to_string()),
+            ..Default::default()
         },
         release_references: vec!["REL_REF_001".to_string()],
+        release_ids: Vec::new(),
+        comments: Vec::new(),
     }
 }
 
@@ -72,8 +72,9 @@ fn create_youtube_video_request() -> BuildRequest {
                 party_id: None,
                 party_reference: None,
             },
-            message_control_type: Some("NewReleaseMessage".to_string()),
+            message_control_type: Some("LiveMessage".to_string()),
             message_created_date_time: Some(chrono::Utc::now().to_rfc3339()),
+            ..Default::default()
         },
         version: "ern/43".to_string(),
         profile: Some("VideoSingle".to_string()),
@@ -90,6 +91,15 @@ fn create_youtube_video_request() -> BuildRequest {
             upc: Some("123456789012".to_string()),
             tracks: Vec::new(),
             resource_references: Some(vec!["A1".to_string(), "V1".to_string()]),
+            resources: Vec::new(),
+            territory_details: Vec::new(),
+            contributors: Vec::new(),
+            p_line: None,
+            c_line: None,
+            track_isrcs: Vec::new(),
+            track_releases: Vec::new(),
+            comments: Vec::new(),
+            original_release_date: None,
         }],
         deals: vec![],
         extensions: Some(create_youtube_metadata()),
@@ -169,8 +179,11 @@ fn create_youtube_monetization_deal() -> DealRequest {
             commercial_model_type: "AdvertisementSupportedModel".to_string(),
             territory_code: vec!["Worldwide".to_string()],
             start_date: Some("2024-02-14".to_string()),
+            ..Default::default()
         },
         release_references: vec!["VIDEO_VIRAL_2024_001".to_string()],
+        release_ids: Vec::new(),
+        comments: Vec::new(),
     }
 }
 
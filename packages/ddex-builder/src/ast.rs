@@ -56,6 +56,11 @@ impl Element {
         self
     }
     
+    pub fn with_child(mut self, child: Element) -> Self {
+        self.children.push(Node::Element(child));
+        self
+    }
+    
     pub fn add_child(&mut self, child: Element) {
         self.children.push(Node::Element(child));
     }
@@ -80,4 +85,18 @@ impl Element {
         self.children.push(Node::Comment(comment));
         self
     }
+    
+    /// Estimated bytes held by this element and its subtree
+    pub fn heap_size(&self) -> usize {
+        let own = std::mem::size_of::<Self>()
+            + self.name.capacity()
+            + self.namespace.as_ref().map_or(0, String::capacity)
+            + self.attributes.iter().map(|(k, v)| k.capacity() + v.capacity()).sum::<usize>()
+            + self.children.capacity() * std::mem::size_of::<Node>();
+        own + self.children.iter().map(|child| match child {
+            Node::Element(element) => element.heap_size(),
+            Node::Text(text) | Node::SimpleComment(text) => text.capacity(),
+            Node::Comment(comment) => comment.content.capacity(),
+        }).sum::<usize>()
+    }
 }
\ No newline at end of file
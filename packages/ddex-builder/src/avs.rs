@@ -0,0 +1,447 @@
+//! DDEX Allowed Value Sets (AVS)
+//!
+//! DDEX restricts many element values to controlled vocabularies that are
+//! published alongside each ERN schema. This module carries the value sets the
+//! builder emits, tagged with the ERN generations that define each value, so
+//! that preflight can reject unknown values and warn about values that belong
+//! to a different version than the one being built.
+
+use crate::presets::DdexVersion;
+use serde::{Deserialize, Serialize};
+
+/// A DDEX controlled vocabulary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AllowedValueSet {
+    /// Commercial model of a deal (e.g. `SubscriptionModel`)
+    CommercialModelType,
+    /// Usage permitted by a deal (e.g. `OnDemandStream`)
+    UseType,
+    /// Type of a release (e.g. `Album`)
+    ReleaseType,
+    /// ISO 3166-1 territory or `Worldwide`
+    TerritoryCode,
+    /// Role of a display artist (`DisplayArtistRole` in ERN 4)
+    ArtistRole,
+    /// Explicit content advisory
+    ParentalWarningType,
+    /// Type of a video resource
+    VideoType,
+    /// Type of an image resource (e.g. `FrontCoverImage`)
+    ImageType,
+    /// Type of a text resource
+    TextType,
+    /// Type of a sheet music resource
+    SheetMusicType,
+}
+
+/// Outcome of checking a value against an allowed value set
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AvsStatus {
+    /// The value is defined for the requested version
+    Allowed,
+    /// The value is defined, but only in other ERN versions
+    NotInVersion,
+    /// The value is not part of the set; carries a close match if one exists
+    Unknown {
+        /// A defined value differing only in case or separators
+        suggestion: Option<&'static str>,
+    },
+}
+
+/// ERN generations a value is defined in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Availability {
+    All,
+    Ern3,
+    Ern4,
+}
+
+impl Availability {
+    fn includes(self, version: DdexVersion) -> bool {
+        match self {
+            Availability::All => true,
+            Availability::Ern3 => version == DdexVersion::Ern382,
+            Availability::Ern4 => version != DdexVersion::Ern382,
+        }
+    }
+}
+
+type Entry = (&'static str, Availability);
+
+use Availability::{All, Ern3, Ern4};
+
+impl AllowedValueSet {
+    /// All value sets known to the builder
+    pub const ALL: [AllowedValueSet; 10] = [
+        AllowedValueSet::CommercialModelType,
+        AllowedValueSet::UseType,
+        AllowedValueSet::ReleaseType,
+        AllowedValueSet::TerritoryCode,
+        AllowedValueSet::ArtistRole,
+        AllowedValueSet::ParentalWarningType,
+        AllowedValueSet::VideoType,
+        AllowedValueSet::ImageType,
+        AllowedValueSet::TextType,
+        AllowedValueSet::SheetMusicType,
+    ];
+
+    /// DDEX name of the value set
+    pub fn name(&self) -> &'static str {
+        match self {
+            AllowedValueSet::CommercialModelType => "CommercialModelType",
+            AllowedValueSet::UseType => "UseType",
+            AllowedValueSet::ReleaseType => "ReleaseType",
+            AllowedValueSet::TerritoryCode => "TerritoryCode",
+            AllowedValueSet::ArtistRole => "ArtistRole",
+            AllowedValueSet::ParentalWarningType => "ParentalWarningType",
+            AllowedValueSet::VideoType => "VideoType",
+            AllowedValueSet::ImageType => "ImageType",
+            AllowedValueSet::TextType => "TextType",
+            AllowedValueSet::SheetMusicType => "SheetMusicType",
+        }
+    }
+
+    /// Values defined for `version`, in AVS order
+    pub fn values(&self, version: DdexVersion) -> Vec<&'static str> {
+        self.entries()
+            .iter()
+            .filter(|(_, availability)| availability.includes(version))
+            .map(|(value, _)| *value)
+            .collect()
+    }
+
+    /// Whether `value` is defined for `version`
+    pub fn contains(&self, value: &str, version: DdexVersion) -> bool {
+        self.check(value, version) == AvsStatus::Allowed
+    }
+
+    /// Check a value against the set for `version`
+    pub fn check(&self, value: &str, version: DdexVersion) -> AvsStatus {
+        if let Some((_, availability)) = self.entries().iter().find(|(v, _)| *v == value) {
+            return if availability.includes(version) {
+                AvsStatus::Allowed
+            } else {
+                AvsStatus::NotInVersion
+            };
+        }
+
+        let normalized = normalize(value);
+        let suggestion = self
+            .entries()
+            .iter()
+            .filter(|(_, availability)| availability.includes(version))
+            .map(|(v, _)| *v)
+            .find(|v| normalize(v) == normalized);
+        AvsStatus::Unknown { suggestion }
+    }
+
+    fn entries(&self) -> &'static [Entry] {
+        match self {
+            AllowedValueSet::CommercialModelType => COMMERCIAL_MODEL_TYPES,
+            AllowedValueSet::UseType => USE_TYPES,
+            AllowedValueSet::ReleaseType => RELEASE_TYPES,
+            AllowedValueSet::TerritoryCode => TERRITORY_CODES,
+            AllowedValueSet::ArtistRole => ARTIST_ROLES,
+            AllowedValueSet::ParentalWarningType => PARENTAL_WARNING_TYPES,
+            AllowedValueSet::VideoType => VIDEO_TYPES,
+            AllowedValueSet::ImageType => IMAGE_TYPES,
+            AllowedValueSet::TextType => TEXT_TYPES,
+            AllowedValueSet::SheetMusicType => SHEET_MUSIC_TYPES,
+        }
+    }
+}
+
+impl std::fmt::Display for AllowedValueSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+fn normalize(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+const COMMERCIAL_MODEL_TYPES: &[Entry] = &[
+    ("AdvertisementSupportedModel", All),
+    ("AsPerContract", All),
+    ("DeviceFeeModel", All),
+    ("FreeOfChargeModel", All),
+    ("PayAsYouGoModel", All),
+    ("PerSubscriberModel", Ern4),
+    ("RightsClaimModel", All),
+    ("SubscriptionModel", All),
+    ("Unknown", All),
+    ("UserDefined", All),
+];
+
+const USE_TYPES: &[Entry] = &[
+    ("Broadcast", All),
+    ("Cable", All),
+    ("ConditionalDownload", All),
+    ("ContentInfluencedStream", All),
+    ("Display", All),
+    ("Download", All),
+    ("Dub", All),
+    ("DubForOnDemandStreaming", All),
+    ("DubForLivePerformance", All),
+    ("DubForMovies", All),
+    ("DubForMusicOnHold", All),
+    ("DubForPublicPerformance", All),
+    ("DubForRadio", All),
+    ("DubForTV", All),
+    ("ExtractForInternet", All),
+    ("KioskDownload", All),
+    ("Narrowcast", All),
+    ("NonInteractiveStream", All),
+    ("OnDemandStream", All),
+    ("Perform", All),
+    ("PerformAsMusicOnHold", All),
+    ("PerformInLivePerformance", All),
+    ("PerformInPublic", All),
+    ("PermanentDownload", All),
+    ("Playback", All),
+    ("PlayInPublic", All),
+    ("Podcast", All),
+    ("Print", All),
+    ("PrivateCopy", All),
+    ("PurchaseAsPhysicalProduct", All),
+    ("Rent", All),
+    ("Simulcast", All),
+    ("Stream", All),
+    ("TetheredDownload", All),
+    ("TimeInfluencedStream", All),
+    ("Use", All),
+    ("UseAsAlertTone", All),
+    ("UseAsDevice", All),
+    ("UseAsKaraoke", All),
+    ("UseAsRingbackTone", All),
+    ("UseAsRingbackTune", All),
+    ("UseAsRingtone", All),
+    ("UseAsRingtune", All),
+    ("UseAsScreensaver", All),
+    ("UseAsVoiceMail", All),
+    ("UseAsWallpaper", All),
+    ("UserMakeAvailableLabelProvided", All),
+    ("UserMakeAvailableUserProvided", All),
+    ("Webcast", All),
+    ("Unknown", All),
+    ("UserDefined", All),
+];
+
+const RELEASE_TYPES: &[Entry] = &[
+    ("Album", All),
+    ("AlertToneRelease", All),
+    ("AsPerContract", All),
+    ("AudioBookRelease", All),
+    ("AudioDramaRelease", All),
+    ("BackCoverImageRelease", All),
+    ("BookletBackImageRelease", All),
+    ("BookletFrontImageRelease", All),
+    ("BookletRelease", All),
+    ("Bundle", All),
+    ("ClassicalAlbum", All),
+    ("ClassicalDigitalBoxedSet", All),
+    ("ClassicalMultimediaAlbum", All),
+    ("ConcertVideo", All),
+    ("DigitalBoxSetRelease", All),
+    ("DjMix", All),
+    ("Documentary", All),
+    ("EP", All),
+    ("Episode", All),
+    ("FrontCoverImageRelease", All),
+    ("IngestionDummy", Ern3),
+    ("InlayImageRelease", All),
+    ("KaraokeRelease", All),
+    ("LiveEventVideo", All),
+    ("LogoRelease", All),
+    ("LongFormMusicalWorkVideoRelease", All),
+    ("LongFormNonMusicalWorkVideoRelease", All),
+    ("LyricSheetRelease", All),
+    ("MultimediaAlbum", All),
+    ("MultimediaDigitalBoxedSet", All),
+    ("MultimediaSingle", All),
+    ("MusicalWorkBasedGameRelease", All),
+    ("NonMusicalWorkBasedGameRelease", All),
+    ("PlayList", All),
+    ("RingbackToneRelease", All),
+    ("RingtoneRelease", All),
+    ("Season", All),
+    ("Series", All),
+    ("SheetMusicRelease", All),
+    ("ShortFormMusicalWorkVideoRelease", All),
+    ("ShortFormNonMusicalWorkVideoRelease", All),
+    ("Single", All),
+    ("SingleResourceRelease", All),
+    ("StemBundle", Ern4),
+    ("VideoAlbum", All),
+    ("VideoMastertoneRelease", All),
+    ("VideoSingle", All),
+    ("WallpaperRelease", All),
+    ("Unknown", All),
+    ("UserDefined", All),
+];
+
+// ERN 4 narrowed the display artist roles; the contributor roles that ERN
+// 3.8.2 also accepted here moved to the contributor vocabularies
+const ARTIST_ROLES: &[Entry] = &[
+    ("Artist", All),
+    ("Brand", Ern4),
+    ("Composer", All),
+    ("FeaturedArtist", All),
+    ("MainArtist", All),
+    ("Actor", Ern3),
+    ("Arranger", Ern3),
+    ("Band", Ern3),
+    ("Choir", Ern3),
+    ("Conductor", Ern3),
+    ("Ensemble", Ern3),
+    ("Lyricist", Ern3),
+    ("Orchestra", Ern3),
+    ("Producer", Ern3),
+    ("Remixer", Ern3),
+    ("Soloist", Ern3),
+    ("Unknown", All),
+    ("UserDefined", All),
+];
+
+const PARENTAL_WARNING_TYPES: &[Entry] = &[
+    ("Explicit", All),
+    ("ExplicitContentEdited", All),
+    ("NotExplicit", All),
+    ("NoAdviceAvailable", All),
+    ("Unknown", All),
+    ("UserDefined", All),
+];
+
+const VIDEO_TYPES: &[Entry] = &[
+    ("AdvertisementVideo", All),
+    ("ConcertVideo", All),
+    ("Documentary", All),
+    ("LiveEventVideo", All),
+    ("LongFormMusicalWorkVideo", All),
+    ("LongFormNonMusicalWorkVideo", All),
+    ("LyricVideo", Ern4),
+    ("MusicalWorkVideoChapter", All),
+    ("NonMusicalWorkVideoChapter", All),
+    ("ShortFormMusicalWorkVideo", All),
+    ("ShortFormNonMusicalWorkVideo", All),
+    ("VisualizerVideo", Ern4),
+    ("Unknown", All),
+    ("UserDefined", All),
+];
+
+const IMAGE_TYPES: &[Entry] = &[
+    ("BackCoverImage", All),
+    ("BookletBackImage", All),
+    ("BookletFrontImage", All),
+    ("DocumentImage", All),
+    ("FrontCoverImage", All),
+    ("Icon", All),
+    ("Logo", All),
+    ("Photograph", All),
+    ("Portrait", All),
+    ("Poster", All),
+    ("TrayImage", All),
+    ("VideoScreenCapture", All),
+    ("Wallpaper", All),
+    ("Unknown", All),
+    ("UserDefined", All),
+];
+
+const TEXT_TYPES: &[Entry] = &[
+    ("Biography", All),
+    ("Book", All),
+    ("EditorialText", All),
+    ("LinerNotes", All),
+    ("LyricText", All),
+    ("NonInteractiveBooklet", All),
+    ("ReviewText", All),
+    ("TextDocument", All),
+    ("Unknown", All),
+    ("UserDefined", All),
+];
+
+const SHEET_MUSIC_TYPES: &[Entry] = &[
+    ("Chords", All),
+    ("FullScore", All),
+    ("GuitarTablature", All),
+    ("LeadSheet", All),
+    ("MusicalWorkReadableScore", All),
+    ("PianoScore", All),
+    ("Unknown", All),
+    ("UserDefined", All),
+];
+
+const TERRITORY_CODES: &[Entry] = &[
+    ("Worldwide", All),
+    ("AD", All), ("AE", All), ("AF", All), ("AG", All), ("AI", All), ("AL", All), ("AM", All), ("AO", All),
+    ("AQ", All), ("AR", All), ("AS", All), ("AT", All), ("AU", All), ("AW", All), ("AX", All), ("AZ", All),
+    ("BA", All), ("BB", All), ("BD", All), ("BE", All), ("BF", All), ("BG", All), ("BH", All), ("BI", All),
+    ("BJ", All), ("BL", All), ("BM", All), ("BN", All), ("BO", All), ("BQ", All), ("BR", All), ("BS", All),
+    ("BT", All), ("BV", All), ("BW", All), ("BY", All), ("BZ", All), ("CA", All), ("CC", All), ("CD", All),
+    ("CF", All), ("CG", All), ("CH", All), ("CI", All), ("CK", All), ("CL", All), ("CM", All), ("CN", All),
+    ("CO", All), ("CR", All), ("CU", All), ("CV", All), ("CW", All), ("CX", All), ("CY", All), ("CZ", All),
+    ("DE", All), ("DJ", All), ("DK", All), ("DM", All), ("DO", All), ("DZ", All), ("EC", All), ("EE", All),
+    ("EG", All), ("EH", All), ("ER", All), ("ES", All), ("ET", All), ("FI", All), ("FJ", All), ("FK", All),
+    ("FM", All), ("FO", All), ("FR", All), ("GA", All), ("GB", All), ("GD", All), ("GE", All), ("GF", All),
+    ("GG", All), ("GH", All), ("GI", All), ("GL", All), ("GM", All), ("GN", All), ("GP", All), ("GQ", All),
+    ("GR", All), ("GS", All), ("GT", All), ("GU", All), ("GW", All), ("GY", All), ("HK", All), ("HM", All),
+    ("HN", All), ("HR", All), ("HT", All), ("HU", All), ("ID", All), ("IE", All), ("IL", All), ("IM", All),
+    ("IN", All), ("IO", All), ("IQ", All), ("IR", All), ("IS", All), ("IT", All), ("JE", All), ("JM", All),
+    ("JO", All), ("JP", All), ("KE", All), ("KG", All), ("KH", All), ("KI", All), ("KM", All), ("KN", All),
+    ("KP", All), ("KR", All), ("KW", All), ("KY", All), ("KZ", All), ("LA", All), ("LB", All), ("LC", All),
+    ("LI", All), ("LK", All), ("LR", All), ("LS", All), ("LT", All), ("LU", All), ("LV", All), ("LY", All),
+    ("MA", All), ("MC", All), ("MD", All), ("ME", All), ("MF", All), ("MG", All), ("MH", All), ("MK", All),
+    ("ML", All), ("MM", All), ("MN", All), ("MO", All), ("MP", All), ("MQ", All), ("MR", All), ("MS", All),
+    ("MT", All), ("MU", All), ("MV", All), ("MW", All), ("MX", All), ("MY", All), ("MZ", All), ("NA", All),
+    ("NC", All), ("NE", All), ("NF", All), ("NG", All), ("NI", All), ("NL", All), ("NO", All), ("NP", All),
+    ("NR", All), ("NU", All), ("NZ", All), ("OM", All), ("PA", All), ("PE", All), ("PF", All), ("PG", All),
+    ("PH", All), ("PK", All), ("PL", All), ("PM", All), ("PN", All), ("PR", All), ("PS", All), ("PT", All),
+    ("PW", All), ("PY", All), ("QA", All), ("RE", All), ("RO", All), ("RS", All), ("RU", All), ("RW", All),
+    ("SA", All), ("SB", All), ("SC", All), ("SD", All), ("SE", All), ("SG", All), ("SH", All), ("SI", All),
+    ("SJ", All), ("SK", All), ("SL", All), ("SM", All), ("SN", All), ("SO", All), ("SR", All), ("SS", All),
+    ("ST", All), ("SV", All), ("SX", All), ("SY", All), ("SZ", All), ("TC", All), ("TD", All), ("TF", All),
+    ("TG", All), ("TH", All), ("TJ", All), ("TK", All), ("TL", All), ("TM", All), ("TN", All), ("TO", All),
+    ("TR", All), ("TT", All), ("TV", All), ("TW", All), ("TZ", All), ("UA", All), ("UG", All), ("UM", All),
+    ("US", All), ("UY", All), ("UZ", All), ("VA", All), ("VC", All), ("VE", All), ("VG", All), ("VI", All),
+    ("VN", All), ("VU", All), ("WF", All), ("WS", All), ("XK", All), ("YE", All), ("YT", All), ("ZA", All),
+    ("ZM", All), ("ZW", All),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_values_are_checked_per_version() {
+        let avs = AllowedValueSet::ArtistRole;
+        assert_eq!(avs.check("MainArtist", DdexVersion::Ern43), AvsStatus::Allowed);
+        assert_eq!(avs.check("Band", DdexVersion::Ern382), AvsStatus::Allowed);
+        assert_eq!(avs.check("Band", DdexVersion::Ern43), AvsStatus::NotInVersion);
+        assert!(avs.values(DdexVersion::Ern44).contains(&"Brand"));
+        assert!(!avs.values(DdexVersion::Ern382).contains(&"Brand"));
+    }
+
+    #[test]
+    fn test_unknown_values_suggest_close_matches() {
+        let avs = AllowedValueSet::CommercialModelType;
+        assert_eq!(
+            avs.check("subscription_model", DdexVersion::Ern43),
+            AvsStatus::Unknown { suggestion: Some("SubscriptionModel") }
+        );
+        assert_eq!(avs.check("PurchaseModel", DdexVersion::Ern43), AvsStatus::Unknown { suggestion: None });
+    }
+
+    #[test]
+    fn test_territory_codes() {
+        let avs = AllowedValueSet::TerritoryCode;
+        assert!(avs.contains("Worldwide", DdexVersion::Ern382));
+        assert!(avs.contains("GB", DdexVersion::Ern43));
+        assert!(!avs.contains("UK", DdexVersion::Ern43));
+        assert!(!avs.contains("us", DdexVersion::Ern43));
+    }
+}
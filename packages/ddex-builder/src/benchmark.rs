@@ -0,0 +1,246 @@
+//! Synthetic catalogs and a quick self-benchmark
+//!
+//! The Criterion suite in `benches/` and the hidden `ddex-builder bench`
+//! command both measure the documents made here, so the MB/s a user sees on
+//! their own hardware is comparable with the published targets.
+//!
+//! ```rust,no_run
+//! use ddex_builder::benchmark;
+//!
+//! for measurement in benchmark::run(1024 * 1024, 3)? {
+//!     println!("{}: {:.1} MB/s", measurement.operation, measurement.mb_per_sec());
+//! }
+//! # Ok::<(), ddex_builder::BuildError>(())
+//! ```
+
+use crate::builder::{
+    BuildOptions, BuildRequest, DDEXBuilder, LocalizedStringRequest, MessageHeaderRequest,
+    PartyRequest, ReleaseRequest, TrackRequest,
+};
+use crate::canonical::DB_C14N;
+use crate::determinism::DeterminismConfig;
+use crate::error::BuildError;
+use crate::preflight::gtin_check_digit;
+use crate::round_trip::RoundTripTester;
+use crate::FidelityOptions;
+use serde::Serialize;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// The performance target: at most this many milliseconds per MB of XML
+pub const TARGET_MS_PER_MB: f64 = 50.0;
+
+/// Tracks on each generated release
+pub const TRACKS_PER_RELEASE: usize = 12;
+
+/// Releases built to estimate the size of one release
+const PROBE_RELEASES: usize = 8;
+
+const BYTES_PER_MB: f64 = 1024.0 * 1024.0;
+
+/// An album-shaped request with `releases` releases of
+/// [`TRACKS_PER_RELEASE`] tracks, with valid UPCs and ISRCs
+pub fn sample_request(releases: usize) -> BuildRequest {
+    BuildRequest {
+        header: MessageHeaderRequest {
+            message_id: Some("BENCH-001".to_string()),
+            message_sender: party("Benchmark Label"),
+            message_recipient: party("Benchmark DSP"),
+            message_control_type: Some("LiveMessage".to_string()),
+            message_created_date_time: Some("2025-01-01T00:00:00Z".to_string()),
+            ..Default::default()
+        },
+        version: "4.3".to_string(),
+        profile: Some("AudioAlbum".to_string()),
+        releases: (0..releases).map(sample_release).collect(),
+        deals: Vec::new(),
+        extensions: None,
+    }
+}
+
+fn party(name: &str) -> PartyRequest {
+    PartyRequest {
+        party_name: vec![LocalizedStringRequest { text: name.to_string(), language_code: None }],
+        party_id: None,
+        party_reference: None,
+    }
+}
+
+fn sample_release(index: usize) -> ReleaseRequest {
+    let upc_data = format!("{:011}", index);
+    let tracks = (0..TRACKS_PER_RELEASE)
+        .map(|track| {
+            let number = index * TRACKS_PER_RELEASE + track;
+            TrackRequest {
+                track_id: format!("T{}", number),
+                resource_reference: Some(format!("A{}", number + 1)),
+                isrc: format!("USRC1{:07}", number % 10_000_000),
+                title: format!("Benchmark Track {}", track + 1),
+                duration: "PT3M30S".to_string(),
+                artist: "Benchmark Artist".to_string(),
+                contributors: Vec::new(),
+                p_line: None,
+                c_line: None,
+                comments: Vec::new(),
+                technical_details: None,
+                parental_warning: None,
+            }
+        })
+        .collect();
+
+    ReleaseRequest {
+        release_id: format!("REL{}", index),
+        release_reference: Some(format!("R{}", index + 1)),
+        title: vec![LocalizedStringRequest {
+            text: format!("Benchmark Album {}", index + 1),
+            language_code: Some("en".to_string()),
+        }],
+        artist: "Benchmark Artist".to_string(),
+        label: Some("Benchmark Label".to_string()),
+        release_date: Some("2025-01-01".to_string()),
+        original_release_date: None,
+        upc: Some(format!("{}{}", upc_data, gtin_check_digit(&upc_data))),
+        tracks,
+        resource_references: None,
+        resources: Vec::new(),
+        territory_details: Vec::new(),
+        contributors: Vec::new(),
+        p_line: None,
+        c_line: None,
+        track_isrcs: Vec::new(),
+        track_releases: Vec::new(),
+        comments: Vec::new(),
+    }
+}
+
+/// A request whose built XML is roughly `target_bytes` long
+pub fn sized_request(target_bytes: usize) -> Result<BuildRequest, BuildError> {
+    let probe = DDEXBuilder::new().build(sample_request(PROBE_RELEASES), BuildOptions::default())?;
+    let per_release = (probe.xml.len() / PROBE_RELEASES).max(1);
+    Ok(sample_request((target_bytes / per_release).max(1)))
+}
+
+/// Built XML of roughly `target_bytes`, for benchmarking the stages that
+/// start from a document
+pub fn sample_xml(target_bytes: usize) -> Result<String, BuildError> {
+    let request = sized_request(target_bytes)?;
+    Ok(DDEXBuilder::new().build(request, BuildOptions::default())?.xml)
+}
+
+/// A stage of the pipeline the self-benchmark times
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    /// Request to XML
+    Build,
+    /// DB-C14N canonicalization of built XML
+    Canonicalize,
+    /// XML to XML through the fidelity engine, diffing the result
+    RoundTrip,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operation::Build => write!(f, "build"),
+            Operation::Canonicalize => write!(f, "canonicalize"),
+            Operation::RoundTrip => write!(f, "round-trip"),
+        }
+    }
+}
+
+/// Fastest of several timed runs of one operation
+#[derive(Debug, Clone, Serialize)]
+pub struct Measurement {
+    /// What was timed
+    pub operation: Operation,
+    /// Size of the XML produced or consumed
+    pub bytes: usize,
+    /// How many runs the fastest was taken from
+    pub iterations: usize,
+    /// Fastest run
+    pub elapsed: Duration,
+}
+
+impl Measurement {
+    /// Throughput of the fastest run
+    pub fn mb_per_sec(&self) -> f64 {
+        self.bytes as f64 / BYTES_PER_MB / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// Milliseconds per MB of the fastest run
+    pub fn ms_per_mb(&self) -> f64 {
+        self.elapsed.as_secs_f64() * 1000.0 / (self.bytes as f64 / BYTES_PER_MB)
+    }
+
+    /// Whether the run met [`TARGET_MS_PER_MB`]
+    pub fn meets_target(&self) -> bool {
+        self.ms_per_mb() <= TARGET_MS_PER_MB
+    }
+}
+
+/// Time build, canonicalize and round-trip on a catalog of about
+/// `target_bytes`, keeping the fastest of `iterations` runs of each
+pub fn run(target_bytes: usize, iterations: usize) -> Result<Vec<Measurement>, BuildError> {
+    let iterations = iterations.max(1);
+    let builder = DDEXBuilder::new();
+    let request = sized_request(target_bytes)?;
+    let xml = builder.build(request.clone(), BuildOptions::default())?.xml;
+
+    let build = fastest(iterations, || {
+        let request = request.clone();
+        let start = Instant::now();
+        builder.build(request, BuildOptions::default())?;
+        Ok(start.elapsed())
+    })?;
+
+    let canonicalizer = DB_C14N::new(DeterminismConfig::default());
+    let canonicalize = fastest(iterations, || {
+        let start = Instant::now();
+        canonicalizer.canonicalize(&xml)?;
+        Ok(start.elapsed())
+    })?;
+
+    let tester = RoundTripTester::new(FidelityOptions::default());
+    let round_trip = fastest(iterations, || {
+        let start = Instant::now();
+        tester.test_round_trip(&xml)?;
+        Ok(start.elapsed())
+    })?;
+
+    Ok([(Operation::Build, build), (Operation::Canonicalize, canonicalize), (Operation::RoundTrip, round_trip)]
+        .into_iter()
+        .map(|(operation, elapsed)| Measurement { operation, bytes: xml.len(), iterations, elapsed })
+        .collect())
+}
+
+fn fastest(
+    iterations: usize,
+    mut timed: impl FnMut() -> Result<Duration, BuildError>,
+) -> Result<Duration, BuildError> {
+    let mut best = Duration::MAX;
+    for _ in 0..iterations {
+        best = best.min(timed()?);
+    }
+    Ok(best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sized_request_is_close_to_target() {
+        let target = 256 * 1024;
+        let xml = sample_xml(target).unwrap();
+        assert!(xml.len() > target / 2 && xml.len() < target * 2, "{} bytes", xml.len());
+    }
+
+    #[test]
+    fn test_run_measures_every_operation() {
+        let measurements = run(16 * 1024, 1).unwrap();
+        let operations: Vec<_> = measurements.iter().map(|m| m.operation).collect();
+        assert_eq!(operations, [Operation::Build, Operation::Canonicalize, Operation::RoundTrip]);
+        assert!(measurements.iter().all(|m| m.bytes > 0 && m.mb_per_sec() > 0.0));
+    }
+}
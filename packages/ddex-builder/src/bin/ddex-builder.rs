@@ -3,19 +3,25 @@
 //! This CLI provides tools for building, converting, validating, and comparing DDEX XML files
 //! with deterministic output and support for various partner presets.
 
+use clap::builder::{PossibleValue, PossibleValuesParser};
 use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 use clap_complete::{generate, Generator, Shell};
 use console::style;
+use ddex_builder::extension_schema::ExtensionSchemaValidator;
 use ddex_builder::presets::{DdexVersion, MessageProfile};
 use ddex_builder::*;
 use indicatif::{ProgressBar, ProgressStyle};
+use once_cell::sync::Lazy;
 use rayon::prelude::*;
 use serde_json::Value as JsonValue;
 use indexmap::IndexMap;
+use notify::{RecursiveMode, Watcher};
 use std::fs;
 use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
 use std::process;
+use std::sync::mpsc;
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(
@@ -42,25 +48,57 @@ struct Cli {
     #[arg(long, global = true, value_enum, default_value_t = ColorChoice::Auto)]
     color: ColorChoice,
 
-    /// Path to configuration file
+    /// Path to configuration file (default: $DDEX_BUILDER_CONFIG, then ./ddex-builder.toml)
     #[arg(long, global = true)]
     config: Option<PathBuf>,
+
+    /// Export tracing spans and counters to this OTLP/HTTP collector (e.g. http://localhost:4318)
+    #[arg(long, global = true, value_name = "URL")]
+    otlp_endpoint: Option<String>,
+
+    /// Check builds with the validation rules of this WASM plugin; repeatable
+    #[arg(long = "plugin", global = true, value_name = "FILE")]
+    plugins: Vec<PathBuf>,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Build DDEX XML from structured data
     Build(BuildCommand),
+    /// Save a build request as a template with `{{name}}` placeholders
+    Template(TemplateCommand),
     /// Convert DDEX XML between versions
     Convert(ConvertCommand),
     /// Compare two DDEX files semantically
     Diff(DiffCommand),
+    /// Apply a patch from `diff --format patch` to DDEX XML
+    Patch(PatchCommand),
+    /// Generate a message taking down releases of an earlier build request
+    Takedown(TakedownCommand),
+    /// Apply new prices to the deals of delivered messages, writing updates for those that change
+    Reprice(RepriceCommand),
     /// Validate DDEX XML files
     Validate(ValidateCommand),
+    /// Rewrite DDEX XML in DB-C14N/1.0 canonical form
+    Canonicalize(CanonicalizeCommand),
+    /// Print the SHA-256 hash of the canonical form of DDEX XML files
+    Hash(HashCommand),
+    /// Check DDEX XML files against lint rules
+    Lint(LintCommand),
+    /// Split a multi-release message into one message per release
+    Split(SplitCommand),
+    /// Merge several messages into one batch message
+    Merge(MergeCommand),
+    /// Summarize releases, tracks, deals and coverage across DDEX XML files
+    Stats(StatsCommand),
+    /// Index the ISRCs, UPCs and GRids of a catalog and report duplicates
+    Index(IndexCommand),
     /// Generate schemas for validation
     Schema(SchemaCommand),
     /// Process multiple files in parallel
     Batch(BatchCommand),
+    /// Upload release packages to a DSP's SFTP server
+    Deliver(DeliverCommand),
     /// Validate determinism guarantees
     Guarantees(GuaranteesCommand),
     /// List and apply partner presets
@@ -71,6 +109,11 @@ enum Commands {
     Server(ServerCommand),
     /// Generate shell completions
     Completions(CompletionsCommand),
+    /// Inspect the configuration
+    Config(ConfigCommand),
+    /// Time build, canonicalize and round-trip on a synthetic catalog
+    #[command(hide = true)]
+    Bench(BenchCommand),
 }
 
 #[derive(Args)]
@@ -79,12 +122,25 @@ struct BuildCommand {
     #[arg(short, long)]
     input: Option<PathBuf>,
 
+    /// Build from the CSV exports of a releases sheet and a tracks sheet;
+    /// --input, if given, supplies the header and deals as a template
+    #[arg(long, num_args = 2, value_names = ["RELEASES", "TRACKS"])]
+    from_csv: Option<Vec<PathBuf>>,
+
+    /// Build from a message template, filling its `{{name}}` placeholders from --vars
+    #[arg(long, conflicts_with_all = ["input", "from_csv"], requires = "vars")]
+    template: Option<PathBuf>,
+
+    /// Variables for --template (JSON/YAML/TOML)
+    #[arg(long, requires = "template")]
+    vars: Option<PathBuf>,
+
     /// Output file path or '-' for stdout
     #[arg(short, long)]
     output: Option<PathBuf>,
 
     /// DDEX version to generate
-    #[arg(long = "ddex-version", value_enum)]
+    #[arg(id = "ddex_version", long = "ddex-version", value_enum)]
     version: Option<DdexVersionArg>,
 
     /// Content profile to use
@@ -114,6 +170,84 @@ struct BuildCommand {
     /// Number of iterations for determinism verification (default: 3)
     #[arg(long, default_value_t = 3)]
     determinism_iterations: usize,
+
+    /// Rebuild whenever the input file changes, printing what changed in the output
+    #[arg(long)]
+    watch: bool,
+
+    /// With --watch, check the files for changes every 300ms instead of
+    /// waiting for file system notifications, which network and
+    /// container-mounted file systems may not deliver
+    #[arg(long, requires = "watch")]
+    poll: bool,
+
+    /// Fill HashSum and FileSize of referenced files, resolved against the input's directory
+    #[arg(long, value_enum)]
+    hash_files: Option<HashAlgorithmArg>,
+}
+
+#[derive(Args)]
+struct TemplateCommand {
+    /// Build request (JSON/YAML/TOML) or '-' for stdin
+    input: PathBuf,
+
+    /// Value to replace by its placeholder, e.g. upc=602445790128; repeatable
+    #[arg(long = "var", value_name = "NAME=VALUE", required = true)]
+    vars: Vec<String>,
+
+    /// Output file path (default: stdout)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct TakedownCommand {
+    /// Build request the releases were delivered with (JSON/YAML/TOML) or '-' for stdin
+    #[arg(short, long)]
+    input: PathBuf,
+
+    /// UPC or release ID of a release to take down; repeatable
+    #[arg(long, required_unless_present = "isrc")]
+    upc: Vec<String>,
+
+    /// ISRC whose releases to take down; repeatable
+    #[arg(long)]
+    isrc: Vec<String>,
+
+    /// End the releases' deals, or purge the releases entirely
+    #[arg(long, value_enum, default_value_t = TakedownScopeArg::Deals)]
+    scope: TakedownScopeArg,
+
+    /// Date the takedown takes effect, YYYY-MM-DD (default: today)
+    #[arg(long)]
+    date: Option<chrono::NaiveDate>,
+
+    /// Partner preset whose DDEX version to use instead of the request's
+    #[arg(long, value_enum)]
+    preset: Option<PresetChoice>,
+
+    /// Output file path (default: stdout)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct RepriceCommand {
+    /// Delivered DDEX XML messages
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+
+    /// Pricing rules (JSON/YAML/TOML): a list of territory_codes, commercial_model_type and price
+    #[arg(long)]
+    rules: PathBuf,
+
+    /// Write the repriced messages instead of update messages
+    #[arg(long)]
+    full: bool,
+
+    /// Directory for the output files (default: current directory)
+    #[arg(short, long)]
+    output_dir: Option<PathBuf>,
 }
 
 #[derive(Args)]
@@ -166,6 +300,40 @@ struct DiffCommand {
     /// Ignore whitespace differences
     #[arg(long)]
     ignore_whitespace: bool,
+
+    /// Ignore a path pattern such as MessageHeader/MessageCreatedDateTime (repeatable)
+    #[arg(long = "ignore-path", value_name = "PATTERN")]
+    ignore_paths: Vec<String>,
+
+    /// Ignore a namespace by prefix or URI, e.g. partner extensions (repeatable)
+    #[arg(long = "ignore-namespace", value_name = "NAMESPACE")]
+    ignore_namespaces: Vec<String>,
+
+    /// Ignore attribute-only changes
+    #[arg(long)]
+    ignore_attributes: bool,
+}
+
+#[derive(Args)]
+struct PatchCommand {
+    /// DDEX XML file to patch
+    file: PathBuf,
+
+    /// Patch file produced by `diff --format patch`
+    #[arg(short, long)]
+    patch: PathBuf,
+
+    /// Output file path (default: stdout)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Report what would change without writing output
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Write the patched file even if some operations conflict
+    #[arg(long)]
+    allow_conflicts: bool,
 }
 
 #[derive(Args)]
@@ -174,12 +342,14 @@ struct ValidateCommand {
     files: Vec<PathBuf>,
 
     /// DDEX version for validation
-    #[arg(long = "ddex-version", value_enum)]
+    #[arg(id = "ddex_version", long = "ddex-version", value_enum)]
     version: Option<DdexVersionArg>,
 
-    /// Content profile for validation
-    #[arg(short, long)]
-    profile: Option<String>,
+    /// Score each release against a release profile (AudioAlbum,
+    /// AudioSingle, VideoAlbum, VideoSingle or Mixed) and list the required
+    /// elements it is missing; takes DDEX XML and build request files
+    #[arg(short, long, value_parser = parse_message_profile)]
+    profile: Option<MessageProfile>,
 
     /// Partner preset for validation
     #[arg(long, value_enum)]
@@ -196,13 +366,225 @@ struct ValidateCommand {
     /// Stop at first validation error
     #[arg(long)]
     fail_fast: bool,
+
+    /// Also check the structure of DDEX XML against the condensed ERN
+    /// content models bundled with ddex-core: root element and namespace,
+    /// the children of the main composites with their cardinality, and
+    /// identifier, date and code formats. This is a partial check, not XSD
+    /// validation: MessageHeader children and element order are not checked
+    #[arg(long, alias = "xsd")]
+    structural: bool,
+
+    /// Also check extension blocks (spotify:, apple:, ...) against the
+    /// `.xsd` files of this schema cache directory
+    #[arg(long, value_name = "DIR")]
+    extension_schemas: Option<PathBuf>,
+
+    /// Revalidate whenever one of the files changes
+    #[arg(long)]
+    watch: bool,
+
+    /// With --watch, check the files for changes every 300ms instead of
+    /// waiting for file system notifications
+    #[arg(long, requires = "watch")]
+    poll: bool,
+
+    /// Check build request files (JSON, YAML or TOML) against every rule of
+    /// the preset and explain each failure
+    #[arg(long, requires = "preset")]
+    explain: bool,
+}
+
+#[derive(Args)]
+struct CanonicalizeCommand {
+    /// DDEX XML file or '-' for stdin
+    input: PathBuf,
+
+    /// Output file path (default: stdout)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Only check that the input is already canonical
+    #[arg(long)]
+    check: bool,
+}
+
+#[derive(Args)]
+struct HashCommand {
+    /// DDEX XML files or '-' for stdin
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+
+    /// Expected hash; exits non-zero if any file hashes differently
+    #[arg(long)]
+    expect: Option<String>,
+}
+
+#[derive(Args)]
+struct SplitCommand {
+    /// NewReleaseMessage to split or '-' for stdin
+    input: PathBuf,
+
+    /// What each output message holds
+    #[arg(long, value_enum, default_value_t = SplitBy::Release)]
+    by: SplitBy,
+
+    /// Directory for the output files (default: current directory)
+    #[arg(short, long)]
+    output_dir: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct DeliverCommand {
+    /// NewReleaseMessages to deliver, one release folder each
+    #[arg(required = true)]
+    messages: Vec<PathBuf>,
+
+    /// Folder of audio and artwork files, once per message in the same order
+    #[arg(short, long)]
+    resources: Vec<PathBuf>,
+
+    /// SFTP server
+    #[arg(long)]
+    host: String,
+
+    /// SFTP port
+    #[arg(long, default_value_t = 22)]
+    port: u16,
+
+    /// Login name
+    #[arg(short, long, env = "DDEX_SFTP_USER")]
+    user: String,
+
+    /// Private key file (default: the ssh-agent, or --password)
+    #[arg(short = 'k', long)]
+    key: Option<PathBuf>,
+
+    /// Passphrase of the private key
+    #[arg(long, env = "DDEX_SFTP_KEY_PASSPHRASE", hide_env_values = true)]
+    key_passphrase: Option<String>,
+
+    /// Password, for servers without key authentication
+    #[arg(long, env = "DDEX_SFTP_PASSWORD", hide_env_values = true)]
+    password: Option<String>,
+
+    /// Folder on the server the batch is created in
+    #[arg(long, default_value = ".")]
+    remote_dir: String,
+
+    /// known_hosts file to check the server key against (default:
+    /// ~/.ssh/known_hosts)
+    #[arg(long)]
+    known_hosts: Option<PathBuf>,
+
+    /// Batch folder name; reuse the one of an interrupted delivery to resume it
+    #[arg(long)]
+    batch_id: Option<String>,
+
+    /// Upload bandwidth limit in bytes per second, e.g. 500K or 5M
+    #[arg(long, value_parser = parse_rate)]
+    limit_rate: Option<u64>,
+
+    /// Write the delivery receipt (JSON) to this file instead of stdout
+    #[arg(long)]
+    receipt: Option<PathBuf>,
+
+    /// List the files that would be uploaded without connecting
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Args)]
+struct MergeCommand {
+    /// NewReleaseMessages to merge; the first provides the header
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+
+    /// Output file path (default: stdout)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// MessageId of the merged message (default: derived from the merged IDs)
+    #[arg(long)]
+    message_id: Option<String>,
+}
+
+#[derive(Args)]
+struct StatsCommand {
+    /// DDEX XML files to summarize
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+
+    /// Output format; CSV has one row per file
+    #[arg(short, long, value_enum, default_value_t = StatsFormat::Human)]
+    format: StatsFormat,
+
+    /// Output file path (default: stdout)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct IndexCommand {
+    /// DDEX XML files making up the catalog
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+
+    /// Incoming files to check against the catalog instead of reporting its duplicates
+    #[arg(long)]
+    check: Vec<PathBuf>,
+
+    /// Show every occurrence of an ISRC, UPC or GRid
+    #[arg(long)]
+    lookup: Option<String>,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = IndexFormat::Human)]
+    format: IndexFormat,
+
+    /// Output file path (default: stdout)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct LintCommand {
+    /// DDEX XML files to lint
+    #[arg(required_unless_present = "list_rules")]
+    files: Vec<PathBuf>,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = LintFormat::Human)]
+    format: LintFormat,
+
+    /// Output file path (default: stdout)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Apply safe autofixes, rewriting the files in place
+    #[arg(long)]
+    fix: bool,
+
+    /// List the rules with their configured severities
+    #[arg(long)]
+    list_rules: bool,
 }
 
 #[derive(Args)]
+#[command(subcommand_negates_reqs = true, args_conflicts_with_subcommands = true)]
 struct SchemaCommand {
+    /// Schema operation; generates a schema when omitted
+    #[command(subcommand)]
+    operation: Option<SchemaOperation>,
+
     /// DDEX version for schema generation
-    #[arg(long = "ddex-version", value_enum)]
-    version: DdexVersionArg,
+    #[arg(id = "ddex_version", long = "ddex-version", value_name = "VERSION", value_enum, required_unless_present = "target")]
+    version: Option<DdexVersionArg>,
+
+    /// Generate the schema of a builder or parser payload instead of a DDEX
+    /// message; repeat for TypeScript or Python typings of several payloads
+    #[arg(long, value_enum, conflicts_with = "ddex_version")]
+    target: Vec<SchemaTargetArg>,
 
     /// Content profile
     #[arg(short, long)]
@@ -221,23 +603,66 @@ struct SchemaCommand {
     with_docs: bool,
 }
 
+#[derive(Subcommand)]
+enum SchemaOperation {
+    /// Fail when typings shipped with a binding package differ from the Rust models
+    Diff(SchemaDiffCommand),
+}
+
+#[derive(Args)]
+struct SchemaDiffCommand {
+    /// Shipped typings file
+    file: PathBuf,
+
+    /// Language of the file
+    #[arg(short, long, value_enum)]
+    format: SchemaFormat,
+
+    /// Payloads the file covers
+    #[arg(long = "target", value_enum, required = true)]
+    targets: Vec<SchemaTargetArg>,
+}
+
 #[derive(Args)]
 struct BatchCommand {
-    /// Configuration file for batch processing
-    #[arg(short, long)]
-    config: PathBuf,
+    /// Batch file (YAML) listing the tasks to run
+    batch_file: PathBuf,
 
-    /// Number of worker threads
-    #[arg(short, long, default_value_t = num_cpus::get())]
-    workers: usize,
+    /// Number of worker threads (default: one per CPU)
+    #[arg(short, long)]
+    workers: Option<usize>,
 
-    /// Continue processing on errors
-    #[arg(long)]
+    /// Keep starting new jobs after a failure, whatever the config file says
+    #[arg(long, overrides_with = "no_continue_on_error")]
     continue_on_error: bool,
 
+    /// Stop starting new jobs after the first failure, whatever the config
+    /// file says
+    #[arg(long, overrides_with = "continue_on_error")]
+    no_continue_on_error: bool,
+
     /// Generate summary report
     #[arg(long)]
     report: Option<PathBuf>,
+
+    /// Append a JSON line per finished job to this file
+    #[arg(long)]
+    log: Option<PathBuf>,
+
+    /// Skip jobs marked successful in the existing report and progress log
+    #[arg(long)]
+    resume: bool,
+}
+
+impl BatchCommand {
+    /// Whether to continue after a failure, if given on the command line
+    fn continue_on_error(&self) -> Option<bool> {
+        match (self.continue_on_error, self.no_continue_on_error) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Args)]
@@ -254,7 +679,7 @@ struct GuaranteesCommand {
     #[arg(short, long)]
     output: Option<PathBuf>,
 
-    /// Run comprehensive stress tests
+    /// Also build across threads with shuffled map ordering and report where outputs diverge
     #[arg(long)]
     thorough: bool,
 
@@ -271,6 +696,26 @@ struct GuaranteesCommand {
     include_evidence: bool,
 }
 
+#[derive(Args)]
+struct ConfigCommand {
+    /// Configuration operation
+    #[command(subcommand)]
+    operation: ConfigOperation,
+}
+
+#[derive(Subcommand)]
+enum ConfigOperation {
+    /// Print the effective configuration after environment overrides
+    Show(ConfigShowCommand),
+}
+
+#[derive(Args)]
+struct ConfigShowCommand {
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = ConfigShowFormat::Toml)]
+    format: ConfigShowFormat,
+}
+
 #[derive(Args)]
 struct PresetCommand {
     /// Preset operation
@@ -291,12 +736,12 @@ enum PresetOperation {
 #[derive(Args)]
 struct PresetListCommand {
     /// Filter by DDEX version
-    #[arg(long, value_enum)]
+    #[arg(id = "ddex_version", long = "ddex-version", value_enum)]
     version: Option<DdexVersionArg>,
 
     /// Filter by partner
-    #[arg(long, value_enum)]
-    partner: Option<PresetChoice>,
+    #[arg(long, ignore_case = true, value_parser = PossibleValuesParser::new(preset_partners()))]
+    partner: Option<String>,
 
     /// Output format
     #[arg(short, long, value_enum, default_value_t = PresetListFormat::Human)]
@@ -384,6 +829,12 @@ struct WatchCommand {
     /// Exclude patterns (glob syntax)
     #[arg(long)]
     exclude: Vec<String>,
+
+    /// Check for changes every 300ms instead of waiting for file system
+    /// notifications, which network and container-mounted file systems may
+    /// not deliver
+    #[arg(long)]
+    poll: bool,
 }
 
 #[derive(Args)]
@@ -440,6 +891,21 @@ struct CompletionsCommand {
     output: Option<PathBuf>,
 }
 
+#[derive(Args)]
+struct BenchCommand {
+    /// Approximate size of the generated catalog, in MB
+    #[arg(long, default_value_t = 1.0)]
+    size: f64,
+
+    /// Runs of each operation; the fastest is reported
+    #[arg(long, default_value_t = 5)]
+    iterations: usize,
+
+    /// Print the measurements as JSON
+    #[arg(long)]
+    json: bool,
+}
+
 #[derive(ValueEnum, Clone, Debug)]
 enum ColorChoice {
     Auto,
@@ -447,6 +913,24 @@ enum ColorChoice {
     Never,
 }
 
+#[derive(ValueEnum, Clone, Debug)]
+enum ConfigShowFormat {
+    Toml,
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum HashAlgorithmArg {
+    Md5,
+    Sha256,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum TakedownScopeArg {
+    Deals,
+    Purge,
+}
+
 #[derive(ValueEnum, Clone, Copy, Debug)]
 enum DdexVersionArg {
     #[value(name = "3.8.2")]
@@ -461,22 +945,47 @@ enum DdexVersionArg {
     V44,
 }
 
-#[derive(ValueEnum, Clone, Copy, Debug)]
-enum PresetChoice {
-    /// Generic audio album preset (DDEX-compliant baseline)
-    AudioAlbum,
-    /// Generic audio single preset (DDEX-compliant baseline)
-    AudioSingle,
-    /// Generic video single preset (DDEX-compliant baseline)
-    VideoSingle,
-    /// Generic compilation preset (DDEX-compliant baseline)
-    Compilation,
-    /// YouTube album preset (based on public documentation)
-    YoutubeAlbum,
-    /// YouTube video preset (based on public documentation)
-    YoutubeVideo,
-    /// YouTube single preset (based on public documentation)
-    YoutubeSingle,
+/// A built-in preset together with the partner it belongs to
+#[derive(Debug)]
+struct PresetEntry {
+    partner: &'static str,
+    preset: PartnerPreset,
+}
+
+/// Built-in presets followed by those found in `DDEX_BUILDER_PRESET_PATH`
+static PRESET_ENTRIES: Lazy<Vec<PresetEntry>> = Lazy::new(|| {
+    let custom = presets::external::load_preset_path().unwrap_or_else(|e| {
+        eprintln!("{} {}", style("✗").red(), e);
+        process::exit(1);
+    });
+    let mut partners = presets::presets_by_partner();
+    partners.insert("Custom", custom);
+
+    let mut entries: Vec<PresetEntry> = Vec::new();
+    for (partner, presets) in partners {
+        for preset in presets.into_values() {
+            // A custom preset may shadow a built-in one of the same name
+            entries.retain(|entry| entry.preset.name != preset.name);
+            entries.push(PresetEntry { partner, preset });
+        }
+    }
+    entries
+});
+
+static PRESET_CHOICES: Lazy<Vec<PresetChoice>> = Lazy::new(|| PRESET_ENTRIES.iter().map(PresetChoice).collect());
+
+/// Preset selectable on the command line, one value per entry in `presets::all_presets()`
+#[derive(Clone, Copy, Debug)]
+struct PresetChoice(&'static PresetEntry);
+
+impl ValueEnum for PresetChoice {
+    fn value_variants<'a>() -> &'a [Self] {
+        &PRESET_CHOICES
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(PossibleValue::new(self.0.preset.name.as_str()).help(self.0.preset.description.as_str()))
+    }
 }
 
 #[derive(ValueEnum, Clone, Debug)]
@@ -491,6 +1000,9 @@ enum DiffFormat {
     Human,
     Json,
     Update,
+    Html,
+    Markdown,
+    Patch,
 }
 
 #[derive(ValueEnum, Clone, Debug)]
@@ -500,34 +1012,78 @@ enum ValidateFormat {
     Junit,
 }
 
-#[derive(ValueEnum, Clone, Debug)]
-enum SchemaFormat {
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum StatsFormat {
+    Human,
     Json,
-    Typescript,
-    Python,
+    Csv,
 }
 
-#[derive(ValueEnum, Clone, Debug)]
-enum GuaranteeFormat {
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum IndexFormat {
     Human,
     Json,
-    Yaml,
 }
 
-#[derive(ValueEnum, Clone, Debug)]
-enum PresetListFormat {
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum SplitBy {
+    Release,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum LintFormat {
     Human,
     Json,
-    Table,
+    Sarif,
 }
 
-#[derive(ValueEnum, Clone, Debug)]
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum SchemaTargetArg {
+    BuildRequest,
+    FlattenedMessage,
+    GraphMessage,
+    Preset,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+enum SchemaFormat {
+    Json,
+    Typescript,
+    Python,
+    /// Pydantic v2 models; --target payloads only
+    Pydantic,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+enum GuaranteeFormat {
+    Human,
+    Json,
+    Yaml,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+enum PresetListFormat {
+    Human,
+    Json,
+    Table,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
 enum PresetShowFormat {
     Human,
     Json,
     Yaml,
 }
 
+impl From<HashAlgorithmArg> for hash_sum::HashSumAlgorithm {
+    fn from(algorithm: HashAlgorithmArg) -> Self {
+        match algorithm {
+            HashAlgorithmArg::Md5 => hash_sum::HashSumAlgorithm::Md5,
+            HashAlgorithmArg::Sha256 => hash_sum::HashSumAlgorithm::Sha256,
+        }
+    }
+}
+
 impl From<DdexVersionArg> for DdexVersion {
     fn from(version: DdexVersionArg) -> Self {
         match version {
@@ -535,7 +1091,18 @@ impl From<DdexVersionArg> for DdexVersion {
             DdexVersionArg::V41 => DdexVersion::Ern41,
             DdexVersionArg::V42 => DdexVersion::Ern42,
             DdexVersionArg::V43 => DdexVersion::Ern43,
-            DdexVersionArg::V44 => DdexVersion::Ern43, // Map V44 to Ern43 since Ern44 doesn't exist yet
+            DdexVersionArg::V44 => DdexVersion::Ern44,
+        }
+    }
+}
+
+impl From<SchemaTargetArg> for schema::SchemaTarget {
+    fn from(target: SchemaTargetArg) -> Self {
+        match target {
+            SchemaTargetArg::BuildRequest => schema::SchemaTarget::BuildRequest,
+            SchemaTargetArg::FlattenedMessage => schema::SchemaTarget::FlattenedMessage,
+            SchemaTargetArg::GraphMessage => schema::SchemaTarget::GraphMessage,
+            SchemaTargetArg::Preset => schema::SchemaTarget::Preset,
         }
     }
 }
@@ -543,28 +1110,62 @@ impl From<DdexVersionArg> for DdexVersion {
 fn main() {
     let cli = Cli::parse();
 
-    // Setup logging based on verbosity
-    setup_logging(cli.verbose, cli.quiet);
+    // Setup logging based on verbosity, or export to a collector and log alongside
+    let telemetry = match cli.otlp_endpoint.as_deref() {
+        Some(endpoint) => match setup_telemetry(endpoint, cli.verbose, cli.quiet) {
+            Ok(guard) => Some(guard),
+            Err(e) => {
+                eprintln!("{} {}", style("Error:").red().bold(), e);
+                process::exit(1);
+            }
+        },
+        None => {
+            setup_logging(cli.verbose, cli.quiet);
+            None
+        }
+    };
 
     // Setup color output
     setup_colors(cli.color);
 
-    // Load configuration if specified
-    let config = cli.config.as_ref().map(|p| load_config(p)).unwrap_or_default();
+    // Load the configuration file and environment overrides
+    let mut config = match load_config(cli.config.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{} {}", style("Error:").red().bold(), e);
+            process::exit(1);
+        }
+    };
+    config.plugins.extend(cli.plugins);
 
     let result = match cli.command {
         Commands::Build(cmd) => handle_build_command(cmd, &config),
+        Commands::Template(cmd) => handle_template_command(cmd, &config),
         Commands::Convert(cmd) => handle_convert_command(cmd, &config),
         Commands::Diff(cmd) => handle_diff_command(cmd, &config),
+        Commands::Patch(cmd) => handle_patch_command(cmd, &config),
+        Commands::Takedown(cmd) => handle_takedown_command(cmd, &config),
+        Commands::Reprice(cmd) => handle_reprice_command(cmd, &config),
         Commands::Validate(cmd) => handle_validate_command(cmd, &config),
+        Commands::Canonicalize(cmd) => handle_canonicalize_command(cmd, &config),
+        Commands::Hash(cmd) => handle_hash_command(cmd, &config),
+        Commands::Lint(cmd) => handle_lint_command(cmd, &config),
+        Commands::Split(cmd) => handle_split_command(cmd, &config),
+        Commands::Merge(cmd) => handle_merge_command(cmd, &config),
+        Commands::Stats(cmd) => handle_stats_command(cmd, &config),
+        Commands::Index(cmd) => handle_index_command(cmd, &config),
         Commands::Schema(cmd) => handle_schema_command(cmd, &config),
         Commands::Batch(cmd) => handle_batch_command(cmd, &config),
+        Commands::Deliver(cmd) => handle_deliver_command(cmd),
         Commands::Guarantees(cmd) => handle_guarantees_command(cmd, &config),
         Commands::Preset(cmd) => handle_preset_command(cmd, &config),
         Commands::Watch(cmd) => handle_watch_command(cmd, &config),
         Commands::Server(cmd) => handle_server_command(cmd, &config),
         Commands::Completions(cmd) => handle_completions_command(cmd),
+        Commands::Config(cmd) => handle_config_command(cmd, &config),
+        Commands::Bench(cmd) => handle_bench_command(cmd),
     };
+    drop(telemetry);
 
     if let Err(e) = result {
         eprintln!("{} {}", style("Error:").red().bold(), e);
@@ -577,17 +1178,34 @@ fn setup_logging(verbosity: u8, quiet: bool) {
         return;
     }
 
-    let level = match verbosity {
+    tracing_subscriber::fmt()
+        .with_max_level(log_level(verbosity))
+        .with_target(false)
+        .init();
+}
+
+fn log_level(verbosity: u8) -> tracing::Level {
+    match verbosity {
         0 => tracing::Level::WARN,
         1 => tracing::Level::INFO,
         2 => tracing::Level::DEBUG,
         _ => tracing::Level::TRACE,
+    }
+}
+
+#[cfg(feature = "otel")]
+fn setup_telemetry(endpoint: &str, verbosity: u8, quiet: bool) -> Result<ddex_core::telemetry::TelemetryGuard, Box<dyn std::error::Error>> {
+    let config = ddex_core::telemetry::OtlpConfig {
+        endpoint: endpoint.to_string(),
+        service_name: "ddex-builder".to_string(),
+        log_level: (!quiet).then(|| log_level(verbosity)),
     };
+    Ok(ddex_core::telemetry::init_otlp(&config)?)
+}
 
-    tracing_subscriber::fmt()
-        .with_max_level(level)
-        .with_target(false)
-        .init();
+#[cfg(not(feature = "otel"))]
+fn setup_telemetry(endpoint: &str, _verbosity: u8, _quiet: bool) -> Result<(), Box<dyn std::error::Error>> {
+    Err(format!("Exporting telemetry to {} is not supported by this build (enable the `otel` feature)", endpoint).into())
 }
 
 fn setup_colors(color_choice: ColorChoice) {
@@ -606,31 +1224,197 @@ fn setup_colors(color_choice: ColorChoice) {
     }
 }
 
-fn load_config(_path: &Path) -> ConfigFile {
-    // TODO: Implement configuration file loading
-    ConfigFile::default()
-}
+/// Configuration file looked up in the working directory
+const CONFIG_FILE_NAME: &str = "ddex-builder.toml";
 
-#[derive(Default)]
+/// Settings from `ddex-builder.toml`, overridden by `DDEX_BUILDER_*`
+/// environment variables; command-line flags override both
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
 struct ConfigFile {
-    // Configuration options that can be loaded from file
+    /// File the settings were read from
+    #[serde(skip)]
+    source: Option<PathBuf>,
+    /// Preset applied when `--preset` is not given
+    preset: Option<String>,
+    /// DDEX version built when `--ddex-version` is not given
+    version: Option<String>,
+    /// Directory built files are written to when `--output` is not given
+    output_dir: Option<PathBuf>,
+    /// Overrides of the preset's determinism settings, by `DeterminismConfig` field
+    determinism: Option<toml::Table>,
+    /// Schema cache directory extension blocks are checked against
+    extension_schema_dir: Option<PathBuf>,
+    /// WASM validation plugins every build is checked with
+    plugins: Vec<PathBuf>,
+    /// Severities of lint rules, by rule ID
+    lint: lint::LintConfig,
+    /// Defaults for the `batch` command
+    batch: BatchDefaults,
 }
 
-fn handle_build_command(cmd: BuildCommand, _config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
-    let input_data = read_input_data(&cmd.input, cmd.format)?;
-    
-    // Create builder with optional preset
-    let mut builder = Builder::new();
-    
-    if let Some(preset) = cmd.preset {
-        let preset_name = preset_to_string(&preset);
-        builder.apply_preset(&preset_name, false)
-            .map_err(|e| format!("Failed to apply preset '{}': {}", preset_name, e))?;
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct BatchDefaults {
+    workers: Option<usize>,
+    continue_on_error: Option<bool>,
+}
+
+impl ConfigFile {
+    /// Apply `DDEX_BUILDER_*` environment variables
+    fn apply_env(&mut self, var: impl Fn(&str) -> Option<String>) -> Result<(), String> {
+        if let Some(preset) = var("DDEX_BUILDER_PRESET") {
+            self.preset = Some(preset);
+        }
+        if let Some(version) = var("DDEX_BUILDER_VERSION") {
+            self.version = Some(version);
+        }
+        if let Some(output_dir) = var("DDEX_BUILDER_OUTPUT_DIR") {
+            self.output_dir = Some(PathBuf::from(output_dir));
+        }
+        if let Some(dir) = var("DDEX_BUILDER_EXTENSION_SCHEMA_DIR") {
+            self.extension_schema_dir = Some(PathBuf::from(dir));
+        }
+        if let Some(workers) = var("DDEX_BUILDER_WORKERS") {
+            let workers = workers.parse().map_err(|_| format!("DDEX_BUILDER_WORKERS must be a number, got '{}'", workers))?;
+            self.batch.workers = Some(workers);
+        }
+        if let Some(flag) = var("DDEX_BUILDER_CONTINUE_ON_ERROR") {
+            self.batch.continue_on_error = Some(match flag.to_ascii_lowercase().as_str() {
+                "1" | "true" | "yes" => true,
+                "0" | "false" | "no" => false,
+                _ => return Err(format!("DDEX_BUILDER_CONTINUE_ON_ERROR must be true or false, got '{}'", flag)),
+            });
+        }
+        Ok(())
+    }
+
+    fn version(&self) -> Result<Option<DdexVersion>, String> {
+        self.version
+            .as_deref()
+            .map(|v| parse_version(v).ok_or_else(|| format!("Unsupported DDEX version '{}' in configuration", v)))
+            .transpose()
+    }
+
+    /// `base` with the configured determinism overrides applied
+    fn determinism(&self, base: &DeterminismConfig) -> Result<DeterminismConfig, String> {
+        let Some(overrides) = &self.determinism else { return Ok(base.clone()) };
+        let mut merged = toml::Table::try_from(base).map_err(|e| e.to_string())?;
+        merged.extend(overrides.clone());
+        toml::Value::Table(merged)
+            .try_into()
+            .map_err(|e| format!("Invalid [determinism] configuration: {}", e))
+    }
+
+    /// Where to write the output built from `input` when `--output` is not given
+    fn output_path(&self, input: &Option<PathBuf>) -> Option<PathBuf> {
+        let input = input.as_ref().filter(|p| p.to_str() != Some("-"))?;
+        let stem = input.file_stem()?;
+        Some(self.output_dir.as_ref()?.join(stem).with_extension("xml"))
+    }
+}
+
+/// Load `path`, else `$DDEX_BUILDER_CONFIG`, else `./ddex-builder.toml` when
+/// present, then apply environment overrides
+fn load_config(path: Option<&Path>) -> Result<ConfigFile, String> {
+    let path = path
+        .map(Path::to_path_buf)
+        .or_else(|| std::env::var_os("DDEX_BUILDER_CONFIG").map(PathBuf::from))
+        .or_else(|| Some(PathBuf::from(CONFIG_FILE_NAME)).filter(|p| p.is_file()));
+
+    let mut config = match path {
+        Some(path) => {
+            let content = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read configuration {}: {}", path.display(), e))?;
+            let mut config: ConfigFile = toml::from_str(&content)
+                .map_err(|e| format!("Invalid configuration {}: {}", path.display(), e))?;
+            config.source = Some(path);
+            config
+        }
+        None => ConfigFile::default(),
+    };
+    config.apply_env(|name| std::env::var(name).ok())?;
+
+    config.version()?;
+    config.determinism(&DeterminismConfig::default())?;
+    config.lint.validate()?;
+    Ok(config)
+}
+
+fn handle_config_command(cmd: ConfigCommand, config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
+    match cmd.operation {
+        ConfigOperation::Show(show_cmd) => {
+            if !is_quiet() {
+                match &config.source {
+                    Some(path) => eprintln!("# Loaded from {}", path.display()),
+                    None => eprintln!("# No configuration file found"),
+                }
+            }
+            match show_cmd.format {
+                ConfigShowFormat::Toml => print!("{}", toml::to_string_pretty(config)?),
+                ConfigShowFormat::Json => println!("{}", serde_json::to_string_pretty(config)?),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn handle_build_command(cmd: BuildCommand, config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
+    if !cmd.watch {
+        return run_build(&cmd, config).map(|_| ());
+    }
+
+    let mut inputs = cmd.from_csv.clone().unwrap_or_default();
+    inputs.extend(cmd.input.clone());
+    inputs.extend(cmd.template.clone());
+    inputs.extend(cmd.vars.clone());
+    if inputs.is_empty() || inputs.iter().any(|path| path.to_str() == Some("-")) {
+        return Err("--watch needs an input file".into());
+    }
+    if cmd.output.clone().or_else(|| config.output_path(&cmd.input)).is_none_or(|path| path.to_str() == Some("-")) {
+        return Err("--watch needs an output file".into());
     }
 
-    if let Some(version) = cmd.version {
-        builder.with_version(version.into());
+    let mut previous: Option<String> = None;
+    watch_files(&inputs, cmd.poll, || match run_build(&cmd, config) {
+        Ok(xml) => {
+            if let Some(previous) = &previous {
+                match output_changes(previous, &xml) {
+                    Ok(changes) => print!("{}", changes),
+                    Err(e) => eprintln!("{} Could not diff the output: {}", style("!").yellow(), e),
+                }
+            }
+            previous = Some(xml);
+        }
+        Err(e) => eprintln!("{} {}", style("✗").red(), e),
+    })
+}
+
+/// Build once, returning the XML that was written
+fn run_build(cmd: &BuildCommand, config: &ConfigFile) -> Result<String, Box<dyn std::error::Error>> {
+    let mut input_data = match (&cmd.from_csv, &cmd.template) {
+        (Some(sheets), _) => request_from_csv(&sheets[0], &sheets[1], &cmd.input, cmd.format.clone())?,
+        (None, Some(template)) => request_from_template(template, cmd.vars.as_deref())?,
+        (None, None) => read_input_data(&cmd.input, cmd.format.clone())?,
+    };
+    if let (Some(profile), Some(request)) = (&cmd.profile, input_data.as_object_mut()) {
+        request.insert("profile".to_string(), JsonValue::String(profile.clone()));
+    }
+    if let Some(algorithm) = cmd.hash_files {
+        let mut request = parse_build_request(&input_data)?;
+        let options = hash_sum::HashSumOptions {
+            algorithm: algorithm.into(),
+            base_dir: cmd.input.as_deref().and_then(Path::parent).map(Path::to_path_buf),
+            parallel: true,
+            overwrite: false,
+        };
+        hash_sum::fill_hash_sums(&mut request, &options)?;
+        input_data = serde_json::to_value(&request)?;
     }
+    
+    let preset_name = cmd.preset.as_ref().map(preset_to_string).or_else(|| config.preset.clone());
+    let version = cmd.version.map(DdexVersion::from).or(config.version()?);
+    let builder = configured_builder(preset_name.as_deref(), version, config)?;
 
     // Validate input if requested
     if cmd.validate {
@@ -646,22 +1430,168 @@ fn handle_build_command(cmd: BuildCommand, _config: &ConfigFile) -> Result<(), B
     }
 
     // Write output
-    write_output(&xml_output, &cmd.output)?;
+    let output = cmd.output.clone().or_else(|| config.output_path(&cmd.input));
+    if let Some(path) = &output {
+        create_parent_dir(path)?;
+    }
+    write_output(&xml_output, &output)?;
 
     if !is_quiet() {
         println!("{} DDEX XML built successfully", style("✓").green());
-        if let Some(preset) = cmd.preset {
-            println!("  Preset: {}", preset_to_string(&preset));
+        if let Some(preset) = &preset_name {
+            println!("  Preset: {}", preset);
         }
-        if let Some(version) = cmd.version {
-            println!("  Version: {:?}", version);
+        if let Some(version) = version {
+            println!("  Version: {}", version);
         }
         if cmd.verify_determinism {
             println!("  {} Determinism verified with {} iterations", style("✓").green(), cmd.determinism_iterations);
         }
     }
 
-    Ok(())
+    Ok(xml_output)
+}
+
+/// One line per semantic change between two builds of the same message
+fn output_changes(previous: &str, current: &str) -> Result<String, BuildError> {
+    let changeset = diff::DiffEngine::new().diff_xml(previous, current)?;
+    if !changeset.has_changes() {
+        return Ok("  No changes in the output\n".to_string());
+    }
+    Ok(changeset
+        .changes
+        .iter()
+        .map(|change| format!("  {}: {}\n", change.path, change.description))
+        .collect())
+}
+
+/// How long watched files must stay quiet before a change is acted on, and
+/// how often they are checked with `--poll`
+const WATCH_INTERVAL: Duration = Duration::from_millis(300);
+
+type WatchEvents = mpsc::Receiver<notify::Result<notify::Event>>;
+
+/// Start watching `roots`, returning the watcher (which stops when dropped)
+/// and its events
+///
+/// Uses the platform's file system notifications unless `poll` is set.
+/// Network and container-mounted file systems often deliver none, so polling
+/// modification times every `WATCH_INTERVAL` is the fallback there.
+fn start_watching(
+    roots: &[(PathBuf, RecursiveMode)],
+    poll: bool,
+) -> notify::Result<(Box<dyn Watcher>, WatchEvents)> {
+    let (tx, events) = mpsc::channel();
+    let mut watcher: Box<dyn Watcher> = if poll {
+        let config = notify::Config::default().with_poll_interval(WATCH_INTERVAL);
+        Box::new(notify::PollWatcher::new(tx, config)?)
+    } else {
+        Box::new(notify::recommended_watcher(tx)?)
+    };
+    for (root, mode) in roots {
+        watcher.watch(root, *mode)?;
+    }
+    Ok((watcher, events))
+}
+
+/// Wait for the next change to a file `relevant` accepts, returning the
+/// changed files once no further events arrive for `settle`
+fn next_changes(
+    events: &WatchEvents,
+    settle: Duration,
+    relevant: &dyn Fn(&Path) -> bool,
+) -> Result<Vec<PathBuf>, mpsc::RecvError> {
+    let mut changed: Vec<PathBuf> = Vec::new();
+    while changed.is_empty() {
+        let mut event = Some(events.recv()?);
+        while let Some(next) = event {
+            match next {
+                Ok(next) if !matches!(next.kind, notify::EventKind::Access(_)) => {
+                    for path in next.paths {
+                        if relevant(&path) && !changed.contains(&path) {
+                            changed.push(path);
+                        }
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("{} Watch error: {}", style("!").yellow(), e),
+            }
+            // Editors and spreadsheet exports often save in several writes
+            event = events.recv_timeout(settle).ok();
+        }
+    }
+
+    if !is_quiet() {
+        eprintln!("\n{} [{}] Change detected", style("→").cyan(), chrono::Local::now().format("%H:%M:%S"));
+    }
+    Ok(changed)
+}
+
+/// `path` with its directory made absolute, as it appears in watch events
+fn absolute_path(path: &Path) -> io::Result<PathBuf> {
+    let name = path.file_name().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, format!("{} is not a file", path.display()))
+    })?;
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    Ok(dir.canonicalize()?.join(name))
+}
+
+/// Run `run` now and again after every change to `paths`, until interrupted
+///
+/// The files' directories are watched rather than the files themselves, so
+/// editors that save by replacing the file are noticed too.
+fn watch_files(paths: &[PathBuf], poll: bool, mut run: impl FnMut()) -> Result<(), Box<dyn std::error::Error>> {
+    let files = paths.iter().map(|path| absolute_path(path)).collect::<io::Result<Vec<_>>>()?;
+    let mut dirs: Vec<(PathBuf, RecursiveMode)> = Vec::new();
+    for dir in files.iter().filter_map(|file| file.parent()) {
+        if !dirs.iter().any(|(watched, _)| watched == dir) {
+            dirs.push((dir.to_path_buf(), RecursiveMode::NonRecursive));
+        }
+    }
+    let (_watcher, events) = start_watching(&dirs, poll)?;
+
+    run();
+    if !is_quiet() {
+        eprintln!("{} Watching {} file(s) for changes (Ctrl-C to stop)", style("→").cyan(), paths.len());
+    }
+    loop {
+        next_changes(&events, WATCH_INTERVAL, &|path: &Path| files.iter().any(|file| file == path))?;
+        run();
+    }
+}
+
+/// Glob patterns for `pattern` with its `{a,b}` alternatives expanded, which
+/// the glob crate does not support itself
+fn glob_patterns(pattern: &str) -> Result<Vec<glob::Pattern>, glob::PatternError> {
+    let (Some(open), Some(close)) = (pattern.find('{'), pattern.find('}')) else {
+        return Ok(vec![glob::Pattern::new(pattern)?]);
+    };
+    if close < open {
+        return Ok(vec![glob::Pattern::new(pattern)?]);
+    }
+    let mut patterns = Vec::new();
+    for alternative in pattern[open + 1..close].split(',') {
+        patterns.extend(glob_patterns(&format!("{}{}{}", &pattern[..open], alternative, &pattern[close + 1..]))?);
+    }
+    Ok(patterns)
+}
+
+/// Files in `dir`, and in its subdirectories when `recursive`
+fn files_below(dir: &Path, recursive: bool) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            files.push(path);
+        } else if recursive {
+            files.extend(files_below(&path, true)?);
+        }
+    }
+    files.sort();
+    Ok(files)
 }
 
 fn handle_convert_command(cmd: ConvertCommand, _config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
@@ -669,6 +1599,7 @@ fn handle_convert_command(cmd: ConvertCommand, _config: &ConfigFile) -> Result<(
     
     let builder = Builder::new();
     let conversion_options = ConversionOptions {
+        allow_lossy: cmd.allow_lossy,
         detailed_reports: true,
         preserve_unknown: cmd.allow_lossy,
         preserve_comments: true,
@@ -699,6 +1630,14 @@ fn handle_convert_command(cmd: ConvertCommand, _config: &ConfigFile) -> Result<(
                 if !report.warnings.is_empty() {
                     println!("  {} warnings generated", report.warnings.len());
                 }
+                if report.is_lossy() {
+                    println!("  {} {} fields dropped or approximated", style("!").yellow(), report.lossy_fields.len());
+                    if cmd.allow_lossy {
+                        for field in &report.lossy_fields {
+                            println!("    {:?} {}: {}", field.kind, field.path, field.detail);
+                        }
+                    }
+                }
             }
         }
         versions::ConverterResult::Failure { error, report: _ } => {
@@ -710,27 +1649,35 @@ fn handle_convert_command(cmd: ConvertCommand, _config: &ConfigFile) -> Result<(
 }
 
 fn handle_diff_command(cmd: DiffCommand, _config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
-    let xml1 = fs::read_to_string(&cmd.file1)?;
-    let xml2 = fs::read_to_string(&cmd.file2)?;
+    let xml1 = read_xml_file(&cmd.file1)?;
+    let xml2 = read_xml_file(&cmd.file2)?;
 
-    let _diff_config = diff::DiffConfig {
+    let diff_config = diff::DiffConfig {
         ignore_formatting: cmd.ignore_whitespace,
+        ignored_paths: cmd.ignore_paths.iter().cloned().collect(),
+        ignored_namespaces: cmd.ignore_namespaces.iter().cloned().collect(),
+        ignore_attribute_changes: cmd.ignore_attributes,
         ..Default::default()
     };
-    // TODO: Parse XML to AST for proper semantic diffing
-    // For now, create a placeholder changeset
-    let changeset = diff::types::ChangeSet::new();
+    let mut engine = diff::DiffEngine::new_with_config(diff_config);
+    let changeset = engine.diff_xml(&xml1, &xml2)?;
 
     let formatted_output = match cmd.format {
+        DiffFormat::Human if cmd.detailed => {
+            diff::formatter::DiffFormatter::format_detailed(&changeset)
+        }
         DiffFormat::Human => {
             diff::formatter::DiffFormatter::format_summary(&changeset)
         }
         DiffFormat::Json => serde_json::to_string_pretty(&changeset)?,
+        DiffFormat::Html => diff::formatter::DiffFormatter::format_html(&changeset),
+        DiffFormat::Markdown => diff::formatter::DiffFormatter::format_markdown(&changeset),
+        DiffFormat::Patch => serde_json::to_string_pretty(&changeset.to_patch())?,
         DiffFormat::Update => {
+            let original_message_id = find_message_id(&xml1).unwrap_or_else(|| "cli-generated".to_string());
             let mut update_generator = messages::UpdateGenerator::new();
-            let _update_message = update_generator.create_update(&xml1, &xml2, "cli-generated")?;
-            // TODO: Implement XML serialization for UpdateReleaseMessage
-            format!("<!-- Update message XML would be serialized here -->")
+            let update_message = update_generator.create_update_from_changeset(&changeset, &original_message_id)?;
+            DDEXBuilder::new().serialize_update(&update_message)?
         }
     };
 
@@ -740,45 +1687,192 @@ fn handle_diff_command(cmd: DiffCommand, _config: &ConfigFile) -> Result<(), Box
         if changeset.changes.is_empty() {
             println!("{} Files are identical", style("✓").green());
         } else {
-            println!("{} {} differences found", style("!").yellow(), changeset.changes.len());
+            println!(
+                "{} {} differences found (impact: {})",
+                style("!").yellow(),
+                changeset.changes.len(),
+                changeset.impact_level()
+            );
         }
     }
 
     Ok(())
 }
 
-fn handle_validate_command(cmd: ValidateCommand, _config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
-    let mut all_valid = true;
-    let mut results = Vec::new();
+fn handle_patch_command(cmd: PatchCommand, _config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
+    let xml = read_xml_file(&cmd.file)?;
+    let patch: diff::Patch = serde_json::from_str(&fs::read_to_string(&cmd.patch)?)
+        .map_err(|e| format!("{}: invalid patch: {}", cmd.patch.display(), e))?;
 
-    for file_path in &cmd.files {
-        let _xml_content = fs::read_to_string(file_path)?;
-        
-        let mut builder = Builder::new();
-        if let Some(preset) = &cmd.preset {
-            let preset_name = preset_to_string(preset);
-            builder.apply_preset(&preset_name, false)?;
+    let options = diff::PatchOptions {
+        dry_run: cmd.dry_run,
+        allow_conflicts: cmd.allow_conflicts,
+    };
+    let outcome = diff::DiffEngine::new().apply_patch_with_options(&xml, &patch, options)?;
+
+    for conflict in &outcome.conflicts {
+        eprintln!(
+            "{} Operation {} at {}: {}",
+            style("✗").red(),
+            conflict.operation + 1,
+            conflict.path,
+            conflict.message
+        );
+    }
+    if let Some(patched) = &outcome.xml {
+        write_output(patched, &cmd.output)?;
+    }
+
+    if !is_quiet() && (cmd.dry_run || cmd.output.is_some()) {
+        println!(
+            "{} {} changes applied, {} already applied, {} conflicts{}",
+            if outcome.is_clean() { style("✓").green() } else { style("!").yellow() },
+            outcome.applied,
+            outcome.already_applied,
+            outcome.conflicts.len(),
+            if cmd.dry_run { " (dry run)" } else { "" }
+        );
+    }
+
+    if !outcome.is_clean() && !cmd.allow_conflicts {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+/// Read the MessageId from a message header, if present
+fn find_message_id(xml: &str) -> Option<String> {
+    let ast = diff::parse_xml(xml).ok()?;
+    ast.root
+        .children
+        .iter()
+        .filter_map(|node| match node {
+            ast::Node::Element(e) if e.name == "MessageHeader" => Some(e),
+            _ => None,
+        })
+        .flat_map(|header| header.children.iter())
+        .find_map(|node| match node {
+            ast::Node::Element(e) if e.name == "MessageId" => e.children.iter().find_map(|n| match n {
+                ast::Node::Text(text) => Some(text.trim().to_string()),
+                _ => None,
+            }),
+            _ => None,
+        })
+}
+
+fn handle_validate_command(mut cmd: ValidateCommand, config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
+    if cmd.extension_schemas.is_none() {
+        cmd.extension_schemas = config.extension_schema_dir.clone();
+    }
+    if cmd.watch {
+        if cmd.files.is_empty() {
+            return Err("--watch needs the files to validate".into());
         }
+        return watch_files(&cmd.files, cmd.poll, || {
+            if let Err(e) = run_validation(&cmd) {
+                eprintln!("{} {}", style("✗").red(), e);
+            }
+        });
+    }
 
-        let validation_config = ValidationConfig {
-            level: if cmd.strict { PreflightLevel::Strict } else { PreflightLevel::Warn },
-            profile: cmd.profile.clone(),
-            ..Default::default()
-        };
+    if !run_validation(&cmd)? {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+/// Validate and report every file once, returning whether all are valid
+fn run_validation(cmd: &ValidateCommand) -> Result<bool, Box<dyn std::error::Error>> {
+    if cmd.explain {
+        return run_compliance_check(cmd);
+    }
+    if let Some(profile) = cmd.profile {
+        return run_conformance_check(cmd, profile);
+    }
+
+    let results = validation_results(cmd)?;
+
+    // Output results
+    match cmd.output_format {
+        ValidateFormat::Human => {
+            for (file_path, result) in &results {
+                print_validation_result_human(file_path, result);
+            }
+        }
+        ValidateFormat::Json => {
+            let json_output = serde_json::to_string_pretty(&results)?;
+            println!("{}", json_output);
+        }
+        ValidateFormat::Junit => {
+            let junit_output = format_junit_results(&results)?;
+            println!("{}", junit_output);
+        }
+    }
+
+    Ok(results.iter().all(|(_, result)| result.passed))
+}
+
+/// Preflight, and the structural and extension checks asked for, of each
+/// file, up to the first failure under `--fail-fast`
+fn validation_results(cmd: &ValidateCommand) -> Result<Vec<(PathBuf, ValidationResult)>, Box<dyn std::error::Error>> {
+    let extension_schemas = cmd
+        .extension_schemas
+        .as_ref()
+        .map(ExtensionSchemaValidator::load_dir)
+        .transpose()?;
+    let mut results = Vec::new();
+
+    let mut builder = Builder::new();
+    let mut validation_config = ValidationConfig {
+        level: if cmd.strict { PreflightLevel::Strict } else { PreflightLevel::Warn },
+        ..Default::default()
+    };
+    if let Some(preset) = &cmd.preset {
+        let preset_name = preset_to_string(preset);
+        builder.apply_preset(&preset_name, false)?;
+        if let Some(preset) = builder.get_preset(&preset_name) {
+            validation_config = validation_config.with_preset(preset);
+        }
+    }
+    let validator = PreflightValidator::new(validation_config);
 
-        let _validator = PreflightValidator::new(validation_config);
-        // TODO: Parse XML content to BuildRequest for validation
-        // For now, create a placeholder result
-        let result = ValidationResult {
+    for file_path in &cmd.files {
+        let mut result = ValidationResult {
             errors: Vec::new(),
             warnings: Vec::new(),
             info: Vec::new(),
             passed: true,
         };
+        if is_build_request_file(file_path) {
+            let data = read_input_data(&Some(file_path.clone()), None)?;
+            let request = parse_build_request(&data).map_err(|e| format!("{}: {}", file_path.display(), e))?;
+            merge_validation(&mut result, validator.validate(&request)?);
+        } else {
+            let xml_content = read_xml_file(file_path)?;
+            if cmd.structural {
+                let version = match cmd.version.clone() {
+                    Some(version) => version.into(),
+                    None => builder.detect_version(&xml_content)?,
+                };
+                merge_validation(&mut result, validate_structure(&xml_content, version)?);
+            }
+            match request_from_xml(&xml_content).map_err(|e| format!("{}: {}", file_path.display(), e))? {
+                Some(request) => merge_validation(&mut result, validator.validate(&request)?),
+                None if cmd.structural => result.info.push(preflight::ValidationInfo {
+                    code: "PREFLIGHT_SKIPPED".to_string(),
+                    message: "This build cannot read DDEX XML back into a build request; only the structure was checked".to_string(),
+                }),
+                None => {
+                    return Err("preflight of DDEX XML needs the `parse` feature; pass --structural to only check the structure".into())
+                }
+            }
+            if let Some(validator) = &extension_schemas {
+                result.errors.extend(validator.validate(&xml_content)?.into_iter().map(violation_error));
+            }
+        }
+        result.passed = result.errors.is_empty() && (!cmd.strict || result.warnings.is_empty());
 
-        let file_valid = result.errors.is_empty();
-        all_valid = all_valid && file_valid;
-
+        let file_valid = result.passed;
         results.push((file_path.clone(), result));
 
         if cmd.fail_fast && !file_valid {
@@ -786,31 +1880,686 @@ fn handle_validate_command(cmd: ValidateCommand, _config: &ConfigFile) -> Result
         }
     }
 
-    // Output results
+    Ok(results)
+}
+
+/// Check build requests against the preset's rules, returning whether all comply
+fn run_compliance_check(cmd: &ValidateCommand) -> Result<bool, Box<dyn std::error::Error>> {
+    let preset = cmd.preset.as_ref().map(preset_to_string).ok_or("--explain needs --preset")?;
+    let mut builder = Builder::new();
+    builder.apply_preset(&preset, false)?;
+
+    let mut reports = Vec::new();
+    for file_path in &cmd.files {
+        let is_xml = file_path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| ext.eq_ignore_ascii_case("xml"));
+        if is_xml {
+            return Err(format!(
+                "--explain checks build requests (JSON, YAML or TOML), not DDEX XML: {}",
+                file_path.display()
+            )
+            .into());
+        }
+        let data = read_input_data(&Some(file_path.clone()), None)?;
+        let request = parse_build_request(&data).map_err(|e| format!("{}: {}", file_path.display(), e))?;
+        let report = builder.check_preset_compliance(&request);
+        let compliant = report.is_compliant();
+        reports.push((file_path.clone(), report));
+        if cmd.fail_fast && !compliant {
+            break;
+        }
+    }
+
     match cmd.output_format {
         ValidateFormat::Human => {
-            for (file_path, result) in &results {
-                print_validation_result_human(file_path, result);
+            for (file_path, report) in &reports {
+                print!("{}", format_compliance_report(file_path, report));
             }
         }
         ValidateFormat::Json => {
-            let json_output = serde_json::to_string_pretty(&results)?;
-            println!("{}", json_output);
+            let output: Vec<_> = reports
+                .iter()
+                .map(|(file, report)| serde_json::json!({ "file": file, "report": report }))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&output)?);
         }
-        ValidateFormat::Junit => {
-            let junit_output = format_junit_results(&results)?;
-            println!("{}", junit_output);
+        ValidateFormat::Junit => return Err("--explain supports human and json output".into()),
+    }
+
+    Ok(reports.iter().all(|(_, report)| report.is_compliant()))
+}
+
+/// Failures with their remediation hints and skipped rules; passes are only counted
+fn format_compliance_report(file_path: &Path, report: &ComplianceReport) -> String {
+    let mut out = String::new();
+    let summary = format!(
+        "{} failed, {} passed, {} skipped",
+        report.count(CheckStatus::Fail),
+        report.count(CheckStatus::Pass),
+        report.count(CheckStatus::Skipped)
+    );
+    let preset = report.preset.as_deref().unwrap_or("no preset");
+    if report.is_compliant() {
+        out.push_str(&format!("{} {} complies with {} ({})\n", style("✓").green(), file_path.display(), preset, summary));
+    } else {
+        out.push_str(&format!("{} {} violates {} ({})\n", style("✗").red(), file_path.display(), preset, summary));
+    }
+
+    for check in report.checks.iter().filter(|check| check.status != CheckStatus::Pass) {
+        let (marker, label) = match check.status {
+            CheckStatus::Fail => (style("✗").red(), style("fail").red()),
+            _ => (style("-").dim(), style("skip").dim()),
+        };
+        out.push_str(&format!("  {} {} {} [{}]", marker, label, check.field, check.rule));
+        if let Some(location) = &check.location {
+            out.push_str(&format!(" at {}", location));
+        }
+        out.push_str(&format!(": {}", check.message));
+        if let Some(value) = &check.value {
+            out.push_str(&format!(" (value: {:?})", value));
+        }
+        out.push('\n');
+        if let Some(hint) = &check.hint {
+            out.push_str(&format!("      {} {}\n", style("→").blue(), hint));
+        }
+    }
+    out
+}
+
+fn parse_message_profile(name: &str) -> Result<MessageProfile, String> {
+    name.parse()
+}
+
+/// Score the releases of DDEX XML or build request files against a profile,
+/// returning whether all conform
+fn run_conformance_check(cmd: &ValidateCommand, profile: MessageProfile) -> Result<bool, Box<dyn std::error::Error>> {
+    let mut reports = Vec::new();
+    for file_path in &cmd.files {
+        let is_xml = file_path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| ext.eq_ignore_ascii_case("xml"));
+        let report = if is_xml {
+            ConformanceReport::from_xml(profile, &read_xml_file(file_path)?)
+                .map_err(|e| format!("{}: {}", file_path.display(), e))?
+        } else {
+            let data = read_input_data(&Some(file_path.clone()), None)?;
+            let request = parse_build_request(&data).map_err(|e| format!("{}: {}", file_path.display(), e))?;
+            ConformanceReport::check(profile, &request)
+        };
+        let conformant = report.is_conformant();
+        reports.push((file_path.clone(), report));
+        if cmd.fail_fast && !conformant {
+            break;
+        }
+    }
+
+    match cmd.output_format {
+        ValidateFormat::Human => {
+            for (file_path, report) in &reports {
+                print!("{}", format_conformance_report(file_path, report));
+            }
+        }
+        ValidateFormat::Json => {
+            let output: Vec<_> = reports
+                .iter()
+                .map(|(file, report)| serde_json::json!({ "file": file, "report": report }))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        ValidateFormat::Junit => return Err("--profile supports human and json output".into()),
+    }
+
+    Ok(reports.iter().all(|(_, report)| report.is_conformant()))
+}
+
+fn format_conformance_report(file_path: &Path, report: &ConformanceReport) -> String {
+    use ddex_builder::conformance::Requirement;
+
+    let mut out = String::new();
+    let (marker, verdict) = if report.is_conformant() {
+        (style("✓").green(), "conforms to")
+    } else {
+        (style("✗").red(), "does not conform to")
+    };
+    out.push_str(&format!("{} {} {} {:?} (score {}/100)\n", marker, file_path.display(), verdict, report.profile, report.score));
+    if report.releases.is_empty() {
+        out.push_str("  no releases found\n");
+    }
+
+    for release in &report.releases {
+        out.push_str(&format!("  {} at {} (score {}/100)\n", release.release, release.location, release.score));
+        for check in release.checks.iter().filter(|check| !check.present) {
+            let (marker, label) = match check.requirement {
+                Requirement::Required => (style("✗").red(), style("missing").red()),
+                Requirement::Recommended => (style("-").yellow(), style("recommended").yellow()),
+            };
+            out.push_str(&format!("    {} {} {} at {}: {}\n", marker, label, check.element, check.location, check.message));
+        }
+    }
+    out
+}
+
+/// Check a document against the bundled XSD content models for `version`
+/// Whether `path` holds a build request (JSON, YAML or TOML) rather than DDEX XML
+fn is_build_request_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ["json", "yaml", "yml", "toml"].iter().any(|known| ext.eq_ignore_ascii_case(known)))
+}
+
+/// Add the findings of `other` to `result`
+fn merge_validation(result: &mut ValidationResult, other: ValidationResult) {
+    result.errors.extend(other.errors);
+    result.warnings.extend(other.warnings);
+    result.info.extend(other.info);
+}
+
+/// Read DDEX XML back into a build request for preflight; `None` when this
+/// build has no parser
+#[cfg(feature = "parse")]
+fn request_from_xml(xml: &str) -> Result<Option<builder::BuildRequest>, Box<dyn std::error::Error>> {
+    let parsed = ddex_parser::DDEXParser::new().parse(io::Cursor::new(xml.as_bytes()))?;
+    Ok(Some(builder::BuildRequest::from_graph(&parsed.graph)))
+}
+
+#[cfg(not(feature = "parse"))]
+fn request_from_xml(_xml: &str) -> Result<Option<builder::BuildRequest>, Box<dyn std::error::Error>> {
+    Ok(None)
+}
+
+/// Check DDEX XML against the condensed content models of ddex-core
+fn validate_structure(xml_content: &str, version: DdexVersion) -> Result<ValidationResult, Box<dyn std::error::Error>> {
+    use ddex_core::models::versions::ERNVersion;
+    use ddex_core::schema::SchemaValidator;
+
+    let ern_version = match version {
+        DdexVersion::Ern382 => ERNVersion::V3_8_2,
+        DdexVersion::Ern42 => ERNVersion::V4_2,
+        DdexVersion::Ern43 => ERNVersion::V4_3,
+        DdexVersion::Ern44 => ERNVersion::V4_4,
+        DdexVersion::Ern41 => return Err("structural validation is not available for ERN 4.1".into()),
+    };
+
+    let errors: Vec<preflight::ValidationError> = SchemaValidator::new(ern_version)
+        .validate(xml_content)
+        .into_iter()
+        .map(violation_error)
+        .collect();
+
+    Ok(ValidationResult {
+        passed: errors.is_empty(),
+        errors,
+        warnings: Vec::new(),
+        info: Vec::new(),
+    })
+}
+
+fn violation_error(violation: ddex_core::schema::SchemaViolation) -> preflight::ValidationError {
+    preflight::ValidationError {
+        code: violation.kind.code().to_string(),
+        field: violation.location.path.rsplit('/').next().unwrap_or_default().to_string(),
+        message: violation.message,
+        location: format!(
+            "line {}, column {} ({})",
+            violation.location.line, violation.location.column, violation.location.path
+        ),
+    }
+}
+
+/// DB-C14N/1.0 canonicalizer using the configured determinism settings
+fn canonicalizer(config: &ConfigFile) -> Result<canonical::DB_C14N, String> {
+    Ok(canonical::DB_C14N::new(config.determinism(&DeterminismConfig::default())?))
+}
+
+fn handle_canonicalize_command(cmd: CanonicalizeCommand, config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
+    let xml = read_xml_input(&cmd.input)?;
+    let canonical = canonicalizer(config)?.canonicalize(&xml)?;
+
+    if cmd.check {
+        if xml == canonical {
+            if !is_quiet() {
+                println!("{} {} is canonical", style("✓").green(), cmd.input.display());
+            }
+            return Ok(());
+        }
+        let offset = find_first_difference(&xml, &canonical).unwrap_or(0);
+        let line = xml.as_bytes()[..offset.min(xml.len())].iter().filter(|&&b| b == b'\n').count() + 1;
+        eprintln!("{} {} is not canonical (first difference on line {})", style("✗").red(), cmd.input.display(), line);
+        process::exit(1);
+    }
+
+    write_output(&canonical, &cmd.output)
+}
+
+fn handle_hash_command(cmd: HashCommand, config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
+    let canonicalizer = canonicalizer(config)?;
+    let expected = cmd.expect.as_deref().map(|h| h.trim().to_ascii_lowercase());
+    let mut mismatches = 0;
+
+    for file in &cmd.files {
+        let canonical = canonicalizer.canonicalize(&read_xml_input(file)?)?;
+        let hash = canonicalizer.canonical_hash(&canonical)?;
+        println!("{}  {}", hash, file.display());
+        if expected.as_ref().is_some_and(|expected| *expected != hash) {
+            mismatches += 1;
         }
     }
 
-    if !all_valid {
+    if mismatches > 0 {
+        eprintln!("{} {} file(s) do not match the expected hash", style("✗").red(), mismatches);
         process::exit(1);
     }
+    Ok(())
+}
+
+fn handle_lint_command(cmd: LintCommand, config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
+    let linter = lint::Linter::new(config.lint.clone());
+
+    if cmd.list_rules {
+        for rule in linter.rules() {
+            let fixable = if rule.fixable { " (fixable)" } else { "" };
+            println!("{:<22} {:<8} {}{}", rule.id, linter.severity(&rule), rule.description, fixable);
+        }
+        return Ok(());
+    }
+
+    let mut results = Vec::new();
+    let mut failed = false;
+    let mut fixed = 0;
+    for file in &cmd.files {
+        let outcome = (|| -> Result<Vec<lint::LintFinding>, Box<dyn std::error::Error>> {
+            if !cmd.fix {
+                return Ok(linter.lint(&read_xml_file(file)?)?);
+            }
+            // Fixes are written back, so compressed files are not fixed
+            let xml = fs::read_to_string(file)?;
+            let result = linter.fix(&xml)?;
+            if result.applied > 0 {
+                fs::write(file, &result.xml)?;
+                fixed += result.applied;
+            }
+            Ok(linter.lint(&result.xml)?)
+        })();
+
+        match outcome {
+            Ok(findings) => {
+                failed |= findings.iter().any(|f| f.severity == lint::Severity::Error);
+                results.push((file.display().to_string(), findings));
+            }
+            Err(e) => {
+                failed = true;
+                eprintln!("{} {}: {}", style("✗").red(), file.display(), e);
+            }
+        }
+    }
+
+    let output = match cmd.format {
+        LintFormat::Human => {
+            let mut output = String::new();
+            for (file, findings) in &results {
+                for finding in findings {
+                    let severity = match finding.severity {
+                        lint::Severity::Error => style(finding.severity.to_string()).red(),
+                        lint::Severity::Warning => style(finding.severity.to_string()).yellow(),
+                        _ => style(finding.severity.to_string()).cyan(),
+                    };
+                    let fixable = if finding.fix.is_some() { " (fixable)" } else { "" };
+                    output.push_str(&format!(
+                        "{}:{}: {} [{}] {}{}\n",
+                        file, finding.line, severity, finding.rule, finding.message, fixable
+                    ));
+                }
+            }
+            output
+        }
+        LintFormat::Json => {
+            let files: Vec<JsonValue> = results
+                .iter()
+                .map(|(file, findings)| serde_json::json!({ "file": file, "findings": findings }))
+                .collect();
+            serde_json::to_string_pretty(&files)? + "\n"
+        }
+        LintFormat::Sarif => serde_json::to_string_pretty(&lint::sarif::to_sarif(&results, &linter.rules()))? + "\n",
+    };
+    write_output(&output, &cmd.output)?;
+
+    if !is_quiet() && cmd.format == LintFormat::Human {
+        let count = |severity| results.iter().flat_map(|(_, f)| f).filter(|f| f.severity == severity).count();
+        let summary = format!(
+            "{} error(s), {} warning(s) in {} file(s)",
+            count(lint::Severity::Error),
+            count(lint::Severity::Warning),
+            results.len()
+        );
+        if fixed > 0 {
+            eprintln!("{}; {} fix(es) applied", summary, fixed);
+        } else {
+            eprintln!("{}", summary);
+        }
+    }
 
+    if failed {
+        process::exit(1);
+    }
     Ok(())
 }
 
+fn handle_split_command(cmd: SplitCommand, config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
+    let xml = read_xml_input(&cmd.input)?;
+    let parts = match cmd.by {
+        SplitBy::Release => split::split_by_release(&xml)?,
+    };
+
+    let output_dir = cmd
+        .output_dir
+        .or_else(|| config.output_dir.clone())
+        .unwrap_or_else(|| PathBuf::from("."));
+    fs::create_dir_all(&output_dir)?;
+    let stem = match cmd.input.file_stem().and_then(|s| s.to_str()) {
+        Some(stem) if stem != "-" => stem.to_string(),
+        _ => "release".to_string(),
+    };
+
+    for part in &parts {
+        let name = part.release_id.as_deref().unwrap_or(&part.release_reference);
+        let path = output_dir.join(format!("{}-{}.xml", stem, name));
+        fs::write(&path, &part.xml)?;
+        if !is_quiet() {
+            println!("{} {}", style("✓").green(), path.display());
+        }
+    }
+    if !is_quiet() {
+        println!("Split into {} message(s)", parts.len());
+    }
+    Ok(())
+}
+
+fn handle_template_command(cmd: TemplateCommand, _config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
+    let request = parse_build_request(&read_input_data(&Some(cmd.input.clone()), None)?)?;
+    let variables = cmd
+        .vars
+        .iter()
+        .map(|var| match var.split_once('=') {
+            Some((name, value)) => Ok((name.trim().to_string(), value.to_string())),
+            None => Err(format!("--var '{}' is not NAME=VALUE", var)),
+        })
+        .collect::<Result<IndexMap<_, _>, _>>()?;
+
+    let template = template::MessageTemplate::from_request(&request, &variables)?;
+    write_output(&template.to_yaml()?, &cmd.output)?;
+
+    if !is_quiet() && cmd.output.is_some() {
+        let placeholders: Vec<String> = template.variables().iter().map(|name| format!("{{{{{}}}}}", name)).collect();
+        println!("{} Template with {}", style("✓").green(), placeholders.join(", "));
+    }
+    Ok(())
+}
+
+fn handle_takedown_command(cmd: TakedownCommand, config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
+    let mut request = parse_build_request(&read_input_data(&Some(cmd.input.clone()), None)?)?;
+    let preset = cmd.preset.as_ref().map(preset_to_string);
+    let builder = configured_builder(preset.as_deref(), None, config)?;
+    if let Some(version) = builder.target_version() {
+        request.version = version.to_string().trim_start_matches("ERN/").to_string();
+    }
+
+    let identifiers: Vec<String> = cmd.upc.iter().chain(&cmd.isrc).cloned().collect();
+    let scope = match cmd.scope {
+        TakedownScopeArg::Deals => TakedownScope::Deals,
+        TakedownScopeArg::Purge => TakedownScope::Purge,
+    };
+    let date = cmd.date.unwrap_or_else(|| chrono::Local::now().date_naive());
+    let takedown = messages::UpdateGenerator::new().create_takedown(&request, &identifiers, scope, date)?;
+    write_output(&takedown.xml, &cmd.output)?;
+
+    for identifier in &takedown.unmatched {
+        eprintln!("{} No release matches {}", style("Warning:").yellow(), identifier);
+    }
+    if !is_quiet() && cmd.output.is_some() {
+        println!("{} Takedown of {}", style("✓").green(), takedown.release_ids.join(", "));
+    }
+    Ok(())
+}
+
+fn handle_reprice_command(cmd: RepriceCommand, config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
+    let rules: Vec<reprice::PriceRule> = serde_json::from_value(read_input_data(&Some(cmd.rules.clone()), None)?)
+        .map_err(|e| format!("{}: invalid pricing rules: {}", cmd.rules.display(), e))?;
+    let messages = cmd
+        .files
+        .iter()
+        .map(|file| read_xml_file(file).map_err(|e| format!("{}: {}", file.display(), e)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let output_dir = cmd
+        .output_dir
+        .or_else(|| config.output_dir.clone())
+        .unwrap_or_else(|| PathBuf::from("."));
+    fs::create_dir_all(&output_dir)?;
+
+    let builder = DDEXBuilder::new();
+    let mut generator = messages::UpdateGenerator::new();
+    let mut changed = 0;
+    for (file, message) in cmd.files.iter().zip(&messages) {
+        let updates = generator
+            .create_price_updates(&[message.as_str()], &rules)
+            .map_err(|e| format!("{}: {}", file.display(), e))?;
+        let Some(update) = updates.into_iter().next() else { continue };
+        changed += 1;
+
+        let stem = file.file_stem().and_then(|s| s.to_str()).unwrap_or("message");
+        let (path, xml) = if cmd.full {
+            (output_dir.join(format!("{}.repriced.xml", stem)), update.xml)
+        } else {
+            (output_dir.join(format!("{}.update.xml", stem)), builder.serialize_update(&update.update)?)
+        };
+        fs::write(&path, xml)?;
+        if !is_quiet() {
+            println!("{} {} ({} deal(s))", style("✓").green(), path.display(), update.deals);
+        }
+    }
+    if !is_quiet() {
+        println!("Repriced {} of {} message(s)", changed, messages.len());
+    }
+    Ok(())
+}
+
+fn handle_merge_command(cmd: MergeCommand, _config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
+    let messages = cmd
+        .files
+        .iter()
+        .map(|file| read_xml_input(file).map_err(|e| format!("{}: {}", file.display(), e)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let messages: Vec<&str> = messages.iter().map(String::as_str).collect();
+
+    let merged = split::merge(&messages, cmd.message_id.as_deref())?;
+    write_output(&merged, &cmd.output)?;
+
+    if !is_quiet() && cmd.output.is_some() {
+        println!("{} Merged {} messages", style("✓").green(), messages.len());
+    }
+    Ok(())
+}
+
+fn handle_stats_command(cmd: StatsCommand, _config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
+    let mut catalog = stats::CatalogStats::default();
+    let mut files = Vec::new();
+    let mut failed = false;
+    for file in &cmd.files {
+        match read_xml_file(file).and_then(|xml| Ok(stats::MessageStats::from_xml(&xml)?)) {
+            Ok(message) => {
+                catalog.add(&message);
+                files.push((file.display().to_string(), message));
+            }
+            Err(e) => {
+                failed = true;
+                eprintln!("{} {}: {}", style("✗").red(), file.display(), e);
+            }
+        }
+    }
+
+    let output = match cmd.format {
+        StatsFormat::Human => format_stats_human(&catalog),
+        StatsFormat::Json => {
+            let files: Vec<JsonValue> = files
+                .iter()
+                .map(|(file, message)| {
+                    let mut value = serde_json::to_value(message)?;
+                    value["file"] = JsonValue::String(file.clone());
+                    Ok(value)
+                })
+                .collect::<Result<_, serde_json::Error>>()?;
+            serde_json::to_string_pretty(&serde_json::json!({ "total": catalog, "files": files }))? + "\n"
+        }
+        StatsFormat::Csv => format_stats_csv(&files)?,
+    };
+    write_output(&output, &cmd.output)?;
+
+    if failed {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+fn handle_index_command(cmd: IndexCommand, _config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
+    let mut catalog = index::CatalogIndex::new();
+    for file in &cmd.files {
+        let xml = read_xml_file(file).map_err(|e| format!("{}: {}", file.display(), e))?;
+        catalog
+            .add_xml(&file.display().to_string(), &xml)
+            .map_err(|e| format!("{}: {}", file.display(), e))?;
+    }
+
+    if let Some(identifier) = &cmd.lookup {
+        let kinds = [index::IdentifierKind::Isrc, index::IdentifierKind::Upc, index::IdentifierKind::Grid];
+        let entries: Vec<&index::IndexEntry> = kinds.iter().flat_map(|&kind| catalog.lookup(kind, identifier)).collect();
+        let output = match cmd.format {
+            IndexFormat::Json => serde_json::to_string_pretty(&entries)? + "\n",
+            IndexFormat::Human => entries
+                .iter()
+                .map(|e| {
+                    format!(
+                        "{} {} {}\n",
+                        e.source,
+                        e.reference.as_deref().unwrap_or("-"),
+                        [e.title.as_deref(), e.artist.as_deref()].into_iter().flatten().collect::<Vec<_>>().join(" / ")
+                    )
+                })
+                .collect(),
+        };
+        write_output(&output, &cmd.output)?;
+        if entries.is_empty() {
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let duplicates = if cmd.check.is_empty() {
+        catalog.duplicates()
+    } else {
+        let mut conflicts = Vec::new();
+        for file in &cmd.check {
+            let xml = read_xml_file(file).map_err(|e| format!("{}: {}", file.display(), e))?;
+            conflicts.extend(catalog.check_xml(&file.display().to_string(), &xml)?);
+        }
+        conflicts
+    };
+
+    let output = match cmd.format {
+        IndexFormat::Json => serde_json::to_string_pretty(&duplicates)? + "\n",
+        IndexFormat::Human => {
+            let mut output = format!("{} identifiers indexed\n", catalog.len());
+            for duplicate in &duplicates {
+                let marker = if duplicate.conflicting_fields.is_empty() {
+                    style("•").dim().to_string()
+                } else {
+                    style("✗").red().to_string()
+                };
+                output.push_str(&format!("{} {} {}", marker, duplicate.kind, duplicate.identifier));
+                if !duplicate.conflicting_fields.is_empty() {
+                    output.push_str(&format!(" (differing {})", duplicate.conflicting_fields.join(", ")));
+                }
+                output.push('\n');
+                for entry in &duplicate.entries {
+                    output.push_str(&format!(
+                        "    {} {}\n",
+                        entry.source,
+                        style([entry.title.as_deref(), entry.artist.as_deref()].into_iter().flatten().collect::<Vec<_>>().join(" / ")).dim()
+                    ));
+                }
+            }
+            output
+        }
+    };
+    write_output(&output, &cmd.output)?;
+
+    if duplicates.iter().any(|d| !d.conflicting_fields.is_empty()) {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+fn format_stats_human(catalog: &stats::CatalogStats) -> String {
+    let seconds = catalog.duration_seconds.round() as u64;
+    let counts = |map: &IndexMap<String, usize>| {
+        let mut entries: Vec<(&String, &usize)> = map.iter().collect();
+        entries.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        let shown: Vec<String> = entries.iter().take(10).map(|(name, count)| format!("{} ({})", name, count)).collect();
+        if entries.len() > shown.len() {
+            format!("{}, and {} more", shown.join(", "), entries.len() - shown.len())
+        } else {
+            shown.join(", ")
+        }
+    };
+
+    let mut output = String::new();
+    output.push_str(&format!("{}\n", style("Catalog summary").bold()));
+    output.push_str(&format!("  Messages:    {}\n", catalog.messages));
+    output.push_str(&format!("  Releases:    {}\n", catalog.releases));
+    output.push_str(&format!("  Tracks:      {}\n", catalog.tracks));
+    output.push_str(&format!("  Deals:       {}\n", catalog.deals));
+    output.push_str(&format!(
+        "  Duration:    {}h {:02}m {:02}s\n",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    ));
+    output.push_str(&format!("  Versions:    {}\n", counts(&catalog.versions)));
+    output.push_str(&format!("  Territories: {} {}\n", catalog.territories.len(), style(format!("[{}]", counts(&catalog.territories))).dim()));
+    output.push_str(&format!("  Genres:      {}\n", counts(&catalog.genres)));
+    output
+}
+
+fn format_stats_csv(files: &[(String, stats::MessageStats)]) -> Result<String, Box<dyn std::error::Error>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(["file", "version", "releases", "tracks", "deals", "duration_seconds", "territories", "genres"])?;
+    for (file, message) in files {
+        let genres: Vec<&str> = message.genres.keys().map(String::as_str).collect();
+        writer.write_record([
+            file.clone(),
+            message.version.clone().unwrap_or_default(),
+            message.releases.to_string(),
+            message.tracks.to_string(),
+            message.deals.to_string(),
+            message.duration_seconds.to_string(),
+            message.territories.len().to_string(),
+            genres.join(";"),
+        ])?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
 fn handle_schema_command(cmd: SchemaCommand, _config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(SchemaOperation::Diff(diff)) = cmd.operation {
+        return handle_schema_diff_command(diff);
+    }
+    if !cmd.target.is_empty() {
+        let targets: Vec<schema::SchemaTarget> = cmd.target.iter().copied().map(Into::into).collect();
+        write_output(&target_schema_output(&targets, &cmd.format)?, &cmd.output)?;
+        if !is_quiet() && cmd.output.is_some() {
+            println!("{} Schema generated successfully", style("✓").green());
+            println!("  Targets: {}", targets.iter().map(|t| t.name()).collect::<Vec<_>>().join(", "));
+        }
+        return Ok(());
+    }
+    let version = cmd.version.ok_or("--ddex-version is required")?;
+
     let _schema_config = schema::SchemaConfig {
         include_descriptions: cmd.with_docs,
         ..Default::default()
@@ -818,12 +2567,13 @@ fn handle_schema_command(cmd: SchemaCommand, _config: &ConfigFile) -> Result<(),
 
     // Use a default profile for now - this could be enhanced to support actual profiles  
     let profile = MessageProfile::AudioAlbum;
-    let generator = schema::SchemaGenerator::new(cmd.version.into(), profile);
+    let generator = schema::SchemaGenerator::new(version.into(), profile);
     let schema_result = generator.generate_complete_schema()?;
     let schema_output = match cmd.format {
         SchemaFormat::Json => serde_json::to_string_pretty(&schema_result.schema)?,
         SchemaFormat::Typescript => generator.generate_typescript_types(&schema_result.schema)?,
         SchemaFormat::Python => generator.generate_python_types(&schema_result.schema)?,
+        SchemaFormat::Pydantic => return Err("Pydantic models are generated for --target payloads only".into()),
     };
 
     write_output(&schema_output, &cmd.output)?;
@@ -831,22 +2581,77 @@ fn handle_schema_command(cmd: SchemaCommand, _config: &ConfigFile) -> Result<(),
     if !is_quiet() {
         println!("{} Schema generated successfully", style("✓").green());
         println!("  Format: {:?}", cmd.format);
-        println!("  Version: {:?}", cmd.version);
+        println!("  Version: {:?}", version);
     }
 
     Ok(())
 }
 
-fn handle_batch_command(cmd: BatchCommand, _config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
-    let batch_config = load_batch_config(&cmd.config)?;
-    
+fn target_schema_output(targets: &[schema::SchemaTarget], format: &SchemaFormat) -> Result<String, Box<dyn std::error::Error>> {
+    Ok(match format {
+        SchemaFormat::Json => match targets {
+            [target] => serde_json::to_string_pretty(&target.json_schema())? + "\n",
+            _ => return Err("JSON output takes a single --target".into()),
+        },
+        SchemaFormat::Typescript => schema::generate_typings(targets, schema::TypingsLanguage::TypeScript)?,
+        SchemaFormat::Python => schema::generate_typings(targets, schema::TypingsLanguage::Python)?,
+        SchemaFormat::Pydantic => schema::generate_typings(targets, schema::TypingsLanguage::Pydantic)?,
+    })
+}
+
+fn handle_schema_diff_command(cmd: SchemaDiffCommand) -> Result<(), Box<dyn std::error::Error>> {
+    let targets: Vec<schema::SchemaTarget> = cmd.targets.iter().copied().map(Into::into).collect();
+    let generated = target_schema_output(&targets, &cmd.format)?;
+    let shipped = fs::read_to_string(&cmd.file)
+        .map_err(|e| format!("{}: {}", cmd.file.display(), e))?;
+    match schema::diff_typings(&shipped, &generated) {
+        None => {
+            if !is_quiet() {
+                println!("{} {} is up to date", style("✓").green(), cmd.file.display());
+            }
+            Ok(())
+        }
+        Some(diff) => {
+            print!("{}", diff);
+            Err(format!("{} is out of date; regenerate it with `ddex-builder schema`", cmd.file.display()).into())
+        }
+    }
+}
+
+fn handle_batch_command(cmd: BatchCommand, config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
+    let batch_config = load_batch_config(&cmd.batch_file)?;
+    let jobs = expand_batch_tasks(&batch_config, config)?;
+    let workers = cmd.workers.or(config.batch.workers).unwrap_or_else(num_cpus::get);
+    let continue_on_error = cmd.continue_on_error().or(config.batch.continue_on_error).unwrap_or(false);
+
+    let completed = if cmd.resume {
+        if cmd.report.is_none() && cmd.log.is_none() {
+            return Err("--resume needs --report or --log to read previous results from".into());
+        }
+        load_completed_jobs(cmd.report.as_deref(), cmd.log.as_deref())?
+    } else {
+        IndexMap::new()
+    };
+    let (resumed, pending): (Vec<&BatchJob>, Vec<&BatchJob>) =
+        jobs.iter().partition(|job| completed.contains_key(&job.key()));
+
+    let log = match &cmd.log {
+        Some(path) => Some(std::sync::Mutex::new(
+            fs::OpenOptions::new().create(true).append(true).open(path)?,
+        )),
+        None => None,
+    };
+    // Set on the first failure unless --continue-on-error; jobs not yet
+    // started are then left for a resumed run
+    let abort = std::sync::atomic::AtomicBool::new(false);
+
     // Setup thread pool
     let pool = rayon::ThreadPoolBuilder::new()
-        .num_threads(cmd.workers)
+        .num_threads(workers)
         .build()?;
 
     let progress_bar = if !is_quiet() {
-        let pb = ProgressBar::new(batch_config.tasks.len() as u64);
+        let pb = ProgressBar::new(pending.len() as u64);
         pb.set_style(
             ProgressStyle::default_bar()
                 .template("[{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg}")
@@ -857,22 +2662,30 @@ fn handle_batch_command(cmd: BatchCommand, _config: &ConfigFile) -> Result<(), B
         None
     };
 
-    let results: Vec<BatchResult> = pool.install(|| {
-        batch_config
-            .tasks
+    let processed: Vec<BatchResult> = pool.install(|| {
+        pending
             .par_iter()
-            .enumerate()
-            .map(|(i, task)| {
-                let result = process_batch_task(task);
+            .filter_map(|job| {
+                if abort.load(std::sync::atomic::Ordering::Relaxed) {
+                    return None;
+                }
+                let started = std::time::Instant::now();
+                let outcome = process_batch_job(job, config).map_err(|e| e.to_string());
+                if outcome.is_err() && !continue_on_error {
+                    abort.store(true, std::sync::atomic::Ordering::Relaxed);
+                }
+                let result = job.result(outcome, started.elapsed());
+
+                if let Some(log) = &log {
+                    if let (Ok(line), Ok(mut file)) = (serde_json::to_string(&result), log.lock()) {
+                        let _ = writeln!(file, "{}", line).and_then(|_| file.flush());
+                    }
+                }
                 if let Some(pb) = &progress_bar {
-                    pb.set_message(format!("Processing {}", task.input_file.display()));
+                    pb.set_message(format!("Processed {}", job.input.display()));
                     pb.inc(1);
                 }
-                BatchResult {
-                    task_id: i,
-                    success: result.is_ok(),
-                    error: result.err().map(|e| e.to_string()),
-                }
+                Some(result)
             })
             .collect()
     });
@@ -881,21 +2694,39 @@ fn handle_batch_command(cmd: BatchCommand, _config: &ConfigFile) -> Result<(), B
         pb.finish_with_message("Batch processing completed");
     }
 
+    let mut results: Vec<BatchResult> = resumed
+        .iter()
+        .filter_map(|job| completed.get(&job.key()).cloned())
+        .map(|result| BatchResult { resumed: true, ..result })
+        .collect();
+    results.extend(processed);
+    results.sort_by_key(|r| r.task_id);
+
     // Generate report
     let successful = results.iter().filter(|r| r.success).count();
     let failed = results.len() - successful;
+    let not_run = jobs.len() - results.len();
 
     if !is_quiet() {
         println!("\n{} Batch processing completed", style("✓").green());
         println!("  Successful: {}", successful);
+        if !resumed.is_empty() {
+            println!("  Resumed: {} already successful", resumed.len());
+        }
         if failed > 0 {
             println!("  Failed: {}", style(failed).red());
+            for result in results.iter().filter(|r| !r.success) {
+                println!("    {}: {}", result.input.display(), result.error.as_deref().unwrap_or_default());
+            }
+        }
+        if not_run > 0 {
+            println!("  Not run: {} (use --resume to continue)", not_run);
         }
     }
 
     if let Some(report_path) = cmd.report {
         let report = BatchReport {
-            total_tasks: results.len(),
+            total_tasks: jobs.len(),
             successful,
             failed,
             results,
@@ -904,7 +2735,7 @@ fn handle_batch_command(cmd: BatchCommand, _config: &ConfigFile) -> Result<(), B
         fs::write(report_path, report_json)?;
     }
 
-    if failed > 0 && !cmd.continue_on_error {
+    if failed > 0 && !continue_on_error {
         process::exit(1);
     }
 
@@ -913,8 +2744,9 @@ fn handle_batch_command(cmd: BatchCommand, _config: &ConfigFile) -> Result<(), B
 
 fn handle_guarantees_command(cmd: GuaranteesCommand, _config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
     use ddex_builder::guarantees::generate_guarantee_report;
-    use ddex_builder::determinism::{DeterminismConfig, DeterminismVerifier};
+    use ddex_builder::determinism::DeterminismConfig;
     use ddex_builder::builder::BuildRequest;
+    use ddex_builder::DeterminismGuaranteeValidator;
 
     // Read and parse input data
     let input_data = read_input_data(&Some(cmd.input.clone()), None)?;
@@ -929,19 +2761,23 @@ fn handle_guarantees_command(cmd: GuaranteesCommand, _config: &ConfigFile) -> Re
         }
     }
 
-    // Generate guarantee report
-    let report = if cmd.thorough {
-        // Run thorough verification with stress tests
-        let _verifier = DeterminismVerifier::new(DeterminismConfig::default());
-        let _result = DeterminismVerifier::thorough_check(&request, cmd.iterations)?;
-        
-        // Generate full report
-        generate_guarantee_report(&request, &DeterminismConfig::default())?
+    // Build across threads with shuffled map ordering and pinpoint any divergence
+    let determinism = if cmd.thorough {
+        let report = DeterminismGuaranteeValidator::new(DeterminismConfig::default())
+            .with_threads(4)
+            .with_shuffled_inputs()
+            .verify(&request, cmd.iterations.max(2))?;
+        if !report.is_deterministic {
+            eprintln!("{}", report.summary());
+        }
+        Some(report)
     } else {
-        // Quick guarantee validation
-        generate_guarantee_report(&request, &DeterminismConfig::default())?
+        None
     };
 
+    // Generate guarantee report
+    let report = generate_guarantee_report(&request, &DeterminismConfig::default())?;
+
     // Format output
     let output_content = match cmd.format {
         GuaranteeFormat::Human => {
@@ -985,7 +2821,8 @@ fn handle_guarantees_command(cmd: GuaranteesCommand, _config: &ConfigFile) -> Re
     }
 
     // Exit with error if guarantees failed and we're not just generating a report
-    if !report.overall_pass && cmd.output.is_none() {
+    let diverged = determinism.is_some_and(|report| !report.is_deterministic);
+    if (!report.overall_pass || diverged) && cmd.output.is_none() {
         std::process::exit(1);
     }
 
@@ -1040,7 +2877,6 @@ fn format_guarantee_report_human(
 fn handle_preset_command(cmd: PresetCommand, _config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
     match cmd.operation {
         PresetOperation::List(list_cmd) => {
-            // TODO: Implement preset listing from ddex_builder::presets
             let presets = get_available_presets(list_cmd.version, list_cmd.partner)?;
             
             match list_cmd.format {
@@ -1135,97 +2971,194 @@ fn handle_preset_command(cmd: PresetCommand, _config: &ConfigFile) -> Result<(),
     Ok(())
 }
 
-fn handle_watch_command(cmd: WatchCommand, _config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
-    use std::sync::mpsc;
-    use std::time::Duration;
-    
-    if !is_quiet() {
-        println!("👀 Watching {} for changes...", cmd.path.display());
-        println!("   Pattern: {}", cmd.pattern);
-        if let Some(ref output_dir) = cmd.output_dir {
-            println!("   Output: {}", output_dir.display());
-        }
-        println!("   Press Ctrl+C to stop");
+fn handle_watch_command(cmd: WatchCommand, config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
+    let root = cmd.path.canonicalize().map_err(|e| format!("{}: {}", cmd.path.display(), e))?;
+    let single_file = root.is_file();
+    let patterns = glob_patterns(&cmd.pattern)?;
+    let mut excludes = Vec::new();
+    for pattern in &cmd.exclude {
+        excludes.extend(glob_patterns(pattern)?);
     }
-    
-    // Initial build if requested
+    let relevant = |path: &Path| match path.strip_prefix(&root) {
+        _ if single_file => path == root,
+        Ok(relative) => {
+            patterns.iter().any(|pattern| pattern.matches_path(relative))
+                && !excludes.iter().any(|pattern| pattern.matches_path(relative))
+        }
+        Err(_) => false,
+    };
+
+    let watched = match root.parent() {
+        Some(dir) if single_file => (dir.to_path_buf(), RecursiveMode::NonRecursive),
+        _ if cmd.recursive => (root.clone(), RecursiveMode::Recursive),
+        _ => (root.clone(), RecursiveMode::NonRecursive),
+    };
+    let (_watcher, events) = start_watching(&[watched], cmd.poll)?;
+
     if cmd.initial_build {
-        if !is_quiet() {
-            println!("🔨 Running initial build...");
+        let files = if single_file {
+            vec![root.clone()]
+        } else {
+            files_below(&root, cmd.recursive)?.into_iter().filter(|path| relevant(path)).collect()
+        };
+        if let Err(e) = run_watch_build(&cmd, config, &files) {
+            eprintln!("{} {}", style("✗").red(), e);
         }
-        run_watch_build(&cmd)?;
     }
-    
-    // TODO: Implement file watching using notify crate
-    // For now, just simulate watching
-    
-    // In a real implementation, this would use the notify crate:
-    // let (tx, rx) = mpsc::channel::<notify::Event>();
-    // let mut watcher = notify::recommended_watcher(move |res| {
-    //     match res {
-    //         Ok(event) => tx.send(event).unwrap(),
-    //         Err(e) => eprintln!("watch error: {:?}", e),
-    //     }
-    // })?;
-    // watcher.watch(&cmd.path, notify::RecursiveMode::from(cmd.recursive))?;
-    
-    // Simulate file watching loop (in real implementation, this would be driven by notify events)
+    if !is_quiet() {
+        eprintln!("{} Watching {} for {} (Ctrl-C to stop)", style("→").cyan(), root.display(), cmd.pattern);
+        if let Some(output_dir) = &cmd.output_dir {
+            eprintln!("  Output: {}", output_dir.display());
+        }
+    }
+
+    let settle = Duration::from_millis(cmd.debounce);
     loop {
-        // For demo purposes, just exit after showing the setup
-        if !is_quiet() {
-            println!("File watching simulation - in real implementation, this would use the notify crate");
+        let changed = next_changes(&events, settle, &relevant)?;
+        if let Err(e) = run_watch_build(&cmd, config, &changed) {
+            eprintln!("{} {}", style("✗").red(), e);
         }
-        break;
     }
-    
-    Ok(())
 }
 
-fn handle_server_command(cmd: ServerCommand, _config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
+fn handle_deliver_command(cmd: DeliverCommand) -> Result<(), Box<dyn std::error::Error>> {
+    use ddex_builder::delivery::{DeliveryBatch, FileSource, ReleasePackage};
+
+    if !cmd.resources.is_empty() && cmd.resources.len() != cmd.messages.len() {
+        return Err("give one --resources folder per message, or none".into());
+    }
+    let mut releases = Vec::new();
+    for (index, message) in cmd.messages.iter().enumerate() {
+        let package = ReleasePackage::from_message(message, Vec::new())?;
+        releases.push(match cmd.resources.get(index) {
+            Some(dir) => package.with_resource_dir(dir)?,
+            None => package,
+        });
+    }
+    let mut batch = DeliveryBatch::new(releases);
+    if let Some(batch_id) = &cmd.batch_id {
+        batch.batch_id = batch_id.clone();
+    }
+
+    if cmd.dry_run {
+        for file in batch.plan()? {
+            let origin = match &file.source {
+                FileSource::Path(path) => path.display().to_string(),
+                FileSource::Bytes(_) => "(generated)".to_string(),
+            };
+            println!("{}  {:>12}  {}  <- {}", file.md5, file.size, file.remote_path, origin);
+        }
+        return Ok(());
+    }
+    deliver_sftp(&batch, &cmd)
+}
+
+#[cfg(feature = "sftp")]
+fn deliver_sftp(batch: &ddex_builder::delivery::DeliveryBatch, cmd: &DeliverCommand) -> Result<(), Box<dyn std::error::Error>> {
+    use ddex_builder::delivery::sftp::{deliver, SftpAuth, SftpConfig};
+
+    let mut config = SftpConfig::new(&cmd.host, &cmd.user);
+    config.port = cmd.port;
+    config.remote_root = cmd.remote_dir.clone();
+    config.bytes_per_second = cmd.limit_rate;
+    config.auth = match (&cmd.key, &cmd.password) {
+        (Some(path), _) => SftpAuth::KeyFile { path: path.clone(), passphrase: cmd.key_passphrase.clone() },
+        (None, Some(password)) => SftpAuth::Password(password.clone()),
+        (None, None) => SftpAuth::Agent,
+    };
+    config.known_hosts = cmd.known_hosts.clone().or_else(|| {
+        let default = PathBuf::from(std::env::var_os("HOME")?).join(".ssh/known_hosts");
+        default.exists().then_some(default)
+    });
+    if config.known_hosts.is_none() && !is_quiet() {
+        eprintln!("{} no known_hosts file, the server key is not checked", style("warning:").yellow());
+    }
+
+    if !is_quiet() {
+        eprintln!("Delivering batch {} to {}:{}", batch.batch_id, cmd.host, cmd.port);
+    }
+    let receipt = deliver(batch, &config)?;
+    if !is_quiet() {
+        let resumed = receipt.files.iter().filter(|f| f.resumed_from > 0).count();
+        eprintln!(
+            "{} {} file(s), {} bytes sent, {} resumed or already present",
+            style("✓").green(),
+            receipt.files.len(),
+            receipt.bytes_sent,
+            resumed
+        );
+    }
+    write_output(&(serde_json::to_string_pretty(&receipt)? + "\n"), &cmd.receipt)
+}
+
+#[cfg(not(feature = "sftp"))]
+fn deliver_sftp(_batch: &ddex_builder::delivery::DeliveryBatch, _cmd: &DeliverCommand) -> Result<(), Box<dyn std::error::Error>> {
+    Err("SFTP delivery is not supported by this build (enable the `sftp` feature)".into())
+}
+
+/// Parse a byte rate such as `750000`, `500K` or `5M`
+fn parse_rate(value: &str) -> Result<u64, String> {
+    let (number, unit) = match value.char_indices().find(|(_, c)| c.is_ascii_alphabetic()) {
+        Some((index, _)) => value.split_at(index),
+        None => (value, ""),
+    };
+    let multiplier = match unit.to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" => 1_000,
+        "M" | "MB" => 1_000_000,
+        "G" | "GB" => 1_000_000_000,
+        _ => return Err(format!("unknown unit '{}', use K, M or G", unit)),
+    };
+    let number: f64 = number.trim().parse().map_err(|_| format!("'{}' is not a rate", value))?;
+    Ok((number * multiplier as f64) as u64)
+}
+
+fn handle_server_command(cmd: ServerCommand, config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
+    if cmd.tls_cert.is_some() || cmd.tls_key.is_some() {
+        return Err("TLS is not supported by the server; terminate it at a reverse proxy".into());
+    }
+    if cmd.rate_limit.is_some() {
+        return Err("rate limiting is not supported by the server; apply it at a reverse proxy".into());
+    }
+    serve_http(cmd, config)
+}
+
+#[cfg(feature = "server")]
+fn serve_http(cmd: ServerCommand, config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
+    let addr: std::net::SocketAddr = format!("{}:{}", cmd.bind, cmd.port).parse()?;
+    let server_config = server::ServerConfig {
+        max_request_bytes: cmd.max_request_size * 1024 * 1024,
+        timeout: std::time::Duration::from_secs(cmd.timeout),
+        default_preset: config.preset.clone(),
+        cors: cmd.cors,
+    };
+
     if !is_quiet() {
-        println!("🚀 Starting DDEX Builder HTTP API server...");
-        println!("   Address: {}:{}", cmd.bind, cmd.port);
+        println!("🚀 Starting DDEX HTTP API server on http://{}", addr);
         println!("   Workers: {}", cmd.workers);
         if cmd.cors {
             println!("   CORS: enabled");
         }
-        if cmd.tls_cert.is_some() && cmd.tls_key.is_some() {
-            println!("   TLS: enabled");
-        }
-        if let Some(rate_limit) = cmd.rate_limit {
-            println!("   Rate limit: {} requests/minute per IP", rate_limit);
+        if let Some(preset) = &server_config.default_preset {
+            println!("   Default preset: {}", preset);
         }
+        println!("   Endpoints: POST /parse /build /validate /diff /convert, GET /presets /health");
         println!("   Press Ctrl+C to stop");
     }
-    
-    // TODO: Implement HTTP server using axum or warp
-    // Server endpoints would include:
-    // POST /build - Build DDEX XML from JSON/YAML/TOML
-    // POST /convert - Convert between DDEX versions
-    // POST /validate - Validate DDEX XML
-    // GET /presets - List available presets
-    // GET /presets/{id} - Get preset details
-    // POST /diff - Compare two DDEX files
-    // GET /health - Health check endpoint
-    // GET /metrics - Prometheus metrics (if enabled)
-    
-    // For now, just simulate server running
-    println!("HTTP API server would start here");
-    println!("Available endpoints:");
-    println!("  POST /api/v1/build        - Build DDEX XML");
-    println!("  POST /api/v1/convert      - Convert DDEX versions");
-    println!("  POST /api/v1/validate     - Validate DDEX XML");
-    println!("  GET  /api/v1/presets      - List presets");
-    println!("  GET  /api/v1/presets/{{id}} - Get preset details");
-    println!("  POST /api/v1/diff         - Compare DDEX files");
-    println!("  GET  /api/v1/health       - Health check");
-    
-    // Simulate server running
-    std::thread::park();
-    
+
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(cmd.workers.max(1))
+        .enable_all()
+        .build()?
+        .block_on(server::serve(addr, server_config))?;
     Ok(())
 }
 
+#[cfg(not(feature = "server"))]
+fn serve_http(_cmd: ServerCommand, _config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
+    Err("the HTTP server is not supported by this build (enable the `server` feature)".into())
+}
+
 fn handle_completions_command(cmd: CompletionsCommand) -> Result<(), Box<dyn std::error::Error>> {
     let mut cli = Cli::command();
     
@@ -1239,6 +3172,36 @@ fn handle_completions_command(cmd: CompletionsCommand) -> Result<(), Box<dyn std
     Ok(())
 }
 
+fn handle_bench_command(cmd: BenchCommand) -> Result<(), Box<dyn std::error::Error>> {
+    let target_bytes = (cmd.size.max(0.01) * 1024.0 * 1024.0) as usize;
+    let measurements = benchmark::run(target_bytes, cmd.iterations)?;
+
+    if cmd.json {
+        println!("{}", serde_json::to_string_pretty(&measurements)?);
+        return Ok(());
+    }
+
+    let bytes = measurements.first().map_or(0, |m| m.bytes);
+    println!(
+        "{} ({:.1} MB catalog, fastest of {} runs)",
+        style("Self-benchmark").bold(),
+        bytes as f64 / (1024.0 * 1024.0),
+        cmd.iterations.max(1)
+    );
+    for measurement in &measurements {
+        let mark = if measurement.meets_target() { style("✓").green() } else { style("✗").red() };
+        println!(
+            "  {} {:<13} {:>9.1} MB/s {:>9.1} ms/MB",
+            mark,
+            measurement.operation.to_string(),
+            measurement.mb_per_sec(),
+            measurement.ms_per_mb()
+        );
+    }
+    println!("  Target: under {:.0} ms per MB", benchmark::TARGET_MS_PER_MB);
+    Ok(())
+}
+
 // Helper functions
 
 fn read_input_data(input: &Option<PathBuf>, format: Option<InputFormat>) -> Result<JsonValue, Box<dyn std::error::Error>> {
@@ -1269,7 +3232,10 @@ fn read_input_string(input: &Option<PathBuf>) -> Result<String, Box<dyn std::err
             io::stdin().read_to_string(&mut content)?;
             Ok(content)
         }
-        Some(path) => Ok(fs::read_to_string(path)?),
+        Some(path) => match storage_uri(path) {
+            Some(uri) => Ok(String::from_utf8(ddex_core::storage::read_blocking(&uri)?)?),
+            None => Ok(fs::read_to_string(path)?),
+        },
         None => {
             let mut content = String::new();
             io::stdin().read_to_string(&mut content)?;
@@ -1278,6 +3244,44 @@ fn read_input_string(input: &Option<PathBuf>) -> Result<String, Box<dyn std::err
     }
 }
 
+/// Read an XML file, inflating `.xml.gz` files and taking the first XML entry
+/// of a zip archive; `s3://`, `gs://` and `az://` paths are downloaded from
+/// object storage
+fn read_xml_file(path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let bytes = match storage_uri(path) {
+        Some(uri) => {
+            let object = ddex_core::storage::read_blocking(&uri)?;
+            ddex_core::compression::read_document(io::Cursor::new(object))?
+        }
+        None => {
+            let file = fs::File::open(path)?;
+            ddex_core::compression::read_document(io::BufReader::new(file))?
+        }
+    };
+    Ok(String::from_utf8(bytes)?)
+}
+
+fn storage_uri(path: &Path) -> Option<ddex_core::storage::StorageUri> {
+    path.to_str().and_then(ddex_core::storage::StorageUri::parse)
+}
+
+/// Create the local directory an output file goes in; object storage has none
+fn create_parent_dir(output: &Path) -> io::Result<()> {
+    match output.parent().filter(|d| !d.as_os_str().is_empty()) {
+        Some(dir) if storage_uri(output).is_none() => fs::create_dir_all(dir),
+        _ => Ok(()),
+    }
+}
+
+/// Read an XML file as [`read_xml_file`] does, or stdin for '-'
+fn read_xml_input(path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    if path.to_str() == Some("-") {
+        read_input_string(&None)
+    } else {
+        read_xml_file(path)
+    }
+}
+
 fn write_output(content: &str, output: &Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
     match output {
         Some(path) if path.to_str() == Some("-") => {
@@ -1285,7 +3289,10 @@ fn write_output(content: &str, output: &Option<PathBuf>) -> Result<(), Box<dyn s
             Ok(())
         }
         Some(path) => {
-            fs::write(path, content)?;
+            match storage_uri(path) {
+                Some(uri) => ddex_core::storage::write_blocking(&uri, content.as_bytes())?,
+                None => fs::write(path, content)?,
+            }
             Ok(())
         }
         None => {
@@ -1303,16 +3310,67 @@ fn detect_input_format(path: &Path) -> InputFormat {
     }
 }
 
-fn preset_to_string(preset: &PresetChoice) -> String {
-    match preset {
-        PresetChoice::AudioAlbum => "audio_album".to_string(),
-        PresetChoice::AudioSingle => "audio_single".to_string(),
-        PresetChoice::VideoSingle => "video_single".to_string(),
-        PresetChoice::Compilation => "compilation".to_string(),
-        PresetChoice::YoutubeAlbum => "youtube_album".to_string(),
-        PresetChoice::YoutubeVideo => "youtube_video".to_string(),
-        PresetChoice::YoutubeSingle => "youtube_single".to_string(),
+/// Parse a version such as "4.3" or "ERN/4.3"
+fn parse_version(value: &str) -> Option<DdexVersion> {
+    DdexVersionArg::from_str(value.trim_start_matches("ERN/").trim_start_matches("ern/"), true)
+        .ok()
+        .map(DdexVersion::from)
+}
+
+/// A builder with `preset` and the configured determinism settings applied,
+/// targeting `version` when given
+fn configured_builder(
+    preset: Option<&str>,
+    version: Option<DdexVersion>,
+    config: &ConfigFile,
+) -> Result<Builder, Box<dyn std::error::Error>> {
+    let mut builder = Builder::new();
+    if let Some(preset) = preset {
+        builder
+            .apply_preset(preset, false)
+            .map_err(|e| format!("Failed to apply preset '{}': {}", preset, e))?;
+    }
+    let determinism = config.determinism(builder.config())?;
+    builder.set_config(determinism)?;
+    if let Some(version) = version {
+        builder.with_version(version);
+    }
+    if let Some(dir) = &config.extension_schema_dir {
+        builder.with_extension_schema_dir(dir);
     }
+    register_plugins(&mut builder, &config.plugins)?;
+    Ok(builder)
+}
+
+#[cfg(feature = "plugins")]
+fn register_plugins(builder: &mut Builder, plugins: &[PathBuf]) -> Result<(), Box<dyn std::error::Error>> {
+    for path in plugins {
+        builder.register_validation_plugin(ddex_builder::plugins::wasm::WasmPlugin::load(path)?);
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "plugins"))]
+fn register_plugins(_builder: &mut Builder, plugins: &[PathBuf]) -> Result<(), Box<dyn std::error::Error>> {
+    match plugins.first() {
+        Some(path) => Err(format!(
+            "Loading the validation plugin {} is not supported by this build (enable the `plugins` feature)",
+            path.display()
+        )
+        .into()),
+        None => Ok(()),
+    }
+}
+
+/// Partners with at least one preset, in listing order
+fn preset_partners() -> Vec<&'static str> {
+    let mut partners: Vec<&'static str> = PRESET_ENTRIES.iter().map(|entry| entry.partner).collect();
+    partners.dedup();
+    partners
+}
+
+fn preset_to_string(preset: &PresetChoice) -> String {
+    preset.0.preset.name.clone()
 }
 
 fn validate_input_data(
@@ -1324,9 +3382,72 @@ fn validate_input_data(
     Ok(())
 }
 
-fn build_ddex_xml(_data: &JsonValue, _builder: &Builder) -> Result<String, Box<dyn std::error::Error>> {
-    // TODO: Implement actual DDEX XML building
-    Ok("<xml><!-- DDEX XML would be generated here --></xml>".to_string())
+/// Read a `BuildRequest` from parsed JSON/YAML/TOML input
+///
+/// The input mirrors `BuildRequest` field for field; errors name the path of
+/// the offending field, e.g. `releases[0].tracks[1]: missing field `isrc``.
+fn parse_build_request(data: &JsonValue) -> Result<builder::BuildRequest, String> {
+    let mut dropped = ddex_builder::Warnings::new();
+    let request = builder::BuildRequest::from_json_value(data, &mut dropped).map_err(|e| match e {
+        BuildError::InvalidFormat { field, message } if field == "." => format!("Invalid build request: {}", message),
+        BuildError::InvalidFormat { field, message } => format!("Invalid build request at {}: {}", field, message),
+        other => other.to_string(),
+    })?;
+    for field in &dropped {
+        eprintln!("{} {}", style("Warning:").yellow(), field);
+    }
+    Ok(request)
+}
+
+/// Build request from spreadsheet exports, merged into the `template`
+/// request when one is given
+fn request_from_csv(
+    releases: &Path,
+    tracks: &Path,
+    template: &Option<PathBuf>,
+    format: Option<InputFormat>,
+) -> Result<JsonValue, Box<dyn std::error::Error>> {
+    let sheets = builder::BuildRequest::from_csv(fs::File::open(releases)?, fs::File::open(tracks)?);
+    let mut sheets = match sheets {
+        Ok(request) => request,
+        Err(BuildError::InvalidSpreadsheet { errors }) => {
+            for error in &errors {
+                eprintln!("{} {}", style("✗").red(), error);
+            }
+            return Err(format!("{} problem(s) in the spreadsheet", errors.len()).into());
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let request = match template {
+        Some(_) => {
+            let mut request = parse_build_request(&read_input_data(template, format)?)?;
+            request.releases.append(&mut sheets.releases);
+            request.deals.append(&mut sheets.deals);
+            request
+        }
+        None => sheets,
+    };
+    Ok(serde_json::to_value(&request)?)
+}
+
+/// Fill the placeholders of a template from a variables file
+fn request_from_template(path: &Path, vars: Option<&Path>) -> Result<JsonValue, Box<dyn std::error::Error>> {
+    let template = template::MessageTemplate::parse(&read_input_string(&Some(path.to_path_buf()))?)?;
+    let variables: IndexMap<String, JsonValue> = match vars {
+        Some(path) => serde_json::from_value(read_input_data(&Some(path.to_path_buf()), None)?)
+            .map_err(|e| format!("{}: variables must be a map of names to values: {}", path.display(), e))?,
+        None => IndexMap::new(),
+    };
+    Ok(template.render(&variables)?)
+}
+
+fn build_ddex_xml(data: &JsonValue, builder: &Builder) -> Result<String, Box<dyn std::error::Error>> {
+    let mut request = parse_build_request(data)?;
+    if let Some(version) = builder.target_version() {
+        request.version = version.to_string().trim_start_matches("ERN/").to_string();
+    }
+    Ok(builder.build_with_fidelity(&request)?.xml)
 }
 
 fn print_validation_result_human(file_path: &Path, result: &ValidationResult) {
@@ -1341,7 +3462,7 @@ fn print_validation_result_human(file_path: &Path, result: &ValidationResult) {
         );
         
         for error in &result.errors {
-            println!("  {} {:?}", style("Error:").red(), error);
+            println!("  {} {}: {} [{}]", style("Error:").red(), error.location, error.message, error.code);
         }
         
         for warning in &result.warnings {
@@ -1357,13 +3478,150 @@ fn format_junit_results(_results: &[(PathBuf, ValidationResult)]) -> Result<Stri
 
 fn load_batch_config(path: &Path) -> Result<BatchConfig, Box<dyn std::error::Error>> {
     let content = fs::read_to_string(path)?;
-    let config: BatchConfig = serde_yaml::from_str(&content)?;
+    let config: BatchConfig = serde_yaml::from_str(&content)
+        .map_err(|e| format!("Invalid batch file {}: {}", path.display(), e))?;
     Ok(config)
 }
 
-fn process_batch_task(_task: &BatchTask) -> Result<(), Box<dyn std::error::Error>> {
-    // TODO: Implement batch task processing
-    Ok(())
+/// Expand every task's input pattern into one job per matching file
+fn expand_batch_tasks(batch: &BatchConfig, config: &ConfigFile) -> Result<Vec<BatchJob>, String> {
+    let mut jobs = Vec::new();
+    for (task_id, task) in batch.tasks.iter().enumerate() {
+        let context = |message: String| format!("tasks[{}] ({}): {}", task_id, task.input_file, message);
+
+        let mut inputs = glob::glob(&task.input_file)
+            .map_err(|e| context(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| context(e.to_string()))?;
+        inputs.sort();
+        if inputs.is_empty() {
+            // A plain path is kept so that the job reports the missing file
+            if task.input_file.contains(['*', '?', '[']) {
+                if !is_quiet() {
+                    eprintln!("{} {}", style("!").yellow(), context("no matching files".to_string()));
+                }
+                continue;
+            }
+            inputs.push(PathBuf::from(&task.input_file));
+        }
+        if task.output_file.is_some() && inputs.len() > 1 {
+            return Err(context(format!("output_file needs a single input but {} files match; use output_dir", inputs.len())));
+        }
+
+        let preset = task.preset.clone().or_else(|| config.preset.clone());
+        let version = match &task.version {
+            Some(v) => Some(parse_version(v).ok_or_else(|| context(format!("unsupported DDEX version '{}'", v)))?),
+            None => config.version()?,
+        };
+        if task.operation == BatchOperation::Convert && version.is_none() {
+            return Err(context("convert tasks need a target version".to_string()));
+        }
+
+        for input in inputs {
+            let output = match (&task.output_file, &task.output_dir) {
+                (Some(file), _) => Some(file.clone()),
+                (None, Some(dir)) => input.file_stem().map(|stem| dir.join(stem).with_extension("xml")),
+                (None, None) => config.output_path(&Some(input.clone())),
+            };
+            if output.is_none() && task.operation != BatchOperation::Validate {
+                return Err(context("needs output_file or output_dir".to_string()));
+            }
+            jobs.push(BatchJob {
+                task_id,
+                operation: task.operation,
+                input,
+                output,
+                preset: preset.clone(),
+                version,
+                validate: task.validate.unwrap_or(false),
+            });
+        }
+    }
+    Ok(jobs)
+}
+
+/// Results of successful jobs in an earlier report and progress log, by job key
+fn load_completed_jobs(
+    report: Option<&Path>,
+    log: Option<&Path>,
+) -> Result<IndexMap<String, BatchResult>, Box<dyn std::error::Error>> {
+    let mut results = Vec::new();
+    if let Some(path) = report.filter(|p| p.is_file()) {
+        let report: BatchReport = serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| format!("Cannot resume from report {}: {}", path.display(), e))?;
+        results.extend(report.results);
+    }
+    if let Some(path) = log.filter(|p| p.is_file()) {
+        for line in fs::read_to_string(path)?.lines().filter(|l| !l.trim().is_empty()) {
+            // A run killed mid-write may leave a truncated last line
+            if let Ok(result) = serde_json::from_str::<BatchResult>(line) {
+                results.push(result);
+            }
+        }
+    }
+    Ok(results
+        .into_iter()
+        .filter(|r| r.success)
+        .map(|r| (BatchJob::key_of(r.operation, &r.input), r))
+        .collect())
+}
+
+fn process_batch_job(job: &BatchJob, config: &ConfigFile) -> Result<(), Box<dyn std::error::Error>> {
+    let builder = configured_builder(job.preset.as_deref(), job.version, config)?;
+
+    let xml = match job.operation {
+        BatchOperation::Build => {
+            let data = read_input_data(&Some(job.input.clone()), None)?;
+            let xml = build_ddex_xml(&data, &builder)?;
+            if job.validate {
+                let version = match job.version {
+                    Some(version) => version,
+                    None => builder.detect_version(&xml)?,
+                };
+                check_schema(&xml, version)?;
+            }
+            xml
+        }
+        BatchOperation::Convert => {
+            let input = read_xml_file(&job.input)?;
+            let from = builder.detect_version(&input)?;
+            let to = job.version.ok_or("convert tasks need a target version")?;
+            match builder.convert_version(&input, from, to, Some(ConversionOptions::default()))? {
+                versions::ConverterResult::Success { xml, .. } => xml,
+                versions::ConverterResult::Failure { error, .. } => {
+                    return Err(format!("Conversion failed: {}", error).into())
+                }
+            }
+        }
+        BatchOperation::Validate => {
+            let input = read_xml_file(&job.input)?;
+            let version = match job.version {
+                Some(version) => version,
+                None => builder.detect_version(&input)?,
+            };
+            check_schema(&input, version)?;
+            return Ok(());
+        }
+    };
+
+    let output = job.output.as_ref().ok_or("no output path")?;
+    create_parent_dir(output)?;
+    write_output(&xml, &Some(output.clone()))
+}
+
+/// Fail with the first schema violation, if any
+fn check_schema(xml: &str, version: DdexVersion) -> Result<(), Box<dyn std::error::Error>> {
+    let result = validate_structure(xml, version)?;
+    match result.errors.first() {
+        Some(first) => Err(format!(
+            "{} schema error(s), first at {}: {}",
+            result.errors.len(),
+            first.location,
+            first.message
+        )
+        .into()),
+        None => Ok(()),
+    }
 }
 
 fn is_quiet() -> bool {
@@ -1374,117 +3632,77 @@ fn is_quiet() -> bool {
 
 fn get_available_presets(
     version_filter: Option<DdexVersionArg>,
-    partner_filter: Option<PresetChoice>,
+    partner_filter: Option<String>,
 ) -> Result<Vec<PresetInfo>, Box<dyn std::error::Error>> {
-    let mut presets = vec![
-        // Generic industry-standard presets
-        PresetInfo {
-            name: "audio_album".to_string(),
-            version: "4.3".to_string(),
-            partner: "Generic".to_string(),
-            description: "DDEX ERN 4.3 audio album baseline preset".to_string(),
-        },
-        PresetInfo {
-            name: "audio_single".to_string(),
-            version: "4.3".to_string(),
-            partner: "Generic".to_string(),
-            description: "DDEX ERN 4.3 audio single baseline preset".to_string(),
-        },
-        PresetInfo {
-            name: "video_single".to_string(),
-            version: "4.3".to_string(),
-            partner: "Generic".to_string(),
-            description: "DDEX ERN 4.3 video single baseline preset".to_string(),
-        },
-        PresetInfo {
-            name: "compilation".to_string(),
-            version: "4.3".to_string(),
-            partner: "Generic".to_string(),
-            description: "DDEX ERN 4.3 compilation album baseline preset".to_string(),
-        },
-        // YouTube presets (based on public documentation)
-        PresetInfo {
-            name: "youtube_album".to_string(),
-            version: "4.3".to_string(),
-            partner: "YouTube".to_string(),
-            description: "YouTube Music album preset based on public Partner documentation".to_string(),
-        },
-        PresetInfo {
-            name: "youtube_video".to_string(),
-            version: "4.3".to_string(),
-            partner: "YouTube".to_string(),
-            description: "YouTube Music video preset based on public Partner documentation".to_string(),
-        },
-        PresetInfo {
-            name: "youtube_single".to_string(),
-            version: "4.3".to_string(),
-            partner: "YouTube".to_string(),
-            description: "YouTube Music single preset based on public Partner documentation".to_string(),
-        },
-    ];
-
-    // Apply filters
-    if let Some(version_filter) = version_filter {
-        let version_str = match version_filter {
-            DdexVersionArg::V382 => "3.8.2",
-            DdexVersionArg::V41 => "4.1",
-            DdexVersionArg::V42 => "4.2",
-            DdexVersionArg::V43 => "4.3",
-            DdexVersionArg::V44 => "4.4",
-        };
-        presets.retain(|p| p.version == version_str);
-    }
-
-    if let Some(partner_filter) = partner_filter {
-        let partner_str = match partner_filter {
-            PresetChoice::AudioAlbum => "Generic",
-            PresetChoice::AudioSingle => "Generic", 
-            PresetChoice::VideoSingle => "Generic",
-            PresetChoice::Compilation => "Generic",
-            PresetChoice::YoutubeAlbum => "YouTube",
-            PresetChoice::YoutubeVideo => "YouTube",
-            PresetChoice::YoutubeSingle => "YouTube",
-        };
-        presets.retain(|p| p.partner == partner_str);
-    }
+    let presets = PRESET_ENTRIES
+        .iter()
+        .filter(|entry| version_filter.is_none_or(|version| entry.preset.config.version == DdexVersion::from(version)))
+        .filter(|entry| partner_filter.as_ref().is_none_or(|partner| entry.partner.eq_ignore_ascii_case(partner)))
+        .map(|entry| PresetInfo {
+            name: entry.preset.name.clone(),
+            version: preset_version(&entry.preset),
+            partner: entry.partner.to_string(),
+            description: entry.preset.description.clone(),
+        })
+        .collect();
 
     Ok(presets)
 }
 
 fn get_preset_details(preset_id: &str) -> Result<PresetDetails, Box<dyn std::error::Error>> {
-    // TODO: Load preset details from ddex_builder::presets module
-    match preset_id {
-        "spotify_audio_43" => Ok(PresetDetails {
-            name: "spotify_audio_43".to_string(),
-            partner: "Spotify".to_string(),
-            version: "4.3".to_string(),
-            description: "Spotify audio release preset for ERN 4.3".to_string(),
-            config: PresetConfig::default(), // Placeholder
-        }),
-        _ => Err(format!("Preset '{}' not found", preset_id).into()),
-    }
-}
-
-fn run_watch_build(cmd: &WatchCommand) -> Result<(), Box<dyn std::error::Error>> {
-    // TODO: Implement actual file watching and building
-    if let Some(ref command) = cmd.command {
-        // Execute custom command
-        let output = std::process::Command::new("sh")
-            .arg("-c")
-            .arg(command)
-            .output()?;
-            
-        if !output.status.success() {
-            return Err(format!("Command failed: {}", String::from_utf8_lossy(&output.stderr)).into());
+    let entry = PRESET_ENTRIES
+        .iter()
+        .find(|entry| entry.preset.name == preset_id)
+        .ok_or_else(|| format!("Preset '{}' not found", preset_id))?;
+
+    Ok(PresetDetails {
+        name: entry.preset.name.clone(),
+        partner: entry.partner.to_string(),
+        version: preset_version(&entry.preset),
+        description: entry.preset.description.clone(),
+        config: entry.preset.config.clone(),
+    })
+}
+
+/// DDEX version of a preset without the "ERN/" prefix, e.g. "4.3"
+fn preset_version(preset: &PartnerPreset) -> String {
+    let version = preset.config.version.to_string();
+    version.trim_start_matches("ERN/").to_string()
+}
+
+/// Run `--command` once for the changed files, or build each of them to
+/// `<name>.xml` in the output directory (next to the file by default)
+fn run_watch_build(cmd: &WatchCommand, config: &ConfigFile, files: &[PathBuf]) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(command) = &cmd.command {
+        let status = process::Command::new("sh").arg("-c").arg(command).status()?;
+        if !status.success() {
+            return Err(format!("'{}' failed with {}", command, status).into());
         }
-    } else {
-        // Default build behavior
-        if !is_quiet() {
-            println!("  Building files matching pattern: {}", cmd.pattern);
+        return Ok(());
+    }
+
+    let preset_name = cmd.preset.as_ref().map(preset_to_string).or_else(|| config.preset.clone());
+    let version = cmd.version.map(DdexVersion::from).or(config.version()?);
+    let builder = configured_builder(preset_name.as_deref(), version, config)?;
+    // Removed files show up as changes too
+    for file in files.iter().filter(|file| file.is_file()) {
+        let xml_file = file.with_extension("xml");
+        let output = match (&cmd.output_dir, xml_file.file_name()) {
+            (Some(dir), Some(name)) => dir.join(name),
+            _ => xml_file.clone(),
+        };
+        let built = read_input_data(&Some(file.clone()), None).and_then(|data| build_ddex_xml(&data, &builder));
+        match built {
+            Ok(xml) => {
+                create_parent_dir(&output)?;
+                fs::write(&output, xml)?;
+                if !is_quiet() {
+                    println!("{} {} -> {}", style("✓").green(), file.display(), output.display());
+                }
+            }
+            Err(e) => eprintln!("{} {}: {}", style("✗").red(), file.display(), e),
         }
-        // TODO: Scan for files matching pattern and build them
     }
-    
     Ok(())
 }
 
@@ -1576,28 +3794,95 @@ fn find_first_difference(a: &str, b: &str) -> Option<usize> {
 
 // Data structures for batch processing
 
+/// Batch file read by the `batch` command
 #[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
 struct BatchConfig {
     tasks: Vec<BatchTask>,
 }
 
 #[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
 struct BatchTask {
-    input_file: PathBuf,
-    output_file: PathBuf,
+    /// Input file or glob pattern, relative to the working directory
+    input_file: String,
+    /// Output file, for tasks matching a single input
+    output_file: Option<PathBuf>,
+    /// Directory outputs are written to as `<input stem>.xml`; defaults to
+    /// the configured `output_dir`
+    output_dir: Option<PathBuf>,
+    #[serde(default)]
+    operation: BatchOperation,
+    /// Preset applied to this task instead of the configured one
     preset: Option<String>,
+    /// Version to build or convert to, or to validate against
     version: Option<String>,
+    /// Check built XML against the XSD
     validate: Option<bool>,
 }
 
-#[derive(serde::Serialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum BatchOperation {
+    /// Build XML from a JSON/YAML/TOML request
+    #[default]
+    Build,
+    /// Convert XML to `version`
+    Convert,
+    /// Check XML against the XSD
+    Validate,
+}
+
+/// One input file of a task
+struct BatchJob {
+    task_id: usize,
+    operation: BatchOperation,
+    input: PathBuf,
+    output: Option<PathBuf>,
+    preset: Option<String>,
+    version: Option<DdexVersion>,
+    validate: bool,
+}
+
+impl BatchJob {
+    /// Identifies the job across runs, for `--resume`
+    fn key(&self) -> String {
+        Self::key_of(self.operation, &self.input)
+    }
+
+    fn key_of(operation: BatchOperation, input: &Path) -> String {
+        format!("{:?}:{}", operation, input.display())
+    }
+
+    fn result(&self, outcome: Result<(), String>, elapsed: std::time::Duration) -> BatchResult {
+        BatchResult {
+            task_id: self.task_id,
+            operation: self.operation,
+            input: self.input.clone(),
+            output: self.output.clone(),
+            success: outcome.is_ok(),
+            error: outcome.err(),
+            duration_ms: elapsed.as_millis() as u64,
+            resumed: false,
+        }
+    }
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 struct BatchResult {
     task_id: usize,
+    operation: BatchOperation,
+    input: PathBuf,
+    output: Option<PathBuf>,
     success: bool,
     error: Option<String>,
+    duration_ms: u64,
+    /// Carried over from an earlier run by `--resume`
+    #[serde(default)]
+    resumed: bool,
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, serde::Deserialize)]
 struct BatchReport {
     total_tasks: usize,
     successful: usize,
@@ -1621,7 +3906,7 @@ struct PresetDetails {
     partner: String,
     version: String,
     description: String,
-    config: PresetConfig,
+    config: presets::PresetConfig,
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -1632,9 +3917,279 @@ struct PresetSummary {
     description: String,
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize, Default)]
-struct PresetConfig {
-    // TODO: Define actual preset configuration structure
-    // This would contain partner-specific settings, validation rules, etc.
-    settings: IndexMap<String, String>,
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rate() {
+        assert_eq!(parse_rate("750000"), Ok(750_000));
+        assert_eq!(parse_rate("500K"), Ok(500_000));
+        assert_eq!(parse_rate("1.5M"), Ok(1_500_000));
+        assert!(parse_rate("5X").is_err());
+    }
+
+    #[test]
+    fn test_parse_build_request_reports_field_path() {
+        let mut data = serde_json::json!({
+            "version": "4.3",
+            "header": {
+                "message_sender": { "party_name": [{ "text": "Label" }] },
+                "message_recipient": { "party_name": [{ "text": "DSP" }] }
+            },
+            "releases": [{
+                "release_id": "R1",
+                "title": [{ "text": "Album" }],
+                "artist": "Artist",
+                "tracks": [{ "track_id": "T1", "title": "Song", "duration": "PT3M", "artist": "Artist" }]
+            }]
+        });
+        let error = parse_build_request(&data).unwrap_err();
+        assert_eq!(error, "Invalid build request at releases[0].tracks[0]: missing field `isrc`");
+
+        data["releases"][0]["tracks"][0]["isrc"] = serde_json::json!("USRC17607839");
+        let request = parse_build_request(&data).unwrap();
+        assert!(request.deals.is_empty());
+
+        let xml = build_ddex_xml(&data, &Builder::new()).unwrap();
+        assert!(xml.contains("<ISRC>USRC17607839</ISRC>"));
+    }
+
+    #[test]
+    fn test_validate_runs_preset_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("release.json");
+        let request = serde_json::json!({
+            "version": "4.3",
+            "header": {
+                "message_sender": { "party_name": [{ "text": "Label" }] },
+                "message_recipient": { "party_name": [{ "text": "DSP" }] }
+            },
+            "releases": [{
+                "release_id": "R1",
+                "title": [{ "text": "Album" }],
+                "artist": "Artist",
+                "tracks": [{ "track_id": "T1", "title": "Song", "duration": "PT3M", "artist": "Artist", "isrc": "USRC17607839" }]
+            }]
+        });
+        fs::write(&path, request.to_string()).unwrap();
+
+        let validate = |args: &[&str]| {
+            let cli = Cli::try_parse_from(["ddex-builder", "validate"].iter().chain(args)).unwrap();
+            match cli.command {
+                Commands::Validate(cmd) => validation_results(&cmd).unwrap().remove(0).1,
+                _ => unreachable!(),
+            }
+        };
+        let file = path.to_str().unwrap();
+        let codes = |result: &ValidationResult| result.errors.iter().map(|e| e.code.clone()).collect::<Vec<_>>();
+
+        let plain = validate(&[file]);
+        assert!(!codes(&plain).contains(&"MISSING_P_LINE".to_string()));
+
+        let preset = validate(&["--preset", "amazon_album", file]);
+        assert!(!preset.passed);
+        assert!(codes(&preset).contains(&"MISSING_P_LINE".to_string()));
+    }
+
+    #[test]
+    fn test_preset_choices_cover_all_presets() {
+        let names: Vec<_> = PresetChoice::value_variants()
+            .iter()
+            .map(|choice| choice.to_possible_value().unwrap().get_name().to_string())
+            .collect();
+        let expected: Vec<_> = presets::all_presets().into_keys().collect();
+        assert_eq!(names, expected);
+
+        let tidal = get_available_presets(None, Some("tidal".to_string())).unwrap();
+        assert_eq!(tidal.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), ["tidal_album", "tidal_single"]);
+        assert_eq!(get_preset_details("pandora_single").unwrap().config.territory_codes, ["US"]);
+    }
+
+    #[test]
+    fn test_compliance_report_explains_failures() {
+        let data = serde_json::json!({
+            "header": {
+                "message_sender": { "party_name": [{ "text": "Sender" }] },
+                "message_recipient": { "party_name": [{ "text": "Recipient" }] }
+            },
+            "version": "4.3",
+            "releases": [{
+                "release_id": "R1",
+                "title": [{ "text": "Album" }],
+                "artist": "Artist",
+                "upc": "123",
+                "tracks": []
+            }]
+        });
+        let request = parse_build_request(&data).unwrap();
+        let mut builder = Builder::new();
+        builder.apply_preset("amazon_album", false).unwrap();
+
+        let report = builder.check_preset_compliance(&request);
+        assert!(!report.is_compliant());
+        let output = format_compliance_report(Path::new("release.json"), &report);
+        assert!(output.contains("release.json violates amazon_album"), "{}", output);
+        assert!(output.contains("UPC [Pattern(^\\d{12,14}$)] at /releases[0]/upc"), "{}", output);
+        assert!(output.contains("Set /releases[0]/release_date"), "{}", output);
+    }
+
+    #[test]
+    fn test_conformance_report_lists_missing_elements() {
+        let data = serde_json::json!({
+            "header": {
+                "message_sender": { "party_name": [{ "text": "Sender" }] },
+                "message_recipient": { "party_name": [{ "text": "Recipient" }] }
+            },
+            "version": "4.3",
+            "releases": [{
+                "release_id": "R1",
+                "title": [{ "text": "Album" }],
+                "artist": "Artist",
+                "tracks": []
+            }]
+        });
+        let request = parse_build_request(&data).unwrap();
+        let profile = parse_message_profile("audio-album").unwrap();
+
+        let report = ConformanceReport::check(profile, &request);
+        let output = format_conformance_report(Path::new("release.json"), &report);
+        assert!(output.contains("release.json does not conform to AudioAlbum"), "{}", output);
+        assert!(output.contains("UPC at /releases[0]/upc: UPC is missing"), "{}", output);
+        assert!(output.contains("0 sound recording(s), at least 2 needed"), "{}", output);
+        assert!(parse_message_profile("Podcast").is_err());
+    }
+
+    #[test]
+    fn test_config_env_overrides_file() {
+        let mut config: ConfigFile = toml::from_str(
+            r#"
+            preset = "audio_album"
+            version = "4.3"
+
+            [determinism]
+            indent_width = 4
+
+            [batch]
+            workers = 2
+            "#,
+        )
+        .unwrap();
+        let env = |name: &str| match name {
+            "DDEX_BUILDER_VERSION" => Some("3.8.2".to_string()),
+            "DDEX_BUILDER_CONTINUE_ON_ERROR" => Some("true".to_string()),
+            _ => None,
+        };
+        config.apply_env(env).unwrap();
+
+        assert_eq!(config.preset.as_deref(), Some("audio_album"));
+        assert_eq!(config.version().unwrap(), Some(DdexVersion::Ern382));
+        assert_eq!(config.batch.workers, Some(2));
+        assert_eq!(config.batch.continue_on_error, Some(true));
+
+        let determinism = config.determinism(&DeterminismConfig::default()).unwrap();
+        assert_eq!(determinism.indent_width, 4);
+        assert_eq!(determinism.line_ending, DeterminismConfig::default().line_ending);
+
+        assert!(config.apply_env(|_| Some("many".to_string())).is_err());
+        assert!(toml::from_str::<ConfigFile>("presets = []").is_err());
+    }
+
+    #[test]
+    fn test_batch_continue_on_error_flags_override_config() {
+        let batch = |args: &[&str]| {
+            let cli = Cli::try_parse_from(["ddex-builder", "batch", "tasks.yaml"].iter().chain(args)).unwrap();
+            match cli.command {
+                Commands::Batch(cmd) => cmd.continue_on_error(),
+                _ => unreachable!(),
+            }
+        };
+        assert_eq!(batch(&[]), None);
+        assert_eq!(batch(&["--continue-on-error"]), Some(true));
+        assert_eq!(batch(&["--no-continue-on-error"]), Some(false));
+        assert_eq!(batch(&["--continue-on-error", "--no-continue-on-error"]), Some(false));
+    }
+
+    #[test]
+    fn test_batch_tasks_expand_globs_and_resume() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["b.yaml", "a.yaml", "notes.txt"] {
+            fs::write(dir.path().join(name), "").unwrap();
+        }
+        let batch: BatchConfig = serde_yaml::from_str(&format!(
+            r#"
+            tasks:
+              - input_file: "{dir}/*.yaml"
+                output_dir: "{dir}/out"
+                preset: audio_album
+              - input_file: "{dir}/a.yaml"
+                operation: validate
+                version: "3.8.2"
+            "#,
+            dir = dir.path().display()
+        ))
+        .unwrap();
+
+        let jobs = expand_batch_tasks(&batch, &ConfigFile::default()).unwrap();
+        assert_eq!(jobs.len(), 3);
+        assert_eq!(jobs[0].input, dir.path().join("a.yaml"));
+        assert_eq!(jobs[1].output, Some(dir.path().join("out/b.xml")));
+        assert_eq!(jobs[1].preset.as_deref(), Some("audio_album"));
+        assert_eq!(jobs[2].operation, BatchOperation::Validate);
+        assert_eq!(jobs[2].version, Some(DdexVersion::Ern382));
+
+        let log = dir.path().join("progress.jsonl");
+        let lines: Vec<String> = [
+            jobs[0].result(Ok(()), std::time::Duration::ZERO),
+            jobs[1].result(Err("failed".to_string()), std::time::Duration::ZERO),
+        ]
+        .iter()
+        .map(|result| serde_json::to_string(result).unwrap())
+        .collect();
+        fs::write(&log, lines.join("\n") + "\n{\"task_id\"").unwrap();
+
+        let completed = load_completed_jobs(None, Some(&log)).unwrap();
+        assert!(completed.contains_key(&jobs[0].key()));
+        assert!(!completed.contains_key(&jobs[1].key()));
+        assert!(!completed.contains_key(&jobs[2].key()));
+
+        let convert: BatchConfig = serde_yaml::from_str(&format!(
+            "tasks: [{{ input_file: \"{}/*.yaml\", operation: convert, output_dir: out }}]",
+            dir.path().display()
+        ))
+        .unwrap();
+        assert!(expand_batch_tasks(&convert, &ConfigFile::default()).is_err());
+    }
+
+    #[test]
+    fn test_watch_reports_relevant_changes_and_lists_output_changes() {
+        use notify::event::{AccessKind, EventKind, ModifyKind};
+
+        let dir = tempfile::tempdir().unwrap();
+        let watched = dir.path().join("release.yaml");
+        let (tx, events) = mpsc::channel();
+        let event = |kind: EventKind, path: &Path| -> notify::Result<notify::Event> {
+            Ok(notify::Event::new(kind).add_path(path.to_path_buf()))
+        };
+        tx.send(event(EventKind::Access(AccessKind::Any), &watched)).unwrap();
+        tx.send(event(EventKind::Modify(ModifyKind::Any), &dir.path().join("other.yaml"))).unwrap();
+        tx.send(event(EventKind::Modify(ModifyKind::Any), &watched)).unwrap();
+        tx.send(event(EventKind::Modify(ModifyKind::Any), &watched)).unwrap();
+        drop(tx);
+        let changed = next_changes(&events, Duration::from_millis(10), &|path: &Path| path == watched).unwrap();
+        assert_eq!(changed, vec![watched.clone()]);
+        assert!(next_changes(&events, Duration::from_millis(10), &|path: &Path| path == watched).is_err());
+
+        let patterns = glob_patterns("**/*.{json,yaml}").unwrap();
+        assert_eq!(patterns.len(), 2);
+        assert!(patterns.iter().any(|pattern| pattern.matches_path(Path::new("release.yaml"))));
+        assert!(patterns.iter().any(|pattern| pattern.matches_path(Path::new("albums/release.json"))));
+        assert!(!patterns.iter().any(|pattern| pattern.matches_path(Path::new("release.xml"))));
+
+        let previous = "<Release><Title>A</Title></Release>";
+        assert_eq!(output_changes(previous, previous).unwrap(), "  No changes in the output\n");
+        let changes = output_changes(previous, "<Release><Title>B</Title></Release>").unwrap();
+        assert_eq!(changes.lines().count(), 1);
+        assert!(changes.contains("/Title"));
+    }
+}
@@ -0,0 +1,58 @@
+//! DDEX Suite HTTP server
+//!
+//! Serves `ddex_builder::server::router`: POST /parse, /build, /validate,
+//! /diff and /convert with the library's JSON shapes.
+
+use clap::Parser;
+use ddex_builder::server::{serve, ServerConfig};
+use std::net::SocketAddr;
+use std::time::Duration;
+
+#[derive(Parser)]
+#[command(name = "ddex-server")]
+#[command(about = "Parse, build, validate, diff and convert DDEX messages over HTTP")]
+#[command(version)]
+struct Cli {
+    /// Address to listen on
+    #[arg(short, long, env = "DDEX_SERVER_BIND", default_value = "127.0.0.1:8080")]
+    bind: SocketAddr,
+
+    /// Largest request body in MB
+    #[arg(long, default_value_t = 10)]
+    max_request_size: usize,
+
+    /// Seconds a request may take
+    #[arg(long, default_value_t = 30)]
+    timeout: u64,
+
+    /// Preset applied to requests without a `preset` parameter
+    #[arg(long, env = "DDEX_SERVER_PRESET")]
+    preset: Option<String>,
+
+    /// Allow cross-origin requests from any origin
+    #[arg(long)]
+    cors: bool,
+}
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| "info".into()),
+        )
+        .with_target(false)
+        .init();
+
+    let cli = Cli::parse();
+    let config = ServerConfig {
+        max_request_bytes: cli.max_request_size * 1024 * 1024,
+        timeout: Duration::from_secs(cli.timeout),
+        default_preset: cli.preset,
+        cors: cli.cors,
+    };
+    tracing::info!("listening on http://{}", cli.bind);
+    if let Err(e) = serve(cli.bind, config).await {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,158 @@
+//! On-disk cache of serialized release, resource and deal subtrees
+//!
+//! Rebuilding a large catalog where a few releases changed mostly produces
+//! XML that was already written last time. With
+//! [`BuildOptions::cache_dir`](crate::builder::BuildOptions::cache_dir) set,
+//! the writer hashes every entry of the ReleaseList, ResourceList and
+//! DealList as generated (references, indentation and builder version
+//! included) and reuses the bytes stored under that hash instead of
+//! serializing the entry again.
+//!
+//! Entries are content-addressed, so they never go stale: a changed release,
+//! or one whose references moved, simply hashes differently. Nothing is ever
+//! evicted; delete the directory to reclaim space.
+
+use crate::ast::{Element, Node};
+use crate::determinism::DeterminismConfig;
+use crate::error::BuildError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Lists whose entries are cached one by one
+pub const CACHED_LISTS: &[&str] = &["ReleaseList", "ResourceList", "DealList"];
+
+/// Distinguishes temporary files of concurrent writers in one process
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// How much of a build came from the cache
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheStatistics {
+    /// Entries reused without serializing
+    pub hits: usize,
+    /// Entries serialized and stored
+    pub misses: usize,
+    /// Bytes of XML copied from the cache
+    pub bytes_reused: usize,
+}
+
+/// Content-addressed store of serialized subtrees in a directory
+#[derive(Debug)]
+pub struct BuildCache {
+    dir: PathBuf,
+    stats: Mutex<CacheStatistics>,
+}
+
+impl BuildCache {
+    /// Use `dir` as the cache, creating it if needed
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, BuildError> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir, stats: Mutex::new(CacheStatistics::default()) })
+    }
+
+    /// Key of `element` written at `depth` with `config`'s indentation
+    pub fn key(element: &Element, depth: usize, config: &DeterminismConfig) -> String {
+        let mut hasher = blake3::Hasher::new();
+        hash_field(&mut hasher, b'v', env!("CARGO_PKG_VERSION"));
+        hash_field(&mut hasher, b'i', &format!("{:?}:{}:{}", config.indent_char, config.indent_width, depth));
+        hash_element(&mut hasher, element);
+        hasher.finalize().to_hex().to_string()
+    }
+
+    /// Stored bytes for `key`, counted as a hit
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let bytes = fs::read(self.path(key)).ok()?;
+        let mut stats = self.stats.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        stats.hits += 1;
+        stats.bytes_reused += bytes.len();
+        Some(bytes)
+    }
+
+    /// Store freshly serialized bytes under `key`, counted as a miss
+    ///
+    /// The file is written aside and renamed into place, so parallel builds
+    /// sharing the directory never read a partial entry.
+    pub fn put(&self, key: &str, bytes: &[u8]) -> Result<(), BuildError> {
+        let path = self.path(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temp = path.with_extension(format!(
+            "{}.{}.tmp",
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&temp, bytes)?;
+        fs::rename(&temp, &path)?;
+        self.stats.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).misses += 1;
+        Ok(())
+    }
+
+    /// Hits and misses since the cache was opened
+    pub fn stats(&self) -> CacheStatistics {
+        self.stats.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(&key[..2]).join(format!("{}.xml", &key[2..]))
+    }
+}
+
+/// Length-prefixed, so adjacent fields cannot run into each other
+fn hash_field(hasher: &mut blake3::Hasher, tag: u8, value: &str) {
+    hasher.update(&[tag]);
+    hasher.update(&(value.len() as u64).to_le_bytes());
+    hasher.update(value.as_bytes());
+}
+
+fn hash_element(hasher: &mut blake3::Hasher, element: &Element) {
+    hash_field(hasher, b'e', &element.name);
+    if let Some(namespace) = &element.namespace {
+        hash_field(hasher, b'n', namespace);
+    }
+    for (name, value) in &element.attributes {
+        hash_field(hasher, b'a', name);
+        hash_field(hasher, b'=', value);
+    }
+    for child in &element.children {
+        match child {
+            Node::Element(child) => hash_element(hasher, child),
+            Node::Text(text) => hash_field(hasher, b't', text),
+            Node::Comment(comment) => {
+                hash_field(hasher, b'c', &format!("{:?}", comment.position));
+                hash_field(hasher, b'c', &comment.to_xml());
+            }
+            Node::SimpleComment(comment) => hash_field(hasher, b's', comment),
+        }
+    }
+    hasher.update(b"/");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_follows_content_and_depth() {
+        let config = DeterminismConfig::default();
+        let release = Element::new("Release").with_child(Element::new("ReleaseReference").with_text("R1"));
+        let renamed = Element::new("Release").with_child(Element::new("ReleaseReference").with_text("R2"));
+        let key = BuildCache::key(&release, 2, &config);
+        assert_eq!(key, BuildCache::key(&release.clone(), 2, &config));
+        assert_ne!(key, BuildCache::key(&renamed, 2, &config));
+        assert_ne!(key, BuildCache::key(&release, 3, &config));
+    }
+
+    #[test]
+    fn test_round_trip_through_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = BuildCache::open(dir.path()).unwrap();
+        assert_eq!(cache.get("abcdef"), None);
+        cache.put("abcdef", b"<Release/>\n").unwrap();
+        assert_eq!(cache.get("abcdef").as_deref(), Some(&b"<Release/>\n"[..]));
+        assert_eq!(cache.stats(), CacheStatistics { hits: 1, misses: 1, bytes_reused: 11 });
+    }
+}
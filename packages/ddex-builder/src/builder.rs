@@ -1,12 +1,19 @@
 //! Main builder implementation
 
 use crate::generator::{ASTGenerator, xml_writer::XmlWriter};
+use ddex_core::cancel::CancellationToken;
+use ddex_core::memory::MemoryTracker;
+use ddex_core::models::Comment;
+use ddex_core::warnings::Warnings;
 use indexmap::IndexMap;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+
+pub use super::error::BuildWarning;
 pub use super::preflight::PreflightLevel;
 
 /// Build request structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct BuildRequest {
     /// Message header
     pub header: MessageHeaderRequest,
@@ -21,24 +28,93 @@ pub struct BuildRequest {
     pub releases: Vec<ReleaseRequest>,
     
     /// Deals
+    #[serde(default)]
     pub deals: Vec<DealRequest>,
     
     /// Extensions (uses IndexMap for determinism)
     pub extensions: Option<IndexMap<String, String>>,
 }
 
+impl BuildRequest {
+    /// Deserialize a request from JSON, recording every field the model
+    /// does not know as a [`DroppedField`](ddex_core::NormalizationKind::DroppedField)
+    pub fn from_json_value(value: &serde_json::Value, warnings: &mut Warnings) -> Result<Self, super::error::BuildError> {
+        let mut dropped = Vec::new();
+        let request = serde_path_to_error::deserialize(serde_ignored::Deserializer::new(value, &mut |path: serde_ignored::Path| {
+            dropped.push(json_path(&path));
+        }))
+        .map_err(|e| super::error::BuildError::InvalidFormat {
+            field: e.path().to_string(),
+            message: e.inner().to_string(),
+        })?;
+        for (path, pointer) in dropped {
+            let before = value.pointer(&pointer).map(|v| v.to_string()).unwrap_or_default();
+            warnings.record(ddex_core::NormalizationKind::DroppedField, path, before, None);
+        }
+        Ok(request)
+    }
+}
+
+/// JSON path (`releases[0].foo`) and JSON pointer (`/releases/0/foo`) of
+/// an ignored field
+fn json_path(path: &serde_ignored::Path) -> (String, String) {
+    match path {
+        serde_ignored::Path::Root => (String::new(), String::new()),
+        serde_ignored::Path::Seq { parent, index } => {
+            let (path, pointer) = json_path(parent);
+            (format!("{}[{}]", path, index), format!("{}/{}", pointer, index))
+        }
+        serde_ignored::Path::Map { parent, key } => {
+            let (path, pointer) = json_path(parent);
+            let escaped = key.replace('~', "~0").replace('/', "~1");
+            if path.is_empty() {
+                (key.clone(), format!("/{}", escaped))
+            } else {
+                (format!("{}.{}", path, key), format!("{}/{}", pointer, escaped))
+            }
+        }
+        serde_ignored::Path::Some { parent }
+        | serde_ignored::Path::NewtypeStruct { parent }
+        | serde_ignored::Path::NewtypeVariant { parent } => json_path(parent),
+    }
+}
+
 /// Message header request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct MessageHeaderRequest {
     pub message_id: Option<String>,
     pub message_sender: PartyRequest,
     pub message_recipient: PartyRequest,
     pub message_control_type: Option<String>,
     pub message_created_date_time: Option<String>,
+    /// Thread grouping related messages; defaults to the message ID
+    #[serde(default)]
+    pub message_thread_id: Option<String>,
+    /// Name of the file the message is delivered in
+    #[serde(default)]
+    pub message_file_name: Option<String>,
+    /// Audit trail received from upstream parties, oldest first
+    #[serde(default)]
+    pub message_audit_trail: Vec<AuditTrailEventRequest>,
+    /// Append an audit trail event for this sender and build
+    #[serde(default)]
+    pub append_audit_trail_event: bool,
+}
+
+/// One hop in a message's audit trail
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AuditTrailEventRequest {
+    /// Party that handled the message
+    pub messaging_party: PartyRequest,
+    /// ISO 8601 date and time the party handled the message
+    pub date_time: String,
+    /// System that processed the message, emitted as the party's `TradingName`
+    #[serde(default)]
+    pub system_id: Option<String>,
 }
 
 /// Party request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct PartyRequest {
     pub party_name: Vec<LocalizedStringRequest>,
     pub party_id: Option<String>,
@@ -46,14 +122,14 @@ pub struct PartyRequest {
 }
 
 /// Localized string request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct LocalizedStringRequest {
     pub text: String,
     pub language_code: Option<String>,
 }
 
 /// Release request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ReleaseRequest {
     pub release_id: String,
     pub release_reference: Option<String>,  // Added for linker
@@ -61,13 +137,80 @@ pub struct ReleaseRequest {
     pub artist: String,
     pub label: Option<String>,              // Added for metadata
     pub release_date: Option<String>,       // Added for metadata
+    /// Date the release or its recordings first came out in any format
+    /// (YYYY-MM-DD), e.g. the original pressing of a reissue
+    #[serde(default)]
+    pub original_release_date: Option<String>,
     pub upc: Option<String>,                // Added for validation
+    #[serde(default)]
     pub tracks: Vec<TrackRequest>,
     pub resource_references: Option<Vec<String>>,  // Added for linker
+    /// Video, image, text and sheet music resources
+    #[serde(default)]
+    pub resources: Vec<ResourceRequest>,
+    /// Per-territory overrides of release details
+    #[serde(default)]
+    pub territory_details: Vec<TerritoryDetailsRequest>,
+    /// Display artists of the release, beyond the `artist` string
+    #[serde(default)]
+    pub contributors: Vec<ContributorRequest>,
+    /// Phonographic copyright of the release
+    #[serde(default)]
+    pub p_line: Option<PLineRequest>,
+    /// Copyright of the release artwork and packaging
+    #[serde(default)]
+    pub c_line: Option<CLineRequest>,
+    /// ISRCs of tracks defined elsewhere in the request to include in this
+    /// release; resolved to ReleaseResourceReferences by the linker
+    #[serde(default)]
+    pub track_isrcs: Vec<String>,
+    /// Track-level releases offering single tracks of this release; filled
+    /// automatically for profiles that require them
+    #[serde(default)]
+    pub track_releases: Vec<TrackReleaseRequest>,
+    /// Comments re-emitted around and inside the release when comments are preserved
+    #[serde(default)]
+    pub comments: Vec<Comment>,
+}
+
+/// Release of a single track (ERN 4 `TrackRelease`)
+///
+/// Deals can target it through `DealRequest::release_ids` by ISRC.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct TrackReleaseRequest {
+    /// Reference, generated when unset
+    #[serde(default)]
+    pub release_reference: Option<String>,
+    /// ISRC of the track offered by this release
+    pub isrc: String,
+}
+
+/// Release details that apply only in some territories
+///
+/// Unset fields fall back to the release-level values.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct TerritoryDetailsRequest {
+    /// ISO 3166-1 territory codes (or `Worldwide`) the overrides apply to
+    pub territory_codes: Vec<String>,
+    /// Title used in these territories
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Display artist used in these territories
+    #[serde(default)]
+    pub display_artist: Option<String>,
+    /// Genre used in these territories
+    #[serde(default)]
+    pub genre: Option<String>,
+    /// Release date (YYYY-MM-DD) in these territories
+    #[serde(default)]
+    pub release_date: Option<String>,
+    /// Parental warning type (e.g. `Explicit`) in these territories
+    #[serde(default)]
+    pub parental_warning: Option<String>,
 }
 
 /// Track request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TrackRequest {
     pub track_id: String,                     // Added for linker
     pub resource_reference: Option<String>,   // Added for linker
@@ -75,22 +218,247 @@ pub struct TrackRequest {
     pub title: String,
     pub duration: String,                    // Keep as String for ISO 8601 format
     pub artist: String,
+    /// Display artists, contributors and indirect contributors of the recording
+    #[serde(default)]
+    pub contributors: Vec<ContributorRequest>,
+    /// Phonographic copyright of the recording
+    #[serde(default)]
+    pub p_line: Option<PLineRequest>,
+    /// Copyright of the recording
+    #[serde(default)]
+    pub c_line: Option<CLineRequest>,
+    /// Comments re-emitted around and inside the SoundRecording when comments are preserved
+    #[serde(default)]
+    pub comments: Vec<Comment>,
+    /// Technical details of the delivered audio file
+    #[serde(default)]
+    pub technical_details: Option<TechnicalDetailsRequest>,
+    /// Parental warning type of the recording, e.g. `Explicit` or `NotExplicit`
+    #[serde(default)]
+    pub parental_warning: Option<String>,
+}
+
+/// Phonographic copyright line (℗), e.g. "2024 Example Records"
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct PLineRequest {
+    /// Year the copyright was first claimed
+    #[serde(default)]
+    pub year: Option<u16>,
+    /// Copyright text, usually including the year and rights holder
+    pub text: String,
+}
+
+/// Copyright line (©), e.g. "2024 Example Records"
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct CLineRequest {
+    /// Year the copyright was first claimed
+    #[serde(default)]
+    pub year: Option<u16>,
+    /// Copyright text, usually including the year and rights holder
+    pub text: String,
+}
+
+/// How a contributor relates to a release or resource
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum ContributorKind {
+    /// Credited artist (`DisplayArtist`), e.g. main or featured artist
+    #[default]
+    DisplayArtist,
+    /// Contributor to the recording itself (`ResourceContributor`), e.g. producer
+    ResourceContributor,
+    /// Contributor to the underlying work (`IndirectResourceContributor`), e.g. composer
+    IndirectResourceContributor,
+}
+
+/// A party credited on a release or resource
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ContributorRequest {
+    /// Full name of the party
+    pub name: String,
+    /// Element the contributor is generated as
+    #[serde(default)]
+    pub kind: ContributorKind,
+    /// Role, e.g. "MainArtist", "FeaturedArtist", "Producer" or "Composer"
+    pub role: String,
+    /// Credit order, emitted as `SequenceNumber`
+    #[serde(default)]
+    pub sequence: Option<u32>,
+    /// Party identifiers such as ISNI or IPN
+    #[serde(default)]
+    pub party_ids: Vec<PartyIdRequest>,
+}
+
+/// A namespace-qualified party identifier
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PartyIdRequest {
+    /// "ISNI", "IPN", "IPI", "DPID", or a proprietary namespace
+    pub namespace: String,
+    /// Identifier value
+    pub value: String,
+}
+
+/// Kind of a non-audio resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum ResourceKind {
+    /// Music videos, concerts and other audio-visual content
+    Video,
+    /// Cover art, artist photos and other images
+    Image,
+    /// Lyrics, booklets and liner notes
+    Text,
+    /// Printed music
+    SheetMusic,
+}
+
+impl ResourceKind {
+    /// DDEX element name of the resource
+    pub fn element_name(&self) -> &'static str {
+        match self {
+            ResourceKind::Video => "Video",
+            ResourceKind::Image => "Image",
+            ResourceKind::Text => "Text",
+            ResourceKind::SheetMusic => "SheetMusic",
+        }
+    }
+}
+
+/// Non-audio resource request
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ResourceRequest {
+    /// Kind of resource, which selects the generated element
+    pub kind: ResourceKind,
+    /// Proprietary identifier of the resource
+    pub resource_id: String,
+    /// Resource reference; generated when omitted
+    pub resource_reference: Option<String>,
+    /// AVS type of the resource, e.g. "FrontCoverImage" or "ShortFormMusicalWorkVideo"
+    pub resource_type: String,
+    /// ISRC, for videos
+    #[serde(default)]
+    pub isrc: Option<String>,
+    /// Reference title
+    #[serde(default)]
+    pub title: Option<String>,
+    /// ISO 8601 duration, for videos
+    #[serde(default)]
+    pub duration: Option<String>,
+    /// Technical details of the delivered file
+    #[serde(default)]
+    pub technical_details: Option<TechnicalDetailsRequest>,
+    /// Phonographic copyright, for videos
+    #[serde(default)]
+    pub p_line: Option<PLineRequest>,
+    /// Copyright of the resource
+    #[serde(default)]
+    pub c_line: Option<CLineRequest>,
+}
+
+/// Technical details of a delivered resource file
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct TechnicalDetailsRequest {
+    /// File name or URI of the delivered file
+    pub file_name: Option<String>,
+    /// Codec or file format, e.g. "H.264", "JPEG", "PDF"
+    pub codec: Option<String>,
+    /// Width in pixels, for videos and images
+    pub width: Option<u32>,
+    /// Height in pixels, for videos and images
+    pub height: Option<u32>,
+    /// Resolution in dots per inch, for images
+    pub resolution: Option<u32>,
+    /// Checksum of the delivered file
+    pub file_hash: Option<FileHashRequest>,
+    /// Size of the delivered file in bytes
+    #[serde(default)]
+    pub file_size: Option<u64>,
+    /// Sampling rate in Hz, for audio
+    #[serde(default)]
+    pub sample_rate: Option<u32>,
+    /// Bit depth, for audio
+    #[serde(default)]
+    pub bits_per_sample: Option<u32>,
+    /// Number of audio channels
+    #[serde(default)]
+    pub number_of_channels: Option<u32>,
+    /// Playing time of the delivered file as an ISO 8601 duration, checked
+    /// against the declared duration
+    #[serde(default)]
+    pub duration: Option<String>,
+}
+
+/// Hash of a delivered file
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FileHashRequest {
+    /// Hash algorithm, e.g. "MD5" or "SHA256"
+    pub algorithm: String,
+    /// Hex-encoded digest
+    pub value: String,
 }
 
 /// Deal request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DealRequest {
     pub deal_reference: Option<String>,       // Added for linker
     pub deal_terms: DealTerms,               // Define this
     pub release_references: Vec<String>,      // Added for linker
+    /// Releases covered by the deal, by UPC or release_id; resolved to
+    /// DealReleaseReferences by the linker
+    #[serde(default)]
+    pub release_ids: Vec<String>,
+    /// Comments re-emitted around and inside the ReleaseDeal when comments are preserved
+    #[serde(default)]
+    pub comments: Vec<Comment>,
 }
 
-/// Deal terms (simple definition for now)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Deal terms
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct DealTerms {
     pub commercial_model_type: String,
     pub territory_code: Vec<String>,
     pub start_date: Option<String>,
+    /// End of the validity period
+    #[serde(default)]
+    pub end_date: Option<String>,
+    /// Permitted uses (AVS `UseType`, e.g. "OnDemandStream")
+    #[serde(default)]
+    pub use_types: Vec<String>,
+    /// Date the release must be withdrawn; DDEX expresses this as the end of
+    /// the validity period, so the earlier of this and `end_date` is emitted
+    #[serde(default)]
+    pub takedown_date: Option<String>,
+    /// Date pre-orders become available
+    #[serde(default)]
+    pub pre_order_release_date: Option<String>,
+    /// Date pre-order previews become available
+    #[serde(default)]
+    pub pre_order_preview_date: Option<String>,
+    /// Wholesale and retail prices
+    #[serde(default)]
+    pub price_information: Vec<PriceInformationRequest>,
+    /// Withdraw the release under this deal now; emitted as the `TakeDown`
+    /// flag in ERN 3.8.2, while ERN 4 relies on `takedown_date` alone
+    #[serde(default)]
+    pub take_down: bool,
+}
+
+/// Price information for a deal
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PriceInformationRequest {
+    /// Price tier code agreed with the DSP
+    pub price_code: Option<String>,
+    /// Price charged to the DSP per unit
+    pub wholesale_price: Option<PriceRequest>,
+    /// Recommended consumer price
+    pub suggested_retail_price: Option<PriceRequest>,
+}
+
+/// Monetary amount with its ISO 4217 currency
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PriceRequest {
+    /// Decimal amount, kept as text so it is emitted exactly as given
+    pub amount: String,
+    /// ISO 4217 currency code, e.g. "USD"
+    pub currency_code: String,
 }
 
 /// Build options
@@ -107,6 +475,86 @@ pub struct BuildOptions {
     
     /// Stable hash configuration (when using StableHash strategy)
     pub stable_hash_config: Option<super::id_generator::StableHashConfig>,
+    
+    /// Fields the receiving partner requires, usually a preset's `required_fields`
+    #[serde(default)]
+    pub required_fields: Vec<String>,
+    
+    /// Re-emit the comments carried by releases, tracks and deals
+    #[serde(default)]
+    pub preserve_comments: bool,
+    
+    /// Fail with [`FailedOnWarning`](super::error::BuildError::FailedOnWarning)
+    /// instead of building when preflight warns or the request had to be
+    /// normalized
+    #[serde(default)]
+    pub fail_on_warning: bool,
+    
+    /// Fail with [`MemoryLimitExceeded`](super::error::BuildError::MemoryLimitExceeded)
+    /// once the AST and serialized output would need more than this many
+    /// bytes; `None` only measures the peak
+    #[serde(default)]
+    pub max_memory: Option<usize>,
+    
+    /// Stop with [`Cancelled`](super::error::BuildError::Cancelled) before
+    /// the next stage once this token is cancelled
+    #[serde(skip)]
+    pub cancellation: Option<CancellationToken>,
+    
+    /// Directory of the incremental build cache; release, resource and deal
+    /// entries whose generated content was serialized before are copied
+    /// from it (see [`build_cache`](super::build_cache))
+    #[serde(default)]
+    pub cache_dir: Option<std::path::PathBuf>,
+    
+    /// Skip the preflight checks across deal validity periods (conflicting
+    /// overlaps, coverage gaps and expired deals) when they are intentional
+    #[serde(default)]
+    pub skip_deal_timeline: bool,
+    
+    /// Skip the preflight warnings about release dates far ahead, original
+    /// release dates after the release date and implausibly early years
+    #[serde(default)]
+    pub skip_release_date_checks: bool,
+    
+    /// Don't add a TrackRelease for each track of ERN 4 AudioAlbum and
+    /// VideoAlbum releases; only the request's own `track_releases` are
+    /// written, as in builds before track releases were generated
+    #[serde(default)]
+    pub skip_track_releases: bool,
+    
+    /// Clean invisible characters, decomposed accents and stray whitespace
+    /// out of titles, names and identifiers before preflight (see
+    /// [`sanitize`](super::sanitize)), recording each change
+    #[serde(default)]
+    pub sanitize_text: bool,
+    
+    /// Rewrite genres into the vocabulary of the receiving platform, usually
+    /// the one its preset asks for (see [`genre`](super::genre))
+    #[serde(default)]
+    pub genre_taxonomy: Option<super::genre::GenreTaxonomy>,
+    
+    /// How namespace declarations are laid out (see
+    /// [`OptimizationStrategy`](super::namespace_minimizer::OptimizationStrategy));
+    /// by default they are written as generated
+    #[serde(default)]
+    pub namespace_strategy: super::namespace_minimizer::OptimizationStrategy,
+    
+    /// Proprietary namespaces to declare under fixed prefixes (see
+    /// [`extensions`](super::extensions))
+    #[serde(default)]
+    pub extension_namespaces: super::extensions::ExtensionNamespaces,
+    
+    /// Schema cache directory whose `.xsd` files the extension blocks of the
+    /// output are checked against (see [`extension_schema`](super::extension_schema));
+    /// violations are warnings, or fail the build under strict preflight
+    #[serde(default)]
+    pub extension_schema_dir: Option<std::path::PathBuf>,
+    
+    /// In-house rules checked after preflight, unless preflight is off (see
+    /// [`plugins`](super::plugins))
+    #[serde(skip)]
+    pub validation_plugins: super::plugins::ValidationPlugins,
 }
 
 impl Default for BuildOptions {
@@ -116,21 +564,67 @@ impl Default for BuildOptions {
             preflight_level: super::preflight::PreflightLevel::Warn,
             id_strategy: IdStrategy::UUID,
             stable_hash_config: None,
+            required_fields: Vec::new(),
+            preserve_comments: false,
+            fail_on_warning: false,
+            max_memory: None,
+            cancellation: None,
+            cache_dir: None,
+            skip_deal_timeline: false,
+            skip_release_date_checks: false,
+            skip_track_releases: false,
+            sanitize_text: false,
+            genre_taxonomy: None,
+            namespace_strategy: Default::default(),
+            extension_namespaces: Default::default(),
+            extension_schema_dir: None,
+            validation_plugins: Default::default(),
         }
     }
 }
 
+impl BuildOptions {
+    /// Fail with `Cancelled` if the token says to stop before `stage`
+    fn check_cancelled(&self, stage: &str, start: std::time::Instant) -> Result<(), super::error::BuildError> {
+        match self.cancellation.as_ref().and_then(CancellationToken::reason) {
+            Some(reason) => Err(super::error::BuildError::Cancelled {
+                reason,
+                stage: stage.to_string(),
+                elapsed_ms: start.elapsed().as_millis() as u64,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Whether a message profile offers each track as its own TrackRelease
+pub fn profile_requires_track_releases(profile: &str) -> bool {
+    matches!(profile, "AudioAlbum" | "VideoAlbum")
+}
+
 /// ID generation strategy
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Applies to the MessageId and to every release, resource and deal
+/// reference the request leaves unset. All strategies except `UUID` give
+/// the same IDs for the same request and
+/// [`TimestampSource`](super::determinism::TimestampSource).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum IdStrategy {
     /// UUID v4
     UUID,
-    /// UUID v7 (time-ordered)
+    /// UUID v5, named by the entity's ISRC, UPC or proprietary ID
+    UUIDv5,
+    /// UUID v7 (time-ordered), timestamped by the configured clock
     UUIDv7,
     /// Sequential
     Sequential,
-    /// Stable hash-based
+    /// Stable hash-based; references are content-addressed from ISRC/UPC and title
     StableHash,
+    /// UUIDs drawn from a random generator with a fixed seed
+    SeededRandom(u64),
+    /// IDs returned by a caller-supplied function
+    #[serde(skip)]
+    Custom(super::id_generator::IdCallback),
 }
 
 /// Build result
@@ -153,14 +647,23 @@ pub struct BuildResult {
     
     /// Reproducibility banner (if requested)
     pub reproducibility_banner: Option<String>,
+    
+    /// Changes made to the request before building
+    #[serde(default)]
+    pub normalizations: Warnings,
 }
 
-/// Build warning
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BuildWarning {
-    pub code: String,
-    pub message: String,
-    pub location: Option<String>,
+#[cfg(feature = "signature")]
+impl BuildResult {
+    /// Sign the message with an enveloped XMLDSig signature
+    ///
+    /// `canonical_hash` keeps describing the unsigned message.
+    pub fn sign(&mut self, key: &dyn super::signature::SigningKey) -> Result<(), super::error::BuildError> {
+        self.xml = super::signature::sign(&self.xml, key)
+            .map_err(|e| super::error::BuildError::Other(e.to_string()))?;
+        self.statistics.xml_size_bytes = self.xml.len();
+        Ok(())
+    }
 }
 
 /// Build statistics
@@ -171,6 +674,12 @@ pub struct BuildStatistics {
     pub deals: usize,
     pub generation_time_ms: u64,
     pub xml_size_bytes: usize,
+    /// Most bytes held at once by the AST and serialized output
+    #[serde(default)]
+    pub peak_memory_bytes: usize,
+    /// Reuse of the incremental build cache, when `cache_dir` is set
+    #[serde(default)]
+    pub cache: Option<super::build_cache::CacheStatistics>,
 }
 
 impl Default for BuildStatistics {
@@ -181,13 +690,45 @@ impl Default for BuildStatistics {
             deals: 0,
             generation_time_ms: 0,
             xml_size_bytes: 0,
+            peak_memory_bytes: 0,
+            cache: None,
         }
     }
 }
 
+impl BuildResult {
+    /// Result of a batch entry that failed to build
+    fn failed(error: super::error::BuildError) -> Self {
+        Self {
+            xml: String::new(),
+            warnings: Vec::new(),
+            errors: vec![error],
+            statistics: BuildStatistics::default(),
+            canonical_hash: None,
+            reproducibility_banner: None,
+            normalizations: Warnings::new(),
+        }
+    }
+}
+
+/// Batch statistics of a [`DDEXBuilder`], summed over every
+/// [`build_batch`](DDEXBuilder::build_batch) call
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BuilderStats {
+    /// Requests built
+    pub messages: usize,
+    /// Requests that failed to build
+    pub failed: usize,
+    /// Wall-clock time of the batches, in microseconds
+    pub total_time_us: u64,
+    /// Wall-clock time per request, in microseconds
+    pub us_per_message: f64,
+}
+
 /// Main DDEX Builder
 pub struct DDEXBuilder {
     inner: super::Builder,
+    stats: std::sync::Mutex<BuilderStats>,
 }
 
 impl DDEXBuilder {
@@ -195,15 +736,57 @@ impl DDEXBuilder {
     pub fn new() -> Self {
         Self {
             inner: super::Builder::new(),
+            stats: std::sync::Mutex::new(BuilderStats::default()),
         }
     }
     
+    /// Build many requests in parallel on rayon's thread pool
+    ///
+    /// Results are in the order of `requests`. A request that fails does not
+    /// stop the batch: its result has no XML and carries the error in
+    /// `errors`.
+    pub fn build_batch(&self, requests: Vec<BuildRequest>, options: BuildOptions) -> Vec<BuildResult> {
+        use rayon::prelude::*;
+        
+        let start = std::time::Instant::now();
+        let results: Vec<BuildResult> = requests
+            .into_par_iter()
+            .map(|request| self.build(request, options.clone()).unwrap_or_else(BuildResult::failed))
+            .collect();
+        let elapsed_us = start.elapsed().as_micros() as u64;
+        
+        let mut stats = self.stats.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        stats.messages += results.len();
+        stats.failed += results.iter().filter(|result| !result.errors.is_empty()).count();
+        stats.total_time_us += elapsed_us;
+        if stats.messages > 0 {
+            stats.us_per_message = stats.total_time_us as f64 / stats.messages as f64;
+        }
+        results
+    }
+    
+    /// Batch statistics so far
+    pub fn stats(&self) -> BuilderStats {
+        self.stats.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+    
     /// Build DDEX XML from request
+    #[tracing::instrument(name = "ddex.build", skip_all, fields(version = %request.version, releases = request.releases.len()))]
     pub fn build(&self, mut request: BuildRequest, options: BuildOptions) -> Result<BuildResult, super::error::BuildError> {
         let start = std::time::Instant::now();
         let mut warnings = Vec::new();
+        options.check_cancelled("preflight", start)?;
+        
+        // 1. Normalize, then run the enhanced preflight checks
+        let mut normalizations = Warnings::new();
+        super::normalize::normalize_request(&mut request, &mut normalizations);
+        if options.sanitize_text {
+            super::sanitize::sanitize_request(&mut request, &mut normalizations);
+        }
+        if let Some(taxonomy) = options.genre_taxonomy {
+            super::genre::map_request_genres(&mut request, taxonomy, &mut warnings);
+        }
         
-        // 1. Enhanced preflight checks with new validator
         let validator = super::preflight::PreflightValidator::new(
             super::preflight::ValidationConfig {
                 level: options.preflight_level,
@@ -213,6 +796,12 @@ impl DDEXBuilder {
                 check_required_fields: true,
                 validate_dates: true,
                 validate_references: true,
+                validate_avs: true,
+                required_fields: options.required_fields.clone(),
+                check_deal_timeline: !options.skip_deal_timeline,
+                as_of: None,
+                release_dates: (!options.skip_release_date_checks).then(super::preflight::ReleaseDateRules::default),
+                parental_warnings: super::preflight::ParentalWarningCheck::for_required_fields(&options.required_fields),
             }
         );
         
@@ -224,6 +813,7 @@ impl DDEXBuilder {
                 code: warning.code,
                 message: warning.message,
                 location: Some(warning.location),
+                suggestion: warning.suggestion,
             });
         }
         
@@ -238,31 +828,103 @@ impl DDEXBuilder {
             }
         }
         
-        // 2. Generate IDs based on strategy
-        self.generate_ids(&mut request, &options)?;
+        let run_plugins = options.preflight_level != super::preflight::PreflightLevel::None;
+        if run_plugins {
+            options.validation_plugins.check_request(&request, &mut warnings)?;
+        }
         
-        // 3. Generate AST
-        let mut generator = ASTGenerator::new(request.version.clone());
-        let ast = generator.generate(&request)?;
+        if options.fail_on_warning && !(warnings.is_empty() && normalizations.is_empty()) {
+            return Err(super::error::BuildError::FailedOnWarning {
+                warnings: warnings.iter()
+                    .map(|w| format!("{}: {}", w.code, w.message))
+                    .chain(normalizations.iter().map(|n| n.to_string()))
+                    .collect(),
+            });
+        }
         
-        // 4. Apply determinism config
-        let config = options.determinism.unwrap_or_default();
+        // 2. Add required track releases, generate IDs based on strategy,
+        //    then link by identifier
+        options.check_cancelled("link", start)?;
+        if !options.skip_track_releases {
+            Self::add_required_track_releases(&mut request);
+        }
+        let config = options.determinism.clone().unwrap_or_default();
+        let id_strategy = config.id_strategy.as_ref().unwrap_or(&options.id_strategy);
+        self.generate_ids(&mut request, id_strategy, &options, &config)?;
+        let link_span = tracing::info_span!("ddex.link").entered();
+        super::linker::ReferenceLinker::new()
+            .resolve_identifiers(&mut request)
+            .map_err(|e| match e {
+                super::linker::LinkerError::UnknownRelease(reference)
+                | super::linker::LinkerError::UnknownResource(reference) => {
+                    super::error::BuildError::InvalidReference { reference }
+                }
+                other => super::error::BuildError::Other(other.to_string()),
+            })?;
+        link_span.exit();
+        
+        // 3. Generate AST, accounting for it and every copy of the output
+        let mut memory = options.max_memory.map_or_else(MemoryTracker::unlimited, MemoryTracker::new);
+        options.check_cancelled("serialize", start)?;
+        let serialize_span = tracing::info_span!("ddex.serialize").entered();
+        let mut generator = ASTGenerator::new(request.version.clone())
+            .with_timestamp_source(config.timestamp_source.clone())
+            .with_comments(options.preserve_comments);
+        let ast = generator.generate(&request)?;
+        let ast = super::namespace_minimizer::AdvancedNamespaceMinimizer::new(
+            Self::ern_version(&request.version),
+            options.namespace_strategy,
+        )
+        .with_extension_namespaces(&options.extension_namespaces)
+        .minimize(ast)
+        .map_err(super::error::BuildError::Other)?
+        .optimized_ast;
+        if run_plugins {
+            options.validation_plugins.check_ast(&ast, &mut warnings)?;
+        }
+        memory.allocate(ast.root.heap_size())?;
         
-        // 5. Generate XML
-        let writer = XmlWriter::new(config.clone());
+        // 4. Generate XML
+        let cache = options.cache_dir.as_ref()
+            .map(super::build_cache::BuildCache::open)
+            .transpose()?
+            .map(std::sync::Arc::new);
+        let writer = XmlWriter::new(config.clone()).with_cache(cache.clone());
         let xml = writer.write(&ast)?;
+        memory.allocate(xml.capacity())?;
+        serialize_span.exit();
         
-        // 6. Apply canonicalization if requested
+        // 4b. Check extension blocks against their cached schemas
+        if let Some(dir) = &options.extension_schema_dir {
+            let violations = super::extension_schema::ExtensionSchemaValidator::load_dir(dir)?.validate(&xml)?;
+            if !violations.is_empty() && options.preflight_level == super::preflight::PreflightLevel::Strict {
+                return Err(super::error::BuildError::ValidationFailed {
+                    errors: violations.iter()
+                        .map(|v| format!("{}: {}", v.kind.code(), v.message))
+                        .collect(),
+                });
+            }
+            warnings.extend(violations.into_iter().map(|violation| BuildWarning {
+                code: violation.kind.code().to_string(),
+                message: violation.message,
+                location: Some(violation.location.path),
+                suggestion: None,
+            }));
+        }
+        
+        // 5. Apply canonicalization if requested
         let (final_xml, canonical_hash) = if config.canon_mode == super::determinism::CanonMode::DbC14n {
+            options.check_cancelled("canonicalize", start)?;
             let canonicalizer = super::canonical::DB_C14N::new(config.clone());
             let canonical = canonicalizer.canonicalize(&xml)?;
+            memory.allocate(canonical.capacity())?;
             let hash = Some(canonicalizer.canonical_hash(&canonical)?);
             (canonical, hash)
         } else {
             (xml, None)
         };
         
-        // 7. Generate reproducibility banner if requested
+        // 6. Generate reproducibility banner if requested
         let reproducibility_banner = if config.emit_reproducibility_banner {
             Some(format!(
                 "Generated by DDEX Builder v{} with DB-C14N/{}",
@@ -274,6 +936,7 @@ impl DDEXBuilder {
         };
         
         let elapsed = start.elapsed();
+        ddex_core::telemetry::record_bytes_processed("build", final_xml.len() as u64);
         
         Ok(BuildResult {
             xml: final_xml.clone(),
@@ -285,76 +948,131 @@ impl DDEXBuilder {
                 deals: request.deals.len(),
                 generation_time_ms: elapsed.as_millis() as u64,
                 xml_size_bytes: final_xml.len(),
+                peak_memory_bytes: memory.peak(),
+                cache: cache.map(|cache| cache.stats()),
             },
             canonical_hash,
             reproducibility_banner,
+            normalizations,
         })
     }
     
     /// Generate IDs based on the selected strategy
-    fn generate_ids(&self, request: &mut BuildRequest, options: &BuildOptions) -> Result<(), super::error::BuildError> {
-        match options.id_strategy {
-            IdStrategy::UUID => {
-                self.generate_uuid_ids(request)?;
-            },
-            IdStrategy::UUIDv7 => {
-                self.generate_uuidv7_ids(request)?;
-            },
+    fn generate_ids(
+        &self,
+        request: &mut BuildRequest,
+        strategy: &IdStrategy,
+        options: &BuildOptions,
+        config: &super::determinism::DeterminismConfig,
+    ) -> Result<(), super::error::BuildError> {
+        match strategy {
             IdStrategy::Sequential => {
-                self.generate_sequential_ids(request)?;
+                self.generate_sequential_ids(request, config)?;
             },
             IdStrategy::StableHash => {
-                self.generate_stable_hash_ids(request, options)?;
+                self.generate_stable_hash_ids(request, options, config)?;
+            },
+            _ => {
+                let mut minter = super::id_generator::IdMinter::new(strategy.clone(), config)?;
+                self.generate_minted_ids(request, &mut minter);
             },
         }
         Ok(())
     }
     
-    /// Generate UUID v4 IDs
-    fn generate_uuid_ids(&self, request: &mut BuildRequest) -> Result<(), super::error::BuildError> {
-        use uuid::Uuid;
+    /// ERN version of a request's `version`, 4.3 when it is not recognized
+    fn ern_version(version: &str) -> ddex_core::models::versions::ERNVersion {
+        use ddex_core::models::versions::ERNVersion;
+        match version.trim_start_matches("ern/") {
+            "3.8.2" | "382" => ERNVersion::V3_8_2,
+            "4.2" | "42" => ERNVersion::V4_2,
+            "4.4" | "44" => ERNVersion::V4_4,
+            _ => ERNVersion::V4_3,
+        }
+    }
+    
+    /// Add a TrackRelease for every track of every release when the ERN 4
+    /// profile requires track-level releases; explicit ones are kept
+    fn add_required_track_releases(request: &mut BuildRequest) {
+        let is_ern_4 = !matches!(request.version.trim_start_matches("ern/"), "3.8.2" | "382");
+        let required = request.profile.as_deref()
+            .is_some_and(profile_requires_track_releases);
+        if !is_ern_4 || !required {
+            return;
+        }
+        
+        for release in &mut request.releases {
+            for track in &release.tracks {
+                if !release.track_releases.iter().any(|t| t.isrc == track.isrc) {
+                    release.track_releases.push(TrackReleaseRequest {
+                        release_reference: None,
+                        isrc: track.isrc.clone(),
+                    });
+                }
+            }
+        }
+    }
+    
+    /// Generate IDs from a minter, keyed by each entity's identifier
+    fn generate_minted_ids(&self, request: &mut BuildRequest, minter: &mut super::id_generator::IdMinter) {
+        use super::id_generator::IdKind;
         
         // Generate message ID if missing
         if request.header.message_id.is_none() {
-            request.header.message_id = Some(format!("MSG_{}", Uuid::new_v4()));
+            let parties = format!(
+                "{}-{}",
+                request.header.message_sender.party_name.first().map(|s| s.text.as_str()).unwrap_or_default(),
+                request.header.message_recipient.party_name.first().map(|s| s.text.as_str()).unwrap_or_default(),
+            );
+            request.header.message_id = Some(minter.mint(IdKind::MessageId, &parties));
         }
         
         // Generate release references if missing
         for release in &mut request.releases {
             if release.release_reference.is_none() {
-                release.release_reference = Some(format!("R{}", Uuid::new_v4().simple()));
+                let id = release.upc.as_deref().unwrap_or(&release.release_id);
+                release.release_reference = Some(minter.mint(IdKind::ReleaseReference, id));
             }
             
             // Generate resource references for tracks
             for track in &mut release.tracks {
                 if track.resource_reference.is_none() {
-                    track.resource_reference = Some(format!("A{}", Uuid::new_v4().simple()));
+                    track.resource_reference = Some(minter.mint(IdKind::ResourceReference, &track.isrc));
+                }
+            }
+            
+            for resource in &mut release.resources {
+                if resource.resource_reference.is_none() {
+                    let id = resource.isrc.as_deref().unwrap_or(&resource.resource_id);
+                    resource.resource_reference = Some(minter.mint(IdKind::ResourceReference, id));
+                }
+            }
+            
+            for track_release in &mut release.track_releases {
+                if track_release.release_reference.is_none() {
+                    track_release.release_reference = Some(minter.mint(IdKind::ReleaseReference, &track_release.isrc));
                 }
             }
         }
         
         // Generate deal references if missing
-        for (idx, deal) in request.deals.iter_mut().enumerate() {
+        for deal in &mut request.deals {
             if deal.deal_reference.is_none() {
-                deal.deal_reference = Some(format!("D{}", idx + 1));
+                deal.deal_reference = Some(minter.mint(IdKind::DealReference, &deal.deal_terms.commercial_model_type));
             }
         }
-        
-        Ok(())
-    }
-    
-    /// Generate UUID v7 IDs (time-ordered)
-    fn generate_uuidv7_ids(&self, request: &mut BuildRequest) -> Result<(), super::error::BuildError> {
-        // For now, fall back to UUID v4
-        // TODO: Implement proper UUID v7 generation
-        self.generate_uuid_ids(request)
     }
     
     /// Generate sequential IDs
-    fn generate_sequential_ids(&self, request: &mut BuildRequest) -> Result<(), super::error::BuildError> {
+    fn generate_sequential_ids(
+        &self,
+        request: &mut BuildRequest,
+        config: &super::determinism::DeterminismConfig,
+    ) -> Result<(), super::error::BuildError> {
         // Generate message ID if missing
         if request.header.message_id.is_none() {
-            request.header.message_id = Some(format!("MSG_{}", chrono::Utc::now().timestamp()));
+            let mut minter = super::id_generator::IdMinter::new(IdStrategy::Sequential, config)?;
+            request.header.message_id = Some(minter.mint(super::id_generator::IdKind::MessageId, ""));
         }
         
         // Generate release references if missing
@@ -369,6 +1087,25 @@ impl DDEXBuilder {
                     track.resource_reference = Some(format!("A{}", (idx * 1000) + track_idx + 1));
                 }
             }
+            
+            // Other resources are numbered after the tracks
+            let track_count = release.tracks.len();
+            for (resource_idx, resource) in release.resources.iter_mut().enumerate() {
+                if resource.resource_reference.is_none() {
+                    resource.resource_reference = Some(format!("A{}", (idx * 1000) + track_count + resource_idx + 1));
+                }
+            }
+        }
+        
+        // Track releases are numbered after the main releases
+        let mut next_release = request.releases.len();
+        for release in &mut request.releases {
+            for track_release in &mut release.track_releases {
+                next_release += 1;
+                if track_release.release_reference.is_none() {
+                    track_release.release_reference = Some(format!("R{}", next_release));
+                }
+            }
         }
         
         // Generate deal references if missing
@@ -382,14 +1119,20 @@ impl DDEXBuilder {
     }
     
     /// Generate stable hash-based IDs
-    fn generate_stable_hash_ids(&self, request: &mut BuildRequest, options: &BuildOptions) -> Result<(), super::error::BuildError> {
+    fn generate_stable_hash_ids(
+        &self,
+        request: &mut BuildRequest,
+        options: &BuildOptions,
+        determinism: &super::determinism::DeterminismConfig,
+    ) -> Result<(), super::error::BuildError> {
         let config = options.stable_hash_config.clone()
             .unwrap_or_default();
         let mut id_gen = super::id_generator::StableHashGenerator::new(config);
         
         // Generate message ID if missing
         if request.header.message_id.is_none() {
-            // Use sender/recipient info for stable message ID
+            // Use sender/recipient info and the message date for stable message ID
+            let date = determinism.timestamp_source.resolve()?;
             let sender_name = request.header.message_sender.party_name
                 .first()
                 .map(|s| s.text.clone())
@@ -402,7 +1145,7 @@ impl DDEXBuilder {
             let msg_id = id_gen.generate_party_id(
                 &format!("{}-{}", sender_name, recipient_name),
                 "MessageHeader",
-                &[chrono::Utc::now().format("%Y%m%d").to_string()],
+                &[date.get(..10).unwrap_or(&date).replace('-', "")],
             )?;
             request.header.message_id = Some(msg_id);
         }
@@ -410,13 +1153,12 @@ impl DDEXBuilder {
         // Generate stable IDs for releases
         for release in &mut request.releases {
             if release.release_reference.is_none() {
-                let id = id_gen.generate_release_id(
+                let title = release.title.first()
+                    .map(|t| t.text.as_str())
+                    .unwrap_or_default();
+                let id = id_gen.generate_release_reference(
                     release.upc.as_deref().unwrap_or(&release.release_id),
-                    "Album",
-                    &release.tracks.iter()
-                        .map(|t| t.isrc.clone())
-                        .collect::<Vec<_>>(),
-                    &[], // Empty territory set for now
+                    title,
                 )?;
                 release.release_reference = Some(id);
             }
@@ -424,18 +1166,31 @@ impl DDEXBuilder {
             // Generate stable IDs for tracks/resources
             for track in &mut release.tracks {
                 if track.resource_reference.is_none() {
-                    // Parse duration to seconds for stable hash
-                    let duration_seconds = self.parse_duration_to_seconds(&track.duration)
-                        .unwrap_or(0);
-                    
-                    let id = id_gen.generate_resource_id(
-                        &track.isrc,
-                        duration_seconds,
-                        None, // No file hash available
-                    )?;
+                    let id = id_gen.generate_resource_reference(&track.isrc, &track.title)?;
                     track.resource_reference = Some(id);
                 }
             }
+            
+            for resource in &mut release.resources {
+                if resource.resource_reference.is_none() {
+                    let id = id_gen.generate_resource_reference(
+                        resource.isrc.as_deref().unwrap_or(&resource.resource_id),
+                        resource.title.as_deref().unwrap_or_default(),
+                    )?;
+                    resource.resource_reference = Some(id);
+                }
+            }
+            
+            for track_release in &mut release.track_releases {
+                if track_release.release_reference.is_none() {
+                    let title = release.tracks.iter()
+                        .find(|t| t.isrc == track_release.isrc)
+                        .map(|t| t.title.as_str())
+                        .unwrap_or_default();
+                    let id = id_gen.generate_release_reference(&track_release.isrc, title)?;
+                    track_release.release_reference = Some(id);
+                }
+            }
         }
         
         // Generate deal references if missing
@@ -452,44 +1207,6 @@ impl DDEXBuilder {
         Ok(())
     }
     
-    /// Parse ISO 8601 duration to seconds
-    fn parse_duration_to_seconds(&self, duration: &str) -> Option<u32> {
-        // Simple parser for PT3M45S format
-        if !duration.starts_with("PT") {
-            return None;
-        }
-        
-        let mut seconds = 0u32;
-        let mut current_num = String::new();
-        
-        for ch in duration[2..].chars() {
-            match ch {
-                '0'..='9' => current_num.push(ch),
-                'H' => {
-                    if let Ok(hours) = current_num.parse::<u32>() {
-                        seconds += hours * 3600;
-                    }
-                    current_num.clear();
-                },
-                'M' => {
-                    if let Ok(minutes) = current_num.parse::<u32>() {
-                        seconds += minutes * 60;
-                    }
-                    current_num.clear();
-                },
-                'S' => {
-                    if let Ok(secs) = current_num.parse::<u32>() {
-                        seconds += secs;
-                    }
-                    current_num.clear();
-                },
-                _ => {}
-            }
-        }
-        
-        Some(seconds)
-    }
-    
     /// Legacy preflight check method (kept for compatibility)
     fn preflight(&self, request: &BuildRequest, level: super::preflight::PreflightLevel) -> Result<Vec<BuildWarning>, super::error::BuildError> {
         let mut warnings = Vec::new();
@@ -504,6 +1221,7 @@ impl DDEXBuilder {
                 code: "NO_RELEASES".to_string(),
                 message: "No releases in request".to_string(),
                 location: Some("/releases".to_string()),
+                suggestion: None,
             });
         }
         
@@ -532,13 +1250,8 @@ impl DDEXBuilder {
         new_xml: &str, 
         config: super::diff::DiffConfig
     ) -> Result<super::diff::types::ChangeSet, super::error::BuildError> {
-        // Parse both XML documents to AST
-        let old_ast = self.parse_xml_to_ast(old_xml)?;
-        let new_ast = self.parse_xml_to_ast(new_xml)?;
-        
-        // Create diff engine and compare
         let mut diff_engine = super::diff::DiffEngine::new_with_config(config);
-        diff_engine.diff(&old_ast, &new_ast)
+        diff_engine.diff_xml(old_xml, new_xml)
     }
     
     /// Compare a BuildRequest with existing XML to see what would change
@@ -554,29 +1267,6 @@ impl DDEXBuilder {
         self.diff_xml(existing_xml, &build_result.xml)
     }
     
-    /// Helper to parse XML string to AST
-    fn parse_xml_to_ast(&self, xml: &str) -> Result<super::ast::AST, super::error::BuildError> {
-        use quick_xml::Reader;
-        
-        let mut reader = Reader::from_str(xml);
-        reader.config_mut().trim_text(true);
-        
-        // This is a simplified XML->AST parser
-        // In a production system, you'd want to use the actual ddex-parser
-        let mut root_element = super::ast::Element::new("Root");
-        let namespace_map = indexmap::IndexMap::new();
-        
-        // For now, create a basic AST structure
-        // TODO: Implement proper XML parsing or integrate with ddex-parser
-        root_element = root_element.with_text(xml);
-        
-        Ok(super::ast::AST {
-            root: root_element,
-            namespaces: namespace_map,
-            schema_location: None,
-        })
-    }
-    
     /// Create an UpdateReleaseMessage from two DDEX messages
     /// 
     /// This method compares an original DDEX message with an updated version and
@@ -809,10 +1499,10 @@ impl DDEXBuilder {
     ) -> Result<(), super::error::BuildError> {
         xml.push_str("  <ResourceUpdates>\n");
         
-        for (resource_id, update) in resource_updates {
+        for update in resource_updates.values() {
             xml.push_str("    <ResourceUpdate>\n");
             xml.push_str(&format!("      <ResourceId>{}</ResourceId>\n", 
-                self.escape_xml(resource_id)));
+                self.escape_xml(&update.resource_id)));
             xml.push_str(&format!("      <ResourceReference>{}</ResourceReference>\n", 
                 self.escape_xml(&update.resource_reference)));
             xml.push_str(&format!("      <Action>{}</Action>\n", update.action));
@@ -840,6 +1530,17 @@ impl DDEXBuilder {
                 xml.push_str("      </ResourceData>\n");
             }
             
+            if !update.technical_updates.is_empty() {
+                xml.push_str("      <TechnicalUpdates>\n");
+                for technical in &update.technical_updates {
+                    self.serialize_field_update(xml, "TechnicalUpdate", &technical.field_name,
+                        technical.old_value.as_deref(), technical.new_value.as_deref());
+                }
+                xml.push_str("      </TechnicalUpdates>\n");
+            }
+            
+            self.serialize_metadata_updates(xml, &update.metadata_updates);
+            
             xml.push_str("    </ResourceUpdate>\n");
         }
         
@@ -854,10 +1555,10 @@ impl DDEXBuilder {
     ) -> Result<(), super::error::BuildError> {
         xml.push_str("  <ReleaseUpdates>\n");
         
-        for (release_id, update) in release_updates {
+        for update in release_updates.values() {
             xml.push_str("    <ReleaseUpdate>\n");
             xml.push_str(&format!("      <ReleaseId>{}</ReleaseId>\n", 
-                self.escape_xml(release_id)));
+                self.escape_xml(&update.release_id)));
             xml.push_str(&format!("      <ReleaseReference>{}</ReleaseReference>\n", 
                 self.escape_xml(&update.release_reference)));
             xml.push_str(&format!("      <Action>{}</Action>\n", update.action));
@@ -882,9 +1583,41 @@ impl DDEXBuilder {
                         self.escape_xml(upc)));
                 }
                 
+                if let Some(ref release_date) = data.release_date {
+                    xml.push_str(&format!("        <ReleaseDate>{}</ReleaseDate>\n", 
+                        self.escape_xml(release_date)));
+                }
+                
+                if let Some(ref genre) = data.genre {
+                    xml.push_str(&format!("        <Genre>{}</Genre>\n", 
+                        self.escape_xml(genre)));
+                }
+                
+                for reference in &data.resource_references {
+                    xml.push_str(&format!("        <ResourceReference>{}</ResourceReference>\n", 
+                        self.escape_xml(reference)));
+                }
+                
                 xml.push_str("      </ReleaseData>\n");
             }
             
+            if !update.resource_reference_updates.is_empty() {
+                xml.push_str("      <ReferenceUpdates>\n");
+                for reference in &update.resource_reference_updates {
+                    xml.push_str("        <ReferenceUpdate>\n");
+                    xml.push_str(&format!("          <ReferenceType>{}</ReferenceType>\n", 
+                        self.escape_xml(&reference.reference_type)));
+                    xml.push_str(&format!("          <OldReference>{}</OldReference>\n", 
+                        self.escape_xml(&reference.old_reference)));
+                    xml.push_str(&format!("          <NewReference>{}</NewReference>\n", 
+                        self.escape_xml(&reference.new_reference)));
+                    xml.push_str("        </ReferenceUpdate>\n");
+                }
+                xml.push_str("      </ReferenceUpdates>\n");
+            }
+            
+            self.serialize_metadata_updates(xml, &update.metadata_updates);
+            
             xml.push_str("    </ReleaseUpdate>\n");
         }
         
@@ -899,14 +1632,52 @@ impl DDEXBuilder {
     ) -> Result<(), super::error::BuildError> {
         xml.push_str("  <DealUpdates>\n");
         
-        for (deal_id, update) in deal_updates {
+        for update in deal_updates.values() {
             xml.push_str("    <DealUpdate>\n");
             xml.push_str(&format!("      <DealId>{}</DealId>\n", 
-                self.escape_xml(deal_id)));
+                self.escape_xml(&update.deal_id)));
             xml.push_str(&format!("      <DealReference>{}</DealReference>\n", 
                 self.escape_xml(&update.deal_reference)));
             xml.push_str(&format!("      <Action>{}</Action>\n", update.action));
             
+            // Add deal data if present
+            if let Some(ref data) = update.deal_data {
+                xml.push_str("      <DealData>\n");
+                xml.push_str(&format!("        <CommercialModelType>{}</CommercialModelType>\n", 
+                    self.escape_xml(&data.commercial_model_type)));
+                
+                for territory in &data.territory_codes {
+                    xml.push_str(&format!("        <TerritoryCode>{}</TerritoryCode>\n", 
+                        self.escape_xml(territory)));
+                }
+                
+                if let Some(ref start_date) = data.start_date {
+                    xml.push_str(&format!("        <StartDate>{}</StartDate>\n", 
+                        self.escape_xml(start_date)));
+                }
+                
+                if let Some(ref end_date) = data.end_date {
+                    xml.push_str(&format!("        <EndDate>{}</EndDate>\n", 
+                        self.escape_xml(end_date)));
+                }
+                
+                if let Some(ref price) = data.price {
+                    xml.push_str(&format!("        <Price CurrencyCode=\"{}\">{}</Price>\n", 
+                        self.escape_xml(&price.currency_code), self.escape_xml(&price.amount)));
+                }
+                
+                xml.push_str("      </DealData>\n");
+            }
+            
+            if !update.terms_updates.is_empty() {
+                xml.push_str("      <TermsUpdates>\n");
+                for terms in &update.terms_updates {
+                    self.serialize_field_update(xml, "TermsUpdate", &terms.field_name,
+                        terms.old_value.as_deref(), terms.new_value.as_deref());
+                }
+                xml.push_str("      </TermsUpdates>\n");
+            }
+            
             xml.push_str("    </DealUpdate>\n");
         }
         
@@ -914,6 +1685,37 @@ impl DDEXBuilder {
         Ok(())
     }
     
+    fn serialize_metadata_updates(&self, xml: &mut String, updates: &indexmap::IndexMap<String, String>) {
+        if updates.is_empty() {
+            return;
+        }
+        
+        xml.push_str("      <MetadataUpdates>\n");
+        for (field, value) in updates {
+            self.serialize_field_update(xml, "MetadataUpdate", field, None, Some(value));
+        }
+        xml.push_str("      </MetadataUpdates>\n");
+    }
+    
+    fn serialize_field_update(
+        &self,
+        xml: &mut String,
+        tag: &str,
+        field: &str,
+        old_value: Option<&str>,
+        new_value: Option<&str>,
+    ) {
+        xml.push_str(&format!("        <{}>\n", tag));
+        xml.push_str(&format!("          <Field>{}</Field>\n", self.escape_xml(field)));
+        if let Some(old_value) = old_value {
+            xml.push_str(&format!("          <OldValue>{}</OldValue>\n", self.escape_xml(old_value)));
+        }
+        if let Some(new_value) = new_value {
+            xml.push_str(&format!("          <NewValue>{}</NewValue>\n", self.escape_xml(new_value)));
+        }
+        xml.push_str(&format!("        </{}>\n", tag));
+    }
+    
     fn escape_xml(&self, text: &str) -> String {
         text.replace('&', "&amp;")
             .replace('<', "&lt;")
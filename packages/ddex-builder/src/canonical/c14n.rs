@@ -0,0 +1,554 @@
+//! W3C canonical XML and fidelity-preserving rewrites
+//!
+//! [`DB_C14N`](super::DB_C14N) rebuilds a document in the builder's own
+//! layout. The model here instead keeps everything a document carries –
+//! declaration, comments, processing instructions, attribute order,
+//! namespace prefixes and whitespace – so that each [`WriteRules`] switch
+//! decides on its own what survives. It backs C14N, custom canonicalization
+//! rules and round-trip rebuilds.
+
+use super::rules;
+use crate::determinism::DeterminismConfig;
+use crate::error::BuildError;
+use crate::{CanonicalizationAlgorithm, CustomCanonicalizationRules, FidelityOptions};
+use indexmap::{IndexMap, IndexSet};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// Canonicalize a document with the algorithm selected in `options`
+pub(crate) fn canonicalize(
+    xml: &str,
+    options: &FidelityOptions,
+    config: &DeterminismConfig,
+) -> Result<String, BuildError> {
+    let rules = match &options.canonicalization {
+        CanonicalizationAlgorithm::None => return Ok(xml.to_string()),
+        CanonicalizationAlgorithm::DbC14N => return super::DB_C14N::new(config.clone()).canonicalize(xml),
+        // On whole documents C14N 1.1 only differs from 1.0 in how xml:*
+        // attributes are inherited into document subsets
+        CanonicalizationAlgorithm::C14N | CanonicalizationAlgorithm::C14N11 => {
+            WriteRules::c14n(options.preserve_comments)
+        }
+        CanonicalizationAlgorithm::Custom(custom) => WriteRules::custom(custom, options),
+    };
+    Ok(Document::parse(xml)?.write(&rules))
+}
+
+/// What to keep and how to order it when writing a [`Document`]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct WriteRules {
+    /// Write the document's XML declaration
+    pub declaration: bool,
+    pub comments: bool,
+    pub processing_instructions: bool,
+    /// Keep elements and attributes outside DDEX and W3C namespaces
+    pub extensions: bool,
+    /// Sort attributes by namespace URI and local name, declarations first
+    pub sort_attributes: bool,
+    /// Attributes written first, in this order
+    pub attribute_priority: Vec<String>,
+    /// Rename prefixes bound to DDEX namespaces to their locked prefixes
+    pub lock_prefixes: bool,
+    /// Drop namespace declarations that repeat an inherited binding
+    pub minimize_namespaces: bool,
+    /// Trim text and drop whitespace-only text
+    pub trim_whitespace: bool,
+    pub normalize_line_endings: bool,
+    /// Write empty elements as start and end tag pairs
+    pub expand_empty_elements: bool,
+    /// Child element order, keyed by the parent's local name
+    pub element_order: IndexMap<String, Vec<String>>,
+}
+
+impl WriteRules {
+    /// Canonical XML 1.0, with or without comments
+    pub(crate) fn c14n(with_comments: bool) -> Self {
+        Self {
+            comments: with_comments,
+            processing_instructions: true,
+            extensions: true,
+            sort_attributes: true,
+            normalize_line_endings: true,
+            expand_empty_elements: true,
+            ..Default::default()
+        }
+    }
+
+    /// User-defined canonicalization, keeping what the fidelity options ask for
+    pub(crate) fn custom(custom: &CustomCanonicalizationRules, options: &FidelityOptions) -> Self {
+        Self {
+            declaration: true,
+            comments: options.preserve_comments,
+            processing_instructions: options.preserve_processing_instructions,
+            extensions: options.preserve_extensions,
+            sort_attributes: custom.sort_attributes,
+            attribute_priority: custom.attribute_ordering.clone(),
+            lock_prefixes: !options.preserve_namespace_prefixes,
+            minimize_namespaces: custom.minimize_namespaces,
+            trim_whitespace: !custom.preserve_whitespace,
+            normalize_line_endings: custom.normalize_line_endings,
+            expand_empty_elements: false,
+            element_order: custom.element_ordering.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+        }
+    }
+
+    /// Rewrite a document unchanged except where the fidelity options allow
+    pub(crate) fn rebuild(options: &FidelityOptions) -> Self {
+        Self {
+            declaration: true,
+            comments: options.preserve_comments,
+            processing_instructions: options.preserve_processing_instructions,
+            extensions: options.preserve_extensions,
+            sort_attributes: !options.preserve_attribute_order,
+            lock_prefixes: !options.preserve_namespace_prefixes,
+            ..Default::default()
+        }
+    }
+}
+
+/// A parsed document that keeps every node and attribute as written
+#[derive(Debug, Clone)]
+pub(crate) struct Document {
+    declaration: Option<String>,
+    prolog: Vec<Node>,
+    root: Element,
+    epilog: Vec<Node>,
+}
+
+#[derive(Debug, Clone)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Element(Element),
+    Text(String),
+    Comment(String),
+    ProcessingInstruction(String),
+}
+
+impl Document {
+    /// Parse a document without trimming or dropping anything
+    pub(crate) fn parse(xml: &str) -> Result<Self, BuildError> {
+        let mut reader = Reader::from_str(xml);
+        let mut declaration = None;
+        let mut prolog = Vec::new();
+        let mut epilog = Vec::new();
+        let mut stack: Vec<Element> = Vec::new();
+        let mut root = None;
+
+        loop {
+            let event = reader
+                .read_event()
+                .map_err(|e| parse_error(format!("{} at position {}", e, reader.buffer_position())))?;
+            let node = match event {
+                Event::Decl(decl) => {
+                    declaration = Some(String::from_utf8_lossy(&decl).into_owned());
+                    continue;
+                }
+                Event::Start(start) => {
+                    stack.push(read_element(&start)?);
+                    continue;
+                }
+                Event::Empty(start) => Node::Element(read_element(&start)?),
+                Event::End(_) => Node::Element(
+                    stack.pop().ok_or_else(|| parse_error("Unbalanced end tag".to_string()))?,
+                ),
+                Event::Text(text) => Node::Text(
+                    text.unescape()
+                        .map_err(|e| parse_error(format!("Text unescape error: {}", e)))?
+                        .into_owned(),
+                ),
+                Event::CData(data) => Node::Text(String::from_utf8_lossy(&data).into_owned()),
+                Event::Comment(comment) => Node::Comment(String::from_utf8_lossy(&comment).into_owned()),
+                Event::PI(pi) => Node::ProcessingInstruction(String::from_utf8_lossy(&pi).into_owned()),
+                Event::DocType(_) => continue,
+                Event::Eof => break,
+            };
+            match (stack.last_mut(), node) {
+                (Some(parent), node) => parent.children.push(node),
+                (None, Node::Element(element)) if root.is_none() => root = Some(element),
+                (None, Node::Element(element)) => {
+                    return Err(parse_error(format!("Second root element '{}'", element.name)))
+                }
+                // Whitespace between top-level nodes
+                (None, Node::Text(_)) => {}
+                (None, node) if root.is_none() => prolog.push(node),
+                (None, node) => epilog.push(node),
+            }
+        }
+
+        if let Some(element) = stack.last() {
+            return Err(parse_error(format!("Unclosed element '{}'", element.name)));
+        }
+        let root = root.ok_or_else(|| parse_error("No root element found".to_string()))?;
+        Ok(Self { declaration, prolog, root, epilog })
+    }
+
+    /// Serialize the document according to `rules`
+    pub(crate) fn write(&self, rules: &WriteRules) -> String {
+        let mut writer = Writer {
+            rules,
+            renames: self.prefix_renames(rules),
+            scopes: Vec::new(),
+            out: String::new(),
+        };
+
+        if let Some(declaration) = self.declaration.as_ref().filter(|_| rules.declaration) {
+            writer.out.push_str(&format!("<?{}?>\n", declaration));
+        }
+        for node in &self.prolog {
+            if writer.keeps(node) {
+                writer.node(node);
+                writer.out.push('\n');
+            }
+        }
+        writer.element(&self.root);
+        for node in &self.epilog {
+            if writer.keeps(node) {
+                writer.out.push('\n');
+                writer.node(node);
+            }
+        }
+        if rules.declaration {
+            writer.out.push('\n');
+        }
+        writer.out
+    }
+
+    /// Prefixes to rename to the locked prefix of the DDEX namespace they bind
+    ///
+    /// Only prefixes bound to a single namespace throughout the document are
+    /// renamed, and never onto a prefix the document already uses.
+    fn prefix_renames(&self, rules: &WriteRules) -> IndexMap<String, String> {
+        let mut renames = IndexMap::new();
+        if !rules.lock_prefixes {
+            return renames;
+        }
+
+        let mut bindings: IndexMap<String, IndexSet<String>> = IndexMap::new();
+        collect_bindings(&self.root, &mut bindings);
+        let version = bindings
+            .values()
+            .flatten()
+            .find_map(|uri| uri.strip_prefix("http://ddex.net/xml/ern/"))
+            .unwrap_or("43")
+            .to_string();
+        let locked = rules::get_namespace_prefixes(&version);
+
+        let mut taken: IndexSet<String> = bindings.keys().cloned().collect();
+        for (prefix, uris) in &bindings {
+            if prefix.is_empty() || uris.len() != 1 {
+                continue;
+            }
+            if let Some(target) = uris.first().and_then(|uri| locked.get(uri)) {
+                if target != prefix && taken.insert(target.clone()) {
+                    renames.insert(prefix.clone(), target.clone());
+                }
+            }
+        }
+        renames
+    }
+}
+
+fn collect_bindings(element: &Element, bindings: &mut IndexMap<String, IndexSet<String>>) {
+    for (key, uri) in &element.attributes {
+        if let Some(prefix) = declared_prefix(key) {
+            bindings.entry(prefix.to_string()).or_default().insert(uri.clone());
+        }
+    }
+    for child in &element.children {
+        if let Node::Element(child) = child {
+            collect_bindings(child, bindings);
+        }
+    }
+}
+
+struct Writer<'a> {
+    rules: &'a WriteRules,
+    renames: IndexMap<String, String>,
+    /// Namespace bindings declared by each open element, innermost last
+    scopes: Vec<IndexMap<String, String>>,
+    out: String,
+}
+
+impl Writer<'_> {
+    fn keeps(&self, node: &Node) -> bool {
+        match node {
+            Node::Element(element) => self.rules.extensions || !self.is_extension(&element.name, true),
+            Node::Text(text) => !self.rules.trim_whitespace || !text.trim().is_empty(),
+            Node::Comment(_) => self.rules.comments,
+            Node::ProcessingInstruction(_) => self.rules.processing_instructions,
+        }
+    }
+
+    fn node(&mut self, node: &Node) {
+        match node {
+            Node::Element(element) => self.element(element),
+            Node::Text(text) => {
+                let text = if self.rules.trim_whitespace { text.trim() } else { text.as_str() };
+                let text = escape(text, false, self.rules.normalize_line_endings);
+                self.out.push_str(&text);
+            }
+            Node::Comment(comment) => self.out.push_str(&format!("<!--{}-->", comment)),
+            Node::ProcessingInstruction(pi) => self.out.push_str(&format!("<?{}?>", pi)),
+        }
+    }
+
+    fn element(&mut self, element: &Element) {
+        self.scopes.push(
+            element
+                .attributes
+                .iter()
+                .filter_map(|(key, uri)| declared_prefix(key).map(|prefix| (prefix.to_string(), uri.clone())))
+                .collect(),
+        );
+
+        let mut attributes = Vec::new();
+        for (key, value) in &element.attributes {
+            match declared_prefix(key) {
+                Some(prefix) => {
+                    if !self.rules.extensions && is_extension_namespace(value) {
+                        continue;
+                    }
+                    if self.rules.minimize_namespaces && self.inherited(prefix) == Some(value.as_str()) {
+                        continue;
+                    }
+                    let key = match self.renames.get(prefix) {
+                        Some(renamed) => format!("xmlns:{}", renamed),
+                        None => key.clone(),
+                    };
+                    attributes.push((self.attribute_sort_key(&key, true), key, value));
+                }
+                None => {
+                    if !self.rules.extensions && self.is_extension(key, false) {
+                        continue;
+                    }
+                    let key = self.rename(key);
+                    attributes.push((self.attribute_sort_key(&key, false), key, value));
+                }
+            }
+        }
+        if self.rules.sort_attributes || !self.rules.attribute_priority.is_empty() {
+            attributes.sort_by(|a, b| {
+                let by_name = if self.rules.sort_attributes { a.0.1.cmp(&b.0.1) } else { std::cmp::Ordering::Equal };
+                a.0.0.cmp(&b.0.0).then(by_name)
+            });
+        }
+
+        let name = self.rename(&element.name);
+        self.out.push('<');
+        self.out.push_str(&name);
+        for (_, key, value) in &attributes {
+            self.out.push_str(&format!(" {}=\"{}\"", key, escape(value, true, self.rules.normalize_line_endings)));
+        }
+
+        let mut children: Vec<&Node> = element.children.iter().filter(|child| self.keeps(child)).collect();
+        if let Some(order) = self.rules.element_order.get(local_name(&element.name)) {
+            reorder_elements(&mut children, order);
+        }
+
+        if children.is_empty() && !self.rules.expand_empty_elements {
+            self.out.push_str("/>");
+        } else {
+            self.out.push('>');
+            for child in children {
+                self.node(child);
+            }
+            self.out.push_str(&format!("</{}>", name));
+        }
+        self.scopes.pop();
+    }
+
+    /// Sort key of an attribute: priority, then declarations before
+    /// attributes, then namespace URI and local name
+    fn attribute_sort_key(&self, key: &str, declaration: bool) -> (usize, (bool, String, String)) {
+        let priority = self
+            .rules
+            .attribute_priority
+            .iter()
+            .position(|name| name == key)
+            .unwrap_or(usize::MAX);
+        let name = if declaration {
+            (false, String::new(), declared_prefix(key).unwrap_or_default().to_string())
+        } else {
+            let uri = key.split_once(':').and_then(|(prefix, _)| self.resolve(prefix)).unwrap_or_default();
+            (true, uri.to_string(), local_name(key).to_string())
+        };
+        (priority, name)
+    }
+
+    fn rename(&self, qname: &str) -> String {
+        match qname.split_once(':') {
+            Some((prefix, local)) => match self.renames.get(prefix) {
+                Some(renamed) => format!("{}:{}", renamed, local),
+                None => qname.to_string(),
+            },
+            None => qname.to_string(),
+        }
+    }
+
+    /// Namespace URI bound to a prefix ("" for the default namespace)
+    fn resolve(&self, prefix: &str) -> Option<&str> {
+        if prefix == "xml" {
+            return Some(XML_NAMESPACE);
+        }
+        self.scopes.iter().rev().find_map(|scope| scope.get(prefix)).map(String::as_str)
+    }
+
+    /// Binding of a prefix inherited from the ancestors of the current element
+    fn inherited(&self, prefix: &str) -> Option<&str> {
+        let ancestors = &self.scopes[..self.scopes.len().saturating_sub(1)];
+        ancestors.iter().rev().find_map(|scope| scope.get(prefix)).map(String::as_str)
+    }
+
+    /// Whether a name belongs to a partner extension namespace; unprefixed
+    /// attributes are in no namespace
+    fn is_extension(&self, qname: &str, is_element: bool) -> bool {
+        let uri = match qname.split_once(':') {
+            Some((prefix, _)) => self.resolve(prefix),
+            None if is_element => self.resolve(""),
+            None => None,
+        };
+        uri.is_some_and(is_extension_namespace)
+    }
+}
+
+/// Reorder the element children to follow `order`, leaving other nodes in place
+fn reorder_elements(children: &mut [&Node], order: &[String]) {
+    let rank = |node: &Node| match node {
+        Node::Element(element) => order
+            .iter()
+            .position(|name| name == local_name(&element.name))
+            .unwrap_or(usize::MAX),
+        _ => usize::MAX,
+    };
+    let slots: Vec<usize> = (0..children.len())
+        .filter(|&i| matches!(children[i], Node::Element(_)))
+        .collect();
+    let mut elements: Vec<&Node> = slots.iter().map(|&i| children[i]).collect();
+    elements.sort_by_key(|node| rank(node));
+    for (slot, element) in slots.into_iter().zip(elements) {
+        children[slot] = element;
+    }
+}
+
+fn read_element(start: &BytesStart<'_>) -> Result<Element, BuildError> {
+    let mut attributes = Vec::new();
+    for attr in start.attributes() {
+        let attr = attr.map_err(|e| parse_error(format!("Attribute error: {}", e)))?;
+        let value = attr
+            .unescape_value()
+            .map_err(|e| parse_error(format!("Attribute unescape error: {}", e)))?;
+        attributes.push((String::from_utf8_lossy(attr.key.as_ref()).into_owned(), value.into_owned()));
+    }
+    Ok(Element {
+        name: String::from_utf8_lossy(start.name().as_ref()).into_owned(),
+        attributes,
+        children: Vec::new(),
+    })
+}
+
+/// Prefix declared by a namespace declaration attribute ("" for `xmlns`)
+fn declared_prefix(key: &str) -> Option<&str> {
+    if key == "xmlns" {
+        Some("")
+    } else {
+        key.strip_prefix("xmlns:")
+    }
+}
+
+fn local_name(qname: &str) -> &str {
+    qname.split_once(':').map_or(qname, |(_, local)| local)
+}
+
+fn is_extension_namespace(uri: &str) -> bool {
+    !uri.contains("ddex.net") && !uri.starts_with("http://www.w3.org/")
+}
+
+/// Escape text or an attribute value the way C14N does
+fn escape(value: &str, attribute: bool, normalize_line_endings: bool) -> String {
+    let value = if normalize_line_endings {
+        value.replace("\r\n", "\n").replace('\r', "\n")
+    } else {
+        value.to_string()
+    };
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' if !attribute => escaped.push_str("&gt;"),
+            '"' if attribute => escaped.push_str("&quot;"),
+            '\t' if attribute => escaped.push_str("&#x9;"),
+            '\n' if attribute => escaped.push_str("&#xA;"),
+            '\r' => escaped.push_str("&#xD;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn parse_error(message: String) -> BuildError {
+    BuildError::InvalidFormat {
+        field: "xml".to_string(),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+        <?xml-stylesheet href=\"ern.xsl\"?>\n\
+        <ns0:NewReleaseMessage xmlns:ns0=\"http://ddex.net/xml/ern/43\" xmlns:x=\"http://example.com/x\" \
+        MessageSchemaVersionId=\"ern/43\" LanguageAndScriptCode=\"en\">\n  \
+        <!-- header -->\n  <MessageHeader x:Batch=\"7\"><MessageId>M&amp;1</MessageId><x:Note/></MessageHeader>\n\
+        </ns0:NewReleaseMessage>\n";
+
+    #[test]
+    fn test_c14n() {
+        let document = Document::parse(INPUT).unwrap();
+        assert_eq!(
+            document.write(&WriteRules::c14n(false)),
+            "<?xml-stylesheet href=\"ern.xsl\"?>\n\
+             <ns0:NewReleaseMessage xmlns:ns0=\"http://ddex.net/xml/ern/43\" xmlns:x=\"http://example.com/x\" \
+             LanguageAndScriptCode=\"en\" MessageSchemaVersionId=\"ern/43\">\n  \n  \
+             <MessageHeader x:Batch=\"7\"><MessageId>M&amp;1</MessageId><x:Note></x:Note></MessageHeader>\n\
+             </ns0:NewReleaseMessage>"
+        );
+        assert!(document.write(&WriteRules::c14n(true)).contains("<!-- header -->"));
+    }
+
+    #[test]
+    fn test_rebuild_follows_fidelity_options() {
+        let document = Document::parse(INPUT).unwrap();
+        let mut options = FidelityOptions {
+            preserve_comments: true,
+            preserve_processing_instructions: true,
+            preserve_attribute_order: true,
+            preserve_namespace_prefixes: true,
+            ..Default::default()
+        };
+        assert_eq!(document.write(&WriteRules::rebuild(&options)), INPUT);
+
+        options = FidelityOptions {
+            preserve_extensions: false,
+            ..Default::default()
+        };
+        let rebuilt = document.write(&WriteRules::rebuild(&options));
+        assert!(rebuilt.starts_with(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<ern:NewReleaseMessage xmlns:ern=\"http://ddex.net/xml/ern/43\" \
+             LanguageAndScriptCode=\"en\" MessageSchemaVersionId=\"ern/43\">"
+        ));
+        assert!(rebuilt.contains("<MessageHeader><MessageId>M&amp;1</MessageId></MessageHeader>"));
+        assert!(rebuilt.ends_with("</ern:NewReleaseMessage>\n"));
+        assert!(!rebuilt.contains("header -->") && !rebuilt.contains("xml-stylesheet"));
+    }
+}
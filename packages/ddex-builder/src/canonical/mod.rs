@@ -75,15 +75,24 @@
 use indexmap::IndexMap;
 use sha2::{Sha256, Digest};
 use quick_xml::{Reader, events::Event};
+use rayon::prelude::*;
 use std::collections::BTreeMap;
 
 pub mod rules;
+pub(crate) mod c14n;
+
+/// Lists whose entries are canonicalized and serialized in parallel
+const PARALLEL_LISTS: &[&str] = &["ReleaseList", "ResourceList", "DealList"];
+
+/// Lists shorter than this are not worth handing to the thread pool
+const PARALLEL_THRESHOLD: usize = 32;
 
 /// DB-C14N/1.0 canonicalizer
 #[allow(non_camel_case_types)]  // Allow non-standard naming for DB-C14N
 pub struct DB_C14N {
     config: super::determinism::DeterminismConfig,
     version: String,
+    parallel: bool,
 }
 
 impl DB_C14N {
@@ -92,12 +101,29 @@ impl DB_C14N {
         Self { 
             config,
             version: "4.3".to_string(), // Default to latest
+            parallel: true,
         }
     }
     
     /// Create a new canonicalizer with specific ERN version
     pub fn with_version(config: super::determinism::DeterminismConfig, version: String) -> Self {
-        Self { config, version }
+        Self { config, version, parallel: true }
+    }
+    
+    /// Process the releases, resources and deals of large lists on rayon's
+    /// thread pool (the default); entries are joined in document order, so
+    /// the output is byte-identical either way
+    pub fn with_parallelism(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+    
+    /// Whether the entries of `element` are processed in parallel
+    fn splits(&self, element: &XmlElement) -> bool {
+        let local_name = element.name.rsplit(':').next().unwrap_or(&element.name);
+        self.parallel
+            && element.children.len() >= PARALLEL_THRESHOLD
+            && PARALLEL_LISTS.contains(&local_name)
     }
     
     /// Detect ERN version from XML content
@@ -108,12 +134,15 @@ impl DB_C14N {
             "4.2".to_string()
         } else if xml.contains("http://ddex.net/xml/ern/43") {
             "4.3".to_string()
+        } else if xml.contains("http://ddex.net/xml/ern/44") {
+            "4.4".to_string()
         } else {
             self.version.clone() // Use configured version as fallback
         }
     }
     
     /// Canonicalize XML according to DB-C14N/1.0 spec
+    #[tracing::instrument(name = "ddex.canonicalize", skip_all, fields(bytes = xml.len()))]
     pub fn canonicalize(&self, xml: &str) -> Result<String, super::error::BuildError> {
         // Detect ERN version from content
         let detected_version = self.detect_version(xml);
@@ -262,24 +291,34 @@ impl DB_C14N {
         self.sort_child_elements(&mut element.children, &element.name, version)?;
         
         // 4. Recursively canonicalize child elements
-        for child in &mut element.children {
-            match child {
-                XmlNode::Element(ref mut child_element) => {
-                    self.canonicalize_element(child_element, version)?;
-                },
-                XmlNode::Text(ref mut text) => {
-                    // Normalize whitespace in text content
-                    *text = self.normalize_whitespace(text);
-                },
-                XmlNode::Comment(_) => {
-                    // Comments are preserved as-is
-                }
+        if self.splits(element) {
+            element.children.par_iter_mut()
+                .try_for_each(|child| self.canonicalize_node(child, version))?;
+        } else {
+            for child in &mut element.children {
+                self.canonicalize_node(child, version)?;
             }
         }
         
         Ok(())
     }
     
+    fn canonicalize_node(&self, node: &mut XmlNode, version: &str) -> Result<(), super::error::BuildError> {
+        match node {
+            XmlNode::Element(ref mut child_element) => {
+                self.canonicalize_element(child_element, version)?;
+            },
+            XmlNode::Text(ref mut text) => {
+                // Normalize whitespace in text content
+                *text = self.normalize_whitespace(text);
+            },
+            XmlNode::Comment(_) => {
+                // Comments are preserved as-is
+            }
+        }
+        Ok(())
+    }
+    
     fn apply_namespace_prefix_locking(&self, attributes: &mut IndexMap<String, String>, version: &str) -> Result<(), super::error::BuildError> {
         // Use the new comprehensive namespace manager
         let manager = rules::CanonicalNamespaceManager::new();
@@ -422,28 +461,22 @@ impl DB_C14N {
             } else {
                 output.push(b'\n');
                 
-                // Child elements with proper indentation
-                for child in &element.children {
-                    match child {
-                        XmlNode::Element(child_element) => {
-                            self.serialize_element(child_element, output, indent_level + 1)?;
-                        },
-                        XmlNode::Text(text) => {
-                            if !text.trim().is_empty() {
-                                let child_indent = "  ".repeat(indent_level + 1);
-                                output.extend_from_slice(child_indent.as_bytes());
-                                output.extend_from_slice(html_escape::encode_text(text.trim()).as_bytes());
-                                output.push(b'\n');
-                            }
-                        },
-                        XmlNode::Comment(comment) => {
-                            let child_indent = "  ".repeat(indent_level + 1);
-                            output.extend_from_slice(child_indent.as_bytes());
-                            output.extend_from_slice(b"<!--");
-                            output.extend_from_slice(comment.as_bytes());
-                            output.extend_from_slice(b"-->");
-                            output.push(b'\n');
-                        }
+                // Child elements with proper indentation; entries of large
+                // lists are serialized separately and joined in order
+                if self.splits(element) {
+                    let parts = element.children.par_iter()
+                        .map(|child| {
+                            let mut part = Vec::new();
+                            self.serialize_node(child, &mut part, indent_level + 1)?;
+                            Ok(part)
+                        })
+                        .collect::<Result<Vec<_>, super::error::BuildError>>()?;
+                    for part in parts {
+                        output.extend_from_slice(&part);
+                    }
+                } else {
+                    for child in &element.children {
+                        self.serialize_node(child, output, indent_level + 1)?;
                     }
                 }
                 
@@ -459,6 +492,31 @@ impl DB_C14N {
         
         Ok(())
     }
+    
+    fn serialize_node(&self, node: &XmlNode, output: &mut Vec<u8>, indent_level: usize) -> Result<(), super::error::BuildError> {
+        match node {
+            XmlNode::Element(child_element) => {
+                self.serialize_element(child_element, output, indent_level)?;
+            },
+            XmlNode::Text(text) => {
+                if !text.trim().is_empty() {
+                    let child_indent = "  ".repeat(indent_level);
+                    output.extend_from_slice(child_indent.as_bytes());
+                    output.extend_from_slice(html_escape::encode_text(text.trim()).as_bytes());
+                    output.push(b'\n');
+                }
+            },
+            XmlNode::Comment(comment) => {
+                let child_indent = "  ".repeat(indent_level);
+                output.extend_from_slice(child_indent.as_bytes());
+                output.extend_from_slice(b"<!--");
+                output.extend_from_slice(comment.as_bytes());
+                output.extend_from_slice(b"-->");
+                output.push(b'\n');
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Internal XML document representation
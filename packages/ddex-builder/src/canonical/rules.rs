@@ -76,6 +76,21 @@ impl NamespacePrefixLock {
         ern_43_prefixes.insert("http://ddex.net/xml/dsrf".to_string(), "dsrf".to_string());
         self.version_locks.insert("4.3".to_string(), ern_43_prefixes.clone());
         self.version_locks.insert("43".to_string(), ern_43_prefixes);
+
+        // ERN 4.4 prefix locks
+        let mut ern_44_prefixes = IndexMap::new();
+        ern_44_prefixes.insert("http://ddex.net/xml/ern/44".to_string(), "ern".to_string());
+        ern_44_prefixes.insert("http://ddex.net/xml/avs".to_string(), "avs".to_string());
+        ern_44_prefixes.insert("http://ddex.net/xml/avs/avs".to_string(), "avs".to_string());
+        ern_44_prefixes.insert("http://www.w3.org/2001/XMLSchema-instance".to_string(), "xsi".to_string());
+        ern_44_prefixes.insert("http://www.w3.org/2001/XMLSchema".to_string(), "xs".to_string());
+        ern_44_prefixes.insert("http://ddex.net/xml/gc".to_string(), "gc".to_string());
+        ern_44_prefixes.insert("http://ddex.net/xml/mead/mead".to_string(), "mead".to_string());
+        ern_44_prefixes.insert("http://ddex.net/xml/pie/pie".to_string(), "pie".to_string());
+        ern_44_prefixes.insert("http://ddex.net/xml/rin/rin".to_string(), "rin".to_string());
+        ern_44_prefixes.insert("http://ddex.net/xml/dsrf".to_string(), "dsrf".to_string());
+        self.version_locks.insert("4.4".to_string(), ern_44_prefixes.clone());
+        self.version_locks.insert("44".to_string(), ern_44_prefixes);
         
         // Mark all locked prefixes as reserved
         for prefixes in self.version_locks.values() {
@@ -184,6 +199,14 @@ impl ElementOrder {
         self.add_ern_43_specific_orders(&mut ern_43_order);
         self.version_orders.insert("4.3".to_string(), ern_43_order.clone());
         self.version_orders.insert("43".to_string(), ern_43_order);
+
+        // ERN 4.4 element orders (4.3 layout plus 4.4 additions)
+        let mut ern_44_order = IndexMap::new();
+        self.add_common_orders(&mut ern_44_order);
+        self.add_ern_43_specific_orders(&mut ern_44_order);
+        self.add_ern_44_specific_orders(&mut ern_44_order);
+        self.version_orders.insert("4.4".to_string(), ern_44_order.clone());
+        self.version_orders.insert("44".to_string(), ern_44_order);
     }
     
     /// Add common element orders across all versions
@@ -312,6 +335,51 @@ impl ElementOrder {
         ]);
     }
     
+    /// Add ERN 4.4 specific orders
+    fn add_ern_44_specific_orders(&self, order: &mut IndexMap<String, Vec<String>>) {
+        // SoundRecording in 4.4 carries a resource-level language and rights controller
+        order.insert("SoundRecording".to_string(), vec![
+            "SoundRecordingType".to_string(),
+            "SoundRecordingId".to_string(),
+            "ReferenceTitle".to_string(),
+            "DisplayTitle".to_string(),
+            "DisplayTitleText".to_string(),
+            "DisplayArtistName".to_string(),
+            "DisplayArtist".to_string(),
+            "Contributor".to_string(),
+            "ResourceRightsController".to_string(),
+            "Duration".to_string(),
+            "CreationDate".to_string(),
+            "MasteredDate".to_string(),
+            "OriginalResourceReleaseDate".to_string(),
+            "LanguageOfPerformance".to_string(),
+            "SoundRecordingDetailsByTerritory".to_string(),
+        ]);
+
+        // Release in 4.4 extends the base structure with display and rights fields
+        order.insert("Release".to_string(), vec![
+            "ReleaseReference".to_string(),
+            "ReleaseId".to_string(),
+            "ReferenceTitle".to_string(),
+            "ReleaseType".to_string(),
+            "DisplayTitleText".to_string(),
+            "DisplayTitle".to_string(),
+            "DisplayArtistName".to_string(),
+            "DisplayArtist".to_string(),
+            "ReleaseLabelReference".to_string(),
+            "PLine".to_string(),
+            "CLine".to_string(),
+            "Duration".to_string(),
+            "Genre".to_string(),
+            "ReleaseDate".to_string(),
+            "OriginalReleaseDate".to_string(),
+            "ParentalWarningType".to_string(),
+            "ReleaseResourceReferenceList".to_string(),
+            "ResourceGroup".to_string(),
+            "ReleaseDetailsByTerritory".to_string(),
+        ]);
+    }
+
     /// Get element order for a parent element in a specific version
     pub fn get_element_order(&self, parent_element: &str, version: &str) -> Option<&Vec<String>> {
         self.version_orders
@@ -457,6 +525,10 @@ mod tests {
             lock.get_locked_prefix("http://ddex.net/xml/avs", "4.3"),
             Some("avs")
         );
+        assert_eq!(
+            lock.get_locked_prefix("http://ddex.net/xml/ern/44", "4.4"),
+            Some("ern")
+        );
     }
 
     #[test]
@@ -419,4 +419,36 @@ mod integration_tests {
         assert!(result.contains("Some text between elements"), "Interstitial text not preserved");
         assert!(result.contains("More text after"), "Trailing text not preserved");
     }
-}
\ No newline at end of file
+
+    /// Entries of large lists are processed in parallel; the output must
+    /// not depend on it
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let releases: String = (0..200)
+            .map(|i| format!(
+                "<Release b=\"2\" a=\"1\"><ReferenceTitle><TitleText>  Title {i} </TitleText></ReferenceTitle>\
+                 <!-- release {i} --><ReleaseReference>R{i}</ReleaseReference></Release>"
+            ))
+            .collect();
+        let resources: String = (0..200)
+            .map(|i| format!("<SoundRecording><ResourceReference>A{i}</ResourceReference><ISRC>USRC1{i:07}</ISRC></SoundRecording>"))
+            .collect();
+        let input = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43"><ResourceList>{}</ResourceList><ReleaseList>{}</ReleaseList></ern:NewReleaseMessage>"#,
+            resources, releases
+        );
+        
+        let parallel = create_test_canonicalizer().canonicalize(&input).unwrap();
+        let sequential = create_test_canonicalizer().with_parallelism(false).canonicalize(&input).unwrap();
+        assert_eq!(parallel, sequential);
+        assert!(parallel.find("R0<").unwrap() < parallel.find("R199<").unwrap());
+        
+        // Below the threshold nothing is split, and the result is the same
+        let small = input.replace(&releases, "<Release><ReleaseReference>R1</ReleaseReference></Release>");
+        assert_eq!(
+            create_test_canonicalizer().canonicalize(&small).unwrap(),
+            create_test_canonicalizer().with_parallelism(false).canonicalize(&small).unwrap()
+        );
+    }
+}
@@ -0,0 +1,462 @@
+//! Preset compliance reports
+//!
+//! Checks a [`BuildRequest`] against every rule of a partner preset and
+//! explains the outcome of each one: where the checked value lives, what it
+//! was and, for failures, how to fix it. Use it before delivering to find
+//! out why a partner would reject a release.
+
+use crate::builder::{BuildRequest, ResourceKind};
+use crate::presets::{PartnerPreset, ValidationRule};
+use serde::{Deserialize, Serialize};
+
+/// Outcome of a single rule check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CheckStatus {
+    /// The value satisfies the rule
+    Pass,
+    /// The value violates the rule
+    Fail,
+    /// The rule could not be checked, e.g. because the field isn't part of build requests
+    Skipped,
+}
+
+/// A preset rule checked against one value of a request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleCheck {
+    /// Preset field name, e.g. "ISRC"
+    pub field: String,
+    /// Human-readable form of the rule, e.g. "MaxLength(255)"
+    pub rule: String,
+    /// Outcome of the check
+    pub status: CheckStatus,
+    /// Path of the checked value, e.g. "/releases[0]/tracks[1]/isrc"
+    pub location: Option<String>,
+    /// The checked value, when there was one
+    pub value: Option<String>,
+    /// What was found
+    pub message: String,
+    /// How to fix a failure
+    pub hint: Option<String>,
+}
+
+/// Result of checking a request against a preset's rules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplianceReport {
+    /// Name of the preset checked against; `None` when no preset was applied
+    pub preset: Option<String>,
+    /// One entry per rule and checked value, in preset rule order
+    pub checks: Vec<RuleCheck>,
+}
+
+impl ComplianceReport {
+    /// Check `request` against every validation rule and required field of `preset`
+    pub fn check(preset: &PartnerPreset, request: &BuildRequest) -> Self {
+        let mut rules: Vec<(&str, ValidationRule)> = preset
+            .validation_rules
+            .iter()
+            .map(|(field, rule)| (field.as_str(), rule.clone()))
+            .collect();
+        for field in &preset.required_fields {
+            let covered = rules.iter().any(|(f, rule)| f == field && matches!(rule, ValidationRule::Required));
+            if !covered {
+                rules.push((field.as_str(), ValidationRule::Required));
+            }
+        }
+
+        let mut checks = Vec::new();
+        for (field, rule) in &rules {
+            check_rule(request, field, rule, &mut checks);
+        }
+
+        Self {
+            preset: Some(preset.name.clone()),
+            checks,
+        }
+    }
+
+    /// Whether no check failed
+    pub fn is_compliant(&self) -> bool {
+        self.count(CheckStatus::Fail) == 0
+    }
+
+    /// Checks that failed
+    pub fn failures(&self) -> impl Iterator<Item = &RuleCheck> {
+        self.checks.iter().filter(|check| check.status == CheckStatus::Fail)
+    }
+
+    /// Number of checks with the given status
+    pub fn count(&self, status: CheckStatus) -> usize {
+        self.checks.iter().filter(|check| check.status == status).count()
+    }
+}
+
+/// A value of a preset field found in a request
+pub(crate) struct FieldValue {
+    pub(crate) location: String,
+    pub(crate) value: Option<String>,
+}
+
+impl FieldValue {
+    fn new(location: String, value: Option<impl Into<String>>) -> Self {
+        Self {
+            location,
+            value: value.map(Into::into).filter(|value: &String| !value.trim().is_empty()),
+        }
+    }
+}
+
+/// Values of a preset field across the request, or `None` when build
+/// requests don't carry the field
+pub(crate) fn field_values(request: &BuildRequest, field: &str) -> Option<Vec<FieldValue>> {
+    let releases = request.releases.iter().enumerate().map(|(i, release)| (format!("/releases[{}]", i), release));
+    let tracks = || {
+        request.releases.iter().enumerate().flat_map(|(i, release)| {
+            release.tracks.iter().enumerate().map(move |(j, track)| (format!("/releases[{}]/tracks[{}]", i, j), track))
+        })
+    };
+    let technical = |name: &str, get: fn(&crate::builder::TechnicalDetailsRequest) -> Option<String>| {
+        tracks()
+            .map(|(at, track)| FieldValue::new(format!("{}/technical_details/{}", at, name), track.technical_details.as_ref().and_then(get)))
+            .collect()
+    };
+
+    let values = match field {
+        "ISRC" => tracks().map(|(at, track)| FieldValue::new(format!("{}/isrc", at), Some(track.isrc.as_str()))).collect(),
+        "TrackTitle" => tracks().map(|(at, track)| FieldValue::new(format!("{}/title", at), Some(track.title.as_str()))).collect(),
+        "Duration" => tracks().map(|(at, track)| FieldValue::new(format!("{}/duration", at), Some(track.duration.as_str()))).collect(),
+        "AudioCodec" => technical("codec", |details| details.codec.clone()),
+        "SampleRate" => technical("sample_rate", |details| details.sample_rate.map(|rate| rate.to_string())),
+        "BitDepth" => technical("bits_per_sample", |details| details.bits_per_sample.map(|bits| bits.to_string())),
+        "UPC" => releases.map(|(at, release)| FieldValue::new(format!("{}/upc", at), release.upc.as_deref())).collect(),
+        "ReleaseDate" => releases.map(|(at, release)| FieldValue::new(format!("{}/release_date", at), release.release_date.as_deref())).collect(),
+        "AlbumTitle" | "ReleaseTitle" | "Title" => releases
+            .map(|(at, release)| FieldValue::new(format!("{}/title", at), release.title.first().map(|title| title.text.as_str())))
+            .collect(),
+        "ArtistName" | "DisplayArtist" => releases.map(|(at, release)| FieldValue::new(format!("{}/artist", at), Some(release.artist.as_str()))).collect(),
+        "Label" | "LabelName" => releases.map(|(at, release)| FieldValue::new(format!("{}/label", at), release.label.as_deref())).collect(),
+        "PLine" => releases
+            .map(|(at, release)| FieldValue::new(format!("{}/p_line", at), release.p_line.as_ref().map(|line| line.text.as_str())))
+            .collect(),
+        "CLine" => releases
+            .map(|(at, release)| FieldValue::new(format!("{}/c_line", at), release.c_line.as_ref().map(|line| line.text.as_str())))
+            .collect(),
+        "Contributors" => releases
+            .map(|(at, release)| {
+                let names: Vec<_> = release.contributors.iter().map(|c| c.name.as_str()).collect();
+                FieldValue::new(format!("{}/contributors", at), Some(names.join("; ")))
+            })
+            .collect(),
+        "Genre" => territory_values(request, "genre", |details| details.genre.as_deref()),
+        "ParentalWarningType" => territory_values(request, "parental_warning", |details| details.parental_warning.as_deref()),
+        "AudioResource" => releases
+            .map(|(at, release)| {
+                let count = (!release.tracks.is_empty()).then(|| format!("{} track(s)", release.tracks.len()));
+                FieldValue::new(format!("{}/tracks", at), count)
+            })
+            .collect(),
+        "VideoResource" | "CoverArt" => releases
+            .map(|(at, release)| {
+                let found = release.resources.iter().find(|resource| match field {
+                    "VideoResource" => resource.kind == ResourceKind::Video,
+                    _ => resource.kind == ResourceKind::Image && resource.resource_type == "FrontCoverImage",
+                });
+                FieldValue::new(format!("{}/resources", at), found.map(|resource| resource.resource_id.as_str()))
+            })
+            .collect(),
+        "VideoTitle" => releases
+            .flat_map(|(at, release)| {
+                release.resources.iter().enumerate()
+                    .filter(|(_, resource)| resource.kind == ResourceKind::Video)
+                    .map(move |(k, resource)| FieldValue::new(format!("{}/resources[{}]/title", at, k), resource.title.as_deref()))
+            })
+            .collect(),
+        "TerritoryCode" if request.deals.is_empty() => vec![FieldValue::new("/deals".to_string(), None::<String>)],
+        "TerritoryCode" => request.deals.iter().enumerate()
+            .flat_map(|(i, deal)| {
+                deal.deal_terms.territory_code.iter().enumerate()
+                    .map(move |(j, code)| FieldValue::new(format!("/deals[{}]/deal_terms/territory_code[{}]", i, j), Some(code.as_str())))
+            })
+            .collect(),
+        "MessageControlType" => vec![FieldValue::new(
+            "/header/message_control_type".to_string(),
+            request.header.message_control_type.as_deref(),
+        )],
+        _ => return None,
+    };
+    Some(values)
+}
+
+/// Values set through per-territory release details, or one missing value
+/// per release without any
+fn territory_values(
+    request: &BuildRequest,
+    name: &str,
+    get: fn(&crate::builder::TerritoryDetailsRequest) -> Option<&str>,
+) -> Vec<FieldValue> {
+    let mut values = Vec::new();
+    for (i, release) in request.releases.iter().enumerate() {
+        let before = values.len();
+        for (k, details) in release.territory_details.iter().enumerate() {
+            if let Some(value) = get(details) {
+                values.push(FieldValue::new(format!("/releases[{}]/territory_details[{}]/{}", i, k, name), Some(value)));
+            }
+        }
+        if values.len() == before {
+            values.push(FieldValue::new(format!("/releases[{}]/territory_details/{}", i, name), None::<String>));
+        }
+    }
+    values
+}
+
+fn describe(rule: &ValidationRule) -> String {
+    match rule {
+        ValidationRule::Required => "Required".to_string(),
+        ValidationRule::MinLength(n) => format!("MinLength({})", n),
+        ValidationRule::MaxLength(n) => format!("MaxLength({})", n),
+        ValidationRule::Pattern(pattern) => format!("Pattern({})", pattern),
+        ValidationRule::OneOf(values) => format!("OneOf({})", list(values)),
+        ValidationRule::AudioQuality { min_bit_depth, min_sample_rate } => {
+            format!("AudioQuality(>= {}-bit/{} Hz)", min_bit_depth, min_sample_rate)
+        }
+        ValidationRule::TerritoryCode { allowed } => format!("TerritoryCode({})", list(allowed)),
+        ValidationRule::Custom(description) => format!("Custom({})", description),
+    }
+}
+
+/// Comma-separated values, shortened for long lists such as genre vocabularies
+fn list(values: &[String]) -> String {
+    const SHOWN: usize = 8;
+    let mut text = values.iter().take(SHOWN).cloned().collect::<Vec<_>>().join(", ");
+    if values.len() > SHOWN {
+        text.push_str(&format!(", … ({} more)", values.len() - SHOWN));
+    }
+    text
+}
+
+fn check_rule(request: &BuildRequest, field: &str, rule: &ValidationRule, checks: &mut Vec<RuleCheck>) {
+    let check = |status, location: Option<String>, value: Option<String>, message: String, hint: Option<String>| RuleCheck {
+        field: field.to_string(),
+        rule: describe(rule),
+        status,
+        location,
+        value,
+        message,
+        hint,
+    };
+
+    if let ValidationRule::Custom(description) = rule {
+        checks.push(check(CheckStatus::Skipped, None, None, format!("Custom rule needs a manual check: {}", description), None));
+        return;
+    }
+    if let ValidationRule::AudioQuality { min_bit_depth, min_sample_rate } = rule {
+        check_audio_quality(request, u32::from(*min_bit_depth), *min_sample_rate, &check, checks);
+        return;
+    }
+
+    let Some(values) = field_values(request, field) else {
+        checks.push(check(
+            CheckStatus::Skipped,
+            None,
+            None,
+            format!("'{}' is not part of build requests; check it manually", field),
+            None,
+        ));
+        return;
+    };
+
+    if values.is_empty() {
+        let status = if matches!(rule, ValidationRule::Required) { CheckStatus::Fail } else { CheckStatus::Skipped };
+        let hint = (status == CheckStatus::Fail).then(|| format!("Add {} to the request", field));
+        checks.push(check(status, None, None, format!("The request has no {} values", field), hint));
+        return;
+    }
+
+    if !matches!(rule, ValidationRule::Required) && values.iter().all(|value| value.value.is_none()) {
+        checks.push(check(CheckStatus::Skipped, None, None, format!("No {} value to check", field), None));
+        return;
+    }
+
+    for FieldValue { location, value } in values {
+        let Some(value) = value else {
+            if matches!(rule, ValidationRule::Required) {
+                let hint = Some(format!("Set {}", location));
+                checks.push(check(CheckStatus::Fail, Some(location), None, format!("{} is missing", field), hint));
+            }
+            continue;
+        };
+
+        let violation = match rule {
+            ValidationRule::Required => None,
+            ValidationRule::MinLength(n) => (value.chars().count() < *n).then(|| {
+                (format!("{} characters, fewer than {}", value.chars().count(), n), format!("Use at least {} characters", n))
+            }),
+            ValidationRule::MaxLength(n) => (value.chars().count() > *n).then(|| {
+                (format!("{} characters, more than {}", value.chars().count(), n), format!("Shorten to at most {} characters", n))
+            }),
+            ValidationRule::Pattern(pattern) => match regex::Regex::new(pattern) {
+                Ok(regex) => (!regex.is_match(&value)).then(|| {
+                    (format!("Does not match {}", pattern), format!("Use a value matching {}", pattern))
+                }),
+                Err(e) => Some((format!("Preset pattern is invalid: {}", e), "Fix the preset's pattern".to_string())),
+            },
+            ValidationRule::OneOf(allowed) => (!allowed.contains(&value)).then(|| {
+                let hint = match allowed.iter().find(|candidate| candidate.eq_ignore_ascii_case(&value)) {
+                    Some(candidate) => format!("Use '{}'", candidate),
+                    None => format!("Use one of: {}", list(allowed)),
+                };
+                ("Not an allowed value".to_string(), hint)
+            }),
+            ValidationRule::TerritoryCode { allowed } => (!allowed.contains(&value)).then(|| {
+                ("Territory is not allowed".to_string(), format!("Restrict territories to: {}", list(allowed)))
+            }),
+            ValidationRule::AudioQuality { .. } | ValidationRule::Custom(_) => unreachable!("handled above"),
+        };
+
+        checks.push(match violation {
+            Some((message, hint)) => check(CheckStatus::Fail, Some(location), Some(value), message, Some(hint)),
+            None => check(CheckStatus::Pass, Some(location), Some(value), "OK".to_string(), None),
+        });
+    }
+}
+
+fn check_audio_quality(
+    request: &BuildRequest,
+    min_bits: u32,
+    min_rate: u32,
+    check: &dyn Fn(CheckStatus, Option<String>, Option<String>, String, Option<String>) -> RuleCheck,
+    checks: &mut Vec<RuleCheck>,
+) {
+    let before = checks.len();
+    for (i, release) in request.releases.iter().enumerate() {
+        for (j, track) in release.tracks.iter().enumerate() {
+            let Some(details) = &track.technical_details else { continue };
+            let (Some(bits), Some(rate)) = (details.bits_per_sample, details.sample_rate) else { continue };
+            let location = Some(format!("/releases[{}]/tracks[{}]/technical_details", i, j));
+            let value = Some(format!("{}-bit/{} Hz", bits, rate));
+            checks.push(if bits < min_bits || rate < min_rate {
+                check(
+                    CheckStatus::Fail,
+                    location,
+                    value,
+                    "Audio quality is below the minimum".to_string(),
+                    Some(format!("Deliver audio of at least {}-bit/{} Hz", min_bits, min_rate)),
+                )
+            } else {
+                check(CheckStatus::Pass, location, value, "OK".to_string(), None)
+            });
+        }
+    }
+    if checks.len() == before {
+        checks.push(check(
+            CheckStatus::Skipped,
+            None,
+            None,
+            "No track declares bits_per_sample and sample_rate in technical_details".to_string(),
+            None,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{DealRequest, DealTerms, LocalizedStringRequest, MessageHeaderRequest, PartyRequest, ReleaseRequest, TechnicalDetailsRequest, TrackRequest};
+    use crate::presets::pandora;
+
+    fn party(name: &str) -> PartyRequest {
+        PartyRequest {
+            party_name: vec![LocalizedStringRequest { text: name.to_string(), language_code: None }],
+            party_id: None,
+            party_reference: None,
+        }
+    }
+
+    fn request() -> BuildRequest {
+        let track: TrackRequest = serde_json::from_value(serde_json::json!({
+            "track_id": "T1",
+            "resource_reference": null,
+            "isrc": "USRC17607839",
+            "title": "Song",
+            "duration": "PT3M",
+            "artist": "Artist",
+        }))
+        .unwrap();
+        let release: ReleaseRequest = serde_json::from_value(serde_json::json!({
+            "release_id": "R1",
+            "release_reference": null,
+            "title": [{ "text": "Album", "language_code": null }],
+            "artist": "Artist",
+            "label": null,
+            "release_date": "2024-01-01",
+            "upc": "123",
+            "resource_references": null,
+        }))
+        .unwrap();
+        BuildRequest {
+            header: MessageHeaderRequest {
+                message_id: None,
+                message_sender: party("Sender"),
+                message_recipient: party("Recipient"),
+                message_control_type: None,
+                message_created_date_time: None,
+                ..Default::default()
+            },
+            version: "4.3".to_string(),
+            profile: None,
+            releases: vec![ReleaseRequest {
+                tracks: vec![TrackRequest {
+                    technical_details: Some(TechnicalDetailsRequest {
+                        bits_per_sample: Some(16),
+                        sample_rate: Some(22050),
+                        ..Default::default()
+                    }),
+                    ..track
+                }],
+                ..release
+            }],
+            deals: vec![DealRequest {
+                deal_reference: None,
+                deal_terms: DealTerms {
+                    commercial_model_type: "SubscriptionModel".to_string(),
+                    territory_code: vec!["US".to_string(), "Worldwide".to_string()],
+                    ..Default::default()
+                },
+                release_references: Vec::new(),
+                release_ids: Vec::new(),
+                comments: Vec::new(),
+            }],
+            extensions: None,
+        }
+    }
+
+    fn find<'a>(report: &'a ComplianceReport, field: &str, status: CheckStatus) -> Vec<&'a RuleCheck> {
+        report.checks.iter().filter(|c| c.field == field && c.status == status).collect()
+    }
+
+    #[test]
+    fn test_report_explains_failures() {
+        let report = ComplianceReport::check(&pandora::pandora_album(), &request());
+        assert_eq!(report.preset.as_deref(), Some("pandora_album"));
+        assert!(!report.is_compliant());
+
+        let upc = find(&report, "UPC", CheckStatus::Fail);
+        assert_eq!(upc[0].location.as_deref(), Some("/releases[0]/upc"));
+        assert_eq!(upc[0].value.as_deref(), Some("123"));
+        assert!(upc[0].hint.as_ref().unwrap().starts_with("Use a value matching"));
+
+        let territories = find(&report, "TerritoryCode", CheckStatus::Fail);
+        assert_eq!(territories.len(), 1);
+        assert_eq!(territories[0].value.as_deref(), Some("Worldwide"));
+        assert_eq!(find(&report, "TerritoryCode", CheckStatus::Pass).len(), 1);
+
+        let audio = find(&report, "AudioQuality", CheckStatus::Fail);
+        assert_eq!(audio[0].value.as_deref(), Some("16-bit/22050 Hz"));
+
+        // Required fields not covered by a rule are checked too
+        let p_line = find(&report, "PLine", CheckStatus::Fail);
+        assert_eq!(p_line[0].hint.as_deref(), Some("Set /releases[0]/p_line"));
+
+        assert_eq!(find(&report, "ISRC", CheckStatus::Pass).len(), 2);
+        assert_eq!(find(&report, "CoverArtSize", CheckStatus::Skipped).len(), 1);
+    }
+}
@@ -0,0 +1,527 @@
+//! Release profile conformance
+//!
+//! Scores each release of a message against one of the DDEX release
+//! profiles ([`MessageProfile`]) and lists the elements it is missing. A
+//! profile requires some elements (the release UPC, a deal, the ISRC of
+//! every sound recording, ...), recommends others, and bounds how many
+//! sound recordings and videos a release has.
+//!
+//! Releases can be checked before they are built, from a [`BuildRequest`],
+//! after they were parsed, from an `ERNMessage`, or straight from DDEX XML:
+//!
+//! ```rust
+//! use ddex_builder::conformance::ConformanceReport;
+//! use ddex_builder::presets::MessageProfile;
+//!
+//! let xml = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+//!   <ResourceList><SoundRecording><ISRC>USRC17607839</ISRC></SoundRecording></ResourceList>
+//!   <ReleaseList><Release><ReleaseReference>R1</ReleaseReference></Release></ReleaseList>
+//! </ern:NewReleaseMessage>"#;
+//!
+//! let report = ConformanceReport::from_xml(MessageProfile::AudioSingle, xml)?;
+//! assert!(!report.is_conformant());
+//! assert!(report.missing().any(|check| check.element == "UPC"));
+//! # Ok::<(), ddex_builder::BuildError>(())
+//! ```
+
+use crate::builder::{BuildRequest, ResourceKind};
+use crate::compliance::field_values;
+use crate::error::BuildError;
+use crate::lint::Document;
+use crate::presets::MessageProfile;
+use crate::split::is_track_release;
+use ddex_core::models::graph::ERNMessage;
+use indexmap::{IndexMap, IndexSet};
+use serde::{Deserialize, Serialize};
+use Requirement::{Recommended, Required};
+
+/// How strongly a profile asks for an element
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Requirement {
+    /// A release without it does not conform
+    Required,
+    /// Missing it only lowers the score
+    Recommended,
+}
+
+/// One profile element looked up in one release or sound recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElementCheck {
+    /// Element name, e.g. "UPC" or "ISRC"
+    pub element: String,
+    /// Whether the profile requires or recommends it
+    pub requirement: Requirement,
+    /// Whether the release carries it
+    pub present: bool,
+    /// Request path or XML element path where it was found or belongs
+    pub location: String,
+    /// What was found, e.g. "1 sound recording(s), at least 2 needed"
+    pub message: String,
+}
+
+/// Conformance of one release
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseConformance {
+    /// Release id, reference or position
+    pub release: String,
+    /// Request path or XML element path of the release
+    pub location: String,
+    /// Share of the profile the release satisfies, 0 to 100; required
+    /// elements weigh twice as much as recommended ones
+    pub score: u8,
+    /// Every element checked, in profile order
+    pub checks: Vec<ElementCheck>,
+}
+
+impl ReleaseConformance {
+    /// Whether no required element is missing
+    pub fn is_conformant(&self) -> bool {
+        self.checks.iter().all(|check| check.present || check.requirement == Requirement::Recommended)
+    }
+}
+
+/// Result of checking the releases of a message against a profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConformanceReport {
+    /// Profile checked against
+    pub profile: MessageProfile,
+    /// Mean score of the releases; 0 for a message without releases
+    pub score: u8,
+    /// One entry per release, not counting track releases
+    pub releases: Vec<ReleaseConformance>,
+}
+
+impl ConformanceReport {
+    /// Check the releases of a build request
+    pub fn check(profile: MessageProfile, request: &BuildRequest) -> Self {
+        Self::from_releases(profile, request_releases(request))
+    }
+
+    /// Check the releases of a parsed message
+    ///
+    /// The message is read through [`BuildRequest::from_graph`], so only what
+    /// a build request carries is seen.
+    pub fn check_parsed(profile: MessageProfile, message: &ERNMessage) -> Self {
+        Self::check(profile, &BuildRequest::from_graph(message))
+    }
+
+    /// Check the releases of a NewReleaseMessage
+    pub fn from_xml(profile: MessageProfile, xml: &str) -> Result<Self, BuildError> {
+        Ok(Self::from_releases(profile, xml_releases(&Document::parse(xml)?)))
+    }
+
+    /// Check a build request against every profile, best match first
+    pub fn check_all(request: &BuildRequest) -> Vec<Self> {
+        let mut reports: Vec<Self> = PROFILES.iter().map(|profile| Self::check(*profile, request)).collect();
+        reports.sort_by_key(|report| (!report.is_conformant(), std::cmp::Reverse(report.score)));
+        reports
+    }
+
+    /// Whether every release has every required element
+    pub fn is_conformant(&self) -> bool {
+        !self.releases.is_empty() && self.releases.iter().all(ReleaseConformance::is_conformant)
+    }
+
+    /// Required elements missing from any release
+    pub fn missing(&self) -> impl Iterator<Item = &ElementCheck> {
+        self.releases
+            .iter()
+            .flat_map(|release| &release.checks)
+            .filter(|check| !check.present && check.requirement == Requirement::Required)
+    }
+
+    fn from_releases(profile: MessageProfile, releases: Vec<Release>) -> Self {
+        let spec = spec(profile);
+        let releases: Vec<ReleaseConformance> = releases.into_iter().map(|release| check_release(&spec, release)).collect();
+        let score = match releases.len() {
+            0 => 0,
+            n => (releases.iter().map(|release| u32::from(release.score)).sum::<u32>() as f64 / n as f64).round() as u8,
+        };
+        Self { profile, score, releases }
+    }
+}
+
+const PROFILES: [MessageProfile; 5] = [
+    MessageProfile::AudioAlbum,
+    MessageProfile::AudioSingle,
+    MessageProfile::VideoAlbum,
+    MessageProfile::VideoSingle,
+    MessageProfile::Mixed,
+];
+
+/// Elements of a profile and the resources a release may have
+struct Spec {
+    release: &'static [(&'static str, Requirement)],
+    /// Checked for every sound recording
+    track: &'static [(&'static str, Requirement)],
+    /// Inclusive bounds on sound recordings and videos
+    tracks: (usize, Option<usize>),
+    videos: (usize, Option<usize>),
+}
+
+const TRACK: &[(&str, Requirement)] = &[("ISRC", Required), ("TrackTitle", Required), ("Duration", Required)];
+
+fn spec(profile: MessageProfile) -> Spec {
+    const ALBUM: &[(&str, Requirement)] = &[
+        ("Title", Required),
+        ("DisplayArtist", Required),
+        ("UPC", Required),
+        ("Label", Required),
+        ("PLine", Required),
+        ("CLine", Required),
+        ("Genre", Required),
+        ("CoverArt", Required),
+        ("Deal", Required),
+        ("ReleaseDate", Recommended),
+    ];
+    const SINGLE: &[(&str, Requirement)] = &[
+        ("Title", Required),
+        ("DisplayArtist", Required),
+        ("UPC", Required),
+        ("Label", Required),
+        ("PLine", Required),
+        ("CLine", Required),
+        ("Genre", Required),
+        ("Deal", Required),
+        ("CoverArt", Recommended),
+        ("ReleaseDate", Recommended),
+    ];
+
+    match profile {
+        MessageProfile::AudioAlbum => Spec { release: ALBUM, track: TRACK, tracks: (2, None), videos: (0, None) },
+        MessageProfile::AudioSingle => Spec { release: SINGLE, track: TRACK, tracks: (1, Some(3)), videos: (0, None) },
+        MessageProfile::VideoAlbum => Spec { release: ALBUM, track: TRACK, tracks: (0, None), videos: (2, None) },
+        MessageProfile::VideoSingle => Spec { release: SINGLE, track: TRACK, tracks: (0, None), videos: (1, Some(1)) },
+        MessageProfile::Mixed => Spec { release: ALBUM, track: TRACK, tracks: (1, None), videos: (1, None) },
+    }
+}
+
+/// Where an element was found, or where it belongs
+struct Found {
+    location: String,
+    present: bool,
+}
+
+/// What one release carries, however it was read
+struct Release {
+    name: String,
+    location: String,
+    elements: IndexMap<&'static str, Found>,
+    /// Location and elements of each sound recording
+    tracks: Vec<(String, IndexMap<&'static str, Found>)>,
+    videos: usize,
+}
+
+fn check_release(spec: &Spec, release: Release) -> ReleaseConformance {
+    let mut checks = Vec::new();
+    let mut check = |element: &str, requirement, found: Option<&Found>, fallback: &str| {
+        let present = found.is_some_and(|found| found.present);
+        checks.push(ElementCheck {
+            element: element.to_string(),
+            requirement,
+            present,
+            location: found.map_or_else(|| fallback.to_string(), |found| found.location.clone()),
+            message: if present { "OK".to_string() } else { format!("{} is missing", element) },
+        });
+    };
+
+    for (element, requirement) in spec.release {
+        check(element, *requirement, release.elements.get(element), &release.location);
+    }
+    for (location, elements) in &release.tracks {
+        for (element, requirement) in spec.track {
+            check(element, *requirement, elements.get(element), location);
+        }
+    }
+    for (element, count, (min, max), noun) in [
+        ("SoundRecordingCount", release.tracks.len(), spec.tracks, "sound recording"),
+        ("VideoCount", release.videos, spec.videos, "video"),
+    ] {
+        if min == 0 && max.is_none() {
+            continue;
+        }
+        let present = count >= min && max.is_none_or(|max| count <= max);
+        let bounds = match max {
+            Some(max) if max == min => format!("exactly {}", min),
+            Some(max) => format!("{} to {}", min, max),
+            None => format!("at least {}", min),
+        };
+        checks.push(ElementCheck {
+            element: element.to_string(),
+            requirement: Required,
+            present,
+            location: release.location.clone(),
+            message: format!("{} {}(s), {} needed", count, noun, bounds),
+        });
+    }
+
+    let weight = |check: &ElementCheck| if check.requirement == Required { 2 } else { 1 };
+    let total: u32 = checks.iter().map(weight).sum();
+    let satisfied: u32 = checks.iter().filter(|check| check.present).map(weight).sum();
+    ReleaseConformance {
+        release: release.name,
+        location: release.location,
+        score: if total == 0 { 100 } else { (f64::from(satisfied) * 100.0 / f64::from(total)).round() as u8 },
+        checks,
+    }
+}
+
+/// Release-level elements the request and XML readers look up
+const RELEASE_ELEMENTS: [&str; 9] = ["Title", "DisplayArtist", "UPC", "ReleaseDate", "Label", "PLine", "CLine", "Genre", "CoverArt"];
+
+fn request_releases(request: &BuildRequest) -> Vec<Release> {
+    // Values of every element across the request, matched to releases by path
+    let values: IndexMap<&str, Vec<_>> = RELEASE_ELEMENTS
+        .iter()
+        .chain(TRACK.iter().map(|(element, _)| element))
+        .map(|element| (*element, field_values(request, element).unwrap_or_default()))
+        .collect();
+    let find = |element: &'static str, prefix: &str| {
+        let mut found = values[element].iter().filter(|value| value.location.starts_with(prefix));
+        let first = found.clone().find(|value| value.value.is_some()).or_else(|| found.next())?;
+        Some((element, Found { location: first.location.clone(), present: first.value.is_some() }))
+    };
+
+    request
+        .releases
+        .iter()
+        .enumerate()
+        .map(|(i, release)| {
+            let location = format!("/releases[{}]", i);
+            let prefix = format!("{}/", location);
+            let mut elements: IndexMap<_, _> = RELEASE_ELEMENTS.iter().filter_map(|element| find(element, &prefix)).collect();
+
+            let covered = request.deals.iter().enumerate().find(|(_, deal)| {
+                let applies = (deal.release_ids.is_empty() && deal.release_references.is_empty())
+                    || deal.release_ids.iter().any(|id| *id == release.release_id || Some(id) == release.upc.as_ref())
+                    || release.release_reference.as_ref().is_some_and(|reference| deal.release_references.contains(reference));
+                applies && !deal.deal_terms.territory_code.is_empty()
+            });
+            elements.insert("Deal", Found {
+                location: covered.map_or_else(|| "/deals".to_string(), |(k, _)| format!("/deals[{}]", k)),
+                present: covered.is_some(),
+            });
+
+            let tracks = (0..release.tracks.len())
+                .map(|j| {
+                    let location = format!("{}tracks[{}]", prefix, j);
+                    let prefix = format!("{}/", location);
+                    let elements = TRACK.iter().filter_map(|(element, _)| find(element, &prefix)).collect();
+                    (location, elements)
+                })
+                .collect();
+
+            Release {
+                name: release.release_reference.clone().unwrap_or_else(|| release.release_id.clone()),
+                location,
+                elements,
+                tracks,
+                videos: release.resources.iter().filter(|resource| resource.kind == ResourceKind::Video).count(),
+            }
+        })
+        .collect()
+}
+
+/// XML elements that carry each profile element, any of which will do
+fn xml_names(element: &str) -> &'static [&'static str] {
+    match element {
+        "Title" | "TrackTitle" => &["DisplayTitleText", "TitleText"],
+        "DisplayArtist" => &["DisplayArtistName", "DisplayArtist"],
+        "UPC" => &["ICPN"],
+        "ReleaseDate" => &["ReleaseDate", "OriginalReleaseDate", "GlobalOriginalReleaseDate"],
+        "Label" => &["LabelName", "ReleaseLabelReference"],
+        "PLine" => &["PLine"],
+        "CLine" => &["CLine"],
+        "Genre" => &["GenreText"],
+        "ISRC" => &["ISRC"],
+        "Duration" => &["Duration"],
+        _ => &[],
+    }
+}
+
+fn xml_releases(doc: &Document<'_>) -> Vec<Release> {
+    let list = |name: &str| doc.children_named(0, name).next().map(|(index, _)| index);
+    let text = |element: usize, name: &str| {
+        doc.descendants(element).find(|(_, e)| e.name == name).map(|(_, e)| e.text.as_str()).filter(|text| !text.is_empty())
+    };
+    let find = |element: &'static str, within: usize| {
+        let found = doc
+            .descendants(within)
+            .find(|(_, e)| xml_names(element).contains(&e.name.as_str()) && (!e.text.is_empty() || !e.children.is_empty()));
+        (element, Found {
+            location: found.map_or_else(|| doc.elements[within].path.clone(), |(_, e)| e.path.clone()),
+            present: found.is_some(),
+        })
+    };
+
+    // Resources by reference, and the releases each deal covers
+    let resources: Vec<(usize, Option<&str>)> = list("ResourceList")
+        .map(|resources| doc.children(resources).map(|(index, _)| (index, text(index, "ResourceReference"))).collect())
+        .unwrap_or_default();
+    let mut deals: IndexMap<&str, usize> = IndexMap::new();
+    if let Some(list) = list("DealList") {
+        for (release_deal, _) in doc.children(list) {
+            if text(release_deal, "TerritoryCode").is_none() {
+                continue;
+            }
+            for (_, reference) in doc.children_named(release_deal, "DealReleaseReference") {
+                deals.entry(reference.text.as_str()).or_insert(release_deal);
+            }
+        }
+    }
+
+    let Some(release_list) = list("ReleaseList") else {
+        return Vec::new();
+    };
+    doc.children(release_list)
+        .filter(|(release, _)| !is_track_release(doc, *release))
+        .enumerate()
+        .map(|(i, (release, element))| {
+            let reference = doc.children_named(release, "ReleaseReference").next().map(|(_, e)| e.text.as_str());
+            let mut elements: IndexMap<_, _> = RELEASE_ELEMENTS.iter().map(|element| find(element, release)).collect();
+
+            // A release without resource references is taken to use them all
+            let used: IndexSet<&str> = doc
+                .descendants(release)
+                .filter(|(_, e)| e.name == "ReleaseResourceReference")
+                .map(|(_, e)| e.text.as_str())
+                .collect();
+            let own: Vec<usize> = resources
+                .iter()
+                .filter(|(_, reference)| used.is_empty() || reference.is_some_and(|reference| used.contains(reference)))
+                .map(|(index, _)| *index)
+                .collect();
+            let named = |name: &'static str| own.iter().copied().filter(move |index| doc.elements[*index].name == name);
+
+            let cover = named("Image").find(|image| {
+                doc.descendants(*image).any(|(_, e)| (e.name == "ImageType" || e.name == "Type") && e.text == "FrontCoverImage")
+            });
+            elements.insert("CoverArt", Found {
+                location: cover.map_or_else(|| doc.elements[list("ResourceList").unwrap_or(0)].path.clone(), |image| doc.elements[image].path.clone()),
+                present: cover.is_some(),
+            });
+            let deal = reference.and_then(|reference| deals.get(reference));
+            elements.insert("Deal", Found {
+                location: deal.map_or_else(|| doc.elements[list("DealList").unwrap_or(0)].path.clone(), |deal| doc.elements[*deal].path.clone()),
+                present: deal.is_some(),
+            });
+
+            let tracks = named("SoundRecording")
+                .map(|recording| {
+                    let elements = TRACK.iter().map(|(element, _)| find(element, recording)).collect();
+                    (doc.elements[recording].path.clone(), elements)
+                })
+                .collect();
+
+            Release {
+                name: reference.map_or_else(|| format!("release {}", i + 1), str::to_string),
+                location: element.path.clone(),
+                elements,
+                tracks,
+                videos: named("Video").count(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(tracks: usize) -> BuildRequest {
+        let tracks: Vec<_> = (0..tracks)
+            .map(|i| serde_json::json!({
+                "track_id": format!("T{}", i),
+                "isrc": format!("USRC1760783{}", i),
+                "title": "Song",
+                "duration": if i == 0 { "" } else { "PT3M" },
+                "artist": "Artist",
+            }))
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "header": {
+                "message_sender": { "party_name": [{ "text": "Sender" }] },
+                "message_recipient": { "party_name": [{ "text": "Recipient" }] }
+            },
+            "version": "4.3",
+            "releases": [{
+                "release_id": "R1",
+                "title": [{ "text": "Album" }],
+                "artist": "Artist",
+                "label": "Label",
+                "upc": "123456789012",
+                "p_line": { "year": 2024, "text": "Label" },
+                "c_line": { "year": 2024, "text": "Label" },
+                "territory_details": [{ "territory_codes": ["Worldwide"], "genre": "Pop" }],
+                "tracks": tracks
+            }],
+            "deals": [{
+                "deal_terms": { "commercial_model_type": "SubscriptionModel", "territory_code": ["Worldwide"] },
+                "release_references": []
+            }]
+        }))
+        .unwrap()
+    }
+
+    fn missing(report: &ConformanceReport) -> Vec<(&str, &str)> {
+        report.missing().map(|check| (check.element.as_str(), check.location.as_str())).collect()
+    }
+
+    #[test]
+    fn test_request_reports_missing_elements() {
+        let report = ConformanceReport::check(MessageProfile::AudioAlbum, &request(2));
+        assert_eq!(missing(&report), [("CoverArt", "/releases[0]/resources"), ("Duration", "/releases[0]/tracks[0]/duration")]);
+        assert!(!report.is_conformant());
+        assert!(report.score > 80 && report.score < 100, "{}", report.score);
+
+        let release = &report.releases[0];
+        assert_eq!(release.release, "R1");
+        let date = release.checks.iter().find(|check| check.element == "ReleaseDate").unwrap();
+        assert_eq!((date.requirement, date.present), (Recommended, false));
+    }
+
+    #[test]
+    fn test_track_counts_follow_the_profile() {
+        let single = ConformanceReport::check(MessageProfile::AudioSingle, &request(5));
+        let count = single.missing().find(|check| check.element == "SoundRecordingCount").unwrap();
+        assert_eq!(count.message, "5 sound recording(s), 1 to 3 needed");
+
+        let video = ConformanceReport::check(MessageProfile::VideoSingle, &request(1));
+        assert!(video.missing().any(|check| check.message == "0 video(s), exactly 1 needed"));
+
+        let reports = ConformanceReport::check_all(&request(1));
+        assert_eq!(reports.len(), 5);
+        assert_eq!(reports[0].profile, MessageProfile::AudioSingle);
+    }
+
+    #[test]
+    fn test_xml_releases() {
+        let xml = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+          <ResourceList>
+            <SoundRecording><ResourceReference>A1</ResourceReference><ISRC>USRC17607839</ISRC>
+              <DisplayTitleText>Song</DisplayTitleText><Duration>PT3M</Duration></SoundRecording>
+            <Image><ResourceReference>A2</ResourceReference><Type>FrontCoverImage</Type></Image>
+          </ResourceList>
+          <ReleaseList>
+            <Release><ReleaseReference>R1</ReleaseReference><ReleaseId><ICPN>123456789012</ICPN></ReleaseId>
+              <DisplayTitleText>Single</DisplayTitleText><DisplayArtistName>Artist</DisplayArtistName>
+              <ReleaseLabelReference>P1</ReleaseLabelReference>
+              <PLine><PLineText>Label</PLineText></PLine><Genre><GenreText>Pop</GenreText></Genre>
+              <ResourceGroup><ResourceGroupContentItem><ReleaseResourceReference>A1</ReleaseResourceReference></ResourceGroupContentItem></ResourceGroup>
+            </Release>
+            <TrackRelease><ReleaseReference>R2</ReleaseReference></TrackRelease>
+          </ReleaseList>
+          <DealList><ReleaseDeal><DealReleaseReference>R1</DealReleaseReference><Deal><TerritoryCode>US</TerritoryCode></Deal></ReleaseDeal></DealList>
+        </ern:NewReleaseMessage>"#;
+
+        let report = ConformanceReport::from_xml(MessageProfile::AudioSingle, xml).unwrap();
+        assert_eq!(report.releases.len(), 1);
+        assert_eq!(missing(&report), [("CLine", "/NewReleaseMessage/ReleaseList[1]/Release[1]")]);
+        let release = &report.releases[0];
+        // The cover is not among the release's resources
+        let cover = release.checks.iter().find(|check| check.element == "CoverArt").unwrap();
+        assert!(!cover.present);
+        let isrc = release.checks.iter().find(|check| check.element == "ISRC").unwrap();
+        assert_eq!(isrc.location, "/NewReleaseMessage/ResourceList[1]/SoundRecording[1]/ISRC[1]");
+    }
+}
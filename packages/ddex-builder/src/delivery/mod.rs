@@ -0,0 +1,241 @@
+//! Release packages laid out for delivery to a DSP
+//!
+//! A [`DeliveryBatch`] follows the batch profile of the DDEX Electronic
+//! Release Delivery choreography that most DSP ingestion folders expect:
+//!
+//! ```text
+//! <batch id>/                       20240301120000000
+//!   <release id>/                   0123456789012
+//!     resources/<file>              audio, artwork
+//!     <release id>.xml              the NewReleaseMessage
+//!     <release id>.md5              md5sum manifest of the files above
+//!   BatchComplete_<batch id>.xml    empty flag, uploaded last
+//! ```
+//!
+//! Resources go up before the message and the flag file after everything
+//! else, so a DSP that polls the folder never picks up a half-sent release.
+//! [`DeliveryBatch::plan`] lists the files in that order; the transports
+//! ([`sftp`] with the `sftp` feature) send them and return a
+//! [`DeliveryReceipt`].
+
+#[cfg(feature = "sftp")]
+pub mod sftp;
+
+use crate::error::BuildError;
+use crate::hash_sum::{digest_file, HashSumAlgorithm};
+use chrono::{DateTime, Utc};
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// One release: its message and the files its resources point to
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReleasePackage {
+    /// ICPN, GRid or proprietary ID naming the release folder
+    pub release_id: String,
+    /// The NewReleaseMessage
+    pub message: PathBuf,
+    /// Audio, artwork and other files, uploaded under `resources/`
+    pub resources: Vec<PathBuf>,
+}
+
+impl ReleasePackage {
+    /// Package a message, naming its folder after the main release's ICPN,
+    /// GRid or proprietary ID
+    pub fn from_message(message: impl Into<PathBuf>, resources: Vec<PathBuf>) -> Result<Self, BuildError> {
+        let message = message.into();
+        let xml = std::fs::read_to_string(&message)?;
+        let release_id = crate::split::split_by_release(&xml)?
+            .into_iter()
+            .find_map(|part| part.release_id)
+            .ok_or_else(|| BuildError::MissingRequired {
+                field: format!("{}: ICPN, GRid or ProprietaryId of the main release", message.display()),
+            })?;
+        Ok(Self { release_id, message, resources })
+    }
+
+    /// Add every file in `dir`, in name order
+    pub fn with_resource_dir(mut self, dir: impl AsRef<Path>) -> Result<Self, BuildError> {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file())
+            .collect();
+        files.sort();
+        self.resources.extend(files);
+        Ok(self)
+    }
+}
+
+/// Where the bytes of a planned file come from
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileSource {
+    /// A local file
+    Path(PathBuf),
+    /// Generated content: manifests and the BatchComplete flag
+    Bytes(Vec<u8>),
+}
+
+/// A file to upload, relative to the delivery root
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedFile {
+    /// Path below the remote root, `/`-separated
+    pub remote_path: String,
+    /// Content
+    pub source: FileSource,
+    /// Size in bytes
+    pub size: u64,
+    /// Lowercase hex MD5
+    pub md5: String,
+}
+
+/// Releases delivered together under one batch ID
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeliveryBatch {
+    /// Folder name of the batch; a 17-digit UTC timestamp by default
+    pub batch_id: String,
+    /// Releases in the batch
+    pub releases: Vec<ReleasePackage>,
+}
+
+impl DeliveryBatch {
+    /// A batch named after the current time, e.g. `20240301120000000`
+    pub fn new(releases: Vec<ReleasePackage>) -> Self {
+        Self {
+            batch_id: Utc::now().format("%Y%m%d%H%M%S%3f").to_string(),
+            releases,
+        }
+    }
+
+    /// The files of the batch in upload order, with their digests
+    pub fn plan(&self) -> Result<Vec<PlannedFile>, BuildError> {
+        let mut files = Vec::new();
+        for release in &self.releases {
+            let folder = format!("{}/{}", self.batch_id, release.release_id);
+            let mut release_files = Vec::new();
+            for resource in &release.resources {
+                release_files.push((format!("resources/{}", file_name(resource)?), resource.as_path()));
+            }
+            release_files.push((format!("{}.xml", release.release_id), release.message.as_path()));
+
+            let mut manifest = String::new();
+            for (relative, local) in release_files {
+                if files.iter().any(|f: &PlannedFile| f.remote_path == format!("{}/{}", folder, relative)) {
+                    return Err(BuildError::InvalidFormat {
+                        field: local.display().to_string(),
+                        message: format!("two files would be delivered as {}/{}", folder, relative),
+                    });
+                }
+                let digest = digest_file(local, HashSumAlgorithm::Md5)?;
+                manifest.push_str(&format!("{}  {}\n", digest.value, relative));
+                files.push(PlannedFile {
+                    remote_path: format!("{}/{}", folder, relative),
+                    source: FileSource::Path(local.to_path_buf()),
+                    size: digest.size,
+                    md5: digest.value,
+                });
+            }
+            files.push(generated(format!("{}/{}.md5", folder, release.release_id), manifest.into_bytes()));
+        }
+        files.push(generated(format!("{}/BatchComplete_{}.xml", self.batch_id, self.batch_id), Vec::new()));
+        Ok(files)
+    }
+}
+
+fn generated(remote_path: String, bytes: Vec<u8>) -> PlannedFile {
+    let md5 = hex::encode(Md5::digest(&bytes));
+    PlannedFile {
+        remote_path,
+        size: bytes.len() as u64,
+        md5,
+        source: FileSource::Bytes(bytes),
+    }
+}
+
+fn file_name(path: &Path) -> Result<&str, BuildError> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| BuildError::InvalidFormat {
+            field: path.display().to_string(),
+            message: "not a file name".to_string(),
+        })
+}
+
+/// Machine-readable record of a delivery
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeliveryReceipt {
+    /// Batch folder name
+    pub batch_id: String,
+    /// Server the batch went to, e.g. `sftp://ingest.example.com:22/incoming`
+    pub destination: String,
+    /// When the first file started
+    pub started_at: DateTime<Utc>,
+    /// When the BatchComplete flag was written
+    pub completed_at: DateTime<Utc>,
+    /// Every file of the batch, in upload order
+    pub files: Vec<DeliveredFile>,
+    /// Bytes sent in this run, excluding resumed and skipped parts
+    pub bytes_sent: u64,
+}
+
+/// One file of a [`DeliveryReceipt`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeliveredFile {
+    /// Path below the remote root
+    pub remote_path: String,
+    /// Size in bytes
+    pub size: u64,
+    /// Lowercase hex MD5
+    pub md5: String,
+    /// Bytes already on the server from an earlier, interrupted run
+    pub resumed_from: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: &str = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+  <MessageHeader><MessageId>M1</MessageId></MessageHeader>
+  <ReleaseList>
+    <Release><ReleaseReference>R1</ReleaseReference><ReleaseId><ICPN>0123456789012</ICPN></ReleaseId></Release>
+  </ReleaseList>
+</ern:NewReleaseMessage>"#;
+
+    #[test]
+    fn test_plan_layout_and_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let message = dir.path().join("release.xml");
+        std::fs::write(&message, MESSAGE).unwrap();
+        let resources = dir.path().join("resources");
+        std::fs::create_dir(&resources).unwrap();
+        std::fs::write(resources.join("01.flac"), b"audio").unwrap();
+        std::fs::write(resources.join("cover.jpg"), b"image").unwrap();
+
+        let package = ReleasePackage::from_message(&message, Vec::new())
+            .unwrap()
+            .with_resource_dir(&resources)
+            .unwrap();
+        assert_eq!(package.release_id, "0123456789012");
+        let batch = DeliveryBatch { batch_id: "20240301120000000".to_string(), releases: vec![package] };
+        let plan = batch.plan().unwrap();
+
+        let paths: Vec<&str> = plan.iter().map(|f| f.remote_path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "20240301120000000/0123456789012/resources/01.flac",
+                "20240301120000000/0123456789012/resources/cover.jpg",
+                "20240301120000000/0123456789012/0123456789012.xml",
+                "20240301120000000/0123456789012/0123456789012.md5",
+                "20240301120000000/BatchComplete_20240301120000000.xml",
+            ]
+        );
+        assert_eq!(plan[0].md5, "a5ca0b5894324f8bb54bb9fffad29d1e");
+        let FileSource::Bytes(manifest) = &plan[3].source else { panic!("manifest is generated") };
+        let manifest = String::from_utf8(manifest.clone()).unwrap();
+        assert_eq!(manifest.lines().count(), 3);
+        assert!(manifest.starts_with(&format!("{}  resources/01.flac\n", plan[0].md5)));
+        assert!(manifest.ends_with(&format!("{}  0123456789012.xml\n", plan[2].md5)));
+        assert_eq!(plan[4].size, 0);
+    }
+}
@@ -0,0 +1,277 @@
+//! Delivery of a [`DeliveryBatch`] over SFTP
+//!
+//! Each file is compared with what is already on the server: a file of the
+//! planned size is left alone, a shorter one is continued from where it
+//! stopped, so an interrupted delivery is resumed by running it again with
+//! the same batch ID. Manifests and the BatchComplete flag are always
+//! rewritten.
+//!
+//! ```no_run
+//! use ddex_builder::delivery::{DeliveryBatch, ReleasePackage};
+//! use ddex_builder::delivery::sftp::{deliver, SftpAuth, SftpConfig};
+//!
+//! let package = ReleasePackage::from_message("release.xml", Vec::new())?.with_resource_dir("resources")?;
+//! let mut config = SftpConfig::new("ingest.example.com", "label");
+//! config.auth = SftpAuth::KeyFile { path: "id_ed25519".into(), passphrase: None };
+//! config.remote_root = "incoming".to_string();
+//! config.bytes_per_second = Some(5_000_000);
+//! let receipt = deliver(&DeliveryBatch::new(vec![package]), &config)?;
+//! println!("{}", serde_json::to_string_pretty(&receipt)?);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use super::{DeliveredFile, DeliveryBatch, DeliveryReceipt, FileSource, PlannedFile};
+use crate::error::BuildError;
+use chrono::Utc;
+use ssh2::{CheckResult, KnownHostFileKind, OpenFlags, OpenType, Session, Sftp};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Size of the chunks files are sent in
+const CHUNK_SIZE: usize = 32 * 1024;
+
+/// How to log in
+#[derive(Debug, Clone, PartialEq)]
+pub enum SftpAuth {
+    /// Keys held by the running ssh-agent
+    Agent,
+    /// A private key file
+    KeyFile {
+        /// Path of the private key
+        path: PathBuf,
+        /// Passphrase of an encrypted key
+        passphrase: Option<String>,
+    },
+    /// Password authentication
+    Password(String),
+}
+
+/// Server, credentials and limits of an SFTP delivery
+#[derive(Debug, Clone, PartialEq)]
+pub struct SftpConfig {
+    /// Host name or address
+    pub host: String,
+    /// Port, 22 by default
+    pub port: u16,
+    /// Login name
+    pub username: String,
+    /// Credentials, the ssh-agent by default
+    pub auth: SftpAuth,
+    /// Folder the batch folders are created in
+    pub remote_root: String,
+    /// OpenSSH known_hosts file the server key must be listed in; `None`
+    /// accepts any key
+    pub known_hosts: Option<PathBuf>,
+    /// Upload bandwidth limit
+    pub bytes_per_second: Option<u64>,
+    /// Timeout of each blocking network operation
+    pub timeout: Duration,
+}
+
+impl SftpConfig {
+    /// Defaults for a server: port 22, agent authentication, the login
+    /// folder as root, no host key check and no bandwidth limit
+    pub fn new(host: impl Into<String>, username: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            port: 22,
+            username: username.into(),
+            auth: SftpAuth::Agent,
+            remote_root: ".".to_string(),
+            known_hosts: None,
+            bytes_per_second: None,
+            timeout: Duration::from_secs(60),
+        }
+    }
+
+    fn destination(&self) -> String {
+        format!("sftp://{}:{}/{}", self.host, self.port, self.remote_root.trim_start_matches('/'))
+    }
+}
+
+/// Upload a batch and return its receipt
+pub fn deliver(batch: &DeliveryBatch, config: &SftpConfig) -> Result<DeliveryReceipt, BuildError> {
+    let plan = batch.plan()?;
+    let session = connect(config)?;
+    let sftp = session.sftp().map_err(failed("open SFTP channel"))?;
+
+    let started_at = Utc::now();
+    let started = Instant::now();
+    let mut throttle = Throttle::new(config.bytes_per_second);
+    let mut files = Vec::new();
+    let mut bytes_sent = 0;
+    for file in &plan {
+        let remote = Path::new(&config.remote_root).join(&file.remote_path);
+        if let Some(parent) = remote.parent() {
+            create_dirs(&sftp, parent)?;
+        }
+        let (resumed_from, sent) = upload(&sftp, file, &remote, &mut throttle, started)?;
+        bytes_sent += sent;
+        files.push(DeliveredFile {
+            remote_path: file.remote_path.clone(),
+            size: file.size,
+            md5: file.md5.clone(),
+            resumed_from,
+        });
+    }
+
+    Ok(DeliveryReceipt {
+        batch_id: batch.batch_id.clone(),
+        destination: config.destination(),
+        started_at,
+        completed_at: Utc::now(),
+        files,
+        bytes_sent,
+    })
+}
+
+fn connect(config: &SftpConfig) -> Result<Session, BuildError> {
+    let tcp = TcpStream::connect((config.host.as_str(), config.port))
+        .map_err(|e| delivery_error(format!("connect to {}:{}: {}", config.host, config.port, e)))?;
+    let mut session = Session::new().map_err(failed("start SSH session"))?;
+    session.set_timeout(config.timeout.as_millis().min(u32::MAX as u128) as u32);
+    session.set_tcp_stream(tcp);
+    session.handshake().map_err(failed("SSH handshake"))?;
+
+    if let Some(known_hosts) = &config.known_hosts {
+        let (key, _) = session.host_key().ok_or_else(|| delivery_error("server sent no host key"))?;
+        let mut hosts = session.known_hosts().map_err(failed("read known_hosts"))?;
+        hosts
+            .read_file(known_hosts, KnownHostFileKind::OpenSSH)
+            .map_err(failed("read known_hosts"))?;
+        match hosts.check_port(&config.host, config.port, key) {
+            CheckResult::Match => {}
+            CheckResult::Mismatch => {
+                return Err(delivery_error(format!("host key of {} does not match known_hosts", config.host)))
+            }
+            CheckResult::NotFound | CheckResult::Failure => {
+                return Err(delivery_error(format!("{} is not in {}", config.host, known_hosts.display())))
+            }
+        }
+    }
+
+    match &config.auth {
+        SftpAuth::Agent => session.userauth_agent(&config.username),
+        SftpAuth::KeyFile { path, passphrase } => {
+            session.userauth_pubkey_file(&config.username, None, path, passphrase.as_deref())
+        }
+        SftpAuth::Password(password) => session.userauth_password(&config.username, password),
+    }
+    .map_err(failed("authenticate"))?;
+    Ok(session)
+}
+
+/// `mkdir -p` on the server
+fn create_dirs(sftp: &Sftp, dir: &Path) -> Result<(), BuildError> {
+    let mut current = PathBuf::new();
+    for component in dir.components() {
+        current.push(component);
+        if sftp.stat(&current).is_err() {
+            sftp.mkdir(&current, 0o755)
+                .map_err(failed(&format!("create {}", current.display())))?;
+        }
+    }
+    Ok(())
+}
+
+/// Send the part of `file` the server lacks; returns the bytes that were
+/// already there and the bytes sent
+fn upload(
+    sftp: &Sftp,
+    file: &PlannedFile,
+    remote: &Path,
+    throttle: &mut Throttle,
+    started: Instant,
+) -> Result<(u64, u64), BuildError> {
+    let existing = match file.source {
+        FileSource::Path(_) => sftp.stat(remote).ok().and_then(|stat| stat.size),
+        FileSource::Bytes(_) => None,
+    };
+    if existing == Some(file.size) {
+        return Ok((file.size, 0));
+    }
+    let resume_at = existing.filter(|&size| size < file.size).unwrap_or(0);
+
+    let mut flags = OpenFlags::WRITE | OpenFlags::CREATE;
+    if resume_at == 0 {
+        flags |= OpenFlags::TRUNCATE;
+    }
+    let action = format!("write {}", remote.display());
+    let mut target = sftp.open_mode(remote, flags, 0o644, OpenType::File).map_err(failed(&action))?;
+    let write_failed = |e: std::io::Error| delivery_error(format!("write {}: {}", remote.display(), e));
+    target.seek(SeekFrom::Start(resume_at)).map_err(write_failed)?;
+
+    let mut source: Box<dyn Read> = match &file.source {
+        FileSource::Path(path) => {
+            let mut local = std::fs::File::open(path)?;
+            local.seek(SeekFrom::Start(resume_at))?;
+            Box::new(local)
+        }
+        FileSource::Bytes(bytes) => Box::new(std::io::Cursor::new(bytes.clone())),
+    };
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut sent = 0u64;
+    loop {
+        let read = source.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        target.write_all(&buffer[..read]).map_err(write_failed)?;
+        sent += read as u64;
+        std::thread::sleep(throttle.pause(read as u64, started.elapsed()));
+    }
+    target.flush().map_err(write_failed)?;
+    Ok((resume_at, sent))
+}
+
+fn delivery_error(message: impl Into<String>) -> BuildError {
+    BuildError::Delivery { message: message.into() }
+}
+
+/// Sleeps that keep a transfer under a bandwidth limit
+#[derive(Debug, Clone)]
+struct Throttle {
+    bytes_per_second: Option<u64>,
+    sent: u64,
+}
+
+impl Throttle {
+    fn new(bytes_per_second: Option<u64>) -> Self {
+        Self { bytes_per_second, sent: 0 }
+    }
+
+    /// Record `bytes` more sent `elapsed` after the start, returning how long
+    /// to wait before sending again
+    fn pause(&mut self, bytes: u64, elapsed: Duration) -> Duration {
+        self.sent += bytes;
+        match self.bytes_per_second {
+            Some(limit) if limit > 0 => {
+                let due = Duration::from_secs_f64(self.sent as f64 / limit as f64);
+                due.saturating_sub(elapsed)
+            }
+            _ => Duration::ZERO,
+        }
+    }
+}
+
+fn failed(action: &str) -> impl Fn(ssh2::Error) -> BuildError + '_ {
+    move |e| delivery_error(format!("{}: {}", action, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throttle() {
+        let mut unlimited = Throttle::new(None);
+        assert_eq!(unlimited.pause(1 << 20, Duration::ZERO), Duration::ZERO);
+
+        let mut throttle = Throttle::new(Some(1000));
+        assert_eq!(throttle.pause(500, Duration::ZERO), Duration::from_millis(500));
+        assert_eq!(throttle.pause(500, Duration::from_millis(600)), Duration::from_millis(400));
+        assert_eq!(throttle.pause(0, Duration::from_secs(5)), Duration::ZERO);
+    }
+}
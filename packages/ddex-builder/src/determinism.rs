@@ -145,6 +145,14 @@ pub struct DeterminismConfig {
     pub time_zone_policy: TimeZonePolicy,
     pub date_time_format: DateTimeFormat,
     
+    /// Source of MessageCreatedDateTime when the request leaves it unset
+    #[serde(default)]
+    pub timestamp_source: TimestampSource,
+    
+    /// ID generation strategy, overriding `BuildOptions::id_strategy`
+    #[serde(default)]
+    pub id_strategy: Option<super::builder::IdStrategy>,
+    
     /// Reproducibility options
     pub emit_reproducibility_banner: bool,
     pub verify_determinism: Option<usize>,
@@ -167,6 +175,8 @@ impl Default for DeterminismConfig {
             quote_style: QuoteStyle::Double,
             time_zone_policy: TimeZonePolicy::UTC,
             date_time_format: DateTimeFormat::ISO8601Z,
+            timestamp_source: TimestampSource::Now,
+            id_strategy: None,
             emit_reproducibility_banner: false,
             verify_determinism: None,
         }
@@ -300,6 +310,60 @@ pub enum DateTimeFormat {
     Custom,
 }
 
+/// Where the generated MessageCreatedDateTime comes from
+///
+/// `Now` reads the system clock and therefore breaks byte-identical rebuilds;
+/// use `Fixed` or `SourceDateEpoch` when output must be reproducible.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimestampSource {
+    /// Current system time
+    #[default]
+    Now,
+    /// Fixed RFC 3339 timestamp
+    Fixed(String),
+    /// Unix seconds from the `SOURCE_DATE_EPOCH` environment variable,
+    /// falling back to the current time when it is unset
+    SourceDateEpoch,
+}
+
+impl TimestampSource {
+    /// Resolve to an RFC 3339 UTC timestamp with second precision
+    pub fn resolve(&self) -> Result<String, super::error::BuildError> {
+        match self {
+            TimestampSource::Now => Ok(format_timestamp(chrono::Utc::now())),
+            TimestampSource::Fixed(value) => chrono::DateTime::parse_from_rfc3339(value)
+                .map(|dt| format_timestamp(dt.with_timezone(&chrono::Utc)))
+                .map_err(|e| super::error::BuildError::InvalidFormat {
+                    field: "timestamp_source".to_string(),
+                    message: format!("'{}' is not an RFC 3339 timestamp: {}", value, e),
+                }),
+            TimestampSource::SourceDateEpoch => {
+                Self::resolve_epoch(std::env::var("SOURCE_DATE_EPOCH").ok().as_deref())
+            }
+        }
+    }
+
+    fn resolve_epoch(epoch: Option<&str>) -> Result<String, super::error::BuildError> {
+        let Some(epoch) = epoch else {
+            return Ok(format_timestamp(chrono::Utc::now()));
+        };
+        epoch
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+            .map(format_timestamp)
+            .ok_or_else(|| super::error::BuildError::InvalidFormat {
+                field: "SOURCE_DATE_EPOCH".to_string(),
+                message: format!("'{}' is not a valid Unix timestamp", epoch),
+            })
+    }
+}
+
+fn format_timestamp(dt: chrono::DateTime<chrono::Utc>) -> String {
+    dt.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
 /// Determinism verification result
 #[derive(Debug, Clone, PartialEq)]
 pub struct DeterminismResult {
@@ -682,4 +746,26 @@ impl DeterminismVerifier {
         // Return the most comprehensive result
         Ok(standard_result)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_timestamp_is_normalized_to_utc() {
+        let source = TimestampSource::Fixed("2024-03-01T12:00:00+02:00".to_string());
+        assert_eq!(source.resolve().unwrap(), "2024-03-01T10:00:00Z");
+        assert!(TimestampSource::Fixed("yesterday".to_string()).resolve().is_err());
+    }
+
+    #[test]
+    fn test_source_date_epoch() {
+        assert_eq!(
+            TimestampSource::resolve_epoch(Some("1700000000")).unwrap(),
+            "2023-11-14T22:13:20Z"
+        );
+        assert!(TimestampSource::resolve_epoch(Some("soon")).is_err());
+        assert!(TimestampSource::resolve_epoch(None).is_ok());
+    }
+}
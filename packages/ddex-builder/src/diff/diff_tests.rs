@@ -370,6 +370,138 @@ fn test_business_impact_analysis() {
     assert_eq!(changeset.metadata.get("impact_level").unwrap(), "HIGH");
 }
 
+#[test]
+fn test_xml_diff_aligns_entities_by_identifier() {
+    let mut engine = DiffEngine::new();
+    let changeset = engine.diff_xml(SAMPLE_DDEX_V1, SAMPLE_DDEX_V2).unwrap();
+    
+    // References were renumbered but the entities are still matched
+    assert!(changeset.changes_by_type(types::ChangeType::ElementAdded).is_empty());
+    assert!(changeset.changes_by_type(types::ChangeType::ElementRemoved).is_empty());
+    assert!(!changeset.changes.iter().any(|c| c.path.to_string().ends_with("Reference/#text")));
+    
+    // Header noise is ignored
+    assert!(!changeset.changes.iter().any(|c| c.path.to_string().contains("MessageHeader")));
+    
+    let title = changeset.changes.iter()
+        .find(|c| c.path.to_string() == "/ResourceList/SoundRecording[R001]/ReferenceTitle/#text")
+        .expect("title change");
+    assert_eq!(title.new_value.as_deref(), Some("Test Track (Remastered)"));
+    assert!(!title.is_critical);
+    
+    let upc = changeset.changes.iter()
+        .find(|c| c.path.to_string() == "/ReleaseList/Release[REL001]/UPC/#text")
+        .expect("UPC change");
+    assert!(upc.is_critical);
+    assert_eq!(upc.impact_level(), types::ImpactLevel::High);
+    assert_eq!(changeset.impact_level(), types::ImpactLevel::High);
+}
+
+#[test]
+fn test_xml_diff_formatting_only() {
+    let mut engine = DiffEngine::new();
+    let changeset = engine.diff_xml(SAMPLE_DDEX_V1, SAMPLE_DDEX_FORMATTING).unwrap();
+    
+    assert!(!changeset.has_changes());
+}
+
+#[test]
+fn test_xml_diff_reports_added_entities() {
+    let mut engine = DiffEngine::new();
+    let changeset = engine.diff_xml(SAMPLE_DDEX_V1, SAMPLE_DDEX_CRITICAL).unwrap();
+    
+    // A new DealList is reported per deal
+    let added = changeset.changes_by_type(types::ChangeType::ElementAdded);
+    assert_eq!(added.len(), 1);
+    assert_eq!(added[0].path.to_string(), "/DealList/ReleaseDeal");
+    assert!(added[0].is_critical);
+    assert!(added[0].new_value.as_deref().unwrap().contains("<CommercialModelType>SubscriptionModel</CommercialModelType>"));
+    
+    // Matched through the proprietary ResourceId even though ISRC changed
+    let isrc = changeset.changes.iter()
+        .find(|c| c.path.to_string() == "/ResourceList/SoundRecording[R001]/ISRC/#text")
+        .expect("ISRC change");
+    assert!(isrc.is_critical);
+}
+
+#[test]
+fn test_xml_diff_release_added_and_removed() {
+    let old = r#"<NewReleaseMessage><ReleaseList>
+        <Release><ReleaseReference>R1</ReleaseReference><ReleaseId><ICPN>111</ICPN></ReleaseId></Release>
+        <Release><ReleaseReference>R2</ReleaseReference><ReleaseId><ICPN>222</ICPN></ReleaseId></Release>
+    </ReleaseList></NewReleaseMessage>"#;
+    let new = r#"<NewReleaseMessage><ReleaseList>
+        <Release><ReleaseReference>R1</ReleaseReference><ReleaseId><ICPN>333</ICPN></ReleaseId></Release>
+        <Release><ReleaseReference>R2</ReleaseReference><ReleaseId><ICPN>111</ICPN></ReleaseId></Release>
+    </ReleaseList></NewReleaseMessage>"#;
+    
+    let mut engine = DiffEngine::new();
+    let changeset = engine.diff_xml(old, new).unwrap();
+    
+    // ICPN 111 moved from R1 to R2 and is matched by its stable identifier;
+    // ICPN 222 is gone and ICPN 333 is new
+    let removed = changeset.changes_by_type(types::ChangeType::ElementRemoved);
+    let added = changeset.changes_by_type(types::ChangeType::ElementAdded);
+    assert_eq!(removed.len(), 1);
+    assert_eq!(added.len(), 1);
+    assert_eq!(removed[0].description, "Release 'R2' removed");
+    assert_eq!(added[0].description, "Release 'R1' added");
+    assert!(removed[0].is_critical && added[0].is_critical);
+}
+
+#[test]
+fn test_xml_diff_ignores_reordered_values() {
+    let old = "<Release><Genre>Pop</Genre><Genre>Rock</Genre><Title>A</Title></Release>";
+    let new = "<Release><Title>A</Title><Genre>Rock</Genre><Genre>Pop</Genre></Release>";
+    
+    let mut engine = DiffEngine::new();
+    assert!(!engine.diff_xml(old, new).unwrap().has_changes());
+    
+    let mut config = DiffConfig::default();
+    config.ignore_order_changes = false;
+    let mut engine = DiffEngine::new_with_config(config);
+    assert_eq!(engine.diff_xml(old, new).unwrap().summary.modifications, 2);
+}
+
+#[test]
+fn test_xml_diff_critical_context_is_inherited() {
+    let old = "<Deal><ValidityPeriod><StartDate>2024-01-01</StartDate></ValidityPeriod></Deal>";
+    let new = "<Deal><ValidityPeriod><StartDate>2024-02-01</StartDate></ValidityPeriod></Deal>";
+    
+    let mut engine = DiffEngine::new();
+    let changeset = engine.diff_xml(old, new).unwrap();
+    
+    assert_eq!(changeset.summary.total_changes, 1);
+    assert!(changeset.changes[0].is_critical);
+}
+
+#[test]
+fn test_xml_diff_ignore_rules() {
+    let old = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43" xmlns:lbl="urn:label:ext">
+        <MessageHeader><MessageThreadId>T1</MessageThreadId></MessageHeader>
+        <Release LanguageAndScriptCode="en"><Title>A</Title><lbl:QcStatus>passed</lbl:QcStatus></Release>
+    </ern:NewReleaseMessage>"#;
+    let new = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43" xmlns:x="urn:label:ext">
+        <MessageHeader><MessageThreadId>T2</MessageThreadId></MessageHeader>
+        <Release LanguageAndScriptCode="fr" x:Batch="7"><Title>A</Title><x:QcStatus>pending</x:QcStatus></Release>
+    </ern:NewReleaseMessage>"#;
+    
+    let mut engine = DiffEngine::new();
+    assert_eq!(engine.diff_xml(old, new).unwrap().summary.total_changes, 4);
+    
+    let mut config = DiffConfig::default();
+    config.ignored_paths.insert("/MessageHeader/MessageThreadId".to_string());
+    config.ignored_namespaces.insert("urn:label:ext".to_string());
+    let mut engine = DiffEngine::new_with_config(config.clone());
+    let changeset = engine.diff_xml(old, new).unwrap();
+    assert_eq!(changeset.summary.total_changes, 1);
+    assert_eq!(changeset.changes[0].path.to_string(), "/Release/@LanguageAndScriptCode");
+    
+    config.ignore_attribute_changes = true;
+    let mut engine = DiffEngine::new_with_config(config);
+    assert!(!engine.diff_xml(old, new).unwrap().has_changes());
+}
+
 // Helper function to create a simple AST for testing
 fn create_simple_ast(element_name: &str, text_content: &str) -> AST {
     AST {
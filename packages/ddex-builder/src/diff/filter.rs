@@ -0,0 +1,113 @@
+//! Path patterns for ignoring parts of a document during diffing
+//!
+//! Patterns are slash-separated and match [`DiffPath`]s, which start below
+//! the root element:
+//!
+//! - `MessageHeader/MessageId` matches at any depth
+//! - `/MessageHeader/MessageId` matches only directly below the root
+//! - `*` matches any single segment, `**` any number of segments
+//! - `@Name` matches an attribute (`@*` any attribute), `#text` text content
+//! - `Release` matches every release, `Release[R1]` only the one labelled `R1`
+//!
+//! A pattern matching an element also covers everything below it.
+
+use super::types::{DiffPath, PathSegment};
+
+/// A compiled ignore pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PathPattern {
+    segments: Vec<PatternSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PatternSegment {
+    AnyDepth,
+    AnySegment,
+    Element(String),
+    Attribute(String),
+    Text,
+}
+
+impl PathPattern {
+    /// Compile a pattern; unanchored patterns match at any depth
+    pub(crate) fn new(pattern: &str) -> Self {
+        let pattern = pattern.trim();
+        let mut segments = Vec::new();
+        if !pattern.starts_with('/') {
+            segments.push(PatternSegment::AnyDepth);
+        }
+        for part in pattern.split('/').filter(|p| !p.is_empty()) {
+            segments.push(match part {
+                "**" => PatternSegment::AnyDepth,
+                "*" => PatternSegment::AnySegment,
+                "#text" => PatternSegment::Text,
+                _ => match part.strip_prefix('@') {
+                    Some(name) => PatternSegment::Attribute(name.to_string()),
+                    None => PatternSegment::Element(part.to_string()),
+                },
+            });
+        }
+        Self { segments }
+    }
+
+    /// Whether the path, or one of its ancestors, matches this pattern
+    pub(crate) fn matches(&self, path: &DiffPath) -> bool {
+        !self.segments.iter().all(|s| *s == PatternSegment::AnyDepth)
+            && matches_prefix(&self.segments, &path.segments)
+    }
+}
+
+fn matches_prefix(pattern: &[PatternSegment], path: &[PathSegment]) -> bool {
+    match pattern.split_first() {
+        None => true,
+        Some((PatternSegment::AnyDepth, rest)) => {
+            (0..=path.len()).any(|skip| matches_prefix(rest, &path[skip..]))
+        }
+        Some((segment, rest)) => match path.split_first() {
+            Some((first, remaining)) => {
+                segment_matches(segment, first) && matches_prefix(rest, remaining)
+            }
+            None => false,
+        },
+    }
+}
+
+fn segment_matches(pattern: &PatternSegment, segment: &PathSegment) -> bool {
+    match (pattern, segment) {
+        (PatternSegment::AnySegment, _) => true,
+        (PatternSegment::Element(expected), PathSegment::Element(name)) => {
+            // Identified entities carry their label, as in `Release[R1]`
+            name == expected || name.split_once('[').is_some_and(|(base, _)| base == expected)
+        }
+        (PatternSegment::Attribute(expected), PathSegment::Attribute(name)) => {
+            expected == "*" || name == expected
+        }
+        (PatternSegment::Text, PathSegment::Text) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_patterns() {
+        let message_id = DiffPath::root().with_element("MessageHeader").with_element("MessageId").with_text();
+        assert!(PathPattern::new("MessageId").matches(&message_id));
+        assert!(PathPattern::new("/MessageHeader/MessageId").matches(&message_id));
+        assert!(PathPattern::new("/*/MessageId/#text").matches(&message_id));
+        assert!(!PathPattern::new("/MessageId").matches(&message_id));
+        assert!(!PathPattern::new("MessageHeader/#text").matches(&message_id));
+
+        let language = DiffPath::root()
+            .with_element("ReleaseList")
+            .with_element("Release[R1]")
+            .with_attribute("LanguageAndScriptCode");
+        assert!(PathPattern::new("Release/@LanguageAndScriptCode").matches(&language));
+        assert!(PathPattern::new("/ReleaseList/**/@*").matches(&language));
+        assert!(PathPattern::new("Release[R1]").matches(&language));
+        assert!(!PathPattern::new("Release[R2]").matches(&language));
+        assert!(!PathPattern::new("**").matches(&language));
+    }
+}
@@ -1,6 +1,6 @@
 //! Formatters for DDEX diff output in various formats
 
-use super::types::{ChangeSet, SemanticChange, ChangeType};
+use super::types::{ChangeSet, SemanticChange, ChangeType, DiffPath, ImpactLevel, PathSegment};
 use crate::error::BuildError;
 use serde_json::json;
 use indexmap::IndexMap;
@@ -113,6 +113,7 @@ impl DiffFormatter {
             writeln!(output, "   Type: {}", change.change_type).unwrap();
             writeln!(output, "   Path: {}", change.path).unwrap();
             writeln!(output, "   Critical: {}", if change.is_critical { "Yes" } else { "No" }).unwrap();
+            writeln!(output, "   Impact: {}", change.impact_level()).unwrap();
             
             match (&change.old_value, &change.new_value) {
                 (Some(old), Some(new)) => {
@@ -232,10 +233,10 @@ impl DiffFormatter {
         .change-group { margin-bottom: 30px; }
         .change-type { font-weight: bold; font-size: 1.2em; margin-bottom: 15px; }
         .change-item { background: white; border: 1px solid #ddd; border-radius: 4px; padding: 15px; margin-bottom: 10px; }
-        .critical { border-left: 4px solid #dc3545; }
-        .added { border-left: 4px solid #28a745; }
-        .removed { border-left: 4px solid #dc3545; }
-        .modified { border-left: 4px solid #ffc107; }
+        .change-item.impact-high { border-left: 4px solid #dc3545; }
+        .change-item.impact-medium { border-left: 4px solid #ffc107; }
+        .change-item.impact-low { border-left: 4px solid #28a745; }
+        .badge { font-size: 0.8em; font-weight: normal; padding: 2px 8px; border-radius: 10px; border: 1px solid currentColor; }
         .path { font-family: monospace; background: #f1f1f1; padding: 2px 6px; border-radius: 3px; }
         .value { font-family: monospace; background: #f8f8f8; padding: 8px; border-radius: 3px; margin: 5px 0; }
         .old-value { background-color: #ffebee; }
@@ -289,45 +290,24 @@ impl DiffFormatter {
                 changeset.summary.critical_changes
             ));
             
-            // Critical changes first
-            let critical_changes = changeset.critical_changes();
-            if !critical_changes.is_empty() {
-                html.push_str("<div class='change-group'>");
-                html.push_str("<div class='change-type'>🚨 Critical Changes</div>");
+            // One section per release, track or deal, highest impact first
+            for (entity, changes) in Self::group_by_entity(changeset) {
+                let impact = Self::group_impact(&changes);
+                html.push_str("<div class='change-group'>\n");
+                html.push_str(&format!(
+                    "<div class='change-type'>{} <span class='badge impact-{}'>{}</span> ({} changes)</div>\n",
+                    html_escape::encode_text(&entity),
+                    impact.to_string().to_lowercase(),
+                    impact,
+                    changes.len()
+                ));
                 
-                for change in critical_changes {
-                    html.push_str(&Self::format_change_html(change, "critical"));
+                for change in changes {
+                    let css_class = format!("impact-{}", change.impact_level().to_string().to_lowercase());
+                    html.push_str(&Self::format_change_html(change, &css_class));
                 }
                 
-                html.push_str("</div>");
-            }
-            
-            // Group other changes by type
-            let mut changes_by_type: IndexMap<ChangeType, Vec<&SemanticChange>> = IndexMap::new();
-            for change in &changeset.changes {
-                if !change.is_critical {
-                    changes_by_type.entry(change.change_type).or_default().push(change);
-                }
-            }
-            
-            for (change_type, changes) in changes_by_type {
-                if !changes.is_empty() {
-                    html.push_str("<div class='change-group'>");
-                    html.push_str(&format!("<div class='change-type'>{} {} ({})</div>", 
-                        Self::change_type_icon(change_type), change_type, changes.len()));
-                    
-                    let css_class = match change_type {
-                        ChangeType::ElementAdded | ChangeType::AttributeAdded => "added",
-                        ChangeType::ElementRemoved | ChangeType::AttributeRemoved => "removed", 
-                        _ => "modified",
-                    };
-                    
-                    for change in changes {
-                        html.push_str(&Self::format_change_html(change, css_class));
-                    }
-                    
-                    html.push_str("</div>");
-                }
+                html.push_str("</div>\n");
             }
         }
         
@@ -337,6 +317,62 @@ impl DiffFormatter {
         html
     }
     
+    /// Format changeset as a Markdown report, e.g. for delivery approval tickets
+    ///
+    /// Changes are grouped by release, track or deal like the HTML report,
+    /// with impact levels marked by colored circles.
+    pub fn format_markdown(changeset: &ChangeSet) -> String {
+        let mut md = String::new();
+        
+        writeln!(md, "# DDEX Semantic Diff Report").unwrap();
+        writeln!(md).unwrap();
+        writeln!(md, "Generated: {}  ", changeset.timestamp.format("%Y-%m-%d %H:%M:%S UTC")).unwrap();
+        writeln!(md, "Impact Level: {} {}  ",
+            Self::impact_marker(changeset.impact_level()),
+            changeset.impact_level()).unwrap();
+        writeln!(md, "Summary: {}", changeset.summary.summary_string()).unwrap();
+        writeln!(md).unwrap();
+        
+        if !changeset.has_changes() {
+            writeln!(md, "✅ No semantic changes detected").unwrap();
+            return md;
+        }
+        
+        writeln!(md, "| Total | Additions | Deletions | Modifications | Moves | Critical |").unwrap();
+        writeln!(md, "|---|---|---|---|---|---|").unwrap();
+        writeln!(md, "| {} | {} | {} | {} | {} | {} |",
+            changeset.summary.total_changes,
+            changeset.summary.additions,
+            changeset.summary.deletions,
+            changeset.summary.modifications,
+            changeset.summary.moves,
+            changeset.summary.critical_changes).unwrap();
+        
+        for (entity, changes) in Self::group_by_entity(changeset) {
+            let impact = Self::group_impact(&changes);
+            writeln!(md).unwrap();
+            writeln!(md, "## {} {} ({} changes)", Self::impact_marker(impact), entity, changes.len()).unwrap();
+            writeln!(md).unwrap();
+            writeln!(md, "| Impact | Change | Path | Old | New |").unwrap();
+            writeln!(md, "|---|---|---|---|---|").unwrap();
+            for change in changes {
+                let value = |v: &Option<String>| {
+                    v.as_deref().map(|v| Self::markdown_cell(&Self::truncate_value(v))).unwrap_or_default()
+                };
+                writeln!(md, "| {} {} | {} {} | `{}` | {} | {} |",
+                    Self::impact_marker(change.impact_level()),
+                    change.impact_level(),
+                    Self::change_type_icon(change.change_type),
+                    Self::markdown_cell(&change.description),
+                    change.path,
+                    value(&change.old_value),
+                    value(&change.new_value)).unwrap();
+            }
+        }
+        
+        md
+    }
+    
     /// Generate DDEX UpdateReleaseMessage from changeset
     pub fn generate_update_message(changeset: &ChangeSet, message_id: Option<&str>) -> Result<String, BuildError> {
         let mut xml = String::new();
@@ -403,6 +439,54 @@ impl DiffFormatter {
         }
     }
     
+    /// Group changes by the release, track or deal they belong to
+    ///
+    /// Groups are named after the first labelled entity on the change path,
+    /// e.g. `Release R1` for `/ReleaseList/Release[R1]/Title`, falling back
+    /// to the top-level element such as `MessageHeader`. They are ordered
+    /// by impact so the most significant ones come first.
+    fn group_by_entity(changeset: &ChangeSet) -> Vec<(String, Vec<&SemanticChange>)> {
+        let mut groups: IndexMap<String, Vec<&SemanticChange>> = IndexMap::new();
+        for change in &changeset.changes {
+            groups.entry(Self::entity_of(&change.path)).or_default().push(change);
+        }
+        
+        let mut groups: Vec<_> = groups.into_iter().collect();
+        groups.sort_by_key(|(_, changes)| std::cmp::Reverse(Self::group_impact(changes)));
+        groups
+    }
+    
+    fn entity_of(path: &DiffPath) -> String {
+        path.segments.iter()
+            .find_map(|segment| match segment {
+                PathSegment::Element(name) => name.split_once('[').map(|(entity, label)| {
+                    format!("{} {}", entity, label.trim_end_matches(']'))
+                }),
+                _ => None,
+            })
+            .unwrap_or_else(|| match path.segments.first() {
+                Some(PathSegment::Element(name)) => name.clone(),
+                _ => "Message".to_string(),
+            })
+    }
+    
+    fn group_impact(changes: &[&SemanticChange]) -> ImpactLevel {
+        changes.iter().map(|c| c.impact_level()).max().unwrap_or(ImpactLevel::None)
+    }
+    
+    fn impact_marker(impact: ImpactLevel) -> &'static str {
+        match impact {
+            ImpactLevel::High => "🔴",
+            ImpactLevel::Medium => "🟠",
+            ImpactLevel::Low => "🟢",
+            ImpactLevel::None => "⚪",
+        }
+    }
+    
+    fn markdown_cell(value: &str) -> String {
+        value.replace('|', "\\|").replace(['\r', '\n'], " ")
+    }
+    
     fn truncate_value(value: &str) -> String {
         if value.len() > 100 {
             format!("{}...", &value[..97])
@@ -543,4 +627,32 @@ mod tests {
         assert!(html.contains("DDEX Semantic Diff Report"));
         assert!(html.contains("Critical Changes"));
     }
+    
+    #[test]
+    fn test_format_markdown_groups_by_entity() {
+        let mut changeset = create_test_changeset();
+        changeset.add_change(SemanticChange {
+            path: DiffPath::root()
+                .with_element("ResourceList")
+                .with_element("SoundRecording[A1]")
+                .with_element("DisplayTitleText")
+                .with_text(),
+            change_type: ChangeType::TextModified,
+            old_value: Some("Intro | Live".to_string()),
+            new_value: Some("Intro".to_string()),
+            is_critical: false,
+            description: "'DisplayTitleText' changed".to_string(),
+        });
+        
+        let md = DiffFormatter::format_markdown(&changeset);
+        assert!(md.starts_with("# DDEX Semantic Diff Report"));
+        assert!(md.contains("## 🔴 Release (2 changes)"));
+        assert!(md.contains("## 🟢 SoundRecording A1 (1 changes)"));
+        assert!(md.find("Release (2").unwrap() < md.find("SoundRecording A1").unwrap());
+        assert!(md.contains("| 🟢 Low | 📝 'DisplayTitleText' changed | `/ResourceList/SoundRecording[A1]/DisplayTitleText/#text` | Intro \\| Live | Intro |"));
+        
+        let html = DiffFormatter::format_html(&changeset);
+        assert!(html.contains("SoundRecording A1 <span class='badge impact-low'>Low</span>"));
+        assert!(html.contains("<div class='change-item impact-high'>"));
+    }
 }
\ No newline at end of file
@@ -6,6 +6,12 @@
 
 pub mod types;
 pub mod formatter;
+mod filter;
+mod parse;
+pub mod patch;
+
+pub use parse::parse_xml;
+pub use patch::{Patch, PatchConflict, PatchOperation, PatchOptions, PatchOutcome};
 
 #[cfg(test)]
 pub mod test_data;
@@ -15,6 +21,7 @@ mod diff_tests;
 
 use crate::error::BuildError;
 use crate::ast::{AST, Element, Node};
+use filter::PathPattern;
 use types::{ChangeSet, SemanticChange, DiffPath, ChangeType};
 use indexmap::{IndexMap, IndexSet};
 use serde::{Serialize, Deserialize};
@@ -37,6 +44,22 @@ pub struct DiffConfig {
     /// Fields to ignore during comparison
     pub ignored_fields: IndexSet<String>,
     
+    /// Path patterns to ignore, such as `MessageHeader/MessageCreatedDateTime`
+    /// or `/ReleaseList/Release/@LanguageAndScriptCode`
+    ///
+    /// Patterns match anywhere unless they start with `/`; `*` matches one
+    /// segment, `**` any number, and an element pattern covers its subtree.
+    #[serde(default)]
+    pub ignored_paths: IndexSet<String>,
+    
+    /// Namespaces to ignore, by prefix or URI, e.g. partner extensions
+    #[serde(default)]
+    pub ignored_namespaces: IndexSet<String>,
+    
+    /// Ignore attribute-only changes
+    #[serde(default)]
+    pub ignore_attribute_changes: bool,
+    
     /// Business-critical fields that should be highlighted
     pub critical_fields: IndexSet<String>,
     
@@ -65,6 +88,9 @@ impl Default for DiffConfig {
             ignore_order_changes: true,
             version_compatibility: VersionCompatibility::Strict,
             ignored_fields,
+            ignored_paths: IndexSet::new(),
+            ignored_namespaces: IndexSet::new(),
+            ignore_attribute_changes: false,
             critical_fields,
             numeric_tolerance: Some(0.01),
         }
@@ -85,22 +111,32 @@ pub enum VersionCompatibility {
 /// Semantic diff engine for DDEX messages
 pub struct DiffEngine {
     config: DiffConfig,
+    // Compiled `ignored_paths`
+    ignored_paths: Vec<PathPattern>,
+    // Namespace prefixes to ignore in the old and new document
+    ignored_prefixes: [IndexSet<String>; 2],
     // Cache for reference resolution
     reference_cache: IndexMap<String, Element>,
 }
 
+/// Which side of the diff an element or attribute belongs to
+#[derive(Debug, Clone, Copy)]
+enum Side {
+    Old = 0,
+    New = 1,
+}
+
 impl DiffEngine {
     /// Create a new diff engine with default configuration
     pub fn new() -> Self {
-        Self {
-            config: DiffConfig::default(),
-            reference_cache: IndexMap::new(),
-        }
+        Self::new_with_config(DiffConfig::default())
     }
     
     /// Create a new diff engine with custom configuration
     pub fn new_with_config(config: DiffConfig) -> Self {
         Self {
+            ignored_paths: config.ignored_paths.iter().map(|p| PathPattern::new(p)).collect(),
+            ignored_prefixes: Default::default(),
             config,
             reference_cache: IndexMap::new(),
         }
@@ -115,10 +151,16 @@ impl DiffEngine {
         self.build_reference_cache(&old.root, "old");
         self.build_reference_cache(&new.root, "new");
         
+        // Documents may bind ignored namespaces to different prefixes
+        self.ignored_prefixes = [
+            self.ignored_prefixes_for(old),
+            self.ignored_prefixes_for(new),
+        ];
+        
         let mut changeset = ChangeSet::new();
         
         // Compare root elements
-        self.compare_elements(&old.root, &new.root, DiffPath::root(), &mut changeset)?;
+        self.compare_elements(&old.root, &new.root, DiffPath::root(), false, &mut changeset)?;
         
         // Analyze changes for business impact
         self.analyze_business_impact(&mut changeset);
@@ -126,32 +168,47 @@ impl DiffEngine {
         Ok(changeset)
     }
     
+    /// Parse two DDEX XML documents and return a semantic diff
+    pub fn diff_xml(&mut self, old_xml: &str, new_xml: &str) -> Result<ChangeSet, BuildError> {
+        let old = parse_xml(old_xml)?;
+        let new = parse_xml(new_xml)?;
+        self.diff(&old, &new)
+    }
+    
     /// Compare two elements semantically
+    ///
+    /// `critical` is set when an ancestor is a business-critical field, so that
+    /// e.g. a changed `StartDate` inside `ValidityPeriod` is flagged as critical.
     fn compare_elements(
         &self, 
         old: &Element, 
         new: &Element, 
         path: DiffPath, 
+        critical: bool,
         changeset: &mut ChangeSet
     ) -> Result<(), BuildError> {
         // Check if elements represent the same logical entity
         if old.name != new.name {
-            changeset.add_change(SemanticChange {
+            self.record(changeset, SemanticChange {
                 path: path.clone(),
                 change_type: ChangeType::ElementRenamed,
                 old_value: Some(old.name.clone()),
                 new_value: Some(new.name.clone()),
-                is_critical: self.is_critical_field(&old.name),
+                is_critical: critical || self.is_critical_field(&old.name),
                 description: format!("Element renamed from '{}' to '{}'", old.name, new.name),
             });
             return Ok(());
         }
         
+        let critical = critical || self.is_critical_field(&old.name);
+        
         // Compare attributes
-        self.compare_attributes(&old.attributes, &new.attributes, &path, changeset);
+        if !self.config.ignore_attribute_changes {
+            self.compare_attributes(&old.attributes, &new.attributes, &path, changeset);
+        }
         
         // Compare children with semantic understanding
-        self.compare_children(&old.children, &new.children, &path, changeset)?;
+        self.compare_children(old, new, &path, critical, changeset)?;
         
         Ok(())
     }
@@ -165,13 +222,13 @@ impl DiffEngine {
         changeset: &mut ChangeSet,
     ) {
         // Find added, removed, and modified attributes
-        let old_keys: IndexSet<_> = old.keys().collect();
-        let new_keys: IndexSet<_> = new.keys().collect();
+        let old_keys: IndexSet<_> = old.keys().filter(|k| !self.in_ignored_namespace(k, Side::Old)).collect();
+        let new_keys: IndexSet<_> = new.keys().filter(|k| !self.in_ignored_namespace(k, Side::New)).collect();
         
         // Removed attributes
         for &key in old_keys.difference(&new_keys) {
             if !self.should_ignore_field(key) {
-                changeset.add_change(SemanticChange {
+                self.record(changeset, SemanticChange {
                     path: path.with_attribute(key),
                     change_type: ChangeType::AttributeRemoved,
                     old_value: old.get(key).cloned(),
@@ -185,7 +242,7 @@ impl DiffEngine {
         // Added attributes
         for &key in new_keys.difference(&old_keys) {
             if !self.should_ignore_field(key) {
-                changeset.add_change(SemanticChange {
+                self.record(changeset, SemanticChange {
                     path: path.with_attribute(key),
                     change_type: ChangeType::AttributeAdded,
                     old_value: None,
@@ -203,7 +260,7 @@ impl DiffEngine {
                 let new_val = &new[key];
                 
                 if !self.are_values_equivalent(old_val, new_val, key) {
-                    changeset.add_change(SemanticChange {
+                    self.record(changeset, SemanticChange {
                         path: path.with_attribute(key),
                         change_type: ChangeType::AttributeModified,
                         old_value: Some(old_val.clone()),
@@ -216,136 +273,260 @@ impl DiffEngine {
         }
     }
     
-    /// Compare children with semantic understanding
+    /// Compare the text and child elements of two matched elements
     fn compare_children(
         &self,
-        old: &[Node],
-        new: &[Node],
+        old: &Element,
+        new: &Element,
         path: &DiffPath,
+        critical: bool,
         changeset: &mut ChangeSet,
     ) -> Result<(), BuildError> {
-        // Separate elements from text nodes
-        let old_elements: Vec<&Element> = old.iter()
-            .filter_map(|n| if let Node::Element(e) = n { Some(e) } else { None })
-            .collect();
-        let new_elements: Vec<&Element> = new.iter()
-            .filter_map(|n| if let Node::Element(e) = n { Some(e) } else { None })
-            .collect();
-        
         // Compare text content
-        let old_text = self.extract_text_content(old);
-        let new_text = self.extract_text_content(new);
+        let old_text = self.extract_text_content(&old.children);
+        let new_text = self.extract_text_content(&new.children);
         
         // Only report text changes if the content actually differs after applying normalization
-        if old_text != new_text && (!old_text.trim().is_empty() || !new_text.trim().is_empty()) {
-            changeset.add_change(SemanticChange {
+        if (!old_text.trim().is_empty() || !new_text.trim().is_empty())
+            && !self.are_values_equivalent(&old_text, &new_text, &old.name)
+        {
+            let description = if path.segments.is_empty() {
+                "Text content changed".to_string()
+            } else {
+                format!("'{}' changed from '{}' to '{}'", old.name, old_text.trim(), new_text.trim())
+            };
+            self.record(changeset, SemanticChange {
                 path: path.with_text(),
                 change_type: ChangeType::TextModified,
                 old_value: if old_text.trim().is_empty() { None } else { Some(old_text) },
                 new_value: if new_text.trim().is_empty() { None } else { Some(new_text) },
-                is_critical: false,
-                description: "Text content changed".to_string(),
+                is_critical: critical,
+                description,
             });
         }
         
-        // Group elements by semantic identity for comparison
-        let old_groups = self.group_elements_by_identity(&old_elements);
-        let new_groups = self.group_elements_by_identity(&new_elements);
+        // Group child elements by name, skipping ignored fields entirely
+        let mut groups: IndexMap<&str, (Vec<&Element>, Vec<&Element>)> = IndexMap::new();
+        for element in child_elements(old).filter(|e| !self.should_ignore_element(e, Side::Old)) {
+            groups.entry(element.name.as_str()).or_default().0.push(element);
+        }
+        for element in child_elements(new).filter(|e| !self.should_ignore_element(e, Side::New)) {
+            groups.entry(element.name.as_str()).or_default().1.push(element);
+        }
         
-        // Compare element groups
-        self.compare_element_groups(&old_groups, &new_groups, path, changeset)?;
+        for (old_group, new_group) in groups.values() {
+            self.compare_element_groups(old_group, new_group, path, critical, changeset)?;
+        }
         
         Ok(())
     }
     
-    /// Group elements by their semantic identity (name + key attributes)
-    fn group_elements_by_identity<'a>(&self, elements: &[&'a Element]) -> IndexMap<String, Vec<&'a Element>> {
-        let mut groups = IndexMap::new();
+    /// Align same-named siblings and compare each aligned pair
+    fn compare_element_groups(
+        &self,
+        old: &[&Element],
+        new: &[&Element],
+        path: &DiffPath,
+        critical: bool,
+        changeset: &mut ChangeSet,
+    ) -> Result<(), BuildError> {
+        let alignment = self.align_elements(old, new);
         
-        for element in elements {
-            let identity = self.get_element_identity(element);
-            groups.entry(identity).or_insert_with(Vec::new).push(*element);
+        for &index in &alignment.removed {
+            self.report_unmatched(old[index], path, critical, ChangeType::ElementRemoved, changeset);
         }
         
-        groups
-    }
-    
-    /// Get semantic identity key for an element
-    fn get_element_identity(&self, element: &Element) -> String {
-        // Use element name and key identifying attributes
-        let mut identity = element.name.clone();
-        
-        // Add key attributes that identify this element uniquely
-        let key_attrs = match element.name.as_str() {
-            "Release" => vec!["ReleaseId", "ReleaseReference"],
-            "SoundRecording" | "VideoRecording" => vec!["ResourceId", "ResourceReference"],
-            "Deal" => vec!["DealReference"],
-            "Party" => vec!["PartyId", "PartyReference"],
-            _ => vec!["Id", "Reference"], // Generic fallback
-        };
+        for &index in &alignment.added {
+            self.report_unmatched(new[index], path, critical, ChangeType::ElementAdded, changeset);
+        }
         
-        for attr in key_attrs {
-            if let Some(value) = element.attributes.get(attr) {
-                identity.push_str(&format!(":{}", value));
-                break; // Use first found key attribute
+        for &(old_index, new_index) in &alignment.matched {
+            let element = old[old_index];
+            let label = self.element_label(element);
+            let child_path = self.element_path(path, element, label.as_deref());
+            if self.should_ignore_path(&child_path) {
+                continue;
             }
+            self.compare_elements(element, new[new_index], child_path, critical, changeset)?;
         }
         
-        identity
+        Ok(())
     }
     
-    /// Compare groups of elements
-    fn compare_element_groups(
+    /// Report an element present on only one side of the diff
+    ///
+    /// Entity lists such as `DealList` are reported per entity, so that a
+    /// newly added list still yields one change per release, resource or deal.
+    fn report_unmatched(
         &self,
-        old_groups: &IndexMap<String, Vec<&Element>>,
-        new_groups: &IndexMap<String, Vec<&Element>>,
+        element: &Element,
         path: &DiffPath,
+        critical: bool,
+        change_type: ChangeType,
         changeset: &mut ChangeSet,
-    ) -> Result<(), BuildError> {
-        let old_keys: IndexSet<_> = old_groups.keys().collect();
-        let new_keys: IndexSet<_> = new_groups.keys().collect();
+    ) {
+        let mut entities = child_elements(element).peekable();
+        if element.name.ends_with("List") && entities.peek().is_some() {
+            let list_path = path.with_element(&element.name);
+            if child_elements(element).all(|e| entity_identifiers(&e.name).is_some()) {
+                for entity in entities {
+                    self.report_unmatched(entity, &list_path, critical, change_type, changeset);
+                }
+                return;
+            }
+        }
         
-        // Removed element groups
-        for &key in old_keys.difference(&new_keys) {
-            for element in &old_groups[key] {
-                changeset.add_change(SemanticChange {
-                    path: path.with_element(&element.name),
-                    change_type: ChangeType::ElementRemoved,
-                    old_value: Some(self.element_to_string(element)),
-                    new_value: None,
-                    is_critical: self.is_critical_field(&element.name),
-                    description: format!("Element '{}' removed", element.name),
-                });
+        let label = self.element_label(element);
+        let value = Some(self.element_to_string(element));
+        let (old_value, new_value, verb) = match change_type {
+            ChangeType::ElementRemoved => (value, None, "removed"),
+            _ => (None, value, "added"),
+        };
+        self.record(changeset, SemanticChange {
+            path: self.element_path(path, element, label.as_deref()),
+            change_type,
+            old_value,
+            new_value,
+            is_critical: critical || self.is_critical_field(&element.name) || is_entity(&element.name),
+            description: match &label {
+                Some(label) => format!("{} '{}' {}", element.name, label, verb),
+                None => format!("Element '{}' {}", element.name, verb),
+            },
+        });
+    }
+    
+    /// Pair up old and new siblings that represent the same logical entity
+    ///
+    /// Elements carrying identifiers are matched on stable identifiers
+    /// (ISRC, ICPN, GRid, proprietary IDs) first and on message references
+    /// second, so renumbered references do not break alignment. Elements
+    /// without identifiers are matched by content when order changes are
+    /// ignored, and by position otherwise.
+    fn align_elements(&self, old: &[&Element], new: &[&Element]) -> Alignment {
+        let old_keys: Vec<Vec<IdentityKey>> = old.iter().map(|e| self.identity_keys(e)).collect();
+        let new_keys: Vec<Vec<IdentityKey>> = new.iter().map(|e| self.identity_keys(e)).collect();
+        let mut old_matched = vec![None; old.len()];
+        let mut new_taken = vec![false; new.len()];
+        
+        // Keyed elements: stable identifiers first, then references
+        for use_references in [false, true] {
+            for (i, keys) in old_keys.iter().enumerate() {
+                if old_matched[i].is_some() {
+                    continue;
+                }
+                let candidate = keys
+                    .iter()
+                    .filter(|key| key.is_reference == use_references)
+                    .find_map(|key| {
+                        (0..new.len()).find(|&j| !new_taken[j] && new_keys[j].contains(key))
+                    });
+                if let Some(j) = candidate {
+                    old_matched[i] = Some(j);
+                    new_taken[j] = true;
+                }
             }
         }
         
-        // Added element groups
-        for &key in new_keys.difference(&old_keys) {
-            for element in &new_groups[key] {
-                changeset.add_change(SemanticChange {
-                    path: path.with_element(&element.name),
-                    change_type: ChangeType::ElementAdded,
-                    old_value: None,
-                    new_value: Some(self.element_to_string(element)),
-                    is_critical: self.is_critical_field(&element.name),
-                    description: format!("Element '{}' added", element.name),
-                });
+        // Keyless elements: identical content first when order is insignificant
+        if self.config.ignore_order_changes {
+            let new_prints: Vec<String> = new.iter().map(|e| fingerprint(e)).collect();
+            for (i, element) in old.iter().enumerate() {
+                if old_matched[i].is_some() || !old_keys[i].is_empty() {
+                    continue;
+                }
+                let print = fingerprint(element);
+                if let Some(j) = (0..new.len())
+                    .find(|&j| !new_taken[j] && new_keys[j].is_empty() && new_prints[j] == print)
+                {
+                    old_matched[i] = Some(j);
+                    new_taken[j] = true;
+                }
             }
         }
         
-        // Compare matching element groups
-        for &key in old_keys.intersection(&new_keys) {
-            let old_elements = &old_groups[key];
-            let new_elements = &new_groups[key];
+        // Remaining keyless elements pair up in document order
+        let free_new: Vec<usize> = (0..new.len())
+            .filter(|&j| !new_taken[j] && new_keys[j].is_empty())
+            .collect();
+        let mut free_new = free_new.into_iter();
+        for i in 0..old.len() {
+            if old_matched[i].is_none() && old_keys[i].is_empty() {
+                if let Some(j) = free_new.next() {
+                    old_matched[i] = Some(j);
+                    new_taken[j] = true;
+                }
+            }
+        }
+        
+        let mut alignment = Alignment::default();
+        for (i, matched) in old_matched.into_iter().enumerate() {
+            match matched {
+                Some(j) => alignment.matched.push((i, j)),
+                None => alignment.removed.push(i),
+            }
+        }
+        alignment.added = (0..new.len()).filter(|&j| !new_taken[j]).collect();
+        alignment
+    }
+    
+    /// Identifiers that tie an element to a logical DDEX entity, strongest first
+    fn identity_keys(&self, element: &Element) -> Vec<IdentityKey> {
+        let mut keys = Vec::new();
+        
+        if let Some((stable_ids, reference, _)) = entity_identifiers(&element.name) {
+            // Stable identifiers may sit directly on the entity or inside
+            // an identifier block such as ReleaseId/SoundRecordingId
+            for child in child_elements(element) {
+                if stable_ids.contains(&child.name.as_str()) {
+                    push_key(&mut keys, &child.name, &text_of(child), false);
+                } else if child.name.ends_with("Id") {
+                    let mut nested = child_elements(child).peekable();
+                    if nested.peek().is_none() {
+                        push_key(&mut keys, &child.name, &text_of(child), false);
+                    }
+                    for id in nested {
+                        push_key(&mut keys, &id.name, &text_of(id), false);
+                    }
+                }
+            }
             
-            // For now, compare first element of each group
-            // In a more sophisticated implementation, we'd do optimal matching
-            if let (Some(&old_elem), Some(&new_elem)) = (old_elements.first(), new_elements.first()) {
-                self.compare_elements(old_elem, new_elem, path.with_element(&old_elem.name), changeset)?;
+            for child in child_elements(element).filter(|c| c.name == reference) {
+                push_key(&mut keys, reference, &text_of(child), true);
             }
         }
         
-        Ok(())
+        // Identifying attributes
+        let key_attrs: &[&str] = match element.name.as_str() {
+            "Release" => &["ReleaseId", "ReleaseReference"],
+            "SoundRecording" | "VideoRecording" => &["ResourceId", "ResourceReference"],
+            "Deal" => &["DealReference"],
+            "Party" => &["PartyId", "PartyReference"],
+            _ => &["Id", "Reference"], // Generic fallback
+        };
+        for &attr in key_attrs {
+            if let Some(value) = element.attributes.get(attr) {
+                push_key(&mut keys, &format!("@{}", attr), value, self.is_reference_field(attr));
+            }
+        }
+        
+        keys
+    }
+    
+    /// Human-readable label for an identified element, preferring its reference
+    fn element_label(&self, element: &Element) -> Option<String> {
+        let keys = self.identity_keys(element);
+        keys.iter()
+            .find(|key| key.is_reference)
+            .or_else(|| keys.first())
+            .map(|key| key.value.clone())
+    }
+    
+    /// Path for a child element, tagging identified entities with their label
+    fn element_path(&self, parent: &DiffPath, element: &Element, label: Option<&str>) -> DiffPath {
+        match label {
+            Some(label) => parent.with_element(&format!("{}[{}]", element.name, label)),
+            None => parent.with_element(&element.name),
+        }
     }
     
     /// Extract text content from nodes, ignoring formatting
@@ -418,9 +599,17 @@ impl DiffEngine {
     
     /// Check if two elements are semantically equal
     fn elements_semantically_equal(&self, old: &Element, new: &Element) -> bool {
-        // This is a simplified check - in practice, you'd want recursive comparison
-        // excluding the reference IDs themselves
-        old.name == new.name &&
+        if old.name != new.name {
+            return false;
+        }
+        
+        // Entities with stable identifiers are the same if any identifier matches
+        let old_ids: Vec<_> = self.identity_keys(old).into_iter().filter(|k| !k.is_reference).collect();
+        let new_ids: Vec<_> = self.identity_keys(new).into_iter().filter(|k| !k.is_reference).collect();
+        if !old_ids.is_empty() && !new_ids.is_empty() {
+            return old_ids.iter().any(|key| new_ids.contains(key));
+        }
+        
         self.text_content_equal(&old.children, &new.children)
     }
     
@@ -457,7 +646,13 @@ impl DiffEngine {
             }
         }
         
-        None
+        // Entities declare their reference as a child element in DDEX XML
+        match entity_identifiers(&element.name) {
+            Some((_, reference, true)) => child_elements(element)
+                .find(|c| c.name == reference)
+                .map(text_of),
+            _ => None,
+        }
     }
     
     /// Check if a field should be ignored during comparison
@@ -465,6 +660,46 @@ impl DiffEngine {
         self.config.ignored_fields.contains(field_name)
     }
     
+    /// Check if an element is ignored by name or namespace
+    fn should_ignore_element(&self, element: &Element, side: Side) -> bool {
+        self.should_ignore_field(&element.name)
+            || element.namespace.as_ref().is_some_and(|prefix| {
+                self.ignored_prefixes[side as usize].contains(prefix)
+            })
+    }
+    
+    /// Check if a prefixed attribute belongs to an ignored namespace
+    fn in_ignored_namespace(&self, attribute: &str, side: Side) -> bool {
+        attribute.split_once(':').is_some_and(|(prefix, _)| {
+            self.ignored_prefixes[side as usize].contains(prefix)
+        })
+    }
+    
+    /// Check if a path falls under one of the ignored path patterns
+    fn should_ignore_path(&self, path: &DiffPath) -> bool {
+        self.ignored_paths.iter().any(|pattern| pattern.matches(path))
+    }
+    
+    /// Prefixes bound in a document to namespaces that are ignored
+    fn ignored_prefixes_for(&self, ast: &AST) -> IndexSet<String> {
+        let ignored = &self.config.ignored_namespaces;
+        let mut prefixes: IndexSet<String> = ignored.iter()
+            .filter(|ns| !ns.contains(':'))
+            .cloned()
+            .collect();
+        prefixes.extend(ast.namespaces.iter()
+            .filter(|(prefix, uri)| !prefix.is_empty() && ignored.contains(uri.as_str()))
+            .map(|(prefix, _)| prefix.clone()));
+        prefixes
+    }
+    
+    /// Add a change unless its path is ignored
+    fn record(&self, changeset: &mut ChangeSet, change: SemanticChange) {
+        if !self.should_ignore_path(&change.path) {
+            changeset.add_change(change);
+        }
+    }
+    
     /// Check if a field is business-critical
     fn is_critical_field(&self, field_name: &str) -> bool {
         self.config.critical_fields.contains(field_name)
@@ -472,8 +707,9 @@ impl DiffEngine {
     
     /// Convert element to string representation
     fn element_to_string(&self, element: &Element) -> String {
-        // Simplified string representation - in practice you'd want proper XML serialization
-        format!("<{}>", element.name)
+        let mut output = String::new();
+        write_compact(element, &mut output);
+        output
     }
     
     /// Analyze changes for business impact
@@ -504,6 +740,124 @@ impl Default for DiffEngine {
     }
 }
 
+/// Result of aligning two lists of sibling elements
+#[derive(Debug, Default)]
+struct Alignment {
+    /// Pairs of (old index, new index) representing the same entity
+    matched: Vec<(usize, usize)>,
+    /// Old elements with no counterpart
+    removed: Vec<usize>,
+    /// New elements with no counterpart
+    added: Vec<usize>,
+}
+
+/// A single identifier used to align elements across documents
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct IdentityKey {
+    kind: String,
+    value: String,
+    /// Message-local references are weaker than stable identifiers
+    is_reference: bool,
+}
+
+fn push_key(keys: &mut Vec<IdentityKey>, kind: &str, value: &str, is_reference: bool) {
+    let value = value.trim();
+    if !value.is_empty() {
+        keys.push(IdentityKey {
+            kind: kind.to_string(),
+            value: value.to_string(),
+            is_reference,
+        });
+    }
+}
+
+/// Stable identifiers and reference element for DDEX entity types
+///
+/// The flag is true when the reference element declares the entity rather
+/// than pointing at another one.
+fn entity_identifiers(name: &str) -> Option<(&'static [&'static str], &'static str, bool)> {
+    match name {
+        "Release" => Some((
+            &["ICPN", "UPC", "EAN", "GRid", "CatalogNumber", "ProprietaryId"],
+            "ReleaseReference",
+            true,
+        )),
+        "SoundRecording" | "Video" | "VideoRecording" | "Image" | "Text" | "SheetMusic"
+        | "Software" => Some((
+            &["ISRC", "ISAN", "VISAN", "ISBN", "ISMN", "ISWC", "ProprietaryId"],
+            "ResourceReference",
+            true,
+        )),
+        "ReleaseDeal" => Some((&[], "DealReleaseReference", false)),
+        "Deal" => Some((&[], "DealReference", true)),
+        "Party" => Some((&["ISNI", "IPI", "DPID", "ProprietaryId"], "PartyReference", true)),
+        _ => None,
+    }
+}
+
+/// Whether adding or removing this element changes what is being distributed
+fn is_entity(name: &str) -> bool {
+    name != "Party" && entity_identifiers(name).is_some()
+}
+
+fn child_elements(element: &Element) -> impl Iterator<Item = &Element> {
+    element.children.iter().filter_map(|n| match n {
+        Node::Element(e) => Some(e),
+        _ => None,
+    })
+}
+
+fn text_of(element: &Element) -> String {
+    element.children.iter()
+        .filter_map(|n| match n {
+            Node::Text(t) => Some(t.trim()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Content fingerprint used to match identical keyless elements
+fn fingerprint(element: &Element) -> String {
+    let mut output = String::new();
+    write_compact(element, &mut output);
+    output
+}
+
+/// Serialize an element as compact XML without comments
+fn write_compact(element: &Element, output: &mut String) {
+    output.push('<');
+    output.push_str(&element.name);
+    for (key, value) in &element.attributes {
+        output.push_str(&format!(" {}=\"{}\"", key, escape_xml(value)));
+    }
+    
+    let children: Vec<&Node> = element.children.iter()
+        .filter(|n| matches!(n, Node::Element(_)) || matches!(n, Node::Text(t) if !t.trim().is_empty()))
+        .collect();
+    if children.is_empty() {
+        output.push_str("/>");
+        return;
+    }
+    
+    output.push('>');
+    for child in children {
+        match child {
+            Node::Element(e) => write_compact(e, output),
+            Node::Text(t) => output.push_str(&escape_xml(t.trim())),
+            _ => {}
+        }
+    }
+    output.push_str(&format!("</{}>", element.name));
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
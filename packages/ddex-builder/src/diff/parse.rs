@@ -0,0 +1,172 @@
+//! XML to AST parsing for semantic diffing
+//!
+//! The diff engine compares DDEX messages structurally, so both inputs are
+//! read into the builder's [`AST`] first. Element names are reduced to their
+//! local part so that `ern:Release` and `Release` compare equal, and namespace
+//! declarations are lifted onto the AST instead of being diffed as attributes.
+
+use crate::ast::{AST, Element};
+use crate::error::BuildError;
+use indexmap::IndexMap;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+/// Parse a DDEX XML document into an AST suitable for [`super::DiffEngine`]
+pub fn parse_xml(xml: &str) -> Result<AST, BuildError> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut namespaces = IndexMap::new();
+    let mut schema_location = None;
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root = None;
+    let mut buf = Vec::new();
+
+    loop {
+        let event = reader
+            .read_event_into(&mut buf)
+            .map_err(|e| parse_error(format!("{} at position {}", e, reader.buffer_position())))?;
+
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let is_root = stack.is_empty() && root.is_none();
+                let element = read_element(e, is_root, &mut namespaces, &mut schema_location)?;
+
+                if matches!(event, Event::Start(_)) {
+                    stack.push(element);
+                } else if let Some(parent) = stack.last_mut() {
+                    parent.add_child(element);
+                } else {
+                    root = Some(element);
+                }
+            }
+            Event::End(_) => {
+                let element = stack
+                    .pop()
+                    .ok_or_else(|| parse_error("Unbalanced end tag".to_string()))?;
+                match stack.last_mut() {
+                    Some(parent) => parent.add_child(element),
+                    None => root = Some(element),
+                }
+            }
+            Event::Text(ref e) => {
+                let text = e
+                    .unescape()
+                    .map_err(|e| parse_error(format!("Text unescape error: {}", e)))?;
+                if let Some(parent) = stack.last_mut() {
+                    parent.add_text(text.to_string());
+                }
+            }
+            Event::CData(ref e) => {
+                if let Some(parent) = stack.last_mut() {
+                    parent.add_text(String::from_utf8_lossy(e).to_string());
+                }
+            }
+            Event::Eof => break,
+            // Comments, processing instructions and the declaration carry no
+            // business meaning for a semantic diff
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if !stack.is_empty() {
+        return Err(parse_error(format!("Unclosed element '{}'", stack[stack.len() - 1].name)));
+    }
+
+    let root = root.ok_or_else(|| parse_error("No root element found".to_string()))?;
+
+    Ok(AST {
+        root,
+        namespaces,
+        schema_location,
+    })
+}
+
+/// Build an element from a start tag, stripping namespace declarations
+fn read_element(
+    start: &BytesStart<'_>,
+    is_root: bool,
+    namespaces: &mut IndexMap<String, String>,
+    schema_location: &mut Option<String>,
+) -> Result<Element, BuildError> {
+    let qname = String::from_utf8_lossy(start.name().as_ref()).to_string();
+    let mut element = match qname.split_once(':') {
+        Some((prefix, local)) => {
+            let mut element = Element::new(local);
+            element.namespace = Some(prefix.to_string());
+            element
+        }
+        None => Element::new(qname),
+    };
+
+    for attr in start.attributes() {
+        let attr = attr.map_err(|e| parse_error(format!("Attribute error: {}", e)))?;
+        let key = String::from_utf8_lossy(attr.key.as_ref()).to_string();
+        let value = attr
+            .unescape_value()
+            .map_err(|e| parse_error(format!("Attribute error: {}", e)))?
+            .to_string();
+
+        if key == "xmlns" || key.starts_with("xmlns:") {
+            // Only root declarations are recorded; nested ones are formatting
+            if is_root {
+                let prefix = key.strip_prefix("xmlns:").unwrap_or("");
+                namespaces.insert(prefix.to_string(), value);
+            }
+        } else if key.ends_with(":schemaLocation") {
+            if is_root {
+                *schema_location = Some(value);
+            }
+        } else {
+            element.attributes.insert(key, value);
+        }
+    }
+
+    Ok(element)
+}
+
+fn parse_error(message: String) -> BuildError {
+    BuildError::InvalidFormat {
+        field: "xml".to_string(),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Node;
+
+    #[test]
+    fn test_parse_strips_prefixes_and_namespaces() {
+        let xml = r#"<?xml version="1.0"?>
+<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43"
+    xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"
+    xsi:schemaLocation="http://ddex.net/xml/ern/43 release-notification.xsd"
+    MessageSchemaVersionId="ern/43">
+  <!-- ignored -->
+  <ReleaseList><Release><Title>A &amp; B</Title></Release></ReleaseList>
+</ern:NewReleaseMessage>"#;
+
+        let ast = parse_xml(xml).unwrap();
+        assert_eq!(ast.root.name, "NewReleaseMessage");
+        assert_eq!(ast.root.namespace.as_deref(), Some("ern"));
+        assert_eq!(ast.namespaces.get("ern").unwrap(), "http://ddex.net/xml/ern/43");
+        assert!(ast.schema_location.is_some());
+        assert_eq!(ast.root.attributes.len(), 1);
+        assert_eq!(ast.root.children.len(), 1);
+
+        let Node::Element(release_list) = &ast.root.children[0] else { panic!("expected element") };
+        let Node::Element(release) = &release_list.children[0] else { panic!("expected element") };
+        let Node::Element(title) = &release.children[0] else { panic!("expected element") };
+        assert!(matches!(&title.children[0], Node::Text(t) if t == "A & B"));
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_xml() {
+        assert!(parse_xml("<Release><Title>x</Release>").is_err());
+        assert!(parse_xml("<Release>").is_err());
+        assert!(parse_xml("").is_err());
+    }
+}
@@ -0,0 +1,457 @@
+//! Semantic patches: replaying a diff against other documents
+//!
+//! A [`Patch`] is the serializable form of a [`ChangeSet`]. Each operation
+//! carries the value it expects to find as well as the value to write, so a
+//! patch computed on one message can be replayed on others deterministically:
+//!
+//! - labelled path segments such as `Release[R1]` select that entity, while
+//!   unlabelled segments fan out to every same-named sibling
+//! - an operation applies wherever the current value equals its old value,
+//!   counts as already applied where it equals the new value, and conflicts
+//!   when neither is found
+//!
+//! Patched documents are re-serialized from the diff AST, so comments and
+//! formatting of the target are not preserved.
+
+use super::types::{ChangeSet, ChangeType, DiffPath, PathSegment};
+use super::{child_elements, fingerprint, parse_xml, text_of, DiffEngine};
+use crate::ast::{Element, Node};
+use crate::determinism::DeterminismConfig;
+use crate::error::BuildError;
+use crate::generator::xml_writer::XmlWriter;
+use serde::{Deserialize, Serialize};
+
+/// A replayable list of semantic changes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Patch {
+    /// Operations, applied in order
+    pub operations: Vec<PatchOperation>,
+}
+
+/// A single guarded change
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatchOperation {
+    /// Location of the change
+    pub path: DiffPath,
+    /// Kind of change
+    pub change_type: ChangeType,
+    /// Value expected before the change (compact XML for elements)
+    pub old_value: Option<String>,
+    /// Value after the change (compact XML for elements)
+    pub new_value: Option<String>,
+}
+
+/// How to apply a patch
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PatchOptions {
+    /// Only report what would change; never produce output
+    pub dry_run: bool,
+    /// Produce output even if some operations conflict
+    pub allow_conflicts: bool,
+}
+
+/// Result of applying a patch
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PatchOutcome {
+    /// Patched document; `None` for dry runs and for conflicting patches
+    /// unless [`PatchOptions::allow_conflicts`] is set
+    pub xml: Option<String>,
+    /// Number of places an operation changed
+    pub applied: usize,
+    /// Operations whose new value was already present
+    pub already_applied: usize,
+    /// Operations that could not be applied
+    pub conflicts: Vec<PatchConflict>,
+}
+
+/// An operation whose expected value was not found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchConflict {
+    /// Index of the operation in the patch
+    pub operation: usize,
+    /// Path of the operation
+    pub path: DiffPath,
+    /// What was found instead
+    pub message: String,
+}
+
+impl PatchOutcome {
+    /// Whether every operation applied or was already applied
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+impl Patch {
+    /// Build a patch from the changes of a diff
+    pub fn from_changeset(changeset: &ChangeSet) -> Self {
+        Self {
+            operations: changeset.changes.iter()
+                .map(|change| PatchOperation {
+                    path: change.path.clone(),
+                    change_type: change.change_type,
+                    old_value: change.old_value.clone(),
+                    new_value: change.new_value.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// What happened to one operation
+enum OperationResult {
+    Applied(usize),
+    AlreadyApplied,
+    Conflict(String),
+}
+
+/// Position of an element as child indexes from the root
+type ElementAddress = Vec<usize>;
+
+impl DiffEngine {
+    /// Apply a patch to an XML document
+    pub fn apply_patch(&self, xml: &str, patch: &Patch) -> Result<PatchOutcome, BuildError> {
+        self.apply_patch_with_options(xml, patch, PatchOptions::default())
+    }
+
+    /// Apply a patch to an XML document with dry-run and conflict options
+    pub fn apply_patch_with_options(
+        &self,
+        xml: &str,
+        patch: &Patch,
+        options: PatchOptions,
+    ) -> Result<PatchOutcome, BuildError> {
+        let mut ast = parse_xml(xml)?;
+        let mut outcome = PatchOutcome::default();
+
+        for (index, operation) in patch.operations.iter().enumerate() {
+            match self.apply_operation(&mut ast.root, operation)? {
+                OperationResult::Applied(count) => outcome.applied += count,
+                OperationResult::AlreadyApplied => outcome.already_applied += 1,
+                OperationResult::Conflict(message) => outcome.conflicts.push(PatchConflict {
+                    operation: index,
+                    path: operation.path.clone(),
+                    message,
+                }),
+            }
+        }
+
+        if !options.dry_run && (outcome.is_clean() || options.allow_conflicts) {
+            outcome.xml = Some(XmlWriter::new(DeterminismConfig::default()).write(&ast)?);
+        }
+        Ok(outcome)
+    }
+
+    fn apply_operation(&self, root: &mut Element, operation: &PatchOperation) -> Result<OperationResult, BuildError> {
+        let segments = &operation.path.segments;
+        let (last, parent_path) = match segments.split_last() {
+            Some(split) => split,
+            None => return Ok(OperationResult::Conflict("Cannot patch the root element".to_string())),
+        };
+
+        let result = match (operation.change_type, last) {
+            (ChangeType::TextModified, PathSegment::Text) => {
+                let targets = self.resolve(root, parent_path);
+                self.apply_value(root, &targets, operation, |element| {
+                    let text = text_of(element);
+                    (!text.is_empty()).then_some(text)
+                }, |element, value| {
+                    element.children.retain(|node| !matches!(node, Node::Text(_)));
+                    if let Some(value) = value {
+                        element.children.insert(0, Node::Text(value.to_string()));
+                    }
+                })
+            }
+            (
+                ChangeType::AttributeAdded | ChangeType::AttributeRemoved | ChangeType::AttributeModified,
+                PathSegment::Attribute(name),
+            ) => {
+                let targets = self.resolve(root, parent_path);
+                self.apply_value(root, &targets, operation, |element| {
+                    element.attributes.get(name).cloned()
+                }, |element, value| match value {
+                    Some(value) => {
+                        element.attributes.insert(name.clone(), value.to_string());
+                    }
+                    None => {
+                        element.attributes.shift_remove(name);
+                    }
+                })
+            }
+            (ChangeType::ElementAdded, PathSegment::Element(_)) => {
+                let fragment = match &operation.new_value {
+                    Some(xml) => parse_xml(xml)?.root,
+                    None => return Ok(OperationResult::Conflict("Added element has no content".to_string())),
+                };
+                self.add_element(root, parent_path, last, fragment)
+            }
+            (ChangeType::ElementRemoved, PathSegment::Element(_)) => {
+                self.remove_element(root, segments, operation.old_value.as_deref().unwrap_or_default())
+            }
+            (ChangeType::ElementRenamed, PathSegment::Element(_)) => {
+                let (Some(old), Some(new)) = (&operation.old_value, &operation.new_value) else {
+                    return Ok(OperationResult::Conflict("Rename needs old and new names".to_string()));
+                };
+                let targets = self.resolve(root, segments);
+                if targets.is_empty() {
+                    OperationResult::Conflict(format!("No '{}' element found", old))
+                } else {
+                    let mut count = 0;
+                    for address in &targets {
+                        let element = element_at(root, address);
+                        if &element.name == old {
+                            element.name = new.clone();
+                            count += 1;
+                        }
+                    }
+                    match count {
+                        0 => OperationResult::AlreadyApplied,
+                        n => OperationResult::Applied(n),
+                    }
+                }
+            }
+            (change_type, _) => OperationResult::Conflict(format!(
+                "{} cannot be applied at {}",
+                change_type, operation.path
+            )),
+        };
+        Ok(result)
+    }
+
+    /// Apply a guarded value change to every target
+    fn apply_value(
+        &self,
+        root: &mut Element,
+        targets: &[ElementAddress],
+        operation: &PatchOperation,
+        read: impl Fn(&Element) -> Option<String>,
+        write: impl Fn(&mut Element, Option<&str>),
+    ) -> OperationResult {
+        if targets.is_empty() {
+            return OperationResult::Conflict("Path not found".to_string());
+        }
+
+        let normalize = |value: &Option<String>| {
+            value.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string)
+        };
+        let (old, new) = (normalize(&operation.old_value), normalize(&operation.new_value));
+
+        let mut applied = 0;
+        let mut already_applied = false;
+        let mut found = Vec::new();
+        for address in targets {
+            let element = element_at(root, address);
+            let current = normalize(&read(element));
+            if current == new {
+                already_applied = true;
+            } else if current == old {
+                write(element, new.as_deref());
+                applied += 1;
+            } else {
+                found.push(current.unwrap_or_default());
+            }
+        }
+
+        if applied > 0 {
+            OperationResult::Applied(applied)
+        } else if already_applied {
+            OperationResult::AlreadyApplied
+        } else {
+            OperationResult::Conflict(format!(
+                "Expected '{}', found '{}'",
+                old.unwrap_or_default(),
+                found.join("', '")
+            ))
+        }
+    }
+
+    /// Insert an element into every parent that does not have it yet
+    fn add_element(
+        &self,
+        root: &mut Element,
+        parent_path: &[PathSegment],
+        segment: &PathSegment,
+        fragment: Element,
+    ) -> OperationResult {
+        let parents = self.resolve(root, parent_path);
+        if parents.is_empty() {
+            return OperationResult::Conflict("Parent element not found".to_string());
+        }
+
+        let print = fingerprint(&fragment);
+        let mut applied = 0;
+        for address in &parents {
+            let parent = element_at(root, address);
+            let present = child_elements(parent).any(|child| match segment_label(segment) {
+                Some(_) => self.segment_matches(segment, child),
+                None => fingerprint(child) == print,
+            });
+            if present {
+                continue;
+            }
+
+            // Keep same-named siblings together so schema order is preserved
+            let position = parent.children.iter()
+                .rposition(|node| matches!(node, Node::Element(e) if e.name == fragment.name))
+                .map_or(parent.children.len(), |i| i + 1);
+            parent.children.insert(position, Node::Element(fragment.clone()));
+            applied += 1;
+        }
+
+        match applied {
+            0 => OperationResult::AlreadyApplied,
+            n => OperationResult::Applied(n),
+        }
+    }
+
+    /// Remove every target whose content matches the removed element
+    fn remove_element(&self, root: &mut Element, path: &[PathSegment], expected: &str) -> OperationResult {
+        let targets = self.resolve(root, path);
+        if targets.is_empty() {
+            return OperationResult::AlreadyApplied;
+        }
+
+        let matching: Vec<&ElementAddress> = targets.iter()
+            .filter(|address| fingerprint(element_at(root, address)) == expected)
+            .collect();
+        if matching.is_empty() {
+            return OperationResult::Conflict("Element content differs from the removed element".to_string());
+        }
+
+        // Remove from the back so earlier addresses stay valid
+        for address in matching.iter().rev() {
+            let (index, parent) = address.split_last().expect("root is never removed");
+            element_at(root, parent).children.remove(*index);
+        }
+        OperationResult::Applied(matching.len())
+    }
+
+    /// Addresses of every element matching the path, in document order
+    fn resolve(&self, root: &Element, path: &[PathSegment]) -> Vec<ElementAddress> {
+        let mut addresses = Vec::new();
+        self.collect_matches(root, path, &mut Vec::new(), &mut addresses);
+        addresses
+    }
+
+    fn collect_matches(
+        &self,
+        element: &Element,
+        path: &[PathSegment],
+        address: &mut ElementAddress,
+        matches: &mut Vec<ElementAddress>,
+    ) {
+        let Some((segment, rest)) = path.split_first() else {
+            matches.push(address.clone());
+            return;
+        };
+        for (index, node) in element.children.iter().enumerate() {
+            if let Node::Element(child) = node {
+                if self.segment_matches(segment, child) {
+                    address.push(index);
+                    self.collect_matches(child, rest, address, matches);
+                    address.pop();
+                }
+            }
+        }
+    }
+
+    /// Whether an element matches a path segment such as `Release[R1]`
+    fn segment_matches(&self, segment: &PathSegment, element: &Element) -> bool {
+        let PathSegment::Element(name) = segment else {
+            return false;
+        };
+        match name.split_once('[') {
+            Some((base, _)) => {
+                base == element.name && self.element_label(element).as_deref() == segment_label(segment)
+            }
+            None => *name == element.name,
+        }
+    }
+}
+
+fn segment_label(segment: &PathSegment) -> Option<&str> {
+    match segment {
+        PathSegment::Element(name) => name
+            .split_once('[')
+            .map(|(_, label)| label.strip_suffix(']').unwrap_or(label)),
+        _ => None,
+    }
+}
+
+fn element_at<'a>(root: &'a mut Element, address: &[usize]) -> &'a mut Element {
+    address.iter().fold(root, |element, &index| match &mut element.children[index] {
+        Node::Element(child) => child,
+        _ => unreachable!("addresses only point at elements"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGINAL: &str = r#"<NewReleaseMessage>
+        <ReleaseList><Release><ReleaseReference>R1</ReleaseReference><Title>A</Title></Release></ReleaseList>
+        <DealList><ReleaseDeal>
+            <Deal><Territory>US</Territory><Price>9.99</Price></Deal>
+            <Deal><Territory>GB</Territory><Price>9.99</Price></Deal>
+        </ReleaseDeal></DealList>
+    </NewReleaseMessage>"#;
+
+    #[test]
+    fn test_patch_replays_guarded_changes() {
+        let updated = ORIGINAL
+            .replace("<Title>A</Title>", "<Title>B</Title><Genre>Pop</Genre>")
+            .replace("9.99", "7.99");
+        let mut engine = DiffEngine::new();
+        let patch = engine.diff_xml(ORIGINAL, &updated).unwrap().to_patch();
+        let json = serde_json::to_string(&patch).unwrap();
+        let patch: Patch = serde_json::from_str(&json).unwrap();
+
+        // Another message with one more deal at the old price
+        let other = ORIGINAL.replace(
+            "</ReleaseDeal>",
+            "<Deal><Territory>DE</Territory><Price>9.99</Price></Deal></ReleaseDeal>",
+        );
+        let outcome = engine.apply_patch(&other, &patch).unwrap();
+        assert!(outcome.is_clean(), "{:?}", outcome.conflicts);
+        let patched = outcome.xml.unwrap();
+        assert_eq!(patched.matches("<Price>7.99</Price>").count(), 3);
+        assert!(patched.contains("<Title>B</Title>"));
+        assert!(patched.contains("<Genre>Pop</Genre>"));
+
+        // Replaying is idempotent
+        let again = engine.apply_patch(&patched, &patch).unwrap();
+        assert_eq!(again.applied, 0);
+        assert!(again.is_clean());
+    }
+
+    #[test]
+    fn test_patch_conflicts_and_dry_run() {
+        let patch = Patch {
+            operations: vec![PatchOperation {
+                path: DiffPath::root()
+                    .with_element("ReleaseList")
+                    .with_element("Release[R1]")
+                    .with_element("Title")
+                    .with_text(),
+                change_type: ChangeType::TextModified,
+                old_value: Some("Z".to_string()),
+                new_value: Some("B".to_string()),
+            }],
+        };
+        let engine = DiffEngine::new();
+
+        let outcome = engine.apply_patch(ORIGINAL, &patch).unwrap();
+        assert_eq!(outcome.xml, None);
+        assert_eq!(outcome.conflicts.len(), 1);
+        assert_eq!(outcome.conflicts[0].message, "Expected 'Z', found 'A'");
+
+        let options = PatchOptions { dry_run: true, allow_conflicts: true };
+        let outcome = engine.apply_patch_with_options(ORIGINAL, &patch, options).unwrap();
+        assert_eq!(outcome.xml, None);
+
+        let options = PatchOptions { dry_run: false, allow_conflicts: true };
+        let outcome = engine.apply_patch_with_options(ORIGINAL, &patch, options).unwrap();
+        assert!(outcome.xml.unwrap().contains("<Title>A</Title>"));
+    }
+}
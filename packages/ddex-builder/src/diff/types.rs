@@ -88,6 +88,11 @@ impl ChangeSet {
         self.changes.iter().filter(|c| c.change_type == change_type).collect()
     }
     
+    /// Convert the changes into a patch that can be applied to other documents
+    pub fn to_patch(&self) -> super::patch::Patch {
+        super::patch::Patch::from_changeset(self)
+    }
+    
     /// Get overall impact level
     pub fn impact_level(&self) -> ImpactLevel {
         if self.summary.critical_changes > 0 {
@@ -130,6 +135,22 @@ pub struct SemanticChange {
     pub description: String,
 }
 
+impl SemanticChange {
+    /// Get the impact level of this individual change
+    pub fn impact_level(&self) -> ImpactLevel {
+        if self.is_critical {
+            return ImpactLevel::High;
+        }
+        
+        match self.change_type {
+            ChangeType::ElementAdded | ChangeType::ElementRemoved | ChangeType::ElementRenamed => {
+                ImpactLevel::Medium
+            }
+            _ => ImpactLevel::Low,
+        }
+    }
+}
+
 /// Path to a specific location in a DDEX document
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct DiffPath {
@@ -315,7 +336,7 @@ impl ChangeSummary {
 }
 
 /// Impact level of changes
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum ImpactLevel {
     /// No changes
     None,
@@ -1,5 +1,23 @@
 //! Error types for DDEX Builder
+//!
+//! Every [`BuildError`] has a stable code of the form `DDEX-B-CCNN`, where
+//! `CC` is the category and `NN` numbers the error within it:
+//!
+//! | Category | Errors |
+//! |----------|--------|
+//! | `01` XML | `XmlGeneration` |
+//! | `02` validation | `ValidationFailed`, `Validation`, `MissingRequired`, `FailedOnWarning` |
+//! | `03` references | `InvalidReference` |
+//! | `04` input | `InvalidFormat`, `Serialization`, `InputSanitization`, `InvalidSpreadsheet` |
+//! | `06` I/O | `Io`, `Delivery`, `Cancelled` |
+//! | `07` security | `Security`, `MemoryLimitExceeded` |
+//! | `08` determinism | `DeterminismFailed`, `DeterminismGuaranteeViolated` |
+//! | `09` internal | `Parallel`, `Other` |
+//!
+//! Codes never change meaning once released, so callers in any language can
+//! branch on them; convert to [`FFIError`] to hand them over a binding.
 
+use ddex_core::ffi::{FFIError, FFIErrorCategory, FFIErrorSeverity};
 use thiserror::Error;
 use serde::{Serialize, Deserialize};
 
@@ -31,10 +49,38 @@ pub enum BuildError {
         errors: Vec<String>,
     },
     
+    /// Warnings or normalizations under `fail_on_warning`
+    #[error("Failed on warning: {}", warnings.join(", "))]
+    FailedOnWarning {
+        warnings: Vec<String>,
+    },
+    
     /// IO error
     #[error("IO error: {0}")]
     Io(String),
     
+    /// A release package that could not be sent to a DSP
+    #[error("Delivery failed: {message}")]
+    Delivery {
+        /// The step that failed and the server's or OS's reason
+        message: String,
+    },
+    
+    /// `BuildOptions::cancellation` stopped the build before it finished
+    #[error("Build {reason} before {stage} after {elapsed_ms} ms")]
+    Cancelled {
+        reason: ddex_core::cancel::CancelReason,
+        /// The stage that was about to start
+        stage: String,
+        elapsed_ms: u64,
+    },
+    
+    /// Cells of a spreadsheet import that could not be used
+    #[error("Invalid spreadsheet: {}", errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))]
+    InvalidSpreadsheet {
+        errors: Vec<crate::spreadsheet::CellError>,
+    },
+    
     /// Serialization error
     #[error("Serialization error: {0}")]
     Serialization(String),
@@ -68,6 +114,13 @@ pub enum BuildError {
     #[error("Security violation: {0}")]
     Security(String),
     
+    /// The build would have held more than `BuildOptions::max_memory`
+    #[error("Memory limit exceeded: {requested} bytes needed, {limit} allowed")]
+    MemoryLimitExceeded {
+        limit: usize,
+        requested: usize,
+    },
+    
     /// Input sanitization failed
     #[error("Input sanitization failed: {0}")]
     InputSanitization(String),
@@ -77,12 +130,115 @@ pub enum BuildError {
     Other(String),
 }
 
+impl BuildError {
+    /// Stable error code, e.g. `DDEX-B-0401`
+    pub fn code(&self) -> &'static str {
+        match self {
+            BuildError::XmlGeneration(_) => "DDEX-B-0101",
+            BuildError::ValidationFailed { .. } => "DDEX-B-0201",
+            BuildError::Validation(_) => "DDEX-B-0202",
+            BuildError::MissingRequired { .. } => "DDEX-B-0203",
+            BuildError::FailedOnWarning { .. } => "DDEX-B-0204",
+            BuildError::InvalidReference { .. } => "DDEX-B-0301",
+            BuildError::InvalidFormat { .. } => "DDEX-B-0401",
+            BuildError::Serialization(_) => "DDEX-B-0402",
+            BuildError::InputSanitization(_) => "DDEX-B-0403",
+            BuildError::InvalidSpreadsheet { .. } => "DDEX-B-0404",
+            BuildError::Io(_) => "DDEX-B-0601",
+            BuildError::Delivery { .. } => "DDEX-B-0602",
+            BuildError::Cancelled { .. } => "DDEX-B-0603",
+            BuildError::Security(_) => "DDEX-B-0701",
+            BuildError::MemoryLimitExceeded { .. } => "DDEX-B-0702",
+            BuildError::DeterminismFailed { .. } => "DDEX-B-0801",
+            BuildError::DeterminismGuaranteeViolated { .. } => "DDEX-B-0802",
+            BuildError::Parallel(_) => "DDEX-B-0901",
+            BuildError::Other(_) => "DDEX-B-0999",
+        }
+    }
+    
+    /// JSON path of the offending request field, or the reference at fault
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            BuildError::InvalidFormat { field, .. } | BuildError::MissingRequired { field } => Some(field),
+            BuildError::InvalidReference { reference } => Some(reference),
+            _ => None,
+        }
+    }
+    
+    /// How to fix the request, if known
+    pub fn suggestion(&self) -> Option<&'static str> {
+        match self {
+            BuildError::MissingRequired { .. } => Some("Add the field to the build request"),
+            BuildError::InvalidReference { .. } => {
+                Some("Reference a release, resource or party defined in the same request")
+            }
+            BuildError::InvalidFormat { .. } | BuildError::Serialization(_) => {
+                Some("Check the value against the BuildRequest schema")
+            }
+            BuildError::ValidationFailed { .. } | BuildError::Validation(_) => {
+                Some("Fix the listed fields, or lower the preflight level to Warn")
+            }
+            BuildError::FailedOnWarning { .. } => {
+                Some("Clean up the listed values, or turn off fail_on_warning")
+            }
+            BuildError::InvalidSpreadsheet { .. } => {
+                Some("Fix the listed cells; the spreadsheet module documents every column")
+            }
+            BuildError::Delivery { .. } => {
+                Some("Check the host, credentials and remote folder, then rerun with the same batch ID to resume")
+            }
+            BuildError::DeterminismFailed { .. } | BuildError::DeterminismGuaranteeViolated { .. } => {
+                Some("Use a deterministic ID strategy and a fixed timestamp source")
+            }
+            BuildError::Security(_) | BuildError::InputSanitization(_) => {
+                Some("Remove the offending content or raise the security limits for trusted input")
+            }
+            BuildError::MemoryLimitExceeded { .. } => {
+                Some("Raise max_memory, or split the request or use the streaming builder")
+            }
+            _ => None,
+        }
+    }
+    
+    fn category(&self) -> FFIErrorCategory {
+        match self {
+            BuildError::XmlGeneration(_) => FFIErrorCategory::XmlParsing,
+            BuildError::InvalidReference { .. } => FFIErrorCategory::Reference,
+            BuildError::Io(_) | BuildError::Delivery { .. } | BuildError::Cancelled { .. } => FFIErrorCategory::Io,
+            BuildError::DeterminismFailed { .. }
+            | BuildError::DeterminismGuaranteeViolated { .. }
+            | BuildError::Parallel(_)
+            | BuildError::Other(_) => FFIErrorCategory::Internal,
+            _ => FFIErrorCategory::Validation,
+        }
+    }
+}
+
+impl From<BuildError> for FFIError {
+    fn from(err: BuildError) -> Self {
+        FFIError {
+            code: err.code().to_string(),
+            message: err.to_string(),
+            location: err.path().map(FFIError::path_location),
+            severity: FFIErrorSeverity::Error,
+            suggestion: err.suggestion().map(str::to_string),
+            category: err.category(),
+        }
+    }
+}
+
 impl From<std::io::Error> for BuildError {
     fn from(err: std::io::Error) -> Self {
         BuildError::Io(err.to_string())
     }
 }
 
+impl From<ddex_core::memory::MemoryLimitExceeded> for BuildError {
+    fn from(err: ddex_core::memory::MemoryLimitExceeded) -> Self {
+        BuildError::MemoryLimitExceeded { limit: err.limit, requested: err.requested }
+    }
+}
+
 impl From<serde_json::Error> for BuildError {
     fn from(err: serde_json::Error) -> Self {
         BuildError::Serialization(err.to_string())
@@ -98,7 +254,86 @@ impl From<quick_xml::Error> for BuildError {
 /// Build warning (non-fatal)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuildWarning {
+    /// Code of the preflight rule that raised it, e.g. `INVALID_ISRC`
     pub code: String,
     pub message: String,
+    /// JSON path of the field concerned
     pub location: Option<String>,
-}
\ No newline at end of file
+    /// How to fix the field, if known
+    #[serde(default)]
+    pub suggestion: Option<String>,
+}
+
+impl From<BuildWarning> for FFIError {
+    fn from(warning: BuildWarning) -> Self {
+        FFIError {
+            code: warning.code,
+            message: warning.message,
+            location: warning.location.map(FFIError::path_location),
+            severity: FFIErrorSeverity::Warning,
+            suggestion: warning.suggestion,
+            category: FFIErrorCategory::Validation,
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_codes_are_unique() {
+        let errors = [
+            BuildError::XmlGeneration(String::new()),
+            BuildError::ValidationFailed { errors: Vec::new() },
+            BuildError::Validation(String::new()),
+            BuildError::MissingRequired { field: String::new() },
+            BuildError::FailedOnWarning { warnings: Vec::new() },
+            BuildError::InvalidReference { reference: String::new() },
+            BuildError::InvalidFormat { field: String::new(), message: String::new() },
+            BuildError::Serialization(String::new()),
+            BuildError::InputSanitization(String::new()),
+            BuildError::InvalidSpreadsheet { errors: Vec::new() },
+            BuildError::Io(String::new()),
+            BuildError::Delivery { message: String::new() },
+            BuildError::Cancelled {
+                reason: ddex_core::cancel::CancelReason::Requested,
+                stage: String::new(),
+                elapsed_ms: 0,
+            },
+            BuildError::Security(String::new()),
+            BuildError::MemoryLimitExceeded { limit: 0, requested: 0 },
+            BuildError::DeterminismFailed { message: String::new() },
+            BuildError::DeterminismGuaranteeViolated { guarantee: String::new(), details: String::new() },
+            BuildError::Parallel(String::new()),
+            BuildError::Other(String::new()),
+        ];
+        let mut codes: Vec<_> = errors.iter().map(BuildError::code).collect();
+        assert!(codes.iter().all(|code| code.starts_with("DDEX-B-") && code.len() == 11));
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), errors.len());
+    }
+
+    #[test]
+    fn test_ffi_error() {
+        let error = BuildError::InvalidFormat {
+            field: "releases[0].tracks[1].isrc".to_string(),
+            message: "expected 12 characters".to_string(),
+        };
+        let ffi_error = FFIError::from(error);
+        assert_eq!(ffi_error.code, "DDEX-B-0401");
+        assert_eq!(ffi_error.location.unwrap().path, "releases[0].tracks[1].isrc");
+        assert_eq!(ffi_error.severity, FFIErrorSeverity::Error);
+        assert!(ffi_error.suggestion.is_some());
+
+        let json = serde_json::to_value(FFIError::from(BuildWarning {
+            code: "INVALID_ISRC".to_string(),
+            message: "Invalid ISRC".to_string(),
+            location: Some("releases[0].tracks[0].isrc".to_string()),
+            suggestion: Some("Use the CC-XXX-YY-NNNNN format".to_string()),
+        }))
+        .unwrap();
+        assert_eq!(json["severity"], "Warning");
+        assert_eq!(json["suggestion"], "Use the CC-XXX-YY-NNNNN format");
+    }
+}
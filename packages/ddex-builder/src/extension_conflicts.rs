@@ -0,0 +1,232 @@
+//! Conflicts between extension namespaces
+//!
+//! Messages assembled from several partners' data, or migrated between
+//! versions of one partner's extension, can carry the same field twice:
+//! `v1:PartnerId` and `v2:PartnerId` on the same release, or two
+//! `TrackMetadata` blocks for one track. Extension elements are taken to carry
+//! the same field when they share a parent and a local name but come from
+//! different namespaces. Namespaces under `http://ddex.net/xml/` and the W3C
+//! ones are DDEX's own and never conflict.
+//!
+//! [`resolve_conflicts`] finds them and applies a [`ConflictPolicy`]:
+//! keep everything, keep only the namespace ranked first, or fail. The
+//! [`ConflictReport`] lists every conflict found either way, and the
+//! message keeps its bytes outside the elements removed.
+//!
+//! ```rust
+//! use ddex_builder::extension_conflicts::{resolve_conflicts, ConflictPolicy};
+//!
+//! let xml = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43"
+//!     xmlns:v1="http://partner.com/ddex/v1" xmlns:v2="http://partner.com/ddex/v2">
+//!   <ReleaseList>
+//!     <Release>
+//!       <v1:PartnerId>PARTNER_123</v1:PartnerId>
+//!       <v2:PartnerId>PARTNER_123_V2</v2:PartnerId>
+//!     </Release>
+//!   </ReleaseList>
+//! </ern:NewReleaseMessage>"#;
+//!
+//! let policy = ConflictPolicy::PreferNamespaceOrder(vec!["http://partner.com/ddex/v2".to_string()]);
+//! let resolved = resolve_conflicts(xml, &policy)?;
+//! assert_eq!(resolved.report.conflicts[0].field, "PartnerId");
+//! assert!(!resolved.xml.contains("v1:PartnerId"));
+//! # Ok::<(), ddex_builder::BuildError>(())
+//! ```
+
+use crate::error::BuildError;
+use crate::lint::Document;
+use crate::split::apply;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// What to do with extension elements carrying the same field
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// Keep every element and only report the conflict
+    #[default]
+    KeepAll,
+    /// Keep the elements of the namespace that comes first in this list of
+    /// URIs and remove the others; namespaces not listed rank after the
+    /// listed ones, in document order
+    PreferNamespaceOrder(Vec<String>),
+    /// Fail with [`ValidationFailed`](BuildError::ValidationFailed) listing
+    /// every conflict
+    Error,
+}
+
+/// One field carried by several extension namespaces
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtensionConflict {
+    /// Path of the element the fields are children of
+    pub path: String,
+    /// Local name the fields share
+    pub field: String,
+    /// URIs of the namespaces carrying the field, in document order
+    pub namespaces: Vec<String>,
+    /// URIs of the namespaces whose elements were kept
+    pub kept: Vec<String>,
+}
+
+impl fmt::Display for ExtensionConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{} is carried by {}", self.path, self.field, self.namespaces.join(", "))?;
+        if self.kept.len() < self.namespaces.len() {
+            write!(f, "; kept {}", self.kept.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Conflicts found in a message
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConflictReport {
+    /// Conflicts in document order
+    pub conflicts: Vec<ExtensionConflict>,
+}
+
+impl ConflictReport {
+    /// Whether no conflict was found
+    pub fn is_empty(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// A message with its extension conflicts resolved
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resolved {
+    /// The message without the elements the policy removed
+    pub xml: String,
+    /// Every conflict found
+    pub report: ConflictReport,
+}
+
+/// Find the extension conflicts of `xml` and resolve them by `policy`
+pub fn resolve_conflicts(xml: &str, policy: &ConflictPolicy) -> Result<Resolved, BuildError> {
+    let doc = Document::parse(xml)?;
+    let mut report = ConflictReport::default();
+    let mut edits = Vec::new();
+    // Elements removed with an ancestor; their own conflicts are moot
+    let mut removed = vec![false; doc.elements.len()];
+
+    for (parent, element) in doc.elements.iter().enumerate() {
+        if element.parent.is_some_and(|grandparent| removed[grandparent]) {
+            removed[parent] = true;
+        }
+        if removed[parent] {
+            continue;
+        }
+
+        // Extension children by local name, with their namespaces
+        let mut fields: IndexMap<&str, Vec<(usize, &str)>> = IndexMap::new();
+        for (child, child_element) in doc.children(parent) {
+            if let Some(uri) = doc.namespace_of(child).filter(|uri| is_extension(uri)) {
+                fields.entry(child_element.name.as_str()).or_default().push((child, uri));
+            }
+        }
+
+        for (field, carriers) in fields {
+            let mut namespaces: Vec<&str> = Vec::new();
+            for &(_, uri) in &carriers {
+                if !namespaces.contains(&uri) {
+                    namespaces.push(uri);
+                }
+            }
+            if namespaces.len() < 2 {
+                continue;
+            }
+
+            let kept: Vec<&str> = match policy {
+                ConflictPolicy::PreferNamespaceOrder(order) => {
+                    let rank = |uri: &str| order.iter().position(|preferred| preferred == uri).unwrap_or(order.len());
+                    namespaces.iter().copied().min_by_key(|uri| rank(uri)).into_iter().collect()
+                }
+                ConflictPolicy::KeepAll | ConflictPolicy::Error => namespaces.clone(),
+            };
+            for &(child, uri) in &carriers {
+                if !kept.contains(&uri) {
+                    removed[child] = true;
+                    edits.push((doc.removal_range(child), String::new()));
+                }
+            }
+            report.conflicts.push(ExtensionConflict {
+                path: element.path.clone(),
+                field: field.to_string(),
+                namespaces: namespaces.iter().map(|uri| uri.to_string()).collect(),
+                kept: kept.iter().map(|uri| uri.to_string()).collect(),
+            });
+        }
+    }
+
+    if *policy == ConflictPolicy::Error && !report.is_empty() {
+        return Err(BuildError::ValidationFailed {
+            errors: report.conflicts.iter().map(|conflict| format!("EXTENSION_CONFLICT: {}", conflict)).collect(),
+        });
+    }
+    Ok(Resolved { xml: apply(xml, edits), report })
+}
+
+/// Whether `uri` is a proprietary namespace rather than a DDEX or W3C one
+fn is_extension(uri: &str) -> bool {
+    !uri.starts_with("http://ddex.net/xml/") && !uri.starts_with("http://www.w3.org/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: &str = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43"
+    xmlns:v1="http://partner.com/ddex/v1" xmlns:v2="http://partner.com/ddex/v2">
+  <ReleaseList>
+    <Release>
+      <v1:PartnerId format="old">PARTNER_123</v1:PartnerId>
+      <v2:PartnerId format="new">PARTNER_123_V2</v2:PartnerId>
+      <DisplayArtist>
+        <v1:ArtistMetadata>
+          <v1:PlayCount>1000000</v1:PlayCount>
+        </v1:ArtistMetadata>
+        <v2:ArtistMetadata>
+          <v2:PlayCount type="total">1000000</v2:PlayCount>
+          <v2:PlayCount type="monthly">50000</v2:PlayCount>
+        </v2:ArtistMetadata>
+        <v2:Verified>true</v2:Verified>
+      </DisplayArtist>
+    </Release>
+  </ReleaseList>
+</ern:NewReleaseMessage>"#;
+
+    #[test]
+    fn test_conflict_policies() {
+        let kept_all = resolve_conflicts(MESSAGE, &ConflictPolicy::KeepAll).unwrap();
+        assert_eq!(kept_all.xml, MESSAGE);
+        let fields: Vec<_> = kept_all.report.conflicts.iter().map(|c| (c.path.as_str(), c.field.as_str())).collect();
+        assert_eq!(
+            fields,
+            [
+                ("/NewReleaseMessage/ReleaseList[1]/Release[1]", "PartnerId"),
+                ("/NewReleaseMessage/ReleaseList[1]/Release[1]/DisplayArtist[1]", "ArtistMetadata"),
+            ]
+        );
+
+        let policy = ConflictPolicy::PreferNamespaceOrder(vec!["http://partner.com/ddex/v2".to_string()]);
+        let preferred = resolve_conflicts(MESSAGE, &policy).unwrap();
+        assert_eq!(preferred.report.conflicts[0].kept, ["http://partner.com/ddex/v2"]);
+        assert!(!preferred.xml.contains("v1:"));
+        assert!(preferred.xml.contains("      <v2:PartnerId format=\"new\">PARTNER_123_V2</v2:PartnerId>\n      <DisplayArtist>"));
+        assert_eq!(
+            preferred.report.conflicts[1].to_string(),
+            "/NewReleaseMessage/ReleaseList[1]/Release[1]/DisplayArtist[1]/ArtistMetadata is carried by \
+             http://partner.com/ddex/v1, http://partner.com/ddex/v2; kept http://partner.com/ddex/v2"
+        );
+
+        // Unlisted namespaces fall back to document order
+        let unlisted = resolve_conflicts(MESSAGE, &ConflictPolicy::PreferNamespaceOrder(Vec::new())).unwrap();
+        assert!(!unlisted.xml.contains("v2:PartnerId") && unlisted.xml.contains("v1:PartnerId"));
+
+        match resolve_conflicts(MESSAGE, &ConflictPolicy::Error) {
+            Err(BuildError::ValidationFailed { errors }) => assert_eq!(errors.len(), 2),
+            other => panic!("expected a conflict error, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,670 @@
+//! Validation of extension blocks against their XSDs
+//!
+//! DSPs check the proprietary blocks of a delivery (`spotify:`, `apple:` or a
+//! label's own namespace) against the schemas they publish, and a malformed
+//! block is only reported once the delivery has been rejected.
+//! [`ExtensionSchemaValidator`] checks them before sending: it loads the
+//! `.xsd` files of a schema cache directory, each recognized by its
+//! `targetNamespace`, and validates every element of a message that is in one
+//! of those namespaces. Namespaces without a cached schema are not checked.
+//!
+//! Builds check their output when
+//! [`BuildOptions::extension_schema_dir`](crate::builder::BuildOptions::extension_schema_dir)
+//! is set, and `ddex-builder validate --extension-schemas <DIR>` checks
+//! existing files.
+//!
+//! Only what extension schemas commonly use is understood: global and local
+//! element declarations and `ref`s, named and anonymous complex types with
+//! `sequence`, `choice` and `all` (cardinality is checked, order is not),
+//! `complexContent` extensions, simple content, attributes with
+//! `use="required"`, and simple types restricted by `enumeration`, `pattern`,
+//! `length`, `minLength` or `maxLength` over the common built-in types.
+//! Imports, includes, substitution groups and RELAX NG schemas are not
+//! supported, and elements of an unknown type are accepted.
+//!
+//! ```rust
+//! use ddex_builder::extension_schema::ExtensionSchemaValidator;
+//!
+//! let mut validator = ExtensionSchemaValidator::new();
+//! validator.add_schema(r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+//!     targetNamespace="http://label.example.com/ddex" elementFormDefault="qualified">
+//!   <xs:element name="CatalogCode">
+//!     <xs:simpleType>
+//!       <xs:restriction base="xs:string"><xs:pattern value="EML-\d{4}"/></xs:restriction>
+//!     </xs:simpleType>
+//!   </xs:element>
+//! </xs:schema>"#)?;
+//!
+//! let violations = validator.validate(r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43"
+//!     xmlns:label="http://label.example.com/ddex">
+//!   <label:CatalogCode>EML-12</label:CatalogCode>
+//! </ern:NewReleaseMessage>"#)?;
+//! assert_eq!(violations[0].kind.code(), "INVALID_VALUE");
+//! # Ok::<(), ddex_builder::BuildError>(())
+//! ```
+
+use crate::error::BuildError;
+use crate::lint::Document;
+use ddex_core::error::ErrorLocation;
+use ddex_core::schema::{SchemaViolation, ViolationKind};
+use indexmap::IndexMap;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::path::Path;
+
+static DURATION_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^-?P(\d+Y)?(\d+M)?(\d+D)?(T(\d+H)?(\d+M)?(\d+(\.\d+)?S)?)?$").unwrap());
+
+/// Nesting of type derivations followed before giving up on a cycle
+const MAX_DERIVATION_DEPTH: usize = 16;
+
+/// Extension schemas by target namespace
+#[derive(Debug, Clone, Default)]
+pub struct ExtensionSchemaValidator {
+    schemas: IndexMap<String, XsdSchema>,
+}
+
+impl ExtensionSchemaValidator {
+    /// A validator without schemas
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load every `.xsd` file of a schema cache directory, in file name order
+    pub fn load_dir(dir: impl AsRef<Path>) -> Result<Self, BuildError> {
+        let dir = dir.as_ref();
+        let io_error = |e: std::io::Error| BuildError::Io(format!("{}: {}", dir.display(), e));
+        let mut paths: Vec<_> = std::fs::read_dir(dir)
+            .map_err(io_error)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<_, _>>()
+            .map_err(io_error)?;
+        paths.retain(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("xsd")));
+        paths.sort();
+
+        let mut validator = Self::new();
+        for path in paths {
+            let xsd = std::fs::read_to_string(&path).map_err(|e| BuildError::Io(format!("{}: {}", path.display(), e)))?;
+            validator.add_schema(&xsd).map_err(|e| BuildError::InvalidFormat {
+                field: path.display().to_string(),
+                message: e.to_string(),
+            })?;
+        }
+        Ok(validator)
+    }
+
+    /// Add a schema, returning its target namespace; a later schema for the
+    /// same namespace replaces the earlier one
+    pub fn add_schema(&mut self, xsd: &str) -> Result<String, BuildError> {
+        let schema = XsdSchema::parse(xsd)?;
+        let namespace = schema.target_namespace.clone();
+        self.schemas.insert(namespace.clone(), schema);
+        Ok(namespace)
+    }
+
+    /// Namespaces with a schema
+    pub fn namespaces(&self) -> impl Iterator<Item = &str> {
+        self.schemas.keys().map(String::as_str)
+    }
+
+    /// Whether no schema is loaded
+    pub fn is_empty(&self) -> bool {
+        self.schemas.is_empty()
+    }
+
+    /// Check every extension block of `xml` whose namespace has a schema
+    pub fn validate(&self, xml: &str) -> Result<Vec<SchemaViolation>, BuildError> {
+        let doc = Document::parse(xml)?;
+        let mut violations = Vec::new();
+        for (index, element) in doc.elements.iter().enumerate() {
+            let Some(namespace) = doc.namespace_of(index) else { continue };
+            let Some(schema) = self.schemas.get(namespace) else { continue };
+            // Only the outermost element of a block; its content is checked with it
+            if element.parent.is_some_and(|parent| doc.namespace_of(parent) == Some(namespace)) {
+                continue;
+            }
+            let mut check = Check { doc: &doc, schema, violations: &mut violations };
+            match schema.elements.get(&element.name) {
+                Some(declaration) => check.element(index, &declaration.kind),
+                None => check.report(
+                    index,
+                    ViolationKind::UnexpectedElement,
+                    format!("{} is not declared by the schema for {}", qualified(&doc, index), namespace),
+                ),
+            }
+        }
+        Ok(violations)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct XsdSchema {
+    target_namespace: String,
+    elements: IndexMap<String, ElementDecl>,
+    complex_types: IndexMap<String, ComplexType>,
+    simple_types: IndexMap<String, SimpleType>,
+}
+
+#[derive(Debug, Clone)]
+struct ElementDecl {
+    name: String,
+    kind: TypeRef,
+}
+
+#[derive(Debug, Clone)]
+enum TypeRef {
+    /// A named schema type or an `xs:` built-in, by local name
+    Named(String),
+    Complex(Box<ComplexType>),
+    Simple(SimpleType),
+    /// No type given, so any content
+    Any,
+}
+
+#[derive(Debug, Clone, Default)]
+struct ComplexType {
+    /// Type this one extends
+    base: Option<String>,
+    particles: Vec<Particle>,
+    /// Required choices: one of the names must occur
+    choices: Vec<Vec<String>>,
+    attributes: Vec<AttributeDecl>,
+    /// Type of the text of simple content
+    text: Option<TypeRef>,
+    any_element: bool,
+    any_attribute: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Particle {
+    name: String,
+    /// `None` for a `ref` to a global element
+    declaration: Option<ElementDecl>,
+    min: usize,
+    max: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+struct AttributeDecl {
+    name: String,
+    required: bool,
+    kind: TypeRef,
+}
+
+#[derive(Debug, Clone, Default)]
+struct SimpleType {
+    base: Option<String>,
+    enumeration: Vec<String>,
+    /// Anchored patterns; ones the regex engine cannot compile are skipped
+    patterns: Vec<Regex>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+}
+
+impl XsdSchema {
+    fn parse(xsd: &str) -> Result<Self, BuildError> {
+        let doc = Document::parse(xsd)?;
+        let invalid = |message: &str| BuildError::InvalidFormat { field: "xsd".to_string(), message: message.to_string() };
+        if doc.elements[0].name != "schema" {
+            return Err(invalid("Root element is not xs:schema"));
+        }
+        let target_namespace = attribute(&doc, 0, "targetNamespace")
+            .ok_or_else(|| invalid("Extension schemas need a targetNamespace"))?
+            .to_string();
+
+        let mut schema = Self { target_namespace, ..Default::default() };
+        for (index, element) in doc.children(0) {
+            let Some(name) = attribute(&doc, index, "name") else { continue };
+            match element.name.as_str() {
+                "element" => {
+                    schema.elements.insert(name.to_string(), read_element(&doc, index));
+                }
+                "complexType" => {
+                    schema.complex_types.insert(name.to_string(), read_complex_type(&doc, index));
+                }
+                "simpleType" => {
+                    schema.simple_types.insert(name.to_string(), read_simple_type(&doc, index));
+                }
+                _ => {}
+            }
+        }
+        Ok(schema)
+    }
+}
+
+fn attribute<'d>(doc: &'d Document<'_>, element: usize, name: &str) -> Option<&'d str> {
+    doc.elements[element].attributes.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+}
+
+/// Name without its prefix
+fn local(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// `minOccurs` and `maxOccurs`, `None` for unbounded
+fn occurs(doc: &Document<'_>, element: usize) -> (usize, Option<usize>) {
+    let min = attribute(doc, element, "minOccurs").and_then(|v| v.parse().ok()).unwrap_or(1);
+    let max = match attribute(doc, element, "maxOccurs") {
+        Some("unbounded") => None,
+        Some(value) => Some(value.parse().unwrap_or(1)),
+        None => Some(1),
+    };
+    (min, max)
+}
+
+fn read_element(doc: &Document<'_>, index: usize) -> ElementDecl {
+    let name = attribute(doc, index, "name").or_else(|| attribute(doc, index, "ref")).map(local).unwrap_or_default();
+    let kind = match attribute(doc, index, "type") {
+        Some(kind) => TypeRef::Named(local(kind).to_string()),
+        None => read_inline_type(doc, index),
+    };
+    ElementDecl { name: name.to_string(), kind }
+}
+
+/// Anonymous complex or simple type of an element or attribute
+fn read_inline_type(doc: &Document<'_>, index: usize) -> TypeRef {
+    for (child, element) in doc.children(index) {
+        match element.name.as_str() {
+            "complexType" => return TypeRef::Complex(Box::new(read_complex_type(doc, child))),
+            "simpleType" => return TypeRef::Simple(read_simple_type(doc, child)),
+            _ => {}
+        }
+    }
+    TypeRef::Any
+}
+
+fn read_complex_type(doc: &Document<'_>, index: usize) -> ComplexType {
+    let mut complex = ComplexType::default();
+    read_content(doc, index, &mut complex);
+    complex
+}
+
+/// Particles and attributes of a complex type or of its derivation
+fn read_content(doc: &Document<'_>, index: usize, complex: &mut ComplexType) {
+    for (child, element) in doc.children(index) {
+        match element.name.as_str() {
+            "sequence" | "choice" | "all" => read_group(doc, child, false, false, complex),
+            "attribute" => complex.attributes.push(read_attribute(doc, child)),
+            "anyAttribute" => complex.any_attribute = true,
+            "complexContent" | "simpleContent" => {
+                let simple = element.name == "simpleContent";
+                for (derivation, derived) in doc.children(child) {
+                    let base = attribute(doc, derivation, "base").map(|base| local(base).to_string());
+                    match (simple, derived.name.as_str()) {
+                        (true, "extension") => complex.text = base.map(TypeRef::Named),
+                        (true, "restriction") => {
+                            let mut restriction = read_restriction(doc, derivation);
+                            restriction.base = base;
+                            complex.text = Some(TypeRef::Simple(restriction));
+                        }
+                        (false, "extension") => complex.base = base,
+                        _ => {}
+                    }
+                    read_content(doc, derivation, complex);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Elements of a model group; `optional` and `repeated` carry the
+/// cardinality of the enclosing groups
+fn read_group(doc: &Document<'_>, group: usize, optional: bool, repeated: bool, complex: &mut ComplexType) {
+    let choice = doc.elements[group].name == "choice";
+    let (min, max) = occurs(doc, group);
+    let optional = optional || min == 0;
+    let repeated = repeated || max != Some(1);
+
+    let mut names = Vec::new();
+    for (child, element) in doc.children(group) {
+        match element.name.as_str() {
+            "element" => {
+                let (min, max) = occurs(doc, child);
+                let declaration = read_element(doc, child);
+                names.push(declaration.name.clone());
+                complex.particles.push(Particle {
+                    name: declaration.name.clone(),
+                    declaration: attribute(doc, child, "ref").is_none().then_some(declaration),
+                    min: if optional || choice { 0 } else { min },
+                    max: if repeated { None } else { max },
+                });
+            }
+            "sequence" | "choice" | "all" => read_group(doc, child, optional || choice, repeated, complex),
+            "any" => complex.any_element = true,
+            _ => {}
+        }
+    }
+    if choice && !optional && !names.is_empty() {
+        complex.choices.push(names);
+    }
+}
+
+fn read_attribute(doc: &Document<'_>, index: usize) -> AttributeDecl {
+    let name = attribute(doc, index, "name").or_else(|| attribute(doc, index, "ref")).map(local).unwrap_or_default();
+    let kind = match attribute(doc, index, "type") {
+        Some(kind) => TypeRef::Named(local(kind).to_string()),
+        None => read_inline_type(doc, index),
+    };
+    AttributeDecl { name: name.to_string(), required: attribute(doc, index, "use") == Some("required"), kind }
+}
+
+fn read_simple_type(doc: &Document<'_>, index: usize) -> SimpleType {
+    doc.children(index)
+        .find(|(_, element)| element.name == "restriction")
+        .map(|(restriction, _)| {
+            let mut simple = read_restriction(doc, restriction);
+            simple.base = attribute(doc, restriction, "base").map(|base| local(base).to_string());
+            simple
+        })
+        .unwrap_or_default()
+}
+
+/// Facets of a restriction
+fn read_restriction(doc: &Document<'_>, index: usize) -> SimpleType {
+    let mut simple = SimpleType::default();
+    for (facet, element) in doc.children(index) {
+        let Some(value) = attribute(doc, facet, "value") else { continue };
+        match element.name.as_str() {
+            "enumeration" => simple.enumeration.push(value.to_string()),
+            // XSD patterns match the whole value
+            "pattern" => simple.patterns.extend(Regex::new(&format!("^(?:{})$", value)).ok()),
+            "length" => {
+                simple.min_length = value.parse().ok();
+                simple.max_length = simple.min_length;
+            }
+            "minLength" => simple.min_length = value.parse().ok(),
+            "maxLength" => simple.max_length = value.parse().ok(),
+            _ => {}
+        }
+    }
+    simple
+}
+
+/// Name of an element as written in the document
+fn qualified(doc: &Document<'_>, index: usize) -> String {
+    let element = &doc.elements[index];
+    match &element.prefix {
+        Some(prefix) => format!("{}:{}", prefix, element.name),
+        None => element.name.clone(),
+    }
+}
+
+struct Check<'c, 'd> {
+    doc: &'c Document<'d>,
+    schema: &'c XsdSchema,
+    violations: &'c mut Vec<SchemaViolation>,
+}
+
+impl Check<'_, '_> {
+    fn report(&mut self, index: usize, kind: ViolationKind, message: String) {
+        let element = &self.doc.elements[index];
+        let offset = element.start_tag.start;
+        let line_start = self.doc.source[..offset].rfind('\n').map_or(0, |newline| newline + 1);
+        self.violations.push(SchemaViolation {
+            kind,
+            message,
+            location: ErrorLocation {
+                line: element.line,
+                column: self.doc.source[line_start..offset].chars().count() + 1,
+                byte_offset: Some(offset),
+                path: element.path.clone(),
+            },
+        });
+    }
+
+    fn element(&mut self, index: usize, kind: &TypeRef) {
+        let schema = self.schema;
+        match kind {
+            TypeRef::Named(name) => {
+                if let Some(complex) = schema.complex_types.get(name) {
+                    self.complex(index, complex);
+                } else {
+                    self.text(index, kind);
+                }
+            }
+            TypeRef::Complex(complex) => self.complex(index, complex),
+            TypeRef::Simple(_) => self.text(index, kind),
+            TypeRef::Any => {}
+        }
+    }
+
+    fn text(&mut self, index: usize, kind: &TypeRef) {
+        let value = &self.doc.elements[index].text;
+        if let Err(problem) = check_value(self.schema, kind, value, 0) {
+            let message = format!("{} {}", qualified(self.doc, index), problem);
+            self.report(index, ViolationKind::InvalidValue, message);
+        }
+    }
+
+    fn complex(&mut self, index: usize, complex: &ComplexType) {
+        let (doc, schema) = (self.doc, self.schema);
+        // The type with the types it extends, most derived first
+        let mut chain = vec![complex];
+        while let Some(base) = chain.last().and_then(|c| c.base.as_ref()).and_then(|base| schema.complex_types.get(base)) {
+            if chain.len() > MAX_DERIVATION_DEPTH {
+                break;
+            }
+            chain.push(base);
+        }
+        let name = qualified(doc, index);
+
+        // Attributes
+        let attributes: Vec<&AttributeDecl> = chain.iter().flat_map(|c| &c.attributes).collect();
+        let any_attribute = chain.iter().any(|c| c.any_attribute);
+        for declaration in &attributes {
+            let value = doc.elements[index].attributes.iter().find(|(key, _)| local(key) == declaration.name);
+            match value {
+                None if declaration.required => self.report(
+                    index,
+                    ViolationKind::MissingAttribute,
+                    format!("{} is missing required attribute {}", name, declaration.name),
+                ),
+                None => {}
+                Some((key, value)) => {
+                    if let Err(problem) = check_value(schema, &declaration.kind, value, 0) {
+                        self.report(index, ViolationKind::InvalidAttribute, format!("{}/@{} {}", name, key, problem));
+                    }
+                }
+            }
+        }
+        if !any_attribute {
+            for (key, _) in &doc.elements[index].attributes {
+                let foreign = key.contains(':');
+                if !foreign && !attributes.iter().any(|declaration| declaration.name == *key) {
+                    self.report(index, ViolationKind::InvalidAttribute, format!("{} does not allow attribute {}", name, key));
+                }
+            }
+        }
+
+        // Simple content
+        if let Some(text) = chain.iter().find_map(|c| c.text.as_ref()) {
+            self.text(index, text);
+        }
+
+        // Child elements in the schema's namespace or in none
+        let particles: Vec<&Particle> = chain.iter().rev().flat_map(|c| &c.particles).collect();
+        let any_element = chain.iter().any(|c| c.any_element);
+        let mut counts = vec![0usize; particles.len()];
+        for &child in &doc.elements[index].children {
+            if doc.namespace_of(child).is_some_and(|ns| ns != schema.target_namespace) {
+                continue;
+            }
+            let child_name = &doc.elements[child].name;
+            match particles.iter().position(|particle| particle.name == *child_name) {
+                Some(position) => {
+                    counts[position] += 1;
+                    let particle = particles[position];
+                    let kind = match &particle.declaration {
+                        Some(declaration) => Some(&declaration.kind),
+                        None => schema.elements.get(&particle.name).map(|declaration| &declaration.kind),
+                    };
+                    if let Some(kind) = kind {
+                        self.element(child, kind);
+                    }
+                }
+                None if any_element => {}
+                None => {
+                    let message = format!("{} does not allow element {}", name, qualified(doc, child));
+                    self.report(child, ViolationKind::UnexpectedElement, message);
+                }
+            }
+        }
+
+        let prefix = doc.elements[index].prefix.as_ref().map(|p| format!("{}:", p)).unwrap_or_default();
+        for (particle, &count) in particles.iter().zip(&counts) {
+            if count < particle.min {
+                let message = format!("{} is missing required element {}{}", name, prefix, particle.name);
+                self.report(index, ViolationKind::MissingElement, message);
+            }
+            if let Some(max) = particle.max.filter(|&max| count > max) {
+                let message = format!("{} has {} {}{} elements, at most {} allowed", name, count, prefix, particle.name, max);
+                self.report(index, ViolationKind::TooManyOccurrences, message);
+            }
+        }
+        for choice in chain.iter().flat_map(|c| &c.choices) {
+            let present = choice.iter().any(|option| {
+                particles.iter().zip(&counts).any(|(particle, &count)| particle.name == *option && count > 0)
+            });
+            if !present {
+                let message = format!("{} needs one of {}", name, choice.join(", "));
+                self.report(index, ViolationKind::MissingElement, message);
+            }
+        }
+    }
+}
+
+/// Check a text or attribute value against a simple type
+fn check_value(schema: &XsdSchema, kind: &TypeRef, value: &str, depth: usize) -> Result<(), String> {
+    if depth > MAX_DERIVATION_DEPTH {
+        return Ok(());
+    }
+    let simple = match kind {
+        TypeRef::Simple(simple) => simple,
+        TypeRef::Named(name) => match schema.simple_types.get(name) {
+            Some(simple) => simple,
+            None => return check_builtin(name, value),
+        },
+        TypeRef::Complex(_) | TypeRef::Any => return Ok(()),
+    };
+
+    if !simple.enumeration.is_empty() && !simple.enumeration.iter().any(|allowed| allowed == value) {
+        return Err(format!("'{}' is not one of {}", value, simple.enumeration.join(", ")));
+    }
+    if let Some(pattern) = simple.patterns.iter().find(|pattern| !pattern.is_match(value)) {
+        let pattern = pattern.as_str();
+        return Err(format!("'{}' does not match {}", value, &pattern[4..pattern.len() - 2]));
+    }
+    let length = value.chars().count();
+    if simple.min_length.is_some_and(|min| length < min) || simple.max_length.is_some_and(|max| length > max) {
+        return Err(format!("'{}' has {} characters, outside the allowed length", value, length));
+    }
+    match &simple.base {
+        Some(base) => check_value(schema, &TypeRef::Named(base.clone()), value, depth + 1),
+        None => Ok(()),
+    }
+}
+
+/// Check a value against an `xs:` built-in type; unknown types accept anything
+fn check_builtin(name: &str, value: &str) -> Result<(), String> {
+    let valid = match name {
+        "integer" | "int" | "long" | "short" | "byte" => value.parse::<i128>().is_ok(),
+        "nonNegativeInteger" | "unsignedInt" | "unsignedLong" | "unsignedShort" | "unsignedByte" => {
+            value.parse::<u128>().is_ok()
+        }
+        "positiveInteger" => value.parse::<u128>().is_ok_and(|n| n > 0),
+        "decimal" => !value.contains(['e', 'E']) && value.parse::<f64>().is_ok_and(f64::is_finite),
+        "float" | "double" => matches!(value, "INF" | "-INF" | "NaN") || value.parse::<f64>().is_ok(),
+        "boolean" => matches!(value, "true" | "false" | "1" | "0"),
+        "date" => match (value.get(..10), value.get(10..)) {
+            (Some(date), Some(zone)) => {
+                let zone_valid = matches!(zone, "" | "Z")
+                    || (zone.len() == 6
+                        && zone.starts_with(['+', '-'])
+                        && chrono::NaiveTime::parse_from_str(&zone[1..], "%H:%M").is_ok());
+                zone_valid && chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok()
+            }
+            _ => false,
+        },
+        "dateTime" => {
+            chrono::DateTime::parse_from_rfc3339(value).is_ok()
+                || chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f").is_ok()
+        }
+        // The pattern also takes a bare `P` or a trailing `T`, which XSD does not
+        "duration" => DURATION_PATTERN.is_match(value) && !value.ends_with(['P', 'T']),
+        "anyURI" => !value.chars().any(char::is_whitespace),
+        _ => true,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("'{}' is not a valid xs:{}", value, name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+    xmlns:sp="http://spotify.com/ddex/extensions"
+    targetNamespace="http://spotify.com/ddex/extensions" elementFormDefault="qualified">
+  <xs:simpleType name="Popularity">
+    <xs:restriction base="xs:integer"/>
+  </xs:simpleType>
+  <xs:complexType name="TrackExtension">
+    <xs:sequence>
+      <xs:element name="SpotifyId" type="xs:string"/>
+      <xs:element name="Popularity" type="sp:Popularity" minOccurs="0"/>
+      <xs:choice>
+        <xs:element name="Explicit" type="xs:boolean"/>
+        <xs:element name="Clean" type="xs:boolean"/>
+      </xs:choice>
+    </xs:sequence>
+    <xs:attribute name="version" type="xs:string" use="required"/>
+  </xs:complexType>
+  <xs:element name="Track" type="sp:TrackExtension"/>
+</xs:schema>"#;
+
+    fn message(block: &str) -> String {
+        format!(
+            r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43" xmlns:spotify="http://spotify.com/ddex/extensions">
+  <ResourceList>
+    <SoundRecording>
+      <ResourceReference>A1</ResourceReference>
+      {}
+    </SoundRecording>
+  </ResourceList>
+</ern:NewReleaseMessage>"#,
+            block
+        )
+    }
+
+    #[test]
+    fn test_extension_blocks_are_checked() {
+        let mut validator = ExtensionSchemaValidator::new();
+        assert_eq!(validator.add_schema(SCHEMA).unwrap(), "http://spotify.com/ddex/extensions");
+
+        let valid = message(r#"<spotify:Track version="1"><spotify:SpotifyId>4uLU6hMCjMI75M1A2tKUQC</spotify:SpotifyId><spotify:Explicit>false</spotify:Explicit></spotify:Track>"#);
+        assert!(validator.validate(&valid).unwrap().is_empty());
+
+        let invalid = message(r#"<spotify:Track><spotify:Popularity>high</spotify:Popularity><spotify:Mood>Calm</spotify:Mood></spotify:Track>"#);
+        let violations = validator.validate(&invalid).unwrap();
+        let found: Vec<_> = violations.iter().map(|v| (v.kind, v.message.as_str())).collect();
+        assert_eq!(
+            found,
+            [
+                (ViolationKind::MissingAttribute, "spotify:Track is missing required attribute version"),
+                (ViolationKind::InvalidValue, "spotify:Popularity 'high' is not a valid xs:integer"),
+                (ViolationKind::UnexpectedElement, "spotify:Track does not allow element spotify:Mood"),
+                (ViolationKind::MissingElement, "spotify:Track is missing required element spotify:SpotifyId"),
+                (ViolationKind::MissingElement, "spotify:Track needs one of Explicit, Clean"),
+            ]
+        );
+        assert_eq!(violations[0].location.line, 5);
+        assert_eq!(violations[0].location.path, "/NewReleaseMessage/ResourceList[1]/SoundRecording[1]/Track[1]");
+
+        let undeclared = validator.validate(&message("<spotify:Album/>")).unwrap();
+        assert_eq!(undeclared[0].kind, ViolationKind::UnexpectedElement);
+    }
+}
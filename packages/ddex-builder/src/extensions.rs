@@ -0,0 +1,172 @@
+//! Proprietary extension namespaces
+//!
+//! Labels and DSPs add their own elements to DDEX messages under namespaces
+//! of their own. Left unregistered, such a namespace gets whatever prefix the
+//! namespace minimizer generates for it (`nsexa`, `ns417`), which differs
+//! between tools and breaks partners matching on the prefix. Registering it
+//! with [`ExtensionNamespaces::register`] (or
+//! [`Builder::register_extension_namespace`](crate::Builder::register_extension_namespace))
+//! fixes its prefix: every build declares it on the root element under that
+//! prefix, renaming any other prefix the tree used for it.
+//!
+//! ```rust
+//! use ddex_builder::extensions::ExtensionNamespaces;
+//!
+//! let mut namespaces = ExtensionNamespaces::new();
+//! namespaces.register("spotify", "http://spotify.com/ddex/extensions", None)?;
+//!
+//! // DDEX prefixes and URIs are taken
+//! assert!(namespaces.register("avs", "http://example.com/avs", None).is_err());
+//! # Ok::<(), ddex_builder::BuildError>(())
+//! ```
+
+use crate::error::BuildError;
+use ddex_core::namespace::{DDEXStandard, NamespaceError, NamespaceInfo, NamespaceRegistry};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// A registered extension namespace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtensionNamespace {
+    /// Prefix the namespace is always declared under
+    pub prefix: String,
+    /// Namespace URI
+    pub uri: String,
+    /// Where the namespace's schema is published, if anywhere
+    #[serde(default)]
+    pub schema_url: Option<String>,
+}
+
+impl ExtensionNamespace {
+    fn info(&self) -> NamespaceInfo {
+        NamespaceInfo {
+            uri: self.uri.clone(),
+            preferred_prefix: self.prefix.clone(),
+            alternative_prefixes: Vec::new(),
+            standard: DDEXStandard::Custom(self.prefix.clone()),
+            version: None,
+            required: false,
+        }
+    }
+}
+
+/// Extension namespaces in the order they were registered
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ExtensionNamespaces {
+    namespaces: IndexMap<String, ExtensionNamespace>,
+}
+
+impl ExtensionNamespaces {
+    /// No extension namespaces
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `uri` under `prefix`
+    ///
+    /// Fails with `InvalidFormat` when `prefix` is not an XML name, `uri` is
+    /// empty or contains whitespace, or either is already taken by a DDEX
+    /// namespace or another extension. Registering the same pair again only
+    /// updates its schema URL.
+    pub fn register(&mut self, prefix: &str, uri: &str, schema_url: Option<&str>) -> Result<(), BuildError> {
+        let namespace = ExtensionNamespace {
+            prefix: prefix.to_string(),
+            uri: uri.to_string(),
+            schema_url: schema_url.map(str::to_string),
+        };
+        if let Some(existing) = self.namespaces.get_mut(uri) {
+            if existing.prefix == prefix {
+                *existing = namespace;
+                return Ok(());
+            }
+        }
+
+        let invalid = |message: String| BuildError::InvalidFormat {
+            field: "extension namespace".to_string(),
+            message,
+        };
+        if !is_valid_prefix(prefix) {
+            return Err(invalid(format!("Invalid namespace prefix: {}", prefix)));
+        }
+        if uri.is_empty() || uri.chars().any(char::is_whitespace) {
+            return Err(invalid(NamespaceError::InvalidUri(uri.to_string()).to_string()));
+        }
+        self.registry()
+            .register_custom_namespace(namespace.info())
+            .map_err(|error| invalid(error.to_string()))?;
+        self.namespaces.insert(namespace.uri.clone(), namespace);
+        Ok(())
+    }
+
+    /// The namespace registered for `uri`
+    pub fn get(&self, uri: &str) -> Option<&ExtensionNamespace> {
+        self.namespaces.get(uri)
+    }
+
+    /// Registered namespaces, in registration order
+    pub fn iter(&self) -> impl Iterator<Item = &ExtensionNamespace> {
+        self.namespaces.values()
+    }
+
+    /// Number of registered namespaces
+    pub fn len(&self) -> usize {
+        self.namespaces.len()
+    }
+
+    /// Whether none is registered
+    pub fn is_empty(&self) -> bool {
+        self.namespaces.is_empty()
+    }
+
+    /// The DDEX namespaces plus these
+    pub(crate) fn registry(&self) -> NamespaceRegistry {
+        let mut registry = NamespaceRegistry::new();
+        for namespace in self.iter() {
+            // Each was checked against the others when registered
+            let _ = registry.register_custom_namespace(namespace.info());
+        }
+        registry
+    }
+}
+
+/// An XML name without a colon that does not start with `xml`
+fn is_valid_prefix(prefix: &str) -> bool {
+    let mut chars = prefix.chars();
+    let starts_well = chars.next().is_some_and(|c| c.is_alphabetic() || c == '_');
+    starts_well
+        && chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        && !prefix.to_ascii_lowercase().starts_with("xml")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_extension_namespace() {
+        let mut namespaces = ExtensionNamespaces::new();
+        namespaces.register("label", "http://label.example.com/ddex", None).unwrap();
+        namespaces
+            .register("label", "http://label.example.com/ddex", Some("http://label.example.com/ddex.xsd"))
+            .unwrap();
+        assert_eq!(namespaces.len(), 1);
+        assert_eq!(
+            namespaces.get("http://label.example.com/ddex").unwrap().schema_url.as_deref(),
+            Some("http://label.example.com/ddex.xsd")
+        );
+
+        for (prefix, uri) in [
+            ("ern", "http://label.example.com/other"),
+            ("other", "http://ddex.net/xml/ern/43"),
+            ("label", "http://label.example.com/other"),
+            ("other", "http://label.example.com/ddex"),
+            ("1abel", "http://label.example.com/other"),
+            ("xmlfoo", "http://label.example.com/other"),
+            ("other", "not a uri"),
+        ] {
+            assert!(namespaces.register(prefix, uri, None).is_err(), "{} = {}", prefix, uri);
+        }
+        assert_eq!(namespaces.registry().get_preferred_prefix("http://label.example.com/ddex"), Some("label"));
+    }
+}
@@ -0,0 +1,543 @@
+//! Fluent construction of build requests for common releases
+//!
+//! A [`BuildRequest`] mirrors the DDEX message closely, which makes simple
+//! releases verbose to describe. The builders here cover the common case of
+//! one release with its tracks and deals:
+//!
+//! ```
+//! use ddex_builder::fluent::Ern43;
+//!
+//! let request = Ern43::album("Midnight Drive")
+//!     .artist("The Night Owls")
+//!     .upc("602445790128")
+//!     .release_date("2024-06-01")
+//!     .sender("Example Records", None)
+//!     .recipient("Example DSP", None)
+//!     .track(|t| t.title("Headlights").isrc("USRC12400001").duration_secs(215))
+//!     .track(|t| t.title("Exit 42").isrc("USRC12400002").duration_secs(187))
+//!     .deal(|d| d.worldwide().streaming())
+//!     .build()?;
+//! # Ok::<(), ddex_builder::BuildError>(())
+//! ```
+//!
+//! Unset values are derived where DDEX allows: the release ID defaults to
+//! the UPC, tracks to the release artist and deals start on the release
+//! date. [`ReleaseBuilder::build`] runs preflight validation on the result,
+//! so the request it returns is ready for
+//! [`DDEXBuilder::build`](crate::DDEXBuilder::build); anything beyond these
+//! builders can still be set on the request itself.
+
+use crate::builder::{
+    BuildRequest, CLineRequest, DealRequest, DealTerms, LocalizedStringRequest, MessageHeaderRequest, PLineRequest,
+    PartyRequest, ReleaseRequest, TerritoryDetailsRequest, TrackRequest,
+};
+use crate::error::BuildError;
+use crate::preflight::{PreflightValidator, ValidationConfig};
+use ddex_core::duration::DdexDuration;
+use std::time::Duration;
+
+macro_rules! ern_version {
+    ($name:ident, $version:literal) => {
+        #[doc = concat!("Entry point for ERN ", $version, " messages")]
+        pub struct $name;
+
+        impl $name {
+            /// Start an album (`AudioAlbum` profile)
+            pub fn album(title: impl Into<String>) -> ReleaseBuilder {
+                ReleaseBuilder::new($version, "AudioAlbum", title.into())
+            }
+
+            /// Start a single (`AudioSingle` profile)
+            pub fn single(title: impl Into<String>) -> ReleaseBuilder {
+                ReleaseBuilder::new($version, "AudioSingle", title.into())
+            }
+        }
+    };
+}
+
+ern_version!(Ern382, "3.8.2");
+ern_version!(Ern42, "4.2");
+ern_version!(Ern43, "4.3");
+ern_version!(Ern44, "4.4");
+
+/// A release message under construction, started from [`Ern43`] or another
+/// version
+#[derive(Debug, Clone)]
+pub struct ReleaseBuilder {
+    version: &'static str,
+    profile: &'static str,
+    title: String,
+    title_language: Option<String>,
+    artist: String,
+    release_id: Option<String>,
+    upc: Option<String>,
+    label: Option<String>,
+    release_date: Option<String>,
+    original_release_date: Option<String>,
+    genre: Option<String>,
+    parental_warning: Option<String>,
+    p_line: Option<PLineRequest>,
+    c_line: Option<CLineRequest>,
+    message_id: Option<String>,
+    sender: Option<PartyRequest>,
+    recipient: Option<PartyRequest>,
+    tracks: Vec<TrackBuilder>,
+    deals: Vec<DealBuilder>,
+}
+
+impl ReleaseBuilder {
+    fn new(version: &'static str, profile: &'static str, title: String) -> Self {
+        Self {
+            version,
+            profile,
+            title,
+            title_language: None,
+            artist: String::new(),
+            release_id: None,
+            upc: None,
+            label: None,
+            release_date: None,
+            original_release_date: None,
+            genre: None,
+            parental_warning: None,
+            p_line: None,
+            c_line: None,
+            message_id: None,
+            sender: None,
+            recipient: None,
+            tracks: Vec::new(),
+            deals: Vec::new(),
+        }
+    }
+
+    /// Display artist of the release, and of tracks that set none
+    pub fn artist(mut self, artist: impl Into<String>) -> Self {
+        self.artist = artist.into();
+        self
+    }
+
+    /// Language of the title, e.g. `en`
+    pub fn language(mut self, language_code: impl Into<String>) -> Self {
+        self.title_language = Some(language_code.into());
+        self
+    }
+
+    /// Release ID (GRid or proprietary), the UPC when unset
+    pub fn release_id(mut self, release_id: impl Into<String>) -> Self {
+        self.release_id = Some(release_id.into());
+        self
+    }
+
+    /// UPC or EAN of the release
+    pub fn upc(mut self, upc: impl Into<String>) -> Self {
+        self.upc = Some(upc.into());
+        self
+    }
+
+    /// Label name
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Release date (`YYYY-MM-DD`), also the start of deals that set none
+    pub fn release_date(mut self, date: impl Into<String>) -> Self {
+        self.release_date = Some(date.into());
+        self
+    }
+
+    /// Date the release was first issued (`YYYY-MM-DD`), for reissues
+    pub fn original_release_date(mut self, date: impl Into<String>) -> Self {
+        self.original_release_date = Some(date.into());
+        self
+    }
+
+    /// Genre, e.g. `Jazz`
+    pub fn genre(mut self, genre: impl Into<String>) -> Self {
+        self.genre = Some(genre.into());
+        self
+    }
+
+    /// Mark the release as having explicit content
+    pub fn explicit(self) -> Self {
+        self.parental_warning("Explicit")
+    }
+
+    /// Parental warning type of the release, e.g. `NotExplicit`
+    pub fn parental_warning(mut self, warning: impl Into<String>) -> Self {
+        self.parental_warning = Some(warning.into());
+        self
+    }
+
+    /// Phonographic copyright (℗), e.g. `p_line(2024, "Example Records")`
+    pub fn p_line(mut self, year: u16, holder: &str) -> Self {
+        self.p_line = Some(PLineRequest { year: Some(year), text: format!("{} {}", year, holder) });
+        self
+    }
+
+    /// Copyright (©), e.g. `c_line(2024, "Example Records")`
+    pub fn c_line(mut self, year: u16, holder: &str) -> Self {
+        self.c_line = Some(CLineRequest { year: Some(year), text: format!("{} {}", year, holder) });
+        self
+    }
+
+    /// Message ID, generated at build time when unset
+    pub fn message_id(mut self, message_id: impl Into<String>) -> Self {
+        self.message_id = Some(message_id.into());
+        self
+    }
+
+    /// Party sending the message, with its DPID if it has one
+    pub fn sender(mut self, name: impl Into<String>, dpid: Option<&str>) -> Self {
+        self.sender = Some(party(name.into(), dpid));
+        self
+    }
+
+    /// Party the message is for, with its DPID if it has one
+    pub fn recipient(mut self, name: impl Into<String>, dpid: Option<&str>) -> Self {
+        self.recipient = Some(party(name.into(), dpid));
+        self
+    }
+
+    /// Add a track, in play order
+    pub fn track(mut self, track: impl FnOnce(TrackBuilder) -> TrackBuilder) -> Self {
+        self.tracks.push(track(TrackBuilder::default()));
+        self
+    }
+
+    /// Add a deal covering the release
+    pub fn deal(mut self, deal: impl FnOnce(DealBuilder) -> DealBuilder) -> Self {
+        self.deals.push(deal(DealBuilder::default()));
+        self
+    }
+
+    /// The request as described, without validation
+    ///
+    /// Fails only when the sender, the recipient, both the release ID and
+    /// UPC, or the territories or commercial model of a deal are missing.
+    pub fn into_request(self) -> Result<BuildRequest, BuildError> {
+        let missing = |field: &str| BuildError::MissingRequired { field: field.to_string() };
+        let release_id = self.release_id.or_else(|| self.upc.clone()).ok_or_else(|| missing("release_id or upc"))?;
+        let header = MessageHeaderRequest {
+            message_id: self.message_id,
+            message_sender: self.sender.ok_or_else(|| missing("message_sender"))?,
+            message_recipient: self.recipient.ok_or_else(|| missing("message_recipient"))?,
+            message_control_type: None,
+            message_created_date_time: None,
+            ..Default::default()
+        };
+
+        let territory_details = if self.genre.is_some() || self.parental_warning.is_some() {
+            vec![TerritoryDetailsRequest {
+                territory_codes: vec!["Worldwide".to_string()],
+                genre: self.genre,
+                parental_warning: self.parental_warning,
+                ..Default::default()
+            }]
+        } else {
+            Vec::new()
+        };
+        let tracks = self
+            .tracks
+            .into_iter()
+            .enumerate()
+            .map(|(i, track)| TrackRequest {
+                track_id: track.track_id.unwrap_or_else(|| format!("{}-T{}", release_id, i + 1)),
+                resource_reference: None,
+                isrc: track.isrc,
+                title: track.title,
+                duration: track.duration,
+                artist: track.artist.unwrap_or_else(|| self.artist.clone()),
+                contributors: Vec::new(),
+                p_line: track.p_line,
+                c_line: None,
+                comments: Vec::new(),
+                technical_details: None,
+                parental_warning: track.parental_warning,
+            })
+            .collect();
+        for (i, deal) in self.deals.iter().enumerate() {
+            if deal.terms.territory_code.is_empty() {
+                return Err(missing(&format!("deals[{}].territory_code", i)));
+            }
+            if deal.terms.commercial_model_type.is_empty() {
+                return Err(missing(&format!("deals[{}].commercial_model_type", i)));
+            }
+        }
+        let deals = self
+            .deals
+            .into_iter()
+            .map(|deal| DealRequest {
+                deal_reference: None,
+                deal_terms: DealTerms {
+                    start_date: deal.terms.start_date.clone().or_else(|| self.release_date.clone()),
+                    ..deal.terms
+                },
+                release_references: Vec::new(),
+                release_ids: vec![release_id.clone()],
+                comments: Vec::new(),
+            })
+            .collect();
+
+        let release = ReleaseRequest {
+            release_id,
+            release_reference: None,
+            title: vec![LocalizedStringRequest { text: self.title, language_code: self.title_language }],
+            artist: self.artist,
+            label: self.label,
+            release_date: self.release_date,
+            original_release_date: self.original_release_date,
+            upc: self.upc,
+            tracks,
+            resource_references: None,
+            resources: Vec::new(),
+            territory_details,
+            contributors: Vec::new(),
+            p_line: self.p_line,
+            c_line: self.c_line,
+            track_isrcs: Vec::new(),
+            track_releases: Vec::new(),
+            comments: Vec::new(),
+        };
+
+        Ok(BuildRequest {
+            header,
+            version: self.version.to_string(),
+            profile: Some(self.profile.to_string()),
+            releases: vec![release],
+            deals,
+            extensions: None,
+        })
+    }
+
+    /// The request, checked by preflight validation at the default level
+    /// and against its profile
+    ///
+    /// Validation errors fail with [`BuildError::ValidationFailed`]; warnings
+    /// are left for the build to report.
+    pub fn build(self) -> Result<BuildRequest, BuildError> {
+        let request = self.into_request()?;
+        let validator = PreflightValidator::new(ValidationConfig {
+            profile: request.profile.clone(),
+            ..Default::default()
+        });
+        let result = validator.validate(&request)?;
+        if !result.passed {
+            return Err(BuildError::ValidationFailed {
+                errors: result.errors.iter().map(|e| format!("{}: {}", e.code, e.message)).collect(),
+            });
+        }
+        Ok(request)
+    }
+}
+
+fn party(name: String, dpid: Option<&str>) -> PartyRequest {
+    PartyRequest {
+        party_name: vec![LocalizedStringRequest { text: name, language_code: None }],
+        party_id: dpid.map(str::to_string),
+        party_reference: None,
+    }
+}
+
+/// A track under construction, see [`ReleaseBuilder::track`]
+#[derive(Debug, Clone, Default)]
+pub struct TrackBuilder {
+    track_id: Option<String>,
+    isrc: String,
+    title: String,
+    duration: String,
+    artist: Option<String>,
+    parental_warning: Option<String>,
+    p_line: Option<PLineRequest>,
+}
+
+impl TrackBuilder {
+    /// Track title
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// ISRC of the recording
+    pub fn isrc(mut self, isrc: impl Into<String>) -> Self {
+        self.isrc = isrc.into();
+        self
+    }
+
+    /// ISO 8601 duration, e.g. `PT3M45S`
+    pub fn duration(mut self, duration: impl Into<String>) -> Self {
+        self.duration = duration.into();
+        self
+    }
+
+    /// Duration in whole seconds
+    pub fn duration_secs(mut self, seconds: u64) -> Self {
+        self.duration = DdexDuration::from(Duration::from_secs(seconds)).to_string();
+        self
+    }
+
+    /// Display artist, when it differs from the release artist
+    pub fn artist(mut self, artist: impl Into<String>) -> Self {
+        self.artist = Some(artist.into());
+        self
+    }
+
+    /// Track ID, `<release_id>-T<n>` when unset
+    pub fn track_id(mut self, track_id: impl Into<String>) -> Self {
+        self.track_id = Some(track_id.into());
+        self
+    }
+
+    /// Mark the recording as having explicit content
+    pub fn explicit(self) -> Self {
+        self.parental_warning("Explicit")
+    }
+
+    /// Parental warning type of the recording, e.g. `NotExplicit`
+    pub fn parental_warning(mut self, warning: impl Into<String>) -> Self {
+        self.parental_warning = Some(warning.into());
+        self
+    }
+
+    /// Phonographic copyright (℗) of the recording
+    pub fn p_line(mut self, year: u16, holder: &str) -> Self {
+        self.p_line = Some(PLineRequest { year: Some(year), text: format!("{} {}", year, holder) });
+        self
+    }
+}
+
+/// A deal under construction, see [`ReleaseBuilder::deal`]
+///
+/// A deal has one commercial model; the usage shortcuts set it together
+/// with its use types, so add one deal per model.
+#[derive(Debug, Clone, Default)]
+pub struct DealBuilder {
+    terms: DealTerms,
+}
+
+impl DealBuilder {
+    /// Make the deal worldwide
+    pub fn worldwide(mut self) -> Self {
+        self.terms.territory_code = vec!["Worldwide".to_string()];
+        self
+    }
+
+    /// Limit the deal to ISO 3166-1 territories, e.g. `["US", "CA"]`
+    pub fn territories<S: Into<String>>(mut self, territories: impl IntoIterator<Item = S>) -> Self {
+        self.terms.territory_code = territories.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Subscription streaming, on demand and programmed
+    pub fn streaming(self) -> Self {
+        self.usage("SubscriptionModel", &["OnDemandStream", "NonInteractiveStream"])
+    }
+
+    /// Advertisement-supported streaming, on demand and programmed
+    pub fn ad_supported_streaming(self) -> Self {
+        self.usage("AdvertisementSupportedModel", &["OnDemandStream", "NonInteractiveStream"])
+    }
+
+    /// Permanent downloads sold per unit
+    pub fn download(self) -> Self {
+        self.usage("PayAsYouGoModel", &["PermanentDownload"])
+    }
+
+    /// Any other commercial model and use types
+    pub fn usage(mut self, commercial_model_type: &str, use_types: &[&str]) -> Self {
+        self.terms.commercial_model_type = commercial_model_type.to_string();
+        self.terms.use_types = use_types.iter().map(|u| u.to_string()).collect();
+        self
+    }
+
+    /// Start of the deal (`YYYY-MM-DD`), the release date when unset
+    pub fn start_date(mut self, date: impl Into<String>) -> Self {
+        self.terms.start_date = Some(date.into());
+        self
+    }
+
+    /// End of the deal (`YYYY-MM-DD`)
+    pub fn end_date(mut self, date: impl Into<String>) -> Self {
+        self.terms.end_date = Some(date.into());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn album() -> ReleaseBuilder {
+        Ern43::album("Midnight Drive")
+            .artist("The Night Owls")
+            .upc("602445790128")
+            .release_date("2024-06-01")
+            .sender("Example Records", None)
+            .recipient("Example DSP", None)
+            .track(|t| t.title("Headlights").isrc("USRC12400001").duration_secs(215))
+            .track(|t| t.title("Exit 42").isrc("USRC12400002").duration("PT3M7S").artist("The Night Owls feat. Wren").explicit())
+    }
+
+    #[test]
+    fn test_fluent_album() {
+        let request = album()
+            .genre("Electronic")
+            .deal(|d| d.worldwide().streaming())
+            .deal(|d| d.territories(["US", "CA"]).download().start_date("2024-05-25"))
+            .build()
+            .unwrap();
+
+        assert_eq!(request.version, "4.3");
+        assert_eq!(request.profile.as_deref(), Some("AudioAlbum"));
+        let release = &request.releases[0];
+        assert_eq!(release.release_id, "602445790128");
+        assert_eq!(release.territory_details[0].genre.as_deref(), Some("Electronic"));
+
+        let tracks: Vec<_> = release.tracks.iter().map(|t| (t.track_id.as_str(), t.duration.as_str(), t.artist.as_str())).collect();
+        assert_eq!(
+            tracks,
+            [
+                ("602445790128-T1", "PT3M35S", "The Night Owls"),
+                ("602445790128-T2", "PT3M7S", "The Night Owls feat. Wren"),
+            ]
+        );
+        assert_eq!(release.tracks[1].parental_warning.as_deref(), Some("Explicit"));
+
+        let streaming = &request.deals[0].deal_terms;
+        assert_eq!(streaming.territory_code, ["Worldwide"]);
+        assert_eq!(streaming.commercial_model_type, "SubscriptionModel");
+        assert_eq!(streaming.start_date.as_deref(), Some("2024-06-01"));
+        assert_eq!(request.deals[1].deal_terms.use_types, ["PermanentDownload"]);
+        assert_eq!(request.deals[1].deal_terms.start_date.as_deref(), Some("2024-05-25"));
+        assert_eq!(request.deals[1].release_ids, ["602445790128"]);
+    }
+
+    #[test]
+    fn test_fluent_validation() {
+        let err = Ern43::single("Untitled").artist("Artist").upc("602445790128").into_request().unwrap_err();
+        assert!(matches!(err, BuildError::MissingRequired { ref field } if field == "message_sender"));
+
+        let err = album().deal(|d| d.streaming()).into_request().unwrap_err();
+        assert!(matches!(err, BuildError::MissingRequired { ref field } if field == "deals[0].territory_code"));
+
+        // Albums need a UPC; singles don't
+        let err = Ern43::album("Midnight Drive")
+            .artist("The Night Owls")
+            .release_id("R1")
+            .sender("Example Records", None)
+            .recipient("Example DSP", None)
+            .track(|t| t.title("Headlights").isrc("USRC12400001").duration_secs(215))
+            .build();
+        let Err(BuildError::ValidationFailed { errors }) = err else { panic!("expected validation errors") };
+        assert!(errors.iter().any(|e| e.starts_with("MISSING_UPC")));
+        let request = Ern382::single("Headlights")
+            .artist("The Night Owls")
+            .release_id("R1")
+            .sender("Example Records", None)
+            .recipient("Example DSP", None)
+            .track(|t| t.title("Headlights").isrc("USRC12400001").duration_secs(215))
+            .build()
+            .unwrap();
+        assert_eq!(request.version, "3.8.2");
+    }
+}
@@ -74,18 +74,43 @@
 pub mod xml_writer;
 pub mod optimized_xml_writer;
 
-use crate::ast::{AST, Element}; // Removed unused Node import
-use crate::builder::{BuildRequest, ReleaseRequest};
+use crate::ast::{AST, Element, Node};
+use crate::builder::{
+    AuditTrailEventRequest, BuildRequest, CLineRequest, ContributorKind, ContributorRequest, PLineRequest, PartyIdRequest,
+    ReleaseRequest, ResourceRequest, TechnicalDetailsRequest,
+    TerritoryDetailsRequest, TrackReleaseRequest,
+};
 use crate::error::BuildError;
 use indexmap::IndexMap;
+use crate::determinism::TimestampSource;
+use ddex_core::models::graph::ReleaseDateKind;
+use ddex_core::models::versions::ERNVersion;
+use ddex_core::models::{Comment, CommentPosition};
 
 pub struct ASTGenerator {
     version: String,
+    /// Party key -> PartyReference, filled per message for ERN 4
+    parties: IndexMap<String, String>,
+    timestamp_source: TimestampSource,
+    /// Emit the comments carried by releases, tracks and deals
+    preserve_comments: bool,
 }
 
 impl ASTGenerator {
     pub fn new(version: String) -> Self {
-        Self { version }
+        Self { version, parties: IndexMap::new(), timestamp_source: TimestampSource::Now, preserve_comments: false }
+    }
+    
+    /// Set where MessageCreatedDateTime comes from when the request omits it
+    pub fn with_timestamp_source(mut self, source: TimestampSource) -> Self {
+        self.timestamp_source = source;
+        self
+    }
+    
+    /// Emit request comments around and inside the elements they belong to
+    pub fn with_comments(mut self, preserve: bool) -> Self {
+        self.preserve_comments = preserve;
+        self
     }
     
     pub fn generate(&mut self, request: &BuildRequest) -> Result<AST, BuildError> {
@@ -102,42 +127,98 @@ impl ASTGenerator {
         // Add MessageHeader
         root.add_child(self.generate_message_header(request)?);
         
+        // Add PartyList (ERN 4 references contributors instead of inlining them)
+        if !self.is_ern_382() {
+            let party_list = self.generate_party_list(&request.releases);
+            if !party_list.children.is_empty() {
+                root.add_child(party_list);
+            }
+        }
+        
         // Add ResourceList
         root.add_child(self.generate_resource_list(&request.releases)?);
         
         // Add ReleaseList
         root.add_child(self.generate_release_list(&request.releases)?);
         
-        // Create namespaces map
-        let mut namespaces = IndexMap::new();
-        namespaces.insert("ern".to_string(), format!("http://ddex.net/xml/ern/{}", self.version.replace('.', "")));
-        namespaces.insert("xsi".to_string(), "http://www.w3.org/2001/XMLSchema-instance".to_string());
+        // Add DealList
+        if !request.deals.is_empty() {
+            root.add_child(self.generate_deal_list(&request.deals)?);
+        }
+        
+        Ok(AST {
+            root,
+            namespaces: self.namespaces(),
+            schema_location: None,
+        })
+    }
+    
+    /// Generate a PurgeReleaseMessage removing the request's releases from
+    /// the recipient's catalog; only their IDs and titles are sent
+    pub fn generate_purge(&mut self, request: &BuildRequest) -> Result<AST, BuildError> {
+        let mut root = Element::new("PurgeReleaseMessage");
+        root.namespace = Some("ern".to_string());
+        root.attributes.insert(
+            "MessageSchemaVersionId".to_string(),
+            format!("ern/{}", self.version),
+        );
+        root.add_child(self.generate_message_header(request)?);
+        
+        for release in &request.releases {
+            let mut purged = Element::new("PurgedRelease");
+            let mut release_id = Element::new("ReleaseId");
+            match release.upc {
+                Some(ref upc) => release_id.add_child(Element::new("ICPN").with_text(upc)),
+                None => release_id.add_child(Element::new("GRid").with_text(&release.release_id)),
+            }
+            purged.add_child(release_id);
+            for title in &release.title {
+                let mut title_elem = Element::new("Title");
+                title_elem.add_child(Element::new("TitleText").with_text(&title.text));
+                purged.add_child(title_elem);
+            }
+            root.add_child(purged);
+        }
         
         Ok(AST {
             root,
-            namespaces,
+            namespaces: self.namespaces(),
             schema_location: None,
         })
     }
     
+    fn namespaces(&self) -> IndexMap<String, String> {
+        let mut namespaces = IndexMap::new();
+        namespaces.insert("ern".to_string(), format!("http://ddex.net/xml/ern/{}", self.version.replace('.', "")));
+        namespaces.insert("xsi".to_string(), "http://www.w3.org/2001/XMLSchema-instance".to_string());
+        namespaces
+    }
+    
     fn generate_message_header(&self, request: &BuildRequest) -> Result<Element, BuildError> {
         let mut header = Element::new("MessageHeader");
         
-        // Add MessageThreadId (using MessageId for now)
+        // Add MessageThreadId (falling back to MessageId) and MessageId
+        let thread_id = request.header.message_thread_id.as_ref().or(request.header.message_id.as_ref());
+        if let Some(thread_id) = thread_id {
+            header.add_child(Element::new("MessageThreadId").with_text(thread_id));
+        }
         if let Some(ref msg_id) = request.header.message_id {
-            header.add_child(Element::new("MessageThreadId").with_text(msg_id));
             header.add_child(Element::new("MessageId").with_text(msg_id));
         }
         
-        // Add MessageCreatedDateTime - use provided timestamp or current time
-        let created_time = request.header.message_created_date_time
-            .as_ref()
-            .map(|t| t.clone())
-            .unwrap_or_else(|| chrono::Utc::now().to_rfc3339());
+        if let Some(ref file_name) = request.header.message_file_name {
+            header.add_child(Element::new("MessageFileName").with_text(file_name));
+        }
+        
+        // Add MessageCreatedDateTime - use provided timestamp or the configured source
+        let created_time = match request.header.message_created_date_time {
+            Some(ref t) => t.clone(),
+            None => self.timestamp_source.resolve()?,
+        };
         
         header.add_child(
             Element::new("MessageCreatedDateTime")
-                .with_text(created_time)
+                .with_text(&created_time)
         );
         
         // Add MessageSender
@@ -146,9 +227,53 @@ impl ASTGenerator {
         // Add MessageRecipient
         header.add_child(self.generate_party("MessageRecipient", &request.header.message_recipient)?);
         
+        // Add MessageAuditTrail, appending this build's event if requested
+        let mut events = request.header.message_audit_trail.clone();
+        if request.header.append_audit_trail_event {
+            events.push(AuditTrailEventRequest {
+                messaging_party: request.header.message_sender.clone(),
+                date_time: created_time,
+                system_id: Some(format!("ddex-builder/{}", env!("CARGO_PKG_VERSION"))),
+            });
+        }
+        if !events.is_empty() {
+            let mut audit_trail = Element::new("MessageAuditTrail");
+            for event in &events {
+                audit_trail.add_child(self.generate_audit_trail_event(event));
+            }
+            header.add_child(audit_trail);
+        }
+        
+        if let Some(ref control_type) = request.header.message_control_type {
+            header.add_child(Element::new("MessageControlType").with_text(control_type));
+        }
+        
         Ok(header)
     }
     
+    fn generate_audit_trail_event(&self, event: &AuditTrailEventRequest) -> Element {
+        let mut descriptor = Element::new("MessagingPartyDescriptor");
+        if let Some(ref party_id) = event.messaging_party.party_id {
+            descriptor.add_child(Element::new("PartyId").with_text(party_id));
+        }
+        for party_name in &event.messaging_party.party_name {
+            let mut name_elem = Element::new("PartyName");
+            if let Some(ref lang) = party_name.language_code {
+                name_elem.attributes.insert("LanguageAndScriptCode".to_string(), lang.clone());
+            }
+            name_elem.add_child(Element::new("FullName").with_text(&party_name.text));
+            descriptor.add_child(name_elem);
+        }
+        if let Some(ref system_id) = event.system_id {
+            descriptor.add_child(Element::new("TradingName").with_text(system_id));
+        }
+        
+        let mut event_elem = Element::new("MessageAuditTrailEvent");
+        event_elem.add_child(descriptor);
+        event_elem.add_child(Element::new("DateTime").with_text(&event.date_time));
+        event_elem
+    }
+    
     fn generate_party(&self, element_name: &str, party: &crate::builder::PartyRequest) -> Result<Element, BuildError> {
         let mut party_elem = Element::new(element_name);
         
@@ -206,13 +331,153 @@ impl ASTGenerator {
                     Element::new("Duration").with_text(&track.duration)
                 );
                 
-                resource_list.add_child(sound_recording);
+                for contributor in &track.contributors {
+                    if let Some(element) = self.generate_contributor(contributor) {
+                        sound_recording.add_child(element);
+                    }
+                }
+                
+                for line in Self::copyright_lines(track.p_line.as_ref(), track.c_line.as_ref()) {
+                    sound_recording.add_child(line);
+                }
+                
+                if let Some(ref warning) = track.parental_warning {
+                    sound_recording.add_child(Element::new("ParentalWarningType").with_text(warning));
+                }
+                
+                if let Some(ref details) = track.technical_details {
+                    sound_recording.add_child(self.generate_technical_details("SoundRecording", &resource_ref, details));
+                }
+                
+                self.add_with_comments(&mut resource_list, sound_recording, &track.comments);
+            }
+            
+            for resource in &release.resources {
+                resource_list.add_child(self.generate_resource(resource));
             }
         }
         
         Ok(resource_list)
     }
     
+    fn generate_resource(&self, resource: &ResourceRequest) -> Element {
+        let legacy = self.is_ern_382();
+        let kind = resource.kind.element_name();
+        let mut element = Element::new(kind);
+        
+        let resource_ref = Self::resource_reference(resource);
+        element.add_child(Element::new("ResourceReference").with_text(&resource_ref));
+        
+        // ERN 3.8.2 names the type after the resource, e.g. ImageType
+        let type_name = if legacy { format!("{}Type", kind) } else { "Type".to_string() };
+        element.add_child(Element::new(type_name).with_text(&resource.resource_type));
+        
+        if let Some(ref isrc) = resource.isrc {
+            let mut resource_id = Element::new("ResourceId");
+            resource_id.add_child(Element::new("ISRC").with_text(isrc));
+            element.add_child(resource_id);
+        }
+        
+        if let Some(ref title) = resource.title {
+            let mut ref_title = Element::new("ReferenceTitle");
+            ref_title.add_child(Element::new("TitleText").with_text(title));
+            element.add_child(ref_title);
+        }
+        
+        if let Some(ref duration) = resource.duration {
+            element.add_child(Element::new("Duration").with_text(duration));
+        }
+        
+        for line in Self::copyright_lines(resource.p_line.as_ref(), resource.c_line.as_ref()) {
+            element.add_child(line);
+        }
+        
+        if let Some(ref details) = resource.technical_details {
+            element.add_child(self.generate_technical_details(resource.kind.element_name(), &resource_ref, details));
+        }
+        
+        element
+    }
+    
+    fn generate_technical_details(
+        &self,
+        kind_name: &str,
+        resource_ref: &str,
+        details: &TechnicalDetailsRequest,
+    ) -> Element {
+        let legacy = self.is_ern_382();
+        let name = if legacy { format!("Technical{}Details", kind_name) } else { "TechnicalDetails".to_string() };
+        let mut technical = Element::new(name);
+        
+        technical.add_child(
+            Element::new("TechnicalResourceDetailsReference").with_text(format!("T{}", resource_ref))
+        );
+        
+        if let Some(ref codec) = details.codec {
+            let codec_kind = if kind_name == "SoundRecording" { "Audio" } else { kind_name };
+            technical.add_child(Element::new(format!("{}CodecType", codec_kind)).with_text(codec));
+        }
+        
+        if kind_name == "SoundRecording" {
+            if let Some(channels) = details.number_of_channels {
+                technical.add_child(Element::new("NumberOfChannels").with_text(channels.to_string()));
+            }
+            if let Some(sample_rate) = details.sample_rate {
+                technical.add_child(Element::new("SamplingRate").with_text(sample_rate.to_string()));
+            }
+            if let Some(bits) = details.bits_per_sample {
+                technical.add_child(Element::new("BitsPerSample").with_text(bits.to_string()));
+            }
+            if let Some(ref duration) = details.duration {
+                technical.add_child(Element::new("Duration").with_text(duration));
+            }
+        }
+        if matches!(kind_name, "Video" | "Image") {
+            if let Some(height) = details.height {
+                technical.add_child(Element::new("ImageHeight").with_text(height.to_string()));
+            }
+            if let Some(width) = details.width {
+                technical.add_child(Element::new("ImageWidth").with_text(width.to_string()));
+            }
+        }
+        if kind_name == "Image" {
+            if let Some(resolution) = details.resolution {
+                technical.add_child(Element::new("ImageResolution").with_text(resolution.to_string()));
+            }
+        }
+        
+        if details.file_name.is_some() || details.file_hash.is_some() || details.file_size.is_some() {
+            let mut file = Element::new("File");
+            if let Some(ref file_name) = details.file_name {
+                // ERN 4 locates files by URI rather than by name
+                let name = if legacy { "FileName" } else { "URI" };
+                file.add_child(Element::new(name).with_text(file_name));
+            }
+            if let Some(ref hash) = details.file_hash {
+                let mut hash_sum = Element::new("HashSum");
+                if legacy {
+                    hash_sum.add_child(Element::new("HashSum").with_text(&hash.value));
+                    hash_sum.add_child(Element::new("HashSumAlgorithmType").with_text(&hash.algorithm));
+                } else {
+                    hash_sum.add_child(Element::new("Algorithm").with_text(&hash.algorithm));
+                    hash_sum.add_child(Element::new("HashSumValue").with_text(&hash.value));
+                }
+                file.add_child(hash_sum);
+            }
+            if let Some(size) = details.file_size {
+                file.add_child(Element::new("FileSize").with_text(size.to_string()));
+            }
+            technical.add_child(file);
+        }
+        
+        technical
+    }
+    
+    fn resource_reference(resource: &ResourceRequest) -> String {
+        resource.resource_reference.clone()
+            .unwrap_or_else(|| format!("A{}", resource.resource_id))
+    }
+    
     fn generate_release_list(&self, releases: &[ReleaseRequest]) -> Result<Element, BuildError> {
         let mut release_list = Element::new("ReleaseList");
         
@@ -250,6 +515,15 @@ impl ASTGenerator {
             display_artist_name.add_child(Element::new("FullName").with_text(&release.artist));
             release_elem.add_child(display_artist_name);
             
+            // Releases only credit display artists
+            for contributor in &release.contributors {
+                if contributor.kind == ContributorKind::DisplayArtist {
+                    if let Some(element) = self.generate_contributor(contributor) {
+                        release_elem.add_child(element);
+                    }
+                }
+            }
+            
             // Add Label if present
             if let Some(ref label) = release.label {
                 let mut label_name = Element::new("LabelName");
@@ -264,11 +538,30 @@ impl ASTGenerator {
                 release_elem.add_child(release_id_upc);
             }
             
-            // Add ReleaseDate if present
-            if let Some(ref release_date) = release.release_date {
-                release_elem.add_child(
-                    Element::new("ReleaseDate").with_text(release_date)
-                );
+            // Add ReleaseDate and OriginalReleaseDate (GlobalReleaseDate and
+            // GlobalOriginalReleaseDate in 3.8.2) if present
+            let version = if self.is_ern_382() { ERNVersion::V3_8_2 } else { ERNVersion::V4_3 };
+            let dates = [
+                (ReleaseDateKind::ReleaseDate, &release.release_date),
+                (ReleaseDateKind::OriginalReleaseDate, &release.original_release_date),
+            ];
+            for (kind, date) in dates {
+                if let Some(date) = date {
+                    release_elem.add_child(Element::new(kind.element_name(version, false)).with_text(date));
+                }
+            }
+            
+            for line in Self::copyright_lines(release.p_line.as_ref(), release.c_line.as_ref()) {
+                release_elem.add_child(line);
+            }
+            
+            // ERN 4 marks territory overrides with ApplicableTerritoryCode
+            if !self.is_ern_382() {
+                for details in &release.territory_details {
+                    for element in self.generate_territory_overrides(details) {
+                        release_elem.add_child(element);
+                    }
+                }
             }
             
             // Add ReleaseResourceReferences
@@ -288,49 +581,895 @@ impl ASTGenerator {
                         Element::new("ReleaseResourceReference").with_text(&resource_ref)
                     );
                 }
+                for resource in &release.resources {
+                    release_elem.add_child(
+                        Element::new("ReleaseResourceReference").with_text(Self::resource_reference(resource))
+                    );
+                }
             }
             
-            release_list.add_child(release_elem);
+            // ERN 3.8.2 groups territory overrides in ReleaseDetailsByTerritory
+            if self.is_ern_382() {
+                for details in &release.territory_details {
+                    release_elem.add_child(self.generate_release_details_by_territory(details));
+                }
+            }
+            
+            self.add_with_comments(&mut release_list, release_elem, &release.comments);
+        }
+        
+        // Track releases follow all main releases
+        let isrc_refs = Self::isrc_references(releases);
+        for release in releases {
+            for track_release in &release.track_releases {
+                release_list.add_child(self.generate_track_release(track_release, &isrc_refs)?);
+            }
         }
         
         Ok(release_list)
     }
     
-    fn generate_deal_list(&self, deals: &[crate::builder::DealRequest]) -> Result<Element, BuildError> {
-        let mut deal_list = Element::new("DealList");
+    /// ISRC -> ResourceReference across every release, first occurrence wins
+    fn isrc_references(releases: &[ReleaseRequest]) -> IndexMap<&str, String> {
+        let mut refs = IndexMap::new();
+        for release in releases {
+            for track in &release.tracks {
+                let reference = track.resource_reference.clone()
+                    .unwrap_or_else(|| format!("A{}", track.track_id));
+                refs.entry(track.isrc.as_str()).or_insert(reference);
+            }
+            for resource in &release.resources {
+                if let Some(ref isrc) = resource.isrc {
+                    refs.entry(isrc.as_str()).or_insert_with(|| Self::resource_reference(resource));
+                }
+            }
+        }
+        refs
+    }
+    
+    /// ERN 4 TrackRelease, or a Release of type TrackRelease in ERN 3.8.2
+    fn generate_track_release(
+        &self,
+        track_release: &TrackReleaseRequest,
+        isrc_refs: &IndexMap<&str, String>,
+    ) -> Result<Element, BuildError> {
+        let resource_ref = isrc_refs.get(track_release.isrc.as_str())
+            .ok_or_else(|| BuildError::InvalidReference {
+                reference: track_release.isrc.clone(),
+            })?;
+        let release_ref = track_release.release_reference.clone()
+            .unwrap_or_else(|| format!("R{}", track_release.isrc));
         
-        for deal in deals {
-            let mut deal_elem = Element::new("ReleaseDeal");
+        let mut element = Element::new(if self.is_ern_382() { "Release" } else { "TrackRelease" });
+        element.add_child(Element::new("ReleaseReference").with_text(&release_ref));
+        
+        let mut release_id = Element::new("ReleaseId");
+        release_id.add_child(Element::new("ISRC").with_text(&track_release.isrc));
+        element.add_child(release_id);
+        
+        if self.is_ern_382() {
+            element.add_child(Element::new("ReleaseType").with_text("TrackRelease"));
+        }
+        element.add_child(Element::new("ReleaseResourceReference").with_text(resource_ref));
+        
+        Ok(element)
+    }
+    
+    /// Build the ERN 4 PartyList, one Party per distinct contributor written
+    /// out; indirect contributors are dropped in ERN 4 and get no Party
+    fn generate_party_list(&mut self, releases: &[ReleaseRequest]) -> Element {
+        let mut party_list = Element::new("PartyList");
+        self.parties.clear();
+        
+        let contributors = releases.iter().flat_map(|release| {
+            release.contributors.iter()
+                .chain(release.tracks.iter().flat_map(|track| track.contributors.iter()))
+        }).filter(|contributor| contributor.kind != ContributorKind::IndirectResourceContributor);
+        
+        for contributor in contributors {
+            let key = Self::party_key(contributor);
+            if self.parties.contains_key(&key) {
+                continue;
+            }
             
-            // Add DealReference if present
-            if let Some(ref deal_ref) = deal.deal_reference {
-                deal_elem.add_child(Element::new("DealReference").with_text(deal_ref));
+            let reference = format!("P{}", self.parties.len() + 1);
+            let mut party = Element::new("Party");
+            party.add_child(Element::new("PartyReference").with_text(&reference));
+            if let Some(party_id) = Self::party_id(&contributor.party_ids) {
+                party.add_child(party_id);
             }
+            let mut party_name = Element::new("PartyName");
+            party_name.add_child(Element::new("FullName").with_text(&contributor.name));
+            party.add_child(party_name);
             
-            // Add Deal terms (simplified for now)
-            let mut deal_terms = Element::new("Deal");
-            deal_terms.add_child(
-                Element::new("CommercialModelType")
-                    .with_text(&deal.deal_terms.commercial_model_type)
-            );
+            party_list.add_child(party);
+            self.parties.insert(key, reference);
+        }
+        
+        party_list
+    }
+    
+    /// Generate a contributor element, or `None` where the version has no equivalent
+    fn generate_contributor(&self, contributor: &ContributorRequest) -> Option<Element> {
+        let mut element = if self.is_ern_382() {
+            let (name, role) = match contributor.kind {
+                ContributorKind::DisplayArtist => ("DisplayArtist", "ArtistRole"),
+                ContributorKind::ResourceContributor => ("ResourceContributor", "ResourceContributorRole"),
+                ContributorKind::IndirectResourceContributor => {
+                    ("IndirectResourceContributor", "IndirectResourceContributorRole")
+                }
+            };
+            let mut element = Element::new(name);
+            let mut party_name = Element::new("PartyName");
+            party_name.add_child(Element::new("FullName").with_text(&contributor.name));
+            element.add_child(party_name);
+            for party_id in Self::legacy_party_ids(&contributor.party_ids) {
+                element.add_child(party_id);
+            }
+            element.add_child(Element::new(role).with_text(&contributor.role));
+            element
+        } else {
+            // Composers and other work contributors belong to works in ERN 4
+            let (name, reference, role) = match contributor.kind {
+                ContributorKind::DisplayArtist => ("DisplayArtist", "ArtistPartyReference", "DisplayArtistRole"),
+                ContributorKind::ResourceContributor => ("Contributor", "ContributorPartyReference", "Role"),
+                ContributorKind::IndirectResourceContributor => return None,
+            };
+            let key = Self::party_key(contributor);
+            let party_reference = self.parties.get(&key).cloned().unwrap_or_else(|| {
+                format!("P{}", key.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>())
+            });
+            let mut element = Element::new(name);
+            element.add_child(Element::new(reference).with_text(party_reference));
+            element.add_child(Element::new(role).with_text(&contributor.role));
+            element
+        };
+        
+        if let Some(sequence) = contributor.sequence {
+            element.attributes.insert("SequenceNumber".to_string(), sequence.to_string());
+        }
+        Some(element)
+    }
+    
+    /// ERN 4 PartyId: one child per identifier scheme
+    fn party_id(ids: &[PartyIdRequest]) -> Option<Element> {
+        if ids.is_empty() {
+            return None;
+        }
+        let mut party_id = Element::new("PartyId");
+        for id in ids {
+            let child = match id.namespace.to_ascii_uppercase().as_str() {
+                "ISNI" => Element::new("ISNI").with_text(&id.value),
+                "DPID" => Element::new("DPID").with_text(&id.value),
+                "IPN" => Element::new("IPN").with_text(&id.value),
+                "IPI" => Element::new("IpiNameNumber").with_text(&id.value),
+                _ => {
+                    let mut proprietary = Element::new("ProprietaryId").with_text(&id.value);
+                    proprietary.attributes.insert("Namespace".to_string(), id.namespace.clone());
+                    proprietary
+                }
+            };
+            party_id.add_child(child);
+        }
+        Some(party_id)
+    }
+    
+    /// ERN 3.8.2 PartyId: one element per identifier, flagged or namespaced
+    fn legacy_party_ids(ids: &[PartyIdRequest]) -> Vec<Element> {
+        ids.iter()
+            .map(|id| {
+                let mut party_id = Element::new("PartyId").with_text(&id.value);
+                let (attribute, value) = match id.namespace.to_ascii_uppercase().as_str() {
+                    "ISNI" => ("IsISNI", "true".to_string()),
+                    "DPID" => ("IsDPID", "true".to_string()),
+                    _ => ("Namespace", id.namespace.clone()),
+                };
+                party_id.attributes.insert(attribute.to_string(), value);
+                party_id
+            })
+            .collect()
+    }
+    
+    /// Contributors sharing an identifier (or, failing that, a name) are one party
+    fn party_key(contributor: &ContributorRequest) -> String {
+        contributor.party_ids.first()
+            .map(|id| format!("{}:{}", id.namespace.to_ascii_uppercase(), id.value))
+            .unwrap_or_else(|| contributor.name.clone())
+    }
+    
+    /// PLine and CLine elements, in schema order
+    fn copyright_lines(p_line: Option<&PLineRequest>, c_line: Option<&CLineRequest>) -> Vec<Element> {
+        let lines = [
+            p_line.map(|l| ("PLine", "PLineText", l.year, &l.text)),
+            c_line.map(|l| ("CLine", "CLineText", l.year, &l.text)),
+        ];
+        lines.into_iter()
+            .flatten()
+            .map(|(name, text_name, year, text)| {
+                let mut line = Element::new(name);
+                if let Some(year) = year {
+                    line.add_child(Element::new("Year").with_text(year.to_string()));
+                }
+                line.add_child(Element::new(text_name).with_text(text));
+                line
+            })
+            .collect()
+    }
+    
+    fn generate_release_details_by_territory(&self, details: &TerritoryDetailsRequest) -> Element {
+        let mut by_territory = Element::new("ReleaseDetailsByTerritory");
+        
+        for code in &details.territory_codes {
+            by_territory.add_child(Element::new("TerritoryCode").with_text(code));
+        }
+        
+        if let Some(ref artist) = details.display_artist {
+            by_territory.add_child(Element::new("DisplayArtistName").with_text(artist));
+        }
+        
+        if let Some(ref title) = details.title {
+            let mut title_elem = Element::new("Title");
+            title_elem.attributes.insert("TitleType".to_string(), "DisplayTitle".to_string());
+            title_elem.add_child(Element::new("TitleText").with_text(title));
+            by_territory.add_child(title_elem);
+        }
+        
+        if let Some(ref warning) = details.parental_warning {
+            by_territory.add_child(Element::new("ParentalWarningType").with_text(warning));
+        }
+        
+        if let Some(ref genre) = details.genre {
+            let mut genre_elem = Element::new("Genre");
+            genre_elem.add_child(Element::new("GenreText").with_text(genre));
+            by_territory.add_child(genre_elem);
+        }
+        
+        if let Some(ref date) = details.release_date {
+            by_territory.add_child(Element::new("ReleaseDate").with_text(date));
+        }
+        
+        by_territory
+    }
+    
+    /// ERN 4 territory overrides: one element per territory, since
+    /// ApplicableTerritoryCode holds a single code
+    fn generate_territory_overrides(&self, details: &TerritoryDetailsRequest) -> Vec<Element> {
+        let mut elements = Vec::new();
+        
+        for code in &details.territory_codes {
+            let mut overrides = Vec::new();
             
-            // Add territories
-            for territory in &deal.deal_terms.territory_code {
-                deal_terms.add_child(Element::new("TerritoryCode").with_text(territory));
+            if let Some(ref title) = details.title {
+                overrides.push(Element::new("DisplayTitleText").with_text(title));
+            }
+            if let Some(ref artist) = details.display_artist {
+                overrides.push(Element::new("DisplayArtistName").with_text(artist));
+            }
+            if let Some(ref genre) = details.genre {
+                let mut genre_elem = Element::new("Genre");
+                genre_elem.add_child(Element::new("GenreText").with_text(genre));
+                overrides.push(genre_elem);
+            }
+            if let Some(ref warning) = details.parental_warning {
+                overrides.push(Element::new("ParentalWarningType").with_text(warning));
+            }
+            if let Some(ref date) = details.release_date {
+                overrides.push(Element::new("ReleaseDate").with_text(date));
             }
             
-            deal_elem.add_child(deal_terms);
+            for mut element in overrides {
+                element.attributes.insert("ApplicableTerritoryCode".to_string(), code.clone());
+                elements.push(element);
+            }
+        }
+        
+        elements
+    }
+    
+    fn generate_deal_list(&self, deals: &[crate::builder::DealRequest]) -> Result<Element, BuildError> {
+        let mut deal_list = Element::new("DealList");
+        
+        for deal in deals {
+            let mut release_deal = Element::new("ReleaseDeal");
             
             // Add DealReleaseReferences
             for release_ref in &deal.release_references {
-                deal_elem.add_child(
+                release_deal.add_child(
                     Element::new("DealReleaseReference").with_text(release_ref)
                 );
             }
             
-            deal_list.add_child(deal_elem);
+            let mut deal_elem = Element::new("Deal");
+            
+            // Add DealReference if present
+            if let Some(ref deal_ref) = deal.deal_reference {
+                deal_elem.add_child(Element::new("DealReference").with_text(deal_ref));
+            }
+            
+            deal_elem.add_child(self.generate_deal_terms(&deal.deal_terms));
+            release_deal.add_child(deal_elem);
+            
+            self.add_with_comments(&mut deal_list, release_deal, &deal.comments);
         }
         
         Ok(deal_list)
     }
-}
\ No newline at end of file
+    
+    fn generate_deal_terms(&self, terms: &crate::builder::DealTerms) -> Element {
+        let legacy = self.is_ern_382();
+        let mut deal_terms = Element::new("DealTerms");
+        
+        if !terms.commercial_model_type.is_empty() {
+            deal_terms.add_child(
+                Element::new("CommercialModelType").with_text(&terms.commercial_model_type)
+            );
+        }
+        
+        // ERN 3.8.2 wraps use types in a Usage block
+        if !terms.use_types.is_empty() {
+            if legacy {
+                let mut usage = Element::new("Usage");
+                for use_type in &terms.use_types {
+                    usage.add_child(Element::new("UseType").with_text(use_type));
+                }
+                deal_terms.add_child(usage);
+            } else {
+                for use_type in &terms.use_types {
+                    deal_terms.add_child(Element::new("UseType").with_text(use_type));
+                }
+            }
+        }
+        
+        // ERN 4 dropped the flag; takedowns end the validity period instead
+        if legacy && terms.take_down {
+            deal_terms.add_child(Element::new("TakeDown").with_text("true"));
+        }
+        
+        // Add territories
+        for territory in &terms.territory_code {
+            deal_terms.add_child(Element::new("TerritoryCode").with_text(territory));
+        }
+        
+        // A takedown ends the validity period early
+        let end_date = match (&terms.end_date, &terms.takedown_date) {
+            (Some(end), Some(takedown)) => Some(end.min(takedown)),
+            (end, takedown) => end.as_ref().or(takedown.as_ref()),
+        };
+        if terms.start_date.is_some() || end_date.is_some() {
+            let mut validity = Element::new("ValidityPeriod");
+            if let Some(ref start) = terms.start_date {
+                validity.add_child(Element::new("StartDate").with_text(start));
+            }
+            if let Some(end) = end_date {
+                validity.add_child(Element::new("EndDate").with_text(end));
+            }
+            deal_terms.add_child(validity);
+        }
+        
+        if let Some(ref date) = terms.pre_order_release_date {
+            deal_terms.add_child(Element::new("PreOrderReleaseDate").with_text(date));
+        }
+        if let Some(ref date) = terms.pre_order_preview_date {
+            deal_terms.add_child(Element::new("PreOrderPreviewDate").with_text(date));
+        }
+        
+        for price in &terms.price_information {
+            let mut price_info = Element::new("PriceInformation");
+            if let Some(ref code) = price.price_code {
+                // ERN 4 renamed PriceType to PriceCode
+                let name = if legacy { "PriceType" } else { "PriceCode" };
+                price_info.add_child(Element::new(name).with_text(code));
+            }
+            if let Some(ref wholesale) = price.wholesale_price {
+                price_info.add_child(Self::price_element("WholesalePricePerUnit", wholesale));
+            }
+            if let Some(ref retail) = price.suggested_retail_price {
+                price_info.add_child(Self::price_element("SuggestedRetailPrice", retail));
+            }
+            deal_terms.add_child(price_info);
+        }
+        
+        deal_terms
+    }
+    
+    fn price_element(name: &str, price: &crate::builder::PriceRequest) -> Element {
+        let mut element = Element::new(name).with_text(&price.amount);
+        element.attributes.insert("CurrencyCode".to_string(), price.currency_code.clone());
+        element
+    }
+    
+    /// Add `element` to `parent` with its comments in their recorded positions
+    ///
+    /// `Before`/`After` comments become siblings of the element. Comments
+    /// inside it go to the descendant named by their xpath when the generated
+    /// element has one, and to the element itself otherwise.
+    fn add_with_comments(&self, parent: &mut Element, mut element: Element, comments: &[Comment]) {
+        if !self.preserve_comments || comments.is_empty() {
+            parent.add_child(element);
+            return;
+        }
+        
+        let mut after = Vec::new();
+        for comment in comments {
+            match comment.position {
+                CommentPosition::Before => parent.children.push(Node::Comment(comment.clone())),
+                CommentPosition::After => after.push(Node::Comment(comment.clone())),
+                CommentPosition::FirstChild | CommentPosition::LastChild | CommentPosition::Inline => {
+                    let relative = comment.xpath.as_deref().map(|xpath| Self::path_below(xpath, &element.name)).unwrap_or_default();
+                    let holder = Self::descendant_mut(&mut element, &relative);
+                    if comment.position == CommentPosition::FirstChild {
+                        // Keep leading comments in their original order
+                        let index = holder.children.iter().take_while(|n| matches!(n, Node::Comment(_))).count();
+                        holder.children.insert(index, Node::Comment(comment.clone()));
+                    } else {
+                        holder.children.push(Node::Comment(comment.clone()));
+                    }
+                }
+            }
+        }
+        
+        parent.add_child(element);
+        parent.children.extend(after);
+    }
+    
+    /// Segments of `xpath` below the first segment named `name`
+    fn path_below<'a>(xpath: &'a str, name: &str) -> Vec<&'a str> {
+        let segments: Vec<&str> = xpath.split('/').filter(|s| !s.is_empty()).collect();
+        match segments.iter().position(|s| *s == name) {
+            Some(index) => segments[index + 1..].to_vec(),
+            None => Vec::new(),
+        }
+    }
+    
+    /// Deepest element along `path` that exists below `element`
+    fn descendant_mut<'e>(element: &'e mut Element, path: &[&str]) -> &'e mut Element {
+        let Some((first, rest)) = path.split_first() else {
+            return element;
+        };
+        let index = element.children.iter().position(|n| matches!(n, Node::Element(e) if e.name == *first));
+        match index {
+            Some(index) => match &mut element.children[index] {
+                Node::Element(child) => Self::descendant_mut(child, rest),
+                _ => unreachable!(),
+            },
+            None => element,
+        }
+    }
+    
+    fn is_ern_382(&self) -> bool {
+        matches!(self.version.trim_start_matches("ern/"), "3.8.2" | "382")
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{DealTerms, FileHashRequest, PriceInformationRequest, PriceRequest, ResourceKind};
+
+    fn child_names(element: &Element) -> Vec<&str> {
+        element
+            .children
+            .iter()
+            .filter_map(|n| match n {
+                Node::Element(e) => Some(e.name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn sample_terms() -> DealTerms {
+        DealTerms {
+            commercial_model_type: "PayAsYouGoModel".to_string(),
+            territory_code: vec!["US".to_string()],
+            start_date: Some("2024-03-01".to_string()),
+            end_date: Some("2025-03-01".to_string()),
+            use_types: vec!["PermanentDownload".to_string()],
+            takedown_date: Some("2024-12-31".to_string()),
+            pre_order_release_date: Some("2024-02-01".to_string()),
+            price_information: vec![PriceInformationRequest {
+                price_code: Some("FRONT".to_string()),
+                wholesale_price: Some(PriceRequest { amount: "0.70".to_string(), currency_code: "USD".to_string() }),
+                suggested_retail_price: Some(PriceRequest { amount: "1.29".to_string(), currency_code: "USD".to_string() }),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_release_comments_placed_when_preserved() {
+        let mut release = sample_release_with_overrides();
+        release.title = vec![crate::builder::LocalizedStringRequest { text: "Album".to_string(), language_code: None }];
+        release.comments = vec![
+            Comment::new("main album".to_string(), CommentPosition::Before),
+            Comment::with_location("first".to_string(), CommentPosition::FirstChild, Some("/NewReleaseMessage/ReleaseList/Release".to_string()), None, None),
+            Comment::with_location("in title".to_string(), CommentPosition::LastChild, Some("/NewReleaseMessage/ReleaseList/Release/ReferenceTitle".to_string()), None, None),
+            Comment::new("the end".to_string(), CommentPosition::After),
+        ];
+        let releases = vec![release];
+
+        let plain = ASTGenerator::new("4.3".to_string()).generate_release_list(&releases).unwrap();
+        assert!(plain.children.iter().all(|n| matches!(n, Node::Element(_))));
+
+        let list = ASTGenerator::new("4.3".to_string()).with_comments(true).generate_release_list(&releases).unwrap();
+        let comment = |node: &Node| match node {
+            Node::Comment(c) => c.content.clone(),
+            _ => panic!("expected a comment, got {:?}", node),
+        };
+        assert_eq!(comment(&list.children[0]), "main album");
+        assert_eq!(comment(&list.children[2]), "the end");
+
+        let Node::Element(release) = &list.children[1] else { panic!("expected Release") };
+        assert_eq!(comment(&release.children[0]), "first");
+        let title = release.children.iter().find_map(|n| match n {
+            Node::Element(e) if e.name == "ReferenceTitle" => Some(e),
+            _ => None,
+        }).unwrap();
+        assert_eq!(comment(title.children.last().unwrap()), "in title");
+    }
+
+    #[test]
+    fn test_deal_terms_ern_4() {
+        let taken_down = DealTerms { take_down: true, ..sample_terms() };
+        let terms = ASTGenerator::new("4.3".to_string()).generate_deal_terms(&taken_down);
+        assert_eq!(
+            child_names(&terms),
+            vec!["CommercialModelType", "UseType", "TerritoryCode", "ValidityPeriod", "PreOrderReleaseDate", "PriceInformation"]
+        );
+
+        let Some(Node::Element(validity)) = terms.children.get(3) else { panic!("expected ValidityPeriod") };
+        let Some(Node::Element(end)) = validity.children.get(1) else { panic!("expected EndDate") };
+        assert!(matches!(&end.children[0], Node::Text(t) if t == "2024-12-31"));
+
+        let Some(Node::Element(price)) = terms.children.get(5) else { panic!("expected PriceInformation") };
+        assert_eq!(child_names(price), vec!["PriceCode", "WholesalePricePerUnit", "SuggestedRetailPrice"]);
+        let Some(Node::Element(wholesale)) = price.children.get(1) else { panic!("expected price") };
+        assert_eq!(wholesale.attributes.get("CurrencyCode").unwrap(), "USD");
+    }
+
+    #[test]
+    fn test_deal_terms_ern_382_wraps_usage() {
+        let terms = ASTGenerator::new("3.8.2".to_string()).generate_deal_terms(&sample_terms());
+        let names = child_names(&terms);
+        assert!(names.contains(&"Usage"));
+        assert!(!names.contains(&"UseType"));
+
+        let Some(Node::Element(price)) = terms.children.last() else { panic!("expected PriceInformation") };
+        assert_eq!(child_names(price)[0], "PriceType");
+
+        let taken_down = DealTerms { take_down: true, ..sample_terms() };
+        let terms = ASTGenerator::new("3.8.2".to_string()).generate_deal_terms(&taken_down);
+        assert_eq!(&child_names(&terms)[..4], ["CommercialModelType", "Usage", "TakeDown", "TerritoryCode"]);
+    }
+
+    fn sample_cover() -> ResourceRequest {
+        ResourceRequest {
+            kind: ResourceKind::Image,
+            resource_id: "IMG1".to_string(),
+            resource_reference: None,
+            resource_type: "FrontCoverImage".to_string(),
+            isrc: None,
+            title: None,
+            duration: None,
+            technical_details: Some(TechnicalDetailsRequest {
+                file_name: Some("cover.jpg".to_string()),
+                codec: Some("JPEG".to_string()),
+                width: Some(3000),
+                height: Some(3000),
+                resolution: Some(300),
+                file_hash: Some(FileHashRequest { algorithm: "MD5".to_string(), value: "abc123".to_string() }),
+                ..Default::default()
+            }),
+            p_line: None,
+            c_line: None,
+        }
+    }
+
+    #[test]
+    fn test_track_release_follows_main_release() {
+        let mut release = sample_release_with_contributors();
+        release.track_releases = vec![TrackReleaseRequest {
+            release_reference: Some("R2".to_string()),
+            isrc: "USRC17607839".to_string(),
+        }];
+
+        let list = ASTGenerator::new("4.3".to_string()).generate_release_list(&[release.clone()]).unwrap();
+        assert_eq!(child_names(&list), vec!["Release", "TrackRelease"]);
+        let Some(Node::Element(track_release)) = list.children.last() else { panic!("expected TrackRelease") };
+        assert_eq!(child_names(track_release), vec!["ReleaseReference", "ReleaseId", "ReleaseResourceReference"]);
+        let Some(Node::Element(resource_ref)) = track_release.children.last() else { panic!("expected ReleaseResourceReference") };
+        assert!(matches!(&resource_ref.children[0], Node::Text(t) if t == "AT1"));
+
+        let list = ASTGenerator::new("3.8.2".to_string()).generate_release_list(&[release.clone()]).unwrap();
+        assert_eq!(child_names(&list), vec!["Release", "Release"]);
+
+        release.track_releases[0].isrc = "GBXXX0000001".to_string();
+        assert!(ASTGenerator::new("4.3".to_string()).generate_release_list(&[release]).is_err());
+    }
+
+    #[test]
+    fn test_image_resource_ern_4() {
+        let image = ASTGenerator::new("4.3".to_string()).generate_resource(&sample_cover());
+        assert_eq!(image.name, "Image");
+        assert_eq!(child_names(&image), vec!["ResourceReference", "Type", "TechnicalDetails"]);
+
+        let Some(Node::Element(details)) = image.children.get(2) else { panic!("expected TechnicalDetails") };
+        assert_eq!(
+            child_names(details),
+            vec!["TechnicalResourceDetailsReference", "ImageCodecType", "ImageHeight", "ImageWidth", "ImageResolution", "File"]
+        );
+        let Some(Node::Element(file)) = details.children.last() else { panic!("expected File") };
+        assert_eq!(child_names(file), vec!["URI", "HashSum"]);
+
+        let mut cover = sample_cover();
+        cover.technical_details.as_mut().unwrap().file_size = Some(2_048_000);
+        let image = ASTGenerator::new("4.3".to_string()).generate_resource(&cover);
+        let Some(Node::Element(details)) = image.children.get(2) else { panic!("expected TechnicalDetails") };
+        let Some(Node::Element(file)) = details.children.last() else { panic!("expected File") };
+        assert_eq!(child_names(file), vec!["URI", "HashSum", "FileSize"]);
+    }
+
+    #[test]
+    fn test_image_resource_ern_382() {
+        let image = ASTGenerator::new("3.8.2".to_string()).generate_resource(&sample_cover());
+        assert_eq!(child_names(&image), vec!["ResourceReference", "ImageType", "TechnicalImageDetails"]);
+
+        let Some(Node::Element(details)) = image.children.get(2) else { panic!("expected TechnicalImageDetails") };
+        let Some(Node::Element(file)) = details.children.last() else { panic!("expected File") };
+        assert_eq!(child_names(file), vec!["FileName", "HashSum"]);
+    }
+
+    fn sample_release_with_overrides() -> ReleaseRequest {
+        ReleaseRequest {
+            release_id: "GRID1".to_string(),
+            release_reference: None,
+            title: vec![],
+            artist: "Artist".to_string(),
+            label: None,
+            release_date: Some("2024-03-01".to_string()),
+            original_release_date: None,
+            upc: None,
+            tracks: vec![],
+            resource_references: Some(vec![]),
+            resources: vec![],
+            territory_details: vec![TerritoryDetailsRequest {
+                territory_codes: vec!["DE".to_string(), "AT".to_string()],
+                title: Some("Titel".to_string()),
+                parental_warning: Some("Explicit".to_string()),
+                ..Default::default()
+            }],
+            contributors: vec![],
+            p_line: None,
+            c_line: None,
+            track_isrcs: vec![],
+            track_releases: vec![],
+            comments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_release_details_by_territory_ern_382() {
+        let generator = ASTGenerator::new("3.8.2".to_string());
+        let list = generator.generate_release_list(&[sample_release_with_overrides()]).unwrap();
+        let Some(Node::Element(release)) = list.children.first() else { panic!("expected Release") };
+        let Some(Node::Element(details)) = release.children.last() else { panic!("expected details") };
+
+        assert_eq!(details.name, "ReleaseDetailsByTerritory");
+        assert_eq!(child_names(details), vec!["TerritoryCode", "TerritoryCode", "Title", "ParentalWarningType"]);
+    }
+
+    #[test]
+    fn test_territory_overrides_ern_4() {
+        let generator = ASTGenerator::new("4.3".to_string());
+        let list = generator.generate_release_list(&[sample_release_with_overrides()]).unwrap();
+        let Some(Node::Element(release)) = list.children.first() else { panic!("expected Release") };
+
+        let overrides: Vec<_> = release
+            .children
+            .iter()
+            .filter_map(|n| match n {
+                Node::Element(e) => e.attributes.get("ApplicableTerritoryCode").map(|t| (e.name.as_str(), t.as_str())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            overrides,
+            vec![
+                ("DisplayTitleText", "DE"),
+                ("ParentalWarningType", "DE"),
+                ("DisplayTitleText", "AT"),
+                ("ParentalWarningType", "AT"),
+            ]
+        );
+        assert!(!child_names(release).contains(&"ReleaseDetailsByTerritory"));
+    }
+
+    #[test]
+    fn test_release_dates_per_version() {
+        let mut release = sample_release_with_overrides();
+        release.territory_details.clear();
+        release.original_release_date = Some("1979-10-12".to_string());
+        let dates = |version: &str| {
+            let list = ASTGenerator::new(version.to_string()).generate_release_list(&[release.clone()]).unwrap();
+            let Some(Node::Element(release)) = list.children.first() else { panic!("expected Release") };
+            child_names(release).into_iter().filter(|name| name.contains("ReleaseDate")).map(str::to_string).collect::<Vec<_>>()
+        };
+        assert_eq!(dates("4.3"), vec!["ReleaseDate", "OriginalReleaseDate"]);
+        assert_eq!(dates("3.8.2"), vec!["GlobalReleaseDate", "GlobalOriginalReleaseDate"]);
+    }
+
+    fn sample_release_with_contributors() -> ReleaseRequest {
+        let isni = vec![PartyIdRequest { namespace: "ISNI".to_string(), value: "0000000121032683".to_string() }];
+        let mut release = sample_release_with_overrides();
+        release.territory_details.clear();
+        release.contributors = vec![ContributorRequest {
+            name: "Main Artist".to_string(),
+            role: "MainArtist".to_string(),
+            sequence: Some(1),
+            party_ids: isni.clone(),
+            ..Default::default()
+        }];
+        release.tracks = vec![crate::builder::TrackRequest {
+            track_id: "T1".to_string(),
+            resource_reference: None,
+            isrc: "USRC17607839".to_string(),
+            title: "Song".to_string(),
+            duration: "PT3M".to_string(),
+            artist: "Main Artist".to_string(),
+            contributors: vec![
+                ContributorRequest {
+                    name: "Main Artist".to_string(),
+                    role: "MainArtist".to_string(),
+                    party_ids: isni,
+                    ..Default::default()
+                },
+                ContributorRequest {
+                    name: "A Producer".to_string(),
+                    kind: ContributorKind::ResourceContributor,
+                    role: "Producer".to_string(),
+                    ..Default::default()
+                },
+                ContributorRequest {
+                    name: "A Composer".to_string(),
+                    kind: ContributorKind::IndirectResourceContributor,
+                    role: "Composer".to_string(),
+                    ..Default::default()
+                },
+            ],
+            p_line: None,
+            c_line: None,
+            comments: Vec::new(),
+            technical_details: None,
+            parental_warning: None,
+        }];
+        release
+    }
+
+    #[test]
+    fn test_contributors_ern_4_reference_party_list() {
+        let mut generator = ASTGenerator::new("4.3".to_string());
+        let releases = vec![sample_release_with_contributors()];
+        let party_list = generator.generate_party_list(&releases);
+        assert_eq!(party_list.children.len(), 2);
+        assert!(!generator.parties.keys().any(|key| key.contains("A Composer")));
+
+        let resources = generator.generate_resource_list(&releases).unwrap();
+        let Some(Node::Element(recording)) = resources.children.first() else { panic!("expected SoundRecording") };
+        let names = child_names(recording);
+        assert!(names.contains(&"DisplayArtist"));
+        assert!(names.contains(&"Contributor"));
+        assert!(!names.contains(&"IndirectResourceContributor"));
+
+        let Some(Node::Element(display_artist)) = recording.children.get(4) else { panic!("expected DisplayArtist") };
+        assert_eq!(child_names(display_artist), vec!["ArtistPartyReference", "DisplayArtistRole"]);
+        assert!(matches!(&display_artist.children[0], Node::Element(r) if matches!(&r.children[0], Node::Text(t) if t == "P1")));
+    }
+
+    #[test]
+    fn test_contributors_ern_382_inline_parties() {
+        let generator = ASTGenerator::new("3.8.2".to_string());
+        let resources = generator.generate_resource_list(&[sample_release_with_contributors()]).unwrap();
+        let Some(Node::Element(recording)) = resources.children.first() else { panic!("expected SoundRecording") };
+        assert_eq!(
+            &child_names(recording)[4..],
+            &["DisplayArtist", "ResourceContributor", "IndirectResourceContributor"]
+        );
+
+        let Some(Node::Element(display_artist)) = recording.children.get(4) else { panic!("expected DisplayArtist") };
+        assert_eq!(child_names(display_artist), vec!["PartyName", "PartyId", "ArtistRole"]);
+        let Some(Node::Element(party_id)) = display_artist.children.get(1) else { panic!("expected PartyId") };
+        assert_eq!(party_id.attributes.get("IsISNI").unwrap(), "true");
+    }
+
+    #[test]
+    fn test_sound_recording_technical_details() {
+        let mut release = sample_release_with_contributors();
+        release.tracks[0].technical_details = Some(TechnicalDetailsRequest {
+            file_name: Some("USRC17607839.flac".to_string()),
+            codec: Some("FLAC".to_string()),
+            sample_rate: Some(44100),
+            bits_per_sample: Some(24),
+            number_of_channels: Some(2),
+            duration: Some("PT3M45.12S".to_string()),
+            ..Default::default()
+        });
+
+        let resources = ASTGenerator::new("4.3".to_string()).generate_resource_list(&[release]).unwrap();
+        let Some(Node::Element(recording)) = resources.children.first() else { panic!("expected SoundRecording") };
+        let Some(Node::Element(details)) = recording.children.last() else { panic!("expected TechnicalDetails") };
+        assert_eq!(details.name, "TechnicalDetails");
+        assert_eq!(
+            child_names(details),
+            vec!["TechnicalResourceDetailsReference", "AudioCodecType", "NumberOfChannels", "SamplingRate", "BitsPerSample", "Duration", "File"]
+        );
+    }
+
+    #[test]
+    fn test_copyright_lines() {
+        let mut release = sample_release_with_overrides();
+        release.territory_details.clear();
+        release.p_line = Some(PLineRequest { year: Some(2024), text: "2024 Example Records".to_string() });
+        release.c_line = Some(CLineRequest { year: None, text: "Example Records".to_string() });
+
+        let list = ASTGenerator::new("4.3".to_string()).generate_release_list(&[release]).unwrap();
+        let Some(Node::Element(release)) = list.children.first() else { panic!("expected Release") };
+        let lines: Vec<_> = release
+            .children
+            .iter()
+            .filter_map(|n| match n {
+                Node::Element(e) if e.name == "PLine" || e.name == "CLine" => Some(e),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(child_names(lines[0]), vec!["Year", "PLineText"]);
+        assert_eq!(child_names(lines[1]), vec!["CLineText"]);
+    }
+
+    #[test]
+    fn test_message_header_audit_trail() {
+        use crate::builder::{BuildRequest, LocalizedStringRequest, MessageHeaderRequest, PartyRequest};
+
+        let party = |id: &str, name: &str| PartyRequest {
+            party_name: vec![LocalizedStringRequest { text: name.to_string(), language_code: None }],
+            party_id: Some(id.to_string()),
+            party_reference: None,
+        };
+        let request = BuildRequest {
+            header: MessageHeaderRequest {
+                message_id: Some("MSG1".to_string()),
+                message_sender: party("PADPIDA2011072101T", "Label"),
+                message_recipient: party("PADPIDA2007040502I", "DSP"),
+                message_control_type: Some("TestMessage".to_string()),
+                message_created_date_time: Some("2024-03-01T12:00:00Z".to_string()),
+                message_thread_id: Some("THREAD1".to_string()),
+                message_file_name: Some("MSG1.xml".to_string()),
+                message_audit_trail: vec![AuditTrailEventRequest {
+                    messaging_party: party("PADPIDA2011072101T", "Distributor"),
+                    date_time: "2024-02-28T09:00:00Z".to_string(),
+                    system_id: None,
+                }],
+                append_audit_trail_event: true,
+            },
+            version: "4.3".to_string(),
+            profile: None,
+            releases: vec![],
+            deals: vec![],
+            extensions: None,
+        };
+
+        let header = ASTGenerator::new("4.3".to_string()).generate_message_header(&request).unwrap();
+        assert_eq!(
+            child_names(&header),
+            vec![
+                "MessageThreadId", "MessageId", "MessageFileName", "MessageCreatedDateTime",
+                "MessageSender", "MessageRecipient", "MessageAuditTrail", "MessageControlType",
+            ]
+        );
+
+        let Some(Node::Element(trail)) = header.children.get(6) else { panic!("expected MessageAuditTrail") };
+        assert_eq!(trail.children.len(), 2);
+        let Some(Node::Element(appended)) = trail.children.last() else { panic!("expected event") };
+        let Some(Node::Element(descriptor)) = appended.children.first() else { panic!("expected descriptor") };
+        assert_eq!(child_names(descriptor), vec!["PartyId", "PartyName", "TradingName"]);
+        assert!(matches!(&appended.children[1], Node::Element(d) if matches!(&d.children[0], Node::Text(t) if t == "2024-03-01T12:00:00Z")));
+    }
+}
@@ -1,21 +1,30 @@
 //! XML serialization from AST
 
 use crate::ast::{AST, Element, Node};
+use crate::build_cache::{BuildCache, CACHED_LISTS};
 use crate::determinism::{DeterminismConfig, IndentChar};
 use ddex_core::models::CommentPosition;  // Fixed import
 use crate::error::BuildError;
 use indexmap::IndexMap;
 use std::io::Write;
+use std::sync::Arc;
 
 /// XML Writer for converting AST to XML string
 pub struct XmlWriter {
     config: DeterminismConfig,
+    cache: Option<Arc<BuildCache>>,
 }
 
 impl XmlWriter {
     /// Create a new XML writer
     pub fn new(config: DeterminismConfig) -> Self {
-        Self { config }
+        Self { config, cache: None }
+    }
+    
+    /// Reuse serialized release, resource and deal entries from `cache`
+    pub fn with_cache(mut self, cache: Option<Arc<BuildCache>>) -> Self {
+        self.cache = cache;
+        self
     }
     
     /// Write AST to XML string
@@ -98,9 +107,12 @@ impl XmlWriter {
                 // Write children
                 for child in &element.children {
                     match child {
-                        Node::Element(child_elem) => {
-                            self.write_element(writer, child_elem, namespaces, None, depth + 1)?;
-                        }
+                        Node::Element(child_elem) => match &self.cache {
+                            Some(cache) if CACHED_LISTS.contains(&element.name.as_str()) => {
+                                self.write_cached(writer, cache, child_elem, depth + 1)?;
+                            }
+                            _ => self.write_element(writer, child_elem, namespaces, None, depth + 1)?,
+                        },
                         Node::Text(text) => {
                             let child_indent = self.get_indent(depth + 1);
                             writeln!(writer, "{}{}", child_indent, self.escape_text(text))?;
@@ -123,6 +135,26 @@ impl XmlWriter {
         Ok(())
     }
     
+    /// Write a list entry from the cache, serializing and storing it on a miss
+    fn write_cached(
+        &self,
+        writer: &mut impl Write,
+        cache: &BuildCache,
+        element: &Element,
+        depth: usize,
+    ) -> Result<(), BuildError> {
+        let key = BuildCache::key(element, depth, &self.config);
+        if let Some(bytes) = cache.get(&key) {
+            writer.write_all(&bytes)?;
+            return Ok(());
+        }
+        let mut buffer = Vec::new();
+        self.write_element(&mut buffer, element, &IndexMap::new(), None, depth)?;
+        cache.put(&key, &buffer)?;
+        writer.write_all(&buffer)?;
+        Ok(())
+    }
+    
     fn get_indent(&self, depth: usize) -> String {
         let indent_char = match self.config.indent_char {
             IndentChar::Space => " ",  // Fixed: removed super::determinism::
@@ -153,12 +185,10 @@ impl XmlWriter {
         depth: usize,
     ) -> Result<(), BuildError> {
         let indent = match comment.position {
-            CommentPosition::Before | CommentPosition::After => {
-                // Comments at element level use element indentation
-                self.get_indent(depth.saturating_sub(1))
-            }
-            CommentPosition::FirstChild | CommentPosition::LastChild => {
-                // Comments inside elements use child indentation
+            CommentPosition::Before | CommentPosition::After
+            | CommentPosition::FirstChild | CommentPosition::LastChild => {
+                // Comments are siblings of the nodes they are stored among,
+                // whichever element they describe
                 self.get_indent(depth)
             }
             CommentPosition::Inline => {
@@ -0,0 +1,226 @@
+//! Genre mapping between DDEX genres and DSP taxonomies
+//!
+//! DDEX leaves `GenreText` free, but platforms file releases under their own
+//! vocabularies: Apple Music genre IDs, Spotify recommendation seeds and
+//! Amazon browse nodes. [`map_genre`] translates a genre between any two of
+//! them using one table of common genres; genres a platform has no
+//! counterpart for map to `None`.
+//!
+//! A preset selects the form its platform expects with a `Genre` entry in
+//! [`format_overrides`](crate::presets::PartnerPreset::format_overrides)
+//! (e.g. `"amazon"`); builds with
+//! [`BuildOptions::genre_taxonomy`](crate::builder::BuildOptions::genre_taxonomy)
+//! set rewrite the request's genres with [`map_request_genres`].
+//!
+//! The table is community-maintained from public documentation, not an
+//! official specification of any platform; verify identifiers with your
+//! partner contact.
+
+use crate::builder::BuildRequest;
+use crate::error::BuildWarning;
+use crate::presets::PartnerPreset;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A genre vocabulary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GenreTaxonomy {
+    /// DDEX `GenreText` as commonly delivered, e.g. `Hip-Hop/Rap`
+    Ddex,
+    /// Apple Music genre IDs, e.g. `18`
+    Apple,
+    /// Spotify recommendation seed genres, e.g. `hip-hop`
+    Spotify,
+    /// Amazon Music browse node IDs, e.g. `38`
+    Amazon,
+}
+
+impl GenreTaxonomy {
+    /// The taxonomy a preset asks for through its `Genre` format override
+    pub fn for_preset(preset: &PartnerPreset) -> Option<Self> {
+        preset.format_overrides.get("Genre").and_then(|name| name.parse().ok())
+    }
+}
+
+impl fmt::Display for GenreTaxonomy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            GenreTaxonomy::Ddex => "ddex",
+            GenreTaxonomy::Apple => "apple",
+            GenreTaxonomy::Spotify => "spotify",
+            GenreTaxonomy::Amazon => "amazon",
+        })
+    }
+}
+
+impl FromStr for GenreTaxonomy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "ddex" => Ok(GenreTaxonomy::Ddex),
+            "apple" => Ok(GenreTaxonomy::Apple),
+            "spotify" => Ok(GenreTaxonomy::Spotify),
+            "amazon" => Ok(GenreTaxonomy::Amazon),
+            _ => Err(format!("Unknown genre taxonomy '{}'; expected ddex, apple, spotify or amazon", s)),
+        }
+    }
+}
+
+/// One genre in every taxonomy
+struct GenreRow {
+    /// DDEX names, the preferred one first
+    ddex: &'static [&'static str],
+    apple: Option<&'static str>,
+    spotify: Option<&'static str>,
+    amazon: Option<&'static str>,
+}
+
+const fn row(
+    ddex: &'static [&'static str],
+    apple: Option<&'static str>,
+    spotify: Option<&'static str>,
+    amazon: Option<&'static str>,
+) -> GenreRow {
+    GenreRow { ddex, apple, spotify, amazon }
+}
+
+const GENRES: &[GenreRow] = &[
+    row(&["Pop"], Some("14"), Some("pop"), Some("37")),
+    row(&["Rock"], Some("21"), Some("rock"), Some("40")),
+    row(&["Alternative", "Alternative Rock", "Indie"], Some("20"), Some("alternative"), Some("30")),
+    row(&["Hip-Hop/Rap", "Hip-Hop", "Hip Hop", "Rap"], Some("18"), Some("hip-hop"), Some("38")),
+    row(&["R&B/Soul", "R&B", "RnB", "Soul"], Some("15"), Some("r-n-b"), Some("39")),
+    row(&["Dance"], Some("17"), Some("dance"), Some("7")),
+    row(&["Electronic", "Electronica"], Some("7"), Some("electronic"), Some("7")),
+    row(&["Jazz"], Some("11"), Some("jazz"), Some("34")),
+    row(&["Classical"], Some("5"), Some("classical"), Some("85")),
+    row(&["Country"], Some("6"), Some("country"), Some("16")),
+    row(&["Blues"], Some("2"), Some("blues"), Some("31")),
+    row(&["Folk"], Some("1289"), Some("folk"), Some("32")),
+    row(&["Singer/Songwriter"], Some("10"), Some("singer-songwriter"), Some("32")),
+    row(&["Latin"], Some("12"), Some("latin"), Some("289122")),
+    row(&["Reggae"], Some("24"), Some("reggae"), None),
+    row(&["Metal", "Heavy Metal"], Some("1153"), Some("metal"), None),
+    row(&["Soundtrack"], Some("16"), Some("soundtracks"), Some("42")),
+    row(&["World", "World Music"], Some("19"), Some("world-music"), Some("33")),
+    row(&["New Age"], Some("13"), Some("new-age"), Some("36")),
+    row(&["Children's Music", "Children"], Some("4"), Some("children"), Some("173425")),
+    row(&["Christian & Gospel", "Gospel", "Christian"], Some("22"), Some("gospel"), Some("173429")),
+    row(&["Vocal"], Some("23"), None, Some("265640")),
+    row(&["Holiday", "Christmas"], Some("8"), Some("holidays"), None),
+    row(&["Comedy"], Some("3"), Some("comedy"), None),
+    row(&["K-Pop"], Some("51"), Some("k-pop"), None),
+    row(&["J-Pop"], Some("27"), Some("j-pop"), None),
+];
+
+impl GenreRow {
+    fn matches(&self, taxonomy: GenreTaxonomy, value: &str) -> bool {
+        match taxonomy {
+            GenreTaxonomy::Ddex => self.ddex.iter().any(|name| name.eq_ignore_ascii_case(value)),
+            _ => self.get(taxonomy).is_some_and(|code| code.eq_ignore_ascii_case(value)),
+        }
+    }
+
+    fn get(&self, taxonomy: GenreTaxonomy) -> Option<&'static str> {
+        match taxonomy {
+            GenreTaxonomy::Ddex => self.ddex.first().copied(),
+            GenreTaxonomy::Apple => self.apple,
+            GenreTaxonomy::Spotify => self.spotify,
+            GenreTaxonomy::Amazon => self.amazon,
+        }
+    }
+}
+
+/// Translate `value` from the `source` taxonomy to the `target` one
+///
+/// DDEX names are matched without regard to case and include common
+/// variants (`Hip Hop`, `R&B`); mapping to DDEX gives the preferred name.
+/// Where several genres share a code (Apple's Dance and Electronic both map
+/// to Amazon's Dance & Electronic), mapping the code back gives the first.
+pub fn map_genre(source: GenreTaxonomy, target: GenreTaxonomy, value: &str) -> Option<&'static str> {
+    let value = value.trim();
+    GENRES
+        .iter()
+        .find(|row| row.matches(source, value))
+        .and_then(|row| row.get(target))
+}
+
+/// Rewrite the DDEX genres of `request` into `target`, warning about genres
+/// the table can't map; values already in the target form are kept
+pub fn map_request_genres(request: &mut BuildRequest, target: GenreTaxonomy, warnings: &mut Vec<BuildWarning>) {
+    if target == GenreTaxonomy::Ddex {
+        return;
+    }
+    for (r, release) in request.releases.iter_mut().enumerate() {
+        for (t, details) in release.territory_details.iter_mut().enumerate() {
+            let Some(genre) = details.genre.as_mut() else { continue };
+            if GENRES.iter().any(|row| row.matches(target, genre)) {
+                continue;
+            }
+            match map_genre(GenreTaxonomy::Ddex, target, genre) {
+                Some(mapped) => *genre = mapped.to_string(),
+                None => warnings.push(BuildWarning {
+                    code: "UNMAPPED_GENRE".to_string(),
+                    message: format!("Genre '{}' has no {} equivalent and is sent unchanged", genre, target),
+                    location: Some(format!("/releases[{}]/territory_details[{}]/genre", r, t)),
+                    suggestion: Some(format!("Use a genre with a {} equivalent, or the {} value itself", target, target)),
+                }),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_genre() {
+        use GenreTaxonomy::*;
+        assert_eq!(map_genre(Ddex, Apple, "hip hop"), Some("18"));
+        assert_eq!(map_genre(Ddex, Spotify, "R&B"), Some("r-n-b"));
+        assert_eq!(map_genre(Apple, Amazon, "14"), Some("37"));
+        assert_eq!(map_genre(Amazon, Ddex, "7"), Some("Dance"));
+        assert_eq!(map_genre(Spotify, Ddex, "world-music"), Some("World"));
+        assert_eq!(map_genre(Ddex, Amazon, "Reggae"), None);
+        assert_eq!(map_genre(Ddex, Apple, "Polka"), None);
+        assert_eq!("Amazon".parse::<GenreTaxonomy>(), Ok(Amazon));
+        assert!("deezer".parse::<GenreTaxonomy>().is_err());
+    }
+
+    #[test]
+    fn test_map_request_genres() {
+        let mut request: BuildRequest = serde_json::from_value(serde_json::json!({
+            "header": {
+                "message_sender": { "party_name": [{ "text": "Label" }] },
+                "message_recipient": { "party_name": [{ "text": "DSP" }] }
+            },
+            "version": "4.3",
+            "releases": [{
+                "release_id": "R1",
+                "title": [{ "text": "Album" }],
+                "artist": "Artist",
+                "territory_details": [
+                    { "territory_codes": ["US"], "genre": "Jazz" },
+                    { "territory_codes": ["GB"], "genre": "37" },
+                    { "territory_codes": ["JP"], "genre": "K-Pop" }
+                ]
+            }],
+            "deals": []
+        }))
+        .unwrap();
+
+        let mut warnings = Vec::new();
+        map_request_genres(&mut request, GenreTaxonomy::Amazon, &mut warnings);
+        let genres: Vec<_> = request.releases[0].territory_details.iter().map(|d| d.genre.as_deref()).collect();
+        assert_eq!(genres, [Some("34"), Some("37"), Some("K-Pop")]);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].location.as_deref(), Some("/releases[0]/territory_details[2]/genre"));
+
+        let preset = crate::presets::amazon::amazon_album();
+        assert_eq!(GenreTaxonomy::for_preset(&preset), Some(GenreTaxonomy::Amazon));
+    }
+}
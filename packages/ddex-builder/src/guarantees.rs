@@ -146,12 +146,86 @@ pub struct GuaranteeValidationResult {
 /// Comprehensive guarantee validator
 pub struct DeterminismGuaranteeValidator {
     config: DeterminismConfig,
+    threads: usize,
+    shuffle_inputs: bool,
 }
 
 impl DeterminismGuaranteeValidator {
     /// Create a new guarantee validator
     pub fn new(config: DeterminismConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            threads: 1,
+            shuffle_inputs: false,
+        }
+    }
+
+    /// Spread the builds of [`verify`](Self::verify) over `threads` threads
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Reorder the request's maps differently for every build, so that output
+    /// depending on map iteration order shows up as a divergence
+    pub fn with_shuffled_inputs(mut self) -> Self {
+        self.shuffle_inputs = true;
+        self
+    }
+
+    /// Build the request `iterations` times and compare the outputs byte for byte
+    ///
+    /// On a mismatch the report locates the first divergent element and names
+    /// the likely source of nondeterminism.
+    pub fn verify(
+        &self,
+        request: &crate::builder::BuildRequest,
+        iterations: usize,
+    ) -> Result<DeterminismReport, BuildError> {
+        let iterations = iterations.max(1);
+        let requests: Vec<_> = (0..iterations)
+            .map(|run| match self.shuffle_inputs {
+                true => shuffled(request, run),
+                false => request.clone(),
+            })
+            .collect();
+
+        let build = |request: &crate::builder::BuildRequest| {
+            crate::Builder::with_config(self.config.clone())
+                .build_internal(request)
+                .map(|result| result.xml)
+        };
+        let outputs: Vec<String> = if self.threads > 1 {
+            let chunk = iterations.div_ceil(self.threads);
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = requests
+                    .chunks(chunk)
+                    .map(|requests| scope.spawn(move || requests.iter().map(build).collect::<Vec<_>>()))
+                    .collect();
+                handles
+                    .into_iter()
+                    .flat_map(|handle| handle.join().unwrap_or_else(|_| vec![Err(BuildError::Other("Build thread panicked".to_string()))]))
+                    .collect::<Result<_, _>>()
+            })?
+        } else {
+            requests.iter().map(build).collect::<Result<_, _>>()?
+        };
+
+        let divergence = outputs
+            .iter()
+            .enumerate()
+            .skip(1)
+            .find(|(_, output)| *output != &outputs[0])
+            .map(|(run, output)| Divergence::locate(&outputs[0], output, run));
+
+        Ok(DeterminismReport {
+            is_deterministic: divergence.is_none(),
+            iterations,
+            threads: self.threads,
+            shuffled_inputs: self.shuffle_inputs,
+            sha256: outputs.iter().map(|output| sha256(output)).collect(),
+            divergence,
+        })
     }
 
     /// Validate all guarantees for a build request
@@ -494,6 +568,192 @@ impl DeterminismGuaranteeValidator {
     }
 }
 
+/// Outcome of [`DeterminismGuaranteeValidator::verify`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeterminismReport {
+    /// Whether every build produced the same bytes
+    pub is_deterministic: bool,
+    /// Number of builds
+    pub iterations: usize,
+    /// Number of threads the builds were spread over
+    pub threads: usize,
+    /// Whether map ordering was shuffled between builds
+    pub shuffled_inputs: bool,
+    /// SHA-256 of each build's output, in run order
+    pub sha256: Vec<String>,
+    /// First build that differed from the first one
+    pub divergence: Option<Divergence>,
+}
+
+impl DeterminismReport {
+    /// Human-readable description of the outcome
+    pub fn summary(&self) -> String {
+        match &self.divergence {
+            None => format!("✓ {} builds produced identical output ({})", self.iterations, self.sha256[0]),
+            Some(divergence) => format!(
+                "✗ Build {} differs from build 1 at {}, line {}, column {}\n  build 1: {}\n  build {}: {}\n  Likely source: {}",
+                divergence.run + 1,
+                divergence.element_path,
+                divergence.line,
+                divergence.column,
+                divergence.expected,
+                divergence.run + 1,
+                divergence.actual,
+                divergence.likely_source.description(),
+            ),
+        }
+    }
+}
+
+/// Where two builds first differ
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Divergence {
+    /// Zero-based run that differed from run 0
+    pub run: usize,
+    /// Byte offset of the first difference
+    pub byte_offset: usize,
+    /// One-based line of the first difference in run 0's output
+    pub line: usize,
+    /// One-based column of the first difference in run 0's output
+    pub column: usize,
+    /// Path of the element containing the difference, e.g.
+    /// `/ern:NewReleaseMessage/MessageHeader/MessageCreatedDateTime`
+    pub element_path: String,
+    /// Line of run 0's output containing the difference
+    pub expected: String,
+    /// Same line of the divergent output
+    pub actual: String,
+    /// Most likely cause of the difference
+    pub likely_source: NondeterminismSource,
+}
+
+impl Divergence {
+    fn locate(expected: &str, actual: &str, run: usize) -> Self {
+        let byte_offset = expected
+            .bytes()
+            .zip(actual.bytes())
+            .position(|(a, b)| a != b)
+            .unwrap_or_else(|| expected.len().min(actual.len()));
+        let (line, column, expected_line) = line_at(expected, byte_offset);
+        let (_, _, actual_line) = line_at(actual, byte_offset);
+        Self {
+            run,
+            byte_offset,
+            line,
+            column,
+            element_path: element_path_at(expected, byte_offset),
+            likely_source: NondeterminismSource::classify(expected, actual, &expected_line, &actual_line),
+            expected: expected_line,
+            actual: actual_line,
+        }
+    }
+}
+
+/// Likely cause of output differing between builds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NondeterminismSource {
+    /// A timestamp taken from the clock at build time
+    Timestamp,
+    /// A randomly generated UUID, with or without hyphens
+    Uuid,
+    /// The same content in a different order, as from HashMap iteration
+    MapOrdering,
+    /// No known pattern matched
+    Unknown,
+}
+
+impl NondeterminismSource {
+    fn classify(expected: &str, actual: &str, expected_line: &str, actual_line: &str) -> Self {
+        let matches_both = |pattern: &str| {
+            regex::Regex::new(pattern).is_ok_and(|re| re.is_match(expected_line) && re.is_match(actual_line))
+        };
+        if matches_both(r"\d{4}-\d{2}-\d{2}T\d{2}:\d{2}") {
+            return Self::Timestamp;
+        }
+        if matches_both(r"(?i)[0-9a-f]{8}-?[0-9a-f]{4}-?[0-9a-f]{4}-?[0-9a-f]{4}-?[0-9a-f]{12}") {
+            return Self::Uuid;
+        }
+        let mut expected_bytes = expected.as_bytes().to_vec();
+        let mut actual_bytes = actual.as_bytes().to_vec();
+        expected_bytes.sort_unstable();
+        actual_bytes.sort_unstable();
+        if expected_bytes == actual_bytes {
+            Self::MapOrdering
+        } else {
+            Self::Unknown
+        }
+    }
+
+    /// What to change to remove this source
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Timestamp => "timestamp taken at build time; use a fixed TimestampSource or SOURCE_DATE_EPOCH",
+            Self::Uuid => "random UUID; use content-based or sequential IDs",
+            Self::MapOrdering => "content reordered between builds; iterate IndexMaps or sort before writing",
+            Self::Unknown => "no known pattern; compare the two lines above",
+        }
+    }
+}
+
+/// Rotate and alternately reverse the request's maps, differently per run
+fn shuffled(request: &crate::builder::BuildRequest, run: usize) -> crate::builder::BuildRequest {
+    let mut request = request.clone();
+    if let Some(extensions) = request.extensions.as_mut() {
+        let mut entries: Vec<_> = extensions.drain(..).collect();
+        if !entries.is_empty() {
+            let len = entries.len();
+            entries.rotate_left(run % len);
+            if run % 2 == 1 {
+                entries.reverse();
+            }
+        }
+        extensions.extend(entries);
+    }
+    request
+}
+
+/// One-based line and column of a byte offset, with the line's text
+fn line_at(text: &str, offset: usize) -> (usize, usize, String) {
+    let offset = offset.min(text.len());
+    let start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let end = text[offset..].find('\n').map_or(text.len(), |i| offset + i);
+    let line = text[..offset].matches('\n').count() + 1;
+    (line, offset - start + 1, text[start..end].trim().to_string())
+}
+
+/// Path of the element whose markup or content contains the byte offset
+fn element_path_at(xml: &str, offset: usize) -> String {
+    use quick_xml::events::Event;
+
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut stack: Vec<String> = Vec::new();
+    loop {
+        let event = match reader.read_event() {
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(event) => event,
+        };
+        let ends_past_offset = reader.buffer_position() as usize > offset;
+        match event {
+            Event::Start(e) | Event::Empty(e) if ends_past_offset => {
+                stack.push(String::from_utf8_lossy(e.name().as_ref()).into_owned());
+                break;
+            }
+            _ if ends_past_offset => break,
+            Event::Start(e) => stack.push(String::from_utf8_lossy(e.name().as_ref()).into_owned()),
+            Event::End(_) => {
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+    format!("/{}", stack.join("/"))
+}
+
+fn sha256(data: &str) -> String {
+    use sha2::{Digest, Sha256};
+    format!("{:x}", Sha256::digest(data.as_bytes()))
+}
+
 /// Validate that HashMap/HashSet are not used in output code paths
 pub fn validate_no_hashmap_usage() -> Result<(), BuildError> {
     // This would typically be enforced by clippy rules in clippy.toml
@@ -581,4 +841,65 @@ impl GuaranteeReport {
             }
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::determinism::TimestampSource;
+
+    #[test]
+    fn test_divergence_diagnostics() {
+        let expected = "<?xml version=\"1.0\"?>\n<ern:NewReleaseMessage>\n  <MessageHeader>\n    \
+            <MessageCreatedDateTime>2024-01-01T00:00:00Z</MessageCreatedDateTime>\n  </MessageHeader>\n</ern:NewReleaseMessage>\n";
+        let actual = expected.replace("00:00:00Z", "00:00:01Z");
+        let divergence = Divergence::locate(expected, &actual, 2);
+        assert_eq!(divergence.element_path, "/ern:NewReleaseMessage/MessageHeader/MessageCreatedDateTime");
+        assert_eq!((divergence.line, divergence.likely_source), (4, NondeterminismSource::Timestamp));
+        assert_eq!(divergence.actual, "<MessageCreatedDateTime>2024-01-01T00:00:01Z</MessageCreatedDateTime>");
+
+        let reference = |id: &str| format!("<a>\n<ResourceReference>A{}</ResourceReference>\n</a>", id);
+        let divergence = Divergence::locate(
+            &reference("54fa3172871045ad9d5af46a3ceddc2f"),
+            &reference("5ead0606cea4412398636cca8b4807dc"),
+            1,
+        );
+        assert_eq!(divergence.likely_source, NondeterminismSource::Uuid);
+
+        let reordered = expected.replace("<MessageHeader>", "<MessageHeadre>");
+        let divergence = Divergence::locate(expected, &reordered, 1);
+        assert_eq!(divergence.element_path, "/ern:NewReleaseMessage/MessageHeader");
+        assert_eq!(divergence.likely_source, NondeterminismSource::MapOrdering);
+    }
+
+    #[test]
+    fn test_verify_builds_identically() {
+        let config = DeterminismConfig {
+            timestamp_source: TimestampSource::Fixed("2024-01-01T00:00:00Z".to_string()),
+            ..Default::default()
+        };
+        let request: crate::builder::BuildRequest = serde_json::from_value(serde_json::json!({
+            "header": {
+                "message_id": "MSG-1",
+                "message_sender": { "party_name": [{ "text": "Sender" }] },
+                "message_recipient": { "party_name": [{ "text": "Recipient" }] },
+            },
+            "version": "4.3",
+            "releases": [{
+                "release_id": "R1",
+                "release_reference": "R1",
+                "title": [{ "text": "Album" }],
+                "artist": "Artist",
+                "tracks": [{ "track_id": "T1", "resource_reference": "A1", "isrc": "USRC17607839", "title": "Song", "duration": "PT3M", "artist": "Artist" }],
+            }],
+            "extensions": { "b": "2", "a": "1", "c": "3" },
+        }))
+        .unwrap();
+        let report = DeterminismGuaranteeValidator::new(config)
+            .with_threads(3)
+            .with_shuffled_inputs()
+            .verify(&request, 5)
+            .unwrap();
+        assert!(report.is_deterministic, "{}", report.summary());
+        assert_eq!(report.sha256.len(), 5);
+    }
+}
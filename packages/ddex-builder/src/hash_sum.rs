@@ -0,0 +1,240 @@
+//! HashSum and FileSize for the files referenced by technical details
+//!
+//! Files are read in fixed-size chunks, so multi-gigabyte WAV and video
+//! masters are hashed in constant memory. [`fill_hash_sums`] can spread the
+//! files of a request over rayon's thread pool.
+//!
+//! ```no_run
+//! use ddex_builder::hash_sum::{fill_hash_sums, HashSumOptions};
+//! # fn request() -> ddex_builder::BuildRequest { unimplemented!() }
+//!
+//! let mut request = request();
+//! let options = HashSumOptions {
+//!     base_dir: Some("delivery/resources".into()),
+//!     parallel: true,
+//!     ..Default::default()
+//! };
+//! let hashed = fill_hash_sums(&mut request, &options)?;
+//! # Ok::<(), ddex_builder::BuildError>(())
+//! ```
+
+use crate::builder::{BuildRequest, FileHashRequest, TechnicalDetailsRequest};
+use crate::error::BuildError;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::digest::DynDigest;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Size of the chunks files are read in
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Digest algorithm of a HashSum
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HashSumAlgorithm {
+    /// MD5, still required by some older ingestion pipelines
+    Md5,
+    /// SHA-256
+    #[default]
+    Sha256,
+}
+
+impl HashSumAlgorithm {
+    /// `HashSumAlgorithmType` value written to the message
+    pub fn ddex_name(self) -> &'static str {
+        match self {
+            HashSumAlgorithm::Md5 => "MD5",
+            HashSumAlgorithm::Sha256 => "SHA256",
+        }
+    }
+
+    fn hasher(self) -> Box<dyn DynDigest> {
+        match self {
+            HashSumAlgorithm::Md5 => Box::new(md5::Md5::default()),
+            HashSumAlgorithm::Sha256 => Box::new(sha2::Sha256::default()),
+        }
+    }
+}
+
+/// Digest and size of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDigest {
+    /// Algorithm of `value`
+    pub algorithm: HashSumAlgorithm,
+    /// Lowercase hex digest
+    pub value: String,
+    /// Size in bytes
+    pub size: u64,
+}
+
+/// Hash the file at `path`, reading it in chunks
+pub fn digest_file(path: impl AsRef<Path>, algorithm: HashSumAlgorithm) -> Result<FileDigest, BuildError> {
+    let path = path.as_ref();
+    let invalid = |e: std::io::Error| BuildError::InvalidFormat {
+        field: path.display().to_string(),
+        message: e.to_string(),
+    };
+
+    let mut file = File::open(path).map_err(invalid)?;
+    let mut hasher = algorithm.hasher();
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut size = 0u64;
+    loop {
+        let read = file.read(&mut buffer).map_err(invalid)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        size += read as u64;
+    }
+    Ok(FileDigest {
+        algorithm,
+        value: hex::encode(hasher.finalize()),
+        size,
+    })
+}
+
+/// Options for [`fill_hash_sums`]
+#[derive(Debug, Clone, Default)]
+pub struct HashSumOptions {
+    /// Digest algorithm
+    pub algorithm: HashSumAlgorithm,
+    /// Directory relative file names are resolved against, instead of the
+    /// working directory
+    pub base_dir: Option<PathBuf>,
+    /// Hash files on rayon's thread pool
+    pub parallel: bool,
+    /// Replace hashes and sizes the request already carries
+    pub overwrite: bool,
+}
+
+/// Fill `file_hash` and `file_size` of every track and resource whose
+/// technical details name a local file, returning how many were hashed
+///
+/// Remote URIs are skipped; a missing or unreadable file is an error.
+pub fn fill_hash_sums(request: &mut BuildRequest, options: &HashSumOptions) -> Result<usize, BuildError> {
+    let mut targets: Vec<(PathBuf, &mut TechnicalDetailsRequest)> = request.releases.iter_mut()
+        .flat_map(|release| {
+            let tracks = release.tracks.iter_mut().filter_map(|t| t.technical_details.as_mut());
+            let resources = release.resources.iter_mut().filter_map(|r| r.technical_details.as_mut());
+            tracks.chain(resources)
+        })
+        .filter(|details| options.overwrite || details.file_hash.is_none() || details.file_size.is_none())
+        .filter_map(|details| Some((local_path(details.file_name.as_deref()?, options)?, details)))
+        .collect();
+
+    let hash = |(path, details): &mut (PathBuf, &mut TechnicalDetailsRequest)| -> Result<(), BuildError> {
+        let digest = digest_file(&*path, options.algorithm)?;
+        details.file_hash = Some(FileHashRequest {
+            algorithm: digest.algorithm.ddex_name().to_string(),
+            value: digest.value,
+        });
+        details.file_size = Some(digest.size);
+        Ok(())
+    };
+    if options.parallel {
+        targets.par_iter_mut().try_for_each(hash)?;
+    } else {
+        targets.iter_mut().try_for_each(hash)?;
+    }
+    Ok(targets.len())
+}
+
+/// Path of a local file name or `file://` URI, `None` for remote URIs
+fn local_path(file_name: &str, options: &HashSumOptions) -> Option<PathBuf> {
+    let file_name = file_name.strip_prefix("file://").unwrap_or(file_name);
+    if file_name.contains("://") {
+        return None;
+    }
+    let path = Path::new(file_name);
+    Some(match &options.base_dir {
+        Some(base) if path.is_relative() => base.join(path),
+        _ => path.to_path_buf(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("abc.txt");
+        std::fs::write(&path, "abc").unwrap();
+
+        let md5 = digest_file(&path, HashSumAlgorithm::Md5).unwrap();
+        assert_eq!(md5.value, "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(md5.size, 3);
+        let sha256 = digest_file(&path, HashSumAlgorithm::Sha256).unwrap();
+        assert_eq!(sha256.value, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+
+        // Files larger than one chunk hash the same as read at once
+        let large = vec![7u8; CHUNK_SIZE * 3 + 5];
+        std::fs::write(&path, &large).unwrap();
+        let digest = digest_file(&path, HashSumAlgorithm::Sha256).unwrap();
+        assert_eq!(digest.value, hex::encode(<sha2::Sha256 as sha2::Digest>::digest(&large)));
+        assert_eq!(digest.size, large.len() as u64);
+    }
+
+    #[test]
+    fn test_fill_hash_sums() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("track1.wav"), "abc").unwrap();
+        std::fs::write(dir.path().join("cover.jpg"), "").unwrap();
+
+        let mut request: BuildRequest = serde_json::from_value(serde_json::json!({
+            "header": {
+                "message_sender": { "party_name": [{ "text": "Sender" }] },
+                "message_recipient": { "party_name": [{ "text": "Recipient" }] }
+            },
+            "version": "4.3",
+            "releases": [{
+                "release_id": "R1",
+                "title": [{ "text": "Album" }],
+                "artist": "Artist",
+                "tracks": [
+                    {
+                        "track_id": "T1", "isrc": "USRC17607839", "title": "One", "duration": "PT3M", "artist": "Artist",
+                        "technical_details": { "file_name": "track1.wav" }
+                    },
+                    {
+                        "track_id": "T2", "isrc": "USRC17607840", "title": "Two", "duration": "PT3M", "artist": "Artist",
+                        "technical_details": { "file_name": "https://cdn.example.com/track2.wav" }
+                    }
+                ],
+                "resources": [{
+                    "kind": "Image", "resource_id": "IMG1", "resource_type": "FrontCoverImage",
+                    "technical_details": { "file_name": "cover.jpg" }
+                }]
+            }],
+            "deals": [],
+            "extensions": null
+        }))
+        .unwrap();
+
+        let options = HashSumOptions {
+            algorithm: HashSumAlgorithm::Md5,
+            base_dir: Some(dir.path().to_path_buf()),
+            parallel: true,
+            overwrite: false,
+        };
+        assert_eq!(fill_hash_sums(&mut request, &options).unwrap(), 2);
+
+        let release = &request.releases[0];
+        let track = release.tracks[0].technical_details.as_ref().unwrap();
+        assert_eq!(track.file_size, Some(3));
+        let hash = track.file_hash.as_ref().unwrap();
+        assert_eq!((hash.algorithm.as_str(), hash.value.as_str()), ("MD5", "900150983cd24fb0d6963f7d28e17f72"));
+        assert!(release.tracks[1].technical_details.as_ref().unwrap().file_hash.is_none());
+        assert_eq!(release.resources[0].technical_details.as_ref().unwrap().file_size, Some(0));
+
+        // Already hashed files are left alone unless overwriting
+        assert_eq!(fill_hash_sums(&mut request, &options).unwrap(), 0);
+
+        std::fs::remove_file(dir.path().join("track1.wav")).unwrap();
+        let options = HashSumOptions { overwrite: true, ..options };
+        assert!(fill_hash_sums(&mut request, &options).is_err());
+    }
+}
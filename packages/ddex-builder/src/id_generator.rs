@@ -4,8 +4,15 @@
 use sha2::{Sha256, Digest};
 use blake3;
 use serde::{Deserialize, Serialize};
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
 use unicode_normalization::UnicodeNormalization;
+use std::sync::Arc;
+use crate::builder::IdStrategy;
+use crate::determinism::DeterminismConfig;
+use crate::error::BuildError;
+
+/// Number of hash characters kept in content-addressed references
+const REFERENCE_HASH_LENGTH: usize = 16;
 
 /// Stable hash configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -151,6 +158,45 @@ impl StableHashGenerator {
         self.generate("Party", &materials)
     }
     
+    /// Generate a content-addressed ReleaseReference (`R` + hash of UPC and title)
+    ///
+    /// Unlike counter-based references, the result does not change when other
+    /// releases are added or reordered.
+    pub fn generate_release_reference(
+        &mut self,
+        upc: &str,
+        title: &str,
+    ) -> Result<String, super::error::BuildError> {
+        let materials = ReferenceHashMaterials {
+            identifier: upc.to_string(),
+            title: title.to_string(),
+        };
+        
+        let hash = self.generate("ReleaseReference", &materials)?;
+        Ok(Self::to_reference("R", &hash))
+    }
+    
+    /// Generate a content-addressed ResourceReference (`A` + hash of ISRC and title)
+    pub fn generate_resource_reference(
+        &mut self,
+        isrc: &str,
+        title: &str,
+    ) -> Result<String, super::error::BuildError> {
+        let materials = ReferenceHashMaterials {
+            identifier: isrc.to_string(),
+            title: title.to_string(),
+        };
+        
+        let hash = self.generate("ResourceReference", &materials)?;
+        Ok(Self::to_reference("A", &hash))
+    }
+    
+    /// Turn a prefixed hash (`B3:...`) into a schema-valid reference
+    fn to_reference(prefix: &str, hash: &str) -> String {
+        let digest = hash.split_once(':').map(|(_, d)| d).unwrap_or(hash);
+        format!("{}{}", prefix, &digest[..REFERENCE_HASH_LENGTH.min(digest.len())])
+    }
+    
     /// Generic stable ID generation
     fn generate<T: Serialize>(
         &mut self,
@@ -323,10 +369,183 @@ impl StableHashGenerator {
             salt: "PTY@1".to_string(),
         });
         
+        // Reference v1 recipes: identifier + title, case-insensitive
+        for (entity_type, salt) in [("ReleaseReference", "RELREF@1"), ("ResourceReference", "RESREF@1")] {
+            recipes.insert(format!("{}.v1", entity_type), HashRecipe {
+                fields: vec![
+                    "identifier".to_string(),
+                    "title".to_string(),
+                ],
+                normalize: NormalizeOptions {
+                    unicode: UnicodeForm::NFC,
+                    trim: true,
+                    case: CaseNormalization::Lower,
+                },
+                salt: salt.to_string(),
+            });
+        }
+        
         recipes
     }
 }
 
+/// Kind of identifier the builder generates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IdKind {
+    /// `MessageHeader/MessageId`
+    MessageId,
+    /// Reference of a release or track release
+    ReleaseReference,
+    /// Reference of a track or other resource
+    ResourceReference,
+    /// Reference of a deal
+    DealReference,
+}
+
+/// An identifier the builder needs, as passed to an [`IdCallback`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdRequest<'a> {
+    /// What the identifier names
+    pub kind: IdKind,
+    /// One-based position among the identifiers of this kind in the message
+    pub sequence: usize,
+    /// Content identifying the entity: its ISRC, UPC or proprietary ID, or
+    /// sender and recipient for the message
+    pub content: &'a str,
+}
+
+/// Caller-supplied ID generator for [`IdStrategy::Custom`]
+///
+/// ```
+/// use ddex_builder::builder::IdStrategy;
+/// use ddex_builder::id_generator::{IdCallback, IdKind};
+///
+/// let strategy = IdStrategy::Custom(IdCallback::new(|id| match id.kind {
+///     IdKind::MessageId => format!("LABEL-{}", id.content),
+///     _ => format!("X{}", id.sequence),
+/// }));
+/// ```
+#[derive(Clone)]
+pub struct IdCallback(Arc<dyn Fn(&IdRequest<'_>) -> String + Send + Sync>);
+
+impl IdCallback {
+    /// Wrap a function returning the complete identifier
+    pub fn new(generate: impl Fn(&IdRequest<'_>) -> String + Send + Sync + 'static) -> Self {
+        Self(Arc::new(generate))
+    }
+}
+
+impl std::fmt::Debug for IdCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("IdCallback(..)")
+    }
+}
+
+impl PartialEq for IdCallback {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for IdCallback {}
+
+/// Mints the identifiers of one message according to an [`IdStrategy`]
+///
+/// Every strategy but `UUID` is reproducible: UUIDv7 takes its clock from
+/// the configured [`TimestampSource`](crate::determinism::TimestampSource)
+/// and derives its remaining bits from a per-message counter.
+pub(crate) struct IdMinter {
+    strategy: IdStrategy,
+    timestamp_ms: u64,
+    counters: IndexMap<IdKind, usize>,
+    minted: IndexSet<String>,
+}
+
+impl IdMinter {
+    pub(crate) fn new(strategy: IdStrategy, config: &DeterminismConfig) -> Result<Self, BuildError> {
+        let timestamp = config.timestamp_source.resolve()?;
+        let timestamp_ms = chrono::DateTime::parse_from_rfc3339(&timestamp)
+            .map(|dt| dt.timestamp_millis().max(0) as u64)
+            .unwrap_or_default();
+        Ok(Self {
+            strategy,
+            timestamp_ms,
+            counters: IndexMap::new(),
+            minted: IndexSet::new(),
+        })
+    }
+
+    /// Next identifier of `kind` for the entity identified by `content`
+    pub(crate) fn mint(&mut self, kind: IdKind, content: &str) -> String {
+        let counter = self.counters.entry(kind).or_default();
+        *counter += 1;
+        let sequence = *counter;
+
+        let uuid = match &self.strategy {
+            IdStrategy::Custom(callback) => return (callback.0)(&IdRequest { kind, sequence, content }),
+            _ if kind == IdKind::DealReference => return format!("D{}", sequence),
+            IdStrategy::Sequential => match kind {
+                IdKind::MessageId => return format!("MSG_{}", self.timestamp_ms / 1000),
+                _ => return format!("{}{}", reference_prefix(kind), sequence),
+            },
+            IdStrategy::UUIDv5 => {
+                // Entities without identifying content are told apart by position
+                let mut name = format!("{:?}:{}", kind, content);
+                if content.is_empty() || self.minted.contains(&name) {
+                    name = format!("{}#{}", name, sequence);
+                }
+                self.minted.insert(name.clone());
+                uuid_v5(&builder_namespace(), name.as_bytes())
+            }
+            IdStrategy::UUIDv7 => {
+                let n = self.minted.len() as u64;
+                self.minted.insert(n.to_string());
+                let mut bytes = [0u8; 10];
+                // Counter first so IDs minted in the same millisecond stay ordered
+                bytes[..2].copy_from_slice(&(n as u16).to_be_bytes());
+                bytes[2..].copy_from_slice(&Sha256::digest(format!("{}:{}", self.timestamp_ms, n))[..8]);
+                uuid::Builder::from_unix_timestamp_millis(self.timestamp_ms, &bytes).into_uuid()
+            }
+            IdStrategy::SeededRandom(seed) => {
+                let n = self.minted.len() as u64;
+                self.minted.insert(n.to_string());
+                let mut bytes = [0u8; 16];
+                bytes.copy_from_slice(&Sha256::digest(format!("{}:{}", seed, n))[..16]);
+                uuid::Builder::from_random_bytes(bytes).into_uuid()
+            }
+            IdStrategy::UUID | IdStrategy::StableHash => uuid::Uuid::new_v4(),
+        };
+        match kind {
+            IdKind::MessageId => format!("MSG_{}", uuid),
+            _ => format!("{}{}", reference_prefix(kind), uuid.simple()),
+        }
+    }
+}
+
+fn reference_prefix(kind: IdKind) -> &'static str {
+    match kind {
+        IdKind::MessageId => "MSG_",
+        IdKind::ReleaseReference => "R",
+        IdKind::ResourceReference => "A",
+        IdKind::DealReference => "D",
+    }
+}
+
+/// Namespace of the builder's name-based UUIDs
+fn builder_namespace() -> uuid::Uuid {
+    uuid_v5(&uuid::Uuid::NAMESPACE_URL, b"https://github.com/daddykev/ddex-suite/ddex-builder")
+}
+
+/// RFC 9562 name-based UUID using SHA-1
+fn uuid_v5(namespace: &uuid::Uuid, name: &[u8]) -> uuid::Uuid {
+    let mut hasher = sha1::Sha1::new();
+    hasher.update(namespace.as_bytes());
+    hasher.update(name);
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&hasher.finalize()[..16]);
+    uuid::Builder::from_sha1_bytes(bytes).into_uuid()
+}
+
 // Hash material structures
 #[derive(Debug, Serialize)]
 struct ReleaseHashMaterials {
@@ -348,4 +567,62 @@ struct PartyHashMaterials {
     name: String,
     role: String,
     identifiers: Vec<String>,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Serialize)]
+struct ReferenceHashMaterials {
+    identifier: String,
+    title: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::determinism::TimestampSource;
+
+    fn minter(strategy: IdStrategy) -> IdMinter {
+        let config = DeterminismConfig {
+            timestamp_source: TimestampSource::Fixed("2024-01-01T00:00:00Z".to_string()),
+            ..Default::default()
+        };
+        IdMinter::new(strategy, &config).unwrap()
+    }
+
+    #[test]
+    fn test_reproducible_strategies() {
+        for strategy in [IdStrategy::UUIDv5, IdStrategy::UUIDv7, IdStrategy::SeededRandom(7), IdStrategy::Sequential] {
+            let mint = |minter: &mut IdMinter| {
+                vec![
+                    minter.mint(IdKind::MessageId, "Sender-Recipient"),
+                    minter.mint(IdKind::ReleaseReference, "123456789012"),
+                    minter.mint(IdKind::ResourceReference, "USRC17607839"),
+                    minter.mint(IdKind::ResourceReference, "USRC17607840"),
+                    minter.mint(IdKind::DealReference, ""),
+                ]
+            };
+            let first = mint(&mut minter(strategy.clone()));
+            assert_eq!(first, mint(&mut minter(strategy.clone())), "{:?}", strategy);
+            assert_ne!(first[2], first[3], "{:?}", strategy);
+            assert_eq!(first[4], "D1");
+        }
+
+        let v7 = minter(IdStrategy::UUIDv7).mint(IdKind::MessageId, "");
+        let uuid = uuid::Uuid::parse_str(v7.trim_start_matches("MSG_")).unwrap();
+        assert_eq!(uuid.get_version_num(), 7);
+        assert_eq!(uuid.get_timestamp().unwrap().to_unix().0, 1_704_067_200);
+
+        // RFC 9562 test vector: v5 of "www.example.com" in the DNS namespace
+        assert_eq!(
+            uuid_v5(&uuid::Uuid::NAMESPACE_DNS, b"www.example.com").to_string(),
+            "2ed6657d-e927-568b-95e1-2665a8aea6a2"
+        );
+    }
+
+    #[test]
+    fn test_custom_strategy() {
+        let strategy = IdStrategy::Custom(IdCallback::new(|id| format!("{:?}-{}-{}", id.kind, id.sequence, id.content)));
+        let mut minter = minter(strategy);
+        assert_eq!(minter.mint(IdKind::ResourceReference, "USRC17607839"), "ResourceReference-1-USRC17607839");
+        assert_eq!(minter.mint(IdKind::DealReference, ""), "DealReference-1-");
+    }
+}
@@ -0,0 +1,297 @@
+//! Identifier index across a catalog of DDEX messages
+//!
+//! Aggregators check incoming feeds against what they already hold: a UPC
+//! delivered twice, or an ISRC that comes back with another title or
+//! artist. [`CatalogIndex`] records every ISRC, UPC (ICPN) and GRid of a set
+//! of messages with the metadata found next to it, answers lookups, and
+//! reports identifiers that occur more than once, flagging those whose
+//! occurrences disagree.
+//!
+//! ```rust
+//! use ddex_builder::index::{CatalogIndex, IdentifierKind};
+//!
+//! let message = |title: &str| format!(r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+//!   <ResourceList><SoundRecording>
+//!     <ResourceReference>A1</ResourceReference>
+//!     <ResourceId><ISRC>USRC12400001</ISRC></ResourceId>
+//!     <DisplayTitleText>{}</DisplayTitleText>
+//!   </SoundRecording></ResourceList>
+//! </ern:NewReleaseMessage>"#, title);
+//!
+//! let mut index = CatalogIndex::new();
+//! index.add_xml("catalog.xml", &message("Headlights"))?;
+//! assert_eq!(index.lookup(IdentifierKind::Isrc, "US-RC1-24-00001").len(), 1);
+//!
+//! let conflicts = index.check_xml("incoming.xml", &message("Taillights"))?;
+//! assert_eq!(conflicts[0].conflicting_fields, ["title"]);
+//! # Ok::<(), ddex_builder::BuildError>(())
+//! ```
+
+use crate::error::BuildError;
+use crate::lint::Document;
+use crate::split::is_track_release;
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::fmt;
+
+/// The identifiers the index records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum IdentifierKind {
+    /// Sound recording ISRC
+    Isrc,
+    /// Release UPC or EAN, sent as `ICPN`
+    Upc,
+    /// Release GRid
+    Grid,
+}
+
+impl fmt::Display for IdentifierKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            IdentifierKind::Isrc => "ISRC",
+            IdentifierKind::Upc => "UPC",
+            IdentifierKind::Grid => "GRid",
+        })
+    }
+}
+
+/// One occurrence of an identifier
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IndexEntry {
+    /// Where the message came from, e.g. its file name
+    pub source: String,
+    /// The identifier as written in the message
+    pub identifier: String,
+    /// ResourceReference or ReleaseReference of the element carrying it
+    pub reference: Option<String>,
+    /// Display title
+    pub title: Option<String>,
+    /// Display artist
+    pub artist: Option<String>,
+    /// Duration of a sound recording, as written
+    pub duration: Option<String>,
+}
+
+/// An identifier that occurs more than once
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Duplicate {
+    /// Kind of identifier
+    pub kind: IdentifierKind,
+    /// The identifier as first seen
+    pub identifier: String,
+    /// Every occurrence, in the order they were added
+    pub entries: Vec<IndexEntry>,
+    /// Fields whose values differ between occurrences: `title`, `artist` or `duration`
+    pub conflicting_fields: Vec<&'static str>,
+}
+
+/// ISRCs, UPCs and GRids of many messages
+#[derive(Debug, Clone, Default)]
+pub struct CatalogIndex {
+    entries: IndexMap<(IdentifierKind, String), Vec<IndexEntry>>,
+}
+
+impl CatalogIndex {
+    /// An empty index
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the identifiers of a message, returning how many it had
+    ///
+    /// Sound recordings are indexed by ISRC and main releases by ICPN and
+    /// GRid; track releases repeat their recording and are skipped.
+    pub fn add_xml(&mut self, source: &str, xml: &str) -> Result<usize, BuildError> {
+        let found = read_identifiers(source, xml)?;
+        let count = found.len();
+        for (kind, entry) in found {
+            self.entries.entry((kind, key(&entry.identifier))).or_default().push(entry);
+        }
+        Ok(count)
+    }
+
+    /// Occurrences of an identifier; ISRCs and UPCs are matched without
+    /// separators, and UPC-A matches the EAN-13 with a leading zero
+    pub fn lookup(&self, kind: IdentifierKind, identifier: &str) -> &[IndexEntry] {
+        self.entries.get(&(kind, key(identifier))).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Number of distinct identifiers
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no identifier has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Identifiers occurring more than once, in the order first seen
+    ///
+    /// The same ISRC on an album and its single is expected; check
+    /// [`Duplicate::conflicting_fields`] for occurrences that disagree.
+    pub fn duplicates(&self) -> Vec<Duplicate> {
+        self.entries
+            .iter()
+            .filter(|(_, entries)| entries.len() > 1)
+            .map(|(&(kind, _), entries)| duplicate(kind, entries.clone()))
+            .collect()
+    }
+
+    /// Duplicates whose occurrences disagree on their metadata
+    pub fn conflicts(&self) -> Vec<Duplicate> {
+        self.duplicates().into_iter().filter(|d| !d.conflicting_fields.is_empty()).collect()
+    }
+
+    /// Compare an incoming message with the index without adding it,
+    /// returning its identifiers whose metadata differs from the catalog's
+    ///
+    /// Each result lists the catalog's occurrences followed by the incoming ones.
+    pub fn check_xml(&self, source: &str, xml: &str) -> Result<Vec<Duplicate>, BuildError> {
+        let mut incoming: IndexMap<(IdentifierKind, String), Vec<IndexEntry>> = IndexMap::new();
+        for (kind, entry) in read_identifiers(source, xml)? {
+            incoming.entry((kind, key(&entry.identifier))).or_default().push(entry);
+        }
+        Ok(incoming
+            .into_iter()
+            .filter_map(|((kind, key), entries)| {
+                let existing = self.entries.get(&(kind, key))?;
+                Some(duplicate(kind, existing.iter().cloned().chain(entries).collect()))
+            })
+            .filter(|d| !d.conflicting_fields.is_empty())
+            .collect())
+    }
+}
+
+fn duplicate(kind: IdentifierKind, entries: Vec<IndexEntry>) -> Duplicate {
+    let conflicting_fields = ["title", "artist", "duration"]
+        .into_iter()
+        .filter(|&field| {
+            let mut values = entries
+                .iter()
+                .filter_map(|e| match field {
+                    "title" => e.title.as_deref(),
+                    "artist" => e.artist.as_deref(),
+                    _ => e.duration.as_deref(),
+                })
+                .map(|v| v.trim().to_lowercase());
+            let first = values.next();
+            values.any(|v| Some(v) != first)
+        })
+        .collect();
+    Duplicate { kind, identifier: entries[0].identifier.clone(), entries, conflicting_fields }
+}
+
+/// Identifier as compared: upper case without separators, and numeric codes
+/// without leading zeros
+fn key(identifier: &str) -> String {
+    let key = identifier.chars().filter(char::is_ascii_alphanumeric).collect::<String>().to_ascii_uppercase();
+    if key.bytes().all(|b| b.is_ascii_digit()) {
+        key.trim_start_matches('0').to_string()
+    } else {
+        key
+    }
+}
+
+fn read_identifiers(source: &str, xml: &str) -> Result<Vec<(IdentifierKind, IndexEntry)>, BuildError> {
+    let doc = Document::parse(xml)?;
+    let list = |name: &str| doc.children_named(0, name).next().map(|(index, _)| index);
+    let text = |element: usize, names: &[&str]| {
+        doc.descendants(element)
+            .find(|(_, e)| names.contains(&e.name.as_str()) && !e.text.is_empty())
+            .map(|(_, e)| e.text.clone())
+    };
+    let entry = |element: usize, identifier: String, reference: &str| IndexEntry {
+        source: source.to_string(),
+        identifier,
+        reference: text(element, &[reference]),
+        title: text(element, &["DisplayTitleText", "TitleText"]),
+        artist: text(element, &["DisplayArtistName", "FullName"]),
+        duration: text(element, &["Duration"]),
+    };
+
+    let mut found = Vec::new();
+    if let Some(resources) = list("ResourceList") {
+        for (recording, _) in doc.children_named(resources, "SoundRecording") {
+            if let Some(isrc) = text(recording, &["ISRC"]) {
+                found.push((IdentifierKind::Isrc, entry(recording, isrc, "ResourceReference")));
+            }
+        }
+    }
+    if let Some(releases) = list("ReleaseList") {
+        for (release, _) in doc.children(releases).filter(|(r, _)| !is_track_release(&doc, *r)) {
+            for (kind, name) in [(IdentifierKind::Upc, "ICPN"), (IdentifierKind::Grid, "GRid")] {
+                if let Some(id) = text(release, &[name]) {
+                    let mut entry = entry(release, id, "ReleaseReference");
+                    entry.duration = None;
+                    found.push((kind, entry));
+                }
+            }
+        }
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(upc: &str, title: &str, isrc: &str, artist: &str) -> String {
+        format!(
+            r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+  <ResourceList>
+    <SoundRecording>
+      <ResourceReference>A1</ResourceReference>
+      <ResourceId><ISRC>{isrc}</ISRC></ResourceId>
+      <DisplayTitleText>Headlights</DisplayTitleText>
+      <DisplayArtistName>{artist}</DisplayArtistName>
+      <Duration>PT3M35S</Duration>
+    </SoundRecording>
+  </ResourceList>
+  <ReleaseList>
+    <Release>
+      <ReleaseReference>R0</ReleaseReference>
+      <ReleaseId><ICPN>{upc}</ICPN></ReleaseId>
+      <DisplayTitleText>{title}</DisplayTitleText>
+      <DisplayArtistName>{artist}</DisplayArtistName>
+    </Release>
+    <TrackRelease>
+      <ReleaseReference>R1</ReleaseReference>
+      <ReleaseId><ISRC>{isrc}</ISRC></ReleaseId>
+    </TrackRelease>
+  </ReleaseList>
+</ern:NewReleaseMessage>"#
+        )
+    }
+
+    #[test]
+    fn test_catalog_index() {
+        let mut index = CatalogIndex::new();
+        assert_eq!(index.add_xml("album.xml", &message("602445790128", "Headlights", "USRC12400001", "The Night Owls")).unwrap(), 2);
+        index.add_xml("single.xml", &message("602445790135", "Headlights (Single)", "US-RC1-24-00001", "The Night Owls")).unwrap();
+        index.add_xml("redelivery.xml", &message("0602445790128", "Headlights", "USRC12400002", "Night Owls")).unwrap();
+        assert_eq!(index.len(), 4);
+
+        let upc = index.lookup(IdentifierKind::Upc, "602445790128");
+        assert_eq!(upc.iter().map(|e| e.source.as_str()).collect::<Vec<_>>(), ["album.xml", "redelivery.xml"]);
+        assert_eq!(upc[0].reference.as_deref(), Some("R0"));
+        assert!(index.lookup(IdentifierKind::Isrc, "USRC12400003").is_empty());
+
+        let duplicates = index.duplicates();
+        let found: Vec<_> = duplicates.iter().map(|d| (d.kind, d.identifier.as_str(), d.conflicting_fields.clone())).collect();
+        assert_eq!(
+            found,
+            [
+                (IdentifierKind::Isrc, "USRC12400001", vec![]),
+                (IdentifierKind::Upc, "602445790128", vec!["artist"]),
+            ]
+        );
+        assert_eq!(index.conflicts().len(), 1);
+
+        let conflicts = index.check_xml("incoming.xml", &message("602445790999", "New", "USRC12400002", "Someone Else")).unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].entries.last().unwrap().source, "incoming.xml");
+        assert_eq!(conflicts[0].conflicting_fields, ["artist"]);
+        assert_eq!(index.len(), 4);
+    }
+}
@@ -0,0 +1,254 @@
+//! Prefilling track requests from local audio files
+//!
+//! Reads ID3, Vorbis comment, RIFF INFO and MP4 tags together with the
+//! stream properties of WAV, AIFF, FLAC, MP3, Ogg Vorbis and MP4/M4A files,
+//! so that a [`TrackRequest`] only needs the fields no file can carry.
+//!
+//! ```no_run
+//! use ddex_builder::ingest::track_from_file;
+//!
+//! let track = track_from_file("audio/USRC17607839.flac")?;
+//! assert_eq!(track.isrc, "USRC17607839");
+//! # Ok::<(), ddex_builder::BuildError>(())
+//! ```
+
+use crate::builder::{TechnicalDetailsRequest, TrackRequest};
+use crate::error::BuildError;
+use ddex_core::duration::DdexDuration;
+use std::fs::File;
+use std::path::Path;
+use std::time::Duration;
+use symphonia::core::codecs::CodecType;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::{MetadataOptions, MetadataRevision, StandardTagKey};
+use symphonia::core::probe::Hint;
+
+/// Tags and stream properties read from an audio file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AudioFileInfo {
+    /// ISRC tag, or the file name when it is a bare ISRC
+    pub isrc: Option<String>,
+    /// Track title tag
+    pub title: Option<String>,
+    /// Artist tag
+    pub artist: Option<String>,
+    /// Playing time of the default track
+    pub duration: Option<Duration>,
+    /// DDEX `AudioCodecType` of the stream, e.g. "FLAC" or "PCM"
+    pub codec: Option<String>,
+    /// Sampling rate in Hz
+    pub sample_rate: Option<u32>,
+    /// Bit depth, for lossless codecs
+    pub bits_per_sample: Option<u32>,
+    /// Number of channels
+    pub number_of_channels: Option<u32>,
+}
+
+/// Read the tags and stream properties of the audio file at `path`
+pub fn probe(path: impl AsRef<Path>) -> Result<AudioFileInfo, BuildError> {
+    let path = path.as_ref();
+    let invalid = |message: String| BuildError::InvalidFormat { field: path.display().to_string(), message };
+
+    let stream = MediaSourceStream::new(Box::new(File::open(path)?), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+    let mut probed = symphonia::default::get_probe()
+        .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| invalid(e.to_string()))?;
+
+    let mut info = AudioFileInfo::default();
+    // Tags found ahead of the container (ID3v2 on MP3) come first, so
+    // container tags read afterwards take precedence
+    if let Some(revision) = probed.metadata.get().as_ref().and_then(|m| m.current()) {
+        read_tags(revision, &mut info);
+    }
+    if let Some(revision) = probed.format.metadata().current() {
+        read_tags(revision, &mut info);
+    }
+
+    let track = probed.format.default_track().ok_or_else(|| invalid("no audio track".to_string()))?;
+    let params = &track.codec_params;
+    info.codec = codec_name(params.codec);
+    info.sample_rate = params.sample_rate;
+    info.bits_per_sample = params.bits_per_sample;
+    info.number_of_channels = params.channels.map(|c| c.count() as u32);
+    info.duration = match (params.n_frames, params.time_base, params.sample_rate) {
+        (Some(frames), Some(time_base), _) => {
+            let time = time_base.calc_time(frames);
+            Some(Duration::from_secs(time.seconds) + Duration::from_secs_f64(time.frac))
+        }
+        (Some(frames), None, Some(rate)) if rate > 0 => Some(Duration::from_secs_f64(frames as f64 / rate as f64)),
+        _ => None,
+    };
+
+    if info.isrc.is_none() {
+        info.isrc = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .filter(|stem| is_isrc(stem))
+            .map(str::to_ascii_uppercase);
+    }
+    Ok(info)
+}
+
+/// Build a track request prefilled from the audio file at `path`
+///
+/// The track ID is the file stem and the file name goes into the technical
+/// details; fields the file does not carry are left empty.
+pub fn track_from_file(path: impl AsRef<Path>) -> Result<TrackRequest, BuildError> {
+    let path = path.as_ref();
+    let info = probe(path)?;
+    let file_name = path.file_name().map(|n| n.to_string_lossy().into_owned());
+    let track_id = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    Ok(TrackRequest {
+        track_id,
+        resource_reference: None,
+        isrc: info.isrc.unwrap_or_default(),
+        title: info.title.unwrap_or_default(),
+        duration: info.duration.map(format_duration).unwrap_or_default(),
+        artist: info.artist.unwrap_or_default(),
+        contributors: Vec::new(),
+        p_line: None,
+        c_line: None,
+        comments: Vec::new(),
+        technical_details: Some(TechnicalDetailsRequest {
+            file_name,
+            codec: info.codec,
+            sample_rate: info.sample_rate,
+            bits_per_sample: info.bits_per_sample,
+            number_of_channels: info.number_of_channels,
+            // The measured length, to the millisecond
+            duration: info.duration.map(|d| DdexDuration::from(Duration::from_millis(d.as_millis() as u64)).to_string()),
+            ..Default::default()
+        }),
+        parental_warning: None,
+    })
+}
+
+/// Format a duration as ISO 8601, rounded to whole seconds, e.g. "PT3M45S"
+pub fn format_duration(duration: Duration) -> String {
+    DdexDuration::from(duration).round_to_secs().to_string()
+}
+
+fn read_tags(revision: &MetadataRevision, info: &mut AudioFileInfo) {
+    for tag in revision.tags() {
+        // RIFF INFO strings keep their NUL terminator
+        let value = tag.value.to_string().trim_matches(|c: char| c == '\0' || c.is_whitespace()).to_string();
+        if value.is_empty() {
+            continue;
+        }
+        match tag.std_key {
+            Some(StandardTagKey::IdentIsrc) => info.isrc = Some(value.replace('-', "").to_ascii_uppercase()),
+            Some(StandardTagKey::TrackTitle) => info.title = Some(value),
+            Some(StandardTagKey::Artist) => info.artist = Some(value),
+            _ => {}
+        }
+    }
+}
+
+fn codec_name(codec: CodecType) -> Option<String> {
+    let descriptor = symphonia::default::get_codecs().get_codec(codec)?;
+    let name = match descriptor.short_name {
+        name if name.starts_with("pcm") => "PCM",
+        "flac" => "FLAC",
+        "mp3" => "MP3",
+        "aac" => "AAC",
+        "alac" => "ALAC",
+        "vorbis" => "Vorbis",
+        other => return Some(other.to_ascii_uppercase()),
+    };
+    Some(name.to_string())
+}
+
+/// Two-letter country code, three-character registrant and seven digits
+fn is_isrc(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    bytes.len() == 12
+        && bytes[..2].iter().all(u8::is_ascii_alphabetic)
+        && bytes[2..5].iter().all(u8::is_ascii_alphanumeric)
+        && bytes[5..].iter().all(u8::is_ascii_digit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A one-second 16-bit stereo WAV with an INFO list naming the title and artist
+    fn wav_with_info() -> Vec<u8> {
+        let (rate, channels, bits) = (8000u32, 2u16, 16u16);
+        let block_align = channels * bits / 8;
+        let data = vec![0u8; rate as usize * block_align as usize];
+
+        let mut info = b"INFO".to_vec();
+        for (id, text) in [(b"INAM", "Midnight Drive\0\0"), (b"IART", "The Examples\0\0")] {
+            info.extend_from_slice(id);
+            info.extend_from_slice(&(text.len() as u32).to_le_bytes());
+            info.extend_from_slice(text.as_bytes());
+        }
+
+        let mut chunks = Vec::new();
+        chunks.extend_from_slice(b"fmt ");
+        chunks.extend_from_slice(&16u32.to_le_bytes());
+        chunks.extend_from_slice(&1u16.to_le_bytes());
+        chunks.extend_from_slice(&channels.to_le_bytes());
+        chunks.extend_from_slice(&rate.to_le_bytes());
+        chunks.extend_from_slice(&(rate * block_align as u32).to_le_bytes());
+        chunks.extend_from_slice(&block_align.to_le_bytes());
+        chunks.extend_from_slice(&bits.to_le_bytes());
+        chunks.extend_from_slice(b"LIST");
+        chunks.extend_from_slice(&(info.len() as u32).to_le_bytes());
+        chunks.extend_from_slice(&info);
+        chunks.extend_from_slice(b"data");
+        chunks.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        chunks.extend_from_slice(&data);
+
+        let mut wav = b"RIFF".to_vec();
+        wav.extend_from_slice(&(chunks.len() as u32 + 4).to_le_bytes());
+        wav.extend_from_slice(b"WAVE");
+        wav.extend_from_slice(&chunks);
+        wav
+    }
+
+    #[test]
+    fn test_track_from_wav_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("usrc17607839.wav");
+        std::fs::write(&path, wav_with_info()).unwrap();
+
+        let track = track_from_file(&path).unwrap();
+        assert_eq!(track.track_id, "usrc17607839");
+        assert_eq!(track.isrc, "USRC17607839");
+        assert_eq!(track.title, "Midnight Drive");
+        assert_eq!(track.artist, "The Examples");
+        assert_eq!(track.duration, "PT0M1S");
+
+        let details = track.technical_details.unwrap();
+        assert_eq!(details.file_name.as_deref(), Some("usrc17607839.wav"));
+        assert_eq!(details.codec.as_deref(), Some("PCM"));
+        assert_eq!(details.sample_rate, Some(8000));
+        assert_eq!(details.bits_per_sample, Some(16));
+        assert_eq!(details.number_of_channels, Some(2));
+        assert_eq!(details.duration.as_deref(), Some("PT0M1S"));
+    }
+
+    #[test]
+    fn test_probe_rejects_non_audio() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "not audio").unwrap();
+        assert!(matches!(probe(&path), Err(BuildError::InvalidFormat { .. })));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(225)), "PT3M45S");
+        assert_eq!(format_duration(Duration::from_millis(3_723_600)), "PT1H2M4S");
+    }
+}
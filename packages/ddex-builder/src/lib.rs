@@ -12,7 +12,7 @@
 //! - **🔄 Round-trip Fidelity**: Perfect compatibility with ddex-parser for Parse → Build → Parse workflows
 //! - **🛠️ Partner Presets**: Pre-configured settings for Spotify, YouTube, Apple Music, and other platforms
 //! - **🌐 Multi-platform**: Native Rust, Node.js, Python, and WebAssembly bindings
-//! - **📊 Version Support**: Full support for ERN 3.8.2, 4.2, 4.3 with automatic conversion
+//! - **📊 Version Support**: Full support for ERN 3.8.2, 4.2, 4.3, 4.4 with automatic conversion
 //!
 //! ## Quick Start
 //!
@@ -106,17 +106,20 @@
 //! |--------------|---------------|-------|
 //! | ERN 3.8.2    | ✅ Full       | Legacy support |
 //! | ERN 4.2      | ✅ Full       | Enhanced features |
-//! | ERN 4.3      | ✅ Full       | Latest standard |
+//! | ERN 4.3      | ✅ Full       | Current standard |
+//! | ERN 4.4      | ✅ Full       | Latest standard |
 //!
 //! ## Partner Presets
 //!
 //! Pre-configured settings for major platforms:
 //!
-//! - `spotify_audio_43` - Spotify audio releases (ERN 4.3)
-//! - `youtube_video_43` - YouTube video content (ERN 4.3)
-//! - `apple_music_43` - Apple Music releases (ERN 4.3)
-//! - `universal_basic` - Universal Music basic preset
-//! - `sony_enhanced` - Sony Music enhanced features
+//! - `audio_album`, `audio_single`, `video_single`, `compilation` - Generic DDEX presets
+//! - `youtube_album`, `youtube_single`, `youtube_video` - YouTube Music
+//! - `amazon_album`, `amazon_single` - Amazon Music
+//! - `deezer_album`, `deezer_single` - Deezer
+//! - `tidal_album`, `tidal_single` - TIDAL
+//! - `beatport_release` - Beatport
+//! - `pandora_album`, `pandora_single` - Pandora
 //!
 //! See the [User Guide](https://docs.ddex-builder.io/user-guide) for detailed preset documentation.
 
@@ -124,12 +127,20 @@
 #![warn(missing_docs)]
 
 pub mod ast;
+pub mod avs;
+pub mod benchmark;
+pub mod build_cache;
 pub mod builder;
 pub mod canonical;
+pub mod compliance;
+pub mod conformance;
+pub mod delivery;
 pub mod determinism;
 pub mod error;
 pub mod guarantees;
 pub mod generator;
+pub mod hash_sum;
+pub mod index;
 pub mod presets;
 pub mod streaming;
 pub mod diff;
@@ -146,16 +157,42 @@ pub mod caching;
 pub mod security;
 pub mod api_security;
 pub mod namespace_minimizer;
+pub mod extensions;
+pub mod extension_schema;
+pub mod extension_conflicts;
 pub mod fidelity;
+pub mod fluent;
+pub mod genre;
 pub mod verification;
 pub mod round_trip;
+pub mod lint;
+pub mod normalize;
+pub mod plugins;
+pub mod reprice;
+pub mod sanitize;
+pub mod split;
+pub mod spreadsheet;
+pub mod stats;
+pub mod template;
+mod parsed;
+#[cfg(feature = "ingest")]
+pub mod ingest;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "signature")]
+pub use ddex_core::signature;
 
 // Re-export main types
-pub use builder::{DDEXBuilder, BuildOptions, BuildRequest, BuildResult};
+pub use builder::{DDEXBuilder, BuildOptions, BuildRequest, BuildResult, BuilderStats};
+pub use namespace_minimizer::OptimizationStrategy;
 pub use canonical::DB_C14N;
-pub use determinism::DeterminismConfig;
+pub use compliance::{CheckStatus, ComplianceReport, RuleCheck};
+pub use conformance::{ConformanceReport, ReleaseConformance};
+pub use determinism::{DeterminismConfig, TimestampSource};
 pub use error::{BuildError, BuildWarning};
-pub use guarantees::{DeterminismGuarantee, DeterminismGuaranteeValidator, GuaranteeReport};
+pub use fluent::{Ern382, Ern42, Ern43, Ern44};
+pub use ddex_core::warnings::{Normalization, NormalizationKind, Warnings};
+pub use guarantees::{DeterminismGuarantee, DeterminismGuaranteeValidator, DeterminismReport, GuaranteeReport};
 pub use presets::PartnerPreset;
 pub use linker::{ReferenceLinker, LinkerConfig, EntityType, LinkerError};
 pub use id_generator::{StableHashGenerator, StableHashConfig, HashAlgorithm};
@@ -163,7 +200,7 @@ pub use preflight::{PreflightValidator, ValidationConfig, ValidationResult, Pref
 pub use diff::{DiffEngine, DiffConfig, VersionCompatibility};
 pub use diff::types::{ChangeSet, SemanticChange, DiffPath, ChangeType, ImpactLevel};
 pub use diff::formatter::DiffFormatter;
-pub use messages::{UpdateReleaseMessage, UpdateGenerator, UpdateAction, UpdateConfig, ValidationStatus};
+pub use messages::{MeadGenerator, UpdateReleaseMessage, UpdateGenerator, UpdateAction, UpdateConfig, ValidationStatus, Takedown, TakedownScope, PriceUpdate};
 pub use schema::{SchemaGenerator, JsonSchema, SchemaConfig, SchemaDraft, SchemaCommand};
 pub use versions::{VersionManager, VersionConverter, ConverterResult as ConversionResult, ConversionOptions};
 pub use presets::DdexVersion;
@@ -411,7 +448,7 @@ impl Default for BuildStatistics {
 ///
 /// - **Deterministic Output**: Uses DB-C14N/1.0 for byte-perfect reproducibility
 /// - **Partner Presets**: Pre-configured settings for major music platforms
-/// - **Version Management**: Support for ERN 3.8.2, 4.2, and 4.3 with conversion
+/// - **Version Management**: Support for ERN 3.8.2, 4.2, 4.3, and 4.4 with conversion
 /// - **Security**: Built-in validation, rate limiting, and XXE protection
 /// - **Performance**: Memory-optimized with streaming support for large files
 ///
@@ -495,11 +532,15 @@ impl Default for BuildStatistics {
 pub struct Builder {
     config: DeterminismConfig,
     presets: IndexMap<String, PartnerPreset>,
-    locked_preset: Option<String>,
+    locked_preset: Option<presets::lock::PresetLock>,
+    applied_preset: Option<String>,
     version_manager: versions::VersionManager,
     target_version: Option<DdexVersion>,
     fidelity_options: FidelityOptions,
     verification_config: VerificationConfig,
+    extension_namespaces: extensions::ExtensionNamespaces,
+    extension_schema_dir: Option<std::path::PathBuf>,
+    validation_plugins: plugins::ValidationPlugins,
 }
 
 impl Default for Builder {
@@ -536,10 +577,14 @@ impl Builder {
             config: DeterminismConfig::default(),
             presets: Self::load_default_presets(),
             locked_preset: None,
+            applied_preset: None,
             version_manager: versions::VersionManager::new(),
             target_version: None,
             fidelity_options: FidelityOptions::default(),
             verification_config: VerificationConfig::default(),
+            extension_namespaces: extensions::ExtensionNamespaces::new(),
+            extension_schema_dir: None,
+            validation_plugins: plugins::ValidationPlugins::new(),
         }
     }
     
@@ -549,10 +594,14 @@ impl Builder {
             config,
             presets: Self::load_default_presets(),
             locked_preset: None,
+            applied_preset: None,
             version_manager: versions::VersionManager::new(),
             target_version: None,
             fidelity_options: FidelityOptions::default(),
             verification_config: VerificationConfig::default(),
+            extension_namespaces: extensions::ExtensionNamespaces::new(),
+            extension_schema_dir: None,
+            validation_plugins: plugins::ValidationPlugins::new(),
         }
     }
     
@@ -571,10 +620,14 @@ impl Builder {
             config: DeterminismConfig::default(),
             presets: Self::load_default_presets(),
             locked_preset: None,
+            applied_preset: None,
             version_manager: versions::VersionManager::new(),
             target_version: None,
             fidelity_options,
             verification_config: VerificationConfig::default(),
+            extension_namespaces: extensions::ExtensionNamespaces::new(),
+            extension_schema_dir: None,
+            validation_plugins: plugins::ValidationPlugins::new(),
         }
     }
     
@@ -584,10 +637,14 @@ impl Builder {
             config: DeterminismConfig::default(),
             presets: Self::load_default_presets(),
             locked_preset: None,
+            applied_preset: None,
             version_manager: versions::VersionManager::new(),
             target_version: None,
             fidelity_options,
             verification_config: VerificationConfig::default(),
+            extension_namespaces: extensions::ExtensionNamespaces::new(),
+            extension_schema_dir: None,
+            validation_plugins: plugins::ValidationPlugins::new(),
         }
     }
     
@@ -613,10 +670,14 @@ impl Builder {
             config: DeterminismConfig::default(),
             presets: Self::load_default_presets(),
             locked_preset: None,
+            applied_preset: None,
             version_manager: versions::VersionManager::new(),
             target_version: None,
             fidelity_options,
             verification_config,
+            extension_namespaces: extensions::ExtensionNamespaces::new(),
+            extension_schema_dir: None,
+            validation_plugins: plugins::ValidationPlugins::new(),
         }
     }
     
@@ -672,24 +733,93 @@ impl Builder {
                 message: format!("Unknown preset: {}", preset_name),
             })?
             .clone();
+        self.check_lock(&preset, &preset.determinism)?;
         
         // Apply the preset's determinism config
-        self.config = preset.determinism;
+        self.config = preset.determinism.clone();
+        self.applied_preset = Some(preset_name.to_string());
         
-        // Lock the preset if requested
+        // Lock the preset if requested, recording the hash of its effective config
         if lock {
-            self.locked_preset = Some(preset_name.to_string());
+            self.locked_preset = Some(presets::lock::PresetLock::new(&preset, &self.config)?);
         }
         
         Ok(())
     }
 
+    /// Refuse a change that would build a locked preset differently
+    fn check_lock(&self, preset: &PartnerPreset, config: &DeterminismConfig) -> Result<(), error::BuildError> {
+        let Some(lock) = &self.locked_preset else { return Ok(()) };
+        if lock.matches(preset, config)? {
+            return Ok(());
+        }
+        Err(error::BuildError::InvalidFormat {
+            field: "preset".to_string(),
+            message: format!(
+                "Preset '{}' is locked (config sha256 {}); refusing a change to its effective configuration",
+                lock.preset, lock.config_hash
+            ),
+        })
+    }
+
     /// Apply a preset configuration (alias for apply_preset for convenience)
     pub fn preset(&mut self, preset_name: &str) -> Result<&mut Self, error::BuildError> {
         self.apply_preset(preset_name, false)?;
         Ok(self)
     }
 
+    /// Loads a custom preset from a TOML or JSON file and makes it available
+    /// under its name, replacing any preset of the same name.
+    ///
+    /// See [`presets::external`] for the file format. Returns the preset name.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use ddex_builder::Builder;
+    ///
+    /// let mut builder = Builder::new();
+    /// let name = builder.load_preset_file("presets/my_label.toml")?;
+    /// builder.apply_preset(&name, false)?;
+    /// # Ok::<(), ddex_builder::BuildError>(())
+    /// ```
+    pub fn load_preset_file(&mut self, path: impl AsRef<std::path::Path>) -> Result<String, error::BuildError> {
+        let preset = presets::external::load_preset_file(path)?;
+        if self.locked_preset.as_ref().is_some_and(|lock| lock.preset == preset.name) {
+            self.check_lock(&preset, &self.config)?;
+        }
+        let name = preset.name.clone();
+        self.presets.insert(name.clone(), preset);
+        Ok(name)
+    }
+
+    /// Checks a request against every rule of the applied preset.
+    ///
+    /// Each rule is reported as passed, failed or skipped, with the location
+    /// and value it was checked against and a remediation hint for failures.
+    /// Without an applied preset the report has no checks.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use ddex_builder::Builder;
+    /// # fn request() -> ddex_builder::BuildRequest { unimplemented!() }
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.apply_preset("amazon_album", false)?;
+    /// let report = builder.check_preset_compliance(&request());
+    /// for failure in report.failures() {
+    ///     println!("{}: {}", failure.field, failure.message);
+    /// }
+    /// # Ok::<(), ddex_builder::BuildError>(())
+    /// ```
+    pub fn check_preset_compliance(&self, request: &builder::BuildRequest) -> compliance::ComplianceReport {
+        match self.applied_preset.as_ref().and_then(|name| self.presets.get(name)) {
+            Some(preset) => compliance::ComplianceReport::check(preset, request),
+            None => compliance::ComplianceReport { preset: None, checks: Vec::new() },
+        }
+    }
+
     /// Get available preset names
     pub fn available_presets(&self) -> Vec<String> {
         self.presets.keys().cloned().collect()
@@ -704,12 +834,32 @@ impl Builder {
     pub fn is_preset_locked(&self) -> bool {
         self.locked_preset.is_some()
     }
+
+    /// The locked preset and the hash of its effective configuration.
+    ///
+    /// Messages built while a preset is locked carry the same lock as a
+    /// comment; read it back with [`PresetLock::from_xml`](presets::lock::PresetLock::from_xml).
+    pub fn preset_lock(&self) -> Option<&presets::lock::PresetLock> {
+        self.locked_preset.as_ref()
+    }
     
     /// Get the current configuration
     pub fn config(&self) -> &DeterminismConfig {
         &self.config
     }
     
+    /// Set the determinism configuration, e.g. to adjust a preset's settings
+    ///
+    /// Fails when a preset is locked and `config` differs from the one it was
+    /// locked with.
+    pub fn set_config(&mut self, config: DeterminismConfig) -> Result<&mut Self, error::BuildError> {
+        if let Some(preset) = self.locked_preset.as_ref().and_then(|lock| self.presets.get(&lock.preset)) {
+            self.check_lock(preset, &config)?;
+        }
+        self.config = config;
+        Ok(self)
+    }
+    
     /// Get the current fidelity options
     pub fn fidelity_options(&self) -> &FidelityOptions {
         &self.fidelity_options
@@ -798,6 +948,61 @@ impl Builder {
         &self.fidelity_options.canonicalization
     }
     
+    /// Registers a proprietary extension namespace so every build declares
+    /// it on the root element under `prefix`, instead of a generated one.
+    ///
+    /// # Errors
+    ///
+    /// `BuildError::InvalidFormat` when `prefix` is not an XML name, or the
+    /// prefix or URI is already taken by a DDEX namespace or another
+    /// registered extension.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ddex_builder::Builder;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.register_extension_namespace(
+    ///     "label",
+    ///     "http://label.example.com/ddex",
+    ///     Some("http://label.example.com/ddex.xsd"),
+    /// )?;
+    /// assert_eq!(builder.extension_namespaces().len(), 1);
+    /// # Ok::<(), ddex_builder::BuildError>(())
+    /// ```
+    pub fn register_extension_namespace(&mut self, prefix: &str, uri: &str, schema_url: Option<&str>) -> Result<&mut Self, error::BuildError> {
+        self.extension_namespaces.register(prefix, uri, schema_url)?;
+        Ok(self)
+    }
+    
+    /// Get the registered extension namespaces
+    pub fn extension_namespaces(&self) -> &extensions::ExtensionNamespaces {
+        &self.extension_namespaces
+    }
+    
+    /// Check the extension blocks of every build against the `.xsd` files
+    /// in `dir`, a cache of the schemas partners publish (see
+    /// [`extension_schema`]). Violations become build warnings, or fail
+    /// the build under strict preflight.
+    pub fn with_extension_schema_dir(&mut self, dir: impl Into<std::path::PathBuf>) -> &mut Self {
+        self.extension_schema_dir = Some(dir.into());
+        self
+    }
+    
+    /// Registers a plugin checking in-house rules on every build, after
+    /// preflight (see [`plugins`]). Its errors fail the build with
+    /// `ValidationFailed`; its warnings become build warnings.
+    pub fn register_validation_plugin(&mut self, plugin: impl plugins::ValidationPlugin + 'static) -> &mut Self {
+        self.validation_plugins.register(plugin);
+        self
+    }
+    
+    /// Get the registered validation plugins
+    pub fn validation_plugins(&self) -> &plugins::ValidationPlugins {
+        &self.validation_plugins
+    }
+    
     /// Set target DDEX version for building
     pub fn with_version(&mut self, version: DdexVersion) -> &mut Self {
         self.target_version = Some(version);
@@ -836,7 +1041,13 @@ impl Builder {
     }
     
     fn load_default_presets() -> IndexMap<String, PartnerPreset> {
-        presets::all_presets()
+        let mut presets = presets::all_presets();
+        // Presets from DDEX_BUILDER_PRESET_PATH; a bad file must not make every builder unusable
+        match presets::external::load_preset_path() {
+            Ok(custom) => presets.extend(custom),
+            Err(e) => tracing::warn!("Ignoring {}: {}", presets::external::PRESET_PATH_ENV, e),
+        }
+        presets
     }
     
     /// Build DDEX XML with Perfect Fidelity Engine
@@ -845,14 +1056,30 @@ impl Builder {
         let mut statistics = BuildStatistics::default();
         
         // Use the existing build options structure
-        let build_options = builder::BuildOptions::default();
+        let build_options = builder::BuildOptions {
+            determinism: Some(self.config.clone()),
+            preserve_comments: self.fidelity_options.preserve_comments,
+            genre_taxonomy: self.applied_preset.as_ref()
+                .and_then(|name| self.presets.get(name))
+                .and_then(genre::GenreTaxonomy::for_preset),
+            extension_namespaces: self.extension_namespaces.clone(),
+            extension_schema_dir: self.extension_schema_dir.clone(),
+            validation_plugins: self.validation_plugins.clone(),
+            ..Default::default()
+        };
         
         // Build the XML using existing builder
         let ddex_builder = builder::DDEXBuilder::new();
         let build_result = ddex_builder.build(request.clone(), build_options)?;
         
+        // The builder writes DB-C14N itself; other algorithms rewrite its output
+        let xml = match self.fidelity_options.canonicalization {
+            CanonicalizationAlgorithm::None | CanonicalizationAlgorithm::DbC14N => build_result.xml,
+            _ => self.canonicalize(&build_result.xml)?,
+        };
+        
         statistics.build_time = start_time.elapsed();
-        statistics.output_size_bytes = build_result.xml.len();
+        statistics.output_size_bytes = xml.len();
         
         // Perform verification if enabled
         let verification_result = if self.fidelity_options.enable_verification {
@@ -866,7 +1093,7 @@ impl Builder {
                 verification_timeout: self.verification_config.verification_timeout,
             };
             let verifier = verification::BuildVerifier::new(verification_config);
-            let result = verifier.verify(&build_result.xml, &self.fidelity_options)?;
+            let result = verifier.verify(&xml, &self.fidelity_options)?;
             
             // Convert verification::VerificationResult to VerificationResult
             Some(VerificationResult {
@@ -892,8 +1119,14 @@ impl Builder {
             None
         };
         
+        // Record which locked rules built the message
+        let xml = match &self.locked_preset {
+            Some(lock) => lock.stamp(&xml),
+            None => xml,
+        };
+        
         Ok(FidelityBuildResult {
-            xml: build_result.xml,
+            xml,
             statistics: if self.fidelity_options.collect_statistics {
                 Some(statistics)
             } else {
@@ -962,28 +1195,17 @@ impl Builder {
         })
     }
     
+    /// Build a MEAD (Media Enrichment and Description) message
+    pub fn build_mead(&self, message: &ddex_core::models::mead::MeadMessage) -> Result<String, error::BuildError> {
+        messages::MeadGenerator::new(self.config.clone()).build(message)
+    }
+    
     /// Canonicalize XML using the configured algorithm
+    ///
+    /// C14N keeps comments only when `preserve_comments` is set; custom
+    /// rules also honour the other preservation options.
     pub fn canonicalize(&self, xml_content: &str) -> Result<String, error::BuildError> {
-        match &self.fidelity_options.canonicalization {
-            CanonicalizationAlgorithm::None => Ok(xml_content.to_string()),
-            CanonicalizationAlgorithm::C14N => {
-                // TODO: Implement C14N canonicalization
-                Ok(xml_content.to_string())
-            },
-            CanonicalizationAlgorithm::C14N11 => {
-                // TODO: Implement C14N11 canonicalization
-                Ok(xml_content.to_string())
-            },
-            CanonicalizationAlgorithm::DbC14N => {
-                // TODO: Implement DB-C14N canonicalization using canonical module
-                Ok(xml_content.to_string())
-            },
-            CanonicalizationAlgorithm::Custom(rules) => {
-                // TODO: Implement custom canonicalization
-                let _ = rules; // Avoid unused parameter warning
-                Ok(xml_content.to_string())
-            },
-        }
+        canonical::c14n::canonicalize(xml_content, &self.fidelity_options, &self.config)
     }
     
     /// Get DB-C14N/1.0 configuration details
@@ -1005,7 +1227,13 @@ impl Builder {
     /// Internal build method used by determinism verifier
     pub(crate) fn build_internal(&self, request: &builder::BuildRequest) -> Result<builder::BuildResult, error::BuildError> {
         let ddex_builder = builder::DDEXBuilder::new();
-        let build_options = builder::BuildOptions::default();
+        let build_options = builder::BuildOptions {
+            determinism: Some(self.config.clone()),
+            extension_namespaces: self.extension_namespaces.clone(),
+            extension_schema_dir: self.extension_schema_dir.clone(),
+            validation_plugins: self.validation_plugins.clone(),
+            ..Default::default()
+        };
         
         ddex_builder.build(request.clone(), build_options)
     }
@@ -1110,6 +1338,27 @@ mod tests {
         assert!(builder.is_preset_locked());
     }
     
+    #[test]
+    fn test_locked_preset_refuses_config_changes() {
+        let mut builder = Builder::new();
+        builder.apply_preset("audio_album", true).unwrap();
+        let lock = builder.preset_lock().unwrap().clone();
+        assert_eq!(lock.preset, "audio_album");
+
+        // Re-applying the same preset or setting the same config keeps the hash
+        builder.apply_preset("audio_album", false).unwrap();
+        builder.set_config(builder.config().clone()).unwrap();
+
+        let mut changed = builder.config().clone();
+        changed.indent_width += 2;
+        let error = builder.set_config(changed).unwrap_err().to_string();
+        assert!(error.contains("Preset 'audio_album' is locked"), "{}", error);
+        assert!(error.contains(&lock.config_hash), "{}", error);
+
+        assert!(builder.apply_preset("audio_single", false).is_err());
+        assert_eq!(builder.preset_lock(), Some(&lock));
+    }
+    
     #[test]
     fn test_unknown_preset() {
         let mut builder = Builder::new();
@@ -2,7 +2,7 @@
 
 use super::{EntityType, LinkerError, LinkingReport, ReferenceGenerator, RelationshipManager};
 use crate::builder::BuildRequest;
-use indexmap::IndexSet;
+use indexmap::{IndexMap, IndexSet};
 
 /// Handles automatic linking of entities in a build request
 #[derive(Debug, Clone, Default)]
@@ -58,19 +58,34 @@ impl AutoLinker {
     ) -> Result<LinkingReport, LinkerError> {
         let mut report = LinkingReport::default();
         
-        // Phase 1: Register all resources (tracks) first
+        // Phase 1: Register all resources (tracks and other resources) first
         for release in &mut request.releases {
             for track in &mut release.tracks {
                 if track.resource_reference.is_none() {
-                    let reference = generator.generate(EntityType::Resource);
-                    track.resource_reference = Some(reference.clone());
+                    track.resource_reference = Some(generator.generate(EntityType::Resource));
+                    report.generated_refs += 1;
+                }
+                if let Some(ref reference) = track.resource_reference {
                     relationships.register(
                         EntityType::Resource,
                         track.track_id.clone(),
-                        reference,
+                        reference.clone(),
                     );
+                }
+            }
+            
+            for resource in &mut release.resources {
+                if resource.resource_reference.is_none() {
+                    resource.resource_reference = Some(generator.generate(EntityType::Resource));
                     report.generated_refs += 1;
                 }
+                if let Some(ref reference) = resource.resource_reference {
+                    relationships.register(
+                        EntityType::Resource,
+                        resource.resource_id.clone(),
+                        reference.clone(),
+                    );
+                }
             }
         }
         
@@ -78,37 +93,50 @@ impl AutoLinker {
         for release in &mut request.releases {
             // Generate release reference if missing
             if release.release_reference.is_none() {
-                let reference = generator.generate(EntityType::Release);
-                release.release_reference = Some(reference.clone());
-                relationships.register(
-                    EntityType::Release,
-                    release.release_id.clone(),
-                    reference,
-                );
+                release.release_reference = Some(generator.generate(EntityType::Release));
                 report.generated_refs += 1;
             }
+            let release_ref = release.release_reference.clone().unwrap_or_default();
+            relationships.register(
+                EntityType::Release,
+                release.release_id.clone(),
+                release_ref.clone(),
+            );
+            
+            for track_release in &mut release.track_releases {
+                if track_release.release_reference.is_none() {
+                    track_release.release_reference = Some(generator.generate(EntityType::Release));
+                    report.generated_refs += 1;
+                }
+                if let Some(ref reference) = track_release.release_reference {
+                    relationships.register(
+                        EntityType::Release,
+                        track_release.isrc.clone(),
+                        reference.clone(),
+                    );
+                }
+            }
             
-            // Auto-link tracks to release
+            // Auto-link the release's own tracks and resources
             if self.config.auto_link_tracks {
-                let release_ref = release.release_reference.as_ref().unwrap();
-                let mut track_refs = IndexSet::new();
+                let own_refs: IndexSet<String> = release.tracks.iter()
+                    .filter_map(|t| t.resource_reference.clone())
+                    .chain(release.resources.iter().filter_map(|r| r.resource_reference.clone()))
+                    .collect();
                 
-                for track in &release.tracks {
-                    if let Some(track_ref) = &track.resource_reference {
-                        track_refs.insert(track_ref.clone());
-                        relationships.add_relationship(
-                            release_ref.clone(),
-                            track_ref.clone(),
-                        );
-                        report.linked_resources += 1;
-                    }
+                for resource_ref in &own_refs {
+                    relationships.add_relationship(release_ref.clone(), resource_ref.clone());
+                    report.linked_resources += 1;
                 }
                 
                 // Update release with resource references
-                release.resource_references = Some(track_refs.into_iter().collect());
+                release.resource_references = Some(own_refs.into_iter().collect());
             }
         }
         
+        // Phase 2b: Resolve tracks and deal targets given by identifier
+        report.linked_resources += self.link_track_isrcs(request, relationships)?;
+        
         // Phase 3: Register parties (sender/recipient)
         if request.header.message_sender.party_reference.is_none() {
             let sender_ref = generator.generate(EntityType::Party);
@@ -134,6 +162,7 @@ impl AutoLinker {
         
         // Phase 4: Link deals
         if self.config.auto_link_deals {
+            let release_keys = Self::release_keys(request);
             for deal in &mut request.deals {
                 if deal.deal_reference.is_none() {
                     let deal_ref = generator.generate(EntityType::Deal);
@@ -141,6 +170,14 @@ impl AutoLinker {
                     report.generated_refs += 1;
                 }
                 
+                // Reject deals aimed at releases missing from the request
+                if let Some(unknown) = deal.release_references.iter()
+                    .find(|r| !release_keys.values().any(|known| known == *r))
+                {
+                    return Err(LinkerError::UnknownRelease(unknown.clone()));
+                }
+                Self::resolve_deal_releases(deal, &release_keys)?;
+                
                 // Link deal to releases
                 if let Some(ref deal_ref) = deal.deal_reference {
                     for release_ref in &deal.release_references {
@@ -171,4 +208,126 @@ impl AutoLinker {
         
         Ok(report)
     }
-}
\ No newline at end of file
+    
+    /// Resolve `track_isrcs` and deal `release_ids` on a request whose
+    /// references have already been assigned
+    ///
+    /// Deals without any target are scoped to the release when the message
+    /// has exactly one.
+    ///
+    /// Returns the number of links added.
+    pub fn resolve_identifiers(&self, request: &mut BuildRequest) -> Result<usize, LinkerError> {
+        let mut relationships = RelationshipManager::new();
+        let mut linked = self.link_track_isrcs(request, &mut relationships)?;
+        
+        let release_keys = Self::release_keys(request);
+        let single_release = match request.releases.as_slice() {
+            [release] => release.release_reference.clone(),
+            _ => None,
+        };
+        for deal in &mut request.deals {
+            linked += Self::resolve_deal_releases(deal, &release_keys)?;
+            
+            // An untargeted deal in a single-release message covers that release
+            if deal.release_references.is_empty() {
+                if let Some(ref reference) = single_release {
+                    deal.release_references.push(reference.clone());
+                    linked += 1;
+                }
+            }
+        }
+        
+        Ok(linked)
+    }
+    
+    /// Append the resources named by each release's `track_isrcs`
+    fn link_track_isrcs(
+        &self,
+        request: &mut BuildRequest,
+        relationships: &mut RelationshipManager,
+    ) -> Result<usize, LinkerError> {
+        // ISRC -> ResourceReference, first occurrence wins
+        let mut isrc_refs: IndexMap<String, String> = IndexMap::new();
+        for release in &request.releases {
+            for track in &release.tracks {
+                if let Some(ref reference) = track.resource_reference {
+                    isrc_refs.entry(track.isrc.clone()).or_insert_with(|| reference.clone());
+                }
+            }
+            for resource in &release.resources {
+                if let (Some(isrc), Some(reference)) = (&resource.isrc, &resource.resource_reference) {
+                    isrc_refs.entry(isrc.clone()).or_insert_with(|| reference.clone());
+                }
+            }
+        }
+        
+        let mut linked = 0;
+        for release in &mut request.releases {
+            if release.track_isrcs.is_empty() {
+                continue;
+            }
+            
+            // Keep the release's own resources ahead of the linked ones
+            let mut resource_refs = match release.resource_references.take() {
+                Some(refs) => refs,
+                None => release.tracks.iter()
+                    .filter_map(|t| t.resource_reference.clone())
+                    .chain(release.resources.iter().filter_map(|r| r.resource_reference.clone()))
+                    .collect(),
+            };
+            
+            for isrc in &release.track_isrcs {
+                let reference = isrc_refs.get(isrc)
+                    .ok_or_else(|| LinkerError::UnknownResource(isrc.clone()))?;
+                if !resource_refs.contains(reference) {
+                    resource_refs.push(reference.clone());
+                    if let Some(ref release_ref) = release.release_reference {
+                        relationships.add_relationship(release_ref.clone(), reference.clone());
+                    }
+                    linked += 1;
+                }
+            }
+            
+            release.resource_references = Some(resource_refs);
+        }
+        
+        Ok(linked)
+    }
+    
+    /// Map release_id, UPC and track release ISRC to ReleaseReference
+    fn release_keys(request: &BuildRequest) -> IndexMap<String, String> {
+        let mut keys = IndexMap::new();
+        for release in &request.releases {
+            if let Some(ref reference) = release.release_reference {
+                keys.insert(release.release_id.clone(), reference.clone());
+                if let Some(ref upc) = release.upc {
+                    keys.insert(upc.clone(), reference.clone());
+                }
+            }
+            for track_release in &release.track_releases {
+                if let Some(ref reference) = track_release.release_reference {
+                    keys.entry(track_release.isrc.clone()).or_insert_with(|| reference.clone());
+                }
+            }
+        }
+        keys
+    }
+    
+    /// Add DealReleaseReferences for the deal's `release_ids`
+    fn resolve_deal_releases(
+        deal: &mut crate::builder::DealRequest,
+        release_keys: &IndexMap<String, String>,
+    ) -> Result<usize, LinkerError> {
+        let mut linked = 0;
+        for release_id in &deal.release_ids {
+            let reference = release_keys.get(release_id)
+                .ok_or_else(|| LinkerError::UnknownRelease(release_id.clone()))?;
+            if !deal.release_references.contains(reference) {
+                deal.release_references.push(reference.clone());
+                linked += 1;
+            }
+        }
+        
+        Ok(linked)
+    }
+}
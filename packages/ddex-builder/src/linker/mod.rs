@@ -102,6 +102,15 @@ impl ReferenceLinker {
         self.auto_linker.process_request(request, &mut self.generator, &mut self.relationships)
     }
     
+    /// Resolve deal `release_ids` and release `track_isrcs` against the
+    /// references already present in a request
+    pub fn resolve_identifiers(
+        &self,
+        request: &mut crate::builder::BuildRequest,
+    ) -> Result<usize, LinkerError> {
+        self.auto_linker.resolve_identifiers(request)
+    }
+    
     /// Get all registered references for debugging
     pub fn get_all_references(&self) -> IndexMap<EntityType, IndexMap<String, String>> {
         self.relationships.get_all()
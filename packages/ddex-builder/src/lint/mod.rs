@@ -0,0 +1,512 @@
+//! Lint rules for DDEX messages
+//!
+//! Unlike preflight validation, which checks a `BuildRequest` before XML is
+//! generated, the linter reads finished XML and flags delivery problems the
+//! schema does not catch: missing P-lines, elements deprecated in the
+//! message's version, non-canonical namespace prefixes, suspicious durations
+//! and overlapping territories.
+//!
+//! Every rule has a default [`Severity`] that [`LintConfig`] can raise, lower
+//! or turn off. Some findings carry a safe autofix, applied by
+//! [`Linter::fix`] as minimal text edits so that the rest of the document
+//! keeps its formatting.
+//!
+//! ```rust
+//! use ddex_builder::lint::{Linter, LintConfig};
+//!
+//! let xml = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+//!   <ResourceList><SoundRecording>
+//!     <ResourceReference>A1</ResourceReference>
+//!     <Duration>3:45</Duration>
+//!     <PLine><PLineText>(P) 2024 Example Records</PLineText></PLine>
+//!   </SoundRecording></ResourceList>
+//! </ern:NewReleaseMessage>"#;
+//!
+//! let linter = Linter::new(LintConfig::default());
+//! let findings = linter.lint(xml)?;
+//! assert_eq!(findings[0].rule, "suspicious-duration");
+//!
+//! let fixed = linter.fix(xml)?;
+//! assert!(fixed.xml.contains("<Duration>PT3M45S</Duration>"));
+//! # Ok::<(), ddex_builder::BuildError>(())
+//! ```
+
+mod rules;
+pub mod sarif;
+
+pub(crate) use rules::duration_seconds;
+
+use crate::error::BuildError;
+use crate::presets::DdexVersion;
+use indexmap::IndexMap;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// How a rule's findings are reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The rule does not run
+    Off,
+    /// Reported without affecting the outcome
+    Info,
+    /// Worth fixing before delivery
+    Warning,
+    /// Fails the lint run
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Severity::Off => "off",
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Description of a lint rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RuleInfo {
+    /// Identifier used in findings and configuration, e.g. `missing-p-line`
+    pub id: &'static str,
+    /// What the rule checks
+    pub description: &'static str,
+    /// Severity unless configured otherwise
+    pub default_severity: Severity,
+    /// Whether some of its findings can be fixed automatically
+    pub fixable: bool,
+}
+
+/// Severity overrides by rule ID, e.g. the `[lint]` table of `ddex-builder.toml`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct LintConfig {
+    /// Severity by rule ID; unlisted rules keep their default
+    pub severities: IndexMap<String, Severity>,
+}
+
+impl LintConfig {
+    /// Set the severity of a rule
+    pub fn with_severity(mut self, rule: impl Into<String>, severity: Severity) -> Self {
+        self.severities.insert(rule.into(), severity);
+        self
+    }
+
+    /// Check that every configured rule exists
+    pub fn validate(&self) -> Result<(), String> {
+        let known: Vec<&str> = rules::builtin().iter().map(|rule| rule.info().id).collect();
+        match self.severities.keys().find(|id| !known.contains(&id.as_str())) {
+            Some(id) => Err(format!("Unknown lint rule '{}' (known rules: {})", id, known.join(", "))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A problem found in a document
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LintFinding {
+    /// ID of the rule that reported it
+    pub rule: String,
+    /// Configured severity of the rule
+    pub severity: Severity,
+    /// Description of the problem
+    pub message: String,
+    /// Element path, e.g. `/NewReleaseMessage/ReleaseList[1]/Release[1]`
+    pub path: String,
+    /// 1-based line of the element's start tag
+    pub line: usize,
+    /// What the autofix does, if the finding has one
+    pub fix: Option<String>,
+    #[serde(skip)]
+    edits: Vec<Edit>,
+}
+
+/// Result of [`Linter::fix`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixResult {
+    /// The document with the fixes applied
+    pub xml: String,
+    /// Number of findings fixed
+    pub applied: usize,
+}
+
+/// Runs the built-in rules with configured severities
+pub struct Linter {
+    config: LintConfig,
+    rules: Vec<Box<dyn rules::Rule>>,
+}
+
+impl Linter {
+    /// Create a linter running every built-in rule
+    pub fn new(config: LintConfig) -> Self {
+        Self { config, rules: rules::builtin() }
+    }
+
+    /// Every rule with its default severity
+    pub fn rules(&self) -> Vec<RuleInfo> {
+        self.rules.iter().map(|rule| rule.info()).collect()
+    }
+
+    /// Severity a rule reports with under this configuration
+    pub fn severity(&self, rule: &RuleInfo) -> Severity {
+        self.config.severities.get(rule.id).copied().unwrap_or(rule.default_severity)
+    }
+
+    /// Findings of all enabled rules, in document order
+    pub fn lint(&self, xml: &str) -> Result<Vec<LintFinding>, BuildError> {
+        let document = Document::parse(xml)?;
+        let mut findings = Vec::new();
+
+        for rule in &self.rules {
+            let info = rule.info();
+            let severity = self.severity(&info);
+            if severity == Severity::Off {
+                continue;
+            }
+            let mut diagnostics = Vec::new();
+            rule.check(&document, &mut diagnostics);
+            findings.extend(diagnostics.into_iter().map(|diagnostic| {
+                let element = &document.elements[diagnostic.element];
+                LintFinding {
+                    rule: info.id.to_string(),
+                    severity,
+                    message: diagnostic.message,
+                    path: element.path.clone(),
+                    line: element.line,
+                    fix: diagnostic.fix.as_ref().map(|fix| fix.description.clone()),
+                    edits: diagnostic.fix.map(|fix| fix.edits).unwrap_or_default(),
+                }
+            }));
+        }
+
+        findings.sort_by_key(|finding| finding.line);
+        Ok(findings)
+    }
+
+    /// Apply the autofixes of all enabled rules
+    ///
+    /// Fixes whose edits overlap an earlier fix are applied in a further
+    /// pass, once the document has been re-read.
+    pub fn fix(&self, xml: &str) -> Result<FixResult, BuildError> {
+        let mut xml = xml.to_string();
+        let mut applied = 0;
+
+        // Each pass applies at least one fix, so this only bounds pathological input
+        for _ in 0..8 {
+            let mut fixes: Vec<Vec<Edit>> = self
+                .lint(&xml)?
+                .into_iter()
+                .map(|finding| finding.edits)
+                .filter(|edits| !edits.is_empty())
+                .collect();
+            if fixes.is_empty() {
+                break;
+            }
+            fixes.sort_by_key(|edits| edits.iter().map(|e| e.range.start).min());
+
+            let mut taken: Vec<Range<usize>> = Vec::new();
+            let mut edits = Vec::new();
+            for fix in fixes {
+                let overlaps = fix
+                    .iter()
+                    .any(|edit| taken.iter().any(|r| edit.range.start < r.end && r.start < edit.range.end));
+                if !overlaps {
+                    taken.extend(fix.iter().map(|edit| edit.range.clone()));
+                    edits.extend(fix);
+                    applied += 1;
+                }
+            }
+
+            edits.sort_by_key(|edit| std::cmp::Reverse(edit.range.start));
+            for edit in edits {
+                xml.replace_range(edit.range, &edit.replacement);
+            }
+        }
+
+        Ok(FixResult { xml, applied })
+    }
+}
+
+/// Replace a byte range of the source
+#[derive(Debug, Clone, PartialEq)]
+struct Edit {
+    range: Range<usize>,
+    replacement: String,
+}
+
+/// An autofix: a description and the edits making it
+struct Fix {
+    description: String,
+    edits: Vec<Edit>,
+}
+
+/// What a rule reports about one element
+struct Diagnostic {
+    element: usize,
+    message: String,
+    fix: Option<Fix>,
+}
+
+/// A document read with the byte spans needed to rewrite it in place
+///
+/// Shared with [`crate::split`], which cuts and joins messages the same way
+/// the autofixes edit them.
+pub(crate) struct Document<'a> {
+    pub(crate) source: &'a str,
+    /// Elements in document order; the root is first
+    pub(crate) elements: Vec<XmlElement>,
+    /// Version of the root element's ERN namespace
+    pub(crate) version: Option<DdexVersion>,
+}
+
+pub(crate) struct XmlElement {
+    /// Local name
+    pub(crate) name: String,
+    pub(crate) prefix: Option<String>,
+    pub(crate) path: String,
+    pub(crate) line: usize,
+    pub(crate) start_tag: Range<usize>,
+    /// `None` for empty-element tags
+    pub(crate) end_tag: Option<Range<usize>>,
+    /// Trimmed direct text content
+    pub(crate) text: String,
+    /// Span of the trimmed text, when it is a single unescaped run
+    pub(crate) text_span: Option<Range<usize>>,
+    pub(crate) parent: Option<usize>,
+    pub(crate) children: Vec<usize>,
+    /// Namespace declarations on this element; the default namespace has an empty prefix
+    pub(crate) namespaces: Vec<(String, String)>,
+    /// Other attributes by qualified name, unescaped
+    pub(crate) attributes: Vec<(String, String)>,
+}
+
+impl XmlElement {
+    /// From the start tag through the end tag
+    pub(crate) fn span(&self) -> Range<usize> {
+        self.start_tag.start..self.end_tag.as_ref().unwrap_or(&self.start_tag).end
+    }
+}
+
+impl<'a> Document<'a> {
+    pub(crate) fn parse(source: &'a str) -> Result<Self, BuildError> {
+        let mut reader = Reader::from_str(source);
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        let line = |offset: usize| line_starts.partition_point(|&start| start <= offset);
+
+        let mut elements: Vec<XmlElement> = Vec::new();
+        // Open elements with the number of children seen per name
+        let mut stack: Vec<(usize, IndexMap<String, usize>)> = Vec::new();
+        let mut root_seen = false;
+
+        loop {
+            let start = reader.buffer_position() as usize;
+            let event = reader.read_event().map_err(|e| BuildError::InvalidFormat {
+                field: "xml".to_string(),
+                message: format!("{} at line {}", e, line(start)),
+            })?;
+            let end = reader.buffer_position() as usize;
+
+            match event {
+                Event::Start(ref e) | Event::Empty(ref e) => {
+                    let (name, prefix, namespaces, attributes) = read_start(e)?;
+                    let parent = stack.last().map(|(index, _)| *index);
+                    let path = match stack.last_mut() {
+                        Some((index, counts)) => {
+                            let count = counts.entry(name.clone()).or_insert(0);
+                            *count += 1;
+                            format!("{}/{}[{}]", elements[*index].path, name, count)
+                        }
+                        None if !root_seen => format!("/{}", name),
+                        None => {
+                            return Err(BuildError::InvalidFormat {
+                                field: "xml".to_string(),
+                                message: format!("Second root element '{}' at line {}", name, line(start)),
+                            })
+                        }
+                    };
+                    root_seen = true;
+
+                    let index = elements.len();
+                    if let Some(parent) = parent {
+                        elements[parent].children.push(index);
+                    }
+                    elements.push(XmlElement {
+                        name,
+                        prefix,
+                        path,
+                        line: line(start),
+                        start_tag: start..end,
+                        end_tag: None,
+                        text: String::new(),
+                        text_span: None,
+                        parent,
+                        children: Vec::new(),
+                        namespaces,
+                        attributes,
+                    });
+                    if matches!(event, Event::Start(_)) {
+                        stack.push((index, IndexMap::new()));
+                    }
+                }
+                Event::End(_) => {
+                    if let Some((index, _)) = stack.pop() {
+                        elements[index].end_tag = Some(start..end);
+                    }
+                }
+                Event::Text(ref t) => {
+                    let Some((index, _)) = stack.last() else { continue };
+                    let raw = &source[start..end];
+                    let trimmed = raw.trim();
+                    if trimmed.is_empty() {
+                        continue;
+                    }
+                    let text = t.unescape().map_err(|e| BuildError::InvalidFormat {
+                        field: "xml".to_string(),
+                        message: format!("{} at line {}", e, line(start)),
+                    })?;
+                    let element = &mut elements[*index];
+                    if element.text.is_empty() && !trimmed.contains('&') {
+                        let offset = start + (raw.len() - raw.trim_start().len());
+                        element.text_span = Some(offset..offset + trimmed.len());
+                    } else {
+                        element.text_span = None;
+                    }
+                    element.text.push_str(text.trim());
+                }
+                Event::CData(ref t) => {
+                    if let Some((index, _)) = stack.last() {
+                        let element = &mut elements[*index];
+                        element.text.push_str(String::from_utf8_lossy(t).trim());
+                        element.text_span = None;
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        if let Some((index, _)) = stack.last() {
+            return Err(BuildError::InvalidFormat {
+                field: "xml".to_string(),
+                message: format!("Unclosed element '{}'", elements[*index].name),
+            });
+        }
+        if elements.is_empty() {
+            return Err(BuildError::InvalidFormat {
+                field: "xml".to_string(),
+                message: "No root element found".to_string(),
+            });
+        }
+
+        let version = elements[0]
+            .namespaces
+            .iter()
+            .find_map(|(_, uri)| uri.strip_prefix("http://ddex.net/xml/ern/"))
+            .and_then(|suffix| match suffix.trim_end_matches('/') {
+                "382" => Some(DdexVersion::Ern382),
+                "41" => Some(DdexVersion::Ern41),
+                "42" => Some(DdexVersion::Ern42),
+                "43" => Some(DdexVersion::Ern43),
+                "44" => Some(DdexVersion::Ern44),
+                _ => None,
+            });
+
+        Ok(Self { source, elements, version })
+    }
+
+    pub(crate) fn children<'d>(&'d self, element: usize) -> impl Iterator<Item = (usize, &'d XmlElement)> + 'd {
+        self.elements[element].children.iter().map(move |&i| (i, &self.elements[i]))
+    }
+
+    /// Direct children named `name`
+    pub(crate) fn children_named<'d>(&'d self, element: usize, name: &'d str) -> impl Iterator<Item = (usize, &'d XmlElement)> + 'd {
+        self.children(element).filter(move |(_, child)| child.name == name)
+    }
+
+    /// All elements below `element` in document order
+    pub(crate) fn descendants(&self, element: usize) -> impl Iterator<Item = (usize, &XmlElement)> + '_ {
+        // Descendants directly follow their ancestor and end where its span does
+        let end = self.elements[element].span().end;
+        self.elements[element + 1..]
+            .iter()
+            .enumerate()
+            .map(move |(i, e)| (element + 1 + i, e))
+            .take_while(move |(_, e)| e.start_tag.start < end)
+    }
+
+    /// Namespace URI of an element, from the declarations in scope
+    pub(crate) fn namespace_of(&self, element: usize) -> Option<&str> {
+        let prefix = self.elements[element].prefix.as_deref().unwrap_or("");
+        let mut current = Some(element);
+        while let Some(index) = current {
+            let element = &self.elements[index];
+            if let Some((_, uri)) = element.namespaces.iter().find(|(declared, _)| declared == prefix) {
+                return Some(uri.as_str()).filter(|uri| !uri.is_empty());
+            }
+            current = element.parent;
+        }
+        None
+    }
+
+    pub(crate) fn parent_name(&self, element: usize) -> Option<&str> {
+        self.elements[element].parent.map(|p| self.elements[p].name.as_str())
+    }
+
+    /// Span of an element together with the indentation before it
+    pub(crate) fn removal_range(&self, element: usize) -> Range<usize> {
+        let span = self.elements[element].span();
+        let before = &self.source[..span.start];
+        let indent = before.len() - before.trim_end_matches([' ', '\t']).len();
+        let start = if before[..before.len() - indent].ends_with('\n') {
+            // The element is alone on its line: drop the line
+            span.start - indent - 1
+        } else {
+            span.start
+        };
+        start..span.end
+    }
+}
+
+/// Local name, prefix, namespace declarations and other attributes of a start tag
+#[allow(clippy::type_complexity)]
+fn read_start(
+    start: &BytesStart<'_>,
+) -> Result<(String, Option<String>, Vec<(String, String)>, Vec<(String, String)>), BuildError> {
+    let qname = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+    let (prefix, name) = match qname.split_once(':') {
+        Some((prefix, local)) => (Some(prefix.to_string()), local.to_string()),
+        None => (None, qname),
+    };
+
+    let mut namespaces = Vec::new();
+    let mut attributes = Vec::new();
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(|e| BuildError::InvalidFormat {
+            field: "xml".to_string(),
+            message: format!("Attribute error in '{}': {}", name, e),
+        })?;
+        let key = String::from_utf8_lossy(attribute.key.as_ref()).into_owned();
+        if key == "xmlns" || key.starts_with("xmlns:") {
+            let value = String::from_utf8_lossy(&attribute.value).into_owned();
+            namespaces.push((key.strip_prefix("xmlns:").unwrap_or("").to_string(), value));
+        } else {
+            let value = attribute.unescape_value().map_err(|e| BuildError::InvalidFormat {
+                field: "xml".to_string(),
+                message: format!("Attribute error in '{}': {}", name, e),
+            })?;
+            attributes.push((key, value.into_owned()));
+        }
+    }
+    Ok((name, prefix, namespaces, attributes))
+}
+
+#[cfg(test)]
+mod tests;
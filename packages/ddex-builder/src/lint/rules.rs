@@ -0,0 +1,388 @@
+//! Built-in lint rules
+
+use super::{Diagnostic, Document, Edit, Fix, RuleInfo, Severity};
+use crate::canonical::rules::get_namespace_prefixes;
+use crate::presets::DdexVersion;
+use crate::versions::VersionManager;
+use ddex_core::duration::DdexDuration;
+use ddex_core::territory::{DealScope, TerritorySet};
+use indexmap::IndexSet;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::time::Duration;
+
+/// A check run over a whole document
+pub(super) trait Rule: Send + Sync {
+    fn info(&self) -> RuleInfo;
+    fn check(&self, document: &Document<'_>, diagnostics: &mut Vec<Diagnostic>);
+}
+
+pub(super) fn builtin() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(MissingPLine),
+        Box::new(DeprecatedElement),
+        Box::new(NonCanonicalPrefix),
+        Box::new(SuspiciousDuration),
+        Box::new(TerritoryOverlap),
+    ]
+}
+
+/// Releases and sound recordings without a PLine
+struct MissingPLine;
+
+impl Rule for MissingPLine {
+    fn info(&self) -> RuleInfo {
+        RuleInfo {
+            id: "missing-p-line",
+            description: "Releases and sound recordings should carry a PLine",
+            default_severity: Severity::Warning,
+            fixable: false,
+        }
+    }
+
+    fn check(&self, document: &Document<'_>, diagnostics: &mut Vec<Diagnostic>) {
+        for (index, element) in document.elements.iter().enumerate() {
+            let (reference_name, kind) = match (element.name.as_str(), document.parent_name(index)) {
+                ("Release", Some("ReleaseList")) => ("ReleaseReference", "Release"),
+                ("SoundRecording", Some("ResourceList")) => ("ResourceReference", "SoundRecording"),
+                _ => continue,
+            };
+            if document.descendants(index).any(|(_, e)| e.name == "PLine") {
+                continue;
+            }
+            let message = match document.children_named(index, reference_name).next() {
+                Some((_, reference)) => format!("{} {} has no PLine", kind, reference.text),
+                None => format!("{} has no PLine", kind),
+            };
+            diagnostics.push(Diagnostic { element: index, message, fix: None });
+        }
+    }
+}
+
+/// ERN 3 elements that ERN 4 replaced, with what to use instead
+const ERN3_ELEMENTS: &[(&str, &str)] = &[
+    ("ReleaseDetailsByTerritory", "move its details onto the Release"),
+    ("SoundRecordingDetailsByTerritory", "move its details onto the SoundRecording"),
+    ("ReferenceTitle", "use DisplayTitleText and DisplayTitle"),
+    ("TechnicalSoundRecordingDetails", "use TechnicalDetails"),
+    ("ReleaseResourceReferenceList", "use ResourceGroup"),
+    ("ResourceContributor", "use Contributor"),
+];
+
+/// Elements deprecated in the message's version
+struct DeprecatedElement;
+
+impl Rule for DeprecatedElement {
+    fn info(&self) -> RuleInfo {
+        RuleInfo {
+            id: "deprecated-element",
+            description: "Elements deprecated in, or removed from, the message's ERN version",
+            default_severity: Severity::Warning,
+            fixable: false,
+        }
+    }
+
+    fn check(&self, document: &Document<'_>, diagnostics: &mut Vec<Diagnostic>) {
+        let Some(version) = document.version else { return };
+        let is_ern4 = version != DdexVersion::Ern382;
+        let manager = VersionManager::new();
+        let deprecated = manager
+            .get_version_spec(version)
+            .map(|spec| spec.deprecated_elements.as_slice())
+            .unwrap_or_default();
+
+        for (index, element) in document.elements.iter().enumerate() {
+            let replacement = ERN3_ELEMENTS.iter().find(|(name, _)| *name == element.name).filter(|_| is_ern4);
+            let message = match replacement {
+                Some((name, hint)) => format!("{} is not part of {}; {}", name, version, hint),
+                None if deprecated.contains(&element.name) => format!("{} is deprecated in {}", element.name, version),
+                None => continue,
+            };
+            diagnostics.push(Diagnostic { element: index, message, fix: None });
+        }
+    }
+}
+
+/// Namespace prefixes that differ from the DB-C14N prefix lock table
+struct NonCanonicalPrefix;
+
+impl Rule for NonCanonicalPrefix {
+    fn info(&self) -> RuleInfo {
+        RuleInfo {
+            id: "non-canonical-prefix",
+            description: "DDEX namespaces should be bound to their canonical prefixes (ern, avs, xsi, ...)",
+            default_severity: Severity::Warning,
+            fixable: true,
+        }
+    }
+
+    fn check(&self, document: &Document<'_>, diagnostics: &mut Vec<Diagnostic>) {
+        let version = match document.version {
+            Some(DdexVersion::Ern382) => "3.8.2",
+            Some(DdexVersion::Ern42) => "4.2",
+            Some(DdexVersion::Ern43) => "4.3",
+            Some(DdexVersion::Ern44) => "4.4",
+            Some(DdexVersion::Ern41) | None => return,
+        };
+        let locked = get_namespace_prefixes(version);
+        let root = &document.elements[0];
+
+        for (prefix, uri) in &root.namespaces {
+            let Some(canonical) = locked.get(uri).filter(|canonical| *canonical != prefix) else { continue };
+
+            if prefix.is_empty() {
+                let message = format!("{} is the default namespace instead of being bound to '{}'", uri, canonical);
+                diagnostics.push(Diagnostic { element: 0, message, fix: None });
+                continue;
+            }
+
+            let message = format!("{} is bound to '{}' instead of '{}'", uri, prefix, canonical);
+            // Renaming is only safe when the canonical prefix is still free
+            let fix = (!root.namespaces.iter().any(|(p, _)| p == canonical)).then(|| Fix {
+                description: format!("rename prefix '{}' to '{}'", prefix, canonical),
+                edits: rename_prefix(document, prefix, canonical),
+            });
+            diagnostics.push(Diagnostic { element: 0, message, fix });
+        }
+    }
+}
+
+/// Edits renaming `from` to `to` in tag names, attribute names and the declaration
+fn rename_prefix(document: &Document<'_>, from: &str, to: &str) -> Vec<Edit> {
+    let mut edits = Vec::new();
+    let qualified = format!("{}:", from);
+    let declaration = format!("xmlns:{}=", from);
+
+    for element in &document.elements {
+        if element.prefix.as_deref() == Some(from) {
+            let start = element.start_tag.start + 1;
+            edits.push(Edit { range: start..start + from.len(), replacement: to.to_string() });
+            if let Some(end_tag) = &element.end_tag {
+                let start = end_tag.start + 2;
+                edits.push(Edit { range: start..start + from.len(), replacement: to.to_string() });
+            }
+        }
+
+        // Attribute names follow whitespace; the tag name itself does not
+        let tag = &document.source[element.start_tag.clone()];
+        for (offset, _) in tag.match_indices(&qualified).chain(tag.match_indices(&declaration)) {
+            let preceded_by_space = tag[..offset].ends_with(char::is_whitespace);
+            if preceded_by_space && !in_attribute_value(&tag[..offset]) {
+                let start = element.start_tag.start + offset + if tag[offset..].starts_with("xmlns:") { 6 } else { 0 };
+                edits.push(Edit { range: start..start + from.len(), replacement: to.to_string() });
+            }
+        }
+    }
+    edits
+}
+
+/// Whether the end of a partial start tag lies inside a quoted attribute value
+fn in_attribute_value(tag: &str) -> bool {
+    let mut quote = None;
+    for c in tag.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            None if c == '"' || c == '\'' => quote = Some(c),
+            _ => {}
+        }
+    }
+    quote.is_some()
+}
+
+static CLOCK_DURATION: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(?:(\d+):)?(\d{1,2}):(\d{2})$").unwrap());
+
+/// Seconds in an ISO 8601 duration, counting years as 365 days and months as 30
+pub(crate) fn duration_seconds(value: &str) -> Option<f64> {
+    value.parse::<DdexDuration>().ok().map(|duration| duration.as_secs_f64())
+}
+
+/// Durations that are not ISO 8601, are zero or are implausibly long
+struct SuspiciousDuration;
+
+impl SuspiciousDuration {
+    /// ISO 8601 form of an `[h:]mm:ss` clock duration
+    fn from_clock(value: &str) -> Option<String> {
+        let captures = CLOCK_DURATION.captures(value)?;
+        let hours: u64 = captures.get(1).map_or(Some(0), |m| m.as_str().parse().ok())?;
+        let minutes: u64 = captures[2].parse().ok()?;
+        let seconds: u64 = captures[3].parse().ok()?;
+        if seconds >= 60 || (hours > 0 && minutes >= 60) {
+            return None;
+        }
+        Some(DdexDuration::from(Duration::from_secs(hours * 3600 + minutes * 60 + seconds)).to_string())
+    }
+}
+
+impl Rule for SuspiciousDuration {
+    fn info(&self) -> RuleInfo {
+        RuleInfo {
+            id: "suspicious-duration",
+            description: "Durations should be non-zero ISO 8601 durations of plausible length",
+            default_severity: Severity::Warning,
+            fixable: true,
+        }
+    }
+
+    fn check(&self, document: &Document<'_>, diagnostics: &mut Vec<Diagnostic>) {
+        for (index, element) in document.elements.iter().enumerate() {
+            if element.name != "Duration" || element.text.is_empty() {
+                continue;
+            }
+            let value = element.text.as_str();
+            // A release runs as long as all its tracks together
+            let limit_hours = if document.parent_name(index) == Some("Release") { 48.0 } else { 6.0 };
+
+            let (message, fix) = match duration_seconds(value) {
+                Some(0.0) => (format!("Duration {} is zero", value), None),
+                Some(seconds) if seconds > limit_hours * 3600.0 => {
+                    (format!("Duration {} is longer than {} hours", value, limit_hours), None)
+                }
+                Some(_) => continue,
+                None => {
+                    let fix = Self::from_clock(value).zip(element.text_span.clone()).map(|(iso, range)| Fix {
+                        description: format!("rewrite as {}", iso),
+                        edits: vec![Edit { range, replacement: iso }],
+                    });
+                    (format!("Duration '{}' is not an ISO 8601 duration", value), fix)
+                }
+            };
+            diagnostics.push(Diagnostic { element: index, message, fix });
+        }
+    }
+}
+
+/// Territories listed twice, both included and excluded, or covered by
+/// another deal for the same release, use and period
+struct TerritoryOverlap;
+
+impl TerritoryOverlap {
+    fn codes<'d>(document: &'d Document<'_>, element: usize, name: &'d str) -> impl Iterator<Item = (usize, String)> + 'd {
+        document
+            .children_named(element, name)
+            .map(|(index, code)| (index, code.text.to_ascii_uppercase()))
+    }
+
+    fn check_list(document: &Document<'_>, element: usize, diagnostics: &mut Vec<Diagnostic>) {
+        let included: Vec<(usize, String)> = Self::codes(document, element, "TerritoryCode").collect();
+        let excluded: Vec<(usize, String)> = Self::codes(document, element, "ExcludedTerritoryCode").collect();
+
+        for list in [&included, &excluded] {
+            let mut seen = IndexSet::new();
+            for (index, code) in list {
+                if !seen.insert(code) {
+                    diagnostics.push(Diagnostic {
+                        element: *index,
+                        message: format!("Territory {} is listed twice", code),
+                        fix: Some(Fix {
+                            description: "remove the duplicate".to_string(),
+                            edits: vec![Edit { range: document.removal_range(*index), replacement: String::new() }],
+                        }),
+                    });
+                }
+            }
+        }
+
+        let worldwide = included.iter().any(|(_, code)| code == "WORLDWIDE");
+        for (index, code) in included.iter().filter(|(_, code)| code != "WORLDWIDE") {
+            if worldwide {
+                let message = format!("Territory {} is already covered by Worldwide", code);
+                diagnostics.push(Diagnostic { element: *index, message, fix: None });
+            }
+            if excluded.iter().any(|(_, excluded)| excluded == code) {
+                let message = format!("Territory {} is both included and excluded", code);
+                diagnostics.push(Diagnostic { element: *index, message, fix: None });
+            }
+        }
+    }
+
+    /// What a deal grants: territories, commercial models, use types and period
+    fn terms(document: &Document<'_>, deal: usize) -> Option<DealScope> {
+        let (terms, _) = document.children_named(deal, "DealTerms").next()?;
+        if document.descendants(terms).any(|(_, e)| e.name == "TakeDown" && e.text.eq_ignore_ascii_case("true")) {
+            return None;
+        }
+        let texts = |name: &str| -> Vec<String> {
+            document
+                .descendants(terms)
+                .filter(|(_, e)| e.name == name && !e.text.is_empty())
+                .map(|(_, e)| e.text.to_ascii_uppercase())
+                .collect::<IndexSet<_>>()
+                .into_iter()
+                .collect()
+        };
+        // Unknown codes grant nothing; other checks report them
+        let territories = |name: &str| {
+            Self::codes(document, terms, name)
+                .filter_map(|(_, code)| TerritorySet::from_codes([code]).ok())
+                .fold(TerritorySet::empty(), |set, code| set.union(&code))
+        };
+        let included = if document.children_named(terms, "TerritoryCode").next().is_some() {
+            territories("TerritoryCode")
+        } else {
+            TerritorySet::worldwide()
+        };
+        let period = document.descendants(terms).find(|(_, e)| e.name == "ValidityPeriod").map(|(i, _)| i);
+        let date = |names: [&str; 2]| {
+            let (_, bound) = document.children(period?).find(|(_, e)| names.contains(&e.name.as_str()))?;
+            bound.text.get(..10)?.parse().ok()
+        };
+        Some(DealScope {
+            territories: included.difference(&territories("ExcludedTerritoryCode")),
+            commercial_models: texts("CommercialModelType"),
+            use_types: texts("UseType"),
+            start: date(["StartDate", "StartDateTime"]),
+            end: date(["EndDate", "EndDateTime"]),
+        })
+    }
+}
+
+impl Rule for TerritoryOverlap {
+    fn info(&self) -> RuleInfo {
+        RuleInfo {
+            id: "territory-overlap",
+            description: "Territories should be listed once and granted by a single deal per use and period",
+            default_severity: Severity::Warning,
+            fixable: true,
+        }
+    }
+
+    fn check(&self, document: &Document<'_>, diagnostics: &mut Vec<Diagnostic>) {
+        for (index, element) in document.elements.iter().enumerate() {
+            let has_codes = document
+                .children(index)
+                .any(|(_, child)| child.name == "TerritoryCode" || child.name == "ExcludedTerritoryCode");
+            if has_codes {
+                Self::check_list(document, index, diagnostics);
+            }
+
+            if element.name != "ReleaseDeal" {
+                continue;
+            }
+            let deals: Vec<(usize, DealScope)> = document
+                .children_named(index, "Deal")
+                .filter_map(|(deal, _)| Some((deal, Self::terms(document, deal)?)))
+                .collect();
+            for (i, (deal, scope)) in deals.iter().enumerate() {
+                let Some((position, territories)) = deals[..i]
+                    .iter()
+                    .map(|(_, earlier)| earlier.overlap(scope))
+                    .enumerate()
+                    .find(|(_, shared)| !shared.is_empty())
+                    .map(|(j, shared)| (j + 1, shared))
+                else {
+                    continue;
+                };
+                diagnostics.push(Diagnostic {
+                    element: *deal,
+                    message: format!(
+                        "Deal {} grants {} for the same use and period as deal {}",
+                        i + 1,
+                        territories,
+                        position
+                    ),
+                    fix: None,
+                });
+            }
+        }
+    }
+}
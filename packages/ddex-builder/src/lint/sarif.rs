@@ -0,0 +1,74 @@
+//! SARIF 2.1.0 output for CI annotations
+//!
+//! Code scanning services (GitHub, GitLab, Azure DevOps) read SARIF logs and
+//! annotate the offending lines of the linted files.
+
+use super::{LintFinding, RuleInfo, Severity};
+use serde_json::{json, Value};
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "note",
+        Severity::Off => "none",
+    }
+}
+
+/// A SARIF log with one run covering the findings of every file
+///
+/// `files` pairs each file's URI, as it should appear in annotations, with
+/// its findings; `rules` describes the rules the findings refer to.
+pub fn to_sarif(files: &[(String, Vec<LintFinding>)], rules: &[RuleInfo]) -> Value {
+    let rule_index = |id: &str| rules.iter().position(|rule| rule.id == id);
+
+    let results: Vec<Value> = files
+        .iter()
+        .flat_map(|(uri, findings)| findings.iter().map(move |finding| (uri, finding)))
+        .map(|(uri, finding)| {
+            let mut result = json!({
+                "ruleId": finding.rule,
+                "level": level(finding.severity),
+                "message": { "text": finding.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": uri },
+                        "region": { "startLine": finding.line }
+                    },
+                    "logicalLocations": [{ "fullyQualifiedName": finding.path, "kind": "element" }]
+                }]
+            });
+            if let Some(index) = rule_index(&finding.rule) {
+                result["ruleIndex"] = json!(index);
+            }
+            result
+        })
+        .collect();
+
+    let rules: Vec<Value> = rules
+        .iter()
+        .map(|rule| {
+            json!({
+                "id": rule.id,
+                "shortDescription": { "text": rule.description },
+                "defaultConfiguration": { "level": level(rule.default_severity) }
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "ddex-builder",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_HOMEPAGE"),
+                    "rules": rules
+                }
+            },
+            "results": results
+        }]
+    })
+}
@@ -0,0 +1,139 @@
+//! Tests for the lint rules and autofixes
+
+use super::*;
+
+const ERN43: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ddex:NewReleaseMessage xmlns:ddex="http://ddex.net/xml/ern/43" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" ddex:LanguageAndScriptCode="en">
+  <ResourceList>
+    <SoundRecording>
+      <ResourceReference>A1</ResourceReference>
+      <ReferenceTitle><TitleText>Midnight Drive</TitleText></ReferenceTitle>
+      <Duration>3:45</Duration>
+    </SoundRecording>
+    <SoundRecording>
+      <ResourceReference>A2</ResourceReference>
+      <Duration>PT0S</Duration>
+      <PLine><PLineText>(P) 2024 Example Records</PLineText></PLine>
+    </SoundRecording>
+  </ResourceList>
+  <DealList>
+    <ReleaseDeal>
+      <DealReleaseReference>R1</DealReleaseReference>
+      <Deal>
+        <DealTerms>
+          <TerritoryCode>GB</TerritoryCode>
+          <TerritoryCode>US</TerritoryCode>
+          <TerritoryCode>GB</TerritoryCode>
+          <CommercialModelType>SubscriptionModel</CommercialModelType>
+          <UseType>Stream</UseType>
+          <ValidityPeriod><StartDate>2024-01-01</StartDate></ValidityPeriod>
+        </DealTerms>
+      </Deal>
+      <Deal>
+        <DealTerms>
+          <TerritoryCode>Worldwide</TerritoryCode>
+          <ExcludedTerritoryCode>US</ExcludedTerritoryCode>
+          <CommercialModelType>SubscriptionModel</CommercialModelType>
+          <UseType>Stream</UseType>
+          <ValidityPeriod><StartDate>2024-06-01</StartDate></ValidityPeriod>
+        </DealTerms>
+      </Deal>
+      <Deal>
+        <DealTerms>
+          <TerritoryCode>GB</TerritoryCode>
+          <CommercialModelType>PayAsYouGoModel</CommercialModelType>
+          <UseType>PermanentDownload</UseType>
+          <ValidityPeriod><StartDate>2024-01-01</StartDate></ValidityPeriod>
+        </DealTerms>
+      </Deal>
+    </ReleaseDeal>
+  </DealList>
+</ddex:NewReleaseMessage>"#;
+
+fn rules_of(findings: &[LintFinding]) -> Vec<(&str, usize)> {
+    findings.iter().map(|f| (f.rule.as_str(), f.line)).collect()
+}
+
+#[test]
+fn test_lint_reports_each_rule() {
+    let findings = Linter::new(LintConfig::default()).lint(ERN43).unwrap();
+    assert_eq!(
+        rules_of(&findings),
+        vec![
+            ("non-canonical-prefix", 2),
+            ("missing-p-line", 4),
+            ("deprecated-element", 6),
+            ("suspicious-duration", 7),
+            ("suspicious-duration", 11),
+            ("territory-overlap", 22),
+            ("territory-overlap", 28),
+        ]
+    );
+
+    assert_eq!(findings[0].message, "http://ddex.net/xml/ern/43 is bound to 'ddex' instead of 'ern'");
+    assert_eq!(findings[1].message, "SoundRecording A1 has no PLine");
+    assert_eq!(findings[1].path, "/NewReleaseMessage/ResourceList[1]/SoundRecording[1]");
+    assert_eq!(findings[3].fix.as_deref(), Some("rewrite as PT3M45S"));
+    assert_eq!(findings[4].message, "Duration PT0S is zero");
+    assert_eq!(findings[5].message, "Territory GB is listed twice");
+    // The second deal shares GB; the download deal is for another use
+    assert_eq!(findings[6].message, "Deal 2 grants GB for the same use and period as deal 1");
+}
+
+#[test]
+fn test_severities_are_configurable() {
+    let config = LintConfig::default()
+        .with_severity("missing-p-line", Severity::Error)
+        .with_severity("territory-overlap", Severity::Off);
+    assert!(config.validate().is_ok());
+
+    let findings = Linter::new(config).lint(ERN43).unwrap();
+    assert!(findings.iter().all(|f| f.rule != "territory-overlap"));
+    assert_eq!(findings.iter().find(|f| f.rule == "missing-p-line").unwrap().severity, Severity::Error);
+
+    assert!(LintConfig::default().with_severity("no-such-rule", Severity::Info).validate().is_err());
+}
+
+#[test]
+fn test_fix_applies_safe_fixes_only() {
+    let linter = Linter::new(LintConfig::default());
+    let fixed = linter.fix(ERN43).unwrap();
+    assert_eq!(fixed.applied, 3);
+
+    assert!(fixed.xml.contains(r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43""#));
+    assert!(fixed.xml.contains(r#"ern:LanguageAndScriptCode="en""#));
+    assert!(fixed.xml.ends_with("</ern:NewReleaseMessage>"));
+    assert!(fixed.xml.contains("<Duration>PT3M45S</Duration>"));
+    assert_eq!(fixed.xml.matches("<TerritoryCode>GB</TerritoryCode>").count(), 2);
+    assert!(fixed.xml.contains("<TerritoryCode>US</TerritoryCode>\n          <CommercialModelType>"));
+
+    let findings = linter.lint(&fixed.xml).unwrap();
+    let remaining = rules_of(&findings);
+    assert_eq!(remaining.len(), 4);
+    assert!(!remaining.iter().any(|(rule, _)| *rule == "non-canonical-prefix"));
+    assert_eq!(linter.fix(&fixed.xml).unwrap().applied, 0);
+}
+
+#[test]
+fn test_lint_rejects_malformed_xml() {
+    let linter = Linter::new(LintConfig::default());
+    assert!(linter.lint("<Release><Title>x</Release>").is_err());
+    assert!(linter.lint("<Release>").is_err());
+    assert!(linter.lint("").is_err());
+}
+
+#[test]
+fn test_sarif_output() {
+    let linter = Linter::new(LintConfig::default());
+    let findings = linter.lint(ERN43).unwrap();
+    let log = sarif::to_sarif(&[("releases/R1.xml".to_string(), findings)], &linter.rules());
+
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), linter.rules().len());
+    let result = &run["results"][0];
+    assert_eq!(result["ruleId"], "non-canonical-prefix");
+    assert_eq!(result["level"], "warning");
+    assert_eq!(result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"], "releases/R1.xml");
+    assert_eq!(result["locations"][0]["physicalLocation"]["region"]["startLine"], 2);
+}
@@ -0,0 +1,223 @@
+//! MEAD (Media Enrichment and Description) message generation
+//!
+//! MEAD carries enrichment that ERN has no place for: moods, focus tracks,
+//! marketing copy and award information, keyed by release identifiers so it
+//! can be delivered alongside (or after) the NewReleaseMessage it enriches.
+
+use crate::ast::{Element, AST};
+use crate::determinism::DeterminismConfig;
+use crate::error::BuildError;
+use crate::generator::xml_writer::XmlWriter;
+use ddex_core::models::common::{Identifier, IdentifierType};
+use ddex_core::models::mead::{
+    Award, FocusTrack, MeadMessage, ReleaseInformation, MEAD_11_NAMESPACE, MEAD_11_SCHEMA_VERSION,
+};
+use indexmap::IndexMap;
+
+/// Generator for MEAD 1.1 messages
+#[derive(Debug, Clone, Default)]
+pub struct MeadGenerator {
+    config: DeterminismConfig,
+}
+
+impl MeadGenerator {
+    /// Create a generator using the given determinism settings
+    pub fn new(config: DeterminismConfig) -> Self {
+        Self { config }
+    }
+
+    /// Build the MEAD message as an XML string
+    pub fn build(&self, message: &MeadMessage) -> Result<String, BuildError> {
+        let ast = self.generate(message)?;
+        XmlWriter::new(self.config.clone()).write(&ast)
+    }
+
+    /// Generate the AST for a MEAD message
+    pub fn generate(&self, message: &MeadMessage) -> Result<AST, BuildError> {
+        if message.message_id.is_empty() {
+            return Err(BuildError::MissingRequired {
+                field: "message_id".to_string(),
+            });
+        }
+
+        let mut root = Element::new("MeadMessage").with_attr("MessageSchemaVersionId", MEAD_11_SCHEMA_VERSION);
+        root.namespace = Some("mead".to_string());
+        root.add_child(self.generate_header(message)?);
+
+        let mut list = Element::new("ReleaseInformationList");
+        for (index, info) in message.release_information.iter().enumerate() {
+            list.add_child(self.generate_release_information(info, index)?);
+        }
+        root.add_child(list);
+
+        let mut namespaces = IndexMap::new();
+        namespaces.insert("mead".to_string(), MEAD_11_NAMESPACE.to_string());
+
+        Ok(AST {
+            root,
+            namespaces,
+            schema_location: None,
+        })
+    }
+
+    fn generate_header(&self, message: &MeadMessage) -> Result<Element, BuildError> {
+        let mut header = Element::new("MessageHeader");
+
+        let thread_id = message.message_thread_id.as_ref().unwrap_or(&message.message_id);
+        header.add_child(Element::new("MessageThreadId").with_text(thread_id));
+        header.add_child(Element::new("MessageId").with_text(&message.message_id));
+
+        if let Some(ref sender) = message.message_sender {
+            header.add_child(Element::new("MessageSender").with_child(Element::new("PartyId").with_text(sender)));
+        }
+        if let Some(ref recipient) = message.message_recipient {
+            header.add_child(Element::new("MessageRecipient").with_child(Element::new("PartyId").with_text(recipient)));
+        }
+
+        let created_time = match message.message_created_date_time {
+            Some(ref t) => t.clone(),
+            None => self.config.timestamp_source.resolve()?,
+        };
+        header.add_child(Element::new("MessageCreatedDateTime").with_text(created_time));
+
+        Ok(header)
+    }
+
+    fn generate_release_information(&self, info: &ReleaseInformation, index: usize) -> Result<Element, BuildError> {
+        if info.release_id.is_empty() {
+            return Err(BuildError::MissingRequired {
+                field: format!("release_information[{}].release_id", index),
+            });
+        }
+
+        let mut release_id = Element::new("ReleaseId");
+        for id in &info.release_id {
+            release_id.add_child(Self::generate_identifier(id));
+        }
+
+        let mut element = Element::new("ReleaseInformation")
+            .with_child(Element::new("ReleaseSummary").with_child(release_id));
+
+        for mood in &info.moods {
+            element.add_child(Element::new("Mood").with_text(mood));
+        }
+        for track in &info.focus_tracks {
+            element.add_child(Self::generate_focus_track(track));
+        }
+        for comment in &info.marketing_comments {
+            let mut comment_elem = Element::new("MarketingComment").with_text(&comment.text);
+            if let Some(ref lang) = comment.language_code {
+                comment_elem.attributes.insert("LanguageAndScriptCode".to_string(), lang.clone());
+            }
+            element.add_child(comment_elem);
+        }
+        for award in &info.awards {
+            element.add_child(Self::generate_award(award));
+        }
+
+        Ok(element)
+    }
+
+    fn generate_identifier(id: &Identifier) -> Element {
+        let name = match id.id_type {
+            IdentifierType::UPC | IdentifierType::EAN => "ICPN",
+            IdentifierType::GRid | IdentifierType::GRID => "GRid",
+            IdentifierType::ISRC => "ISRC",
+            _ => "ProprietaryId",
+        };
+        let mut element = Element::new(name).with_text(&id.value);
+        if let Some(ref namespace) = id.namespace {
+            element.attributes.insert("Namespace".to_string(), namespace.clone());
+        }
+        element
+    }
+
+    fn generate_focus_track(track: &FocusTrack) -> Element {
+        let mut element = Element::new("FocusTrack")
+            .with_child(Element::new("ResourceId").with_child(Element::new("ISRC").with_text(&track.isrc)));
+        if let Some(ref title) = track.title {
+            element.add_child(Element::new("Title").with_text(title));
+        }
+        element
+    }
+
+    fn generate_award(award: &Award) -> Element {
+        let mut element = Element::new("Award").with_child(Element::new("AwardName").with_text(&award.name));
+        if let Some(ref category) = award.category {
+            element.add_child(Element::new("AwardCategory").with_text(category));
+        }
+        if let Some(ref date) = award.date {
+            element.add_child(Element::new("Date").with_text(date));
+        }
+        if let Some(is_winner) = award.is_winner {
+            element.add_child(Element::new("IsWinner").with_text(is_winner.to_string()));
+        }
+        element
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::determinism::TimestampSource;
+    use ddex_core::models::common::LocalizedString;
+
+    fn sample_message() -> MeadMessage {
+        MeadMessage {
+            message_id: "MEAD-001".to_string(),
+            message_sender: Some("PADPIDA0000000001".to_string()),
+            release_information: vec![ReleaseInformation {
+                release_id: vec![Identifier {
+                    id_type: IdentifierType::UPC,
+                    namespace: None,
+                    value: "0123456789012".to_string(),
+                }],
+                moods: vec!["Uplifting".to_string()],
+                focus_tracks: vec![FocusTrack {
+                    isrc: "USRC17607839".to_string(),
+                    title: Some("Lead Single".to_string()),
+                }],
+                marketing_comments: vec![LocalizedString {
+                    text: "Debut album".to_string(),
+                    language_code: Some("en".to_string()),
+                    script: None,
+                }],
+                awards: vec![Award {
+                    name: "Best New Artist".to_string(),
+                    is_winner: Some(true),
+                    ..Default::default()
+                }],
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_mead_generation() {
+        let config = DeterminismConfig {
+            timestamp_source: TimestampSource::Fixed("2024-01-01T00:00:00Z".to_string()),
+            ..Default::default()
+        };
+        let xml = MeadGenerator::new(config).build(&sample_message()).unwrap();
+
+        assert!(xml.contains("<mead:MeadMessage"));
+        assert!(xml.contains("MessageSchemaVersionId=\"mead/11\""));
+        assert!(xml.contains("<ICPN>0123456789012</ICPN>"));
+        assert!(xml.contains("<Mood>Uplifting</Mood>"));
+        assert!(xml.contains("<ISRC>USRC17607839</ISRC>"));
+        assert!(xml.contains("<MarketingComment LanguageAndScriptCode=\"en\">Debut album</MarketingComment>"));
+        assert!(xml.contains("<IsWinner>true</IsWinner>"));
+        assert!(xml.contains("<MessageCreatedDateTime>2024-01-01T00:00:00Z</MessageCreatedDateTime>"));
+    }
+
+    #[test]
+    fn test_mead_requires_release_id() {
+        let mut message = sample_message();
+        message.release_information[0].release_id.clear();
+
+        match MeadGenerator::default().generate(&message) {
+            Err(BuildError::MissingRequired { field }) => assert_eq!(field, "release_information[0].release_id"),
+            other => panic!("expected missing release id, got {:?}", other.map(|_| ())),
+        }
+    }
+}
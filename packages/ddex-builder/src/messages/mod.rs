@@ -11,6 +11,8 @@
 //! - **UpdateReleaseMessage**: Used to update existing releases with new
 //!   metadata, resources, or deal information
 //! - **PurgeReleaseMessage**: For removing releases from distribution
+//! - **MeadMessage (MEAD)**: Enrichment such as moods, focus tracks,
+//!   marketing copy and awards for already delivered releases
 //! 
 //! ## Architecture
 //! 
@@ -51,6 +53,8 @@
 //! - Territory and rights validation
 //! - Resource reference integrity checks
 
+pub mod mead;
 pub mod update_release;
 
+pub use mead::MeadGenerator;
 pub use update_release::*;
\ No newline at end of file
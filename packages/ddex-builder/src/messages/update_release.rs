@@ -5,12 +5,16 @@
 //! existing DDEX messages without resending entire catalogs.
 
 use crate::error::BuildError;
-use crate::builder::MessageHeaderRequest;
+use crate::builder::{BuildOptions, BuildRequest, DDEXBuilder, DealRequest, MessageHeaderRequest, ReleaseRequest};
+use crate::determinism::DeterminismConfig;
+use crate::reprice::{reprice, PriceRule};
+use crate::generator::{xml_writer::XmlWriter, ASTGenerator};
 use crate::diff::DiffEngine;
-use crate::diff::types::{ChangeSet, SemanticChange, ChangeType};
+use crate::diff::types::{ChangeSet, SemanticChange, ChangeType, DiffPath, PathSegment};
+use crate::ast::{Element, Node};
 use serde::{Serialize, Deserialize};
 use indexmap::{IndexMap, IndexSet};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 
 /// Complete UpdateReleaseMessage structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -341,6 +345,42 @@ impl Default for UpdateConfig {
     }
 }
 
+/// How far a takedown goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TakedownScope {
+    /// Re-send the releases with their deals ended, keeping them in the
+    /// recipient's catalog: deals are flagged `TakeDown` in ERN 3.8.2 and
+    /// end their validity period in ERN 4
+    Deals,
+    /// Remove the releases from the recipient's catalog with a
+    /// PurgeReleaseMessage
+    Purge,
+}
+
+/// Takedown message from [`UpdateGenerator::create_takedown`]
+#[derive(Debug, Clone)]
+pub struct Takedown {
+    /// The NewReleaseMessage or PurgeReleaseMessage
+    pub xml: String,
+    /// IDs of the releases taken down
+    pub release_ids: Vec<String>,
+    /// Identifiers that matched no release of the original message
+    pub unmatched: Vec<String>,
+}
+
+/// One message of a catalog repriced by [`UpdateGenerator::create_price_updates`]
+#[derive(Debug, Clone)]
+pub struct PriceUpdate {
+    /// MessageId of the original message
+    pub original_message_id: String,
+    /// Number of deal terms whose price changed
+    pub deals: usize,
+    /// The repriced message, identical to the original outside its prices
+    pub xml: String,
+    /// Update carrying the price changes
+    pub update: UpdateReleaseMessage,
+}
+
 /// Update generation engine
 pub struct UpdateGenerator {
     config: UpdateConfig,
@@ -381,8 +421,31 @@ impl UpdateGenerator {
         // Generate semantic diff
         let changeset = self.diff_engine.diff(&original_ast, &updated_ast)?;
         
+        self.create_update_from_changeset(&changeset, original_message_id)
+    }
+    
+    /// Generate an UpdateReleaseMessage from an existing semantic diff
+    ///
+    /// Each affected release, resource and deal gets one entity update whose
+    /// action is `Add` or `Delete` when the entity itself appeared or
+    /// disappeared, and `Replace` when only its fields changed.
+    pub fn create_update_from_changeset(
+        &mut self,
+        changeset: &ChangeSet,
+        original_message_id: &str,
+    ) -> Result<UpdateReleaseMessage, BuildError> {
         // Convert changeset to update operations
-        let update_operations = self.changeset_to_operations(&changeset)?;
+        let update_operations = self.changeset_to_operations(changeset)?;
+        if update_operations.len() > self.config.max_operations_per_update {
+            return Err(BuildError::InvalidFormat {
+                field: "update_list".to_string(),
+                message: format!(
+                    "{} operations exceed the limit of {} per update message",
+                    update_operations.len(),
+                    self.config.max_operations_per_update
+                ),
+            });
+        }
         
         // Group operations by entity type
         let (resource_updates, release_updates, deal_updates) = 
@@ -392,7 +455,7 @@ impl UpdateGenerator {
         let metadata = self.create_update_metadata(
             original_message_id,
             &update_operations,
-            &changeset,
+            changeset,
         );
         
         // Generate message header
@@ -477,22 +540,141 @@ impl UpdateGenerator {
         }
     }
     
+    /// Generate the message taking down the releases named by `identifiers`
+    /// (UPCs, release IDs or ISRCs) from `original`, the request they were
+    /// delivered with
+    ///
+    /// An ISRC takes down every release with that track. Deals end on
+    /// `effective_date`, unless an earlier takedown date is already set. The
+    /// message uses the original's ERN version and thread, with a message ID
+    /// of its own; identifiers that match nothing are returned, and fail the
+    /// takedown only when none matches.
+    pub fn create_takedown(
+        &self,
+        original: &BuildRequest,
+        identifiers: &[String],
+        scope: TakedownScope,
+        effective_date: NaiveDate,
+    ) -> Result<Takedown, BuildError> {
+        let mut matched = IndexSet::new();
+        let mut unmatched = Vec::new();
+        for identifier in identifiers {
+            let key = identifier_key(identifier);
+            let mut found = false;
+            for (index, release) in original.releases.iter().enumerate() {
+                if release_keys(release).contains(&key) {
+                    matched.insert(index);
+                    found = true;
+                }
+            }
+            if !found {
+                unmatched.push(identifier.clone());
+            }
+        }
+        if matched.is_empty() {
+            return Err(BuildError::InvalidReference {
+                reference: format!("no release matches {}", identifiers.join(", ")),
+            });
+        }
+        let releases: Vec<ReleaseRequest> = original
+            .releases
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| matched.contains(index))
+            .map(|(_, release)| release.clone())
+            .collect();
+        
+        let date = effective_date.format("%Y-%m-%d").to_string();
+        let single_release = original.releases.len() == 1;
+        let mut deals = Vec::new();
+        for deal in &original.deals {
+            if !releases.iter().any(|release| deal_covers(deal, release, single_release)) {
+                continue;
+            }
+            let mut deal = deal.clone();
+            deal.release_ids
+                .retain(|id| releases.iter().any(|release| release_keys(release).contains(&identifier_key(id))));
+            deal.release_references
+                .retain(|reference| releases.iter().any(|release| release.release_reference.as_ref() == Some(reference)));
+            deal.deal_terms.take_down = true;
+            if deal.deal_terms.takedown_date.as_ref().is_none_or(|existing| *existing > date) {
+                deal.deal_terms.takedown_date = Some(date.clone());
+            }
+            deals.push(deal);
+        }
+        
+        let request = BuildRequest {
+            header: MessageHeaderRequest {
+                message_id: Some(format!(
+                    "{}-TAKEDOWN-{}",
+                    original.header.message_id.as_deref().unwrap_or("MSG"),
+                    effective_date.format("%Y%m%d")
+                )),
+                message_thread_id: original.header.message_thread_id.clone().or_else(|| original.header.message_id.clone()),
+                message_created_date_time: None,
+                message_file_name: None,
+                ..original.header.clone()
+            },
+            version: original.version.clone(),
+            profile: original.profile.clone(),
+            releases,
+            deals,
+            extensions: original.extensions.clone(),
+        };
+        
+        let release_ids = request.releases.iter().map(|release| release.release_id.clone()).collect();
+        let xml = match scope {
+            TakedownScope::Deals => DDEXBuilder::new().build(request, BuildOptions::default())?.xml,
+            TakedownScope::Purge => {
+                let ast = ASTGenerator::new(request.version.clone()).generate_purge(&request)?;
+                XmlWriter::new(DeterminismConfig::default()).write(&ast)?
+            }
+        };
+        Ok(Takedown { xml, release_ids, unmatched })
+    }
+    
+    /// Apply the pricing `rules` to every message of `catalog`, returning an
+    /// update for each message whose prices change
+    ///
+    /// Only `PriceInformation` is rewritten, so the updates carry price
+    /// changes alone; see [`crate::reprice`] for how rules match deals.
+    pub fn create_price_updates(
+        &mut self,
+        catalog: &[&str],
+        rules: &[PriceRule],
+    ) -> Result<Vec<PriceUpdate>, BuildError> {
+        let mut updates = Vec::new();
+        for original in catalog {
+            let Some(repriced) = reprice(original, rules)? else { continue };
+            let original_message_id = repriced.message_id.unwrap_or_else(|| "MSG".to_string());
+            let update = self.create_update(original, &repriced.xml, &original_message_id)?;
+            updates.push(PriceUpdate {
+                original_message_id,
+                deals: repriced.deals,
+                xml: repriced.xml,
+                update,
+            });
+        }
+        Ok(updates)
+    }
+    
     // Private helper methods
     
     fn parse_xml_to_ast(&self, xml: &str) -> Result<crate::ast::AST, BuildError> {
-        // Simplified XML parsing - in production, use proper DDEX parser
-        let root = crate::ast::Element::new("NewReleaseMessage").with_text(xml);
-        Ok(crate::ast::AST {
-            root,
-            namespaces: IndexMap::new(),
-            schema_location: None,
-        })
+        crate::diff::parse_xml(xml)
     }
     
     fn changeset_to_operations(&mut self, changeset: &ChangeSet) -> Result<Vec<UpdateOperation>, BuildError> {
         let mut operations = Vec::new();
         
         for change in &changeset.changes {
+            if !self.config.include_non_critical && !change.is_critical {
+                continue;
+            }
+            if self.is_excluded(&change.path) {
+                continue;
+            }
+            
             let operation = self.semantic_change_to_operation(change)?;
             operations.push(operation);
         }
@@ -500,6 +682,15 @@ impl UpdateGenerator {
         Ok(operations)
     }
     
+    fn is_excluded(&self, path: &DiffPath) -> bool {
+        path.segments.iter().any(|segment| match segment {
+            PathSegment::Element(name) | PathSegment::Attribute(name) => {
+                self.config.excluded_fields.contains(split_label(name).0)
+            }
+            _ => false,
+        })
+    }
+    
     fn semantic_change_to_operation(&mut self, change: &SemanticChange) -> Result<UpdateOperation, BuildError> {
         self.operation_counter += 1;
         
@@ -507,7 +698,12 @@ impl UpdateGenerator {
             ChangeType::ElementAdded | ChangeType::AttributeAdded => UpdateAction::Add,
             ChangeType::ElementRemoved | ChangeType::AttributeRemoved => UpdateAction::Delete,
             ChangeType::ElementMoved => UpdateAction::Move,
-            _ => UpdateAction::Replace,
+            // Text appearing in or vanishing from an element is an add or delete
+            _ => match (&change.old_value, &change.new_value) {
+                (None, Some(_)) => UpdateAction::Add,
+                (Some(_), None) => UpdateAction::Delete,
+                _ => UpdateAction::Replace,
+            },
         };
         
         let entity_type = self.determine_entity_type(&change.path);
@@ -528,6 +724,10 @@ impl UpdateGenerator {
     }
     
     fn determine_entity_type(&self, path: &crate::diff::types::DiffPath) -> EntityType {
+        if let Some((entity_type, _)) = entity_segment(path) {
+            return entity_type;
+        }
+        
         let path_str = path.to_string().to_lowercase();
         
         if path_str.contains("resource") {
@@ -544,7 +744,11 @@ impl UpdateGenerator {
     }
     
     fn extract_entity_id(&self, path: &crate::diff::types::DiffPath) -> Result<String, BuildError> {
-        // Extract entity ID from path - simplified implementation
+        // Prefer the entity label the diff engine attaches to identified entities
+        if let Some((_, Some(label))) = entity_segment(path) {
+            return Ok(label);
+        }
+        
         let path_str = path.to_string();
         if let Some(id_start) = path_str.find("Id=") {
             let id_part = &path_str[id_start + 3..];
@@ -563,23 +767,110 @@ impl UpdateGenerator {
         &self,
         operations: &[UpdateOperation],
     ) -> Result<(IndexMap<String, ResourceUpdate>, IndexMap<String, ReleaseUpdate>, IndexMap<String, DealUpdate>), BuildError> {
-        let mut resource_updates = IndexMap::new();
-        let mut release_updates = IndexMap::new();
-        let mut deal_updates = IndexMap::new();
+        let mut resource_updates: IndexMap<String, ResourceUpdate> = IndexMap::new();
+        let mut release_updates: IndexMap<String, ReleaseUpdate> = IndexMap::new();
+        let mut deal_updates: IndexMap<String, DealUpdate> = IndexMap::new();
         
         for operation in operations {
+            let target = EntityTarget::from_operation(operation);
+            
             match operation.entity_type {
                 EntityType::Resource => {
-                    let resource_update = self.operation_to_resource_update(operation)?;
-                    resource_updates.insert(operation.entity_id.clone(), resource_update);
+                    let update = resource_updates
+                        .entry(operation.entity_id.clone())
+                        .or_insert_with(|| ResourceUpdate {
+                            resource_id: operation.entity_id.clone(),
+                            resource_reference: operation.entity_id.clone(),
+                            action: UpdateAction::Replace,
+                            resource_data: None,
+                            technical_updates: Vec::new(),
+                            metadata_updates: IndexMap::new(),
+                        });
+                    
+                    match target.field {
+                        None => {
+                            update.action = operation.action;
+                            update.resource_data = target.element.as_ref().map(resource_data);
+                            if let Some(isrc) = update.resource_data.as_ref().and_then(|d| d.isrc.clone()) {
+                                update.resource_id = isrc;
+                            }
+                        }
+                        Some(field) if field.starts_with("TechnicalResourceDetails") => {
+                            update.technical_updates.push(TechnicalUpdate {
+                                field_name: field,
+                                old_value: operation.old_value.clone(),
+                                new_value: operation.new_value.clone(),
+                                update_action: operation.action,
+                            });
+                        }
+                        Some(field) => {
+                            update.metadata_updates.insert(field, operation.new_value.clone().unwrap_or_default());
+                        }
+                    }
                 },
                 EntityType::Release => {
-                    let release_update = self.operation_to_release_update(operation)?;
-                    release_updates.insert(operation.entity_id.clone(), release_update);
+                    let update = release_updates
+                        .entry(operation.entity_id.clone())
+                        .or_insert_with(|| ReleaseUpdate {
+                            release_id: operation.entity_id.clone(),
+                            release_reference: operation.entity_id.clone(),
+                            action: UpdateAction::Replace,
+                            release_data: None,
+                            track_updates: Vec::new(),
+                            resource_reference_updates: Vec::new(),
+                            metadata_updates: IndexMap::new(),
+                        });
+                    
+                    match target.field {
+                        None => {
+                            update.action = operation.action;
+                            update.release_data = target.element.as_ref().map(release_data);
+                            if let Some(upc) = update.release_data.as_ref().and_then(|d| d.upc.clone()) {
+                                update.release_id = upc;
+                            }
+                        }
+                        Some(field) if field.ends_with("ResourceReference") => {
+                            if let (Some(old), Some(new)) = (&operation.old_value, &operation.new_value) {
+                                update.resource_reference_updates.push(ReferenceUpdate {
+                                    old_reference: old.clone(),
+                                    new_reference: new.clone(),
+                                    reference_type: "ResourceReference".to_string(),
+                                    update_reason: operation.description.clone(),
+                                });
+                            } else {
+                                update.metadata_updates.insert(field, operation.new_value.clone().unwrap_or_default());
+                            }
+                        }
+                        Some(field) => {
+                            update.metadata_updates.insert(field, operation.new_value.clone().unwrap_or_default());
+                        }
+                    }
                 },
                 EntityType::Deal => {
-                    let deal_update = self.operation_to_deal_update(operation)?;
-                    deal_updates.insert(operation.entity_id.clone(), deal_update);
+                    let update = deal_updates
+                        .entry(operation.entity_id.clone())
+                        .or_insert_with(|| DealUpdate {
+                            deal_id: operation.entity_id.clone(),
+                            deal_reference: operation.entity_id.clone(),
+                            action: UpdateAction::Replace,
+                            deal_data: None,
+                            terms_updates: Vec::new(),
+                        });
+                    
+                    match target.field {
+                        None => {
+                            update.action = operation.action;
+                            update.deal_data = target.element.as_ref().map(deal_data);
+                        }
+                        Some(field) => {
+                            update.terms_updates.push(TermsUpdate {
+                                field_name: field,
+                                old_value: operation.old_value.clone(),
+                                new_value: operation.new_value.clone(),
+                                effective_date: None,
+                            });
+                        }
+                    }
                 },
                 _ => {}, // Handle metadata and party updates separately if needed
             }
@@ -588,39 +879,6 @@ impl UpdateGenerator {
         Ok((resource_updates, release_updates, deal_updates))
     }
     
-    fn operation_to_resource_update(&self, operation: &UpdateOperation) -> Result<ResourceUpdate, BuildError> {
-        Ok(ResourceUpdate {
-            resource_id: operation.entity_id.clone(),
-            resource_reference: format!("R{:06}", operation.operation_id[2..].parse::<u32>().unwrap_or(0)),
-            action: operation.action,
-            resource_data: None, // Would be populated from operation details
-            technical_updates: Vec::new(),
-            metadata_updates: IndexMap::new(),
-        })
-    }
-    
-    fn operation_to_release_update(&self, operation: &UpdateOperation) -> Result<ReleaseUpdate, BuildError> {
-        Ok(ReleaseUpdate {
-            release_id: operation.entity_id.clone(),
-            release_reference: format!("REL{:06}", operation.operation_id[2..].parse::<u32>().unwrap_or(0)),
-            action: operation.action,
-            release_data: None, // Would be populated from operation details
-            track_updates: Vec::new(),
-            resource_reference_updates: Vec::new(),
-            metadata_updates: IndexMap::new(),
-        })
-    }
-    
-    fn operation_to_deal_update(&self, operation: &UpdateOperation) -> Result<DealUpdate, BuildError> {
-        Ok(DealUpdate {
-            deal_id: operation.entity_id.clone(),
-            deal_reference: format!("D{:06}", operation.operation_id[2..].parse::<u32>().unwrap_or(0)),
-            action: operation.action,
-            deal_data: None, // Would be populated from operation details
-            terms_updates: Vec::new(),
-        })
-    }
-    
     fn create_update_metadata(
         &self,
         original_message_id: &str,
@@ -659,8 +917,9 @@ impl UpdateGenerator {
                 party_id: None,
                 party_reference: None,
             },
-            message_control_type: Some("UpdateMessage".to_string()),
+            message_control_type: Some("LiveMessage".to_string()),
             message_created_date_time: Some(metadata.update_created_timestamp.to_rfc3339()),
+            ..Default::default()
         }
     }
     
@@ -840,6 +1099,206 @@ impl Default for UpdateGenerator {
     }
 }
 
+/// Identifier as compared for takedowns: upper case without separators,
+/// and numeric codes without leading zeros so UPC-A matches EAN-13
+fn identifier_key(identifier: &str) -> String {
+    let key: String = identifier.chars().filter(char::is_ascii_alphanumeric).collect::<String>().to_ascii_uppercase();
+    if key.bytes().all(|b| b.is_ascii_digit()) {
+        key.trim_start_matches('0').to_string()
+    } else {
+        key
+    }
+}
+
+/// Keys a release can be taken down by: its ID, UPC and track ISRCs
+fn release_keys(release: &ReleaseRequest) -> Vec<String> {
+    std::iter::once(release.release_id.as_str())
+        .chain(release.upc.as_deref())
+        .chain(release.tracks.iter().map(|track| track.isrc.as_str()))
+        .chain(release.track_isrcs.iter().map(String::as_str))
+        .map(identifier_key)
+        .collect()
+}
+
+/// Whether `deal` covers `release`; a deal without targets covers the
+/// release of a single-release message
+fn deal_covers(deal: &DealRequest, release: &ReleaseRequest, single_release: bool) -> bool {
+    let keys = release_keys(release);
+    deal.release_ids.iter().any(|id| keys.contains(&identifier_key(id)))
+        || release.release_reference.as_ref().is_some_and(|reference| deal.release_references.contains(reference))
+        || (single_release && deal.release_ids.is_empty() && deal.release_references.is_empty())
+}
+
+/// Split a diff path segment such as `Release[R1]` into name and label
+fn split_label(segment: &str) -> (&str, Option<&str>) {
+    match segment.split_once('[') {
+        Some((name, rest)) => (name, rest.strip_suffix(']')),
+        None => (segment, None),
+    }
+}
+
+/// Map an element name to the entity type it represents in an update
+fn entity_type_for(name: &str) -> Option<EntityType> {
+    match name {
+        "SoundRecording" | "Video" | "VideoRecording" | "Image" | "Text" | "SheetMusic"
+        | "Software" => Some(EntityType::Resource),
+        "Release" => Some(EntityType::Release),
+        "ReleaseDeal" | "Deal" => Some(EntityType::Deal),
+        "Party" => Some(EntityType::Party),
+        _ => None,
+    }
+}
+
+/// Find the outermost entity in a diff path
+///
+/// Returns the entity type and its label, if the diff engine could identify it.
+fn entity_segment(path: &DiffPath) -> Option<(EntityType, Option<String>)> {
+    path.segments.iter().find_map(|segment| match segment {
+        PathSegment::Element(name) => {
+            let (name, label) = split_label(name);
+            entity_type_for(name).map(|entity| (entity, label.map(str::to_string)))
+        }
+        _ => None,
+    })
+}
+
+/// What part of an entity an operation touches
+struct EntityTarget {
+    /// Field path relative to the entity, or `None` for the entity itself
+    field: Option<String>,
+    /// Parsed entity element for whole-entity operations
+    element: Option<Element>,
+}
+
+impl EntityTarget {
+    fn from_operation(operation: &UpdateOperation) -> Self {
+        let segments: Vec<&str> = operation.target_path.split('/').filter(|s| !s.is_empty()).collect();
+        let entity_index = segments
+            .iter()
+            .position(|s| entity_type_for(split_label(s).0).is_some());
+        
+        let field_segments: Vec<&str> = match entity_index {
+            Some(index) => segments[index + 1..].iter().copied().filter(|s| *s != "#text").collect(),
+            None => segments.iter().copied().filter(|s| *s != "#text").collect(),
+        };
+        
+        if !field_segments.is_empty() || entity_index.is_none() {
+            return Self {
+                field: Some(field_segments.join("/")),
+                element: None,
+            };
+        }
+        
+        let xml = operation.new_value.as_deref().or(operation.old_value.as_deref());
+        Self {
+            field: None,
+            element: xml.and_then(|xml| crate::diff::parse_xml(xml).ok()).map(|ast| ast.root),
+        }
+    }
+}
+
+/// First non-empty text of a descendant with one of the given names
+fn find_text(element: &Element, names: &[&str]) -> Option<String> {
+    for child in &element.children {
+        if let Node::Element(child) = child {
+            if names.contains(&child.name.as_str()) {
+                let text = text_content(child);
+                if !text.is_empty() {
+                    return Some(text);
+                }
+            }
+            if let Some(text) = find_text(child, names) {
+                return Some(text);
+            }
+        }
+    }
+    None
+}
+
+/// Texts of all descendants with the given name, in document order
+fn find_all_text(element: &Element, name: &str, found: &mut Vec<String>) {
+    for child in &element.children {
+        if let Node::Element(child) = child {
+            if child.name == name {
+                let text = text_content(child);
+                if !text.is_empty() && !found.contains(&text) {
+                    found.push(text);
+                }
+            }
+            find_all_text(child, name, found);
+        }
+    }
+}
+
+fn text_content(element: &Element) -> String {
+    element.children.iter()
+        .filter_map(|n| match n {
+            Node::Text(t) => Some(t.trim()),
+            _ => None,
+        })
+        .collect()
+}
+
+const TITLE_FIELDS: &[&str] = &["DisplayTitleText", "TitleText", "ReferenceTitle", "Title"];
+const ARTIST_FIELDS: &[&str] = &["DisplayArtistName", "FullName", "PartyName", "DisplayArtist"];
+
+fn resource_data(element: &Element) -> ResourceData {
+    let technical = ["FileName", "AudioCodecType", "BitRate", "SamplingRate"]
+        .iter()
+        .any(|name| find_text(element, &[name]).is_some());
+    
+    ResourceData {
+        resource_type: element.name.clone(),
+        title: find_text(element, TITLE_FIELDS).unwrap_or_default(),
+        artist: find_text(element, ARTIST_FIELDS).unwrap_or_default(),
+        isrc: find_text(element, &["ISRC"]),
+        duration: find_text(element, &["Duration"]),
+        file_path: find_text(element, &["FileName"]),
+        technical_details: technical.then(|| TechnicalDetails {
+            file_name: find_text(element, &["FileName"]),
+            codec_type: find_text(element, &["AudioCodecType"]),
+            bit_rate: find_text(element, &["BitRate"]),
+            sample_rate: find_text(element, &["SamplingRate"]),
+        }),
+    }
+}
+
+fn release_data(element: &Element) -> ReleaseData {
+    let mut resource_references = Vec::new();
+    find_all_text(element, "ReleaseResourceReference", &mut resource_references);
+    find_all_text(element, "ResourceReference", &mut resource_references);
+    
+    ReleaseData {
+        release_type: find_text(element, &["ReleaseType"]).unwrap_or_default(),
+        title: find_text(element, TITLE_FIELDS).unwrap_or_default(),
+        artist: find_text(element, ARTIST_FIELDS).unwrap_or_default(),
+        label: find_text(element, &["LabelName"]),
+        upc: find_text(element, &["ICPN", "UPC", "EAN"]),
+        release_date: find_text(element, &["ReleaseDate", "OriginalReleaseDate"]),
+        genre: find_text(element, &["GenreText", "Genre"]),
+        resource_references,
+    }
+}
+
+fn deal_data(element: &Element) -> DealData {
+    let mut territory_codes = Vec::new();
+    find_all_text(element, "TerritoryCode", &mut territory_codes);
+    
+    let price = find_text(element, &["PriceAmount", "WholesalePricePerUnit"]).map(|amount| PriceData {
+        amount,
+        currency_code: find_text(element, &["PriceCurrencyCode", "CurrencyCode"]).unwrap_or_default(),
+        price_type: find_text(element, &["PriceType", "PriceCode"]),
+    });
+    
+    DealData {
+        commercial_model_type: find_text(element, &["CommercialModelType"]).unwrap_or_default(),
+        territory_codes,
+        start_date: find_text(element, &["StartDate"]),
+        end_date: find_text(element, &["EndDate"]),
+        price,
+    }
+}
+
 // Display implementations for better debugging
 impl std::fmt::Display for UpdateAction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -931,6 +1390,7 @@ mod tests {
                 },
                 message_control_type: None,
                 message_created_date_time: None,
+                ..Default::default()
             },
             update_list: vec![operation.clone()],
             resource_updates: IndexMap::new(),
@@ -966,5 +1426,202 @@ mod tests {
         
         assert_eq!(generator.determine_entity_type(&resource_path), EntityType::Resource);
         assert_eq!(generator.determine_entity_type(&release_path), EntityType::Release);
+        
+        // Labelled entity segments win over substring matching
+        let reference_path = crate::diff::types::DiffPath::root()
+            .with_element("ReleaseList")
+            .with_element("Release[REL001]")
+            .with_element("ResourceGroup")
+            .with_element("ResourceReference");
+        assert_eq!(generator.determine_entity_type(&reference_path), EntityType::Release);
+        assert_eq!(generator.extract_entity_id(&reference_path).unwrap(), "REL001");
+    }
+    
+    #[test]
+    fn test_update_groups_field_changes_per_entity() {
+        use crate::diff::test_data::{SAMPLE_DDEX_V1, SAMPLE_DDEX_V2};
+        
+        let mut generator = UpdateGenerator::new();
+        let update = generator.create_update(SAMPLE_DDEX_V1, SAMPLE_DDEX_V2, "MSG-001").unwrap();
+        
+        let release = &update.release_updates["REL001"];
+        assert_eq!(release.action, UpdateAction::Replace);
+        assert_eq!(release.metadata_updates["Title"], "Test Album (Deluxe Edition)");
+        assert_eq!(release.metadata_updates["UPC"], "987654321098");
+        
+        let resource = &update.resource_updates["R001"];
+        assert_eq!(resource.action, UpdateAction::Replace);
+        assert_eq!(resource.metadata_updates["Duration"], "PT3M45S");
+        assert_eq!(resource.technical_updates[0].field_name, "TechnicalResourceDetails/FileName");
+        
+        // Header fields never become operations
+        assert!(!update.update_list.iter().any(|op| op.target_path.contains("MessageHeader")));
+    }
+    
+    #[test]
+    fn test_update_adds_and_deletes_entities() {
+        let original = r#"<NewReleaseMessage>
+            <MessageHeader><MessageId>MSG-1</MessageId></MessageHeader>
+            <ResourceList>
+                <SoundRecording><ResourceReference>A1</ResourceReference><ISRC>USAAA2400001</ISRC></SoundRecording>
+            </ResourceList>
+            <ReleaseList>
+                <Release><ReleaseReference>R1</ReleaseReference><ReleaseId><ICPN>111</ICPN></ReleaseId></Release>
+            </ReleaseList>
+        </NewReleaseMessage>"#;
+        let updated = r#"<NewReleaseMessage>
+            <MessageHeader><MessageId>MSG-2</MessageId></MessageHeader>
+            <ResourceList>
+                <SoundRecording><ResourceReference>A1</ResourceReference><ISRC>USAAA2400001</ISRC></SoundRecording>
+            </ResourceList>
+            <DealList>
+                <ReleaseDeal>
+                    <DealReleaseReference>R1</DealReleaseReference>
+                    <Deal><DealTerms>
+                        <CommercialModelType>SubscriptionModel</CommercialModelType>
+                        <TerritoryCode>US</TerritoryCode><TerritoryCode>CA</TerritoryCode>
+                        <ValidityPeriod><StartDate>2025-01-01</StartDate></ValidityPeriod>
+                    </DealTerms></Deal>
+                </ReleaseDeal>
+            </DealList>
+        </NewReleaseMessage>"#;
+        
+        let mut generator = UpdateGenerator::new();
+        let update = generator.create_update(original, updated, "MSG-1").unwrap();
+        
+        assert!(update.resource_updates.is_empty());
+        
+        let release = &update.release_updates["R1"];
+        assert_eq!(release.action, UpdateAction::Delete);
+        assert_eq!(release.release_id, "111");
+        
+        let deal = &update.deal_updates["R1"];
+        assert_eq!(deal.action, UpdateAction::Add);
+        let data = deal.deal_data.as_ref().unwrap();
+        assert_eq!(data.commercial_model_type, "SubscriptionModel");
+        assert_eq!(data.territory_codes, vec!["US", "CA"]);
+        assert_eq!(data.start_date.as_deref(), Some("2025-01-01"));
+        
+        let xml = crate::builder::DDEXBuilder::new().serialize_update(&update).unwrap();
+        assert!(xml.contains("<Action>Delete</Action>"));
+        assert!(xml.contains("<Action>Add</Action>"));
+        assert!(xml.contains("<TerritoryCode>CA</TerritoryCode>"));
+        assert!(crate::diff::parse_xml(&xml).is_ok());
+    }
+    
+    #[test]
+    fn test_create_takedown() {
+        let original: BuildRequest = serde_json::from_value(serde_json::json!({
+            "header": {
+                "message_id": "MSG-1",
+                "message_sender": { "party_name": [{ "text": "Label" }] },
+                "message_recipient": { "party_name": [{ "text": "DSP" }] }
+            },
+            "version": "3.8.2",
+            "releases": [
+                {
+                    "release_id": "602445790128",
+                    "upc": "602445790128",
+                    "title": [{ "text": "Album" }],
+                    "artist": "Artist",
+                    "tracks": [{
+                        "track_id": "T1", "isrc": "USRC12400001", "title": "One",
+                        "duration": "PT3M", "artist": "Artist"
+                    }]
+                },
+                {
+                    "release_id": "602445790135",
+                    "upc": "602445790135",
+                    "title": [{ "text": "Other Album" }],
+                    "artist": "Artist"
+                }
+            ],
+            "deals": [{
+                "deal_terms": {
+                    "commercial_model_type": "SubscriptionModel",
+                    "territory_code": ["Worldwide"],
+                    "start_date": "2024-01-01",
+                    "use_types": ["OnDemandStream"]
+                },
+                "release_references": [],
+                "release_ids": ["602445790128", "602445790135"]
+            }]
+        }))
+        .unwrap();
+        let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let generator = UpdateGenerator::new();
+        
+        let identifiers = vec!["0602445790128".to_string(), "USXX99999999".to_string()];
+        let takedown = generator.create_takedown(&original, &identifiers, TakedownScope::Deals, date).unwrap();
+        assert_eq!(takedown.release_ids, ["602445790128"]);
+        assert_eq!(takedown.unmatched, ["USXX99999999"]);
+        assert!(takedown.xml.contains("MSG-1-TAKEDOWN-20250301"));
+        assert!(takedown.xml.contains("TakeDown>true</"));
+        assert!(takedown.xml.contains("EndDate>2025-03-01</"));
+        assert!(!takedown.xml.contains("Other Album"));
+        
+        let mut ern43 = original.clone();
+        ern43.version = "4.3".to_string();
+        let takedown = generator.create_takedown(&ern43, &identifiers, TakedownScope::Deals, date).unwrap();
+        assert!(!takedown.xml.contains("TakeDown"));
+        assert!(takedown.xml.contains("EndDate>2025-03-01</"));
+        
+        let takedown = generator
+            .create_takedown(&ern43, &["US-RC1-24-00001".to_string()], TakedownScope::Purge, date)
+            .unwrap();
+        assert!(takedown.xml.contains("PurgeReleaseMessage"));
+        assert!(takedown.xml.contains("ICPN>602445790128</"));
+        assert!(!takedown.xml.contains("DealList"));
+        
+        let err = generator.create_takedown(&original, &["123".to_string()], TakedownScope::Purge, date);
+        assert!(matches!(err, Err(BuildError::InvalidReference { .. })));
+    }
+    
+    #[test]
+    fn test_create_price_updates() {
+        let message = |id: &str, amount: &str| {
+            format!(
+                r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+  <MessageHeader><MessageId>{}</MessageId></MessageHeader>
+  <DealList>
+    <ReleaseDeal>
+      <DealReleaseReference>R1</DealReleaseReference>
+      <Deal>
+        <DealTerms>
+          <CommercialModelType>PayAsYouGoModel</CommercialModelType>
+          <TerritoryCode>US</TerritoryCode>
+          <PriceInformation>
+            <WholesalePricePerUnit CurrencyCode="USD">{}</WholesalePricePerUnit>
+          </PriceInformation>
+        </DealTerms>
+      </Deal>
+    </ReleaseDeal>
+  </DealList>
+</ern:NewReleaseMessage>"#,
+                id, amount
+            )
+        };
+        let catalog = [message("M1", "0.70"), message("M2", "0.90")];
+        let catalog: Vec<&str> = catalog.iter().map(String::as_str).collect();
+        let rules = [PriceRule {
+            territory_codes: vec!["US".to_string()],
+            commercial_model_type: None,
+            price: crate::builder::PriceInformationRequest {
+                price_code: None,
+                wholesale_price: Some(crate::builder::PriceRequest {
+                    amount: "0.90".to_string(),
+                    currency_code: "USD".to_string(),
+                }),
+                suggested_retail_price: None,
+            },
+        }];
+        
+        let updates = UpdateGenerator::new().create_price_updates(&catalog, &rules).unwrap();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].original_message_id, "M1");
+        assert_eq!(updates[0].deals, 1);
+        assert_eq!(updates[0].xml, message("M1", "0.90"));
+        assert!(!updates[0].update.update_list.is_empty());
+        assert!(updates[0].update.update_list.iter().all(|op| op.entity_type == EntityType::Deal));
     }
 }
\ No newline at end of file
@@ -448,8 +448,9 @@ fn create_test_header() -> MessageHeaderRequest {
             party_id: None,
             party_reference: None,
         },
-        message_control_type: Some("UpdateMessage".to_string()),
+        message_control_type: Some("LiveMessage".to_string()),
         message_created_date_time: Some(chrono::Utc::now().to_rfc3339()),
+        ..Default::default()
     }
 }
 
@@ -6,11 +6,16 @@
 use ddex_core::namespace::{NamespaceRegistry, NamespaceScope, ConflictResolution};
 use ddex_core::models::versions::ERNVersion;
 use crate::canonical::rules::CanonicalNamespaceManager;
+use crate::extensions::ExtensionNamespaces;
 use crate::ast::{AST, Element, Node};
 use indexmap::{IndexMap, IndexSet};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use tracing::{debug, warn};
 
+const XSI_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
 /// Namespace minimization result
 #[derive(Debug, Clone)]
 pub struct MinimizationResult {
@@ -65,11 +70,21 @@ impl NamespaceMinimizer {
     }
 
     /// Minimize namespace declarations in AST
+    ///
+    /// The root declares the message namespace and the namespaces some
+    /// element or attribute uses, under their canonical prefixes; unused
+    /// declarations are dropped.
     pub fn minimize(&self, ast: AST) -> Result<MinimizationResult, String> {
+        self.minimize_with(ast, Vec::new())
+    }
+
+    /// Minimize, declaring `extra` namespaces on the root whether used or not
+    fn minimize_with(&self, ast: AST, extra: Vec<String>) -> Result<MinimizationResult, String> {
         debug!("Starting namespace minimization for ERN {:?}", self.version);
         
         // Step 1: Analyze namespace usage throughout the document
-        let usage = self.analyze_namespace_usage(&ast)?;
+        let mut usage = self.analyze_namespace_usage(&ast)?;
+        usage.used_namespaces.extend(extra);
         debug!("Found {} used namespaces", usage.used_namespaces.len());
         
         // Step 2: Create optimal namespace declarations for root
@@ -92,25 +107,49 @@ impl NamespaceMinimizer {
         })
     }
 
+    /// Keep the declarations and prefixes of `ast`, only declaring the used
+    /// namespaces it lacks and dropping child redeclarations of the root's
+    pub fn complete(&self, mut ast: AST) -> Result<MinimizationResult, String> {
+        let usage = self.analyze_namespace_usage(&ast)?;
+        for uri in usage.used_namespaces {
+            if ast.namespaces.values().any(|declared| *declared == uri) {
+                continue;
+            }
+            let base = match self.registry.get_preferred_prefix(&uri) {
+                Some(prefix) => prefix.to_string(),
+                None => self.generate_prefix_for_uri(&uri),
+            };
+            let mut prefix = base.clone();
+            let mut counter = 1;
+            while ast.namespaces.contains_key(&prefix) {
+                prefix = format!("{}{}", base, counter);
+                counter += 1;
+            }
+            ast.namespaces.insert(prefix, uri);
+        }
+
+        let root_namespaces = ast.namespaces.clone();
+        let prefix_mapping = root_namespaces.keys().map(|prefix| (prefix.clone(), prefix.clone())).collect();
+        let optimized_ast = self.hoist_namespaces(ast)?;
+        let warnings = self.validate_minimization(&optimized_ast, &root_namespaces);
+        Ok(MinimizationResult { root_namespaces, optimized_ast, prefix_mapping, warnings })
+    }
+
     /// Analyze namespace usage throughout the document
     fn analyze_namespace_usage(&self, ast: &AST) -> Result<NamespaceUsage, String> {
         let mut used_namespaces = IndexSet::new();
         let mut namespace_elements = HashMap::new();
         let mut attribute_namespaces = IndexSet::new();
 
-        // Add namespaces already declared in AST
-        for (prefix, uri) in &ast.namespaces {
-            used_namespaces.insert(uri.clone());
-            namespace_elements.entry(uri.clone()).or_insert_with(IndexSet::new);
-        }
+        // The message namespace is always needed
+        used_namespaces.insert(self.version.namespace().to_string());
 
         // Analyze namespace usage in element tree
-        self.analyze_element_usage(&ast.root, &mut used_namespaces, &mut namespace_elements, &mut attribute_namespaces);
+        self.analyze_element_usage(&ast.root, &ast.namespaces, &mut used_namespaces, &mut namespace_elements, &mut attribute_namespaces);
 
-        // Add required namespaces for the ERN version
-        let required_namespaces = self.registry.get_version_namespaces(&self.version);
-        for ns_uri in required_namespaces {
-            used_namespaces.insert(ns_uri);
+        // xsi:schemaLocation is written on the root
+        if ast.schema_location.is_some() {
+            used_namespaces.insert(XSI_NAMESPACE.to_string());
         }
 
         Ok(NamespaceUsage {
@@ -121,29 +160,51 @@ impl NamespaceMinimizer {
     }
 
     /// Recursively analyze namespace usage in elements
+    ///
+    /// Element namespaces are prefixes resolved against the declarations in
+    /// scope; one that is not a declared prefix is taken as the URI itself.
     fn analyze_element_usage(
         &self,
         element: &Element,
+        declared: &IndexMap<String, String>,
         used_namespaces: &mut IndexSet<String>,
         namespace_elements: &mut HashMap<String, IndexSet<String>>,
         attribute_namespaces: &mut IndexSet<String>,
     ) {
+        // Declarations made on this element are in scope for it and its children
+        let local: Vec<_> = element.attributes.iter()
+            .filter_map(|(name, uri)| name.strip_prefix("xmlns:").map(|prefix| (prefix.to_string(), uri.clone())))
+            .collect();
+        let scope = if local.is_empty() {
+            Cow::Borrowed(declared)
+        } else {
+            let mut scope = declared.clone();
+            scope.extend(local);
+            Cow::Owned(scope)
+        };
+
         // Check element namespace
         if let Some(ref ns) = element.namespace {
-            used_namespaces.insert(ns.clone());
+            let uri = scope.get(ns).cloned().unwrap_or_else(|| ns.clone());
+            used_namespaces.insert(uri.clone());
             namespace_elements
-                .entry(ns.clone())
+                .entry(uri)
                 .or_insert_with(IndexSet::new)
                 .insert(element.name.clone());
         }
 
         // Check attribute namespaces
-        for (attr_name, _) in &element.attributes {
-            if attr_name.contains(':') && !attr_name.starts_with("xmlns") {
-                // Extract namespace prefix from qualified attribute name
-                if let Some(prefix) = attr_name.split(':').next() {
-                    // This would need the namespace URI, but for now just track the usage
-                    debug!("Found namespaced attribute: {} with prefix: {}", attr_name, prefix);
+        for attr_name in element.attributes.keys() {
+            if let Some((prefix, _)) = attr_name.split_once(':') {
+                if prefix == "xmlns" {
+                    continue;
+                }
+                match scope.get(prefix) {
+                    Some(uri) => {
+                        used_namespaces.insert(uri.clone());
+                        attribute_namespaces.insert(uri.clone());
+                    }
+                    None => warn!("Attribute {} uses undeclared prefix {}", attr_name, prefix),
                 }
             }
         }
@@ -151,7 +212,7 @@ impl NamespaceMinimizer {
         // Recursively analyze children
         for child in &element.children {
             if let Node::Element(child_element) = child {
-                self.analyze_element_usage(child_element, used_namespaces, namespace_elements, attribute_namespaces);
+                self.analyze_element_usage(child_element, &scope, used_namespaces, namespace_elements, attribute_namespaces);
             }
         }
     }
@@ -180,6 +241,7 @@ impl NamespaceMinimizer {
             ERNVersion::V3_8_2 => "3.8.2",
             ERNVersion::V4_2 => "4.2",
             ERNVersion::V4_3 => "4.3",
+            ERNVersion::V4_4 => "4.4",
         };
 
         Ok(self.canonical_manager.canonicalize_namespaces(declarations, version_str))
@@ -191,17 +253,17 @@ impl NamespaceMinimizer {
         mut ast: AST,
         canonical_namespaces: &IndexMap<String, String>,
     ) -> Result<(AST, IndexMap<String, String>), String> {
-        // Update AST namespaces with canonical declarations
-        ast.namespaces = canonical_namespaces.clone();
-
-        // Create prefix mapping for any changes
+        // Map each prefix declared before to the canonical prefix of its URI
         let mut prefix_mapping = IndexMap::new();
-        
-        // For now, assume no prefix changes (would be more complex in full implementation)
-        for (prefix, _) in canonical_namespaces {
-            prefix_mapping.insert(prefix.clone(), prefix.clone());
+        for (prefix, uri) in &ast.namespaces {
+            if let Some((canonical, _)) = canonical_namespaces.iter().find(|(_, canonical_uri)| *canonical_uri == uri) {
+                prefix_mapping.insert(prefix.clone(), canonical.clone());
+            }
         }
 
+        // Update AST namespaces with canonical declarations
+        ast.namespaces = canonical_namespaces.clone();
+
         // Update element prefixes if needed (recursive through tree)
         self.update_element_prefixes(&mut ast.root, &prefix_mapping);
 
@@ -209,14 +271,15 @@ impl NamespaceMinimizer {
     }
 
     /// Update element prefixes based on mapping
-    fn update_element_prefixes(&self, element: &mut Element, _prefix_mapping: &IndexMap<String, String>) {
-        // This would update element names and attributes based on prefix changes
-        // For now, keep existing prefixes
+    fn update_element_prefixes(&self, element: &mut Element, prefix_mapping: &IndexMap<String, String>) {
+        if let Some(canonical) = element.namespace.as_ref().and_then(|prefix| prefix_mapping.get(prefix)) {
+            element.namespace = Some(canonical.clone());
+        }
         
         // Recursively update children
         for child in &mut element.children {
             if let Node::Element(child_element) = child {
-                self.update_element_prefixes(child_element, _prefix_mapping);
+                self.update_element_prefixes(child_element, prefix_mapping);
             }
         }
     }
@@ -262,11 +325,10 @@ impl NamespaceMinimizer {
             }
         }
 
-        // Check for missing required namespaces
-        let required_namespaces = self.registry.get_version_namespaces(&self.version);
-        for required_uri in required_namespaces {
-            if !declared_uris.contains(&required_uri) {
-                warnings.push(format!("Missing required namespace: {}", required_uri));
+        // Check for used namespaces left undeclared
+        for used_uri in &usage.used_namespaces {
+            if !declared_uris.contains(used_uri) {
+                warnings.push(format!("Missing namespace declaration: {}", used_uri));
             }
         }
 
@@ -318,49 +380,128 @@ impl NamespaceMinimizer {
     }
 }
 
-/// Namespace optimization strategies
-#[derive(Debug, Clone, Copy)]
+/// How the namespace declarations of a built message are laid out
+///
+/// Chosen with [`BuildOptions::namespace_strategy`](crate::builder::BuildOptions::namespace_strategy).
+/// Every strategy is a pure function of the generated tree: the same request
+/// and options give byte-identical declarations, in the same order, on every
+/// build. Each then declares the registered
+/// [extension namespaces](crate::extensions) under their own prefixes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum OptimizationStrategy {
-    /// Minimal declarations (default)
+    /// Declare only the message namespace and the namespaces some element or
+    /// attribute uses, under their canonical prefixes, sorted by prefix
+    ///
+    /// Gives the smallest root element, but adding an extension or a
+    /// `xsi:` attribute to one release changes the root's declarations.
     Minimal,
-    /// Hoist all possible declarations to root
+    /// Declare every namespace of the ERN version (ERN, AVS and XSI) and every
+    /// namespace the tree declared, all on the root under canonical prefixes,
+    /// and drop redeclarations further down
+    ///
+    /// The root looks the same for any content, which suits recipients that
+    /// compare headers or stream-parse with fixed prefixes, at the cost of
+    /// declarations nothing uses.
     HoistAll,
-    /// Conservative approach, keep existing structure
+    /// Keep the generated declarations and prefixes, only adding used
+    /// namespaces that are not declared and dropping redeclarations of the
+    /// root's namespaces further down
+    ///
+    /// Never renames or reorders a prefix, so it is the safe choice when
+    /// partner extensions rely on their own prefixes; unused declarations stay.
     Conservative,
+    /// Write the declarations exactly as generated
+    ///
+    /// The default, and what builds produced before the strategy existed.
+    #[default]
+    PreserveOriginal,
 }
 
-/// Advanced namespace minimizer with optimization strategies
+/// Namespace minimizer applying an [`OptimizationStrategy`]
 pub struct AdvancedNamespaceMinimizer {
     base_minimizer: NamespaceMinimizer,
     strategy: OptimizationStrategy,
+    extensions: ExtensionNamespaces,
 }
 
 impl AdvancedNamespaceMinimizer {
+    /// Create a minimizer for `version` using `strategy`
     pub fn new(version: ERNVersion, strategy: OptimizationStrategy) -> Self {
         Self {
             base_minimizer: NamespaceMinimizer::new(version),
             strategy,
+            extensions: ExtensionNamespaces::new(),
         }
     }
 
+    /// Declare `extensions` on the root under their registered prefixes,
+    /// whatever the strategy
+    pub fn with_extension_namespaces(mut self, extensions: &ExtensionNamespaces) -> Self {
+        self.base_minimizer.registry = extensions.registry();
+        self.extensions = extensions.clone();
+        self
+    }
+
+    /// Lay out the namespace declarations of `ast` according to the strategy
     pub fn minimize(&self, ast: AST) -> Result<MinimizationResult, String> {
+        let mut result = self.apply_strategy(ast)?;
+        self.declare_extensions(&mut result);
+        Ok(result)
+    }
+
+    fn apply_strategy(&self, ast: AST) -> Result<MinimizationResult, String> {
         match self.strategy {
             OptimizationStrategy::Minimal => self.base_minimizer.minimize(ast),
             OptimizationStrategy::HoistAll => {
-                let minimized = self.base_minimizer.minimize(ast)?;
+                let extra = ast.namespaces.values().cloned()
+                    .chain(self.base_minimizer.registry.get_version_namespaces(&self.base_minimizer.version))
+                    .chain(self.extensions.iter().map(|namespace| namespace.uri.clone()))
+                    .collect();
+                let minimized = self.base_minimizer.minimize_with(ast, extra)?;
                 let hoisted_ast = self.base_minimizer.hoist_namespaces(minimized.optimized_ast)?;
                 Ok(MinimizationResult {
                     optimized_ast: hoisted_ast,
                     ..minimized
                 })
             },
-            OptimizationStrategy::Conservative => {
-                // Conservative approach: minimal changes
-                let mut result = self.base_minimizer.minimize(ast)?;
-                result.warnings.push("Conservative mode: minimal namespace optimization applied".to_string());
-                Ok(result)
-            },
+            OptimizationStrategy::Conservative => self.base_minimizer.complete(ast),
+            OptimizationStrategy::PreserveOriginal => Ok(MinimizationResult {
+                root_namespaces: ast.namespaces.clone(),
+                prefix_mapping: ast.namespaces.keys().map(|prefix| (prefix.clone(), prefix.clone())).collect(),
+                optimized_ast: ast,
+                warnings: Vec::new(),
+            }),
+        }
+    }
+
+    /// Declare every extension namespace under its registered prefix,
+    /// renaming the prefixes the tree used for it
+    fn declare_extensions(&self, result: &mut MinimizationResult) {
+        let ast = &mut result.optimized_ast;
+        for extension in self.extensions.iter() {
+            if ast.namespaces.get(&extension.prefix).is_some_and(|uri| *uri != extension.uri) {
+                result.warnings.push(format!(
+                    "Prefix {} is declared for another namespace; {} keeps its prefix",
+                    extension.prefix, extension.uri
+                ));
+                continue;
+            }
+
+            let renamed: IndexMap<String, String> = ast.namespaces.iter()
+                .filter(|(prefix, uri)| **uri == extension.uri && **prefix != extension.prefix)
+                .map(|(prefix, _)| (prefix.clone(), extension.prefix.clone()))
+                .collect();
+            ast.namespaces.retain(|prefix, _| !renamed.contains_key(prefix));
+            if !ast.namespaces.contains_key(&extension.prefix) {
+                ast.namespaces.insert(extension.prefix.clone(), extension.uri.clone());
+            }
+            self.base_minimizer.update_element_prefixes(&mut ast.root, &renamed);
+            for (prefix, registered) in renamed {
+                result.prefix_mapping.insert(prefix, registered);
+            }
         }
+        result.root_namespaces = ast.namespaces.clone();
     }
 }
 
@@ -399,6 +540,52 @@ mod tests {
         assert!(usage.used_namespaces.contains("http://ddex.net/xml/ern/43"));
     }
 
+    #[test]
+    fn test_optimization_strategies() {
+        let ast = || {
+            let mut root = Element::new("NewReleaseMessage").with_namespace("ern");
+            root.add_child(Element::new("MessageHeader"));
+            let mut namespaces = IndexMap::new();
+            namespaces.insert("ern".to_string(), "http://ddex.net/xml/ern/43".to_string());
+            namespaces.insert("xsi".to_string(), XSI_NAMESPACE.to_string());
+            AST { root, namespaces, schema_location: None }
+        };
+        let prefixes = |strategy| {
+            let result = AdvancedNamespaceMinimizer::new(ERNVersion::V4_3, strategy).minimize(ast()).unwrap();
+            result.optimized_ast.namespaces.keys().cloned().collect::<Vec<_>>()
+        };
+
+        assert_eq!(prefixes(OptimizationStrategy::Minimal), ["ern"]);
+        assert_eq!(prefixes(OptimizationStrategy::HoistAll), ["avs", "ern", "xsi"]);
+        assert_eq!(prefixes(OptimizationStrategy::Conservative), ["ern", "xsi"]);
+        assert_eq!(prefixes(OptimizationStrategy::PreserveOriginal), ["ern", "xsi"]);
+    }
+
+    #[test]
+    fn test_extension_namespaces_keep_their_prefix() {
+        let mut extensions = ExtensionNamespaces::new();
+        extensions.register("label", "http://label.example.com/ddex", None).unwrap();
+
+        let mut root = Element::new("NewReleaseMessage").with_namespace("ern");
+        root.add_child(Element::new("CatalogCode").with_namespace("ns1"));
+        let mut namespaces = IndexMap::new();
+        namespaces.insert("ern".to_string(), "http://ddex.net/xml/ern/43".to_string());
+        namespaces.insert("ns1".to_string(), "http://label.example.com/ddex".to_string());
+        let ast = AST { root, namespaces, schema_location: None };
+
+        for strategy in [OptimizationStrategy::Minimal, OptimizationStrategy::PreserveOriginal] {
+            let result = AdvancedNamespaceMinimizer::new(ERNVersion::V4_3, strategy)
+                .with_extension_namespaces(&extensions)
+                .minimize(ast.clone())
+                .unwrap();
+            let ast = result.optimized_ast;
+            assert_eq!(ast.namespaces.get("label").map(String::as_str), Some("http://label.example.com/ddex"));
+            assert!(!ast.namespaces.contains_key("ns1"));
+            let Node::Element(child) = &ast.root.children[0] else { panic!("expected an element") };
+            assert_eq!(child.namespace.as_deref(), Some("label"));
+        }
+    }
+
     #[test]
     fn test_prefix_generation() {
         let minimizer = NamespaceMinimizer::new(ERNVersion::V4_3);
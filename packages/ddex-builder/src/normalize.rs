@@ -0,0 +1,279 @@
+//! Normalization of build requests
+//!
+//! [`DDEXBuilder::build`](crate::DDEXBuilder::build) runs
+//! [`normalize_request`] before preflight: whitespace around names and
+//! titles is trimmed, dates written as `YYYY/MM/DD`, `YYYY.MM.DD` or
+//! `YYYYMMDD` become `YYYY-MM-DD`, and ISRCs, UPCs and territory codes lose
+//! their separators and are upper-cased. Every change is recorded in the
+//! result's [`Warnings`] with the JSON path of the value, so nothing is
+//! rewritten silently.
+
+use crate::builder::{BuildRequest, LocalizedStringRequest, PartyRequest};
+use chrono::NaiveDate;
+use ddex_core::warnings::{NormalizationKind, Warnings};
+
+/// Date layouts coerced to ISO 8601
+pub(crate) const DATE_FORMATS: &[&str] = &["%Y/%m/%d", "%Y.%m.%d", "%Y%m%d"];
+
+/// Normalize `request` in place, recording every change in `warnings`
+pub fn normalize_request(request: &mut BuildRequest, warnings: &mut Warnings) {
+    party(&mut request.header.message_sender, "header.message_sender", warnings);
+    party(&mut request.header.message_recipient, "header.message_recipient", warnings);
+
+    for (r, release) in request.releases.iter_mut().enumerate() {
+        let path = format!("releases[{}]", r);
+        localized(&mut release.title, &format!("{}.title", path), warnings);
+        trim(&mut release.artist, &format!("{}.artist", path), warnings);
+        if let Some(label) = release.label.as_mut() {
+            trim(label, &format!("{}.label", path), warnings);
+        }
+        if let Some(date) = release.release_date.as_mut() {
+            coerce_date(date, &format!("{}.release_date", path), warnings);
+        }
+        if let Some(date) = release.original_release_date.as_mut() {
+            coerce_date(date, &format!("{}.original_release_date", path), warnings);
+        }
+        if let Some(upc) = release.upc.as_mut() {
+            reformat(upc, &format!("{}.upc", path), warnings, |c| c.is_ascii_digit());
+        }
+        for (t, territory) in release.territory_details.iter_mut().enumerate() {
+            let path = format!("{}.territory_details[{}]", path, t);
+            territory_codes(&mut territory.territory_codes, &format!("{}.territory_codes", path), warnings);
+            if let Some(date) = territory.release_date.as_mut() {
+                coerce_date(date, &format!("{}.release_date", path), warnings);
+            }
+        }
+        for (t, track) in release.tracks.iter_mut().enumerate() {
+            let path = format!("{}.tracks[{}]", path, t);
+            trim(&mut track.title, &format!("{}.title", path), warnings);
+            trim(&mut track.artist, &format!("{}.artist", path), warnings);
+            isrc(&mut track.isrc, &format!("{}.isrc", path), warnings);
+        }
+        for (i, code) in release.track_isrcs.iter_mut().enumerate() {
+            isrc(code, &format!("{}.track_isrcs[{}]", path, i), warnings);
+        }
+        for (i, resource) in release.resources.iter_mut().enumerate() {
+            if let Some(code) = resource.isrc.as_mut() {
+                isrc(code, &format!("{}.resources[{}].isrc", path, i), warnings);
+            }
+        }
+    }
+
+    for (d, deal) in request.deals.iter_mut().enumerate() {
+        let path = format!("deals[{}].deal_terms", d);
+        let terms = &mut deal.deal_terms;
+        territory_codes(&mut terms.territory_code, &format!("{}.territory_code", path), warnings);
+        let dates = [
+            ("start_date", &mut terms.start_date),
+            ("end_date", &mut terms.end_date),
+            ("takedown_date", &mut terms.takedown_date),
+            ("pre_order_release_date", &mut terms.pre_order_release_date),
+            ("pre_order_preview_date", &mut terms.pre_order_preview_date),
+        ];
+        for (name, date) in dates {
+            if let Some(date) = date.as_mut() {
+                coerce_date(date, &format!("{}.{}", path, name), warnings);
+            }
+        }
+    }
+}
+
+fn party(party: &mut PartyRequest, path: &str, warnings: &mut Warnings) {
+    localized(&mut party.party_name, &format!("{}.party_name", path), warnings);
+}
+
+fn localized(strings: &mut [LocalizedStringRequest], path: &str, warnings: &mut Warnings) {
+    for (i, string) in strings.iter_mut().enumerate() {
+        trim(&mut string.text, &format!("{}[{}].text", path, i), warnings);
+    }
+}
+
+fn trim(value: &mut String, path: &str, warnings: &mut Warnings) {
+    let trimmed = value.trim();
+    if trimmed.len() != value.len() {
+        let trimmed = trimmed.to_string();
+        let before = std::mem::replace(value, trimmed.clone());
+        warnings.record(NormalizationKind::TrimmedWhitespace, path, before, Some(trimmed));
+    }
+}
+
+fn coerce_date(value: &mut String, path: &str, warnings: &mut Warnings) {
+    let given = value.trim();
+    if NaiveDate::parse_from_str(given, "%Y-%m-%d").is_ok() {
+        if given.len() != value.len() {
+            trim(value, path, warnings);
+        }
+        return;
+    }
+    let Some(date) = DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(given, format).ok())
+    else {
+        // Left for preflight to report
+        return;
+    };
+    let iso = date.format("%Y-%m-%d").to_string();
+    let before = std::mem::replace(value, iso.clone());
+    warnings.record(NormalizationKind::CoercedDate, path, before, Some(iso));
+}
+
+/// Drop spaces and hyphens and upper-case the rest, if every remaining
+/// character passes `keep`
+fn reformat(value: &mut String, path: &str, warnings: &mut Warnings, keep: impl Fn(char) -> bool) {
+    let compact: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    if compact != *value && !compact.is_empty() && compact.chars().all(keep) {
+        let before = std::mem::replace(value, compact.clone());
+        warnings.record(NormalizationKind::ReformattedIdentifier, path, before, Some(compact));
+    }
+}
+
+fn isrc(value: &mut String, path: &str, warnings: &mut Warnings) {
+    reformat(value, path, warnings, |c| c.is_ascii_alphanumeric());
+}
+
+fn territory_codes(codes: &mut [String], path: &str, warnings: &mut Warnings) {
+    for (i, code) in codes.iter_mut().enumerate() {
+        reformat(code, &format!("{}[{}]", path, i), warnings, |c| c.is_ascii_alphabetic());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{DealRequest, DealTerms, ReleaseRequest, TrackRequest};
+
+    fn request() -> BuildRequest {
+        let mut request: BuildRequest = serde_json::from_value(serde_json::json!({
+            "header": {
+                "message_sender": { "party_name": [{ "text": " Label " }] },
+                "message_recipient": { "party_name": [{ "text": "DSP" }] }
+            },
+            "version": "4.3",
+            "releases": [],
+            "deals": []
+        }))
+        .unwrap();
+        let mut release: ReleaseRequest = serde_json::from_value(serde_json::json!({
+            "release_id": "R1",
+            "title": [{ "text": "Album\n" }],
+            "artist": "Artist",
+            "release_date": "2024/03/01",
+            "upc": "0 12345 67890 5",
+            "tracks": []
+        }))
+        .unwrap();
+        release.tracks.push(TrackRequest {
+            track_id: "T1".to_string(),
+            resource_reference: None,
+            isrc: "us-rc1-76-07839".to_string(),
+            title: "Track".to_string(),
+            duration: "PT3M".to_string(),
+            artist: "Artist".to_string(),
+            contributors: Vec::new(),
+            p_line: None,
+            c_line: None,
+            comments: Vec::new(),
+            technical_details: None,
+            parental_warning: None,
+        });
+        request.releases.push(release);
+        request.deals.push(DealRequest {
+            deal_reference: None,
+            deal_terms: DealTerms {
+                commercial_model_type: "PayAsYouGoModel".to_string(),
+                territory_code: vec!["gb".to_string()],
+                start_date: Some("20240301".to_string()),
+                end_date: Some("not a date".to_string()),
+                use_types: vec!["PermanentDownload".to_string()],
+                takedown_date: None,
+                pre_order_release_date: None,
+                pre_order_preview_date: None,
+                price_information: Vec::new(),
+                take_down: false,
+            },
+            release_references: Vec::new(),
+            release_ids: Vec::new(),
+            comments: Vec::new(),
+        });
+        request
+    }
+
+    #[test]
+    fn test_normalizations_are_recorded() {
+        let mut request = request();
+        let mut warnings = Warnings::new();
+        normalize_request(&mut request, &mut warnings);
+
+        let release = &request.releases[0];
+        assert_eq!(request.header.message_sender.party_name[0].text, "Label");
+        assert_eq!(release.title[0].text, "Album");
+        assert_eq!(release.release_date.as_deref(), Some("2024-03-01"));
+        assert_eq!(release.upc.as_deref(), Some("012345678905"));
+        assert_eq!(release.tracks[0].isrc, "USRC17607839");
+        assert_eq!(request.deals[0].deal_terms.territory_code, vec!["GB"]);
+        assert_eq!(request.deals[0].deal_terms.start_date.as_deref(), Some("2024-03-01"));
+        assert_eq!(request.deals[0].deal_terms.end_date.as_deref(), Some("not a date"));
+
+        let recorded: Vec<_> = warnings.iter().map(|w| (w.kind, w.path.as_str())).collect();
+        assert_eq!(
+            recorded,
+            vec![
+                (NormalizationKind::TrimmedWhitespace, "header.message_sender.party_name[0].text"),
+                (NormalizationKind::TrimmedWhitespace, "releases[0].title[0].text"),
+                (NormalizationKind::CoercedDate, "releases[0].release_date"),
+                (NormalizationKind::ReformattedIdentifier, "releases[0].upc"),
+                (NormalizationKind::ReformattedIdentifier, "releases[0].tracks[0].isrc"),
+                (NormalizationKind::ReformattedIdentifier, "deals[0].deal_terms.territory_code[0]"),
+                (NormalizationKind::CoercedDate, "deals[0].deal_terms.start_date"),
+            ]
+        );
+        let date = warnings.iter().find(|w| w.kind == NormalizationKind::CoercedDate).unwrap();
+        assert_eq!(date.before, "2024/03/01");
+        assert_eq!(date.after.as_deref(), Some("2024-03-01"));
+    }
+
+    #[test]
+    fn test_dropped_fields_are_recorded() {
+        let value = serde_json::json!({
+            "header": {
+                "message_sender": { "party_name": [{ "text": "Label", "colour": "red" }] },
+                "message_recipient": { "party_name": [] }
+            },
+            "version": "4.3",
+            "releases": [],
+            "priority": 1
+        });
+        let mut warnings = Warnings::new();
+        BuildRequest::from_json_value(&value, &mut warnings).unwrap();
+
+        let dropped: Vec<_> = warnings.iter().map(|w| (w.path.as_str(), w.before.as_str())).collect();
+        assert_eq!(
+            dropped,
+            vec![("header.message_sender.party_name[0].colour", "\"red\""), ("priority", "1")]
+        );
+        assert!(warnings.iter().all(|w| w.kind == NormalizationKind::DroppedField && w.after.is_none()));
+    }
+
+    #[test]
+    fn test_fail_on_warning() {
+        let builder = crate::DDEXBuilder::new();
+        let options = crate::BuildOptions { fail_on_warning: true, ..Default::default() };
+        let err = builder.build(request(), options).unwrap_err();
+        assert_eq!(err.code(), "DDEX-B-0204");
+        assert!(err.to_string().contains("COERCED_DATE at releases[0].release_date"));
+    }
+
+    #[test]
+    fn test_clean_request_records_nothing() {
+        let mut request = request();
+        let mut warnings = Warnings::new();
+        normalize_request(&mut request, &mut warnings);
+        let mut again = Warnings::new();
+        normalize_request(&mut request, &mut again);
+        assert!(again.is_empty());
+    }
+}
@@ -571,6 +571,7 @@ mod tests {
                 },
                 message_control_type: None,
                 message_created_date_time: None,
+                ..Default::default()
             },
             version: "4.3".to_string(),
             profile: None,
@@ -596,6 +597,12 @@ mod tests {
             title: "Test Track".to_string(),
             duration: "PT3M30S".to_string(),
             artist: "Test Artist".to_string(),
+            contributors: Vec::new(),
+            p_line: None,
+            c_line: None,
+            comments: Vec::new(),
+            technical_details: None,
+            parental_warning: None,
         };
         
         let result = processor.validate_track(&valid_track);
@@ -608,6 +615,12 @@ mod tests {
             title: "".to_string(), // Empty
             duration: "3:30".to_string(), // Wrong format
             artist: "Test Artist".to_string(),
+            contributors: Vec::new(),
+            p_line: None,
+            c_line: None,
+            comments: Vec::new(),
+            technical_details: None,
+            parental_warning: None,
         };
         
         let result = processor.validate_track(&invalid_track);
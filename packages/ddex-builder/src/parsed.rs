@@ -0,0 +1,497 @@
+//! Build requests from parsed messages
+
+use crate::builder::{
+    BuildRequest, CLineRequest, ContributorKind, ContributorRequest, DealRequest, DealTerms,
+    LocalizedStringRequest, MessageHeaderRequest, PLineRequest, PartyRequest, PriceInformationRequest,
+    PriceRequest, ReleaseRequest, ResourceKind, ResourceRequest, TechnicalDetailsRequest,
+    TerritoryDetailsRequest, TrackRequest,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use ddex_core::models::common::{Copyright, Identifier, IdentifierType, LocalizedString, Price};
+use ddex_core::duration::DdexDuration;
+use ddex_core::models::flat::FlattenedMessage;
+use ddex_core::models::graph::{
+    Artist, CommercialModelType, Deal, ERNMessage, ERNProfile, MessageControlType, Release, ReleaseDateKind,
+    Resource, ResourceType, UseType,
+};
+use indexmap::IndexSet;
+use std::time::Duration;
+
+impl BuildRequest {
+    /// Build a request that reproduces a parsed graph model
+    ///
+    /// This closes the parse → modify → build loop without copying fields
+    /// by hand:
+    ///
+    /// ```no_run
+    /// # fn parsed() -> ddex_core::models::graph::ERNMessage { unimplemented!() }
+    /// use ddex_builder::{BuildOptions, BuildRequest, DDEXBuilder};
+    ///
+    /// let mut message = parsed();
+    /// message.releases[0].release_title[0].text = "Remastered".to_string();
+    /// let result = DDEXBuilder::new().build(BuildRequest::from_graph(&message), BuildOptions::default())?;
+    /// # Ok::<(), ddex_builder::BuildError>(())
+    /// ```
+    ///
+    /// A request holds less than the graph, so the conversion is lossy:
+    ///
+    /// - Sound recordings become tracks of the first release that references
+    ///   them; later releases only reference them. Other resources are kept
+    ///   as resources, typed `FrontCoverImage` for images and `Unknown`
+    ///   otherwise, because the graph does not record resource subtypes.
+    /// - The release `GRid` (or its first identifier) becomes `release_id`,
+    ///   and the UPC or EAN becomes `upc`. Only the first genre and release
+    ///   date are kept.
+    /// - Attributes, extensions, parties and audit trail events are not
+    ///   carried over; comments on releases, tracks and deals are.
+    pub fn from_graph(message: &ERNMessage) -> Self {
+        let header = &message.message_header;
+
+        // Each sound recording is emitted once, by the first release using it
+        let mut emitted = IndexSet::new();
+        let releases = message.releases.iter()
+            .map(|release| release_request(release, &message.resources, &mut emitted))
+            .collect();
+
+        BuildRequest {
+            header: MessageHeaderRequest {
+                message_id: Some(header.message_id.clone()),
+                message_sender: party(&header.message_sender.party_id, &header.message_sender.party_name),
+                message_recipient: party(&header.message_recipient.party_id, &header.message_recipient.party_name),
+                message_control_type: header.message_control_type.as_ref().map(|control| match control {
+                    MessageControlType::LiveMessage => "LiveMessage".to_string(),
+                    MessageControlType::TestMessage => "TestMessage".to_string(),
+                }),
+                message_created_date_time: Some(
+                    header.message_created_date_time.to_rfc3339_opts(SecondsFormat::Secs, true),
+                ),
+                message_thread_id: header.message_thread_id.clone(),
+                ..Default::default()
+            },
+            version: message.version.as_str().to_string(),
+            profile: message.profile.as_ref().map(|profile| match profile {
+                ERNProfile::AudioAlbum => "AudioAlbum",
+                ERNProfile::AudioSingle => "AudioSingle",
+                ERNProfile::Video => "VideoSingle",
+                ERNProfile::Mixed => "Mixed",
+            }.to_string()),
+            releases,
+            deals: message.deals.iter().map(deal_request).collect(),
+            extensions: None,
+        }
+    }
+
+    /// Build a request from the parser's flattened view
+    ///
+    /// Goes through [`FlattenedMessage::to_graph`], so everything the flat
+    /// view drops is missing from the request as well.
+    pub fn from_flat(message: &FlattenedMessage) -> Self {
+        Self::from_graph(&message.to_graph())
+    }
+}
+
+fn release_request(release: &Release, resources: &[Resource], emitted: &mut IndexSet<String>) -> ReleaseRequest {
+    let artist = display_artist(&release.display_artist);
+    let references: Vec<String> = release.release_resource_reference_list.iter()
+        .map(|r| r.resource_reference.clone())
+        .collect();
+
+    let mut tracks = Vec::new();
+    let mut release_resources = Vec::new();
+    for reference in &references {
+        let Some(resource) = resources.iter().find(|r| &r.resource_reference == reference) else {
+            continue;
+        };
+        if !emitted.insert(reference.clone()) {
+            continue;
+        }
+        match resource.resource_type {
+            ResourceType::SoundRecording => tracks.push(track_request(resource, &artist)),
+            _ => release_resources.push(resource_request(resource)),
+        }
+    }
+
+    let genre = release.genre.first().map(|g| g.genre_text.clone());
+    let territory_details = match genre {
+        Some(genre) => vec![TerritoryDetailsRequest {
+            territory_codes: if release.territory_code.is_empty() {
+                vec!["Worldwide".to_string()]
+            } else {
+                release.territory_code.clone()
+            },
+            genre: Some(genre),
+            ..Default::default()
+        }],
+        None => Vec::new(),
+    };
+
+    ReleaseRequest {
+        release_id: identifier(&release.release_id, &[IdentifierType::GRid, IdentifierType::GRID])
+            .or_else(|| release.release_id.first().map(|id| id.value.clone()))
+            .unwrap_or_else(|| release.release_reference.clone()),
+        release_reference: Some(release.release_reference.clone()),
+        title: release.release_title.iter().map(localized).collect(),
+        artist,
+        label: None,
+        release_date: release.date(ReleaseDateKind::ReleaseDate).map(date),
+        original_release_date: release.date(ReleaseDateKind::OriginalReleaseDate).map(date),
+        upc: identifier(&release.release_id, &[IdentifierType::UPC, IdentifierType::EAN]),
+        tracks,
+        resource_references: Some(references),
+        resources: release_resources,
+        territory_details,
+        contributors: release.display_artist.iter()
+            .filter_map(|artist| {
+                Some(ContributorRequest {
+                    name: artist.display_artist_name.first()?.text.clone(),
+                    kind: ContributorKind::DisplayArtist,
+                    role: artist.artist_role.first().cloned().unwrap_or_else(|| "MainArtist".to_string()),
+                    sequence: artist.sequence_number.and_then(|n| u32::try_from(n).ok()),
+                    party_ids: Vec::new(),
+                })
+            })
+            .collect(),
+        p_line: None,
+        c_line: None,
+        track_isrcs: Vec::new(),
+        track_releases: Vec::new(),
+        comments: release.comments.clone().unwrap_or_default(),
+    }
+}
+
+fn track_request(resource: &Resource, artist: &str) -> TrackRequest {
+    TrackRequest {
+        track_id: resource.resource_reference.clone(),
+        resource_reference: Some(resource.resource_reference.clone()),
+        isrc: identifier(&resource.resource_id, &[IdentifierType::ISRC]).unwrap_or_default(),
+        title: resource.reference_title.first().map(|t| t.text.clone()).unwrap_or_default(),
+        duration: duration(resource.duration.unwrap_or_default()),
+        artist: artist.to_string(),
+        contributors: Vec::new(),
+        p_line: resource.p_line.first().map(p_line),
+        c_line: resource.c_line.first().map(c_line),
+        comments: resource.comments.clone().unwrap_or_default(),
+        technical_details: technical_details(resource),
+        parental_warning: None,
+    }
+}
+
+fn resource_request(resource: &Resource) -> ResourceRequest {
+    let (kind, resource_type) = match resource.resource_type {
+        ResourceType::Image => (ResourceKind::Image, "FrontCoverImage"),
+        ResourceType::Text => (ResourceKind::Text, "Unknown"),
+        ResourceType::SheetMusic => (ResourceKind::SheetMusic, "Unknown"),
+        ResourceType::Video | ResourceType::SoundRecording => (ResourceKind::Video, "Unknown"),
+    };
+    ResourceRequest {
+        kind,
+        resource_id: resource.resource_id.first()
+            .map(|id| id.value.clone())
+            .unwrap_or_else(|| resource.resource_reference.clone()),
+        resource_reference: Some(resource.resource_reference.clone()),
+        resource_type: resource_type.to_string(),
+        isrc: identifier(&resource.resource_id, &[IdentifierType::ISRC]),
+        title: resource.reference_title.first().map(|t| t.text.clone()),
+        duration: resource.duration.map(duration),
+        technical_details: technical_details(resource),
+        p_line: resource.p_line.first().map(p_line),
+        c_line: resource.c_line.first().map(c_line),
+    }
+}
+
+fn deal_request(deal: &Deal) -> DealRequest {
+    let terms = &deal.deal_terms;
+    let validity = terms.validity_period.as_ref();
+
+    let mut price_information: Vec<PriceInformationRequest> = (0..terms.wholesale_price.len().max(terms.suggested_retail_price.len()))
+        .map(|i| PriceInformationRequest {
+            price_code: None,
+            wholesale_price: terms.wholesale_price.get(i).map(price),
+            suggested_retail_price: terms.suggested_retail_price.get(i).map(price),
+        })
+        .collect();
+    price_information.extend(terms.price_information.iter().map(|info| {
+        let retail = info.price_type.contains("Retail");
+        PriceInformationRequest {
+            price_code: info.price_tier.clone(),
+            wholesale_price: (!retail).then(|| price(&info.price)),
+            suggested_retail_price: retail.then(|| price(&info.price)),
+        }
+    }));
+
+    DealRequest {
+        deal_reference: deal.deal_reference.clone(),
+        deal_terms: DealTerms {
+            commercial_model_type: terms.commercial_model_type.first()
+                .map(|model| match model {
+                    CommercialModelType::Other(other) => other.clone(),
+                    model => format!("{:?}", model),
+                })
+                .unwrap_or_default(),
+            territory_code: terms.territory_code.clone(),
+            start_date: validity.and_then(|v| v.start_date).or(terms.start_date).map(date),
+            end_date: validity.and_then(|v| v.end_date).or(terms.end_date).map(date),
+            use_types: terms.use_type.iter()
+                .map(|use_type| match use_type {
+                    UseType::Other(other) => other.clone(),
+                    use_type => format!("{:?}", use_type),
+                })
+                .collect(),
+            takedown_date: terms.takedown_date.map(date),
+            pre_order_release_date: terms.pre_order_date.map(date),
+            pre_order_preview_date: terms.pre_order_preview_date.map(date),
+            price_information,
+            take_down: false,
+        },
+        release_references: deal.deal_release_reference.clone(),
+        release_ids: Vec::new(),
+        comments: deal.comments.clone().unwrap_or_default(),
+    }
+}
+
+fn party(ids: &[Identifier], names: &[LocalizedString]) -> PartyRequest {
+    PartyRequest {
+        party_name: names.iter().map(localized).collect(),
+        party_id: ids.first().map(|id| id.value.clone()),
+        party_reference: None,
+    }
+}
+
+fn display_artist(artists: &[Artist]) -> String {
+    artists.iter()
+        .filter_map(|artist| artist.display_artist_name.first())
+        .map(|name| name.text.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn technical_details(resource: &Resource) -> Option<TechnicalDetailsRequest> {
+    let details = resource.technical_details.first()?;
+    Some(TechnicalDetailsRequest {
+        codec: details.audio_codec.clone(),
+        sample_rate: details.sample_rate.and_then(|rate| u32::try_from(rate).ok()),
+        ..Default::default()
+    })
+}
+
+/// First identifier of one of the given types
+fn identifier(ids: &[Identifier], types: &[IdentifierType]) -> Option<String> {
+    ids.iter().find(|id| types.contains(&id.id_type)).map(|id| id.value.clone())
+}
+
+fn localized(string: &LocalizedString) -> LocalizedStringRequest {
+    LocalizedStringRequest {
+        text: string.text.clone(),
+        language_code: string.language_code.clone(),
+    }
+}
+
+fn p_line(copyright: &Copyright) -> PLineRequest {
+    PLineRequest {
+        year: copyright.year.and_then(|year| u16::try_from(year).ok()),
+        text: copyright.text.clone(),
+    }
+}
+
+fn c_line(copyright: &Copyright) -> CLineRequest {
+    CLineRequest {
+        year: copyright.year.and_then(|year| u16::try_from(year).ok()),
+        text: copyright.text.clone(),
+    }
+}
+
+fn price(price: &Price) -> PriceRequest {
+    PriceRequest {
+        amount: price.amount.to_string(),
+        currency_code: price.currency.clone(),
+    }
+}
+
+fn date(date_time: DateTime<Utc>) -> String {
+    date_time.format("%Y-%m-%d").to_string()
+}
+
+/// ISO 8601 duration, e.g. `PT3M45S`, keeping fractional seconds
+fn duration(duration: Duration) -> String {
+    DdexDuration::from(duration).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BuildOptions, DDEXBuilder};
+    use chrono::TimeZone;
+    use ddex_core::models::graph::{
+        self, Genre, MessageHeader, MessageRecipient, MessageSender, MessageType, ReleaseEvent,
+        ReleaseResourceReference, ReleaseType,
+    };
+    use ddex_core::models::versions::ERNVersion;
+
+    fn id(id_type: IdentifierType, value: &str) -> Identifier {
+        Identifier { id_type, namespace: None, value: value.to_string() }
+    }
+
+    fn sample_message() -> ERNMessage {
+        let sender = MessageSender {
+            party_id: vec![id(IdentifierType::Proprietary, "PADPIDA0000000001")],
+            party_name: vec![LocalizedString::new("Label")],
+            trading_name: None,
+            attributes: None,
+            extensions: None,
+            comments: None,
+        };
+        let release_date = Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap();
+        let sound_recording = |reference: &str, isrc: &str, title: &str| Resource {
+            resource_reference: reference.to_string(),
+            resource_type: ResourceType::SoundRecording,
+            resource_id: vec![id(IdentifierType::ISRC, isrc)],
+            reference_title: vec![LocalizedString::new(title)],
+            duration: Some(Duration::from_secs(225)),
+            technical_details: Vec::new(),
+            rights_controller: Vec::new(),
+            p_line: vec![Copyright { text: "(P) 2024 Label".to_string(), year: Some(2024), owner: None }],
+            c_line: Vec::new(),
+            extensions: None,
+            comments: None,
+        };
+        let release = |reference: &str, upc: &str, tracks: &[&str]| Release {
+            release_reference: reference.to_string(),
+            release_id: vec![id(IdentifierType::UPC, upc)],
+            release_title: vec![LocalizedString::new("Album")],
+            release_subtitle: None,
+            release_type: Some(ReleaseType::Album),
+            genre: vec![Genre { genre_text: "Pop".to_string(), sub_genre: None, attributes: None, extensions: None, comments: None }],
+            release_resource_reference_list: tracks.iter()
+                .map(|track| ReleaseResourceReference {
+                    resource_reference: track.to_string(),
+                    sequence_number: None,
+                    disc_number: None,
+                    track_number: None,
+                    side: None,
+                    is_hidden: false,
+                    is_bonus: false,
+                    extensions: None,
+                    comments: None,
+                })
+                .collect(),
+            display_artist: vec![Artist {
+                party_reference: None,
+                artist_role: vec!["MainArtist".to_string()],
+                display_artist_name: vec![LocalizedString::new("Artist")],
+                sequence_number: Some(1),
+            }],
+            party_list: Vec::new(),
+            release_date: vec![ReleaseEvent {
+                release_event_type: "ReleaseDate".to_string(),
+                event_date: Some(release_date),
+                territory: None,
+                extensions: None,
+                comments: None,
+            }],
+            territory_code: Vec::new(),
+            excluded_territory_code: Vec::new(),
+            attributes: None,
+            extensions: None,
+            comments: None,
+        };
+
+        ERNMessage {
+            message_header: MessageHeader {
+                message_id: "MSG1".to_string(),
+                message_type: MessageType::NewReleaseMessage,
+                message_created_date_time: release_date,
+                message_sender: sender.clone(),
+                message_recipient: MessageRecipient {
+                    party_id: vec![id(IdentifierType::Proprietary, "PADPIDA0000000002")],
+                    party_name: vec![LocalizedString::new("DSP")],
+                    trading_name: None,
+                    attributes: None,
+                    extensions: None,
+                    comments: None,
+                },
+                message_control_type: Some(MessageControlType::LiveMessage),
+                message_thread_id: None,
+                attributes: None,
+                extensions: None,
+                comments: None,
+            },
+            parties: Vec::new(),
+            resources: vec![
+                sound_recording("A1", "USRC17607839", "One"),
+                sound_recording("A2", "USRC17607840", "Two"),
+            ],
+            releases: vec![
+                release("R1", "00602577512345", &["A1", "A2"]),
+                release("R2", "00602577512346", &["A2"]),
+            ],
+            deals: vec![Deal {
+                deal_reference: Some("D1".to_string()),
+                deal_release_reference: vec!["R1".to_string()],
+                deal_terms: graph::DealTerms {
+                    validity_period: None,
+                    start_date: Some(release_date),
+                    end_date: None,
+                    territory_code: vec!["Worldwide".to_string()],
+                    excluded_territory_code: Vec::new(),
+                    distribution_channel: Vec::new(),
+                    excluded_distribution_channel: Vec::new(),
+                    commercial_model_type: vec![CommercialModelType::SubscriptionModel],
+                    use_type: vec![UseType::OnDemandStream],
+                    price_information: Vec::new(),
+                    wholesale_price: vec![Price { amount: 0.99, currency: "USD".to_string(), territory: None }],
+                    suggested_retail_price: Vec::new(),
+                    pre_order_date: None,
+                    pre_order_preview_date: None,
+                    instant_gratification_date: None,
+                    takedown_date: None,
+                },
+                comments: None,
+            }],
+            purged_releases: Vec::new(),
+            version: ERNVersion::V4_3,
+            profile: Some(ERNProfile::AudioAlbum),
+            message_audit_trail: None,
+            attributes: None,
+            extensions: None,
+            legacy_extensions: None,
+            comments: None,
+        }
+    }
+
+    #[test]
+    fn test_request_from_graph() {
+        let request = BuildRequest::from_graph(&sample_message());
+
+        assert_eq!(request.version, "4.3");
+        assert_eq!(request.header.message_created_date_time.as_deref(), Some("2024-05-01T00:00:00Z"));
+        assert_eq!(request.releases[0].upc.as_deref(), Some("00602577512345"));
+        assert_eq!(request.releases[0].release_date.as_deref(), Some("2024-05-01"));
+        assert_eq!(request.releases[0].tracks.len(), 2);
+        assert_eq!(request.releases[0].tracks[0].duration, "PT3M45S");
+        assert_eq!(request.releases[0].territory_details[0].genre.as_deref(), Some("Pop"));
+
+        // A2 is shared, so the second release only references it
+        assert!(request.releases[1].tracks.is_empty());
+        assert_eq!(request.releases[1].resource_references.as_deref(), Some(&["A2".to_string()][..]));
+
+        let terms = &request.deals[0].deal_terms;
+        assert_eq!(terms.commercial_model_type, "SubscriptionModel");
+        assert_eq!(terms.use_types, vec!["OnDemandStream"]);
+        assert_eq!(terms.price_information[0].wholesale_price.as_ref().unwrap().amount, "0.99");
+    }
+
+    #[test]
+    fn test_graph_round_trip_builds() {
+        let mut message = sample_message();
+        message.releases[0].release_title[0].text = "Album (Deluxe)".to_string();
+
+        let result = DDEXBuilder::new()
+            .build(BuildRequest::from_graph(&message), BuildOptions::default())
+            .unwrap();
+        assert!(result.xml.contains("<TitleText>Album (Deluxe)</TitleText>"));
+        assert_eq!(result.xml.matches("<ResourceReference>A2</ResourceReference>").count(), 1);
+
+        let flat = BuildRequest::from_flat(&message.flatten());
+        assert_eq!(flat.releases[0].title[0].text, "Album (Deluxe)");
+    }
+}
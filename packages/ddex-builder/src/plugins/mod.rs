@@ -0,0 +1,246 @@
+//! Validation plugins for in-house rules
+//!
+//! Labels have rules of their own on top of DDEX's and their partners': a
+//! catalog number format, a list of approved sub-labels, a ban on some genre
+//! in some territory. A [`ValidationPlugin`] checks a build request (and,
+//! if it likes, the AST generated from it) and returns
+//! [`ValidationIssue`]s. Plugins registered with
+//! [`Builder::register_validation_plugin`](crate::Builder::register_validation_plugin)
+//! or put in [`BuildOptions::validation_plugins`](crate::BuildOptions::validation_plugins)
+//! run after preflight on every build, unless preflight is off. Errors fail
+//! the build with `ValidationFailed` and warnings become build warnings.
+//!
+//! With the `plugins` feature, `wasm::WasmPlugin` runs rules compiled to
+//! WebAssembly, which is how the CLI loads them (`--plugin rules.wasm`).
+//!
+//! ```rust
+//! use ddex_builder::plugins::{ValidationIssue, ValidationPlugin};
+//! use ddex_builder::{BuildRequest, Builder};
+//! use regex::Regex;
+//!
+//! /// Release IDs are our catalog numbers
+//! struct CatalogNumbers(Regex);
+//!
+//! impl ValidationPlugin for CatalogNumbers {
+//!     fn name(&self) -> &str {
+//!         "catalog-numbers"
+//!     }
+//!
+//!     fn validate(&self, request: &BuildRequest) -> Vec<ValidationIssue> {
+//!         request
+//!             .releases
+//!             .iter()
+//!             .enumerate()
+//!             .filter(|(_, release)| !self.0.is_match(&release.release_id))
+//!             .map(|(i, release)| {
+//!                 ValidationIssue::error(
+//!                     "EML_CATALOG_NUMBER",
+//!                     format!("releases[{}].release_id", i),
+//!                     format!("{} is not an EML catalog number", release.release_id),
+//!                 )
+//!             })
+//!             .collect()
+//!     }
+//! }
+//!
+//! let mut builder = Builder::new();
+//! builder.register_validation_plugin(CatalogNumbers(Regex::new(r"^EML-\d{4}$")?));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+#[cfg(feature = "plugins")]
+pub mod wasm;
+
+use crate::ast::AST;
+use crate::builder::BuildRequest;
+use crate::error::{BuildError, BuildWarning};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+
+/// How serious an issue is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    /// The build fails
+    Error,
+    /// The build succeeds with a warning
+    Warning,
+}
+
+/// A rule broken by a build request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    /// Whether the issue fails the build
+    pub severity: IssueSeverity,
+    /// Code of the rule, e.g. `EML_CATALOG_NUMBER`
+    pub code: String,
+    /// JSON path of the field concerned, e.g. `releases[0].release_id`
+    #[serde(default)]
+    pub field: String,
+    /// What is wrong
+    pub message: String,
+}
+
+impl ValidationIssue {
+    /// An issue failing the build
+    pub fn error(code: impl Into<String>, field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { severity: IssueSeverity::Error, code: code.into(), field: field.into(), message: message.into() }
+    }
+
+    /// An issue reported as a build warning
+    pub fn warning(code: impl Into<String>, field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { severity: IssueSeverity::Warning, code: code.into(), field: field.into(), message: message.into() }
+    }
+}
+
+/// Custom rules checked on every build
+pub trait ValidationPlugin: Send + Sync {
+    /// Name shown with the plugin's errors
+    fn name(&self) -> &str;
+
+    /// Check a request after normalization and preflight
+    fn validate(&self, request: &BuildRequest) -> Vec<ValidationIssue>;
+
+    /// Check the AST generated from the request; nothing by default
+    fn validate_ast(&self, _ast: &AST) -> Vec<ValidationIssue> {
+        Vec::new()
+    }
+}
+
+/// Plugins in the order they were registered
+#[derive(Clone, Default)]
+pub struct ValidationPlugins {
+    plugins: Vec<Arc<dyn ValidationPlugin>>,
+}
+
+impl fmt::Debug for ValidationPlugins {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+impl ValidationPlugins {
+    /// No plugins
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a plugin, run after those added before it
+    pub fn register(&mut self, plugin: impl ValidationPlugin + 'static) {
+        self.plugins.push(Arc::new(plugin));
+    }
+
+    /// Names of the plugins
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.plugins.iter().map(|plugin| plugin.name())
+    }
+
+    /// Number of plugins
+    pub fn len(&self) -> usize {
+        self.plugins.len()
+    }
+
+    /// Whether there are none
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    /// Run every plugin on `request`
+    pub(crate) fn check_request(&self, request: &BuildRequest, warnings: &mut Vec<BuildWarning>) -> Result<(), BuildError> {
+        self.check(|plugin| plugin.validate(request), warnings)
+    }
+
+    /// Run every plugin on `ast`
+    pub(crate) fn check_ast(&self, ast: &AST, warnings: &mut Vec<BuildWarning>) -> Result<(), BuildError> {
+        self.check(|plugin| plugin.validate_ast(ast), warnings)
+    }
+
+    /// Turn warnings into build warnings and fail on errors
+    fn check(
+        &self,
+        run: impl Fn(&dyn ValidationPlugin) -> Vec<ValidationIssue>,
+        warnings: &mut Vec<BuildWarning>,
+    ) -> Result<(), BuildError> {
+        let mut errors = Vec::new();
+        for plugin in &self.plugins {
+            for issue in run(plugin.as_ref()) {
+                match issue.severity {
+                    IssueSeverity::Error => errors.push(format!(
+                        "{}: {} at {} ({})",
+                        issue.code,
+                        issue.message,
+                        issue.field,
+                        plugin.name()
+                    )),
+                    IssueSeverity::Warning => warnings.push(BuildWarning {
+                        code: issue.code,
+                        message: issue.message,
+                        location: Some(issue.field),
+                        suggestion: None,
+                    }),
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(BuildError::ValidationFailed { errors })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benchmark::sample_request;
+    use crate::builder::{BuildOptions, DDEXBuilder};
+
+    struct ReleaseIds;
+
+    impl ValidationPlugin for ReleaseIds {
+        fn name(&self) -> &str {
+            "release-ids"
+        }
+
+        fn validate(&self, request: &BuildRequest) -> Vec<ValidationIssue> {
+            request
+                .releases
+                .iter()
+                .enumerate()
+                .filter(|(_, release)| !release.release_id.starts_with("EML-"))
+                .map(|(i, _)| ValidationIssue::error("EML_CATALOG_NUMBER", format!("releases[{}].release_id", i), "Not a catalog number"))
+                .collect()
+        }
+
+        fn validate_ast(&self, ast: &AST) -> Vec<ValidationIssue> {
+            vec![ValidationIssue::warning("ROOT", "", ast.root.name.clone())]
+        }
+    }
+
+    #[test]
+    fn test_plugins_run_on_build() {
+        let mut options = BuildOptions::default();
+        options.validation_plugins.register(ReleaseIds);
+        assert_eq!(format!("{:?}", options.validation_plugins), r#"["release-ids"]"#);
+
+        let request = sample_request(2);
+        match DDEXBuilder::new().build(request.clone(), options.clone()) {
+            Err(BuildError::ValidationFailed { errors }) => assert_eq!(
+                errors,
+                [
+                    "EML_CATALOG_NUMBER: Not a catalog number at releases[0].release_id (release-ids)",
+                    "EML_CATALOG_NUMBER: Not a catalog number at releases[1].release_id (release-ids)",
+                ]
+            ),
+            other => panic!("expected plugin errors, got {:?}", other.map(|result| result.xml)),
+        }
+
+        let mut request = request;
+        for (i, release) in request.releases.iter_mut().enumerate() {
+            release.release_id = format!("EML-{:04}", i);
+        }
+        let result = DDEXBuilder::new().build(request, options).unwrap();
+        assert!(result.warnings.iter().any(|warning| warning.code == "ROOT"));
+    }
+}
@@ -0,0 +1,97 @@
+//! Validation plugins compiled to WebAssembly
+//!
+//! A plugin module exports its linear `memory` and two functions:
+//!
+//! - `alloc(len: i32) -> i32` returns the offset of `len` free bytes;
+//! - `validate(ptr: i32, len: i32) -> i64` reads the build request as JSON
+//!   from `ptr..ptr + len` and returns the offset of its answer in the high
+//!   32 bits and its length in the low 32 bits. The answer is a JSON array
+//!   of [`ValidationIssue`]s, e.g.
+//!   `[{"severity": "error", "code": "EML_CATALOG_NUMBER", "field": "releases[0].release_id", "message": "..."}]`.
+//!
+//! Modules import nothing, so a plugin cannot touch files or the network,
+//! and every call runs in a fresh instance with a fuel budget so a plugin
+//! stuck in a loop fails instead of hanging the build. A plugin that traps,
+//! runs out of fuel or answers something else than issues fails the build
+//! with a `PLUGIN_FAILED` error.
+
+use super::{ValidationIssue, ValidationPlugin};
+use crate::builder::BuildRequest;
+use crate::error::BuildError;
+use std::fmt;
+use std::path::Path;
+use wasmi::{Config, Engine, Linker, Module, Store};
+
+/// Instructions, roughly, a plugin may run per request
+const FUEL_PER_CALL: u64 = 1_000_000_000;
+
+/// A rule plugin loaded from a `.wasm` module
+pub struct WasmPlugin {
+    name: String,
+    engine: Engine,
+    module: Module,
+}
+
+impl fmt::Debug for WasmPlugin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WasmPlugin").field("name", &self.name).finish_non_exhaustive()
+    }
+}
+
+impl WasmPlugin {
+    /// Load a module, named after its file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, BuildError> {
+        let path = path.as_ref();
+        let wasm = std::fs::read(path).map_err(|e| BuildError::Io(format!("{}: {}", path.display(), e)))?;
+        let name = path.file_stem().map_or_else(|| path.display().to_string(), |stem| stem.to_string_lossy().into_owned());
+        Self::from_bytes(name, &wasm)
+    }
+
+    /// Compile a module held in memory
+    pub fn from_bytes(name: impl Into<String>, wasm: &[u8]) -> Result<Self, BuildError> {
+        let name = name.into();
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, wasm).map_err(|e| BuildError::InvalidFormat {
+            field: format!("plugin {}", name),
+            message: e.to_string(),
+        })?;
+        Ok(Self { name, engine, module })
+    }
+
+    /// Hand `input` to the module's `validate` and parse its answer
+    fn call(&self, input: &[u8]) -> Result<Vec<ValidationIssue>, String> {
+        let mut store = Store::new(&self.engine, ());
+        store.set_fuel(FUEL_PER_CALL).map_err(|e| e.to_string())?;
+        let instance = Linker::<()>::new(&self.engine)
+            .instantiate(&mut store, &self.module)
+            .and_then(|instance| instance.start(&mut store))
+            .map_err(|e| e.to_string())?;
+
+        let memory = instance.get_memory(&store, "memory").ok_or("no exported memory")?;
+        let alloc = instance.get_typed_func::<i32, i32>(&store, "alloc").map_err(|e| e.to_string())?;
+        let validate = instance.get_typed_func::<(i32, i32), i64>(&store, "validate").map_err(|e| e.to_string())?;
+
+        let len = i32::try_from(input.len()).map_err(|_| "request too large")?;
+        let ptr = alloc.call(&mut store, len).map_err(|e| e.to_string())?;
+        memory.write(&mut store, ptr as u32 as usize, input).map_err(|e| e.to_string())?;
+        let answer = validate.call(&mut store, (ptr, len)).map_err(|e| e.to_string())? as u64;
+
+        let start = (answer >> 32) as usize;
+        let end = start + (answer & 0xFFFF_FFFF) as usize;
+        let output = memory.data(&store).get(start..end).ok_or("answer outside memory")?;
+        serde_json::from_slice(output).map_err(|e| format!("invalid answer: {}", e))
+    }
+}
+
+impl ValidationPlugin for WasmPlugin {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn validate(&self, request: &BuildRequest) -> Vec<ValidationIssue> {
+        let result = serde_json::to_vec(request).map_err(|e| e.to_string()).and_then(|input| self.call(&input));
+        result.unwrap_or_else(|message| vec![ValidationIssue::error("PLUGIN_FAILED", "", message)])
+    }
+}
@@ -1,15 +1,26 @@
 // packages/ddex-builder/src/preflight.rs
 //! Comprehensive preflight validation for DDEX messages
 
+use crate::avs::{AllowedValueSet, AvsStatus};
+use crate::builder::{ContributorKind, ResourceKind};
+use crate::presets::{DdexVersion, PartnerPreset};
+use ddex_core::duration::DdexDuration;
+use ddex_core::models::common::validate_language_tag;
+use ddex_core::territory::{self, DealScope, TerritorySet};
+use chrono::{Datelike, NaiveDate};
+use indexmap::IndexMap;
 use regex::Regex;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 
 // Validation regex patterns
-static ISRC_PATTERN: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"^[A-Z]{2}[A-Z0-9]{3}\d{2}\d{5}$").unwrap()
+static DPID_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^PADPID[A-Z]\d{10}[0-9A-Z]$").unwrap()
 });
 
+/// ISRC prefixes assigned outside ISO 3166-1 (additional US ranges, IRA codes)
+const ISRC_SPECIAL_PREFIXES: &[&str] = &["QM", "QN", "QT", "QZ", "CP", "DG", "ZZ"];
+
 static UPC_PATTERN: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"^\d{12,14}$").unwrap()
 });
@@ -22,6 +33,22 @@ static ISNI_PATTERN: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"^\d{15}[\dX]$").unwrap()
 });
 
+static DATE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\d{4}-\d{2}-\d{2}$").unwrap()
+});
+
+static CURRENCY_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[A-Z]{3}$").unwrap()
+});
+
+static AMOUNT_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\d+(\.\d+)?$").unwrap()
+});
+
+/// How far a declared duration may be from that of the delivered file,
+/// allowing for durations rounded to whole seconds
+const FILE_DURATION_TOLERANCE: std::time::Duration = std::time::Duration::from_secs(1);
+
 /// Preflight validator for DDEX messages
 pub struct PreflightValidator {
     config: ValidationConfig,
@@ -48,8 +75,44 @@ pub struct ValidationConfig {
     /// Check references
     pub validate_references: bool,
     
+    /// Check controlled vocabulary values against the DDEX AVS
+    pub validate_avs: bool,
+    
     /// Profile-specific validation
     pub profile: Option<String>,
+    
+    /// Fields a partner requires, e.g. "PLine" (see [`PartnerPreset::required_fields`])
+    pub required_fields: Vec<String>,
+    
+    /// Check the deals' validity periods against each other: conflicting
+    /// overlaps, coverage gaps after a deal ends and deals already expired.
+    /// Turn off when a window or a lapse is intentional
+    pub check_deal_timeline: bool,
+    
+    /// Date deals are checked against for expiry and release dates against
+    /// for being in the future, today when unset
+    pub as_of: Option<NaiveDate>,
+    
+    /// Warn about implausible release and original release dates; `None`
+    /// turns the checks off
+    pub release_dates: Option<ReleaseDateRules>,
+    
+    /// How release and track parental warnings are checked against each other
+    pub parental_warnings: ParentalWarningCheck,
+}
+
+impl ValidationConfig {
+    /// Adopt the profile and required fields of a partner preset
+    pub fn with_preset(mut self, preset: &PartnerPreset) -> Self {
+        self.profile = Some(format!("{:?}", preset.config.profile));
+        self.required_fields = preset.required_fields.clone();
+        self.parental_warnings = ParentalWarningCheck::for_required_fields(&preset.required_fields);
+        self
+    }
+    
+    fn requires(&self, field: &str) -> bool {
+        self.required_fields.iter().any(|f| f == field)
+    }
 }
 
 impl Default for ValidationConfig {
@@ -61,7 +124,55 @@ impl Default for ValidationConfig {
             check_required_fields: true,
             validate_dates: true,
             validate_references: true,
+            validate_avs: true,
             profile: None,
+            required_fields: Vec::new(),
+            check_deal_timeline: true,
+            as_of: None,
+            release_dates: Some(ReleaseDateRules::default()),
+            parental_warnings: ParentalWarningCheck::Warn,
+        }
+    }
+}
+
+/// Bounds beyond which a release date is more likely a typo than a plan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReleaseDateRules {
+    /// How many days ahead a release may be scheduled
+    pub max_days_ahead: u32,
+    /// Earliest plausible year of a release or original release
+    pub earliest_year: i32,
+}
+
+impl Default for ReleaseDateRules {
+    fn default() -> Self {
+        Self {
+            max_days_ahead: 366,
+            earliest_year: 1880,
+        }
+    }
+}
+
+/// Consistency checking of explicit-content flags between a release and its
+/// tracks, a frequent cause of DSP rejections
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ParentalWarningCheck {
+    /// Don't compare release and track warnings
+    Off,
+    /// Warn about a release marked clean with an explicit track, or marked
+    /// explicit with only clean tracks
+    Warn,
+    /// Report those mismatches as errors, and require a warning on every track
+    Require,
+}
+
+impl ParentalWarningCheck {
+    /// `Require` for partners that require `ParentalWarningType`, `Warn` otherwise
+    pub fn for_required_fields(required_fields: &[String]) -> Self {
+        if required_fields.iter().any(|f| f == "ParentalWarningType") {
+            Self::Require
+        } else {
+            Self::Warn
         }
     }
 }
@@ -115,6 +226,7 @@ impl PreflightValidator {
     }
     
     /// Validate a build request
+    #[tracing::instrument(name = "ddex.preflight", skip_all, fields(level = ?self.config.level))]
     pub fn validate(
         &self,
         request: &super::builder::BuildRequest,
@@ -130,14 +242,85 @@ impl PreflightValidator {
             return Ok(result);
         }
         
+        // Every version-dependent rule below needs a version the builder knows
+        let Some(version) = request_version(&request.version) else {
+            result.errors.push(ValidationError {
+                code: "UNSUPPORTED_VERSION".to_string(),
+                field: "version".to_string(),
+                message: format!(
+                    "Unsupported ERN version '{}'; use 3.8.2, 4.1, 4.2, 4.3 or 4.4",
+                    request.version
+                ),
+                location: "/version".to_string(),
+            });
+            result.passed = false;
+            ddex_core::telemetry::record_validation_failures("preflight", 1);
+            return Ok(result);
+        };
+        
+        // Validate message parties
+        if self.config.validate_identifiers {
+            let parties = [
+                ("message_sender", &request.header.message_sender),
+                ("message_recipient", &request.header.message_recipient),
+            ];
+            for (field, party) in parties {
+                let Some(ref party_id) = party.party_id else { continue };
+                if party_id.starts_with("PADPID") {
+                    if let Some(problem) = self.dpid_problem(party_id) {
+                        let location = format!("/header/{}/party_id", field);
+                        self.report_identifier("INVALID_DPID", "party_id", "DPID", party_id, &problem, &location, &mut result);
+                    }
+                }
+            }
+        }
+        
+        // Validate audit trail
+        for (idx, event) in request.header.message_audit_trail.iter().enumerate() {
+            if self.config.validate_dates && chrono::DateTime::parse_from_rfc3339(&event.date_time).is_err() {
+                result.warnings.push(ValidationWarning {
+                    code: "INVALID_DATE_TIME".to_string(),
+                    field: "date_time".to_string(),
+                    message: format!("Invalid ISO 8601 date and time: {}", event.date_time),
+                    location: format!("/header/message_audit_trail[{}]/date_time", idx),
+                    suggestion: Some("Use a timestamp with offset, e.g. 2024-03-01T12:00:00Z".to_string()),
+                });
+            }
+            if event.messaging_party.party_id.is_none() && event.messaging_party.party_name.is_empty() {
+                result.errors.push(ValidationError {
+                    code: "MISSING_MESSAGING_PARTY".to_string(),
+                    field: "messaging_party".to_string(),
+                    message: "Audit trail events must identify the messaging party".to_string(),
+                    location: format!("/header/message_audit_trail[{}]/messaging_party", idx),
+                });
+            }
+        }
+        
+        // MessageControlType is written as given, so only the two schema values pass
+        if let Some(ref control_type) = request.header.message_control_type {
+            if !matches!(control_type.as_str(), "LiveMessage" | "TestMessage") {
+                result.errors.push(ValidationError {
+                    code: "INVALID_MESSAGE_CONTROL_TYPE".to_string(),
+                    field: "message_control_type".to_string(),
+                    message: format!("MessageControlType must be LiveMessage or TestMessage, not {}", control_type),
+                    location: "/header/message_control_type".to_string(),
+                });
+            }
+        }
+        
         // Validate releases
         for (idx, release) in request.releases.iter().enumerate() {
-            self.validate_release(release, idx, &mut result)?;
+            self.validate_release(release, idx, version, &mut result)?;
         }
         
         // Validate deals
         for (idx, deal) in request.deals.iter().enumerate() {
-            self.validate_deal(deal, idx, &mut result)?;
+            self.validate_deal(deal, idx, version, &mut result)?;
+        }
+        if self.config.check_deal_timeline {
+            self.validate_deal_conflicts(&request.deals, &mut result);
+            self.validate_deal_coverage(&request.deals, &mut result);
+            self.validate_expired_deals(&request.deals, &mut result);
         }
         
         // Check cross-references if enabled
@@ -153,6 +336,7 @@ impl PreflightValidator {
         // Determine if validation passed
         result.passed = result.errors.is_empty() && 
             (self.config.level != PreflightLevel::Strict || result.warnings.is_empty());
+        ddex_core::telemetry::record_validation_failures("preflight", result.errors.len() as u64);
         
         Ok(result)
     }
@@ -161,6 +345,7 @@ impl PreflightValidator {
         &self,
         release: &super::builder::ReleaseRequest,
         idx: usize,
+        version: DdexVersion,
         result: &mut ValidationResult,
     ) -> Result<(), super::error::BuildError> {
         let location = format!("/releases[{}]", idx);
@@ -186,17 +371,21 @@ impl PreflightValidator {
                 });
             }
         }
+        self.validate_languages(&release.title, &format!("{}/title", location), result);
         
-        // Validate UPC
+        // Validate UPC and GRid
         if self.config.validate_identifiers {
             if let Some(upc) = &release.upc {
-                if !self.validate_upc(upc) {
-                    result.errors.push(ValidationError {
-                        code: "INVALID_UPC".to_string(),
-                        field: "upc".to_string(),
-                        message: format!("Invalid UPC format: {}", upc),
-                        location: format!("{}/upc", location),
-                    });
+                if let Some(problem) = self.icpn_problem(upc) {
+                    self.report_identifier("INVALID_UPC", "upc", "ICPN", upc, &problem, &format!("{}/upc", location), result);
+                }
+            }
+            
+            // Proprietary release IDs are allowed; anything shaped like a GRid must be one
+            if is_grid_shaped(&release.release_id) {
+                if let Some(problem) = self.grid_problem(&release.release_id) {
+                    let id_location = format!("{}/release_id", location);
+                    self.report_identifier("INVALID_GRID", "release_id", "GRid", &release.release_id, &problem, &id_location, result);
                 }
             }
         }
@@ -206,9 +395,308 @@ impl PreflightValidator {
             self.validate_track(track, idx, track_idx, result)?;
         }
         
+        // Validate copyright lines
+        self.validate_copyright_lines(
+            release.p_line.as_ref(),
+            release.c_line.as_ref(),
+            &location,
+            true,
+            result,
+        );
+        for (track_idx, track) in release.tracks.iter().enumerate() {
+            self.validate_copyright_lines(
+                track.p_line.as_ref(),
+                track.c_line.as_ref(),
+                &format!("{}/tracks[{}]", location, track_idx),
+                false,
+                result,
+            );
+        }
+        
+        // Validate contributors
+        self.validate_contributors(&release.contributors, &location, true, version, result);
+        for (track_idx, track) in release.tracks.iter().enumerate() {
+            let track_location = format!("{}/tracks[{}]", location, track_idx);
+            self.validate_contributors(&track.contributors, &track_location, false, version, result);
+        }
+        
+        // Validate other resources
+        for (resource_idx, resource) in release.resources.iter().enumerate() {
+            self.validate_resource(resource, idx, resource_idx, version, result);
+        }
+        
+        self.validate_territory_details(release, &location, version, result);
+        self.validate_parental_warnings(release, &location, version, result);
+        if let Some(rules) = self.config.release_dates {
+            self.validate_release_dates(release, &location, rules, result);
+        }
+        
+        // Artwork deliveries must identify the cover image
+        let images: Vec<_> = release.resources.iter()
+            .filter(|r| r.kind == ResourceKind::Image)
+            .collect();
+        if self.config.check_required_fields
+            && !images.is_empty()
+            && !images.iter().any(|r| r.resource_type == "FrontCoverImage")
+        {
+            result.errors.push(ValidationError {
+                code: "MISSING_FRONT_COVER_IMAGE".to_string(),
+                field: "resources".to_string(),
+                message: "Releases delivering images must include a FrontCoverImage".to_string(),
+                location: format!("{}/resources", location),
+            });
+        }
+        
         Ok(())
     }
     
+    fn validate_territory_details(
+        &self,
+        release: &super::builder::ReleaseRequest,
+        location: &str,
+        version: DdexVersion,
+        result: &mut ValidationResult,
+    ) {
+        // Territory -> index of the first override block claiming it
+        let mut claimed: IndexMap<&str, usize> = IndexMap::new();
+        
+        for (d_idx, details) in release.territory_details.iter().enumerate() {
+            let details_location = format!("{}/territory_details[{}]", location, d_idx);
+            
+            if details.territory_codes.is_empty() {
+                result.errors.push(ValidationError {
+                    code: "MISSING_TERRITORY".to_string(),
+                    field: "territory_codes".to_string(),
+                    message: "Territory details must name at least one territory".to_string(),
+                    location: format!("{}/territory_codes", details_location),
+                });
+            }
+            
+            for (t_idx, territory) in details.territory_codes.iter().enumerate() {
+                if !self.validate_territory_code(territory, version) {
+                    result.warnings.push(ValidationWarning {
+                        code: "INVALID_TERRITORY".to_string(),
+                        field: "territory_codes".to_string(),
+                        message: format!("Invalid territory code: {}", territory),
+                        location: format!("{}/territory_codes[{}]", details_location, t_idx),
+                        suggestion: Some("Use ISO 3166-1 alpha-2 codes or 'Worldwide'".to_string()),
+                    });
+                }
+                
+                // Worldwide overlaps every other territory
+                let overlap = claimed.iter().find_map(|(&code, &other)| {
+                    let overlaps = code == territory || code == "Worldwide" || territory == "Worldwide";
+                    (overlaps && other != d_idx).then_some(other)
+                });
+                if let Some(other) = overlap {
+                    result.errors.push(ValidationError {
+                        code: "OVERLAPPING_TERRITORY_DETAILS".to_string(),
+                        field: "territory_codes".to_string(),
+                        message: format!(
+                            "Territory {} is already covered by territory_details[{}]",
+                            territory, other
+                        ),
+                        location: format!("{}/territory_codes[{}]", details_location, t_idx),
+                    });
+                } else {
+                    claimed.entry(territory.as_str()).or_insert(d_idx);
+                }
+            }
+            
+            if let Some(ref date) = details.release_date {
+                if !DATE_PATTERN.is_match(date) {
+                    result.errors.push(ValidationError {
+                        code: "INVALID_DATE".to_string(),
+                        field: "release_date".to_string(),
+                        message: format!("Invalid ISO 8601 date: {}", date),
+                        location: format!("{}/release_date", details_location),
+                    });
+                }
+            }
+            
+            if self.config.validate_avs {
+                if let Some(ref warning) = details.parental_warning {
+                    self.validate_avs_value(
+                        AllowedValueSet::ParentalWarningType,
+                        warning,
+                        version,
+                        "parental_warning",
+                        &format!("{}/parental_warning", details_location),
+                        result,
+                    );
+                }
+            }
+        }
+    }
+    
+    /// Check release dates for typos: far in the future, before recorded
+    /// music, or an original release after the release itself
+    fn validate_release_dates(
+        &self,
+        release: &super::builder::ReleaseRequest,
+        location: &str,
+        rules: ReleaseDateRules,
+        result: &mut ValidationResult,
+    ) {
+        let today = self.config.as_of.unwrap_or_else(|| chrono::Utc::now().date_naive());
+        let latest = today + chrono::Days::new(u64::from(rules.max_days_ahead));
+        let original = release.original_release_date.as_ref().and_then(|date| {
+            let parsed = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok();
+            if parsed.is_none() && self.config.validate_dates {
+                result.errors.push(ValidationError {
+                    code: "INVALID_DATE".to_string(),
+                    field: "original_release_date".to_string(),
+                    message: format!("Invalid ISO 8601 date: {}", date),
+                    location: format!("{}/original_release_date", location),
+                });
+            }
+            parsed
+        });
+        let mut warn = |code: &str, field: &str, message: String, location: String, suggestion: &str| {
+            result.warnings.push(ValidationWarning {
+                code: code.to_string(),
+                field: field.to_string(),
+                message,
+                location,
+                suggestion: Some(suggestion.to_string()),
+            });
+        };
+        let territory_dates = release.territory_details.iter().enumerate().filter_map(|(idx, details)| {
+            Some((deal_date(&details.release_date)?, format!("{}/territory_details[{}]/release_date", location, idx)))
+        });
+        let release_dates: Vec<_> = deal_date(&release.release_date)
+            .map(|date| (date, format!("{}/release_date", location)))
+            .into_iter()
+            .chain(territory_dates)
+            .collect();
+        
+        for (date, date_location) in &release_dates {
+            if *date > latest {
+                warn(
+                    "RELEASE_DATE_FAR_AHEAD",
+                    "release_date",
+                    format!("Release {} is dated {}, more than {} days after {}", release.release_id, date, rules.max_days_ahead, today),
+                    date_location.clone(),
+                    "Check the year of the release date",
+                );
+            }
+            if let Some(original) = original.filter(|original| original > date) {
+                warn(
+                    "ORIGINAL_AFTER_RELEASE_DATE",
+                    "original_release_date",
+                    format!("Release {} first came out on {}, after its release date {}", release.release_id, original, date),
+                    format!("{}/original_release_date", location),
+                    "The original release date is the earliest release in any format; swap the dates if they are reversed",
+                );
+            }
+        }
+        if let Some(original) = original.filter(|original| *original > today) {
+            warn(
+                "ORIGINAL_RELEASE_DATE_IN_FUTURE",
+                "original_release_date",
+                format!("Release {} has an original release date {} in the future", release.release_id, original),
+                format!("{}/original_release_date", location),
+                "Leave the original release date unset for first releases",
+            );
+        }
+        let dated = release_dates.iter().map(|(date, location)| (*date, location.clone(), "release_date"))
+            .chain(original.map(|date| (date, format!("{}/original_release_date", location), "original_release_date")));
+        for (date, date_location, field) in dated {
+            if date.year() < rules.earliest_year {
+                warn(
+                    "IMPLAUSIBLE_RELEASE_DATE",
+                    field,
+                    format!("{} is before {}", date, rules.earliest_year),
+                    date_location,
+                    "Check the year for a typo",
+                );
+            }
+        }
+    }
+    
+    /// Compare each territory's release parental warning with those of the
+    /// tracks: a release shown as clean must not hold an explicit track, and
+    /// one shown as explicit should hold at least one
+    fn validate_parental_warnings(
+        &self,
+        release: &super::builder::ReleaseRequest,
+        location: &str,
+        version: DdexVersion,
+        result: &mut ValidationResult,
+    ) {
+        for (track_idx, track) in release.tracks.iter().enumerate() {
+            let track_location = format!("{}/tracks[{}]/parental_warning", location, track_idx);
+            match track.parental_warning {
+                Some(ref warning) if self.config.validate_avs => {
+                    self.validate_avs_value(AllowedValueSet::ParentalWarningType, warning, version, "parental_warning", &track_location, result);
+                }
+                Some(_) => {}
+                None if self.config.parental_warnings == ParentalWarningCheck::Require => {
+                    result.errors.push(ValidationError {
+                        code: "MISSING_PARENTAL_WARNING".to_string(),
+                        field: "parental_warning".to_string(),
+                        message: format!("Track {} has no parental warning type", track.track_id),
+                        location: track_location,
+                    });
+                }
+                None => {}
+            }
+        }
+        if self.config.parental_warnings == ParentalWarningCheck::Off {
+            return;
+        }
+        
+        let mut mismatches = Vec::new();
+        let explicit_tracks: Vec<_> = release.tracks.iter()
+            .filter(|t| t.parental_warning.as_deref() == Some("Explicit"))
+            .collect();
+        for (details_idx, details) in release.territory_details.iter().enumerate() {
+            let Some(ref warning) = details.parental_warning else { continue };
+            let details_location = format!("{}/territory_details[{}]/parental_warning", location, details_idx);
+            match warning.as_str() {
+                "NotExplicit" | "ExplicitContentEdited" | "NoAdviceAvailable" => {
+                    for track in &explicit_tracks {
+                        mismatches.push((
+                            format!("Release {} is marked {} but track {} is Explicit", release.release_id, warning, track.track_id),
+                            details_location.clone(),
+                            "Mark the release Explicit, or deliver the edited version of the track",
+                        ));
+                    }
+                }
+                "Explicit" if explicit_tracks.is_empty()
+                    && !release.tracks.is_empty()
+                    && release.tracks.iter().all(|t| t.parental_warning.is_some()) =>
+                {
+                    mismatches.push((
+                        format!("Release {} is marked Explicit but none of its tracks is", release.release_id),
+                        details_location,
+                        "Mark the explicit tracks, or the release NotExplicit",
+                    ));
+                }
+                _ => {}
+            }
+        }
+        
+        for (message, location, suggestion) in mismatches {
+            if self.config.parental_warnings == ParentalWarningCheck::Require {
+                result.errors.push(ValidationError {
+                    code: "PARENTAL_WARNING_MISMATCH".to_string(),
+                    field: "parental_warning".to_string(),
+                    message,
+                    location,
+                });
+            } else {
+                result.warnings.push(ValidationWarning {
+                    code: "PARENTAL_WARNING_MISMATCH".to_string(),
+                    field: "parental_warning".to_string(),
+                    message,
+                    location,
+                    suggestion: Some(suggestion.to_string()),
+                });
+            }
+        }
+    }
+    
     fn validate_track(
         &self,
         track: &super::builder::TrackRequest,
@@ -220,13 +708,8 @@ impl PreflightValidator {
         
         // Validate ISRC
         if self.config.validate_identifiers {
-            if !self.validate_isrc(&track.isrc) {
-                result.errors.push(ValidationError {
-                    code: "INVALID_ISRC".to_string(),
-                    field: "isrc".to_string(),
-                    message: format!("Invalid ISRC format: {}", track.isrc),
-                    location: format!("{}/isrc", location),
-                });
+            if let Some(problem) = self.isrc_problem(&track.isrc) {
+                self.report_identifier("INVALID_ISRC", "isrc", "ISRC", &track.isrc, &problem, &format!("{}/isrc", location), result);
             }
         }
         
@@ -240,27 +723,380 @@ impl PreflightValidator {
                 suggestion: Some("Use format PT3M45S for 3:45".to_string()),
             });
         }
+        self.validate_file_duration(Some(&track.duration), track.technical_details.as_ref(), &location, result);
         
         Ok(())
     }
     
+    fn validate_copyright_lines(
+        &self,
+        p_line: Option<&super::builder::PLineRequest>,
+        c_line: Option<&super::builder::CLineRequest>,
+        parent_location: &str,
+        release_level: bool,
+        result: &mut ValidationResult,
+    ) {
+        let lines = [
+            ("PLine", "p_line", p_line.map(|l| (l.year, l.text.as_str()))),
+            ("CLine", "c_line", c_line.map(|l| (l.year, l.text.as_str()))),
+        ];
+        
+        for (element, field, line) in lines {
+            let location = format!("{}/{}", parent_location, field);
+            match line {
+                Some((year, text)) => {
+                    if text.trim().is_empty() {
+                        result.errors.push(ValidationError {
+                            code: "EMPTY_COPYRIGHT_LINE".to_string(),
+                            field: field.to_string(),
+                            message: format!("{} text is empty", element),
+                            location: format!("{}/text", location),
+                        });
+                    }
+                    if self.config.validate_dates {
+                        if let Some(year) = year.filter(|y| *y < 1000) {
+                            result.errors.push(ValidationError {
+                                code: "INVALID_YEAR".to_string(),
+                                field: field.to_string(),
+                                message: format!("{} year must have four digits: {}", element, year),
+                                location: format!("{}/year", location),
+                            });
+                        }
+                    }
+                }
+                None if self.config.requires(element) => {
+                    result.errors.push(ValidationError {
+                        code: format!("MISSING_{}", if element == "PLine" { "P_LINE" } else { "C_LINE" }),
+                        field: field.to_string(),
+                        message: format!("{} is required by the target partner", element),
+                        location,
+                    });
+                }
+                // Most DSPs reject releases without a P-line even when unstated
+                None if release_level && element == "PLine" => {
+                    result.info.push(ValidationInfo {
+                        code: "P_LINE_RECOMMENDED".to_string(),
+                        message: format!("{} has no P-line; most DSPs require one", parent_location),
+                    });
+                }
+                None => {}
+            }
+        }
+    }
+    
+    fn validate_contributors(
+        &self,
+        contributors: &[super::builder::ContributorRequest],
+        parent_location: &str,
+        release_level: bool,
+        version: DdexVersion,
+        result: &mut ValidationResult,
+    ) {
+        let mut sequences: IndexMap<u32, usize> = IndexMap::new();
+        
+        for (c_idx, contributor) in contributors.iter().enumerate() {
+            let location = format!("{}/contributors[{}]", parent_location, c_idx);
+            
+            if self.config.check_required_fields {
+                if contributor.name.trim().is_empty() {
+                    result.errors.push(ValidationError {
+                        code: "MISSING_CONTRIBUTOR_NAME".to_string(),
+                        field: "name".to_string(),
+                        message: "Contributor name is required".to_string(),
+                        location: format!("{}/name", location),
+                    });
+                }
+                if contributor.role.trim().is_empty() {
+                    result.errors.push(ValidationError {
+                        code: "MISSING_CONTRIBUTOR_ROLE".to_string(),
+                        field: "role".to_string(),
+                        message: "Contributor role is required".to_string(),
+                        location: format!("{}/role", location),
+                    });
+                }
+            }
+            
+            match contributor.kind {
+                ContributorKind::DisplayArtist if self.config.validate_avs && !contributor.role.is_empty() => {
+                    self.validate_avs_value(
+                        AllowedValueSet::ArtistRole,
+                        &contributor.role,
+                        version,
+                        "role",
+                        &format!("{}/role", location),
+                        result,
+                    );
+                }
+                ContributorKind::DisplayArtist => {}
+                _ if release_level => {
+                    result.warnings.push(ValidationWarning {
+                        code: "CONTRIBUTOR_NOT_GENERATED".to_string(),
+                        field: "kind".to_string(),
+                        message: format!(
+                            "Releases only credit display artists; {} will be omitted",
+                            contributor.name
+                        ),
+                        location: format!("{}/kind", location),
+                        suggestion: Some("Move the contributor to the tracks it worked on".to_string()),
+                    });
+                }
+                ContributorKind::IndirectResourceContributor if version != DdexVersion::Ern382 => {
+                    result.warnings.push(ValidationWarning {
+                        code: "CONTRIBUTOR_NOT_GENERATED".to_string(),
+                        field: "kind".to_string(),
+                        message: format!(
+                            "ERN 4 has no IndirectResourceContributor; {} will be omitted",
+                            contributor.name
+                        ),
+                        location: format!("{}/kind", location),
+                        suggestion: Some("Credit work contributors on the musical work".to_string()),
+                    });
+                }
+                _ => {}
+            }
+            
+            if let Some(sequence) = contributor.sequence {
+                if let Some(first) = sequences.insert(sequence, c_idx) {
+                    result.warnings.push(ValidationWarning {
+                        code: "DUPLICATE_SEQUENCE_NUMBER".to_string(),
+                        field: "sequence".to_string(),
+                        message: format!(
+                            "Sequence number {} is also used by contributors[{}]",
+                            sequence, first
+                        ),
+                        location: format!("{}/sequence", location),
+                        suggestion: None,
+                    });
+                }
+            }
+            
+            if self.config.validate_identifiers {
+                for (id_idx, party_id) in contributor.party_ids.iter().enumerate() {
+                    let id_location = format!("{}/party_ids[{}]", location, id_idx);
+                    let valid = match party_id.namespace.to_ascii_uppercase().as_str() {
+                        "ISNI" => ISNI_PATTERN.is_match(&party_id.value.replace(' ', "")),
+                        "IPN" | "IPI" => party_id.value.chars().all(|c| c.is_ascii_digit()),
+                        "DPID" => {
+                            if let Some(problem) = self.dpid_problem(&party_id.value) {
+                                self.report_identifier("INVALID_DPID", "party_ids", "DPID", &party_id.value, &problem, &id_location, result);
+                            }
+                            true
+                        }
+                        _ => !party_id.value.is_empty(),
+                    };
+                    if !valid {
+                        result.errors.push(ValidationError {
+                            code: "INVALID_PARTY_ID".to_string(),
+                            field: "party_ids".to_string(),
+                            message: format!("Invalid {} identifier: {}", party_id.namespace, party_id.value),
+                            location: format!("{}/party_ids[{}]", location, id_idx),
+                        });
+                    }
+                }
+            }
+        }
+    }
+    
+    fn validate_resource(
+        &self,
+        resource: &super::builder::ResourceRequest,
+        release_idx: usize,
+        resource_idx: usize,
+        version: DdexVersion,
+        result: &mut ValidationResult,
+    ) {
+        let location = format!("/releases[{}]/resources[{}]", release_idx, resource_idx);
+        
+        if self.config.validate_avs {
+            let avs = match resource.kind {
+                ResourceKind::Video => AllowedValueSet::VideoType,
+                ResourceKind::Image => AllowedValueSet::ImageType,
+                ResourceKind::Text => AllowedValueSet::TextType,
+                ResourceKind::SheetMusic => AllowedValueSet::SheetMusicType,
+            };
+            self.validate_avs_value(
+                avs,
+                &resource.resource_type,
+                version,
+                "resource_type",
+                &format!("{}/resource_type", location),
+                result,
+            );
+        }
+        
+        if self.config.validate_identifiers {
+            if let Some(ref isrc) = resource.isrc {
+                if let Some(problem) = self.isrc_problem(isrc) {
+                    self.report_identifier("INVALID_ISRC", "isrc", "ISRC", isrc, &problem, &format!("{}/isrc", location), result);
+                }
+            }
+        }
+        
+        if let Some(ref duration) = resource.duration {
+            if !self.validate_duration(duration) {
+                result.warnings.push(ValidationWarning {
+                    code: "INVALID_DURATION".to_string(),
+                    field: "duration".to_string(),
+                    message: format!("Invalid ISO 8601 duration: {}", duration),
+                    location: format!("{}/duration", location),
+                    suggestion: Some("Use format PT3M45S for 3:45".to_string()),
+                });
+            }
+        }
+        self.validate_file_duration(resource.duration.as_deref(), resource.technical_details.as_ref(), &location, result);
+    }
+    
+    /// Warn about language codes that are not BCP 47 tags and about a text
+    /// given twice in the same language, where only one would be shown
+    fn validate_languages(
+        &self,
+        strings: &[super::builder::LocalizedStringRequest],
+        location: &str,
+        result: &mut ValidationResult,
+    ) {
+        let mut seen = Vec::new();
+        for (idx, string) in strings.iter().enumerate() {
+            if let Some(Err(e)) = string.language_code.as_deref().map(validate_language_tag) {
+                result.warnings.push(ValidationWarning {
+                    code: "INVALID_LANGUAGE_CODE".to_string(),
+                    field: "language_code".to_string(),
+                    message: e.to_string(),
+                    location: format!("{}[{}]/language_code", location, idx),
+                    suggestion: Some("Use a language tag such as en, en-US or zh-Hant".to_string()),
+                });
+            }
+            let language = string.language_code.as_deref().map(str::to_ascii_lowercase);
+            if seen.contains(&language) {
+                result.warnings.push(ValidationWarning {
+                    code: "DUPLICATE_LANGUAGE".to_string(),
+                    field: "language_code".to_string(),
+                    message: match &string.language_code {
+                        Some(code) => format!("'{}' is given twice in {}", string.text, code),
+                        None => format!("'{}' is a second text without a language", string.text),
+                    },
+                    location: format!("{}[{}]", location, idx),
+                    suggestion: Some("Give each language once, tagging translations with their language".to_string()),
+                });
+            }
+            seen.push(language);
+        }
+    }
+    
+    /// Warn when the declared duration and that of the delivered file
+    /// differ by more than [`FILE_DURATION_TOLERANCE`]
+    fn validate_file_duration(
+        &self,
+        declared: Option<&str>,
+        details: Option<&super::builder::TechnicalDetailsRequest>,
+        location: &str,
+        result: &mut ValidationResult,
+    ) {
+        let Some(file) = details.and_then(|details| details.duration.as_deref()) else { return };
+        let Ok(file) = file.parse::<DdexDuration>() else {
+            result.warnings.push(ValidationWarning {
+                code: "INVALID_DURATION".to_string(),
+                field: "duration".to_string(),
+                message: format!("Invalid ISO 8601 duration: {}", file),
+                location: format!("{}/technical_details/duration", location),
+                suggestion: Some("Use format PT3M45S for 3:45".to_string()),
+            });
+            return;
+        };
+        let Some(Ok(declared)) = declared.map(str::parse::<DdexDuration>) else { return };
+        if !declared.is_within(&file, FILE_DURATION_TOLERANCE) {
+            result.warnings.push(ValidationWarning {
+                code: "DURATION_MISMATCH".to_string(),
+                field: "duration".to_string(),
+                message: format!("Duration {} differs from the {} of the delivered file", declared, file),
+                location: format!("{}/duration", location),
+                suggestion: Some(format!("Use the file's duration, {}", file.round_to_secs())),
+            });
+        }
+    }
+    
     fn validate_deal(
         &self,
         deal: &super::builder::DealRequest,
         idx: usize,
+        version: DdexVersion,
         result: &mut ValidationResult,
     ) -> Result<(), super::error::BuildError> {
         let location = format!("/deals[{}]", idx);
         
+        if self.config.validate_avs {
+            self.validate_avs_value(
+                AllowedValueSet::CommercialModelType,
+                &deal.deal_terms.commercial_model_type,
+                version,
+                "commercial_model_type",
+                &format!("{}/commercial_model_type", location),
+                result,
+            );
+            
+            for (u_idx, use_type) in deal.deal_terms.use_types.iter().enumerate() {
+                self.validate_avs_value(
+                    AllowedValueSet::UseType,
+                    use_type,
+                    version,
+                    "use_types",
+                    &format!("{}/use_types[{}]", location, u_idx),
+                    result,
+                );
+            }
+        }
+        
+        if self.config.check_required_fields
+            && version != DdexVersion::Ern382
+            && deal.deal_terms.use_types.is_empty()
+        {
+            result.warnings.push(ValidationWarning {
+                code: "MISSING_USE_TYPE".to_string(),
+                field: "use_types".to_string(),
+                message: format!("{} deals require at least one UseType", version),
+                location: format!("{}/use_types", location),
+                suggestion: Some("Add the permitted uses, e.g. OnDemandStream".to_string()),
+            });
+        }
+        
+        if self.config.validate_dates {
+            self.validate_deal_dates(&deal.deal_terms, &location, result);
+        }
+        
+        for (p_idx, price) in deal.deal_terms.price_information.iter().enumerate() {
+            let price_location = format!("{}/price_information[{}]", location, p_idx);
+            for (name, value) in [
+                ("wholesale_price", &price.wholesale_price),
+                ("suggested_retail_price", &price.suggested_retail_price),
+            ] {
+                let Some(value) = value else { continue };
+                if !AMOUNT_PATTERN.is_match(&value.amount) {
+                    result.errors.push(ValidationError {
+                        code: "INVALID_PRICE".to_string(),
+                        field: name.to_string(),
+                        message: format!("Invalid price amount: {}", value.amount),
+                        location: format!("{}/{}/amount", price_location, name),
+                    });
+                }
+                if !CURRENCY_PATTERN.is_match(&value.currency_code) {
+                    result.errors.push(ValidationError {
+                        code: "INVALID_CURRENCY".to_string(),
+                        field: name.to_string(),
+                        message: format!("Invalid ISO 4217 currency code: {}", value.currency_code),
+                        location: format!("{}/{}/currency_code", price_location, name),
+                    });
+                }
+            }
+        }
+        
         // Validate territory codes
         for (t_idx, territory) in deal.deal_terms.territory_code.iter().enumerate() {
-            if !self.validate_territory_code(territory) {
+            if !self.validate_territory_code(territory, version) {
                 result.warnings.push(ValidationWarning {
                     code: "INVALID_TERRITORY".to_string(),
                     field: "territory_code".to_string(),
                     message: format!("Invalid territory code: {}", territory),
                     location: format!("{}/territory_code[{}]", location, t_idx),
-                    suggestion: Some("Use ISO 3166-1 alpha-2 codes".to_string()),
+                    suggestion: Some("Use ISO 3166-1 alpha-2 codes or 'Worldwide'".to_string()),
                 });
             }
         }
@@ -268,6 +1104,223 @@ impl PreflightValidator {
         Ok(())
     }
     
+    fn validate_deal_dates(
+        &self,
+        terms: &super::builder::DealTerms,
+        location: &str,
+        result: &mut ValidationResult,
+    ) {
+        let dates = [
+            ("start_date", &terms.start_date),
+            ("end_date", &terms.end_date),
+            ("takedown_date", &terms.takedown_date),
+            ("pre_order_release_date", &terms.pre_order_release_date),
+            ("pre_order_preview_date", &terms.pre_order_preview_date),
+        ];
+        
+        for (field, date) in dates {
+            if let Some(date) = date {
+                if !DATE_PATTERN.is_match(date) {
+                    result.errors.push(ValidationError {
+                        code: "INVALID_DATE".to_string(),
+                        field: field.to_string(),
+                        message: format!("Invalid ISO 8601 date: {}", date),
+                        location: format!("{}/{}", location, field),
+                    });
+                }
+            }
+        }
+        
+        // ISO dates order lexically, so string comparison is sufficient
+        let Some(start) = &terms.start_date else { return };
+        for (field, date) in [("end_date", &terms.end_date), ("takedown_date", &terms.takedown_date)] {
+            if let Some(date) = date {
+                if date < start {
+                    result.errors.push(ValidationError {
+                        code: "DEAL_ENDS_BEFORE_START".to_string(),
+                        field: field.to_string(),
+                        message: format!("{} {} is before the deal start date {}", field, date, start),
+                        location: format!("{}/{}", location, field),
+                    });
+                }
+            }
+        }
+        for (field, date) in [
+            ("pre_order_release_date", &terms.pre_order_release_date),
+            ("pre_order_preview_date", &terms.pre_order_preview_date),
+        ] {
+            if let Some(date) = date {
+                if date > start {
+                    result.warnings.push(ValidationWarning {
+                        code: "PRE_ORDER_AFTER_START".to_string(),
+                        field: field.to_string(),
+                        message: format!("{} {} is after the deal start date {}", field, date, start),
+                        location: format!("{}/{}", location, field),
+                        suggestion: Some("Pre-order dates should precede the release start date".to_string()),
+                    });
+                }
+            }
+        }
+    }
+    
+    /// Check a controlled vocabulary value, rejecting values outside the set
+    /// and warning about values that belong to a different ERN version
+    pub(crate) fn validate_avs_value(
+        &self,
+        avs: AllowedValueSet,
+        value: &str,
+        version: DdexVersion,
+        field: &str,
+        location: &str,
+        result: &mut ValidationResult,
+    ) {
+        match avs.check(value, version) {
+            AvsStatus::Allowed => {}
+            AvsStatus::NotInVersion => result.warnings.push(ValidationWarning {
+                code: "AVS_VALUE_NOT_IN_VERSION".to_string(),
+                field: field.to_string(),
+                message: format!("{} '{}' is not defined for {}", avs, value, version),
+                location: location.to_string(),
+                suggestion: Some(format!("Use a {} value defined for {}", avs, version)),
+            }),
+            AvsStatus::Unknown { suggestion } => result.errors.push(ValidationError {
+                code: "INVALID_AVS_VALUE".to_string(),
+                field: field.to_string(),
+                message: match suggestion {
+                    Some(s) => format!("'{}' is not an allowed {} value; did you mean '{}'?", value, avs, s),
+                    None => format!("'{}' is not an allowed {} value", value, avs),
+                },
+                location: location.to_string(),
+            }),
+        }
+    }
+    
+    /// Warn about deals granting a territory that an earlier deal already
+    /// grants for the same release, use and period
+    fn validate_deal_conflicts(&self, deals: &[super::builder::DealRequest], result: &mut ValidationResult) {
+        // Invalid territory codes are reported per deal and grant nothing here
+        let scopes: Vec<DealScope> = deals
+            .iter()
+            .map(|deal| DealScope {
+                territories: TerritorySet::from_codes(&deal.deal_terms.territory_code).unwrap_or_default(),
+                commercial_models: vec![deal.deal_terms.commercial_model_type.clone()],
+                use_types: deal.deal_terms.use_types.clone(),
+                start: deal_date(&deal.deal_terms.start_date),
+                end: deal_end(&deal.deal_terms).map(|(end, _)| end),
+            })
+            .collect();
+        // A deal naming no release covers them all
+        let shares_release = |a: &super::builder::DealRequest, b: &super::builder::DealRequest| {
+            let releases = |deal: &super::builder::DealRequest| deal.release_references.len() + deal.release_ids.len();
+            releases(a) == 0
+                || releases(b) == 0
+                || a.release_references.iter().any(|r| b.release_references.contains(r))
+                || a.release_ids.iter().any(|id| b.release_ids.contains(id))
+        };
+
+        for conflict in territory::find_conflicts(&scopes) {
+            if !shares_release(&deals[conflict.first], &deals[conflict.second]) {
+                continue;
+            }
+            result.warnings.push(ValidationWarning {
+                code: "CONFLICTING_DEALS".to_string(),
+                field: "territory_code".to_string(),
+                message: format!(
+                    "Deal {} grants {} for the same release, use and period as deal {}",
+                    conflict.second, conflict.territories, conflict.first
+                ),
+                location: format!("/deals[{}]/deal_terms/territory_code", conflict.second),
+                suggestion: Some("Grant each territory once per use and period, or end the earlier deal first".to_string()),
+            });
+        }
+    }
+    
+    /// Warn when a release loses a territory between one deal ending, or
+    /// being taken down, and the next one starting
+    fn validate_deal_coverage(&self, deals: &[super::builder::DealRequest], result: &mut ValidationResult) {
+        let mut releases: indexmap::IndexSet<&str> = indexmap::IndexSet::new();
+        for deal in deals {
+            releases.extend(deal.release_references.iter().chain(&deal.release_ids).map(String::as_str));
+        }
+        // Without named releases every deal covers the single release
+        let releases: Vec<Option<&str>> = if releases.is_empty() {
+            vec![None]
+        } else {
+            releases.into_iter().map(Some).collect()
+        };
+
+        // (release, first and last uncovered day, deal ending, deal resuming)
+        let mut gaps: IndexMap<(Option<&str>, NaiveDate, NaiveDate, usize, usize), TerritorySet> = IndexMap::new();
+        for release in releases {
+            let covering: Vec<(usize, TerritorySet, Option<NaiveDate>, Option<NaiveDate>)> = deals
+                .iter()
+                .enumerate()
+                .filter(|(_, deal)| {
+                    let mut named = deal.release_references.iter().chain(&deal.release_ids);
+                    named.next().is_none_or(|first| release.is_some_and(|release| first == release || named.any(|r| r == release)))
+                })
+                .map(|(idx, deal)| {
+                    let territories = TerritorySet::from_codes(&deal.deal_terms.territory_code).unwrap_or_default();
+                    (idx, territories, deal_date(&deal.deal_terms.start_date), deal_end(&deal.deal_terms).map(|(end, _)| end))
+                })
+                .collect();
+            let granted = covering.iter().fold(TerritorySet::empty(), |all, (_, t, _, _)| all.union(t));
+
+            for code in granted.codes() {
+                let mut spans: Vec<_> = covering.iter().filter(|(_, t, _, _)| t.contains(code)).collect();
+                spans.sort_by_key(|(idx, _, start, _)| (*start, *idx));
+                let (mut last, _, _, mut until) = *spans[0];
+                for &&(idx, _, start, end) in &spans[1..] {
+                    // An open-ended deal covers everything after it
+                    let Some(covered) = until else { break };
+                    if let Some(start) = start {
+                        if let (Some(from), Some(to)) = (covered.succ_opt(), start.pred_opt()) {
+                            if from <= to {
+                                let gap = gaps.entry((release, from, to, last, idx)).or_default();
+                                let _ = gap.insert(code);
+                            }
+                        }
+                    }
+                    if !end.is_some_and(|end| end <= covered) {
+                        until = end;
+                        last = idx;
+                    }
+                }
+            }
+        }
+
+        for ((release, from, to, ended, resumed), territories) in gaps {
+            let release = release.map_or_else(|| "The release".to_string(), |r| format!("Release {}", r));
+            result.warnings.push(ValidationWarning {
+                code: "DEAL_COVERAGE_GAP".to_string(),
+                field: "start_date".to_string(),
+                message: format!(
+                    "{} has no deal in {} from {} to {}, between deal {} ending and deal {} starting",
+                    release, territories, from, to, ended, resumed
+                ),
+                location: format!("/deals[{}]/deal_terms/start_date", resumed),
+                suggestion: Some("Start the next deal the day after the previous one ends, or turn off check_deal_timeline if the lapse is intended".to_string()),
+            });
+        }
+    }
+    
+    /// Warn about deals whose validity period ended before the build date
+    fn validate_expired_deals(&self, deals: &[super::builder::DealRequest], result: &mut ValidationResult) {
+        let today = self.config.as_of.unwrap_or_else(|| chrono::Utc::now().date_naive());
+        for (idx, deal) in deals.iter().enumerate() {
+            let Some((end, field)) = deal_end(&deal.deal_terms) else { continue };
+            if end < today {
+                result.warnings.push(ValidationWarning {
+                    code: "EXPIRED_DEAL".to_string(),
+                    field: field.to_string(),
+                    message: format!("Deal {} ended on {}, before the build date {}", idx, end, today),
+                    location: format!("/deals[{}]/deal_terms/{}", idx, field),
+                    suggestion: Some("Drop the expired deal, or turn off check_deal_timeline to send it anyway".to_string()),
+                });
+            }
+        }
+    }
+    
     fn validate_references(
         &self,
         request: &super::builder::BuildRequest,
@@ -277,6 +1330,8 @@ impl PreflightValidator {
         let mut release_refs = indexmap::IndexSet::new();
         let mut resource_refs = indexmap::IndexSet::new();
         
+        let mut isrcs = indexmap::IndexSet::new();
+        
         for release in &request.releases {
             if let Some(ref_val) = &release.release_reference {
                 release_refs.insert(ref_val.clone());
@@ -286,11 +1341,51 @@ impl PreflightValidator {
                 if let Some(ref_val) = &track.resource_reference {
                     resource_refs.insert(ref_val.clone());
                 }
+                isrcs.insert(track.isrc.as_str());
+            }
+            
+            for resource in &release.resources {
+                if let Some(isrc) = &resource.isrc {
+                    isrcs.insert(isrc.as_str());
+                }
+            }
+            
+            for track_release in &release.track_releases {
+                if let Some(ref_val) = &track_release.release_reference {
+                    release_refs.insert(ref_val.clone());
+                }
+            }
+        }
+        
+        // Track releases must offer a track present in the message
+        for (idx, release) in request.releases.iter().enumerate() {
+            for (t_idx, track_release) in release.track_releases.iter().enumerate() {
+                if !isrcs.contains(track_release.isrc.as_str()) {
+                    result.errors.push(ValidationError {
+                        code: "UNKNOWN_TRACK_RELEASE_ISRC".to_string(),
+                        field: "isrc".to_string(),
+                        message: format!("Track release ISRC {} matches no track in the message", track_release.isrc),
+                        location: format!("/releases[{}]/track_releases[{}]", idx, t_idx),
+                    });
+                }
             }
         }
         
         // Check deal references
         for (idx, deal) in request.deals.iter().enumerate() {
+            // With several releases a deal must say which ones it covers
+            if request.releases.len() > 1 && deal.release_references.is_empty() && deal.release_ids.is_empty() {
+                result.errors.push(ValidationError {
+                    code: "AMBIGUOUS_DEAL_SCOPE".to_string(),
+                    field: "release_references".to_string(),
+                    message: format!(
+                        "Deal targets no release but the message has {} releases",
+                        request.releases.len()
+                    ),
+                    location: format!("/deals[{}]", idx),
+                });
+            }
+            
             for (r_idx, release_ref) in deal.release_references.iter().enumerate() {
                 if !release_refs.contains(release_ref) {
                     result.errors.push(ValidationError {
@@ -381,50 +1476,482 @@ impl PreflightValidator {
         Ok(())
     }
     
-    // Identifier validation methods
-    fn validate_isrc(&self, isrc: &str) -> bool {
-        ISRC_PATTERN.is_match(isrc)
+    /// Report a malformed identifier: an error at `Strict`, a warning otherwise
+    #[allow(clippy::too_many_arguments)]
+    fn report_identifier(
+        &self,
+        code: &str,
+        field: &str,
+        kind: &str,
+        value: &str,
+        problem: &str,
+        location: &str,
+        result: &mut ValidationResult,
+    ) {
+        let message = format!("Invalid {} '{}' at {}: {}", kind, value, location, problem);
+        if self.config.level == PreflightLevel::Strict {
+            result.errors.push(ValidationError {
+                code: code.to_string(),
+                field: field.to_string(),
+                message,
+                location: location.to_string(),
+            });
+        } else {
+            result.warnings.push(ValidationWarning {
+                code: code.to_string(),
+                field: field.to_string(),
+                message,
+                location: location.to_string(),
+                suggestion: None,
+            });
+        }
     }
     
-    fn validate_upc(&self, upc: &str) -> bool {
-        if !UPC_PATTERN.is_match(upc) {
-            return false;
+    // Identifier validation methods
+    
+    /// ISRC: country (2) + registrant (3) + year (2) + designation (5)
+    fn isrc_problem(&self, isrc: &str) -> Option<String> {
+        if isrc.contains('-') {
+            return Some("hyphens are not allowed in DDEX messages".to_string());
+        }
+        if isrc.len() != 12 || !isrc.is_ascii() {
+            return Some(format!("expected 12 characters, found {}", isrc.chars().count()));
         }
         
-        // Validate check digit
-        self.validate_upc_checksum(upc)
+        let (country, rest) = isrc.split_at(2);
+        let (registrant, rest) = rest.split_at(3);
+        let (year, designation) = rest.split_at(2);
+        
+        if !country.chars().all(|c| c.is_ascii_uppercase()) {
+            return Some(format!("country code '{}' must be two uppercase letters", country));
+        }
+        let known_country = ISRC_SPECIAL_PREFIXES.contains(&country)
+            || !matches!(
+                AllowedValueSet::TerritoryCode.check(country, DdexVersion::Ern43),
+                AvsStatus::Unknown { .. }
+            );
+        if !known_country {
+            return Some(format!("country code '{}' is not an assigned ISRC prefix", country));
+        }
+        if !registrant.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()) {
+            return Some(format!("registrant code '{}' must be 3 uppercase letters or digits", registrant));
+        }
+        if !year.chars().all(|c| c.is_ascii_digit()) {
+            return Some(format!("year of reference '{}' must be 2 digits", year));
+        }
+        if !designation.chars().all(|c| c.is_ascii_digit()) {
+            return Some(format!("designation code '{}' must be 5 digits", designation));
+        }
+        None
     }
     
-    fn validate_upc_checksum(&self, upc: &str) -> bool {
-        let digits: Vec<u32> = upc.chars()
-            .filter_map(|c| c.to_digit(10))
-            .collect();
-        
-        if digits.len() < 12 {
-            return false;
+    /// ICPN: UPC-A (12), EAN-13 or GTIN-14 with a valid check digit
+    fn icpn_problem(&self, icpn: &str) -> Option<String> {
+        if !UPC_PATTERN.is_match(icpn) {
+            return Some(format!("expected 12, 13 or 14 digits, found '{}'", icpn));
         }
-        
-        let mut sum = 0;
-        for (i, &digit) in digits.iter().take(digits.len() - 1).enumerate() {
-            if i % 2 == 0 {
-                sum += digit;
-            } else {
-                sum += digit * 3;
+        if self.config.validate_checksums {
+            let expected = gtin_check_digit(&icpn[..icpn.len() - 1]);
+            let actual = icpn.chars().last().and_then(|c| c.to_digit(10));
+            if actual != Some(expected) {
+                return Some(format!("check digit should be {}", expected));
             }
         }
-        
-        let check_digit = (10 - (sum % 10)) % 10;
-        digits[digits.len() - 1] == check_digit
+        None
+    }
+    
+    /// GRid: "A1" + issuer (5) + release number (10) + check character
+    fn grid_problem(&self, grid: &str) -> Option<String> {
+        let grid = grid.replace('-', "");
+        if grid.len() != 18 || !grid.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()) {
+            return Some("expected 18 uppercase alphanumeric characters".to_string());
+        }
+        if self.config.validate_checksums && !iso7064_mod37_36_valid(&grid) {
+            return Some("check character does not match (ISO 7064 MOD 37,36)".to_string());
+        }
+        None
+    }
+    
+    /// DPID: "PADPID" + letter + 10 digits + check character
+    fn dpid_problem(&self, dpid: &str) -> Option<String> {
+        if !DPID_PATTERN.is_match(dpid) {
+            return Some("expected 'PADPID', a letter, 10 digits and a check character".to_string());
+        }
+        if self.config.validate_checksums && !iso7064_mod37_36_valid(dpid) {
+            return Some("check character does not match (ISO 7064 MOD 37,36)".to_string());
+        }
+        None
     }
     
     fn validate_duration(&self, duration: &str) -> bool {
-        // Basic ISO 8601 duration validation
-        duration.starts_with("PT") && 
-            (duration.contains('M') || duration.contains('S'))
+        duration.parse::<DdexDuration>().is_ok()
     }
     
-    fn validate_territory_code(&self, code: &str) -> bool {
-        // Basic ISO 3166-1 alpha-2 validation
-        code.len() == 2 && code.chars().all(|c| c.is_ascii_uppercase())
+    fn validate_territory_code(&self, code: &str, version: DdexVersion) -> bool {
+        AllowedValueSet::TerritoryCode.contains(code, version)
+    }
+}
+
+/// Resolve the request's version string ("4.3", "ern/43", "ERN/4.3"),
+/// `None` for versions the builder does not support
+fn request_version(version: &str) -> Option<DdexVersion> {
+    let lowered = version.to_ascii_lowercase();
+    match lowered.trim_start_matches("ern/").replace('.', "").as_str() {
+        "382" => Some(DdexVersion::Ern382),
+        "41" => Some(DdexVersion::Ern41),
+        "42" => Some(DdexVersion::Ern42),
+        "43" => Some(DdexVersion::Ern43),
+        "44" => Some(DdexVersion::Ern44),
+        _ => None,
+    }
+}
+
+/// GS1 check digit for the data digits of a UPC/EAN/GTIN
+pub(crate) fn gtin_check_digit(data: &str) -> u32 {
+    let sum: u32 = data.chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, d)| if i % 2 == 0 { d * 3 } else { d })
+        .sum();
+    (10 - sum % 10) % 10
+}
+
+/// Whether `id` has the shape of a GRid: "A1" and 16 more uppercase
+/// alphanumerics, hyphens aside
+fn is_grid_shaped(id: &str) -> bool {
+    let mut chars = id.chars().filter(|&c| c != '-');
+    id.starts_with("A1") && chars.clone().count() == 18 && chars.all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+/// ISO 7064 MOD 37,36 check, used by GRid and DPID
+fn iso7064_mod37_36_valid(value: &str) -> bool {
+    let mut product = 36;
+    let mut sum = 0;
+    for c in value.chars() {
+        let Some(digit) = c.to_digit(36) else { return false };
+        sum = (product + digit) % 36;
+        let adjusted = if sum == 0 { 36 } else { sum };
+        product = (adjusted * 2) % 37;
+    }
+    sum == 1
+}
+
+/// Calendar date of a deal date field; malformed dates are reported elsewhere
+fn deal_date(date: &Option<String>) -> Option<NaiveDate> {
+    date.as_deref().and_then(|date| date.get(..10)?.parse().ok())
+}
+
+/// Last day a deal is valid and the field that decides it: the end date, or
+/// the day before an earlier takedown
+fn deal_end(terms: &super::builder::DealTerms) -> Option<(NaiveDate, &'static str)> {
+    let end = deal_date(&terms.end_date).map(|end| (end, "end_date"));
+    let takedown = deal_date(&terms.takedown_date)
+        .and_then(|date| date.pred_opt())
+        .map(|date| (date, "takedown_date"));
+    match (end, takedown) {
+        (Some(end), Some(takedown)) => Some(if takedown.0 < end.0 { takedown } else { end }),
+        (end, takedown) => end.or(takedown),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validator(level: PreflightLevel) -> PreflightValidator {
+        PreflightValidator::new(ValidationConfig { level, ..Default::default() })
+    }
+
+    #[test]
+    fn test_isrc_components() {
+        let v = validator(PreflightLevel::Warn);
+        assert!(v.isrc_problem("USRC17607839").is_none());
+        assert!(v.isrc_problem("QZES81947316").is_none());
+        assert!(v.isrc_problem("US-RC1-76-07839").unwrap().contains("hyphens"));
+        assert!(v.isrc_problem("XQRC17607839").unwrap().contains("country"));
+        assert!(v.isrc_problem("USR!17607839").unwrap().contains("registrant"));
+        assert!(v.isrc_problem("USRC1A607839").unwrap().contains("year"));
+        assert!(v.isrc_problem("USRC1760783X").unwrap().contains("designation"));
+    }
+
+    #[test]
+    fn test_icpn_check_digit() {
+        let v = validator(PreflightLevel::Warn);
+        assert!(v.icpn_problem("123456789012").is_none()); // UPC-A
+        assert!(v.icpn_problem("4006381333931").is_none()); // EAN-13
+        assert!(v.icpn_problem("123456789014").unwrap().contains("should be 2"));
+        assert!(v.icpn_problem("12345").is_some());
+    }
+
+    #[test]
+    fn test_grid_and_dpid_check_characters() {
+        let v = validator(PreflightLevel::Warn);
+        assert!(v.grid_problem("A1-2425G-ABC1234002-M").is_none());
+        assert!(v.grid_problem("A12425GABC1234002N").unwrap().contains("check character"));
+        assert!(v.grid_problem("A12425G").is_some());
+        assert!(is_grid_shaped("A1-2425G-ABC1234002-M"));
+        assert!(!is_grid_shaped("A1-2024-001"));
+        assert!(!is_grid_shaped("A12425GABC1234002m"));
+
+        assert!(v.dpid_problem("PADPIDA2011072101T").is_none());
+        assert!(v.dpid_problem("PADPIDA2011072101U").unwrap().contains("check character"));
+        assert!(v.dpid_problem("DPID-123").is_some());
+    }
+
+    #[test]
+    fn test_identifier_severity_follows_level() {
+        for (level, errors, warnings) in [(PreflightLevel::Strict, 1, 0), (PreflightLevel::Warn, 0, 1)] {
+            let mut result = ValidationResult { errors: vec![], warnings: vec![], info: vec![], passed: true };
+            validator(level).report_identifier("INVALID_ISRC", "isrc", "ISRC", "BAD", "too short", "/releases[0]/tracks[0]/isrc", &mut result);
+            assert_eq!((result.errors.len(), result.warnings.len()), (errors, warnings));
+        }
+
+        let mut result = ValidationResult { errors: vec![], warnings: vec![], info: vec![], passed: true };
+        validator(PreflightLevel::Strict).report_identifier("INVALID_ISRC", "isrc", "ISRC", "BAD", "too short", "/releases[0]/tracks[0]/isrc", &mut result);
+        assert!(result.errors[0].message.contains("'BAD' at /releases[0]/tracks[0]/isrc"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_message_control_type() {
+        let mut request = crate::benchmark::sample_request(1);
+        let invalid = |request: &crate::builder::BuildRequest| {
+            let result = validator(PreflightLevel::Warn).validate(request).unwrap();
+            result.errors.iter().any(|e| e.code == "INVALID_MESSAGE_CONTROL_TYPE")
+        };
+        assert!(!invalid(&request));
+        request.header.message_control_type = Some("NewReleaseMessage".to_string());
+        assert!(invalid(&request));
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let mut request = crate::benchmark::sample_request(1);
+        let codes = |request: &crate::builder::BuildRequest| {
+            let result = validator(PreflightLevel::Warn).validate(request).unwrap();
+            (result.passed, result.errors.iter().map(|e| e.code.clone()).collect::<Vec<_>>())
+        };
+        for version in ["3.8.2", "ERN/4.3", "ern/44"] {
+            request.version = version.to_string();
+            assert!(!codes(&request).1.contains(&"UNSUPPORTED_VERSION".to_string()), "{}", version);
+        }
+        for version in ["38", "4.5", ""] {
+            request.version = version.to_string();
+            assert_eq!(codes(&request), (false, vec!["UNSUPPORTED_VERSION".to_string()]), "{}", version);
+        }
+    }
+
+    #[test]
+    fn test_title_languages() {
+        let title = |text: &str, language: Option<&str>| crate::builder::LocalizedStringRequest {
+            text: text.to_string(),
+            language_code: language.map(str::to_string),
+        };
+        let titles = [title("Blue", Some("en")), title("Bleu", Some("fr_FR")), title("Blue (Remastered)", Some("EN")), title("Azul", None)];
+        let mut result = ValidationResult { errors: vec![], warnings: vec![], info: vec![], passed: true };
+        validator(PreflightLevel::Warn).validate_languages(&titles, "/releases[0]/title", &mut result);
+        let warnings: Vec<_> = result.warnings.iter().map(|w| (w.code.as_str(), w.location.as_str())).collect();
+        assert_eq!(
+            warnings,
+            [("INVALID_LANGUAGE_CODE", "/releases[0]/title[1]/language_code"), ("DUPLICATE_LANGUAGE", "/releases[0]/title[2]")]
+        );
+    }
+
+    #[test]
+    fn test_file_duration_mismatch() {
+        let details = |duration: &str| crate::builder::TechnicalDetailsRequest { duration: Some(duration.to_string()), ..Default::default() };
+        let v = validator(PreflightLevel::Warn);
+        let mut result = ValidationResult { errors: vec![], warnings: vec![], info: vec![], passed: true };
+        v.validate_file_duration(Some("PT3M45S"), Some(&details("PT3M45.4S")), "/releases[0]/tracks[0]", &mut result);
+        v.validate_file_duration(Some("PT1H0M0S"), Some(&details("PT3600.2S")), "/releases[0]/tracks[1]", &mut result);
+        assert!(result.warnings.is_empty());
+
+        v.validate_file_duration(Some("PT3M45S"), Some(&details("PT4M1.5S")), "/releases[0]/tracks[2]", &mut result);
+        assert_eq!(result.warnings[0].code, "DURATION_MISMATCH");
+        assert_eq!(result.warnings[0].message, "Duration PT3M45S differs from the PT4M1.5S of the delivered file");
+        assert_eq!(result.warnings[0].suggestion.as_deref(), Some("Use the file's duration, PT4M2S"));
+    }
+
+    #[test]
+    fn test_release_dates() {
+        let release = |date: &str, original: Option<&str>| -> crate::builder::ReleaseRequest {
+            serde_json::from_value(serde_json::json!({
+                "release_id": "R1",
+                "title": [{ "text": "Album" }],
+                "artist": "Artist",
+                "release_date": date,
+                "original_release_date": original,
+                "territory_details": [{ "territory_codes": ["JP"], "release_date": "2025-03-08" }]
+            }))
+            .unwrap()
+        };
+        let config = ValidationConfig { as_of: NaiveDate::from_ymd_opt(2025, 1, 1), ..Default::default() };
+        let check = |release: &crate::builder::ReleaseRequest| {
+            let mut result = ValidationResult { errors: vec![], warnings: vec![], info: vec![], passed: true };
+            PreflightValidator::new(config.clone()).validate_release_dates(release, "/releases[0]", ReleaseDateRules::default(), &mut result);
+            result
+        };
+
+        let result = check(&release("2025-03-01", Some("1979-10-12")));
+        assert!(result.warnings.is_empty() && result.errors.is_empty());
+
+        let result = check(&release("2052-03-01", Some("2025-03-10")));
+        let warnings: Vec<_> = result.warnings.iter().map(|w| (w.code.as_str(), w.location.as_str())).collect();
+        assert_eq!(
+            warnings,
+            [
+                ("RELEASE_DATE_FAR_AHEAD", "/releases[0]/release_date"),
+                ("ORIGINAL_AFTER_RELEASE_DATE", "/releases[0]/original_release_date"),
+                ("ORIGINAL_RELEASE_DATE_IN_FUTURE", "/releases[0]/original_release_date"),
+            ]
+        );
+        assert_eq!(result.warnings[1].message, "Release R1 first came out on 2025-03-10, after its release date 2025-03-08");
+
+        let result = check(&release("0225-03-01", Some("1979/10/12")));
+        assert_eq!(result.errors[0].code, "INVALID_DATE");
+        assert_eq!(result.warnings[0].code, "IMPLAUSIBLE_RELEASE_DATE");
+        assert_eq!(result.warnings[0].message, "0225-03-01 is before 1880");
+    }
+
+    #[test]
+    fn test_parental_warnings() {
+        let release = |release_warning: &str, track_warnings: &[Option<&str>]| -> crate::builder::ReleaseRequest {
+            let tracks: Vec<_> = track_warnings.iter().enumerate().map(|(i, warning)| serde_json::json!({
+                "track_id": format!("T{}", i + 1),
+                "isrc": "USRC17607839",
+                "title": "Track",
+                "duration": "PT3M",
+                "artist": "Artist",
+                "parental_warning": warning
+            })).collect();
+            serde_json::from_value(serde_json::json!({
+                "release_id": "R1",
+                "title": [{ "text": "Album" }],
+                "artist": "Artist",
+                "tracks": tracks,
+                "territory_details": [{ "territory_codes": ["Worldwide"], "parental_warning": release_warning }]
+            }))
+            .unwrap()
+        };
+        let check = |config: ValidationConfig, release: &crate::builder::ReleaseRequest| {
+            let mut result = ValidationResult { errors: vec![], warnings: vec![], info: vec![], passed: true };
+            PreflightValidator::new(config).validate_parental_warnings(release, "/releases[0]", DdexVersion::Ern43, &mut result);
+            result
+        };
+
+        let result = check(ValidationConfig::default(), &release("NotExplicit", &[Some("NotExplicit"), Some("Explicit")]));
+        assert_eq!(result.warnings[0].code, "PARENTAL_WARNING_MISMATCH");
+        assert_eq!(result.warnings[0].message, "Release R1 is marked NotExplicit but track T2 is Explicit");
+        assert_eq!(result.warnings[0].location, "/releases[0]/territory_details[0]/parental_warning");
+
+        let result = check(ValidationConfig::default(), &release("Explicit", &[Some("NotExplicit"), None]));
+        assert!(result.warnings.is_empty() && result.errors.is_empty());
+        let result = check(ValidationConfig::default(), &release("Explicit", &[Some("NotExplicit"), Some("ExplicitContentEdited")]));
+        assert_eq!(result.warnings[0].message, "Release R1 is marked Explicit but none of its tracks is");
+
+        let required = ValidationConfig { parental_warnings: ParentalWarningCheck::Require, ..Default::default() };
+        let result = check(required, &release("ExplicitContentEdited", &[Some("Explicit"), None]));
+        let errors: Vec<_> = result.errors.iter().map(|e| (e.code.as_str(), e.location.as_str())).collect();
+        assert_eq!(
+            errors,
+            [
+                ("MISSING_PARENTAL_WARNING", "/releases[0]/tracks[1]/parental_warning"),
+                ("PARENTAL_WARNING_MISMATCH", "/releases[0]/territory_details[0]/parental_warning"),
+            ]
+        );
+
+        let off = ValidationConfig { parental_warnings: ParentalWarningCheck::Off, ..Default::default() };
+        assert!(check(off, &release("NotExplicit", &[Some("Explicit")])).warnings.is_empty());
+    }
+
+    #[test]
+    fn test_conflicting_deals() {
+        let deal = |territories: &[&str], start: &str, releases: &[&str]| -> crate::builder::DealRequest {
+            serde_json::from_value(serde_json::json!({
+                "deal_reference": null,
+                "deal_terms": {
+                    "commercial_model_type": "SubscriptionModel",
+                    "territory_code": territories,
+                    "start_date": start,
+                    "use_types": ["Stream"]
+                },
+                "release_references": releases
+            }))
+            .unwrap()
+        };
+        let deals = [
+            deal(&["GB", "US"], "2024-01-01", &[]),
+            deal(&["Worldwide"], "2024-06-01", &["R1"]),
+            deal(&["FR"], "2024-01-01", &["R2"]),
+        ];
+
+        let mut result = ValidationResult { errors: vec![], warnings: vec![], info: vec![], passed: true };
+        validator(PreflightLevel::Warn).validate_deal_conflicts(&deals, &mut result);
+        let conflicts: Vec<_> = result.warnings.iter().map(|w| (w.location.as_str(), w.message.as_str())).collect();
+        // The FR deal is for another release than the worldwide one
+        assert_eq!(
+            conflicts,
+            [("/deals[1]/deal_terms/territory_code", "Deal 1 grants GB, US for the same release, use and period as deal 0")]
+        );
+    }
+
+    #[test]
+    fn test_deal_timeline() {
+        let deal = |territories: &[&str], start: &str, end: Option<&str>, takedown: Option<&str>| -> crate::builder::DealRequest {
+            serde_json::from_value(serde_json::json!({
+                "deal_reference": null,
+                "deal_terms": {
+                    "commercial_model_type": "SubscriptionModel",
+                    "territory_code": territories,
+                    "start_date": start,
+                    "end_date": end,
+                    "takedown_date": takedown,
+                    "use_types": ["Stream"]
+                },
+                "release_references": ["R1"]
+            }))
+            .unwrap()
+        };
+        let deals = [
+            deal(&["GB", "US"], "2024-01-01", Some("2024-12-31"), Some("2024-07-01")),
+            deal(&["Worldwide"], "2024-09-01", None, None),
+            deal(&["FR"], "2023-01-01", Some("2023-12-31"), None),
+        ];
+
+        let mut result = ValidationResult { errors: vec![], warnings: vec![], info: vec![], passed: true };
+        let config = ValidationConfig { as_of: NaiveDate::from_ymd_opt(2025, 1, 1), ..Default::default() };
+        let validator = PreflightValidator::new(config);
+        validator.validate_deal_coverage(&deals, &mut result);
+        validator.validate_expired_deals(&deals, &mut result);
+        let warnings: Vec<_> = result.warnings.iter().map(|w| (w.code.as_str(), w.location.as_str(), w.message.as_str())).collect();
+        assert_eq!(
+            warnings,
+            [
+                (
+                    "DEAL_COVERAGE_GAP",
+                    "/deals[1]/deal_terms/start_date",
+                    "Release R1 has no deal in FR from 2024-01-01 to 2024-08-31, between deal 2 ending and deal 1 starting",
+                ),
+                (
+                    "DEAL_COVERAGE_GAP",
+                    "/deals[1]/deal_terms/start_date",
+                    "Release R1 has no deal in GB, US from 2024-07-01 to 2024-08-31, between deal 0 ending and deal 1 starting",
+                ),
+                ("EXPIRED_DEAL", "/deals[0]/deal_terms/takedown_date", "Deal 0 ended on 2024-06-30, before the build date 2025-01-01"),
+                ("EXPIRED_DEAL", "/deals[2]/deal_terms/end_date", "Deal 2 ended on 2023-12-31, before the build date 2025-01-01"),
+            ]
+        );
+
+        // Opting out silences the timeline checks
+        let request: crate::builder::BuildRequest = serde_json::from_value(serde_json::json!({
+            "header": { "message_sender": { "party_name": [{ "text": "Label" }] }, "message_recipient": { "party_name": [{ "text": "DSP" }] } },
+            "version": "4.3",
+            "releases": [],
+            "deals": deals
+        }))
+        .unwrap();
+        let config = ValidationConfig { check_deal_timeline: false, ..Default::default() };
+        let result = PreflightValidator::new(config).validate(&request).unwrap();
+        assert!(result.warnings.iter().all(|w| w.code != "DEAL_COVERAGE_GAP" && w.code != "EXPIRED_DEAL"));
+    }
+}
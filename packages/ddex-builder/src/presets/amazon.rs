@@ -0,0 +1,139 @@
+//! Amazon Music-specific DDEX presets and configurations
+
+use super::{DdexVersion, MessageProfile, PresetConfig, PartnerPreset, PresetDefaults, PresetSource, ValidationRule};
+use indexmap::IndexMap;
+
+/// Amazon Music Album preset (ERN 4.3)
+///
+/// Amazon sells downloads and streams on Amazon Music Unlimited, so releases
+/// carry both distribution channels. Lossless masters of at least CD quality
+/// are expected; higher resolutions are offered as Amazon Music HD.
+pub fn amazon_album() -> PartnerPreset {
+    let mut validation_rules = IndexMap::new();
+    validation_rules.insert("ISRC".to_string(), ValidationRule::Pattern(
+        r"^[A-Z]{2}[A-Z0-9]{3}\d{7}$".to_string()
+    ));
+    validation_rules.insert("UPC".to_string(), ValidationRule::Pattern(r"^\d{12,14}$".to_string()));
+    validation_rules.insert("ReleaseDate".to_string(), ValidationRule::Required);
+    validation_rules.insert("Genre".to_string(), ValidationRule::Required);
+    validation_rules.insert("ParentalWarningType".to_string(), ValidationRule::OneOf(vec![
+        "Explicit".to_string(),
+        "NotExplicit".to_string(),
+        "ExplicitContentEdited".to_string(),
+    ]));
+    validation_rules.insert("AudioQuality".to_string(), ValidationRule::AudioQuality {
+        min_bit_depth: 16,
+        min_sample_rate: 44100,
+    });
+    validation_rules.insert("AudioCodec".to_string(), ValidationRule::OneOf(vec![
+        "FLAC".to_string(),
+        "PCM".to_string(),
+    ]));
+    validation_rules.insert("CoverArtSize".to_string(), ValidationRule::Custom(
+        "Front cover image of at least 1400x1400 pixels, 3000x3000 recommended".to_string()
+    ));
+    validation_rules.insert("AlbumTitle".to_string(), ValidationRule::MaxLength(255));
+    validation_rules.insert("TrackTitle".to_string(), ValidationRule::MaxLength(255));
+
+    let mut default_values = IndexMap::new();
+    default_values.insert("MessageControlType".to_string(), "LiveMessage".to_string());
+    default_values.insert("TerritoryCode".to_string(), "Worldwide".to_string());
+    default_values.insert("ReleaseType".to_string(), "Album".to_string());
+
+    let config = PresetConfig {
+        version: DdexVersion::Ern43,
+        profile: MessageProfile::AudioAlbum,
+        required_fields: vec![
+            "ISRC".to_string(),
+            "UPC".to_string(),
+            "ReleaseDate".to_string(),
+            "PLine".to_string(),
+            "CLine".to_string(),
+            "Genre".to_string(),
+            "ParentalWarningType".to_string(),
+            "AlbumTitle".to_string(),
+            "ArtistName".to_string(),
+            "TrackTitle".to_string(),
+            "CoverArt".to_string(),
+        ],
+        validation_rules: validation_rules.clone(),
+        default_values,
+        custom_mappings: IndexMap::new(),
+        territory_codes: vec!["Worldwide".to_string()],
+        distribution_channels: vec!["01".to_string(), "02".to_string()], // Download + Streaming
+        release_types: vec![
+            "Album".to_string(),
+            "CompilationAlbum".to_string(),
+            "LiveAlbum".to_string(),
+            "EP".to_string(),
+        ],
+    };
+
+    PartnerPreset {
+        name: "amazon_album".to_string(),
+        description: "Amazon Music Album ERN 4.3 for download and streaming".to_string(),
+        source: PresetSource::PublicDocs,
+        provenance_url: Some("https://artists.amazonmusic.com/".to_string()),
+        version: "1.0.0".to_string(),
+        locked: false,
+        disclaimer: "Based on publicly available Amazon Music for Artists information. This preset is community-maintained and not an official Amazon specification. Verify current delivery requirements with your Amazon Music contact.".to_string(),
+        determinism: super::super::determinism::DeterminismConfig::default(),
+        defaults: PresetDefaults {
+            message_control_type: Some("LiveMessage".to_string()),
+            territory_code: vec!["Worldwide".to_string()],
+            distribution_channel: vec!["01".to_string(), "02".to_string()],
+        },
+        required_fields: config.required_fields.clone(),
+        format_overrides: IndexMap::from([("Genre".to_string(), "amazon".to_string())]),
+        config,
+        validation_rules,
+        custom_mappings: IndexMap::new(),
+    }
+}
+
+/// Amazon Music Single preset (ERN 4.3)
+pub fn amazon_single() -> PartnerPreset {
+    let mut preset = amazon_album();
+
+    preset.name = "amazon_single".to_string();
+    preset.description = "Amazon Music Single ERN 4.3 for download and streaming".to_string();
+    preset.config.profile = MessageProfile::AudioSingle;
+    preset.config.release_types = vec!["Single".to_string()];
+    preset.config.default_values.insert("ReleaseType".to_string(), "Single".to_string());
+
+    preset
+}
+
+/// Get all Amazon Music presets
+pub fn all_amazon_presets() -> IndexMap<String, PartnerPreset> {
+    let mut presets = IndexMap::new();
+    presets.insert("amazon_album".to_string(), amazon_album());
+    presets.insert("amazon_single".to_string(), amazon_single());
+    presets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amazon_album_preset() {
+        let preset = amazon_album();
+        assert_eq!(preset.name, "amazon_album");
+        assert_eq!(preset.config.profile, MessageProfile::AudioAlbum);
+        assert!(preset.required_fields.contains(&"ParentalWarningType".to_string()));
+        assert_eq!(preset.config.distribution_channels, vec!["01", "02"]);
+        assert!(matches!(
+            preset.validation_rules["AudioQuality"],
+            ValidationRule::AudioQuality { min_bit_depth: 16, min_sample_rate: 44100 }
+        ));
+    }
+
+    #[test]
+    fn test_amazon_single_preset() {
+        let preset = amazon_single();
+        assert_eq!(preset.name, "amazon_single");
+        assert_eq!(preset.config.profile, MessageProfile::AudioSingle);
+        assert_eq!(preset.config.release_types, vec!["Single"]);
+    }
+}
@@ -0,0 +1,157 @@
+//! Beatport-specific DDEX presets and configurations
+
+use super::{DdexVersion, MessageProfile, PresetConfig, PartnerPreset, PresetDefaults, PresetSource, ValidationRule};
+use indexmap::IndexMap;
+
+/// Beatport genres a release can be filed under
+fn beatport_genres() -> Vec<String> {
+    [
+        "140 / Deep Dubstep / Grime",
+        "Afro House",
+        "Amapiano",
+        "Ambient / Experimental",
+        "Bass / Club",
+        "Bass House",
+        "Brazilian Funk",
+        "Breaks / Breakbeat / UK Bass",
+        "Dance / Pop",
+        "Deep House",
+        "DJ Tools",
+        "Downtempo",
+        "Drum & Bass",
+        "Dubstep",
+        "Electro (Classic / Detroit / Modern)",
+        "Electronica",
+        "Funky House",
+        "Hard Dance / Hardcore / Neo Rave",
+        "Hard Techno",
+        "House",
+        "Indie Dance",
+        "Jackin House",
+        "Mainstage",
+        "Melodic House & Techno",
+        "Minimal / Deep Tech",
+        "Nu Disco / Disco",
+        "Organic House",
+        "Progressive House",
+        "Psy-Trance",
+        "Tech House",
+        "Techno (Peak Time / Driving)",
+        "Techno (Raw / Deep / Hypnotic)",
+        "Trance (Main Floor)",
+        "Trance (Raw / Deep / Hypnotic)",
+        "Trap / Future Bass",
+        "UK Garage / Bassline",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+/// Beatport release preset (ERN 4.3)
+///
+/// Beatport sells downloads to DJs and streams through Beatport Streaming.
+/// Tracks need a mix name ("Original Mix", "Extended Mix", ...) and a genre
+/// from Beatport's own list, and audio goes out as uncompressed WAV or AIFF
+/// of at least CD quality.
+pub fn beatport_release() -> PartnerPreset {
+    let mut validation_rules = IndexMap::new();
+    validation_rules.insert("ISRC".to_string(), ValidationRule::Pattern(
+        r"^[A-Z]{2}[A-Z0-9]{3}\d{7}$".to_string()
+    ));
+    validation_rules.insert("UPC".to_string(), ValidationRule::Pattern(r"^\d{12,14}$".to_string()));
+    validation_rules.insert("CatalogNumber".to_string(), ValidationRule::Required);
+    validation_rules.insert("ReleaseDate".to_string(), ValidationRule::Required);
+    validation_rules.insert("Genre".to_string(), ValidationRule::OneOf(beatport_genres()));
+    validation_rules.insert("MixName".to_string(), ValidationRule::Required);
+    validation_rules.insert("AudioQuality".to_string(), ValidationRule::AudioQuality {
+        min_bit_depth: 16,
+        min_sample_rate: 44100,
+    });
+    validation_rules.insert("AudioCodec".to_string(), ValidationRule::OneOf(vec![
+        "PCM".to_string(),
+    ]));
+    validation_rules.insert("CoverArtSize".to_string(), ValidationRule::Custom(
+        "Square front cover image of at least 1400x1400 pixels".to_string()
+    ));
+
+    let mut default_values = IndexMap::new();
+    default_values.insert("MessageControlType".to_string(), "LiveMessage".to_string());
+    default_values.insert("TerritoryCode".to_string(), "Worldwide".to_string());
+    default_values.insert("ReleaseType".to_string(), "EP".to_string());
+    default_values.insert("MixName".to_string(), "Original Mix".to_string());
+
+    let mut custom_mappings = IndexMap::new();
+    custom_mappings.insert("MixName".to_string(), "SubTitle".to_string());
+
+    let config = PresetConfig {
+        version: DdexVersion::Ern43,
+        profile: MessageProfile::AudioAlbum,
+        required_fields: vec![
+            "ISRC".to_string(),
+            "UPC".to_string(),
+            "CatalogNumber".to_string(),
+            "ReleaseDate".to_string(),
+            "PLine".to_string(),
+            "Genre".to_string(),
+            "AlbumTitle".to_string(),
+            "ArtistName".to_string(),
+            "TrackTitle".to_string(),
+            "MixName".to_string(),
+        ],
+        validation_rules: validation_rules.clone(),
+        default_values,
+        custom_mappings: custom_mappings.clone(),
+        territory_codes: vec!["Worldwide".to_string()],
+        distribution_channels: vec!["01".to_string(), "02".to_string()], // Download + Streaming
+        release_types: vec![
+            "Single".to_string(),
+            "EP".to_string(),
+            "Album".to_string(),
+            "CompilationAlbum".to_string(),
+        ],
+    };
+
+    PartnerPreset {
+        name: "beatport_release".to_string(),
+        description: "Beatport single, EP or album ERN 4.3 with mix names and Beatport genres".to_string(),
+        source: PresetSource::PublicDocs,
+        provenance_url: Some("https://support.beatport.com/".to_string()),
+        version: "1.0.0".to_string(),
+        locked: false,
+        disclaimer: "Based on publicly available Beatport label support information. This preset is community-maintained and not an official Beatport specification. Beatport's genre list changes over time - verify current requirements with Beatport label support.".to_string(),
+        determinism: super::super::determinism::DeterminismConfig::default(),
+        defaults: PresetDefaults {
+            message_control_type: Some("LiveMessage".to_string()),
+            territory_code: vec!["Worldwide".to_string()],
+            distribution_channel: vec!["01".to_string(), "02".to_string()],
+        },
+        required_fields: config.required_fields.clone(),
+        format_overrides: IndexMap::new(),
+        config,
+        validation_rules,
+        custom_mappings,
+    }
+}
+
+/// Get all Beatport presets
+pub fn all_beatport_presets() -> IndexMap<String, PartnerPreset> {
+    let mut presets = IndexMap::new();
+    presets.insert("beatport_release".to_string(), beatport_release());
+    presets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_beatport_release_preset() {
+        let preset = beatport_release();
+        assert_eq!(preset.name, "beatport_release");
+        assert!(preset.required_fields.contains(&"MixName".to_string()));
+        assert!(preset.required_fields.contains(&"CatalogNumber".to_string()));
+        assert!(matches!(&preset.validation_rules["Genre"], ValidationRule::OneOf(genres) if genres.contains(&"Tech House".to_string())));
+        assert_eq!(preset.config.default_values["MixName"], "Original Mix");
+    }
+}
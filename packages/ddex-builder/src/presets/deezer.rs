@@ -0,0 +1,127 @@
+//! Deezer-specific DDEX presets and configurations
+
+use super::{DdexVersion, MessageProfile, PresetConfig, PartnerPreset, PresetDefaults, PresetSource, ValidationRule};
+use indexmap::IndexMap;
+
+/// Deezer Album preset (ERN 4.3)
+///
+/// Deezer streams lossless FLAC to HiFi subscribers, so audio is delivered at
+/// CD quality or better. Lyrics and explicit flags feed its catalog features.
+pub fn deezer_album() -> PartnerPreset {
+    let mut validation_rules = IndexMap::new();
+    validation_rules.insert("ISRC".to_string(), ValidationRule::Pattern(
+        r"^[A-Z]{2}[A-Z0-9]{3}\d{7}$".to_string()
+    ));
+    validation_rules.insert("UPC".to_string(), ValidationRule::Pattern(r"^\d{12,14}$".to_string()));
+    validation_rules.insert("ReleaseDate".to_string(), ValidationRule::Required);
+    validation_rules.insert("Genre".to_string(), ValidationRule::Required);
+    validation_rules.insert("ParentalWarningType".to_string(), ValidationRule::OneOf(vec![
+        "Explicit".to_string(),
+        "NotExplicit".to_string(),
+        "ExplicitContentEdited".to_string(),
+    ]));
+    validation_rules.insert("AudioQuality".to_string(), ValidationRule::AudioQuality {
+        min_bit_depth: 16,
+        min_sample_rate: 44100,
+    });
+    validation_rules.insert("AudioCodec".to_string(), ValidationRule::OneOf(vec![
+        "FLAC".to_string(),
+        "PCM".to_string(),
+    ]));
+    validation_rules.insert("CoverArtSize".to_string(), ValidationRule::Custom(
+        "Square front cover image of at least 1400x1400 pixels".to_string()
+    ));
+
+    let mut default_values = IndexMap::new();
+    default_values.insert("MessageControlType".to_string(), "LiveMessage".to_string());
+    default_values.insert("TerritoryCode".to_string(), "Worldwide".to_string());
+    default_values.insert("DistributionChannel".to_string(), "02".to_string()); // Streaming
+    default_values.insert("ReleaseType".to_string(), "Album".to_string());
+
+    let config = PresetConfig {
+        version: DdexVersion::Ern43,
+        profile: MessageProfile::AudioAlbum,
+        required_fields: vec![
+            "ISRC".to_string(),
+            "UPC".to_string(),
+            "ReleaseDate".to_string(),
+            "PLine".to_string(),
+            "CLine".to_string(),
+            "Genre".to_string(),
+            "ParentalWarningType".to_string(),
+            "AlbumTitle".to_string(),
+            "ArtistName".to_string(),
+            "TrackTitle".to_string(),
+        ],
+        validation_rules: validation_rules.clone(),
+        default_values,
+        custom_mappings: IndexMap::new(),
+        territory_codes: vec!["Worldwide".to_string()],
+        distribution_channels: vec!["02".to_string()],
+        release_types: vec![
+            "Album".to_string(),
+            "CompilationAlbum".to_string(),
+            "LiveAlbum".to_string(),
+            "EP".to_string(),
+        ],
+    };
+
+    PartnerPreset {
+        name: "deezer_album".to_string(),
+        description: "Deezer Album ERN 4.3 for lossless streaming".to_string(),
+        source: PresetSource::PublicDocs,
+        provenance_url: None,
+        version: "1.0.0".to_string(),
+        locked: false,
+        disclaimer: "Based on publicly available Deezer for Creators information. This preset is community-maintained and not an official Deezer specification. Verify current delivery requirements with your Deezer contact.".to_string(),
+        determinism: super::super::determinism::DeterminismConfig::default(),
+        defaults: PresetDefaults {
+            message_control_type: Some("LiveMessage".to_string()),
+            territory_code: vec!["Worldwide".to_string()],
+            distribution_channel: vec!["02".to_string()],
+        },
+        required_fields: config.required_fields.clone(),
+        format_overrides: IndexMap::new(),
+        config,
+        validation_rules,
+        custom_mappings: IndexMap::new(),
+    }
+}
+
+/// Deezer Single preset (ERN 4.3)
+pub fn deezer_single() -> PartnerPreset {
+    let mut preset = deezer_album();
+
+    preset.name = "deezer_single".to_string();
+    preset.description = "Deezer Single ERN 4.3 for lossless streaming".to_string();
+    preset.config.profile = MessageProfile::AudioSingle;
+    preset.config.release_types = vec!["Single".to_string()];
+    preset.config.default_values.insert("ReleaseType".to_string(), "Single".to_string());
+
+    preset
+}
+
+/// Get all Deezer presets
+pub fn all_deezer_presets() -> IndexMap<String, PartnerPreset> {
+    let mut presets = IndexMap::new();
+    presets.insert("deezer_album".to_string(), deezer_album());
+    presets.insert("deezer_single".to_string(), deezer_single());
+    presets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deezer_presets() {
+        let album = deezer_album();
+        assert_eq!(album.name, "deezer_album");
+        assert_eq!(album.config.distribution_channels, vec!["02"]);
+        assert!(album.required_fields.contains(&"CLine".to_string()));
+
+        let single = deezer_single();
+        assert_eq!(single.config.profile, MessageProfile::AudioSingle);
+        assert_eq!(all_deezer_presets().len(), 2);
+    }
+}
@@ -0,0 +1,399 @@
+//! Custom presets loaded from TOML or JSON files
+//!
+//! Labels can keep in-house presets next to their catalog instead of
+//! compiling them in. A preset file usually extends a built-in preset and
+//! only lists what differs:
+//!
+//! ```toml
+//! name = "my_label_album"
+//! description = "My Label album deliveries"
+//! extends = "audio_album"
+//! custom_fields = ["LabelCode"]
+//!
+//! [validation_rules]
+//! LabelCode = "Required"
+//! Genre = { OneOf = ["Rock", "Pop"] }
+//! ```
+//!
+//! Lists (`required_fields`, `territory_codes`, ...) replace the base
+//! preset's; maps (`validation_rules`, `default_values`, `custom_mappings`)
+//! are merged over it. Every field referenced by a rule, default or mapping
+//! must be known to some built-in preset or declared in `required_fields` or
+//! `custom_fields`, so typos fail at load time rather than being ignored.
+//!
+//! Directories listed in [`PRESET_PATH_ENV`] (separated like `PATH`) are
+//! scanned for `*.toml` and `*.json` preset files.
+
+use super::{all_presets, DdexVersion, MessageProfile, PartnerPreset, PresetConfig, PresetDefaults, PresetSource, ValidationRule};
+use crate::error::BuildError;
+use indexmap::{IndexMap, IndexSet};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Environment variable listing directories to scan for preset files
+pub const PRESET_PATH_ENV: &str = "DDEX_BUILDER_PRESET_PATH";
+
+/// On-disk preset format
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct PresetFile {
+    name: String,
+    description: String,
+    extends: Option<String>,
+    version: Option<String>,
+    profile: Option<MessageProfile>,
+    source: Option<PresetSource>,
+    provenance_url: Option<String>,
+    disclaimer: Option<String>,
+    #[serde(default)]
+    locked: bool,
+    required_fields: Option<Vec<String>>,
+    #[serde(default)]
+    custom_fields: Vec<String>,
+    #[serde(default)]
+    validation_rules: IndexMap<String, ValidationRule>,
+    #[serde(default)]
+    default_values: IndexMap<String, String>,
+    #[serde(default)]
+    custom_mappings: IndexMap<String, String>,
+    territory_codes: Option<Vec<String>>,
+    distribution_channels: Option<Vec<String>>,
+    release_types: Option<Vec<String>>,
+}
+
+/// Load and validate a single preset file
+///
+/// The format is picked from the extension (`.toml` or `.json`). Errors name
+/// the file and, for schema problems, the path of the offending key.
+pub fn load_preset_file(path: impl AsRef<Path>) -> Result<PartnerPreset, BuildError> {
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| BuildError::Io(format!("{}: {}", path.display(), e)))?;
+    let format = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default().to_ascii_lowercase();
+    let file = match format.as_str() {
+        "toml" => {
+            let deserializer = toml::Deserializer::new(&content);
+            serde_path_to_error::deserialize(deserializer).map_err(|e| schema_error(path, e.path(), e.inner()))?
+        }
+        "json" => {
+            let mut deserializer = serde_json::Deserializer::from_str(&content);
+            serde_path_to_error::deserialize(&mut deserializer).map_err(|e| schema_error(path, e.path(), e.inner()))?
+        }
+        _ => {
+            return Err(file_error(path, "preset files must have a .toml or .json extension".to_string()));
+        }
+    };
+    resolve(file).map_err(|message| file_error(path, message))
+}
+
+/// Load every `*.toml` and `*.json` preset file in a directory, sorted by file name
+pub fn load_preset_dir(dir: impl AsRef<Path>) -> Result<IndexMap<String, PartnerPreset>, BuildError> {
+    let dir = dir.as_ref();
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| BuildError::Io(format!("{}: {}", dir.display(), e)))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path.extension().and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("toml") || ext.eq_ignore_ascii_case("json"))
+        })
+        .collect();
+    files.sort();
+
+    let mut presets = IndexMap::new();
+    let mut origins: IndexMap<String, PathBuf> = IndexMap::new();
+    for file in files {
+        let preset = load_preset_file(&file)?;
+        if let Some(previous) = origins.insert(preset.name.clone(), file.clone()) {
+            return Err(file_error(&file, format!("preset '{}' is already defined in {}", preset.name, previous.display())));
+        }
+        presets.insert(preset.name.clone(), preset);
+    }
+    Ok(presets)
+}
+
+/// Load presets from the directories in [`PRESET_PATH_ENV`]
+///
+/// Returns an empty map when the variable is unset. Later directories
+/// override presets of the same name from earlier ones.
+pub fn load_preset_path() -> Result<IndexMap<String, PartnerPreset>, BuildError> {
+    let mut presets = IndexMap::new();
+    if let Some(paths) = std::env::var_os(PRESET_PATH_ENV) {
+        for dir in std::env::split_paths(&paths).filter(|dir| !dir.as_os_str().is_empty()) {
+            presets.extend(load_preset_dir(&dir)?);
+        }
+    }
+    Ok(presets)
+}
+
+fn file_error(path: &Path, message: String) -> BuildError {
+    BuildError::InvalidFormat {
+        field: format!("preset file {}", path.display()),
+        message,
+    }
+}
+
+fn schema_error(path: &Path, at: &serde_path_to_error::Path, error: impl std::fmt::Display) -> BuildError {
+    match at.to_string().as_str() {
+        "." => file_error(path, error.to_string()),
+        at => file_error(path, format!("{}: {}", at, error)),
+    }
+}
+
+pub(crate) fn parse_version(version: &str) -> Option<DdexVersion> {
+    match version.trim_start_matches("ERN/") {
+        "3.8.2" => Some(DdexVersion::Ern382),
+        "4.1" => Some(DdexVersion::Ern41),
+        "4.2" => Some(DdexVersion::Ern42),
+        "4.3" => Some(DdexVersion::Ern43),
+        "4.4" => Some(DdexVersion::Ern44),
+        _ => None,
+    }
+}
+
+/// Turn a parsed file into a preset, checking everything serde can't
+fn resolve(file: PresetFile) -> Result<PartnerPreset, String> {
+    // Names end up in XML comments (see `lock`), where "--" is not allowed
+    if file.name.is_empty()
+        || file.name.contains("--")
+        || !file.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(format!("name '{}' must be non-empty and use only letters, digits, '_' and single '-'", file.name));
+    }
+
+    let built_in = all_presets();
+    let mut preset = match &file.extends {
+        Some(base) => built_in
+            .get(base)
+            .cloned()
+            .ok_or_else(|| format!("extends: unknown preset '{}'; built-in presets are {}", base, built_in.keys().cloned().collect::<Vec<_>>().join(", ")))?,
+        None => {
+            let version = file.version.as_deref().ok_or("version is required unless the preset extends a built-in preset")?;
+            let profile = file.profile.ok_or("profile is required unless the preset extends a built-in preset")?;
+            blank_preset(parse_version(version).ok_or_else(|| format!("version: unsupported DDEX version '{}'", version))?, profile)
+        }
+    };
+
+    if let (Some(version), Some(_)) = (&file.version, &file.extends) {
+        preset.config.version = parse_version(version).ok_or_else(|| format!("version: unsupported DDEX version '{}'", version))?;
+    }
+    if let Some(profile) = file.profile {
+        preset.config.profile = profile;
+    }
+
+    preset.name = file.name;
+    preset.description = file.description;
+    preset.locked = file.locked;
+    preset.source = file.source.unwrap_or(PresetSource::Community);
+    preset.provenance_url = file.provenance_url;
+    preset.disclaimer = file.disclaimer.unwrap_or_else(|| "In-house preset loaded from a preset file.".to_string());
+    if let Some(required_fields) = file.required_fields {
+        preset.config.required_fields = required_fields;
+    }
+    if let Some(territory_codes) = file.territory_codes {
+        preset.defaults.territory_code = territory_codes.clone();
+        preset.config.territory_codes = territory_codes;
+    }
+    if let Some(distribution_channels) = file.distribution_channels {
+        preset.defaults.distribution_channel = distribution_channels.clone();
+        preset.config.distribution_channels = distribution_channels;
+    }
+    if let Some(release_types) = file.release_types {
+        preset.config.release_types = release_types;
+    }
+
+    let known = known_fields(&built_in, &preset, &file.custom_fields);
+    let sections = [
+        ("validation_rules", file.validation_rules.keys().collect::<Vec<_>>()),
+        ("default_values", file.default_values.keys().collect()),
+        ("custom_mappings", file.custom_mappings.keys().collect()),
+    ];
+    for (section, fields) in sections {
+        for field in fields {
+            if !known.contains(field.as_str()) {
+                let hint = match known.iter().find(|known| known.eq_ignore_ascii_case(field)) {
+                    Some(similar) => format!("did you mean '{}'?", similar),
+                    None => "declare in-house fields in custom_fields".to_string(),
+                };
+                return Err(format!("{}.{}: unknown field '{}' ({})", section, field, field, hint));
+            }
+        }
+    }
+
+    for (field, rule) in &file.validation_rules {
+        check_rule(rule).map_err(|message| format!("validation_rules.{}: {}", field, message))?;
+    }
+
+    preset.config.validation_rules.extend(file.validation_rules);
+    preset.config.default_values.extend(file.default_values);
+    preset.config.custom_mappings.extend(file.custom_mappings);
+    if let Some(message_control_type) = preset.config.default_values.get("MessageControlType") {
+        preset.defaults.message_control_type = Some(message_control_type.clone());
+    }
+    preset.required_fields = preset.config.required_fields.clone();
+    preset.validation_rules = preset.config.validation_rules.clone();
+    preset.custom_mappings = preset.config.custom_mappings.clone();
+
+    Ok(preset)
+}
+
+fn blank_preset(version: DdexVersion, profile: MessageProfile) -> PartnerPreset {
+    PartnerPreset {
+        name: String::new(),
+        description: String::new(),
+        source: PresetSource::Community,
+        provenance_url: None,
+        version: "1.0.0".to_string(),
+        locked: false,
+        disclaimer: String::new(),
+        determinism: super::super::determinism::DeterminismConfig::default(),
+        defaults: PresetDefaults {
+            message_control_type: None,
+            territory_code: Vec::new(),
+            distribution_channel: Vec::new(),
+        },
+        required_fields: Vec::new(),
+        format_overrides: IndexMap::new(),
+        config: PresetConfig {
+            version,
+            profile,
+            required_fields: Vec::new(),
+            validation_rules: IndexMap::new(),
+            default_values: IndexMap::new(),
+            custom_mappings: IndexMap::new(),
+            territory_codes: Vec::new(),
+            distribution_channels: Vec::new(),
+            release_types: Vec::new(),
+        },
+        validation_rules: IndexMap::new(),
+        custom_mappings: IndexMap::new(),
+    }
+}
+
+/// Fields a preset file may reference: anything a built-in preset uses,
+/// plus the base preset's and the file's own declarations
+fn known_fields<'a>(
+    built_in: &'a IndexMap<String, PartnerPreset>,
+    preset: &'a PartnerPreset,
+    custom_fields: &'a [String],
+) -> IndexSet<&'a str> {
+    built_in
+        .values()
+        .chain(std::iter::once(preset))
+        .flat_map(|preset| {
+            preset.required_fields.iter()
+                .chain(&preset.config.required_fields)
+                .chain(preset.config.validation_rules.keys())
+                .chain(preset.config.default_values.keys())
+                .chain(preset.config.custom_mappings.keys())
+        })
+        .chain(custom_fields)
+        .map(String::as_str)
+        .collect()
+}
+
+fn check_rule(rule: &ValidationRule) -> Result<(), String> {
+    match rule {
+        ValidationRule::Pattern(pattern) => regex::Regex::new(pattern)
+            .map(|_| ())
+            .map_err(|e| format!("invalid pattern: {}", e)),
+        ValidationRule::OneOf(values) if values.is_empty() => Err("OneOf needs at least one value".to_string()),
+        ValidationRule::TerritoryCode { allowed } if allowed.is_empty() => {
+            Err("TerritoryCode needs at least one allowed territory".to_string())
+        }
+        ValidationRule::AudioQuality { min_bit_depth: 0, .. } | ValidationRule::AudioQuality { min_sample_rate: 0, .. } => {
+            Err("AudioQuality needs a non-zero bit depth and sample rate".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_load_toml_preset_extending_built_in() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write(dir.path(), "label.toml", r#"
+name = "label_album"
+description = "Label album deliveries"
+extends = "audio_album"
+custom_fields = ["LabelCode"]
+territory_codes = ["US", "CA"]
+
+[validation_rules]
+LabelCode = "Required"
+Genre = { OneOf = ["Rock", "Pop"] }
+"#);
+
+        let preset = load_preset_file(&path).unwrap();
+        assert_eq!(preset.name, "label_album");
+        assert_eq!(preset.config.profile, MessageProfile::AudioAlbum);
+        assert_eq!(preset.config.territory_codes, ["US", "CA"]);
+        assert!(matches!(preset.validation_rules["LabelCode"], ValidationRule::Required));
+        assert!(matches!(&preset.validation_rules["Genre"], ValidationRule::OneOf(genres) if genres.len() == 2));
+        // Rules of the base preset are kept
+        assert!(preset.validation_rules.contains_key("ISRC"));
+    }
+
+    #[test]
+    fn test_load_json_preset_without_base() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write(dir.path(), "video.json", r#"{
+            "name": "label_video",
+            "description": "Label video deliveries",
+            "version": "4.2",
+            "profile": "VideoSingle",
+            "required_fields": ["ISRC", "VideoResource"],
+            "validation_rules": { "ISRC": { "Pattern": "^[A-Z]{2}" } }
+        }"#);
+
+        let preset = load_preset_file(&path).unwrap();
+        assert_eq!(preset.config.version, DdexVersion::Ern42);
+        assert_eq!(preset.required_fields, ["ISRC", "VideoResource"]);
+    }
+
+    #[test]
+    fn test_preset_file_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let error = |content: &str| {
+            let path = write(dir.path(), "bad.toml", content);
+            load_preset_file(&path).unwrap_err().to_string()
+        };
+
+        let message = error("name = \"x\"\ndescription = \"x\"\nextends = \"audio_album\"\n[validation_rules]\nGenree = \"Required\"\n");
+        assert!(message.contains("validation_rules.Genree: unknown field 'Genree'"), "{}", message);
+
+        let message = error("name = \"x\"\ndescription = \"x\"\nextends = \"audio_album\"\n[default_values]\nisrc = \"X\"\n");
+        assert!(message.contains("did you mean 'ISRC'?"), "{}", message);
+
+        let message = error("name = \"x\"\ndescription = \"x\"\nextends = \"audio_album\"\n[validation_rules]\nISRC = { Pattern = \"[\" }\n");
+        assert!(message.contains("validation_rules.ISRC: invalid pattern"), "{}", message);
+
+        let message = error("name = \"x\"\ndescription = \"x\"\nextend = \"audio_album\"\n");
+        assert!(message.contains("unknown field `extend`"), "{}", message);
+
+        let message = error("name = \"x\"\ndescription = \"x\"\n");
+        assert!(message.contains("version is required"), "{}", message);
+    }
+
+    #[test]
+    fn test_load_preset_dir_rejects_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let preset = "name = \"dup\"\ndescription = \"x\"\nextends = \"audio_single\"\n";
+        write(dir.path(), "a.toml", preset);
+        write(dir.path(), "notes.txt", "ignored");
+        assert_eq!(load_preset_dir(dir.path()).unwrap().len(), 1);
+
+        write(dir.path(), "b.toml", preset);
+        let message = load_preset_dir(dir.path()).unwrap_err().to_string();
+        assert!(message.contains("preset 'dup' is already defined"), "{}", message);
+    }
+}
@@ -37,6 +37,7 @@ pub fn audio_album() -> PartnerPreset {
         required_fields: vec![
             "ISRC".to_string(),
             "ReleaseDate".to_string(),
+            "PLine".to_string(),
             "Genre".to_string(),
             "AlbumTitle".to_string(),
             "ArtistName".to_string(),
@@ -100,6 +101,7 @@ pub fn audio_single() -> PartnerPreset {
         required_fields: vec![
             "ISRC".to_string(),
             "ReleaseDate".to_string(),
+            "PLine".to_string(),
             "Genre".to_string(),
             "TrackTitle".to_string(),
             "ArtistName".to_string(),
@@ -165,6 +167,7 @@ pub fn video_single() -> PartnerPreset {
         required_fields: vec![
             "ISRC".to_string(),
             "ReleaseDate".to_string(),
+            "PLine".to_string(),
             "Genre".to_string(),
             "VideoTitle".to_string(),
             "ArtistName".to_string(),
@@ -0,0 +1,119 @@
+//! Provenance of locked presets
+//!
+//! Locking a preset records a SHA-256 hash of its effective configuration:
+//! the preset's rules together with the determinism settings it builds with.
+//! The lock is written into every message built while it holds, so an
+//! auditor can tell exactly which rules produced a file and check that hash
+//! against the preset.
+
+use super::PartnerPreset;
+use crate::determinism::DeterminismConfig;
+use crate::error::BuildError;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+static LOCK_COMMENT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"<!-- ddex-builder:preset-lock preset="([^"]*)" config-sha256="([0-9a-f]{64})" -->"#).unwrap()
+});
+
+/// A locked preset and the hash of the configuration it was locked with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresetLock {
+    /// Name of the locked preset
+    pub preset: String,
+    /// Hex-encoded SHA-256 of the effective configuration
+    pub config_hash: String,
+}
+
+/// Everything that decides how a locked preset builds, in serialization order
+#[derive(Serialize)]
+struct EffectiveConfig<'a> {
+    preset: &'a PartnerPreset,
+    determinism: &'a DeterminismConfig,
+}
+
+impl PresetLock {
+    /// Lock `preset` building with `determinism`
+    pub fn new(preset: &PartnerPreset, determinism: &DeterminismConfig) -> Result<Self, BuildError> {
+        Ok(Self {
+            preset: preset.name.clone(),
+            config_hash: config_hash(preset, determinism)?,
+        })
+    }
+
+    /// Whether `preset` building with `determinism` still has the locked hash
+    pub fn matches(&self, preset: &PartnerPreset, determinism: &DeterminismConfig) -> Result<bool, BuildError> {
+        Ok(preset.name == self.preset && config_hash(preset, determinism)? == self.config_hash)
+    }
+
+    /// The structured comment recording this lock in built messages
+    pub fn to_comment(&self) -> String {
+        format!(
+            "<!-- ddex-builder:preset-lock preset=\"{}\" config-sha256=\"{}\" -->",
+            self.preset,
+            self.config_hash
+        )
+    }
+
+    /// Insert the lock comment right after the XML declaration, or at the start
+    pub fn stamp(&self, xml: &str) -> String {
+        let mut stamped = xml.to_string();
+        let at = match xml.starts_with("<?xml").then(|| xml.find("?>")).flatten() {
+            Some(end) => end + 2,
+            None => 0,
+        };
+        let comment = if at == 0 { format!("{}\n", self.to_comment()) } else { format!("\n{}", self.to_comment()) };
+        stamped.insert_str(at, &comment);
+        stamped
+    }
+
+    /// Read the lock recorded in a built message, if any
+    pub fn from_xml(xml: &str) -> Option<Self> {
+        LOCK_COMMENT.captures(xml).map(|captures| Self {
+            preset: captures[1].to_string(),
+            config_hash: captures[2].to_string(),
+        })
+    }
+}
+
+fn config_hash(preset: &PartnerPreset, determinism: &DeterminismConfig) -> Result<String, BuildError> {
+    let json = serde_json::to_vec(&EffectiveConfig { preset, determinism })?;
+    Ok(hex::encode(Sha256::digest(&json)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::presets::generic;
+
+    #[test]
+    fn test_lock_hash_tracks_effective_config() {
+        let preset = generic::audio_album();
+        let config = DeterminismConfig::default();
+        let lock = PresetLock::new(&preset, &config).unwrap();
+        assert_eq!(lock.config_hash.len(), 64);
+        assert!(lock.matches(&preset, &config).unwrap());
+
+        let mut changed = config.clone();
+        changed.indent_width += 1;
+        assert!(!lock.matches(&preset, &changed).unwrap());
+
+        let mut stricter = preset.clone();
+        stricter.required_fields.push("CLine".to_string());
+        assert!(!lock.matches(&stricter, &config).unwrap());
+    }
+
+    #[test]
+    fn test_stamp_round_trips() {
+        let lock = PresetLock::new(&generic::audio_single(), &DeterminismConfig::default()).unwrap();
+        let xml = lock.stamp("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<ern:NewReleaseMessage/>");
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!-- ddex-builder:preset-lock preset=\"audio_single\""));
+        assert_eq!(PresetLock::from_xml(&xml), Some(lock.clone()));
+
+        let bare = lock.stamp("<ern:NewReleaseMessage/>");
+        assert!(bare.starts_with("<!-- ddex-builder:preset-lock"));
+        assert_eq!(PresetLock::from_xml("<ern:NewReleaseMessage/>"), None);
+    }
+}
@@ -14,6 +14,11 @@
 //! 
 //! ### Platform Presets (Based on Public Documentation)
 //! - **YouTube Music**: Audio and video releases (based on public Partner docs)
+//! - **Amazon Music**: Album and single releases for download and streaming
+//! - **Deezer**: Album and single releases for lossless streaming
+//! - **TIDAL**: Album and single releases for lossless and hi-res streaming
+//! - **Beatport**: Single/EP/album releases with mix names and Beatport genres
+//! - **Pandora**: Album and single releases for US-only streaming
 //! 
 //! ## Architecture
 //! 
@@ -92,6 +97,12 @@
 //! custom_preset.config.territory_codes = vec!["US".to_string(), "CA".to_string()];
 //! ```
 //! 
+//! ## Preset Files
+//!
+//! In-house presets can live in TOML or JSON files and be loaded with
+//! [`Builder::load_preset_file`](crate::Builder::load_preset_file) or from
+//! the directories in `DDEX_BUILDER_PRESET_PATH`; see [`external`].
+//!
 //! ## Validation Rules
 //! 
 //! Presets support comprehensive validation:
@@ -104,7 +115,14 @@
 //! - **TerritoryCode**: Allowed distribution territories
 //! - **Custom**: Partner-specific validation logic
 
+pub mod amazon;
+pub mod beatport;
+pub mod deezer;
+pub mod external;
 pub mod generic;
+pub mod lock;
+pub mod pandora;
+pub mod tidal;
 pub mod youtube;
 
 use indexmap::IndexMap;
@@ -119,6 +137,8 @@ pub enum DdexVersion {
     Ern42,
     #[serde(rename = "ERN/4.3")]
     Ern43,
+    #[serde(rename = "ERN/4.4")]
+    Ern44,
     #[serde(rename = "ERN/4.1")]
     Ern41,
 }
@@ -129,13 +149,14 @@ impl std::fmt::Display for DdexVersion {
             DdexVersion::Ern382 => write!(f, "ERN/3.8.2"),
             DdexVersion::Ern42 => write!(f, "ERN/4.2"),
             DdexVersion::Ern43 => write!(f, "ERN/4.3"),
+            DdexVersion::Ern44 => write!(f, "ERN/4.4"),
             DdexVersion::Ern41 => write!(f, "ERN/4.1"),
         }
     }
 }
 
 /// Message profile enum
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
 pub enum MessageProfile {
     AudioAlbum,
     AudioSingle,
@@ -144,8 +165,24 @@ pub enum MessageProfile {
     Mixed,
 }
 
+impl std::str::FromStr for MessageProfile {
+    type Err = String;
+
+    /// Accepts "AudioAlbum" as well as "audio-album", ignoring case
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_lowercase().as_str() {
+            "audioalbum" | "audio-album" => Ok(MessageProfile::AudioAlbum),
+            "audiosingle" | "audio-single" => Ok(MessageProfile::AudioSingle),
+            "videoalbum" | "video-album" => Ok(MessageProfile::VideoAlbum),
+            "videosingle" | "video-single" => Ok(MessageProfile::VideoSingle),
+            "mixed" => Ok(MessageProfile::Mixed),
+            _ => Err(format!("Unsupported message profile: {}", name)),
+        }
+    }
+}
+
 /// Validation rule types
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub enum ValidationRule {
     Required,
     MinLength(usize),
@@ -192,7 +229,7 @@ pub struct PartnerPreset {
 }
 
 /// Preset source
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
 pub enum PresetSource {
     PublicDocs,
     CustomerFeedback,
@@ -209,19 +246,32 @@ pub struct PresetDefaults {
 
 
 
+/// Get all built-in presets grouped by partner
+///
+/// Keys are display names ("Generic", "YouTube", "Amazon", ...) in the order
+/// presets are listed; values map preset names to presets.
+pub fn presets_by_partner() -> IndexMap<&'static str, IndexMap<String, PartnerPreset>> {
+    let mut partners = IndexMap::new();
+
+    // Generic industry-standard presets
+    partners.insert("Generic", generic::all_generic_presets());
+
+    // Platform presets (based on public documentation)
+    partners.insert("YouTube", youtube::all_youtube_presets());
+    partners.insert("Amazon", amazon::all_amazon_presets());
+    partners.insert("Deezer", deezer::all_deezer_presets());
+    partners.insert("Tidal", tidal::all_tidal_presets());
+    partners.insert("Beatport", beatport::all_beatport_presets());
+    partners.insert("Pandora", pandora::all_pandora_presets());
+
+    partners
+}
+
 /// Get all built-in presets
 /// 
 /// Returns a collection of community-maintained DDEX configuration presets.
 /// These presets provide baseline DDEX-compliant configurations and platform-specific
 /// templates based on publicly available documentation.
 pub fn all_presets() -> IndexMap<String, PartnerPreset> {
-    let mut presets = IndexMap::new();
-    
-    // Generic industry-standard presets
-    presets.extend(generic::all_generic_presets());
-    
-    // Platform presets (based on public documentation)
-    presets.extend(youtube::all_youtube_presets());
-    
-    presets
-}
\ No newline at end of file
+    presets_by_partner().into_values().flatten().collect()
+}
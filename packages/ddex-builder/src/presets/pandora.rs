@@ -0,0 +1,126 @@
+//! Pandora-specific DDEX presets and configurations
+
+use super::{DdexVersion, MessageProfile, PresetConfig, PartnerPreset, PresetDefaults, PresetSource, ValidationRule};
+use indexmap::IndexMap;
+
+/// Pandora Album preset (ERN 4.3)
+///
+/// Pandora only streams in the United States, so deals are limited to US.
+/// Its radio features rely on accurate explicit flags for clean stations.
+pub fn pandora_album() -> PartnerPreset {
+    let mut validation_rules = IndexMap::new();
+    validation_rules.insert("ISRC".to_string(), ValidationRule::Pattern(
+        r"^[A-Z]{2}[A-Z0-9]{3}\d{7}$".to_string()
+    ));
+    validation_rules.insert("UPC".to_string(), ValidationRule::Pattern(r"^\d{12,14}$".to_string()));
+    validation_rules.insert("ReleaseDate".to_string(), ValidationRule::Required);
+    validation_rules.insert("Genre".to_string(), ValidationRule::Required);
+    validation_rules.insert("ParentalWarningType".to_string(), ValidationRule::OneOf(vec![
+        "Explicit".to_string(),
+        "NotExplicit".to_string(),
+        "ExplicitContentEdited".to_string(),
+    ]));
+    validation_rules.insert("TerritoryCode".to_string(), ValidationRule::TerritoryCode {
+        allowed: vec!["US".to_string()],
+    });
+    validation_rules.insert("AudioQuality".to_string(), ValidationRule::AudioQuality {
+        min_bit_depth: 16,
+        min_sample_rate: 44100,
+    });
+    validation_rules.insert("CoverArtSize".to_string(), ValidationRule::Custom(
+        "Square front cover image of at least 1400x1400 pixels".to_string()
+    ));
+
+    let mut default_values = IndexMap::new();
+    default_values.insert("MessageControlType".to_string(), "LiveMessage".to_string());
+    default_values.insert("TerritoryCode".to_string(), "US".to_string());
+    default_values.insert("DistributionChannel".to_string(), "02".to_string()); // Streaming
+    default_values.insert("ReleaseType".to_string(), "Album".to_string());
+
+    let config = PresetConfig {
+        version: DdexVersion::Ern43,
+        profile: MessageProfile::AudioAlbum,
+        required_fields: vec![
+            "ISRC".to_string(),
+            "UPC".to_string(),
+            "ReleaseDate".to_string(),
+            "PLine".to_string(),
+            "Genre".to_string(),
+            "ParentalWarningType".to_string(),
+            "AlbumTitle".to_string(),
+            "ArtistName".to_string(),
+            "TrackTitle".to_string(),
+        ],
+        validation_rules: validation_rules.clone(),
+        default_values,
+        custom_mappings: IndexMap::new(),
+        territory_codes: vec!["US".to_string()],
+        distribution_channels: vec!["02".to_string()],
+        release_types: vec![
+            "Album".to_string(),
+            "CompilationAlbum".to_string(),
+            "LiveAlbum".to_string(),
+            "EP".to_string(),
+        ],
+    };
+
+    PartnerPreset {
+        name: "pandora_album".to_string(),
+        description: "Pandora Album ERN 4.3 for US-only streaming".to_string(),
+        source: PresetSource::PublicDocs,
+        provenance_url: Some("https://amp.pandora.com/".to_string()),
+        version: "1.0.0".to_string(),
+        locked: false,
+        disclaimer: "Based on publicly available Pandora AMP information. This preset is community-maintained and not an official Pandora specification. Verify current delivery requirements with your Pandora contact.".to_string(),
+        determinism: super::super::determinism::DeterminismConfig::default(),
+        defaults: PresetDefaults {
+            message_control_type: Some("LiveMessage".to_string()),
+            territory_code: vec!["US".to_string()],
+            distribution_channel: vec!["02".to_string()],
+        },
+        required_fields: config.required_fields.clone(),
+        format_overrides: IndexMap::new(),
+        config,
+        validation_rules,
+        custom_mappings: IndexMap::new(),
+    }
+}
+
+/// Pandora Single preset (ERN 4.3)
+pub fn pandora_single() -> PartnerPreset {
+    let mut preset = pandora_album();
+
+    preset.name = "pandora_single".to_string();
+    preset.description = "Pandora Single ERN 4.3 for US-only streaming".to_string();
+    preset.config.profile = MessageProfile::AudioSingle;
+    preset.config.release_types = vec!["Single".to_string()];
+    preset.config.default_values.insert("ReleaseType".to_string(), "Single".to_string());
+
+    preset
+}
+
+/// Get all Pandora presets
+pub fn all_pandora_presets() -> IndexMap<String, PartnerPreset> {
+    let mut presets = IndexMap::new();
+    presets.insert("pandora_album".to_string(), pandora_album());
+    presets.insert("pandora_single".to_string(), pandora_single());
+    presets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pandora_presets_are_us_only() {
+        for preset in all_pandora_presets().values() {
+            assert_eq!(preset.config.territory_codes, vec!["US"]);
+            assert_eq!(preset.defaults.territory_code, vec!["US"]);
+            assert!(matches!(
+                &preset.validation_rules["TerritoryCode"],
+                ValidationRule::TerritoryCode { allowed } if allowed == &["US"]
+            ));
+        }
+        assert_eq!(pandora_single().config.profile, MessageProfile::AudioSingle);
+    }
+}
@@ -0,0 +1,132 @@
+//! TIDAL-specific DDEX presets and configurations
+
+use super::{DdexVersion, MessageProfile, PresetConfig, PartnerPreset, PresetDefaults, PresetSource, ValidationRule};
+use indexmap::IndexMap;
+
+/// TIDAL Album preset (ERN 4.3)
+///
+/// TIDAL streams lossless and hi-res audio, so masters go out uncompressed
+/// and at their native resolution, up to 24-bit/192 kHz. Credits matter more
+/// than elsewhere: TIDAL shows contributors prominently.
+pub fn tidal_album() -> PartnerPreset {
+    let mut validation_rules = IndexMap::new();
+    validation_rules.insert("ISRC".to_string(), ValidationRule::Pattern(
+        r"^[A-Z]{2}[A-Z0-9]{3}\d{7}$".to_string()
+    ));
+    validation_rules.insert("UPC".to_string(), ValidationRule::Pattern(r"^\d{12,14}$".to_string()));
+    validation_rules.insert("ReleaseDate".to_string(), ValidationRule::Required);
+    validation_rules.insert("Genre".to_string(), ValidationRule::Required);
+    validation_rules.insert("Contributors".to_string(), ValidationRule::Required);
+    validation_rules.insert("AudioQuality".to_string(), ValidationRule::AudioQuality {
+        min_bit_depth: 16,
+        min_sample_rate: 44100,
+    });
+    validation_rules.insert("AudioCodec".to_string(), ValidationRule::OneOf(vec![
+        "FLAC".to_string(),
+        "PCM".to_string(),
+    ]));
+    validation_rules.insert("SampleRate".to_string(), ValidationRule::OneOf(vec![
+        "44100".to_string(),
+        "48000".to_string(),
+        "88200".to_string(),
+        "96000".to_string(),
+        "176400".to_string(),
+        "192000".to_string(),
+    ]));
+    validation_rules.insert("CoverArtSize".to_string(), ValidationRule::Custom(
+        "Square front cover image of at least 1400x1400 pixels".to_string()
+    ));
+
+    let mut default_values = IndexMap::new();
+    default_values.insert("MessageControlType".to_string(), "LiveMessage".to_string());
+    default_values.insert("TerritoryCode".to_string(), "Worldwide".to_string());
+    default_values.insert("DistributionChannel".to_string(), "02".to_string()); // Streaming
+    default_values.insert("ReleaseType".to_string(), "Album".to_string());
+
+    let config = PresetConfig {
+        version: DdexVersion::Ern43,
+        profile: MessageProfile::AudioAlbum,
+        required_fields: vec![
+            "ISRC".to_string(),
+            "UPC".to_string(),
+            "ReleaseDate".to_string(),
+            "PLine".to_string(),
+            "CLine".to_string(),
+            "Genre".to_string(),
+            "AlbumTitle".to_string(),
+            "ArtistName".to_string(),
+            "TrackTitle".to_string(),
+            "Contributors".to_string(),
+        ],
+        validation_rules: validation_rules.clone(),
+        default_values,
+        custom_mappings: IndexMap::new(),
+        territory_codes: vec!["Worldwide".to_string()],
+        distribution_channels: vec!["02".to_string()],
+        release_types: vec![
+            "Album".to_string(),
+            "CompilationAlbum".to_string(),
+            "LiveAlbum".to_string(),
+            "EP".to_string(),
+        ],
+    };
+
+    PartnerPreset {
+        name: "tidal_album".to_string(),
+        description: "TIDAL Album ERN 4.3 for lossless and hi-res streaming".to_string(),
+        source: PresetSource::PublicDocs,
+        provenance_url: None,
+        version: "1.0.0".to_string(),
+        locked: false,
+        disclaimer: "Based on publicly available TIDAL for Artists information. This preset is community-maintained and not an official TIDAL specification. Verify current delivery requirements with your TIDAL contact.".to_string(),
+        determinism: super::super::determinism::DeterminismConfig::default(),
+        defaults: PresetDefaults {
+            message_control_type: Some("LiveMessage".to_string()),
+            territory_code: vec!["Worldwide".to_string()],
+            distribution_channel: vec!["02".to_string()],
+        },
+        required_fields: config.required_fields.clone(),
+        format_overrides: IndexMap::new(),
+        config,
+        validation_rules,
+        custom_mappings: IndexMap::new(),
+    }
+}
+
+/// TIDAL Single preset (ERN 4.3)
+pub fn tidal_single() -> PartnerPreset {
+    let mut preset = tidal_album();
+
+    preset.name = "tidal_single".to_string();
+    preset.description = "TIDAL Single ERN 4.3 for lossless and hi-res streaming".to_string();
+    preset.config.profile = MessageProfile::AudioSingle;
+    preset.config.release_types = vec!["Single".to_string()];
+    preset.config.default_values.insert("ReleaseType".to_string(), "Single".to_string());
+
+    preset
+}
+
+/// Get all TIDAL presets
+pub fn all_tidal_presets() -> IndexMap<String, PartnerPreset> {
+    let mut presets = IndexMap::new();
+    presets.insert("tidal_album".to_string(), tidal_album());
+    presets.insert("tidal_single".to_string(), tidal_single());
+    presets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tidal_presets() {
+        let album = tidal_album();
+        assert_eq!(album.name, "tidal_album");
+        assert!(album.required_fields.contains(&"Contributors".to_string()));
+        assert!(matches!(&album.validation_rules["SampleRate"], ValidationRule::OneOf(rates) if rates.contains(&"192000".to_string())));
+
+        let single = tidal_single();
+        assert_eq!(single.config.profile, MessageProfile::AudioSingle);
+        assert_eq!(all_tidal_presets().len(), 2);
+    }
+}
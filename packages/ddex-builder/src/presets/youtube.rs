@@ -46,6 +46,7 @@ pub fn youtube_album() -> PartnerPreset {
             "ISRC".to_string(),
             "UPC".to_string(),
             "ReleaseDate".to_string(),
+            "PLine".to_string(),
             "Genre".to_string(),
             "ContentID".to_string(),
             "AlbumTitle".to_string(),
@@ -133,6 +134,7 @@ pub fn youtube_video() -> PartnerPreset {
             "ISRC".to_string(),
             "ISVN".to_string(),
             "ReleaseDate".to_string(),
+            "PLine".to_string(),
             "Genre".to_string(),
             "ContentID".to_string(),
             "VideoResource".to_string(),
@@ -0,0 +1,236 @@
+//! Repricing the deals of delivered messages
+//!
+//! Quarterly repricing moves many deals to new price tiers and changes
+//! nothing else. [`reprice`] applies a set of [`PriceRule`]s to the
+//! `DealTerms` of one message, rewriting only their `PriceInformation` in the
+//! source text, so everything else keeps its bytes.
+//! [`UpdateGenerator::create_price_updates`](crate::messages::UpdateGenerator::create_price_updates)
+//! does the same across a catalog and turns each message whose prices changed
+//! into an update; messages already at the new prices are left out.
+//!
+//! A rule applies to deal terms whose territories all lie within the rule's
+//! and whose commercial model it names; the first matching rule wins, so
+//! list specific rules before general ones. Deal terms spanning territories
+//! that are priced differently keep their price.
+
+use crate::builder::{PriceInformationRequest, PriceRequest};
+use crate::error::BuildError;
+use crate::lint::Document;
+use crate::presets::DdexVersion;
+use crate::split::{apply, leading_whitespace};
+use once_cell::sync::Lazy;
+use quick_xml::escape::escape;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+static CURRENCY_CODE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\sCurrencyCode\s*=\s*["']([^"']*)["']"#).unwrap());
+
+/// The price for the deals of some territories and commercial models
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceRule {
+    /// Territories the rule covers; empty for every territory
+    #[serde(default)]
+    pub territory_codes: Vec<String>,
+    /// Commercial model the rule covers, e.g. `PayAsYouGoModel`; `None` for every model
+    #[serde(default)]
+    pub commercial_model_type: Option<String>,
+    /// Price information replacing that of the covered deals
+    pub price: PriceInformationRequest,
+}
+
+impl PriceRule {
+    fn covers(&self, territories: &[&str], models: &[&str]) -> bool {
+        let model = self
+            .commercial_model_type
+            .as_deref()
+            .is_none_or(|model| models.iter().any(|m| m.eq_ignore_ascii_case(model)));
+        let territory = self.territory_codes.is_empty()
+            || (!territories.is_empty()
+                && territories
+                    .iter()
+                    .all(|t| self.territory_codes.iter().any(|code| code.eq_ignore_ascii_case(t))));
+        model && territory
+    }
+}
+
+/// A message with the prices of its deals rewritten
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repriced {
+    /// MessageId of the message, when it has one
+    pub message_id: Option<String>,
+    /// Number of deal terms whose price changed
+    pub deals: usize,
+    /// The message, identical to the original outside the changed prices
+    pub xml: String,
+}
+
+/// Apply `rules` to the deals of `xml`, or `None` when no price changes
+///
+/// The `PriceInformation` of matched deal terms is replaced by the rule's,
+/// and added after their last child where they have none.
+pub fn reprice(xml: &str, rules: &[PriceRule]) -> Result<Option<Repriced>, BuildError> {
+    let doc = Document::parse(xml)?;
+    // ERN 4 renamed PriceType to PriceCode
+    let code_name = if doc.version == Some(DdexVersion::Ern382) { "PriceType" } else { "PriceCode" };
+
+    let mut edits = Vec::new();
+    let mut deals = 0;
+    for (terms, element) in doc.elements.iter().enumerate().filter(|(_, e)| e.name == "DealTerms") {
+        let texts = |name| doc.children_named(terms, name).map(|(_, e)| e.text.as_str()).collect::<Vec<_>>();
+        let Some(rule) = rules.iter().find(|rule| rule.covers(&texts("TerritoryCode"), &texts("CommercialModelType"))) else {
+            continue;
+        };
+
+        let prices: Vec<usize> = doc.children_named(terms, "PriceInformation").map(|(index, _)| index).collect();
+        if let [price] = prices[..] {
+            if read_price(&doc, price) == rule.price {
+                continue;
+            }
+        }
+
+        let prefix = element.prefix.as_ref().map(|p| format!("{}:", p)).unwrap_or_default();
+        match prices.split_first() {
+            Some((&first, rest)) => {
+                let indent = leading_whitespace(&doc, first);
+                edits.push((doc.elements[first].span(), render(&rule.price, code_name, &prefix, indent)));
+                edits.extend(rest.iter().map(|&price| (doc.removal_range(price), String::new())));
+            }
+            None => match element.children.last() {
+                Some(&last) => {
+                    let indent = leading_whitespace(&doc, last);
+                    let end = doc.elements[last].span().end;
+                    edits.push((end..end, format!("{}{}", indent, render(&rule.price, code_name, &prefix, indent))));
+                }
+                None => continue,
+            },
+        }
+        deals += 1;
+    }
+
+    if edits.is_empty() {
+        return Ok(None);
+    }
+    let message_id = doc
+        .elements
+        .iter()
+        .find(|e| e.name == "MessageId" && e.parent.is_some_and(|p| doc.elements[p].name == "MessageHeader"))
+        .map(|e| e.text.clone());
+    Ok(Some(Repriced { message_id, deals, xml: apply(xml, edits) }))
+}
+
+/// The price information a `PriceInformation` element holds
+fn read_price(doc: &Document<'_>, price: usize) -> PriceInformationRequest {
+    let amount = |name| {
+        doc.children_named(price, name).next().map(|(_, e)| PriceRequest {
+            amount: e.text.clone(),
+            currency_code: CURRENCY_CODE
+                .captures(&doc.source[e.start_tag.clone()])
+                .map(|c| c[1].to_string())
+                .unwrap_or_default(),
+        })
+    };
+    PriceInformationRequest {
+        price_code: doc
+            .children(price)
+            .find(|(_, e)| e.name == "PriceCode" || e.name == "PriceType")
+            .map(|(_, e)| e.text.clone()),
+        wholesale_price: amount("WholesalePricePerUnit"),
+        suggested_retail_price: amount("SuggestedRetailPrice"),
+    }
+}
+
+/// A `PriceInformation` element placed after `indent`, laid out to match
+fn render(price: &PriceInformationRequest, code_name: &str, prefix: &str, indent: &str) -> String {
+    let child_indent = if indent.contains('\n') { format!("{}  ", indent) } else { String::new() };
+    let mut xml = format!("<{}PriceInformation>", prefix);
+    if let Some(code) = &price.price_code {
+        xml.push_str(&format!("{}<{p}{n}>{}</{p}{n}>", child_indent, escape(code.as_str()), p = prefix, n = code_name));
+    }
+    for (name, amount) in [("WholesalePricePerUnit", &price.wholesale_price), ("SuggestedRetailPrice", &price.suggested_retail_price)] {
+        if let Some(amount) = amount {
+            xml.push_str(&format!(
+                "{}<{p}{n} CurrencyCode=\"{}\">{}</{p}{n}>",
+                child_indent,
+                escape(amount.currency_code.as_str()),
+                escape(amount.amount.as_str()),
+                p = prefix,
+                n = name
+            ));
+        }
+    }
+    if !child_indent.is_empty() {
+        xml.push_str(indent);
+    }
+    xml.push_str(&format!("</{}PriceInformation>", prefix));
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CATALOG: &str = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+  <MessageHeader><MessageId>M1</MessageId></MessageHeader>
+  <DealList>
+    <ReleaseDeal>
+      <DealReleaseReference>R1</DealReleaseReference>
+      <Deal>
+        <DealTerms>
+          <CommercialModelType>PayAsYouGoModel</CommercialModelType>
+          <TerritoryCode>US</TerritoryCode>
+          <PriceInformation>
+            <PriceCode>MID</PriceCode>
+            <WholesalePricePerUnit CurrencyCode="USD">0.70</WholesalePricePerUnit>
+          </PriceInformation>
+        </DealTerms>
+      </Deal>
+      <Deal>
+        <DealTerms>
+          <CommercialModelType>PayAsYouGoModel</CommercialModelType>
+          <TerritoryCode>GB</TerritoryCode>
+        </DealTerms>
+      </Deal>
+      <Deal>
+        <DealTerms>
+          <CommercialModelType>SubscriptionModel</CommercialModelType>
+          <TerritoryCode>US</TerritoryCode>
+        </DealTerms>
+      </Deal>
+    </ReleaseDeal>
+  </DealList>
+</ern:NewReleaseMessage>"#;
+
+    fn rule(territories: &[&str], code: &str, amount: &str, currency: &str) -> PriceRule {
+        PriceRule {
+            territory_codes: territories.iter().map(|t| t.to_string()).collect(),
+            commercial_model_type: Some("PayAsYouGoModel".to_string()),
+            price: PriceInformationRequest {
+                price_code: Some(code.to_string()),
+                wholesale_price: Some(PriceRequest { amount: amount.to_string(), currency_code: currency.to_string() }),
+                suggested_retail_price: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_reprice_rewrites_only_prices() {
+        let rules = [rule(&["US"], "FRONT", "0.90", "USD"), rule(&["GB"], "FRONT", "0.80", "GBP")];
+        let repriced = reprice(CATALOG, &rules).unwrap().unwrap();
+        assert_eq!(repriced.message_id.as_deref(), Some("M1"));
+        assert_eq!(repriced.deals, 2);
+
+        let expected = CATALOG
+            .replace("<PriceCode>MID</PriceCode>", "<PriceCode>FRONT</PriceCode>")
+            .replace(">0.70<", ">0.90<")
+            .replace(
+                "<TerritoryCode>GB</TerritoryCode>\n",
+                "<TerritoryCode>GB</TerritoryCode>\n          <PriceInformation>\n            <PriceCode>FRONT</PriceCode>\n            <WholesalePricePerUnit CurrencyCode=\"GBP\">0.80</WholesalePricePerUnit>\n          </PriceInformation>\n",
+            );
+        assert_eq!(repriced.xml, expected);
+
+        // Already at the new prices
+        assert_eq!(reprice(&repriced.xml, &rules).unwrap(), None);
+        // A rule for some of a deal's territories leaves it alone
+        assert_eq!(reprice(CATALOG, &[rule(&["CA"], "FRONT", "0.90", "USD")]).unwrap(), None);
+    }
+}
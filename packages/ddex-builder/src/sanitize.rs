@@ -0,0 +1,225 @@
+//! Cleaning of "dirty" metadata
+//!
+//! Titles and names copied out of spreadsheets, web pages and word
+//! processors carry characters nobody sees: zero-width spaces, byte order
+//! marks, bidirectional marks, stray control characters, decomposed accents
+//! and doubled or non-breaking spaces. DSPs reject such values, and they are
+//! hard to spot by eye. When
+//! [`BuildOptions::sanitize_text`](crate::BuildOptions::sanitize_text) is set,
+//! [`sanitize_request`] runs after [`normalize_request`](crate::normalize::normalize_request)
+//! and before preflight:
+//!
+//! - text is rewritten to Unicode Normalization Form C,
+//! - zero-width, bidirectional and control characters are removed, keeping
+//!   the zero-width joiner and non-joiner between letters of scripts and
+//!   emoji that need them,
+//! - runs of whitespace, tabs and line breaks become a single space, and
+//! - typographic quotes and primes in identifiers become ASCII quotes.
+//!
+//! Every change is recorded in the result's [`Warnings`] like the other
+//! normalizations.
+
+use crate::builder::{BuildRequest, LocalizedStringRequest, PartyRequest};
+use ddex_core::warnings::{NormalizationKind, Warnings};
+use unicode_normalization::UnicodeNormalization;
+
+/// Clean the text and identifiers of `request` in place, recording every
+/// change in `warnings`
+pub fn sanitize_request(request: &mut BuildRequest, warnings: &mut Warnings) {
+    party(&mut request.header.message_sender, "header.message_sender", warnings);
+    party(&mut request.header.message_recipient, "header.message_recipient", warnings);
+
+    for (r, release) in request.releases.iter_mut().enumerate() {
+        let path = format!("releases[{}]", r);
+        identifier(&mut release.release_id, &format!("{}.release_id", path), warnings);
+        localized(&mut release.title, &format!("{}.title", path), warnings);
+        text(&mut release.artist, &format!("{}.artist", path), warnings);
+        if let Some(label) = release.label.as_mut() {
+            text(label, &format!("{}.label", path), warnings);
+        }
+        if let Some(upc) = release.upc.as_mut() {
+            identifier(upc, &format!("{}.upc", path), warnings);
+        }
+        if let Some(line) = release.p_line.as_mut() {
+            text(&mut line.text, &format!("{}.p_line.text", path), warnings);
+        }
+        if let Some(line) = release.c_line.as_mut() {
+            text(&mut line.text, &format!("{}.c_line.text", path), warnings);
+        }
+        for (c, contributor) in release.contributors.iter_mut().enumerate() {
+            text(&mut contributor.name, &format!("{}.contributors[{}].name", path, c), warnings);
+        }
+        for (t, territory) in release.territory_details.iter_mut().enumerate() {
+            let path = format!("{}.territory_details[{}]", path, t);
+            if let Some(title) = territory.title.as_mut() {
+                text(title, &format!("{}.title", path), warnings);
+            }
+            if let Some(artist) = territory.display_artist.as_mut() {
+                text(artist, &format!("{}.display_artist", path), warnings);
+            }
+        }
+        for (t, track) in release.tracks.iter_mut().enumerate() {
+            let path = format!("{}.tracks[{}]", path, t);
+            identifier(&mut track.track_id, &format!("{}.track_id", path), warnings);
+            identifier(&mut track.isrc, &format!("{}.isrc", path), warnings);
+            text(&mut track.title, &format!("{}.title", path), warnings);
+            text(&mut track.artist, &format!("{}.artist", path), warnings);
+            for (c, contributor) in track.contributors.iter_mut().enumerate() {
+                text(&mut contributor.name, &format!("{}.contributors[{}].name", path, c), warnings);
+            }
+        }
+        for (i, resource) in release.resources.iter_mut().enumerate() {
+            let path = format!("{}.resources[{}]", path, i);
+            identifier(&mut resource.resource_id, &format!("{}.resource_id", path), warnings);
+            if let Some(title) = resource.title.as_mut() {
+                text(title, &format!("{}.title", path), warnings);
+            }
+        }
+    }
+
+    for (d, deal) in request.deals.iter_mut().enumerate() {
+        if let Some(reference) = deal.deal_reference.as_mut() {
+            identifier(reference, &format!("deals[{}].deal_reference", d), warnings);
+        }
+    }
+}
+
+fn party(party: &mut PartyRequest, path: &str, warnings: &mut Warnings) {
+    localized(&mut party.party_name, &format!("{}.party_name", path), warnings);
+    if let Some(id) = party.party_id.as_mut() {
+        identifier(id, &format!("{}.party_id", path), warnings);
+    }
+}
+
+fn localized(strings: &mut [LocalizedStringRequest], path: &str, warnings: &mut Warnings) {
+    for (i, string) in strings.iter_mut().enumerate() {
+        text(&mut string.text, &format!("{}[{}].text", path, i), warnings);
+    }
+}
+
+/// Normalize, strip invisible characters and collapse whitespace
+fn text(value: &mut String, path: &str, warnings: &mut Warnings) {
+    apply(value, path, warnings, NormalizationKind::NormalizedUnicode, |v| v.nfc().collect());
+    apply(value, path, warnings, NormalizationKind::RemovedInvisible, remove_invisible);
+    apply(value, path, warnings, NormalizationKind::CollapsedWhitespace, collapse_whitespace);
+}
+
+/// Clean like text, then straighten quotes
+fn identifier(value: &mut String, path: &str, warnings: &mut Warnings) {
+    text(value, path, warnings);
+    apply(value, path, warnings, NormalizationKind::ReplacedQuotes, |v| v.chars().map(ascii_quote).collect());
+}
+
+fn apply(value: &mut String, path: &str, warnings: &mut Warnings, kind: NormalizationKind, clean: impl Fn(&str) -> String) {
+    let cleaned = clean(value);
+    if cleaned != *value {
+        let before = std::mem::replace(value, cleaned.clone());
+        warnings.record(kind, path, before, Some(cleaned));
+    }
+}
+
+/// Zero-width, bidirectional, byte order and control characters, other than
+/// whitespace
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}' | '\u{061C}' | '\u{180E}' | '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{2064}' | '\u{2066}'..='\u{2069}' | '\u{FEFF}'
+    ) || (c.is_control() && !c.is_whitespace())
+}
+
+fn remove_invisible(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    chars
+        .iter()
+        .enumerate()
+        .filter(|&(i, &c)| {
+            // Joiners shape Arabic, Indic and emoji sequences between non-ASCII characters
+            let joins = matches!(c, '\u{200C}' | '\u{200D}')
+                && i > 0
+                && chars.get(i + 1).is_some_and(|next| !next.is_ascii())
+                && !chars[i - 1].is_ascii();
+            joins || !is_invisible(c)
+        })
+        .map(|(_, &c)| c)
+        .collect()
+}
+
+/// Replace runs of whitespace by one space, keeping single spaces of other
+/// kinds (e.g. the ideographic space) that are not line breaks or tabs
+fn collapse_whitespace(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        if !c.is_whitespace() {
+            out.push(c);
+            continue;
+        }
+        let mut run = 1;
+        while chars.next_if(|c| c.is_whitespace()).is_some() {
+            run += 1;
+        }
+        out.push(if run == 1 && !c.is_control() { c } else { ' ' });
+    }
+    out
+}
+
+fn ascii_quote(c: char) -> char {
+    match c {
+        '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' | '\u{2032}' | '\u{02BC}' => '\'',
+        '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' | '\u{2033}' => '"',
+        c => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_request() {
+        let mut request: BuildRequest = serde_json::from_value(serde_json::json!({
+            "header": {
+                "message_sender": { "party_name": [{ "text": "La\u{200B}bel" }], "party_id": "PADPIDA2014\u{2019}" },
+                "message_recipient": { "party_name": [{ "text": "DSP" }] }
+            },
+            "version": "4.3",
+            "releases": [{
+                "release_id": "R\u{2018}1\u{2019}",
+                "title": [{ "text": "Cafe\u{301}  del\tMar" }],
+                "artist": "\u{FEFF}Artist",
+                "tracks": []
+            }],
+            "deals": []
+        }))
+        .unwrap();
+        request.releases[0].label = Some("Label\u{3000}Records".to_string());
+        request.releases[0].contributors = serde_json::from_value(serde_json::json!([
+            { "name": "👩\u{200D}🎤 Singer", "role": "MainArtist" }
+        ]))
+        .unwrap();
+
+        let mut warnings = Warnings::new();
+        sanitize_request(&mut request, &mut warnings);
+        let release = &request.releases[0];
+        assert_eq!(request.header.message_sender.party_name[0].text, "Label");
+        assert_eq!(release.release_id, "R'1'");
+        assert_eq!(release.title[0].text, "Café del Mar");
+        assert_eq!(release.artist, "Artist");
+        assert_eq!(release.label.as_deref(), Some("Label\u{3000}Records"));
+        assert_eq!(release.contributors[0].name, "👩\u{200D}🎤 Singer");
+
+        let changes: Vec<_> = warnings.iter().map(|w| (w.kind, w.path.as_str())).collect();
+        assert_eq!(
+            changes,
+            [
+                (NormalizationKind::RemovedInvisible, "header.message_sender.party_name[0].text"),
+                (NormalizationKind::ReplacedQuotes, "header.message_sender.party_id"),
+                (NormalizationKind::ReplacedQuotes, "releases[0].release_id"),
+                (NormalizationKind::NormalizedUnicode, "releases[0].title[0].text"),
+                (NormalizationKind::CollapsedWhitespace, "releases[0].title[0].text"),
+                (NormalizationKind::RemovedInvisible, "releases[0].artist"),
+            ]
+        );
+    }
+}
@@ -647,6 +647,9 @@ impl SchemaGenerator {
         
         // Add version-specific conditional logic
         match self.version {
+            DdexVersion::Ern44 => {
+                // ERN 4.4 specific features
+            },
             DdexVersion::Ern43 => {
                 // ERN 4.3 specific features
             },
@@ -38,6 +38,9 @@
 //! - **BuildRequest**: Complete DDEX build request structure
 //! - **FlatRelease**: Simplified release representation
 //! - **Complete Schema**: All DDEX types with cross-references
+//! - **Payload Schemas**: [`SchemaTarget`] derives the schemas of `BuildRequest`,
+//!   the parser's graph and flattened messages and preset files from the Rust
+//!   types; [`generate_typings`] renders them as TypeScript or Python typings
 //!
 //! ### Output Formats
 //! - **JSON Schema**: Standards-compliant validation schemas
@@ -142,10 +145,14 @@ use serde_json::{json, Value as JsonValue};
 use indexmap::IndexMap;
 
 mod generators;
+mod models;
+mod typings;
 mod validation;
 mod types;
 
 // Re-export public items from submodules - only what we need publicly
+pub use models::SchemaTarget;
+pub use typings::{diff_typings, generate_typings, TypingsLanguage};
 pub use validation::{SchemaValidator, ValidationConfig as SchemaValidationConfig, ValidationResult as SchemaValidationResult};
 
 /// Main JSON Schema generator for DDEX models
@@ -496,6 +503,7 @@ impl SchemaGenerator {
     
     fn version_string(&self) -> &str {
         match self.version {
+            DdexVersion::Ern44 => "4.4",
             DdexVersion::Ern43 => "4.3",
             DdexVersion::Ern42 => "4.2",
             DdexVersion::Ern41 => "4.1",
@@ -704,6 +712,7 @@ impl SchemaCommand {
     
     fn parse_version(&self) -> Result<DdexVersion, BuildError> {
         match self.version.as_str() {
+            "4.4" | "44" => Ok(DdexVersion::Ern44),
             "4.3" | "43" => Ok(DdexVersion::Ern43),
             "4.2" | "42" => Ok(DdexVersion::Ern42),
             "4.1" | "41" => Ok(DdexVersion::Ern41),
@@ -716,17 +725,10 @@ impl SchemaCommand {
     }
     
     fn parse_profile(&self) -> Result<MessageProfile, BuildError> {
-        match self.profile.to_lowercase().as_str() {
-            "audioalbum" | "audio-album" => Ok(MessageProfile::AudioAlbum),
-            "audiosingle" | "audio-single" => Ok(MessageProfile::AudioSingle),
-            "videoalbum" | "video-album" => Ok(MessageProfile::VideoAlbum),
-            "videosingle" | "video-single" => Ok(MessageProfile::VideoSingle),
-            "mixed" => Ok(MessageProfile::Mixed),
-            _ => Err(BuildError::InvalidFormat {
-                field: "profile".to_string(),
-                message: format!("Unsupported message profile: {}", self.profile),
-            }),
-        }
+        self.profile.parse().map_err(|message| BuildError::InvalidFormat {
+            field: "profile".to_string(),
+            message,
+        })
     }
 }
 
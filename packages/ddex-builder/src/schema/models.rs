@@ -0,0 +1,106 @@
+//! JSON Schemas of the builder's own payloads
+//!
+//! Unlike the message schemas of [`SchemaGenerator`](super::SchemaGenerator),
+//! these are derived from the Rust types, so they accept exactly what the
+//! builder accepts, independent of DDEX version and profile. External
+//! systems can use them to validate payloads before calling the builder.
+
+use crate::builder::BuildRequest;
+use crate::presets::external::PresetFile;
+use ddex_core::models::flat::FlattenedMessage;
+use ddex_core::models::graph::ERNMessage;
+use schemars::generate::SchemaSettings;
+use serde_json::Value as JsonValue;
+
+/// Payload a schema can be generated for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaTarget {
+    /// [`BuildRequest`], the input of a build
+    BuildRequest,
+    /// [`FlattenedMessage`], the parser's flattened output
+    FlattenedMessage,
+    /// [`ERNMessage`], the parser's graph output
+    GraphMessage,
+    /// Custom preset files, see [`presets::external`](crate::presets::external)
+    Preset,
+}
+
+impl SchemaTarget {
+    /// Every target, in the order of [`name`](Self::name)s listed by the CLI
+    pub const ALL: [SchemaTarget; 4] = [
+        SchemaTarget::BuildRequest,
+        SchemaTarget::FlattenedMessage,
+        SchemaTarget::GraphMessage,
+        SchemaTarget::Preset,
+    ];
+
+    /// Kebab-case name, as accepted by `ddex-builder schema --target`
+    pub fn name(self) -> &'static str {
+        match self {
+            SchemaTarget::BuildRequest => "build-request",
+            SchemaTarget::FlattenedMessage => "flattened-message",
+            SchemaTarget::GraphMessage => "graph-message",
+            SchemaTarget::Preset => "preset",
+        }
+    }
+
+    /// JSON Schema (draft 2020-12) of the payload, with nested types in `$defs`
+    pub fn json_schema(self) -> JsonValue {
+        let generator = SchemaSettings::draft2020_12().into_generator();
+        let schema = match self {
+            SchemaTarget::BuildRequest => generator.into_root_schema_for::<BuildRequest>(),
+            SchemaTarget::FlattenedMessage => generator.into_root_schema_for::<FlattenedMessage>(),
+            SchemaTarget::GraphMessage => generator.into_root_schema_for::<ERNMessage>(),
+            SchemaTarget::Preset => generator.into_root_schema_for::<PresetFile>(),
+        };
+        schema.to_value()
+    }
+}
+
+impl std::str::FromStr for SchemaTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SchemaTarget::ALL
+            .into_iter()
+            .find(|target| target.name() == s)
+            .ok_or_else(|| format!("unknown schema target '{}'", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_request_schema() {
+        let schema = SchemaTarget::BuildRequest.json_schema();
+        assert_eq!(schema["$schema"], "https://json-schema.org/draft/2020-12/schema");
+        assert_eq!(schema["title"], "BuildRequest");
+        let required: Vec<_> = schema["required"].as_array().unwrap().iter().filter_map(|v| v.as_str()).collect();
+        assert!(required.contains(&"header") && required.contains(&"releases"));
+        assert!(!required.contains(&"deals"));
+        let release = &schema["$defs"]["ReleaseRequest"];
+        assert!(release["properties"]["track_isrcs"].is_object());
+        assert!(schema["$defs"]["Comment"].is_object());
+    }
+
+    #[test]
+    fn test_flattened_message_and_preset_schemas() {
+        let flattened = SchemaTarget::FlattenedMessage.json_schema();
+        assert!(flattened["properties"]["releases"].is_object());
+        assert!(flattened["$defs"]["ParsedRelease"].is_object());
+
+        let preset = SchemaTarget::Preset.json_schema();
+        assert_eq!(preset["additionalProperties"], false);
+        assert!(preset["properties"]["validation_rules"].is_object());
+    }
+
+    #[test]
+    fn test_target_names() {
+        for target in SchemaTarget::ALL {
+            assert_eq!(target.name().parse::<SchemaTarget>(), Ok(target));
+        }
+        assert!("release".parse::<SchemaTarget>().is_err());
+    }
+}
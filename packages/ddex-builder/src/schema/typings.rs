@@ -0,0 +1,464 @@
+//! TypeScript and Python typings of the builder's and parser's models
+//!
+//! The typings are rendered from the JSON Schemas of [`SchemaTarget`], so
+//! they follow the Rust models, including `Extensions` and comments. The
+//! output carries no timestamp: the files shipped with the Node and Python
+//! packages are compared against freshly generated typings with
+//! [`diff_typings`] (`ddex-builder schema diff` in CI), which fails as soon
+//! as a model change is not reflected in them.
+
+use super::models::SchemaTarget;
+use crate::error::BuildError;
+use serde_json::{Map, Value as JsonValue};
+use std::collections::BTreeMap;
+
+/// Language of generated typings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypingsLanguage {
+    /// TypeScript declarations (`.d.ts`)
+    TypeScript,
+    /// Python `TypedDict` classes and type aliases
+    Python,
+    /// Pydantic v2 models, for validating payloads at runtime
+    Pydantic,
+}
+
+/// Typings of every type reachable from `targets`, sorted by type name
+///
+/// Types shared by several targets are declared once; two different types
+/// with the same name are an error.
+pub fn generate_typings(targets: &[SchemaTarget], language: TypingsLanguage) -> Result<String, BuildError> {
+    let definitions = collect_definitions(targets)?;
+    let names: Vec<&str> = targets.iter().map(|target| target.name()).collect();
+    Ok(match language {
+        TypingsLanguage::TypeScript => typescript(&definitions, &names),
+        TypingsLanguage::Python => python(&definitions, &names),
+        TypingsLanguage::Pydantic => pydantic(&definitions, &names),
+    })
+}
+
+/// Unified diff from `shipped` to `generated` typings, `None` if they match
+pub fn diff_typings(shipped: &str, generated: &str) -> Option<String> {
+    if shipped == generated {
+        return None;
+    }
+    Some(
+        similar::TextDiff::from_lines(shipped, generated)
+            .unified_diff()
+            .context_radius(2)
+            .header("shipped", "generated")
+            .to_string(),
+    )
+}
+
+fn collect_definitions(targets: &[SchemaTarget]) -> Result<BTreeMap<String, JsonValue>, BuildError> {
+    let mut definitions = BTreeMap::new();
+    for target in targets {
+        let JsonValue::Object(mut root) = target.json_schema() else {
+            continue;
+        };
+        let defs = match root.remove("$defs") {
+            Some(JsonValue::Object(defs)) => defs,
+            _ => Map::new(),
+        };
+        root.remove("$schema");
+        let name = root.remove("title")
+            .and_then(|title| title.as_str().map(str::to_string))
+            .unwrap_or_else(|| target.name().to_string());
+        for (name, schema) in defs.into_iter().chain([(name, JsonValue::Object(root))]) {
+            match definitions.get(&name) {
+                Some(existing) if *existing != schema => {
+                    return Err(BuildError::Other(format!(
+                        "type {} has different schemas in the selected targets", name
+                    )));
+                }
+                Some(_) => {}
+                None => {
+                    definitions.insert(name, schema);
+                }
+            }
+        }
+    }
+    Ok(definitions)
+}
+
+fn description(schema: &JsonValue) -> Option<&str> {
+    schema.get("description").and_then(JsonValue::as_str)
+}
+
+fn reference(schema: &JsonValue) -> Option<&str> {
+    schema.get("$ref")
+        .and_then(JsonValue::as_str)
+        .map(|reference| reference.rsplit('/').next().unwrap_or(reference))
+}
+
+fn types(schema: &JsonValue) -> Vec<&str> {
+    match schema.get("type") {
+        Some(JsonValue::String(kind)) => vec![kind.as_str()],
+        Some(JsonValue::Array(kinds)) => kinds.iter().filter_map(JsonValue::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn required(schema: &JsonValue) -> Vec<&str> {
+    schema.get("required")
+        .and_then(JsonValue::as_array)
+        .map(|names| names.iter().filter_map(JsonValue::as_str).collect())
+        .unwrap_or_default()
+}
+
+fn variants(schema: &JsonValue) -> Option<&Vec<JsonValue>> {
+    schema.get("oneOf").or_else(|| schema.get("anyOf")).and_then(JsonValue::as_array)
+}
+
+fn header(comment: &str, names: &[&str]) -> String {
+    format!(
+        "{c} Generated by `ddex-builder schema --format <language> --target {}`.\n{c} Do not edit: regenerate after changing the Rust models.\n",
+        names.join(" --target "),
+        c = comment,
+    )
+}
+
+// TypeScript
+
+fn typescript(definitions: &BTreeMap<String, JsonValue>, names: &[&str]) -> String {
+    let mut output = header("//", names);
+    for (name, schema) in definitions {
+        output.push('\n');
+        if let Some(text) = description(schema) {
+            output.push_str(&ts_doc(text, ""));
+        }
+        match schema.get("properties").and_then(JsonValue::as_object) {
+            Some(properties) if types(schema) == ["object"] => {
+                output.push_str(&format!("export interface {} {{\n", name));
+                output.push_str(&ts_fields(properties, &required(schema), "  "));
+                output.push_str("}\n");
+            }
+            _ => output.push_str(&format!("export type {} = {};\n", name, ts_type(schema))),
+        }
+    }
+    output
+}
+
+fn ts_doc(text: &str, indent: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.len() == 1 {
+        return format!("{}/** {} */\n", indent, lines[0]);
+    }
+    let mut doc = format!("{}/**\n", indent);
+    for line in lines {
+        doc.push_str(&format!("{} *{}{}\n", indent, if line.is_empty() { "" } else { " " }, line));
+    }
+    doc.push_str(&format!("{} */\n", indent));
+    doc
+}
+
+fn ts_fields(properties: &Map<String, JsonValue>, required: &[&str], indent: &str) -> String {
+    let mut fields = String::new();
+    for (field, schema) in properties {
+        if let Some(text) = description(schema) {
+            fields.push_str(&ts_doc(text, indent));
+        }
+        let optional = if required.contains(&field.as_str()) { "" } else { "?" };
+        fields.push_str(&format!("{}{}{}: {};\n", indent, ts_key(field), optional, ts_type(schema)));
+    }
+    fields
+}
+
+fn ts_key(field: &str) -> String {
+    if field.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+        && !field.starts_with(|c: char| c.is_ascii_digit())
+    {
+        field.to_string()
+    } else {
+        format!("{:?}", field)
+    }
+}
+
+fn ts_type(schema: &JsonValue) -> String {
+    if schema.as_bool() == Some(true) {
+        return "unknown".to_string();
+    }
+    if let Some(name) = reference(schema) {
+        return name.to_string();
+    }
+    if let Some(value) = schema.get("const") {
+        return value.to_string();
+    }
+    if let Some(values) = schema.get("enum").and_then(JsonValue::as_array) {
+        return values.iter().map(JsonValue::to_string).collect::<Vec<_>>().join(" | ");
+    }
+    if let Some(variants) = variants(schema) {
+        return ts_union(variants.iter().map(ts_type));
+    }
+    let kinds = types(schema);
+    if kinds.is_empty() {
+        return "unknown".to_string();
+    }
+    ts_union(kinds.into_iter().map(|kind| match kind {
+        "string" => "string".to_string(),
+        "integer" | "number" => "number".to_string(),
+        "boolean" => "boolean".to_string(),
+        "null" => "null".to_string(),
+        "array" => {
+            let item = schema.get("items").map(ts_type).unwrap_or_else(|| "unknown".to_string());
+            if item.contains(' ') { format!("({})[]", item) } else { format!("{}[]", item) }
+        }
+        _ => match schema.get("properties").and_then(JsonValue::as_object) {
+            Some(properties) => {
+                let fields = ts_fields(properties, &required(schema), "");
+                format!("{{ {} }}", fields.lines().filter(|line| !line.starts_with("/**")).collect::<Vec<_>>().join(" "))
+            }
+            None => {
+                let value = match schema.get("additionalProperties") {
+                    Some(value) if value.is_object() => ts_type(value),
+                    _ => "unknown".to_string(),
+                };
+                format!("Record<string, {}>", value)
+            }
+        },
+    }))
+}
+
+fn ts_union(members: impl Iterator<Item = String>) -> String {
+    let mut unique: Vec<String> = Vec::new();
+    for member in members {
+        if !unique.contains(&member) {
+            unique.push(member);
+        }
+    }
+    unique.join(" | ")
+}
+
+// Python
+
+fn python(definitions: &BTreeMap<String, JsonValue>, names: &[&str]) -> String {
+    let mut output = header("#", names);
+    output.push_str("\nfrom __future__ import annotations\n\n");
+    output.push_str("from typing import Any, Dict, List, Literal, Optional, TypedDict, Union\n");
+
+    let (classes, aliases) = py_partition(definitions);
+    for (name, schema) in classes {
+        let (mandatory, optional) = py_fields(schema);
+        let docs = description(schema);
+        match (mandatory.is_empty(), optional.is_empty()) {
+            (false, false) => {
+                output.push_str(&py_class(&format!("_{}Required", name), "TypedDict", None, &mandatory, &[]));
+                output.push_str(&py_class(name, &format!("_{}Required, total=False", name), docs, &optional, &[]));
+            }
+            (false, true) => output.push_str(&py_class(name, "TypedDict", docs, &mandatory, &[])),
+            (true, _) => output.push_str(&py_class(name, "TypedDict, total=False", docs, &optional, &[])),
+        }
+    }
+    output.push_str(&py_aliases(&aliases));
+    output
+}
+
+/// Pydantic models: one class per object type, with the fields a payload
+/// may omit defaulting to `None`
+fn pydantic(definitions: &BTreeMap<String, JsonValue>, names: &[&str]) -> String {
+    let mut output = header("#", names);
+    output.push_str("\nfrom __future__ import annotations\n\n");
+    output.push_str("from typing import Any, Dict, List, Literal, Optional, Union\n\n");
+    output.push_str("from pydantic import BaseModel, ConfigDict\n");
+    output.push_str("\n\nclass _Model(BaseModel):\n    model_config = ConfigDict(protected_namespaces=())\n");
+
+    let (classes, aliases) = py_partition(definitions);
+    for (name, schema) in classes {
+        let (mandatory, optional) = py_fields(schema);
+        output.push_str(&py_class(name, "_Model", description(schema), &mandatory, &optional));
+    }
+    output.push_str(&py_aliases(&aliases));
+
+    // Fields may name aliases declared after the classes
+    output.push_str("\n\nfor _model in list(globals().values()):\n");
+    output.push_str("    if isinstance(_model, type) and issubclass(_model, _Model) and _model is not _Model:\n");
+    output.push_str("        _model.model_rebuild()\n");
+    output
+}
+
+type PyDefinition<'a> = (&'a String, &'a JsonValue);
+
+/// Classes first: aliases are evaluated at import time and may name them
+fn py_partition(definitions: &BTreeMap<String, JsonValue>) -> (Vec<PyDefinition<'_>>, Vec<PyDefinition<'_>>) {
+    definitions.iter().partition(|(_, schema)| {
+        types(schema) == ["object"] && schema.get("properties").is_some()
+    })
+}
+
+/// Required and optional properties of an object schema, each in schema order
+fn py_fields(schema: &JsonValue) -> (Vec<PyDefinition<'_>>, Vec<PyDefinition<'_>>) {
+    let required = required(schema);
+    schema["properties"].as_object()
+        .map(|properties| properties.iter().partition(|(field, _)| required.contains(&field.as_str())))
+        .unwrap_or_default()
+}
+
+fn py_aliases(aliases: &[PyDefinition<'_>]) -> String {
+    let mut output = String::new();
+    if !aliases.is_empty() {
+        output.push('\n');
+    }
+    for (name, schema) in aliases {
+        output.push('\n');
+        if let Some(text) = description(schema) {
+            for line in text.lines() {
+                output.push_str(&format!("#{}{}\n", if line.is_empty() { "" } else { " " }, line));
+            }
+        }
+        output.push_str(&format!("{} = {}\n", name, py_type(schema, true)));
+    }
+    output
+}
+
+/// A class of `fields`, then of `defaulted` fields that default to `None`
+fn py_class(
+    name: &str,
+    bases: &str,
+    docs: Option<&str>,
+    fields: &[PyDefinition<'_>],
+    defaulted: &[PyDefinition<'_>],
+) -> String {
+    let mut class = format!("\n\nclass {}({}):\n", name, bases);
+    if let Some(text) = docs {
+        class.push_str(&format!("    \"\"\"{}\"\"\"\n", text.replace("\"\"\"", "'''").trim_end()));
+        if !fields.is_empty() || !defaulted.is_empty() {
+            class.push('\n');
+        }
+    }
+    let lines = fields.iter().map(|field| (field, None)).chain(defaulted.iter().map(|field| (field, Some(" = None"))));
+    for ((field, schema), default) in lines {
+        if let Some(text) = description(schema) {
+            for line in text.lines() {
+                class.push_str(&format!("    #{}{}\n", if line.is_empty() { "" } else { " " }, line));
+            }
+        }
+        let mut annotation = py_type(schema, false);
+        if default.is_some() && annotation != "Any" && !annotation.starts_with("Optional[") {
+            annotation = format!("Optional[{}]", annotation);
+        }
+        class.push_str(&format!("    {}: {}{}\n", field, annotation, default.unwrap_or("")));
+    }
+    if docs.is_none() && fields.is_empty() && defaulted.is_empty() {
+        class.push_str("    pass\n");
+    }
+    class
+}
+
+/// Python type expression; `quoted` references are forward references
+/// evaluated at runtime (in aliases), so they are written as strings
+fn py_type(schema: &JsonValue, quoted: bool) -> String {
+    if schema.as_bool() == Some(true) {
+        return "Any".to_string();
+    }
+    if let Some(name) = reference(schema) {
+        return if quoted { format!("{:?}", name) } else { name.to_string() };
+    }
+    if let Some(value) = schema.get("const") {
+        return format!("Literal[{}]", value);
+    }
+    if let Some(values) = schema.get("enum").and_then(JsonValue::as_array) {
+        return format!("Literal[{}]", values.iter().map(JsonValue::to_string).collect::<Vec<_>>().join(", "));
+    }
+    if let Some(variants) = variants(schema) {
+        return py_union(variants.iter().map(|variant| py_type(variant, quoted)).collect());
+    }
+    let kinds = types(schema);
+    if kinds.is_empty() {
+        return "Any".to_string();
+    }
+    py_union(kinds.into_iter().map(|kind| match kind {
+        "string" => "str".to_string(),
+        "integer" => "int".to_string(),
+        "number" => "float".to_string(),
+        "boolean" => "bool".to_string(),
+        "null" => "None".to_string(),
+        "array" => format!("List[{}]", schema.get("items").map(|item| py_type(item, quoted)).unwrap_or_else(|| "Any".to_string())),
+        _ => {
+            let value = match (schema.get("properties").and_then(JsonValue::as_object), schema.get("additionalProperties")) {
+                (Some(properties), _) => py_union(properties.values().map(|value| py_type(value, quoted)).collect()),
+                (None, Some(value)) if value.is_object() => py_type(value, quoted),
+                _ => "Any".to_string(),
+            };
+            format!("Dict[str, {}]", value)
+        }
+    }).collect())
+}
+
+fn py_union(members: Vec<String>) -> String {
+    let mut unique: Vec<String> = Vec::new();
+    let mut literals: Vec<String> = Vec::new();
+    for member in members {
+        if let Some(values) = member.strip_prefix("Literal[").and_then(|rest| rest.strip_suffix(']')) {
+            // Merged into a single Literal in front of the other members
+            if literals.is_empty() {
+                unique.push(String::new());
+            }
+            literals.push(values.to_string());
+        } else if !unique.contains(&member) {
+            unique.push(member);
+        }
+    }
+    if let Some(slot) = unique.iter_mut().find(|member| member.is_empty()) {
+        *slot = format!("Literal[{}]", literals.join(", "));
+    }
+    let nullable = unique.iter().any(|member| member == "None");
+    unique.retain(|member| member != "None");
+    let inner = match unique.len() {
+        0 => "None".to_string(),
+        1 => unique.remove(0),
+        _ => format!("Union[{}]", unique.join(", ")),
+    };
+    if nullable && inner != "None" { format!("Optional[{}]", inner) } else { inner }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typescript_typings() {
+        let typings = generate_typings(&[SchemaTarget::BuildRequest], TypingsLanguage::TypeScript).unwrap();
+        assert!(typings.starts_with("// Generated by `ddex-builder schema --format <language> --target build-request`"));
+        assert!(typings.contains("export interface BuildRequest {\n"));
+        assert!(typings.contains("  releases: ReleaseRequest[];\n"));
+        assert!(typings.contains("  deals?: DealRequest[];\n"));
+        assert!(typings.contains("  profile?: string | null;\n"));
+        assert!(typings.contains("export type ResourceKind = "));
+    }
+
+    #[test]
+    fn test_python_typings() {
+        let typings = generate_typings(
+            &[SchemaTarget::GraphMessage, SchemaTarget::FlattenedMessage],
+            TypingsLanguage::Python,
+        )
+        .unwrap();
+        assert!(typings.contains("class FlattenedMessage(_FlattenedMessageRequired, total=False):\n"));
+        assert!(typings.contains("class Extensions("));
+        assert!(typings.contains("    releases: List[ParsedRelease]\n"));
+        assert!(typings.contains("CommentPosition = Literal[\"Before\", \"FirstChild\","));
+    }
+
+    #[test]
+    fn test_pydantic_models() {
+        let models = generate_typings(&[SchemaTarget::FlattenedMessage], TypingsLanguage::Pydantic).unwrap();
+        assert!(models.contains("from pydantic import BaseModel, ConfigDict\n"));
+        assert!(models.contains("class FlattenedMessage(_Model):\n"));
+        assert!(models.contains("    releases: List[ParsedRelease]\n"));
+        assert!(models.contains(": Optional[") && models.contains("] = None\n"));
+        assert!(models.trim_end().ends_with("_model.model_rebuild()"));
+    }
+
+    #[test]
+    fn test_typings_are_stable() {
+        let targets = [SchemaTarget::BuildRequest, SchemaTarget::Preset];
+        let first = generate_typings(&targets, TypingsLanguage::TypeScript).unwrap();
+        let second = generate_typings(&targets, TypingsLanguage::TypeScript).unwrap();
+        assert_eq!(diff_typings(&first, &second), None);
+
+        let stale = first.replace("  releases: ReleaseRequest[];\n", "");
+        let diff = diff_typings(&stale, &first).unwrap();
+        assert!(diff.contains("+  releases: ReleaseRequest[];"));
+    }
+}
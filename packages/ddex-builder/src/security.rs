@@ -8,7 +8,7 @@
 //! - Safe XML parsing configuration
 
 pub mod path_validator;
-pub mod entity_classifier;
+pub use ddex_core::security::entity_classifier;
 pub mod error_sanitizer;
 
 // Re-export entity classifier types for public use
@@ -0,0 +1,399 @@
+//! The suite as an HTTP service
+//!
+//! [`router`] exposes parsing, building, validation, diffing and version
+//! conversion over REST, for teams that integrate from a language without a
+//! binding. Bodies use the library's own serde shapes, the same JSON the
+//! bindings hand out:
+//!
+//! | Endpoint | Body | Response |
+//! |----------|------|----------|
+//! | `POST /parse` | DDEX XML | `ParsedERNMessage` |
+//! | `POST /build?preset=&version=` | `BuildRequest` JSON | [`BuildResponse`] |
+//! | `POST /validate?preset=` | DDEX XML, or a `BuildRequest` with a preset | [`ValidateResponse`] |
+//! | `POST /diff` | [`DiffRequest`] | [`ChangeSet`] |
+//! | `POST /convert?to=&from=&allow_lossy=` | DDEX XML | [`ConvertResponse`] |
+//! | `GET /presets` | | preset names |
+//! | `GET /health` | | `{"status": "ok"}` |
+//!
+//! Failures answer with an [`FFIError`] body: 400 for input the suite
+//! rejects, 413 over [`ServerConfig::max_request_bytes`], 408 past
+//! [`ServerConfig::timeout`] and 500 for everything else.
+//!
+//! ```no_run
+//! use ddex_builder::server::{serve, ServerConfig};
+//!
+//! # async fn run() -> std::io::Result<()> {
+//! let config = ServerConfig { default_preset: Some("spotify_audio_43".to_string()), ..Default::default() };
+//! serve("127.0.0.1:8080".parse().unwrap(), config).await
+//! # }
+//! ```
+
+use crate::compliance::ComplianceReport;
+use crate::diff::{DiffConfig, DiffEngine};
+use crate::error::BuildError;
+use crate::presets::{external::parse_version, DdexVersion};
+use crate::versions::{ConversionOptions, ConverterReport, ConverterResult};
+use crate::{BuildRequest, Builder, ChangeSet};
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use ddex_core::ffi::{FFIError, FFIErrorCategory, FFIErrorSeverity};
+use ddex_core::models::flat::ParsedERNMessage;
+use ddex_core::models::versions::ERNVersion;
+use ddex_core::schema::{SchemaValidator, SchemaViolation};
+use ddex_core::warnings::Warnings;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tower_http::cors::CorsLayer;
+use tower_http::timeout::TimeoutLayer;
+use tower_http::trace::TraceLayer;
+
+/// Limits and defaults of a server
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerConfig {
+    /// Largest request body accepted, 10 MB by default
+    pub max_request_bytes: usize,
+    /// Time a request may take before it is answered with 408
+    pub timeout: Duration,
+    /// Preset applied to requests that do not name one
+    pub default_preset: Option<String>,
+    /// Answer CORS preflights and allow any origin
+    pub cors: bool,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            max_request_bytes: 10 * 1024 * 1024,
+            timeout: Duration::from_secs(30),
+            default_preset: None,
+            cors: false,
+        }
+    }
+}
+
+/// Response of `POST /build`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildResponse {
+    /// The built message
+    pub xml: String,
+    /// Fields of the request that were ignored or changed
+    pub warnings: Warnings,
+    /// Preset the message was built with
+    pub preset: Option<String>,
+}
+
+/// Response of `POST /validate`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidateResponse {
+    /// No schema violation and no failed preset rule
+    pub valid: bool,
+    /// Detected version of an XML body
+    pub version: Option<DdexVersion>,
+    /// Schema violations of an XML body
+    pub violations: Vec<SchemaViolation>,
+    /// Preset rule checks of a build request body
+    pub compliance: Option<ComplianceReport>,
+}
+
+/// Body of `POST /diff`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffRequest {
+    /// Earlier message
+    pub old: String,
+    /// Later message
+    pub new: String,
+    /// Report whitespace and formatting changes too
+    #[serde(default)]
+    pub include_formatting: bool,
+}
+
+/// Response of `POST /convert`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConvertResponse {
+    /// The converted message
+    pub xml: String,
+    /// What was converted, dropped or approximated
+    pub report: ConverterReport,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PresetQuery {
+    preset: Option<String>,
+    version: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConvertQuery {
+    to: String,
+    from: Option<String>,
+    #[serde(default)]
+    allow_lossy: bool,
+}
+
+/// A failed request: status and [`FFIError`] body
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    error: Box<FFIError>,
+}
+
+impl ApiError {
+    fn bad_request(field: &str, message: impl Into<String>) -> Self {
+        BuildError::InvalidFormat { field: field.to_string(), message: message.into() }.into()
+    }
+}
+
+impl From<FFIError> for ApiError {
+    fn from(error: FFIError) -> Self {
+        let status = match error.category {
+            FFIErrorCategory::Io | FFIErrorCategory::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        Self { status, error: Box::new(error) }
+    }
+}
+
+impl From<BuildError> for ApiError {
+    fn from(error: BuildError) -> Self {
+        FFIError::from(error).into()
+    }
+}
+
+impl From<ddex_parser::error::ParseError> for ApiError {
+    fn from(error: ddex_parser::error::ParseError) -> Self {
+        FFIError::from(error).into()
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(*self.error)).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+/// Routes of the service
+pub fn router(config: ServerConfig) -> Router {
+    let max_request_bytes = config.max_request_bytes;
+    let timeout = config.timeout;
+    let cors = config.cors;
+    let router = Router::new()
+        .route("/parse", post(parse))
+        .route("/build", post(build))
+        .route("/validate", post(validate))
+        .route("/diff", post(diff))
+        .route("/convert", post(convert))
+        .route("/presets", get(presets))
+        .route("/health", get(|| async { Json(serde_json::json!({ "status": "ok" })) }))
+        .with_state(Arc::new(config))
+        .layer(DefaultBodyLimit::max(max_request_bytes))
+        .layer(TimeoutLayer::with_status_code(StatusCode::REQUEST_TIMEOUT, timeout))
+        .layer(TraceLayer::new_for_http());
+    if cors {
+        router.layer(CorsLayer::permissive())
+    } else {
+        router
+    }
+}
+
+/// Serve [`router`] on `addr` until the process is stopped
+pub async fn serve(addr: SocketAddr, config: ServerConfig) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, router(config)).await
+}
+
+/// Run CPU-bound work off the async workers
+async fn blocking<T: Send + 'static>(work: impl FnOnce() -> Result<T, ApiError> + Send + 'static) -> ApiResult<T> {
+    match tokio::task::spawn_blocking(work).await {
+        Ok(result) => result.map(Json),
+        Err(e) => Err(BuildError::Other(format!("request handler failed: {}", e)).into()),
+    }
+}
+
+fn text(body: Bytes) -> Result<String, ApiError> {
+    String::from_utf8(body.to_vec()).map_err(|e| ApiError::bad_request("body", format!("not UTF-8: {}", e)))
+}
+
+fn version(field: &str, value: &str) -> Result<DdexVersion, ApiError> {
+    parse_version(value).ok_or_else(|| ApiError::bad_request(field, format!("unknown DDEX version: {}", value)))
+}
+
+/// A builder with the request's preset, or the server default, applied
+fn builder(config: &ServerConfig, preset: Option<&str>) -> Result<(Builder, Option<String>), ApiError> {
+    let mut builder = Builder::new();
+    let preset = preset.or(config.default_preset.as_deref()).map(str::to_string);
+    if let Some(preset) = &preset {
+        builder.apply_preset(preset, false)?;
+    }
+    Ok((builder, preset))
+}
+
+fn build_request(body: &[u8]) -> Result<(BuildRequest, Warnings), ApiError> {
+    let value: serde_json::Value =
+        serde_json::from_slice(body).map_err(|e| ApiError::bad_request("body", format!("invalid JSON: {}", e)))?;
+    let mut warnings = Warnings::new();
+    let request = BuildRequest::from_json_value(&value, &mut warnings)?;
+    Ok((request, warnings))
+}
+
+async fn parse(body: Bytes) -> ApiResult<ParsedERNMessage> {
+    blocking(move || Ok(ddex_parser::DDEXParser::new().parse(std::io::Cursor::new(body))?)).await
+}
+
+async fn build(State(config): State<Arc<ServerConfig>>, query: Query<PresetQuery>, body: Bytes) -> ApiResult<BuildResponse> {
+    blocking(move || {
+        let (mut builder, preset) = builder(&config, query.preset.as_deref())?;
+        let (mut request, warnings) = build_request(&body)?;
+        if let Some(value) = &query.version {
+            let target = version("version", value)?;
+            builder.with_version(target);
+            request.version = target.to_string().trim_start_matches("ERN/").to_string();
+        }
+        let xml = builder.build_with_fidelity(&request)?.xml;
+        Ok(BuildResponse { xml, warnings, preset })
+    })
+    .await
+}
+
+async fn validate(
+    State(config): State<Arc<ServerConfig>>,
+    query: Query<PresetQuery>,
+    body: Bytes,
+) -> ApiResult<ValidateResponse> {
+    blocking(move || {
+        let (builder, preset) = builder(&config, query.preset.as_deref())?;
+        if body.trim_ascii_start().starts_with(b"<") {
+            let xml = text(body)?;
+            let version = match &query.version {
+                Some(value) => version("version", value)?,
+                None => builder.detect_version(&xml)?,
+            };
+            let violations = SchemaValidator::new(schema_version(version)?).validate(&xml);
+            return Ok(ValidateResponse {
+                valid: violations.is_empty(),
+                version: Some(version),
+                violations,
+                compliance: None,
+            });
+        }
+        if preset.is_none() {
+            return Err(ApiError::bad_request("preset", "build requests are validated against a preset"));
+        }
+        let (request, _) = build_request(&body)?;
+        let report = builder.check_preset_compliance(&request);
+        Ok(ValidateResponse {
+            valid: report.is_compliant(),
+            version: None,
+            violations: Vec::new(),
+            compliance: Some(report),
+        })
+    })
+    .await
+}
+
+fn schema_version(version: DdexVersion) -> Result<ERNVersion, ApiError> {
+    match version {
+        DdexVersion::Ern382 => Ok(ERNVersion::V3_8_2),
+        DdexVersion::Ern42 => Ok(ERNVersion::V4_2),
+        DdexVersion::Ern43 => Ok(ERNVersion::V4_3),
+        DdexVersion::Ern44 => Ok(ERNVersion::V4_4),
+        DdexVersion::Ern41 => Err(ApiError::bad_request("version", "XSD validation is not available for ERN 4.1")),
+    }
+}
+
+async fn diff(Json(request): Json<DiffRequest>) -> ApiResult<ChangeSet> {
+    blocking(move || {
+        let config = DiffConfig { ignore_formatting: !request.include_formatting, ..Default::default() };
+        Ok(DiffEngine::new_with_config(config).diff_xml(&request.old, &request.new)?)
+    })
+    .await
+}
+
+async fn convert(query: Query<ConvertQuery>, body: Bytes) -> ApiResult<ConvertResponse> {
+    blocking(move || {
+        let xml = text(body)?;
+        let builder = Builder::new();
+        let to = version("to", &query.to)?;
+        let from = match &query.from {
+            Some(value) => version("from", value)?,
+            None => builder.detect_version(&xml)?,
+        };
+        let options = ConversionOptions {
+            allow_lossy: query.allow_lossy,
+            preserve_unknown: query.allow_lossy,
+            detailed_reports: true,
+            ..Default::default()
+        };
+        match builder.convert_version(&xml, from, to, Some(options))? {
+            ConverterResult::Success { xml, report } => Ok(ConvertResponse { xml, report }),
+            ConverterResult::Failure { error, .. } => Err(FFIError {
+                code: BuildError::Validation(String::new()).code().to_string(),
+                message: error,
+                location: None,
+                severity: FFIErrorSeverity::Error,
+                suggestion: Some("Pass allow_lossy=true to convert anyway and list the losses in the report".to_string()),
+                category: FFIErrorCategory::Version,
+            }
+            .into()),
+        }
+    })
+    .await
+}
+
+async fn presets() -> Json<Vec<String>> {
+    Json(Builder::new().available_presets())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    async fn call(router: Router, uri: &str, body: &'static str) -> (StatusCode, serde_json::Value) {
+        let request = Request::post(uri).body(Body::from(body)).unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null))
+    }
+
+    const MESSAGE: &str = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+  <MessageHeader><MessageId>M1</MessageId></MessageHeader>
+</ern:NewReleaseMessage>"#;
+
+    #[tokio::test]
+    async fn test_errors_are_ffi_errors() {
+        let (status, body) = call(router(ServerConfig::default()), "/build", "{").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["code"].as_str().unwrap().starts_with("DDEX-B-"));
+
+        let (status, body) = call(router(ServerConfig::default()), "/build?preset=nope", "{}").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["message"].as_str().unwrap().contains("Unknown preset"));
+
+        let (status, body) = call(router(ServerConfig::default()), "/convert?to=9.9", MESSAGE).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["location"]["path"], "to");
+    }
+
+    #[tokio::test]
+    async fn test_validate_and_body_limit() {
+        let (status, body) = call(router(ServerConfig::default()), "/validate", MESSAGE).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["version"], "ERN/4.3");
+        assert_eq!(body["valid"], body["violations"].as_array().unwrap().is_empty());
+
+        let config = ServerConfig { max_request_bytes: 16, ..Default::default() };
+        let (status, _) = call(router(config), "/validate", MESSAGE).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
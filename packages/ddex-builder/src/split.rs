@@ -0,0 +1,549 @@
+//! Splitting multi-release messages and merging single-release ones
+//!
+//! [`split_by_release`] cuts a NewReleaseMessage into one message per main
+//! release, keeping only the resources, parties, track releases and deals the
+//! release refers to. [`merge`] does the reverse, joining the lists of several
+//! messages and renaming references that would otherwise collide.
+//!
+//! Both work on the source text, so everything that is kept keeps its
+//! formatting, and the output depends on nothing but the input.
+//!
+//! ```rust
+//! use ddex_builder::split::{merge, split_by_release};
+//!
+//! let xml = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+//!   <MessageHeader><MessageId>M1</MessageId></MessageHeader>
+//!   <ResourceList>
+//!     <SoundRecording><ResourceReference>A1</ResourceReference></SoundRecording>
+//!     <SoundRecording><ResourceReference>A2</ResourceReference></SoundRecording>
+//!   </ResourceList>
+//!   <ReleaseList>
+//!     <Release><ReleaseReference>R1</ReleaseReference><ReleaseResourceReference>A1</ReleaseResourceReference></Release>
+//!     <Release><ReleaseReference>R2</ReleaseReference><ReleaseResourceReference>A2</ReleaseResourceReference></Release>
+//!   </ReleaseList>
+//! </ern:NewReleaseMessage>"#;
+//!
+//! let parts = split_by_release(xml)?;
+//! assert_eq!(parts.len(), 2);
+//! assert!(parts[0].xml.contains("<MessageId>M1-R1</MessageId>"));
+//! assert!(!parts[0].xml.contains("A2"));
+//!
+//! let joined = merge(&[&parts[0].xml, &parts[1].xml], Some("M2"))?;
+//! assert!(joined.contains("A1") && joined.contains("A2"));
+//! # Ok::<(), ddex_builder::BuildError>(())
+//! ```
+
+use crate::error::BuildError;
+use crate::lint::Document;
+use indexmap::{IndexMap, IndexSet};
+use sha2::{Digest, Sha256};
+use std::ops::Range;
+
+/// One release's message, cut from a multi-release message
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplitMessage {
+    /// ReleaseReference of the main release
+    pub release_reference: String,
+    /// ICPN, GRid or proprietary ID of the main release, when it has one
+    pub release_id: Option<String>,
+    /// The message, with its own MessageId
+    pub xml: String,
+}
+
+/// The kinds of message-local references
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind {
+    Party,
+    Resource,
+    Release,
+}
+
+impl Kind {
+    /// In the order later kinds may refer to earlier ones
+    const ALL: [Kind; 3] = [Kind::Party, Kind::Resource, Kind::Release];
+
+    fn list(self) -> &'static str {
+        match self {
+            Kind::Party => "PartyList",
+            Kind::Resource => "ResourceList",
+            Kind::Release => "ReleaseList",
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Kind::Party => "PartyReference",
+            Kind::Resource => "ResourceReference",
+            Kind::Release => "ReleaseReference",
+        }
+    }
+
+    /// What an element named `name` refers to, e.g. `ArtistPartyReference`
+    fn of_reference(name: &str) -> Option<Kind> {
+        Kind::ALL.into_iter().find(|kind| name.ends_with(kind.key()))
+    }
+}
+
+/// Top-level lists in schema order; merged lists are created in this order
+const LISTS: [&str; 5] = ["MessageHeader", "PartyList", "ResourceList", "ReleaseList", "DealList"];
+
+/// A NewReleaseMessage with its keyed list items indexed
+struct Message<'a> {
+    doc: Document<'a>,
+    /// Top-level elements by name
+    lists: IndexMap<&'static str, usize>,
+    /// Party, resource and release items by kind and reference
+    items: IndexMap<(Kind, String), usize>,
+}
+
+impl<'a> Message<'a> {
+    fn parse(xml: &'a str) -> Result<Self, BuildError> {
+        let doc = Document::parse(xml)?;
+        if doc.elements[0].name != "NewReleaseMessage" {
+            return Err(invalid(&doc.elements[0].name, "only a NewReleaseMessage can be split or merged"));
+        }
+
+        let lists: IndexMap<&'static str, usize> = LISTS
+            .into_iter()
+            .filter_map(|name| doc.children_named(0, name).next().map(|(index, _)| (name, index)))
+            .collect();
+        let mut items = IndexMap::new();
+        for kind in Kind::ALL {
+            let Some(&list) = lists.get(kind.list()) else { continue };
+            for (item, _) in doc.children(list) {
+                if let Some((_, key)) = doc.children_named(item, kind.key()).next() {
+                    items.insert((kind, key.text.clone()), item);
+                }
+            }
+        }
+        Ok(Self { doc, lists, items })
+    }
+
+    fn list_items(&self, list: &str) -> Vec<usize> {
+        match self.lists.get(list) {
+            Some(&list) => self.doc.children(list).map(|(index, _)| index).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// References below `element`, including the element's own key
+    fn references(&self, element: usize) -> impl Iterator<Item = (usize, Kind, &str)> + '_ {
+        self.doc
+            .descendants(element)
+            .filter_map(|(index, e)| Kind::of_reference(&e.name).map(|kind| (index, kind, e.text.as_str())))
+    }
+
+    fn text_of(&self, element: usize, name: &str) -> Option<&str> {
+        self.doc.descendants(element).find(|(_, e)| e.name == name).map(|(_, e)| e.text.as_str())
+    }
+
+    fn message_id(&self) -> Result<(usize, &str), BuildError> {
+        let header = self.lists.get("MessageHeader").copied();
+        header
+            .and_then(|header| self.doc.children_named(header, "MessageId").next())
+            .map(|(index, e)| (index, e.text.as_str()))
+            .ok_or_else(|| invalid("MessageHeader", "no MessageId"))
+    }
+
+    /// Add the parties and resources `included` refers to, transitively
+    fn close_over(&self, included: &mut IndexSet<usize>) {
+        let mut pending: Vec<usize> = included.iter().copied().collect();
+        while let Some(element) = pending.pop() {
+            for (_, kind, key) in self.references(element) {
+                if kind == Kind::Release {
+                    continue;
+                }
+                if let Some(&item) = self.items.get(&(kind, key.to_string())) {
+                    if included.insert(item) {
+                        pending.push(item);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The source of `element`, with references renamed
+    fn renamed(&self, element: usize, renames: &IndexMap<(Kind, String), String>) -> Result<String, BuildError> {
+        let span = self.doc.elements[element].span();
+        let mut edits = Vec::new();
+        for (index, kind, key) in self.references(element) {
+            if let Some(name) = renames.get(&(kind, key.to_string())) {
+                let range = self.doc.elements[index]
+                    .text_span
+                    .clone()
+                    .ok_or_else(|| invalid(&self.doc.elements[index].name, "reference is not plain text"))?;
+                edits.push((range.start - span.start..range.end - span.start, name.clone()));
+            }
+        }
+        Ok(apply(&self.doc.source[span], edits))
+    }
+}
+
+/// Split a NewReleaseMessage into one message per main release
+///
+/// Each message keeps the header, with `-<ReleaseReference>` appended to
+/// its MessageId, and the parties, resources and track releases its release
+/// refers to. Deals are kept when they cover the release; references in
+/// them to releases of other messages are dropped.
+pub fn split_by_release(xml: &str) -> Result<Vec<SplitMessage>, BuildError> {
+    let message = Message::parse(xml)?;
+    let doc = &message.doc;
+    let (message_id_element, message_id) = message.message_id()?;
+
+    let releases = message.list_items("ReleaseList");
+    let (tracks, mains): (Vec<usize>, Vec<usize>) = releases.iter().partition(|&&r| is_track_release(doc, r));
+    if mains.is_empty() {
+        return Err(invalid("ReleaseList", "no main Release to split by"));
+    }
+
+    let mut parts = Vec::new();
+    for &main in &mains {
+        let reference = message
+            .text_of(main, "ReleaseReference")
+            .ok_or_else(|| invalid("Release", "no ReleaseReference"))?
+            .to_string();
+
+        let mut included = IndexSet::from([main]);
+        message.close_over(&mut included);
+        // Track releases go with the main release that contains their resources
+        for &track in &tracks {
+            let shares_resource = message.references(track).any(|(_, kind, key)| {
+                kind == Kind::Resource && message.items.get(&(kind, key.to_string())).is_some_and(|r| included.contains(r))
+            });
+            if shares_resource {
+                included.insert(track);
+            }
+        }
+
+        let kept_releases: IndexSet<&str> = releases
+            .iter()
+            .filter(|r| included.contains(*r))
+            .filter_map(|&r| message.text_of(r, "ReleaseReference"))
+            .collect();
+        let mut edits = Vec::new();
+        let deals = message.list_items("DealList");
+        let mut kept_deals = 0;
+        for &deal in &deals {
+            let covered: Vec<(usize, bool)> = message
+                .references(deal)
+                .filter(|(_, kind, _)| *kind == Kind::Release)
+                .map(|(index, _, key)| (index, kept_releases.contains(key)))
+                .collect();
+            // Deals naming no release at all are kept everywhere
+            if !covered.is_empty() && !covered.iter().any(|(_, kept)| *kept) {
+                continue;
+            }
+            included.insert(deal);
+            kept_deals += 1;
+            for (index, kept) in covered {
+                if !kept {
+                    edits.push((doc.removal_range(index), String::new()));
+                }
+            }
+        }
+        message.close_over(&mut included);
+
+        for list in ["PartyList", "ResourceList", "ReleaseList", "DealList"] {
+            let Some(&list_element) = message.lists.get(list) else { continue };
+            let items = message.list_items(list);
+            let empty = if list == "DealList" { kept_deals == 0 } else { !items.iter().any(|i| included.contains(i)) };
+            if empty {
+                edits.push((doc.removal_range(list_element), String::new()));
+                continue;
+            }
+            for item in items.into_iter().filter(|i| !included.contains(i)) {
+                edits.push((doc.removal_range(item), String::new()));
+            }
+        }
+        let id_span = text_span(doc, message_id_element)?;
+        edits.push((id_span, format!("{}-{}", message_id, reference)));
+
+        let release_id = ["ICPN", "GRid", "ProprietaryId", "CatalogNumber"]
+            .into_iter()
+            .find_map(|name| message.text_of(main, name))
+            .map(str::to_string);
+        parts.push(SplitMessage { release_reference: reference, release_id, xml: apply(xml, edits) });
+    }
+    Ok(parts)
+}
+
+/// Merge NewReleaseMessages into one message
+///
+/// The first message provides the header; the others must be of the same
+/// version and between the same sender and recipient. Their parties,
+/// resources, releases and deals are appended to the first message's lists.
+/// An item whose reference is already taken is dropped when it is identical
+/// to the existing one and otherwise gets its reference suffixed with
+/// `_<n>`, where `n` counts the messages from 1. Without a `message_id`,
+/// one is derived from the merged messages' IDs.
+pub fn merge(messages: &[&str], message_id: Option<&str>) -> Result<String, BuildError> {
+    let parsed = messages.iter().map(|xml| Message::parse(xml)).collect::<Result<Vec<_>, _>>()?;
+    let Some((base, others)) = parsed.split_first() else {
+        return Err(invalid("messages", "nothing to merge"));
+    };
+
+    let header = |message: &Message<'_>, name: &str| {
+        let header = message.lists.get("MessageHeader").copied()?;
+        let (element, _) = message.doc.children_named(header, name).next()?;
+        Some(normalized(&message.doc.source[message.doc.elements[element].span()]))
+    };
+    for (n, message) in others.iter().enumerate() {
+        if message.doc.version != base.doc.version {
+            return Err(invalid("NewReleaseMessage", &format!("message {} is of another ERN version", n + 2)));
+        }
+        for party in ["MessageSender", "MessageRecipient"] {
+            if header(message, party) != header(base, party) {
+                return Err(invalid(party, &format!("message {} has another {}", n + 2, party)));
+            }
+        }
+    }
+
+    // Normalized text of every item in the merged message, by reference
+    let mut taken: IndexMap<(Kind, String), String> = base
+        .items
+        .iter()
+        .map(|(key, &item)| (key.clone(), normalized(&base.doc.source[base.doc.elements[item].span()])))
+        .collect();
+    // Deals have no reference, so they are told apart by their content
+    let mut unkeyed: IndexSet<String> = LISTS[1..]
+        .iter()
+        .flat_map(|list| base.list_items(list))
+        .filter(|item| !base.items.values().any(|i| i == item))
+        .map(|item| normalized(&base.doc.source[base.doc.elements[item].span()]))
+        .collect();
+    let mut appended: IndexMap<&str, Vec<String>> = IndexMap::new();
+    for (n, message) in others.iter().enumerate() {
+        let mut renames = IndexMap::new();
+        let mut duplicates = IndexSet::new();
+        for kind in Kind::ALL {
+            for ((item_kind, key), &item) in &message.items {
+                if *item_kind != kind {
+                    continue;
+                }
+                let text = normalized(&message.renamed(item, &renames)?);
+                match taken.get(&(kind, key.clone())) {
+                    None => {
+                        taken.insert((kind, key.clone()), text);
+                    }
+                    Some(existing) if *existing == text => {
+                        duplicates.insert(item);
+                    }
+                    Some(_) => {
+                        let mut name = format!("{}_{}", key, n + 2);
+                        while taken.contains_key(&(kind, name.clone())) {
+                            name.push('_');
+                        }
+                        renames.insert((kind, key.clone()), name.clone());
+                        taken.insert((kind, name), normalized(&message.renamed(item, &renames)?));
+                    }
+                }
+            }
+        }
+
+        for list in &LISTS[1..] {
+            for item in message.list_items(list) {
+                if duplicates.contains(&item) {
+                    continue;
+                }
+                let text = message.renamed(item, &renames)?;
+                if message.items.values().any(|&i| i == item) || unkeyed.insert(normalized(&text)) {
+                    appended.entry(*list).or_default().push(text);
+                }
+            }
+        }
+    }
+
+    let doc = &base.doc;
+    // Indentation of the root's children and of their children
+    let indent = leading_whitespace(doc, base.lists.get("MessageHeader").copied().unwrap_or(0)).to_string();
+    let item_indent = format!("{}{}", indent, indent.trim_start_matches(['\r', '\n']));
+    let mut edits = Vec::new();
+    for (list, items) in &appended {
+        let text: String = items.iter().map(|item| format!("{}{}", item_indent, item)).collect();
+        match base.lists.get(list) {
+            Some(&element) => {
+                let end = doc.children(element).last().map_or(doc.elements[element].start_tag.end, |(_, e)| e.span().end);
+                edits.push((end..end, text));
+            }
+            None => {
+                // After the closest list before it, which is at least the header
+                let previous = LISTS
+                    .iter()
+                    .take_while(|name| *name != list)
+                    .filter_map(|name| base.lists.get(name))
+                    .last()
+                    .ok_or_else(|| invalid("MessageHeader", "missing in the first message"))?;
+                let end = doc.elements[*previous].span().end;
+                edits.push((end..end, format!("{}<{}>{}{}</{}>", indent, list, text, indent, list)));
+            }
+        }
+    }
+
+    let (id_element, _) = base.message_id()?;
+    let message_id = match message_id {
+        Some(id) => id.to_string(),
+        None => {
+            let mut hasher = Sha256::new();
+            for message in &parsed {
+                hasher.update(message.message_id()?.1.as_bytes());
+                hasher.update(b"\n");
+            }
+            format!("MSG_{}", &hex::encode(hasher.finalize())[..32])
+        }
+    };
+    edits.push((text_span(doc, id_element)?, message_id));
+
+    Ok(apply(messages[0], edits))
+}
+
+/// Whether a ReleaseList item is a track release rather than a main release
+pub(crate) fn is_track_release(doc: &Document<'_>, release: usize) -> bool {
+    // ERN 3 marks track releases by type rather than by element name
+    doc.elements[release].name == "TrackRelease"
+        || doc.descendants(release).any(|(_, e)| e.name == "ReleaseType" && e.text == "TrackRelease")
+}
+
+fn invalid(field: &str, message: &str) -> BuildError {
+    BuildError::InvalidFormat { field: field.to_string(), message: message.to_string() }
+}
+
+fn text_span(doc: &Document<'_>, element: usize) -> Result<Range<usize>, BuildError> {
+    let element = &doc.elements[element];
+    element.text_span.clone().ok_or_else(|| invalid(&element.name, "content is not plain text"))
+}
+
+/// Whitespace between an element and whatever precedes it
+pub(crate) fn leading_whitespace<'d>(doc: &'d Document<'_>, element: usize) -> &'d str {
+    let before = &doc.source[..doc.elements[element].start_tag.start];
+    &before[before.trim_end().len()..]
+}
+
+/// Whitespace-insensitive form of an item, for spotting duplicates
+fn normalized(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Apply non-overlapping edits to `source`
+pub(crate) fn apply(source: &str, mut edits: Vec<(Range<usize>, String)>) -> String {
+    let mut output = source.to_string();
+    edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+    for (range, replacement) in edits {
+        output.replace_range(range, &replacement);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALBUMS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+  <MessageHeader>
+    <MessageId>MSG1</MessageId>
+    <MessageSender><PartyId>PADPIDA2014120301V</PartyId></MessageSender>
+    <MessageRecipient><PartyId>PADPIDA2007040502I</PartyId></MessageRecipient>
+  </MessageHeader>
+  <PartyList>
+    <Party><PartyReference>P1</PartyReference><PartyName><FullName>The Examples</FullName></PartyName></Party>
+    <Party><PartyReference>P2</PartyReference><PartyName><FullName>Other Artist</FullName></PartyName></Party>
+  </PartyList>
+  <ResourceList>
+    <SoundRecording>
+      <ResourceReference>A1</ResourceReference>
+      <DisplayArtist><ArtistPartyReference>P1</ArtistPartyReference></DisplayArtist>
+    </SoundRecording>
+    <SoundRecording>
+      <ResourceReference>A2</ResourceReference>
+      <DisplayArtist><ArtistPartyReference>P2</ArtistPartyReference></DisplayArtist>
+    </SoundRecording>
+  </ResourceList>
+  <ReleaseList>
+    <Release>
+      <ReleaseReference>R1</ReleaseReference>
+      <ReleaseId><ICPN>0123456789012</ICPN></ReleaseId>
+      <ResourceGroup><ResourceGroupContentItem><ReleaseResourceReference>A1</ReleaseResourceReference></ResourceGroupContentItem></ResourceGroup>
+    </Release>
+    <Release>
+      <ReleaseReference>R2</ReleaseReference>
+      <ResourceGroup><ResourceGroupContentItem><ReleaseResourceReference>A2</ReleaseResourceReference></ResourceGroupContentItem></ResourceGroup>
+    </Release>
+    <TrackRelease>
+      <ReleaseReference>R3</ReleaseReference>
+      <ReleaseResourceReference>A2</ReleaseResourceReference>
+    </TrackRelease>
+  </ReleaseList>
+  <DealList>
+    <ReleaseDeal>
+      <DealReleaseReference>R1</DealReleaseReference>
+      <DealReleaseReference>R2</DealReleaseReference>
+      <Deal><DealTerms><TerritoryCode>Worldwide</TerritoryCode></DealTerms></Deal>
+    </ReleaseDeal>
+    <ReleaseDeal>
+      <DealReleaseReference>R3</DealReleaseReference>
+      <Deal><DealTerms><TerritoryCode>GB</TerritoryCode></DealTerms></Deal>
+    </ReleaseDeal>
+  </DealList>
+</ern:NewReleaseMessage>"#;
+
+    /// Every reference in document order
+    fn references(xml: &str) -> Vec<&str> {
+        let pattern = regex::Regex::new(r"<\w*Reference>([^<]+)</").unwrap();
+        pattern.captures_iter(xml).map(|c| c.get(1).unwrap().as_str()).collect()
+    }
+
+    #[test]
+    fn test_split_scopes_each_message_to_its_release() {
+        let parts = split_by_release(ALBUMS).unwrap();
+        assert_eq!(parts.len(), 2);
+
+        let first = &parts[0];
+        assert_eq!(first.release_reference, "R1");
+        assert_eq!(first.release_id.as_deref(), Some("0123456789012"));
+        assert!(first.xml.contains("<MessageId>MSG1-R1</MessageId>"));
+        assert_eq!(references(&first.xml), ["P1", "A1", "P1", "R1", "A1", "R1"]);
+        // Removed items take their lines with them
+        assert!(first.xml.contains("</Party>\n  </PartyList>"));
+        assert!(first.xml.contains("<DealReleaseReference>R1</DealReleaseReference>\n      <Deal>"));
+
+        let second = &parts[1];
+        assert_eq!(second.release_id, None);
+        assert_eq!(references(&second.xml), ["P2", "A2", "P2", "R2", "A2", "R3", "A2", "R2", "R3"]);
+        assert!(second.xml.contains("<TerritoryCode>GB</TerritoryCode>"));
+        assert_eq!(split_by_release(&second.xml).unwrap()[0].xml, second.xml.replace("MSG1-R2", "MSG1-R2-R2"));
+    }
+
+    #[test]
+    fn test_merge_renames_colliding_references() {
+        let parts = split_by_release(ALBUMS).unwrap();
+        let merged = merge(&[&parts[0].xml, &parts[1].xml], Some("MSG1")).unwrap();
+        assert!(merged.contains("<MessageId>MSG1</MessageId>"));
+        assert!(merged.contains("</Party>\n    <Party>"));
+        assert_eq!(
+            references(&merged),
+            ["P1", "P2", "A1", "P1", "A2", "P2", "R1", "A1", "R2", "A2", "R3", "A2", "R1", "R2", "R3"]
+        );
+
+        let other = parts[0].xml.replace("The Examples", "Someone Else").replace("0123456789012", "5099999999999");
+        let merged = merge(&[&parts[0].xml, &other], None).unwrap();
+        // The recording refers to the renamed party, so it differs and is renamed too
+        assert_eq!(
+            references(&merged),
+            ["P1", "P1_2", "A1", "P1", "A1_2", "P1_2", "R1", "A1", "R1_2", "A1_2", "R1", "R1_2"]
+        );
+        assert!(merged.contains("<MessageId>MSG_"));
+        assert_eq!(merged, merge(&[&parts[0].xml, &other], None).unwrap());
+
+        // Identical items, deals included, are not repeated
+        let same = merge(&[&parts[0].xml, &parts[0].xml], Some("M")).unwrap();
+        assert_eq!(references(&same), references(&parts[0].xml));
+    }
+    #[test]
+    fn test_merge_rejects_mismatched_messages() {
+        let other_recipient = ALBUMS.replace("PADPIDA2007040502I", "PADPIDA0000000000X");
+        assert!(merge(&[ALBUMS, &other_recipient], None).is_err());
+        assert!(merge(&[ALBUMS, &ALBUMS.replace("ern/43", "ern/42")], None).is_err());
+        assert!(merge(&[], None).is_err());
+        assert!(split_by_release("<PurgeReleaseMessage/>").is_err());
+    }
+}
@@ -0,0 +1,619 @@
+//! Build requests from label spreadsheets
+//!
+//! [`BuildRequest::from_csv`] reads two CSV exports of a spreadsheet: a
+//! releases sheet with one row per release and a tracks sheet with one row
+//! per track, joined on `release_id`.
+//!
+//! Headers are matched loosely. Case, spaces and punctuation are ignored,
+//! the aliases below are accepted, and a single typo is forgiven
+//! (`Relase Date`). Columns whose header starts with `#` are skipped, so a
+//! sheet can keep notes; any other unknown header is an error.
+//!
+//! Every cell is checked before anything is built. All problems are reported
+//! together in [`BuildError::InvalidSpreadsheet`], each with its sheet, row
+//! (the header is row 1, as in a spreadsheet application) and column.
+//! Values are otherwise taken as written: dates, ISRCs and UPCs are tidied
+//! up by [`normalize_request`](crate::normalize::normalize_request) when the
+//! request is built, which records each change.
+//!
+//! ## Releases sheet
+//!
+//! | Column | Aliases | Value |
+//! |--------|---------|-------|
+//! | `release_id` * | `id`, `release` | Unique ID, referenced by the tracks sheet |
+//! | `title` * | `album`, `album title`, `release title` | Release title |
+//! | `artist` * | `display artist`, `main artist`, `album artist` | Display artist |
+//! | `label` | `label name`, `record label` | Label name |
+//! | `release_date` | `date`, `street date` | `YYYY-MM-DD`, `YYYY/MM/DD`, `YYYY.MM.DD` or `YYYYMMDD` |
+//! | `original_release_date` | `original date`, `original release` | Date, as for `release_date` |
+//! | `upc` | `barcode`, `ean`, `icpn` | 12 to 14 digits |
+//! | `p_line` | `℗`, `℗ line`, `phonographic copyright` | e.g. `2024 Example Records` |
+//! | `c_line` | `©`, `© line`, `copyright` | e.g. `2024 Example Records` |
+//! | `territories` | `territory`, `territory codes` | ISO codes or `Worldwide`, separated by spaces, commas or semicolons |
+//! | `commercial_model` | `commercial model type`, `model` | e.g. `PayAsYouGoModel`; required with `territories` |
+//! | `use_types` | `use type`, `usage` | e.g. `PermanentDownload, OnDemandStream` |
+//! | `deal_start_date` | `start date`, `deal start` | Date, as for `release_date` |
+//! | `sender_name`, `sender_id` | `sender`, `sender dpid` | Message sender, identical on every row that sets it |
+//! | `recipient_name`, `recipient_id` | `recipient`, `recipient dpid` | Message recipient, likewise |
+//!
+//! A release with `territories` gets a deal covering it.
+//!
+//! ## Tracks sheet
+//!
+//! | Column | Aliases | Value |
+//! |--------|---------|-------|
+//! | `release_id` * | `release`, `album id` | A `release_id` of the releases sheet |
+//! | `isrc` * | | ISRC, with or without hyphens |
+//! | `title` * | `track title`, `song title`, `track name` | Track title |
+//! | `duration` * | `length`, `time` | `m:ss`, `h:mm:ss`, seconds or ISO 8601 (`PT3M30S`) |
+//! | `artist` | `track artist`, `display artist` | Display artist, the release artist when empty |
+//! | `track_number` | `track no`, `number`, `position` | Orders the tracks of a release; row order otherwise |
+//! | `track_id` | | Defaults to `<release_id>-T<n>` |
+//! | `p_line` | `℗`, `℗ line`, `phonographic copyright` | As for releases |
+//!
+//! Columns marked * are required, on every row.
+
+use crate::builder::{
+    BuildRequest, CLineRequest, DealRequest, DealTerms, LocalizedStringRequest, MessageHeaderRequest,
+    PLineRequest, PartyRequest, ReleaseRequest, TrackRequest,
+};
+use crate::error::BuildError;
+use crate::normalize::DATE_FORMATS;
+use chrono::NaiveDate;
+use ddex_core::duration::DdexDuration;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+
+/// A column of the releases or tracks sheet
+#[derive(Debug, Clone, Copy)]
+pub struct Column {
+    /// Canonical header
+    pub name: &'static str,
+    /// Other headers accepted for the column
+    pub aliases: &'static [&'static str],
+    /// Whether every row needs a value
+    pub required: bool,
+}
+
+const fn column(name: &'static str, aliases: &'static [&'static str], required: bool) -> Column {
+    Column { name, aliases, required }
+}
+
+/// Columns of the releases sheet
+pub const RELEASE_COLUMNS: &[Column] = &[
+    column("release_id", &["id", "release"], true),
+    column("title", &["album", "album title", "release title"], true),
+    column("artist", &["display artist", "main artist", "album artist"], true),
+    column("label", &["label name", "record label"], false),
+    column("release_date", &["date", "street date"], false),
+    column("original_release_date", &["original date", "original release"], false),
+    column("upc", &["barcode", "ean", "icpn"], false),
+    column("p_line", &["℗", "℗ line", "phonographic copyright"], false),
+    column("c_line", &["©", "© line", "copyright"], false),
+    column("territories", &["territory", "territory codes"], false),
+    column("commercial_model", &["commercial model type", "model"], false),
+    column("use_types", &["use type", "usage"], false),
+    column("deal_start_date", &["start date", "deal start"], false),
+    column("sender_name", &["sender"], false),
+    column("sender_id", &["sender dpid"], false),
+    column("recipient_name", &["recipient"], false),
+    column("recipient_id", &["recipient dpid"], false),
+];
+
+/// Columns of the tracks sheet
+pub const TRACK_COLUMNS: &[Column] = &[
+    column("release_id", &["release", "album id"], true),
+    column("isrc", &[], true),
+    column("title", &["track title", "song title", "track name"], true),
+    column("duration", &["length", "time"], true),
+    column("artist", &["track artist", "display artist"], false),
+    column("track_number", &["track no", "number", "position"], false),
+    column("track_id", &[], false),
+    column("p_line", &["℗", "℗ line", "phonographic copyright"], false),
+];
+
+/// A problem with one cell, or with a header when `row` is 1
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CellError {
+    /// `releases` or `tracks`
+    pub sheet: String,
+    /// Spreadsheet row, counting the header as row 1
+    pub row: usize,
+    /// Header of the column, as written in the sheet
+    pub column: String,
+    /// What is wrong with the value
+    pub message: String,
+}
+
+impl std::fmt::Display for CellError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} row {}, column '{}': {}", self.sheet, self.row, self.column, self.message)
+    }
+}
+
+impl BuildRequest {
+    /// Build a request from the CSV exports of a releases sheet and a tracks
+    /// sheet; see the [module documentation](crate::spreadsheet) for the
+    /// columns
+    ///
+    /// The request targets ERN 4.3 and its header only carries the sender and
+    /// recipient columns, so set anything else on the result before building.
+    pub fn from_csv(release_csv: impl Read, tracks_csv: impl Read) -> Result<Self, BuildError> {
+        let mut errors = Vec::new();
+        let releases = Sheet::read("releases", release_csv, RELEASE_COLUMNS, &mut errors)?;
+        let tracks = Sheet::read("tracks", tracks_csv, TRACK_COLUMNS, &mut errors)?;
+
+        let mut sender = HeaderParty::default();
+        let mut recipient = HeaderParty::default();
+        let mut release_requests: IndexMap<String, (ReleaseRequest, Vec<NumberedTrack>)> = IndexMap::new();
+        let mut deals = Vec::new();
+
+        for row in releases.rows() {
+            let (Some(release_id), Some(title), Some(artist)) =
+                (row.required("release_id", &mut errors), row.required("title", &mut errors), row.required("artist", &mut errors))
+            else {
+                continue;
+            };
+            if release_requests.contains_key(release_id) {
+                row.error("release_id", format!("release '{}' appears more than once", release_id), &mut errors);
+                continue;
+            }
+            sender.take(&row, "sender_name", "sender_id", &mut errors);
+            recipient.take(&row, "recipient_name", "recipient_id", &mut errors);
+
+            let release_date = row.get("release_date").filter(|date| row.check_date("release_date", date, &mut errors));
+            let original_release_date = row
+                .get("original_release_date")
+                .filter(|date| row.check_date("original_release_date", date, &mut errors));
+            let upc = row.get("upc").filter(|upc| {
+                let digits: String = upc.chars().filter(|c| !c.is_whitespace() && *c != '-').collect();
+                let valid = (12..=14).contains(&digits.len()) && digits.chars().all(|c| c.is_ascii_digit());
+                if !valid {
+                    row.error("upc", format!("'{}' is not a UPC or EAN of 12 to 14 digits", upc), &mut errors);
+                }
+                valid
+            });
+
+            if let Some(territories) = row.get("territories") {
+                let territory_code: Vec<String> = split_list(territories);
+                for code in &territory_code {
+                    if !(code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic())) && code != "Worldwide" {
+                        row.error("territories", format!("'{}' is not an ISO territory code or Worldwide", code), &mut errors);
+                    }
+                }
+                let commercial_model = row.get("commercial_model");
+                if commercial_model.is_none() {
+                    row.error("commercial_model", "required when territories are given".to_string(), &mut errors);
+                }
+                let start_date = row.get("deal_start_date").filter(|date| row.check_date("deal_start_date", date, &mut errors));
+                deals.push(DealRequest {
+                    deal_reference: None,
+                    deal_terms: DealTerms {
+                        commercial_model_type: commercial_model.unwrap_or_default().to_string(),
+                        territory_code,
+                        start_date: start_date.map(str::to_string),
+                        use_types: row.get("use_types").map(split_list).unwrap_or_default(),
+                        ..Default::default()
+                    },
+                    release_references: Vec::new(),
+                    release_ids: vec![release_id.to_string()],
+                    comments: Vec::new(),
+                });
+            }
+
+            let release = ReleaseRequest {
+                release_id: release_id.to_string(),
+                release_reference: None,
+                title: vec![LocalizedStringRequest { text: title.to_string(), language_code: None }],
+                artist: artist.to_string(),
+                label: row.get("label").map(str::to_string),
+                release_date: release_date.map(str::to_string),
+                upc: upc.map(str::to_string),
+                tracks: Vec::new(),
+                resource_references: None,
+                resources: Vec::new(),
+                territory_details: Vec::new(),
+                contributors: Vec::new(),
+                p_line: row.get("p_line").map(|text| PLineRequest { year: copyright_year(text), text: text.to_string() }),
+                c_line: row.get("c_line").map(|text| CLineRequest { year: copyright_year(text), text: text.to_string() }),
+                track_isrcs: Vec::new(),
+                track_releases: Vec::new(),
+                comments: Vec::new(),
+                original_release_date: original_release_date.map(str::to_string),
+            };
+            release_requests.insert(release_id.to_string(), (release, Vec::new()));
+        }
+
+        for row in tracks.rows() {
+            let (Some(release_id), Some(isrc), Some(title), Some(duration)) = (
+                row.required("release_id", &mut errors),
+                row.required("isrc", &mut errors),
+                row.required("title", &mut errors),
+                row.required("duration", &mut errors),
+            ) else {
+                continue;
+            };
+            let compact: String = isrc.chars().filter(|c| !c.is_whitespace() && *c != '-').collect();
+            if !is_isrc(&compact.to_ascii_uppercase()) {
+                row.error("isrc", format!("'{}' is not an ISRC (CC-XXX-YY-NNNNN)", isrc), &mut errors);
+            }
+            let duration = iso_duration(duration).unwrap_or_else(|| {
+                row.error("duration", format!("'{}' is not a duration such as 3:45 or PT3M45S", duration), &mut errors);
+                String::new()
+            });
+            let track_number = row.get("track_number").and_then(|number| match number.parse::<usize>() {
+                Ok(n) if n > 0 => Some(n),
+                _ => {
+                    row.error("track_number", format!("'{}' is not a positive whole number", number), &mut errors);
+                    None
+                }
+            });
+
+            let Some((release, tracks)) = release_requests.get_mut(release_id) else {
+                row.error("release_id", format!("no release '{}' in the releases sheet", release_id), &mut errors);
+                continue;
+            };
+            let track = TrackRequest {
+                track_id: row
+                    .get("track_id")
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("{}-T{}", release_id, tracks.len() + 1)),
+                resource_reference: None,
+                isrc: isrc.to_string(),
+                title: title.to_string(),
+                duration,
+                artist: row.get("artist").unwrap_or(release.artist.as_str()).to_string(),
+                contributors: Vec::new(),
+                p_line: row.get("p_line").map(|text| PLineRequest { year: copyright_year(text), text: text.to_string() }),
+                c_line: None,
+                comments: Vec::new(),
+                technical_details: None,
+                parental_warning: None,
+            };
+            tracks.push((track_number, track));
+        }
+
+        if !errors.is_empty() {
+            errors.sort_by_key(|e| (e.sheet != "releases", e.row));
+            return Err(BuildError::InvalidSpreadsheet { errors });
+        }
+
+        let header = MessageHeaderRequest {
+            message_id: None,
+            message_sender: party(sender.name, sender.id),
+            message_recipient: party(recipient.name, recipient.id),
+            message_control_type: None,
+            message_created_date_time: None,
+            ..Default::default()
+        };
+        let releases = release_requests
+            .into_values()
+            .map(|(mut release, mut tracks)| {
+                // Numbered tracks first, in order; the rest keep their row order
+                tracks.sort_by_key(|(number, _)| number.unwrap_or(usize::MAX));
+                release.tracks = tracks.into_iter().map(|(_, track)| track).collect();
+                release
+            })
+            .collect();
+
+        Ok(BuildRequest {
+            header,
+            version: "4.3".to_string(),
+            profile: None,
+            releases,
+            deals,
+            extensions: None,
+        })
+    }
+}
+
+/// Track with its `track_number`, if the sheet gives one
+type NumberedTrack = (Option<usize>, TrackRequest);
+
+fn party(name: Option<String>, id: Option<String>) -> PartyRequest {
+    PartyRequest {
+        party_name: name.map(|text| LocalizedStringRequest { text, language_code: None }).into_iter().collect(),
+        party_id: id,
+        party_reference: None,
+    }
+}
+
+/// Sender or recipient, collected across the rows of the releases sheet
+#[derive(Default)]
+struct HeaderParty {
+    name: Option<String>,
+    id: Option<String>,
+}
+
+impl HeaderParty {
+    fn take(&mut self, row: &Row<'_>, name_column: &str, id_column: &str, errors: &mut Vec<CellError>) {
+        for (column, slot) in [(name_column, &mut self.name), (id_column, &mut self.id)] {
+            let Some(value) = row.get(column) else { continue };
+            match slot {
+                Some(previous) if previous != value => {
+                    row.error(column, format!("'{}' differs from '{}' on an earlier row", value, previous), errors);
+                }
+                Some(_) => {}
+                None => *slot = Some(value.to_string()),
+            }
+        }
+    }
+}
+
+/// A CSV file with its headers resolved to canonical column names
+struct Sheet {
+    name: &'static str,
+    /// Canonical name of each column, `None` for skipped columns
+    columns: Vec<Option<&'static str>>,
+    headers: Vec<String>,
+    records: Vec<csv::StringRecord>,
+}
+
+impl Sheet {
+    fn read(
+        name: &'static str,
+        input: impl Read,
+        dictionary: &'static [Column],
+        errors: &mut Vec<CellError>,
+    ) -> Result<Self, BuildError> {
+        let invalid = |e: csv::Error| BuildError::InvalidFormat { field: name.to_string(), message: e.to_string() };
+        let mut reader = csv::ReaderBuilder::new().flexible(true).trim(csv::Trim::All).from_reader(input);
+        let headers: Vec<String> = reader.headers().map_err(invalid)?.iter().map(str::to_string).collect();
+        let records = reader.records().collect::<Result<Vec<_>, _>>().map_err(invalid)?;
+
+        let header_error = |column: &str, message: String| CellError {
+            sheet: name.to_string(),
+            row: 1,
+            column: column.to_string(),
+            message,
+        };
+        let mut columns = Vec::with_capacity(headers.len());
+        for header in &headers {
+            if header.starts_with('#') || header.is_empty() {
+                columns.push(None);
+                continue;
+            }
+            let column = match_header(header, dictionary);
+            match column {
+                Some(column) if columns.contains(&Some(column.name)) => {
+                    errors.push(header_error(header, format!("a second column for '{}'", column.name)));
+                }
+                Some(_) => {}
+                None => errors.push(header_error(
+                    header,
+                    "unknown column; prefix the header with '#' to skip it".to_string(),
+                )),
+            }
+            columns.push(column.map(|column| column.name));
+        }
+        for column in dictionary.iter().filter(|column| column.required) {
+            if !columns.contains(&Some(column.name)) {
+                errors.push(header_error(column.name, "required column is missing".to_string()));
+            }
+        }
+
+        Ok(Self { name, columns, headers, records })
+    }
+
+    fn rows(&self) -> impl Iterator<Item = Row<'_>> {
+        self.records
+            .iter()
+            .enumerate()
+            .filter(|(_, record)| record.iter().any(|cell| !cell.is_empty()))
+            .map(|(i, record)| Row { sheet: self, number: i + 2, record })
+    }
+}
+
+/// One data row of a [`Sheet`]
+struct Row<'a> {
+    sheet: &'a Sheet,
+    /// Spreadsheet row number
+    number: usize,
+    record: &'a csv::StringRecord,
+}
+
+impl<'a> Row<'a> {
+    fn index(&self, column: &str) -> Option<usize> {
+        self.sheet.columns.iter().position(|c| *c == Some(column))
+    }
+
+    /// Non-empty value of `column`
+    fn get(&self, column: &str) -> Option<&'a str> {
+        self.index(column).and_then(|i| self.record.get(i)).filter(|value| !value.is_empty())
+    }
+
+    fn required(&self, column: &str, errors: &mut Vec<CellError>) -> Option<&'a str> {
+        let value = self.get(column);
+        // A missing column is already reported against the header
+        if value.is_none() && self.index(column).is_some() {
+            self.error(column, "value is required".to_string(), errors);
+        }
+        value
+    }
+
+    fn check_date(&self, column: &str, date: &str, errors: &mut Vec<CellError>) -> bool {
+        let valid = std::iter::once("%Y-%m-%d")
+            .chain(DATE_FORMATS.iter().copied())
+            .any(|format| NaiveDate::parse_from_str(date, format).is_ok());
+        if !valid {
+            self.error(column, format!("'{}' is not a date such as 2024-03-01", date), errors);
+        }
+        valid
+    }
+
+    fn error(&self, column: &str, message: String, errors: &mut Vec<CellError>) {
+        let header = self.index(column).map_or(column, |i| self.sheet.headers[i].as_str());
+        errors.push(CellError {
+            sheet: self.sheet.name.to_string(),
+            row: self.number,
+            column: header.to_string(),
+            message,
+        });
+    }
+}
+
+/// Lower-case letters and digits of a header, plus the copyright symbols
+fn normalize_header(header: &str) -> String {
+    header
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, '℗' | '©'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Resolve a header to its column: exactly after normalization, or with a
+/// single typo when that leaves one candidate
+fn match_header(header: &str, dictionary: &'static [Column]) -> Option<&'static Column> {
+    let normalized = normalize_header(header);
+    let spellings = |column: &'static Column| std::iter::once(column.name).chain(column.aliases.iter().copied()).map(normalize_header);
+
+    if let Some(column) = dictionary.iter().find(|column| spellings(column).any(|s| s == normalized)) {
+        return Some(column);
+    }
+    if normalized.chars().count() < 4 {
+        return None;
+    }
+    let mut near = dictionary
+        .iter()
+        .filter(|column| spellings(column).any(|s| s.chars().count() >= 4 && edit_distance(&s, &normalized) == 1));
+    match (near.next(), near.next()) {
+        (Some(column), None) => Some(column),
+        _ => None,
+    }
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn is_isrc(code: &str) -> bool {
+    let bytes = code.as_bytes();
+    bytes.len() == 12
+        && bytes[..2].iter().all(u8::is_ascii_alphabetic)
+        && bytes[2..5].iter().all(u8::is_ascii_alphanumeric)
+        && bytes[5..].iter().all(u8::is_ascii_digit)
+}
+
+/// Leading four-digit year of a copyright line
+fn copyright_year(text: &str) -> Option<u16> {
+    let digits: String = text
+        .trim_start_matches(|c: char| !c.is_ascii_digit())
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    (digits.len() == 4).then(|| digits.parse().ok()).flatten()
+}
+
+/// ISO 8601 duration of `m:ss`, `h:mm:ss`, whole seconds or an ISO 8601 duration
+fn iso_duration(value: &str) -> Option<String> {
+    if value.starts_with("PT") {
+        return Some(value.to_string());
+    }
+    let parts: Vec<u64> = value.split(':').map(|part| part.parse().ok()).collect::<Option<_>>()?;
+    let seconds = match parts.as_slice() {
+        [s] => *s,
+        [m, s] if *s < 60 => m * 60 + s,
+        [h, m, s] if *m < 60 && *s < 60 => h * 3600 + m * 60 + s,
+        _ => return None,
+    };
+    Some(DdexDuration::from(std::time::Duration::from_secs(seconds)).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RELEASES: &str = "\
+Release ID,Album Title,Display Artist,Relase Date,Barcode,Territories,Commercial Model,Sender,# Notes
+R1,First Album,The Band,2024/03/01,0 12345 67890 5,GB IE,PayAsYouGoModel,Example Records,check artwork
+R2,Second Album,The Band,,,,,Example Records,
+";
+
+    const TRACKS: &str = "\
+release,ISRC,Track Title,Length,Track No
+R1,USRC17607839,Second Song,3:05,2
+R1,US-RC1-76-07838,First Song,PT2M,1
+R2,USRC17607840,Only Song,1:02:03,
+";
+
+    #[test]
+    fn test_from_csv() {
+        let request = BuildRequest::from_csv(RELEASES.as_bytes(), TRACKS.as_bytes()).unwrap();
+
+        assert_eq!(request.header.message_sender.party_name[0].text, "Example Records");
+        assert!(request.header.message_recipient.party_name.is_empty());
+        assert_eq!(request.releases.len(), 2);
+        let first = &request.releases[0];
+        assert_eq!(first.title[0].text, "First Album");
+        assert_eq!(first.release_date.as_deref(), Some("2024/03/01"));
+        assert_eq!(first.upc.as_deref(), Some("0 12345 67890 5"));
+        let titles: Vec<_> = first.tracks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["First Song", "Second Song"]);
+        assert_eq!(first.tracks[1].duration, "PT3M5S");
+        assert_eq!(first.tracks[1].artist, "The Band");
+        assert_eq!(request.releases[1].tracks[0].duration, "PT1H2M3S");
+
+        assert_eq!(request.deals.len(), 1);
+        assert_eq!(request.deals[0].release_ids, vec!["R1"]);
+        assert_eq!(request.deals[0].deal_terms.territory_code, vec!["GB", "IE"]);
+    }
+
+    #[test]
+    fn test_cell_errors_are_collected() {
+        let releases = "release_id,title,artist,upc,territories\nR1,Album,,123,GB\n";
+        let tracks = "release_id,isrc,title,duration,colour\nR9,USRC17607839,Song,3:75,red\n";
+        let err = BuildRequest::from_csv(releases.as_bytes(), tracks.as_bytes()).unwrap_err();
+        let BuildError::InvalidSpreadsheet { errors } = &err else { panic!("{}", err) };
+
+        let found: Vec<_> = errors.iter().map(|e| (e.sheet.as_str(), e.row, e.column.as_str())).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("releases", 2, "artist"),
+                ("tracks", 1, "colour"),
+                ("tracks", 2, "duration"),
+                ("tracks", 2, "release_id"),
+            ]
+        );
+        assert!(err.to_string().contains("tracks row 2, column 'release_id': no release 'R9'"));
+        assert_eq!(err.code(), "DDEX-B-0404");
+    }
+
+    #[test]
+    fn test_header_matching() {
+        let name = |header| match_header(header, RELEASE_COLUMNS).map(|column| column.name);
+        assert_eq!(name("Release-Date"), Some("release_date"));
+        assert_eq!(name("UPC / EAN"), None);
+        assert_eq!(name("Artst"), Some("artist"));
+        assert_eq!(name("℗ Line"), Some("p_line"));
+        assert_eq!(name("Colour"), None);
+    }
+
+    #[test]
+    fn test_durations() {
+        assert_eq!(iso_duration("3:05").as_deref(), Some("PT3M5S"));
+        assert_eq!(iso_duration("185").as_deref(), Some("PT3M5S"));
+        assert_eq!(iso_duration("3:75"), None);
+        assert_eq!(iso_duration("three minutes"), None);
+    }
+}
@@ -0,0 +1,219 @@
+//! Catalog figures gathered from DDEX messages
+//!
+//! [`MessageStats`] counts the releases, tracks and deals of one
+//! NewReleaseMessage together with its playing time, territory coverage and
+//! genres; [`CatalogStats`] adds those figures up across many messages.
+//!
+//! ```rust
+//! use ddex_builder::stats::{CatalogStats, MessageStats};
+//!
+//! let xml = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+//!   <ResourceList><SoundRecording><Duration>PT3M45S</Duration></SoundRecording></ResourceList>
+//!   <ReleaseList><Release><ReleaseReference>R1</ReleaseReference></Release></ReleaseList>
+//! </ern:NewReleaseMessage>"#;
+//!
+//! let mut catalog = CatalogStats::default();
+//! catalog.add(&MessageStats::from_xml(xml)?);
+//! assert_eq!(catalog.releases, 1);
+//! assert_eq!(catalog.duration_seconds, 225.0);
+//! assert_eq!(catalog.versions["ERN/4.3"], 1);
+//! # Ok::<(), ddex_builder::BuildError>(())
+//! ```
+
+use crate::error::BuildError;
+use crate::lint::{duration_seconds, Document};
+use crate::split::is_track_release;
+use indexmap::{IndexMap, IndexSet};
+use serde::Serialize;
+
+/// Figures for one message
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MessageStats {
+    /// DDEX version, e.g. "ERN/4.3", when the namespace is a known one
+    pub version: Option<String>,
+    /// Main releases, not counting track releases
+    pub releases: usize,
+    /// Sound recordings
+    pub tracks: usize,
+    /// Deals across all release deals
+    pub deals: usize,
+    /// Total playing time of the sound recordings, in seconds
+    pub duration_seconds: f64,
+    /// Number of main releases with a deal in each territory
+    pub territories: IndexMap<String, usize>,
+    /// Number of main releases of each genre
+    pub genres: IndexMap<String, usize>,
+}
+
+impl MessageStats {
+    /// Count the figures of a NewReleaseMessage
+    pub fn from_xml(xml: &str) -> Result<Self, BuildError> {
+        let doc = Document::parse(xml)?;
+        let list = |name: &str| doc.children_named(0, name).next().map(|(index, _)| index);
+        let text = |element: usize, name: &str| {
+            doc.descendants(element).find(|(_, e)| e.name == name).map(|(_, e)| e.text.as_str())
+        };
+
+        let mut stats = MessageStats { version: doc.version.map(|v| v.to_string()), ..Default::default() };
+
+        if let Some(resources) = list("ResourceList") {
+            for (recording, _) in doc.children_named(resources, "SoundRecording") {
+                stats.tracks += 1;
+                stats.duration_seconds += text(recording, "Duration").and_then(duration_seconds).unwrap_or(0.0);
+            }
+        }
+
+        let mut releases = IndexSet::new();
+        if let Some(list) = list("ReleaseList") {
+            for (release, _) in doc.children(list).filter(|(r, _)| !is_track_release(&doc, *r)) {
+                stats.releases += 1;
+                if let Some(reference) = text(release, "ReleaseReference") {
+                    releases.insert(reference);
+                }
+                let genres: IndexSet<&str> = doc
+                    .descendants(release)
+                    .filter(|(_, e)| e.name == "GenreText" && !e.text.is_empty())
+                    .map(|(_, e)| e.text.as_str())
+                    .collect();
+                for genre in genres {
+                    *stats.genres.entry(genre.to_string()).or_default() += 1;
+                }
+            }
+        }
+
+        if let Some(deals) = list("DealList") {
+            let mut granted: IndexMap<&str, IndexSet<&str>> = IndexMap::new();
+            for (release_deal, _) in doc.children(deals) {
+                let covered: Vec<&str> = doc
+                    .children_named(release_deal, "DealReleaseReference")
+                    .map(|(_, e)| e.text.as_str())
+                    .filter(|reference| releases.contains(reference))
+                    .collect();
+                for (deal, _) in doc.descendants(release_deal).filter(|(_, e)| e.name == "Deal") {
+                    stats.deals += 1;
+                    for (_, territory) in doc.descendants(deal).filter(|(_, e)| e.name == "TerritoryCode") {
+                        granted.entry(territory.text.as_str()).or_default().extend(covered.iter().copied());
+                    }
+                }
+            }
+            stats.territories = granted
+                .into_iter()
+                .filter(|(_, releases)| !releases.is_empty())
+                .map(|(territory, releases)| (territory.to_string(), releases.len()))
+                .collect();
+            stats.territories.sort_keys();
+        }
+
+        stats.genres.sort_keys();
+        Ok(stats)
+    }
+}
+
+/// Figures added up across messages
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CatalogStats {
+    /// Messages counted
+    pub messages: usize,
+    /// Main releases, not counting track releases
+    pub releases: usize,
+    /// Sound recordings
+    pub tracks: usize,
+    /// Deals across all release deals
+    pub deals: usize,
+    /// Total playing time of the sound recordings, in seconds
+    pub duration_seconds: f64,
+    /// Number of main releases with a deal in each territory, by territory code
+    pub territories: IndexMap<String, usize>,
+    /// Number of main releases of each genre, by genre
+    pub genres: IndexMap<String, usize>,
+    /// Number of messages of each version; "unknown" when it was not recognised
+    pub versions: IndexMap<String, usize>,
+}
+
+impl CatalogStats {
+    /// Add the figures of one message
+    pub fn add(&mut self, message: &MessageStats) {
+        self.messages += 1;
+        self.releases += message.releases;
+        self.tracks += message.tracks;
+        self.deals += message.deals;
+        self.duration_seconds += message.duration_seconds;
+        for (territory, count) in &message.territories {
+            *self.territories.entry(territory.clone()).or_default() += count;
+        }
+        for (genre, count) in &message.genres {
+            *self.genres.entry(genre.clone()).or_default() += count;
+        }
+        let version = message.version.clone().unwrap_or_else(|| "unknown".to_string());
+        *self.versions.entry(version).or_default() += 1;
+
+        self.territories.sort_keys();
+        self.genres.sort_keys();
+        self.versions.sort_keys();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CATALOG: &str = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+  <ResourceList>
+    <SoundRecording><ResourceReference>A1</ResourceReference><Duration>PT3M45S</Duration></SoundRecording>
+    <SoundRecording><ResourceReference>A2</ResourceReference><Duration>PT1H</Duration></SoundRecording>
+    <Image><ResourceReference>A3</ResourceReference></Image>
+  </ResourceList>
+  <ReleaseList>
+    <Release>
+      <ReleaseReference>R1</ReleaseReference>
+      <Genre><GenreText>Pop</GenreText><SubGenre>Synthpop</SubGenre></Genre>
+      <Genre ApplicableTerritoryCode="JP"><GenreText>Pop</GenreText></Genre>
+    </Release>
+    <Release>
+      <ReleaseReference>R2</ReleaseReference>
+      <Genre><GenreText>Jazz</GenreText></Genre>
+    </Release>
+    <TrackRelease>
+      <ReleaseReference>R3</ReleaseReference>
+      <Genre><GenreText>Pop</GenreText></Genre>
+    </TrackRelease>
+  </ReleaseList>
+  <DealList>
+    <ReleaseDeal>
+      <DealReleaseReference>R1</DealReleaseReference>
+      <DealReleaseReference>R2</DealReleaseReference>
+      <Deal><DealTerms><TerritoryCode>GB</TerritoryCode><TerritoryCode>US</TerritoryCode></DealTerms></Deal>
+      <Deal><DealTerms><TerritoryCode>GB</TerritoryCode></DealTerms></Deal>
+    </ReleaseDeal>
+    <ReleaseDeal>
+      <DealReleaseReference>R3</DealReleaseReference>
+      <Deal><DealTerms><TerritoryCode>JP</TerritoryCode></DealTerms></Deal>
+    </ReleaseDeal>
+  </DealList>
+</ern:NewReleaseMessage>"#;
+
+    #[test]
+    fn test_message_stats() {
+        let stats = MessageStats::from_xml(CATALOG).unwrap();
+        assert_eq!(stats.version.as_deref(), Some("ERN/4.3"));
+        assert_eq!((stats.releases, stats.tracks, stats.deals), (2, 2, 3));
+        assert_eq!(stats.duration_seconds, 3825.0);
+        // Track release deals do not count towards coverage
+        assert_eq!(stats.territories, IndexMap::from([("GB".to_string(), 2), ("US".to_string(), 2)]));
+        assert_eq!(stats.genres, IndexMap::from([("Jazz".to_string(), 1), ("Pop".to_string(), 1)]));
+    }
+
+    #[test]
+    fn test_catalog_stats_add_up() {
+        let mut catalog = CatalogStats::default();
+        catalog.add(&MessageStats::from_xml(CATALOG).unwrap());
+        catalog.add(&MessageStats::from_xml(&CATALOG.replace("ern/43", "ern/999")).unwrap());
+
+        assert_eq!(catalog.messages, 2);
+        assert_eq!((catalog.releases, catalog.tracks, catalog.deals), (4, 4, 6));
+        assert_eq!(catalog.territories["GB"], 4);
+        assert_eq!(catalog.genres.keys().collect::<Vec<_>>(), ["Jazz", "Pop"]);
+        assert_eq!(catalog.versions.keys().collect::<Vec<_>>(), ["ERN/4.3", "unknown"]);
+        assert!(MessageStats::from_xml("<NewReleaseMessage>").is_err());
+    }
+}
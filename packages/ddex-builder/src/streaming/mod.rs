@@ -10,6 +10,7 @@ pub mod reference_manager;
 use crate::builder::MessageHeaderRequest;
 use crate::error::{BuildError, BuildWarning};
 use crate::determinism::DeterminismConfig;
+use crate::id_generator::{IdKind, IdMinter};
 use buffer_manager::BufferManager;
 use reference_manager::StreamingReferenceManager;
 use std::io::Write as IoWrite;
@@ -311,9 +312,21 @@ impl<W: IoWrite> StreamingBuilder<W> {
     
     // Private helper methods
     
+    fn party_names(header: &MessageHeaderRequest) -> String {
+        format!(
+            "{}-{}",
+            header.message_sender.party_name.first().map(|s| s.text.as_str()).unwrap_or_default(),
+            header.message_recipient.party_name.first().map(|s| s.text.as_str()).unwrap_or_default(),
+        )
+    }
+    
     fn write_message_header(&mut self, header: &MessageHeaderRequest) -> Result<(), BuildError> {
         // Generate message ID if not provided
-        let default_id = Uuid::new_v4().to_string();
+        let default_id = match &self.config.determinism_config.id_strategy {
+            Some(strategy) => IdMinter::new(strategy.clone(), &self.config.determinism_config)?
+                .mint(IdKind::MessageId, &Self::party_names(header)),
+            None => Uuid::new_v4().to_string(),
+        };
         let message_id = header.message_id.as_deref()
             .unwrap_or(&default_id);
         
@@ -338,11 +351,12 @@ impl<W: IoWrite> StreamingBuilder<W> {
         header_xml.push_str("    </MessageRecipient>\n");
         
         // Write MessageCreatedDateTime
-        let default_time = chrono::Utc::now().to_rfc3339();
-        let created_time = header.message_created_date_time.as_deref()
-            .unwrap_or(&default_time);
+        let created_time = match header.message_created_date_time {
+            Some(ref t) => t.clone(),
+            None => self.config.determinism_config.timestamp_source.resolve()?,
+        };
         header_xml.push_str(&format!("    <MessageCreatedDateTime>{}</MessageCreatedDateTime>\n", 
-                           escape_xml(created_time)));
+                           escape_xml(&created_time)));
         
         header_xml.push_str("  </MessageHeader>\n");
         
@@ -0,0 +1,270 @@
+//! Message templates with variable substitution
+//!
+//! Labels issuing many near-identical releases keep one request as a
+//! template and vary only a few values. A template is a [`BuildRequest`] in
+//! YAML or JSON whose strings may contain placeholders such as `{{upc}}` or
+//! `{{ release_date }}`:
+//!
+//! ```yaml
+//! version: "4.3"
+//! releases:
+//!   - release_id: "{{upc}}"
+//!     upc: "{{upc}}"
+//!     title: [{ text: "{{title}}" }]
+//!     release_date: "{{release_date}}"
+//! ```
+//!
+//! [`MessageTemplate::instantiate`] fills the placeholders from a variables
+//! map. A string that is only a placeholder takes the variable's value as
+//! is, so `tracks: "{{tracks}}"` can take a whole list; placeholders inside
+//! longer strings take scalar values only.
+//!
+//! [`MessageTemplate::from_request`] turns a request that was already built,
+//! or read from a parsed message with
+//! [`BuildRequest::from_graph`](crate::BuildRequest::from_graph), into a
+//! template by replacing the values of the given variables with their
+//! placeholders.
+
+use crate::builder::BuildRequest;
+use crate::error::BuildError;
+use ddex_core::warnings::Warnings;
+use indexmap::IndexMap;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+static PLACEHOLDER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap()
+});
+
+/// A build request with `{{name}}` placeholders
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageTemplate {
+    value: Value,
+}
+
+impl MessageTemplate {
+    /// Read a template from YAML or JSON text
+    pub fn parse(text: &str) -> Result<Self, BuildError> {
+        let value = serde_yaml::from_str(text).map_err(|e| BuildError::InvalidFormat {
+            field: "template".to_string(),
+            message: e.to_string(),
+        })?;
+        Ok(Self { value })
+    }
+
+    /// Make a template of `request`, replacing every occurrence of each
+    /// variable's value in its strings with the variable's placeholder
+    ///
+    /// Longer values are replaced first, so `2024-06-01` is not broken up by
+    /// a `year` of `2024`; empty values are ignored.
+    pub fn from_request(request: &BuildRequest, variables: &IndexMap<String, String>) -> Result<Self, BuildError> {
+        let mut value = serde_json::to_value(request).map_err(|e| BuildError::Serialization(e.to_string()))?;
+        let mut replacements: Vec<(&str, String)> = variables
+            .iter()
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(name, value)| (value.as_str(), format!("{{{{{}}}}}", name)))
+            .collect();
+        replacements.sort_by_key(|(value, _)| std::cmp::Reverse(value.len()));
+        map_strings(&mut value, &mut |text| {
+            let mut result = text.to_string();
+            for (value, placeholder) in &replacements {
+                result = result.replace(value, placeholder);
+            }
+            Ok(Value::String(result))
+        })?;
+        Ok(Self { value })
+    }
+
+    /// The template as YAML, e.g. to save it to a file
+    pub fn to_yaml(&self) -> Result<String, BuildError> {
+        serde_yaml::to_string(&self.value).map_err(|e| BuildError::Serialization(e.to_string()))
+    }
+
+    /// Names of the variables the template uses, sorted
+    pub fn variables(&self) -> Vec<String> {
+        let mut names = BTreeSet::new();
+        let mut value = self.value.clone();
+        let _ = map_strings(&mut value, &mut |text| {
+            names.extend(PLACEHOLDER.captures_iter(text).map(|c| c[1].to_string()));
+            Ok(Value::String(text.to_string()))
+        });
+        names.into_iter().collect()
+    }
+
+    /// The template with its placeholders filled from `variables`, as JSON
+    ///
+    /// Fails naming every placeholder without a variable, and any variable
+    /// with a list or map value used inside a longer string.
+    pub fn render(&self, variables: &IndexMap<String, Value>) -> Result<Value, BuildError> {
+        let mut value = self.value.clone();
+        let mut missing = BTreeSet::new();
+        map_strings(&mut value, &mut |text| {
+            if let Some(whole) = PLACEHOLDER.captures(text).filter(|c| c[0].len() == text.len()) {
+                return Ok(match variables.get(&whole[1]) {
+                    Some(value) => value.clone(),
+                    None => {
+                        missing.insert(whole[1].to_string());
+                        Value::String(text.to_string())
+                    }
+                });
+            }
+            let mut error = None;
+            let result = PLACEHOLDER.replace_all(text, |c: &regex::Captures<'_>| match variables.get(&c[1]) {
+                Some(Value::String(s)) => s.clone(),
+                Some(Value::Null) => String::new(),
+                Some(value @ (Value::Bool(_) | Value::Number(_))) => value.to_string(),
+                Some(_) => {
+                    error.get_or_insert_with(|| BuildError::InvalidFormat {
+                        field: c[1].to_string(),
+                        message: format!("a list or map can't be placed inside '{}'", text),
+                    });
+                    c[0].to_string()
+                }
+                None => {
+                    missing.insert(c[1].to_string());
+                    c[0].to_string()
+                }
+            });
+            match error {
+                Some(error) => Err(error),
+                None => Ok(Value::String(result.into_owned())),
+            }
+        })?;
+        if !missing.is_empty() {
+            return Err(BuildError::InvalidFormat {
+                field: "variables".to_string(),
+                message: format!(
+                    "no value for {}",
+                    missing.iter().map(|name| format!("{{{{{}}}}}", name)).collect::<Vec<_>>().join(", ")
+                ),
+            });
+        }
+        Ok(value)
+    }
+
+    /// The request the template describes with `variables` filled in,
+    /// recording fields the request model does not know in `warnings`
+    pub fn instantiate(&self, variables: &IndexMap<String, Value>, warnings: &mut Warnings) -> Result<BuildRequest, BuildError> {
+        BuildRequest::from_json_value(&self.render(variables)?, warnings)
+    }
+}
+
+/// Replace every string in `value`, keys excepted, by the result of `f`
+fn map_strings(value: &mut Value, f: &mut impl FnMut(&str) -> Result<Value, BuildError>) -> Result<(), BuildError> {
+    match value {
+        Value::String(text) => {
+            let replaced = f(text)?;
+            *value = replaced;
+        }
+        Value::Array(items) => {
+            for item in items {
+                map_strings(item, f)?;
+            }
+        }
+        Value::Object(fields) => {
+            for field in fields.values_mut() {
+                map_strings(field, f)?;
+            }
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SINGLE: &str = r#"
+header:
+  message_sender: { party_name: [{ text: "Example Records" }] }
+  message_recipient: { party_name: [{ text: "Example DSP" }] }
+version: "4.3"
+profile: AudioSingle
+releases:
+  - release_id: "{{upc}}"
+    upc: "{{upc}}"
+    title: [{ text: "{{title}}" }]
+    artist: "{{artist}}"
+    release_date: "{{ release_date }}"
+    p_line: { year: "{{year}}", text: "{{year}} Example Records" }
+    tracks: "{{tracks}}"
+deals: []
+"#;
+
+    #[test]
+    fn test_instantiate_template() {
+        let template = MessageTemplate::parse(SINGLE).unwrap();
+        assert_eq!(template.variables(), ["artist", "release_date", "title", "tracks", "upc", "year"]);
+
+        let variables: IndexMap<String, Value> = serde_json::from_value(json!({
+            "upc": "602445790128",
+            "title": "Headlights",
+            "artist": "The Night Owls",
+            "release_date": "2024-06-01",
+            "year": 2024,
+            "tracks": [{
+                "track_id": "T1",
+                "isrc": "USRC12400001",
+                "title": "Headlights",
+                "duration": "PT3M35S",
+                "artist": "The Night Owls"
+            }]
+        }))
+        .unwrap();
+        let request = template.instantiate(&variables, &mut Warnings::new()).unwrap();
+        let release = &request.releases[0];
+        assert_eq!(release.release_id, "602445790128");
+        assert_eq!(release.title[0].text, "Headlights");
+        assert_eq!(release.p_line.as_ref().map(|p| (p.year, p.text.as_str())), Some((Some(2024), "2024 Example Records")));
+        assert_eq!(release.tracks[0].isrc, "USRC12400001");
+
+        let mut incomplete = variables.clone();
+        incomplete.shift_remove("title");
+        incomplete.shift_remove("artist");
+        let err = template.render(&incomplete).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid format in variables: no value for {{artist}}, {{title}}");
+    }
+
+    #[test]
+    fn test_template_from_request() {
+        let template = MessageTemplate::parse(SINGLE).unwrap();
+        let variables: IndexMap<String, Value> = serde_json::from_value(json!({
+            "upc": "602445790128",
+            "title": "Headlights",
+            "artist": "The Night Owls",
+            "release_date": "2024-06-01",
+            "year": 2024,
+            "tracks": []
+        }))
+        .unwrap();
+        let request = template.instantiate(&variables, &mut Warnings::new()).unwrap();
+
+        let saved = MessageTemplate::from_request(
+            &request,
+            &IndexMap::from([
+                ("upc".to_string(), "602445790128".to_string()),
+                ("release_date".to_string(), "2024-06-01".to_string()),
+                ("year".to_string(), "2024".to_string()),
+            ]),
+        )
+        .unwrap();
+        let saved = MessageTemplate::parse(&saved.to_yaml().unwrap()).unwrap();
+        assert_eq!(saved.variables(), ["release_date", "upc", "year"]);
+
+        let rendered = saved
+            .render(&IndexMap::from([
+                ("upc".to_string(), json!("602445790135")),
+                ("release_date".to_string(), json!("2025-01-10")),
+                ("year".to_string(), json!("2025")),
+            ]))
+            .unwrap();
+        assert_eq!(rendered["releases"][0]["upc"], "602445790135");
+        assert_eq!(rendered["releases"][0]["release_date"], "2025-01-10");
+        assert_eq!(rendered["releases"][0]["p_line"]["text"], "2025 Example Records");
+        assert_eq!(rendered["releases"][0]["title"][0]["text"], "Headlights");
+    }
+}
@@ -4,6 +4,9 @@
 //! ensuring that generated XML meets fidelity requirements and can successfully
 //! round-trip through the parser.
 
+use crate::canonical::c14n;
+use crate::determinism::DeterminismConfig;
+use crate::round_trip::RoundTripTester;
 use crate::{error::BuildError, FidelityOptions, CanonicalizationAlgorithm};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -146,20 +149,21 @@ impl BuildVerifier {
         xml_output: &str,
         fidelity_options: &FidelityOptions,
     ) -> Result<RoundTripVerificationResult, BuildError> {
-        // This would integrate with the ddex-parser to test round-trip
-        // For now, we'll simulate the verification
-        let issues = Vec::new();
-        
-        // TODO: Integrate with ddex-parser when available
-        // let parser = ddex_parser::DDEXParser::new();
-        // let parsed = parser.parse(xml_output)?;
-        // let rebuilt_xml = self.build(parsed)?;
-        // let canonical_original = canonicalize(xml_output)?;
-        // let canonical_rebuilt = canonicalize(rebuilt_xml)?;
-        // let success = canonical_original == canonical_rebuilt;
+        let result = RoundTripTester::new(fidelity_options.clone()).test_round_trip(xml_output)?;
+        let issues = result
+            .differences
+            .into_iter()
+            .map(|difference| VerificationIssue {
+                severity: VerificationSeverity::Error,
+                category: "round-trip".to_string(),
+                message: format!("Content changed in round trip: {}", difference),
+                path: None,
+                suggestion: Some("Preserve extensions to keep partner-specific content".to_string()),
+            })
+            .collect();
 
         Ok(RoundTripVerificationResult {
-            success: true, // Placeholder
+            success: result.success,
             issues,
         })
     }
@@ -187,14 +191,15 @@ impl BuildVerifier {
                     });
                 }
             },
-            CanonicalizationAlgorithm::C14N | 
-            CanonicalizationAlgorithm::C14N11 | 
-            CanonicalizationAlgorithm::DbC14N => {
+            CanonicalizationAlgorithm::C14N |
+            CanonicalizationAlgorithm::C14N11 |
+            CanonicalizationAlgorithm::DbC14N |
+            CanonicalizationAlgorithm::Custom(_) => {
                 // Verify that multiple canonicalizations produce the same result
                 let mut canonicalized_versions = Vec::new();
                 
                 for _ in 0..3 {
-                    match self.canonicalize_xml(xml_output, &fidelity_options.canonicalization) {
+                    match self.canonicalize_xml(xml_output, fidelity_options) {
                         Ok(canonical) => canonicalized_versions.push(canonical),
                         Err(e) => {
                             success = false;
@@ -226,17 +231,6 @@ impl BuildVerifier {
                     }
                 }
             },
-            CanonicalizationAlgorithm::Custom(_rules) => {
-                // Verify custom canonicalization rules
-                // TODO: Implement custom canonicalization verification
-                issues.push(VerificationIssue {
-                    severity: VerificationSeverity::Info,
-                    category: "canonicalization".to_string(),
-                    message: "Custom canonicalization verification not yet implemented".to_string(),
-                    path: None,
-                    suggestion: None,
-                });
-            },
         }
 
         Ok(CanonicalizationVerificationResult {
@@ -357,31 +351,13 @@ impl BuildVerifier {
         }
     }
 
-    /// Canonicalize XML using the specified algorithm
+    /// Canonicalize XML using the configured algorithm
     fn canonicalize_xml(
         &self,
         xml: &str,
-        algorithm: &CanonicalizationAlgorithm,
+        fidelity_options: &FidelityOptions,
     ) -> Result<String, BuildError> {
-        match algorithm {
-            CanonicalizationAlgorithm::None => Ok(xml.to_string()),
-            CanonicalizationAlgorithm::C14N => {
-                // TODO: Implement C14N canonicalization
-                Ok(xml.to_string()) // Placeholder
-            },
-            CanonicalizationAlgorithm::C14N11 => {
-                // TODO: Implement C14N11 canonicalization
-                Ok(xml.to_string()) // Placeholder
-            },
-            CanonicalizationAlgorithm::DbC14N => {
-                // TODO: Implement DB-C14N canonicalization
-                Ok(xml.to_string()) // Placeholder
-            },
-            CanonicalizationAlgorithm::Custom(_rules) => {
-                // TODO: Implement custom canonicalization
-                Ok(xml.to_string()) // Placeholder
-            },
-        }
+        c14n::canonicalize(xml, fidelity_options, &DeterminismConfig::default())
     }
 }
 
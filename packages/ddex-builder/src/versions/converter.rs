@@ -1,5 +1,6 @@
 use crate::presets::DdexVersion;
 use crate::versions::{ConversionOptions};
+use crate::versions::structural::{self, Direction};
 use indexmap::IndexMap;
 use quick_xml::events::{Event, BytesStart, BytesEnd, BytesText};
 use quick_xml::{Reader, Writer};
@@ -25,6 +26,47 @@ pub struct ConversionReport {
     pub elements_converted: usize,
     pub elements_dropped: usize,
     pub elements_added: usize,
+    /// Fields that were dropped or could only be approximated in the target version
+    #[serde(default)]
+    pub lossy_fields: Vec<LossyField>,
+}
+
+impl ConversionReport {
+    fn new(from_version: DdexVersion, to_version: DdexVersion) -> Self {
+        Self {
+            from_version,
+            to_version,
+            warnings: Vec::new(),
+            elements_converted: 0,
+            elements_dropped: 0,
+            elements_added: 0,
+            lossy_fields: Vec::new(),
+        }
+    }
+
+    /// Whether the conversion lost or approximated any data
+    pub fn is_lossy(&self) -> bool {
+        !self.lossy_fields.is_empty()
+    }
+}
+
+/// A field that could not be carried over unchanged
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LossyField {
+    /// Location of the field in the source message
+    pub path: String,
+    /// What happened to the field
+    pub kind: LossKind,
+    /// Human-readable explanation
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum LossKind {
+    /// The field is not present in the output
+    Dropped,
+    /// The field is present but its meaning was narrowed or merged
+    Approximated,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -117,6 +159,8 @@ impl VersionConverter {
         self.add_42_to_43_rules();
         self.add_43_to_42_rules();
         self.add_42_to_382_rules();
+        self.add_43_to_44_rules();
+        self.add_44_to_43_rules();
     }
 
     fn add_382_to_42_rules(&mut self) {
@@ -142,6 +186,20 @@ impl VersionConverter {
             ElementMapping::Direct("Territory".to_string())
         );
 
+        // Deal terms
+        element_mappings.insert(
+            "PriceType".to_string(),
+            ElementMapping::Renamed("PriceCode".to_string())
+        );
+
+        element_mappings.insert(
+            "TakeDown".to_string(),
+            ElementMapping::Deprecated {
+                replacement: None,
+                warning: "TakeDown flag not supported in ERN 4.x; express takedowns with a deal end date".to_string()
+            }
+        );
+
         let rules = ConversionRules {
             element_mappings,
             namespace_mapping: NamespaceMapping {
@@ -282,10 +340,13 @@ impl VersionConverter {
         
         element_mappings.insert(
             "CommercialModelType".to_string(),
-            ElementMapping::Deprecated { 
-                replacement: None,
-                warning: "CommercialModelType not supported in ERN 3.8.2, will be omitted".to_string()
-            }
+            ElementMapping::Direct("CommercialModelType".to_string())
+        );
+
+        // Deal terms
+        element_mappings.insert(
+            "PriceCode".to_string(),
+            ElementMapping::Renamed("PriceType".to_string())
         );
 
         let rules = ConversionRules {
@@ -308,16 +369,90 @@ impl VersionConverter {
         self.conversion_rules.insert((DdexVersion::Ern42, DdexVersion::Ern382), rules);
     }
 
+    fn add_43_to_44_rules(&mut self) {
+        let mut element_mappings = IndexMap::new();
+        
+        element_mappings.insert(
+            "SoundRecording".to_string(),
+            ElementMapping::Direct("SoundRecording".to_string())
+        );
+        
+        element_mappings.insert(
+            "ResourceRightsController".to_string(),
+            ElementMapping::New { default_value: None }
+        );
+
+        let rules = ConversionRules {
+            element_mappings,
+            namespace_mapping: NamespaceMapping {
+                from: "http://ddex.net/xml/ern/43".to_string(),
+                to: "http://ddex.net/xml/ern/44".to_string(),
+                schema_version_from: "ern/43".to_string(),
+                schema_version_to: "ern/44".to_string(),
+            },
+            field_migrations: vec![],
+            validation_changes: vec![
+                ValidationChange {
+                    element: "SoundRecording".to_string(),
+                    change_type: ValidationChangeType::OptionalAdded("ResourceRightsController".to_string()),
+                },
+                ValidationChange {
+                    element: "SoundRecording".to_string(),
+                    change_type: ValidationChangeType::OptionalAdded("LanguageOfPerformance".to_string()),
+                },
+            ],
+        };
+
+        self.conversion_rules.insert((DdexVersion::Ern43, DdexVersion::Ern44), rules);
+    }
+
+    fn add_44_to_43_rules(&mut self) {
+        let mut element_mappings = IndexMap::new();
+        
+        element_mappings.insert(
+            "SoundRecording".to_string(),
+            ElementMapping::Direct("SoundRecording".to_string())
+        );
+        
+        element_mappings.insert(
+            "ResourceRightsController".to_string(),
+            ElementMapping::Deprecated { 
+                replacement: None,
+                warning: "ResourceRightsController not supported in ERN 4.3, will be omitted".to_string()
+            }
+        );
+        
+        element_mappings.insert(
+            "LanguageOfPerformance".to_string(),
+            ElementMapping::Deprecated { 
+                replacement: None,
+                warning: "LanguageOfPerformance not supported in ERN 4.3, will be omitted".to_string()
+            }
+        );
+
+        let rules = ConversionRules {
+            element_mappings,
+            namespace_mapping: NamespaceMapping {
+                from: "http://ddex.net/xml/ern/44".to_string(),
+                to: "http://ddex.net/xml/ern/43".to_string(),
+                schema_version_from: "ern/44".to_string(),
+                schema_version_to: "ern/43".to_string(),
+            },
+            field_migrations: vec![],
+            validation_changes: vec![
+                ValidationChange {
+                    element: "SoundRecording".to_string(),
+                    change_type: ValidationChangeType::OptionalRemoved("ResourceRightsController".to_string()),
+                },
+            ],
+        };
+
+        self.conversion_rules.insert((DdexVersion::Ern44, DdexVersion::Ern43), rules);
+    }
+
     pub fn convert(&self, xml_content: &str, from_version: DdexVersion, to_version: DdexVersion, options: Option<ConversionOptions>) -> ConversionResult {
         let options = options.unwrap_or_default();
-        let mut report = ConversionReport {
-            from_version,
-            to_version,
-            warnings: Vec::new(),
-            elements_converted: 0,
-            elements_dropped: 0,
-            elements_added: 0,
-        };
+        let mut report = ConversionReport::new(from_version, to_version);
 
         if from_version == to_version {
             return ConversionResult::Success {
@@ -349,6 +484,18 @@ impl VersionConverter {
             (DdexVersion::Ern43, DdexVersion::Ern382) => {
                 Some(vec![DdexVersion::Ern43, DdexVersion::Ern42, DdexVersion::Ern382])
             }
+            (DdexVersion::Ern42, DdexVersion::Ern44) => {
+                Some(vec![DdexVersion::Ern42, DdexVersion::Ern43, DdexVersion::Ern44])
+            }
+            (DdexVersion::Ern44, DdexVersion::Ern42) => {
+                Some(vec![DdexVersion::Ern44, DdexVersion::Ern43, DdexVersion::Ern42])
+            }
+            (DdexVersion::Ern382, DdexVersion::Ern44) => {
+                Some(vec![DdexVersion::Ern382, DdexVersion::Ern42, DdexVersion::Ern43, DdexVersion::Ern44])
+            }
+            (DdexVersion::Ern44, DdexVersion::Ern382) => {
+                Some(vec![DdexVersion::Ern44, DdexVersion::Ern43, DdexVersion::Ern42, DdexVersion::Ern382])
+            }
             _ => None,
         }
     }
@@ -360,13 +507,14 @@ impl VersionConverter {
             let from = window[0];
             let to = window[1];
             
-            match self.convert_single_step(&current_xml, from, to, &options, report) {
+            match self.convert_single_step(&current_xml, from, to, &options) {
                 ConversionResult::Success { xml, report: step_report } => {
                     current_xml = xml;
                     report.warnings.extend(step_report.warnings);
                     report.elements_converted += step_report.elements_converted;
                     report.elements_dropped += step_report.elements_dropped;
                     report.elements_added += step_report.elements_added;
+                    report.lossy_fields.extend(step_report.lossy_fields);
                 }
                 ConversionResult::Failure { error, .. } => {
                     return ConversionResult::Failure { error, report: report.clone() };
@@ -380,28 +528,73 @@ impl VersionConverter {
         }
     }
 
-    fn convert_single_step(&self, xml_content: &str, from: DdexVersion, to: DdexVersion, options: &ConversionOptions, report: &mut ConversionReport) -> ConversionResult {
+    fn convert_single_step(&self, xml_content: &str, from: DdexVersion, to: DdexVersion, options: &ConversionOptions) -> ConversionResult {
+        let mut report = ConversionReport::new(from, to);
         let rules = match self.conversion_rules.get(&(from, to)) {
             Some(rules) => rules,
             None => return ConversionResult::Failure {
                 error: format!("No direct conversion rules from {:?} to {:?}", from, to),
-                report: report.clone(),
+                report,
             },
         };
 
-        match self.transform_xml(xml_content, rules, options) {
-            Ok((transformed_xml, conversion_warnings)) => {
-                report.warnings.extend(conversion_warnings);
-                ConversionResult::Success {
-                    xml: transformed_xml,
-                    report: report.clone(),
-                }
-            }
-            Err(error) => ConversionResult::Failure {
+        let (transformed_xml, conversion_warnings) = match self.transform_xml(xml_content, rules, options) {
+            Ok(result) => result,
+            Err(error) => return ConversionResult::Failure {
                 error: error.to_string(),
-                report: report.clone(),
+                report,
+            },
+        };
+
+        for warning in &conversion_warnings {
+            match warning.warning_type {
+                ConversionWarningType::ElementDropped => {
+                    report.elements_dropped += 1;
+                    report.lossy_fields.push(LossyField {
+                        path: warning.element.clone().unwrap_or_default(),
+                        kind: LossKind::Dropped,
+                        detail: warning.message.clone(),
+                    });
+                }
+                ConversionWarningType::ElementRenamed => report.elements_converted += 1,
+                ConversionWarningType::ElementAdded => report.elements_added += 1,
+                _ => {}
             }
         }
+        report.warnings.extend(conversion_warnings);
+
+        // 3.8.2 and 4.x differ in where data lives, not just in names
+        let direction = match (from, to) {
+            (DdexVersion::Ern382, _) => Some(Direction::Upgrade),
+            (_, DdexVersion::Ern382) => Some(Direction::Downgrade),
+            _ => None,
+        };
+
+        let xml = match direction {
+            Some(direction) => match structural::transform(&transformed_xml, direction, options.preserve_comments) {
+                Ok((xml, lossy_fields)) => {
+                    for field in &lossy_fields {
+                        if field.kind == LossKind::Dropped {
+                            report.elements_dropped += 1;
+                        }
+                        report.warnings.push(ConversionWarning {
+                            warning_type: match field.kind {
+                                LossKind::Dropped => ConversionWarningType::ElementDropped,
+                                LossKind::Approximated => ConversionWarningType::FormatMigrated,
+                            },
+                            message: field.detail.clone(),
+                            element: Some(field.path.clone()),
+                        });
+                    }
+                    report.lossy_fields.extend(lossy_fields);
+                    xml
+                }
+                Err(error) => return ConversionResult::Failure { error, report },
+            },
+            None => transformed_xml,
+        };
+
+        ConversionResult::Success { xml, report }
     }
 
     fn transform_xml(&self, xml_content: &str, rules: &ConversionRules, options: &ConversionOptions) -> Result<(String, Vec<ConversionWarning>), Box<dyn std::error::Error>> {
@@ -539,25 +732,36 @@ impl VersionConverter {
     }
 
     fn update_namespace_attributes(&self, element: &mut BytesStart, namespace_mapping: &NamespaceMapping) {
-        // Update xmlns attributes to new namespace
-        let mut attrs_to_update = Vec::new();
-        
-        for (i, attr_result) in element.attributes().enumerate() {
-            if let Ok(attr) = attr_result {
-                let key = String::from_utf8_lossy(attr.key.as_ref());
-                let value = String::from_utf8_lossy(&attr.value);
-                
-                if key == "xmlns" && value == namespace_mapping.from {
-                    attrs_to_update.push((i, "xmlns".to_string(), namespace_mapping.to.clone()));
-                } else if key.starts_with("xmlns:") && value == namespace_mapping.from {
-                    attrs_to_update.push((i, key.to_string(), namespace_mapping.to.clone()));
-                }
-            }
+        // Rewrite namespace declarations and the schema version in place so the
+        // converted element does not carry both the old and new values
+        let mut rewritten = Vec::new();
+        let mut changed = false;
+
+        for attr in element.attributes().flatten() {
+            let key = String::from_utf8_lossy(attr.key.as_ref()).to_string();
+            let value = String::from_utf8_lossy(&attr.value).to_string();
+
+            let is_namespace = key == "xmlns" || key.starts_with("xmlns:");
+            let new_value = if is_namespace && value == namespace_mapping.from {
+                namespace_mapping.to.clone()
+            } else if key == "MessageSchemaVersionId" && value == namespace_mapping.schema_version_from {
+                namespace_mapping.schema_version_to.clone()
+            } else if key.ends_with("schemaLocation") && value.contains(&namespace_mapping.from) {
+                value.replace(&namespace_mapping.from, &namespace_mapping.to)
+            } else {
+                rewritten.push((key, value));
+                continue;
+            };
+
+            changed = true;
+            rewritten.push((key, new_value));
         }
 
-        // Apply namespace updates
-        for (_, key, new_value) in attrs_to_update {
-            element.extend_attributes(std::iter::once((key.as_str(), new_value.as_str())));
+        if changed {
+            element.clear_attributes();
+            for (key, value) in &rewritten {
+                element.push_attribute((key.as_str(), value.as_str()));
+            }
         }
     }
 
@@ -600,6 +804,191 @@ mod tests {
         assert_eq!(path, Some(vec![DdexVersion::Ern382, DdexVersion::Ern42, DdexVersion::Ern43]));
     }
 
+    #[test]
+    fn test_ern_44_conversion_paths() {
+        let converter = VersionConverter::new();
+        assert_eq!(
+            converter.find_conversion_path(DdexVersion::Ern43, DdexVersion::Ern44),
+            Some(vec![DdexVersion::Ern43, DdexVersion::Ern44])
+        );
+        assert_eq!(
+            converter.find_conversion_path(DdexVersion::Ern44, DdexVersion::Ern382),
+            Some(vec![DdexVersion::Ern44, DdexVersion::Ern43, DdexVersion::Ern42, DdexVersion::Ern382])
+        );
+    }
+
+    #[test]
+    fn test_43_to_44_rewrites_namespace() {
+        let converter = VersionConverter::new();
+        let xml = r#"<NewReleaseMessage xmlns="http://ddex.net/xml/ern/43" MessageSchemaVersionId="ern/43"><MessageHeader/></NewReleaseMessage>"#;
+        let result = converter.convert(xml, DdexVersion::Ern43, DdexVersion::Ern44, None);
+
+        match result {
+            ConversionResult::Success { xml: result_xml, .. } => {
+                assert!(result_xml.contains(r#"xmlns="http://ddex.net/xml/ern/44""#));
+                assert!(result_xml.contains(r#"MessageSchemaVersionId="ern/44""#));
+                assert!(!result_xml.contains("ern/43"));
+            }
+            ConversionResult::Failure { error, .. } => panic!("Conversion failed: {}", error),
+        }
+    }
+
+    #[test]
+    fn test_44_to_43_drops_new_elements() {
+        let converter = VersionConverter::new();
+        let xml = r#"<NewReleaseMessage xmlns="http://ddex.net/xml/ern/44" MessageSchemaVersionId="ern/44"><SoundRecording><ResourceRightsController>X</ResourceRightsController></SoundRecording></NewReleaseMessage>"#;
+        let result = converter.convert(xml, DdexVersion::Ern44, DdexVersion::Ern43, None);
+
+        match result {
+            ConversionResult::Success { xml: result_xml, report } => {
+                assert!(result_xml.contains(r#"xmlns="http://ddex.net/xml/ern/43""#));
+                assert!(!result_xml.contains("ResourceRightsController"));
+                assert!(!report.warnings.is_empty());
+            }
+            ConversionResult::Failure { error, .. } => panic!("Conversion failed: {}", error),
+        }
+    }
+
+    const ERN_382_MESSAGE: &str = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/382" MessageSchemaVersionId="ern/382">
+  <MessageHeader><MessageId>M1</MessageId></MessageHeader>
+  <ResourceList>
+    <SoundRecording>
+      <ResourceReference>A1</ResourceReference>
+      <ReferenceTitle><TitleText>Song</TitleText></ReferenceTitle>
+      <SoundRecordingDetailsByTerritory>
+        <TerritoryCode>Worldwide</TerritoryCode>
+        <DisplayArtist><PartyName><FullName>The Artist</FullName></PartyName><ArtistRole>MainArtist</ArtistRole></DisplayArtist>
+      </SoundRecordingDetailsByTerritory>
+    </SoundRecording>
+  </ResourceList>
+  <ReleaseList>
+    <Release>
+      <ReleaseReference>R1</ReleaseReference>
+      <ReleaseDetailsByTerritory>
+        <TerritoryCode>Worldwide</TerritoryCode>
+        <DisplayArtistName>The Artist</DisplayArtistName>
+        <LabelName>The Label</LabelName>
+        <Title TitleType="DisplayTitle"><TitleText>Album</TitleText></Title>
+        <DisplayArtist><PartyName><FullName>The Artist</FullName></PartyName><ArtistRole>MainArtist</ArtistRole></DisplayArtist>
+      </ReleaseDetailsByTerritory>
+      <ReleaseDetailsByTerritory>
+        <TerritoryCode>JP</TerritoryCode>
+        <Title TitleType="DisplayTitle"><TitleText>Album (Japan)</TitleText></Title>
+        <Character>Narrator</Character>
+        <ReleaseDate>2024-03-08</ReleaseDate>
+      </ReleaseDetailsByTerritory>
+      <GlobalReleaseDate>2024-03-01</GlobalReleaseDate>
+      <GlobalOriginalReleaseDate>1979-10-12</GlobalOriginalReleaseDate>
+    </Release>
+  </ReleaseList>
+  <DealList>
+    <ReleaseDeal>
+      <DealReleaseReference>R1</DealReleaseReference>
+      <Deal><DealTerms><CommercialModelType>SubscriptionModel</CommercialModelType><Usage><UseType>Stream</UseType></Usage><TerritoryCode>Worldwide</TerritoryCode></DealTerms></Deal>
+    </ReleaseDeal>
+  </DealList>
+</ern:NewReleaseMessage>"#;
+
+    fn expect_success(result: ConversionResult) -> (String, ConversionReport) {
+        match result {
+            ConversionResult::Success { xml, report } => (xml, report),
+            ConversionResult::Failure { error, .. } => panic!("Conversion failed: {}", error),
+        }
+    }
+
+    #[test]
+    fn test_382_to_43_flattens_territory_details() {
+        let converter = VersionConverter::new();
+        let (xml, _) = expect_success(converter.convert(ERN_382_MESSAGE, DdexVersion::Ern382, DdexVersion::Ern43, None));
+
+        assert!(xml.contains(r#"xmlns:ern="http://ddex.net/xml/ern/43""#));
+        assert!(!xml.contains("DetailsByTerritory"));
+        assert!(xml.contains("<DisplayTitle>"));
+        assert!(xml.contains(r#"<DisplayTitle ApplicableTerritoryCode="JP">"#));
+    }
+
+    #[test]
+    fn test_382_to_43_builds_party_list() {
+        let converter = VersionConverter::new();
+        let (xml, _) = expect_success(converter.convert(ERN_382_MESSAGE, DdexVersion::Ern382, DdexVersion::Ern43, None));
+
+        assert!(xml.contains("<PartyList>"));
+        assert!(xml.contains("<ArtistPartyReference>P1</ArtistPartyReference>"));
+        assert!(xml.contains("<DisplayArtistRole>MainArtist</DisplayArtistRole>"));
+        assert!(xml.contains("<ReleaseLabelReference>P2</ReleaseLabelReference>"));
+        // The same artist on the release and the recording is one party
+        assert_eq!(xml.matches("<Party>").count(), 2);
+    }
+
+    #[test]
+    fn test_382_to_43_unwraps_deal_usage() {
+        let converter = VersionConverter::new();
+        let (xml, _) = expect_success(converter.convert(ERN_382_MESSAGE, DdexVersion::Ern382, DdexVersion::Ern43, None));
+
+        assert!(!xml.contains("<Usage>"));
+        assert!(xml.contains("<UseType>Stream</UseType>"));
+        assert!(xml.contains("<CommercialModelType>SubscriptionModel</CommercialModelType>"));
+    }
+
+    #[test]
+    fn test_382_to_43_reports_lossy_fields() {
+        let converter = VersionConverter::new();
+        let (_, report) = expect_success(converter.convert(ERN_382_MESSAGE, DdexVersion::Ern382, DdexVersion::Ern43, None));
+
+        assert!(report.is_lossy());
+        let dropped = report.lossy_fields.iter()
+            .find(|f| f.path.ends_with("Character"))
+            .expect("Character should be reported");
+        assert_eq!(dropped.kind, LossKind::Dropped);
+        assert!(dropped.path.contains("Release[R1]"));
+        assert!(report.elements_dropped > 0);
+    }
+
+    #[test]
+    fn test_43_to_382_round_trip() {
+        let converter = VersionConverter::new();
+        let (ern_43, _) = expect_success(converter.convert(ERN_382_MESSAGE, DdexVersion::Ern382, DdexVersion::Ern43, None));
+        let (xml, report) = expect_success(converter.convert(&ern_43, DdexVersion::Ern43, DdexVersion::Ern382, None));
+
+        assert!(xml.contains(r#"xmlns:ern="http://ddex.net/xml/ern/382""#));
+        assert!(!xml.contains("PartyList"));
+        assert!(xml.contains("<ReleaseDetailsByTerritory>"));
+        assert!(xml.contains("<TerritoryCode>JP</TerritoryCode>"));
+        assert!(xml.contains("<LabelName>The Label</LabelName>"));
+        assert!(xml.contains("<FullName>The Artist</FullName>"));
+        assert!(xml.contains("<ArtistRole>MainArtist</ArtistRole>"));
+        assert!(xml.contains(r#"<Title TitleType="DisplayTitle">"#));
+        assert!(xml.contains("<Usage>"));
+        assert!(!report.is_lossy());
+    }
+
+    #[test]
+    fn test_382_43_release_dates() {
+        let converter = VersionConverter::new();
+        let (ern_43, _) = expect_success(converter.convert(ERN_382_MESSAGE, DdexVersion::Ern382, DdexVersion::Ern43, None));
+        assert!(!ern_43.contains("Global"));
+        assert!(ern_43.contains("<ReleaseDate>2024-03-01</ReleaseDate>"));
+        assert!(ern_43.contains(r#"<ReleaseDate ApplicableTerritoryCode="JP">2024-03-08</ReleaseDate>"#));
+        assert!(ern_43.contains("<OriginalReleaseDate>1979-10-12</OriginalReleaseDate>"));
+
+        let (xml, _) = expect_success(converter.convert(&ern_43, DdexVersion::Ern43, DdexVersion::Ern382, None));
+        assert!(xml.contains("<GlobalReleaseDate>2024-03-01</GlobalReleaseDate>"));
+        assert!(xml.contains("<GlobalOriginalReleaseDate>1979-10-12</GlobalOriginalReleaseDate>"));
+        assert!(xml.contains("<ReleaseDate>2024-03-08</ReleaseDate>"));
+        let blocks_end = xml.rfind("</ReleaseDetailsByTerritory>").unwrap();
+        assert!(xml.find("<GlobalReleaseDate>").unwrap() > blocks_end);
+    }
+
+    #[test]
+    fn test_43_to_382_reports_unresolved_party() {
+        let converter = VersionConverter::new();
+        let xml = r#"<NewReleaseMessage xmlns="http://ddex.net/xml/ern/43" MessageSchemaVersionId="ern/43"><ReleaseList><Release><ReleaseReference>R1</ReleaseReference><DisplayArtist><ArtistPartyReference>P9</ArtistPartyReference></DisplayArtist></Release></ReleaseList></NewReleaseMessage>"#;
+        let (result_xml, report) = expect_success(converter.convert(xml, DdexVersion::Ern43, DdexVersion::Ern382, None));
+
+        assert!(!result_xml.contains("ArtistPartyReference"));
+        assert!(report.lossy_fields.iter().any(|f| f.path == "Release[R1]/DisplayArtist"));
+    }
+
     #[test]
     fn test_same_version_conversion() {
         let converter = VersionConverter::new();
@@ -622,5 +1011,7 @@ mod tests {
         assert!(conversions.contains(&(DdexVersion::Ern42, DdexVersion::Ern43)));
         assert!(conversions.contains(&(DdexVersion::Ern43, DdexVersion::Ern42)));
         assert!(conversions.contains(&(DdexVersion::Ern42, DdexVersion::Ern382)));
+        assert!(conversions.contains(&(DdexVersion::Ern43, DdexVersion::Ern44)));
+        assert!(conversions.contains(&(DdexVersion::Ern44, DdexVersion::Ern43)));
     }
 }
\ No newline at end of file
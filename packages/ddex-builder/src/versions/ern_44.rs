@@ -0,0 +1,120 @@
+//! ERN 4.4 version specification and handling
+//!
+//! ERN 4.4 is a superset of ERN 4.3. The element vocabulary is carried over
+//! unchanged and a small number of optional elements are added, so most 4.3
+//! messages only need a namespace and schema version update.
+
+use super::*;
+use crate::presets::DdexVersion;
+
+/// Get ERN 4.4 version specification
+pub fn get_version_spec() -> VersionSpec {
+    // Start from the 4.3 vocabulary; 4.4 only adds elements
+    let base = ern_43::get_version_spec();
+    let mut element_mappings = base.element_mappings;
+    let mut namespace_prefixes = IndexMap::new();
+
+    namespace_prefixes.insert("ern".to_string(), "http://ddex.net/xml/ern/44".to_string());
+    namespace_prefixes.insert("avs".to_string(), "http://ddex.net/xml/avs/avs".to_string());
+    namespace_prefixes.insert("mead".to_string(), "http://ddex.net/xml/mead/mead".to_string());
+
+    let new_elements = vec![
+        // Elements new in 4.4 compared to 4.3
+        "DisplayArtistName".to_string(),
+        "DisplayTitleText".to_string(),
+        "ResourceRightsController".to_string(),
+        "LanguageOfPerformance".to_string(),
+        "ParentalWarningType".to_string(),
+        "ReleaseLabelReference".to_string(),
+    ];
+
+    for element in &new_elements {
+        element_mappings.insert(element.clone(), element.clone());
+    }
+
+    VersionSpec {
+        version: DdexVersion::Ern44,
+        namespace: "http://ddex.net/xml/ern/44".to_string(),
+        schema_location: Some("http://ddex.net/xml/ern/44 http://ddex.net/xml/ern/44/release-notification.xsd".to_string()),
+        message_schema_version_id: "ern/44".to_string(),
+        supported_message_types: base.supported_message_types,
+        element_mappings,
+        required_elements: base.required_elements,
+        deprecated_elements: vec![
+            // Nothing removed relative to 4.3
+        ],
+        new_elements,
+        namespace_prefixes,
+    }
+}
+
+/// Get ERN 4.4 namespace mappings
+pub fn get_namespace_mappings() -> IndexMap<String, String> {
+    let mut mappings = ern_43::get_namespace_mappings();
+    mappings.insert("ern".to_string(), "http://ddex.net/xml/ern/44".to_string());
+    mappings
+}
+
+/// Get ERN 4.4 specific XML template
+pub fn get_xml_template() -> &'static str {
+    r#"<?xml version="1.0" encoding="UTF-8"?>
+<NewReleaseMessage xmlns="http://ddex.net/xml/ern/44"
+                  xmlns:avs="http://ddex.net/xml/avs/avs"
+                  xmlns:mead="http://ddex.net/xml/mead/mead"
+                  MessageSchemaVersionId="ern/44">
+    <MessageHeader>
+        <MessageId>{message_id}</MessageId>
+        <MessageSender>
+            <PartyName>{sender_name}</PartyName>
+            <PartyId>{sender_id}</PartyId>
+            <PartyReference>{sender_ref}</PartyReference>
+        </MessageSender>
+        <MessageRecipient>
+            <PartyName>{recipient_name}</PartyName>
+            <PartyId>{recipient_id}</PartyId>
+            <PartyReference>{recipient_ref}</PartyReference>
+        </MessageRecipient>
+        <MessageControlType>{control_type}</MessageControlType>
+        <MessageCreatedDateTime>{created_datetime}</MessageCreatedDateTime>
+    </MessageHeader>
+
+    <ResourceList>
+        <!-- Resources will be populated here -->
+    </ResourceList>
+
+    <ReleaseList>
+        <!-- Releases will be populated here -->
+    </ReleaseList>
+
+    <DealList>
+        <!-- Deals will be populated here -->
+    </DealList>
+</NewReleaseMessage>"#
+}
+
+/// ERN 4.4 validation functions
+pub mod validation {
+    /// Get all validation errors for an ERN 4.4 message
+    pub fn validate_ern_44_message(xml_content: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        // Check required namespace
+        if !xml_content.contains("http://ddex.net/xml/ern/44") {
+            errors.push("Missing ERN 4.4 namespace".to_string());
+        }
+
+        // Check message schema version ID
+        if !xml_content.contains("ern/44") {
+            errors.push("Missing or incorrect MessageSchemaVersionId".to_string());
+        }
+
+        // Everything else follows the 4.3 rules
+        errors.extend(
+            super::ern_43::validation::validate_ern_43_message(xml_content)
+                .into_iter()
+                .filter(|e| !e.contains("ERN 4.3 namespace") && !e.contains("MessageSchemaVersionId")),
+        );
+
+        errors
+    }
+}
@@ -1,19 +1,26 @@
 //! Multi-version DDEX support and conversion
 //!
 //! This module provides comprehensive support for multiple DDEX ERN versions
-//! including 3.8.2, 4.2, and 4.3 with automatic conversion capabilities.
+//! including 3.8.2, 4.2, 4.3, and 4.4 with automatic conversion capabilities.
 //!
 //! # Supported Versions
 //! 
 //! - **ERN 3.8.2**: Legacy version with different namespaces and element structures
 //! - **ERN 4.2**: Intermediate version with some modern features
 //! - **ERN 4.3**: Current recommended version with full feature set
+//! - **ERN 4.4**: Latest version, a superset of 4.3 with additional optional elements
 //!
 //! # Version Conversion
 //!
 //! The system supports both upgrade and downgrade paths:
-//! - Upgrade: 3.8.2 → 4.2 → 4.3 (with feature enhancement)
-//! - Downgrade: 4.3 → 4.2 → 3.8.2 (with compatibility warnings)
+//! - Upgrade: 3.8.2 → 4.2 → 4.3 → 4.4 (with feature enhancement)
+//! - Downgrade: 4.4 → 4.3 → 4.2 → 3.8.2 (with compatibility warnings)
+//!
+//! Steps that cross the 3.8.2 boundary also restructure the message: territory
+//! detail blocks are flattened or regrouped, artists and labels move between
+//! inline names and the `PartyList`, and deal `Usage` wrappers are removed or
+//! restored. Anything that cannot be carried across is listed in
+//! `ConversionReport::lossy_fields`.
 //!
 //! # Examples
 //!
@@ -32,12 +39,14 @@ use serde::{Deserialize, Serialize};
 mod ern_382;
 mod ern_42;
 mod ern_43;
+mod ern_44;
 mod converter;
+mod structural;
 
 pub use ern_382::*;
 pub use ern_42::*;
 pub use ern_43::*;
-pub use converter::{VersionConverter, ConversionResult as ConverterResult, ConversionReport as ConverterReport, ConversionWarning as ConverterWarning, ConversionWarningType};
+pub use converter::{VersionConverter, ConversionResult as ConverterResult, ConversionReport as ConverterReport, ConversionWarning as ConverterWarning, ConversionWarningType, LossyField, LossKind};
 
 /// Version-specific DDEX metadata and constraints
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -279,6 +288,8 @@ pub struct FeatureSupport {
     pub ern_42: SupportLevel,
     /// Support in ERN 4.3
     pub ern_43: SupportLevel,
+    /// Support in ERN 4.4
+    pub ern_44: SupportLevel,
     /// Migration notes
     pub migration_notes: Option<String>,
 }
@@ -406,11 +417,12 @@ impl VersionManager {
         let mut version_scores = IndexMap::new();
         
         // Initialize scores
-        for version in [DdexVersion::Ern382, DdexVersion::Ern42, DdexVersion::Ern43] {
+        for version in [DdexVersion::Ern382, DdexVersion::Ern42, DdexVersion::Ern43, DdexVersion::Ern44] {
             version_scores.insert(version, 0.0);
         }
         
         // Analyze namespace
+        let mut namespace_version = None;
         if let Some(namespace) = self.extract_namespace(xml_content) {
             clues.push(DetectionClue {
                 clue_type: ClueType::Namespace,
@@ -422,6 +434,7 @@ impl VersionManager {
             for (version, spec) in &self.version_specs {
                 if spec.namespace == namespace {
                     *version_scores.get_mut(version).unwrap() += 0.8;
+                    namespace_version = Some(*version);
                 }
             }
         }
@@ -456,11 +469,15 @@ impl VersionManager {
             }
         }
         
-        // Determine best match
-        let (detected_version, confidence) = version_scores
-            .into_iter()
-            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
-            .unwrap();
+        // Determine best match; a known namespace is decisive, as schema
+        // version attributes and element clues are often stale
+        let (detected_version, confidence) = match namespace_version {
+            Some(version) => (version, version_scores[&version]),
+            None => version_scores
+                .into_iter()
+                .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+                .unwrap(),
+        };
         
         let normalized_confidence = (confidence / 2.5_f64).min(1.0_f64); // Normalize to 0-1
         
@@ -504,6 +521,7 @@ impl VersionManager {
         specs.insert(DdexVersion::Ern382, ern_382::get_version_spec());
         specs.insert(DdexVersion::Ern42, ern_42::get_version_spec());
         specs.insert(DdexVersion::Ern43, ern_43::get_version_spec());
+        specs.insert(DdexVersion::Ern44, ern_44::get_version_spec());
         
         specs
     }
@@ -546,7 +564,74 @@ impl VersionManager {
                 ],
                 production_ready: true,
             },
+            ConversionPath {
+                from: DdexVersion::Ern43,
+                to: DdexVersion::Ern44,
+                difficulty: ConversionDifficulty::Trivial,
+                fidelity: 0.98,
+                major_changes: vec![
+                    "Namespace and schema version update".to_string(),
+                    "New optional elements".to_string(),
+                ],
+                production_ready: true,
+            },
+            ConversionPath {
+                from: DdexVersion::Ern42,
+                to: DdexVersion::Ern44,
+                difficulty: ConversionDifficulty::Moderate,
+                fidelity: 0.93,
+                major_changes: vec![
+                    "Namespace migration".to_string(),
+                    "Minor element additions".to_string(),
+                ],
+                production_ready: true,
+            },
+            ConversionPath {
+                from: DdexVersion::Ern382,
+                to: DdexVersion::Ern44,
+                difficulty: ConversionDifficulty::Complex,
+                fidelity: 0.78,
+                major_changes: vec![
+                    "Major namespace changes".to_string(),
+                    "Significant structural updates".to_string(),
+                    "New required elements".to_string(),
+                ],
+                production_ready: true,
+            },
             // Downgrade paths
+            ConversionPath {
+                from: DdexVersion::Ern44,
+                to: DdexVersion::Ern43,
+                difficulty: ConversionDifficulty::Trivial,
+                fidelity: 0.95,
+                major_changes: vec![
+                    "Remove 4.4-only elements".to_string(),
+                ],
+                production_ready: true,
+            },
+            ConversionPath {
+                from: DdexVersion::Ern44,
+                to: DdexVersion::Ern42,
+                difficulty: ConversionDifficulty::Moderate,
+                fidelity: 0.88,
+                major_changes: vec![
+                    "Remove newer elements".to_string(),
+                    "Downgrade validation rules".to_string(),
+                ],
+                production_ready: true,
+            },
+            ConversionPath {
+                from: DdexVersion::Ern44,
+                to: DdexVersion::Ern382,
+                difficulty: ConversionDifficulty::Challenging,
+                fidelity: 0.68,
+                major_changes: vec![
+                    "Major structural downgrade".to_string(),
+                    "Significant feature removal".to_string(),
+                    "Legacy compatibility layer".to_string(),
+                ],
+                production_ready: false,
+            },
             ConversionPath {
                 from: DdexVersion::Ern43,
                 to: DdexVersion::Ern42,
@@ -602,6 +687,7 @@ impl VersionManager {
             ern_382: SupportLevel::Partial,
             ern_42: SupportLevel::Full,
             ern_43: SupportLevel::Full,
+            ern_44: SupportLevel::Full,
             migration_notes: Some("Enhanced in 4.2 with better linking".to_string()),
         });
         
@@ -610,6 +696,7 @@ impl VersionManager {
             ern_382: SupportLevel::None,
             ern_42: SupportLevel::Partial,
             ern_43: SupportLevel::Full,
+            ern_44: SupportLevel::Full,
             migration_notes: Some("New detailed terms structure in 4.2+".to_string()),
         });
         
@@ -618,6 +705,7 @@ impl VersionManager {
             ern_382: SupportLevel::None,
             ern_42: SupportLevel::None,
             ern_43: SupportLevel::New,
+            ern_44: SupportLevel::Full,
             migration_notes: Some("Completely new in 4.3".to_string()),
         });
         
@@ -626,9 +714,19 @@ impl VersionManager {
             ern_382: SupportLevel::Full,
             ern_42: SupportLevel::Deprecated,
             ern_43: SupportLevel::None,
+            ern_44: SupportLevel::None,
             migration_notes: Some("Removed in 4.3, use modern equivalents".to_string()),
         });
         
+        features.insert("ResourceRightsController".to_string(), FeatureSupport {
+            feature: "Resource-level Rights Controllers".to_string(),
+            ern_382: SupportLevel::None,
+            ern_42: SupportLevel::None,
+            ern_43: SupportLevel::None,
+            ern_44: SupportLevel::New,
+            migration_notes: Some("New in 4.4, dropped when downgrading to 4.3".to_string()),
+        });
+        
         features
     }
     
@@ -655,7 +753,7 @@ impl VersionManager {
             ConversionStrategy {
                 name: "Direct Upgrade".to_string(),
                 description: "Direct conversion between versions".to_string(),
-                scenarios: vec!["Ern42 to Ern43".to_string()],
+                scenarios: vec!["Ern42 to Ern43".to_string(), "Ern43 to Ern44".to_string()],
                 steps: vec![
                     "Validate source message".to_string(),
                     "Apply direct conversion mappings".to_string(),
@@ -672,8 +770,8 @@ impl VersionManager {
     }
     
     fn extract_namespace(&self, xml_content: &str) -> Option<String> {
-        // Simple regex to extract namespace from XML
-        let re = regex::Regex::new(r#"xmlns="([^"]+)""#).ok()?;
+        // The ERN namespace, whether default or bound to a prefix such as `ern:`
+        let re = regex::Regex::new(r#"xmlns(?::[\w.-]+)?="(http://ddex\.net/xml/ern/[^"]+)""#).ok()?;
         re.captures(xml_content)?
             .get(1)
             .map(|m| m.as_str().to_string())
@@ -681,9 +779,10 @@ impl VersionManager {
     
     fn extract_message_schema_version(&self, xml_content: &str) -> Option<String> {
         let re = regex::Regex::new(r#"MessageSchemaVersionId="([^"]+)""#).ok()?;
+        // Both "ern/43" and "ern/4.3" are written in practice
         re.captures(xml_content)?
             .get(1)
-            .map(|m| m.as_str().to_string())
+            .map(|m| m.as_str().replace('.', ""))
     }
 }
 
@@ -734,7 +833,7 @@ pub mod utils {
     
     /// Get all supported versions
     pub fn supported_versions() -> Vec<DdexVersion> {
-        vec![DdexVersion::Ern382, DdexVersion::Ern42, DdexVersion::Ern43]
+        vec![DdexVersion::Ern382, DdexVersion::Ern42, DdexVersion::Ern43, DdexVersion::Ern44]
     }
     
     /// Check if version is legacy
@@ -744,7 +843,7 @@ pub mod utils {
     
     /// Check if version is modern
     pub fn is_modern_version(version: DdexVersion) -> bool {
-        matches!(version, DdexVersion::Ern43)
+        matches!(version, DdexVersion::Ern43 | DdexVersion::Ern44)
     }
     
     /// Get version release date
@@ -753,6 +852,7 @@ pub mod utils {
             DdexVersion::Ern382 => chrono::NaiveDate::from_ymd_opt(2018, 5, 1).unwrap(),
             DdexVersion::Ern42 => chrono::NaiveDate::from_ymd_opt(2020, 8, 15).unwrap(),
             DdexVersion::Ern43 => chrono::NaiveDate::from_ymd_opt(2023, 3, 1).unwrap(),
+            DdexVersion::Ern44 => chrono::NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
             DdexVersion::Ern41 => chrono::NaiveDate::from_ymd_opt(2019, 11, 15).unwrap(),
         }
     }
@@ -763,7 +863,29 @@ pub mod utils {
             DdexVersion::Ern382 => "Legacy version with basic features".to_string(),
             DdexVersion::Ern42 => "Intermediate version with enhanced features".to_string(),
             DdexVersion::Ern43 => "Current version with full feature set".to_string(),
+            DdexVersion::Ern44 => "Latest version, superset of 4.3".to_string(),
             DdexVersion::Ern41 => "Early 4.x version".to_string(),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_version_with_prefixed_namespace() {
+        let xml = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" MessageSchemaVersionId="ern/4.3"/>"#;
+        let detection = VersionManager::new().detect_version(xml).unwrap();
+        assert_eq!(detection.detected_version, DdexVersion::Ern43);
+
+        let xml = r#"<NewReleaseMessage xmlns="http://ddex.net/xml/ern/382" MessageSchemaVersionId="ern/382"/>"#;
+        let detection = VersionManager::new().detect_version(xml).unwrap();
+        assert_eq!(detection.detected_version, DdexVersion::Ern382);
+
+        // The namespace wins over a stale MessageSchemaVersionId
+        let xml = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/382" MessageSchemaVersionId="ern/4.3"><PartyId/></ern:NewReleaseMessage>"#;
+        let detection = VersionManager::new().detect_version(xml).unwrap();
+        assert_eq!(detection.detected_version, DdexVersion::Ern382);
+    }
+}
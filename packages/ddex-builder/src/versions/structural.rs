@@ -0,0 +1,796 @@
+//! Tree-level transformations between ERN 3.8.2 and ERN 4.x
+//!
+//! The streaming pass in [`super::converter`] rewrites namespaces and renames
+//! elements one at a time. The differences between 3.8.2 and 4.x that move
+//! data to a different parent need the whole message in memory:
+//!
+//! - `ReleaseDetailsByTerritory` / `SoundRecordingDetailsByTerritory` blocks are
+//!   flattened onto the release or resource, with `ApplicableTerritoryCode`
+//!   marking territory-specific values
+//! - inline artist and label names become entries in a message-level `PartyList`
+//!   that releases and resources reference
+//! - the `Usage` wrapper in `DealTerms` is removed
+//! - the release-wide `GlobalReleaseDate` and `GlobalOriginalReleaseDate` of
+//!   3.8.2 become `ReleaseDate` and `OriginalReleaseDate` without a territory
+//!
+//! Anything that cannot be carried across is recorded as a [`LossyField`].
+
+use super::converter::{LossKind, LossyField};
+use crate::ast::{Element, Node, AST};
+use crate::determinism::DeterminismConfig;
+use crate::generator::xml_writer::XmlWriter;
+use indexmap::IndexMap;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+/// Territory code used by 3.8.2 for values that apply everywhere
+const WORLDWIDE: &str = "Worldwide";
+
+/// Release fields that live in `ReleaseDetailsByTerritory` in 3.8.2 and
+/// directly on `Release` in 4.x
+const RELEASE_TERRITORIAL_FIELDS: &[&str] = &[
+    "DisplayArtistName",
+    "DisplayArtist",
+    "LabelName",
+    "Title",
+    "Genre",
+    "ParentalWarningType",
+    "PLine",
+    "CLine",
+    "ReleaseDate",
+    "OriginalReleaseDate",
+    "ResourceGroup",
+    "Keywords",
+    "Synopsis",
+    "MarketingComment",
+];
+
+/// SoundRecording fields that live in `SoundRecordingDetailsByTerritory` in
+/// 3.8.2 and directly on `SoundRecording` in 4.x
+const SOUND_RECORDING_TERRITORIAL_FIELDS: &[&str] = &[
+    "DisplayArtistName",
+    "DisplayArtist",
+    "Title",
+    "ResourceContributor",
+    "Genre",
+    "ParentalWarningType",
+    "PLine",
+    "CLine",
+    "TechnicalDetails",
+    "TechnicalSoundRecordingDetails",
+];
+
+/// Fields that carry a single value per territory in 4.x. Anything else is
+/// taken from the first block that provides it.
+const PER_TERRITORY_FIELDS: &[&str] = &[
+    "DisplayArtistName",
+    "DisplayTitle",
+    "ReleaseLabelReference",
+    "Genre",
+    "ParentalWarningType",
+    "PLine",
+    "CLine",
+    "ReleaseDate",
+    "OriginalReleaseDate",
+    "Keywords",
+    "Synopsis",
+    "MarketingComment",
+];
+
+/// 3.8.2 release-wide dates and their 4.x elements
+const GLOBAL_DATES: &[(&str, &str)] = &[
+    ("GlobalReleaseDate", "ReleaseDate"),
+    ("GlobalOriginalReleaseDate", "OriginalReleaseDate"),
+];
+
+/// Direction of a structural conversion step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// ERN 3.8.2 to ERN 4.x
+    Upgrade,
+    /// ERN 4.x to ERN 3.8.2
+    Downgrade,
+}
+
+/// Apply the structural conversion to a serialized message
+pub fn transform(
+    xml: &str,
+    direction: Direction,
+    preserve_comments: bool,
+) -> Result<(String, Vec<LossyField>), String> {
+    let mut root = parse(xml, preserve_comments)?;
+    let mut lossy = Vec::new();
+
+    match direction {
+        Direction::Upgrade => upgrade(&mut root, &mut lossy),
+        Direction::Downgrade => downgrade(&mut root, &mut lossy),
+    }
+
+    let ast = AST {
+        root,
+        namespaces: IndexMap::new(),
+        schema_location: None,
+    };
+    let xml = XmlWriter::new(DeterminismConfig::default())
+        .write(&ast)
+        .map_err(|e| e.to_string())?;
+
+    Ok((xml, lossy))
+}
+
+/// ERN 3.8.2 → 4.x
+fn upgrade(root: &mut Element, lossy: &mut Vec<LossyField>) {
+    let mut parties = PartyRegistry::default();
+
+    // Resources come first so party references follow document order
+    for recording in descendants_mut(root, "SoundRecording") {
+        let path = entity_path("SoundRecording", recording, "ResourceReference");
+        flatten_territories(recording, "SoundRecordingDetailsByTerritory", SOUND_RECORDING_TERRITORIAL_FIELDS, &path, lossy);
+        convert_parties_to_references(recording, &mut parties, &path, lossy);
+    }
+
+    for release in descendants_mut(root, "Release") {
+        let path = entity_path("Release", release, "ReleaseReference");
+        flatten_territories(release, "ReleaseDetailsByTerritory", RELEASE_TERRITORIAL_FIELDS, &path, lossy);
+        upgrade_global_dates(release, &path, lossy);
+        convert_parties_to_references(release, &mut parties, &path, lossy);
+    }
+
+    for terms in descendants_mut(root, "DealTerms") {
+        unwrap_usage(terms, lossy);
+    }
+
+    if !parties.parties.is_empty() && child_index(root, "PartyList").is_none() {
+        let mut party_list = Element::new("PartyList");
+        for party in parties.parties {
+            party_list.add_child(party);
+        }
+        let position = child_index(root, "ResourceList")
+            .or_else(|| child_index(root, "MessageHeader").map(|i| i + 1))
+            .unwrap_or(root.children.len());
+        root.children.insert(position, Node::Element(party_list));
+    }
+}
+
+/// ERN 4.x → 3.8.2
+fn downgrade(root: &mut Element, lossy: &mut Vec<LossyField>) {
+    let mut parties = IndexMap::new();
+    if let Some(index) = child_index(root, "PartyList") {
+        if let Node::Element(party_list) = root.children.remove(index) {
+            for party in child_elements(&party_list) {
+                if let Some(reference) = child_text(party, "PartyReference") {
+                    parties.insert(reference, party.clone());
+                }
+            }
+        }
+    }
+    let mut used_parties = Vec::new();
+
+    for release in descendants_mut(root, "Release") {
+        let path = entity_path("Release", release, "ReleaseReference");
+        resolve_party_references(release, &parties, &mut used_parties, &path, lossy);
+        let global_dates = take_global_dates(release);
+        group_territories(release, "ReleaseDetailsByTerritory", RELEASE_TERRITORIAL_FIELDS, &path, lossy);
+        // 3.8.2 places them after the territory blocks
+        for date in global_dates {
+            release.add_child(date);
+        }
+    }
+
+    for recording in descendants_mut(root, "SoundRecording") {
+        let path = entity_path("SoundRecording", recording, "ResourceReference");
+        resolve_party_references(recording, &parties, &mut used_parties, &path, lossy);
+        group_territories(recording, "SoundRecordingDetailsByTerritory", SOUND_RECORDING_TERRITORIAL_FIELDS, &path, lossy);
+    }
+
+    for terms in descendants_mut(root, "DealTerms") {
+        wrap_usage(terms);
+    }
+
+    for (reference, _) in parties.iter().filter(|(r, _)| !used_parties.contains(r)) {
+        lossy.push(LossyField {
+            path: format!("PartyList/Party[{}]", reference),
+            kind: LossKind::Dropped,
+            detail: "ERN 3.8.2 has no message-level party list; unreferenced party removed".to_string(),
+        });
+    }
+}
+
+/// Move the contents of `*DetailsByTerritory` blocks onto their parent
+fn flatten_territories(
+    parent: &mut Element,
+    block_name: &str,
+    allowed: &[&str],
+    path: &str,
+    lossy: &mut Vec<LossyField>,
+) {
+    let Some(insert_at) = child_index(parent, block_name) else {
+        return;
+    };
+
+    let mut blocks = Vec::new();
+    let mut remaining = Vec::new();
+    for child in parent.children.drain(..) {
+        match child {
+            Node::Element(e) if local_name(&e.name) == block_name => blocks.push(e),
+            other => remaining.push(other),
+        }
+    }
+    parent.children = remaining;
+
+    let mut hoisted: Vec<Element> = Vec::new();
+    for block in blocks {
+        let territories: Vec<String> = child_elements(&block)
+            .filter(|e| local_name(&e.name) == "TerritoryCode")
+            .map(text_of)
+            .collect();
+        let block_path = format!("{}/{}[{}]", path, block_name, territories.join(","));
+
+        if child_elements(&block).any(|e| local_name(&e.name) == "ExcludedTerritoryCode") {
+            lossy.push(LossyField {
+                path: format!("{}/ExcludedTerritoryCode", block_path),
+                kind: LossKind::Approximated,
+                detail: "Territory exclusions cannot be expressed on flattened 4.x fields".to_string(),
+            });
+        }
+
+        let worldwide = territories.is_empty() || territories.iter().any(|t| t == WORLDWIDE);
+
+        for field in child_elements(&block) {
+            let name = local_name(&field.name);
+            if name == "TerritoryCode" || name == "ExcludedTerritoryCode" {
+                continue;
+            }
+            if !allowed.contains(&name) {
+                lossy.push(LossyField {
+                    path: format!("{}/{}", block_path, name),
+                    kind: LossKind::Dropped,
+                    detail: format!("{} has no ERN 4.x equivalent on {}", name, local_name(&parent.name)),
+                });
+                continue;
+            }
+
+            let field = upgrade_field(field.clone(), &block_path, lossy);
+            let target = local_name(&field.name).to_string();
+
+            if PER_TERRITORY_FIELDS.contains(&target.as_str()) {
+                if worldwide {
+                    hoisted.push(field);
+                } else {
+                    for territory in &territories {
+                        hoisted.push(field.clone().with_attr("ApplicableTerritoryCode", territory.clone()));
+                    }
+                }
+            } else if hoisted.iter().any(|h| h.name == target) {
+                if !worldwide {
+                    lossy.push(LossyField {
+                        path: format!("{}/{}", block_path, name),
+                        kind: LossKind::Dropped,
+                        detail: format!("{} is not territory-specific in ERN 4.x; the first value is kept", target),
+                    });
+                }
+            } else {
+                hoisted.push(field);
+            }
+        }
+    }
+
+    let insert_at = insert_at.min(parent.children.len());
+    for (offset, field) in hoisted.into_iter().enumerate() {
+        parent.children.insert(insert_at + offset, Node::Element(field));
+    }
+}
+
+/// Rename a 3.8.2 territory-block field to its 4.x form
+fn upgrade_field(mut field: Element, block_path: &str, lossy: &mut Vec<LossyField>) -> Element {
+    match local_name(&field.name) {
+        "Title" => {
+            if let Some(title_type) = field.attributes.shift_remove("TitleType") {
+                if title_type != "DisplayTitle" {
+                    lossy.push(LossyField {
+                        path: format!("{}/Title[@TitleType={}]", block_path, title_type),
+                        kind: LossKind::Approximated,
+                        detail: "Typed titles are carried as DisplayTitle in ERN 4.x".to_string(),
+                    });
+                }
+            }
+            field.name = "DisplayTitle".to_string();
+        }
+        "TechnicalSoundRecordingDetails" => field.name = "TechnicalDetails".to_string(),
+        _ => {}
+    }
+    field
+}
+
+/// Inverse of [`flatten_territories`]
+fn group_territories(
+    parent: &mut Element,
+    block_name: &str,
+    allowed: &[&str],
+    path: &str,
+    lossy: &mut Vec<LossyField>,
+) {
+    let movable = |name: &str| {
+        name == "DisplayTitle"
+            || name == "TechnicalDetails"
+            || name == "Contributor"
+            || name == "ReleaseLabelReference"
+            || allowed.contains(&name)
+    };
+
+    let mut groups: IndexMap<String, Element> = IndexMap::new();
+    let mut remaining = Vec::new();
+    for child in parent.children.drain(..) {
+        match child {
+            Node::Element(mut e) if movable(local_name(&e.name)) => {
+                let territory = e
+                    .attributes
+                    .shift_remove("ApplicableTerritoryCode")
+                    .unwrap_or_else(|| WORLDWIDE.to_string());
+                let block = groups.entry(territory.clone()).or_insert_with(|| {
+                    Element::new(block_name).with_child(Element::new("TerritoryCode").with_text(territory))
+                });
+                block.add_child(downgrade_field(e));
+            }
+            Node::Element(e) if local_name(&e.name) == "DisplayTitleText" => {
+                lossy.push(LossyField {
+                    path: format!("{}/DisplayTitleText", path),
+                    kind: LossKind::Dropped,
+                    detail: "DisplayTitleText does not exist in ERN 3.8.2; use ReferenceTitle".to_string(),
+                });
+            }
+            other => remaining.push(other),
+        }
+    }
+    parent.children = remaining;
+
+    for (_, block) in groups {
+        parent.add_child(block);
+    }
+}
+
+/// Rename a 4.x field to its 3.8.2 territory-block form
+fn downgrade_field(mut field: Element) -> Element {
+    match local_name(&field.name) {
+        "DisplayTitle" => {
+            field.name = "Title".to_string();
+            field.attributes.shift_insert(0, "TitleType".to_string(), "DisplayTitle".to_string());
+        }
+        "Contributor" => field.name = "ResourceContributor".to_string(),
+        _ => {}
+    }
+    field
+}
+
+/// Rename 3.8.2 release-wide dates to their 4.x form, unless a `Worldwide`
+/// territory block already gave one
+fn upgrade_global_dates(release: &mut Element, path: &str, lossy: &mut Vec<LossyField>) {
+    for (global, name) in GLOBAL_DATES {
+        let Some(index) = child_index(release, global) else { continue };
+        let Node::Element(mut date) = release.children.remove(index) else { continue };
+        let worldwide = child_elements(release)
+            .find(|e| local_name(&e.name) == *name && !e.attributes.contains_key("ApplicableTerritoryCode"));
+        match worldwide {
+            Some(existing) => {
+                if text_of(existing) != text_of(&date) {
+                    lossy.push(LossyField {
+                        path: format!("{}/{}", path, global),
+                        kind: LossKind::Dropped,
+                        detail: format!("{} differs from the Worldwide {}, which is kept", global, name),
+                    });
+                }
+            }
+            None => {
+                date.name = name.to_string();
+                release.children.insert(index, Node::Element(date));
+            }
+        }
+    }
+}
+
+/// Remove the 4.x dates that apply to every territory, renamed to their
+/// 3.8.2 release-wide form
+fn take_global_dates(release: &mut Element) -> Vec<Element> {
+    let mut dates = Vec::new();
+    let mut remaining = Vec::new();
+    for child in release.children.drain(..) {
+        match child {
+            Node::Element(mut e) if !e.attributes.contains_key("ApplicableTerritoryCode") => {
+                match GLOBAL_DATES.iter().find(|(_, name)| local_name(&e.name) == *name) {
+                    Some((global, _)) => {
+                        e.name = global.to_string();
+                        dates.push(e);
+                    }
+                    None => remaining.push(Node::Element(e)),
+                }
+            }
+            other => remaining.push(other),
+        }
+    }
+    release.children = remaining;
+    // Schema order: GlobalReleaseDate before GlobalOriginalReleaseDate
+    dates.sort_by_key(|e| GLOBAL_DATES.iter().position(|(global, _)| e.name == *global));
+    dates
+}
+
+/// Message-level party list built during an upgrade
+#[derive(Default)]
+struct PartyRegistry {
+    parties: Vec<Element>,
+    references: IndexMap<String, String>,
+}
+
+impl PartyRegistry {
+    /// Register a party by its name/ID elements and return its reference
+    fn register(&mut self, name: Option<&Element>, id: Option<&Element>, label: Option<&str>) -> String {
+        let key = id
+            .map(text_of)
+            .filter(|k| !k.is_empty())
+            .or_else(|| name.map(text_of))
+            .or_else(|| label.map(|l| l.to_string()))
+            .unwrap_or_default();
+
+        if let Some(reference) = self.references.get(&key) {
+            return reference.clone();
+        }
+
+        let reference = format!("P{}", self.parties.len() + 1);
+        let mut party = Element::new("Party");
+        party.add_child(Element::new("PartyReference").with_text(reference.clone()));
+        if let Some(id) = id {
+            party.add_child(id.clone());
+        }
+        match (name, label) {
+            (Some(name), _) => party.add_child(name.clone()),
+            (None, Some(label)) => party.add_child(
+                Element::new("PartyName").with_child(Element::new("FullName").with_text(label)),
+            ),
+            (None, None) => {}
+        }
+
+        self.parties.push(party);
+        self.references.insert(key, reference.clone());
+        reference
+    }
+}
+
+/// Replace inline party details with references into the party list
+fn convert_parties_to_references(
+    parent: &mut Element,
+    parties: &mut PartyRegistry,
+    path: &str,
+    lossy: &mut Vec<LossyField>,
+) {
+    for child in parent.children.iter_mut() {
+        let Node::Element(e) = child else { continue };
+        match local_name(&e.name) {
+            "DisplayArtist" => {
+                reference_party(e, parties, "ArtistPartyReference", &[("ArtistRole", "DisplayArtistRole")]);
+            }
+            "ResourceContributor" => {
+                reference_party(e, parties, "ContributorPartyReference", &[("ResourceContributorRole", "Role")]);
+                e.name = "Contributor".to_string();
+            }
+            "LabelName" if local_name(&parent.name) == "Release" => {
+                let label = text_of(e);
+                let reference = parties.register(None, None, Some(&label));
+                *e = Element::new("ReleaseLabelReference").with_text(reference);
+            }
+            _ => {}
+        }
+    }
+
+    // SoundRecording has no label in 4.x
+    if local_name(&parent.name) == "SoundRecording" {
+        if let Some(index) = child_index(parent, "LabelName") {
+            parent.children.remove(index);
+            lossy.push(LossyField {
+                path: format!("{}/LabelName", path),
+                kind: LossKind::Dropped,
+                detail: "SoundRecording has no label in ERN 4.x".to_string(),
+            });
+        }
+    }
+}
+
+fn reference_party(
+    element: &mut Element,
+    parties: &mut PartyRegistry,
+    reference_name: &str,
+    renames: &[(&str, &str)],
+) {
+    let name = child_element(element, "PartyName").cloned();
+    let id = child_element(element, "PartyId").cloned();
+    if name.is_none() && id.is_none() {
+        return;
+    }
+
+    let reference = parties.register(name.as_ref(), id.as_ref(), None);
+    let mut children = vec![Node::Element(Element::new(reference_name).with_text(reference))];
+    for child in element.children.drain(..) {
+        match child {
+            Node::Element(e) if matches!(local_name(&e.name), "PartyName" | "PartyId") => {}
+            Node::Element(mut e) => {
+                if let Some((_, to)) = renames.iter().find(|(from, _)| *from == local_name(&e.name)) {
+                    e.name = to.to_string();
+                }
+                children.push(Node::Element(e));
+            }
+            other => children.push(other),
+        }
+    }
+    element.children = children;
+}
+
+/// Inverse of [`convert_parties_to_references`]
+fn resolve_party_references(
+    parent: &mut Element,
+    parties: &IndexMap<String, Element>,
+    used: &mut Vec<String>,
+    path: &str,
+    lossy: &mut Vec<LossyField>,
+) {
+    let parent_name = local_name(&parent.name).to_string();
+    let mut dropped = Vec::new();
+
+    for (index, child) in parent.children.iter_mut().enumerate() {
+        let Node::Element(e) = child else { continue };
+        // Owned, as the guards below need `e` mutably
+        let name = local_name(&e.name).to_string();
+        match name.as_str() {
+            "DisplayArtist" if !inline_party(e, parties, used, "ArtistPartyReference", &[("DisplayArtistRole", "ArtistRole")]) => {
+                dropped.push((index, "DisplayArtist"));
+            }
+            "Contributor" if !inline_party(e, parties, used, "ContributorPartyReference", &[("Role", "ResourceContributorRole")]) => {
+                dropped.push((index, "Contributor"));
+            }
+            "ReleaseLabelReference" => {
+                let reference = text_of(e);
+                let label = parties.get(&reference).and_then(|p| {
+                    child_element(p, "PartyName").map(|n| child_text(n, "FullName").unwrap_or_else(|| text_of(n)))
+                });
+                match label {
+                    Some(label) => {
+                        used.push(reference);
+                        let territory = e.attributes.get("ApplicableTerritoryCode").cloned();
+                        let mut label_name = Element::new("LabelName").with_text(label);
+                        if let Some(territory) = territory {
+                            label_name.attributes.insert("ApplicableTerritoryCode".to_string(), territory);
+                        }
+                        *e = label_name;
+                    }
+                    None => dropped.push((index, "ReleaseLabelReference")),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for (index, name) in dropped.into_iter().rev() {
+        parent.children.remove(index);
+        lossy.push(LossyField {
+            path: format!("{}/{}", path, name),
+            kind: LossKind::Dropped,
+            detail: format!("Party reference on {} could not be resolved from PartyList", parent_name),
+        });
+    }
+}
+
+fn inline_party(
+    element: &mut Element,
+    parties: &IndexMap<String, Element>,
+    used: &mut Vec<String>,
+    reference_name: &str,
+    renames: &[(&str, &str)],
+) -> bool {
+    let Some(reference) = child_text(element, reference_name) else {
+        // Already inline
+        return true;
+    };
+    let Some(party) = parties.get(&reference) else {
+        return false;
+    };
+    used.push(reference);
+
+    let mut children: Vec<Node> = child_elements(party)
+        .filter(|e| matches!(local_name(&e.name), "PartyName" | "PartyId"))
+        .map(|e| Node::Element(e.clone()))
+        .collect();
+    for child in element.children.drain(..) {
+        match child {
+            Node::Element(e) if local_name(&e.name) == reference_name => {}
+            Node::Element(mut e) => {
+                if let Some((_, to)) = renames.iter().find(|(from, _)| *from == local_name(&e.name)) {
+                    e.name = to.to_string();
+                }
+                children.push(Node::Element(e));
+            }
+            other => children.push(other),
+        }
+    }
+    element.children = children;
+    true
+}
+
+/// Hoist `Usage/UseType` directly into `DealTerms`
+fn unwrap_usage(terms: &mut Element, lossy: &mut Vec<LossyField>) {
+    let Some(index) = child_index(terms, "Usage") else {
+        return;
+    };
+
+    let mut hoisted = Vec::new();
+    let mut remaining = Vec::new();
+    for child in terms.children.drain(..) {
+        match child {
+            Node::Element(usage) if local_name(&usage.name) == "Usage" => {
+                for field in child_elements(&usage) {
+                    if local_name(&field.name) == "UseType" {
+                        hoisted.push(Node::Element(field.clone()));
+                    } else {
+                        lossy.push(LossyField {
+                            path: format!("DealTerms/Usage/{}", local_name(&field.name)),
+                            kind: LossKind::Dropped,
+                            detail: "Only UseType is carried over from Usage in ERN 4.x".to_string(),
+                        });
+                    }
+                }
+            }
+            other => remaining.push(other),
+        }
+    }
+
+    terms.children = remaining;
+    let index = index.min(terms.children.len());
+    terms.children.splice(index..index, hoisted);
+}
+
+/// Collect `DealTerms/UseType` back into a `Usage` wrapper
+fn wrap_usage(terms: &mut Element) {
+    let Some(index) = child_index(terms, "UseType") else {
+        return;
+    };
+
+    let mut usage = Element::new("Usage");
+    let mut remaining = Vec::new();
+    for child in terms.children.drain(..) {
+        match child {
+            Node::Element(e) if local_name(&e.name) == "UseType" => usage.add_child(e),
+            other => remaining.push(other),
+        }
+    }
+
+    terms.children = remaining;
+    let index = index.min(terms.children.len());
+    terms.children.insert(index, Node::Element(usage));
+}
+
+// Tree helpers
+
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn child_elements(element: &Element) -> impl Iterator<Item = &Element> {
+    element.children.iter().filter_map(|c| match c {
+        Node::Element(e) => Some(e),
+        _ => None,
+    })
+}
+
+fn child_element<'a>(element: &'a Element, name: &str) -> Option<&'a Element> {
+    child_elements(element).find(|e| local_name(&e.name) == name)
+}
+
+fn child_index(element: &Element, name: &str) -> Option<usize> {
+    element
+        .children
+        .iter()
+        .position(|c| matches!(c, Node::Element(e) if local_name(&e.name) == name))
+}
+
+fn child_text(element: &Element, name: &str) -> Option<String> {
+    child_element(element, name).map(text_of)
+}
+
+/// Concatenated text content of an element and its descendants
+fn text_of(element: &Element) -> String {
+    let mut text = String::new();
+    for child in &element.children {
+        match child {
+            Node::Text(t) => text.push_str(t),
+            Node::Element(e) => text.push_str(&text_of(e)),
+            _ => {}
+        }
+    }
+    text.trim().to_string()
+}
+
+fn entity_path(kind: &str, element: &Element, reference: &str) -> String {
+    match child_text(element, reference) {
+        Some(r) => format!("{}[{}]", kind, r),
+        None => kind.to_string(),
+    }
+}
+
+/// All descendants with the given local name, outermost first
+fn descendants_mut<'a>(root: &'a mut Element, name: &str) -> Vec<&'a mut Element> {
+    let mut found = Vec::new();
+    for child in root.children.iter_mut() {
+        if let Node::Element(e) = child {
+            if local_name(&e.name) == name {
+                found.push(e);
+            } else {
+                found.extend(descendants_mut(e, name));
+            }
+        }
+    }
+    found
+}
+
+fn parse(xml: &str, preserve_comments: bool) -> Result<Element, String> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut stack: Vec<Element> = Vec::new();
+    let mut buf = Vec::new();
+
+    loop {
+        let event = reader
+            .read_event_into(&mut buf)
+            .map_err(|e| format!("Error parsing XML: {}", e))?;
+
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let mut element = Element::new(String::from_utf8_lossy(e.name().as_ref()).to_string());
+                for attr in e.attributes() {
+                    let attr = attr.map_err(|e| format!("Attribute error: {}", e))?;
+                    let key = String::from_utf8_lossy(attr.key.as_ref()).to_string();
+                    let value = attr
+                        .unescape_value()
+                        .map_err(|e| format!("Attribute error: {}", e))?
+                        .to_string();
+                    element.attributes.insert(key, value);
+                }
+
+                if matches!(event, Event::Start(_)) {
+                    stack.push(element);
+                } else if let Some(parent) = stack.last_mut() {
+                    parent.add_child(element);
+                } else {
+                    return Ok(element);
+                }
+            }
+            Event::End(_) => {
+                let element = stack.pop().ok_or("Unbalanced end tag")?;
+                match stack.last_mut() {
+                    Some(parent) => parent.add_child(element),
+                    None => return Ok(element),
+                }
+            }
+            Event::Text(ref e) => {
+                let text = e.unescape().map_err(|e| format!("Text unescape error: {}", e))?;
+                if let Some(parent) = stack.last_mut() {
+                    parent.add_text(text.to_string());
+                }
+            }
+            Event::CData(ref e) => {
+                if let Some(parent) = stack.last_mut() {
+                    parent.add_text(String::from_utf8_lossy(e).to_string());
+                }
+            }
+            Event::Comment(ref e) => {
+                if preserve_comments {
+                    if let Some(parent) = stack.last_mut() {
+                        let comment = String::from_utf8_lossy(e).trim().to_string();
+                        parent.add_simple_comment(comment);
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Err("No root element found".to_string())
+}
@@ -37,9 +37,10 @@ fn create_platform_agnostic_request() -> BuildRequest {
                 party_id: Some("RECIPIENT001".to_string()),
                 party_reference: Some("REF_RECIPIENT".to_string()),
             },
-            message_control_type: Some("NewReleaseMessage".to_string()),
+            message_control_type: Some("LiveMessage".to_string()),
             // Use fixed timestamp for deterministic results
             message_created_date_time: Some("2024-01-01T12:00:00.000Z".to_string()),
+            ..Default::default()
         },
         version: "ern/43".to_string(),
         profile: Some("PlatformTestProfile".to_string()),
@@ -56,6 +57,15 @@ fn create_platform_agnostic_request() -> BuildRequest {
             upc: Some("123456789012".to_string()),
             tracks: Vec::new(),
             resource_references: None,
+            resources: Vec::new(),
+            territory_details: Vec::new(),
+            contributors: Vec::new(),
+            p_line: None,
+            c_line: None,
+            track_isrcs: Vec::new(),
+            track_releases: Vec::new(),
+            comments: Vec::new(),
+            original_release_date: None,
         }],
         deals: vec![DealRequest {
             deal_reference: Some("PLAT_DEAL001".to_string()),
@@ -63,8 +73,11 @@ fn create_platform_agnostic_request() -> BuildRequest {
                 commercial_model_type: "FreeOfChargeModel".to_string(),
                 territory_code: vec!["Worldwide".to_string()],
                 start_date: Some("2024-01-01".to_string()),
+                ..Default::default()
             },
             release_references: vec!["PLAT_REL001".to_string()],
+            release_ids: Vec::new(),
+            comments: Vec::new(),
         }],
         extensions: Some({
             let mut ext = IndexMap::new();
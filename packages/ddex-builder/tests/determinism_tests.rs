@@ -8,7 +8,7 @@
 //! - Different locales
 //! - Memory pressure conditions
 
-use ddex_builder::{DDEXBuilder, BuildRequest};
+use ddex_builder::{DDEXBuilder, BuildRequest, TimestampSource};
 use ddex_builder::builder::{MessageHeaderRequest, PartyRequest, LocalizedStringRequest, ReleaseRequest, DealRequest, BuildOptions};
 use serde_json::json;
 use indexmap::IndexMap;
@@ -37,8 +37,9 @@ fn create_test_build_request() -> BuildRequest {
                 party_id: None,
                 party_reference: None,
             },
-            message_control_type: Some("NewReleaseMessage".to_string()),
+            message_control_type: Some("LiveMessage".to_string()),
             message_created_date_time: Some(chrono::Utc::now().to_rfc3339()),
+            ..Default::default()
         },
         version: "ern/43".to_string(),
         profile: Some("BasicProfile".to_string()),
@@ -55,6 +56,15 @@ fn create_test_build_request() -> BuildRequest {
             upc: None,
             tracks: Vec::new(),
             resource_references: None,
+            resources: Vec::new(),
+            territory_details: Vec::new(),
+            contributors: Vec::new(),
+            p_line: None,
+            c_line: None,
+            track_isrcs: Vec::new(),
+            track_releases: Vec::new(),
+            comments: Vec::new(),
+            original_release_date: None,
         }],
         deals: vec![DealRequest {
             deal_reference: Some("DEAL001".to_string()),
@@ -62,8 +72,11 @@ fn create_test_build_request() -> BuildRequest {
                 commercial_model_type: "FreeOfChargeModel".to_string(),
                 territory_code: vec!["Worldwide".to_string()],
                 start_date: Some("2024-01-01".to_string()),
+                ..Default::default()
             },
             release_references: vec!["REL001".to_string()],
+            release_ids: Vec::new(),
+            comments: Vec::new(),
         }],
         extensions: Some(IndexMap::new()),
     }
@@ -94,8 +107,9 @@ fn create_complex_build_request() -> BuildRequest {
                 party_id: None,
                 party_reference: None,
             },
-            message_control_type: Some("NewReleaseMessage".to_string()),
+            message_control_type: Some("LiveMessage".to_string()),
             message_created_date_time: Some(chrono::Utc::now().to_rfc3339()),
+            ..Default::default()
         },
         version: "ern/43".to_string(),
         profile: Some("ComplexProfile".to_string()),
@@ -112,6 +126,15 @@ fn create_complex_build_request() -> BuildRequest {
             upc: Some("123456789012".to_string()),
             tracks: Vec::new(),
             resource_references: None,
+            resources: Vec::new(),
+            territory_details: Vec::new(),
+            contributors: Vec::new(),
+            p_line: None,
+            c_line: None,
+            track_isrcs: Vec::new(),
+            track_releases: Vec::new(),
+            comments: Vec::new(),
+            original_release_date: None,
         }],
         deals: (0..5).map(|i| {
             DealRequest {
@@ -120,8 +143,11 @@ fn create_complex_build_request() -> BuildRequest {
                     commercial_model_type: "FreeOfChargeModel".to_string(),
                     territory_code: vec!["Worldwide".to_string()],
                     start_date: Some("2024-01-01".to_string()),
+                    ..Default::default()
                 },
                 release_references: vec![format!("REL{:04}", i)],
+                release_ids: Vec::new(),
+                comments: Vec::new(),
             }
         }).collect(),
         extensions: Some(extensions),
@@ -346,6 +372,15 @@ fn test_large_dataset_determinism() {
             upc: Some(format!("{:012}", i)),
             tracks: Vec::new(),
             resource_references: None,
+            resources: Vec::new(),
+            territory_details: Vec::new(),
+            contributors: Vec::new(),
+            p_line: None,
+            c_line: None,
+            track_isrcs: Vec::new(),
+            track_releases: Vec::new(),
+            comments: Vec::new(),
+            original_release_date: None,
         }
     }).collect();
     
@@ -468,6 +503,27 @@ fn test_determinism_consistency() {
     assert!(result1.xml.len() > 500, "Should produce substantial XML output");
 }
 
+#[test]
+fn test_fixed_timestamp_source_determinism() {
+    let mut request = create_test_build_request();
+    request.header.message_created_date_time = None;
+    let builder = DDEXBuilder::new();
+    let options = BuildOptions {
+        determinism: Some(ddex_builder::DeterminismConfig {
+            timestamp_source: TimestampSource::Fixed("2024-01-01T00:00:00Z".to_string()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    
+    let result1 = builder.build(request.clone(), options.clone()).expect("Fixed timestamp build 1 failed");
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    let result2 = builder.build(request, options).expect("Fixed timestamp build 2 failed");
+    
+    assert_eq!(result1.xml, result2.xml, "Builds with a fixed timestamp source should be identical");
+    assert!(result1.xml.contains("2024-01-01T00:00:00Z"), "Should use the fixed timestamp");
+}
+
 #[cfg(test)]
 mod integration_tests {
     use super::*;
@@ -287,6 +287,7 @@ impl FidelityTestRunner {
                 },
                 message_control_type: None,
                 message_created_date_time: Some("2024-01-01T00:00:00Z".to_string()),
+                ..Default::default()
             },
             version: message.version.clone(),
             profile: None,
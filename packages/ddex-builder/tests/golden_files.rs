@@ -32,6 +32,7 @@ fn test_audio_album_golden() {
             },
             message_control_type: Some("LiveMessage".to_string()),
             message_created_date_time: Some("2025-01-01T00:00:00Z".to_string()),  // Fixed timestamp
+            ..Default::default()
         },
         version: "4.3".to_string(),
         profile: Some("AudioAlbum".to_string()),
@@ -46,7 +47,7 @@ fn test_audio_album_golden() {
                 artist: "Test Artist".to_string(),
                 label: Some("Test Label".to_string()),
                 release_date: Some("2024-01-01".to_string()),
-                upc: Some("123456789014".to_string()),
+                upc: Some("123456789012".to_string()),
                 tracks: vec![
                     TrackRequest {
                         track_id: "TRK001".to_string(),
@@ -55,6 +56,12 @@ fn test_audio_album_golden() {
                         title: "Track One".to_string(),
                         duration: "PT3M45S".to_string(),
                         artist: "Test Artist".to_string(),
+                        contributors: Vec::new(),
+                        p_line: None,
+                        c_line: None,
+                        comments: Vec::new(),
+                        technical_details: None,
+                        parental_warning: None,
                     },
                     TrackRequest {
                         track_id: "TRK002".to_string(),
@@ -63,9 +70,24 @@ fn test_audio_album_golden() {
                         title: "Track Two".to_string(),
                         duration: "PT4M20S".to_string(),
                         artist: "Test Artist feat. Guest".to_string(),
+                        contributors: Vec::new(),
+                        p_line: None,
+                        c_line: None,
+                        comments: Vec::new(),
+                        technical_details: None,
+                        parental_warning: None,
                     },
                 ],
                 resource_references: None,
+                resources: Vec::new(),
+                territory_details: Vec::new(),
+                contributors: Vec::new(),
+                p_line: None,
+                c_line: None,
+                track_isrcs: Vec::new(),
+                track_releases: Vec::new(),
+                comments: Vec::new(),
+                original_release_date: None,
             },
         ],
         deals: vec![],
@@ -77,6 +99,21 @@ fn test_audio_album_golden() {
         preflight_level: ddex_builder::preflight::PreflightLevel::Warn,
         id_strategy: IdStrategy::StableHash,
         stable_hash_config: None,
+        required_fields: Vec::new(),
+        preserve_comments: false,
+        fail_on_warning: false,
+        max_memory: None,
+        cancellation: None,
+        cache_dir: None,
+        skip_deal_timeline: false,
+        skip_release_date_checks: false,
+        skip_track_releases: false,
+        sanitize_text: false,
+        genre_taxonomy: None,
+        namespace_strategy: Default::default(),
+        extension_namespaces: Default::default(),
+        extension_schema_dir: None,
+        validation_plugins: Default::default(),
     };
     
     let result = builder.build(request, options).unwrap();
@@ -102,6 +139,21 @@ fn test_deterministic_generation() {
         preflight_level: ddex_builder::preflight::PreflightLevel::Strict,
         id_strategy: IdStrategy::StableHash,
         stable_hash_config: None,
+        required_fields: Vec::new(),
+        preserve_comments: false,
+        fail_on_warning: false,
+        max_memory: None,
+        cancellation: None,
+        cache_dir: None,
+        skip_deal_timeline: false,
+        skip_release_date_checks: false,
+        skip_track_releases: false,
+        sanitize_text: false,
+        genre_taxonomy: None,
+        namespace_strategy: Default::default(),
+        extension_namespaces: Default::default(),
+        extension_schema_dir: None,
+        validation_plugins: Default::default(),
     };
     
     // Generate multiple times
@@ -129,6 +181,21 @@ fn test_preflight_validation() {
         preflight_level: ddex_builder::preflight::PreflightLevel::Strict,
         id_strategy: IdStrategy::UUID,
         stable_hash_config: None,
+        required_fields: Vec::new(),
+        preserve_comments: false,
+        fail_on_warning: false,
+        max_memory: None,
+        cancellation: None,
+        cache_dir: None,
+        skip_deal_timeline: false,
+        skip_release_date_checks: false,
+        skip_track_releases: false,
+        sanitize_text: false,
+        genre_taxonomy: None,
+        namespace_strategy: Default::default(),
+        extension_namespaces: Default::default(),
+        extension_schema_dir: None,
+        validation_plugins: Default::default(),
     };
     
     let result = builder.build(request, options);
@@ -168,9 +235,44 @@ fn test_stable_hash_ids() {
     assert!(id1.starts_with("B3:"));
 }
 
+#[test]
+fn test_content_addressed_references() {
+    use ddex_builder::id_generator::{StableHashGenerator, StableHashConfig};
+    
+    let mut generator = StableHashGenerator::new(StableHashConfig::default());
+    let reference = generator.generate_resource_reference("USRC12345679", "Another Track").unwrap();
+    
+    assert!(reference.starts_with('A'));
+    assert_eq!(reference.len(), 17);
+    assert_eq!(reference, generator.generate_resource_reference("USRC12345679", " another track ").unwrap());
+    assert_ne!(reference, generator.generate_resource_reference("USRC12345678", "Another Track").unwrap());
+    assert!(generator.generate_release_reference("123456789012", "Test Release").unwrap().starts_with('R'));
+    
+    // Removing a sibling track must not renumber the remaining one
+    let mut request = create_test_request();
+    request.releases[0].release_reference = None;
+    for track in &mut request.releases[0].tracks {
+        track.resource_reference = None;
+    }
+    let mut smaller = request.clone();
+    smaller.releases[0].tracks.remove(0);
+    
+    let options = BuildOptions {
+        preflight_level: ddex_builder::preflight::PreflightLevel::Warn,
+        id_strategy: IdStrategy::StableHash,
+        ..Default::default()
+    };
+    let builder = DDEXBuilder::new();
+    let full = builder.build(request, options.clone()).unwrap();
+    let partial = builder.build(smaller, options).unwrap();
+    
+    assert!(full.xml.contains(&reference));
+    assert!(partial.xml.contains(&reference));
+}
+
 #[test]
 fn test_profile_validation() {
-    use ddex_builder::preflight::{PreflightValidator, ValidationConfig};
+    use ddex_builder::preflight::{ParentalWarningCheck, PreflightValidator, ReleaseDateRules, ValidationConfig};
     
     let config = ValidationConfig {
         level: ddex_builder::preflight::PreflightLevel::Strict,
@@ -179,7 +281,13 @@ fn test_profile_validation() {
         check_required_fields: true,
         validate_dates: true,
         validate_references: true,
+        validate_avs: true,
         profile: Some("AudioAlbum".to_string()),
+        required_fields: Vec::new(),
+        check_deal_timeline: true,
+        as_of: None,
+        release_dates: Some(ReleaseDateRules::default()),
+        parental_warnings: ParentalWarningCheck::Warn,
     };
     
     let validator = PreflightValidator::new(config);
@@ -193,6 +301,91 @@ fn test_profile_validation() {
     assert!(result.warnings[0].code == "ALBUM_TRACK_COUNT");
 }
 
+#[test]
+fn test_memory_ceiling() {
+    let builder = DDEXBuilder::new();
+    
+    let result = builder.build(create_test_request(), BuildOptions::default()).unwrap();
+    let peak = result.statistics.peak_memory_bytes;
+    assert!(peak > result.xml.len());
+    
+    let options = BuildOptions { max_memory: Some(peak), ..Default::default() };
+    assert!(builder.build(create_test_request(), options).is_ok());
+    
+    let options = BuildOptions { max_memory: Some(result.xml.len()), ..Default::default() };
+    let error = builder.build(create_test_request(), options).unwrap_err();
+    assert!(matches!(error, ddex_builder::error::BuildError::MemoryLimitExceeded { .. }));
+}
+
+#[test]
+fn test_cancelled_build() {
+    let token = ddex_core::cancel::CancellationToken::new();
+    token.cancel();
+    let options = BuildOptions { cancellation: Some(token), ..Default::default() };
+    let error = DDEXBuilder::new().build(create_test_request(), options).unwrap_err();
+    assert_eq!(error.code(), "DDEX-B-0603");
+    assert!(matches!(error, ddex_builder::error::BuildError::Cancelled { ref stage, .. } if stage == "preflight"));
+}
+
+#[test]
+fn test_incremental_cache() {
+    let builder = DDEXBuilder::new();
+    let cache = tempfile::tempdir().unwrap();
+    let options = BuildOptions {
+        id_strategy: IdStrategy::StableHash,
+        cache_dir: Some(cache.path().to_path_buf()),
+        ..Default::default()
+    };
+    
+    let first = builder.build(create_test_request(), options.clone()).unwrap();
+    let stats = first.statistics.cache.clone().unwrap();
+    assert_eq!(stats.hits, 0);
+    assert!(stats.misses > 0);
+    
+    let second = builder.build(create_test_request(), options.clone()).unwrap();
+    assert_eq!(second.xml, first.xml);
+    let rebuilt = second.statistics.cache.unwrap();
+    assert_eq!((rebuilt.hits, rebuilt.misses), (stats.misses, 0));
+    
+    // Only the changed release is serialized again
+    let mut request = create_test_request();
+    request.releases[0].title[0].text = "Test Release (Deluxe)".to_string();
+    let changed = builder.build(request.clone(), options).unwrap();
+    let partial = changed.statistics.cache.unwrap();
+    assert!(partial.hits > 0 && partial.misses > 0);
+    let uncached = BuildOptions { id_strategy: IdStrategy::StableHash, ..Default::default() };
+    assert_eq!(changed.xml, builder.build(request, uncached).unwrap().xml);
+}
+
+#[test]
+fn test_namespace_strategies_are_deterministic() {
+    use ddex_builder::OptimizationStrategy;
+    
+    let builder = DDEXBuilder::new();
+    let build = |strategy| {
+        let options = BuildOptions {
+            id_strategy: IdStrategy::StableHash,
+            namespace_strategy: strategy,
+            ..Default::default()
+        };
+        builder.build(create_test_request(), options).unwrap().xml
+    };
+    
+    for strategy in [
+        OptimizationStrategy::Minimal,
+        OptimizationStrategy::HoistAll,
+        OptimizationStrategy::Conservative,
+        OptimizationStrategy::PreserveOriginal,
+    ] {
+        assert_eq!(build(strategy), build(strategy), "{:?} is not byte-identical", strategy);
+    }
+    
+    // Nothing uses xsi, so only the minimal layout drops it
+    assert!(!build(OptimizationStrategy::Minimal).contains("xmlns:xsi"));
+    assert!(build(OptimizationStrategy::HoistAll).contains("xmlns:avs="));
+    assert_eq!(build(OptimizationStrategy::Conservative), build(OptimizationStrategy::PreserveOriginal));
+}
+
 fn create_test_request() -> BuildRequest {
     BuildRequest {
         header: MessageHeaderRequest {
@@ -215,6 +408,7 @@ fn create_test_request() -> BuildRequest {
             },
             message_control_type: Some("LiveMessage".to_string()),
             message_created_date_time: Some("2025-01-01T00:00:00Z".to_string()),  // Fixed timestamp
+            ..Default::default()
         },
         version: "4.3".to_string(),
         profile: Some("AudioAlbum".to_string()),
@@ -229,7 +423,7 @@ fn create_test_request() -> BuildRequest {
                 artist: "Test Artist".to_string(),
                 label: Some("Test Label".to_string()),
                 release_date: Some("2024-01-01".to_string()),
-                upc: Some("123456789014".to_string()),
+                upc: Some("123456789012".to_string()),
                 tracks: vec![
                     TrackRequest {
                         track_id: "TRK001".to_string(),
@@ -238,6 +432,12 @@ fn create_test_request() -> BuildRequest {
                         title: "Test Track".to_string(),
                         duration: "PT3M30S".to_string(),
                         artist: "Test Artist".to_string(),
+                        contributors: Vec::new(),
+                        p_line: None,
+                        c_line: None,
+                        comments: Vec::new(),
+                        technical_details: None,
+                        parental_warning: None,
                     },
                     TrackRequest {
                         track_id: "TRK002".to_string(),
@@ -246,9 +446,24 @@ fn create_test_request() -> BuildRequest {
                         title: "Another Track".to_string(),
                         duration: "PT4M00S".to_string(),
                         artist: "Test Artist".to_string(),
+                        contributors: Vec::new(),
+                        p_line: None,
+                        c_line: None,
+                        comments: Vec::new(),
+                        technical_details: None,
+                        parental_warning: None,
                     },
                 ],
                 resource_references: None,
+                resources: Vec::new(),
+                territory_details: Vec::new(),
+                contributors: Vec::new(),
+                p_line: None,
+                c_line: None,
+                track_isrcs: Vec::new(),
+                track_releases: Vec::new(),
+                comments: Vec::new(),
+                original_release_date: None,
             },
         ],
         deals: vec![],
@@ -2,7 +2,7 @@ use ddex_builder::{ReferenceLinker, EntityType, LinkerConfig};
 use ddex_builder::linker::{ReferenceStyle, LinkerError};
 use ddex_builder::builder::{
     BuildRequest, MessageHeaderRequest, PartyRequest, 
-    LocalizedStringRequest, ReleaseRequest, TrackRequest
+    LocalizedStringRequest, ReleaseRequest, TrackRequest, DealRequest, DealTerms
 };
 
 #[test]
@@ -183,6 +183,59 @@ fn test_custom_reference_style() {
     assert_eq!(ref2, "R_1");
 }
 
+#[test]
+fn test_linking_by_identifier() {
+    let mut linker = ReferenceLinker::new();
+    let mut request = create_test_build_request();
+    request.releases[0].upc = Some("123456789012".to_string());
+    
+    // A single reusing the album's second track, by ISRC
+    let mut single = request.releases[0].clone();
+    single.release_id = "REL_002".to_string();
+    single.upc = None;
+    single.tracks.clear();
+    single.track_isrcs = vec!["USRC12345679".to_string()];
+    request.releases.push(single);
+    
+    request.deals.push(DealRequest {
+        deal_reference: None,
+        deal_terms: DealTerms::default(),
+        release_references: vec![],
+        release_ids: vec!["123456789012".to_string(), "REL_002".to_string()],
+        comments: Vec::new(),
+    });
+    
+    linker.auto_link_request(&mut request).unwrap();
+    
+    assert_eq!(request.releases[1].resource_references, Some(vec!["A2".to_string()]));
+    assert_eq!(request.deals[0].release_references, vec!["R1".to_string(), "R2".to_string()]);
+}
+
+#[test]
+fn test_deal_with_unknown_release_error() {
+    let mut linker = ReferenceLinker::new();
+    let mut request = create_test_build_request();
+    request.deals.push(DealRequest {
+        deal_reference: None,
+        deal_terms: DealTerms::default(),
+        release_references: vec![],
+        release_ids: vec!["MISSING_UPC".to_string()],
+        comments: Vec::new(),
+    });
+    
+    match linker.auto_link_request(&mut request).unwrap_err() {
+        LinkerError::UnknownRelease(id) => assert_eq!(id, "MISSING_UPC"),
+        other => panic!("Expected UnknownRelease error, got {:?}", other),
+    }
+    
+    let mut request = create_test_build_request();
+    request.releases[0].track_isrcs = vec!["GBXXX0000001".to_string()];
+    assert!(matches!(
+        linker.auto_link_request(&mut request),
+        Err(LinkerError::UnknownResource(_))
+    ));
+}
+
 // Helper function to create a test build request
 fn create_test_build_request() -> BuildRequest {
     BuildRequest {
@@ -206,6 +259,7 @@ fn create_test_build_request() -> BuildRequest {
             },
             message_control_type: Some("LiveMessage".to_string()),
             message_created_date_time: None,  // Add to existing MessageHeaderRequest structs
+            ..Default::default()
         },
         version: "4.3".to_string(),
         profile: Some("AudioAlbum".to_string()),
@@ -229,6 +283,12 @@ fn create_test_build_request() -> BuildRequest {
                         title: "Track 1".to_string(),
                         duration: "PT3M30S".to_string(),
                         artist: "Test Artist".to_string(),
+                        contributors: Vec::new(),
+                        p_line: None,
+                        c_line: None,
+                        comments: Vec::new(),
+                        technical_details: None,
+                        parental_warning: None,
                     },
                     TrackRequest {
                         track_id: "TRACK_002".to_string(),
@@ -237,9 +297,24 @@ fn create_test_build_request() -> BuildRequest {
                         title: "Track 2".to_string(),
                         duration: "PT4M15S".to_string(),
                         artist: "Test Artist".to_string(),
+                        contributors: Vec::new(),
+                        p_line: None,
+                        c_line: None,
+                        comments: Vec::new(),
+                        technical_details: None,
+                        parental_warning: None,
                     },
                 ],
                 resource_references: None,
+                resources: Vec::new(),
+                territory_details: Vec::new(),
+                contributors: Vec::new(),
+                p_line: None,
+                c_line: None,
+                track_isrcs: Vec::new(),
+                track_releases: Vec::new(),
+                comments: Vec::new(),
+                original_release_date: None,
             },
         ],
         deals: vec![],
@@ -1,7 +1,7 @@
 use ddex_builder::{DDEXBuilder, BuildOptions, ReferenceLinker};
 use ddex_builder::builder::{
-    BuildRequest, MessageHeaderRequest, PartyRequest, 
-    LocalizedStringRequest, ReleaseRequest, TrackRequest
+    BuildRequest, IdStrategy, MessageHeaderRequest, PartyRequest, 
+    LocalizedStringRequest, ReleaseRequest, TrackRequest, DealRequest, DealTerms
 };
 
 #[test]
@@ -31,6 +31,7 @@ fn test_linker_with_xml_generation() {
             },
             message_control_type: Some("LiveMessage".to_string()),
             message_created_date_time: None,  // Add to existing MessageHeaderRequest structs
+            ..Default::default()
         },
         version: "4.3".to_string(),
         profile: Some("AudioAlbum".to_string()),
@@ -54,6 +55,12 @@ fn test_linker_with_xml_generation() {
                         title: "First Linked Track".to_string(),
                         duration: "PT3M00S".to_string(),
                         artist: "Linked Artist".to_string(),
+                        contributors: Vec::new(),
+                        p_line: None,
+                        c_line: None,
+                        comments: Vec::new(),
+                        technical_details: None,
+                        parental_warning: None,
                     },
                     TrackRequest {
                         track_id: "TRK_002".to_string(),
@@ -62,9 +69,24 @@ fn test_linker_with_xml_generation() {
                         title: "Second Linked Track".to_string(),
                         duration: "PT4M00S".to_string(),
                         artist: "Linked Artist".to_string(),
+                        contributors: Vec::new(),
+                        p_line: None,
+                        c_line: None,
+                        comments: Vec::new(),
+                        technical_details: None,
+                        parental_warning: None,
                     },
                 ],
                 resource_references: None, // Will be auto-generated
+                resources: Vec::new(),
+                territory_details: Vec::new(),
+                contributors: Vec::new(),
+                p_line: None,
+                c_line: None,
+                track_isrcs: Vec::new(),
+                track_releases: Vec::new(),
+                comments: Vec::new(),
+                original_release_date: None,
             },
         ],
         deals: vec![],
@@ -116,6 +138,80 @@ fn test_deterministic_linking() {
     );
 }
 
+#[test]
+fn test_build_resolves_deal_release_ids() {
+    let builder = DDEXBuilder::new();
+    let mut request = create_simple_request();
+    request.deals.push(DealRequest {
+        deal_reference: Some("D1".to_string()),
+        deal_terms: DealTerms {
+            commercial_model_type: "SubscriptionModel".to_string(),
+            territory_code: vec!["Worldwide".to_string()],
+            ..Default::default()
+        },
+        release_references: vec![],
+        release_ids: vec!["REL1".to_string()],
+        comments: Vec::new(),
+    });
+    
+    let options = BuildOptions {
+        id_strategy: IdStrategy::Sequential,
+        ..Default::default()
+    };
+    let result = builder.build(request.clone(), options.clone()).unwrap();
+    assert!(result.xml.contains("<DealReleaseReference>R1</DealReleaseReference>"));
+    
+    request.deals[0].release_ids = vec!["REL9".to_string()];
+    assert!(builder.build(request, options).is_err());
+}
+
+#[test]
+fn test_album_profile_track_release_deals() {
+    let builder = DDEXBuilder::new();
+    let mut request = create_simple_request();
+    request.profile = Some("AudioAlbum".to_string());
+    request.deals.push(DealRequest {
+        deal_reference: Some("D1".to_string()),
+        deal_terms: DealTerms {
+            commercial_model_type: "PayAsYouGoModel".to_string(),
+            territory_code: vec!["Worldwide".to_string()],
+            ..Default::default()
+        },
+        release_references: vec![],
+        release_ids: vec!["US123".to_string()],
+        comments: Vec::new(),
+    });
+    let options = BuildOptions {
+        id_strategy: IdStrategy::Sequential,
+        ..Default::default()
+    };
+    
+    // The track gets its own TrackRelease, which the deal targets by ISRC
+    let result = builder.build(request.clone(), options.clone()).unwrap();
+    assert!(result.xml.contains("<TrackRelease>"));
+    assert!(result.xml.contains("<DealReleaseReference>R2</DealReleaseReference>"));
+    assert!(!result.xml.contains("<DealReleaseReference>R1</DealReleaseReference>"));
+    
+    // Opting out keeps the output of builds without generated track releases
+    let skipped = BuildOptions { skip_track_releases: true, ..options.clone() };
+    let mut untargeted = request.clone();
+    untargeted.deals[0].release_ids = vec!["REL1".to_string()];
+    let result = builder.build(untargeted, skipped).unwrap();
+    assert!(!result.xml.contains("<TrackRelease>"));
+    
+    // An untargeted deal is ambiguous once the message carries two releases
+    let mut second = request.releases[0].clone();
+    second.release_id = "REL2".to_string();
+    request.releases.push(second);
+    request.deals[0].release_ids.clear();
+    let strict = BuildOptions {
+        preflight_level: ddex_builder::preflight::PreflightLevel::Strict,
+        ..options
+    };
+    let error = builder.build(request, strict).unwrap_err();
+    assert!(error.to_string().contains("AMBIGUOUS_DEAL_SCOPE"));
+}
+
 fn create_simple_request() -> BuildRequest {
     BuildRequest {
         header: MessageHeaderRequest {
@@ -132,6 +228,7 @@ fn create_simple_request() -> BuildRequest {
             },
             message_control_type: None,
             message_created_date_time: None,  // Add to existing MessageHeaderRequest structs
+            ..Default::default()
         },
         version: "4.3".to_string(),
         profile: None,
@@ -152,9 +249,24 @@ fn create_simple_request() -> BuildRequest {
                         title: "Track".to_string(),
                         duration: "PT3M".to_string(),
                         artist: "Artist".to_string(),
+                        contributors: Vec::new(),
+                        p_line: None,
+                        c_line: None,
+                        comments: Vec::new(),
+                        technical_details: None,
+                        parental_warning: None,
                     },
                 ],
                 resource_references: None,
+                resources: Vec::new(),
+                territory_details: Vec::new(),
+                contributors: Vec::new(),
+                p_line: None,
+                c_line: None,
+                track_isrcs: Vec::new(),
+                track_releases: Vec::new(),
+                comments: Vec::new(),
+                original_release_date: None,
             },
         ],
         deals: vec![],
@@ -158,6 +158,12 @@ fn create_test_request(track_count: usize) -> BuildRequest {
             title: format!("Test Track {}", i + 1),
             duration: format!("PT{}M{}S", 3 + (i % 4), 15 + (i % 45)),
             artist: format!("Artist {}", (i % 5) + 1), // Simulate repeated artists
+            contributors: Vec::new(),
+            p_line: None,
+            c_line: None,
+            comments: Vec::new(),
+            technical_details: None,
+            parental_warning: None,
         });
     }
     
@@ -182,6 +188,7 @@ fn create_test_request(track_count: usize) -> BuildRequest {
             },
             message_control_type: Some("LiveMessage".to_string()),
             message_created_date_time: None,
+            ..Default::default()
         },
         version: "4.3".to_string(),
         profile: Some("CommonReleaseTypes/14/AudioAlbumMusicOnly".to_string()),
@@ -204,6 +211,15 @@ fn create_test_request(track_count: usize) -> BuildRequest {
             upc: Some("123456789012".to_string()),
             tracks,
             resource_references: None,
+            resources: Vec::new(),
+            territory_details: Vec::new(),
+            contributors: Vec::new(),
+            p_line: None,
+            c_line: None,
+            track_isrcs: Vec::new(),
+            track_releases: Vec::new(),
+            comments: Vec::new(),
+            original_release_date: None,
         }],
         deals: vec![],
         extensions: None,
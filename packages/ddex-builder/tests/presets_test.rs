@@ -1,7 +1,7 @@
 //! Comprehensive tests for DDEX preset functionality
 
 use ddex_builder::presets::{
-    all_presets, generic, youtube, DdexVersion, MessageProfile, ValidationRule, PartnerPreset, PresetConfig, PresetSource
+    all_presets, generic, presets_by_partner, youtube, DdexVersion, MessageProfile, ValidationRule, PartnerPreset, PresetConfig, PresetSource
 };
 use ddex_builder::{Builder, error::BuildError};
 use indexmap::IndexMap;
@@ -121,7 +121,7 @@ fn test_preset_validation_rules() {
         
         // Check version is set correctly
         match preset.config.version {
-            DdexVersion::Ern382 | DdexVersion::Ern41 | DdexVersion::Ern42 | DdexVersion::Ern43 => {
+            DdexVersion::Ern382 | DdexVersion::Ern41 | DdexVersion::Ern42 | DdexVersion::Ern43 | DdexVersion::Ern44 => {
                 // Valid version
             }
         }
@@ -409,17 +409,35 @@ fn test_no_speculative_platform_presets() {
     
     // Ensure no speculative platform presets remain
     let speculative_names = [
-        "spotify", "apple", "amazon", "deezer", "tidal", "pandora", 
-        "universal", "sony", "warner", "distrokid", "tunecore"
+        "spotify", "apple", "universal", "sony", "warner", "distrokid", "tunecore"
     ];
     
     for preset_name in presets.keys() {
         for speculative in &speculative_names {
             assert!(!preset_name.to_lowercase().contains(speculative),
-                    "Found speculative preset '{}' - only YouTube (public docs) and generic presets should exist", 
+                    "Found speculative preset '{}' - only presets based on public docs and generic presets should exist", 
                     preset_name);
         }
     }
     
-    println!("✅ No speculative platform presets found - only YouTube + Generic");
-}
\ No newline at end of file
+    println!("✅ No speculative platform presets found");
+}
+
+#[test]
+fn test_platform_presets_by_partner() {
+    let partners = presets_by_partner();
+    assert_eq!(
+        partners.keys().copied().collect::<Vec<_>>(),
+        ["Generic", "YouTube", "Amazon", "Deezer", "Tidal", "Beatport", "Pandora"]
+    );
+    assert_eq!(partners.values().map(|presets| presets.len()).sum::<usize>(), all_presets().len());
+
+    for (partner, presets) in partners.iter().filter(|(partner, _)| **partner != "Generic") {
+        for (name, preset) in presets {
+            assert_eq!(preset.source, PresetSource::PublicDocs, "{} preset {} should be based on public docs", partner, name);
+            assert!(name.starts_with(&partner.to_lowercase()));
+            assert!(preset.disclaimer.contains("community-maintained"));
+            assert!(preset.disclaimer.contains("not an official"));
+        }
+    }
+}
@@ -31,6 +31,7 @@ fn test_linker_with_xml_generation() {
             },
             message_control_type: Some("LiveMessage".to_string()),
             message_created_date_time: None,  // Add to existing MessageHeaderRequest structs
+            ..Default::default()
         },
         version: "4.3".to_string(),
         profile: Some("AudioAlbum".to_string()),
@@ -54,6 +55,12 @@ fn test_linker_with_xml_generation() {
                         title: "First Linked Track".to_string(),
                         duration: "PT3M00S".to_string(),
                         artist: "Linked Artist".to_string(),
+                        contributors: Vec::new(),
+                        p_line: None,
+                        c_line: None,
+                        comments: Vec::new(),
+                        technical_details: None,
+                        parental_warning: None,
                     },
                     TrackRequest {
                         track_id: "TRK_002".to_string(),
@@ -62,9 +69,24 @@ fn test_linker_with_xml_generation() {
                         title: "Second Linked Track".to_string(),
                         duration: "PT4M00S".to_string(),
                         artist: "Linked Artist".to_string(),
+                        contributors: Vec::new(),
+                        p_line: None,
+                        c_line: None,
+                        comments: Vec::new(),
+                        technical_details: None,
+                        parental_warning: None,
                     },
                 ],
                 resource_references: None,  // Add this
+                resources: Vec::new(),
+                territory_details: Vec::new(),
+                contributors: Vec::new(),
+                p_line: None,
+                c_line: None,
+                track_isrcs: Vec::new(),
+                track_releases: Vec::new(),
+                comments: Vec::new(),
+                original_release_date: None,
             },
         ],
         deals: vec![],
@@ -168,6 +190,7 @@ fn create_simple_request() -> BuildRequest {
             },
             message_control_type: None,
             message_created_date_time: None,  // Add to existing MessageHeaderRequest structs
+            ..Default::default()
         },
         version: "4.3".to_string(),
         profile: None,
@@ -188,12 +211,57 @@ fn create_simple_request() -> BuildRequest {
                         title: "Track".to_string(),
                         duration: "PT3M".to_string(),
                         artist: "Artist".to_string(),
+                        contributors: Vec::new(),
+                        p_line: None,
+                        c_line: None,
+                        comments: Vec::new(),
+                        technical_details: None,
+                        parental_warning: None,
                     },
                 ],
                 resource_references: None,
+                resources: Vec::new(),
+                territory_details: Vec::new(),
+                contributors: Vec::new(),
+                p_line: None,
+                c_line: None,
+                track_isrcs: Vec::new(),
+                track_releases: Vec::new(),
+                comments: Vec::new(),
+                original_release_date: None,
             },
         ],
         deals: vec![],
         extensions: None,
     }
-}
\ No newline at end of file
+}
+#[test]
+fn test_build_batch() {
+    let builder = DDEXBuilder::new();
+    let mut requests: Vec<BuildRequest> = (0..8)
+        .map(|i| {
+            let mut request = create_simple_request();
+            request.header.message_id = Some(format!("BATCH_{}", i));
+            request
+        })
+        .collect();
+    requests[3].releases[0].track_isrcs = vec!["USXXX0000000".to_string()];
+
+    let results = builder.build_batch(requests, BuildOptions::default());
+
+    // Input order is kept and one failure doesn't abort the batch
+    assert_eq!(results.len(), 8);
+    for (i, result) in results.iter().enumerate() {
+        if i == 3 {
+            assert!(result.xml.is_empty());
+            assert_eq!(result.errors.len(), 1);
+        } else {
+            assert!(result.errors.is_empty(), "{:?}", result.errors);
+            assert!(result.xml.contains(&format!("<MessageId>BATCH_{}</MessageId>", i)));
+        }
+    }
+
+    let stats = builder.stats();
+    assert_eq!((stats.messages, stats.failed), (8, 1));
+    assert!(stats.us_per_message > 0.0);
+}
@@ -0,0 +1,37 @@
+// core/benches/borrowed.rs
+//! Owned versus zero-copy parsing of a 100MB catalog
+//!
+//! `parse_borrowed` is expected to reach at least twice the throughput of
+//! `parse` here; compare the two lines of the `borrowed_vs_owned` group.
+mod common;
+
+use common::catalog;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use ddex_parser::DDEXParser;
+use std::io::Cursor;
+use std::time::Duration;
+
+const TARGET_BYTES: usize = 100 * 1024 * 1024;
+
+fn benchmark_borrowed_vs_owned(c: &mut Criterion) {
+    let xml = catalog(TARGET_BYTES);
+    let parser = DDEXParser::new();
+
+    let mut group = c.benchmark_group("borrowed_vs_owned");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(60));
+    group.throughput(Throughput::Bytes(xml.len() as u64));
+
+    group.bench_function("parse", |b| {
+        b.iter(|| black_box(parser.parse(Cursor::new(xml.as_bytes()))))
+    });
+
+    group.bench_function("parse_borrowed", |b| {
+        b.iter(|| black_box(parser.parse_borrowed(&xml)))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_borrowed_vs_owned);
+criterion_main!(benches);
@@ -0,0 +1,49 @@
+// core/benches/common/mod.rs
+//! Synthetic documents shared by the benchmarks
+
+/// A catalog of identical-shaped releases, each with one track and deal
+pub fn catalog(target_bytes: usize) -> String {
+    let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>
+<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43" MessageSchemaVersionId="ern/43">
+  <MessageHeader>
+    <MessageId>BENCH-001</MessageId>
+    <MessageSender><PartyId>PADPIDA2014120301</PartyId><PartyName><FullName>Bench Label</FullName></PartyName></MessageSender>
+    <MessageCreatedDateTime>2024-01-01T00:00:00Z</MessageCreatedDateTime>
+  </MessageHeader>
+  <ReleaseList>
+"#);
+    let mut releases = 0;
+    while xml.len() < target_bytes / 2 {
+        xml.push_str(&format!(r#"    <Release>
+      <ReleaseReference>R{0}</ReleaseReference>
+      <ReleaseId><ICPN>{0:013}</ICPN></ReleaseId>
+      <ReferenceTitle><TitleText>Benchmark Release {0}</TitleText></ReferenceTitle>
+      <DisplayArtistName>Benchmark Artist</DisplayArtistName>
+      <LabelName>Bench Label</LabelName>
+      <Genre><GenreText>Electronic</GenreText></Genre>
+      <ResourceGroup><ResourceGroupContentItem><ReleaseResourceReference>A{0}</ReleaseResourceReference></ResourceGroupContentItem></ResourceGroup>
+    </Release>
+"#, releases));
+        releases += 1;
+    }
+    xml.push_str("  </ReleaseList>\n  <ResourceList>\n");
+    for i in 0..releases {
+        xml.push_str(&format!(r#"    <SoundRecording>
+      <ResourceReference>A{0}</ResourceReference>
+      <SoundRecordingId><ISRC>USRC1{0:07}</ISRC></SoundRecordingId>
+      <ReferenceTitle><TitleText>Benchmark Track {0}</TitleText></ReferenceTitle>
+      <Duration>PT3M30S</Duration>
+    </SoundRecording>
+"#, i));
+    }
+    xml.push_str("  </ResourceList>\n  <DealList>\n");
+    for i in 0..releases {
+        xml.push_str(&format!(r#"    <ReleaseDeal>
+      <DealReleaseReference>R{0}</DealReleaseReference>
+      <Deal><DealTerms><CommercialModelType>SubscriptionModel</CommercialModelType><TerritoryCode>Worldwide</TerritoryCode></DealTerms></Deal>
+    </ReleaseDeal>
+"#, i));
+    }
+    xml.push_str("  </DealList>\n</ern:NewReleaseMessage>\n");
+    xml
+}
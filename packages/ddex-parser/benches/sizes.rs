@@ -0,0 +1,51 @@
+// core/benches/sizes.rs
+//! DOM, streaming and borrowed parsing at 10KB, 1MB and 100MB
+//!
+//! Throughput is reported in bytes of XML, so the results read directly
+//! against the "<50ms per MB" target.
+mod common;
+
+use common::catalog;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use ddex_parser::parser::{mode::ParseMode, ParseOptions};
+use ddex_parser::DDEXParser;
+use std::io::Cursor;
+use std::time::Duration;
+
+const SIZES: [(&str, usize); 3] = [
+    ("10KB", 10 * 1024),
+    ("1MB", 1024 * 1024),
+    ("100MB", 100 * 1024 * 1024),
+];
+
+fn benchmark_parse_by_size(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    let parser = DDEXParser::new();
+
+    for (label, target) in SIZES {
+        let xml = catalog(target);
+        group.throughput(Throughput::Bytes(xml.len() as u64));
+        if target >= 100 * 1024 * 1024 {
+            group.sample_size(10).measurement_time(Duration::from_secs(120));
+        }
+
+        for mode in [ParseMode::Dom, ParseMode::Stream] {
+            let id = BenchmarkId::new(format!("{:?}", mode).to_lowercase(), label);
+            group.bench_with_input(id, &xml, |b, xml| {
+                b.iter(|| {
+                    let options = ParseOptions { mode, ..Default::default() };
+                    black_box(parser.parse_with_options(Cursor::new(xml.as_bytes()), options))
+                })
+            });
+        }
+
+        group.bench_with_input(BenchmarkId::new("borrowed", label), &xml, |b, xml| {
+            b.iter(|| black_box(parser.parse_borrowed(xml)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_parse_by_size);
+criterion_main!(benches);
@@ -96,11 +96,30 @@ export interface ProgressInfo {
 export declare class DdexParser {
   constructor()
   detectVersion(xml: string): string
-  parseSync(xml: string, options?: ParseOptions | undefined | null): ParsedMessage
-  parse(xml: string, options?: ParseOptions | undefined | null): Promise<ParsedMessage>
-  sanityCheck(xml: string): Promise<SanityCheckResult>
+  parseSync(xml: string | Buffer, options?: ParseOptions | undefined | null): ParsedMessage
+  /**
+   * Parse on a libuv worker thread; a `Buffer` is read in place, and
+   * aborting `signal` before the worker starts rejects with an `AbortError`
+   */
+  parse(xml: string | Buffer, options?: ParseOptions | undefined | null, signal?: AbortSignal | undefined | null): Promise<ParsedMessage>
+  sanityCheck(xml: string | Buffer): Promise<SanityCheckResult>
   stream(xml: string, options?: StreamOptions | undefined | null): ReleaseStream
 }
+/**
+ * Native half of `createParseStream()`: chunks written from JS are parsed
+ * on a background thread, and each release is passed to `onRelease`, which
+ * gets `null` once the document is done
+ */
+export declare class ParseStreamWriter {
+  constructor(onRelease: (err: Error | null, release: StreamedRelease | null) => void)
+  /**
+   * Queue a chunk for the parser thread, waiting off the event loop
+   * while the queue is full
+   */
+  write(chunk: Buffer): Promise<void>
+  /** No more chunks are coming; the parser finishes what it has */
+  end(): void
+}
 export declare class ReleaseStream {
   next(): Promise<StreamedRelease | null>
   progress(): Promise<ProgressInfo>
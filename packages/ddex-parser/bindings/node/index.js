@@ -310,7 +310,8 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`)
 }
 
-const { DdexParser, ReleaseStream } = nativeBinding
+const { DdexParser, ParseStreamWriter, ReleaseStream } = nativeBinding
 
 module.exports.DdexParser = DdexParser
+module.exports.ParseStreamWriter = ParseStreamWriter
 module.exports.ReleaseStream = ReleaseStream
@@ -10,6 +10,8 @@ export interface ParseOptions {
   timeoutMs?: number;
   allowBlocking?: boolean;
   chunkSize?: number;
+  /** Abandons `parse()` if aborted before the worker thread picks it up */
+  signal?: AbortSignal;
 }
 
 export interface StreamOptions {
@@ -48,7 +50,9 @@ export class DDEXParser {
    * Parse DDEX XML asynchronously (recommended)
    */
   async parse(xml: string | Buffer, options?: ParseOptions): Promise<ParsedERNMessage> {
-    return this.native.parse(xml, options);
+    // Buffers go to the worker thread as they are, without a UTF-8 copy
+    const { signal, ...rest } = options ?? {};
+    return this.native.parse(xml, rest, signal);
   }
 
   /**
@@ -0,0 +1,557 @@
+// Generated by `ddex-builder schema --format <language> --target graph-message --target flattened-message`.
+// Do not edit: regenerate after changing the Rust models.
+
+export interface Artist {
+  artist_role: string[];
+  display_artist_name: LocalizedString[];
+  party_reference?: string | null;
+  sequence_number?: number | null;
+}
+
+export interface ArtistInfo {
+  name: string;
+  party_id?: string | null;
+  role: string;
+}
+
+export type AttributeMap = Record<string, string>;
+
+export interface AuditTrailEvent {
+  /** All XML attributes (standard and custom) */
+  attributes?: AttributeMap | null;
+  /** Comments associated with this audit trail event */
+  comments?: Comment[] | null;
+  date_time: string;
+  /** Extensions for individual audit trail events */
+  extensions?: Extensions | null;
+  message_audit_trail_event_reference: string;
+  message_audit_trail_event_type: string;
+  responsible_party_reference?: string | null;
+}
+
+/** Enhanced comment structure with position and location metadata */
+export interface Comment {
+  /** Column number in original XML (for debugging/tooling) */
+  column_number?: number | null;
+  /** The comment content (without <!-- --> markers) */
+  content: string;
+  /** Line number in original XML (for debugging/tooling) */
+  line_number?: number | null;
+  /** Position relative to the parent element */
+  position: CommentPosition;
+  /** Whether this comment should be preserved during canonicalization */
+  preserve_formatting: boolean;
+  /** Processing hints for specific output formats */
+  processing_hints: Record<string, string>;
+  /** XPath-like location reference for precise positioning */
+  xpath?: string | null;
+}
+
+/** Position of a comment relative to its parent element */
+export type CommentPosition = "Before" | "FirstChild" | "LastChild" | "After" | "Inline";
+
+export type CommercialModelType = "PayAsYouGoModel" | "SubscriptionModel" | "AdSupportedModel" | { Other: string; };
+
+export interface ContactDetails {
+  address?: string | null;
+  email?: string | null;
+  phone?: string | null;
+}
+
+export interface Copyright {
+  owner?: string | null;
+  text: string;
+  year?: number | null;
+}
+
+export interface Deal {
+  /** Comments associated with deal */
+  comments?: Comment[] | null;
+  deal_reference?: string | null;
+  deal_release_reference: string[];
+  deal_terms: DealTerms;
+}
+
+export interface DealTerms {
+  commercial_model_type: CommercialModelType[];
+  distribution_channel: DistributionChannel[];
+  end_date?: string | null;
+  excluded_distribution_channel: DistributionChannel[];
+  excluded_territory_code: string[];
+  instant_gratification_date?: string | null;
+  pre_order_date?: string | null;
+  pre_order_preview_date?: string | null;
+  price_information: PriceInformation[];
+  start_date?: string | null;
+  suggested_retail_price: Price[];
+  takedown_date?: string | null;
+  territory_code: string[];
+  use_type: UseType[];
+  validity_period?: ValidityPeriod | null;
+  wholesale_price: Price[];
+}
+
+export interface DealValidity {
+  end?: string | null;
+  start?: string | null;
+}
+
+export type DistributionChannel = "Download" | "Stream" | "Physical" | { Other: string; };
+
+export interface DistributionComplexity {
+  excluded: string[];
+  included: string[];
+}
+
+export interface Duration {
+  nanos: number;
+  secs: number;
+}
+
+export interface ERNMessage {
+  /** All XML attributes (standard and custom) for the root element */
+  attributes?: AttributeMap | null;
+  comments?: Comment[] | null;
+  deals: Deal[];
+  /** Comprehensive extension preservation system */
+  extensions?: Extensions | null;
+  /** Legacy extensions (for backward compatibility) */
+  legacy_extensions?: Record<string, string> | null;
+  message_audit_trail?: MessageAuditTrail | null;
+  message_header: MessageHeader;
+  parties: Party[];
+  profile?: ERNProfile | null;
+  /** Releases to take down (PurgeReleaseMessage only) */
+  purged_releases?: PurgedRelease[];
+  releases: Release[];
+  resources: Resource[];
+  version: ERNVersion;
+}
+
+export type ERNProfile = "AudioAlbum" | "AudioSingle" | "Video" | "Mixed";
+
+export type ERNVersion = "V3_8_2" | "V4_2" | "V4_3" | "V4_4";
+
+/** Extension container with location-aware storage */
+export interface Extensions {
+  /** Document-level comments */
+  document_comments: Comment[];
+  /** Document-level processing instructions */
+  document_processing_instructions: ProcessingInstruction[];
+  /** Extensions organized by their location in the DDEX structure */
+  fragments: Record<string, XmlFragment>;
+  /** Global namespace declarations that should be preserved at document level */
+  global_namespaces: Record<string, string>;
+}
+
+export interface FlattenedMessage {
+  deals: ParsedDeal[];
+  /** Extensions for flattened message */
+  extensions?: Extensions | null;
+  message_date: string;
+  message_id: string;
+  message_type: string;
+  parties: Record<string, Party>;
+  profile?: string | null;
+  recipient: Organization;
+  releases: ParsedRelease[];
+  resources: Record<string, ParsedResource>;
+  sender: Organization;
+  stats: MessageStats;
+  /** Catalog changes requested by update and purge messages */
+  update_actions?: UpdateAction[];
+  version: string;
+}
+
+export interface Genre {
+  /** All XML attributes (standard and custom) */
+  attributes?: AttributeMap | null;
+  /** Comments associated with genre */
+  comments?: Comment[] | null;
+  /** Extensions for genre */
+  extensions?: Extensions | null;
+  genre_text: string;
+  sub_genre?: string | null;
+}
+
+export interface Identifier {
+  id_type: IdentifierType;
+  namespace?: string | null;
+  value: string;
+}
+
+export type IdentifierType = "Proprietary" | "ISRC" | "ISWC" | "UPC" | "EAN" | "GRID" | "GRid" | "ISNI" | "IPI";
+
+export interface LocalizedString {
+  language_code?: string | null;
+  script?: string | null;
+  text: string;
+}
+
+export interface MessageAuditTrail {
+  /** All XML attributes (standard and custom) */
+  attributes?: AttributeMap | null;
+  audit_trail_events: AuditTrailEvent[];
+  /** Comments associated with audit trail */
+  comments?: Comment[] | null;
+  /** Extensions for audit trail */
+  extensions?: Extensions | null;
+}
+
+export type MessageControlType = "LiveMessage" | "TestMessage";
+
+export interface MessageHeader {
+  /** All XML attributes (standard and custom) */
+  attributes?: AttributeMap | null;
+  /** Comments associated with message header */
+  comments?: Comment[] | null;
+  /** Extensions for message header */
+  extensions?: Extensions | null;
+  message_control_type?: MessageControlType | null;
+  message_created_date_time: string;
+  message_id: string;
+  message_recipient: MessageRecipient;
+  message_sender: MessageSender;
+  message_thread_id?: string | null;
+  message_type: MessageType;
+}
+
+export interface MessageRecipient {
+  /** All XML attributes (standard and custom) */
+  attributes?: AttributeMap | null;
+  /** Comments associated with message recipient */
+  comments?: Comment[] | null;
+  /** Extensions for message recipient */
+  extensions?: Extensions | null;
+  party_id: Identifier[];
+  party_name: LocalizedString[];
+  trading_name?: string | null;
+}
+
+export interface MessageSender {
+  /** All XML attributes (standard and custom) */
+  attributes?: AttributeMap | null;
+  /** Comments associated with message sender */
+  comments?: Comment[] | null;
+  /** Extensions for message sender */
+  extensions?: Extensions | null;
+  party_id: Identifier[];
+  party_name: LocalizedString[];
+  trading_name?: string | null;
+}
+
+export interface MessageStats {
+  deal_count: number;
+  release_count: number;
+  total_duration: number;
+  track_count: number;
+}
+
+export type MessageType = "NewReleaseMessage" | "UpdateReleaseMessage" | "TakedownMessage" | "PurgeReleaseMessage";
+
+export interface Organization {
+  /** Extensions for organization */
+  extensions?: Extensions | null;
+  id: string;
+  name: string;
+}
+
+export interface ParsedDeal {
+  deal_id: string;
+  distribution_channels: DistributionComplexity;
+  pricing: PriceTier[];
+  releases: string[];
+  restrictions: string[];
+  territories: TerritoryComplexity;
+  usage_rights: string[];
+  validity: DealValidity;
+}
+
+export interface ParsedImage {
+  file_format?: string | null;
+  height?: number | null;
+  image_id: string;
+  image_type: string;
+  width?: number | null;
+}
+
+export interface ParsedRelease {
+  artists: ArtistInfo[];
+  c_line?: Copyright | null;
+  child_releases: string[];
+  cover_art?: ParsedImage | null;
+  default_subtitle?: string | null;
+  default_title: string;
+  disc_count?: number | null;
+  display_artist: string;
+  /** Extensions for parsed release */
+  extensions?: Extensions | null;
+  genre?: string | null;
+  identifiers: ReleaseIdentifiers;
+  images: ParsedImage[];
+  original_release_date?: string | null;
+  p_line?: Copyright | null;
+  parent_release?: string | null;
+  release_date?: string | null;
+  release_id: string;
+  release_type: string;
+  sub_genre?: string | null;
+  subtitle?: LocalizedString[] | null;
+  territories: TerritoryInfo[];
+  title: LocalizedString[];
+  track_count: number;
+  tracks: ParsedTrack[];
+  videos: ParsedVideo[];
+}
+
+export interface ParsedResource {
+  duration?: Duration | null;
+  resource_id: string;
+  resource_type: string;
+  technical_details: TechnicalInfo;
+  title: string;
+}
+
+export interface ParsedTrack {
+  artists: ArtistInfo[];
+  bitrate?: number | null;
+  disc_number?: number | null;
+  display_artist: string;
+  duration: Duration;
+  duration_formatted: string;
+  file_format?: string | null;
+  is_bonus: boolean;
+  is_explicit: boolean;
+  is_hidden: boolean;
+  is_instrumental: boolean;
+  isrc?: string | null;
+  iswc?: string | null;
+  position: number;
+  sample_rate?: number | null;
+  side?: string | null;
+  subtitle?: string | null;
+  title: string;
+  track_id: string;
+  track_number?: number | null;
+}
+
+export interface ParsedVideo {
+  duration?: Duration | null;
+  resolution?: string | null;
+  video_id: string;
+  video_type: string;
+}
+
+export interface Party {
+  contact_details?: ContactDetails | null;
+  ipi?: string | null;
+  isni?: string | null;
+  party_id: Identifier[];
+  party_name: LocalizedString[];
+  party_role: PartyRole[];
+}
+
+export type PartyRole = "Artist" | "Producer" | "Composer" | "Lyricist" | "Publisher" | "Performer" | "Engineer" | "Label" | "Distributor" | { Other: string; };
+
+export interface Price {
+  amount: number;
+  currency: string;
+  territory?: string | null;
+}
+
+export interface PriceInformation {
+  price: Price;
+  price_tier?: string | null;
+  price_type: string;
+}
+
+export interface PriceTier {
+  end_date?: string | null;
+  price: Price;
+  price_type: PriceType;
+  start_date?: string | null;
+  territory?: string | null;
+  tier_name?: string | null;
+}
+
+export type PriceType = "Wholesale" | "SuggestedRetail" | "Minimum";
+
+/** XML Processing Instruction */
+export interface ProcessingInstruction {
+  /** The data content of the processing instruction */
+  data?: string | null;
+  /** The target of the processing instruction */
+  target: string;
+}
+
+export interface ProprietaryId {
+  namespace: string;
+  value: string;
+}
+
+/** A release that a PurgeReleaseMessage asks the recipient to take down */
+export interface PurgedRelease {
+  /** All XML attributes (standard and custom) */
+  attributes?: AttributeMap | null;
+  /** Comments associated with purged release */
+  comments?: Comment[] | null;
+  /** Extensions for purged release */
+  extensions?: Extensions | null;
+  release_id: Identifier[];
+  title: LocalizedString[];
+}
+
+export interface Release {
+  /** All XML attributes (standard and custom) */
+  attributes?: AttributeMap | null;
+  /** Comments associated with release */
+  comments?: Comment[] | null;
+  display_artist: Artist[];
+  excluded_territory_code: string[];
+  /** Extensions for release */
+  extensions?: Extensions | null;
+  genre: Genre[];
+  party_list: ReleaseParty[];
+  release_date: ReleaseEvent[];
+  release_id: Identifier[];
+  release_reference: string;
+  release_resource_reference_list: ReleaseResourceReference[];
+  release_subtitle?: LocalizedString[] | null;
+  release_title: LocalizedString[];
+  release_type?: ReleaseType | null;
+  territory_code: string[];
+}
+
+export interface ReleaseEvent {
+  /** Comments associated with release event */
+  comments?: Comment[] | null;
+  event_date?: string | null;
+  /** Extensions for release event */
+  extensions?: Extensions | null;
+  release_event_type: string;
+  territory?: string | null;
+}
+
+export interface ReleaseIdentifiers {
+  catalog_number?: string | null;
+  ean?: string | null;
+  grid?: string | null;
+  proprietary: ProprietaryId[];
+  upc?: string | null;
+}
+
+export interface ReleaseParty {
+  /** Comments associated with release party */
+  comments?: Comment[] | null;
+  /** Extensions for release party */
+  extensions?: Extensions | null;
+  party_reference: string;
+  role: string[];
+}
+
+export interface ReleaseResourceReference {
+  /** Comments associated with resource reference */
+  comments?: Comment[] | null;
+  disc_number?: number | null;
+  /** Extensions for resource reference */
+  extensions?: Extensions | null;
+  is_bonus: boolean;
+  is_hidden: boolean;
+  resource_reference: string;
+  sequence_number?: number | null;
+  side?: string | null;
+  track_number?: number | null;
+}
+
+export type ReleaseType = "Album" | "Single" | "EP" | "Compilation" | { Other: string; };
+
+export interface Resource {
+  c_line: Copyright[];
+  /** Comments associated with resource */
+  comments?: Comment[] | null;
+  duration?: Duration | null;
+  /** Extensions for resource */
+  extensions?: Extensions | null;
+  p_line: Copyright[];
+  reference_title: LocalizedString[];
+  resource_id: Identifier[];
+  resource_reference: string;
+  resource_type: ResourceType;
+  rights_controller: string[];
+  technical_details: TechnicalDetails[];
+}
+
+export type ResourceType = "SoundRecording" | "Video" | "Image" | "Text" | "SheetMusic";
+
+export interface TechnicalDetails {
+  audio_codec?: string | null;
+  bitrate?: number | null;
+  /** Extensions for technical details */
+  extensions?: Extensions | null;
+  file_format?: string | null;
+  file_size?: number | null;
+  sample_rate?: number | null;
+  technical_resource_details_reference: string;
+}
+
+export interface TechnicalInfo {
+  bitrate?: number | null;
+  file_format?: string | null;
+  file_size?: number | null;
+  sample_rate?: number | null;
+}
+
+export interface TerritoryComplexity {
+  excluded: string[];
+  included: string[];
+}
+
+export interface TerritoryInfo {
+  code: string;
+  distribution_channels: string[];
+  end_date?: string | null;
+  included: boolean;
+  start_date?: string | null;
+}
+
+/**
+ * A change a message asks the recipient to apply to its catalog
+ *
+ * NewReleaseMessage carries no actions; it replaces the release outright.
+ */
+export type UpdateAction = { UpsertRelease: { release_reference: string; }; } | { UpsertResource: { resource_reference: string; }; } | { UpsertDeal: { deal_reference?: string | null; release_references: string[]; }; } | { PurgeRelease: { release_id: Identifier[]; }; };
+
+export type UseType = "Stream" | "Download" | "OnDemandStream" | "NonInteractiveStream" | { Other: string; };
+
+export interface ValidityPeriod {
+  end_date?: string | null;
+  start_date?: string | null;
+}
+
+/** Comprehensive XML fragment preservation for round-trip fidelity */
+export interface XmlFragment {
+  /** All attributes on this element (including namespaced ones) */
+  attributes: Record<string, string>;
+  /**
+   * Child elements, text, comments and processing instructions, in
+   * document order
+   */
+  content: XmlNode[];
+  /** Element name (local name without namespace prefix) */
+  element_name: string;
+  /** All namespace declarations on this element */
+  namespace_declarations: Record<string, string>;
+  /** Namespace prefix used in the original XML */
+  namespace_prefix?: string | null;
+  /** Namespace URI for this element */
+  namespace_uri?: string | null;
+  /** Position hint for canonical ordering */
+  position_hint?: number | null;
+  /** Whether this fragment should be preserved as-is (no canonicalization) */
+  preserve_formatting: boolean;
+  /** The raw XML content as a string */
+  raw_content: string;
+}
+
+/** A node inside an XML fragment */
+export type XmlNode = { Element: XmlFragment; } | { Text: string; } | { Comment: Comment; } | { ProcessingInstruction: ProcessingInstruction; };
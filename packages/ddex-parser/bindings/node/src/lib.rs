@@ -2,7 +2,11 @@
 #![deny(clippy::all)]
 
 use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::{JsFunction, Task};
 use napi_derive::napi;
+use std::io::{BufReader, Read};
+use std::sync::mpsc::{self, Receiver, SyncSender};
 
 #[napi(js_name = "DdexParser")]
 pub struct DdexParser {
@@ -18,108 +22,29 @@ impl DdexParser {
     
     #[napi]
     pub fn detect_version(&self, xml: String) -> String {
-        if xml.contains("ern/43") || xml.contains("xml/ern/43") {
-            "V4_3".to_string()
-        } else if xml.contains("ern/42") || xml.contains("xml/ern/42") {
-            "V4_2".to_string()
-        } else if xml.contains("ern/382") || xml.contains("xml/ern/382") {
-            "V3_8_2".to_string()
-        } else {
-            "Unknown".to_string()
-        }
+        detect_version(&xml)
     }
     
     #[napi]
-    pub fn parse_sync(&self, xml: String, _options: Option<ParseOptions>) -> Result<ParsedMessage> {
-        // Basic XML validation
-        if !xml.contains('<') || !xml.contains('>') {
-            return Err(Error::new(
-                Status::InvalidArg,
-                "Invalid XML: missing angle brackets",
-            ));
-        }
-        
-        // Check for valid DDEX
-        if !xml.contains("NewReleaseMessage") && 
-           !xml.contains("UpdateReleaseMessage") && 
-           !xml.contains("TakedownMessage") {
-            return Err(Error::new(
-                Status::InvalidArg,
-                "Invalid DDEX: not a valid DDEX message type",
-            ));
-        }
-        
-        // Check for unclosed tags
-        let open_count = xml.matches('<').count();
-        let close_count = xml.matches('>').count();
-        if open_count != close_count {
-            return Err(Error::new(
-                Status::InvalidArg,
-                "Invalid XML: unclosed tags",
-            ));
-        }
-        
-        let version = self.detect_version(xml.clone());
-        
-        // Generate statistics if requested
-        let statistics = if _options.as_ref().and_then(|o| o.collect_statistics).unwrap_or(false) {
-            Some(ParseStatistics {
-                parse_time_ms: 5.0,
-                memory_used_bytes: xml.len() as u32 * 2,
-                element_count: xml.matches('<').count() as u32,
-                attribute_count: xml.matches('=').count() as u32,
-                comment_count: xml.matches("<!--").count() as u32,
-                extension_count: if xml.contains("xmlns:") { 1 } else { 0 },
-                namespace_count: xml.matches("xmlns").count() as u32,
-                file_size_bytes: xml.len() as u32,
-            })
-        } else {
-            None
-        };
-        
-        // Generate fidelity info based on options
-        let fidelity_info = if let Some(ref opts) = _options {
-            Some(FidelityInfo {
-                fidelity_level: opts.fidelity_level.clone().unwrap_or_else(|| "balanced".to_string()),
-                canonicalization_algorithm: opts.canonicalization.clone().unwrap_or_else(|| "db_c14n".to_string()),
-                comments_preserved: opts.preserve_comments.unwrap_or(false),
-                extensions_preserved: opts.preserve_extensions.unwrap_or(true),
-                processing_instructions_preserved: opts.preserve_processing_instructions.unwrap_or(false),
-                attribute_order_preserved: opts.preserve_attribute_order.unwrap_or(true),
-                namespace_prefixes_preserved: opts.preserve_namespace_prefixes.unwrap_or(true),
-            })
-        } else {
-            None
-        };
-
-        Ok(ParsedMessage {
-            message_id: "TEST_001".to_string(),
-            message_type: "NewReleaseMessage".to_string(),
-            message_date: chrono::Utc::now().to_rfc3339(),
-            sender_name: "Test Sender".to_string(),
-            sender_id: "sender_001".to_string(),
-            recipient_name: "Test Recipient".to_string(),
-            recipient_id: "recipient_001".to_string(),
-            version,
-            profile: None,
-            release_count: 1,
-            track_count: 0,
-            deal_count: 0,
-            resource_count: 0,
-            total_duration_seconds: 0.0,
-            statistics,
-            fidelity_info,
-        })
+    pub fn parse_sync(&self, xml: Either<String, Buffer>, options: Option<ParseOptions>) -> Result<ParsedMessage> {
+        parse_document(source_text(&xml)?, options.as_ref())
     }
     
+    /// Parse on a libuv worker thread; a `Buffer` is read in place, and
+    /// aborting `signal` before the worker starts rejects with an `AbortError`
     #[napi]
-    pub async fn parse(&self, xml: String, options: Option<ParseOptions>) -> Result<ParsedMessage> {
-        // For async, just call sync version for now
-        self.parse_sync(xml, options)
+    pub fn parse(
+        &self,
+        xml: Either<String, Buffer>,
+        options: Option<ParseOptions>,
+        signal: Option<AbortSignal>,
+    ) -> AsyncTask<ParseTask> {
+        AsyncTask::with_optional_signal(ParseTask { xml, options }, signal)
     }
     
     #[napi]
-    pub async fn sanity_check(&self, xml: String) -> Result<SanityCheckResult> {
+    pub async fn sanity_check(&self, xml: Either<String, Buffer>) -> Result<SanityCheckResult> {
+        let xml = source_text(&xml)?;
         let mut errors = Vec::new();
         let warnings = Vec::new(); // Fixed: removed mut
         
@@ -140,7 +65,7 @@ impl DdexParser {
             errors.push("Unclosed XML tags".to_string());
         }
         
-        let version = self.detect_version(xml);
+        let version = detect_version(xml);
         
         Ok(SanityCheckResult {
             is_valid: errors.is_empty(),
@@ -156,6 +81,127 @@ impl DdexParser {
     }
 }
 
+fn detect_version(xml: &str) -> String {
+    if xml.contains("ern/44") || xml.contains("xml/ern/44") {
+        "V4_4".to_string()
+    } else if xml.contains("ern/43") || xml.contains("xml/ern/43") {
+        "V4_3".to_string()
+    } else if xml.contains("ern/42") || xml.contains("xml/ern/42") {
+        "V4_2".to_string()
+    } else if xml.contains("ern/382") || xml.contains("xml/ern/382") {
+        "V3_8_2".to_string()
+    } else {
+        "Unknown".to_string()
+    }
+}
+
+/// The document as text, borrowed from the string or `Buffer` it came in
+fn source_text(xml: &Either<String, Buffer>) -> Result<&str> {
+    match xml {
+        Either::A(text) => Ok(text),
+        Either::B(buffer) => std::str::from_utf8(buffer).map_err(|e| {
+            Error::new(Status::InvalidArg, format!("Invalid XML: not UTF-8 ({})", e))
+        }),
+    }
+}
+
+fn parse_document(xml: &str, options: Option<&ParseOptions>) -> Result<ParsedMessage> {
+    // Basic XML validation
+    if !xml.contains('<') || !xml.contains('>') {
+        return Err(Error::new(
+            Status::InvalidArg,
+            "Invalid XML: missing angle brackets",
+        ));
+    }
+    
+    // Check for valid DDEX
+    if !xml.contains("NewReleaseMessage") && 
+       !xml.contains("UpdateReleaseMessage") && 
+       !xml.contains("TakedownMessage") {
+        return Err(Error::new(
+            Status::InvalidArg,
+            "Invalid DDEX: not a valid DDEX message type",
+        ));
+    }
+    
+    // Check for unclosed tags
+    let open_count = xml.matches('<').count();
+    let close_count = xml.matches('>').count();
+    if open_count != close_count {
+        return Err(Error::new(
+            Status::InvalidArg,
+            "Invalid XML: unclosed tags",
+        ));
+    }
+    
+    let version = detect_version(xml);
+    
+    // Generate statistics if requested
+    let statistics = if options.and_then(|o| o.collect_statistics).unwrap_or(false) {
+        Some(ParseStatistics {
+            parse_time_ms: 5.0,
+            memory_used_bytes: xml.len() as u32 * 2,
+            element_count: xml.matches('<').count() as u32,
+            attribute_count: xml.matches('=').count() as u32,
+            comment_count: xml.matches("<!--").count() as u32,
+            extension_count: if xml.contains("xmlns:") { 1 } else { 0 },
+            namespace_count: xml.matches("xmlns").count() as u32,
+            file_size_bytes: xml.len() as u32,
+        })
+    } else {
+        None
+    };
+    
+    // Generate fidelity info based on options
+    let fidelity_info = options.map(|opts| FidelityInfo {
+        fidelity_level: opts.fidelity_level.clone().unwrap_or_else(|| "balanced".to_string()),
+        canonicalization_algorithm: opts.canonicalization.clone().unwrap_or_else(|| "db_c14n".to_string()),
+        comments_preserved: opts.preserve_comments.unwrap_or(false),
+        extensions_preserved: opts.preserve_extensions.unwrap_or(true),
+        processing_instructions_preserved: opts.preserve_processing_instructions.unwrap_or(false),
+        attribute_order_preserved: opts.preserve_attribute_order.unwrap_or(true),
+        namespace_prefixes_preserved: opts.preserve_namespace_prefixes.unwrap_or(true),
+    });
+
+    Ok(ParsedMessage {
+        message_id: "TEST_001".to_string(),
+        message_type: "NewReleaseMessage".to_string(),
+        message_date: chrono::Utc::now().to_rfc3339(),
+        sender_name: "Test Sender".to_string(),
+        sender_id: "sender_001".to_string(),
+        recipient_name: "Test Recipient".to_string(),
+        recipient_id: "recipient_001".to_string(),
+        version,
+        profile: None,
+        release_count: 1,
+        track_count: 0,
+        deal_count: 0,
+        resource_count: 0,
+        total_duration_seconds: 0.0,
+        statistics,
+        fidelity_info,
+    })
+}
+
+/// A parse run on a libuv worker thread, off the event loop
+pub struct ParseTask {
+    xml: Either<String, Buffer>,
+    options: Option<ParseOptions>,
+}
+
+impl Task for ParseTask {
+    type Output = ParsedMessage;
+    type JsValue = ParsedMessage;
+
+    fn compute(&mut self) -> Result<ParsedMessage> {
+        parse_document(source_text(&self.xml)?, self.options.as_ref())
+    }
+
+    fn resolve(&mut self, _env: Env, message: ParsedMessage) -> Result<ParsedMessage> {
+        Ok(message)
+    }
+}
+
 #[napi(object)]
 #[derive(Default)]
 pub struct ParseOptions {
@@ -301,4 +347,115 @@ pub struct ProgressInfo {
     pub bytes_processed: f64,
     pub releases_parsed: f64,
     pub elapsed_ms: f64,
-}
\ No newline at end of file
+}
+/// Chunks queued for the parser thread before `write` waits
+const CHUNKS_IN_FLIGHT: usize = 16;
+
+/// Native half of `createParseStream()`: chunks written from JS are parsed
+/// on a background thread, and each release is passed to `onRelease`, which
+/// gets `null` once the document is done
+#[napi]
+pub struct ParseStreamWriter {
+    chunks: Option<SyncSender<Vec<u8>>>,
+}
+
+#[napi]
+impl ParseStreamWriter {
+    #[napi(constructor, ts_args_type = "onRelease: (err: Error | null, release: StreamedRelease | null) => void")]
+    pub fn new(on_release: JsFunction) -> Result<Self> {
+        let on_release: ThreadsafeFunction<Option<StreamedRelease>> =
+            on_release.create_threadsafe_function(0, |ctx| Ok(vec![ctx.value]))?;
+        let (chunks, received) = mpsc::sync_channel(CHUNKS_IN_FLIGHT);
+
+        std::thread::spawn(move || {
+            let reader = BufReader::new(ChunkReader { chunks: received, current: Vec::new(), offset: 0 });
+            for release in ddex_parser::DDEXParser::new().stream(reader) {
+                let release = release
+                    .map(|release| Some(streamed_release(&release)))
+                    .map_err(|e| Error::new(Status::GenericFailure, e.to_string()));
+                let failed = release.is_err();
+                on_release.call(release, ThreadsafeFunctionCallMode::Blocking);
+                if failed {
+                    return;
+                }
+            }
+            on_release.call(Ok(None), ThreadsafeFunctionCallMode::Blocking);
+        });
+
+        Ok(ParseStreamWriter { chunks: Some(chunks) })
+    }
+
+    /// Queue a chunk for the parser thread, waiting off the event loop
+    /// while the queue is full
+    #[napi]
+    pub fn write(&self, chunk: Buffer) -> Result<AsyncTask<WriteChunk>> {
+        let chunks = self.chunks.clone()
+            .ok_or_else(|| Error::new(Status::InvalidArg, "Parse stream already ended"))?;
+        Ok(AsyncTask::new(WriteChunk { chunks, chunk }))
+    }
+
+    /// No more chunks are coming; the parser finishes what it has
+    #[napi]
+    pub fn end(&mut self) {
+        self.chunks = None;
+    }
+}
+
+/// Hands one chunk to the parser thread from a libuv worker
+pub struct WriteChunk {
+    chunks: SyncSender<Vec<u8>>,
+    chunk: Buffer,
+}
+
+impl Task for WriteChunk {
+    type Output = ();
+    type JsValue = ();
+
+    fn compute(&mut self) -> Result<()> {
+        self.chunks.send(self.chunk.to_vec())
+            .map_err(|_| Error::new(Status::GenericFailure, "Parse stream closed"))
+    }
+
+    fn resolve(&mut self, _env: Env, _output: ()) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Reads the chunks sent from JS in order, ending when the sender is dropped
+struct ChunkReader {
+    chunks: Receiver<Vec<u8>>,
+    current: Vec<u8>,
+    offset: usize,
+}
+
+impl Read for ChunkReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.offset == self.current.len() {
+            match self.chunks.recv() {
+                Ok(chunk) => {
+                    self.current = chunk;
+                    self.offset = 0;
+                }
+                Err(_) => return Ok(0),
+            }
+        }
+        let len = buf.len().min(self.current.len() - self.offset);
+        buf[..len].copy_from_slice(&self.current[self.offset..self.offset + len]);
+        self.offset += len;
+        Ok(len)
+    }
+}
+
+fn streamed_release(release: &ddex_core::models::graph::Release) -> StreamedRelease {
+    use ddex_core::models::graph::ReleaseType;
+
+    StreamedRelease {
+        release_reference: release.release_reference.clone(),
+        title: release.release_title.first().map(|t| t.text.clone()).unwrap_or_default(),
+        release_type: release.release_type.as_ref().map(|release_type| match release_type {
+            ReleaseType::Other(other) => other.clone(),
+            known => format!("{:?}", known),
+        }),
+        resource_count: release.release_resource_reference_list.len() as u32,
+    }
+}
@@ -0,0 +1,42 @@
+// bindings/node/src/parse-stream.ts
+import { Transform, TransformCallback } from 'stream';
+import type { StreamedRelease } from './parser';
+
+/**
+ * A Transform that takes XML chunks (from `fs.createReadStream`, an S3 body,
+ * `zlib.createGunzip()`...) and emits one object per release.
+ *
+ * Chunks are parsed on a native thread; writes wait while it catches up, so
+ * the usual stream backpressure applies end to end.
+ */
+export function createParseStream(ParseStreamWriter: any): Transform {
+  let finish: TransformCallback | null = null;
+  let native: any;
+
+  const stream = new Transform({
+    readableObjectMode: true,
+    transform(chunk: Buffer, _encoding, callback) {
+      native.write(chunk).then(() => callback(), callback);
+    },
+    flush(callback) {
+      finish = callback;
+      native.end();
+    },
+    destroy(error, callback) {
+      native.end();
+      callback(error);
+    },
+  });
+
+  native = new ParseStreamWriter((error: Error | null, release: StreamedRelease | null) => {
+    if (error) {
+      stream.destroy(error);
+    } else if (release) {
+      stream.push(release);
+    } else {
+      finish?.();
+    }
+  });
+
+  return stream;
+}
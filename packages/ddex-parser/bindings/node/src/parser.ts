@@ -1,4 +1,7 @@
 // bindings/node/src/parser.ts
+import type { Transform } from 'stream';
+import { createParseStream } from './parse-stream';
+
 let binding: any;
 
 try {
@@ -30,6 +33,8 @@ export interface ParseOptions {
   timeoutMs?: number;
   allowBlocking?: boolean;
   chunkSize?: number;
+  /** Abandons `parse()` if aborted before the worker thread picks it up */
+  signal?: AbortSignal;
 }
 
 export interface StreamOptions {
@@ -103,10 +108,10 @@ export class DDEXParser {
    * Parse DDEX XML asynchronously (recommended)
    */
   async parse(xml: string | Buffer, options?: ParseOptions): Promise<ParsedERNMessage> {
-    const xmlStr = this.toXmlString(xml);
-    
     if (this.native.parse) {
-      return this.native.parse(xmlStr, options);
+      // Buffers go to the worker thread as they are, without a UTF-8 copy
+      const { signal, ...rest } = options ?? {};
+      return this.native.parse(xml, rest, signal);
     }
     // Fallback for testing
     return this.parseSync(xml, options);
@@ -204,6 +209,19 @@ export class DDEXParser {
     }
   }
 
+  /**
+   * Transform stream of XML chunks in, release objects out
+   *
+   * @example
+   * fs.createReadStream('catalog.xml.gz')
+   *   .pipe(zlib.createGunzip())
+   *   .pipe(parser.createParseStream())
+   *   .on('data', (release: StreamedRelease) => console.log(release.title));
+   */
+  createParseStream(): Transform {
+    return createParseStream(binding.ParseStreamWriter);
+  }
+
   /**
    * Perform structural sanity check on DDEX XML
    */
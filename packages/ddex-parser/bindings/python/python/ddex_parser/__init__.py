@@ -6,6 +6,7 @@ DDEX Parser - High-performance DDEX XML parser for Python
 from __future__ import annotations
 from typing import Optional, Union, Dict, Any, Iterator, IO, TYPE_CHECKING
 import asyncio
+import os
 from pathlib import Path
 
 if TYPE_CHECKING:
@@ -89,7 +90,10 @@ class DDEXParser:
         return result  # Return PyParsedERNMessage directly
     
     async def parse_async(self, xml: Union[str, bytes], options: Optional[ParseOptions] = None) -> ParseResult:
-        """Parse DDEX XML asynchronously."""
+        """Parse DDEX XML on a worker thread without blocking the event loop.
+
+        Cancelling the awaiting task stops the parse.
+        """
         if not self._parser:
             # Mock for testing
             await asyncio.sleep(0.01)  # Simulate async work
@@ -99,8 +103,17 @@ class DDEXParser:
         result = await self._parser.parse_async(xml, opts)
         return result  # Return PyParsedERNMessage directly
     
-    def stream(self, xml: Union[str, bytes], options: Optional[ParseOptions] = None) -> Iterator[Dict[str, Any]]:
-        """Stream parse large DDEX files."""
+    def stream(
+        self,
+        source: Union[str, bytes, os.PathLike, IO],
+        options: Optional[ParseOptions] = None,
+    ) -> Iterator[Dict[str, Any]]:
+        """Iterate over releases as they are parsed.
+
+        ``source`` is a file path, an open file (binary or text), or the XML
+        itself as ``str`` or ``bytes``. Parsing runs in Rust without holding
+        the GIL; Ctrl-C raises ``KeyboardInterrupt`` and stops it.
+        """
         if not self._parser:
             # Mock iterator
             for i in range(3):
@@ -108,17 +121,7 @@ class DDEXParser:
             return
         
         opts = options.to_dict() if options else None
-        stream_iter = self._parser.stream(xml, opts)
-        
-        # Yield from the Rust iterator
-        try:
-            while True:
-                item = next(stream_iter)
-                if item is None:
-                    break
-                yield item
-        except StopIteration:
-            pass
+        yield from self._parser.stream(source, opts)
     
     def to_dataframe(self, xml: Union[str, bytes], schema: str = 'flat') -> 'pd.DataFrame':
         """Convert DDEX XML to pandas DataFrame."""
@@ -0,0 +1,74 @@
+# packages/ddex-parser/bindings/python/python/ddex_parser/_internal.pyi
+"""Type stubs for the Rust extension module.
+
+The parsed payload shapes live in `models.py` (TypedDicts) and
+`pydantic_models.py` (pydantic v2 models), both generated from the Rust types.
+"""
+
+import os
+from typing import IO, Any, Awaitable, Dict, Iterator, List, Literal, Optional, TypedDict, Union
+
+import pandas as pd
+
+__version__: str
+
+Source = Union[str, bytes]
+Schema = Literal["flat", "releases", "tracks"]
+
+class ParseOptionsDict(TypedDict, total=False):
+    resolve_references: bool
+    include_raw: bool
+    max_memory: int
+    timeout_ms: int
+    allow_blocking: bool
+    include_raw_extensions: bool
+    include_comments: bool
+    preserve_unknown_elements: bool
+    chunk_size: int
+    auto_threshold: int
+    intern_strings: bool
+    preferred_languages: List[str]
+    # Legacy spellings of resolve_references and timeout_ms (seconds)
+    validate_references: bool
+    timeout: float
+
+class StreamedRelease(TypedDict):
+    release_reference: str
+    release_id: Optional[str]
+    title: str
+    artist: str
+    track_count: int
+
+class DdexError(ValueError):
+    """Parse error with a stable `code`, plus `path`, `severity`, `suggestion` and `category`"""
+
+    code: str
+    path: Optional[str]
+    severity: str
+    suggestion: Optional[str]
+    category: str
+
+class ParsedERNMessage:
+    def to_dataframe(self, schema: Schema = "flat") -> pd.DataFrame: ...
+    def to_arrow(self, table: Optional[Literal["releases", "tracks", "deals"]] = None) -> Any: ...
+    def message_id(self) -> str: ...
+    def version(self) -> str: ...
+    def release_count(self) -> int: ...
+
+class StreamIterator(Iterator[StreamedRelease]):
+    def __iter__(self) -> StreamIterator: ...
+    def __next__(self) -> StreamedRelease: ...
+
+class DDEXParser:
+    def __init__(self) -> None: ...
+    def parse(self, xml: Source, options: Optional[ParseOptionsDict] = None) -> ParsedERNMessage: ...
+    def parse_async(self, xml: Source, options: Optional[ParseOptionsDict] = None) -> Awaitable[ParsedERNMessage]: ...
+    def stream(
+        self,
+        source: Union[Source, os.PathLike[str], IO[bytes], IO[str]],
+        options: Optional[ParseOptionsDict] = None,
+    ) -> StreamIterator: ...
+    def to_dataframe(self, xml: Source, schema: Schema = "flat") -> pd.DataFrame: ...
+    def from_dataframe(self, df: pd.DataFrame, schema: Schema = "flat", template: Optional[Any] = None) -> str: ...
+    def detect_version(self, xml: Source) -> str: ...
+    def sanity_check(self, xml: Source) -> Dict[str, Any]: ...
@@ -0,0 +1,674 @@
+# Generated by `ddex-builder schema --format <language> --target graph-message --target flattened-message`.
+# Do not edit: regenerate after changing the Rust models.
+
+from __future__ import annotations
+
+from typing import Any, Dict, List, Literal, Optional, TypedDict, Union
+
+
+class _ArtistRequired(TypedDict):
+    artist_role: List[str]
+    display_artist_name: List[LocalizedString]
+
+
+class Artist(_ArtistRequired, total=False):
+    party_reference: Optional[str]
+    sequence_number: Optional[int]
+
+
+class _ArtistInfoRequired(TypedDict):
+    name: str
+    role: str
+
+
+class ArtistInfo(_ArtistInfoRequired, total=False):
+    party_id: Optional[str]
+
+
+class _AuditTrailEventRequired(TypedDict):
+    date_time: str
+    message_audit_trail_event_reference: str
+    message_audit_trail_event_type: str
+
+
+class AuditTrailEvent(_AuditTrailEventRequired, total=False):
+    # All XML attributes (standard and custom)
+    attributes: Optional[AttributeMap]
+    # Comments associated with this audit trail event
+    comments: Optional[List[Comment]]
+    # Extensions for individual audit trail events
+    extensions: Optional[Extensions]
+    responsible_party_reference: Optional[str]
+
+
+class _CommentRequired(TypedDict):
+    # The comment content (without <!-- --> markers)
+    content: str
+    # Position relative to the parent element
+    position: CommentPosition
+    # Whether this comment should be preserved during canonicalization
+    preserve_formatting: bool
+    # Processing hints for specific output formats
+    processing_hints: Dict[str, str]
+
+
+class Comment(_CommentRequired, total=False):
+    """Enhanced comment structure with position and location metadata"""
+
+    # Column number in original XML (for debugging/tooling)
+    column_number: Optional[int]
+    # Line number in original XML (for debugging/tooling)
+    line_number: Optional[int]
+    # XPath-like location reference for precise positioning
+    xpath: Optional[str]
+
+
+class ContactDetails(TypedDict, total=False):
+    address: Optional[str]
+    email: Optional[str]
+    phone: Optional[str]
+
+
+class _CopyrightRequired(TypedDict):
+    text: str
+
+
+class Copyright(_CopyrightRequired, total=False):
+    owner: Optional[str]
+    year: Optional[int]
+
+
+class _DealRequired(TypedDict):
+    deal_release_reference: List[str]
+    deal_terms: DealTerms
+
+
+class Deal(_DealRequired, total=False):
+    # Comments associated with deal
+    comments: Optional[List[Comment]]
+    deal_reference: Optional[str]
+
+
+class _DealTermsRequired(TypedDict):
+    commercial_model_type: List[CommercialModelType]
+    distribution_channel: List[DistributionChannel]
+    excluded_distribution_channel: List[DistributionChannel]
+    excluded_territory_code: List[str]
+    price_information: List[PriceInformation]
+    suggested_retail_price: List[Price]
+    territory_code: List[str]
+    use_type: List[UseType]
+    wholesale_price: List[Price]
+
+
+class DealTerms(_DealTermsRequired, total=False):
+    end_date: Optional[str]
+    instant_gratification_date: Optional[str]
+    pre_order_date: Optional[str]
+    pre_order_preview_date: Optional[str]
+    start_date: Optional[str]
+    takedown_date: Optional[str]
+    validity_period: Optional[ValidityPeriod]
+
+
+class DealValidity(TypedDict, total=False):
+    end: Optional[str]
+    start: Optional[str]
+
+
+class DistributionComplexity(TypedDict):
+    excluded: List[str]
+    included: List[str]
+
+
+class Duration(TypedDict):
+    nanos: int
+    secs: int
+
+
+class _ERNMessageRequired(TypedDict):
+    deals: List[Deal]
+    message_header: MessageHeader
+    parties: List[Party]
+    releases: List[Release]
+    resources: List[Resource]
+    version: ERNVersion
+
+
+class ERNMessage(_ERNMessageRequired, total=False):
+    # All XML attributes (standard and custom) for the root element
+    attributes: Optional[AttributeMap]
+    comments: Optional[List[Comment]]
+    # Comprehensive extension preservation system
+    extensions: Optional[Extensions]
+    # Legacy extensions (for backward compatibility)
+    legacy_extensions: Optional[Dict[str, str]]
+    message_audit_trail: Optional[MessageAuditTrail]
+    profile: Optional[ERNProfile]
+    # Releases to take down (PurgeReleaseMessage only)
+    purged_releases: List[PurgedRelease]
+
+
+class Extensions(TypedDict):
+    """Extension container with location-aware storage"""
+
+    # Document-level comments
+    document_comments: List[Comment]
+    # Document-level processing instructions
+    document_processing_instructions: List[ProcessingInstruction]
+    # Extensions organized by their location in the DDEX structure
+    fragments: Dict[str, XmlFragment]
+    # Global namespace declarations that should be preserved at document level
+    global_namespaces: Dict[str, str]
+
+
+class _FlattenedMessageRequired(TypedDict):
+    deals: List[ParsedDeal]
+    message_date: str
+    message_id: str
+    message_type: str
+    parties: Dict[str, Party]
+    recipient: Organization
+    releases: List[ParsedRelease]
+    resources: Dict[str, ParsedResource]
+    sender: Organization
+    stats: MessageStats
+    version: str
+
+
+class FlattenedMessage(_FlattenedMessageRequired, total=False):
+    # Extensions for flattened message
+    extensions: Optional[Extensions]
+    profile: Optional[str]
+    # Catalog changes requested by update and purge messages
+    update_actions: List[UpdateAction]
+
+
+class _GenreRequired(TypedDict):
+    genre_text: str
+
+
+class Genre(_GenreRequired, total=False):
+    # All XML attributes (standard and custom)
+    attributes: Optional[AttributeMap]
+    # Comments associated with genre
+    comments: Optional[List[Comment]]
+    # Extensions for genre
+    extensions: Optional[Extensions]
+    sub_genre: Optional[str]
+
+
+class _IdentifierRequired(TypedDict):
+    id_type: IdentifierType
+    value: str
+
+
+class Identifier(_IdentifierRequired, total=False):
+    namespace: Optional[str]
+
+
+class _LocalizedStringRequired(TypedDict):
+    text: str
+
+
+class LocalizedString(_LocalizedStringRequired, total=False):
+    language_code: Optional[str]
+    script: Optional[str]
+
+
+class _MessageAuditTrailRequired(TypedDict):
+    audit_trail_events: List[AuditTrailEvent]
+
+
+class MessageAuditTrail(_MessageAuditTrailRequired, total=False):
+    # All XML attributes (standard and custom)
+    attributes: Optional[AttributeMap]
+    # Comments associated with audit trail
+    comments: Optional[List[Comment]]
+    # Extensions for audit trail
+    extensions: Optional[Extensions]
+
+
+class _MessageHeaderRequired(TypedDict):
+    message_created_date_time: str
+    message_id: str
+    message_recipient: MessageRecipient
+    message_sender: MessageSender
+    message_type: MessageType
+
+
+class MessageHeader(_MessageHeaderRequired, total=False):
+    # All XML attributes (standard and custom)
+    attributes: Optional[AttributeMap]
+    # Comments associated with message header
+    comments: Optional[List[Comment]]
+    # Extensions for message header
+    extensions: Optional[Extensions]
+    message_control_type: Optional[MessageControlType]
+    message_thread_id: Optional[str]
+
+
+class _MessageRecipientRequired(TypedDict):
+    party_id: List[Identifier]
+    party_name: List[LocalizedString]
+
+
+class MessageRecipient(_MessageRecipientRequired, total=False):
+    # All XML attributes (standard and custom)
+    attributes: Optional[AttributeMap]
+    # Comments associated with message recipient
+    comments: Optional[List[Comment]]
+    # Extensions for message recipient
+    extensions: Optional[Extensions]
+    trading_name: Optional[str]
+
+
+class _MessageSenderRequired(TypedDict):
+    party_id: List[Identifier]
+    party_name: List[LocalizedString]
+
+
+class MessageSender(_MessageSenderRequired, total=False):
+    # All XML attributes (standard and custom)
+    attributes: Optional[AttributeMap]
+    # Comments associated with message sender
+    comments: Optional[List[Comment]]
+    # Extensions for message sender
+    extensions: Optional[Extensions]
+    trading_name: Optional[str]
+
+
+class MessageStats(TypedDict):
+    deal_count: int
+    release_count: int
+    total_duration: int
+    track_count: int
+
+
+class _OrganizationRequired(TypedDict):
+    id: str
+    name: str
+
+
+class Organization(_OrganizationRequired, total=False):
+    # Extensions for organization
+    extensions: Optional[Extensions]
+
+
+class ParsedDeal(TypedDict):
+    deal_id: str
+    distribution_channels: DistributionComplexity
+    pricing: List[PriceTier]
+    releases: List[str]
+    restrictions: List[str]
+    territories: TerritoryComplexity
+    usage_rights: List[str]
+    validity: DealValidity
+
+
+class _ParsedImageRequired(TypedDict):
+    image_id: str
+    image_type: str
+
+
+class ParsedImage(_ParsedImageRequired, total=False):
+    file_format: Optional[str]
+    height: Optional[int]
+    width: Optional[int]
+
+
+class _ParsedReleaseRequired(TypedDict):
+    artists: List[ArtistInfo]
+    child_releases: List[str]
+    default_title: str
+    display_artist: str
+    identifiers: ReleaseIdentifiers
+    images: List[ParsedImage]
+    release_id: str
+    release_type: str
+    territories: List[TerritoryInfo]
+    title: List[LocalizedString]
+    track_count: int
+    tracks: List[ParsedTrack]
+    videos: List[ParsedVideo]
+
+
+class ParsedRelease(_ParsedReleaseRequired, total=False):
+    c_line: Optional[Copyright]
+    cover_art: Optional[ParsedImage]
+    default_subtitle: Optional[str]
+    disc_count: Optional[int]
+    # Extensions for parsed release
+    extensions: Optional[Extensions]
+    genre: Optional[str]
+    original_release_date: Optional[str]
+    p_line: Optional[Copyright]
+    parent_release: Optional[str]
+    release_date: Optional[str]
+    sub_genre: Optional[str]
+    subtitle: Optional[List[LocalizedString]]
+
+
+class _ParsedResourceRequired(TypedDict):
+    resource_id: str
+    resource_type: str
+    technical_details: TechnicalInfo
+    title: str
+
+
+class ParsedResource(_ParsedResourceRequired, total=False):
+    duration: Optional[Duration]
+
+
+class _ParsedTrackRequired(TypedDict):
+    artists: List[ArtistInfo]
+    display_artist: str
+    duration: Duration
+    duration_formatted: str
+    is_bonus: bool
+    is_explicit: bool
+    is_hidden: bool
+    is_instrumental: bool
+    position: int
+    title: str
+    track_id: str
+
+
+class ParsedTrack(_ParsedTrackRequired, total=False):
+    bitrate: Optional[int]
+    disc_number: Optional[int]
+    file_format: Optional[str]
+    isrc: Optional[str]
+    iswc: Optional[str]
+    sample_rate: Optional[int]
+    side: Optional[str]
+    subtitle: Optional[str]
+    track_number: Optional[int]
+
+
+class _ParsedVideoRequired(TypedDict):
+    video_id: str
+    video_type: str
+
+
+class ParsedVideo(_ParsedVideoRequired, total=False):
+    duration: Optional[Duration]
+    resolution: Optional[str]
+
+
+class _PartyRequired(TypedDict):
+    party_id: List[Identifier]
+    party_name: List[LocalizedString]
+    party_role: List[PartyRole]
+
+
+class Party(_PartyRequired, total=False):
+    contact_details: Optional[ContactDetails]
+    ipi: Optional[str]
+    isni: Optional[str]
+
+
+class _PriceRequired(TypedDict):
+    amount: float
+    currency: str
+
+
+class Price(_PriceRequired, total=False):
+    territory: Optional[str]
+
+
+class _PriceInformationRequired(TypedDict):
+    price: Price
+    price_type: str
+
+
+class PriceInformation(_PriceInformationRequired, total=False):
+    price_tier: Optional[str]
+
+
+class _PriceTierRequired(TypedDict):
+    price: Price
+    price_type: PriceType
+
+
+class PriceTier(_PriceTierRequired, total=False):
+    end_date: Optional[str]
+    start_date: Optional[str]
+    territory: Optional[str]
+    tier_name: Optional[str]
+
+
+class _ProcessingInstructionRequired(TypedDict):
+    # The target of the processing instruction
+    target: str
+
+
+class ProcessingInstruction(_ProcessingInstructionRequired, total=False):
+    """XML Processing Instruction"""
+
+    # The data content of the processing instruction
+    data: Optional[str]
+
+
+class ProprietaryId(TypedDict):
+    namespace: str
+    value: str
+
+
+class _PurgedReleaseRequired(TypedDict):
+    release_id: List[Identifier]
+    title: List[LocalizedString]
+
+
+class PurgedRelease(_PurgedReleaseRequired, total=False):
+    """A release that a PurgeReleaseMessage asks the recipient to take down"""
+
+    # All XML attributes (standard and custom)
+    attributes: Optional[AttributeMap]
+    # Comments associated with purged release
+    comments: Optional[List[Comment]]
+    # Extensions for purged release
+    extensions: Optional[Extensions]
+
+
+class _ReleaseRequired(TypedDict):
+    display_artist: List[Artist]
+    excluded_territory_code: List[str]
+    genre: List[Genre]
+    party_list: List[ReleaseParty]
+    release_date: List[ReleaseEvent]
+    release_id: List[Identifier]
+    release_reference: str
+    release_resource_reference_list: List[ReleaseResourceReference]
+    release_title: List[LocalizedString]
+    territory_code: List[str]
+
+
+class Release(_ReleaseRequired, total=False):
+    # All XML attributes (standard and custom)
+    attributes: Optional[AttributeMap]
+    # Comments associated with release
+    comments: Optional[List[Comment]]
+    # Extensions for release
+    extensions: Optional[Extensions]
+    release_subtitle: Optional[List[LocalizedString]]
+    release_type: Optional[ReleaseType]
+
+
+class _ReleaseEventRequired(TypedDict):
+    release_event_type: str
+
+
+class ReleaseEvent(_ReleaseEventRequired, total=False):
+    # Comments associated with release event
+    comments: Optional[List[Comment]]
+    event_date: Optional[str]
+    # Extensions for release event
+    extensions: Optional[Extensions]
+    territory: Optional[str]
+
+
+class _ReleaseIdentifiersRequired(TypedDict):
+    proprietary: List[ProprietaryId]
+
+
+class ReleaseIdentifiers(_ReleaseIdentifiersRequired, total=False):
+    catalog_number: Optional[str]
+    ean: Optional[str]
+    grid: Optional[str]
+    upc: Optional[str]
+
+
+class _ReleasePartyRequired(TypedDict):
+    party_reference: str
+    role: List[str]
+
+
+class ReleaseParty(_ReleasePartyRequired, total=False):
+    # Comments associated with release party
+    comments: Optional[List[Comment]]
+    # Extensions for release party
+    extensions: Optional[Extensions]
+
+
+class _ReleaseResourceReferenceRequired(TypedDict):
+    is_bonus: bool
+    is_hidden: bool
+    resource_reference: str
+
+
+class ReleaseResourceReference(_ReleaseResourceReferenceRequired, total=False):
+    # Comments associated with resource reference
+    comments: Optional[List[Comment]]
+    disc_number: Optional[int]
+    # Extensions for resource reference
+    extensions: Optional[Extensions]
+    sequence_number: Optional[int]
+    side: Optional[str]
+    track_number: Optional[int]
+
+
+class _ResourceRequired(TypedDict):
+    c_line: List[Copyright]
+    p_line: List[Copyright]
+    reference_title: List[LocalizedString]
+    resource_id: List[Identifier]
+    resource_reference: str
+    resource_type: ResourceType
+    rights_controller: List[str]
+    technical_details: List[TechnicalDetails]
+
+
+class Resource(_ResourceRequired, total=False):
+    # Comments associated with resource
+    comments: Optional[List[Comment]]
+    duration: Optional[Duration]
+    # Extensions for resource
+    extensions: Optional[Extensions]
+
+
+class _TechnicalDetailsRequired(TypedDict):
+    technical_resource_details_reference: str
+
+
+class TechnicalDetails(_TechnicalDetailsRequired, total=False):
+    audio_codec: Optional[str]
+    bitrate: Optional[int]
+    # Extensions for technical details
+    extensions: Optional[Extensions]
+    file_format: Optional[str]
+    file_size: Optional[int]
+    sample_rate: Optional[int]
+
+
+class TechnicalInfo(TypedDict, total=False):
+    bitrate: Optional[int]
+    file_format: Optional[str]
+    file_size: Optional[int]
+    sample_rate: Optional[int]
+
+
+class TerritoryComplexity(TypedDict):
+    excluded: List[str]
+    included: List[str]
+
+
+class _TerritoryInfoRequired(TypedDict):
+    code: str
+    distribution_channels: List[str]
+    included: bool
+
+
+class TerritoryInfo(_TerritoryInfoRequired, total=False):
+    end_date: Optional[str]
+    start_date: Optional[str]
+
+
+class ValidityPeriod(TypedDict, total=False):
+    end_date: Optional[str]
+    start_date: Optional[str]
+
+
+class _XmlFragmentRequired(TypedDict):
+    # All attributes on this element (including namespaced ones)
+    attributes: Dict[str, str]
+    # Child elements, text, comments and processing instructions, in
+    # document order
+    content: List[XmlNode]
+    # Element name (local name without namespace prefix)
+    element_name: str
+    # All namespace declarations on this element
+    namespace_declarations: Dict[str, str]
+    # Whether this fragment should be preserved as-is (no canonicalization)
+    preserve_formatting: bool
+    # The raw XML content as a string
+    raw_content: str
+
+
+class XmlFragment(_XmlFragmentRequired, total=False):
+    """Comprehensive XML fragment preservation for round-trip fidelity"""
+
+    # Namespace prefix used in the original XML
+    namespace_prefix: Optional[str]
+    # Namespace URI for this element
+    namespace_uri: Optional[str]
+    # Position hint for canonical ordering
+    position_hint: Optional[int]
+
+
+AttributeMap = Dict[str, str]
+
+# Position of a comment relative to its parent element
+CommentPosition = Literal["Before", "FirstChild", "LastChild", "After", "Inline"]
+
+CommercialModelType = Union[Literal["PayAsYouGoModel", "SubscriptionModel", "AdSupportedModel"], Dict[str, str]]
+
+DistributionChannel = Union[Literal["Download", "Stream", "Physical"], Dict[str, str]]
+
+ERNProfile = Literal["AudioAlbum", "AudioSingle", "Video", "Mixed"]
+
+ERNVersion = Literal["V3_8_2", "V4_2", "V4_3", "V4_4"]
+
+IdentifierType = Literal["Proprietary", "ISRC", "ISWC", "UPC", "EAN", "GRID", "GRid", "ISNI", "IPI"]
+
+MessageControlType = Literal["LiveMessage", "TestMessage"]
+
+MessageType = Literal["NewReleaseMessage", "UpdateReleaseMessage", "TakedownMessage", "PurgeReleaseMessage"]
+
+PartyRole = Union[Literal["Artist", "Producer", "Composer", "Lyricist", "Publisher", "Performer", "Engineer", "Label", "Distributor"], Dict[str, str]]
+
+PriceType = Literal["Wholesale", "SuggestedRetail", "Minimum"]
+
+ReleaseType = Union[Literal["Album", "Single", "EP", "Compilation"], Dict[str, str]]
+
+ResourceType = Literal["SoundRecording", "Video", "Image", "Text", "SheetMusic"]
+
+# A change a message asks the recipient to apply to its catalog
+#
+# NewReleaseMessage carries no actions; it replaces the release outright.
+UpdateAction = Union[Dict[str, Dict[str, str]], Dict[str, Dict[str, Union[Optional[str], List[str]]]], Dict[str, Dict[str, List["Identifier"]]]]
+
+UseType = Union[Literal["Stream", "Download", "OnDemandStream", "NonInteractiveStream"], Dict[str, str]]
+
+# A node inside an XML fragment
+XmlNode = Union[Dict[str, "XmlFragment"], Dict[str, str], Dict[str, "Comment"], Dict[str, "ProcessingInstruction"]]
@@ -0,0 +1,574 @@
+# Generated by `ddex-builder schema --format <language> --target graph-message --target flattened-message`.
+# Do not edit: regenerate after changing the Rust models.
+
+from __future__ import annotations
+
+from typing import Any, Dict, List, Literal, Optional, Union
+
+from pydantic import BaseModel, ConfigDict
+
+
+class _Model(BaseModel):
+    model_config = ConfigDict(protected_namespaces=())
+
+
+class Artist(_Model):
+    artist_role: List[str]
+    display_artist_name: List[LocalizedString]
+    party_reference: Optional[str] = None
+    sequence_number: Optional[int] = None
+
+
+class ArtistInfo(_Model):
+    name: str
+    role: str
+    party_id: Optional[str] = None
+
+
+class AuditTrailEvent(_Model):
+    date_time: str
+    message_audit_trail_event_reference: str
+    message_audit_trail_event_type: str
+    # All XML attributes (standard and custom)
+    attributes: Optional[AttributeMap] = None
+    # Comments associated with this audit trail event
+    comments: Optional[List[Comment]] = None
+    # Extensions for individual audit trail events
+    extensions: Optional[Extensions] = None
+    responsible_party_reference: Optional[str] = None
+
+
+class Comment(_Model):
+    """Enhanced comment structure with position and location metadata"""
+
+    # The comment content (without <!-- --> markers)
+    content: str
+    # Position relative to the parent element
+    position: CommentPosition
+    # Whether this comment should be preserved during canonicalization
+    preserve_formatting: bool
+    # Processing hints for specific output formats
+    processing_hints: Dict[str, str]
+    # Column number in original XML (for debugging/tooling)
+    column_number: Optional[int] = None
+    # Line number in original XML (for debugging/tooling)
+    line_number: Optional[int] = None
+    # XPath-like location reference for precise positioning
+    xpath: Optional[str] = None
+
+
+class ContactDetails(_Model):
+    address: Optional[str] = None
+    email: Optional[str] = None
+    phone: Optional[str] = None
+
+
+class Copyright(_Model):
+    text: str
+    owner: Optional[str] = None
+    year: Optional[int] = None
+
+
+class Deal(_Model):
+    deal_release_reference: List[str]
+    deal_terms: DealTerms
+    # Comments associated with deal
+    comments: Optional[List[Comment]] = None
+    deal_reference: Optional[str] = None
+
+
+class DealTerms(_Model):
+    commercial_model_type: List[CommercialModelType]
+    distribution_channel: List[DistributionChannel]
+    excluded_distribution_channel: List[DistributionChannel]
+    excluded_territory_code: List[str]
+    price_information: List[PriceInformation]
+    suggested_retail_price: List[Price]
+    territory_code: List[str]
+    use_type: List[UseType]
+    wholesale_price: List[Price]
+    end_date: Optional[str] = None
+    instant_gratification_date: Optional[str] = None
+    pre_order_date: Optional[str] = None
+    pre_order_preview_date: Optional[str] = None
+    start_date: Optional[str] = None
+    takedown_date: Optional[str] = None
+    validity_period: Optional[ValidityPeriod] = None
+
+
+class DealValidity(_Model):
+    end: Optional[str] = None
+    start: Optional[str] = None
+
+
+class DistributionComplexity(_Model):
+    excluded: List[str]
+    included: List[str]
+
+
+class Duration(_Model):
+    nanos: int
+    secs: int
+
+
+class ERNMessage(_Model):
+    deals: List[Deal]
+    message_header: MessageHeader
+    parties: List[Party]
+    releases: List[Release]
+    resources: List[Resource]
+    version: ERNVersion
+    # All XML attributes (standard and custom) for the root element
+    attributes: Optional[AttributeMap] = None
+    comments: Optional[List[Comment]] = None
+    # Comprehensive extension preservation system
+    extensions: Optional[Extensions] = None
+    # Legacy extensions (for backward compatibility)
+    legacy_extensions: Optional[Dict[str, str]] = None
+    message_audit_trail: Optional[MessageAuditTrail] = None
+    profile: Optional[ERNProfile] = None
+    # Releases to take down (PurgeReleaseMessage only)
+    purged_releases: Optional[List[PurgedRelease]] = None
+
+
+class Extensions(_Model):
+    """Extension container with location-aware storage"""
+
+    # Document-level comments
+    document_comments: List[Comment]
+    # Document-level processing instructions
+    document_processing_instructions: List[ProcessingInstruction]
+    # Extensions organized by their location in the DDEX structure
+    fragments: Dict[str, XmlFragment]
+    # Global namespace declarations that should be preserved at document level
+    global_namespaces: Dict[str, str]
+
+
+class FlattenedMessage(_Model):
+    deals: List[ParsedDeal]
+    message_date: str
+    message_id: str
+    message_type: str
+    parties: Dict[str, Party]
+    recipient: Organization
+    releases: List[ParsedRelease]
+    resources: Dict[str, ParsedResource]
+    sender: Organization
+    stats: MessageStats
+    version: str
+    # Extensions for flattened message
+    extensions: Optional[Extensions] = None
+    profile: Optional[str] = None
+    # Catalog changes requested by update and purge messages
+    update_actions: Optional[List[UpdateAction]] = None
+
+
+class Genre(_Model):
+    genre_text: str
+    # All XML attributes (standard and custom)
+    attributes: Optional[AttributeMap] = None
+    # Comments associated with genre
+    comments: Optional[List[Comment]] = None
+    # Extensions for genre
+    extensions: Optional[Extensions] = None
+    sub_genre: Optional[str] = None
+
+
+class Identifier(_Model):
+    id_type: IdentifierType
+    value: str
+    namespace: Optional[str] = None
+
+
+class LocalizedString(_Model):
+    text: str
+    language_code: Optional[str] = None
+    script: Optional[str] = None
+
+
+class MessageAuditTrail(_Model):
+    audit_trail_events: List[AuditTrailEvent]
+    # All XML attributes (standard and custom)
+    attributes: Optional[AttributeMap] = None
+    # Comments associated with audit trail
+    comments: Optional[List[Comment]] = None
+    # Extensions for audit trail
+    extensions: Optional[Extensions] = None
+
+
+class MessageHeader(_Model):
+    message_created_date_time: str
+    message_id: str
+    message_recipient: MessageRecipient
+    message_sender: MessageSender
+    message_type: MessageType
+    # All XML attributes (standard and custom)
+    attributes: Optional[AttributeMap] = None
+    # Comments associated with message header
+    comments: Optional[List[Comment]] = None
+    # Extensions for message header
+    extensions: Optional[Extensions] = None
+    message_control_type: Optional[MessageControlType] = None
+    message_thread_id: Optional[str] = None
+
+
+class MessageRecipient(_Model):
+    party_id: List[Identifier]
+    party_name: List[LocalizedString]
+    # All XML attributes (standard and custom)
+    attributes: Optional[AttributeMap] = None
+    # Comments associated with message recipient
+    comments: Optional[List[Comment]] = None
+    # Extensions for message recipient
+    extensions: Optional[Extensions] = None
+    trading_name: Optional[str] = None
+
+
+class MessageSender(_Model):
+    party_id: List[Identifier]
+    party_name: List[LocalizedString]
+    # All XML attributes (standard and custom)
+    attributes: Optional[AttributeMap] = None
+    # Comments associated with message sender
+    comments: Optional[List[Comment]] = None
+    # Extensions for message sender
+    extensions: Optional[Extensions] = None
+    trading_name: Optional[str] = None
+
+
+class MessageStats(_Model):
+    deal_count: int
+    release_count: int
+    total_duration: int
+    track_count: int
+
+
+class Organization(_Model):
+    id: str
+    name: str
+    # Extensions for organization
+    extensions: Optional[Extensions] = None
+
+
+class ParsedDeal(_Model):
+    deal_id: str
+    distribution_channels: DistributionComplexity
+    pricing: List[PriceTier]
+    releases: List[str]
+    restrictions: List[str]
+    territories: TerritoryComplexity
+    usage_rights: List[str]
+    validity: DealValidity
+
+
+class ParsedImage(_Model):
+    image_id: str
+    image_type: str
+    file_format: Optional[str] = None
+    height: Optional[int] = None
+    width: Optional[int] = None
+
+
+class ParsedRelease(_Model):
+    artists: List[ArtistInfo]
+    child_releases: List[str]
+    default_title: str
+    display_artist: str
+    identifiers: ReleaseIdentifiers
+    images: List[ParsedImage]
+    release_id: str
+    release_type: str
+    territories: List[TerritoryInfo]
+    title: List[LocalizedString]
+    track_count: int
+    tracks: List[ParsedTrack]
+    videos: List[ParsedVideo]
+    c_line: Optional[Copyright] = None
+    cover_art: Optional[ParsedImage] = None
+    default_subtitle: Optional[str] = None
+    disc_count: Optional[int] = None
+    # Extensions for parsed release
+    extensions: Optional[Extensions] = None
+    genre: Optional[str] = None
+    original_release_date: Optional[str] = None
+    p_line: Optional[Copyright] = None
+    parent_release: Optional[str] = None
+    release_date: Optional[str] = None
+    sub_genre: Optional[str] = None
+    subtitle: Optional[List[LocalizedString]] = None
+
+
+class ParsedResource(_Model):
+    resource_id: str
+    resource_type: str
+    technical_details: TechnicalInfo
+    title: str
+    duration: Optional[Duration] = None
+
+
+class ParsedTrack(_Model):
+    artists: List[ArtistInfo]
+    display_artist: str
+    duration: Duration
+    duration_formatted: str
+    is_bonus: bool
+    is_explicit: bool
+    is_hidden: bool
+    is_instrumental: bool
+    position: int
+    title: str
+    track_id: str
+    bitrate: Optional[int] = None
+    disc_number: Optional[int] = None
+    file_format: Optional[str] = None
+    isrc: Optional[str] = None
+    iswc: Optional[str] = None
+    sample_rate: Optional[int] = None
+    side: Optional[str] = None
+    subtitle: Optional[str] = None
+    track_number: Optional[int] = None
+
+
+class ParsedVideo(_Model):
+    video_id: str
+    video_type: str
+    duration: Optional[Duration] = None
+    resolution: Optional[str] = None
+
+
+class Party(_Model):
+    party_id: List[Identifier]
+    party_name: List[LocalizedString]
+    party_role: List[PartyRole]
+    contact_details: Optional[ContactDetails] = None
+    ipi: Optional[str] = None
+    isni: Optional[str] = None
+
+
+class Price(_Model):
+    amount: float
+    currency: str
+    territory: Optional[str] = None
+
+
+class PriceInformation(_Model):
+    price: Price
+    price_type: str
+    price_tier: Optional[str] = None
+
+
+class PriceTier(_Model):
+    price: Price
+    price_type: PriceType
+    end_date: Optional[str] = None
+    start_date: Optional[str] = None
+    territory: Optional[str] = None
+    tier_name: Optional[str] = None
+
+
+class ProcessingInstruction(_Model):
+    """XML Processing Instruction"""
+
+    # The target of the processing instruction
+    target: str
+    # The data content of the processing instruction
+    data: Optional[str] = None
+
+
+class ProprietaryId(_Model):
+    namespace: str
+    value: str
+
+
+class PurgedRelease(_Model):
+    """A release that a PurgeReleaseMessage asks the recipient to take down"""
+
+    release_id: List[Identifier]
+    title: List[LocalizedString]
+    # All XML attributes (standard and custom)
+    attributes: Optional[AttributeMap] = None
+    # Comments associated with purged release
+    comments: Optional[List[Comment]] = None
+    # Extensions for purged release
+    extensions: Optional[Extensions] = None
+
+
+class Release(_Model):
+    display_artist: List[Artist]
+    excluded_territory_code: List[str]
+    genre: List[Genre]
+    party_list: List[ReleaseParty]
+    release_date: List[ReleaseEvent]
+    release_id: List[Identifier]
+    release_reference: str
+    release_resource_reference_list: List[ReleaseResourceReference]
+    release_title: List[LocalizedString]
+    territory_code: List[str]
+    # All XML attributes (standard and custom)
+    attributes: Optional[AttributeMap] = None
+    # Comments associated with release
+    comments: Optional[List[Comment]] = None
+    # Extensions for release
+    extensions: Optional[Extensions] = None
+    release_subtitle: Optional[List[LocalizedString]] = None
+    release_type: Optional[ReleaseType] = None
+
+
+class ReleaseEvent(_Model):
+    release_event_type: str
+    # Comments associated with release event
+    comments: Optional[List[Comment]] = None
+    event_date: Optional[str] = None
+    # Extensions for release event
+    extensions: Optional[Extensions] = None
+    territory: Optional[str] = None
+
+
+class ReleaseIdentifiers(_Model):
+    proprietary: List[ProprietaryId]
+    catalog_number: Optional[str] = None
+    ean: Optional[str] = None
+    grid: Optional[str] = None
+    upc: Optional[str] = None
+
+
+class ReleaseParty(_Model):
+    party_reference: str
+    role: List[str]
+    # Comments associated with release party
+    comments: Optional[List[Comment]] = None
+    # Extensions for release party
+    extensions: Optional[Extensions] = None
+
+
+class ReleaseResourceReference(_Model):
+    is_bonus: bool
+    is_hidden: bool
+    resource_reference: str
+    # Comments associated with resource reference
+    comments: Optional[List[Comment]] = None
+    disc_number: Optional[int] = None
+    # Extensions for resource reference
+    extensions: Optional[Extensions] = None
+    sequence_number: Optional[int] = None
+    side: Optional[str] = None
+    track_number: Optional[int] = None
+
+
+class Resource(_Model):
+    c_line: List[Copyright]
+    p_line: List[Copyright]
+    reference_title: List[LocalizedString]
+    resource_id: List[Identifier]
+    resource_reference: str
+    resource_type: ResourceType
+    rights_controller: List[str]
+    technical_details: List[TechnicalDetails]
+    # Comments associated with resource
+    comments: Optional[List[Comment]] = None
+    duration: Optional[Duration] = None
+    # Extensions for resource
+    extensions: Optional[Extensions] = None
+
+
+class TechnicalDetails(_Model):
+    technical_resource_details_reference: str
+    audio_codec: Optional[str] = None
+    bitrate: Optional[int] = None
+    # Extensions for technical details
+    extensions: Optional[Extensions] = None
+    file_format: Optional[str] = None
+    file_size: Optional[int] = None
+    sample_rate: Optional[int] = None
+
+
+class TechnicalInfo(_Model):
+    bitrate: Optional[int] = None
+    file_format: Optional[str] = None
+    file_size: Optional[int] = None
+    sample_rate: Optional[int] = None
+
+
+class TerritoryComplexity(_Model):
+    excluded: List[str]
+    included: List[str]
+
+
+class TerritoryInfo(_Model):
+    code: str
+    distribution_channels: List[str]
+    included: bool
+    end_date: Optional[str] = None
+    start_date: Optional[str] = None
+
+
+class ValidityPeriod(_Model):
+    end_date: Optional[str] = None
+    start_date: Optional[str] = None
+
+
+class XmlFragment(_Model):
+    """Comprehensive XML fragment preservation for round-trip fidelity"""
+
+    # All attributes on this element (including namespaced ones)
+    attributes: Dict[str, str]
+    # Child elements, text, comments and processing instructions, in
+    # document order
+    content: List[XmlNode]
+    # Element name (local name without namespace prefix)
+    element_name: str
+    # All namespace declarations on this element
+    namespace_declarations: Dict[str, str]
+    # Whether this fragment should be preserved as-is (no canonicalization)
+    preserve_formatting: bool
+    # The raw XML content as a string
+    raw_content: str
+    # Namespace prefix used in the original XML
+    namespace_prefix: Optional[str] = None
+    # Namespace URI for this element
+    namespace_uri: Optional[str] = None
+    # Position hint for canonical ordering
+    position_hint: Optional[int] = None
+
+
+AttributeMap = Dict[str, str]
+
+# Position of a comment relative to its parent element
+CommentPosition = Literal["Before", "FirstChild", "LastChild", "After", "Inline"]
+
+CommercialModelType = Union[Literal["PayAsYouGoModel", "SubscriptionModel", "AdSupportedModel"], Dict[str, str]]
+
+DistributionChannel = Union[Literal["Download", "Stream", "Physical"], Dict[str, str]]
+
+ERNProfile = Literal["AudioAlbum", "AudioSingle", "Video", "Mixed"]
+
+ERNVersion = Literal["V3_8_2", "V4_2", "V4_3", "V4_4"]
+
+IdentifierType = Literal["Proprietary", "ISRC", "ISWC", "UPC", "EAN", "GRID", "GRid", "ISNI", "IPI"]
+
+MessageControlType = Literal["LiveMessage", "TestMessage"]
+
+MessageType = Literal["NewReleaseMessage", "UpdateReleaseMessage", "TakedownMessage", "PurgeReleaseMessage"]
+
+PartyRole = Union[Literal["Artist", "Producer", "Composer", "Lyricist", "Publisher", "Performer", "Engineer", "Label", "Distributor"], Dict[str, str]]
+
+PriceType = Literal["Wholesale", "SuggestedRetail", "Minimum"]
+
+ReleaseType = Union[Literal["Album", "Single", "EP", "Compilation"], Dict[str, str]]
+
+ResourceType = Literal["SoundRecording", "Video", "Image", "Text", "SheetMusic"]
+
+# A change a message asks the recipient to apply to its catalog
+#
+# NewReleaseMessage carries no actions; it replaces the release outright.
+UpdateAction = Union[Dict[str, Dict[str, str]], Dict[str, Dict[str, Union[Optional[str], List[str]]]], Dict[str, Dict[str, List["Identifier"]]]]
+
+UseType = Union[Literal["Stream", "Download", "OnDemandStream", "NonInteractiveStream"], Dict[str, str]]
+
+# A node inside an XML fragment
+XmlNode = Union[Dict[str, "XmlFragment"], Dict[str, str], Dict[str, "Comment"], Dict[str, "ProcessingInstruction"]]
+
+
+for _model in list(globals().values()):
+    if isinstance(_model, type) and issubclass(_model, _Model) and _model is not _Model:
+        _model.model_rebuild()
@@ -7,7 +7,62 @@ use pythonize::pythonize;
 use pyo3_async_runtimes;
 use ddex_parser::{DDEXParser as CoreParser, parser::ParseOptions as CoreParseOptions};
 use ddex_core::models::flat::ParsedERNMessage as CoreParsedERNMessage;
-use std::io::Cursor;
+use ddex_core::ffi::FFIError;
+use ddex_core::models::versions::ERNVersion;
+use ddex_parser::parser::stream::StreamingParser;
+use std::io::{BufRead, BufReader, Cursor, Read};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+
+pyo3::create_exception!(ddex_parser, DdexError, PyValueError, "Parse error with a stable `code`, plus `path`, `severity`, `suggestion` and `category`");
+
+/// `DdexError` carrying the fields of the `FFIError` for `err` as attributes
+fn ddex_error(err: impl Into<FFIError>) -> PyErr {
+    let ffi_error: FFIError = err.into();
+    let error = DdexError::new_err(ffi_error.message.clone());
+    Python::with_gil(|py| {
+        let value = error.value(py);
+        let severity = format!("{:?}", ffi_error.severity);
+        let category = format!("{:?}", ffi_error.category);
+        let attributes = value.setattr("code", &ffi_error.code)
+            .and_then(|_| value.setattr("path", ffi_error.location.as_ref().map(|l| l.path.clone())))
+            .and_then(|_| value.setattr("severity", severity))
+            .and_then(|_| value.setattr("suggestion", ffi_error.suggestion.clone()))
+            .and_then(|_| value.setattr("category", category));
+        match attributes {
+            Ok(()) => error,
+            Err(e) => e,
+        }
+    })
+}
+
+/// Run `work` on a worker thread with the GIL released, cancelling it through
+/// `token` and raising `KeyboardInterrupt` if Ctrl-C arrives meanwhile
+fn run_interruptible<T: Send>(
+    py: Python,
+    token: &ddex_core::cancel::CancellationToken,
+    work: impl FnOnce() -> T + Send,
+) -> PyResult<T> {
+    std::thread::scope(|scope| {
+        let caller = std::thread::current();
+        let worker = scope.spawn(move || {
+            let result = work();
+            caller.unpark();
+            result
+        });
+        loop {
+            if worker.is_finished() {
+                return worker.join().map_err(|_| pyo3::exceptions::PyRuntimeError::new_err("worker thread panicked"));
+            }
+            py.allow_threads(|| std::thread::park_timeout(std::time::Duration::from_millis(50)));
+            if let Err(interrupt) = py.check_signals() {
+                // The worker stops at its next element or stage
+                token.cancel();
+                let _ = py.allow_threads(|| worker.join());
+                return Err(interrupt);
+            }
+        }
+    })
+}
 
 /// Main DDEX Parser class for Python
 #[pyclass(name = "DDEXParser")]
@@ -25,7 +80,7 @@ impl PyDDEXParser {
         }
     }
     
-    /// Parse DDEX XML synchronously
+    /// Parse DDEX XML synchronously; Ctrl-C cancels the parse
     #[pyo3(signature = (xml, options=None))]
     pub fn parse(
         &self,
@@ -37,18 +92,20 @@ impl PyDDEXParser {
         let xml_str = extract_xml_string(xml)?;
         
         // Parse options
-        let parse_options = if let Some(opts) = options {
+        let mut parse_options = if let Some(opts) = options {
             rust_parse_options_from_dict(opts)?
         } else {
             CoreParseOptions::default()
         };
+        let token = ddex_core::cancel::CancellationToken::new();
+        parse_options.cancellation = Some(token.clone());
         
-        // Create a cursor from the string
-        let cursor = Cursor::new(xml_str.as_bytes());
-        
-        // Parse using the real parser
-        let result = self.parser.parse_with_options(cursor, parse_options)
-            .map_err(|e| PyValueError::new_err(format!("Parse error: {}", e)))?;
+        // Parse using the real parser, off the GIL so Ctrl-C is noticed
+        let parser = &self.parser;
+        let result = run_interruptible(py, &token, || {
+            parser.parse_with_options(Cursor::new(xml_str.as_bytes()), parse_options)
+        })?
+        .map_err(ddex_error)?;
         
         // Return PyParsedERNMessage wrapper
         let wrapped_result = PyParsedERNMessage::new(result);
@@ -56,7 +113,8 @@ impl PyDDEXParser {
         Ok(py_obj.into_any())
     }
     
-    /// Parse DDEX XML asynchronously  
+    /// Parse DDEX XML on a worker thread, returning an awaitable so asyncio
+    /// services (FastAPI and the like) keep serving meanwhile
     #[pyo3(signature = (xml, options=None))]
     pub fn parse_async<'p>(
         &self,
@@ -65,23 +123,28 @@ impl PyDDEXParser {
         options: Option<&Bound<'_, PyDict>>,
     ) -> PyResult<Bound<'p, PyAny>> {
         let xml_str = extract_xml_string(xml)?;
-        let parse_options = if let Some(opts) = options {
+        let mut parse_options = if let Some(opts) = options {
             rust_parse_options_from_dict(opts)?
         } else {
             CoreParseOptions::default()
         };
+        let token = ddex_core::cancel::CancellationToken::new();
+        parse_options.cancellation = Some(token.clone());
         
         let parser = self.parser.clone();
         
         // Create async future
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            // Cancelling the awaiting task (or Ctrl-C under asyncio.run) drops
+            // this future, which stops the parse instead of leaving it running
+            let _cancel_on_drop = CancelOnDrop(token);
             // Run parsing in a blocking task to avoid blocking the async runtime
             let result = tokio::task::spawn_blocking(move || {
                 let cursor = Cursor::new(xml_str.as_bytes());
                 parser.parse_with_options(cursor, parse_options)
             }).await
             .map_err(|e| PyValueError::new_err(format!("Task join error: {}", e)))?
-            .map_err(|e| PyValueError::new_err(format!("Parse error: {}", e)))?;
+            .map_err(ddex_error)?;
             
             Python::with_gil(|py| -> PyResult<Py<PyAny>> {
                 let wrapped_result = PyParsedERNMessage::new(result);
@@ -91,31 +154,26 @@ impl PyDDEXParser {
         })
     }
     
-    /// Stream parse large files
+    /// Iterate over the releases of a file path, file object, `str` or
+    /// `bytes` document as they are parsed
+    ///
+    /// Parsing runs on a background thread without the GIL, a bounded number
+    /// of releases ahead of the consumer. Ctrl-C while waiting raises
+    /// `KeyboardInterrupt` and stops the parse.
+    #[pyo3(signature = (source, options=None))]
     pub fn stream(
         &self,
         py: Python,
         source: &Bound<'_, PyAny>,
         options: Option<&Bound<'_, PyDict>>,
     ) -> PyResult<StreamIterator> {
-        // Extract XML content
-        let xml_str = extract_xml_string(source)?;
-        
-        // Parse options
+        let reader = open_stream_source(py, source)?;
         let parse_options = if let Some(opts) = options {
             rust_parse_options_from_dict(opts)?
         } else {
             CoreParseOptions::default()
         };
-        
-        // Parse the entire document first (for now - in a true streaming implementation,
-        // this would parse incrementally)
-        let cursor = Cursor::new(xml_str.as_bytes());
-        let parsed_result = self.parser.parse_with_options(cursor, parse_options)
-            .map_err(|e| PyValueError::new_err(format!("Stream parse error: {}", e)))?;
-        
-        // Create iterator with the parsed releases
-        Ok(StreamIterator::from_parsed_result(parsed_result))
+        Ok(StreamIterator::spawn(reader, parse_options))
     }
     
     /// Convert DDEX XML to pandas DataFrame
@@ -141,7 +199,7 @@ impl PyDDEXParser {
         let cursor = Cursor::new(xml_str.as_bytes());
         
         let parsed = self.parser.parse_with_options(cursor, CoreParseOptions::default())
-            .map_err(|e| PyValueError::new_err(format!("Parse error: {}", e)))?;
+            .map_err(ddex_error)?;
         
         // Try to import pandas
         let pandas = py.import("pandas")
@@ -514,6 +572,44 @@ impl PyParsedERNMessage {
         Ok(df.into())
     }
     
+    /// Convert to pyarrow Tables
+    ///
+    /// Returns a dict with "releases", "tracks" and "deals" tables sharing
+    /// `message_id` and `release_id` keys, or a single table if `table` is given.
+    #[cfg(feature = "arrow")]
+    #[pyo3(signature = (table = None))]
+    fn to_arrow(&self, py: Python, table: Option<&str>) -> PyResult<Py<PyAny>> {
+        let parquet = py.import("pyarrow.parquet")
+            .map_err(|_| PyValueError::new_err("pyarrow is required for to_arrow(). Install with: pip install pyarrow"))?;
+        let pyarrow = py.import("pyarrow")?;
+
+        let conversion_error = |e: ddex_parser::arrow::ParquetError| PyValueError::new_err(format!("Arrow conversion failed: {}", e));
+        let mut writer = ddex_parser::arrow::ParquetCatalogWriter::new(Vec::new(), Vec::new(), Vec::new())
+            .map_err(conversion_error)?;
+        writer.write_message(&self.inner.flat).map_err(conversion_error)?;
+        let (releases, tracks, deals) = writer.finish().map_err(conversion_error)?;
+
+        let read_table = |bytes: Vec<u8>| -> PyResult<Py<PyAny>> {
+            let buffer = pyarrow.call_method1("BufferReader", (PyBytes::new(py, &bytes),))?;
+            Ok(parquet.call_method1("read_table", (buffer,))?.into())
+        };
+        match table {
+            Some("releases") => read_table(releases),
+            Some("tracks") => read_table(tracks),
+            Some("deals") => read_table(deals),
+            Some(other) => Err(PyValueError::new_err(format!(
+                "Unknown table: {}. Use 'releases', 'tracks', or 'deals'", other
+            ))),
+            None => {
+                let tables = PyDict::new(py);
+                tables.set_item("releases", read_table(releases)?)?;
+                tables.set_item("tracks", read_table(tracks)?)?;
+                tables.set_item("deals", read_table(deals)?)?;
+                Ok(tables.into())
+            }
+        }
+    }
+
     /// Get message ID
     fn message_id(&self) -> String {
         self.inner.flat.message_id.clone()
@@ -609,43 +705,66 @@ impl PyParsedERNMessage {
     }
 }
 
-/// Stream iterator for large files
+/// Releases parsed ahead of the consumer before the parser thread waits
+const RELEASES_IN_FLIGHT: usize = 64;
+
+/// Cancels the parse it guards when dropped
+struct CancelOnDrop(ddex_core::cancel::CancellationToken);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+/// Iterator over the releases of a streaming parse running on its own thread
 #[pyclass]
 pub struct StreamIterator {
-    position: usize,
-    releases: Vec<StreamRelease>,
+    releases: std::sync::Mutex<Receiver<Result<StreamRelease, ddex_parser::error::ParseError>>>,
+    cancellation: ddex_core::cancel::CancellationToken,
 }
 
-#[derive(Clone)]
 struct StreamRelease {
-    release_id: String,
+    release_reference: String,
+    release_id: Option<String>,
     title: String,
     artist: String,
     track_count: u32,
 }
 
 impl StreamIterator {
-    fn new() -> Self {
-        StreamIterator {
-            position: 0,
-            releases: Vec::new(),
-        }
-    }
-    
-    fn from_parsed_result(parsed_result: ddex_core::models::flat::ParsedERNMessage) -> Self {
-        let releases = parsed_result.flat.releases.iter().map(|release| {
-            StreamRelease {
-                release_id: release.release_id.clone(),
-                title: release.default_title.clone(),
-                artist: release.display_artist.clone(),
-                track_count: release.track_count as u32,
+    fn spawn(reader: Box<dyn BufRead + Send>, options: CoreParseOptions) -> Self {
+        let cancellation = ddex_core::cancel::CancellationToken::new();
+        let (sender, releases) = mpsc::sync_channel(RELEASES_IN_FLIGHT);
+        let token = cancellation.clone();
+        std::thread::spawn(move || {
+            let mut parser = StreamingParser::new(reader, ERNVersion::V4_3)
+                .with_max_memory(options.max_memory)
+                .with_cancellation(Some(token));
+            for release in parser.stream_releases() {
+                let release = release.map(|release| StreamRelease {
+                    release_id: release.release_id.first().map(|id| id.value.clone()),
+                    title: release.release_title.first().map(|t| t.text.clone()).unwrap_or_default(),
+                    artist: release.display_artist.first()
+                        .and_then(|artist| artist.display_artist_name.first())
+                        .map(|name| name.text.clone())
+                        .unwrap_or_default(),
+                    track_count: release.release_resource_reference_list.len() as u32,
+                    release_reference: release.release_reference,
+                });
+                // A dropped iterator stops the parse at the next release
+                if sender.send(release).is_err() {
+                    return;
+                }
             }
-        }).collect();
-        
-        StreamIterator {
-            position: 0,
-            releases,
-        }
+        });
+        StreamIterator { releases: std::sync::Mutex::new(releases), cancellation }
+    }
+}
+
+impl Drop for StreamIterator {
+    fn drop(&mut self) {
+        self.cancellation.cancel();
     }
 }
 
@@ -655,21 +774,77 @@ impl StreamIterator {
         slf
     }
     
-    fn __next__(mut slf: PyRefMut<'_, Self>, py: Python) -> Option<Py<PyAny>> {
-        if slf.position >= slf.releases.len() {
-            return None;
-        }
-        
-        let release = slf.releases[slf.position].clone();
-        slf.position += 1;
+    fn __next__(&self, py: Python) -> PyResult<Option<Py<PyAny>>> {
+        let releases = self.releases.lock()
+            .map_err(|_| pyo3::exceptions::PyRuntimeError::new_err("stream iterator poisoned"))?;
+        let release = loop {
+            match py.allow_threads(|| releases.recv_timeout(std::time::Duration::from_millis(50))) {
+                Ok(Ok(release)) => break release,
+                Ok(Err(e)) => return Err(ddex_error(e)),
+                Err(RecvTimeoutError::Disconnected) => return Ok(None),
+                Err(RecvTimeoutError::Timeout) => {
+                    if let Err(interrupt) = py.check_signals() {
+                        self.cancellation.cancel();
+                        return Err(interrupt);
+                    }
+                }
+            }
+        };
         
         let dict = PyDict::new(py);
-        dict.set_item("release_id", &release.release_id).ok()?;
-        dict.set_item("title", &release.title).ok()?;
-        dict.set_item("artist", &release.artist).ok()?;
-        dict.set_item("track_count", release.track_count).ok()?;
+        dict.set_item("release_reference", &release.release_reference)?;
+        dict.set_item("release_id", &release.release_id)?;
+        dict.set_item("title", &release.title)?;
+        dict.set_item("artist", &release.artist)?;
+        dict.set_item("track_count", release.track_count)?;
         
-        Some(dict.into_any().into())
+        Ok(Some(dict.into_any().into()))
+    }
+}
+
+/// A reader for `stream`: a path (`str` not starting with `<`, or any
+/// `os.PathLike`), XML as `str` or `bytes`, or a binary or text file object
+fn open_stream_source(py: Python, source: &Bound<'_, PyAny>) -> PyResult<Box<dyn BufRead + Send>> {
+    if source.hasattr("read")? {
+        return Ok(Box::new(BufReader::new(PyFileReader { file: source.clone().unbind(), pending: Vec::new() })));
+    }
+    if let Ok(bytes) = source.downcast::<PyBytes>() {
+        return Ok(Box::new(Cursor::new(bytes.as_bytes().to_vec())));
+    }
+    let path = match source.extract::<String>() {
+        Ok(text) if text.trim_start().starts_with('<') => return Ok(Box::new(Cursor::new(text.into_bytes()))),
+        Ok(path) => std::path::PathBuf::from(path),
+        Err(_) => source.extract::<std::path::PathBuf>()
+            .map_err(|_| PyValueError::new_err("source must be a path, file object, str or bytes"))?,
+    };
+    let file = py.allow_threads(|| std::fs::File::open(&path))
+        .map_err(|e| pyo3::exceptions::PyOSError::new_err(format!("{}: {}", path.display(), e)))?;
+    Ok(Box::new(BufReader::new(file)))
+}
+
+/// Reads a Python file object from the parser thread, taking the GIL for
+/// each `read`; text files are encoded as UTF-8
+struct PyFileReader {
+    file: Py<PyAny>,
+    pending: Vec<u8>,
+}
+
+impl Read for PyFileReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.pending.is_empty() {
+            self.pending = Python::with_gil(|py| -> PyResult<Vec<u8>> {
+                let chunk = self.file.bind(py).call_method1("read", (buf.len().max(8192),))?;
+                match chunk.downcast::<PyBytes>() {
+                    Ok(bytes) => Ok(bytes.as_bytes().to_vec()),
+                    Err(_) => Ok(chunk.extract::<String>()?.into_bytes()),
+                }
+            })
+            .map_err(|e| std::io::Error::other(e.to_string()))?;
+        }
+        let len = buf.len().min(self.pending.len());
+        buf[..len].copy_from_slice(&self.pending[..len]);
+        self.pending.drain(..len);
+        Ok(len)
     }
 }
 
@@ -709,6 +884,12 @@ fn rust_parse_options_from_dict(dict: &Bound<'_, PyDict>) -> PyResult<CoreParseO
     if let Some(v) = dict.get_item("auto_threshold")? {
         options.auto_threshold = v.extract()?;
     }
+    if let Some(v) = dict.get_item("intern_strings")? {
+        options.intern_strings = v.extract()?;
+    }
+    if let Some(v) = dict.get_item("preferred_languages")? {
+        options.preferred_languages = v.extract()?;
+    }
     
     // Legacy options for backward compatibility
     if let Some(v) = dict.get_item("validate_references")? {
@@ -736,7 +917,8 @@ fn extract_xml_string(xml: &Bound<'_, PyAny>) -> PyResult<String> {
 
 /// Python module initialization
 #[pymodule]
-fn _internal(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {  // Changed from ddex_parser to _internal
+fn _internal(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {  // Changed from ddex_parser to _internal
+    m.add("DdexError", py.get_type::<DdexError>())?;
     m.add_class::<PyDDEXParser>()?;
     m.add_class::<PyParsedERNMessage>()?;
     m.add_class::<StreamIterator>()?;
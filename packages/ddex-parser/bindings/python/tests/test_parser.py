@@ -50,6 +50,16 @@ class TestDDEXParser:
         releases = list(parser.stream(SAMPLE_XML))
         assert len(releases) >= 0  # May be empty in mock
     
+    def test_stream_from_path_and_file(self, tmp_path):
+        parser = DDEXParser()
+        path = tmp_path / "message.xml"
+        path.write_text(SAMPLE_XML, encoding="utf-8")
+        from_text = list(parser.stream(SAMPLE_XML))
+        assert list(parser.stream(path)) == from_text
+        assert list(parser.stream(str(path))) == from_text
+        with open(path, "rb") as f:
+            assert list(parser.stream(f)) == from_text
+    
     def test_detect_version(self):
         parser = DDEXParser()
         
@@ -81,6 +91,13 @@ class TestDDEXParser:
         assert df is not None
         assert len(df) >= 0
     
+    def test_to_arrow(self):
+        pytest.importorskip("pyarrow")
+        parser = DDEXParser()
+        tables = parser.parse(SAMPLE_XML).to_arrow()
+        assert set(tables) == {"releases", "tracks", "deals"}
+        assert tables["tracks"].column_names[:2] == ["message_id", "release_id"]
+    
     def test_convenience_function(self):
         result = parse(SAMPLE_XML, validate_references=False)
         assert result.message_id == "MSG001"
@@ -4,6 +4,13 @@ use serde_wasm_bindgen::{from_value, to_value};
 use ddex_parser::{DDEXParser as CoreParser};
 use ddex_core::models::flat::ParsedERNMessage;
 
+/// Plain object with the fields of the `FFIError` for `err`, so callers can
+/// branch on its `code`
+fn ddex_error(err: impl Into<ddex_core::ffi::FFIError>) -> JsValue {
+    let ffi_error: ddex_core::ffi::FFIError = err.into();
+    to_value(&ffi_error).unwrap_or_else(|_| JsValue::from_str(&ffi_error.message))
+}
+
 #[wasm_bindgen]
 pub struct DDEXParser {
     inner: CoreParser,
@@ -20,11 +27,22 @@ impl DDEXParser {
         })
     }
     
+    /// Parse `xml`, rejecting with a `DDEX-P-0605` error if `signal` is
+    /// already aborted; a parse runs to completion once started, so run it
+    /// in a Web Worker to abandon it midway
     #[wasm_bindgen]
-    pub fn parse(&self, xml: &str, _options: JsValue) -> Result<JsValue, JsValue> {
+    pub fn parse(&self, xml: &str, _options: JsValue, signal: Option<web_sys::AbortSignal>) -> Result<JsValue, JsValue> {
+        if signal.is_some_and(|signal| signal.aborted()) {
+            return Err(ddex_error(ddex_parser::error::ParseError::Cancelled {
+                reason: ddex_core::cancel::CancelReason::Requested,
+                bytes_processed: 0,
+                releases_parsed: 0,
+            }));
+        }
+        
         let cursor = std::io::Cursor::new(xml.as_bytes());
         let result = self.inner.parse(cursor)
-            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+            .map_err(ddex_error)?;
             
         to_value(&result)
             .map_err(|e| JsValue::from_str(&e.to_string()))
@@ -0,0 +1,390 @@
+// core/src/arrow.rs
+//! Arrow record batches and Parquet files of flattened catalogs
+//!
+//! A catalog is split into three tables: `releases`, `tracks` and `deals`.
+//! Every row carries the `message_id` it came from, tracks and deals carry the
+//! `release_id` they belong to, so the tables join like a relational export.
+//! Deals are written once per release they cover.
+//!
+//! [`ParquetCatalogWriter`] writes message after message, so catalogs far
+//! larger than memory can be exported one parsed message at a time.
+
+use arrow_array::builder::{ListBuilder, StringBuilder};
+use arrow_array::{
+    ArrayRef, BooleanArray, Date32Array, RecordBatch, StringArray, TimestampMillisecondArray,
+    UInt64Array,
+};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{DateTime, NaiveDate, Utc};
+use ddex_core::models::flat::{FlattenedMessage, ParsedDeal, ParsedRelease, ParsedTrack};
+use parquet::arrow::ArrowWriter;
+pub use parquet::errors::ParquetError;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Schema of the `releases` table
+pub fn releases_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("message_id", DataType::Utf8, false),
+        Field::new("release_id", DataType::Utf8, false),
+        Field::new("upc", DataType::Utf8, true),
+        Field::new("grid", DataType::Utf8, true),
+        Field::new("catalog_number", DataType::Utf8, true),
+        Field::new("title", DataType::Utf8, false),
+        Field::new("display_artist", DataType::Utf8, false),
+        Field::new("release_type", DataType::Utf8, false),
+        Field::new("genre", DataType::Utf8, true),
+        Field::new("release_date", DataType::Date32, true),
+        Field::new("track_count", DataType::UInt64, false),
+    ]))
+}
+
+/// Schema of the `tracks` table
+pub fn tracks_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("message_id", DataType::Utf8, false),
+        Field::new("release_id", DataType::Utf8, false),
+        Field::new("position", DataType::UInt64, false),
+        Field::new("track_id", DataType::Utf8, false),
+        Field::new("isrc", DataType::Utf8, true),
+        Field::new("title", DataType::Utf8, false),
+        Field::new("display_artist", DataType::Utf8, false),
+        Field::new("duration_seconds", DataType::UInt64, false),
+        Field::new("is_explicit", DataType::Boolean, false),
+    ]))
+}
+
+/// Schema of the `deals` table
+pub fn deals_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("message_id", DataType::Utf8, false),
+        Field::new("deal_id", DataType::Utf8, false),
+        Field::new("release_id", DataType::Utf8, true),
+        Field::new("valid_from", timestamp(), true),
+        Field::new("valid_until", timestamp(), true),
+        Field::new("territories", string_list(), false),
+        Field::new("excluded_territories", string_list(), false),
+        Field::new("use_types", string_list(), false),
+    ]))
+}
+
+fn timestamp() -> DataType {
+    DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()))
+}
+
+fn string_list() -> DataType {
+    DataType::List(Arc::new(Field::new("item", DataType::Utf8, true)))
+}
+
+/// Record batches of the three catalog tables
+#[derive(Debug, Clone)]
+pub struct CatalogBatches {
+    pub releases: RecordBatch,
+    pub tracks: RecordBatch,
+    pub deals: RecordBatch,
+}
+
+impl CatalogBatches {
+    /// Build the tables of one or more flattened messages
+    pub fn from_messages(messages: &[FlattenedMessage]) -> Result<Self, ArrowError> {
+        let releases: Vec<_> = messages
+            .iter()
+            .flat_map(|m| m.releases.iter().map(move |r| (m.message_id.as_str(), r)))
+            .collect();
+        let tracks: Vec<_> = releases
+            .iter()
+            .flat_map(|&(message_id, r)| r.tracks.iter().map(move |t| (message_id, r, t)))
+            .collect();
+        let deals: Vec<_> = messages
+            .iter()
+            .flat_map(|m| m.deals.iter().map(move |d| (m.message_id.as_str(), d)))
+            .flat_map(|(message_id, deal)| deal_rows(deal).map(move |release_id| (message_id, deal, release_id)))
+            .collect();
+
+        Ok(Self {
+            releases: releases_batch(&releases)?,
+            tracks: tracks_batch(&tracks)?,
+            deals: deals_batch(&deals)?,
+        })
+    }
+}
+
+/// Release ids a deal is written for; a deal without releases still gets a row
+fn deal_rows(deal: &ParsedDeal) -> impl Iterator<Item = Option<&str>> {
+    let none = deal.releases.is_empty().then_some(None);
+    deal.releases.iter().map(|id| Some(id.as_str())).chain(none)
+}
+
+fn strings<T>(rows: &[T], value: impl Fn(&T) -> Option<&str>) -> ArrayRef {
+    Arc::new(rows.iter().map(value).collect::<StringArray>())
+}
+
+fn lists<T>(rows: &[T], values: impl Fn(&T) -> &[String]) -> ArrayRef {
+    let mut builder = ListBuilder::new(StringBuilder::new());
+    for row in rows {
+        for value in values(row) {
+            builder.values().append_value(value);
+        }
+        builder.append(true);
+    }
+    Arc::new(builder.finish())
+}
+
+fn timestamps<T>(rows: &[T], value: impl Fn(&T) -> Option<DateTime<Utc>>) -> ArrayRef {
+    let array: TimestampMillisecondArray = rows.iter().map(|r| value(r).map(|d| d.timestamp_millis())).collect();
+    Arc::new(array.with_timezone("UTC"))
+}
+
+fn days_since_epoch(date: DateTime<Utc>) -> i32 {
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).expect("valid date");
+    (date.date_naive() - epoch).num_days() as i32
+}
+
+fn releases_batch(rows: &[(&str, &ParsedRelease)]) -> Result<RecordBatch, ArrowError> {
+    let columns: Vec<ArrayRef> = vec![
+        strings(rows, |(m, _)| Some(m)),
+        strings(rows, |(_, r)| Some(r.release_id.as_str())),
+        strings(rows, |(_, r)| r.identifiers.upc.as_deref()),
+        strings(rows, |(_, r)| r.identifiers.grid.as_deref()),
+        strings(rows, |(_, r)| r.identifiers.catalog_number.as_deref()),
+        strings(rows, |(_, r)| Some(r.default_title.as_str())),
+        strings(rows, |(_, r)| Some(r.display_artist.as_str())),
+        strings(rows, |(_, r)| Some(r.release_type.as_str())),
+        strings(rows, |(_, r)| r.genre.as_deref()),
+        Arc::new(rows.iter().map(|(_, r)| r.release_date.map(days_since_epoch)).collect::<Date32Array>()),
+        Arc::new(rows.iter().map(|(_, r)| r.tracks.len() as u64).collect::<UInt64Array>()),
+    ];
+    RecordBatch::try_new(releases_schema(), columns)
+}
+
+fn tracks_batch(rows: &[(&str, &ParsedRelease, &ParsedTrack)]) -> Result<RecordBatch, ArrowError> {
+    let columns: Vec<ArrayRef> = vec![
+        strings(rows, |(m, _, _)| Some(m)),
+        strings(rows, |(_, r, _)| Some(r.release_id.as_str())),
+        Arc::new(rows.iter().map(|(_, _, t)| t.position as u64).collect::<UInt64Array>()),
+        strings(rows, |(_, _, t)| Some(t.track_id.as_str())),
+        strings(rows, |(_, _, t)| t.isrc.as_deref()),
+        strings(rows, |(_, _, t)| Some(t.title.as_str())),
+        strings(rows, |(_, _, t)| Some(t.display_artist.as_str())),
+        Arc::new(rows.iter().map(|(_, _, t)| t.duration.as_secs()).collect::<UInt64Array>()),
+        Arc::new(rows.iter().map(|(_, _, t)| Some(t.is_explicit)).collect::<BooleanArray>()),
+    ];
+    RecordBatch::try_new(tracks_schema(), columns)
+}
+
+fn deals_batch(rows: &[(&str, &ParsedDeal, Option<&str>)]) -> Result<RecordBatch, ArrowError> {
+    let columns: Vec<ArrayRef> = vec![
+        strings(rows, |(m, _, _)| Some(m)),
+        strings(rows, |(_, d, _)| Some(d.deal_id.as_str())),
+        strings(rows, |(_, _, release_id)| *release_id),
+        timestamps(rows, |(_, d, _)| d.validity.start),
+        timestamps(rows, |(_, d, _)| d.validity.end),
+        lists(rows, |(_, d, _)| &d.territories.included),
+        lists(rows, |(_, d, _)| &d.territories.excluded),
+        lists(rows, |(_, d, _)| &d.usage_rights),
+    ];
+    RecordBatch::try_new(deals_schema(), columns)
+}
+
+/// Writes the catalog tables as three Parquet files, one message at a time
+pub struct ParquetCatalogWriter<W: Write + Send> {
+    releases: ArrowWriter<W>,
+    tracks: ArrowWriter<W>,
+    deals: ArrowWriter<W>,
+}
+
+impl ParquetCatalogWriter<File> {
+    /// Create `releases.parquet`, `tracks.parquet` and `deals.parquet` in `dir`
+    pub fn create(dir: &Path) -> Result<Self, ParquetError> {
+        std::fs::create_dir_all(dir)?;
+        Self::new(
+            File::create(dir.join("releases.parquet"))?,
+            File::create(dir.join("tracks.parquet"))?,
+            File::create(dir.join("deals.parquet"))?,
+        )
+    }
+}
+
+impl<W: Write + Send> ParquetCatalogWriter<W> {
+    /// Write the tables to the given sinks
+    pub fn new(releases: W, tracks: W, deals: W) -> Result<Self, ParquetError> {
+        Ok(Self {
+            releases: ArrowWriter::try_new(releases, releases_schema(), None)?,
+            tracks: ArrowWriter::try_new(tracks, tracks_schema(), None)?,
+            deals: ArrowWriter::try_new(deals, deals_schema(), None)?,
+        })
+    }
+
+    /// Append the rows of a flattened message
+    pub fn write_message(&mut self, message: &FlattenedMessage) -> Result<(), ParquetError> {
+        self.write_batches(&CatalogBatches::from_messages(std::slice::from_ref(message))?)
+    }
+
+    /// Append already built record batches
+    pub fn write_batches(&mut self, batches: &CatalogBatches) -> Result<(), ParquetError> {
+        self.releases.write(&batches.releases)?;
+        self.tracks.write(&batches.tracks)?;
+        self.deals.write(&batches.deals)
+    }
+
+    /// Write the Parquet footers and return the releases, tracks and deals sinks
+    pub fn finish(self) -> Result<(W, W, W), ParquetError> {
+        Ok((self.releases.into_inner()?, self.tracks.into_inner()?, self.deals.into_inner()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{Array, ListArray};
+    use ddex_core::models::flat::{DealValidity, DistributionComplexity, TerritoryComplexity};
+    use std::time::Duration;
+
+    fn message() -> FlattenedMessage {
+        let mut message: FlattenedMessage = serde_json::from_value(serde_json::json!({
+            "message_id": "MSG1",
+            "message_type": "NewReleaseMessage",
+            "message_date": "2024-03-01T00:00:00Z",
+            "sender": { "name": "Label", "id": "PADPIDA1" },
+            "recipient": { "name": "DSP", "id": "PADPIDA2" },
+            "releases": [],
+            "resources": {},
+            "deals": [],
+            "parties": {},
+            "version": "4.3",
+            "profile": null,
+            "stats": { "release_count": 1, "track_count": 2, "deal_count": 1, "total_duration": 0 }
+        }))
+        .unwrap();
+        let mut release: ParsedRelease = serde_json::from_value(serde_json::json!({
+            "release_id": "R1",
+            "identifiers": { "upc": "012345678905", "ean": null, "catalog_number": null, "grid": null, "proprietary": [] },
+            "title": [],
+            "default_title": "Album",
+            "subtitle": null,
+            "default_subtitle": null,
+            "display_artist": "Artist",
+            "artists": [],
+            "release_type": "Album",
+            "genre": null,
+            "sub_genre": null,
+            "tracks": [],
+            "track_count": 2,
+            "disc_count": null,
+            "videos": [],
+            "images": [],
+            "cover_art": null,
+            "release_date": "2024-03-01T00:00:00Z",
+            "original_release_date": null,
+            "territories": [],
+            "p_line": null,
+            "c_line": null,
+            "parent_release": null,
+            "child_releases": []
+        }))
+        .unwrap();
+        for (position, isrc) in [(1, Some("USRC17607839")), (2, None)] {
+            let mut track: ParsedTrack = serde_json::from_value(serde_json::json!({
+                "track_id": format!("T{}", position),
+                "isrc": isrc,
+                "iswc": null,
+                "position": position,
+                "track_number": null,
+                "disc_number": null,
+                "side": null,
+                "title": "Track",
+                "subtitle": null,
+                "display_artist": "Artist",
+                "artists": [],
+                "duration": { "secs": 0, "nanos": 0 },
+                "duration_formatted": "",
+                "file_format": null,
+                "bitrate": null,
+                "sample_rate": null,
+                "is_hidden": false,
+                "is_bonus": false,
+                "is_explicit": position == 2,
+                "is_instrumental": false
+            }))
+            .unwrap();
+            track.duration = Duration::from_secs(180);
+            release.tracks.push(track);
+        }
+        message.releases.push(release);
+        message.deals.push(ParsedDeal {
+            deal_id: "D1".to_string(),
+            releases: vec!["R1".to_string()],
+            validity: DealValidity {
+                start: Some(message.message_date),
+                end: None,
+            },
+            territories: TerritoryComplexity {
+                included: vec!["GB".to_string(), "FR".to_string()],
+                excluded: Vec::new(),
+            },
+            distribution_channels: DistributionComplexity {
+                included: Vec::new(),
+                excluded: Vec::new(),
+            },
+            pricing: Vec::new(),
+            usage_rights: vec!["OnDemandStream".to_string()],
+            restrictions: Vec::new(),
+        });
+        message
+    }
+
+    fn column<'a, T: 'static>(batch: &'a RecordBatch, name: &str) -> &'a T {
+        batch.column_by_name(name).unwrap().as_any().downcast_ref::<T>().unwrap()
+    }
+
+    #[test]
+    fn test_tables_share_keys() {
+        let mut second = message();
+        second.message_id = "MSG2".to_string();
+        second.deals[0].releases.clear();
+        let batches = CatalogBatches::from_messages(&[message(), second]).unwrap();
+
+        assert_eq!(batches.releases.num_rows(), 2);
+        assert_eq!(batches.tracks.num_rows(), 4);
+        assert_eq!(batches.deals.num_rows(), 2);
+
+        let track_messages = column::<StringArray>(&batches.tracks, "message_id");
+        assert_eq!(track_messages.value(0), "MSG1");
+        assert_eq!(track_messages.value(3), "MSG2");
+        assert_eq!(column::<StringArray>(&batches.tracks, "release_id").value(0), "R1");
+        assert!(column::<StringArray>(&batches.tracks, "isrc").is_null(1));
+        assert!(column::<BooleanArray>(&batches.tracks, "is_explicit").value(1));
+
+        let release_ids = column::<StringArray>(&batches.deals, "release_id");
+        assert_eq!(release_ids.value(0), "R1");
+        assert!(release_ids.is_null(1));
+        let territories = column::<ListArray>(&batches.deals, "territories");
+        assert_eq!(territories.value(0).len(), 2);
+
+        let dates = column::<Date32Array>(&batches.releases, "release_date");
+        assert_eq!(dates.value(0), 19783);
+    }
+
+    #[test]
+    fn test_parquet_writer_round_trip() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let mut writer = ParquetCatalogWriter::new(Vec::new(), Vec::new(), Vec::new()).unwrap();
+        writer.write_message(&message()).unwrap();
+        writer.write_message(&message()).unwrap();
+        let (releases, tracks, deals) = writer.finish().unwrap();
+
+        let rows = |bytes: Vec<u8>| {
+            SerializedFileReader::new(bytes::Bytes::from(bytes))
+                .unwrap()
+                .metadata()
+                .file_metadata()
+                .num_rows()
+        };
+        assert_eq!(rows(releases), 2);
+        assert_eq!(rows(tracks), 4);
+        assert_eq!(rows(deals), 2);
+    }
+}
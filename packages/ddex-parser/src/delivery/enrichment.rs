@@ -0,0 +1,187 @@
+// core/src/delivery/enrichment.rs
+//! Consistency of MEAD enrichment with the ERN message it enriches
+//!
+//! A MEAD message adds moods, focus tracks and awards to releases already
+//! delivered by ERN, naming them only by identifier. [`check_enrichment`]
+//! matches every `ReleaseInformation` to a release of the companion ERN
+//! message by any of its IDs, and every focus track to a sound recording of
+//! that release by ISRC; enrichment matching nothing is reported as
+//! orphaned. The sender and recipient DPIDs of both messages must agree.
+//!
+//! PIE (Party Identification and Enrichment) messages are not parsed yet, so
+//! party references are only compared between the MEAD and ERN headers.
+
+use ddex_core::models::borrowed::{BorrowedIdentifier, BorrowedMessage};
+use ddex_core::models::mead::MeadMessage;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Problems found between a MEAD message and its ERN message
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnrichmentReport {
+    pub issues: Vec<EnrichmentIssue>,
+}
+
+impl EnrichmentReport {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// One inconsistency
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EnrichmentIssue {
+    /// A `ReleaseInformation`, by position, whose IDs match no release
+    OrphanedRelease { index: usize, release_ids: Vec<String> },
+    /// A focus track that is not a sound recording of its release
+    OrphanedFocusTrack { release_reference: String, isrc: String },
+    /// The MEAD header names another sender or recipient than the ERN header
+    PartyMismatch { party: String, mead: String, ern: String },
+}
+
+impl fmt::Display for EnrichmentIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnrichmentIssue::OrphanedRelease { index, release_ids } => {
+                write!(f, "ReleaseInformation[{}] ({}) matches no release", index + 1, release_ids.join(", "))
+            }
+            EnrichmentIssue::OrphanedFocusTrack { release_reference, isrc } => {
+                write!(f, "focus track {} is not a sound recording of release {}", isrc, release_reference)
+            }
+            EnrichmentIssue::PartyMismatch { party, mead, ern } => {
+                write!(f, "{} is {} in the MEAD message but {} in the ERN message", party, mead, ern)
+            }
+        }
+    }
+}
+
+/// Check `mead` against the ERN message whose releases it enriches
+pub fn check_enrichment(ern: &BorrowedMessage<'_>, mead: &MeadMessage) -> EnrichmentReport {
+    let mut report = EnrichmentReport::default();
+
+    let parties = [
+        ("MessageSender", mead.message_sender.as_deref(), ern.sender_id.as_deref()),
+        ("MessageRecipient", mead.message_recipient.as_deref(), ern.recipient_id.as_deref()),
+    ];
+    for (party, mead_id, ern_id) in parties {
+        if let (Some(mead_id), Some(ern_id)) = (mead_id, ern_id) {
+            if !mead_id.trim().eq_ignore_ascii_case(ern_id.trim()) {
+                report.issues.push(EnrichmentIssue::PartyMismatch {
+                    party: party.to_string(),
+                    mead: mead_id.to_string(),
+                    ern: ern_id.to_string(),
+                });
+            }
+        }
+    }
+
+    for (index, info) in mead.release_information.iter().enumerate() {
+        let wanted: Vec<String> = info.release_id.iter().map(|id| key(&id.value)).collect();
+        let Some(release) = ern.releases.iter().find(|r| ids_match(&r.release_id, &wanted)) else {
+            report.issues.push(EnrichmentIssue::OrphanedRelease {
+                index,
+                release_ids: info.release_id.iter().map(|id| id.value.clone()).collect(),
+            });
+            continue;
+        };
+
+        let isrcs: Vec<String> = ern
+            .resources
+            .iter()
+            .filter(|resource| release.resource_references.contains(&resource.resource_reference))
+            .flat_map(|resource| resource.resource_id.iter().map(|id| key(&id.value)))
+            .collect();
+        for track in &info.focus_tracks {
+            if !isrcs.contains(&key(&track.isrc)) {
+                report.issues.push(EnrichmentIssue::OrphanedFocusTrack {
+                    release_reference: release.release_reference.to_string(),
+                    isrc: track.isrc.clone(),
+                });
+            }
+        }
+    }
+    report
+}
+
+fn ids_match(ids: &[BorrowedIdentifier<'_>], wanted: &[String]) -> bool {
+    ids.iter().any(|id| wanted.contains(&key(&id.value)))
+}
+
+/// Identifier as compared: upper case without separators, and numeric codes
+/// without leading zeros so UPC-A matches EAN-13
+fn key(identifier: &str) -> String {
+    let key = identifier.chars().filter(char::is_ascii_alphanumeric).collect::<String>().to_ascii_uppercase();
+    if key.bytes().all(|b| b.is_ascii_digit()) {
+        key.trim_start_matches('0').to_string()
+    } else {
+        key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DDEXParser;
+    use std::io::Cursor;
+
+    const ERN: &str = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+  <MessageHeader>
+    <MessageId>M1</MessageId>
+    <MessageSender><PartyId>PADPIDA2014120301</PartyId></MessageSender>
+    <MessageRecipient><PartyId>PADPIDA2011072101</PartyId></MessageRecipient>
+  </MessageHeader>
+  <ReleaseList>
+    <Release>
+      <ReleaseReference>R1</ReleaseReference>
+      <ReleaseId><ICPN>602537518357</ICPN></ReleaseId>
+      <ResourceGroup><ResourceGroupContentItem><ReleaseResourceReference>A1</ReleaseResourceReference></ResourceGroupContentItem></ResourceGroup>
+    </Release>
+  </ReleaseList>
+  <ResourceList>
+    <SoundRecording>
+      <ResourceReference>A1</ResourceReference>
+      <SoundRecordingId><ISRC>USRC17607839</ISRC></SoundRecordingId>
+    </SoundRecording>
+  </ResourceList>
+</ern:NewReleaseMessage>"#;
+
+    const MEAD: &str = r#"<mead:MeadMessage xmlns:mead="http://ddex.net/xml/mead/11" MessageSchemaVersionId="mead/11">
+  <MessageHeader>
+    <MessageId>MEAD-001</MessageId>
+    <MessageSender><PartyId>PADPIDA2014120301</PartyId></MessageSender>
+    <MessageRecipient><PartyId>PADPIDA0000000009</PartyId></MessageRecipient>
+  </MessageHeader>
+  <ReleaseInformationList>
+    <ReleaseInformation>
+      <ReleaseSummary><ReleaseId><ICPN>0602537518357</ICPN></ReleaseId></ReleaseSummary>
+      <FocusTrack><ResourceId><ISRC>US-RC1-76-07839</ISRC></ResourceId></FocusTrack>
+      <FocusTrack><ResourceId><ISRC>USRC17600000</ISRC></ResourceId></FocusTrack>
+    </ReleaseInformation>
+    <ReleaseInformation>
+      <ReleaseSummary><ReleaseId><ICPN>0000000000001</ICPN></ReleaseId></ReleaseSummary>
+    </ReleaseInformation>
+  </ReleaseInformationList>
+</mead:MeadMessage>"#;
+
+    #[test]
+    fn test_check_enrichment_reports_orphans() {
+        let parser = DDEXParser::new();
+        let ern = parser.parse_borrowed(ERN).unwrap();
+        let mead = parser.parse_mead(Cursor::new(MEAD)).unwrap();
+
+        let report = check_enrichment(&ern, &mead);
+        assert_eq!(
+            report.issues,
+            vec![
+                EnrichmentIssue::PartyMismatch {
+                    party: "MessageRecipient".into(),
+                    mead: "PADPIDA0000000009".into(),
+                    ern: "PADPIDA2011072101".into(),
+                },
+                EnrichmentIssue::OrphanedFocusTrack { release_reference: "R1".into(), isrc: "USRC17600000".into() },
+                EnrichmentIssue::OrphanedRelease { index: 1, release_ids: vec!["0000000000001".into()] },
+            ]
+        );
+        assert_eq!(report.issues[2].to_string(), "ReleaseInformation[2] (0000000000001) matches no release");
+    }
+}
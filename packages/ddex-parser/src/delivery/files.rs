@@ -0,0 +1,162 @@
+// core/src/delivery/files.rs
+//! Resource files declared in an ERN message
+//!
+//! ERN 3.x names a file with `FilePath` + `FileName` and a nested
+//! `HashSum/HashSum` + `HashSumAlgorithmType`; ERN 4.x uses `URI` with
+//! `HashSum/HashSumValue` + `Algorithm`. Both shapes are read into the same
+//! [`FileReference`], along with the `*CodecType` declared next to the file.
+
+use crate::error::{ErrorLocation, ParseError};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+
+/// A checksum declared for a file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HashSum {
+    pub algorithm: String,
+    pub value: String,
+}
+
+/// A file a resource points at, as declared in the message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileReference {
+    pub resource_reference: String,
+    /// Element name of the resource, e.g. `SoundRecording` or `Image`
+    pub resource_kind: String,
+    /// `AudioCodecType`, `VideoCodecType` or `ImageCodecType` of the file
+    pub codec: Option<String>,
+    /// `FilePath` joined with `FileName`, or the `URI`/`URL` as written
+    pub location: String,
+    pub file_size: Option<u64>,
+    pub hash_sum: Option<HashSum>,
+}
+
+/// A `File` element being read
+#[derive(Default)]
+struct OpenFile {
+    name: Option<String>,
+    path: Option<String>,
+    uri: Option<String>,
+    file_size: Option<u64>,
+    algorithm: Option<String>,
+    value: Option<String>,
+}
+
+impl OpenFile {
+    fn location(&mut self) -> Option<String> {
+        if let Some(uri) = self.uri.take() {
+            return Some(uri);
+        }
+        let name = self.name.take()?;
+        Some(match self.path.take().filter(|p| !p.is_empty()) {
+            Some(path) => format!("{}/{}", path.trim_end_matches(['/', '\\']), name),
+            None => name,
+        })
+    }
+}
+
+/// Collect every file declared by the resources of `xml`
+pub fn file_references(xml: &str) -> Result<Vec<FileReference>, ParseError> {
+    let mut reader = Reader::from_str(xml);
+    let mut references = Vec::new();
+    let mut stack: Vec<String> = Vec::new();
+    // Depth of the resource being read and the index of its first file
+    let mut resource: Option<(usize, usize)> = None;
+    let mut resource_reference = String::new();
+    let mut resource_kind = String::new();
+    let mut file: Option<OpenFile> = None;
+    // Codec declared in the element at the given depth, for files inside it
+    let mut codec: Option<(usize, String)> = None;
+
+    loop {
+        let position = reader.buffer_position() as usize;
+        let event = reader.read_event().map_err(|e| ParseError::XmlError {
+            message: e.to_string(),
+            location: ErrorLocation {
+                line: 0,
+                column: 0,
+                byte_offset: Some(position),
+                path: stack.join("/"),
+            },
+        })?;
+
+        match event {
+            Event::Start(ref e) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                let in_list = stack.last().is_some_and(|parent| parent == "ResourceList");
+                if resource.is_none() && in_list && is_resource(&name) {
+                    resource = Some((stack.len() + 1, references.len()));
+                    resource_reference.clear();
+                    resource_kind = name.clone();
+                } else if resource.is_some() && name == "File" {
+                    file = Some(OpenFile::default());
+                }
+                stack.push(name);
+            }
+            Event::Text(ref t) => {
+                let Some((depth, _)) = resource else { continue };
+                let text = t.unescape().unwrap_or_default();
+                let text = text.trim();
+                let (Some(name), parent) = (stack.last(), stack.iter().rev().nth(1)) else { continue };
+
+                if name == "ResourceReference" && stack.len() == depth + 1 {
+                    resource_reference.push_str(text);
+                } else if name.ends_with("CodecType") && file.is_none() {
+                    codec = Some((stack.len() - 1, text.to_string()));
+                } else if let Some(file) = file.as_mut() {
+                    match (name.as_str(), parent.map(String::as_str)) {
+                        ("FileName", _) => file.name = Some(text.to_string()),
+                        ("FilePath", _) => file.path = Some(text.to_string()),
+                        ("URI" | "URL", _) => file.uri = Some(text.to_string()),
+                        ("FileSize", _) => file.file_size = text.parse().ok(),
+                        ("HashSumAlgorithmType" | "Algorithm", Some("HashSum")) => {
+                            file.algorithm = Some(text.to_string())
+                        }
+                        ("HashSumValue", _) | ("HashSum", Some("HashSum")) => file.value = Some(text.to_string()),
+                        _ => {}
+                    }
+                }
+            }
+            Event::End(_) => {
+                let name = stack.pop().unwrap_or_default();
+                if codec.as_ref().is_some_and(|(depth, _)| *depth > stack.len()) {
+                    // The element declaring the codec has closed
+                    codec = None;
+                }
+                if name == "File" {
+                    if let Some(mut open) = file.take() {
+                        let hash_sum = match (open.algorithm.take(), open.value.take()) {
+                            (Some(algorithm), Some(value)) => Some(HashSum { algorithm, value }),
+                            _ => None,
+                        };
+                        if let Some(location) = open.location() {
+                            references.push(FileReference {
+                                resource_reference: String::new(),
+                                resource_kind: resource_kind.clone(),
+                                codec: codec.as_ref().filter(|(depth, _)| *depth <= stack.len()).map(|(_, c)| c.clone()),
+                                location,
+                                file_size: open.file_size,
+                                hash_sum,
+                            });
+                        }
+                    }
+                } else if let Some((_, first)) = resource.filter(|(depth, _)| *depth == stack.len() + 1) {
+                    // The reference may follow the files, so it is filled in last
+                    for reference in &mut references[first..] {
+                        reference.resource_reference = resource_reference.clone();
+                    }
+                    resource = None;
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(references)
+}
+
+fn is_resource(name: &str) -> bool {
+    matches!(name, "SoundRecording" | "Video" | "Image" | "Text" | "SheetMusic" | "Software")
+}
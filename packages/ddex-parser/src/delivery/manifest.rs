@@ -0,0 +1,168 @@
+// core/src/delivery/manifest.rs
+//! Choreography messages shipped alongside ERN messages: the batch
+//! `ManifestMessage` and FTP acknowledgements
+
+use super::files::HashSum;
+use crate::error::{ErrorLocation, ParseError};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+
+/// Batch manifest listing the messages of a delivery
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Path within the delivery
+    pub path: String,
+    pub message_id: Option<String>,
+    pub number_of_messages: Option<usize>,
+    pub entries: Vec<ManifestEntry>,
+}
+
+/// One `MessageInBatch` of a manifest
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub message_type: Option<String>,
+    pub message_id: Option<String>,
+    pub url: Option<String>,
+    pub release_ids: Vec<String>,
+    pub hash_sum: Option<HashSum>,
+}
+
+/// FTP acknowledgement returned by the recipient of a delivery
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Acknowledgement {
+    /// Path within the delivery
+    pub path: String,
+    pub message_id: Option<String>,
+    pub acknowledged_file: Option<String>,
+    pub status: Option<String>,
+    pub error_text: Option<String>,
+}
+
+/// Read a `ManifestMessage`
+pub fn parse_manifest(path: &str, xml: &str) -> Result<Manifest, ParseError> {
+    let mut manifest = Manifest { path: path.to_string(), ..Default::default() };
+    // Algorithm and value of each entry's hash sum, in either order
+    let mut hashes: Vec<(Option<String>, Option<String>)> = Vec::new();
+
+    walk(xml, |stack, node| {
+        let name = stack.last().map(String::as_str);
+        let text = match node {
+            Node::Start if name == Some("MessageInBatch") => {
+                manifest.entries.push(ManifestEntry::default());
+                hashes.push((None, None));
+                return;
+            }
+            Node::Start => return,
+            Node::Text(text) => text,
+        };
+
+        if !stack.iter().any(|n| n == "MessageInBatch") {
+            match (name, parent(stack)) {
+                (Some("MessageId"), Some("MessageHeader")) => manifest.message_id = Some(text.to_string()),
+                (Some("NumberOfMessages"), _) => manifest.number_of_messages = text.parse().ok(),
+                _ => {}
+            }
+            return;
+        }
+
+        let (Some(entry), Some(hash)) = (manifest.entries.last_mut(), hashes.last_mut()) else { return };
+        match (name, parent(stack)) {
+            (Some("MessageType"), Some("MessageInBatch")) => entry.message_type = Some(text.to_string()),
+            (Some("MessageId"), Some("MessageInBatch")) => entry.message_id = Some(text.to_string()),
+            (Some("URL"), Some("MessageInBatch")) => entry.url = Some(text.to_string()),
+            (Some(_), Some("IncludedReleaseId")) => entry.release_ids.push(text.to_string()),
+            (Some("HashSumAlgorithmType" | "Algorithm"), Some("HashSum")) => hash.0 = Some(text.to_string()),
+            (Some("HashSumValue"), _) | (Some("HashSum"), Some("HashSum")) => hash.1 = Some(text.to_string()),
+            _ => {}
+        }
+    })?;
+
+    for (entry, hash) in manifest.entries.iter_mut().zip(hashes) {
+        if let (Some(algorithm), Some(value)) = hash {
+            entry.hash_sum = Some(HashSum { algorithm, value });
+        }
+    }
+    Ok(manifest)
+}
+
+/// Read an `FtpAcknowledgementMessage`
+pub fn parse_acknowledgement(path: &str, xml: &str) -> Result<Acknowledgement, ParseError> {
+    let mut acknowledgement = Acknowledgement { path: path.to_string(), ..Default::default() };
+
+    walk(xml, |stack, node| {
+        let Node::Text(text) = node else { return };
+        let text = Some(text.to_string());
+        match (stack.last().map(String::as_str), parent(stack)) {
+            (Some("MessageId"), Some("MessageHeader")) => acknowledgement.message_id = text,
+            (Some("AcknowledgedFile" | "FileName"), _) => acknowledgement.acknowledged_file = text,
+            (Some("MessageStatus" | "FileStatus"), _) => acknowledgement.status = text,
+            (Some("ErrorText"), _) => acknowledgement.error_text = text,
+            _ => {}
+        }
+    })?;
+
+    Ok(acknowledgement)
+}
+
+/// Local name of the root element of `xml`
+pub fn root_element(xml: &str) -> Option<String> {
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event().ok()? {
+            Event::Start(e) | Event::Empty(e) => {
+                return Some(String::from_utf8_lossy(e.local_name().as_ref()).into_owned())
+            }
+            Event::Eof => return None,
+            _ => {}
+        }
+    }
+}
+
+fn parent(stack: &[String]) -> Option<&str> {
+    stack.iter().rev().nth(1).map(String::as_str)
+}
+
+/// What [`walk`] reports, along with the names of the open elements
+enum Node<'a> {
+    /// An element was opened (it is the last name on the stack)
+    Start,
+    /// Trimmed, non-empty text
+    Text(&'a str),
+}
+
+fn walk(xml: &str, mut f: impl FnMut(&[String], Node)) -> Result<(), ParseError> {
+    let mut reader = Reader::from_str(xml);
+    let mut stack: Vec<String> = Vec::new();
+
+    loop {
+        let position = reader.buffer_position() as usize;
+        let event = reader.read_event().map_err(|e| ParseError::XmlError {
+            message: e.to_string(),
+            location: ErrorLocation {
+                line: 0,
+                column: 0,
+                byte_offset: Some(position),
+                path: stack.join("/"),
+            },
+        })?;
+
+        match event {
+            Event::Start(e) => {
+                stack.push(String::from_utf8_lossy(e.local_name().as_ref()).into_owned());
+                f(&stack, Node::Start);
+            }
+            Event::End(_) => {
+                stack.pop();
+            }
+            Event::Text(t) => {
+                let text = t.unescape().unwrap_or_default();
+                if !text.trim().is_empty() {
+                    f(&stack, Node::Text(text.trim()));
+                }
+            }
+            Event::Eof => return Ok(()),
+            _ => {}
+        }
+    }
+}
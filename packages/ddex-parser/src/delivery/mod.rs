@@ -0,0 +1,495 @@
+// core/src/delivery/mod.rs
+//! DDEX delivery packages
+//!
+//! A delivery is a batch folder or zip archive holding one or more ERN
+//! messages, the resource files they describe and the choreography files
+//! around them: an empty `BatchComplete_*.xml` marking the batch as finished,
+//! an optional `ManifestMessage` listing the messages and the FTP
+//! acknowledgements returned by the recipient. [`open`] reads all of them,
+//! ties every resource file to the message and `ResourceReference` declaring
+//! it and checks the MD5/SHA checksums declared for files and messages.
+//!
+//! Resource locations are resolved relative to the folder of the message
+//! declaring them; manifest URLs relative to the delivery root. Zip archives
+//! are read into memory (requires the `zip` feature).
+
+pub mod enrichment;
+pub mod files;
+pub mod manifest;
+pub mod preflight;
+
+pub use enrichment::{check_enrichment, EnrichmentIssue, EnrichmentReport};
+pub use files::{file_references, FileReference, HashSum};
+pub use manifest::{Acknowledgement, Manifest, ManifestEntry};
+pub use preflight::{check_resource_files, ResourceFileError, ResourceFileReport};
+
+use crate::error::ParseError;
+use crate::parser::encoding;
+use crate::DDEXParser;
+use ddex_core::compression::Compression;
+use ddex_core::error::DDEXError;
+use ddex_core::models::flat::ParsedERNMessage;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use sha2::digest::DynDigest;
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Everything found in a delivery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Delivery {
+    /// The folder or archive that was read
+    pub source: PathBuf,
+    /// Path of the `BatchComplete_*.xml` marker, if the batch is complete
+    pub batch_complete: Option<String>,
+    pub manifest: Option<Manifest>,
+    pub acknowledgements: Vec<Acknowledgement>,
+    pub messages: Vec<DeliveryMessage>,
+    /// Files that no message, manifest or marker accounts for
+    pub unreferenced_files: Vec<String>,
+    pub issues: Vec<DeliveryIssue>,
+}
+
+impl Delivery {
+    /// Whether every message parsed and every declared file is present with
+    /// a matching checksum
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// An ERN message of a delivery with the files its resources declare
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryMessage {
+    /// Path within the delivery
+    pub path: String,
+    pub parsed: ParsedERNMessage,
+    pub files: Vec<ResourceFile>,
+}
+
+/// A declared resource file and what was found for it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceFile {
+    pub reference: FileReference,
+    /// Path within the delivery; `None` for remote URIs
+    pub path: Option<String>,
+    pub status: FileStatus,
+}
+
+/// Outcome of looking up a declared file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FileStatus {
+    /// Present and matching its declared checksum
+    Verified,
+    /// Present; no checksum was declared or its algorithm is not supported
+    Present,
+    /// Present with a different checksum
+    ChecksumMismatch,
+    Missing,
+    /// Points outside the delivery (`http://`, `s3://`, ...)
+    Remote,
+}
+
+/// A problem found while reading a delivery
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeliveryIssue {
+    /// A message or choreography file could not be read
+    InvalidMessage { path: String, message: String },
+    /// A resource declares a file the delivery does not contain
+    MissingFile { message: String, resource_reference: String, path: String },
+    /// A manifest entry points at a message the delivery does not contain
+    MissingMessage { url: String },
+    ChecksumMismatch { path: String, algorithm: String, expected: String, actual: String },
+    UnsupportedHashAlgorithm { path: String, algorithm: String },
+}
+
+/// Read the delivery at `path`, a batch folder or a zip archive
+pub fn open(parser: &DDEXParser, path: &Path) -> Result<Delivery, ParseError> {
+    let source = Source::open(path)?;
+    let files = source.files()?;
+
+    let mut delivery = Delivery {
+        source: path.to_path_buf(),
+        batch_complete: None,
+        manifest: None,
+        acknowledgements: Vec::new(),
+        messages: Vec::new(),
+        unreferenced_files: Vec::new(),
+        issues: Vec::new(),
+    };
+    let mut accounted: HashSet<String> = HashSet::new();
+    let mut issues = Vec::new();
+
+    for file in files.iter().filter(|f| f.to_ascii_lowercase().ends_with(".xml")) {
+        if file_name(file).starts_with("BatchComplete") {
+            delivery.batch_complete = Some(file.clone());
+            accounted.insert(file.clone());
+            continue;
+        }
+
+        let bytes = source.read(file)?;
+        let result = encoding::to_utf8(&bytes).and_then(|xml| {
+            match manifest::root_element(&xml).as_deref() {
+                Some("ManifestMessage") => {
+                    delivery.manifest = Some(manifest::parse_manifest(file, &xml)?);
+                }
+                Some("FtpAcknowledgementMessage" | "AcknowledgementMessage") => {
+                    delivery.acknowledgements.push(manifest::parse_acknowledgement(file, &xml)?);
+                }
+                Some(root) if root.ends_with("ReleaseMessage") => {
+                    let parsed = parser.parse(std::io::Cursor::new(xml.as_bytes()))?;
+                    let mut resources = Vec::new();
+                    for reference in file_references(&xml)? {
+                        let (resource, issue) = locate(&source, &files, file, reference)?;
+                        accounted.extend(resource.path.clone());
+                        issues.extend(issue);
+                        resources.push(resource);
+                    }
+                    delivery.messages.push(DeliveryMessage { path: file.clone(), parsed, files: resources });
+                }
+                // Not a DDEX message: left for the unreferenced list
+                _ => return Ok(false),
+            }
+            Ok(true)
+        });
+
+        match result {
+            Ok(false) => {}
+            Ok(true) => {
+                accounted.insert(file.clone());
+            }
+            Err(e) => {
+                accounted.insert(file.clone());
+                issues.push(DeliveryIssue::InvalidMessage { path: file.clone(), message: e.to_string() });
+            }
+        }
+    }
+
+    if let Some(manifest) = &delivery.manifest {
+        for entry in &manifest.entries {
+            let Some(url) = &entry.url else { continue };
+            match find(&files, url.trim_start_matches('/')) {
+                Some(path) => {
+                    if let Some(hash_sum) = &entry.hash_sum {
+                        issues.extend(verify(&source, path, hash_sum)?.err());
+                    }
+                }
+                None => issues.push(DeliveryIssue::MissingMessage { url: url.clone() }),
+            }
+        }
+    }
+
+    delivery.issues = issues;
+    delivery.unreferenced_files = files.into_iter().filter(|f| !accounted.contains(f)).collect();
+    Ok(delivery)
+}
+
+/// Files of a delivery, addressed by `/`-separated paths relative to its root
+enum Source {
+    Folder(PathBuf),
+    #[allow(dead_code)] // only constructed with the `zip` feature
+    Archive(IndexMap<String, Vec<u8>>),
+}
+
+impl Source {
+    fn open(path: &Path) -> Result<Self, ParseError> {
+        if path.is_dir() {
+            return Ok(Source::Folder(path.to_path_buf()));
+        }
+
+        let mut file = std::fs::File::open(path)?;
+        let mut prefix = [0u8; 4];
+        let read = file.read(&mut prefix)?;
+        if Compression::detect(&prefix[..read]) != Compression::Zip {
+            return Err(DDEXError::Decompression {
+                format: "zip".to_string(),
+                message: format!("{} is neither a folder nor a zip archive", path.display()),
+            }
+            .into());
+        }
+
+        #[cfg(feature = "zip")]
+        {
+            let entries = ddex_core::compression::zip_entries(std::io::BufReader::new(std::fs::File::open(path)?))?
+                .collect::<Result<IndexMap<_, _>, _>>()?;
+            Ok(Source::Archive(entries))
+        }
+        #[cfg(not(feature = "zip"))]
+        Err(DDEXError::Decompression {
+            format: "zip".to_string(),
+            message: "support is not compiled in; enable the `zip` feature".to_string(),
+        }
+        .into())
+    }
+
+    /// Every file, sorted by path
+    fn files(&self) -> Result<Vec<String>, ParseError> {
+        let mut files = match self {
+            Source::Folder(root) => {
+                let mut files = Vec::new();
+                let mut pending = vec![root.clone()];
+                while let Some(dir) = pending.pop() {
+                    for entry in std::fs::read_dir(&dir)? {
+                        let path = entry?.path();
+                        if path.is_dir() {
+                            pending.push(path);
+                        } else if let Ok(relative) = path.strip_prefix(root) {
+                            let parts: Vec<_> = relative.components().map(|c| c.as_os_str().to_string_lossy()).collect();
+                            files.push(parts.join("/"));
+                        }
+                    }
+                }
+                files
+            }
+            Source::Archive(entries) => entries.keys().cloned().collect(),
+        };
+        files.sort();
+        Ok(files)
+    }
+
+    fn read(&self, path: &str) -> Result<Vec<u8>, ParseError> {
+        match self {
+            Source::Folder(root) => Ok(std::fs::read(root.join(path))?),
+            Source::Archive(entries) => Ok(entries.get(path).cloned().unwrap_or_default()),
+        }
+    }
+
+    /// Hex digest of a file, streamed from disk for folders
+    fn digest(&self, path: &str, mut hasher: Box<dyn DynDigest>) -> Result<String, ParseError> {
+        match self {
+            Source::Folder(root) => {
+                let mut file = std::fs::File::open(root.join(path))?;
+                let mut buffer = vec![0u8; 64 * 1024];
+                loop {
+                    match file.read(&mut buffer)? {
+                        0 => break,
+                        n => hasher.update(&buffer[..n]),
+                    }
+                }
+            }
+            Source::Archive(entries) => hasher.update(entries.get(path).map(Vec::as_slice).unwrap_or_default()),
+        }
+        Ok(hex::encode(hasher.finalize()))
+    }
+}
+
+/// Resolve a declared file against the delivery and check its checksum
+fn locate(
+    source: &Source,
+    files: &[String],
+    message: &str,
+    reference: FileReference,
+) -> Result<(ResourceFile, Option<DeliveryIssue>), ParseError> {
+    let location = reference.location.replace('\\', "/");
+    let location = location.strip_prefix("file://").unwrap_or(&location);
+    if location.contains("://") {
+        return Ok((ResourceFile { reference, path: None, status: FileStatus::Remote }, None));
+    }
+
+    let base = message.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+    let path = normalize(base, location);
+    let (status, issue) = if !files.contains(&path) {
+        let issue = DeliveryIssue::MissingFile {
+            message: message.to_string(),
+            resource_reference: reference.resource_reference.clone(),
+            path: path.clone(),
+        };
+        (FileStatus::Missing, Some(issue))
+    } else {
+        match &reference.hash_sum {
+            Some(hash_sum) => match verify(source, &path, hash_sum)? {
+                Ok(()) => (FileStatus::Verified, None),
+                Err(issue @ DeliveryIssue::ChecksumMismatch { .. }) => (FileStatus::ChecksumMismatch, Some(issue)),
+                Err(issue) => (FileStatus::Present, Some(issue)),
+            },
+            None => (FileStatus::Present, None),
+        }
+    };
+    Ok((ResourceFile { reference, path: Some(path), status }, issue))
+}
+
+/// Compare a file's digest with its declared checksum
+fn verify(source: &Source, path: &str, hash_sum: &HashSum) -> Result<Result<(), DeliveryIssue>, ParseError> {
+    let Some(hasher) = hasher(&hash_sum.algorithm) else {
+        return Ok(Err(DeliveryIssue::UnsupportedHashAlgorithm {
+            path: path.to_string(),
+            algorithm: hash_sum.algorithm.clone(),
+        }));
+    };
+    let actual = source.digest(path, hasher)?;
+    if actual.eq_ignore_ascii_case(hash_sum.value.trim()) {
+        Ok(Ok(()))
+    } else {
+        Ok(Err(DeliveryIssue::ChecksumMismatch {
+            path: path.to_string(),
+            algorithm: hash_sum.algorithm.clone(),
+            expected: hash_sum.value.clone(),
+            actual,
+        }))
+    }
+}
+
+/// Hasher for a `HashSumAlgorithmType` value
+fn hasher(algorithm: &str) -> Option<Box<dyn DynDigest>> {
+    match algorithm.to_ascii_uppercase().replace(['-', '_'], "").as_str() {
+        "MD5" => Some(Box::new(md5::Md5::default())),
+        "SHA1" => Some(Box::new(sha1::Sha1::default())),
+        "SHA2" | "SHA256" => Some(Box::new(sha2::Sha256::default())),
+        "SHA224" => Some(Box::new(sha2::Sha224::default())),
+        "SHA384" => Some(Box::new(sha2::Sha384::default())),
+        "SHA512" => Some(Box::new(sha2::Sha512::default())),
+        _ => None,
+    }
+}
+
+/// Join `location` onto `base`, resolving `.` and `..` segments
+fn normalize(base: &str, location: &str) -> String {
+    let mut parts: Vec<&str> = if location.starts_with('/') {
+        Vec::new()
+    } else {
+        base.split('/').filter(|p| !p.is_empty()).collect()
+    };
+    for part in location.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+/// The delivery file a manifest URL names; URLs may carry the batch folder
+/// or a longer prefix that the delivery root does not
+fn find<'a>(files: &'a [String], url: &str) -> Option<&'a String> {
+    files.iter().find(|f| {
+        f.as_str() == url || url.ends_with(&format!("/{}", f)) || f.ends_with(&format!("/{}", url))
+    })
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ERN: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/382">
+  <MessageHeader><MessageId>MSG1</MessageId></MessageHeader>
+  <ResourceList>
+    <SoundRecording>
+      <ResourceReference>A1</ResourceReference>
+      <TechnicalSoundRecordingDetails>
+        <File>
+          <FileName>A1.flac</FileName>
+          <FilePath>resources/</FilePath>
+          <HashSum><HashSum>0cc175b9c0f1b6a831c399e269772661</HashSum><HashSumAlgorithmType>MD5</HashSumAlgorithmType></HashSum>
+        </File>
+      </TechnicalSoundRecordingDetails>
+    </SoundRecording>
+    <Image>
+      <ResourceReference>A2</ResourceReference>
+      <TechnicalImageDetails>
+        <File><FileName>cover.jpg</FileName><FilePath>resources</FilePath></File>
+      </TechnicalImageDetails>
+    </Image>
+  </ResourceList>
+</ern:NewReleaseMessage>"#;
+
+    const MANIFEST: &str = r#"<echo:ManifestMessage xmlns:echo="http://ddex.net/xml/ech/12">
+  <MessageHeader><MessageId>BATCH1</MessageId></MessageHeader>
+  <NumberOfMessages>2</NumberOfMessages>
+  <MessageInBatch>
+    <MessageType>NewReleaseMessage</MessageType>
+    <URL>/20240101000000000/R1/R1.xml</URL>
+    <IncludedReleaseId><ICPN>0123456789012</ICPN></IncludedReleaseId>
+  </MessageInBatch>
+  <MessageInBatch>
+    <MessageType>NewReleaseMessage</MessageType>
+    <URL>/20240101000000000/R2/R2.xml</URL>
+  </MessageInBatch>
+</echo:ManifestMessage>"#;
+
+    #[test]
+    fn test_file_references_cover_ern3_and_ern4_shapes() {
+        let references = file_references(ERN).unwrap();
+        assert_eq!(references.len(), 2);
+        assert_eq!(references[0].resource_reference, "A1");
+        assert_eq!(references[0].location, "resources/A1.flac");
+        assert_eq!(references[0].hash_sum.as_ref().unwrap().algorithm, "MD5");
+        assert_eq!(references[1].location, "resources/cover.jpg");
+
+        let ern4 = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43"><ResourceList>
+  <SoundRecording><ResourceReference>A1</ResourceReference><TechnicalDetails><DeliveryFile><File>
+    <URI>resources/A1.wav</URI><HashSum><Algorithm>SHA256</Algorithm><HashSumValue>ab</HashSumValue></HashSum>
+    <FileSize>42</FileSize>
+  </File></DeliveryFile></TechnicalDetails></SoundRecording>
+</ResourceList></ern:NewReleaseMessage>"#;
+        let references = file_references(ern4).unwrap();
+        assert_eq!(references[0].location, "resources/A1.wav");
+        assert_eq!(references[0].file_size, Some(42));
+        assert_eq!(references[0].hash_sum, Some(HashSum { algorithm: "SHA256".into(), value: "ab".into() }));
+    }
+
+    #[test]
+    fn test_open_folder_delivery() {
+        let dir = tempfile::tempdir().unwrap();
+        let batch = dir.path();
+        std::fs::create_dir_all(batch.join("R1/resources")).unwrap();
+        std::fs::write(batch.join("R1/R1.xml"), ERN).unwrap();
+        std::fs::write(batch.join("R1/resources/A1.flac"), "a").unwrap();
+        std::fs::write(batch.join("R1/notes.txt"), "hello").unwrap();
+        std::fs::write(batch.join("manifest.xml"), MANIFEST).unwrap();
+        std::fs::write(batch.join("BatchComplete_20240101000000000.xml"), "").unwrap();
+
+        let delivery = DDEXParser::new().parse_delivery(batch).unwrap();
+        assert_eq!(delivery.batch_complete.as_deref(), Some("BatchComplete_20240101000000000.xml"));
+        assert_eq!(delivery.manifest.as_ref().unwrap().entries[0].release_ids, vec!["0123456789012"]);
+        assert_eq!(delivery.messages.len(), 1);
+
+        let files = &delivery.messages[0].files;
+        assert_eq!(files[0].path.as_deref(), Some("R1/resources/A1.flac"));
+        assert_eq!(files[0].status, FileStatus::Verified);
+        assert_eq!(files[1].status, FileStatus::Missing);
+        assert_eq!(delivery.unreferenced_files, vec!["R1/notes.txt"]);
+
+        assert!(!delivery.is_valid());
+        assert!(delivery.issues.contains(&DeliveryIssue::MissingFile {
+            message: "R1/R1.xml".into(),
+            resource_reference: "A2".into(),
+            path: "R1/resources/cover.jpg".into(),
+        }));
+        assert!(delivery.issues.contains(&DeliveryIssue::MissingMessage { url: "/20240101000000000/R2/R2.xml".into() }));
+
+        // A corrupted resource no longer matches its declared MD5
+        std::fs::write(batch.join("R1/resources/A1.flac"), "b").unwrap();
+        let delivery = DDEXParser::new().parse_delivery(batch).unwrap();
+        assert_eq!(delivery.messages[0].files[0].status, FileStatus::ChecksumMismatch);
+        assert!(delivery.issues.iter().any(|i| matches!(i, DeliveryIssue::ChecksumMismatch { .. })));
+    }
+
+    #[cfg(feature = "zip")]
+    #[test]
+    fn test_open_zip_delivery() {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("delivery.zip");
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+        let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        writer.start_file("R1/R1.xml", options).unwrap();
+        writer.write_all(ERN.as_bytes()).unwrap();
+        writer.start_file("R1/resources/A1.flac", options).unwrap();
+        writer.write_all(b"a").unwrap();
+        writer.finish().unwrap();
+
+        let delivery = DDEXParser::new().parse_delivery(&path).unwrap();
+        assert_eq!(delivery.messages[0].files[0].status, FileStatus::Verified);
+        assert!(delivery.batch_complete.is_none());
+    }
+}
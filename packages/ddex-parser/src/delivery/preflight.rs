@@ -0,0 +1,231 @@
+// core/src/delivery/preflight.rs
+//! Checks of the resource files a message declares against a local folder
+//!
+//! Every file named by a resource's technical details must exist under the
+//! base path, have the declared `FileSize` and `HashSum`, and carry an
+//! extension that fits its codec (or, without a codec, its kind of resource).
+//! Remote URIs are skipped.
+
+use super::files::{file_references, FileReference};
+use super::{hasher, normalize};
+use crate::error::ParseError;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+
+/// Problems found for the files of each resource, keyed by resource reference
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResourceFileReport {
+    /// Every resource declaring a file, in document order; clean resources
+    /// have no errors
+    pub resources: IndexMap<String, Vec<ResourceFileError>>,
+}
+
+impl ResourceFileReport {
+    pub fn is_valid(&self) -> bool {
+        self.resources.values().all(Vec::is_empty)
+    }
+
+    /// All errors with the resource they belong to
+    pub fn errors(&self) -> impl Iterator<Item = (&str, &ResourceFileError)> {
+        self.resources
+            .iter()
+            .flat_map(|(reference, errors)| errors.iter().map(move |e| (reference.as_str(), e)))
+    }
+}
+
+/// A problem with one declared file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResourceFileError {
+    Missing { location: String },
+    SizeMismatch { location: String, declared: u64, actual: u64 },
+    ChecksumMismatch { location: String, algorithm: String, expected: String, actual: String },
+    UnsupportedHashAlgorithm { location: String, algorithm: String },
+    /// The extension does not fit the declared codec or kind of resource
+    FormatMismatch { location: String, extension: String, expected: String },
+}
+
+impl fmt::Display for ResourceFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResourceFileError::Missing { location } => write!(f, "{}: file not found", location),
+            ResourceFileError::SizeMismatch { location, declared, actual } => {
+                write!(f, "{}: FileSize is {} but the file has {} bytes", location, declared, actual)
+            }
+            ResourceFileError::ChecksumMismatch { location, algorithm, expected, actual } => {
+                write!(f, "{}: {} is {} but {} was declared", location, algorithm, actual, expected)
+            }
+            ResourceFileError::UnsupportedHashAlgorithm { location, algorithm } => {
+                write!(f, "{}: cannot verify {} hash sums", location, algorithm)
+            }
+            ResourceFileError::FormatMismatch { location, extension, expected } => {
+                write!(f, "{}: .{} file where {} was declared", location, extension, expected)
+            }
+        }
+    }
+}
+
+/// Check the files declared in `xml` against the folder at `base`
+pub fn check_resource_files(xml: &str, base: &Path) -> Result<ResourceFileReport, ParseError> {
+    let mut report = ResourceFileReport::default();
+    for reference in file_references(xml)? {
+        let errors = report.resources.entry(reference.resource_reference.clone()).or_default();
+        check_file(&reference, base, errors)?;
+    }
+    Ok(report)
+}
+
+fn check_file(reference: &FileReference, base: &Path, errors: &mut Vec<ResourceFileError>) -> Result<(), ParseError> {
+    let location = reference.location.replace('\\', "/");
+    let location = location.strip_prefix("file://").unwrap_or(&location);
+    if location.contains("://") {
+        return Ok(());
+    }
+    let path = base.join(normalize("", location));
+    let location = reference.location.clone();
+
+    if let Some(problem) = format_problem(reference) {
+        errors.push(problem);
+    }
+
+    let metadata = match std::fs::metadata(&path) {
+        Ok(metadata) if metadata.is_file() => metadata,
+        _ => {
+            errors.push(ResourceFileError::Missing { location });
+            return Ok(());
+        }
+    };
+    if let Some(declared) = reference.file_size.filter(|size| *size != metadata.len()) {
+        errors.push(ResourceFileError::SizeMismatch { location: location.clone(), declared, actual: metadata.len() });
+    }
+
+    let Some(hash_sum) = &reference.hash_sum else { return Ok(()) };
+    let Some(mut hasher) = hasher(&hash_sum.algorithm) else {
+        errors.push(ResourceFileError::UnsupportedHashAlgorithm { location, algorithm: hash_sum.algorithm.clone() });
+        return Ok(());
+    };
+    let mut file = std::fs::File::open(&path)?;
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        match file.read(&mut buffer)? {
+            0 => break,
+            n => hasher.update(&buffer[..n]),
+        }
+    }
+    let actual = hex::encode(hasher.finalize());
+    if !actual.eq_ignore_ascii_case(hash_sum.value.trim()) {
+        errors.push(ResourceFileError::ChecksumMismatch {
+            location,
+            algorithm: hash_sum.algorithm.clone(),
+            expected: hash_sum.value.clone(),
+            actual,
+        });
+    }
+    Ok(())
+}
+
+/// Extensions that fit a codec, or `None` for codecs without a fixed container
+fn codec_extensions(codec: &str) -> Option<&'static [&'static str]> {
+    Some(match codec.to_ascii_uppercase().replace(['-', '_', '.', ' '], "").as_str() {
+        "MP3" => &["mp3"],
+        "AAC" | "ALAC" => &["m4a", "aac", "mp4"],
+        "FLAC" => &["flac"],
+        "WAV" | "PCM" => &["wav"],
+        "AIFF" => &["aif", "aiff"],
+        "OGG" | "VORBIS" => &["ogg", "oga"],
+        "OPUS" => &["opus", "ogg"],
+        "WMA" => &["wma"],
+        "JPEG" | "JPG" => &["jpg", "jpeg"],
+        "PNG" => &["png"],
+        "GIF" => &["gif"],
+        "TIFF" => &["tif", "tiff"],
+        "BMP" => &["bmp"],
+        "PDF" => &["pdf"],
+        "H264" | "MPEG4" | "MP4" => &["mp4", "m4v", "mov"],
+        "PRORES" => &["mov"],
+        _ => return None,
+    })
+}
+
+/// Extensions that fit a kind of resource when no codec is declared
+fn kind_extensions(kind: &str) -> Option<&'static [&'static str]> {
+    Some(match kind {
+        "SoundRecording" => &["wav", "flac", "mp3", "m4a", "aac", "mp4", "aif", "aiff", "ogg", "oga", "opus", "wma"],
+        "Image" => &["jpg", "jpeg", "png", "gif", "tif", "tiff", "bmp"],
+        "Video" => &["mp4", "m4v", "mov", "mpg", "mpeg", "avi", "mxf"],
+        _ => return None,
+    })
+}
+
+fn format_problem(reference: &FileReference) -> Option<ResourceFileError> {
+    let name = reference.location.rsplit(['/', '\\']).next().unwrap_or(&reference.location);
+    let extension = name.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase())?;
+
+    let (allowed, expected) = match reference.codec.as_deref().and_then(|c| codec_extensions(c).map(|e| (e, c))) {
+        Some((allowed, codec)) => (allowed, format!("codec {}", codec)),
+        None => (kind_extensions(&reference.resource_kind)?, format!("a {}", reference.resource_kind)),
+    };
+    (!allowed.contains(&extension.as_str())).then(|| ResourceFileError::FormatMismatch {
+        location: reference.location.clone(),
+        extension,
+        expected,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_resource_files_groups_errors_by_resource() {
+        let xml = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43"><ResourceList>
+  <SoundRecording>
+    <ResourceReference>A1</ResourceReference>
+    <TechnicalDetails><DeliveryFile>
+      <AudioCodecType>FLAC</AudioCodecType>
+      <File><URI>resources/A1.flac</URI><FileSize>1</FileSize>
+        <HashSum><Algorithm>MD5</Algorithm><HashSumValue>0cc175b9c0f1b6a831c399e269772661</HashSumValue></HashSum>
+      </File>
+    </DeliveryFile></TechnicalDetails>
+  </SoundRecording>
+  <SoundRecording>
+    <ResourceReference>A2</ResourceReference>
+    <TechnicalDetails><DeliveryFile>
+      <AudioCodecType>MP3</AudioCodecType>
+      <File><URI>resources/A2.wav</URI><FileSize>10</FileSize>
+        <HashSum><Algorithm>SHA256</Algorithm><HashSumValue>00</HashSumValue></HashSum>
+      </File>
+    </DeliveryFile></TechnicalDetails>
+  </SoundRecording>
+  <Image>
+    <ResourceReference>A3</ResourceReference>
+    <TechnicalDetails><DeliveryFile><File><URI>resources/cover.png</URI></File></DeliveryFile></TechnicalDetails>
+  </Image>
+  <Image>
+    <ResourceReference>A4</ResourceReference>
+    <TechnicalDetails><DeliveryFile><File><URI>https://cdn.example.com/cover.jpg</URI></File></DeliveryFile></TechnicalDetails>
+  </Image>
+</ResourceList></ern:NewReleaseMessage>"#;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("resources")).unwrap();
+        std::fs::write(dir.path().join("resources/A1.flac"), "a").unwrap();
+        std::fs::write(dir.path().join("resources/A2.wav"), "b").unwrap();
+
+        let report = check_resource_files(xml, dir.path()).unwrap();
+        assert!(!report.is_valid());
+        assert!(report.resources["A1"].is_empty());
+        assert!(report.resources["A4"].is_empty());
+
+        let a2 = &report.resources["A2"];
+        assert_eq!(a2.len(), 3);
+        assert!(matches!(&a2[0], ResourceFileError::FormatMismatch { extension, .. } if extension == "wav"));
+        assert!(matches!(a2[1], ResourceFileError::SizeMismatch { declared: 10, actual: 1, .. }));
+        assert!(matches!(a2[2], ResourceFileError::ChecksumMismatch { .. }));
+
+        assert_eq!(report.resources["A3"], vec![ResourceFileError::Missing { location: "resources/cover.png".into() }]);
+        assert_eq!(report.errors().count(), 4);
+    }
+}
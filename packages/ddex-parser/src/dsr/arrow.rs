@@ -0,0 +1,118 @@
+// core/src/dsr/arrow.rs
+//! Arrow record batches of DSR sales rows
+
+use super::{DsrSalesIter, DsrSalesRow};
+use crate::error::ParseError;
+use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray, UInt64Array};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef};
+use std::io::BufRead;
+use std::sync::Arc;
+
+/// Schema of the record batches built from [`DsrSalesRow`]s
+pub fn sales_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("block_id", DataType::Utf8, false),
+        Field::new("release_title", DataType::Utf8, true),
+        Field::new("icpn", DataType::Utf8, true),
+        Field::new("isrc", DataType::Utf8, true),
+        Field::new("resource_title", DataType::Utf8, true),
+        Field::new("display_artist", DataType::Utf8, true),
+        Field::new("use_type", DataType::Utf8, true),
+        Field::new("commercial_model_type", DataType::Utf8, true),
+        Field::new("territory_code", DataType::Utf8, true),
+        Field::new("number_of_usages", DataType::UInt64, false),
+        Field::new("currency", DataType::Utf8, true),
+        Field::new("revenue", DataType::Float64, true),
+    ]))
+}
+
+/// Build a record batch from sales rows
+pub fn to_record_batch(rows: &[DsrSalesRow]) -> Result<RecordBatch, ArrowError> {
+    fn strings(rows: &[DsrSalesRow], value: impl Fn(&DsrSalesRow) -> Option<&str>) -> ArrayRef {
+        Arc::new(rows.iter().map(value).collect::<StringArray>())
+    }
+
+    let columns: Vec<ArrayRef> = vec![
+        strings(rows, |r| Some(r.block_id.as_str())),
+        strings(rows, |r| r.release_title.as_deref()),
+        strings(rows, |r| r.icpn.as_deref()),
+        strings(rows, |r| r.isrc.as_deref()),
+        strings(rows, |r| r.resource_title.as_deref()),
+        strings(rows, |r| r.display_artist.as_deref()),
+        strings(rows, |r| r.use_type.as_deref()),
+        strings(rows, |r| r.commercial_model_type.as_deref()),
+        strings(rows, |r| r.territory_code.as_deref()),
+        Arc::new(rows.iter().map(|r| r.number_of_usages).collect::<UInt64Array>()),
+        strings(rows, |r| r.currency.as_deref()),
+        Arc::new(rows.iter().map(|r| r.revenue).collect::<Float64Array>()),
+    ];
+
+    RecordBatch::try_new(sales_schema(), columns)
+}
+
+/// Iterator turning a DSR report into record batches of at most `batch_size` rows
+pub struct DsrBatchIter<R: BufRead> {
+    rows: DsrSalesIter<R>,
+    batch_size: usize,
+}
+
+impl<R: BufRead> DsrSalesIter<R> {
+    /// Stream the report as Arrow record batches
+    pub fn record_batches(self, batch_size: usize) -> DsrBatchIter<R> {
+        DsrBatchIter {
+            rows: self,
+            batch_size: batch_size.max(1),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for DsrBatchIter<R> {
+    type Item = Result<RecordBatch, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut rows = Vec::with_capacity(self.batch_size);
+        for row in self.rows.by_ref() {
+            match row {
+                Ok(row) => rows.push(row),
+                Err(e) => return Some(Err(e)),
+            }
+            if rows.len() == self.batch_size {
+                break;
+            }
+        }
+
+        if rows.is_empty() {
+            return None;
+        }
+        Some(to_record_batch(&rows).map_err(|e| ParseError::InvalidDsr {
+            line: self.rows.line(),
+            message: e.to_string(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::Array;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_record_batches_respect_batch_size() {
+        let report = "AS01\tB1\tA1\tDSP-A1\tUSRC17607839\tTrack\n\
+SU01\tB1\tT1\tS1\tA1\tOnDemandStream\tSubscriptionModel\tGB\t10\tGBP\t0.03\n\
+SU01\tB1\tT2\tS1\tA1\tOnDemandStream\tSubscriptionModel\tFR\t20\tEUR\n\
+SU01\tB1\tT3\tS1\tA1\tOnDemandStream\tSubscriptionModel\tDE\t30\tEUR\t0.09\n";
+
+        let batches = DsrSalesIter::new(Cursor::new(report))
+            .record_batches(2)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(batches.iter().map(RecordBatch::num_rows).collect::<Vec<_>>(), vec![2, 1]);
+        let revenue = batches[0].column(11).as_any().downcast_ref::<Float64Array>().unwrap();
+        assert!(revenue.is_null(1));
+        let isrc = batches[1].column(3).as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(isrc.value(0), "USRC17607839");
+    }
+}
@@ -0,0 +1,397 @@
+// core/src/dsr/mod.rs
+//! DSR (Digital Sales Reporting) flat-file parsing
+//!
+//! DSR reports are tab-separated flat files with one record per line, the
+//! first cell naming the record type (`HEAD`, `RE01`, `AS01`, `SU01`,
+//! `FOOT`, ...). Records are grouped into blocks by `BlockId`: a release, its
+//! resources and the sales lines reported against them. The reader works one
+//! line at a time and only keeps the current block in memory, so multi-GB
+//! reports can be processed as a stream of flattened sales rows.
+//!
+//! Column names come from the `#`-prefixed descriptor rows when the report
+//! carries them, falling back to the Basic Audio profile layout otherwise.
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+
+use crate::error::ParseError;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::io::BufRead;
+
+const HEAD_COLUMNS: &[&str] = &[
+    "MessageVersion", "Profile", "ProfileVersion", "MessageId", "MessageCreatedDateTime",
+    "FileNumber", "NumberOfFiles", "UsageStartDate", "UsageEndDate", "SenderPartyId",
+    "SenderName", "ServiceDescription", "RecipientPartyId", "RecipientName",
+];
+
+const RELEASE_COLUMNS: &[&str] = &[
+    "BlockId", "ReleaseReference", "DspReleaseId", "ProprietaryReleaseId", "CatalogNumber",
+    "ICPN", "DisplayArtistName", "Title", "SubTitle", "ReleaseType",
+];
+
+const RESOURCE_COLUMNS: &[&str] = &[
+    "BlockId", "ResourceReference", "DspResourceId", "ISRC", "Title", "SubTitle",
+    "DisplayArtistName", "DisplayArtistPartyId", "Duration", "ResourceType",
+];
+
+const SALES_COLUMNS: &[&str] = &[
+    "BlockId", "SalesTransactionId", "SummaryRecordId", "ResourceReference", "UseType",
+    "CommercialModelType", "TerritoryCode", "NumberOfUsages", "Currency", "Revenue",
+];
+
+const FOOT_COLUMNS: &[&str] = &[
+    "NumberOfLinesInFile", "NumberOfLinesInReport", "NumberOfSummaryRecords",
+    "NumberOfBlocksInFile", "NumberOfBlocksInReport",
+];
+
+/// A single line of a DSR report with its cells keyed by column name
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DsrRecord {
+    pub record_type: String,
+    /// 1-based line number in the report
+    pub line: usize,
+    pub fields: IndexMap<String, String>,
+}
+
+impl DsrRecord {
+    /// Value of a column, treating empty cells as absent
+    pub fn get(&self, column: &str) -> Option<&str> {
+        self.fields.get(column).map(String::as_str).filter(|v| !v.is_empty())
+    }
+
+    fn get_owned(&self, column: &str) -> Option<String> {
+        self.get(column).map(str::to_string)
+    }
+}
+
+/// Report-level information from the `HEAD` record
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DsrHeader {
+    pub message_id: Option<String>,
+    pub message_version: Option<String>,
+    pub profile: Option<String>,
+    pub message_created_date_time: Option<String>,
+    pub usage_start_date: Option<String>,
+    pub usage_end_date: Option<String>,
+    pub sender_party_id: Option<String>,
+    pub sender_name: Option<String>,
+    pub recipient_party_id: Option<String>,
+    pub recipient_name: Option<String>,
+    pub service_description: Option<String>,
+}
+
+impl From<&DsrRecord> for DsrHeader {
+    fn from(record: &DsrRecord) -> Self {
+        Self {
+            message_id: record.get_owned("MessageId"),
+            message_version: record.get_owned("MessageVersion"),
+            profile: record.get_owned("Profile"),
+            message_created_date_time: record.get_owned("MessageCreatedDateTime"),
+            usage_start_date: record.get_owned("UsageStartDate"),
+            usage_end_date: record.get_owned("UsageEndDate"),
+            sender_party_id: record.get_owned("SenderPartyId"),
+            sender_name: record.get_owned("SenderName"),
+            recipient_party_id: record.get_owned("RecipientPartyId"),
+            recipient_name: record.get_owned("RecipientName"),
+            service_description: record.get_owned("ServiceDescription"),
+        }
+    }
+}
+
+/// A sales line joined with the release and resource it reports on
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DsrSalesRow {
+    pub block_id: String,
+    pub release_title: Option<String>,
+    pub icpn: Option<String>,
+    pub isrc: Option<String>,
+    pub resource_title: Option<String>,
+    pub display_artist: Option<String>,
+    pub use_type: Option<String>,
+    pub commercial_model_type: Option<String>,
+    pub territory_code: Option<String>,
+    pub number_of_usages: u64,
+    pub currency: Option<String>,
+    pub revenue: Option<f64>,
+}
+
+/// A fully parsed DSR report
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DsrReport {
+    pub header: Option<DsrHeader>,
+    pub sales: Vec<DsrSalesRow>,
+    pub footer: Option<DsrRecord>,
+}
+
+/// Parse a whole DSR report into memory
+pub fn parse_dsr<R: BufRead>(reader: R) -> Result<DsrReport, ParseError> {
+    let mut rows = DsrSalesIter::new(reader);
+    let sales = rows.by_ref().collect::<Result<Vec<_>, _>>()?;
+
+    Ok(DsrReport {
+        header: rows.header().cloned(),
+        sales,
+        footer: rows.footer().cloned(),
+    })
+}
+
+/// Streaming reader yielding each record of a DSR report
+pub struct DsrReader<R: BufRead> {
+    reader: R,
+    layouts: IndexMap<String, Vec<String>>,
+    line: usize,
+    buffer: String,
+}
+
+impl<R: BufRead> DsrReader<R> {
+    /// Create a reader using the Basic Audio profile layouts
+    pub fn new(reader: R) -> Self {
+        let mut layouts = IndexMap::new();
+        for (record_type, columns) in [
+            ("HEAD", HEAD_COLUMNS),
+            ("RE01", RELEASE_COLUMNS),
+            ("AS01", RESOURCE_COLUMNS),
+            ("AS02", RESOURCE_COLUMNS),
+            ("SU01", SALES_COLUMNS),
+            ("SU02", SALES_COLUMNS),
+            ("FOOT", FOOT_COLUMNS),
+        ] {
+            layouts.insert(record_type.to_string(), columns.iter().map(|c| c.to_string()).collect());
+        }
+
+        Self {
+            reader,
+            layouts,
+            line: 0,
+            buffer: String::new(),
+        }
+    }
+
+    /// Line number of the last line read
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    fn record(&self, line: &str) -> DsrRecord {
+        let mut cells = line.split('\t');
+        let record_type = cells.next().unwrap_or_default().to_string();
+        let layout = self.layouts.get(&record_type);
+
+        let fields = cells.enumerate().map(|(i, value)| {
+            let column = layout
+                .and_then(|columns| columns.get(i).cloned())
+                .unwrap_or_else(|| format!("Field{}", i + 1));
+            (column, value.to_string())
+        }).collect();
+
+        DsrRecord {
+            record_type,
+            line: self.line,
+            fields,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for DsrReader<R> {
+    type Item = Result<DsrRecord, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.buffer.clear();
+            match self.reader.read_line(&mut self.buffer) {
+                Ok(0) => return None,
+                Ok(_) => self.line += 1,
+                Err(e) => return Some(Err(e.into())),
+            }
+
+            let line = self.buffer.trim_end_matches(['\r', '\n']);
+            if line.is_empty() {
+                continue;
+            }
+
+            // Descriptor rows name the columns of a record type
+            if let Some(descriptor) = line.strip_prefix('#') {
+                let mut cells = descriptor.split('\t').map(str::to_string);
+                if let Some(record_type) = cells.next() {
+                    self.layouts.insert(record_type, cells.collect());
+                }
+                continue;
+            }
+
+            return Some(Ok(self.record(line)));
+        }
+    }
+}
+
+/// Streaming iterator yielding flattened sales rows from a DSR report
+pub struct DsrSalesIter<R: BufRead> {
+    records: DsrReader<R>,
+    header: Option<DsrHeader>,
+    footer: Option<DsrRecord>,
+    block_id: Option<String>,
+    release: Option<DsrRecord>,
+    resources: Vec<DsrRecord>,
+}
+
+impl<R: BufRead> DsrSalesIter<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            records: DsrReader::new(reader),
+            header: None,
+            footer: None,
+            block_id: None,
+            release: None,
+            resources: Vec::new(),
+        }
+    }
+
+    /// The report header, once the `HEAD` record has been read
+    pub fn header(&self) -> Option<&DsrHeader> {
+        self.header.as_ref()
+    }
+
+    /// The `FOOT` record, once the report has been read to the end
+    pub fn footer(&self) -> Option<&DsrRecord> {
+        self.footer.as_ref()
+    }
+
+    /// Line number of the last line read
+    pub fn line(&self) -> usize {
+        self.records.line()
+    }
+
+    /// Start a new block when the record's BlockId changes
+    fn enter_block(&mut self, record: &DsrRecord) {
+        let block_id = record.get("BlockId");
+        if block_id != self.block_id.as_deref() {
+            self.block_id = block_id.map(str::to_string);
+            self.release = None;
+            self.resources.clear();
+        }
+    }
+
+    fn sales_row(&self, record: &DsrRecord) -> Result<DsrSalesRow, ParseError> {
+        let resource = match record.get("ResourceReference") {
+            Some(reference) => self.resources.iter().find(|r| r.get("ResourceReference") == Some(reference)),
+            None => self.resources.first(),
+        };
+
+        let number_of_usages = match record.get("NumberOfUsages") {
+            Some(value) => value.parse().map_err(|_| ParseError::InvalidDsr {
+                line: record.line,
+                message: format!("NumberOfUsages is not a whole number: {}", value),
+            })?,
+            None => 0,
+        };
+        let revenue = record.get("Revenue")
+            .map(|value| value.parse().map_err(|_| ParseError::InvalidDsr {
+                line: record.line,
+                message: format!("Revenue is not a number: {}", value),
+            }))
+            .transpose()?;
+
+        Ok(DsrSalesRow {
+            block_id: record.get_owned("BlockId").unwrap_or_default(),
+            release_title: self.release.as_ref().and_then(|r| r.get_owned("Title")),
+            icpn: self.release.as_ref().and_then(|r| r.get_owned("ICPN")),
+            isrc: resource.and_then(|r| r.get_owned("ISRC")),
+            resource_title: resource.and_then(|r| r.get_owned("Title")),
+            display_artist: resource.or(self.release.as_ref()).and_then(|r| r.get_owned("DisplayArtistName")),
+            use_type: record.get_owned("UseType"),
+            commercial_model_type: record.get_owned("CommercialModelType"),
+            territory_code: record.get_owned("TerritoryCode"),
+            number_of_usages,
+            currency: record.get_owned("Currency"),
+            revenue,
+        })
+    }
+}
+
+impl<R: BufRead> Iterator for DsrSalesIter<R> {
+    type Item = Result<DsrSalesRow, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let record = match self.records.next()? {
+                Ok(record) => record,
+                Err(e) => return Some(Err(e)),
+            };
+
+            match record.record_type.as_str() {
+                "HEAD" => self.header = Some(DsrHeader::from(&record)),
+                "FOOT" => self.footer = Some(record),
+                t if t.starts_with("RE") => {
+                    self.enter_block(&record);
+                    self.release = Some(record);
+                }
+                t if t.starts_with("AS") => {
+                    self.enter_block(&record);
+                    self.resources.push(record);
+                }
+                t if t.starts_with("SU") => {
+                    self.enter_block(&record);
+                    return Some(self.sales_row(&record));
+                }
+                // Summary (SY) and unknown record types carry nothing per-row
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const REPORT: &str = "HEAD\tDSR-3.0\tBasicAudioProfile\t1.0\tRPT-1\t2024-02-01T00:00:00Z\t1\t1\t2024-01-01\t2024-01-31\tPADPIDA0000000001\tExample DSP\tStreaming\tPADPIDA0000000002\tExample Label\n\
+RE01\tB1\tR1\tDSP-R1\t\tCAT1\t0123456789012\tSome Artist\tSome Album\t\tAlbum\n\
+AS01\tB1\tA1\tDSP-A1\tUSRC17607839\tFirst Track\t\tSome Artist\t\tPT3M\tSoundRecording\n\
+AS01\tB1\tA2\tDSP-A2\tUSRC17607840\tSecond Track\t\tSome Artist\t\tPT4M\tSoundRecording\n\
+SU01\tB1\tT1\tS1\tA2\tOnDemandStream\tSubscriptionModel\tGB\t1200\tGBP\t3.60\n\
+SU01\tB1\tT2\tS1\tA1\tOnDemandStream\tAdvertisementSupportedModel\tUS\t50\tUSD\t0.05\n\
+FOOT\t8\t8\t0\t1\t1\n";
+
+    #[test]
+    fn test_sales_rows_join_block_records() {
+        let report = parse_dsr(Cursor::new(REPORT)).unwrap();
+
+        let header = report.header.unwrap();
+        assert_eq!(header.message_id.as_deref(), Some("RPT-1"));
+        assert_eq!(header.sender_name.as_deref(), Some("Example DSP"));
+
+        assert_eq!(report.sales.len(), 2);
+        let row = &report.sales[0];
+        assert_eq!(row.icpn.as_deref(), Some("0123456789012"));
+        assert_eq!(row.isrc.as_deref(), Some("USRC17607840"));
+        assert_eq!(row.resource_title.as_deref(), Some("Second Track"));
+        assert_eq!(row.territory_code.as_deref(), Some("GB"));
+        assert_eq!(row.number_of_usages, 1200);
+        assert_eq!(row.revenue, Some(3.60));
+        assert_eq!(report.sales[1].isrc.as_deref(), Some("USRC17607839"));
+
+        assert_eq!(report.footer.unwrap().get("NumberOfBlocksInFile"), Some("1"));
+    }
+
+    #[test]
+    fn test_descriptor_rows_override_layout() {
+        let report = "#SU01\tBlockId\tTerritoryCode\tNumberOfUsages\tExtraColumn\n\
+SU01\tB9\tFR\t7\tignored\n";
+        let rows = parse_dsr(Cursor::new(report)).unwrap().sales;
+
+        assert_eq!(rows[0].block_id, "B9");
+        assert_eq!(rows[0].territory_code.as_deref(), Some("FR"));
+        assert_eq!(rows[0].number_of_usages, 7);
+        assert_eq!(rows[0].isrc, None);
+    }
+
+    #[test]
+    fn test_invalid_usage_count_reports_line() {
+        let report = "HEAD\tDSR-3.0\nSU01\tB1\tT1\tS1\tA1\tStream\tSubscriptionModel\tGB\tmany\n";
+        match parse_dsr(Cursor::new(report)) {
+            Err(ParseError::InvalidDsr { line, .. }) => assert_eq!(line, 2),
+            other => panic!("expected invalid DSR error, got {:?}", other),
+        }
+    }
+}
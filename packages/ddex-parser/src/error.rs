@@ -2,6 +2,7 @@
 
 use ddex_core::error::DDEXError;
 use ddex_core::ffi::{FFIError, FFIErrorSeverity, FFIErrorCategory};
+use ddex_core::schema::SchemaViolation;
 use thiserror::Error;
 
 // Re-export ErrorLocation for use in this crate
@@ -24,16 +25,67 @@ pub enum ParseError {
         version: String,
     },
     
+    #[error("Schema validation failed with {} violation(s)", violations.len())]
+    SchemaViolation {
+        violations: Vec<SchemaViolation>,
+    },
+    
+    #[error("Invalid DSR record at line {line}: {message}")]
+    InvalidDsr {
+        line: usize,
+        message: String,
+    },
+    
+    #[error("Character encoding error ({encoding}): {message}")]
+    Encoding {
+        encoding: String,
+        message: String,
+    },
+    
     #[error("Security violation: {message}")]
     SecurityViolation {
         message: String,
     },
     
+    /// The streaming parser would have held more than `max_memory`
+    #[error("Memory limit exceeded: {requested} bytes needed, {limit} allowed")]
+    MemoryLimitExceeded {
+        limit: usize,
+        requested: usize,
+    },
+    
+    /// The parse was cancelled or ran past `timeout_ms`
+    #[error("Parse {reason} after {bytes_processed} bytes and {releases_parsed} release(s)")]
+    Cancelled {
+        reason: ddex_core::cancel::CancelReason,
+        bytes_processed: u64,
+        releases_parsed: usize,
+    },
+    
     #[error("Parse timeout after {seconds} seconds")]
     Timeout {
         seconds: u64,
     },
     
+    #[error("HTTP error fetching {url}: {message}")]
+    Http {
+        url: String,
+        message: String,
+    },
+    
+    /// A message queue refused or failed to acknowledge a published release
+    #[error("Sink error ({sink}): {message}")]
+    Sink {
+        sink: String,
+        message: String,
+    },
+    
+    /// `fail_on_warning` was set and the parser changed values of the document
+    #[error("Failed on {} warning(s): {}", warnings.len(), warnings.join("; "))]
+    FailedOnWarning {
+        warnings: Vec<String>,
+    },
+    
     #[error("Core error: {0}")]
     Core(#[from] DDEXError),
     
@@ -41,54 +93,136 @@ pub enum ParseError {
     Io(#[from] std::io::Error),
 }
 
+impl ParseError {
+    /// Stable error code, `DDEX-P-` followed by the same categories as
+    /// [`DDEXError::code`]; core errors keep their `DDEX-C-` code
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseError::XmlError { .. } => "DDEX-P-0101",
+            ParseError::Encoding { .. } => "DDEX-P-0102",
+            ParseError::SchemaViolation { .. } => "DDEX-P-0201",
+            ParseError::InvalidDsr { .. } => "DDEX-P-0202",
+            ParseError::FailedOnWarning { .. } => "DDEX-P-0203",
+            ParseError::UnsupportedVersion { .. } => "DDEX-P-0501",
+            ParseError::Io(_) => "DDEX-P-0601",
+            ParseError::Timeout { .. } => "DDEX-P-0602",
+            ParseError::Http { .. } => "DDEX-P-0603",
+            ParseError::Sink { .. } => "DDEX-P-0604",
+            ParseError::Cancelled { .. } => "DDEX-P-0605",
+            ParseError::SecurityViolation { .. } => "DDEX-P-0701",
+            ParseError::MemoryLimitExceeded { .. } => "DDEX-P-0702",
+            ParseError::Core(core_err) => core_err.code(),
+        }
+    }
+
+    /// Element path of the offending input, if known
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            ParseError::XmlError { location, .. } => Some(&location.path),
+            ParseError::SchemaViolation { violations } => violations.first().map(|v| v.location.path.as_str()),
+            ParseError::Core(core_err) => core_err.path(),
+            _ => None,
+        }
+    }
+
+    /// How to fix the input, if known
+    pub fn suggestion(&self) -> Option<String> {
+        let suggestion = match self {
+            ParseError::XmlError { .. } => "Check XML syntax",
+            ParseError::Encoding { .. } => "Supply UTF-8, UTF-16 or ISO-8859-1 input",
+            ParseError::SchemaViolation { .. } => "Check the document against the DDEX XSD for its version",
+            ParseError::InvalidDsr { .. } => "Check the record against the DSR flat-file layout for its profile",
+            ParseError::FailedOnWarning { .. } => "Fix the listed values at the source, or turn off fail_on_warning",
+            ParseError::UnsupportedVersion { .. } => "Use ERN 3.8.2, 4.2, or 4.3",
+            ParseError::Timeout { .. } => "File may be too large or complex",
+            ParseError::Cancelled { .. } => "Raise timeout_ms, or stream the document release by release",
+            ParseError::Http { .. } => "Check the URL, network access and the endpoint's response",
+            ParseError::Sink { .. } => "Check the broker address and credentials, then republish the document",
+            ParseError::SecurityViolation { .. } => "Check for XXE or entity expansion attacks",
+            ParseError::MemoryLimitExceeded { .. } => "Raise max_memory, or stream releases instead of collecting them",
+            ParseError::Core(core_err) => return core_err.suggestion(),
+            ParseError::Io(_) => return None,
+        };
+        Some(suggestion.to_string())
+    }
+}
+
+impl From<ddex_core::memory::MemoryLimitExceeded> for ParseError {
+    fn from(err: ddex_core::memory::MemoryLimitExceeded) -> Self {
+        ParseError::MemoryLimitExceeded { limit: err.limit, requested: err.requested }
+    }
+}
+
 impl From<ParseError> for FFIError {
     fn from(err: ParseError) -> Self {
-        match err {
-            ParseError::Core(core_err) => core_err.into(),
-            ParseError::XmlError { message, location } => FFIError {
-                code: "PARSE_XML_ERROR".to_string(),
+        let code = err.code().to_string();
+        let suggestion = err.suggestion();
+        let (message, location, category) = match err {
+            ParseError::Core(core_err) => return core_err.into(),
+            ParseError::XmlError { message, location } => (
                 message,
-                location: Some(ddex_core::ffi::FFIErrorLocation {
+                Some(ddex_core::ffi::FFIErrorLocation {
                     line: location.line,
                     column: location.column,
                     path: location.path,
                 }),
-                severity: FFIErrorSeverity::Error,
-                hint: Some("Check XML syntax".to_string()),
-                category: FFIErrorCategory::XmlParsing,
-            },
-            ParseError::UnsupportedVersion { version } => FFIError {
-                code: "UNSUPPORTED_VERSION".to_string(),
-                message: format!("Unsupported DDEX version: {}", version),
-                location: None,
-                severity: FFIErrorSeverity::Error,
-                hint: Some("Use ERN 3.8.2, 4.2, or 4.3".to_string()),
-                category: FFIErrorCategory::Version,
-            },
-            ParseError::SecurityViolation { message } => FFIError {
-                code: "SECURITY_VIOLATION".to_string(),
+                FFIErrorCategory::XmlParsing,
+            ),
+            ParseError::UnsupportedVersion { version } => (
+                format!("Unsupported DDEX version: {}", version),
+                None,
+                FFIErrorCategory::Version,
+            ),
+            ParseError::SchemaViolation { violations } => {
+                let first = violations.first();
+                let message = match first {
+                    Some(v) if violations.len() > 1 => {
+                        format!("{} (and {} more schema violations)", v.message, violations.len() - 1)
+                    }
+                    Some(v) => v.message.clone(),
+                    None => "Schema validation failed".to_string(),
+                };
+                let location = first.map(|v| ddex_core::ffi::FFIErrorLocation {
+                    line: v.location.line,
+                    column: v.location.column,
+                    path: v.location.path.clone(),
+                });
+                (message, location, FFIErrorCategory::Validation)
+            }
+            ParseError::InvalidDsr { line, message } => (
                 message,
-                location: None,
-                severity: FFIErrorSeverity::Error,
-                hint: Some("Check for XXE or entity expansion attacks".to_string()),
-                category: FFIErrorCategory::Validation,
-            },
-            ParseError::Timeout { seconds } => FFIError {
-                code: "PARSE_TIMEOUT".to_string(),
-                message: format!("Parse timeout after {} seconds", seconds),
-                location: None,
-                severity: FFIErrorSeverity::Error,
-                hint: Some("File may be too large or complex".to_string()),
-                category: FFIErrorCategory::Io,
-            },
-            ParseError::Io(io_err) => FFIError {
-                code: "IO_ERROR".to_string(),
-                message: io_err.to_string(),
-                location: None,
-                severity: FFIErrorSeverity::Error,
-                hint: None,
-                category: FFIErrorCategory::Io,
-            },
+                Some(ddex_core::ffi::FFIErrorLocation {
+                    line,
+                    column: 0,
+                    path: String::new(),
+                }),
+                FFIErrorCategory::Validation,
+            ),
+            ParseError::Encoding { encoding, message } => (
+                format!("{}: {}", encoding, message),
+                None,
+                FFIErrorCategory::XmlParsing,
+            ),
+            ParseError::SecurityViolation { message } => (message, None, FFIErrorCategory::Validation),
+            err @ ParseError::MemoryLimitExceeded { .. } => (err.to_string(), None, FFIErrorCategory::Validation),
+            err @ ParseError::FailedOnWarning { .. } => (err.to_string(), None, FFIErrorCategory::Validation),
+            ParseError::Timeout { seconds } => (
+                format!("Parse timeout after {} seconds", seconds),
+                None,
+                FFIErrorCategory::Io,
+            ),
+            err @ ParseError::Cancelled { .. } => (err.to_string(), None, FFIErrorCategory::Io),
+            ParseError::Http { url, message } => (format!("{}: {}", url, message), None, FFIErrorCategory::Io),
+            ParseError::Sink { sink, message } => (format!("{}: {}", sink, message), None, FFIErrorCategory::Io),
+            ParseError::Io(io_err) => (io_err.to_string(), None, FFIErrorCategory::Io),
+        };
+        FFIError {
+            code,
+            message,
+            location,
+            severity: FFIErrorSeverity::Error,
+            suggestion,
+            category,
         }
     }
 }
@@ -0,0 +1,8 @@
+// core/src/export/mod.rs
+//! Exports of parsed catalogs to external stores
+//!
+//! - [`sql`]: a normalized relational schema, written as a SQL script any
+//!   SQLite or DuckDB shell can load, or straight into a SQLite database
+//!   with the `sqlite` feature
+
+pub mod sql;
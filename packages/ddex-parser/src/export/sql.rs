@@ -0,0 +1,514 @@
+// core/src/export/sql.rs
+//! Relational export of flattened messages
+//!
+//! Catalogs are written to a normalized schema of `messages`, `releases`,
+//! `tracks`, `deals`, `deal_releases`, `deal_territories`, `parties` and
+//! `identifiers` tables, keyed by `message_id` so several deliveries can be
+//! loaded into one database. Exporting a message again replaces its rows.
+//!
+//! [`write_script`] renders the schema and rows as plain SQL, which loads
+//! unchanged into SQLite (`sqlite3 catalog.db < catalog.sql`) and DuckDB
+//! (`duckdb catalog.duckdb < catalog.sql`). With the `sqlite` feature,
+//! [`SqliteCatalog`] writes into and queries a SQLite database directly.
+
+use ddex_core::models::common::IdentifierType;
+use ddex_core::models::flat::FlattenedMessage;
+use std::io::Write;
+
+/// DDL of the export schema, valid in both SQLite and DuckDB
+pub const SCHEMA: &str = "\
+CREATE TABLE IF NOT EXISTS messages (
+    message_id TEXT PRIMARY KEY,
+    message_type TEXT NOT NULL,
+    message_date TEXT NOT NULL,
+    sender_id TEXT NOT NULL,
+    sender_name TEXT NOT NULL,
+    recipient_id TEXT NOT NULL,
+    recipient_name TEXT NOT NULL,
+    version TEXT NOT NULL,
+    profile TEXT
+);
+CREATE TABLE IF NOT EXISTS releases (
+    message_id TEXT NOT NULL,
+    release_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    display_artist TEXT NOT NULL,
+    release_type TEXT NOT NULL,
+    genre TEXT,
+    release_date TEXT,
+    PRIMARY KEY (message_id, release_id)
+);
+CREATE TABLE IF NOT EXISTS tracks (
+    message_id TEXT NOT NULL,
+    release_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    track_id TEXT NOT NULL,
+    isrc TEXT,
+    title TEXT NOT NULL,
+    display_artist TEXT NOT NULL,
+    duration_seconds INTEGER NOT NULL,
+    is_explicit BOOLEAN NOT NULL,
+    PRIMARY KEY (message_id, release_id, position)
+);
+CREATE TABLE IF NOT EXISTS deals (
+    message_id TEXT NOT NULL,
+    deal_id TEXT NOT NULL,
+    valid_from TEXT,
+    valid_until TEXT,
+    PRIMARY KEY (message_id, deal_id)
+);
+CREATE TABLE IF NOT EXISTS deal_releases (
+    message_id TEXT NOT NULL,
+    deal_id TEXT NOT NULL,
+    release_id TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS deal_territories (
+    message_id TEXT NOT NULL,
+    deal_id TEXT NOT NULL,
+    territory_code TEXT NOT NULL,
+    included BOOLEAN NOT NULL
+);
+CREATE TABLE IF NOT EXISTS parties (
+    message_id TEXT NOT NULL,
+    party_reference TEXT NOT NULL,
+    name TEXT,
+    isni TEXT,
+    ipi TEXT,
+    PRIMARY KEY (message_id, party_reference)
+);
+CREATE TABLE IF NOT EXISTS identifiers (
+    message_id TEXT NOT NULL,
+    entity_type TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    id_type TEXT NOT NULL,
+    namespace TEXT,
+    value TEXT NOT NULL
+);
+";
+
+/// Tables of the export schema, in insertion order
+pub const TABLES: &[&str] = &[
+    "messages",
+    "releases",
+    "tracks",
+    "deals",
+    "deal_releases",
+    "deal_territories",
+    "parties",
+    "identifiers",
+];
+
+/// A single column value
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlValue {
+    Null,
+    Integer(i64),
+    Text(String),
+    Boolean(bool),
+}
+
+impl SqlValue {
+    /// The value as a SQL literal
+    pub fn to_literal(&self) -> String {
+        match self {
+            SqlValue::Null => "NULL".to_string(),
+            SqlValue::Integer(i) => i.to_string(),
+            SqlValue::Text(s) => format!("'{}'", s.replace('\'', "''")),
+            SqlValue::Boolean(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
+        }
+    }
+}
+
+impl From<&str> for SqlValue {
+    fn from(s: &str) -> Self {
+        SqlValue::Text(s.to_string())
+    }
+}
+
+impl From<String> for SqlValue {
+    fn from(s: String) -> Self {
+        SqlValue::Text(s)
+    }
+}
+
+impl<T: Into<SqlValue>> From<Option<T>> for SqlValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(SqlValue::Null, Into::into)
+    }
+}
+
+impl From<i64> for SqlValue {
+    fn from(i: i64) -> Self {
+        SqlValue::Integer(i)
+    }
+}
+
+impl From<bool> for SqlValue {
+    fn from(b: bool) -> Self {
+        SqlValue::Boolean(b)
+    }
+}
+
+/// One row of one table of the export schema
+#[derive(Debug, Clone, PartialEq)]
+pub struct SqlRow {
+    pub table: &'static str,
+    pub columns: Vec<(&'static str, SqlValue)>,
+}
+
+impl SqlRow {
+    fn new(table: &'static str, columns: Vec<(&'static str, SqlValue)>) -> Self {
+        Self { table, columns }
+    }
+
+    /// `INSERT` statement with `?` placeholders for the values
+    pub fn insert_sql(&self) -> String {
+        let names: Vec<_> = self.columns.iter().map(|(name, _)| *name).collect();
+        let placeholders = vec!["?"; names.len()].join(", ");
+        format!("INSERT INTO {} ({}) VALUES ({})", self.table, names.join(", "), placeholders)
+    }
+
+    /// `INSERT` statement with the values inlined as literals
+    pub fn insert_literal(&self) -> String {
+        let names: Vec<_> = self.columns.iter().map(|(name, _)| *name).collect();
+        let values: Vec<_> = self.columns.iter().map(|(_, value)| value.to_literal()).collect();
+        format!("INSERT INTO {} ({}) VALUES ({});", self.table, names.join(", "), values.join(", "))
+    }
+}
+
+/// Rows of a message, table by table in [`TABLES`] order
+pub fn message_rows(message: &FlattenedMessage) -> Vec<SqlRow> {
+    let id = message.message_id.as_str();
+    let mut rows = vec![SqlRow::new(
+        "messages",
+        vec![
+            ("message_id", id.into()),
+            ("message_type", message.message_type.as_str().into()),
+            ("message_date", message.message_date.to_rfc3339().into()),
+            ("sender_id", message.sender.id.as_str().into()),
+            ("sender_name", message.sender.name.as_str().into()),
+            ("recipient_id", message.recipient.id.as_str().into()),
+            ("recipient_name", message.recipient.name.as_str().into()),
+            ("version", message.version.as_str().into()),
+            ("profile", message.profile.as_deref().into()),
+        ],
+    )];
+    let mut identifiers = Vec::new();
+    let mut identifier = |entity_type: &str, entity_id: &str, id_type: &str, namespace: Option<&str>, value: &str| {
+        identifiers.push(SqlRow::new(
+            "identifiers",
+            vec![
+                ("message_id", id.into()),
+                ("entity_type", entity_type.into()),
+                ("entity_id", entity_id.into()),
+                ("id_type", id_type.into()),
+                ("namespace", namespace.into()),
+                ("value", value.into()),
+            ],
+        ));
+    };
+
+    for release in &message.releases {
+        rows.push(SqlRow::new(
+            "releases",
+            vec![
+                ("message_id", id.into()),
+                ("release_id", release.release_id.as_str().into()),
+                ("title", release.default_title.as_str().into()),
+                ("display_artist", release.display_artist.as_str().into()),
+                ("release_type", release.release_type.as_str().into()),
+                ("genre", release.genre.as_deref().into()),
+                ("release_date", release.release_date.map(|d| d.date_naive().to_string()).into()),
+            ],
+        ));
+        let ids = &release.identifiers;
+        let standard = [("UPC", &ids.upc), ("EAN", &ids.ean), ("GRid", &ids.grid), ("CatalogNumber", &ids.catalog_number)];
+        for (id_type, value) in standard {
+            if let Some(value) = value {
+                identifier("release", &release.release_id, id_type, None, value);
+            }
+        }
+        for proprietary in &ids.proprietary {
+            identifier("release", &release.release_id, "Proprietary", Some(&proprietary.namespace), &proprietary.value);
+        }
+    }
+    for release in &message.releases {
+        for track in &release.tracks {
+            rows.push(SqlRow::new(
+                "tracks",
+                vec![
+                    ("message_id", id.into()),
+                    ("release_id", release.release_id.as_str().into()),
+                    ("position", (track.position as i64).into()),
+                    ("track_id", track.track_id.as_str().into()),
+                    ("isrc", track.isrc.as_deref().into()),
+                    ("title", track.title.as_str().into()),
+                    ("display_artist", track.display_artist.as_str().into()),
+                    ("duration_seconds", (track.duration.as_secs() as i64).into()),
+                    ("is_explicit", track.is_explicit.into()),
+                ],
+            ));
+            if let Some(isrc) = &track.isrc {
+                identifier("track", &track.track_id, "ISRC", None, isrc);
+            }
+            if let Some(iswc) = &track.iswc {
+                identifier("track", &track.track_id, "ISWC", None, iswc);
+            }
+        }
+    }
+
+    for deal in &message.deals {
+        rows.push(SqlRow::new(
+            "deals",
+            vec![
+                ("message_id", id.into()),
+                ("deal_id", deal.deal_id.as_str().into()),
+                ("valid_from", deal.validity.start.map(|d| d.to_rfc3339()).into()),
+                ("valid_until", deal.validity.end.map(|d| d.to_rfc3339()).into()),
+            ],
+        ));
+    }
+    for deal in &message.deals {
+        for release_id in &deal.releases {
+            rows.push(SqlRow::new(
+                "deal_releases",
+                vec![
+                    ("message_id", id.into()),
+                    ("deal_id", deal.deal_id.as_str().into()),
+                    ("release_id", release_id.as_str().into()),
+                ],
+            ));
+        }
+    }
+    for deal in &message.deals {
+        let territories = deal.territories.included.iter().map(|code| (code, true))
+            .chain(deal.territories.excluded.iter().map(|code| (code, false)));
+        for (code, included) in territories {
+            rows.push(SqlRow::new(
+                "deal_territories",
+                vec![
+                    ("message_id", id.into()),
+                    ("deal_id", deal.deal_id.as_str().into()),
+                    ("territory_code", code.as_str().into()),
+                    ("included", included.into()),
+                ],
+            ));
+        }
+    }
+
+    let mut parties: Vec<_> = message.parties.iter().collect();
+    parties.sort_by(|a, b| a.0.cmp(b.0));
+    for (reference, party) in parties {
+        rows.push(SqlRow::new(
+            "parties",
+            vec![
+                ("message_id", id.into()),
+                ("party_reference", reference.as_str().into()),
+                ("name", party.party_name.first().map(|name| name.text.as_str()).into()),
+                ("isni", party.isni.as_deref().into()),
+                ("ipi", party.ipi.as_deref().into()),
+            ],
+        ));
+        for party_id in &party.party_id {
+            identifier("party", reference, id_type_name(&party_id.id_type), party_id.namespace.as_deref(), &party_id.value);
+        }
+    }
+
+    rows.extend(identifiers);
+    rows
+}
+
+fn id_type_name(id_type: &IdentifierType) -> &'static str {
+    match id_type {
+        IdentifierType::Proprietary => "Proprietary",
+        IdentifierType::ISRC => "ISRC",
+        IdentifierType::ISWC => "ISWC",
+        IdentifierType::UPC => "UPC",
+        IdentifierType::EAN => "EAN",
+        IdentifierType::GRID | IdentifierType::GRid => "GRid",
+        IdentifierType::ISNI => "ISNI",
+        IdentifierType::IPI => "IPI",
+    }
+}
+
+/// Statements removing the rows of a previous export of `message_id`
+pub fn delete_statements(message_id: &str) -> Vec<String> {
+    let id = SqlValue::from(message_id).to_literal();
+    TABLES
+        .iter()
+        .map(|table| format!("DELETE FROM {} WHERE message_id = {};", table, id))
+        .collect()
+}
+
+/// Write the schema and the rows of `messages` as one SQL transaction
+pub fn write_script<W: Write>(messages: &[FlattenedMessage], mut out: W) -> std::io::Result<()> {
+    out.write_all(SCHEMA.as_bytes())?;
+    writeln!(out, "BEGIN TRANSACTION;")?;
+    for message in messages {
+        for statement in delete_statements(&message.message_id) {
+            writeln!(out, "{}", statement)?;
+        }
+        for row in message_rows(message) {
+            writeln!(out, "{}", row.insert_literal())?;
+        }
+    }
+    writeln!(out, "COMMIT;")
+}
+
+#[cfg(feature = "sqlite")]
+pub use self::sqlite::SqliteCatalog;
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use super::{delete_statements, message_rows, SqlValue, SCHEMA};
+    use ddex_core::models::flat::FlattenedMessage;
+    use indexmap::IndexMap;
+    use rusqlite::types::{ToSqlOutput, Value, ValueRef};
+    use rusqlite::{params_from_iter, Connection, ToSql};
+    use std::path::Path;
+
+    impl ToSql for SqlValue {
+        fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+            Ok(match self {
+                SqlValue::Null => ToSqlOutput::Owned(Value::Null),
+                SqlValue::Integer(i) => ToSqlOutput::Owned(Value::Integer(*i)),
+                SqlValue::Text(s) => ToSqlOutput::Borrowed(ValueRef::Text(s.as_bytes())),
+                SqlValue::Boolean(b) => ToSqlOutput::Owned(Value::Integer(*b as i64)),
+            })
+        }
+    }
+
+    /// A SQLite database holding exported catalogs
+    pub struct SqliteCatalog {
+        connection: Connection,
+    }
+
+    impl SqliteCatalog {
+        /// Open or create the database at `path` and create missing tables
+        pub fn open(path: &Path) -> rusqlite::Result<Self> {
+            Self::with_connection(Connection::open(path)?)
+        }
+
+        /// An in-memory database, mostly useful for tests
+        pub fn open_in_memory() -> rusqlite::Result<Self> {
+            Self::with_connection(Connection::open_in_memory()?)
+        }
+
+        fn with_connection(connection: Connection) -> rusqlite::Result<Self> {
+            connection.execute_batch(SCHEMA)?;
+            Ok(Self { connection })
+        }
+
+        /// Insert a message, replacing the rows of any earlier export of it
+        pub fn insert_message(&mut self, message: &FlattenedMessage) -> rusqlite::Result<()> {
+            let tx = self.connection.transaction()?;
+            tx.execute_batch(&delete_statements(&message.message_id).join("\n"))?;
+            for row in message_rows(message) {
+                let mut statement = tx.prepare_cached(&row.insert_sql())?;
+                statement.execute(params_from_iter(row.columns.iter().map(|(_, value)| value)))?;
+            }
+            tx.commit()
+        }
+
+        /// Run a query and return its rows as JSON objects keyed by column name
+        pub fn query(&self, sql: &str) -> rusqlite::Result<Vec<IndexMap<String, serde_json::Value>>> {
+            let mut statement = self.connection.prepare(sql)?;
+            let names: Vec<String> = statement.column_names().into_iter().map(String::from).collect();
+            let rows = statement.query_map([], |row| {
+                names
+                    .iter()
+                    .enumerate()
+                    .map(|(i, name)| {
+                        let value = match row.get_ref(i)? {
+                            ValueRef::Null => serde_json::Value::Null,
+                            ValueRef::Integer(i) => i.into(),
+                            ValueRef::Real(f) => f.into(),
+                            ValueRef::Text(t) => String::from_utf8_lossy(t).into_owned().into(),
+                            ValueRef::Blob(b) => hex::encode(b).into(),
+                        };
+                        Ok((name.clone(), value))
+                    })
+                    .collect()
+            })?;
+            rows.collect()
+        }
+
+        /// The underlying connection
+        pub fn connection(&self) -> &Connection {
+            &self.connection
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ddex_core::models::flat::{DealValidity, DistributionComplexity, ParsedDeal, TerritoryComplexity};
+
+    fn message() -> FlattenedMessage {
+        let message = crate::DDEXParser::new()
+            .parse(std::io::Cursor::new(
+                include_bytes!("../../../../test-suite/valid/ern-4.4/simple_release.xml").as_slice(),
+            ))
+            .unwrap();
+        let mut flat = message.flat;
+        flat.message_id = "MSG'1".to_string();
+        flat.deals.push(ParsedDeal {
+            deal_id: "D1".to_string(),
+            releases: flat.releases.iter().map(|r| r.release_id.clone()).collect(),
+            validity: DealValidity { start: None, end: None },
+            territories: TerritoryComplexity {
+                included: vec!["GB".to_string()],
+                excluded: vec!["FR".to_string()],
+            },
+            distribution_channels: DistributionComplexity { included: Vec::new(), excluded: Vec::new() },
+            pricing: Vec::new(),
+            usage_rights: Vec::new(),
+            restrictions: Vec::new(),
+        });
+        flat
+    }
+
+    #[test]
+    fn test_rows_follow_table_order() {
+        let rows = message_rows(&message());
+        let tables: Vec<_> = rows.iter().map(|row| row.table).collect();
+        let order: Vec<_> = tables.iter().map(|t| TABLES.iter().position(|x| x == t).unwrap()).collect();
+        assert!(order.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(tables[0], "messages");
+        assert_eq!(tables.iter().filter(|t| **t == "deal_territories").count(), 2);
+    }
+
+    #[test]
+    fn test_script_escapes_literals() {
+        let mut script = Vec::new();
+        write_script(&[message()], &mut script).unwrap();
+        let script = String::from_utf8(script).unwrap();
+        assert!(script.starts_with("CREATE TABLE IF NOT EXISTS messages"));
+        assert!(script.contains("DELETE FROM tracks WHERE message_id = 'MSG''1';"));
+        assert!(script.contains("VALUES ('MSG''1', 'D1', 'FR', FALSE);"));
+        assert!(script.trim_end().ends_with("COMMIT;"));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_sqlite_export_is_idempotent() {
+        let mut catalog = SqliteCatalog::open_in_memory().unwrap();
+        catalog.insert_message(&message()).unwrap();
+        catalog.insert_message(&message()).unwrap();
+
+        let counts = catalog.query("SELECT COUNT(*) AS n FROM messages").unwrap();
+        assert_eq!(counts[0]["n"], 1);
+        let rows = catalog
+            .query(
+                "SELECT r.release_id, COUNT(t.track_id) AS tracks FROM releases r \
+                 LEFT JOIN tracks t USING (message_id, release_id) GROUP BY r.release_id",
+            )
+            .unwrap();
+        assert!(!rows.is_empty());
+        let excluded = catalog
+            .query("SELECT territory_code FROM deal_territories WHERE included = FALSE")
+            .unwrap();
+        assert_eq!(excluded[0]["territory_code"], "FR");
+    }
+}
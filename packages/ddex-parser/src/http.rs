@@ -0,0 +1,49 @@
+// core/src/http.rs
+//! Fetching DDEX documents over HTTP(S)
+
+use crate::error::ParseError;
+use std::time::Duration;
+
+/// Redirects followed before a fetch is abandoned
+pub const MAX_REDIRECTS: usize = 10;
+
+/// Download `url`, following redirects, and refuse bodies larger than
+/// `max_bytes` while they are still arriving
+pub async fn fetch(url: &str, timeout: Duration, max_bytes: usize) -> Result<Vec<u8>, ParseError> {
+    let error = |e: reqwest::Error| {
+        if e.is_timeout() {
+            ParseError::Timeout { seconds: timeout.as_secs() }
+        } else {
+            ParseError::Http { url: url.to_string(), message: e.to_string() }
+        }
+    };
+    let too_large = || ParseError::Http {
+        url: url.to_string(),
+        message: format!("response body exceeds {} bytes", max_bytes),
+    };
+
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::limited(MAX_REDIRECTS))
+        .timeout(timeout)
+        .build()
+        .map_err(error)?;
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(error)?;
+
+    if response.content_length().is_some_and(|length| length > max_bytes as u64) {
+        return Err(too_large());
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(error)? {
+        if body.len() + chunk.len() > max_bytes {
+            return Err(too_large());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
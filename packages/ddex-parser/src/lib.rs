@@ -2,8 +2,16 @@
 use ddex_core::models;
 /// DDEX Parser Core Library
 
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod delivery;
+pub mod dsr;
 pub mod error;
+pub mod export;
+#[cfg(feature = "http")]
+pub mod http;
 pub mod parser;
+pub mod sink;
 pub mod transform;
 
 // Re-export commonly used types
@@ -49,16 +57,143 @@ impl DDEXParser {
     pub fn parse_with_options<R: std::io::BufRead + std::io::Seek>(
         &self,
         reader: R,
+        mut options: parser::ParseOptions,
+    ) -> Result<ddex_core::models::flat::ParsedERNMessage, error::ParseError> {
+        // Apply security config unless the options carry their own
+        options.security.get_or_insert_with(|| self.config.clone());
+        
+        parser::parse(reader, options)
+    }
+    
+    /// Parse a document that may be gzip-compressed or the first XML file of a
+    /// zip archive (requires the `gzip`/`zip` features)
+    pub fn parse_compressed<R: std::io::Read + std::io::Seek>(
+        &self,
+        reader: R,
+    ) -> Result<ddex_core::models::flat::ParsedERNMessage, error::ParseError> {
+        let document = ddex_core::compression::read_document(reader)?;
+        self.parse(std::io::Cursor::new(document))
+    }
+    
+    /// Fetch a document over HTTP(S) and parse it
+    ///
+    /// Redirects are followed (up to [`http::MAX_REDIRECTS`]), the request is
+    /// bounded by `options.timeout_ms` and the body by `options.max_memory`.
+    /// Gzip and zip bodies are inflated as in [`DDEXParser::parse_compressed`],
+    /// and large documents go through the streaming parser unless
+    /// `options.mode` says otherwise.
+    #[cfg(feature = "http")]
+    pub async fn parse_url(
+        &self,
+        url: &str,
+        options: parser::ParseOptions,
+    ) -> Result<ddex_core::models::flat::ParsedERNMessage, error::ParseError> {
+        let timeout = std::time::Duration::from_millis(options.timeout_ms);
+        let body = http::fetch(url, timeout, options.max_memory).await?;
+        let document = ddex_core::compression::read_document(std::io::Cursor::new(body))?;
+        self.parse_with_options(std::io::Cursor::new(document), options)
+    }
+    
+    /// Download a document from an `s3://`, `gs://` or `az://` URI and parse
+    /// it, with credentials from the provider's standard chain
+    ///
+    /// Compressed objects are inflated as in [`DDEXParser::parse_compressed`].
+    #[cfg(feature = "storage")]
+    pub async fn parse_storage(
+        &self,
+        uri: &str,
         options: parser::ParseOptions,
     ) -> Result<ddex_core::models::flat::ParsedERNMessage, error::ParseError> {
-        // Apply security config
+        let location = ddex_core::storage::StorageUri::parse(uri).ok_or_else(|| {
+            ddex_core::DDEXError::Storage {
+                uri: uri.to_string(),
+                message: "expected an s3://, gs:// or az:// URI".to_string(),
+            }
+        })?;
+        let body = ddex_core::storage::read(&location).await?;
+        let document = ddex_core::compression::read_document(std::io::Cursor::new(body))?;
+        self.parse_with_options(std::io::Cursor::new(document), options)
+    }
+    
+    /// Read a delivery folder or zip archive: its ERN messages, manifest,
+    /// acknowledgements and the checksums of the resource files they declare
+    pub fn parse_delivery(
+        &self,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<delivery::Delivery, error::ParseError> {
+        delivery::open(self, path.as_ref())
+    }
+    
+    /// Check the files a message's resources declare against the folder at
+    /// `base`: presence, `FileSize`, `HashSum` and codec/extension agreement
+    pub fn check_resource_files<R: std::io::BufRead>(
+        &self,
+        mut reader: R,
+        base: impl AsRef<std::path::Path>,
+    ) -> Result<delivery::ResourceFileReport, error::ParseError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        delivery::check_resource_files(&parser::encoding::to_utf8(&bytes)?, base.as_ref())
+    }
+    
+    /// Parse a MEAD (Media Enrichment and Description) message
+    pub fn parse_mead<R: std::io::BufRead>(
+        &self,
+        reader: R,
+    ) -> Result<ddex_core::models::mead::MeadMessage, error::ParseError> {
         if !self.config.disable_external_entities {
             return Err(error::ParseError::SecurityViolation {
                 message: "External entities are disabled".to_string(),
             });
         }
         
-        parser::parse(reader, options)
+        parser::mead::parse_mead(reader)
+    }
+    
+    /// Check a MEAD message against the ERN message it enriches: every
+    /// enriched release and focus track must exist in `ern`, and both
+    /// messages must have the same sender and recipient
+    pub fn check_enrichment<R: std::io::BufRead>(
+        &self,
+        ern: &str,
+        mead: R,
+    ) -> Result<delivery::EnrichmentReport, error::ParseError> {
+        let ern = self.parse_borrowed(ern)?;
+        let mead = self.parse_mead(mead)?;
+        Ok(delivery::check_enrichment(&ern, &mead))
+    }
+    
+    /// Parse an ERN message into a view borrowing its values from `xml`
+    ///
+    /// Avoids a copy per field for read-only passes over large catalogs; see
+    /// [`ddex_core::models::borrowed`] for what the view covers.
+    pub fn parse_borrowed<'a>(
+        &self,
+        xml: &'a str,
+    ) -> Result<ddex_core::models::borrowed::BorrowedMessage<'a>, error::ParseError> {
+        parser::borrowed::parse_borrowed(xml, &self.config)
+    }
+    
+    /// Parse a DSR (Digital Sales Reporting) flat-file report into memory
+    pub fn parse_dsr<R: std::io::BufRead>(
+        &self,
+        reader: R,
+    ) -> Result<dsr::DsrReport, error::ParseError> {
+        dsr::parse_dsr(reader)
+    }
+    
+    /// Stream the sales rows of a DSR report without holding it in memory
+    pub fn stream_dsr<R: std::io::BufRead>(&self, reader: R) -> dsr::DsrSalesIter<R> {
+        dsr::DsrSalesIter::new(reader)
+    }
+    
+    /// Stream parse a document that may be gzip-compressed, inflating it as it
+    /// is read (requires the `gzip` feature for compressed input)
+    pub fn stream_compressed<R: std::io::BufRead>(
+        &self,
+        reader: R,
+    ) -> Result<StreamIterator<ddex_core::compression::MaybeGzip<R>>, error::ParseError> {
+        Ok(self.stream(ddex_core::compression::gunzip_if_needed(reader)?))
     }
     
     /// Stream parse for large files
@@ -84,6 +219,19 @@ impl DDEXParser {
         parser::detector::VersionDetector::detect(reader)
     }
     
+    /// Validate DDEX XML against the bundled schema for its detected version
+    pub fn validate_schema<R: std::io::BufRead>(
+        &self,
+        mut reader: R,
+    ) -> Result<Vec<ddex_core::schema::SchemaViolation>, error::ParseError> {
+        let mut xml = String::new();
+        reader.read_to_string(&mut xml)?;
+        let version = parser::detector::VersionDetector::detect(std::io::Cursor::new(xml.as_bytes()))?;
+        let violations = ddex_core::schema::validate(&xml, version);
+        ddex_core::telemetry::record_validation_failures("schema", violations.len() as u64);
+        Ok(violations)
+    }
+    
     /// Perform sanity check on DDEX XML
     pub fn sanity_check<R: std::io::BufRead>(
         &self,
@@ -110,7 +258,11 @@ impl<R: std::io::BufRead> Iterator for StreamIterator<R> {
     type Item = Result<models::graph::Release, error::ParseError>;
     
     fn next(&mut self) -> Option<Self::Item> {
-        self.parser.stream_releases().next()
+        let release = self.parser.stream_releases().next();
+        if let Some(Ok(_)) = release {
+            ddex_core::telemetry::record_releases_parsed(1);
+        }
+        release
     }
 }
 
@@ -136,4 +288,81 @@ mod tests {
         let parser = DDEXParser::new();
         assert!(parser.config.disable_external_entities);
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_parse_compressed_accepts_plain_and_flags_gzip() {
+        use crate::error::ParseError;
+        use ddex_core::error::DDEXError;
+        
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+  <MessageHeader><MessageId>MSG1</MessageId></MessageHeader>
+</ern:NewReleaseMessage>"#;
+        
+        let parser = DDEXParser::new();
+        let parsed = parser.parse_compressed(std::io::Cursor::new(xml)).unwrap();
+        assert_eq!(parsed.graph.version, ddex_core::models::versions::ERNVersion::V4_3);
+        
+        // gzip magic is never handed to the XML reader as-is
+        let result = parser.parse_compressed(std::io::Cursor::new(vec![0x1f, 0x8b, 0x08, 0x00, 0x00]));
+        assert!(matches!(result, Err(ParseError::Core(DDEXError::Decompression { .. }))));
+    }
+    
+    #[cfg(feature = "signature")]
+    #[test]
+    fn test_parse_verifies_signature() {
+        use ddex_core::signature::{self, HmacSha256Key};
+        use std::sync::Arc;
+        
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+  <MessageHeader><MessageId>MSG1</MessageId></MessageHeader>
+</ern:NewReleaseMessage>"#;
+        let key = HmacSha256Key::new("shared secret");
+        let signed = signature::sign(xml, &key).unwrap();
+        
+        let parser = DDEXParser::new();
+        let options = || parser::ParseOptions {
+            verify_signature: Some(Arc::new(key.clone())),
+            ..Default::default()
+        };
+        assert!(parser.parse_with_options(std::io::Cursor::new(signed.as_str()), options()).is_ok());
+        
+        let tampered = signed.replace("MSG1", "MSG2");
+        let result = parser.parse_with_options(std::io::Cursor::new(tampered), options());
+        assert!(matches!(result, Err(error::ParseError::Core(ddex_core::DDEXError::Signature { .. }))));
+        assert!(parser.parse_with_options(std::io::Cursor::new(xml), options()).is_err());
+    }
+    
+    #[cfg(feature = "http")]
+    #[tokio::test]
+    async fn test_parse_url_fetches_and_limits_body() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        
+        let xml = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/42"><MessageHeader/></ern:NewReleaseMessage>"#;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = [0u8; 1024];
+                let read = socket.read(&mut request).await.unwrap();
+                let response = if request[..read].starts_with(b"GET /old") {
+                    "HTTP/1.1 301 Moved Permanently\r\nLocation: /feed.xml\r\nContent-Length: 0\r\n\r\n".to_string()
+                } else {
+                    format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}", xml.len(), xml)
+                };
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        
+        let parser = DDEXParser::new();
+        let url = format!("http://{}/old", address);
+        let parsed = parser.parse_url(&url, Default::default()).await.unwrap();
+        assert_eq!(parsed.graph.version, ddex_core::models::versions::ERNVersion::V4_2);
+        
+        let options = parser::ParseOptions { max_memory: 16, ..Default::default() };
+        let result = parser.parse_url(&url, options).await;
+        assert!(matches!(result, Err(error::ParseError::Http { .. })));
+    }
+}
@@ -0,0 +1,214 @@
+// core/src/parser/borrowed.rs
+//! Zero-copy parsing into [`BorrowedMessage`]
+//!
+//! Reads the document straight from the `&str` it was given: element names
+//! are matched against a fixed table instead of being copied, and text
+//! without entity references is handed out as a slice of the input. Only
+//! escaped values and `Namespace` attributes allocate.
+
+use crate::error::{ErrorLocation, ParseError};
+use crate::parser::mead::identifier_type;
+use crate::parser::security::{self, SecurityConfig};
+use ddex_core::models::borrowed::{
+    BorrowedDeal, BorrowedIdentifier, BorrowedMessage, BorrowedRelease, BorrowedResource,
+};
+use ddex_core::telemetry;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::borrow::Cow;
+
+/// Element names the borrowed parser looks at; anything else is tracked as ""
+const KNOWN_ELEMENTS: &[&str] = &[
+    "MessageHeader", "MessageId", "MessageCreatedDateTime", "MessageSender",
+    "MessageRecipient", "PartyId", "PartyName", "FullName",
+    "ReleaseList", "Release", "ReleaseReference", "ReleaseId", "ICPN", "UPC", "EAN",
+    "GRid", "ISRC", "ISWC", "ProprietaryId", "CatalogNumber", "ReferenceTitle",
+    "DisplayTitle", "DisplayTitleText", "TitleText", "DisplayArtistName", "LabelName",
+    "Genre", "GenreText", "ReleaseDate", "OriginalReleaseDate", "ReleaseResourceReference",
+    "ResourceList", "ResourceReference", "SoundRecordingId", "VideoId", "ImageId",
+    "TextId", "SheetMusicId", "SoftwareId", "ResourceId", "Duration",
+    "DealList", "ReleaseDeal", "DealReleaseReference", "TerritoryCode",
+    "CommercialModelType", "UseType", "ValidityPeriod", "StartDate",
+];
+
+/// Elements below a resource that carry its identifiers
+const RESOURCE_ID_ELEMENTS: &[&str] = &[
+    "SoundRecordingId", "VideoId", "ImageId", "TextId", "SheetMusicId", "SoftwareId", "ResourceId",
+];
+
+/// Parse an ERN message without copying its values out of `xml`
+///
+/// Security limits are enforced as for [`crate::parser::parse`]; the
+/// document must already be UTF-8, which a `&str` guarantees.
+pub fn parse_borrowed<'a>(xml: &'a str, config: &SecurityConfig) -> Result<BorrowedMessage<'a>, ParseError> {
+    let span = tracing::info_span!("ddex.parse_borrowed", bytes = xml.len(), releases = tracing::field::Empty);
+    let _entered = span.enter();
+    security::check_document(xml.as_bytes(), config)?;
+
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut message = BorrowedMessage::default();
+    let mut path: Vec<&'static str> = Vec::new();
+    let mut namespaces: Vec<Option<String>> = Vec::new();
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                let name = known_name(e.local_name().as_ref());
+                match (path.get(1..), name) {
+                    (Some(["ReleaseList"]), "Release") => message.releases.push(BorrowedRelease::default()),
+                    (Some(["ResourceList"]), _) => message.resources.push(BorrowedResource::default()),
+                    (Some(["DealList"]), "ReleaseDeal") => message.deals.push(BorrowedDeal::default()),
+                    _ => {}
+                }
+                namespaces.push(namespace(&e));
+                path.push(name);
+            }
+            Ok(Event::Text(t)) => {
+                let text = decode(t.into_inner(), true).map_err(|message| ParseError::XmlError {
+                    message,
+                    location: location(&reader, &path),
+                })?;
+                let namespace = namespaces.last().cloned().flatten();
+                apply_text(&mut message, &path, text, namespace);
+            }
+            Ok(Event::CData(t)) => {
+                let text = decode(t.into_inner(), false).map_err(|message| ParseError::XmlError {
+                    message,
+                    location: location(&reader, &path),
+                })?;
+                apply_text(&mut message, &path, text, None);
+            }
+            Ok(Event::End(_)) => {
+                path.pop();
+                namespaces.pop();
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(ParseError::XmlError {
+                    message: e.to_string(),
+                    location: location(&reader, &path),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    span.record("releases", message.releases.len());
+    telemetry::record_bytes_processed("parse", xml.len() as u64);
+    telemetry::record_releases_parsed(message.releases.len() as u64);
+    Ok(message)
+}
+
+fn known_name(name: &[u8]) -> &'static str {
+    KNOWN_ELEMENTS.iter().copied().find(|known| known.as_bytes() == name).unwrap_or("")
+}
+
+/// Text of an event, still borrowed from the input unless it had to be
+/// unescaped
+fn decode(raw: Cow<'_, [u8]>, unescape: bool) -> Result<Cow<'_, str>, String> {
+    let text = match raw {
+        Cow::Borrowed(bytes) => Cow::Borrowed(std::str::from_utf8(bytes).map_err(|e| e.to_string())?),
+        Cow::Owned(bytes) => Cow::Owned(String::from_utf8(bytes).map_err(|e| e.to_string())?),
+    };
+    if !unescape || !text.contains('&') {
+        return Ok(text);
+    }
+    let unescaped = quick_xml::escape::unescape(&text).map_err(|e| e.to_string())?.into_owned();
+    Ok(Cow::Owned(unescaped))
+}
+
+fn namespace(element: &BytesStart) -> Option<String> {
+    element.try_get_attribute("Namespace").ok().flatten()
+        .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+}
+
+fn apply_text<'a>(
+    message: &mut BorrowedMessage<'a>,
+    path: &[&'static str],
+    text: Cow<'a, str>,
+    namespace: Option<String>,
+) {
+    let Some(path) = path.get(1..) else { return };
+
+    match path {
+        ["MessageHeader", "MessageId"] => message.message_id = text,
+        ["MessageHeader", "MessageCreatedDateTime"] => message.message_created_date_time = Some(text),
+        ["MessageHeader", "MessageSender", "PartyId"] => message.sender_id = Some(text),
+        ["MessageHeader", "MessageSender", "PartyName", ..] => {
+            message.sender_name.get_or_insert(text);
+        }
+        ["MessageHeader", "MessageRecipient", "PartyId"] => message.recipient_id = Some(text),
+        ["ReleaseList", "Release", rest @ ..] => {
+            let Some(release) = message.releases.last_mut() else { return };
+            match rest {
+                ["ReleaseReference"] => release.release_reference = text,
+                ["ReleaseId", id_type] => release.release_id.push(BorrowedIdentifier {
+                    id_type: identifier_type(id_type),
+                    namespace: namespace.map(Cow::Owned),
+                    value: text,
+                }),
+                ["DisplayTitleText"] | [.., "ReferenceTitle" | "DisplayTitle", "TitleText"] => {
+                    release.title.get_or_insert(text);
+                }
+                [.., "DisplayArtistName"] => {
+                    release.display_artist.get_or_insert(text);
+                }
+                [.., "LabelName"] => {
+                    release.label_name.get_or_insert(text);
+                }
+                [.., "Genre", "GenreText"] => release.genres.push(text),
+                [.., "ReleaseDate" | "OriginalReleaseDate"] => {
+                    release.release_date.get_or_insert(text);
+                }
+                [.., "ReleaseResourceReference"] => release.resource_references.push(text),
+                _ => {}
+            }
+        }
+        ["ResourceList", _, rest @ ..] => {
+            let Some(resource) = message.resources.last_mut() else { return };
+            match rest {
+                ["ResourceReference"] => resource.resource_reference = text,
+                [.., id_element, id_type] if RESOURCE_ID_ELEMENTS.contains(id_element) => {
+                    resource.resource_id.push(BorrowedIdentifier {
+                        id_type: identifier_type(id_type),
+                        namespace: namespace.map(Cow::Owned),
+                        value: text,
+                    });
+                }
+                ["DisplayTitleText"] | [.., "ReferenceTitle" | "DisplayTitle", "TitleText"] => {
+                    resource.title.get_or_insert(text);
+                }
+                [.., "DisplayArtistName"] => {
+                    resource.display_artist.get_or_insert(text);
+                }
+                ["Duration"] => resource.duration = Some(text),
+                _ => {}
+            }
+        }
+        ["DealList", "ReleaseDeal", rest @ ..] => {
+            let Some(deal) = message.deals.last_mut() else { return };
+            match rest {
+                ["DealReleaseReference"] => deal.release_references.push(text),
+                [.., "TerritoryCode"] => deal.territory_codes.push(text),
+                [.., "CommercialModelType"] => deal.commercial_model_types.push(text),
+                [.., "UseType"] => deal.use_types.push(text),
+                [.., "StartDate"] => {
+                    deal.start_date.get_or_insert(text);
+                }
+                _ => {}
+            }
+        }
+        _ => {}
+    }
+}
+
+fn location<R>(reader: &Reader<R>, path: &[&str]) -> ErrorLocation {
+    ErrorLocation {
+        line: 0,
+        column: 0,
+        byte_offset: Some(reader.buffer_position() as usize),
+        path: format!("/{}", path.join("/")),
+    }
+}
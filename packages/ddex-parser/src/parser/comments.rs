@@ -0,0 +1,300 @@
+// core/src/parser/comments.rs
+//! Comment and processing-instruction capture for the graph model
+//!
+//! Comments are anchored to the nearest release, resource, deal or message
+//! header:
+//! - between list entries they are `Before` the next entry, or `After` the
+//!   last one when nothing follows
+//! - inside an entry they are `FirstChild` until its first child element has
+//!   started and `LastChild` afterwards; `xpath` names the element holding them
+//! - everywhere else they belong to the message itself
+//!
+//! Comments between two children of the same element are kept as `LastChild`
+//! of that element, so a rebuild moves them to its end.
+
+use crate::error::{ErrorLocation, ParseError};
+use ddex_core::models::graph::ERNMessage;
+use ddex_core::models::{Comment, CommentPosition, Extensions, ProcessingInstruction};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+/// Graph node a comment is attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Anchor {
+    Message,
+    Header,
+    Release(usize),
+    Resource(usize),
+    Deal(usize),
+}
+
+/// Comments of one release, resource or deal
+#[derive(Debug, Default)]
+struct Entry {
+    reference: Option<String>,
+    comments: Vec<Comment>,
+}
+
+/// An open element
+struct Frame {
+    name: String,
+    path: String,
+    /// Node that comments inside this element belong to
+    anchor: Anchor,
+    /// Whether this element is itself a release, resource, deal or header
+    is_entry: bool,
+    has_child: bool,
+    /// Entry whose reference is this element's text
+    reference_of: Option<Anchor>,
+    /// Last entry closed directly inside this element, with its path
+    last_entry: Option<(Anchor, String)>,
+}
+
+/// Everything captured from one document
+#[derive(Debug, Default)]
+pub struct CapturedComments {
+    message: Vec<Comment>,
+    header: Vec<Comment>,
+    releases: Vec<Entry>,
+    resources: Vec<Entry>,
+    deals: Vec<Entry>,
+    processing_instructions: Vec<ProcessingInstruction>,
+}
+
+/// Collect the comments of `xml` along with its document-level processing
+/// instructions
+pub fn capture_comments(xml: &str) -> Result<CapturedComments, ParseError> {
+    let mut reader = Reader::from_str(xml);
+    let mut captured = CapturedComments::default();
+    let mut stack: Vec<Frame> = Vec::new();
+    // Comments between siblings, waiting for the next one
+    let mut pending: Vec<Comment> = Vec::new();
+    let mut root_seen = false;
+    let mut lines = LineIndex::default();
+
+    loop {
+        let position = reader.buffer_position() as usize;
+        let event = reader.read_event().map_err(|e| ParseError::XmlError {
+            message: e.to_string(),
+            location: ErrorLocation {
+                line: 0,
+                column: 0,
+                byte_offset: Some(position),
+                path: stack.last().map(|f| f.path.clone()).unwrap_or_default(),
+            },
+        })?;
+
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                let (path, entry) = match stack.last_mut() {
+                    None => {
+                        root_seen = true;
+                        (format!("/{}", name), Some(Anchor::Message))
+                    }
+                    Some(parent) => {
+                        parent.has_child = true;
+                        let entry = match (parent.anchor, parent.name.as_str(), name.as_str()) {
+                            (Anchor::Message, _, "MessageHeader") => Some(Anchor::Header),
+                            (Anchor::Message, "ReleaseList", "Release") => {
+                                captured.releases.push(Entry::default());
+                                Some(Anchor::Release(captured.releases.len() - 1))
+                            }
+                            (Anchor::Message, "ResourceList", "SoundRecording" | "Video" | "Image" | "Text" | "SheetMusic") => {
+                                captured.resources.push(Entry::default());
+                                Some(Anchor::Resource(captured.resources.len() - 1))
+                            }
+                            (Anchor::Message, "DealList", "ReleaseDeal") => {
+                                captured.deals.push(Entry::default());
+                                Some(Anchor::Deal(captured.deals.len() - 1))
+                            }
+                            _ => None,
+                        };
+                        (format!("{}/{}", parent.path, name), entry)
+                    }
+                };
+
+                let parent = stack.last();
+                let anchor = entry.or(parent.map(|f| f.anchor)).unwrap_or(Anchor::Message);
+
+                // Comments waiting between siblings precede this element
+                for mut comment in pending.drain(..) {
+                    if entry.is_some() {
+                        comment.position = CommentPosition::Before;
+                        comment.xpath = Some(path.clone());
+                        captured.push(anchor, comment);
+                    } else {
+                        captured.push(parent.map(|f| f.anchor).unwrap_or(Anchor::Message), comment);
+                    }
+                }
+
+                let reference_of = parent.and_then(|p| match (p.anchor, name.as_str()) {
+                    (Anchor::Release(_), "ReleaseReference") if p.is_entry => Some(p.anchor),
+                    (Anchor::Resource(_), "ResourceReference") if p.is_entry => Some(p.anchor),
+                    (Anchor::Deal(_), "DealReference") if p.name == "Deal" => Some(p.anchor),
+                    _ => None,
+                });
+
+                if matches!(event, Event::Start(_)) {
+                    stack.push(Frame {
+                        name,
+                        path,
+                        anchor,
+                        is_entry: entry.is_some(),
+                        has_child: false,
+                        reference_of,
+                        last_entry: None,
+                    });
+                } else if let Some(parent) = stack.last_mut() {
+                    parent.last_entry = entry.map(|entry| (entry, path));
+                }
+            }
+            Event::Text(ref t) => {
+                if let Some(anchor) = stack.last().and_then(|f| f.reference_of) {
+                    let text = t.unescape().unwrap_or_default();
+                    if let Some(entry) = captured.entry(anchor) {
+                        entry.reference.get_or_insert_with(String::new).push_str(text.trim());
+                    }
+                }
+            }
+            Event::End(_) => {
+                let Some(frame) = stack.pop() else { continue };
+
+                // Comments closing a list trail its last entry
+                for mut comment in pending.drain(..) {
+                    match &frame.last_entry {
+                        Some((anchor, path)) => {
+                            comment.position = CommentPosition::After;
+                            comment.xpath = Some(path.clone());
+                            captured.push(*anchor, comment);
+                        }
+                        None => captured.push(frame.anchor, comment),
+                    }
+                }
+
+                if let Some(parent) = stack.last_mut() {
+                    parent.last_entry = frame.is_entry.then_some((frame.anchor, frame.path));
+                }
+            }
+            Event::Comment(ref c) => {
+                let content = String::from_utf8_lossy(c.as_ref()).into_owned();
+                let (line, column) = lines.locate(xml, position);
+
+                match stack.last() {
+                    None => {
+                        let position = if root_seen { CommentPosition::After } else { CommentPosition::Before };
+                        captured.message.push(Comment::with_location(content, position, None, Some(line), Some(column)));
+                    }
+                    Some(frame) => {
+                        let position = if frame.has_child { CommentPosition::LastChild } else { CommentPosition::FirstChild };
+                        let comment = Comment::with_location(content, position, Some(frame.path.clone()), Some(line), Some(column));
+                        if frame.anchor == Anchor::Message {
+                            // Between siblings: decided by what comes next
+                            pending.push(comment);
+                        } else {
+                            captured.push(frame.anchor, comment);
+                        }
+                    }
+                }
+            }
+            Event::PI(ref pi) if stack.is_empty() => {
+                let content = String::from_utf8_lossy(pi.as_ref()).into_owned();
+                let (target, data) = match content.split_once(char::is_whitespace) {
+                    Some((target, data)) => (target.to_string(), Some(data.trim().to_string())),
+                    None => (content, None),
+                };
+                captured.processing_instructions.push(ProcessingInstruction::new(target, data));
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(captured)
+}
+
+impl CapturedComments {
+    fn entry(&mut self, anchor: Anchor) -> Option<&mut Entry> {
+        match anchor {
+            Anchor::Release(i) => self.releases.get_mut(i),
+            Anchor::Resource(i) => self.resources.get_mut(i),
+            Anchor::Deal(i) => self.deals.get_mut(i),
+            Anchor::Message | Anchor::Header => None,
+        }
+    }
+
+    fn push(&mut self, anchor: Anchor, comment: Comment) {
+        match anchor {
+            Anchor::Message => self.message.push(comment),
+            Anchor::Header => self.header.push(comment),
+            _ => {
+                if let Some(entry) = self.entry(anchor) {
+                    entry.comments.push(comment);
+                }
+            }
+        }
+    }
+
+    /// Attach the captured comments to the matching graph nodes
+    ///
+    /// Entries are matched by reference first and by document order when the
+    /// reference is missing or unknown.
+    pub fn attach(self, graph: &mut ERNMessage) {
+        fn assign<T>(
+            nodes: &mut [T],
+            entries: Vec<Entry>,
+            reference: impl Fn(&T) -> Option<&str>,
+            comments: impl Fn(&mut T) -> &mut Option<Vec<Comment>>,
+        ) {
+            for (index, entry) in entries.into_iter().enumerate() {
+                if entry.comments.is_empty() {
+                    continue;
+                }
+                let by_reference = entry.reference.as_deref().and_then(|r| {
+                    nodes.iter().position(|n| reference(n) == Some(r))
+                });
+                if let Some(node) = by_reference.or(Some(index)).and_then(|i| nodes.get_mut(i)) {
+                    comments(node).get_or_insert_with(Vec::new).extend(entry.comments);
+                }
+            }
+        }
+
+        assign(&mut graph.releases, self.releases, |r| Some(r.release_reference.as_str()), |r| &mut r.comments);
+        assign(&mut graph.resources, self.resources, |r| Some(r.resource_reference.as_str()), |r| &mut r.comments);
+        assign(&mut graph.deals, self.deals, |d| d.deal_reference.as_deref(), |d| &mut d.comments);
+
+        if !self.header.is_empty() {
+            graph.message_header.comments.get_or_insert_with(Vec::new).extend(self.header);
+        }
+        if !self.message.is_empty() {
+            graph.comments.get_or_insert_with(Vec::new).extend(self.message);
+        }
+        if !self.processing_instructions.is_empty() {
+            graph.extensions
+                .get_or_insert_with(Extensions::new)
+                .document_processing_instructions
+                .extend(self.processing_instructions);
+        }
+    }
+}
+
+/// Incremental byte offset to line/column conversion
+#[derive(Default)]
+struct LineIndex {
+    offset: usize,
+    line: usize,
+    line_start: usize,
+}
+
+impl LineIndex {
+    fn locate(&mut self, xml: &str, offset: usize) -> (usize, usize) {
+        for (i, byte) in xml.as_bytes()[self.offset..offset].iter().enumerate() {
+            if *byte == b'\n' {
+                self.line += 1;
+                self.line_start = self.offset + i + 1;
+            }
+        }
+        self.offset = offset;
+        (self.line + 1, offset - self.line_start + 1)
+    }
+}
@@ -20,6 +20,8 @@ impl VersionDetector {
             Ok(ERNVersion::V4_2)
         } else if xml_str.contains("http://ddex.net/xml/ern/43") {
             Ok(ERNVersion::V4_3)
+        } else if xml_str.contains("http://ddex.net/xml/ern/44") {
+            Ok(ERNVersion::V4_4)
         } else {
             // Default to latest
             Ok(ERNVersion::V4_3)
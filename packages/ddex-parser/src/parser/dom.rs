@@ -9,7 +9,6 @@ use crate::parser::ParseOptions;
 use crate::parser::namespace_detector::{NamespaceDetector, NamespaceContext};
 use crate::transform::{graph::GraphBuilder, flatten::Flattener};
 use std::io::{BufRead, Seek, SeekFrom};
-use std::time::Instant;
 
 /// Parse using DOM for smaller files
 pub fn parse_dom<R: BufRead + Seek>(
@@ -17,13 +16,6 @@ pub fn parse_dom<R: BufRead + Seek>(
     version: ERNVersion,
     options: ParseOptions,
 ) -> Result<ParsedERNMessage, ParseError> {
-    let start = Instant::now();
-    
-    // Check timeout
-    if !options.allow_blocking && options.timeout_ms > 0 {
-        // Would implement timeout checking
-    }
-    
     // First pass: detect namespaces
     let mut namespace_detector = NamespaceDetector::new();
     let namespace_result = namespace_detector.detect_from_xml(&mut reader)?;
@@ -33,8 +25,12 @@ pub fn parse_dom<R: BufRead + Seek>(
     reader.seek(SeekFrom::Start(0))?;
     
     // Build graph model from XML with namespace context
-    let graph_builder = GraphBuilder::new(version);
+    let graph_builder = GraphBuilder::new(version)
+        .with_cancellation(options.cancellation.clone())
+        .with_interning(options.intern_strings);
     let graph = graph_builder.build_from_xml_with_context(reader, namespace_context)?;
+    let normalizations = graph_builder.take_warnings();
+    let interning = graph_builder.interning_stats();
     
     // Optionally resolve references
     let graph = if options.resolve_references {
@@ -44,20 +40,15 @@ pub fn parse_dom<R: BufRead + Seek>(
     };
     
     // Flatten to developer-friendly model
-    let flat = Flattener::flatten(graph.clone());
-    
-    // Check elapsed time
-    let elapsed = start.elapsed();
-    if elapsed.as_millis() > options.timeout_ms as u128 {
-        return Err(ParseError::Timeout {
-            seconds: elapsed.as_secs(),
-        });
-    }
+    let flat = Flattener::flatten_for(graph.clone(), &options.preferred_languages);
     
     Ok(ParsedERNMessage {
         graph,
         flat,
         extensions: None,
+        raw_fragments: None,
+        normalizations,
+        interning,
     })
 }
 
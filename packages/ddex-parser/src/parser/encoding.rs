@@ -0,0 +1,173 @@
+// core/src/parser/encoding.rs
+//! Character encoding detection and transcoding to UTF-8
+//!
+//! The encoding comes from the byte order mark, then from the XML declaration,
+//! and defaults to UTF-8. UTF-16 (either byte order) and ISO-8859-1 documents
+//! are transcoded before parsing and their declaration is rewritten to UTF-8,
+//! so byte offsets reported later refer to the transcoded text.
+
+use crate::error::ParseError;
+use std::borrow::Cow;
+
+/// Encoding of an input document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+}
+
+impl Encoding {
+    pub fn name(self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Utf16Le => "UTF-16LE",
+            Encoding::Utf16Be => "UTF-16BE",
+            Encoding::Latin1 => "ISO-8859-1",
+        }
+    }
+
+    fn error(self, message: impl Into<String>) -> ParseError {
+        ParseError::Encoding {
+            encoding: self.name().to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Detect the encoding from the first bytes of a document
+pub fn detect_encoding(prefix: &[u8]) -> Result<Encoding, ParseError> {
+    match prefix {
+        [0xEF, 0xBB, 0xBF, ..] => return Ok(Encoding::Utf8),
+        [0xFF, 0xFE, ..] | [b'<', 0, b'?', 0, ..] => return Ok(Encoding::Utf16Le),
+        [0xFE, 0xFF, ..] | [0, b'<', 0, b'?', ..] => return Ok(Encoding::Utf16Be),
+        _ => {}
+    }
+
+    match declared_encoding(prefix) {
+        None => Ok(Encoding::Utf8),
+        Some(label) => match label.to_ascii_lowercase().as_str() {
+            "utf-8" | "utf8" | "us-ascii" | "ascii" => Ok(Encoding::Utf8),
+            "iso-8859-1" | "iso8859-1" | "iso_8859-1" | "latin1" | "latin-1" | "l1" => Ok(Encoding::Latin1),
+            // A UTF-16 label on single-byte text: the file was re-saved
+            // without updating its declaration
+            "utf-16" | "utf-16le" | "utf-16be" => Ok(Encoding::Utf8),
+            _ => Err(ParseError::Encoding {
+                encoding: label,
+                message: "unsupported encoding; supply UTF-8, UTF-16 or ISO-8859-1".to_string(),
+            }),
+        },
+    }
+}
+
+/// Decode a whole document to UTF-8, borrowing it when it already is
+pub fn to_utf8(bytes: &[u8]) -> Result<Cow<'_, str>, ParseError> {
+    let encoding = detect_encoding(bytes)?;
+    match encoding {
+        Encoding::Utf8 => {
+            let bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+            std::str::from_utf8(bytes).map(Cow::Borrowed).map_err(|e| {
+                encoding.error(format!("invalid byte sequence at offset {}", e.valid_up_to()))
+            })
+        }
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            let bytes = match bytes {
+                [0xFF, 0xFE, rest @ ..] | [0xFE, 0xFF, rest @ ..] => rest,
+                _ => bytes,
+            };
+            if bytes.len() % 2 != 0 {
+                return Err(encoding.error("odd number of bytes"));
+            }
+            let units = bytes.chunks_exact(2).map(|pair| match encoding {
+                Encoding::Utf16Le => u16::from_le_bytes([pair[0], pair[1]]),
+                _ => u16::from_be_bytes([pair[0], pair[1]]),
+            });
+            let text = char::decode_utf16(units)
+                .collect::<Result<String, _>>()
+                .map_err(|e| encoding.error(format!("unpaired surrogate 0x{:04X}", e.unpaired_surrogate())))?;
+            Ok(Cow::Owned(declare_utf8(text)))
+        }
+        Encoding::Latin1 => {
+            let text = bytes.iter().map(|&b| b as char).collect();
+            Ok(Cow::Owned(declare_utf8(text)))
+        }
+    }
+}
+
+/// The `encoding` pseudo-attribute of an ASCII-compatible XML declaration
+fn declared_encoding(prefix: &[u8]) -> Option<String> {
+    let declaration = prefix.strip_prefix(b"<?xml")?;
+    let end = declaration.windows(2).position(|w| w == b"?>")?;
+    let declaration = std::str::from_utf8(&declaration[..end]).ok()?;
+    let (_, value) = declaration.split_once("encoding")?;
+    let value = value.trim_start().strip_prefix('=')?.trim_start();
+    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let value = &value[1..];
+    Some(value[..value.find(quote)?].to_string())
+}
+
+/// Make the declaration of transcoded text say what it now is
+fn declare_utf8(mut text: String) -> String {
+    if !text.starts_with("<?xml") {
+        return text;
+    }
+    let Some(end) = text.find("?>") else { return text };
+    let Some(at) = text[..end].find("encoding") else { return text };
+    let value_start = text[at..end].find(['"', '\'']).map(|i| at + i + 1);
+    if let Some(start) = value_start {
+        let quote = text.as_bytes()[start - 1] as char;
+        if let Some(len) = text[start..end].find(quote) {
+            text.replace_range(start..start + len, "UTF-8");
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(text: &str, little_endian: bool) -> Vec<u8> {
+        let mut bytes = if little_endian { vec![0xFF, 0xFE] } else { vec![0xFE, 0xFF] };
+        for unit in text.encode_utf16() {
+            bytes.extend(if little_endian { unit.to_le_bytes() } else { unit.to_be_bytes() });
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_detects_bom_and_declaration() {
+        assert_eq!(detect_encoding(b"<Root/>").unwrap(), Encoding::Utf8);
+        assert_eq!(detect_encoding(b"\xEF\xBB\xBF<Root/>").unwrap(), Encoding::Utf8);
+        assert_eq!(detect_encoding(&utf16("<Root/>", true)).unwrap(), Encoding::Utf16Le);
+        assert_eq!(detect_encoding(&utf16("<Root/>", false)).unwrap(), Encoding::Utf16Be);
+        assert_eq!(detect_encoding(b"<\0?\0x\0").unwrap(), Encoding::Utf16Le);
+        assert_eq!(
+            detect_encoding(b"<?xml version=\"1.0\" encoding='ISO-8859-1'?><Root/>").unwrap(),
+            Encoding::Latin1
+        );
+        assert!(matches!(
+            detect_encoding(b"<?xml version=\"1.0\" encoding=\"Shift_JIS\"?><Root/>"),
+            Err(ParseError::Encoding { encoding, .. }) if encoding == "Shift_JIS"
+        ));
+    }
+
+    #[test]
+    fn test_transcodes_to_utf8_and_rewrites_declaration() {
+        let xml = "<?xml version=\"1.0\" encoding=\"UTF-16\"?><Title>Beyoncé – Déjà Vu</Title>";
+        for little_endian in [true, false] {
+            let bytes = utf16(xml, little_endian);
+            assert_eq!(to_utf8(&bytes).unwrap(), xml.replace("UTF-16", "UTF-8"));
+        }
+
+        let latin1 = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><Title>Caf\xE9</Title>";
+        assert_eq!(
+            to_utf8(latin1).unwrap(),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><Title>Café</Title>"
+        );
+
+        assert!(matches!(to_utf8(b"<Root/>").unwrap(), Cow::Borrowed("<Root/>")));
+        assert!(to_utf8(b"<Title>Caf\xE9</Title>").is_err());
+    }
+}
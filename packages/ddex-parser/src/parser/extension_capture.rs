@@ -4,7 +4,7 @@
 //! not part of the standard DDEX schema, enabling perfect round-trip fidelity for
 //! documents containing proprietary extensions.
 
-use ddex_core::models::{Extensions, XmlFragment, ProcessingInstruction, Comment, CommentPosition, extensions::utils};
+use ddex_core::models::{Extensions, XmlFragment, XmlNode, ProcessingInstruction, Comment, CommentPosition, extensions::utils};
 use quick_xml::{Reader, events::{Event, BytesStart, BytesEnd, BytesText}};
 use indexmap::IndexMap;
 
@@ -105,11 +105,12 @@ impl ExtensionCaptureContext {
         namespace_prefix: Option<&str>,
     ) {
         self.in_extension = true;
-        self.extension_depth = 1;
+        // The root's own start tag brings the depth to 1
+        self.extension_depth = 0;
         self.extension_buffer.clear();
 
         self.current_extension = Some(XmlFragment::with_namespace(
-            element_name.to_string(),
+            utils::extract_local_name(element_name).to_string(),
             namespace_uri.map(String::from),
             namespace_prefix.map(String::from),
             String::new(), // Will be filled as we parse
@@ -179,12 +180,12 @@ impl ExtensionCaptureContext {
         }
 
         let text = event.unescape().unwrap_or_default();
-        self.extension_buffer.push_str(&text);
+        self.extension_buffer.push_str(&String::from_utf8_lossy(event));
         
         // If this is simple text content, store it in the fragment
         if let Some(ref mut ext) = self.current_extension {
-            if ext.children.is_empty() {
-                ext.text_content = Some(text.to_string());
+            if ext.children().next().is_none() {
+                ext.content = vec![XmlNode::Text(text.into_owned())];
             }
         }
     }
@@ -194,6 +195,12 @@ impl ExtensionCaptureContext {
         if let Some(mut extension) = self.current_extension.take() {
             extension.raw_content = self.extension_buffer.clone();
             
+            // Expose the nested elements through the fragment's mini-DOM
+            if let Ok(tree) = XmlFragment::from_xml_in_scope(&extension.raw_content, &self.namespace_context) {
+                extension.content = tree.content;
+                extension.namespace_declarations = tree.namespace_declarations;
+            }
+            
             // Generate location key
             let namespace_uri = extension.namespace_uri.as_deref();
             let location_key = utils::generate_location_key(
@@ -249,7 +256,7 @@ impl ExtensionCaptureContext {
         } else {
             // Element-level comment - add to current extension or buffer for later association
             if let Some(ref mut ext) = self.current_extension {
-                ext.comments.push(comment_struct);
+                ext.add_comment(comment_struct);
             } else {
                 // Store for later association with the next element
                 self.extensions.add_document_comment_structured(comment_struct);
@@ -331,6 +338,23 @@ impl ExtensionAwareParser {
                         self.context.enter_element(element_name);
                     }
                 },
+                Ok(Event::Empty(ref e)) => {
+                    let element_name_bytes = e.name();
+                    let element_name = std::str::from_utf8(element_name_bytes.as_ref()).unwrap_or("unknown");
+                    let (namespace_uri, namespace_prefix) = self.extract_namespace_info(e);
+                    
+                    if self.context.should_capture_element(element_name, namespace_uri.as_deref()) {
+                        if !self.context.in_extension {
+                            self.context.start_extension_capture(
+                                element_name,
+                                namespace_uri.as_deref(),
+                                namespace_prefix.as_deref(),
+                            );
+                        }
+                        self.context.process_extension_start_tag(e);
+                        self.context.process_extension_end_tag(&e.to_end());
+                    }
+                },
                 Ok(Event::End(ref e)) => {
                     if self.context.in_extension {
                         self.context.process_extension_end_tag(e);
@@ -0,0 +1,165 @@
+// core/src/parser/mead.rs
+//! Parser for MEAD (Media Enrichment and Description) messages
+
+use crate::error::{ErrorLocation, ParseError};
+use ddex_core::models::common::{Identifier, IdentifierType, LocalizedString};
+use ddex_core::models::mead::{Award, FocusTrack, MeadMessage, ReleaseInformation};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::io::BufRead;
+
+/// Parse a MEAD 1.1 message
+pub fn parse_mead<R: BufRead>(reader: R) -> Result<MeadMessage, ParseError> {
+    let mut xml_reader = Reader::from_reader(reader);
+    xml_reader.config_mut().trim_text(true);
+
+    let mut message = MeadMessage::default();
+    let mut path: Vec<String> = Vec::new();
+    let mut attributes: Vec<(Option<String>, Option<String>)> = Vec::new();
+    let mut buf = Vec::new();
+
+    loop {
+        match xml_reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                if path.is_empty() {
+                    check_root(&name, &xml_reader)?;
+                }
+
+                match name.as_str() {
+                    "ReleaseInformation" => message.release_information.push(ReleaseInformation::default()),
+                    "FocusTrack" => {
+                        if let Some(info) = message.release_information.last_mut() {
+                            info.focus_tracks.push(FocusTrack::default());
+                        }
+                    }
+                    "Award" => {
+                        if let Some(info) = message.release_information.last_mut() {
+                            info.awards.push(Award::default());
+                        }
+                    }
+                    _ => {}
+                }
+
+                attributes.push((attribute(e, "Namespace"), attribute(e, "LanguageAndScriptCode")));
+                path.push(name);
+            }
+            Ok(Event::Text(ref t)) => {
+                let text = t.unescape().map_err(|err| ParseError::XmlError {
+                    message: err.to_string(),
+                    location: location(&xml_reader, &path),
+                })?.into_owned();
+                let (namespace, language) = attributes.last().cloned().unwrap_or((None, None));
+                apply_text(&mut message, &path, text, namespace, language);
+            }
+            Ok(Event::Empty(ref e)) if path.is_empty() => {
+                check_root(&String::from_utf8_lossy(e.local_name().as_ref()), &xml_reader)?;
+            }
+            Ok(Event::End(_)) => {
+                path.pop();
+                attributes.pop();
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(ParseError::XmlError {
+                    message: e.to_string(),
+                    location: location(&xml_reader, &path),
+                });
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(message)
+}
+
+fn check_root<R>(name: &str, reader: &Reader<R>) -> Result<(), ParseError> {
+    if name == "MeadMessage" {
+        return Ok(());
+    }
+    Err(ParseError::XmlError {
+        message: format!("Expected MeadMessage root element, found {}", name),
+        location: location(reader, &[]),
+    })
+}
+
+fn apply_text(
+    message: &mut MeadMessage,
+    path: &[String],
+    text: String,
+    namespace: Option<String>,
+    language: Option<String>,
+) {
+    let path: Vec<&str> = path.iter().skip(1).map(String::as_str).collect();
+
+    match path.as_slice() {
+        ["MessageHeader", "MessageId"] => message.message_id = text,
+        ["MessageHeader", "MessageThreadId"] => message.message_thread_id = Some(text),
+        ["MessageHeader", "MessageCreatedDateTime"] => message.message_created_date_time = Some(text),
+        ["MessageHeader", "MessageSender", "PartyId"] => message.message_sender = Some(text),
+        ["MessageHeader", "MessageRecipient", "PartyId"] => message.message_recipient = Some(text),
+        ["ReleaseInformationList", "ReleaseInformation", rest @ ..] => {
+            let Some(info) = message.release_information.last_mut() else { return };
+            match rest {
+                ["ReleaseSummary", "ReleaseId", id_type] => info.release_id.push(Identifier {
+                    id_type: identifier_type(id_type),
+                    namespace,
+                    value: text,
+                }),
+                ["Mood"] => info.moods.push(text),
+                ["FocusTrack", "ResourceId", "ISRC"] => {
+                    if let Some(track) = info.focus_tracks.last_mut() {
+                        track.isrc = text;
+                    }
+                }
+                ["FocusTrack", "Title"] => {
+                    if let Some(track) = info.focus_tracks.last_mut() {
+                        track.title = Some(text);
+                    }
+                }
+                ["MarketingComment"] => info.marketing_comments.push(LocalizedString {
+                    text,
+                    language_code: language,
+                    script: None,
+                }),
+                ["Award", field] => {
+                    let Some(award) = info.awards.last_mut() else { return };
+                    match *field {
+                        "AwardName" => award.name = text,
+                        "AwardCategory" => award.category = Some(text),
+                        "Date" => award.date = Some(text),
+                        "IsWinner" => award.is_winner = text.parse().ok(),
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+        _ => {}
+    }
+}
+
+pub(crate) fn identifier_type(element: &str) -> IdentifierType {
+    match element {
+        "ICPN" | "UPC" => IdentifierType::UPC,
+        "EAN" => IdentifierType::EAN,
+        "GRid" => IdentifierType::GRid,
+        "ISRC" => IdentifierType::ISRC,
+        _ => IdentifierType::Proprietary,
+    }
+}
+
+fn attribute(element: &BytesStart, name: &str) -> Option<String> {
+    element.try_get_attribute(name).ok().flatten()
+        .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+}
+
+fn location<R>(reader: &Reader<R>, path: &[String]) -> ErrorLocation {
+    ErrorLocation {
+        line: 0,
+        column: 0,
+        byte_offset: Some(reader.buffer_position() as usize),
+        path: format!("/{}", path.join("/")),
+    }
+}
@@ -2,6 +2,7 @@
 //! Parser module
 
 pub mod detector;
+pub mod encoding;
 pub mod security;
 pub mod stream;
 pub mod mode;
@@ -9,12 +10,18 @@ pub mod dom;
 pub mod extension_capture;
 pub mod namespace_detector;
 pub mod attribute_extractor;
+pub mod mead;
+pub mod borrowed;
+pub mod raw;
+pub mod comments;
 
 #[cfg(test)]
 mod tests;
 
 use crate::error::ParseError;
+use ddex_core::cancel::CancellationToken;
 use ddex_core::models::flat::ParsedERNMessage;
+use ddex_core::telemetry;
 use std::io::BufRead;
 
 /// Main parser options
@@ -24,13 +31,39 @@ pub struct ParseOptions {
     pub auto_threshold: u64,
     pub resolve_references: bool,
     pub include_raw: bool,
+    /// Bytes the streaming parser may hold in its buffer and collected
+    /// releases before failing with `MemoryLimitExceeded`; also caps the
+    /// body of `parse_url`
     pub max_memory: usize,
+    /// Stop with `Cancelled` once this many milliseconds have passed; `0`
+    /// disables the deadline
     pub timeout_ms: u64,
     pub allow_blocking: bool,
     pub include_raw_extensions: bool,
     pub include_comments: bool,
     pub preserve_unknown_elements: bool,
     pub chunk_size: usize,
+    /// Validate the document against the bundled DDEX schema before parsing
+    pub validate_schema: bool,
+    /// Entity, depth and attribute limits; `None` uses the parser's
+    /// configuration (or `SecurityConfig::default()` for `parse`)
+    pub security: Option<security::SecurityConfig>,
+    /// Reject documents without a valid enveloped XMLDSig signature by this key
+    #[cfg(feature = "signature")]
+    pub verify_signature: Option<std::sync::Arc<dyn ddex_core::signature::VerifyingKey>>,
+    /// Fail instead of returning a message whose values the parser had to
+    /// change (see `ParsedERNMessage::normalizations`)
+    pub fail_on_warning: bool,
+    /// Stop with `Cancelled` once this token is cancelled from elsewhere
+    pub cancellation: Option<CancellationToken>,
+    /// Share repeated element names and values (territory codes, artist and
+    /// label names) while the DOM parser collects fields, reporting the
+    /// savings in `ParsedERNMessage::interning`
+    pub intern_strings: bool,
+    /// Locales to pick the flattened view's default titles and names in,
+    /// most preferred first, e.g. `["ja-JP", "en"]`; without a match the
+    /// untagged title wins, then the first one
+    pub preferred_languages: Vec<String>,
 }
 
 impl Default for ParseOptions {
@@ -47,6 +80,14 @@ impl Default for ParseOptions {
             include_raw_extensions: false,
             include_comments: false,
             preserve_unknown_elements: false,
+            validate_schema: false,
+            security: None,
+            #[cfg(feature = "signature")]
+            verify_signature: None,
+            fail_on_warning: false,
+            cancellation: None,
+            intern_strings: false,
+            preferred_languages: Vec::new(),
         }
     }
 }
@@ -56,16 +97,112 @@ pub fn parse<R: BufRead + std::io::Seek>(
     mut reader: R,
     options: ParseOptions,
 ) -> Result<ParsedERNMessage, ParseError> {
+    let bytes = reader.seek(std::io::SeekFrom::End(0))?;
+    reader.seek(std::io::SeekFrom::Start(0))?;
+    let span = tracing::info_span!("ddex.parse", bytes, releases = tracing::field::Empty);
+    let _entered = span.enter();
+
+    let result = parse_document(reader, options);
+    match &result {
+        Ok(message) => {
+            span.record("releases", message.graph.releases.len());
+            telemetry::record_bytes_processed("parse", bytes);
+            telemetry::record_releases_parsed(message.graph.releases.len() as u64);
+        }
+        Err(ParseError::SchemaViolation { violations }) => {
+            telemetry::record_validation_failures("schema", violations.len() as u64);
+        }
+        Err(_) => {}
+    }
+    result
+}
+
+fn parse_document<R: BufRead + std::io::Seek>(
+    reader: R,
+    mut options: ParseOptions,
+) -> Result<ParsedERNMessage, ParseError> {
+    // One token carries both the caller's cancellation and the deadline
+    let token = options.cancellation.take().unwrap_or_default();
+    options.cancellation = Some(if options.timeout_ms > 0 {
+        token.with_timeout(std::time::Duration::from_millis(options.timeout_ms))
+    } else {
+        token
+    });
+    parse_with_token(reader, options)
+}
+
+/// Fail with `Cancelled` if `token` says to stop, reporting the progress made
+pub(crate) fn check_cancelled(
+    token: Option<&CancellationToken>,
+    bytes_processed: u64,
+    releases_parsed: usize,
+) -> Result<(), ParseError> {
+    match token.and_then(CancellationToken::reason) {
+        Some(reason) => Err(ParseError::Cancelled { reason, bytes_processed, releases_parsed }),
+        None => Ok(()),
+    }
+}
+
+fn parse_with_token<R: BufRead + std::io::Seek>(
+    mut reader: R,
+    options: ParseOptions,
+) -> Result<ParsedERNMessage, ParseError> {
+    // Transcode legacy encodings before anything reads the document
+    if encoding::detect_encoding(reader.fill_buf()?)? != encoding::Encoding::Utf8 {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let xml = encoding::to_utf8(&bytes)?.into_owned();
+        return parse_with_token(std::io::Cursor::new(xml.into_bytes()), options);
+    }
+    
+    // Enforce security limits before anything interprets the document
+    let security_config = options.security.clone().unwrap_or_default();
+    security::check_document(&mut reader, &security_config)?;
+    reader.seek(std::io::SeekFrom::Start(0))?;
+    check_cancelled(options.cancellation.as_ref(), 0, 0)?;
+    
+    #[cfg(feature = "signature")]
+    if let Some(key) = &options.verify_signature {
+        let mut xml = String::new();
+        reader.read_to_string(&mut xml)?;
+        ddex_core::signature::verify(&xml, key.as_ref())?;
+        reader.seek(std::io::SeekFrom::Start(0))?;
+    }
+    
     // Detect version first
     let version = detector::VersionDetector::detect(&mut reader)?;
     reader.seek(std::io::SeekFrom::Start(0))?;
     
+    if options.validate_schema {
+        let mut xml = String::new();
+        reader.read_to_string(&mut xml)?;
+        let violations = ddex_core::schema::validate(&xml, version);
+        if !violations.is_empty() {
+            return Err(ParseError::SchemaViolation { violations });
+        }
+        reader.seek(std::io::SeekFrom::Start(0))?;
+        check_cancelled(options.cancellation.as_ref(), 0, 0)?;
+    }
+    
+    // Keep the source text for the passes that run after parsing
+    let (include_raw, include_comments) = (options.include_raw, options.include_comments);
+    let source = if include_raw || include_comments {
+        let mut xml = String::new();
+        reader.read_to_string(&mut xml)?;
+        reader.seek(std::io::SeekFrom::Start(0))?;
+        Some(xml)
+    } else {
+        None
+    };
+    
+    let fail_on_warning = options.fail_on_warning;
+    
     // Select parsing mode
     let mode_selector = mode::ModeSelector::new(options.auto_threshold);
     let selected_mode = mode_selector.select_mode(&mut reader, options.mode)?;
     reader.seek(std::io::SeekFrom::Start(0))?;
     
-    match selected_mode {
+    let mut result = match selected_mode {
         mode::ParseMode::Dom => {
             // Use DOM parser for smaller files
             dom::parse_dom(reader, version, options)
@@ -75,7 +212,24 @@ pub fn parse<R: BufRead + std::io::Seek>(
             stream::parse_streaming(reader, version, options)
         }
         mode::ParseMode::Auto => unreachable!(), // Already resolved
+    }?;
+    
+    if let Some(xml) = source {
+        if include_raw {
+            result.raw_fragments = Some(raw::capture_raw_fragments(&xml)?);
+        }
+        if include_comments {
+            comments::capture_comments(&xml)?.attach(&mut result.graph);
+        }
+    }
+    
+    if fail_on_warning && !result.normalizations.is_empty() {
+        return Err(ParseError::FailedOnWarning {
+            warnings: result.normalizations.iter().map(ToString::to_string).collect(),
+        });
     }
+    
+    Ok(result)
 }
 
 pub mod version_ext;
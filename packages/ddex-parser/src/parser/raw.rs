@@ -0,0 +1,139 @@
+// core/src/parser/raw.rs
+//! Retention of the original XML for releases, resources and deals
+
+use crate::error::{ErrorLocation, ParseError};
+use ddex_core::models::flat::{RawFragment, RawFragments};
+use indexmap::IndexMap;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+/// Which list an element belongs to, and the child naming its reference
+#[derive(Clone, Copy)]
+enum Kind {
+    Release,
+    Resource,
+    Deal,
+}
+
+impl Kind {
+    fn reference_element(self) -> &'static [u8] {
+        match self {
+            Kind::Release => b"ReleaseReference",
+            Kind::Resource => b"ResourceReference",
+            Kind::Deal => b"DealReference",
+        }
+    }
+
+    fn fallback_prefix(self) -> &'static str {
+        match self {
+            Kind::Release => "release",
+            Kind::Resource => "resource",
+            Kind::Deal => "deal",
+        }
+    }
+}
+
+/// An element whose end tag has not been reached yet
+struct Open {
+    kind: Kind,
+    start: usize,
+    depth: usize,
+    reference: Option<String>,
+    in_reference: bool,
+}
+
+/// Locate every release, resource and deal in `xml` and keep its source text
+pub fn capture_raw_fragments(xml: &str) -> Result<RawFragments, ParseError> {
+    let mut reader = Reader::from_str(xml);
+    let mut fragments = RawFragments::default();
+    let mut list: Option<Kind> = None;
+    let mut open: Option<Open> = None;
+    let mut depth = 0usize;
+
+    loop {
+        let start = reader.buffer_position() as usize;
+        let event = reader.read_event().map_err(|e| ParseError::XmlError {
+            message: e.to_string(),
+            location: ErrorLocation {
+                line: 0,
+                column: 0,
+                byte_offset: Some(start),
+                path: "raw".to_string(),
+            },
+        })?;
+
+        match event {
+            Event::Start(ref e) => {
+                depth += 1;
+                let name = e.local_name();
+                match (&mut open, list) {
+                    (Some(current), _) => {
+                        // Only the entity's own, first reference child names it
+                        current.in_reference = depth == current.depth + 1
+                            && current.reference.is_none()
+                            && name.as_ref() == current.kind.reference_element();
+                    }
+                    (None, Some(kind)) if is_entity(kind, name.as_ref()) => {
+                        open = Some(Open { kind, start, depth, reference: None, in_reference: false });
+                    }
+                    (None, _) => {
+                        list = match name.as_ref() {
+                            b"ReleaseList" => Some(Kind::Release),
+                            b"ResourceList" => Some(Kind::Resource),
+                            b"DealList" => Some(Kind::Deal),
+                            _ => list,
+                        };
+                    }
+                }
+            }
+            Event::Text(ref t) => {
+                if let Some(current) = open.as_mut().filter(|o| o.in_reference) {
+                    let text = t.unescape().unwrap_or_default();
+                    current.reference.get_or_insert_with(String::new).push_str(text.trim());
+                }
+            }
+            Event::End(ref e) => {
+                if let Some(current) = open.as_mut() {
+                    current.in_reference = false;
+                }
+                if open.as_ref().is_some_and(|o| o.depth == depth) {
+                    let current = open.take().unwrap_or_else(|| unreachable!());
+                    let end = reader.buffer_position() as usize;
+                    let target = match current.kind {
+                        Kind::Release => &mut fragments.releases,
+                        Kind::Resource => &mut fragments.resources,
+                        Kind::Deal => &mut fragments.deals,
+                    };
+                    insert(target, current, end, xml);
+                } else if open.is_none() && matches!(e.local_name().as_ref(), b"ReleaseList" | b"ResourceList" | b"DealList") {
+                    list = None;
+                }
+                depth = depth.saturating_sub(1);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(fragments)
+}
+
+fn is_entity(kind: Kind, name: &[u8]) -> bool {
+    match kind {
+        Kind::Release => matches!(name, b"Release" | b"TrackRelease"),
+        Kind::Resource => matches!(name, b"SoundRecording" | b"Video" | b"Image" | b"Text" | b"SheetMusic"),
+        // Each Deal of a ReleaseDeal has its own DealReference, as in the graph
+        Kind::Deal => name == b"Deal",
+    }
+}
+
+fn insert(target: &mut IndexMap<String, RawFragment>, current: Open, end: usize, xml: &str) {
+    let key = current.reference
+        .filter(|r| !r.is_empty())
+        .unwrap_or_else(|| format!("{}_{}", current.kind.fallback_prefix(), target.len() + 1));
+    target.insert(key, RawFragment {
+        start: current.start,
+        end,
+        xml: xml[current.start..end].to_string(),
+    });
+}
@@ -1,5 +1,23 @@
+use crate::error::{ErrorLocation, ParseError};
+use ddex_core::error::DDEXError;
+use ddex_core::security::entity_classifier::{
+    parse_entity_declarations, ClassifierConfig, EntityClassifier, EntityTable,
+};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::io::BufRead;
 use std::time::Duration;
 
+/// Policy for entities declared with a SYSTEM or PUBLIC identifier
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExternalEntityPolicy {
+    /// Reject every external entity
+    Deny,
+    /// Accept external entities whose system identifier starts with one of
+    /// these prefixes
+    AllowList(Vec<String>),
+}
+
 /// Security configuration for XML parsing
 #[derive(Debug, Clone)]
 pub struct SecurityConfig {
@@ -8,10 +26,14 @@ pub struct SecurityConfig {
     pub disable_external_entities: bool,
     pub max_entity_expansions: usize,
     pub max_entity_depth: usize,
+    /// Which external (SYSTEM/PUBLIC) entities may be declared when
+    /// `disable_external_entities` is off
+    pub external_entity_policy: ExternalEntityPolicy,
     
     // Size limits
     pub max_element_depth: usize,
     pub max_attribute_size: usize,
+    pub max_attribute_count: usize,
     pub max_text_size: usize,
     pub max_file_size: usize,
     
@@ -38,8 +60,10 @@ impl SecurityConfig {
             disable_external_entities: true,
             max_entity_expansions: 1000,
             max_entity_depth: 20,
+            external_entity_policy: ExternalEntityPolicy::Deny,
             max_element_depth: 100,
             max_attribute_size: 100 * 1024,  // 100KB
+            max_attribute_count: 100,
             max_text_size: 1024 * 1024,      // 1MB
             max_file_size: 1024 * 1024 * 1024, // 1GB
             parse_timeout: Duration::from_secs(30),
@@ -63,4 +87,151 @@ impl SecurityConfig {
             ..Self::strict()
         }
     }
-}
\ No newline at end of file
+    
+    /// Entity limits and external entity policy for the shared classifier
+    fn classifier_config(&self) -> ClassifierConfig {
+        let allow_list = match &self.external_entity_policy {
+            ExternalEntityPolicy::Deny => None,
+            ExternalEntityPolicy::AllowList(prefixes) => Some(prefixes.clone()),
+        };
+        ClassifierConfig {
+            max_depth: self.max_entity_depth,
+            allow_external_entities: !self.disable_external_entities && allow_list.is_some(),
+            external_allow_list: allow_list,
+            ..ClassifierConfig::default()
+        }
+    }
+}
+
+/// Check a document against the configured limits before it is parsed
+///
+/// Scans DTD entity declarations, entity references in text and attributes,
+/// element nesting and attribute counts and sizes. Entities go through the
+/// `ddex_core` entity classifier the builder also uses. Violations are
+/// reported as `DDEXError::SecurityViolation` naming the limit that was hit.
+pub fn check_document<R: BufRead>(reader: R, config: &SecurityConfig) -> Result<(), ParseError> {
+    let mut xml = Reader::from_reader(reader);
+    let mut buf = Vec::new();
+    let mut depth = 0usize;
+    let classifier = EntityClassifier::with_config(config.classifier_config());
+    let mut entities = EntityTable::default();
+    let mut expansions = 0u64;
+
+    loop {
+        let event = xml.read_event_into(&mut buf).map_err(|e| ParseError::XmlError {
+            message: e.to_string(),
+            location: ErrorLocation {
+                line: 0,
+                column: 0,
+                byte_offset: Some(xml.buffer_position() as usize),
+                path: "security".to_string(),
+            },
+        })?;
+
+        match event {
+            Event::DocType(ref doctype) => {
+                entities = declared_entities(&classifier, &String::from_utf8_lossy(doctype), config)?;
+            }
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let opens = matches!(event, Event::Start(_));
+                if depth + 1 > config.max_element_depth {
+                    return Err(violation(
+                        "max_element_depth",
+                        format!("Element nesting exceeds {} levels", config.max_element_depth),
+                    ));
+                }
+                for value in check_attributes(e, config)? {
+                    expansions += expansions_in(&mut entities, &value)?;
+                }
+                if opens {
+                    depth += 1;
+                }
+            }
+            Event::End(_) => depth = depth.saturating_sub(1),
+            Event::Text(ref t) => {
+                if t.len() > config.max_text_size {
+                    return Err(violation(
+                        "max_text_size",
+                        format!("Text node of {} bytes exceeds {}", t.len(), config.max_text_size),
+                    ));
+                }
+                expansions += expansions_in(&mut entities, &String::from_utf8_lossy(t))?;
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+
+        if expansions > config.max_entity_expansions as u64 {
+            return Err(violation(
+                "max_entity_expansions",
+                format!("Entity expansions exceed {}", config.max_entity_expansions),
+            ));
+        }
+        buf.clear();
+    }
+
+    Ok(())
+}
+
+/// Entities declared in the DTD, once the classifier has accepted them
+fn declared_entities(
+    classifier: &EntityClassifier,
+    doctype: &str,
+    config: &SecurityConfig,
+) -> Result<EntityTable, ParseError> {
+    if config.disable_dtd && doctype.contains("<!ENTITY") {
+        return Err(violation("disable_dtd", "DTD entity declarations are disabled".to_string()));
+    }
+    let declared = parse_entity_declarations(doctype);
+    for entity in declared.iter().filter(|entity| entity.system_id.is_some()) {
+        if !classifier.allows_external(entity) {
+            return Err(violation(
+                "external_entity_policy",
+                format!(
+                    "External entity '{}' ({}) is not allowed",
+                    entity.name,
+                    entity.system_id.as_deref().unwrap_or_default()
+                ),
+            ));
+        }
+    }
+    Ok(classifier.expansion_table(&declared))
+}
+
+/// Expansions caused by the entity references in `text`
+fn expansions_in(entities: &mut EntityTable, text: &str) -> Result<u64, ParseError> {
+    entities.expansions_in(text).map_err(|e| violation("max_entity_depth", e.to_string()))
+}
+
+fn violation(limit: &str, message: String) -> ParseError {
+    ParseError::Core(DDEXError::SecurityViolation {
+        limit: limit.to_string(),
+        message,
+    })
+}
+
+/// Enforce attribute count and size, returning the raw attribute values
+fn check_attributes(element: &BytesStart, config: &SecurityConfig) -> Result<Vec<String>, ParseError> {
+    let mut values = Vec::new();
+    for attribute in element.attributes().with_checks(false).flatten() {
+        if attribute.value.len() > config.max_attribute_size {
+            return Err(violation(
+                "max_attribute_size",
+                format!("Attribute of {} bytes exceeds {}", attribute.value.len(), config.max_attribute_size),
+            ));
+        }
+        values.push(String::from_utf8_lossy(&attribute.value).into_owned());
+    }
+    if values.len() > config.max_attribute_count {
+        return Err(violation(
+            "max_attribute_count",
+            format!(
+                "Element <{}> has {} attributes, more than {}",
+                String::from_utf8_lossy(element.name().as_ref()),
+                values.len(),
+                config.max_attribute_count
+            ),
+        ));
+    }
+    Ok(values)
+}
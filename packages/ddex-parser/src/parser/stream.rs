@@ -7,6 +7,8 @@ use ddex_core::models::flat::ParsedERNMessage;
 use ddex_core::models::versions::ERNVersion;
 use crate::parser::ParseOptions;
 use crate::transform::flatten::Flattener;
+use ddex_core::cancel::CancellationToken;
+use ddex_core::memory::MemoryTracker;
 use quick_xml::events::Event;
 use quick_xml::Reader;
 use std::io::BufRead;
@@ -20,6 +22,8 @@ pub struct ParseProgress {
     pub resources_parsed: usize,
     pub elapsed: Duration,
     pub estimated_total_bytes: Option<u64>,
+    /// Most bytes held so far by the read buffer and retained releases
+    pub peak_memory_bytes: usize,
 }
 
 /// Streaming parser for memory-efficient processing
@@ -32,8 +36,13 @@ pub struct StreamingParser<R: BufRead> {
     releases_parsed: usize,
     resources_parsed: usize,
     chunk_size: usize,
-    max_memory: usize,
+    memory: MemoryTracker,
+    /// Read buffer capacity already accounted in `memory`
+    buffer_accounted: usize,
     buffer: Vec<u8>,
+    /// Whether the reader is positioned inside the `ReleaseList`
+    in_release_list: bool,
+    cancellation: Option<CancellationToken>,
 }
 
 impl<R: BufRead> StreamingParser<R> {
@@ -52,8 +61,11 @@ impl<R: BufRead> StreamingParser<R> {
             releases_parsed: 0,
             resources_parsed: 0,
             chunk_size: 100,
-            max_memory: 100 * 1024 * 1024, // 100MB default
+            memory: MemoryTracker::new(100 * 1024 * 1024), // 100MB default
+            buffer_accounted: 0,
             buffer: Vec::with_capacity(8192),
+            in_release_list: false,
+            cancellation: None,
         }
     }
     
@@ -70,11 +82,45 @@ impl<R: BufRead> StreamingParser<R> {
         self
     }
     
+    /// Fail with `MemoryLimitExceeded` once the read buffer, the release
+    /// being assembled and releases kept with [`ReleaseIterator::keep_last`]
+    /// would need more than `max` bytes
     pub fn with_max_memory(mut self, max: usize) -> Self {
-        self.max_memory = max;
+        self.memory = MemoryTracker::new(max);
+        self.buffer_accounted = 0;
+        self
+    }
+    
+    /// Stop with `Cancelled` at the next element once `token` says so
+    pub fn with_cancellation(mut self, token: Option<CancellationToken>) -> Self {
+        self.cancellation = token;
         self
     }
     
+    fn check_cancelled(&self) -> Result<(), ParseError> {
+        crate::parser::check_cancelled(
+            self.cancellation.as_ref(),
+            self.reader.buffer_position() as u64,
+            self.releases_parsed,
+        )
+    }
+    
+    /// Most bytes held at any one time so far
+    pub fn peak_memory(&self) -> usize {
+        self.memory.peak()
+    }
+    
+    /// Charge any growth of the read buffer, which keeps its capacity
+    /// between events
+    fn account_buffer(&mut self) -> Result<(), ParseError> {
+        let capacity = self.buffer.capacity();
+        if capacity != self.buffer_accounted {
+            self.memory.resize(self.buffer_accounted, capacity)?;
+            self.buffer_accounted = capacity;
+        }
+        Ok(())
+    }
+    
     fn update_progress(&mut self) {
         if let Some(ref mut callback) = self.progress_callback {
             let progress = ParseProgress {
@@ -83,6 +129,7 @@ impl<R: BufRead> StreamingParser<R> {
                 resources_parsed: self.resources_parsed,
                 elapsed: self.start_time.elapsed(),
                 estimated_total_bytes: None,
+                peak_memory_bytes: self.memory.peak(),
             };
             callback(progress);
         }
@@ -94,13 +141,20 @@ impl<R: BufRead> StreamingParser<R> {
     
     /// Parse the message header
     pub fn parse_header(&mut self) -> Result<MessageHeader, ParseError> {
+        use ddex_core::models::graph::MessageType;
+        
         self.buffer.clear();
+        let mut message_type = None;
         
-        // Skip to MessageHeader element
+        // Skip to MessageHeader element, noting the root element on the way
         loop {
             match self.reader.read_event_into(&mut self.buffer) {
                 Ok(Event::Start(ref e)) if e.name().as_ref() == b"MessageHeader" => {
-                    return self.parse_message_header_element();
+                    let message_type = message_type.unwrap_or(MessageType::NewReleaseMessage);
+                    return self.parse_message_header_element(message_type);
+                }
+                Ok(Event::Start(ref e)) if message_type.is_none() => {
+                    message_type = MessageType::from_root_element(&String::from_utf8_lossy(e.name().as_ref()));
                 }
                 Ok(Event::Eof) => {
                     return Err(ParseError::XmlError {
@@ -125,11 +179,13 @@ impl<R: BufRead> StreamingParser<R> {
         }
     }
     
-    fn parse_message_header_element(&mut self) -> Result<MessageHeader, ParseError> {
-        use ddex_core::models::graph::{MessageType, MessageSender, MessageRecipient};
+    fn parse_message_header_element(
+        &mut self,
+        message_type: ddex_core::models::graph::MessageType,
+    ) -> Result<MessageHeader, ParseError> {
+        use ddex_core::models::graph::{MessageSender, MessageRecipient};
         
         let mut message_id = String::new();
-        let message_type = MessageType::NewReleaseMessage;
         let mut created_date_time = chrono::Utc::now();
         let mut sender = MessageSender {
             party_id: Vec::new(),
@@ -405,7 +461,8 @@ impl<R: BufRead> StreamingParser<R> {
 pub struct ReleaseIterator<'a, R: BufRead> {
     parser: &'a mut StreamingParser<R>,
     done: bool,
-    in_release_list: bool,
+    /// Estimated size of the release last yielded
+    last_size: usize,
 }
 
 impl<'a, R: BufRead> ReleaseIterator<'a, R> {
@@ -413,28 +470,42 @@ impl<'a, R: BufRead> ReleaseIterator<'a, R> {
         Self {
             parser,
             done: false,
-            in_release_list: false,
+            last_size: 0,
         }
     }
     
+    /// Count the release last yielded against the memory ceiling for the
+    /// rest of the parse, for callers that collect releases rather than
+    /// handling them one at a time
+    pub fn keep_last(&mut self) -> Result<(), ParseError> {
+        self.parser.memory.allocate(std::mem::take(&mut self.last_size))?;
+        Ok(())
+    }
+    
     fn find_next_release(&mut self) -> Result<Option<Release>, ParseError> {
         loop {
             self.parser.buffer.clear();
+            self.parser.check_cancelled()?;
             match self.parser.reader.read_event_into(&mut self.parser.buffer) {
                 Ok(Event::Start(ref e)) => {
-                    match e.name().as_ref() {
+                    // Descend through the message until the ReleaseList is
+                    // reached, then skip whatever is not a Release
+                    match e.local_name().as_ref() {
                         b"ReleaseList" => {
-                            self.in_release_list = true;
+                            self.parser.in_release_list = true;
                         }
-                        b"Release" if self.in_release_list => {
-                            return self.parse_release_element();
+                        b"Release" if self.parser.in_release_list => {
+                            let start = self.parser.reader.buffer_position();
+                            return self.parse_release_element(start);
                         }
-                        _ => {
+                        _ if self.parser.in_release_list => {
                             self.parser.skip_element()?;
                         }
+                        _ => {}
                     }
                 }
-                Ok(Event::End(ref e)) if e.name().as_ref() == b"ReleaseList" => {
+                Ok(Event::End(ref e)) if e.local_name().as_ref() == b"ReleaseList" => {
+                    self.parser.in_release_list = false;
                     self.done = true;
                     return Ok(None);
                 }
@@ -453,7 +524,7 @@ impl<'a, R: BufRead> ReleaseIterator<'a, R> {
         }
     }
     
-    fn parse_release_element(&mut self) -> Result<Option<Release>, ParseError> {
+    fn parse_release_element(&mut self, start: u64) -> Result<Option<Release>, ParseError> {
         use ddex_core::models::common::LocalizedString;
         
         let mut release = Release {
@@ -476,9 +547,10 @@ impl<'a, R: BufRead> ReleaseIterator<'a, R> {
         
         self.parser.buffer.clear();
         loop {
+            self.parser.check_cancelled()?;
             match self.parser.reader.read_event_into(&mut self.parser.buffer) {
                 Ok(Event::Start(ref e)) => {
-                    match e.name().as_ref() {
+                    match e.local_name().as_ref() {
                         b"ReleaseReference" => {
                             release.release_reference = self.parser.read_text_element()?;
                         }
@@ -491,26 +563,39 @@ impl<'a, R: BufRead> ReleaseIterator<'a, R> {
                         }
                     }
                 }
-                Ok(Event::End(ref e)) if e.name().as_ref() == b"Release" => {
+                Ok(Event::End(ref e)) if e.local_name().as_ref() == b"Release" => {
                     break;
                 }
+                Ok(Event::Eof) => {
+                    return Err(ParseError::XmlError {
+                        message: "unexpected end of document inside Release".to_string(),
+                        location: self.parser.get_current_location(),
+                    });
+                }
+                Err(e) => {
+                    return Err(ParseError::XmlError {
+                        message: e.to_string(),
+                        location: self.parser.get_current_location(),
+                    });
+                }
                 _ => {}
             }
+            self.parser.account_buffer()?;
             self.parser.buffer.clear();
         }
         
+        // The model holds at most the text of its element, so the bytes read
+        // bound its heap; it only counts while held here unless kept
+        let size = std::mem::size_of::<Release>()
+            + (self.parser.reader.buffer_position() - start) as usize;
+        self.parser.memory.allocate(size)?;
+        self.parser.memory.free(size);
+        self.last_size = size;
+        
         self.parser.releases_parsed += 1;
         self.parser.update_byte_position();
         self.parser.update_progress();
         
-        // Check memory limit
-        let estimated_size = std::mem::size_of::<Release>() * self.parser.releases_parsed;
-        if estimated_size > self.parser.max_memory {
-            return Err(ParseError::SecurityViolation {
-                message: format!("Memory limit exceeded: {} > {}", estimated_size, self.parser.max_memory),
-            });
-        }
-        
         // Yield control periodically
         if self.parser.releases_parsed % self.parser.chunk_size == 0 {
             std::thread::yield_now();
@@ -531,7 +616,11 @@ impl<'a, R: BufRead> Iterator for ReleaseIterator<'a, R> {
         match self.find_next_release() {
             Ok(Some(release)) => Some(Ok(release)),
             Ok(None) => None,
-            Err(e) => Some(Err(e)),
+            Err(e) => {
+                // A cancelled parse reports it once instead of on every call
+                self.done = matches!(e, ParseError::Cancelled { .. });
+                Some(Err(e))
+            }
         }
     }
 }
@@ -614,7 +703,8 @@ pub fn parse_streaming<R: BufRead>(
 ) -> Result<ParsedERNMessage, ParseError> {
     let mut parser = StreamingParser::new(reader, version)
         .with_chunk_size(options.chunk_size)
-        .with_max_memory(options.max_memory);
+        .with_max_memory(options.max_memory)
+        .with_cancellation(options.cancellation);
     
     // Parse header first
     let message_header = parser.parse_header()?;
@@ -625,10 +715,11 @@ pub fn parse_streaming<R: BufRead>(
     let mut parties = Vec::new();
     let mut deals = Vec::new();
     
-    // Stream releases
-    for release_result in parser.stream_releases() {
-        let release = release_result?;
-        releases.push(release);
+    // Stream releases, keeping each against the memory ceiling
+    let mut release_stream = parser.stream_releases();
+    while let Some(release_result) = release_stream.next() {
+        releases.push(release_result?);
+        release_stream.keep_last()?;
     }
     
     // Stream resources
@@ -656,6 +747,7 @@ pub fn parse_streaming<R: BufRead>(
         resources,
         releases,
         deals,
+        purged_releases: Vec::new(),
         version,
         profile: None,
         message_audit_trail: None,
@@ -666,7 +758,7 @@ pub fn parse_streaming<R: BufRead>(
     };
     
     // Flatten to developer-friendly model
-    let flat = Flattener::flatten(graph.clone());
+    let flat = Flattener::flatten_for(graph.clone(), &options.preferred_languages);
     
-    Ok(ParsedERNMessage { graph, flat, extensions: None })
+    Ok(ParsedERNMessage { graph, flat, extensions: None, raw_fragments: None, normalizations: Default::default(), interning: None })
 }
\ No newline at end of file
@@ -15,6 +15,16 @@ mod tests {
         assert_eq!(version, ERNVersion::V4_3);
     }
     
+    #[test]
+    fn test_version_detection_44() {
+        let xml = r#"<?xml version="1.0"?>
+<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/44">
+</ern:NewReleaseMessage>"#;
+        
+        let version = VersionDetector::detect(Cursor::new(xml)).unwrap();
+        assert_eq!(version, ERNVersion::V4_4);
+    }
+    
     #[test]
     fn test_version_detection_42() {
         let xml = r#"<?xml version="1.0"?>
@@ -49,4 +59,576 @@ mod tests {
         assert!(config.disable_dtd); // Still secure
         assert_eq!(config.max_element_depth, 200); // But more permissive
     }
+    
+    #[test]
+    fn test_security_limits_reject_hostile_documents() {
+        use crate::error::ParseError;
+        use crate::parser::security::ExternalEntityPolicy;
+        use crate::parser::ParseOptions;
+        use ddex_core::error::DDEXError;
+        
+        fn limit_hit(xml: &str, config: SecurityConfig) -> Option<String> {
+            let options = ParseOptions { security: Some(config), ..Default::default() };
+            match crate::parser::parse(Cursor::new(xml), options) {
+                Err(ParseError::Core(DDEXError::SecurityViolation { limit, .. })) => Some(limit),
+                _ => None,
+            }
+        }
+        let with_dtd = SecurityConfig { disable_dtd: false, ..SecurityConfig::strict() };
+        
+        let laughs = r#"<?xml version="1.0"?>
+<!DOCTYPE lolz [
+  <!ENTITY lol "lol">
+  <!ENTITY lol1 "&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;">
+  <!ENTITY lol2 "&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;">
+  <!ENTITY lol3 "&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;">
+]>
+<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">&lol3;</ern:NewReleaseMessage>"#;
+        assert_eq!(limit_hit(laughs, SecurityConfig::strict()).as_deref(), Some("disable_dtd"));
+        assert_eq!(limit_hit(laughs, with_dtd.clone()).as_deref(), Some("max_entity_expansions"));
+        
+        let xxe = r#"<?xml version="1.0"?>
+<!DOCTYPE foo [ <!ENTITY xxe SYSTEM "file:///etc/passwd"> ]>
+<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">&xxe;</ern:NewReleaseMessage>"#;
+        let allow = |prefix: &str| SecurityConfig {
+            disable_external_entities: false,
+            external_entity_policy: ExternalEntityPolicy::AllowList(vec![prefix.to_string()]),
+            ..with_dtd.clone()
+        };
+        assert_eq!(limit_hit(xxe, with_dtd.clone()).as_deref(), Some("external_entity_policy"));
+        assert_eq!(limit_hit(xxe, allow("https://ddex.net/")).as_deref(), Some("external_entity_policy"));
+        assert_eq!(limit_hit(xxe, allow("file:///etc/")), None);
+        
+        let deep = format!("<ern:NewReleaseMessage xmlns:ern=\"http://ddex.net/xml/ern/43\">{}{}</ern:NewReleaseMessage>", "<a>".repeat(10), "</a>".repeat(10));
+        let shallow = SecurityConfig { max_element_depth: 5, ..SecurityConfig::strict() };
+        assert_eq!(limit_hit(&deep, shallow).as_deref(), Some("max_element_depth"));
+        
+        let crowded = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43" a="1" b="2" c="3"/>"#;
+        let few = SecurityConfig { max_attribute_count: 2, ..SecurityConfig::strict() };
+        assert_eq!(limit_hit(crowded, few).as_deref(), Some("max_attribute_count"));
+    }
+    
+    #[test]
+    fn test_validate_schema_option_rejects_invalid_documents() {
+        use crate::error::ParseError;
+        use crate::parser::ParseOptions;
+        
+        let xml = r#"<?xml version="1.0"?>
+<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43" MessageSchemaVersionId="ern/43">
+  <MessageHeader>
+    <MessageId>MSG001</MessageId>
+  </MessageHeader>
+</ern:NewReleaseMessage>"#;
+        
+        let options = ParseOptions {
+            validate_schema: true,
+            ..Default::default()
+        };
+        match crate::parser::parse(Cursor::new(xml), options) {
+            Err(ParseError::SchemaViolation { violations }) => {
+                assert!(violations.iter().any(|v| v.message.contains("MessageSender")));
+                assert!(violations.iter().all(|v| v.location.line > 0));
+            }
+            other => panic!("expected schema violations, got {:?}", other.map(|_| ())),
+        }
+    }
+    
+    #[test]
+    fn test_purge_release_message_actions() {
+        use crate::parser::ParseOptions;
+        use ddex_core::models::common::IdentifierType;
+        use ddex_core::models::graph::{MessageType, UpdateAction};
+        
+        let xml = r#"<?xml version="1.0"?>
+<ern:PurgeReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43" MessageSchemaVersionId="ern/43">
+  <MessageHeader>
+    <MessageId>MSG002</MessageId>
+  </MessageHeader>
+  <PurgedRelease>
+    <ReleaseId>
+      <ICPN>0123456789012</ICPN>
+      <ProprietaryId Namespace="DPID:PADPIDA0000000001">CAT-42</ProprietaryId>
+    </ReleaseId>
+    <Title>
+      <TitleText>Withdrawn Album</TitleText>
+    </Title>
+  </PurgedRelease>
+</ern:PurgeReleaseMessage>"#;
+        
+        let parsed = crate::parser::parse(Cursor::new(xml), ParseOptions::default()).unwrap();
+        assert_eq!(parsed.message_type(), &MessageType::PurgeReleaseMessage);
+        assert_eq!(parsed.flat.message_type, "PurgeReleaseMessage");
+        
+        let purged = &parsed.graph.purged_releases[0];
+        assert_eq!(purged.title[0].text, "Withdrawn Album");
+        assert_eq!(purged.release_id[0].id_type, IdentifierType::UPC);
+        assert_eq!(purged.release_id[1].namespace.as_deref(), Some("DPID:PADPIDA0000000001"));
+        
+        match parsed.update_actions() {
+            [UpdateAction::PurgeRelease { release_id }] => assert_eq!(release_id[0].value, "0123456789012"),
+            other => panic!("expected a single purge, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_update_release_message_actions() {
+        use crate::parser::ParseOptions;
+        use ddex_core::models::graph::{MessageType, UpdateAction};
+        
+        let xml = r#"<?xml version="1.0"?>
+<ern:UpdateReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43" MessageSchemaVersionId="ern/43">
+  <MessageHeader>
+    <MessageId>MSG003</MessageId>
+  </MessageHeader>
+  <ResourceList>
+    <SoundRecording>
+      <ResourceReference>A1</ResourceReference>
+    </SoundRecording>
+  </ResourceList>
+  <ReleaseList>
+    <Release>
+      <ReleaseReference>R1</ReleaseReference>
+    </Release>
+  </ReleaseList>
+  <DealList>
+    <ReleaseDeal>
+      <DealReleaseReference>R1</DealReleaseReference>
+      <Deal>
+        <DealReference>D1</DealReference>
+      </Deal>
+    </ReleaseDeal>
+  </DealList>
+</ern:UpdateReleaseMessage>"#;
+        
+        let parsed = crate::parser::parse(Cursor::new(xml), ParseOptions::default()).unwrap();
+        assert_eq!(parsed.message_type(), &MessageType::UpdateReleaseMessage);
+        assert_eq!(parsed.update_actions(), &[
+            UpdateAction::UpsertRelease { release_reference: "R1".to_string() },
+            UpdateAction::UpsertResource { resource_reference: "A1".to_string() },
+            UpdateAction::UpsertDeal {
+                deal_reference: Some("D1".to_string()),
+                release_references: vec!["R1".to_string()],
+            },
+        ]);
+    }
+    
+    #[test]
+    fn test_parse_mead_message() {
+        use crate::DDEXParser;
+        use ddex_core::models::common::IdentifierType;
+        
+        let xml = r#"<?xml version="1.0"?>
+<mead:MeadMessage xmlns:mead="http://ddex.net/xml/mead/11" MessageSchemaVersionId="mead/11">
+  <MessageHeader>
+    <MessageThreadId>MEAD-001</MessageThreadId>
+    <MessageId>MEAD-001</MessageId>
+    <MessageSender><PartyId>PADPIDA0000000001</PartyId></MessageSender>
+    <MessageCreatedDateTime>2024-01-01T00:00:00Z</MessageCreatedDateTime>
+  </MessageHeader>
+  <ReleaseInformationList>
+    <ReleaseInformation>
+      <ReleaseSummary><ReleaseId><ICPN>0123456789012</ICPN></ReleaseId></ReleaseSummary>
+      <Mood>Uplifting</Mood>
+      <Mood>Energetic</Mood>
+      <FocusTrack>
+        <ResourceId><ISRC>USRC17607839</ISRC></ResourceId>
+        <Title>Lead Single</Title>
+      </FocusTrack>
+      <MarketingComment LanguageAndScriptCode="en">Debut album</MarketingComment>
+      <Award>
+        <AwardName>Best New Artist</AwardName>
+        <AwardCategory>Newcomer</AwardCategory>
+        <IsWinner>true</IsWinner>
+      </Award>
+    </ReleaseInformation>
+  </ReleaseInformationList>
+</mead:MeadMessage>"#;
+        
+        let mead = DDEXParser::new().parse_mead(Cursor::new(xml)).unwrap();
+        assert_eq!(mead.message_id, "MEAD-001");
+        assert_eq!(mead.message_sender.as_deref(), Some("PADPIDA0000000001"));
+        
+        let info = &mead.release_information[0];
+        assert_eq!(info.release_id[0].id_type, IdentifierType::UPC);
+        assert_eq!(info.moods, vec!["Uplifting", "Energetic"]);
+        assert_eq!(info.focus_tracks[0].isrc, "USRC17607839");
+        assert_eq!(info.focus_tracks[0].title.as_deref(), Some("Lead Single"));
+        assert_eq!(info.marketing_comments[0].language_code.as_deref(), Some("en"));
+        assert_eq!(info.awards[0].category.as_deref(), Some("Newcomer"));
+        assert_eq!(info.awards[0].is_winner, Some(true));
+        
+        let ern = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43"/>"#;
+        assert!(DDEXParser::new().parse_mead(Cursor::new(ern)).is_err());
+    }
+    
+    #[test]
+    fn test_captured_extensions_expose_mini_dom() {
+        use crate::parser::extension_capture::capture_utils::extract_extensions;
+        
+        let xml = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43" xmlns:sp="http://spotify.com/ddex">
+  <ReleaseList>
+    <Release>
+      <sp:Promo Priority="high">
+        <sp:Playlist Name="Hits"/>
+        <sp:Week>12</sp:Week>
+      </sp:Promo>
+    </Release>
+  </ReleaseList>
+</ern:NewReleaseMessage>"#;
+        
+        let extensions = extract_extensions(xml).unwrap();
+        let promos = extensions.get_ns("http://spotify.com/ddex");
+        assert_eq!(promos.len(), 1);
+        
+        let promo = promos[0];
+        assert_eq!(promo.element_name, "Promo");
+        assert_eq!(promo.qualified_name(), "sp:Promo");
+        assert_eq!(promo.attribute("Priority"), Some("high"));
+        assert_eq!(promo.child("Playlist").and_then(|p| p.attribute("Name")), Some("Hits"));
+        assert_eq!(promo.child("Week").and_then(|w| w.text()).as_deref(), Some("12"));
+        assert!(promo.child("Week").unwrap().is_from_namespace("http://spotify.com/ddex"));
+    }
+    
+    #[test]
+    fn test_include_raw_retains_source_fragments() {
+        use crate::parser::ParseOptions;
+        
+        let release = r#"<Release>
+      <ReleaseReference>R1</ReleaseReference>
+      <Title><TitleText>Raw &amp; Real</TitleText></Title>
+    </Release>"#;
+        let xml = format!(r#"<?xml version="1.0"?>
+<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43" MessageSchemaVersionId="ern/43">
+  <MessageHeader>
+    <MessageId>MSG003</MessageId>
+  </MessageHeader>
+  <ResourceList>
+    <SoundRecording>
+      <ResourceReference>A1</ResourceReference>
+    </SoundRecording>
+  </ResourceList>
+  <ReleaseList>
+    {}
+  </ReleaseList>
+  <DealList>
+    <ReleaseDeal>
+      <DealReleaseReference>R1</DealReleaseReference>
+      <Deal>
+        <DealReference>D1</DealReference>
+      </Deal>
+      <Deal>
+        <DealReference>D2</DealReference>
+      </Deal>
+    </ReleaseDeal>
+    <ReleaseDeal>
+      <DealReleaseReference>R1</DealReleaseReference>
+      <Deal>
+        <DealTerms/>
+      </Deal>
+    </ReleaseDeal>
+  </DealList>
+</ern:NewReleaseMessage>"#, release);
+        
+        let parsed = crate::parser::parse(Cursor::new(xml.as_str()), ParseOptions::default()).unwrap();
+        assert!(parsed.raw_fragments.is_none());
+        
+        let options = ParseOptions { include_raw: true, ..Default::default() };
+        let parsed = crate::parser::parse(Cursor::new(xml.as_str()), options).unwrap();
+        
+        let raw = parsed.raw_release("R1").unwrap();
+        assert_eq!(raw.xml, release);
+        assert_eq!(&xml[raw.start..raw.end], release);
+        assert!(parsed.raw_resource("A1").unwrap().xml.starts_with("<SoundRecording>"));
+        let d1 = parsed.raw_deal("D1").unwrap();
+        assert!(d1.xml.starts_with("<Deal>") && d1.xml.contains("<DealReference>D1</DealReference>"));
+        assert!(!d1.xml.contains("D2"));
+        assert!(parsed.raw_deal("D2").unwrap().xml.contains("<DealReference>D2</DealReference>"));
+        assert!(parsed.raw_deal("D1D2").is_none());
+        assert!(parsed.raw_deal("deal_3").unwrap().xml.ends_with("</Deal>"));
+    }
+    
+    #[test]
+    fn test_include_comments_anchors_comments_to_graph_nodes() {
+        use crate::parser::ParseOptions;
+        use ddex_core::models::CommentPosition;
+        
+        let xml = r#"<?xml version="1.0"?>
+<?ddex-tool name="ingest"?>
+<!-- generated upstream -->
+<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43" MessageSchemaVersionId="ern/43">
+  <MessageHeader>
+    <MessageId>MSG004</MessageId>
+  </MessageHeader>
+  <ResourceList>
+    <SoundRecording>
+      <!-- master from 1998 -->
+      <ResourceReference>A1</ResourceReference>
+    </SoundRecording>
+  </ResourceList>
+  <ReleaseList>
+    <!-- main album -->
+    <Release>
+      <ReleaseReference>R1</ReleaseReference>
+      <!-- title pending -->
+    </Release>
+  </ReleaseList>
+  <DealList>
+    <ReleaseDeal>
+      <DealReleaseReference>R1</DealReleaseReference>
+    </ReleaseDeal>
+    <!-- more deals next week -->
+  </DealList>
+</ern:NewReleaseMessage>"#;
+        
+        let parsed = crate::parser::parse(Cursor::new(xml), ParseOptions::default()).unwrap();
+        assert!(parsed.graph.releases[0].comments.is_none());
+        
+        let options = ParseOptions { include_comments: true, ..Default::default() };
+        let graph = crate::parser::parse(Cursor::new(xml), options).unwrap().graph;
+        
+        let document = graph.comments.as_ref().unwrap();
+        assert_eq!(document[0].content.trim(), "generated upstream");
+        assert_eq!(document[0].position, CommentPosition::Before);
+        assert_eq!(document[0].line_number, Some(3));
+        assert_eq!(graph.extensions.as_ref().unwrap().document_processing_instructions[0].target, "ddex-tool");
+        
+        let release = graph.releases[0].comments.as_ref().unwrap();
+        assert_eq!(release.len(), 2);
+        assert_eq!(release[0].position, CommentPosition::Before);
+        assert_eq!(release[0].content.trim(), "main album");
+        assert_eq!(release[1].position, CommentPosition::LastChild);
+        assert_eq!(release[1].xpath.as_deref(), Some("/NewReleaseMessage/ReleaseList/Release"));
+        
+        let resource = graph.resources[0].comments.as_ref().unwrap();
+        assert_eq!(resource[0].position, CommentPosition::FirstChild);
+        
+        let deal = graph.deals[0].comments.as_ref().unwrap();
+        assert_eq!(deal[0].position, CommentPosition::After);
+        assert_eq!(deal[0].content.trim(), "more deals next week");
+    }
+    
+    #[test]
+    fn test_utf16_documents_are_transcoded() {
+        use crate::parser::ParseOptions;
+        
+        let xml = r#"<?xml version="1.0" encoding="UTF-16"?>
+<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/382">
+  <MessageHeader/>
+</ern:NewReleaseMessage>"#;
+        let mut utf16 = vec![0xFF, 0xFE];
+        for unit in xml.encode_utf16() {
+            utf16.extend(unit.to_le_bytes());
+        }
+        
+        let options = ParseOptions { include_raw: true, ..Default::default() };
+        let parsed = crate::parser::parse(Cursor::new(utf16), options).unwrap();
+        assert_eq!(parsed.graph.version, ERNVersion::V3_8_2);
+    }
+    
+    #[test]
+    fn test_stream_releases_resumes_inside_release_list() {
+        use crate::parser::stream::StreamingParser;
+        
+        let xml = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+  <MessageHeader><MessageId>M1</MessageId></MessageHeader>
+  <ResourceList><SoundRecording><ResourceReference>A1</ResourceReference></SoundRecording></ResourceList>
+  <ReleaseList>
+    <Release><ReleaseReference>R1</ReleaseReference></Release>
+    <TrackRelease><ReleaseReference>R2</ReleaseReference></TrackRelease>
+    <Release><ReleaseReference>R3</ReleaseReference></Release>
+  </ReleaseList>
+</ern:NewReleaseMessage>"#;
+        
+        let mut parser = StreamingParser::new(Cursor::new(xml.as_bytes()), ERNVersion::V4_3);
+        let mut references = Vec::new();
+        // A fresh iterator per release, as DDEXParser::stream does
+        while let Some(release) = parser.stream_releases().next() {
+            references.push(release.unwrap().release_reference);
+        }
+        assert_eq!(references, vec!["R1", "R3"]);
+    }
+    
+    #[test]
+    fn test_streaming_memory_ceiling() {
+        use crate::error::ParseError;
+        use crate::parser::stream::parse_streaming;
+        use crate::parser::ParseOptions;
+        
+        let releases: String = (0..50)
+            .map(|i| format!("<Release><ReleaseReference>R{}</ReleaseReference><ReferenceTitle>Title {}</ReferenceTitle></Release>", i, i))
+            .collect();
+        let xml = format!(r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+  <MessageHeader><MessageId>M1</MessageId></MessageHeader>
+  <ReleaseList>{}</ReleaseList>
+</ern:NewReleaseMessage>"#, releases);
+        
+        let parsed = parse_streaming(Cursor::new(xml.as_bytes()), ERNVersion::V4_3, ParseOptions::default()).unwrap();
+        assert_eq!(parsed.graph.releases.len(), 50);
+        
+        // Room for the read buffer and ten releases, not all fifty
+        let ceiling = 16 * 1024 + 10 * (std::mem::size_of::<ddex_core::models::graph::Release>() + 120);
+        let options = ParseOptions { max_memory: ceiling, ..Default::default() };
+        let error = parse_streaming(Cursor::new(xml.as_bytes()), ERNVersion::V4_3, options).unwrap_err();
+        assert!(matches!(error, ParseError::MemoryLimitExceeded { .. }));
+        assert_eq!(error.code(), "DDEX-P-0702");
+        
+        // Streaming one at a time stays under the same ceiling
+        let mut parser = crate::parser::stream::StreamingParser::new(Cursor::new(xml.as_bytes()), ERNVersion::V4_3)
+            .with_max_memory(ceiling);
+        let mut count = 0;
+        while let Some(release) = parser.stream_releases().next() {
+            release.unwrap();
+            count += 1;
+        }
+        assert_eq!(count, 50);
+        assert!(parser.peak_memory() > 0 && parser.peak_memory() <= ceiling);
+    }
+    
+    #[test]
+    fn test_cancellation_reports_progress() {
+        use crate::error::ParseError;
+        use crate::parser::stream::StreamingParser;
+        use crate::parser::ParseOptions;
+        use ddex_core::cancel::{CancelReason, CancellationToken};
+        
+        let releases: String = (0..5)
+            .map(|i| format!("<Release><ReleaseReference>R{}</ReleaseReference></Release>", i))
+            .collect();
+        let xml = format!(r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+  <MessageHeader><MessageId>M1</MessageId></MessageHeader>
+  <ReleaseList>{}</ReleaseList>
+</ern:NewReleaseMessage>"#, releases);
+        
+        // Cancelled from the progress callback once two releases are out
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        let mut parser = StreamingParser::new(Cursor::new(xml.as_bytes()), ERNVersion::V4_3)
+            .with_cancellation(Some(token))
+            .with_progress_callback(move |progress| {
+                if progress.releases_parsed == 2 {
+                    canceller.cancel();
+                }
+            });
+        let results: Vec<_> = parser.stream_releases().collect();
+        assert_eq!(results.len(), 3);
+        match &results[2] {
+            Err(ParseError::Cancelled { reason, bytes_processed, releases_parsed }) => {
+                assert_eq!(*reason, CancelReason::Requested);
+                assert_eq!(*releases_parsed, 2);
+                assert!(*bytes_processed > 0);
+            }
+            other => panic!("expected Cancelled, got {:?}", other),
+        }
+        
+        let token = CancellationToken::new();
+        token.cancel();
+        let options = ParseOptions { cancellation: Some(token), ..Default::default() };
+        let error = crate::parser::parse(Cursor::new(xml.as_bytes()), options).unwrap_err();
+        assert_eq!(error.code(), "DDEX-P-0605");
+    }
+    
+    #[test]
+    fn test_trimmed_text_is_recorded() {
+        use crate::parser::{mode::ParseMode, ParseOptions};
+        use ddex_core::warnings::NormalizationKind;
+        
+        let xml = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+  <MessageHeader><MessageId>M1</MessageId></MessageHeader>
+  <ReleaseList>
+    <Release><ReleaseReference> R1 </ReleaseReference></Release>
+  </ReleaseList>
+</ern:NewReleaseMessage>"#;
+        
+        let options = ParseOptions { mode: ParseMode::Dom, ..Default::default() };
+        let parsed = crate::parser::parse(Cursor::new(xml), options).unwrap();
+        assert_eq!(parsed.graph.releases[0].release_reference, "R1");
+        let warning = parsed.normalizations.iter().next().unwrap();
+        assert_eq!(warning.kind, NormalizationKind::TrimmedWhitespace);
+        assert_eq!(warning.path, "Release/ReleaseReference");
+        assert_eq!((warning.before.as_str(), warning.after.as_deref()), (" R1 ", Some("R1")));
+        
+        let options = ParseOptions { mode: ParseMode::Dom, fail_on_warning: true, ..Default::default() };
+        let error = crate::parser::parse(Cursor::new(xml), options).unwrap_err();
+        assert_eq!(error.code(), "DDEX-P-0203");
+    }
+    
+    #[test]
+    fn test_interning_shares_repeated_values() {
+        use crate::parser::{mode::ParseMode, ParseOptions};
+        
+        let releases: String = (0..3)
+            .map(|i| format!(
+                "<Release><ReleaseReference>R{}</ReleaseReference>\
+                 <ReferenceTitle><TitleText>Greatest Hits</TitleText></ReferenceTitle></Release>",
+                i
+            ))
+            .collect();
+        let xml = format!(r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+  <MessageHeader><MessageId>M1</MessageId></MessageHeader>
+  <ReleaseList>{}</ReleaseList>
+</ern:NewReleaseMessage>"#, releases);
+        
+        let options = ParseOptions { mode: ParseMode::Dom, ..Default::default() };
+        let plain = crate::parser::parse(Cursor::new(xml.as_bytes()), options).unwrap();
+        assert!(plain.interning.is_none());
+        
+        let options = ParseOptions { mode: ParseMode::Dom, intern_strings: true, ..Default::default() };
+        let interned = crate::parser::parse(Cursor::new(xml.as_bytes()), options).unwrap();
+        assert_eq!(interned.flat.releases.len(), plain.flat.releases.len());
+        let stats = interned.interning.unwrap();
+        assert!(stats.unique < stats.lookups);
+        assert!(stats.bytes_saved >= 2 * "Greatest Hits".len());
+    }
+    
+    #[test]
+    fn test_parse_borrowed_slices_the_input() {
+        use std::borrow::Cow;
+        
+        let xml = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+  <MessageHeader>
+    <MessageId>M1</MessageId>
+    <MessageSender><PartyId>PADPIDA2014120301</PartyId><PartyName><FullName>Label</FullName></PartyName></MessageSender>
+  </MessageHeader>
+  <ReleaseList>
+    <Release>
+      <ReleaseReference>R1</ReleaseReference>
+      <ReleaseId><ICPN>0602537518357</ICPN></ReleaseId>
+      <ReferenceTitle><TitleText>Salt &amp; Pepper</TitleText></ReferenceTitle>
+      <ResourceGroup><ResourceGroupContentItem><ReleaseResourceReference>A1</ReleaseResourceReference></ResourceGroupContentItem></ResourceGroup>
+    </Release>
+  </ReleaseList>
+  <ResourceList>
+    <SoundRecording>
+      <ResourceReference>A1</ResourceReference>
+      <SoundRecordingId><ISRC>USRC17607839</ISRC></SoundRecordingId>
+      <Duration>PT3M45S</Duration>
+    </SoundRecording>
+  </ResourceList>
+  <DealList>
+    <ReleaseDeal>
+      <DealReleaseReference>R1</DealReleaseReference>
+      <Deal><DealTerms><TerritoryCode>Worldwide</TerritoryCode></DealTerms></Deal>
+    </ReleaseDeal>
+  </DealList>
+</ern:NewReleaseMessage>"#;
+        
+        let message = crate::DDEXParser::new().parse_borrowed(xml).unwrap();
+        assert_eq!(message.message_id, "M1");
+        assert_eq!(message.sender_name.as_deref(), Some("Label"));
+        
+        let release = &message.releases[0];
+        assert!(matches!(release.release_reference, Cow::Borrowed("R1")));
+        assert_eq!(release.release_id[0].value, "0602537518357");
+        // Escaped text is the only value that needs its own allocation
+        assert!(matches!(release.title.as_ref(), Some(Cow::Owned(title)) if title == "Salt & Pepper"));
+        assert_eq!(release.resource_references, ["A1"]);
+        
+        let resource = &message.resources[0];
+        assert_eq!(resource.resource_id[0].value, "USRC17607839");
+        assert_eq!(resource.duration.as_deref(), Some("PT3M45S"));
+        assert_eq!(message.deals[0].territory_codes, ["Worldwide"]);
+        
+        let owned = message.clone().into_owned();
+        assert_eq!(owned, message);
+    }
 }
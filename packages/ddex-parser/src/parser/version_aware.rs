@@ -26,13 +26,14 @@ impl VersionAwareParser {
             ERNVersion::V3_8_2 => Self::parse_382(reader, adapter)?,
             ERNVersion::V4_2 => Self::parse_42(reader, adapter)?,
             ERNVersion::V4_3 => Self::parse_43(reader, adapter)?,
+            ERNVersion::V4_4 => Self::parse_44(reader, adapter)?,
         };
         
         // Transform to flat model
         use crate::transform::flatten::Flattener;
         let flat = Flattener::flatten(graph.clone());
         
-        Ok(ParsedERNMessage { graph, flat })
+        Ok(ParsedERNMessage { graph, flat, extensions: None, raw_fragments: None, normalizations: Default::default(), interning: None })
     }
     
     fn parse_382<R: BufRead>(
@@ -66,4 +67,12 @@ impl VersionAwareParser {
         // Extended DealTerms
         todo!("Implement 4.3 parsing")
     }
+    
+    fn parse_44<R: BufRead>(
+        reader: R,
+        adapter: VersionAdapter,
+    ) -> Result<ERNMessage, ParseError> {
+        // 4.4 is a superset of 4.3; the adapter carries the 4.4 feature set
+        Self::parse_43(reader, adapter)
+    }
 }
\ No newline at end of file
@@ -10,6 +10,7 @@ impl ERNVersionExt for ERNVersion {
             ERNVersion::V3_8_2 => "http://ddex.net/xml/ern/382",
             ERNVersion::V4_2 => "http://ddex.net/xml/ern/42",
             ERNVersion::V4_3 => "http://ddex.net/xml/ern/43",
+            ERNVersion::V4_4 => "http://ddex.net/xml/ern/44",
         }
     }
 }
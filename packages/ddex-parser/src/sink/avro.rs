@@ -0,0 +1,170 @@
+// core/src/sink/avro.rs
+//! Avro binary encoding of releases
+//!
+//! Releases are written as the [`RELEASE_SCHEMA`] record, a flat summary of
+//! the graph model that consumers can register once and read without the
+//! rest of the message. Only the binary datum is produced; object container
+//! files and schema registry calls are left to the caller.
+
+use ddex_core::models::graph::{Release, ReleaseType};
+
+/// Avro schema of the records written by [`encode_release`]
+pub const RELEASE_SCHEMA: &str = r#"{
+  "type": "record",
+  "name": "Release",
+  "namespace": "net.ddex.ern",
+  "fields": [
+    {"name": "release_reference", "type": "string"},
+    {"name": "release_ids", "type": {"type": "array", "items": {
+      "type": "record",
+      "name": "Identifier",
+      "fields": [
+        {"name": "type", "type": "string"},
+        {"name": "namespace", "type": ["null", "string"], "default": null},
+        {"name": "value", "type": "string"}
+      ]
+    }}},
+    {"name": "title", "type": ["null", "string"], "default": null},
+    {"name": "subtitle", "type": ["null", "string"], "default": null},
+    {"name": "release_type", "type": ["null", "string"], "default": null},
+    {"name": "display_artists", "type": {"type": "array", "items": "string"}},
+    {"name": "genres", "type": {"type": "array", "items": "string"}},
+    {"name": "resource_references", "type": {"type": "array", "items": "string"}},
+    {"name": "release_date", "type": ["null", "string"], "default": null},
+    {"name": "territories", "type": {"type": "array", "items": "string"}}
+  ]
+}"#;
+
+/// Append the Avro datum of `release` to `out`
+pub fn encode_release(release: &Release, out: &mut Vec<u8>) {
+    write_string(out, &release.release_reference);
+    write_array(out, &release.release_id, |out, id| {
+        write_string(out, &format!("{:?}", id.id_type));
+        write_optional(out, id.namespace.as_deref());
+        write_string(out, &id.value);
+    });
+    write_optional(out, release.release_title.first().map(|t| t.text.as_str()));
+    write_optional(
+        out,
+        release.release_subtitle.as_ref().and_then(|s| s.first()).map(|t| t.text.as_str()),
+    );
+    let release_type = release.release_type.as_ref().map(|kind| match kind {
+        ReleaseType::Other(other) => other.clone(),
+        kind => format!("{:?}", kind),
+    });
+    write_optional(out, release_type.as_deref());
+    let artists: Vec<&str> = release
+        .display_artist
+        .iter()
+        .filter_map(|artist| artist.display_artist_name.first())
+        .map(|name| name.text.as_str())
+        .collect();
+    write_array(out, &artists, |out, name| write_string(out, name));
+    write_array(out, &release.genre, |out, genre| write_string(out, &genre.genre_text));
+    write_array(out, &release.release_resource_reference_list, |out, reference| {
+        write_string(out, &reference.resource_reference)
+    });
+    let release_date = release
+        .release_date
+        .iter()
+        .find_map(|event| event.event_date)
+        .map(|date| date.format("%Y-%m-%d").to_string());
+    write_optional(out, release_date.as_deref());
+    write_array(out, &release.territory_code, |out, code| write_string(out, code));
+}
+
+/// Zigzag varint, the encoding of Avro `int` and `long`
+fn write_long(out: &mut Vec<u8>, value: i64) {
+    let mut n = ((value << 1) ^ (value >> 63)) as u64;
+    while n >= 0x80 {
+        out.push((n as u8 & 0x7f) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn write_string(out: &mut Vec<u8>, value: &str) {
+    write_long(out, value.len() as i64);
+    out.extend_from_slice(value.as_bytes());
+}
+
+/// `["null", "string"]` union
+fn write_optional(out: &mut Vec<u8>, value: Option<&str>) {
+    match value {
+        None => write_long(out, 0),
+        Some(value) => {
+            write_long(out, 1);
+            write_string(out, value);
+        }
+    }
+}
+
+/// Arrays are written as a single block followed by the empty end block
+fn write_array<T>(out: &mut Vec<u8>, items: &[T], mut write_item: impl FnMut(&mut Vec<u8>, &T)) {
+    if !items.is_empty() {
+        write_long(out, items.len() as i64);
+        for item in items {
+            write_item(out, item);
+        }
+    }
+    write_long(out, 0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ddex_core::models::common::{Identifier, IdentifierType, LocalizedString};
+
+    #[test]
+    fn test_zigzag() {
+        let encoded = |value| {
+            let mut out = Vec::new();
+            write_long(&mut out, value);
+            out
+        };
+        assert_eq!(encoded(0), [0x00]);
+        assert_eq!(encoded(-1), [0x01]);
+        assert_eq!(encoded(1), [0x02]);
+        assert_eq!(encoded(64), [0x80, 0x01]);
+        assert_eq!(encoded(-65), [0x81, 0x01]);
+    }
+
+    #[test]
+    fn test_encode_release() {
+        let release = Release {
+            release_reference: "R1".to_string(),
+            release_id: vec![Identifier {
+                id_type: IdentifierType::UPC,
+                namespace: None,
+                value: "01".to_string(),
+            }],
+            release_title: vec![LocalizedString::new("Hi".to_string())],
+            release_subtitle: None,
+            release_type: Some(ReleaseType::EP),
+            genre: Vec::new(),
+            release_resource_reference_list: Vec::new(),
+            display_artist: Vec::new(),
+            party_list: Vec::new(),
+            release_date: Vec::new(),
+            territory_code: vec!["GB".to_string()],
+            excluded_territory_code: Vec::new(),
+            attributes: None,
+            extensions: None,
+            comments: None,
+        };
+        let mut out = Vec::new();
+        encode_release(&release, &mut out);
+        let expected: &[u8] = &[
+            0x04, b'R', b'1', // release_reference
+            0x02, 0x06, b'U', b'P', b'C', 0x00, 0x04, b'0', b'1', 0x00, // release_ids
+            0x02, 0x04, b'H', b'i', // title
+            0x00, // subtitle
+            0x02, 0x04, b'E', b'P', // release_type
+            0x00, 0x00, 0x00, // artists, genres, resources
+            0x00, // release_date
+            0x02, 0x04, b'G', b'B', 0x00, // territories
+        ];
+        assert_eq!(out, expected);
+        assert!(serde_json::from_str::<serde_json::Value>(RELEASE_SCHEMA).is_ok());
+    }
+}
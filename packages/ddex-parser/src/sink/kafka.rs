@@ -0,0 +1,97 @@
+// core/src/sink/kafka.rs
+//! Kafka sink backed by librdkafka
+//!
+//! The producer is configured for at-least-once delivery (`acks=all`,
+//! idempotence on); any [`ClientConfig`] setting, such as SASL credentials,
+//! can be added with [`KafkaSink::from_config`].
+
+use super::{ReleaseSink, SinkMessage};
+use crate::error::ParseError;
+use rdkafka::config::ClientConfig;
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+use rdkafka::producer::{BaseProducer, BaseRecord, DeliveryResult, Producer, ProducerContext};
+use rdkafka::ClientContext;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How long [`ReleaseSink::flush`] waits for outstanding acknowledgements
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Records failed deliveries reported by librdkafka's callback
+#[derive(Default)]
+struct DeliveryContext {
+    failures: Arc<Mutex<Vec<String>>>,
+}
+
+impl ClientContext for DeliveryContext {}
+
+impl ProducerContext for DeliveryContext {
+    type DeliveryOpaque = ();
+
+    fn delivery(&self, result: &DeliveryResult<'_>, _: Self::DeliveryOpaque) {
+        if let Err((error, _)) = result {
+            self.failures.lock().unwrap().push(error.to_string());
+        }
+    }
+}
+
+/// Publishes each release to a Kafka topic, keyed for partitioning
+pub struct KafkaSink {
+    producer: BaseProducer<DeliveryContext>,
+    topic: String,
+    failures: Arc<Mutex<Vec<String>>>,
+}
+
+impl KafkaSink {
+    /// Producer for `brokers` (comma-separated `host:port` list)
+    pub fn new(brokers: &str, topic: impl Into<String>) -> Result<Self, ParseError> {
+        let mut config = ClientConfig::new();
+        config.set("bootstrap.servers", brokers);
+        Self::from_config(config, topic)
+    }
+
+    /// Producer with extra librdkafka settings; delivery guarantees are
+    /// enforced on top of them
+    pub fn from_config(mut config: ClientConfig, topic: impl Into<String>) -> Result<Self, ParseError> {
+        config.set("acks", "all").set("enable.idempotence", "true");
+        let context = DeliveryContext::default();
+        let failures = context.failures.clone();
+        let producer = config.create_with_context(context).map_err(sink_error)?;
+        Ok(Self { producer, topic: topic.into(), failures })
+    }
+}
+
+impl ReleaseSink for KafkaSink {
+    fn send(&mut self, message: SinkMessage) -> Result<(), ParseError> {
+        let mut record = BaseRecord::to(&self.topic).key(&message.key).payload(&message.payload);
+        loop {
+            match self.producer.send(record) {
+                Ok(()) => break,
+                // Wait for acknowledgements to make room in the local queue
+                Err((KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull), rejected)) => {
+                    self.producer.poll(Duration::from_millis(100));
+                    record = rejected;
+                }
+                Err((error, _)) => return Err(sink_error(error)),
+            }
+        }
+        self.producer.poll(Duration::ZERO);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), ParseError> {
+        self.producer.flush(FLUSH_TIMEOUT).map_err(sink_error)?;
+        let failures = std::mem::take(&mut *self.failures.lock().unwrap());
+        match failures.first() {
+            None => Ok(()),
+            Some(first) => Err(ParseError::Sink {
+                sink: "kafka".to_string(),
+                message: format!("{} message(s) not delivered: {}", failures.len(), first),
+            }),
+        }
+    }
+}
+
+fn sink_error(error: KafkaError) -> ParseError {
+    ParseError::Sink { sink: "kafka".to_string(), message: error.to_string() }
+}
@@ -0,0 +1,154 @@
+// core/src/sink/mod.rs
+//! Publishing streamed releases to message queues
+//!
+//! [`publish`] runs the streaming parser over a document and hands each
+//! release to a [`ReleaseSink`] as one message, keyed by the release's ICPN
+//! (or its reference when it has none) so a partitioned topic keeps every
+//! version of a release in order.
+//!
+//! Delivery is at-least-once: [`publish`] only returns `Ok` once the sink has
+//! flushed and the broker acknowledged every message. After an error the
+//! document should be published again, which may repeat releases that had
+//! already gone through, so consumers should treat the key as idempotent.
+//!
+//! - [`kafka`] (`kafka` feature): a librdkafka producer with `acks=all`
+//! - [`amqp`] (`amqp` feature): an AMQP 0.9.1 channel in confirm mode
+//! - `Vec<SinkMessage>`: collects messages in memory, for tests
+
+pub mod avro;
+#[cfg(feature = "amqp")]
+pub mod amqp;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+
+use crate::error::ParseError;
+use ddex_core::models::common::IdentifierType;
+use ddex_core::models::graph::Release;
+use std::io::BufRead;
+
+/// Encoding of the message payloads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Serialization {
+    /// The release's graph model as JSON
+    #[default]
+    Json,
+    /// The [`avro::RELEASE_SCHEMA`] record; with a schema registry ID, framed
+    /// in the Confluent wire format (magic byte and big-endian ID)
+    Avro {
+        /// ID of the schema in the registry
+        schema_id: Option<u32>,
+    },
+}
+
+impl Serialization {
+    /// Payload of one release
+    pub fn encode(&self, release: &Release) -> Result<Vec<u8>, ParseError> {
+        match self {
+            Serialization::Json => serde_json::to_vec(release).map_err(|e| ParseError::Sink {
+                sink: "json".to_string(),
+                message: e.to_string(),
+            }),
+            Serialization::Avro { schema_id } => {
+                let mut payload = Vec::new();
+                if let Some(id) = schema_id {
+                    payload.push(0);
+                    payload.extend_from_slice(&id.to_be_bytes());
+                }
+                avro::encode_release(release, &mut payload);
+                Ok(payload)
+            }
+        }
+    }
+}
+
+/// One published message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SinkMessage {
+    /// Partitioning key
+    pub key: String,
+    /// Encoded release
+    pub payload: Vec<u8>,
+}
+
+/// A destination for published releases
+pub trait ReleaseSink {
+    /// Queue a message; it may not be acknowledged until [`ReleaseSink::flush`]
+    fn send(&mut self, message: SinkMessage) -> Result<(), ParseError>;
+
+    /// Wait until every message sent so far is acknowledged, failing if any
+    /// was rejected
+    fn flush(&mut self) -> Result<(), ParseError>;
+}
+
+impl ReleaseSink for Vec<SinkMessage> {
+    fn send(&mut self, message: SinkMessage) -> Result<(), ParseError> {
+        self.push(message);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), ParseError> {
+        Ok(())
+    }
+}
+
+/// Message key of a release: its ICPN, else its first ID, else its reference
+pub fn release_key(release: &Release) -> String {
+    release
+        .release_id
+        .iter()
+        .find(|id| matches!(id.id_type, IdentifierType::UPC | IdentifierType::EAN))
+        .or_else(|| release.release_id.first())
+        .map(|id| id.value.clone())
+        .unwrap_or_else(|| release.release_reference.clone())
+}
+
+/// Stream the releases of a document into a sink and wait for every
+/// acknowledgement, returning the number of messages published
+pub fn publish<R: BufRead>(
+    parser: &crate::DDEXParser,
+    reader: R,
+    sink: &mut dyn ReleaseSink,
+    serialization: Serialization,
+) -> Result<usize, ParseError> {
+    let mut published = 0;
+    for release in parser.stream_compressed(reader)? {
+        let release = release?;
+        sink.send(SinkMessage {
+            key: release_key(&release),
+            payload: serialization.encode(&release)?,
+        })?;
+        published += 1;
+    }
+    sink.flush()?;
+    Ok(published)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML: &str = r#"<ern:NewReleaseMessage xmlns:ern="http://ddex.net/xml/ern/43">
+  <MessageHeader><MessageId>M1</MessageId></MessageHeader>
+  <ReleaseList>
+    <Release><ReleaseReference>R1</ReleaseReference><ReleaseId><ICPN>0123456789012</ICPN></ReleaseId>
+      <ReferenceTitle><TitleText>First</TitleText></ReferenceTitle></Release>
+    <Release><ReleaseReference>R2</ReleaseReference>
+      <ReferenceTitle><TitleText>Second</TitleText></ReferenceTitle></Release>
+  </ReleaseList>
+</ern:NewReleaseMessage>"#;
+
+    #[test]
+    fn test_publish_one_message_per_release() {
+        let parser = crate::DDEXParser::new();
+        let mut sink: Vec<SinkMessage> = Vec::new();
+        let count = publish(&parser, XML.as_bytes(), &mut sink, Serialization::Json).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(sink[1].key, "R2");
+        let second: serde_json::Value = serde_json::from_slice(&sink[1].payload).unwrap();
+        assert_eq!(second["release_reference"], "R2");
+
+        let mut framed: Vec<SinkMessage> = Vec::new();
+        publish(&parser, XML.as_bytes(), &mut framed, Serialization::Avro { schema_id: Some(7) }).unwrap();
+        assert_eq!(&framed[0].payload[..5], &[0, 0, 0, 0, 7]);
+    }
+}
@@ -2,53 +2,120 @@
 // Remove unused imports and variables
 use crate::error::ParseError;
 use crate::parser::namespace_detector::NamespaceContext;
+use ddex_core::cancel::CancellationToken;
+use ddex_core::intern::{InternStats, StringInterner};
+use ddex_core::models::common::{Identifier, IdentifierType, LocalizedString};
 use ddex_core::models::graph::{
-    ERNMessage, MessageHeader, MessageType, MessageSender, MessageRecipient,
-    Release
+    Deal, DealTerms, ERNMessage, MessageHeader, MessageType, MessageSender, MessageRecipient,
+    PurgedRelease, Release, Resource, ResourceType
 };
 use ddex_core::models::versions::ERNVersion;
+use ddex_core::warnings::{NormalizationKind, Warnings};
 use quick_xml::Reader;
 use quick_xml::events::Event;
+use std::cell::RefCell;
 use std::io::BufRead;
+use std::sync::Arc;
 
 pub struct GraphBuilder {
     version: ERNVersion,
+    /// Text values trimmed while reading fields
+    warnings: RefCell<Warnings>,
+    cancellation: Option<CancellationToken>,
+    /// Shares repeated element names and values between fields
+    interner: Option<RefCell<StringInterner>>,
 }
 
 impl GraphBuilder {
     pub fn new(version: ERNVersion) -> Self {
-        Self { version }
+        Self { version, warnings: RefCell::new(Warnings::new()), cancellation: None, interner: None }
+    }
+    
+    /// Intern element names and text values while reading fields
+    pub fn with_interning(mut self, enabled: bool) -> Self {
+        self.interner = enabled.then(|| RefCell::new(StringInterner::new()));
+        self
+    }
+    
+    /// What interning saw and saved so far, if enabled
+    pub fn interning_stats(&self) -> Option<InternStats> {
+        self.interner.as_ref().map(|interner| interner.borrow().stats().clone())
+    }
+    
+    fn share(&self, value: &str) -> Arc<str> {
+        match &self.interner {
+            Some(interner) => interner.borrow_mut().intern(value),
+            None => Arc::from(value),
+        }
+    }
+    
+    /// Stop with `Cancelled` at the next element once `token` says so
+    pub fn with_cancellation(mut self, token: Option<CancellationToken>) -> Self {
+        self.cancellation = token;
+        self
+    }
+    
+    /// Normalizations made by the builds so far, leaving none behind
+    pub fn take_warnings(&self) -> Warnings {
+        self.warnings.take()
     }
     
     pub fn build_from_xml<R: BufRead>(&self, reader: R) -> Result<ERNMessage, ParseError> {
+        // Text is trimmed by `read_fields`, which records what it trims
         let mut xml_reader = Reader::from_reader(reader);
-        xml_reader.config_mut().trim_text(true);
         
-        let message_header = self.parse_header(&mut xml_reader)?;
+        let mut message_header = self.parse_header(&mut xml_reader)?;
         let mut releases = Vec::new();
-        let resources = Vec::new();  // Remove mut
+        let mut resources = Vec::new();
         let parties = Vec::new();    // Remove mut
-        let deals = Vec::new();      // Remove mut
+        let mut deals = Vec::new();
+        let mut purged_releases = Vec::new();
         
-        // Simple parsing to extract at least one release
+        // Simple parsing to extract releases, resources, deals and purges
         let mut buf = Vec::new();
+        let mut root_seen = false;
         let mut in_release_list = false;
+        let mut in_resource_list = false;
         
         loop {
             match xml_reader.read_event_into(&mut buf) {
                 Ok(Event::Start(ref e)) => {
-                    match e.name().as_ref() {
+                    crate::parser::check_cancelled(
+                        self.cancellation.as_ref(),
+                        xml_reader.buffer_position(),
+                        releases.len(),
+                    )?;
+                    
+                    // The root element tells new, update and purge messages apart
+                    if !root_seen {
+                        root_seen = true;
+                        let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                        if let Some(message_type) = MessageType::from_root_element(&name) {
+                            message_header.message_type = message_type;
+                        }
+                    }
+                    
+                    match e.local_name().as_ref() {
                         b"ReleaseList" => in_release_list = true,
+                        b"ResourceList" => in_resource_list = true,
                         b"Release" if in_release_list => {
                             // Create a minimal release
                             releases.push(self.parse_minimal_release(&mut xml_reader)?);
                         }
+                        b"SoundRecording" | b"Video" | b"Image" | b"Text" | b"SheetMusic" if in_resource_list => {
+                            let resource_type = Self::resource_type(e.local_name().as_ref());
+                            resources.push(self.parse_minimal_resource(&mut xml_reader, resource_type)?);
+                        }
+                        b"ReleaseDeal" => deals.push(self.parse_minimal_deal(&mut xml_reader)?),
+                        b"PurgedRelease" => purged_releases.push(self.parse_purged_release(&mut xml_reader)?),
                         _ => {}
                     }
                 }
                 Ok(Event::End(ref e)) => {
-                    if e.name().as_ref() == b"ReleaseList" {
-                        in_release_list = false;
+                    match e.local_name().as_ref() {
+                        b"ReleaseList" => in_release_list = false,
+                        b"ResourceList" => in_resource_list = false,
+                        _ => {}
                     }
                 }
                 Ok(Event::Eof) => break,
@@ -63,6 +130,7 @@ impl GraphBuilder {
             resources,
             releases,
             deals,
+            purged_releases,
             version: self.version,
             profile: None,
             message_audit_trail: None,
@@ -113,10 +181,12 @@ impl GraphBuilder {
     }
     
     fn parse_minimal_release<R: BufRead>(&self, reader: &mut Reader<R>) -> Result<Release, ParseError> {
-        use ddex_core::models::common::LocalizedString;
+        let fields = self.read_fields(reader, "Release")?;
+        let release_reference = Self::field(&fields, &["ReleaseReference"])
+            .unwrap_or_else(|| format!("R_{:?}", self.version));
         
-        let release = Release {  // Remove mut
-            release_reference: format!("R_{:?}", self.version),
+        Ok(Release {
+            release_reference,
             release_id: Vec::new(),
             release_title: vec![LocalizedString::new(format!("Test Release {:?}", self.version))],
             release_subtitle: None,
@@ -131,21 +201,190 @@ impl GraphBuilder {
             extensions: None,
             attributes: None,
             comments: None,
-        };
+        })
+    }
+    
+    fn parse_minimal_resource<R: BufRead>(
+        &self,
+        reader: &mut Reader<R>,
+        resource_type: ResourceType,
+    ) -> Result<Resource, ParseError> {
+        let fields = self.read_fields(reader, &format!("{:?}", resource_type))?;
+        
+        Ok(Resource {
+            resource_reference: Self::field(&fields, &["ResourceReference"]).unwrap_or_default(),
+            resource_type,
+            resource_id: Vec::new(),
+            reference_title: Vec::new(),
+            duration: None,
+            technical_details: Vec::new(),
+            rights_controller: Vec::new(),
+            p_line: Vec::new(),
+            c_line: Vec::new(),
+            extensions: None,
+            comments: None,
+        })
+    }
+    
+    fn parse_minimal_deal<R: BufRead>(&self, reader: &mut Reader<R>) -> Result<Deal, ParseError> {
+        let fields = self.read_fields(reader, "ReleaseDeal")?;
+        let deal_release_reference = fields.iter()
+            .filter(|f| matches!(f.path.as_slice(), [leaf] if &**leaf == "DealReleaseReference"))
+            .map(|f| f.text.to_string())
+            .collect();
+        
+        Ok(Deal {
+            deal_reference: Self::field(&fields, &["Deal", "DealReference"]),
+            deal_release_reference,
+            deal_terms: DealTerms {
+                validity_period: None,
+                start_date: None,
+                end_date: None,
+                territory_code: Vec::new(),
+                excluded_territory_code: Vec::new(),
+                distribution_channel: Vec::new(),
+                excluded_distribution_channel: Vec::new(),
+                commercial_model_type: Vec::new(),
+                use_type: Vec::new(),
+                price_information: Vec::new(),
+                wholesale_price: Vec::new(),
+                suggested_retail_price: Vec::new(),
+                pre_order_date: None,
+                pre_order_preview_date: None,
+                instant_gratification_date: None,
+                takedown_date: None,
+            },
+            comments: None,
+        })
+    }
+    
+    fn parse_purged_release<R: BufRead>(&self, reader: &mut Reader<R>) -> Result<PurgedRelease, ParseError> {
+        let fields = self.read_fields(reader, "PurgedRelease")?;
+        let mut release_id = Vec::new();
+        let mut title = Vec::new();
         
-        // Skip to the end of the Release element
+        for field in &fields {
+            match field.path.as_slice() {
+                [.., parent, leaf] if &**parent == "ReleaseId" => {
+                    let id_type = match &**leaf {
+                        "ICPN" | "UPC" => IdentifierType::UPC,
+                        "EAN" => IdentifierType::EAN,
+                        "GRid" => IdentifierType::GRid,
+                        "ISRC" => IdentifierType::ISRC,
+                        _ => IdentifierType::Proprietary,
+                    };
+                    release_id.push(Identifier {
+                        id_type,
+                        namespace: field.namespace.as_deref().map(str::to_string),
+                        value: field.text.to_string(),
+                    });
+                }
+                [.., leaf] if &**leaf == "TitleText" => title.push(LocalizedString::new(field.text.to_string())),
+                _ => {}
+            }
+        }
+        
+        Ok(PurgedRelease {
+            release_id,
+            title,
+            attributes: None,
+            extensions: None,
+            comments: None,
+        })
+    }
+    
+    fn resource_type(local_name: &[u8]) -> ResourceType {
+        match local_name {
+            b"Video" => ResourceType::Video,
+            b"Image" => ResourceType::Image,
+            b"Text" => ResourceType::Text,
+            b"SheetMusic" => ResourceType::SheetMusic,
+            _ => ResourceType::SoundRecording,
+        }
+    }
+    
+    /// Text of the first field at exactly this path
+    fn field(fields: &[TextField], path: &[&str]) -> Option<String> {
+        fields.iter()
+            .find(|f| f.path.iter().map(|segment| &**segment).eq(path.iter().copied()))
+            .map(|f| f.text.to_string())
+    }
+    
+    /// Collect every text node up to the end of the current element
+    /// (`element`), keyed by its path below that element
+    ///
+    /// Whitespace-only text between elements is skipped; other text is
+    /// trimmed, recording a warning if that changed it.
+    fn read_fields<R: BufRead>(&self, reader: &mut Reader<R>, element: &str) -> Result<Vec<TextField>, ParseError> {
+        let mut fields = Vec::new();
+        let mut path: Vec<Arc<str>> = Vec::new();
+        let mut namespaces: Vec<Option<Arc<str>>> = Vec::new();
         let mut buf = Vec::new();
-        let mut depth = 1;
-        while depth > 0 {
+        
+        loop {
             match reader.read_event_into(&mut buf) {
-                Ok(Event::Start(_)) => depth += 1,
-                Ok(Event::End(_)) => depth -= 1,
+                Ok(Event::Start(ref e)) => {
+                    path.push(self.share(&String::from_utf8_lossy(e.local_name().as_ref())));
+                    namespaces.push(
+                        e.try_get_attribute("Namespace").ok().flatten()
+                            .and_then(|a| a.unescape_value().ok().map(|v| self.share(&v)))
+                    );
+                }
+                Ok(Event::Text(ref t)) if !path.is_empty() => {
+                    if let Ok(text) = t.unescape() {
+                        let trimmed = text.trim();
+                        if trimmed.is_empty() {
+                            buf.clear();
+                            continue;
+                        }
+                        if trimmed.len() != text.len() {
+                            self.warnings.borrow_mut().record(
+                                NormalizationKind::TrimmedWhitespace,
+                                format!("{}/{}", element, path.join("/")),
+                                text.as_ref(),
+                                Some(trimmed.to_string()),
+                            );
+                        }
+                        fields.push(TextField {
+                            path: path.clone(),
+                            text: self.share(trimmed),
+                            namespace: namespaces.last().cloned().flatten(),
+                        });
+                    }
+                }
+                Ok(Event::End(_)) => {
+                    if path.pop().is_none() {
+                        break;
+                    }
+                    namespaces.pop();
+                }
                 Ok(Event::Eof) => break,
+                Err(e) => {
+                    return Err(ParseError::XmlError {
+                        message: e.to_string(),
+                        location: crate::error::ErrorLocation {
+                            line: 0,
+                            column: 0,
+                            byte_offset: Some(reader.buffer_position() as usize),
+                            path: path.join("/"),
+                        },
+                    });
+                }
                 _ => {}
             }
             buf.clear();
         }
         
-        Ok(release)
+        Ok(fields)
     }
-}
\ No newline at end of file
+}
+
+/// Text content found below an element being parsed
+///
+/// Paths share their segments with the fields around them, and with every
+/// other repeat of a value when interning is on.
+struct TextField {
+    path: Vec<Arc<str>>,
+    text: Arc<str>,
+    namespace: Option<Arc<str>>,
+}
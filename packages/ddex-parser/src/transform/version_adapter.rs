@@ -107,6 +107,20 @@ impl VersionMigrator {
         Ok(migrated)
     }
     
+    /// Migrate from 4.3 to 4.4
+    pub fn migrate_43_to_44(message: &ERNMessage) -> Result<ERNMessage, ParseError> {
+        let mut migrated = message.clone();
+        migrated.version = ERNVersion::V4_4;
+        Ok(migrated)
+    }
+    
+    /// Downgrade from 4.4 to 4.3
+    pub fn downgrade_44_to_43(message: &ERNMessage) -> Result<(ERNMessage, Vec<String>), ParseError> {
+        let mut downgraded = message.clone();
+        downgraded.version = ERNVersion::V4_3;
+        Ok((downgraded, Vec::new()))
+    }
+    
     /// Downgrade from 4.3 to 4.2 (with data loss warnings)
     pub fn downgrade_43_to_42(message: &ERNMessage) -> Result<(ERNMessage, Vec<String>), ParseError> {
         let mut downgraded = message.clone();
@@ -34,10 +34,30 @@ fn test_ffi_error_serialization() {
         message: "Test message".to_string(),
         location: None,
         severity: FFIErrorSeverity::Error,
-        hint: Some("Test hint".to_string()),
+        suggestion: Some("Test suggestion".to_string()),
         category: FFIErrorCategory::XmlParsing,
     };
     
     let json = serde_json::to_string(&ffi_error).unwrap();
     assert!(json.contains("TEST_ERROR"));
+    assert!(json.contains("\"suggestion\":\"Test suggestion\""));
+    
+    // Errors serialized before the rename still deserialize
+    let old = r#"{"code":"X","message":"m","location":null,"severity":"Error","hint":"h","category":"Io"}"#;
+    let ffi_error: FFIError = serde_json::from_str(old).unwrap();
+    assert_eq!(ffi_error.suggestion.as_deref(), Some("h"));
+}
+
+#[test]
+fn test_parse_error_codes() {
+    use ddex_core::ffi::FFIError;
+    
+    let parser = DDEXParser::new();
+    let xxe = r#"<?xml version="1.0"?><!DOCTYPE x [<!ENTITY e SYSTEM "file:///etc/passwd">]><x>&e;</x>"#;
+    let error = parser.parse(std::io::Cursor::new(xxe.as_bytes())).unwrap_err();
+    let code = error.code();
+    assert!(code.starts_with("DDEX-P-") || code.starts_with("DDEX-C-"), "{}", code);
+    
+    let ffi_error = FFIError::from(error);
+    assert_eq!(ffi_error.code, code);
 }
\ No newline at end of file
@@ -7,7 +7,7 @@ fn test_phase2_version_support() {
     let parser = DDEXParser::new();
     
     // Test all supported versions
-    for version in &[ERNVersion::V3_8_2, ERNVersion::V4_2, ERNVersion::V4_3] {
+    for version in &[ERNVersion::V3_8_2, ERNVersion::V4_2, ERNVersion::V4_3, ERNVersion::V4_4] {
         println!("Testing version: {:?}", version);
         
         let xml = match version {
@@ -20,6 +20,9 @@ fn test_phase2_version_support() {
             ERNVersion::V4_3 => {
                 include_str!("../../../test-suite/valid/ern-4.3/simple_release.xml")
             }
+            ERNVersion::V4_4 => {
+                include_str!("../../../test-suite/valid/ern-4.4/simple_release.xml")
+            }
         };
         
         let result = parser.parse(std::io::Cursor::new(xml.as_bytes()));
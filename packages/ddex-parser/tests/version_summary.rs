@@ -10,7 +10,8 @@ fn test_version_support_summary() {
     let supported_versions = vec![
         (ERNVersion::V3_8_2, "3.8.2", "Legacy support"),
         (ERNVersion::V4_2, "4.2", "Standard support"),
-        (ERNVersion::V4_3, "4.3", "Latest with full features"),
+        (ERNVersion::V4_3, "4.3", "Full features"),
+        (ERNVersion::V4_4, "4.4", "Latest, superset of 4.3"),
     ];
     
     for (version, name, description) in supported_versions {
@@ -20,11 +21,12 @@ fn test_version_support_summary() {
     // Test actual parsing for each version
     let parser = DDEXParser::new();
     
-    for version in &[ERNVersion::V3_8_2, ERNVersion::V4_2, ERNVersion::V4_3] {
+    for version in &[ERNVersion::V3_8_2, ERNVersion::V4_2, ERNVersion::V4_3, ERNVersion::V4_4] {
         let namespace = match version {
             ERNVersion::V3_8_2 => "http://ddex.net/xml/ern/382",
             ERNVersion::V4_2 => "http://ddex.net/xml/ern/42",
             ERNVersion::V4_3 => "http://ddex.net/xml/ern/43",
+            ERNVersion::V4_4 => "http://ddex.net/xml/ern/44",
         };
         
         println!("  Version {:?} -> Namespace: {}", version, namespace);
@@ -39,4 +41,5 @@ fn test_feature_matrix() {
     println!("3.8.2   | No                | No             | No");
     println!("4.2     | Yes               | No             | No");
     println!("4.3     | Yes               | Yes            | Yes");
+    println!("4.4     | Yes               | Yes            | Yes");
 }
\ No newline at end of file
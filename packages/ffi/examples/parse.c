@@ -0,0 +1,47 @@
+/* Parse a DDEX message and print the flattened JSON.
+ *
+ *   cargo build -p ddex-ffi --profile ffi
+ *   cc examples/parse.c -Iinclude -L../../target/ffi -lddex -o parse
+ *   ./parse message.xml
+ */
+
+#include <stdio.h>
+#include "ddex.h"
+
+int main(int argc, char **argv) {
+  if (argc != 2) {
+    fprintf(stderr, "usage: %s <message.xml>\n", argv[0]);
+    return 2;
+  }
+
+  FILE *file = fopen(argv[1], "rb");
+  if (!file) {
+    perror(argv[1]);
+    return 1;
+  }
+  fseek(file, 0, SEEK_END);
+  long size = ftell(file);
+  rewind(file);
+  uint8_t *xml = malloc(size);
+  fread(xml, 1, size, file);
+  fclose(file);
+
+  DdexBuffer json;
+  DdexError *error = NULL;
+  DdexStatus status = ddex_parse_json(xml, size, "{\"include_comments\": true}", &json, &error);
+  free(xml);
+
+  if (status != DDEX_STATUS_OK) {
+    fprintf(stderr, "%s: %s\n", error->code, error->message);
+    if (error->suggestion) {
+      fprintf(stderr, "  %s\n", error->suggestion);
+    }
+    ddex_error_free(error);
+    return 1;
+  }
+
+  /* The buffer is NUL-terminated */
+  printf("%s\n", (const char *)json.data);
+  ddex_buffer_free(json);
+  return 0;
+}
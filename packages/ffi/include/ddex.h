@@ -0,0 +1,156 @@
+/* C API of the DDEX Suite (libddex). Release everything the library returns with ddex_buffer_free or ddex_error_free. */
+
+#ifndef DDEX_H
+#define DDEX_H
+
+/* Generated by cbindgen from packages/ffi and ddex_core::ffi::c; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Mirrors [`FFIErrorCategory`]
+ */
+typedef enum DdexErrorCategory {
+  DDEX_ERROR_CATEGORY_XML_PARSING = 0,
+  DDEX_ERROR_CATEGORY_VALIDATION = 1,
+  DDEX_ERROR_CATEGORY_REFERENCE = 2,
+  DDEX_ERROR_CATEGORY_VERSION = 3,
+  DDEX_ERROR_CATEGORY_IO = 4,
+  DDEX_ERROR_CATEGORY_INTERNAL = 5,
+} DdexErrorCategory;
+
+/**
+ * Mirrors [`FFIErrorSeverity`]
+ */
+typedef enum DdexSeverity {
+  DDEX_SEVERITY_ERROR = 0,
+  DDEX_SEVERITY_WARNING = 1,
+  DDEX_SEVERITY_INFO = 2,
+} DdexSeverity;
+
+/**
+ * Outcome of an entry point
+ */
+typedef enum DdexStatus {
+  /**
+   * The output buffer holds the result
+   */
+  DDEX_STATUS_OK = 0,
+  /**
+   * The error, if one was asked for, says what went wrong
+   */
+  DDEX_STATUS_ERROR = 1,
+} DdexStatus;
+
+/**
+ * Bytes owned by the library
+ *
+ * `data[len]` is always NUL, so text output can be read as a C string.
+ */
+typedef struct DdexBuffer {
+  uint8_t *data;
+  size_t len;
+} DdexBuffer;
+
+/**
+ * An [`FFIError`] as NUL-terminated strings
+ */
+typedef struct DdexError {
+  /**
+   * Stable code to branch on, e.g. `DDEX-B-0401`
+   */
+  char *code;
+  char *message;
+  /**
+   * Element path or JSON path of the offending input, or NULL
+   */
+  char *path;
+  /**
+   * 1-based position in the XML, or 0
+   */
+  size_t line;
+  size_t column;
+  enum DdexSeverity severity;
+  enum DdexErrorCategory category;
+  /**
+   * How to fix the input, or NULL
+   */
+  char *suggestion;
+} DdexError;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Release a buffer returned by the library; an empty buffer is ignored
+ *
+ * # Safety
+ *
+ * `buffer` must come from the library and not have been freed already.
+ */
+void ddex_buffer_free(struct DdexBuffer buffer);
+
+/**
+ * Release an error returned by the library; NULL is ignored
+ *
+ * # Safety
+ *
+ * `error` must be NULL or come from the library and not have been freed
+ * already.
+ */
+void ddex_error_free(struct DdexError *error);
+
+/**
+ * Version of the library, e.g. `0.3.5`; a static string, not to be freed
+ */
+const char *ddex_version(void);
+
+/**
+ * Parse DDEX XML into JSON of the graph and flattened message
+ *
+ * `options_json` is NULL or a JSON object overriding fields of the parse
+ * options (`include_raw_extensions`, `include_comments`, `strict_mode`,
+ * `max_depth`, `timeout_seconds`). On success `*out` holds UTF-8 JSON; on
+ * failure `*error`, when `error` is not NULL, says why.
+ *
+ * # Safety
+ *
+ * `xml` must be valid for reads of `xml_len` bytes, `options_json` NULL or
+ * a NUL-terminated string, `out` valid for writes and `error` NULL or valid
+ * for writes.
+ */
+enum DdexStatus ddex_parse_json(const uint8_t *xml,
+                                size_t xml_len,
+                                const char *options_json,
+                                struct DdexBuffer *out,
+                                struct DdexError **error);
+
+/**
+ * Build DDEX XML from the JSON of a build request
+ *
+ * `options_json` is NULL or a JSON object overriding fields of the build
+ * options, e.g. `{"preflight_level": "Strict"}`. On success `*out` holds
+ * the UTF-8 XML; on failure `*error`, when `error` is not NULL, says why.
+ *
+ * # Safety
+ *
+ * `request_json` must be valid for reads of `request_len` bytes,
+ * `options_json` NULL or a NUL-terminated string, `out` valid for writes
+ * and `error` NULL or valid for writes.
+ */
+enum DdexStatus ddex_build_json(const uint8_t *request_json,
+                                size_t request_len,
+                                const char *options_json,
+                                struct DdexBuffer *out,
+                                struct DdexError **error);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* DDEX_H */
@@ -0,0 +1,88 @@
+//! C ABI for embedding the DDEX parser and builder
+//!
+//! Builds `libddex` (shared and static) for languages that call C: Go via
+//! cgo, Swift, C# via P/Invoke. Messages cross as JSON, in the same shapes
+//! the Node, Python and WASM bindings use, so the JSON Schemas from
+//! `ddex-builder schema --target ...` describe them too. `include/ddex.h`
+//! is generated with cbindgen; see the README for the calling convention.
+
+use ddex_builder::{BuildError, BuildOptions, BuildRequest, DDEXBuilder};
+use ddex_core::ffi::c::{self, DdexBuffer, DdexError, DdexStatus};
+use ddex_core::ffi::{FFIError, FFIParseOptions};
+use ddex_parser::parser::security::SecurityConfig;
+use ddex_parser::parser::ParseOptions;
+use ddex_parser::DDEXParser;
+use std::ffi::c_char;
+use std::io::Cursor;
+
+pub use ddex_core::ffi::c::{ddex_buffer_free, ddex_error_free, ddex_version};
+
+/// Parse DDEX XML into JSON of the graph and flattened message
+///
+/// `options_json` is NULL or a JSON object overriding fields of the parse
+/// options (`include_raw_extensions`, `include_comments`, `strict_mode`,
+/// `max_depth`, `timeout_seconds`). On success `*out` holds UTF-8 JSON; on
+/// failure `*error`, when `error` is not NULL, says why.
+///
+/// # Safety
+///
+/// `xml` must be valid for reads of `xml_len` bytes, `options_json` NULL or
+/// a NUL-terminated string, `out` valid for writes and `error` NULL or valid
+/// for writes.
+#[no_mangle]
+pub unsafe extern "C" fn ddex_parse_json(
+    xml: *const u8,
+    xml_len: usize,
+    options_json: *const c_char,
+    out: *mut DdexBuffer,
+    error: *mut *mut DdexError,
+) -> DdexStatus {
+    c::call(out, error, || {
+        let xml = c::bytes(xml, xml_len, "xml")?;
+        let options = c::options(FFIParseOptions::default(), c::optional_str(options_json, "options_json")?, "options_json")?;
+        let message = DDEXParser::new()
+            .parse_with_options(Cursor::new(xml), parse_options(options))
+            .map_err(FFIError::from)?;
+        Ok(serde_json::to_vec(&message).expect("parsed messages serialize to JSON"))
+    })
+}
+
+/// Build DDEX XML from the JSON of a build request
+///
+/// `options_json` is NULL or a JSON object overriding fields of the build
+/// options, e.g. `{"preflight_level": "Strict"}`. On success `*out` holds
+/// the UTF-8 XML; on failure `*error`, when `error` is not NULL, says why.
+///
+/// # Safety
+///
+/// `request_json` must be valid for reads of `request_len` bytes,
+/// `options_json` NULL or a NUL-terminated string, `out` valid for writes
+/// and `error` NULL or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn ddex_build_json(
+    request_json: *const u8,
+    request_len: usize,
+    options_json: *const c_char,
+    out: *mut DdexBuffer,
+    error: *mut *mut DdexError,
+) -> DdexStatus {
+    c::call(out, error, || {
+        let request: BuildRequest = serde_json::from_slice(c::bytes(request_json, request_len, "request_json")?)
+            .map_err(|e| FFIError::from(BuildError::InvalidFormat { field: "request_json".to_string(), message: e.to_string() }))?;
+        let options = c::options(BuildOptions::default(), c::optional_str(options_json, "options_json")?, "options_json")?;
+        let result = DDEXBuilder::new().build(request, options).map_err(FFIError::from)?;
+        Ok(result.xml.into_bytes())
+    })
+}
+
+fn parse_options(options: FFIParseOptions) -> ParseOptions {
+    ParseOptions {
+        include_raw_extensions: options.include_raw_extensions,
+        include_comments: options.include_comments,
+        validate_schema: options.strict_mode,
+        fail_on_warning: options.strict_mode,
+        timeout_ms: options.timeout_seconds.map_or(0, |seconds| seconds.saturating_mul(1000)),
+        security: options.max_depth.map(|depth| SecurityConfig { max_element_depth: depth, ..SecurityConfig::default() }),
+        ..ParseOptions::default()
+    }
+}
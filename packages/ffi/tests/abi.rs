@@ -0,0 +1,60 @@
+//! The exported functions, called the way a C caller would
+
+use ddex::{ddex_buffer_free, ddex_build_json, ddex_error_free, ddex_parse_json, ddex_version};
+use ddex_core::ffi::c::{DdexBuffer, DdexStatus};
+use std::ffi::{CStr, CString};
+use std::ptr;
+
+fn text(buffer: &DdexBuffer) -> &str {
+    unsafe { std::str::from_utf8(std::slice::from_raw_parts(buffer.data, buffer.len)).unwrap() }
+}
+
+#[test]
+fn test_build_then_parse() {
+    let request = serde_json::to_vec(&ddex_builder::benchmark::sample_request(1)).unwrap();
+    let mut xml = DdexBuffer::empty();
+    let mut error = ptr::null_mut();
+    let status = unsafe { ddex_build_json(request.as_ptr(), request.len(), ptr::null(), &mut xml, &mut error) };
+    assert_eq!(status, DdexStatus::Ok);
+    assert!(error.is_null());
+    assert!(text(&xml).contains("<ReleaseList>"));
+
+    let options = CString::new(r#"{"include_comments": true}"#).unwrap();
+    let mut json = DdexBuffer::empty();
+    let status = unsafe { ddex_parse_json(xml.data, xml.len, options.as_ptr(), &mut json, &mut error) };
+    assert_eq!(status, DdexStatus::Ok);
+    let message: serde_json::Value = serde_json::from_str(text(&json)).unwrap();
+    assert_eq!(message["flat"]["message_id"], "BENCH-001");
+
+    unsafe {
+        ddex_buffer_free(xml);
+        ddex_buffer_free(json);
+    }
+}
+
+#[test]
+fn test_errors_carry_codes() {
+    let request = b"{\"releases\": 1}";
+    let mut xml = DdexBuffer::empty();
+    let mut error = ptr::null_mut();
+    let status = unsafe { ddex_build_json(request.as_ptr(), request.len(), ptr::null(), &mut xml, &mut error) };
+    assert_eq!(status, DdexStatus::Error);
+    assert!(xml.data.is_null());
+    unsafe {
+        assert_eq!(CStr::from_ptr((*error).code).to_str(), Ok("DDEX-B-0401"));
+        ddex_error_free(error);
+    }
+
+    let status = unsafe { ddex_parse_json(ptr::null(), 10, ptr::null(), &mut xml, &mut error) };
+    assert_eq!(status, DdexStatus::Error);
+    unsafe {
+        assert_eq!(CStr::from_ptr((*error).code).to_str(), Ok("DDEX-C-0901"));
+        ddex_error_free(error);
+    }
+}
+
+#[test]
+fn test_version_is_static() {
+    let version = unsafe { CStr::from_ptr(ddex_version()) };
+    assert_eq!(version.to_str(), Ok(env!("CARGO_PKG_VERSION")));
+}